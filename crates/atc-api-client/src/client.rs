@@ -0,0 +1,285 @@
+//! HTTP client for the ATC server's control-plane API.
+
+use atc_core::models::{Command, DroneState, FlightPlan, FlightPlanRequest, Geofence, Waypoint};
+use atc_core::{Conflict, CreateGeofenceRequest, UpdateGeofenceRequest};
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::error::{ApiError, ApiResult};
+use crate::types::{
+    ConflictHistoryQuery, ConflictQuery, ConflictRecord, FlightPlanHistoryQuery, FlightPlansQuery,
+    IssueCommandRequest, IssueCommandResponse, PointCheckResponse, RouteCheckResponse, UsageQuery,
+    UsageRecord,
+};
+
+/// Client for the ATC server's operator API.
+///
+/// Everything except the public geofence reads needs the admin token, sent as a bearer token.
+#[derive(Debug, Clone)]
+pub struct AtcApiClient {
+    base_url: String,
+    admin_token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl AtcApiClient {
+    /// Create a client for the server at `base_url` (e.g. `http://localhost:3000`).
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    /// Create a client that sends requests through an existing `reqwest` client.
+    pub fn with_http_client(base_url: impl Into<String>, client: reqwest::Client) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        Self {
+            base_url,
+            admin_token: None,
+            client,
+        }
+    }
+
+    /// Set the admin token for control-plane endpoints.
+    pub fn set_admin_token(&mut self, token: Option<String>) {
+        self.admin_token = token;
+    }
+
+    /// Builder-style [`Self::set_admin_token`].
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // ========== FLIGHTS ==========
+
+    /// List flight plans, newest first.
+    pub async fn list_flight_plans(&self, query: &FlightPlansQuery) -> ApiResult<Vec<FlightPlan>> {
+        self.send(self.request(Method::GET, "/v1/flights").query(query))
+            .await
+    }
+
+    /// Search stored flight plans, including completed and cancelled ones.
+    pub async fn flight_plan_history(
+        &self,
+        query: &FlightPlanHistoryQuery,
+    ) -> ApiResult<Vec<FlightPlan>> {
+        self.send(
+            self.request(Method::GET, "/v1/flights/history")
+                .query(query),
+        )
+        .await
+    }
+
+    /// Submit a flight plan; a route is generated when it has no waypoints.
+    ///
+    /// A plan rejected by the scheduler comes back as [`ApiError::Status`]; see
+    /// [`ApiError::rejected_plan`].
+    pub async fn create_flight_plan(&self, request: &FlightPlanRequest) -> ApiResult<FlightPlan> {
+        self.send(self.request(Method::POST, "/v1/flights/plan").json(request))
+            .await
+    }
+
+    /// Reserve an operational intent (slot); the plan comes back `reserved`.
+    pub async fn reserve_operational_intent(
+        &self,
+        request: &FlightPlanRequest,
+    ) -> ApiResult<FlightPlan> {
+        self.send(
+            self.request(Method::POST, "/v1/operational_intents/reserve")
+                .json(request),
+        )
+        .await
+    }
+
+    /// Confirm a reserved operational intent, transitioning it to `approved`.
+    pub async fn confirm_operational_intent(&self, flight_id: &str) -> ApiResult<FlightPlan> {
+        let path = format!("/v1/operational_intents/{}/confirm", flight_id);
+        self.send(self.request(Method::POST, &path)).await
+    }
+
+    /// Replace a reserved operational intent.
+    pub async fn update_operational_intent(
+        &self,
+        flight_id: &str,
+        request: &FlightPlanRequest,
+    ) -> ApiResult<FlightPlan> {
+        let path = format!("/v1/operational_intents/{}", flight_id);
+        self.send(self.request(Method::PUT, &path).json(request))
+            .await
+    }
+
+    /// Cancel an operational intent, transitioning it to `cancelled`.
+    pub async fn cancel_operational_intent(&self, flight_id: &str) -> ApiResult<FlightPlan> {
+        let path = format!("/v1/operational_intents/{}/cancel", flight_id);
+        self.send(self.request(Method::POST, &path)).await
+    }
+
+    // ========== GEOFENCES ==========
+
+    pub async fn list_geofences(&self) -> ApiResult<Vec<Geofence>> {
+        self.send(self.request(Method::GET, "/v1/geofences")).await
+    }
+
+    pub async fn get_geofence(&self, id: &str) -> ApiResult<Geofence> {
+        let path = format!("/v1/geofences/{}", id);
+        self.send(self.request(Method::GET, &path)).await
+    }
+
+    pub async fn create_geofence(&self, request: &CreateGeofenceRequest) -> ApiResult<Geofence> {
+        self.send(self.request(Method::POST, "/v1/geofences").json(request))
+            .await
+    }
+
+    pub async fn update_geofence(
+        &self,
+        id: &str,
+        request: &UpdateGeofenceRequest,
+    ) -> ApiResult<Geofence> {
+        let path = format!("/v1/geofences/{}", id);
+        self.send(self.request(Method::PUT, &path).json(request))
+            .await
+    }
+
+    pub async fn delete_geofence(&self, id: &str) -> ApiResult<()> {
+        let path = format!("/v1/geofences/{}", id);
+        self.send_empty(self.request(Method::DELETE, &path)).await
+    }
+
+    /// Active geofences containing a point; `altitude_m` defaults to 50 m on the server.
+    pub async fn check_point(
+        &self,
+        lat: f64,
+        lon: f64,
+        altitude_m: Option<f64>,
+    ) -> ApiResult<PointCheckResponse> {
+        let mut query = vec![("lat", lat), ("lon", lon)];
+        if let Some(altitude_m) = altitude_m {
+            query.push(("altitude_m", altitude_m));
+        }
+        self.send(
+            self.request(Method::GET, "/v1/geofences/check")
+                .query(&query),
+        )
+        .await
+    }
+
+    /// Active geofences crossed by a route.
+    pub async fn check_route(&self, waypoints: &[Waypoint]) -> ApiResult<RouteCheckResponse> {
+        self.send(
+            self.request(Method::POST, "/v1/geofences/check-route")
+                .json(&json!({ "waypoints": waypoints })),
+        )
+        .await
+    }
+
+    // ========== COMMANDS ==========
+
+    pub async fn issue_command(
+        &self,
+        request: &IssueCommandRequest,
+    ) -> ApiResult<IssueCommandResponse> {
+        self.send(self.request(Method::POST, "/v1/commands").json(request))
+            .await
+    }
+
+    pub async fn list_commands(&self) -> ApiResult<Vec<Command>> {
+        self.send(self.request(Method::GET, "/v1/commands")).await
+    }
+
+    // ========== DRONES ==========
+
+    pub async fn list_drones(&self, owner_id: Option<&str>) -> ApiResult<Vec<DroneState>> {
+        let mut builder = self.request(Method::GET, "/v1/drones");
+        if let Some(owner_id) = owner_id {
+            builder = builder.query(&[("owner_id", owner_id)]);
+        }
+        self.send(builder).await
+    }
+
+    pub async fn get_drone(&self, drone_id: &str) -> ApiResult<DroneState> {
+        let path = format!("/v1/drones/{}", drone_id);
+        self.send(self.request(Method::GET, &path)).await
+    }
+
+    // ========== ANALYTICS ==========
+
+    /// Conflicts currently detected.
+    pub async fn list_conflicts(&self, query: &ConflictQuery) -> ApiResult<Vec<Conflict>> {
+        self.send(self.request(Method::GET, "/v1/conflicts").query(query))
+            .await
+    }
+
+    /// Cleared conflicts with their peak severity and outcome.
+    pub async fn conflict_history(
+        &self,
+        query: &ConflictHistoryQuery,
+    ) -> ApiResult<Vec<ConflictRecord>> {
+        self.send(
+            self.request(Method::GET, "/v1/conflicts/history")
+                .query(query),
+        )
+        .await
+    }
+
+    /// Monthly usage per organization.
+    pub async fn billing_usage(&self, query: &UsageQuery) -> ApiResult<Vec<UsageRecord>> {
+        self.send(self.request(Method::GET, "/v1/billing/usage").query(query))
+            .await
+    }
+
+    /// What each scheduler fairness policy did per operator.
+    pub async fn scheduler_fairness(&self) -> ApiResult<Value> {
+        self.send(self.request(Method::GET, "/v1/scheduler/fairness"))
+            .await
+    }
+
+    // ========== ADMIN ==========
+
+    /// Clear all server state. With `require_idle`, fails while drones are active.
+    pub async fn admin_reset(&self, require_idle: bool) -> ApiResult<Value> {
+        self.send(
+            self.request(Method::POST, "/v1/admin/reset")
+                .json(&json!({ "confirm": "RESET", "require_idle": require_idle })),
+        )
+        .await
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let builder = self
+            .client
+            .request(method, format!("{}{}", self.base_url, path));
+        match self.admin_token.as_deref() {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(&self, builder: RequestBuilder) -> ApiResult<T> {
+        let body = self.send_raw(builder).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    async fn send_empty(&self, builder: RequestBuilder) -> ApiResult<()> {
+        self.send_raw(builder).await.map(|_| ())
+    }
+
+    async fn send_raw(&self, builder: RequestBuilder) -> ApiResult<Vec<u8>> {
+        let response = builder.send().await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+        if !status.is_success() {
+            return Err(error_for(status, &body));
+        }
+        Ok(body.to_vec())
+    }
+}
+
+fn error_for(status: StatusCode, body: &[u8]) -> ApiError {
+    let body = serde_json::from_slice(body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(body).into_owned()));
+    ApiError::Status { status, body }
+}
@@ -0,0 +1,48 @@
+//! Errors returned by the API client.
+
+use atc_core::models::FlightPlan;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    /// The server answered with a non-success status.
+    #[error("ATC request failed ({status}): {body}")]
+    Status { status: StatusCode, body: Value },
+    /// The request never got a response.
+    #[error("ATC request failed: {0}")]
+    Transport(#[from] reqwest::Error),
+    /// The response body did not match the expected type.
+    #[error("unexpected ATC response: {0}")]
+    Decode(#[from] serde_json::Error),
+}
+
+impl ApiError {
+    /// HTTP status of a server-side failure.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// The `error` message of a server-side failure, when the body has one.
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Self::Status { body, .. } => body.get("error").and_then(Value::as_str),
+            _ => None,
+        }
+    }
+
+    /// The plan a scheduling conflict (409) was rejected with, so callers can inspect why.
+    pub fn rejected_plan(&self) -> Option<FlightPlan> {
+        match self {
+            Self::Status { status, body } if *status == StatusCode::CONFLICT => {
+                serde_json::from_value(body.get("plan")?.clone()).ok()
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! ATC API client - Typed access to the ATC server's HTTP API
+//!
+//! Covers the control-plane routes used by operator tooling (flights, geofences, commands,
+//! drones and analytics) with the same request and response types the server speaks, so
+//! frontends and the CLI do not redeclare them. Drones should use `atc-sdk` instead.
+
+pub mod client;
+pub mod error;
+pub mod types;
+
+pub use client::AtcApiClient;
+pub use error::{ApiError, ApiResult};
+pub use types::{
+    ConflictHistoryQuery, ConflictOutcome, ConflictQuery, ConflictRecord, FlightPlanHistoryQuery,
+    FlightPlansQuery, GeofenceConflict, IssueCommandRequest, IssueCommandResponse,
+    PointCheckResponse, RouteCheckResponse, UsageQuery, UsageRecord,
+};
@@ -0,0 +1,176 @@
+//! Request and response types of server routes that have no `atc-core` model.
+
+use atc_core::models::CommandType;
+use atc_core::ConflictSeverity;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Filters for `GET /v1/flights`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlightPlansQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+}
+
+/// Filters for `GET /v1/flights/history`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlightPlanHistoryQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drone_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<atc_core::models::FlightStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Body of `POST /v1/commands`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueCommandRequest {
+    pub drone_id: String,
+    /// Owner/operator ID for access enforcement
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<String>,
+    #[serde(flatten)]
+    pub command_type: CommandType,
+    /// Command expiry in seconds (default: 60)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_in_secs: Option<u32>,
+}
+
+impl IssueCommandRequest {
+    pub fn new(drone_id: impl Into<String>, command_type: CommandType) -> Self {
+        Self {
+            drone_id: drone_id.into(),
+            owner_id: None,
+            command_type,
+            expires_in_secs: None,
+        }
+    }
+}
+
+/// Response of `POST /v1/commands`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueCommandResponse {
+    pub command_id: String,
+    pub drone_id: String,
+    pub status: String,
+}
+
+/// Response of `GET /v1/geofences/check`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PointCheckResponse {
+    pub inside_geofence: bool,
+    pub geofence_ids: Vec<String>,
+}
+
+/// Response of `POST /v1/geofences/check-route`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteCheckResponse {
+    pub conflicts: bool,
+    pub conflicting_geofences: Vec<GeofenceConflict>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeofenceConflict {
+    pub geofence_id: String,
+    pub geofence_name: String,
+    pub segment_index: usize,
+}
+
+/// Filters for `GET /v1/conflicts`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConflictQuery {
+    /// Filter conflicts by owner ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<String>,
+    /// Filter conflicts by airspace sector
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sector_id: Option<String>,
+    /// Conflicts involving this drone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drone_id: Option<String>,
+    /// Comma-separated severities, e.g. `warning,critical`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+    /// `X-Next-Cursor` of the previous page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// Filters for `GET /v1/conflicts/history`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConflictHistoryQuery {
+    /// Conflicts involving this drone
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub drone_id: Option<String>,
+    /// Conflicts still active at or after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    /// Conflicts that started before this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// How a conflict ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictOutcome {
+    /// The pair regained separation while both drones were still tracked.
+    Resolved,
+    /// One of the drones stopped being tracked (lost, landed or removed).
+    Expired,
+}
+
+/// A conflict from first detection until it cleared, from `GET /v1/conflicts/history`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictRecord {
+    pub conflict_id: String,
+    pub drone1_id: String,
+    pub drone2_id: String,
+    pub peak_severity: ConflictSeverity,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// Smallest separation observed while the conflict was active (meters).
+    pub min_separation_m: f64,
+    pub sector_id: Option<String>,
+    pub outcome: ConflictOutcome,
+}
+
+/// Filters for `GET /v1/billing/usage`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageQuery {
+    /// Organization (operator `owner_id`) to report on; all when omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    /// Calendar month `YYYY-MM`; all months when omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub month: Option<String>,
+}
+
+/// Usage for one organization in one month, from `GET /v1/billing/usage`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub organization: String,
+    /// Calendar month, `YYYY-MM` (UTC).
+    pub month: String,
+    pub flight_hours: f64,
+    pub plans_submitted: u64,
+    pub planner_cpu_seconds: f64,
+    pub api_calls: u64,
+}
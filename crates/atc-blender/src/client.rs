@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::time::Duration;
 
+use crate::fault;
+
 /// Generate a dummy JWT token that Blender will accept.
 /// Blender with BYPASS_AUTH_TOKEN_VERIFICATION=1 still validates:
 /// - Token format (must be header.payload.signature)
@@ -97,7 +99,10 @@ impl BlenderClient {
         }
     }
 
-    fn apply_request_id(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    pub(crate) fn apply_request_id(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
         match self.request_id.as_deref() {
             Some(value) if !value.is_empty() => request.header("X-Request-ID", value),
             _ => request,
@@ -132,6 +137,7 @@ impl BlenderClient {
         heading: f64,
         speed_mps: f64,
     ) -> Result<u16> {
+        fault::check("send_observation")?;
         let url = format!(
             "{}/flight_stream/set_air_traffic/{}",
             self.base_url, self.session_id
@@ -159,6 +165,7 @@ impl BlenderClient {
 
     /// Send a snapshot of all drones to Blender.
     pub async fn send_snapshot(&self, drones: &[atc_core::models::DroneState]) -> Result<u16> {
+        fault::check("send_snapshot")?;
         if drones.is_empty() {
             return Ok(200);
         }
@@ -214,6 +221,7 @@ impl BlenderClient {
         &self,
         aircraft_id: &str,
     ) -> Result<BlenderConformanceStatusResponse> {
+        fault::check("fetch_conformance_status")?;
         let url = format!(
             "{}/conformance_monitoring_operations/conformance_status/?aircraft_id={}",
             self.base_url, aircraft_id
@@ -247,6 +255,7 @@ impl BlenderClient {
 
     /// Create a DSS Remote ID subscription for a viewport.
     pub async fn create_rid_subscription(&self, view: &str) -> Result<String> {
+        fault::check("create_rid_subscription")?;
         let url = format!("{}/rid/create_dss_subscription", self.base_url);
 
         let auth_header = self.auth_header();
@@ -292,6 +301,7 @@ impl BlenderClient {
 
     /// Fetch RID data for a DSS subscription.
     pub async fn fetch_rid_data(&self, subscription_id: &str) -> Result<Value> {
+        fault::check("fetch_rid_data")?;
         let url = format!("{}/rid/get_rid_data/{}", self.base_url, subscription_id);
 
         let auth_header = self.auth_header();
@@ -326,6 +336,7 @@ impl BlenderClient {
 
     /// Create a geofence in Flight Blender and return its ID.
     pub async fn create_geofence(&self, payload: &Value) -> Result<String> {
+        fault::check("create_geofence")?;
         let url = format!("{}/geo_fence_ops/set_geo_fence", self.base_url);
         let auth_header = self.auth_header();
 
@@ -365,6 +376,7 @@ impl BlenderClient {
 
     /// Fetch geofences from Flight Blender (optionally filtered by view bbox).
     pub async fn fetch_geofences(&self, view: Option<&str>) -> Result<Vec<Value>> {
+        fault::check("fetch_geofences")?;
         let auth_header = self.auth_header();
         let mut results: Vec<Value> = Vec::new();
         let mut next_url = Some(format!("{}/geo_fence_ops/geo_fence", self.base_url));
@@ -429,6 +441,7 @@ impl BlenderClient {
 
     /// Fetch flight declarations from Flight Blender.
     pub async fn fetch_flight_declarations(&self) -> Result<Vec<Value>> {
+        fault::check("fetch_flight_declarations")?;
         let auth_header = self.auth_header();
         let mut results: Vec<Value> = Vec::new();
         let mut next_url = Some(format!(
@@ -485,6 +498,7 @@ impl BlenderClient {
 
     /// Check whether a flight declaration exists in Flight Blender.
     pub async fn flight_declaration_exists(&self, declaration_id: &str) -> Result<bool> {
+        fault::check("flight_declaration_exists")?;
         let url = format!(
             "{}/flight_declaration_ops/flight_declaration/{}",
             self.base_url, declaration_id
@@ -516,6 +530,7 @@ impl BlenderClient {
 
     /// Delete a geofence in Flight Blender by ID.
     pub async fn delete_geofence(&self, geofence_id: &str) -> Result<()> {
+        fault::check("delete_geofence")?;
         let url = format!(
             "{}/geo_fence_ops/geo_fence/{}/delete",
             self.base_url, geofence_id
@@ -0,0 +1,31 @@
+//! Fault injection hook for chaos testing.
+//!
+//! When a hook is installed it is consulted before every outbound Blender request; returning an
+//! error message fails the call without touching the network. No hook is installed by default.
+
+use std::sync::{Arc, OnceLock, RwLock};
+
+use anyhow::{anyhow, Result};
+
+/// Called with the client operation name; `Some(message)` fails the request.
+pub type FaultHook = Arc<dyn Fn(&'static str) -> Option<String> + Send + Sync>;
+
+fn hook_slot() -> &'static RwLock<Option<FaultHook>> {
+    static HOOK: OnceLock<RwLock<Option<FaultHook>>> = OnceLock::new();
+    HOOK.get_or_init(|| RwLock::new(None))
+}
+
+/// Install (or clear) the process-wide fault hook.
+pub fn set_fault_hook(hook: Option<FaultHook>) {
+    if let Ok(mut slot) = hook_slot().write() {
+        *slot = hook;
+    }
+}
+
+pub(crate) fn check(operation: &'static str) -> Result<()> {
+    let hook = hook_slot().read().ok().and_then(|slot| slot.clone());
+    match hook.and_then(|hook| hook(operation)) {
+        Some(message) => Err(anyhow!("injected fault in {}: {}", operation, message)),
+        None => Ok(()),
+    }
+}
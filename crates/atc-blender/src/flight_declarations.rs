@@ -0,0 +1,144 @@
+//! Create Flight Blender flight declarations from approved ATC flight plans.
+//!
+//! The ATC plan is embedded under `properties.compliance.atc_plan` so the declaration
+//! import loop maps it back to the same flight instead of creating a duplicate.
+
+use anyhow::{Context, Result};
+use atc_core::models::FlightPlan;
+use chrono::Duration as ChronoDuration;
+use serde_json::{json, Value};
+
+use super::client::BlenderClient;
+use super::fault;
+
+/// Fallback operation length when neither arrival time nor flight time is known.
+const DEFAULT_OPERATION_MINUTES: i64 = 30;
+/// Blender `type_of_operation` used when the plan does not specify one (1 = VLOS).
+const DEFAULT_OPERATION_TYPE: u8 = 1;
+
+/// Build a `set_flight_declaration` payload for an ATC flight plan.
+pub fn flight_declaration_payload(plan: &FlightPlan, originating_party: &str) -> Result<Value> {
+    if plan.waypoints.len() < 2 {
+        anyhow::bail!("flight plan {} has fewer than 2 waypoints", plan.flight_id);
+    }
+
+    let metadata = plan.metadata.clone().unwrap_or_default();
+    let start = plan.departure_time;
+    let end = plan
+        .arrival_time
+        .filter(|arrival| *arrival > start)
+        .or_else(|| {
+            metadata
+                .total_flight_time_s
+                .filter(|secs| secs.is_finite() && *secs > 0.0)
+                .map(|secs| start + ChronoDuration::seconds(secs.ceil() as i64))
+        })
+        .unwrap_or_else(|| start + ChronoDuration::minutes(DEFAULT_OPERATION_MINUTES));
+
+    let coordinates: Vec<[f64; 2]> = plan.waypoints.iter().map(|wp| [wp.lon, wp.lat]).collect();
+    let min_alt = plan
+        .waypoints
+        .iter()
+        .map(|wp| wp.altitude_m)
+        .fold(f64::INFINITY, f64::min);
+    let max_alt = plan
+        .waypoints
+        .iter()
+        .map(|wp| wp.altitude_m)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let atc_waypoints: Vec<Value> = plan
+        .waypoints
+        .iter()
+        .map(|wp| {
+            json!({
+                "lat": wp.lat,
+                "lon": wp.lon,
+                "alt": wp.altitude_m,
+                "speed_mps": wp.speed_mps,
+            })
+        })
+        .collect();
+    let mut atc_metadata = metadata.clone();
+    // Keep the embedded copy small; the full report stays on the ATC side.
+    atc_metadata.compliance_report = None;
+
+    Ok(json!({
+        "originating_party": originating_party,
+        "start_datetime": start.to_rfc3339(),
+        "end_datetime": end.to_rfc3339(),
+        "type_of_operation": metadata.operation_type.unwrap_or(DEFAULT_OPERATION_TYPE),
+        "aircraft_id": plan.drone_id,
+        "vehicle_id": plan.drone_id,
+        "operator_id": plan.owner_id.as_deref().unwrap_or(originating_party),
+        "flight_declaration_geo_json": {
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "properties": {
+                    "min_altitude": { "meters": min_alt, "datum": "W84" },
+                    "max_altitude": { "meters": max_alt, "datum": "W84" },
+                    "compliance": {
+                        "atc_plan_id": plan.flight_id,
+                        "atc_plan": {
+                            "id": plan.flight_id,
+                            "waypoints": atc_waypoints,
+                            "trajectory_log": plan.trajectory_log.clone().unwrap_or_default(),
+                            "metadata": atc_metadata,
+                        }
+                    }
+                },
+                "geometry": {
+                    "type": "LineString",
+                    "coordinates": coordinates,
+                }
+            }]
+        }
+    }))
+}
+
+impl BlenderClient {
+    /// Submit a flight declaration to Flight Blender and return its ID.
+    pub async fn create_flight_declaration(&self, payload: &Value) -> Result<String> {
+        fault::check("create_flight_declaration")?;
+        let url = format!(
+            "{}/flight_declaration_ops/set_flight_declaration",
+            self.base_url
+        );
+        let auth_header = self.auth_header();
+
+        let response = self
+            .apply_request_id(
+                self.client
+                    .post(&url)
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", auth_header)
+                    .json(payload),
+            )
+            .send()
+            .await
+            .context("Failed to create flight declaration")?;
+
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .context("Failed to parse flight declaration response")?;
+
+        if !status.is_success() {
+            return Err(anyhow::anyhow!(
+                "Flight declaration create failed: {} {}",
+                status,
+                body
+            ));
+        }
+
+        let declaration_id = body
+            .get("id")
+            .or_else(|| body.get("flight_declaration_id"))
+            .and_then(|value| value.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Flight declaration response missing ID"))?;
+
+        Ok(declaration_id.to_string())
+    }
+}
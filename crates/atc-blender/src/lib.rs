@@ -3,7 +3,14 @@
 //! Handles all communication with the Flight Blender UTM backend.
 
 pub mod client;
+pub mod fault;
+pub mod flight_declarations;
 pub mod sync_geofences;
 
 pub use client::BlenderClient;
-pub use sync_geofences::{conflict_payload, conflict_to_geofence, ConflictGeofence};
+pub use fault::{set_fault_hook, FaultHook};
+pub use flight_declarations::flight_declaration_payload;
+pub use sync_geofences::{
+    conflict_payload, conflict_to_geofence, AvoidanceDirection, AvoidanceRecommendation,
+    ConflictGeofence, CONFLICT_PROPERTIES_VERSION,
+};
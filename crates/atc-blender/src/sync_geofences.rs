@@ -2,15 +2,23 @@
 //!
 //! When conflicts are detected, we push them as temporary
 //! geofences so they appear as red zones in Spotlight.
+//!
+//! Each geofence carries the conflict's severity, the aircraft involved, the predicted duration
+//! and the recommended avoidance as properties, so displays and third parties can render
+//! guidance without querying the ATC. The property layout is versioned by
+//! [`CONFLICT_PROPERTIES_VERSION`].
 
 use anyhow::{Context, Result};
 use atc_core::spatial::offset_by_bearing;
-use atc_core::{Conflict, ConflictSeverity};
+use atc_core::{AvoidanceType, Conflict, ConflictSeverity, Maneuver, ResolutionOption};
 use chrono::{Duration as ChronoDuration, Utc};
 use serde::Serialize;
 use serde_json::Value;
 use std::f64::consts::PI;
 
+/// Version of the conflict properties layout; bumped whenever fields change meaning or go away.
+pub const CONFLICT_PROPERTIES_VERSION: u32 = 2;
+
 /// GeoJSON Feature for a conflict zone geofence.
 #[derive(Debug, Serialize)]
 pub struct ConflictGeofence {
@@ -38,14 +46,87 @@ pub struct GeoJsonGeometry {
     pub coordinates: Vec<Vec<[f64; 2]>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct GeoJsonProperties {
+    /// Always [`CONFLICT_PROPERTIES_VERSION`].
+    pub properties_version: u32,
     pub name: String,
     pub severity: String,
     pub drone1_id: String,
     pub drone2_id: String,
     pub distance_m: f64,
     pub time_to_closest: f64,
+    /// Seconds the predicted violation lasts
+    pub predicted_duration_s: f64,
+    /// Both aircraft, in the order of `drone1_id` and `drone2_id`
+    pub aircraft: Vec<ConflictAircraft>,
+    /// Absent for info conflicts and when the ATC has no avoidance to recommend
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommended_avoidance: Option<AvoidanceRecommendation>,
+}
+
+/// An aircraft involved in a conflict.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConflictAircraft {
+    pub id: String,
+    /// Last known position, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lon: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude_m: Option<f64>,
+    /// Whether this aircraft is the one recommended to give way
+    pub gives_way: bool,
+}
+
+/// How the recommended avoidance is flown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum AvoidanceDirection {
+    /// A single maneuver: `climb`, `descend`, `turn_right`, `turn_left`, `speed_up` or `slow_down`.
+    Maneuver(Maneuver),
+    /// A reroute around the conflict: `lateral`, `vertical` or `combined`.
+    Reroute(AvoidanceType),
+}
+
+/// Avoidance the ATC recommends for a conflict.
+#[derive(Debug, Clone, Serialize)]
+pub struct AvoidanceRecommendation {
+    /// Aircraft expected to give way
+    pub drone_id: String,
+    pub direction: AvoidanceDirection,
+    /// Targets of a single maneuver; absent for reroutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_heading_deg: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_speed_mps: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_altitude_m: Option<f64>,
+}
+
+impl AvoidanceRecommendation {
+    /// Recommend flying a resolution maneuver.
+    pub fn maneuver(drone_id: impl Into<String>, option: &ResolutionOption) -> Self {
+        Self {
+            drone_id: drone_id.into(),
+            direction: AvoidanceDirection::Maneuver(option.maneuver),
+            target_heading_deg: Some(option.target_heading_deg),
+            target_speed_mps: Some(option.target_speed_mps),
+            target_altitude_m: Some(option.target_altitude_m),
+        }
+    }
+
+    /// Recommend rerouting around the conflict.
+    pub fn reroute(drone_id: impl Into<String>, avoidance_type: AvoidanceType) -> Self {
+        Self {
+            drone_id: drone_id.into(),
+            direction: AvoidanceDirection::Reroute(avoidance_type),
+            target_heading_deg: None,
+            target_speed_mps: None,
+            target_altitude_m: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -70,6 +151,7 @@ struct BlenderGeofenceProperties {
     lower_limit: i32,
     start_time: String,
     end_time: String,
+    conflict: GeoJsonProperties,
 }
 
 #[derive(Debug, Serialize)]
@@ -107,6 +189,21 @@ pub fn conflict_to_geofence(
     } else {
         (conflict.drone2_id.as_str(), conflict.drone1_id.as_str())
     };
+    let (pos_a, pos_b) = if id_a == conflict.drone1_id {
+        (drone1_pos, drone2_pos)
+    } else {
+        (drone2_pos, drone1_pos)
+    };
+    let aircraft = [(id_a, pos_a), (id_b, pos_b)]
+        .into_iter()
+        .map(|(id, pos)| ConflictAircraft {
+            id: id.to_string(),
+            lat: pos.map(|p| p.0),
+            lon: pos.map(|p| p.1),
+            altitude_m: pos.map(|p| p.2),
+            gives_way: false,
+        })
+        .collect();
 
     // Calculate midpoint between the two drones (if positions known)
     let (center_lat, center_lon, center_alt) = match (drone1_pos, drone2_pos) {
@@ -154,12 +251,16 @@ pub fn conflict_to_geofence(
                 coordinates: vec![polygon_coords],
             },
             properties: GeoJsonProperties {
+                properties_version: CONFLICT_PROPERTIES_VERSION,
                 name: geofence_id,
                 severity: severity_str.to_string(),
                 drone1_id: id_a.to_string(),
                 drone2_id: id_b.to_string(),
                 distance_m: conflict.distance_m,
                 time_to_closest: conflict.time_to_closest,
+                predicted_duration_s: conflict.predicted_duration_s,
+                aircraft,
+                recommended_avoidance: None,
             },
         },
         geozone_type: "conflict".to_string(),
@@ -167,12 +268,25 @@ pub fn conflict_to_geofence(
     }
 }
 
+impl ConflictGeofence {
+    /// Attach the recommended avoidance and mark the aircraft that gives way.
+    pub fn with_recommendation(mut self, recommendation: AvoidanceRecommendation) -> Self {
+        let properties = &mut self.raw_geo_fence.properties;
+        for aircraft in &mut properties.aircraft {
+            aircraft.gives_way = aircraft.id == recommendation.drone_id;
+        }
+        properties.recommended_avoidance = Some(recommendation);
+        self
+    }
+}
+
 pub fn conflict_payload(geofence: &ConflictGeofence) -> Result<Value> {
     let payload = build_blender_payload(geofence);
     serde_json::to_value(payload).context("Failed to serialize conflict geofence payload")
 }
 
 use super::client::BlenderClient;
+use super::fault;
 
 fn build_blender_payload(geofence: &ConflictGeofence) -> BlenderGeofencePayload {
     let start_time = Utc::now().to_rfc3339();
@@ -188,6 +302,7 @@ fn build_blender_payload(geofence: &ConflictGeofence) -> BlenderGeofencePayload
                 lower_limit: geofence.lower_limit,
                 start_time,
                 end_time,
+                conflict: geofence.raw_geo_fence.properties.clone(),
             },
             geometry: BlenderGeofenceGeometry {
                 geometry_type: geofence.raw_geo_fence.geometry.geometry_type.clone(),
@@ -200,6 +315,7 @@ fn build_blender_payload(geofence: &ConflictGeofence) -> BlenderGeofencePayload
 impl BlenderClient {
     /// Send conflict geofences to Blender.
     pub async fn send_conflict_geofences(&self, geofences: &[ConflictGeofence]) -> Result<u16> {
+        fault::check("send_conflict_geofences")?;
         if geofences.is_empty() {
             return Ok(200);
         }
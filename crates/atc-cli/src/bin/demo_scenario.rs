@@ -14,11 +14,11 @@
 //! If running against the unified Docker stack, default tokens match `docker-compose.unified.yml`:
 //!   --registration-token change-me --admin-token change-me-admin
 
+use atc_api_client::{ApiError, AtcApiClient};
 use atc_core::models::{Command, CommandType};
 use atc_core::spatial::{bearing, haversine_distance, offset_by_bearing};
 use atc_sdk::AtcClient;
 use clap::Parser;
-use serde_json::json;
 use std::env;
 use std::time::Duration;
 use tokio::sync::mpsc;
@@ -129,8 +129,9 @@ struct DemoState {
     hold_until: Option<time::Instant>,
     // Altitude offset from ALTITUDE_CHANGE commands
     altitude_offset_m: f64,
+    // Set by a LAND command; applied on the next tick
+    land_requested: bool,
     // Battery state (minutes)
-    battery_capacity_min: f64,
     battery_reserve_min: f64,
     battery_remaining_min: f64,
     battery_warned: bool,
@@ -171,7 +172,7 @@ impl DemoState {
             is_holding: false,
             hold_until: None,
             altitude_offset_m: 0.0,
-            battery_capacity_min: BATTERY_CAPACITY_MIN,
+            land_requested: false,
             battery_reserve_min: BATTERY_RESERVE_MIN,
             battery_remaining_min: BATTERY_CAPACITY_MIN,
             battery_warned: false,
@@ -386,6 +387,12 @@ async fn handle_command(drone: &mut DemoState, cmd: Command) {
                 drone.drone_id, target_altitude_m, drone.altitude_offset_m
             );
         }
+        CommandType::Land => {
+            drone.is_holding = false;
+            drone.is_rerouting = false;
+            drone.land_requested = true;
+            println!("  [CMD] {} LAND\n", drone.drone_id);
+        }
     }
 
     let _ = drone.client.ack_command(&cmd.command_id).await;
@@ -414,20 +421,11 @@ async fn main() -> anyhow::Result<()> {
     // Reset server state
     if args.reset {
         println!("[SETUP] Resetting server state...");
-        let client = reqwest::Client::new();
-        let response = client
-            .post(format!("{}/v1/admin/reset", args.url))
-            .bearer_auth(&admin_token)
-            .json(&json!({ "confirm": "RESET", "require_idle": false }))
-            .send()
-            .await;
-
-        match response {
-            Ok(resp) if resp.status().is_success() => println!("[SETUP] ✓ Server state cleared"),
-            Ok(resp) => {
-                let status = resp.status();
-                let body = resp.text().await.unwrap_or_default();
-                println!("[SETUP] ⚠ Reset status: {} {}", status, body.trim());
+        let api = AtcApiClient::new(&args.url).with_admin_token(&admin_token);
+        match api.admin_reset(false).await {
+            Ok(_) => println!("[SETUP] ✓ Server state cleared"),
+            Err(ApiError::Status { status, body }) => {
+                println!("[SETUP] ⚠ Reset status: {} {}", status, body)
             }
             Err(e) => println!(
                 "[SETUP] ⚠ Could not reset: {} (check --admin-token / ATC_ADMIN_TOKEN)",
@@ -640,6 +638,21 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
 
+            if drone.land_requested {
+                drone.land_requested = false;
+                if matches!(
+                    drone.phase,
+                    FlightPhase::Cruise | FlightPhase::Takeoff | FlightPhase::Preflight
+                ) {
+                    drone.phase = FlightPhase::Landing;
+                    drone.phase_start_time = elapsed;
+                    println!(
+                        "[{:3}] {} landing at current position",
+                        update_count, drone.drone_id
+                    );
+                }
+            }
+
             // Send telemetry with owner ID
             match drone
                 .client
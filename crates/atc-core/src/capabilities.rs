@@ -0,0 +1,123 @@
+//! Capabilities a drone advertises when it registers.
+//!
+//! Unlike the performance envelope, which describes what the airframe can physically fly,
+//! capabilities describe what the autopilot integration accepts: some vehicles cannot take a
+//! reroute mid-flight or loiter on command. Commands a drone cannot execute are adapted or
+//! refused before they are issued.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::CommandType;
+use crate::performance::DronePerformance;
+
+/// Commands and equipment a drone declared at registration.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DroneCapabilities {
+    /// Accepts REROUTE commands with new waypoints.
+    #[serde(default = "default_true")]
+    pub supports_reroute: bool,
+    /// Accepts HOLD commands (loiter in place).
+    #[serde(default = "default_true")]
+    pub supports_hold: bool,
+    /// Fastest climb the autopilot will command, when lower than the airframe's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_climb_rate_mps: Option<f64>,
+    /// Carries a Remote ID broadcast module.
+    #[serde(default)]
+    pub rid_module: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for DroneCapabilities {
+    /// Accepts every command; no Remote ID module.
+    fn default() -> Self {
+        Self {
+            supports_reroute: true,
+            supports_hold: true,
+            max_climb_rate_mps: None,
+            rid_module: false,
+        }
+    }
+}
+
+impl DroneCapabilities {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Some(rate) = self.max_climb_rate_mps {
+            if !rate.is_finite() || rate <= 0.0 {
+                errors.push("max_climb_rate_mps must be a positive number".to_string());
+            }
+        }
+        errors
+    }
+
+    /// Why the drone cannot execute `command`, if it cannot.
+    pub fn unsupported(&self, command: &CommandType) -> Option<String> {
+        match command {
+            CommandType::Reroute { .. } if !self.supports_reroute => {
+                Some("does not accept REROUTE commands".to_string())
+            }
+            CommandType::Hold { .. } if !self.supports_hold => {
+                Some("does not accept HOLD commands".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// `performance` with the climb rate capped at the advertised maximum. A drone with a
+    /// climb limit but no registered envelope gets the default envelope with that limit.
+    pub fn limit_envelope(
+        &self,
+        performance: Option<DronePerformance>,
+    ) -> Option<DronePerformance> {
+        let Some(max_climb_rate_mps) = self.max_climb_rate_mps else {
+            return performance;
+        };
+        let performance = performance.unwrap_or_default();
+        Some(DronePerformance {
+            max_climb_rate_mps: performance.max_climb_rate_mps.min(max_climb_rate_mps),
+            ..performance
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_unsupported_commands_and_caps_climb() {
+        let capabilities: DroneCapabilities =
+            serde_json::from_str(r#"{"supports_reroute": false, "max_climb_rate_mps": 1.5}"#)
+                .expect("capabilities");
+        assert!(capabilities.validate().is_empty());
+        assert!(capabilities.supports_hold && !capabilities.rid_module);
+
+        let reroute = CommandType::Reroute {
+            waypoints: Vec::new(),
+            reason: None,
+        };
+        assert!(capabilities.unsupported(&reroute).is_some());
+        assert!(capabilities
+            .unsupported(&CommandType::Hold { duration_secs: 30 })
+            .is_none());
+
+        let envelope = capabilities.limit_envelope(None).expect("envelope");
+        assert_eq!(envelope.max_climb_rate_mps, 1.5);
+        let slower = DronePerformance {
+            max_climb_rate_mps: 1.0,
+            ..DronePerformance::default()
+        };
+        assert_eq!(capabilities.limit_envelope(Some(slower)), Some(slower));
+        assert_eq!(DroneCapabilities::default().limit_envelope(None), None);
+
+        let invalid = DroneCapabilities {
+            max_climb_rate_mps: Some(0.0),
+            ..DroneCapabilities::default()
+        };
+        assert_eq!(invalid.validate().len(), 1);
+    }
+}
@@ -7,8 +7,19 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::crewed_traffic::{AircraftCategory, CrewedProtection};
+use crate::models::{Geofence, GeofenceType};
+use crate::rules::VolumeSeparationRule;
+use crate::well_clear::{self, WellClearParams};
+
 const METERS_PER_DEG_LAT: f64 = 111_320.0;
 const CPA_EPS: f64 = 1e-9;
+/// Sampling step when searching for a predicted loss of well clear.
+const WELL_CLEAR_STEP_S: f64 = 0.5;
+/// Sampling step when searching for a predicted geofence entry.
+const BREACH_STEP_S: f64 = 0.25;
+/// Bisection steps refining a predicted geofence entry time.
+const BREACH_REFINE_STEPS: u32 = 8;
 
 /// Severity levels for detected conflicts.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,12 +33,25 @@ pub enum ConflictSeverity {
     Critical,
 }
 
+impl ConflictSeverity {
+    /// Order of escalation, from `Info` (0) to `Critical` (2).
+    pub fn rank(self) -> u8 {
+        match self {
+            ConflictSeverity::Info => 0,
+            ConflictSeverity::Warning => 1,
+            ConflictSeverity::Critical => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct ClosestApproach {
     distance_m: f64,
     time_s: f64,
     pos1: (f64, f64, f64),
     pos2: (f64, f64, f64),
+    /// Length of the violated window the approach was found in.
+    window_s: f64,
 }
 
 /// Current position and velocity of a drone.
@@ -45,6 +69,13 @@ pub struct DronePosition {
     pub velocity_z: f64,
     #[serde(default = "current_timestamp")]
     pub timestamp: f64,
+    /// Crewed tracks are protected by the larger crewed-traffic volume.
+    #[serde(default)]
+    pub category: AircraftCategory,
+    /// Radius (meters) the true position may lie from this one; the separation minima around
+    /// the drone grow by it.
+    #[serde(default)]
+    pub uncertainty_m: f64,
 }
 
 fn current_timestamp() -> f64 {
@@ -66,6 +97,8 @@ impl DronePosition {
             speed_mps: 0.0,
             velocity_z: 0.0,
             timestamp: current_timestamp(),
+            category: AircraftCategory::default(),
+            uncertainty_m: 0.0,
         }
     }
 
@@ -76,6 +109,18 @@ impl DronePosition {
         self.velocity_z = velocity_z;
         self
     }
+
+    /// Set the aircraft category.
+    pub fn with_category(mut self, category: AircraftCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Set the position uncertainty radius.
+    pub fn with_uncertainty(mut self, uncertainty_m: f64) -> Self {
+        self.uncertainty_m = uncertainty_m;
+        self
+    }
 }
 
 /// Detected conflict between two drones.
@@ -93,9 +138,225 @@ pub struct Conflict {
     pub cpa_lat: f64,
     pub cpa_lon: f64,
     pub cpa_altitude_m: f64,
+    /// Horizontal separation at closest approach (meters)
+    #[serde(default)]
+    pub cpa_horizontal_m: f64,
+    /// Vertical separation at closest approach (meters)
+    #[serde(default)]
+    pub cpa_vertical_m: f64,
+    /// Unix timestamp (seconds) of closest approach
+    #[serde(default)]
+    pub cpa_time: f64,
+    /// Seconds the predicted violation lasts, capped at the lookahead
+    #[serde(default)]
+    pub predicted_duration_s: f64,
+    pub timestamp: f64,
+    /// Airspace sector containing the CPA, tagged by the server for dispatcher routing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sector_id: Option<String>,
+    /// Lowered to info because both drones' active plans already keep them separated
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub intent_downgraded: bool,
+    /// Lowered to info because an external track involved is below the quality threshold
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub low_quality_track: bool,
+    /// One of the pair is crewed traffic
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub crewed_traffic: bool,
+}
+
+/// Drone inside, or projected to enter, a geofence within the lookahead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeofenceBreach {
+    pub drone_id: String,
+    pub geofence_id: String,
+    pub geofence_name: String,
+    pub geofence_type: GeofenceType,
+    /// Critical when already inside, warning when projected to enter
+    pub severity: ConflictSeverity,
+    /// Seconds until the boundary is crossed (0 when inside)
+    pub time_to_breach_s: f64,
+    /// Predicted entry point (the drone's position when inside)
+    pub entry_lat: f64,
+    pub entry_lon: f64,
+    pub entry_altitude_m: f64,
     pub timestamp: f64,
 }
 
+/// Project a drone along its velocity and find when it enters `geofence`, if within
+/// `lookahead_s`.
+pub fn predict_geofence_breach(
+    drone: &DronePosition,
+    geofence: &Geofence,
+    lookahead_s: f64,
+) -> Option<GeofenceBreach> {
+    if !geofence.active {
+        return None;
+    }
+    let breach = |severity, time_to_breach_s, (lat, lon, alt): (f64, f64, f64)| GeofenceBreach {
+        drone_id: drone.drone_id.clone(),
+        geofence_id: geofence.id.clone(),
+        geofence_name: geofence.name.clone(),
+        geofence_type: geofence.geofence_type,
+        severity,
+        time_to_breach_s,
+        entry_lat: lat,
+        entry_lon: lon,
+        entry_altitude_m: alt,
+        timestamp: drone.timestamp,
+    };
+    if geofence.contains_point(drone.lat, drone.lon, drone.altitude_m) {
+        return Some(breach(
+            ConflictSeverity::Critical,
+            0.0,
+            (drone.lat, drone.lon, drone.altitude_m),
+        ));
+    }
+    if lookahead_s <= 0.0 || (drone.speed_mps <= 0.0 && drone.velocity_z == 0.0) {
+        return None;
+    }
+    let project = |t: f64| ConflictDetector::predict_position(drone, t);
+    let end = project(lookahead_s);
+    if !geofence.intersects_segment(drone.lat, drone.lon, drone.altitude_m, end.0, end.1, end.2) {
+        return None;
+    }
+    let inside = |t: f64| {
+        let (lat, lon, alt) = project(t);
+        geofence.contains_point(lat, lon, alt)
+    };
+
+    // First sample inside the fence, then bisect back to the boundary. A track that only
+    // clips a corner between samples is reported at the end of the lookahead.
+    let steps = (lookahead_s / BREACH_STEP_S).ceil().max(1.0) as u32;
+    let Some(first_inside) = (1..=steps)
+        .map(|step| (step as f64 * BREACH_STEP_S).min(lookahead_s))
+        .find(|t| inside(*t))
+    else {
+        return Some(breach(ConflictSeverity::Warning, lookahead_s, end));
+    };
+    let (mut outside_t, mut inside_t) = ((first_inside - BREACH_STEP_S).max(0.0), first_inside);
+    for _ in 0..BREACH_REFINE_STEPS {
+        let mid = (outside_t + inside_t) / 2.0;
+        if inside(mid) {
+            inside_t = mid;
+        } else {
+            outside_t = mid;
+        }
+    }
+    Some(breach(
+        ConflictSeverity::Warning,
+        inside_t,
+        project(inside_t),
+    ))
+}
+
+/// Separation minima and lookahead used to classify a drone pair.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeparationThresholds {
+    pub lookahead_seconds: f64,
+    pub horizontal_m: f64,
+    pub vertical_m: f64,
+    /// Warning band, as multiples of the horizontal and vertical minima.
+    pub warning_horizontal_multiplier: f64,
+    pub warning_vertical_multiplier: f64,
+    /// Info band beyond the warning band, as (horizontal, vertical) multiples of the minima;
+    /// `None` reports no info conflicts.
+    pub info_multipliers: Option<(f64, f64)>,
+}
+
+impl SeparationThresholds {
+    /// Apply a volume rule on top of these thresholds.
+    pub fn with_rule(self, rule: &VolumeSeparationRule) -> Self {
+        Self {
+            lookahead_seconds: rule.lookahead_seconds.unwrap_or(self.lookahead_seconds),
+            horizontal_m: rule
+                .min_horizontal_separation_m
+                .unwrap_or(self.horizontal_m),
+            vertical_m: rule.min_vertical_separation_m.unwrap_or(self.vertical_m),
+            warning_horizontal_multiplier: rule
+                .warning_horizontal_multiplier
+                .or(rule.warning_multiplier)
+                .unwrap_or(self.warning_horizontal_multiplier),
+            warning_vertical_multiplier: rule
+                .warning_vertical_multiplier
+                .or(rule.warning_multiplier)
+                .unwrap_or(self.warning_vertical_multiplier),
+            info_multipliers: self.info_multipliers,
+        }
+    }
+
+    /// Grow the minima by an uncertainty radius, the vertical minimum in proportion.
+    pub fn widened_by(self, uncertainty_m: f64) -> Self {
+        if !uncertainty_m.is_finite() || uncertainty_m <= 0.0 || self.horizontal_m <= 0.0 {
+            return self;
+        }
+        let scale = 1.0 + uncertainty_m / self.horizontal_m;
+        Self {
+            horizontal_m: self.horizontal_m * scale,
+            vertical_m: self.vertical_m * scale,
+            ..self
+        }
+    }
+
+    fn warning_horizontal_m(&self) -> f64 {
+        self.horizontal_m * self.warning_horizontal_multiplier
+    }
+
+    fn warning_vertical_m(&self) -> f64 {
+        self.vertical_m * self.warning_vertical_multiplier
+    }
+
+    fn info_band_m(&self) -> Option<(f64, f64)> {
+        self.info_multipliers.map(|(horizontal, vertical)| {
+            (self.horizontal_m * horizontal, self.vertical_m * vertical)
+        })
+    }
+
+    /// Widest horizontal distance any band reaches.
+    fn alert_horizontal_m(&self) -> f64 {
+        let outer = self.horizontal_m.max(self.warning_horizontal_m());
+        self.info_band_m()
+            .map_or(outer, |(horizontal, _)| outer.max(horizontal))
+    }
+
+    /// Field-wise maximum; a pair straddling two volumes gets the more conservative minima.
+    pub fn most_conservative(self, other: Self) -> Self {
+        Self {
+            lookahead_seconds: self.lookahead_seconds.max(other.lookahead_seconds),
+            horizontal_m: self.horizontal_m.max(other.horizontal_m),
+            vertical_m: self.vertical_m.max(other.vertical_m),
+            warning_horizontal_multiplier: self
+                .warning_horizontal_multiplier
+                .max(other.warning_horizontal_multiplier),
+            warning_vertical_multiplier: self
+                .warning_vertical_multiplier
+                .max(other.warning_vertical_multiplier),
+            info_multipliers: match (self.info_multipliers, other.info_multipliers) {
+                (Some(a), Some(b)) => Some((a.0.max(b.0), a.1.max(b.1))),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+}
+
+/// Airspace volume with its own separation thresholds.
+#[derive(Debug, Clone)]
+pub struct SeparationVolume {
+    pub volume: Geofence,
+    pub thresholds: SeparationThresholds,
+}
+
+/// How the detector decides that a pair is in conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DetectionMode {
+    /// Fixed horizontal/vertical separation minima (per volume), with a warning band.
+    #[default]
+    Separation,
+    /// DO-365 style well-clear volume: loss of well clear now is critical, predicted loss
+    /// within the lookahead is a warning.
+    WellClear(WellClearParams),
+}
+
 /// Real-time conflict detection engine.
 ///
 /// Uses position extrapolation to predict conflicts within a
@@ -107,9 +368,18 @@ pub struct ConflictDetector {
     pub separation_horizontal_m: f64,
     /// Minimum vertical separation (meters)
     pub separation_vertical_m: f64,
-    /// Multiplier for warning threshold
-    pub warning_multiplier: f64,
-
+    /// Warning band multipliers of the horizontal and vertical minima
+    pub warning_horizontal_multiplier: f64,
+    pub warning_vertical_multiplier: f64,
+    /// Info band multipliers (horizontal, vertical); `None` disables info conflicts
+    pub info_multipliers: Option<(f64, f64)>,
+
+    /// Per-volume threshold overrides, checked in order
+    volumes: Vec<SeparationVolume>,
+    /// Protection volume around crewed tracks; `None` treats them like drones
+    crewed_protection: Option<CrewedProtection>,
+    /// Conflict definition in use
+    mode: DetectionMode,
     /// Tracked drone positions
     drones: HashMap<String, DronePosition>,
     /// Active conflicts (keyed by sorted drone ID pair)
@@ -129,7 +399,7 @@ impl ConflictDetector {
     /// * `lookahead_seconds` - How far ahead to predict (default 20s)
     /// * `separation_horizontal_m` - Minimum horizontal separation
     /// * `separation_vertical_m` - Minimum vertical separation
-    /// * `warning_multiplier` - Multiplier for warning threshold
+    /// * `warning_multiplier` - Multiplier for warning threshold on both axes
     pub fn new(
         lookahead_seconds: f64,
         separation_horizontal_m: f64,
@@ -140,12 +410,83 @@ impl ConflictDetector {
             lookahead_seconds,
             separation_horizontal_m,
             separation_vertical_m,
-            warning_multiplier,
+            warning_horizontal_multiplier: warning_multiplier,
+            warning_vertical_multiplier: warning_multiplier,
+            info_multipliers: None,
+            volumes: Vec::new(),
+            crewed_protection: None,
+            mode: DetectionMode::default(),
             drones: HashMap::new(),
             active_conflicts: HashMap::new(),
         }
     }
 
+    /// Thresholds used outside every configured volume.
+    pub fn default_thresholds(&self) -> SeparationThresholds {
+        SeparationThresholds {
+            lookahead_seconds: self.lookahead_seconds,
+            horizontal_m: self.separation_horizontal_m,
+            vertical_m: self.separation_vertical_m,
+            warning_horizontal_multiplier: self.warning_horizontal_multiplier,
+            warning_vertical_multiplier: self.warning_vertical_multiplier,
+            info_multipliers: self.info_multipliers,
+        }
+    }
+
+    /// Set the warning band per axis and the optional info band beyond it, as multiples of
+    /// the separation minima. Volumes set afterwards inherit them.
+    pub fn set_alert_bands(
+        &mut self,
+        warning_horizontal_multiplier: f64,
+        warning_vertical_multiplier: f64,
+        info_multipliers: Option<(f64, f64)>,
+    ) {
+        self.warning_horizontal_multiplier = warning_horizontal_multiplier;
+        self.warning_vertical_multiplier = warning_vertical_multiplier;
+        self.info_multipliers = info_multipliers;
+    }
+
+    /// Replace the per-volume threshold overrides.
+    pub fn set_volumes(&mut self, volumes: Vec<SeparationVolume>) {
+        self.volumes = volumes;
+    }
+
+    /// Protect crewed tracks with a larger volume, or treat them like drones with `None`.
+    pub fn set_crewed_protection(&mut self, protection: Option<CrewedProtection>) {
+        self.crewed_protection = protection;
+    }
+
+    /// Switch between separation minima and the well-clear model.
+    pub fn set_detection_mode(&mut self, mode: DetectionMode) {
+        self.mode = mode;
+    }
+
+    pub fn detection_mode(&self) -> DetectionMode {
+        self.mode
+    }
+
+    /// Thresholds for a drone at its current position (first containing volume wins), grown
+    /// to the crewed protection volume for crewed tracks and widened by the drone's position
+    /// uncertainty.
+    pub fn thresholds_at(&self, drone: &DronePosition) -> SeparationThresholds {
+        let thresholds = self
+            .volumes
+            .iter()
+            .find(|volume| {
+                volume.volume.active
+                    && volume
+                        .volume
+                        .contains_point(drone.lat, drone.lon, drone.altitude_m)
+            })
+            .map(|volume| volume.thresholds)
+            .unwrap_or_else(|| self.default_thresholds());
+        let thresholds = match self.crewed_protection {
+            Some(protection) if drone.category.is_crewed() => protection.thresholds(thresholds),
+            _ => thresholds,
+        };
+        thresholds.widened_by(drone.uncertainty_m)
+    }
+
     /// Update tracked position for a drone.
     pub fn update_position(&mut self, position: DronePosition) {
         self.drones.insert(position.drone_id.clone(), position);
@@ -200,36 +541,19 @@ impl ConflictDetector {
         (horizontal, vertical)
     }
 
-    /// Find time and distance of closest approach.
-    /// Returns (severity, time_to_closest_s, closest_distance_m, cpa_lat, cpa_lon, cpa_altitude_m).
+    /// Find the severity and closest approach within the violated separation window.
     fn predict_conflict(
-        &self,
         drone1: &DronePosition,
         drone2: &DronePosition,
-        warning_horizontal_m: f64,
-        warning_vertical_m: f64,
-    ) -> Option<(ConflictSeverity, f64, f64, f64, f64, f64)> {
-        let lookahead = self.lookahead_seconds.max(0.0);
+        thresholds: &SeparationThresholds,
+    ) -> Option<(ConflictSeverity, ClosestApproach)> {
+        let lookahead = thresholds.lookahead_seconds.max(0.0);
         if lookahead <= 0.0 {
             return None;
         }
 
-        let ref_lat = (drone1.lat + drone2.lat) / 2.0;
-        let ref_lon = (drone1.lon + drone2.lon) / 2.0;
-
-        let (d1_x, d1_y) = project_xy(drone1.lat, drone1.lon, ref_lat, ref_lon);
-        let (d2_x, d2_y) = project_xy(drone2.lat, drone2.lon, ref_lat, ref_lon);
-
-        let (v1_x, v1_y) = velocity_xy(drone1);
-        let (v2_x, v2_y) = velocity_xy(drone2);
-
-        let rel_pos_x = d2_x - d1_x;
-        let rel_pos_y = d2_y - d1_y;
-        let rel_pos_z = drone2.altitude_m - drone1.altitude_m;
-
-        let rel_vel_x = v2_x - v1_x;
-        let rel_vel_y = v2_y - v1_y;
-        let rel_vel_z = drone2.velocity_z - drone1.velocity_z;
+        let ((rel_pos_x, rel_pos_y, rel_pos_z), (rel_vel_x, rel_vel_y, rel_vel_z)) =
+            relative_motion(drone1, drone2);
 
         let best = if let Some(window) = conflict_time_window(
             rel_pos_x,
@@ -238,8 +562,8 @@ impl ConflictDetector {
             rel_vel_y,
             rel_pos_z,
             rel_vel_z,
-            self.separation_horizontal_m,
-            self.separation_vertical_m,
+            thresholds.horizontal_m,
+            thresholds.vertical_m,
             lookahead,
         ) {
             (
@@ -253,31 +577,54 @@ impl ConflictDetector {
             rel_vel_y,
             rel_pos_z,
             rel_vel_z,
-            warning_horizontal_m,
-            warning_vertical_m,
+            thresholds.warning_horizontal_m(),
+            thresholds.warning_vertical_m(),
             lookahead,
         ) {
             (
                 ConflictSeverity::Warning,
                 best_approach_in_window(drone1, drone2, window),
             )
+        } else if let Some(window) = thresholds.info_band_m().and_then(|(horizontal, vertical)| {
+            conflict_time_window(
+                rel_pos_x, rel_pos_y, rel_vel_x, rel_vel_y, rel_pos_z, rel_vel_z, horizontal,
+                vertical, lookahead,
+            )
+        }) {
+            (
+                ConflictSeverity::Info,
+                best_approach_in_window(drone1, drone2, window),
+            )
         } else {
             return None;
         };
 
-        let (severity, best) = best;
-
-        let cpa_lat = (best.pos1.0 + best.pos2.0) / 2.0;
-        let cpa_lon = (best.pos1.1 + best.pos2.1) / 2.0;
-        let cpa_altitude_m = (best.pos1.2 + best.pos2.2) / 2.0;
+        Some(best)
+    }
 
+    /// Find the severity and closest approach for a predicted loss of well clear.
+    fn predict_well_clear_loss(
+        drone1: &DronePosition,
+        drone2: &DronePosition,
+        params: &WellClearParams,
+        lookahead_s: f64,
+    ) -> Option<(ConflictSeverity, ClosestApproach)> {
+        let (rel_pos, rel_vel) = relative_motion(drone1, drone2);
+        let loss_s = well_clear::time_to_loss_of_well_clear(
+            rel_pos,
+            rel_vel,
+            params,
+            lookahead_s.max(0.0),
+            WELL_CLEAR_STEP_S,
+        )?;
+        let severity = if loss_s <= 0.0 {
+            ConflictSeverity::Critical
+        } else {
+            ConflictSeverity::Warning
+        };
         Some((
             severity,
-            best.time_s,
-            best.distance_m,
-            cpa_lat,
-            cpa_lon,
-            cpa_altitude_m,
+            best_approach_in_window(drone1, drone2, (loss_s, lookahead_s.max(loss_s))),
         ))
     }
 
@@ -294,10 +641,25 @@ impl ConflictDetector {
             .iter()
             .map(|drone| drone.speed_mps)
             .fold(0.0, f64::max);
-        let warning_h = self.separation_horizontal_m * self.warning_multiplier;
-        let warning_v = self.separation_vertical_m * self.warning_multiplier;
-        let max_threshold = self.separation_horizontal_m.max(warning_h);
-        let cell_size_m = (max_threshold + max_speed * self.lookahead_seconds).max(1.0);
+        let thresholds: Vec<SeparationThresholds> = drone_list
+            .iter()
+            .map(|drone| self.thresholds_at(drone))
+            .collect();
+        // Size the grid for the loosest thresholds in play so no candidate pair is skipped.
+        let mut max_threshold = thresholds
+            .iter()
+            .map(|t| t.alert_horizontal_m())
+            .fold(0.0, f64::max);
+        if let DetectionMode::WellClear(params) = self.mode {
+            // Modified tau can trip at any range the pair closes within tau*.
+            max_threshold =
+                max_threshold.max(params.horizontal_reach_m() + 2.0 * max_speed * params.tau_mod_s);
+        }
+        let max_lookahead = thresholds
+            .iter()
+            .map(|t| t.lookahead_seconds)
+            .fold(0.0, f64::max);
+        let cell_size_m = (max_threshold + max_speed * max_lookahead).max(1.0);
 
         let (ref_lat, ref_lon) = average_lat_lon(&drone_list);
         let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
@@ -319,8 +681,7 @@ impl ConflictDetector {
             let (x, y) = projected[i];
             let cell_x = (x / cell_size_m).floor() as i32;
             let cell_y = (y / cell_size_m).floor() as i32;
-            let search_radius_m =
-                max_threshold + (drone1.speed_mps + max_speed) * self.lookahead_seconds;
+            let search_radius_m = max_threshold + (drone1.speed_mps + max_speed) * max_lookahead;
             let search_cells = (search_radius_m / cell_size_m).ceil() as i32;
 
             for dx in -search_cells..=search_cells {
@@ -334,78 +695,99 @@ impl ConflictDetector {
                             continue;
                         }
                         let drone2 = &drone_list[j];
+                        let pair = thresholds[i].most_conservative(thresholds[j]);
+                        // Crewed protection is a fixed volume, even in well-clear mode.
+                        let protected = self.crewed_protection.is_some()
+                            && (drone1.category.is_crewed() || drone2.category.is_crewed());
+
+                        if let (DetectionMode::WellClear(params), false) = (self.mode, protected) {
+                            let h_dist = crate::spatial::haversine_distance(
+                                drone1.lat, drone1.lon, drone2.lat, drone2.lon,
+                            );
+                            let reach = params.horizontal_reach_m()
+                                + (drone1.speed_mps + drone2.speed_mps)
+                                    * (pair.lookahead_seconds + params.tau_mod_s);
+                            if h_dist > reach {
+                                continue;
+                            }
+                            let Some((severity, approach)) = Self::predict_well_clear_loss(
+                                drone1,
+                                drone2,
+                                &params,
+                                pair.lookahead_seconds,
+                            ) else {
+                                continue;
+                            };
+                            let v_dist = (drone1.altitude_m - drone2.altitude_m).abs();
+                            conflicts.push(build_conflict(
+                                drone1,
+                                drone2,
+                                severity,
+                                (h_dist.powi(2) + v_dist.powi(2)).sqrt(),
+                                approach,
+                            ));
+                            continue;
+                        }
 
                         // Check current separation
                         let (h_dist, v_dist) = Self::check_separation(
                             (drone1.lat, drone1.lon, drone1.altitude_m),
                             (drone2.lat, drone2.lon, drone2.altitude_m),
                         );
-                        let max_possible_distance = max_threshold
-                            + (drone1.speed_mps + drone2.speed_mps) * self.lookahead_seconds;
+                        let max_possible_distance = pair.alert_horizontal_m()
+                            + (drone1.speed_mps + drone2.speed_mps) * pair.lookahead_seconds;
                         if h_dist > max_possible_distance {
                             continue;
                         }
                         let current_distance = (h_dist.powi(2) + v_dist.powi(2)).sqrt();
 
                         // Check for current violation
-                        if h_dist < self.separation_horizontal_m
-                            && v_dist < self.separation_vertical_m
-                        {
+                        if h_dist < pair.horizontal_m && v_dist < pair.vertical_m {
                             // Current position is the CPA for immediate violations
-                            let cpa_lat = (drone1.lat + drone2.lat) / 2.0;
-                            let cpa_lon = (drone1.lon + drone2.lon) / 2.0;
-                            let cpa_altitude_m = (drone1.altitude_m + drone2.altitude_m) / 2.0;
-                            let (drone1_id, drone2_id) = if drone1.drone_id <= drone2.drone_id {
-                                (drone1.drone_id.clone(), drone2.drone_id.clone())
-                            } else {
-                                (drone2.drone_id.clone(), drone1.drone_id.clone())
-                            };
-
-                            conflicts.push(Conflict {
-                                drone1_id,
-                                drone2_id,
-                                severity: ConflictSeverity::Critical,
+                            let (rel_pos, rel_vel) = relative_motion(drone1, drone2);
+                            let window_s = conflict_time_window(
+                                rel_pos.0,
+                                rel_pos.1,
+                                rel_vel.0,
+                                rel_vel.1,
+                                rel_pos.2,
+                                rel_vel.2,
+                                pair.horizontal_m,
+                                pair.vertical_m,
+                                pair.lookahead_seconds.max(0.0),
+                            )
+                            .map(|(_, end)| end)
+                            .unwrap_or(0.0);
+                            let approach = ClosestApproach {
                                 distance_m: current_distance,
-                                time_to_closest: 0.0,
-                                closest_distance_m: current_distance,
-                                cpa_lat,
-                                cpa_lon,
-                                cpa_altitude_m,
-                                timestamp: current_timestamp(),
-                            });
+                                time_s: 0.0,
+                                pos1: (drone1.lat, drone1.lon, drone1.altitude_m),
+                                pos2: (drone2.lat, drone2.lon, drone2.altitude_m),
+                                window_s,
+                            };
+                            conflicts.push(build_conflict(
+                                drone1,
+                                drone2,
+                                ConflictSeverity::Critical,
+                                current_distance,
+                                approach,
+                            ));
                             continue;
                         }
 
-                        let Some((
-                            severity,
-                            time_to_closest,
-                            closest_distance,
-                            cpa_lat,
-                            cpa_lon,
-                            cpa_altitude_m,
-                        )) = self.predict_conflict(drone1, drone2, warning_h, warning_v)
+                        let Some((severity, approach)) =
+                            Self::predict_conflict(drone1, drone2, &pair)
                         else {
                             continue;
                         };
 
-                        let (drone1_id, drone2_id) = if drone1.drone_id <= drone2.drone_id {
-                            (drone1.drone_id.clone(), drone2.drone_id.clone())
-                        } else {
-                            (drone2.drone_id.clone(), drone1.drone_id.clone())
-                        };
-
-                        conflicts.push(Conflict {
-                            drone1_id,
-                            drone2_id,
+                        conflicts.push(build_conflict(
+                            drone1,
+                            drone2,
                             severity,
-                            distance_m: current_distance,
-                            time_to_closest,
-                            closest_distance_m: closest_distance,
-                            cpa_lat,
-                            cpa_lon,
-                            cpa_altitude_m,
-                            timestamp: current_timestamp(),
-                        });
+                            current_distance,
+                            approach,
+                        ));
                     }
                 }
             }
@@ -424,6 +806,206 @@ impl ConflictDetector {
 
         conflicts
     }
+
+    /// Check all tracked drones against active restricted geofences; advisory geofences are
+    /// skipped. Drones are projected over the conflict lookahead.
+    pub fn detect_geofence_breaches(&self, geofences: &[Geofence]) -> Vec<GeofenceBreach> {
+        let restricted: Vec<&Geofence> = geofences
+            .iter()
+            .filter(|geofence| geofence.active && geofence.geofence_type != GeofenceType::Advisory)
+            .collect();
+        let mut breaches: Vec<GeofenceBreach> = self
+            .drones
+            .values()
+            .flat_map(|drone| {
+                restricted.iter().filter_map(move |geofence| {
+                    predict_geofence_breach(drone, geofence, self.lookahead_seconds)
+                })
+            })
+            .collect();
+        breaches.sort_by(|a, b| {
+            a.time_to_breach_s
+                .total_cmp(&b.time_to_breach_s)
+                .then_with(|| a.drone_id.cmp(&b.drone_id))
+                .then_with(|| a.geofence_id.cmp(&b.geofence_id))
+        });
+        breaches
+    }
+}
+
+/// Conflicts that share drones, grouped so they can be resolved together.
+///
+/// When three or more drones converge, resolving each pairwise conflict independently can
+/// send drones into each other; a cluster carries every participant so one coordinated
+/// resolution can be issued instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictCluster {
+    /// Stable ID derived from the sorted participant IDs.
+    pub cluster_id: String,
+    /// Participating drone IDs, sorted.
+    pub drone_ids: Vec<String>,
+    /// Highest severity among the member conflicts.
+    pub severity: ConflictSeverity,
+    /// Earliest closest approach among the member conflicts (seconds).
+    pub time_to_closest: f64,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl ConflictCluster {
+    /// True when more than two drones are involved.
+    pub fn is_multi_aircraft(&self) -> bool {
+        self.drone_ids.len() > 2
+    }
+}
+
+/// Group pairwise conflicts into clusters of drones connected by at least one conflict.
+///
+/// Clusters are ordered by severity (highest first), then by time to closest approach.
+pub fn cluster_conflicts(conflicts: &[Conflict]) -> Vec<ConflictCluster> {
+    fn find(parent: &mut HashMap<String, String>, id: &str) -> String {
+        let mut root = id.to_string();
+        while let Some(next) = parent.get(&root).filter(|next| **next != root) {
+            root = next.clone();
+        }
+        // Path compression keeps later lookups flat.
+        let mut current = id.to_string();
+        while current != root {
+            let next = parent
+                .insert(current, root.clone())
+                .unwrap_or_else(|| root.clone());
+            current = next;
+        }
+        root
+    }
+
+    let mut parent: HashMap<String, String> = HashMap::new();
+    for conflict in conflicts {
+        for id in [&conflict.drone1_id, &conflict.drone2_id] {
+            parent.entry(id.clone()).or_insert_with(|| id.clone());
+        }
+        let root1 = find(&mut parent, &conflict.drone1_id);
+        let root2 = find(&mut parent, &conflict.drone2_id);
+        if root1 != root2 {
+            // Keep the lexicographically smaller root so results are deterministic.
+            let (keep, merge) = if root1 < root2 {
+                (root1, root2)
+            } else {
+                (root2, root1)
+            };
+            parent.insert(merge, keep);
+        }
+    }
+
+    let mut grouped: HashMap<String, Vec<Conflict>> = HashMap::new();
+    for conflict in conflicts {
+        let root = find(&mut parent, &conflict.drone1_id);
+        grouped.entry(root).or_default().push(conflict.clone());
+    }
+
+    let mut clusters: Vec<ConflictCluster> = grouped
+        .into_values()
+        .map(|members| {
+            let mut drone_ids: Vec<String> = members
+                .iter()
+                .flat_map(|c| [c.drone1_id.clone(), c.drone2_id.clone()])
+                .collect();
+            drone_ids.sort();
+            drone_ids.dedup();
+            let severity = members
+                .iter()
+                .map(|c| c.severity)
+                .max_by_key(|severity| severity.rank())
+                .unwrap_or(ConflictSeverity::Info);
+            let time_to_closest = members
+                .iter()
+                .map(|c| c.time_to_closest)
+                .fold(f64::INFINITY, f64::min);
+            ConflictCluster {
+                cluster_id: format!("cluster:{}", drone_ids.join("+")),
+                drone_ids,
+                severity,
+                time_to_closest,
+                conflicts: members,
+            }
+        })
+        .collect();
+
+    clusters.sort_by(|a, b| {
+        b.severity
+            .rank()
+            .cmp(&a.severity.rank())
+            .then(a.time_to_closest.total_cmp(&b.time_to_closest))
+            .then(a.cluster_id.cmp(&b.cluster_id))
+    });
+    clusters
+}
+
+/// Build a conflict record with IDs in sorted order and CPA details filled in.
+fn build_conflict(
+    drone1: &DronePosition,
+    drone2: &DronePosition,
+    severity: ConflictSeverity,
+    current_distance_m: f64,
+    approach: ClosestApproach,
+) -> Conflict {
+    let (drone1_id, drone2_id) = if drone1.drone_id <= drone2.drone_id {
+        (drone1.drone_id.clone(), drone2.drone_id.clone())
+    } else {
+        (drone2.drone_id.clone(), drone1.drone_id.clone())
+    };
+    // The reported CPA is the midpoint between the two drones at closest approach.
+    let (pos1, pos2) = (approach.pos1, approach.pos2);
+    let (cpa_horizontal_m, cpa_vertical_m) = ConflictDetector::check_separation(pos1, pos2);
+    let timestamp = current_timestamp();
+
+    Conflict {
+        drone1_id,
+        drone2_id,
+        severity,
+        distance_m: current_distance_m,
+        time_to_closest: approach.time_s,
+        closest_distance_m: approach.distance_m,
+        cpa_lat: (pos1.0 + pos2.0) / 2.0,
+        cpa_lon: (pos1.1 + pos2.1) / 2.0,
+        cpa_altitude_m: (pos1.2 + pos2.2) / 2.0,
+        cpa_horizontal_m,
+        cpa_vertical_m,
+        cpa_time: timestamp + approach.time_s,
+        predicted_duration_s: approach.window_s,
+        timestamp,
+        sector_id: None,
+        intent_downgraded: false,
+        low_quality_track: false,
+        crewed_traffic: drone1.category.is_crewed() || drone2.category.is_crewed(),
+    }
+}
+
+/// Position and velocity of `drone2` relative to `drone1` in local east/north/up meters.
+fn relative_motion(
+    drone1: &DronePosition,
+    drone2: &DronePosition,
+) -> ((f64, f64, f64), (f64, f64, f64)) {
+    let ref_lat = (drone1.lat + drone2.lat) / 2.0;
+    let ref_lon = (drone1.lon + drone2.lon) / 2.0;
+
+    let (d1_x, d1_y) = project_xy(drone1.lat, drone1.lon, ref_lat, ref_lon);
+    let (d2_x, d2_y) = project_xy(drone2.lat, drone2.lon, ref_lat, ref_lon);
+
+    let (v1_x, v1_y) = velocity_xy(drone1);
+    let (v2_x, v2_y) = velocity_xy(drone2);
+
+    (
+        (
+            d2_x - d1_x,
+            d2_y - d1_y,
+            drone2.altitude_m - drone1.altitude_m,
+        ),
+        (
+            v2_x - v1_x,
+            v2_y - v1_y,
+            drone2.velocity_z - drone1.velocity_z,
+        ),
+    )
 }
 
 fn velocity_xy(drone: &DronePosition) -> (f64, f64) {
@@ -438,6 +1020,7 @@ fn velocity_xy(drone: &DronePosition) -> (f64, f64) {
     (vel_x, vel_y)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn conflict_time_window(
     rel_pos_x: f64,
     rel_pos_y: f64,
@@ -594,6 +1177,7 @@ fn best_approach_in_window(
                 time_s: t,
                 pos1,
                 pos2,
+                window_s: end_s - start_s,
             });
         }
     }
@@ -652,6 +1236,71 @@ mod tests {
         assert_eq!(conflicts[0].severity, ConflictSeverity::Critical);
     }
 
+    #[test]
+    fn converging_drones_form_one_cluster() {
+        let mut detector = ConflictDetector::default();
+        // A, B and C converge on the same point; D is alone far away; E and F are a pair.
+        detector.update_position(DronePosition::new("A", 33.6846, -117.8265, 50.0));
+        detector.update_position(DronePosition::new("B", 33.6847, -117.8265, 50.0));
+        detector.update_position(DronePosition::new("C", 33.6846, -117.8264, 50.0));
+        detector.update_position(DronePosition::new("D", 34.0, -118.0, 50.0));
+        detector.update_position(DronePosition::new("E", 33.5, -117.5, 50.0));
+        detector.update_position(DronePosition::new("F", 33.5, -117.5, 50.0));
+
+        let conflicts = detector.detect_conflicts();
+        assert_eq!(conflicts.len(), 4);
+
+        let clusters = cluster_conflicts(&conflicts);
+        assert_eq!(clusters.len(), 2);
+        let multi: Vec<&ConflictCluster> =
+            clusters.iter().filter(|c| c.is_multi_aircraft()).collect();
+        assert_eq!(multi.len(), 1);
+        assert_eq!(multi[0].drone_ids, vec!["A", "B", "C"]);
+        assert_eq!(multi[0].conflicts.len(), 3);
+        assert_eq!(multi[0].cluster_id, "cluster:A+B+C");
+        assert_eq!(multi[0].severity, ConflictSeverity::Critical);
+    }
+
+    #[test]
+    fn well_clear_mode_flags_fast_closure_earlier() {
+        let params = WellClearParams {
+            dmod_m: 100.0,
+            tau_mod_s: 20.0,
+            hmd_m: 100.0,
+            zthr_m: 40.0,
+        };
+        let d_lat = crate::spatial::meters_to_lat(400.0, 0.0);
+        let place = |detector: &mut ConflictDetector, speed: f64| {
+            detector.update_position(
+                DronePosition::new("A", 0.0, 0.0, 50.0).with_velocity(0.0, speed, 0.0),
+            );
+            detector.update_position(
+                DronePosition::new("B", d_lat, 0.0, 50.0).with_velocity(180.0, speed, 0.0),
+            );
+        };
+
+        // 400m apart closing at 30 m/s: well clear is already lost (tau ~ 12.5s).
+        let mut detector = ConflictDetector::new(5.0, 50.0, 30.0, 2.0);
+        detector.set_detection_mode(DetectionMode::WellClear(params));
+        place(&mut detector, 15.0);
+        let conflicts = detector.detect_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].severity, ConflictSeverity::Critical);
+
+        // The separation model with the same lookahead sees nothing yet.
+        let mut detector = ConflictDetector::new(5.0, 50.0, 30.0, 2.0);
+        place(&mut detector, 15.0);
+        assert!(detector.detect_conflicts().is_empty());
+
+        // Slow closure: loss of well clear is only predicted, so it is a warning.
+        let mut detector = ConflictDetector::new(20.0, 50.0, 30.0, 2.0);
+        detector.set_detection_mode(DetectionMode::WellClear(params));
+        place(&mut detector, 5.0);
+        let conflicts = detector.detect_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].severity, ConflictSeverity::Warning);
+    }
+
     #[test]
     fn test_vertical_conflict_detection() {
         let mut detector = ConflictDetector::default();
@@ -704,4 +1353,161 @@ mod tests {
             conflicts[0].closest_distance_m
         );
     }
+
+    #[test]
+    fn reports_cpa_location_and_separation_components() {
+        let mut detector = ConflictDetector::default();
+
+        // Head-on along the equator, 20m apart vertically, closing at 20 m/s from 200m.
+        let d_lon = crate::spatial::meters_to_lon(100.0, 0.0);
+        detector.update_position(
+            DronePosition::new("A", 0.0, -d_lon, 50.0).with_velocity(90.0, 10.0, 0.0),
+        );
+        detector.update_position(
+            DronePosition::new("B", 0.0, d_lon, 70.0).with_velocity(270.0, 10.0, 0.0),
+        );
+
+        let conflicts = detector.detect_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert!(conflict.cpa_horizontal_m < 50.0);
+        assert!((conflict.cpa_vertical_m - 20.0).abs() < 0.01);
+        assert!(conflict.cpa_lat.abs() < 1e-6);
+        assert!((conflict.cpa_altitude_m - 60.0).abs() < 0.01);
+        assert!((conflict.cpa_time - conflict.timestamp - conflict.time_to_closest).abs() < 1e-6);
+        assert!(conflict.time_to_closest > 0.0);
+        // Inside 50m horizontally from 150m to 250m of closing at 20 m/s.
+        assert!((conflict.predicted_duration_s - 5.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn per_axis_warning_and_info_bands() {
+        let mut rules = crate::rules::SafetyRules {
+            warning_vertical_multiplier: Some(1.2),
+            ..Default::default()
+        };
+        let d_lon = |meters: f64| crate::spatial::meters_to_lon(meters, 0.0);
+
+        // 80 m apart: inside the 100 m horizontal warning band; 40 m vertical clears the
+        // 36 m vertical warning band.
+        let mut detector = rules.conflict_detector();
+        detector.update_position(DronePosition::new("A", 0.0, 0.0, 50.0));
+        detector.update_position(DronePosition::new("B", 0.0, d_lon(80.0), 90.0));
+        assert!(detector.detect_conflicts().is_empty());
+        detector.update_position(DronePosition::new("B", 0.0, d_lon(80.0), 80.0));
+        let conflicts = detector.detect_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].severity, ConflictSeverity::Warning);
+
+        // An info band out to 4x horizontally; vertically it falls back to the warning band.
+        rules.info_horizontal_multiplier = Some(4.0);
+        assert_eq!(rules.info_multipliers(), Some((4.0, 1.2)));
+        let mut detector = rules.conflict_detector();
+        detector.update_position(DronePosition::new("A", 0.0, 0.0, 50.0));
+        detector.update_position(DronePosition::new("B", 0.0, d_lon(150.0), 60.0));
+        let conflicts = detector.detect_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].severity, ConflictSeverity::Info);
+        detector.update_position(DronePosition::new("B", 0.0, d_lon(150.0), 90.0));
+        assert!(detector.detect_conflicts().is_empty());
+    }
+
+    #[test]
+    fn volume_thresholds_apply_inside_corridor() {
+        let corridor = Geofence {
+            id: "corridor".to_string(),
+            name: "Corridor".to_string(),
+            geofence_type: crate::models::GeofenceType::Advisory,
+            polygon: vec![
+                [-0.01, -0.01],
+                [-0.01, 0.01],
+                [0.01, 0.01],
+                [0.01, -0.01],
+                [-0.01, -0.01],
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 120.0,
+            active: true,
+            created_at: chrono::Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
+        };
+        let mut detector = ConflictDetector::default();
+        let rule = VolumeSeparationRule {
+            geofence_id: corridor.id.clone(),
+            min_horizontal_separation_m: Some(10.0),
+            min_vertical_separation_m: None,
+            lookahead_seconds: None,
+            warning_multiplier: None,
+            warning_horizontal_multiplier: None,
+            warning_vertical_multiplier: None,
+        };
+        detector.set_volumes(vec![SeparationVolume {
+            volume: corridor,
+            thresholds: detector.default_thresholds().with_rule(&rule),
+        }]);
+
+        // 30m apart: a violation of the 50m global minimum, but clear of the corridor's 10m.
+        let d_lon = crate::spatial::meters_to_lon(30.0, 0.0);
+        detector.update_position(DronePosition::new("A", 0.0, 0.0, 50.0));
+        detector.update_position(DronePosition::new("B", 0.0, d_lon, 50.0));
+        assert!(detector.detect_conflicts().is_empty());
+
+        // A pair straddling the corridor boundary keeps the global minima.
+        let edge = 0.01 - crate::spatial::meters_to_lon(10.0, 0.0);
+        detector.update_position(DronePosition::new("A", 0.0, edge, 50.0));
+        detector.update_position(DronePosition::new("B", 0.0, edge + d_lon, 50.0));
+        let conflicts = detector.detect_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].severity, ConflictSeverity::Critical);
+    }
+
+    #[test]
+    fn predicts_entry_into_restricted_geofences() {
+        // 100 m wide box starting 100 m east of the origin.
+        let fence = |id: &str, geofence_type| Geofence {
+            id: id.to_string(),
+            name: id.to_string(),
+            geofence_type,
+            polygon: vec![
+                [-0.001, 0.0009],
+                [-0.001, 0.0018],
+                [0.001, 0.0018],
+                [0.001, 0.0009],
+                [-0.001, 0.0009],
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 120.0,
+            active: true,
+            created_at: chrono::Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
+        };
+        let no_fly = fence("nfz", GeofenceType::NoFlyZone);
+        let eastbound = DronePosition::new("A", 0.0, 0.0, 50.0).with_velocity(90.0, 10.0, 0.0);
+
+        // ~100 m out at 10 m/s: about ten seconds to the boundary.
+        let breach = predict_geofence_breach(&eastbound, &no_fly, 20.0).unwrap();
+        assert_eq!(breach.severity, ConflictSeverity::Warning);
+        assert!((breach.time_to_breach_s - 10.0).abs() < 0.2, "{:?}", breach);
+        assert!((breach.entry_lon - 0.0009).abs() < 0.00002);
+        assert!(predict_geofence_breach(&eastbound, &no_fly, 5.0).is_none());
+
+        let inside = DronePosition::new("B", 0.0, 0.0012, 50.0);
+        let breach = predict_geofence_breach(&inside, &no_fly, 20.0).unwrap();
+        assert_eq!(breach.severity, ConflictSeverity::Critical);
+        assert_eq!(breach.time_to_breach_s, 0.0);
+
+        // Advisory fences are not incursions; the detector uses its own lookahead (20 s).
+        let mut detector = ConflictDetector::default();
+        detector.update_position(eastbound);
+        detector.update_position(inside);
+        detector.update_position(DronePosition::new("C", 0.0, -0.01, 50.0));
+        let breaches =
+            detector.detect_geofence_breaches(&[no_fly, fence("adv", GeofenceType::Advisory)]);
+        let ids: Vec<&str> = breaches.iter().map(|b| b.drone_id.as_str()).collect();
+        assert_eq!(ids, ["B", "A"]);
+    }
 }
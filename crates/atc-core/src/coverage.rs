@@ -0,0 +1,216 @@
+//! C2 link coverage.
+//!
+//! BVLOS operations depend on the command-and-control link, so operators upload the polygons
+//! where their C2 radio or LTE service is available. Routes can be planned to stay inside that
+//! coverage, or to keep the stretches outside it short, and flight plans are checked for gaps.
+
+use serde::{Deserialize, Serialize};
+
+use crate::route_engine::RouteGrid;
+use crate::spatial::{haversine_distance, point_in_polygon};
+
+/// Routes are checked for coverage at this spacing.
+const GAP_SAMPLE_SPACING_M: f64 = 25.0;
+
+/// An area with C2 link coverage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageArea {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Link providing the coverage, e.g. `lte` or `900mhz`.
+    #[serde(default)]
+    pub link_type: Option<String>,
+    /// `[lat, lon]` vertices.
+    pub polygon: Vec<[f64; 2]>,
+}
+
+impl CoverageArea {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.id.trim().is_empty() {
+            errors.push("id must not be empty".to_string());
+        }
+        if self.polygon.len() < 3 {
+            errors.push("polygon must have at least 3 vertices".to_string());
+        }
+        if self.polygon.iter().any(|[lat, lon]| {
+            !lat.is_finite()
+                || !lon.is_finite()
+                || !(-90.0..=90.0).contains(lat)
+                || !(-180.0..=180.0).contains(lon)
+        }) {
+            errors.push("polygon vertices must be valid [lat, lon] pairs".to_string());
+        }
+        errors
+    }
+
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        point_in_polygon(lat, lon, &self.polygon)
+    }
+}
+
+/// How the route planner treats C2 coverage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageMode {
+    /// Ignore coverage.
+    #[default]
+    Off,
+    /// Penalize flying outside coverage and cap the longest gap.
+    Limit,
+    /// Stay inside coverage.
+    Require,
+}
+
+pub fn is_covered(areas: &[CoverageArea], lat: f64, lon: f64) -> bool {
+    areas.iter().any(|area| area.contains(lat, lon))
+}
+
+/// A continuous stretch of a route outside C2 coverage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageGap {
+    pub start_lat: f64,
+    pub start_lon: f64,
+    pub end_lat: f64,
+    pub end_lon: f64,
+    /// Distance along the route to the start of the gap.
+    pub start_distance_m: f64,
+    pub length_m: f64,
+}
+
+/// Stretches of a `(lat, lon)` route outside every coverage area, in route order.
+pub fn coverage_gaps(route: &[(f64, f64)], areas: &[CoverageArea]) -> Vec<CoverageGap> {
+    let mut gaps: Vec<CoverageGap> = Vec::new();
+    let mut open = false;
+    let mut visit = |lat: f64, lon: f64, distance_m: f64| {
+        if is_covered(areas, lat, lon) {
+            open = false;
+            return;
+        }
+        match gaps.last_mut() {
+            Some(gap) if open => {
+                gap.end_lat = lat;
+                gap.end_lon = lon;
+                gap.length_m = distance_m - gap.start_distance_m;
+            }
+            _ => {
+                gaps.push(CoverageGap {
+                    start_lat: lat,
+                    start_lon: lon,
+                    end_lat: lat,
+                    end_lon: lon,
+                    start_distance_m: distance_m,
+                    length_m: 0.0,
+                });
+                open = true;
+            }
+        }
+    };
+
+    let Some(&(first_lat, first_lon)) = route.first() else {
+        return Vec::new();
+    };
+    visit(first_lat, first_lon, 0.0);
+    let mut travelled_m = 0.0;
+    for pair in route.windows(2) {
+        let ((lat1, lon1), (lat2, lon2)) = (pair[0], pair[1]);
+        let leg_m = haversine_distance(lat1, lon1, lat2, lon2);
+        let samples = (leg_m / GAP_SAMPLE_SPACING_M).ceil().max(1.0) as usize;
+        for sample in 1..=samples {
+            let t = sample as f64 / samples as f64;
+            visit(
+                lat1 + (lat2 - lat1) * t,
+                lon1 + (lon2 - lon1) * t,
+                travelled_m + leg_m * t,
+            );
+        }
+        travelled_m += leg_m;
+    }
+    gaps
+}
+
+/// Charge `cost` per second flown at grid points outside coverage; an infinite cost excludes
+/// them.
+pub fn apply_coverage(grid: &mut RouteGrid, areas: &[CoverageArea], cost: f64) {
+    for lane in &mut grid.lanes {
+        for point in lane.iter_mut() {
+            point.coverage_cost = if is_covered(areas, point.lat, point.lon) {
+                0.0
+            } else {
+                cost
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Waypoint;
+    use crate::route_engine::{
+        build_lane_offsets, generate_grid_samples, optimize_flight_path, RouteEngineConfig,
+    };
+    use crate::spatial::offset_position;
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    fn area(id: &str, corners: [(f64, f64); 2]) -> CoverageArea {
+        let [(south, west), (north, east)] = corners;
+        let corner = |north_m: f64, east_m: f64| {
+            let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m, east_m);
+            [lat, lon]
+        };
+        CoverageArea {
+            id: id.to_string(),
+            name: None,
+            link_type: Some("lte".to_string()),
+            polygon: vec![
+                corner(south, west),
+                corner(south, east),
+                corner(north, east),
+                corner(north, west),
+            ],
+        }
+    }
+
+    #[test]
+    fn finds_gaps_and_routes_through_coverage() {
+        // Two towers along a 2 km northbound route with a hole between 800 m and 1200 m,
+        // except for a corridor 120-280 m east.
+        let areas = vec![
+            area("south", [(-100.0, -300.0), (800.0, 300.0)]),
+            area("north", [(1_200.0, -300.0), (2_100.0, 300.0)]),
+            area("corridor", [(700.0, 120.0), (1_300.0, 280.0)]),
+        ];
+        let (end_lat, end_lon) = offset_position(ORIGIN.0, ORIGIN.1, 2_000.0, 0.0);
+
+        let gaps = coverage_gaps(&[ORIGIN, (end_lat, end_lon)], &areas);
+        assert_eq!(gaps.len(), 1, "{:?}", gaps);
+        assert!((gaps[0].start_distance_m - 800.0).abs() < 30.0);
+        assert!((gaps[0].length_m - 400.0).abs() < 60.0);
+        assert_eq!(coverage_gaps(&[ORIGIN, (end_lat, end_lon)], &[]).len(), 1);
+
+        let waypoints = vec![
+            Waypoint {
+                lat: ORIGIN.0,
+                lon: ORIGIN.1,
+                altitude_m: 60.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: end_lat,
+                lon: end_lon,
+                altitude_m: 60.0,
+                speed_mps: None,
+            },
+        ];
+        let mut grid =
+            generate_grid_samples(&waypoints, 25.0, &build_lane_offsets(300.0, 50.0), 0.0).unwrap();
+        apply_coverage(&mut grid, &areas, f64::INFINITY);
+        let result = optimize_flight_path(&waypoints, &grid, &[], &RouteEngineConfig::default());
+        assert!(result.success);
+        let route: Vec<(f64, f64)> = result.waypoints.iter().map(|wp| (wp.lat, wp.lon)).collect();
+        assert!(coverage_gaps(&route, &areas).is_empty(), "{:?}", route);
+    }
+}
@@ -0,0 +1,177 @@
+//! Protection volumes around crewed aircraft.
+//!
+//! Drone-vs-drone minima are tens of metres. A crewed aircraft seen on ADS-B or Remote ID
+//! closes far faster and cannot be commanded, so tracks identified as crewed carry a larger
+//! protection volume: a lateral radius of kilometres, an altitude band and a longer lookahead,
+//! so advisories for drones near them start well before the drone minima would.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conflict::SeparationThresholds;
+
+/// What kind of aircraft a track is, as far as its transponder or Remote ID says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AircraftCategory {
+    /// Nothing identifies the aircraft.
+    #[default]
+    Unknown,
+    /// Unmanned aircraft, including every Remote ID broadcaster.
+    Uncrewed,
+    /// Aeroplanes, rotorcraft, gliders, balloons, parachutists and other crewed traffic.
+    Crewed,
+}
+
+impl AircraftCategory {
+    /// Category from an ADS-B emitter category, either the DO-260B code (`"A3"`, `"B6"`) or
+    /// the numeric form some feeds use (OpenSky: 2-8 aircraft, 9-12 sport, 14 UAV).
+    pub fn from_adsb_emitter(value: &str) -> Self {
+        let value = value.trim().to_ascii_uppercase();
+        if let Ok(code) = value.parse::<u32>() {
+            return match code {
+                2..=12 => Self::Crewed,
+                14 => Self::Uncrewed,
+                _ => Self::Unknown,
+            };
+        }
+        match value.as_str() {
+            "A1" | "A2" | "A3" | "A4" | "A5" | "A6" | "A7" => Self::Crewed,
+            "B1" | "B2" | "B3" | "B4" => Self::Crewed,
+            "B6" => Self::Uncrewed,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Category from an ASTM F3411 Remote ID UA type, numeric (`2`) or named
+    /// (`"HelicopterOrMultirotor"`). Remote ID is broadcast by unmanned aircraft, so any
+    /// declared type is uncrewed; `0`/`None` declares nothing.
+    pub fn from_rid_ua_type(value: &str) -> Self {
+        let value = value.trim();
+        if value.is_empty() || value == "0" || value.eq_ignore_ascii_case("none") {
+            Self::Unknown
+        } else {
+            Self::Uncrewed
+        }
+    }
+
+    pub fn is_crewed(self) -> bool {
+        self == Self::Crewed
+    }
+}
+
+/// Protection volume around crewed traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CrewedProtection {
+    /// Lateral radius (m) a drone must keep from crewed traffic.
+    pub horizontal_m: f64,
+    /// Half-height (m) of the altitude band around crewed traffic.
+    pub vertical_m: f64,
+    /// How far ahead (s) to predict encounters with crewed traffic.
+    pub lookahead_seconds: f64,
+    /// Warning band, as a multiple of the protection volume.
+    pub warning_multiplier: f64,
+}
+
+impl Default for CrewedProtection {
+    fn default() -> Self {
+        Self {
+            horizontal_m: 2_000.0,
+            vertical_m: 150.0,
+            lookahead_seconds: 60.0,
+            warning_multiplier: 1.5,
+        }
+    }
+}
+
+impl CrewedProtection {
+    /// Thresholds for a crewed track: the protection volume, or `base` where it is larger.
+    pub fn thresholds(&self, base: SeparationThresholds) -> SeparationThresholds {
+        base.most_conservative(SeparationThresholds {
+            lookahead_seconds: self.lookahead_seconds,
+            horizontal_m: self.horizontal_m,
+            vertical_m: self.vertical_m,
+            warning_horizontal_multiplier: self.warning_multiplier,
+            warning_vertical_multiplier: self.warning_multiplier,
+            info_multipliers: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conflict::{ConflictDetector, ConflictSeverity, DronePosition};
+    use crate::spatial::offset_position;
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    #[test]
+    fn infers_category_from_emitter_and_ua_type() {
+        use AircraftCategory::*;
+        for (emitter, category) in [
+            ("a1", Crewed),
+            ("A7", Crewed),
+            ("B3", Crewed),
+            ("B6", Uncrewed),
+            ("A0", Unknown),
+            ("C1", Unknown),
+            ("8", Crewed),
+            ("14", Uncrewed),
+        ] {
+            assert_eq!(
+                AircraftCategory::from_adsb_emitter(emitter),
+                category,
+                "{emitter}"
+            );
+        }
+        assert_eq!(AircraftCategory::from_rid_ua_type("2"), Uncrewed);
+        assert_eq!(AircraftCategory::from_rid_ua_type("None"), Unknown);
+    }
+
+    #[test]
+    fn crewed_tracks_alert_at_their_protection_volume() {
+        // A drone hovering 1.5 km east of a target at the same altitude: far outside the
+        // drone minima, well inside a crewed protection volume.
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, 0.0, 1_500.0);
+        let detect = |category: AircraftCategory| {
+            let mut detector = ConflictDetector::default();
+            detector.set_crewed_protection(Some(CrewedProtection::default()));
+            detector.update_position(DronePosition::new("DRONE", ORIGIN.0, ORIGIN.1, 100.0));
+            detector.update_position(
+                DronePosition::new("RID-N123", lat, lon, 150.0).with_category(category),
+            );
+            detector.detect_conflicts()
+        };
+        assert!(detect(AircraftCategory::Uncrewed).is_empty());
+        let conflicts = detect(AircraftCategory::Crewed);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].severity, ConflictSeverity::Critical);
+        assert!(conflicts[0].crewed_traffic);
+
+        // Above the altitude band it is only a warning.
+        let mut detector = ConflictDetector::default();
+        detector.set_crewed_protection(Some(CrewedProtection::default()));
+        detector.update_position(DronePosition::new("DRONE", ORIGIN.0, ORIGIN.1, 100.0));
+        detector.update_position(
+            DronePosition::new("RID-N123", lat, lon, 300.0).with_category(AircraftCategory::Crewed),
+        );
+        let conflicts = detector.detect_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].severity, ConflictSeverity::Warning);
+
+        // Crewed traffic closing at 60 m/s from 5 km is flagged a minute out.
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, 5_000.0, 0.0);
+        let mut detector = ConflictDetector::default();
+        detector.set_crewed_protection(Some(CrewedProtection::default()));
+        detector.update_position(DronePosition::new("DRONE", ORIGIN.0, ORIGIN.1, 100.0));
+        detector.update_position(
+            DronePosition::new("RID-N123", lat, lon, 100.0)
+                .with_velocity(180.0, 60.0, 0.0)
+                .with_category(AircraftCategory::Crewed),
+        );
+        let conflicts = detector.detect_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].severity, ConflictSeverity::Critical);
+        assert!(conflicts[0].time_to_closest > 40.0);
+    }
+}
@@ -0,0 +1,209 @@
+//! Dead-reckoning of comms-lost drones.
+//!
+//! A drone that stops reporting keeps flying. Rather than dropping it from conflict detection,
+//! its position is projected along its last velocity, or along its active plan, for a limited
+//! horizon. The projection grows less certain the longer the drone is silent, so the separation
+//! minima around it are widened by an uncertainty radius that grows with time.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conflict::DronePosition;
+use crate::models::FlightPlan;
+use crate::spatial::{
+    bearing, build_timed_path, haversine_distance, interpolate_position, offset_by_bearing,
+};
+
+/// Plan positions are differenced over this interval to recover the planned velocity.
+const PLAN_VELOCITY_INTERVAL_S: f64 = 1.0;
+
+/// How a lost drone's position is projected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeadReckoningMode {
+    /// Lost drones leave conflict detection.
+    #[default]
+    Off,
+    /// Along the last reported velocity.
+    Velocity,
+    /// Along the active flight plan, or the last velocity without one.
+    Plan,
+}
+
+impl DeadReckoningMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "" => Some(Self::Off),
+            "velocity" => Some(Self::Velocity),
+            "plan" => Some(Self::Plan),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DeadReckoningConfig {
+    pub mode: DeadReckoningMode,
+    /// Seconds after the last report the drone is projected for; it is dropped after that.
+    pub horizon_s: f64,
+    /// Growth of the position uncertainty radius (meters per second since the last report).
+    pub uncertainty_growth_mps: f64,
+}
+
+impl Default for DeadReckoningConfig {
+    fn default() -> Self {
+        Self {
+            mode: DeadReckoningMode::Off,
+            horizon_s: 60.0,
+            uncertainty_growth_mps: 5.0,
+        }
+    }
+}
+
+/// Where a drone last reported at `last` should be at `now_s`, with its uncertainty radius;
+/// `None` when dead-reckoning is off or the drone has been silent past the horizon.
+pub fn dead_reckon(
+    last: &DronePosition,
+    plan: Option<&FlightPlan>,
+    now_s: f64,
+    config: &DeadReckoningConfig,
+) -> Option<DronePosition> {
+    let elapsed_s = (now_s - last.timestamp).max(0.0);
+    if config.mode == DeadReckoningMode::Off || elapsed_s > config.horizon_s {
+        return None;
+    }
+    let planned = match (config.mode, plan) {
+        (DeadReckoningMode::Plan, Some(plan)) => along_plan(last, plan, now_s),
+        _ => None,
+    };
+    let mut projected = planned.unwrap_or_else(|| along_velocity(last, elapsed_s));
+    projected.timestamp = now_s;
+    projected.uncertainty_m = last.uncertainty_m + config.uncertainty_growth_mps * elapsed_s;
+    Some(projected)
+}
+
+fn along_velocity(last: &DronePosition, elapsed_s: f64) -> DronePosition {
+    let mut projected = last.clone();
+    if last.speed_mps > 0.0 {
+        let (lat, lon) = offset_by_bearing(
+            last.lat,
+            last.lon,
+            last.speed_mps * elapsed_s,
+            last.heading_deg.to_radians(),
+        );
+        projected.lat = lat;
+        projected.lon = lon;
+    }
+    projected.altitude_m += last.velocity_z * elapsed_s;
+    projected
+}
+
+/// The plan's position at `now_s`, moving at the plan's velocity; `None` outside the plan.
+fn along_plan(last: &DronePosition, plan: &FlightPlan, now_s: f64) -> Option<DronePosition> {
+    let path = build_timed_path(plan)?;
+    let mut index = 0usize;
+    let here = interpolate_position(&path, now_s, &mut index)?;
+    let mut projected = DronePosition::new(&last.drone_id, here.lat, here.lon, here.altitude_m)
+        .with_category(last.category);
+    if let Some(next) = interpolate_position(&path, now_s + PLAN_VELOCITY_INTERVAL_S, &mut index) {
+        projected = projected.with_velocity(
+            bearing(here.lat, here.lon, next.lat, next.lon)
+                .to_degrees()
+                .rem_euclid(360.0),
+            haversine_distance(here.lat, here.lon, next.lat, next.lon) / PLAN_VELOCITY_INTERVAL_S,
+            (next.altitude_m - here.altitude_m) / PLAN_VELOCITY_INTERVAL_S,
+        );
+    }
+    Some(projected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FlightStatus, TrajectoryPoint};
+    use crate::spatial::offset_position;
+    use chrono::{TimeZone, Utc};
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    fn config(mode: DeadReckoningMode) -> DeadReckoningConfig {
+        DeadReckoningConfig {
+            mode,
+            ..DeadReckoningConfig::default()
+        }
+    }
+
+    #[test]
+    fn projects_lost_drones_along_their_velocity_with_growing_uncertainty() {
+        let mut last =
+            DronePosition::new("LOST", ORIGIN.0, ORIGIN.1, 80.0).with_velocity(90.0, 10.0, -1.0);
+        last.timestamp = 1_000.0;
+
+        let projected = dead_reckon(&last, None, 1_020.0, &config(DeadReckoningMode::Velocity))
+            .expect("within horizon");
+        let travelled = haversine_distance(ORIGIN.0, ORIGIN.1, projected.lat, projected.lon);
+        assert!((travelled - 200.0).abs() < 1.0, "{travelled}");
+        assert!(projected.lon > ORIGIN.1);
+        assert!((projected.altitude_m - 60.0).abs() < 1e-9);
+        assert_eq!(projected.uncertainty_m, 100.0);
+        assert_eq!(projected.timestamp, 1_020.0);
+
+        // Past the horizon, or with dead-reckoning off, the drone is dropped.
+        assert!(dead_reckon(&last, None, 1_061.0, &config(DeadReckoningMode::Velocity)).is_none());
+        assert!(dead_reckon(&last, None, 1_020.0, &config(DeadReckoningMode::Off)).is_none());
+        // Without a plan, plan mode falls back to the velocity.
+        let fallback = dead_reckon(&last, None, 1_020.0, &config(DeadReckoningMode::Plan));
+        assert_eq!(fallback.map(|p| p.lat), Some(projected.lat));
+    }
+
+    #[test]
+    fn follows_the_active_plan() {
+        // Departs at unix time 0 and flies 400 m north at 10 m/s, climbing from 50 m to 90 m.
+        let trajectory = [(0.0, 0.0, 50.0), (40.0, 400.0, 90.0)]
+            .iter()
+            .map(|(t, north_m, altitude_m)| {
+                let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, *north_m, 0.0);
+                TrajectoryPoint {
+                    lat,
+                    lon,
+                    altitude_m: *altitude_m,
+                    time_offset_s: Some(*t),
+                }
+            })
+            .collect();
+        let plan = FlightPlan {
+            flight_id: "PLAN-LOST".to_string(),
+            drone_id: "LOST".to_string(),
+            owner_id: None,
+            waypoints: Vec::new(),
+            trajectory_log: Some(trajectory),
+            metadata: None,
+            status: FlightStatus::Active,
+            departure_time: Utc.timestamp_opt(0, 0).unwrap(),
+            arrival_time: None,
+            created_at: Utc.timestamp_opt(0, 0).unwrap(),
+        };
+        // Last heard hovering 100 m up the route.
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, 100.0, 0.0);
+        let mut last = DronePosition::new("LOST", lat, lon, 60.0);
+        last.timestamp = 10.0;
+
+        let projected =
+            dead_reckon(&last, Some(&plan), 30.0, &config(DeadReckoningMode::Plan)).unwrap();
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, 300.0, 0.0);
+        assert!(haversine_distance(projected.lat, projected.lon, lat, lon) < 1.0);
+        assert!((projected.altitude_m - 80.0).abs() < 1e-6);
+        assert!((projected.speed_mps - 10.0).abs() < 0.1);
+        assert!((projected.velocity_z - 1.0).abs() < 1e-6);
+        assert_eq!(projected.uncertainty_m, 100.0);
+
+        // Velocity mode ignores the plan and leaves the hovering drone where it was.
+        let hovering = dead_reckon(
+            &last,
+            Some(&plan),
+            30.0,
+            &config(DeadReckoningMode::Velocity),
+        )
+        .unwrap();
+        assert_eq!((hovering.lat, hovering.lon), (last.lat, last.lon));
+    }
+}
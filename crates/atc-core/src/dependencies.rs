@@ -0,0 +1,133 @@
+//! Ordering constraints between flight plans.
+//!
+//! A plan may wait for another flight to land or for a geofence to be lifted before it
+//! departs, as the legs of a relay delivery do. The scheduler slots such a plan no earlier than
+//! the flights it waits for are expected to land, and the mission loop holds its activation
+//! until every dependency has actually cleared.
+
+use chrono::{DateTime, Utc};
+
+use crate::models::{FlightPlan, FlightStatus, PlanDependency};
+
+/// Where a plan's dependencies stand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyStatus {
+    /// Every dependency has cleared.
+    Met,
+    /// At least one dependency has yet to clear.
+    Waiting,
+    /// A flight the plan waits for will never land: it was rejected, cancelled or is unknown.
+    Broken { flight_id: String },
+}
+
+/// Check `dependencies` against the current flight statuses and geofences.
+pub fn dependency_status(
+    dependencies: &[PlanDependency],
+    flight_status: impl Fn(&str) -> Option<FlightStatus>,
+    geofence_active: impl Fn(&str) -> bool,
+) -> DependencyStatus {
+    let mut status = DependencyStatus::Met;
+    for dependency in dependencies {
+        match dependency {
+            PlanDependency::FlightLanded { flight_id } => match flight_status(flight_id) {
+                Some(FlightStatus::Completed) => {}
+                Some(FlightStatus::Rejected | FlightStatus::Cancelled) | None => {
+                    return DependencyStatus::Broken {
+                        flight_id: flight_id.clone(),
+                    };
+                }
+                Some(_) => status = DependencyStatus::Waiting,
+            },
+            PlanDependency::GeofenceLifted { geofence_id } => {
+                if geofence_active(geofence_id) {
+                    status = DependencyStatus::Waiting;
+                }
+            }
+        }
+    }
+    status
+}
+
+/// Latest time the flights in `dependencies` are expected to have landed, from their actual or
+/// estimated arrival (or departure, when no arrival is known). `None` when no flight is waited
+/// on; geofences carry no lift time, so only the mission loop can hold for them.
+pub fn expected_clear_time<'a>(
+    dependencies: &[PlanDependency],
+    find_flight: impl Fn(&str) -> Option<&'a FlightPlan>,
+) -> Option<DateTime<Utc>> {
+    dependencies
+        .iter()
+        .filter_map(|dependency| match dependency {
+            PlanDependency::FlightLanded { flight_id } => find_flight(flight_id),
+            PlanDependency::GeofenceLifted { .. } => None,
+        })
+        .map(|plan| plan.arrival_time.unwrap_or(plan.departure_time))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn plan(flight_id: &str, status: FlightStatus, arrival_s: Option<i64>) -> FlightPlan {
+        FlightPlan {
+            flight_id: flight_id.to_string(),
+            drone_id: format!("DRONE-{}", flight_id),
+            owner_id: None,
+            waypoints: Vec::new(),
+            trajectory_log: None,
+            metadata: None,
+            status,
+            departure_time: Utc.timestamp_opt(0, 0).unwrap(),
+            arrival_time: arrival_s.map(|s| Utc.timestamp_opt(s, 0).unwrap()),
+            created_at: Utc.timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn relay_leg_waits_for_the_previous_leg_and_the_geofence() {
+        let plans: HashMap<String, FlightPlan> = [
+            plan("LEG-1", FlightStatus::Active, Some(600)),
+            plan("LEG-2", FlightStatus::Approved, Some(1_500)),
+            plan("SCRUBBED", FlightStatus::Cancelled, None),
+        ]
+        .into_iter()
+        .map(|plan| (plan.flight_id.clone(), plan))
+        .collect();
+        let flight = |id: &str| PlanDependency::FlightLanded {
+            flight_id: id.to_string(),
+        };
+        let tfr = PlanDependency::GeofenceLifted {
+            geofence_id: "TFR".to_string(),
+        };
+        let status = |deps: &[PlanDependency], plans: &HashMap<String, FlightPlan>, tfr| {
+            dependency_status(deps, |id| plans.get(id).map(|plan| plan.status), |_| tfr)
+        };
+
+        let deps = [flight("LEG-1"), flight("LEG-2"), tfr];
+        assert_eq!(
+            expected_clear_time(&deps, |id| plans.get(id)),
+            Some(Utc.timestamp_opt(1_500, 0).unwrap())
+        );
+        assert_eq!(expected_clear_time(&deps[2..], |id| plans.get(id)), None);
+
+        assert_eq!(status(&deps, &plans, false), DependencyStatus::Waiting);
+        let mut landed = plans.clone();
+        for id in ["LEG-1", "LEG-2"] {
+            landed.get_mut(id).unwrap().status = FlightStatus::Completed;
+        }
+        assert_eq!(status(&deps, &landed, true), DependencyStatus::Waiting);
+        assert_eq!(status(&deps, &landed, false), DependencyStatus::Met);
+
+        for broken in ["SCRUBBED", "MISSING"] {
+            assert_eq!(
+                status(&[flight("LEG-1"), flight(broken)], &plans, false),
+                DependencyStatus::Broken {
+                    flight_id: broken.to_string()
+                }
+            );
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! GeoJSON import and export of geofences.
+//!
+//! Each geofence is a `Feature` with a `Polygon` geometry (`[lon, lat]` positions, outer ring
+//! only) and its altitude band, type and time window as properties, so airspace maintained in
+//! GIS tools can be loaded and edited without going through the JSON API one fence at a time.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::models::{
+    BreachResponse, CreateGeofenceRequest, Geofence, GeofenceSchedule, GeofenceType,
+};
+
+/// A geofence read from a GeoJSON feature.
+#[derive(Debug, Clone)]
+pub struct GeofenceFeature {
+    /// Feature ID, used to update an existing geofence.
+    pub id: Option<String>,
+    /// Reference of the altitude properties (`amsl`, `wgs84`); the server's when unset.
+    pub altitude_reference: Option<String>,
+    pub active: bool,
+    pub request: CreateGeofenceRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeatureProperties {
+    id: Option<String>,
+    name: Option<String>,
+    geofence_type: Option<GeofenceType>,
+    #[serde(alias = "lower_limit")]
+    lower_altitude_m: Option<f64>,
+    #[serde(alias = "upper_limit")]
+    upper_altitude_m: Option<f64>,
+    altitude_reference: Option<String>,
+    active: Option<bool>,
+    breach_response: Option<BreachResponse>,
+    schedule: Option<GeofenceSchedule>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+}
+
+/// The features of a `FeatureCollection`, or a single `Feature`.
+pub fn features(document: &Value) -> Result<Vec<&Value>, String> {
+    match document.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => document
+            .get("features")
+            .and_then(Value::as_array)
+            .map(|features| features.iter().collect())
+            .ok_or_else(|| "FeatureCollection has no features array".to_string()),
+        Some("Feature") => Ok(vec![document]),
+        Some(other) => Err(format!(
+            "Expected a FeatureCollection or Feature, got {other}"
+        )),
+        None => Err("Missing GeoJSON type".to_string()),
+    }
+}
+
+/// Read a geofence from a `Feature`.
+///
+/// Geofences without a `geofence_type` property are no-fly zones. The time window comes from
+/// a `schedule` object or from `start_time`/`end_time` properties.
+pub fn geofence_from_feature(feature: &Value) -> Result<GeofenceFeature, String> {
+    if feature.get("type").and_then(Value::as_str) != Some("Feature") {
+        return Err("Not a GeoJSON Feature".to_string());
+    }
+    let properties = match feature.get("properties") {
+        None | Some(Value::Null) => Value::Object(Map::new()),
+        Some(properties) => properties.clone(),
+    };
+    let properties: FeatureProperties =
+        serde_json::from_value(properties).map_err(|err| format!("Invalid properties: {err}"))?;
+    let polygon = polygon_from_geometry(feature.get("geometry").unwrap_or(&Value::Null))?;
+
+    let id = match feature.get("id") {
+        Some(Value::String(id)) => Some(id.clone()),
+        Some(Value::Number(id)) => Some(id.to_string()),
+        _ => properties.id,
+    };
+    let name = properties
+        .name
+        .filter(|name| !name.trim().is_empty())
+        .ok_or_else(|| "Missing name property".to_string())?;
+    let schedule = match (
+        properties.schedule,
+        properties.start_time,
+        properties.end_time,
+    ) {
+        (Some(schedule), _, _) => Some(schedule),
+        (None, Some(start), Some(end)) => Some(GeofenceSchedule {
+            start,
+            end,
+            recurrence: None,
+            until: None,
+        }),
+        (None, None, None) => None,
+        (None, _, _) => {
+            return Err("start_time and end_time must be given together".to_string());
+        }
+    };
+
+    Ok(GeofenceFeature {
+        id,
+        altitude_reference: properties.altitude_reference,
+        active: properties.active.unwrap_or(true),
+        request: CreateGeofenceRequest {
+            name,
+            geofence_type: properties.geofence_type.unwrap_or(GeofenceType::NoFlyZone),
+            polygon,
+            lower_altitude_m: properties.lower_altitude_m,
+            upper_altitude_m: properties.upper_altitude_m,
+            breach_response: properties.breach_response,
+            schedule,
+        },
+    })
+}
+
+/// Closed `[lat, lon]` ring of a `Polygon`, or of a `MultiPolygon` with a single part.
+pub fn polygon_from_geometry(geometry: &Value) -> Result<Vec<[f64; 2]>, String> {
+    let coordinates = geometry.get("coordinates");
+    let rings = match geometry.get("type").and_then(Value::as_str) {
+        Some("Polygon") => coordinates.and_then(Value::as_array),
+        Some("MultiPolygon") => match coordinates.and_then(Value::as_array) {
+            Some(parts) if parts.len() == 1 => parts[0].as_array(),
+            Some(_) => return Err("MultiPolygon must have exactly one polygon".to_string()),
+            None => None,
+        },
+        Some(other) => return Err(format!("Unsupported geometry type {other}")),
+        None => return Err("Missing Polygon geometry".to_string()),
+    }
+    .ok_or_else(|| "Invalid polygon coordinates".to_string())?;
+    match rings.len() {
+        0 => return Err("Polygon has no rings".to_string()),
+        1 => {}
+        _ => return Err("Polygon holes are not supported".to_string()),
+    }
+
+    let positions = rings[0]
+        .as_array()
+        .ok_or_else(|| "Invalid polygon coordinates".to_string())?;
+    let mut polygon = Vec::with_capacity(positions.len() + 1);
+    for (index, position) in positions.iter().enumerate() {
+        let (lon, lat) = match position.as_array().map(Vec::as_slice) {
+            Some([lon, lat, ..]) => (lon.as_f64(), lat.as_f64()),
+            _ => (None, None),
+        };
+        match (lon, lat) {
+            (Some(lon), Some(lat))
+                if (-180.0..=180.0).contains(&lon) && (-90.0..=90.0).contains(&lat) =>
+            {
+                polygon.push([lat, lon]);
+            }
+            _ => return Err(format!("Position {index} is not a valid [lon, lat]")),
+        }
+    }
+    if let (Some(first), Some(last)) = (polygon.first().copied(), polygon.last().copied()) {
+        if first != last {
+            polygon.push(first);
+        }
+    }
+    Ok(polygon)
+}
+
+/// A geofence as a GeoJSON `Feature`; altitudes are AMSL.
+pub fn geofence_to_feature(geofence: &Geofence) -> Value {
+    let ring: Vec<[f64; 2]> = geofence
+        .polygon
+        .iter()
+        .map(|&[lat, lon]| [lon, lat])
+        .collect();
+    let mut properties = json!({
+        "name": geofence.name,
+        "geofence_type": geofence.geofence_type,
+        "lower_altitude_m": geofence.lower_altitude_m,
+        "upper_altitude_m": geofence.upper_altitude_m,
+        "altitude_reference": "amsl",
+        "active": geofence.active,
+        "created_at": geofence.created_at,
+    });
+    if let Some(breach_response) = geofence.breach_response {
+        properties["breach_response"] = json!(breach_response);
+    }
+    if let Some(schedule) = &geofence.schedule {
+        properties["schedule"] = json!(schedule);
+    }
+    if let Some(org_id) = &geofence.org_id {
+        properties["org_id"] = json!(org_id);
+    }
+    json!({
+        "type": "Feature",
+        "id": geofence.id,
+        "geometry": { "type": "Polygon", "coordinates": [ring] },
+        "properties": properties,
+    })
+}
+
+pub fn geofences_to_feature_collection(geofences: &[Geofence]) -> Value {
+    json!({
+        "type": "FeatureCollection",
+        "features": geofences.iter().map(geofence_to_feature).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn reads_gis_features_and_closes_the_ring() {
+        let collection = json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "id": 7,
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [[[-117.83, 33.68], [-117.82, 33.68], [-117.82, 33.69]]]
+                },
+                "properties": {
+                    "name": "Stadium",
+                    "geofence_type": "temporary_restriction",
+                    "lower_limit": 0,
+                    "upper_limit": 150,
+                    "start_time": "2026-10-17T18:00:00Z",
+                    "end_time": "2026-10-17T22:00:00Z",
+                    "fill": "#ff0000"
+                }
+            }, {
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [-117.83, 33.68] },
+                "properties": { "name": "Pin" }
+            }]
+        });
+        let features = features(&collection).unwrap();
+        assert_eq!(features.len(), 2);
+
+        let stadium = geofence_from_feature(features[0]).unwrap();
+        assert_eq!(stadium.id.as_deref(), Some("7"));
+        assert!(stadium.active);
+        assert_eq!(
+            stadium.request.geofence_type,
+            GeofenceType::TemporaryRestriction
+        );
+        assert_eq!(stadium.request.upper_altitude_m, Some(150.0));
+        assert_eq!(stadium.request.polygon.len(), 4);
+        assert_eq!(stadium.request.polygon[0], [33.68, -117.83]);
+        assert_eq!(stadium.request.polygon[3], [33.68, -117.83]);
+        let schedule = stadium.request.schedule.unwrap();
+        assert_eq!(
+            schedule.start,
+            Utc.with_ymd_and_hms(2026, 10, 17, 18, 0, 0).unwrap()
+        );
+
+        assert_eq!(
+            geofence_from_feature(features[1]).unwrap_err(),
+            "Unsupported geometry type Point"
+        );
+    }
+
+    #[test]
+    fn exported_features_read_back_unchanged() {
+        let geofence = Geofence {
+            id: "GF-1".to_string(),
+            name: "Depot".to_string(),
+            geofence_type: GeofenceType::RestrictedArea,
+            polygon: vec![
+                [33.68, -117.83],
+                [33.68, -117.82],
+                [33.69, -117.82],
+                [33.68, -117.83],
+            ],
+            lower_altitude_m: 10.0,
+            upper_altitude_m: 90.0,
+            active: false,
+            created_at: Utc::now(),
+            breach_response: Some(BreachResponse::Hold),
+            schedule: None,
+            org_id: None,
+        };
+        let collection = geofences_to_feature_collection(std::slice::from_ref(&geofence));
+        let features = features(&collection).unwrap();
+        let read = geofence_from_feature(features[0]).unwrap();
+        assert_eq!(read.id.as_deref(), Some("GF-1"));
+        assert_eq!(read.altitude_reference.as_deref(), Some("amsl"));
+        assert!(!read.active);
+        assert_eq!(read.request.name, geofence.name);
+        assert_eq!(read.request.geofence_type, geofence.geofence_type);
+        assert_eq!(read.request.polygon, geofence.polygon);
+        assert_eq!(read.request.lower_altitude_m, Some(10.0));
+        assert_eq!(read.request.upper_altitude_m, Some(90.0));
+        assert_eq!(read.request.breach_response, Some(BreachResponse::Hold));
+    }
+
+    #[test]
+    fn rejects_holes_and_missing_names() {
+        let square = json!([
+            [-117.83, 33.68],
+            [-117.82, 33.68],
+            [-117.82, 33.69],
+            [-117.83, 33.68]
+        ]);
+        let with_hole = json!({
+            "type": "Feature",
+            "geometry": { "type": "Polygon", "coordinates": [square, square] },
+            "properties": { "name": "Donut" }
+        });
+        assert_eq!(
+            geofence_from_feature(&with_hole).unwrap_err(),
+            "Polygon holes are not supported"
+        );
+        let unnamed = json!({
+            "type": "Feature",
+            "geometry": { "type": "Polygon", "coordinates": [square] },
+            "properties": null
+        });
+        assert_eq!(
+            geofence_from_feature(&unnamed).unwrap_err(),
+            "Missing name property"
+        );
+        assert!(features(&json!({ "type": "Polygon" })).is_err());
+    }
+}
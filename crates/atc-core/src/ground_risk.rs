@@ -0,0 +1,329 @@
+//! Ground risk from population density.
+//!
+//! SORA ground risk scales with the people under the flight path, so the planner charges for
+//! time spent over populated cells and reports how much population a route overflies. Density
+//! comes from an ESRI ASCII population raster, or is estimated from building footprints.
+
+use serde::{Deserialize, Serialize};
+
+use crate::route_engine::RouteGrid;
+use crate::spatial::{haversine_distance, meters_to_lat, meters_to_lon};
+
+/// Routes are sampled for exposure at this spacing.
+const EXPOSURE_SAMPLE_SPACING_M: f64 = 25.0;
+/// Largest raster accepted, in cells.
+const MAX_RASTER_CELLS: usize = 25_000_000;
+
+/// Population density (people/km²) on a regular lat/lon grid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PopulationRaster {
+    /// South-west corner of the raster.
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub cell_lat_deg: f64,
+    pub cell_lon_deg: f64,
+    pub rows: usize,
+    pub cols: usize,
+    /// Row-major densities with row 0 along the southern edge.
+    pub density_per_km2: Vec<f64>,
+}
+
+impl PopulationRaster {
+    /// Parse an ESRI ASCII grid (`.asc`) of people/km² in WGS84 degrees; NODATA cells read as
+    /// unpopulated.
+    pub fn from_esri_ascii(text: &str) -> Result<Self, String> {
+        let mut ncols = None;
+        let mut nrows = None;
+        let mut xll = None;
+        let mut yll = None;
+        let mut centered = false;
+        let mut cellsize = None;
+        let mut nodata = None;
+        let mut lines = text.lines().peekable();
+        while let Some(line) = lines.peek() {
+            let mut parts = line.split_whitespace();
+            let Some(key) = parts.next() else {
+                lines.next();
+                continue;
+            };
+            if !key.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                break;
+            }
+            let value = parts
+                .next()
+                .ok_or_else(|| format!("missing value for header {key}"))?;
+            let number = value
+                .parse::<f64>()
+                .map_err(|_| format!("invalid value for header {key}: {value}"))?;
+            match key.to_ascii_lowercase().as_str() {
+                "ncols" => ncols = Some(number as usize),
+                "nrows" => nrows = Some(number as usize),
+                "xllcorner" => xll = Some(number),
+                "yllcorner" => yll = Some(number),
+                "xllcenter" => {
+                    xll = Some(number);
+                    centered = true;
+                }
+                "yllcenter" => {
+                    yll = Some(number);
+                    centered = true;
+                }
+                "cellsize" => cellsize = Some(number),
+                "nodata_value" => nodata = Some(number),
+                _ => return Err(format!("unknown header {key}")),
+            }
+            lines.next();
+        }
+
+        let cols = ncols.filter(|n| *n > 0).ok_or("ncols must be positive")?;
+        let rows = nrows.filter(|n| *n > 0).ok_or("nrows must be positive")?;
+        let cellsize = cellsize
+            .filter(|size| size.is_finite() && *size > 0.0)
+            .ok_or("cellsize must be positive")?;
+        let (mut min_lon, mut min_lat) = (
+            xll.ok_or("missing xllcorner")?,
+            yll.ok_or("missing yllcorner")?,
+        );
+        if centered {
+            min_lon -= cellsize / 2.0;
+            min_lat -= cellsize / 2.0;
+        }
+        if rows.saturating_mul(cols) > MAX_RASTER_CELLS {
+            return Err(format!("raster too large ({rows}x{cols} cells)"));
+        }
+
+        let mut values = Vec::with_capacity(rows * cols);
+        for token in lines.flat_map(str::split_whitespace) {
+            let value = token
+                .parse::<f64>()
+                .map_err(|_| format!("invalid cell value {token}"))?;
+            let unpopulated = !value.is_finite() || value < 0.0 || Some(value) == nodata;
+            values.push(if unpopulated { 0.0 } else { value });
+        }
+        if values.len() != rows * cols {
+            return Err(format!(
+                "expected {} cell values, found {}",
+                rows * cols,
+                values.len()
+            ));
+        }
+
+        // The file lists rows north to south.
+        let density_per_km2 = values.chunks(cols).rev().flatten().copied().collect();
+        let raster = Self {
+            min_lat,
+            min_lon,
+            cell_lat_deg: cellsize,
+            cell_lon_deg: cellsize,
+            rows,
+            cols,
+            density_per_km2,
+        };
+        let errors = raster.validate();
+        if !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+        Ok(raster)
+    }
+
+    /// Estimate density over `[min_lat, min_lon, max_lat, max_lon]` by counting
+    /// `people_per_point` at each `(lat, lon)` point (e.g. building centers) into
+    /// `cell_m`-square cells.
+    pub fn from_points(
+        bounds: [f64; 4],
+        cell_m: f64,
+        points: &[(f64, f64)],
+        people_per_point: f64,
+    ) -> Option<Self> {
+        let [min_lat, min_lon, max_lat, max_lon] = bounds;
+        if !(cell_m.is_finite() && cell_m > 0.0) || max_lat <= min_lat || max_lon <= min_lon {
+            return None;
+        }
+        let mid_lat = (min_lat + max_lat) / 2.0;
+        let cell_lat_deg = meters_to_lat(cell_m, mid_lat);
+        let cell_lon_deg = meters_to_lon(cell_m, mid_lat);
+        let rows = ((max_lat - min_lat) / cell_lat_deg).ceil().max(1.0) as usize;
+        let cols = ((max_lon - min_lon) / cell_lon_deg).ceil().max(1.0) as usize;
+        if rows.saturating_mul(cols) > MAX_RASTER_CELLS {
+            return None;
+        }
+        let mut raster = Self {
+            min_lat,
+            min_lon,
+            cell_lat_deg,
+            cell_lon_deg,
+            rows,
+            cols,
+            density_per_km2: vec![0.0; rows * cols],
+        };
+        let per_point = people_per_point.max(0.0) / (cell_m * cell_m / 1_000_000.0);
+        for &(lat, lon) in points {
+            if let Some(idx) = raster.cell_index(lat, lon) {
+                raster.density_per_km2[idx] += per_point;
+            }
+        }
+        Some(raster)
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !(-90.0..=90.0).contains(&self.min_lat) || !(-180.0..=180.0).contains(&self.min_lon) {
+            errors.push("raster origin must be a valid lat/lon".to_string());
+        }
+        let positive = |size: f64| size.is_finite() && size > 0.0;
+        if !positive(self.cell_lat_deg) || !positive(self.cell_lon_deg) {
+            errors.push("raster cell size must be positive".to_string());
+        }
+        if self.density_per_km2.len() != self.rows * self.cols {
+            errors.push("raster cell count does not match rows x cols".to_string());
+        }
+        errors
+    }
+
+    fn cell_index(&self, lat: f64, lon: f64) -> Option<usize> {
+        let row = ((lat - self.min_lat) / self.cell_lat_deg).floor();
+        let col = ((lon - self.min_lon) / self.cell_lon_deg).floor();
+        if !(row >= 0.0 && col >= 0.0) {
+            return None;
+        }
+        let (row, col) = (row as usize, col as usize);
+        (row < self.rows && col < self.cols).then_some(row * self.cols + col)
+    }
+
+    /// People/km² at a point; `None` outside the raster.
+    pub fn density_at(&self, lat: f64, lon: f64) -> Option<f64> {
+        self.cell_index(lat, lon)
+            .map(|idx| self.density_per_km2[idx])
+    }
+}
+
+/// Population a route overflies, for SORA ground risk documentation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GroundRiskExposure {
+    pub max_density_per_km2: f64,
+    /// Density averaged over distance flown.
+    pub mean_density_per_km2: f64,
+    /// Density integrated along the route: people within each kilometre of ground-risk
+    /// buffer width.
+    pub exposure_people_per_km: f64,
+    /// Distance flown over cells with any population.
+    pub populated_distance_m: f64,
+}
+
+/// Charge `cost` per second flown for every 1000 people/km² under each grid point.
+pub fn apply_ground_risk(grid: &mut RouteGrid, raster: &PopulationRaster, cost: f64) {
+    for lane in &mut grid.lanes {
+        for point in lane.iter_mut() {
+            let density = raster.density_at(point.lat, point.lon).unwrap_or(0.0);
+            point.ground_risk_cost = cost * density / 1000.0;
+        }
+    }
+}
+
+/// Population overflown along a `(lat, lon)` route.
+pub fn ground_risk_exposure(route: &[(f64, f64)], raster: &PopulationRaster) -> GroundRiskExposure {
+    let mut exposure = GroundRiskExposure::default();
+    let mut distance_m = 0.0;
+    for pair in route.windows(2) {
+        let ((lat1, lon1), (lat2, lon2)) = (pair[0], pair[1]);
+        let leg_m = haversine_distance(lat1, lon1, lat2, lon2);
+        let samples = (leg_m / EXPOSURE_SAMPLE_SPACING_M).ceil().max(1.0) as usize;
+        let step_m = leg_m / samples as f64;
+        for sample in 0..samples {
+            // Midpoint of each step.
+            let t = (sample as f64 + 0.5) / samples as f64;
+            let density = raster
+                .density_at(lat1 + (lat2 - lat1) * t, lon1 + (lon2 - lon1) * t)
+                .unwrap_or(0.0);
+            exposure.max_density_per_km2 = exposure.max_density_per_km2.max(density);
+            exposure.exposure_people_per_km += density * step_m / 1000.0;
+            if density > 0.0 {
+                exposure.populated_distance_m += step_m;
+            }
+        }
+        distance_m += leg_m;
+    }
+    if distance_m > 0.0 {
+        exposure.mean_density_per_km2 = exposure.exposure_people_per_km * 1000.0 / distance_m;
+    }
+    exposure
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Waypoint;
+    use crate::route_engine::{
+        build_lane_offsets, generate_grid_samples, optimize_flight_path, RouteEngineConfig,
+    };
+    use crate::spatial::offset_position;
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    #[test]
+    fn parses_esri_ascii_rows_north_to_south() {
+        let raster = PopulationRaster::from_esri_ascii(
+            "ncols 2\nnrows 2\nxllcorner -118.0\nyllcorner 33.0\ncellsize 0.5\n\
+             NODATA_value -9999\n100 -9999\n3000 5\n",
+        )
+        .unwrap();
+        assert_eq!(raster.density_at(33.75, -117.25), Some(0.0));
+        assert_eq!(raster.density_at(33.75, -117.9), Some(100.0));
+        assert_eq!(raster.density_at(33.2, -117.9), Some(3000.0));
+        assert_eq!(raster.density_at(32.9, -117.9), None);
+        assert!(PopulationRaster::from_esri_ascii("ncols 2\nnrows 2\ncellsize 1\n1 2 3").is_err());
+    }
+
+    #[test]
+    fn routes_around_dense_cells_and_reports_exposure() {
+        // A town 300-700 m along a 1 km northbound route, reaching 150 m either side.
+        let mut buildings = Vec::new();
+        for north_m in (300..=700).step_by(20) {
+            for east_m in (-150..=150).step_by(20) {
+                buildings.push(offset_position(
+                    ORIGIN.0,
+                    ORIGIN.1,
+                    north_m as f64,
+                    east_m as f64,
+                ));
+            }
+        }
+        let (south, west) = offset_position(ORIGIN.0, ORIGIN.1, -500.0, -500.0);
+        let (north, east) = offset_position(ORIGIN.0, ORIGIN.1, 1_500.0, 500.0);
+        let raster =
+            PopulationRaster::from_points([south, west, north, east], 50.0, &buildings, 3.0)
+                .unwrap();
+
+        let (end_lat, end_lon) = offset_position(ORIGIN.0, ORIGIN.1, 1_000.0, 0.0);
+        let direct = ground_risk_exposure(&[ORIGIN, (end_lat, end_lon)], &raster);
+        assert!(direct.max_density_per_km2 > 1_000.0, "{:?}", direct);
+        assert!(
+            (direct.populated_distance_m - 420.0).abs() < 60.0,
+            "{:?}",
+            direct
+        );
+
+        let waypoints: Vec<Waypoint> = [ORIGIN, (end_lat, end_lon)]
+            .iter()
+            .map(|&(lat, lon)| Waypoint {
+                lat,
+                lon,
+                altitude_m: 60.0,
+                speed_mps: None,
+            })
+            .collect();
+        let mut grid =
+            generate_grid_samples(&waypoints, 25.0, &build_lane_offsets(400.0, 50.0), 0.0).unwrap();
+        apply_ground_risk(&mut grid, &raster, 5.0);
+        let result = optimize_flight_path(&waypoints, &grid, &[], &RouteEngineConfig::default());
+        assert!(result.success);
+        let route: Vec<(f64, f64)> = result.waypoints.iter().map(|wp| (wp.lat, wp.lon)).collect();
+        let planned = ground_risk_exposure(&route, &raster);
+        assert!(
+            planned.exposure_people_per_km < direct.exposure_people_per_km / 2.0,
+            "{:?} vs {:?}",
+            planned,
+            direct
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Per-drone home points and tethers.
+//!
+//! A home is where a drone launches from and returns to. An optional tether limits how far the
+//! drone may fly from it; drones found beyond the tether are told to return home when
+//! `auto_rth` is set.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Waypoint;
+use crate::spatial::haversine_distance;
+use crate::takeoff_landing::{find_vertiport, Vertiport};
+
+/// Registered home location of a drone.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DroneHome {
+    pub lat: f64,
+    pub lon: f64,
+    /// Landing altitude at the home point.
+    pub altitude_m: f64,
+    /// Maximum horizontal distance from home; `None` leaves the drone untethered.
+    #[serde(default)]
+    pub tether_radius_m: Option<f64>,
+    /// Return home automatically when the tether is exceeded.
+    #[serde(default)]
+    pub auto_rth: bool,
+}
+
+impl DroneHome {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !self.lat.is_finite() || !(-90.0..=90.0).contains(&self.lat) {
+            errors.push("lat must be between -90 and 90".to_string());
+        }
+        if !self.lon.is_finite() || !(-180.0..=180.0).contains(&self.lon) {
+            errors.push("lon must be between -180 and 180".to_string());
+        }
+        if !self.altitude_m.is_finite() {
+            errors.push("altitude_m must be a finite number".to_string());
+        }
+        if let Some(radius) = self.tether_radius_m {
+            if !radius.is_finite() || radius <= 0.0 {
+                errors.push("tether_radius_m must be a positive number".to_string());
+            }
+        }
+        if self.auto_rth && self.tether_radius_m.is_none() {
+            errors.push("auto_rth requires tether_radius_m".to_string());
+        }
+        errors
+    }
+
+    /// Horizontal distance from home.
+    pub fn distance_m(&self, lat: f64, lon: f64) -> f64 {
+        haversine_distance(self.lat, self.lon, lat, lon)
+    }
+
+    /// How far beyond the tether a position is; `None` when within it or untethered.
+    pub fn tether_excess_m(&self, lat: f64, lon: f64) -> Option<f64> {
+        let radius = self.tether_radius_m?;
+        let excess = self.distance_m(lat, lon) - radius;
+        (excess > 0.0).then_some(excess)
+    }
+
+    /// Return-to-home route from a position: back above home at the current altitude (never
+    /// below the home altitude), then down to it.
+    pub fn return_waypoints(&self, altitude_m: f64) -> Vec<Waypoint> {
+        let cruise_m = altitude_m.max(self.altitude_m);
+        vec![
+            Waypoint {
+                lat: self.lat,
+                lon: self.lon,
+                altitude_m: cruise_m,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: self.lat,
+                lon: self.lon,
+                altitude_m: self.altitude_m,
+                speed_mps: None,
+            },
+        ]
+    }
+}
+
+/// Whether a route may end at `(lat, lon)`: within `max_distance_m` of the drone's home or
+/// inside a vertiport.
+pub fn is_approved_landing_point(
+    home: Option<&DroneHome>,
+    vertiports: &[Vertiport],
+    lat: f64,
+    lon: f64,
+    max_distance_m: f64,
+) -> bool {
+    home.is_some_and(|home| home.distance_m(lat, lon) <= max_distance_m)
+        || find_vertiport(vertiports, lat, lon).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::offset_position;
+
+    #[test]
+    fn tether_and_landing_points() {
+        let home = DroneHome {
+            lat: 37.0,
+            lon: -122.0,
+            altitude_m: 10.0,
+            tether_radius_m: Some(500.0),
+            auto_rth: true,
+        };
+        assert!(home.validate().is_empty());
+
+        let (near_lat, near_lon) = offset_position(37.0, -122.0, 400.0, 0.0);
+        let (far_lat, far_lon) = offset_position(37.0, -122.0, 0.0, 650.0);
+        assert!(home.tether_excess_m(near_lat, near_lon).is_none());
+        let excess = home.tether_excess_m(far_lat, far_lon).unwrap();
+        assert!((excess - 150.0).abs() < 1.0, "{}", excess);
+
+        let route = home.return_waypoints(60.0);
+        assert_eq!(route.len(), 2);
+        assert_eq!(route[0].altitude_m, 60.0);
+        assert_eq!(route[1].altitude_m, 10.0);
+        assert_eq!(home.return_waypoints(2.0)[0].altitude_m, 10.0);
+
+        assert!(is_approved_landing_point(
+            Some(&home),
+            &[],
+            home.lat,
+            home.lon,
+            50.0
+        ));
+        assert!(!is_approved_landing_point(
+            Some(&home),
+            &[],
+            near_lat,
+            near_lon,
+            50.0
+        ));
+        let pad = Vertiport {
+            id: "pad".to_string(),
+            name: None,
+            lat: near_lat,
+            lon: near_lon,
+            radius_m: 30.0,
+            profile: Default::default(),
+        };
+        assert!(is_approved_landing_point(
+            Some(&home),
+            &[pad],
+            near_lat,
+            near_lon,
+            50.0
+        ));
+
+        let untethered = DroneHome {
+            tether_radius_m: None,
+            ..home
+        };
+        assert_eq!(untethered.validate().len(), 1);
+        assert!(untethered.tether_excess_m(far_lat, far_lon).is_none());
+    }
+}
@@ -0,0 +1,201 @@
+//! Intent-aware conflict filtering.
+//!
+//! The detector extrapolates each drone's current velocity, so two drones flying deconflicted
+//! plans that are about to diverge are still flagged. When both drones are following their
+//! active plans, the plans' own trajectories over the lookahead show whether the conflict is
+//! already resolved.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conflict::{Conflict, ConflictSeverity, DronePosition, SeparationThresholds};
+use crate::models::FlightPlan;
+use crate::spatial::{build_timed_path, haversine_distance, interpolate_position, TimedPoint};
+
+/// Planned trajectories are compared at this interval.
+const SAMPLE_INTERVAL_S: f64 = 1.0;
+
+/// What to do with a conflict the drones' plans already resolve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IntentFilterMode {
+    /// Report conflicts from the projections alone.
+    #[default]
+    Off,
+    /// Keep the conflict at `Info` severity so it is shown but not acted on.
+    Downgrade,
+    /// Drop the conflict.
+    Suppress,
+}
+
+/// A drone's live position together with its active plan.
+#[derive(Debug, Clone, Copy)]
+pub struct PlannedDrone<'a> {
+    pub position: &'a DronePosition,
+    pub plan: &'a FlightPlan,
+}
+
+/// Whether both drones are on their plans and the plans stay separated for the lookahead.
+///
+/// A drone is on its plan when its last reported position is within `conformance_m`
+/// horizontally and vertically of where the plan puts it at that time. Plans without timing,
+/// or that end before the lookahead does, cannot vouch for the encounter.
+pub fn plans_resolve_conflict(
+    drone1: PlannedDrone<'_>,
+    drone2: PlannedDrone<'_>,
+    now_s: f64,
+    thresholds: &SeparationThresholds,
+    conformance_m: f64,
+) -> bool {
+    let (Some(path1), Some(path2)) = (build_timed_path(drone1.plan), build_timed_path(drone2.plan))
+    else {
+        return false;
+    };
+    if !on_plan(drone1.position, &path1, conformance_m)
+        || !on_plan(drone2.position, &path2, conformance_m)
+    {
+        return false;
+    }
+
+    let steps = (thresholds.lookahead_seconds.max(0.0) / SAMPLE_INTERVAL_S).ceil() as u32;
+    let (mut idx1, mut idx2) = (0usize, 0usize);
+    (0..=steps).all(|step| {
+        let t = now_s + (step as f64 * SAMPLE_INTERVAL_S).min(thresholds.lookahead_seconds);
+        let (Some(p1), Some(p2)) = (
+            interpolate_position(&path1, t, &mut idx1),
+            interpolate_position(&path2, t, &mut idx2),
+        ) else {
+            return false;
+        };
+        haversine_distance(p1.lat, p1.lon, p2.lat, p2.lon) >= thresholds.horizontal_m
+            || (p1.altitude_m - p2.altitude_m).abs() >= thresholds.vertical_m
+    })
+}
+
+fn on_plan(position: &DronePosition, path: &[TimedPoint], conformance_m: f64) -> bool {
+    let mut idx = 0usize;
+    interpolate_position(path, position.timestamp, &mut idx).is_some_and(|planned| {
+        haversine_distance(position.lat, position.lon, planned.lat, planned.lon) <= conformance_m
+            && (position.altitude_m - planned.altitude_m).abs() <= conformance_m
+    })
+}
+
+/// Apply `mode` to a conflict the plans resolve; `None` when it is suppressed.
+pub fn apply_intent_filter(mut conflict: Conflict, mode: IntentFilterMode) -> Option<Conflict> {
+    match mode {
+        IntentFilterMode::Off => Some(conflict),
+        IntentFilterMode::Suppress => None,
+        IntentFilterMode::Downgrade => {
+            conflict.severity = ConflictSeverity::Info;
+            conflict.intent_downgraded = true;
+            Some(conflict)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FlightStatus, TrajectoryPoint};
+    use crate::spatial::offset_position;
+    use chrono::{TimeZone, Utc};
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    /// Plan departing at unix time 0 through `(t, north_m, east_m)` points at 50 m.
+    fn plan(drone_id: &str, points: &[(f64, f64, f64)]) -> FlightPlan {
+        let trajectory = points
+            .iter()
+            .map(|(t, north_m, east_m)| {
+                let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, *north_m, *east_m);
+                TrajectoryPoint {
+                    lat,
+                    lon,
+                    altitude_m: 50.0,
+                    time_offset_s: Some(*t),
+                }
+            })
+            .collect();
+        FlightPlan {
+            flight_id: format!("PLAN-{}", drone_id),
+            drone_id: drone_id.to_string(),
+            owner_id: None,
+            waypoints: Vec::new(),
+            trajectory_log: Some(trajectory),
+            metadata: None,
+            status: FlightStatus::Active,
+            departure_time: Utc.timestamp_opt(0, 0).unwrap(),
+            arrival_time: None,
+            created_at: Utc.timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    fn position(drone_id: &str, north_m: f64, east_m: f64, timestamp: f64) -> DronePosition {
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m, east_m);
+        let mut position = DronePosition::new(drone_id, lat, lon, 50.0);
+        position.timestamp = timestamp;
+        position
+    }
+
+    #[test]
+    fn plans_vouch_only_for_conforming_separated_drones() {
+        let thresholds = SeparationThresholds {
+            lookahead_seconds: 20.0,
+            horizontal_m: 50.0,
+            vertical_m: 30.0,
+            warning_horizontal_multiplier: 2.0,
+            warning_vertical_multiplier: 2.0,
+            info_multipliers: None,
+        };
+        // A flies north; B closes from the east, then turns north 120 m off A's track.
+        let plan_a = plan("A", &[(0.0, 0.0, 0.0), (40.0, 400.0, 0.0)]);
+        let plan_b = plan(
+            "B",
+            &[
+                (0.0, 150.0, 150.0),
+                (3.0, 150.0, 120.0),
+                (43.0, 550.0, 120.0),
+            ],
+        );
+        let a = position("A", 20.0, 0.0, 2.0);
+        let b = position("B", 150.0, 130.0, 2.0);
+        let drone = |position, plan| PlannedDrone { position, plan };
+        assert!(plans_resolve_conflict(
+            drone(&a, &plan_a),
+            drone(&b, &plan_b),
+            2.0,
+            &thresholds,
+            25.0
+        ));
+
+        // Off its plan, B's intent no longer counts.
+        let b_off = position("B", 100.0, 130.0, 2.0);
+        assert!(!plans_resolve_conflict(
+            drone(&a, &plan_a),
+            drone(&b_off, &plan_b),
+            2.0,
+            &thresholds,
+            25.0
+        ));
+
+        // Plans that cross within the lookahead do not resolve the conflict.
+        let crossing = plan("B", &[(0.0, 150.0, 150.0), (40.0, 150.0, -250.0)]);
+        let b_crossing = position("B", 150.0, 130.0, 2.0);
+        assert!(!plans_resolve_conflict(
+            drone(&a, &plan_a),
+            drone(&b_crossing, &crossing),
+            2.0,
+            &thresholds,
+            25.0
+        ));
+
+        // Nor does a plan that ends before the lookahead.
+        let short = plan("B", &[(0.0, 150.0, 150.0), (10.0, 150.0, 120.0)]);
+        assert!(!plans_resolve_conflict(
+            drone(&a, &plan_a),
+            drone(&b, &short),
+            2.0,
+            &thresholds,
+            25.0
+        ));
+    }
+}
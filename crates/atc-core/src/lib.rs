@@ -1,20 +1,88 @@
+pub mod capabilities;
 pub mod conflict;
+pub mod coverage;
+pub mod crewed_traffic;
+pub mod dead_reckoning;
+pub mod dependencies;
+pub mod geojson;
+pub mod ground_risk;
+pub mod home;
+pub mod intent;
+pub mod messages;
 pub mod models;
+pub mod msa;
+pub mod performance;
+pub mod planned_traffic;
+pub mod progress;
+pub mod rehearsal;
+pub mod replay;
+pub mod resolution;
 pub mod route_engine;
+pub mod route_profile;
 pub mod routing;
 pub mod rules;
 pub mod spatial;
+pub mod surveillance;
+pub mod takeoff_landing;
+pub mod terrain_clearance;
+pub mod track_estimator;
+pub mod track_quality;
+pub mod weather;
+pub mod well_clear;
+pub mod wind;
 
-pub use conflict::{Conflict, ConflictDetector, ConflictSeverity, DronePosition};
+pub use capabilities::DroneCapabilities;
+pub use conflict::{
+    cluster_conflicts, predict_geofence_breach, Conflict, ConflictCluster, ConflictDetector,
+    ConflictSeverity, DetectionMode, DronePosition, GeofenceBreach, SeparationThresholds,
+    SeparationVolume,
+};
+pub use coverage::{apply_coverage, coverage_gaps, CoverageArea, CoverageGap, CoverageMode};
+pub use crewed_traffic::{AircraftCategory, CrewedProtection};
+pub use dead_reckoning::{dead_reckon, DeadReckoningConfig, DeadReckoningMode};
+pub use dependencies::{dependency_status, expected_clear_time, DependencyStatus};
+pub use geojson::{
+    geofence_from_feature, geofence_to_feature, geofences_to_feature_collection, GeofenceFeature,
+};
+pub use ground_risk::{
+    apply_ground_risk, ground_risk_exposure, GroundRiskExposure, PopulationRaster,
+};
+pub use home::{is_approved_landing_point, DroneHome};
+pub use intent::{apply_intent_filter, plans_resolve_conflict, IntentFilterMode, PlannedDrone};
+pub use messages::{Message, MessageCatalog, MessageFormatter};
 pub use models::{
-    Command, CommandType, CreateGeofenceRequest, DroneState, FlightPlan, FlightPlanMetadata,
-    FlightPlanRequest, FlightStatus, Geofence, GeofenceType, Telemetry, TrajectoryPoint,
-    UpdateGeofenceRequest, Waypoint,
+    AdvisoryAcknowledgment, BreachResponse, Command, CommandSignature, CommandSigningKey,
+    CommandType, CreateGeofenceRequest, DroneState, FlightPlan, FlightPlanMetadata,
+    FlightPlanRequest, FlightStatus, Geofence, GeofenceType, PlanDependency, SignedCommand,
+    Telemetry, TrajectoryPoint, UpdateGeofenceRequest, Waypoint,
 };
+pub use msa::{MsaBounds, MsaGrid};
+pub use performance::DronePerformance;
+pub use planned_traffic::{apply_traffic, PlannedTraffic, TrafficSeparation};
+pub use progress::{flight_progress, FlightProgress};
+pub use rehearsal::{RehearsalIssue, RehearsalIssueKind};
+pub use replay::{ConflictReplay, EncounterBuilder, ReplayTimeline};
+pub use resolution::{resolution_options, Maneuver, ResolutionOption};
 pub use route_engine::{
-    apply_obstacles, build_lane_offsets, generate_grid_samples, optimize_airborne_path,
-    optimize_flight_path, resolve_grid_spacing, RouteEngineConfig, RouteEngineResult,
-    RouteEngineStats, RouteEngineWaypoint, RouteGrid, RouteGridPoint, RouteObstacle,
+    apply_altitude_layers, apply_obstacles, build_altitude_layers, build_lane_offsets,
+    generate_grid_samples, optimize_airborne_path, optimize_flight_path, optimize_flight_path_rrt,
+    resolve_grid_spacing, route_corridor, CorridorConfig, CorridorVolume, RouteEngineConfig,
+    RouteEngineResult, RouteEngineStats, RouteEngineWaypoint, RouteGrid, RouteGridPoint,
+    RouteObstacle, RoutePlanner, RouteSearch, SearchBudget,
 };
+pub use route_profile::{build_route_profile, RouteProfileStation};
 pub use routing::{generate_avoidance_route, select_avoidance_type, AvoidanceType};
 pub use spatial::haversine_distance;
+pub use surveillance::{crosscheck, CrosscheckThresholds, SurveillanceDivergence};
+pub use takeoff_landing::{
+    apply_takeoff_landing_profile, find_vertiport, TakeoffLandingProfile, TerminalPath,
+    TerminalProfile, Vertiport,
+};
+pub use terrain_clearance::{detect_terrain_conflict, TerrainConflict};
+pub use track_estimator::{TrackEstimator, TrackEstimatorConfig, TrackFix};
+pub use track_quality::{
+    apply_track_quality_filter, TrackHistory, TrackQuality, TrackQualityConfig, TrackQualityMode,
+};
+pub use weather::{apply_weather, WeatherCell, WeatherLimits};
+pub use well_clear::{WellClearParams, WellClearState};
+pub use wind::{apply_wind, ground_speed_in_wind, WindField, WindSample};
@@ -0,0 +1,478 @@
+//! Human-readable messages.
+//!
+//! Violations, advisories and compliance results carry a [`Message`]: a stable code plus the
+//! values it mentions. APIs return the code so clients can match on it, and a
+//! [`MessageFormatter`] renders it from a per-locale catalog of templates. English is built in;
+//! other locales are loaded as catalogs and fall back to English for codes they do not cover.
+//!
+//! Templates name their values in braces, optionally with a precision for numbers:
+//! `"Altitude {altitude_m:.1}m exceeds max altitude {max_m:.1}m"`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Locale of the built-in catalog.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Stable message codes.
+pub mod codes {
+    pub const ROUTE_MISSING: &str = "route.missing";
+    pub const ROUTE_TOO_SHORT: &str = "route.too_short";
+    pub const ROUTE_COORDINATE_NOT_FINITE: &str = "route.coordinate_not_finite";
+    pub const ROUTE_COORDINATE_OUT_OF_RANGE: &str = "route.coordinate_out_of_range";
+    pub const ROUTE_ALTITUDE_NOT_FINITE: &str = "route.altitude_not_finite";
+    pub const ROUTE_ALTITUDE_ABOVE_MAX: &str = "route.altitude_above_max";
+    pub const ROUTE_ALTITUDE_BELOW_MIN: &str = "route.altitude_below_min";
+    pub const ROUTE_AGL_ABOVE_MAX: &str = "route.agl_above_max";
+    pub const ROUTE_AGL_BELOW_MIN: &str = "route.agl_below_min";
+    pub const ROUTE_TERRAIN_UNAVAILABLE: &str = "route.terrain_unavailable";
+    pub const ROUTE_BELOW_MSA: &str = "route.below_msa";
+    pub const ROUTE_GEOFENCE: &str = "route.geofence";
+    pub const ROUTE_TETHER: &str = "route.tether";
+    pub const ROUTE_LANDING_POINT: &str = "route.landing_point";
+    pub const ROUTE_TIME_OFFSET_INVALID: &str = "route.time_offset_invalid";
+    pub const ROUTE_TIME_OFFSET_DECREASING: &str = "route.time_offset_decreasing";
+
+    pub const PLAN_DEPENDENCY_UNKNOWN_FLIGHT: &str = "plan.dependency.unknown_flight";
+    pub const PLAN_DEPENDENCY_FLIGHT_ENDED: &str = "plan.dependency.flight_ended";
+    pub const PLAN_DEPENDENCY_UNKNOWN_GEOFENCE: &str = "plan.dependency.unknown_geofence";
+
+    pub const COMPLIANCE_WEATHER_UNAVAILABLE: &str = "compliance.weather.unavailable";
+    pub const COMPLIANCE_WEATHER_MISSING: &str = "compliance.weather.missing";
+    pub const COMPLIANCE_WEATHER_CONDITIONS: &str = "compliance.weather.conditions";
+    pub const COMPLIANCE_BATTERY_MISSING: &str = "compliance.battery.missing";
+    pub const COMPLIANCE_BATTERY_ESTIMATE: &str = "compliance.battery.estimate";
+    pub const COMPLIANCE_POPULATION_UNAVAILABLE: &str = "compliance.population.unavailable";
+    pub const COMPLIANCE_POPULATION_DENSITY: &str = "compliance.population.density";
+    pub const COMPLIANCE_OBSTACLES_UNAVAILABLE: &str = "compliance.obstacles.unavailable";
+    pub const COMPLIANCE_OBSTACLES_ROUTE_MISSING: &str = "compliance.obstacles.route_missing";
+    pub const COMPLIANCE_OBSTACLES_CONFLICTS: &str = "compliance.obstacles.conflicts";
+    pub const COMPLIANCE_C2_NO_COVERAGE_MAP: &str = "compliance.c2_link.no_coverage_map";
+    pub const COMPLIANCE_C2_COVERED: &str = "compliance.c2_link.covered";
+    pub const COMPLIANCE_C2_GAPS_VLOS: &str = "compliance.c2_link.gaps_vlos";
+    pub const COMPLIANCE_C2_GAP_EXCEEDED: &str = "compliance.c2_link.gap_exceeded";
+    pub const COMPLIANCE_C2_GAPS: &str = "compliance.c2_link.gaps";
+
+    pub const ADVISORY_CONFLICT: &str = "advisory.conflict";
+    pub const ADVISORY_GEOFENCE_INSIDE: &str = "advisory.geofence_inside";
+    pub const ADVISORY_GEOFENCE_IMMINENT: &str = "advisory.geofence_imminent";
+    pub const ADVISORY_TERRAIN_BELOW_FLOOR: &str = "advisory.terrain_below_floor";
+    pub const ADVISORY_TERRAIN_PREDICTED: &str = "advisory.terrain_predicted";
+    pub const ADVISORY_TETHER: &str = "advisory.tether";
+    pub const ADVISORY_CONFORMANCE: &str = "advisory.conformance";
+    pub const ADVISORY_COMMAND_THROTTLED: &str = "advisory.command_throttled";
+    pub const ADVISORY_SURVEILLANCE_DIVERGENCE: &str = "advisory.surveillance_divergence";
+
+    pub const REHEARSAL_GEOFENCE: &str = "rehearsal.geofence";
+    pub const REHEARSAL_TETHER: &str = "rehearsal.tether";
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    (codes::ROUTE_MISSING, "Route is required for compliance checks"),
+    (codes::ROUTE_TOO_SHORT, "At least 2 waypoints are required"),
+    (
+        codes::ROUTE_COORDINATE_NOT_FINITE,
+        "Latitude/longitude must be finite numbers",
+    ),
+    (
+        codes::ROUTE_COORDINATE_OUT_OF_RANGE,
+        "Latitude/longitude out of range",
+    ),
+    (
+        codes::ROUTE_ALTITUDE_NOT_FINITE,
+        "Altitude must be a finite number",
+    ),
+    (
+        codes::ROUTE_ALTITUDE_ABOVE_MAX,
+        "Altitude {altitude_m:.1}m exceeds max altitude {max_m:.1}m",
+    ),
+    (
+        codes::ROUTE_ALTITUDE_BELOW_MIN,
+        "Altitude {altitude_m:.1}m is below min altitude {min_m:.1}m",
+    ),
+    (
+        codes::ROUTE_AGL_ABOVE_MAX,
+        "Altitude AGL {agl_m:.1}m exceeds max AGL {max_agl_m:.1}m",
+    ),
+    (
+        codes::ROUTE_AGL_BELOW_MIN,
+        "Altitude AGL {agl_m:.1}m is below min AGL {min_agl_m:.1}m",
+    ),
+    (
+        codes::ROUTE_TERRAIN_UNAVAILABLE,
+        "Terrain fetch failed (required for AGL altitude checks): {error}",
+    ),
+    (
+        codes::ROUTE_BELOW_MSA,
+        "Altitude {altitude_m:.1}m is below the minimum safe altitude {msa_m:.1}m",
+    ),
+    (
+        codes::ROUTE_GEOFENCE,
+        "Route intersects geofence '{geofence_name}'",
+    ),
+    (
+        codes::ROUTE_TETHER,
+        "Route point is {excess_m:.0} m beyond the drone's tether",
+    ),
+    (
+        codes::ROUTE_LANDING_POINT,
+        "Route must end at the drone's home or a vertiport",
+    ),
+    (
+        codes::ROUTE_TIME_OFFSET_INVALID,
+        "Trajectory time_offset_s must be a non-negative finite number",
+    ),
+    (
+        codes::ROUTE_TIME_OFFSET_DECREASING,
+        "Trajectory time_offset_s must be non-decreasing",
+    ),
+    (
+        codes::PLAN_DEPENDENCY_UNKNOWN_FLIGHT,
+        "Depends on unknown flight '{flight_id}'",
+    ),
+    (
+        codes::PLAN_DEPENDENCY_FLIGHT_ENDED,
+        "Depends on flight '{flight_id}', which is {status} and will not land",
+    ),
+    (
+        codes::PLAN_DEPENDENCY_UNKNOWN_GEOFENCE,
+        "Depends on unknown geofence '{geofence_id}'",
+    ),
+    (
+        codes::COMPLIANCE_WEATHER_UNAVAILABLE,
+        "Weather fetch failed: {error}",
+    ),
+    (codes::COMPLIANCE_WEATHER_MISSING, "Weather values missing"),
+    (
+        codes::COMPLIANCE_WEATHER_CONDITIONS,
+        "Wind {wind_mps:.1} m/s, Gust {gust_mps:.1} m/s, Precip {precip_mm:.1} mm (Source: {source})",
+    ),
+    (codes::COMPLIANCE_BATTERY_MISSING, "Battery inputs missing"),
+    (
+        codes::COMPLIANCE_BATTERY_ESTIMATE,
+        "Est {estimated_minutes:.1} min | Remaining {remaining_min:.1} min",
+    ),
+    (
+        codes::COMPLIANCE_POPULATION_UNAVAILABLE,
+        "Population analysis failed: {error}",
+    ),
+    (
+        codes::COMPLIANCE_POPULATION_DENSITY,
+        "Density {density:.0} people/km^2 ({classification})",
+    ),
+    (
+        codes::COMPLIANCE_OBSTACLES_UNAVAILABLE,
+        "Obstacle analysis failed: {error}",
+    ),
+    (codes::COMPLIANCE_OBSTACLES_ROUTE_MISSING, "Route missing"),
+    (codes::COMPLIANCE_OBSTACLES_CONFLICTS, "{count} conflicts"),
+    (codes::COMPLIANCE_C2_NO_COVERAGE_MAP, "No C2 coverage map loaded"),
+    (codes::COMPLIANCE_C2_COVERED, "Route stays within C2 coverage"),
+    (
+        codes::COMPLIANCE_C2_GAPS_VLOS,
+        "{gaps} C2 coverage gaps, longest {longest_gap_s:.0}s (not limited for VLOS)",
+    ),
+    (
+        codes::COMPLIANCE_C2_GAP_EXCEEDED,
+        "Longest C2 coverage gap {longest_gap_s:.0}s exceeds {max_gap_s:.0}s",
+    ),
+    (
+        codes::COMPLIANCE_C2_GAPS,
+        "{gaps} C2 coverage gaps, longest {longest_gap_s:.0}s",
+    ),
+    (
+        codes::ADVISORY_CONFLICT,
+        "Conflict with {other_drone_id} ({distance_m:.0}m separation; closest {cpa_horizontal_m:.0}m horizontal / {cpa_vertical_m:.0}m vertical in {time_to_closest_s:.0}s)",
+    ),
+    (
+        codes::ADVISORY_GEOFENCE_INSIDE,
+        "Inside {geofence_name} ({geofence_type})",
+    ),
+    (
+        codes::ADVISORY_GEOFENCE_IMMINENT,
+        "Projected to enter {geofence_name} ({geofence_type}) in {time_to_breach_s:.0}s",
+    ),
+    (
+        codes::ADVISORY_TERRAIN_BELOW_FLOOR,
+        "Terrain clearance {current_agl_m:.0} m AGL is below the {floor_agl_m:.0} m floor",
+    ),
+    (
+        codes::ADVISORY_TERRAIN_PREDICTED,
+        "Terrain clearance predicted to drop to {min_agl_m:.0} m AGL (floor {floor_agl_m:.0} m) in {time_to_floor_s:.0}s",
+    ),
+    (
+        codes::ADVISORY_TETHER,
+        "{excess_m:.0} m beyond the {tether_radius_m:.0} m tether from home",
+    ),
+    (codes::ADVISORY_CONFORMANCE, "Conformance issue detected"),
+    (
+        codes::ADVISORY_COMMAND_THROTTLED,
+        "Automatic {command} withheld: the {scope} command budget is exhausted",
+    ),
+    (
+        codes::ADVISORY_SURVEILLANCE_DIVERGENCE,
+        "Telemetry and {source} disagree by {horizontal_m:.0} m horizontal / {vertical_m:.0} m vertical",
+    ),
+    (
+        codes::REHEARSAL_GEOFENCE,
+        "Enters geofence '{geofence_name}'",
+    ),
+    (
+        codes::REHEARSAL_TETHER,
+        "Beyond the {tether_radius_m:.0} m tether from home",
+    ),
+];
+
+/// A message as a stable code and the values its templates interpolate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Message {
+    pub code: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, Value>,
+}
+
+impl Message {
+    pub fn new(code: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    pub fn with(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.params.insert(name.to_string(), value.into());
+        self
+    }
+}
+
+/// Renders in English.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let template = ENGLISH
+            .iter()
+            .find(|(code, _)| *code == self.code)
+            .map(|(_, template)| *template);
+        match template {
+            Some(template) => f.write_str(&render(template, &self.params)),
+            None => f.write_str(&self.code),
+        }
+    }
+}
+
+/// Message templates for one locale, keyed by code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageCatalog {
+    pub locale: String,
+    pub templates: HashMap<String, String>,
+}
+
+impl MessageCatalog {
+    pub fn new(locale: &str, templates: HashMap<String, String>) -> Self {
+        Self {
+            locale: normalize_locale(locale),
+            templates,
+        }
+    }
+
+    /// The built-in English catalog.
+    pub fn english() -> Self {
+        Self::new(
+            DEFAULT_LOCALE,
+            ENGLISH
+                .iter()
+                .map(|(code, template)| (code.to_string(), template.to_string()))
+                .collect(),
+        )
+    }
+}
+
+/// Renders messages from per-locale catalogs.
+#[derive(Debug, Clone)]
+pub struct MessageFormatter {
+    default_locale: String,
+    catalogs: HashMap<String, MessageCatalog>,
+}
+
+impl Default for MessageFormatter {
+    fn default() -> Self {
+        let english = MessageCatalog::english();
+        Self {
+            default_locale: english.locale.clone(),
+            catalogs: HashMap::from([(english.locale.clone(), english)]),
+        }
+    }
+}
+
+impl MessageFormatter {
+    /// Add a catalog, or extend the catalog already loaded for its locale.
+    pub fn add_catalog(&mut self, catalog: MessageCatalog) {
+        self.catalogs
+            .entry(catalog.locale.clone())
+            .or_insert_with(|| MessageCatalog::new(&catalog.locale, HashMap::new()))
+            .templates
+            .extend(catalog.templates);
+    }
+
+    /// Locale used when none is requested.
+    pub fn set_default_locale(&mut self, locale: &str) {
+        self.default_locale = normalize_locale(locale);
+    }
+
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    pub fn locales(&self) -> Vec<String> {
+        let mut locales: Vec<String> = self.catalogs.keys().cloned().collect();
+        locales.sort();
+        locales
+    }
+
+    /// Render `message` in `locale` (or the default locale), trying the full locale, then its
+    /// language, then English; unknown codes render as the code itself.
+    pub fn format(&self, message: &Message, locale: Option<&str>) -> String {
+        self.fallback_chain(locale)
+            .iter()
+            .find_map(|catalog| catalog.templates.get(&message.code))
+            .map(|template| render(template, &message.params))
+            .unwrap_or_else(|| message.code.clone())
+    }
+
+    /// Every template available in `locale`, with English filling the gaps.
+    pub fn templates(&self, locale: Option<&str>) -> BTreeMap<String, String> {
+        let mut templates = BTreeMap::new();
+        for catalog in self.fallback_chain(locale).iter().rev() {
+            templates.extend(
+                catalog
+                    .templates
+                    .iter()
+                    .map(|(code, template)| (code.clone(), template.clone())),
+            );
+        }
+        templates
+    }
+
+    /// Catalogs to consult for `locale`, most specific first.
+    fn fallback_chain(&self, locale: Option<&str>) -> Vec<&MessageCatalog> {
+        let locale = locale
+            .map(normalize_locale)
+            .unwrap_or_else(|| self.default_locale.clone());
+        let language = locale.split('-').next().unwrap_or_default().to_string();
+        let mut chain: Vec<&MessageCatalog> = Vec::new();
+        for candidate in [locale, language, DEFAULT_LOCALE.to_string()] {
+            if let Some(catalog) = self.catalogs.get(&candidate) {
+                if !chain
+                    .iter()
+                    .any(|existing| existing.locale == catalog.locale)
+                {
+                    chain.push(catalog);
+                }
+            }
+        }
+        chain
+    }
+}
+
+/// `pt_BR` and `PT-br` both become `pt-br`.
+fn normalize_locale(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_ascii_lowercase()
+}
+
+/// Substitute `{name}` and `{name:.N}` placeholders; unknown names are left as written.
+fn render(template: &str, params: &BTreeMap<String, Value>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        output.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            output.push_str(&rest[open..]);
+            return output;
+        };
+        let placeholder = &after[..close];
+        let (name, precision) = match placeholder.split_once(":.") {
+            Some((name, digits)) => (name, digits.parse::<usize>().ok()),
+            None => (placeholder, None),
+        };
+        match params.get(name) {
+            Some(value) => output.push_str(&render_value(value, precision)),
+            None => output.push_str(&rest[open..open + close + 2]),
+        }
+        rest = &after[close + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn render_value(value: &Value, precision: Option<usize>) -> String {
+    match (value, precision) {
+        (Value::String(text), _) => text.clone(),
+        (Value::Number(number), Some(precision)) => match number.as_f64() {
+            Some(number) => format!("{:.*}", precision, number),
+            None => number.to_string(),
+        },
+        (value, _) => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_from_the_requested_locale_with_english_fallback() {
+        let message = Message::new(codes::ROUTE_ALTITUDE_ABOVE_MAX)
+            .with("altitude_m", 130.04)
+            .with("max_m", 121.0);
+        assert_eq!(
+            message.to_string(),
+            "Altitude 130.0m exceeds max altitude 121.0m"
+        );
+
+        let mut formatter = MessageFormatter::default();
+        formatter.add_catalog(MessageCatalog::new(
+            "de",
+            HashMap::from([(
+                codes::ROUTE_ALTITUDE_ABOVE_MAX.to_string(),
+                "Höhe {altitude_m:.0} m über dem Maximum von {max_m:.0} m".to_string(),
+            )]),
+        ));
+        assert_eq!(
+            formatter.format(&message, Some("de_DE")),
+            "Höhe 130 m über dem Maximum von 121 m"
+        );
+        assert_eq!(formatter.format(&message, None), message.to_string());
+
+        // Codes the German catalog lacks fall back to English.
+        let missing = Message::new(codes::ROUTE_TOO_SHORT);
+        assert_eq!(
+            formatter.format(&missing, Some("de")),
+            "At least 2 waypoints are required"
+        );
+        formatter.set_default_locale("de");
+        assert_eq!(
+            formatter.format(&message, None),
+            "Höhe 130 m über dem Maximum von 121 m"
+        );
+        assert_eq!(
+            formatter.templates(Some("de")).len(),
+            MessageCatalog::english().templates.len()
+        );
+
+        // Unknown codes and placeholders are kept rather than dropped.
+        assert_eq!(
+            formatter.format(&Message::new("custom.code"), None),
+            "custom.code"
+        );
+        assert_eq!(
+            render(
+                "{count} of {total}",
+                &Message::new("x").with("count", 3).params
+            ),
+            "3 of {total}"
+        );
+    }
+}
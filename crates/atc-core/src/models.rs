@@ -3,6 +3,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::capabilities::DroneCapabilities;
+use crate::messages::Message;
+
 /// Telemetry data received from a drone.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Telemetry {
@@ -42,6 +45,12 @@ pub struct DroneState {
     pub velocity_z: f64,
     pub last_update: DateTime<Utc>,
     pub status: DroneStatus,
+    /// Scheduling priority of the drone's current flight plan (lower = higher priority).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduling_priority: Option<u32>,
+    /// Commands and equipment the drone declared at registration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<DroneCapabilities>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -74,6 +83,8 @@ impl DroneState {
             velocity_z: telemetry.velocity_z,
             last_update: telemetry.timestamp,
             status: DroneStatus::Active,
+            scheduling_priority: None,
+            capabilities: None,
         }
     }
 
@@ -97,6 +108,7 @@ impl DroneState {
 
 /// A flight plan submitted by an operator.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct FlightPlan {
     pub flight_id: String,
     pub drone_id: String,
@@ -115,6 +127,7 @@ pub struct FlightPlan {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct FlightPlanRequest {
     pub drone_id: String,
     pub owner_id: Option<String>,
@@ -131,6 +144,7 @@ pub struct FlightPlanRequest {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Waypoint {
     pub lat: f64,
     pub lon: f64,
@@ -140,6 +154,7 @@ pub struct Waypoint {
 
 /// Time-stamped trajectory point for high-fidelity conflict checks.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TrajectoryPoint {
     pub lat: f64,
     pub lon: f64,
@@ -151,6 +166,7 @@ pub struct TrajectoryPoint {
 
 /// Metadata captured with a flight plan submission.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct FlightPlanMetadata {
     #[serde(default)]
     pub drone_speed_mps: Option<f64>,
@@ -170,6 +186,9 @@ pub struct FlightPlanMetadata {
     pub submitted_at: Option<String>,
     #[serde(default)]
     pub blender_declaration_id: Option<String>,
+    /// Last Flight Blender declaration state seen by the declaration sync loop.
+    #[serde(default)]
+    pub blender_declaration_state: Option<u8>,
     #[serde(default)]
     pub operation_type: Option<u8>,
     #[serde(default)]
@@ -193,12 +212,57 @@ pub struct FlightPlanMetadata {
     /// Scheduled delay applied by ATC, in seconds.
     #[serde(default)]
     pub scheduled_delay_s: Option<u64>,
+    /// Cruise altitude change applied by ATC to deconflict without delay, in meters.
+    #[serde(default)]
+    pub scheduled_altitude_offset_m: Option<f64>,
     /// If the plan is reserved, the time (RFC3339) when the reservation expires.
     #[serde(default)]
     pub reservation_expires_at: Option<String>,
+    /// Airspace sector of the departure point, for dispatcher routing.
+    #[serde(default)]
+    pub sector_id: Option<String>,
+    /// Seed of the random route or planner choices behind the plan; resubmitting with it
+    /// reproduces the same route.
+    #[serde(default)]
+    pub planning_seed: Option<u64>,
+    /// Operator acknowledgment of the advisories and nearby TFRs on the route.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advisory_acknowledgment: Option<AdvisoryAcknowledgment>,
+    /// What must happen before the plan may depart (see [`crate::dependencies`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<PlanDependency>,
+}
+
+/// An operator's acknowledgment of the advisories a plan was shown before approval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AdvisoryAcknowledgment {
+    /// `acknowledgment_token` from the 409 listing the advisories
+    pub token: String,
+    /// Advisories the token covers; filled in by ATC
+    #[serde(default)]
+    pub geofence_ids: Vec<String>,
+    /// When ATC accepted the acknowledgment
+    #[serde(default)]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    /// Owner that acknowledged, when the plan has one
+    #[serde(default)]
+    pub acknowledged_by: Option<String>,
+}
+
+/// Something a flight plan waits for before it departs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PlanDependency {
+    /// Depart only after this flight has landed.
+    FlightLanded { flight_id: String },
+    /// Depart only once this geofence is lifted (deactivated or deleted).
+    GeofenceLifted { geofence_id: String },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum FlightStatus {
     /// Reserved (slot held), not yet confirmed for execution.
@@ -219,6 +283,7 @@ pub enum FlightStatus {
 
 /// Command issued to a drone.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Command {
     pub command_id: String,
     pub drone_id: String,
@@ -228,7 +293,52 @@ pub struct Command {
     pub acknowledged: bool,
 }
 
+impl Command {
+    /// Canonical bytes covered by a command signature (compact JSON in field order).
+    pub fn signing_payload(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+}
+
+/// Public half of the server's command signing key, shared with drones at registration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CommandSigningKey {
+    pub key_id: String,
+    /// Signature algorithm (currently always `ed25519`)
+    pub algorithm: String,
+    /// Base64-encoded raw public key
+    pub public_key: String,
+}
+
+/// Signature over [`Command::signing_payload`], sent with the exact bytes it covers.
+///
+/// Verifiers check `signature` against the decoded `payload` and read the command from those
+/// bytes, rather than re-serializing the command they received.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CommandSignature {
+    pub key_id: String,
+    pub algorithm: String,
+    /// Base64-encoded signature
+    pub signature: String,
+    /// Base64-encoded signed bytes (the command's compact JSON)
+    #[serde(default)]
+    pub payload: String,
+}
+
+/// Command as delivered to a drone, signed when the server has a signing key.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SignedCommand {
+    #[serde(flatten)]
+    pub command: Command,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<CommandSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum CommandType {
     /// Hold position (loiter)
@@ -242,12 +352,15 @@ pub enum CommandType {
     },
     /// Resume normal operation
     Resume,
+    /// Land immediately at the current position
+    Land,
 }
 
 // ========== GEOFENCE MODELS ==========
 
 /// A geographic boundary defining restricted airspace.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Geofence {
     pub id: String,
     pub name: String,
@@ -261,10 +374,88 @@ pub struct Geofence {
     /// Whether the geofence is currently active
     pub active: bool,
     pub created_at: DateTime<Utc>,
+    /// Overrides the per-type breach response policy for this geofence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breach_response: Option<BreachResponse>,
+    /// Time window(s) the geofence is in force; always in force while active when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<GeofenceSchedule>,
+    /// Organization the geofence is private to; shared airspace when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<String>,
+}
+
+/// When a geofence is in force, e.g. a no-fly window over a stadium on match days.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GeofenceSchedule {
+    /// Start of the first window
+    pub start: DateTime<Utc>,
+    /// End of the first window
+    pub end: DateTime<Utc>,
+    /// Repeat the window every day or week
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<GeofenceRecurrence>,
+    /// Latest start of a recurring window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum GeofenceRecurrence {
+    Daily,
+    Weekly,
+}
+
+impl GeofenceRecurrence {
+    fn period_secs(self) -> i64 {
+        match self {
+            GeofenceRecurrence::Daily => 86_400,
+            GeofenceRecurrence::Weekly => 7 * 86_400,
+        }
+    }
+}
+
+impl GeofenceSchedule {
+    /// Whether any window overlaps `from..=to`. Windows include their start but not their end.
+    pub fn overlaps(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+        let Some(recurrence) = self.recurrence else {
+            return self.start <= to && self.end > from;
+        };
+        // The first window still open at `from` is the only one that can start earliest.
+        let period = recurrence.period_secs();
+        let cycles = if from < self.end {
+            0
+        } else {
+            (from - self.end).num_seconds() / period + 1
+        };
+        let start = self.start + chrono::Duration::seconds(cycles * period);
+        start <= to && self.until.is_none_or(|until| start <= until)
+    }
+
+    /// Returns list of validation errors (empty = valid).
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.end <= self.start {
+            errors.push("Schedule end must be after its start".to_string());
+        }
+        if let Some(recurrence) = self.recurrence {
+            if (self.end - self.start).num_seconds() > recurrence.period_secs() {
+                errors.push("Schedule window must not be longer than its recurrence".to_string());
+            }
+        }
+        if self.until.is_some_and(|until| until < self.start) {
+            errors.push("Schedule until must not be before its start".to_string());
+        }
+        errors
+    }
 }
 
 /// Type of geofence/restricted area.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum GeofenceType {
     /// No flights allowed
@@ -277,9 +468,25 @@ pub enum GeofenceType {
     Advisory,
 }
 
+/// Automatic response when a drone breaches (or is about to breach) a geofence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum BreachResponse {
+    /// Raise an advisory only
+    Advisory,
+    /// Hold position
+    Hold,
+    /// Fly out of (or stop short of) the geofence
+    Reroute,
+    /// Land immediately
+    Land,
+}
+
 // ========== CONFORMANCE MONITORING ==========
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ConformanceRecord {
     pub id: String,
     pub flight_declaration_id: String,
@@ -304,11 +511,32 @@ pub struct ConformanceStatus {
     pub status: String,
     pub last_checked: DateTime<Utc>,
     pub record: Option<ConformanceRecord>,
+    /// Escalation step applied while nonconforming
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<ConformanceAction>,
+    /// When the current nonconforming stretch began
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonconforming_since: Option<DateTime<Utc>>,
+}
+
+/// Response to a drone that is off its plan, escalated the longer it stays off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConformanceAction {
+    /// Raise a warning advisory only
+    Warn,
+    /// Hold position
+    Hold,
+    /// Fly back to the next point of the active flight plan
+    Reroute,
+    /// Escalate to the operator through the `conformance_escalated` webhook
+    Notify,
 }
 
 // ========== DAA (DETECT AND AVOID) ==========
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum DaaSeverity {
     Advisory,
@@ -317,19 +545,26 @@ pub enum DaaSeverity {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct DaaAdvisory {
     pub advisory_id: String,
     pub drone_id: String,
     pub owner_id: Option<String>,
-    /// Advisory source (conformance, conflict, or other backend)
+    /// Advisory source (conformance, conflict, terrain, or other backend)
     pub source: String,
     pub severity: DaaSeverity,
-    /// Recommended action (monitor, hold, reroute)
+    /// Recommended action (monitor, hold, reroute, climb)
     pub action: String,
     pub description: String,
+    /// Stable code and values behind `description`, when it was generated here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_code: Option<Message>,
     /// Optional related identifier (conflict id, geofence id, etc.)
     pub related_id: Option<String>,
     pub record: Option<ConformanceRecord>,
+    /// Airspace sector the drone was in when the advisory was raised
+    #[serde(default)]
+    pub sector_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub resolved: bool,
@@ -337,16 +572,22 @@ pub struct DaaAdvisory {
 
 /// Request to create a new geofence.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateGeofenceRequest {
     pub name: String,
     pub geofence_type: GeofenceType,
     pub polygon: Vec<[f64; 2]>,
     pub lower_altitude_m: Option<f64>,
     pub upper_altitude_m: Option<f64>,
+    #[serde(default)]
+    pub breach_response: Option<BreachResponse>,
+    #[serde(default)]
+    pub schedule: Option<GeofenceSchedule>,
 }
 
 /// Request to update an existing geofence.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UpdateGeofenceRequest {
     pub name: Option<String>,
     pub geofence_type: Option<GeofenceType>,
@@ -354,31 +595,15 @@ pub struct UpdateGeofenceRequest {
     pub lower_altitude_m: Option<f64>,
     pub upper_altitude_m: Option<f64>,
     pub active: Option<bool>,
+    #[serde(default)]
+    pub breach_response: Option<BreachResponse>,
+    #[serde(default)]
+    pub schedule: Option<GeofenceSchedule>,
 }
 
 impl Geofence {
     fn contains_point_2d(&self, lat: f64, lon: f64) -> bool {
-        // Ray casting: count intersections with polygon edges
-        let mut inside = false;
-        let n = self.polygon.len();
-        if n < 3 {
-            return false;
-        }
-
-        let mut j = n - 1;
-        for i in 0..n {
-            let yi = self.polygon[i][0];
-            let xi = self.polygon[i][1];
-            let yj = self.polygon[j][0];
-            let xj = self.polygon[j][1];
-
-            if ((yi > lat) != (yj > lat)) && (lon < (xj - xi) * (lat - yi) / (yj - yi) + xi) {
-                inside = !inside;
-            }
-            j = i;
-        }
-
-        inside
+        crate::spatial::point_in_polygon(lat, lon, &self.polygon)
     }
 
     /// Check if a point is inside this geofence's polygon.
@@ -392,6 +617,40 @@ impl Geofence {
         self.contains_point_2d(lat, lon)
     }
 
+    /// Distance (m) from a point to this geofence's volume; 0 inside it.
+    pub fn distance_m(&self, lat: f64, lon: f64, altitude_m: f64) -> f64 {
+        let horizontal_m = if self.contains_point_2d(lat, lon) || self.polygon.is_empty() {
+            0.0
+        } else {
+            let polygon = &self.polygon;
+            (0..polygon.len())
+                .map(|i| {
+                    let [lat1, lon1] = polygon[i];
+                    let [lat2, lon2] = polygon[(i + 1) % polygon.len()];
+                    crate::spatial::distance_to_segment_m(lat, lon, lat1, lon1, lat2, lon2)
+                })
+                .fold(f64::INFINITY, f64::min)
+        };
+        let vertical_m = (self.lower_altitude_m - altitude_m)
+            .max(altitude_m - self.upper_altitude_m)
+            .max(0.0);
+        horizontal_m.hypot(vertical_m)
+    }
+
+    /// Whether the geofence is active and scheduled to be in force at `at`.
+    pub fn in_force_at(&self, at: DateTime<Utc>) -> bool {
+        self.in_force_during(at, at)
+    }
+
+    /// Whether the geofence is active and in force at any point between `from` and `to`.
+    pub fn in_force_during(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+        self.active
+            && self
+                .schedule
+                .as_ref()
+                .is_none_or(|schedule| schedule.overlaps(from, to))
+    }
+
     /// Validate geofence configuration.
     /// Returns list of validation errors (empty = valid).
     pub fn validate(&self) -> Vec<String> {
@@ -425,6 +684,10 @@ impl Geofence {
             errors.push("Upper altitude exceeds maximum (10000m)".to_string());
         }
 
+        if let Some(schedule) = &self.schedule {
+            errors.extend(schedule.validate());
+        }
+
         errors
     }
 
@@ -567,6 +830,9 @@ mod tests {
             upper_altitude_m: 100.0,
             active: true,
             created_at: chrono::Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
         };
 
         assert!(geofence.intersects_segment(0.0, 0.0, 50.0, 0.0, 1.0, 50.0));
@@ -595,8 +861,37 @@ mod tests {
             upper_altitude_m: 150.0,
             active: true,
             created_at: chrono::Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
         };
 
         assert!(!geofence.intersects_segment(0.0, 0.0, 0.0, 0.0, 1.0, 200.0));
     }
+
+    #[test]
+    fn recurring_schedule_overlaps_later_windows_until_it_ends() {
+        let start = DateTime::parse_from_rfc3339("2026-03-07T18:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let hours = chrono::Duration::hours;
+        let schedule = GeofenceSchedule {
+            start,
+            end: start + hours(4),
+            recurrence: Some(GeofenceRecurrence::Weekly),
+            until: Some(start + chrono::Duration::weeks(2)),
+        };
+        assert!(schedule.validate().is_empty());
+
+        assert!(!schedule.overlaps(start - hours(2), start - hours(1)));
+        assert!(schedule.overlaps(start - hours(1), start));
+        assert!(!schedule.overlaps(start + hours(4), start + hours(5)));
+        let next_week = start + chrono::Duration::weeks(1);
+        assert!(schedule.overlaps(next_week + hours(3), next_week + hours(3)));
+        assert!(!schedule.overlaps(next_week + hours(4), next_week + hours(24)));
+        // A flight spanning the gap between two windows still overlaps the second one.
+        assert!(schedule.overlaps(start + hours(5), next_week + hours(1)));
+        let after_until = start + chrono::Duration::weeks(3);
+        assert!(!schedule.overlaps(after_until, after_until + hours(1)));
+    }
 }
@@ -0,0 +1,321 @@
+//! Minimum safe altitude (MSA) grids.
+//!
+//! An MSA grid divides an area into cells holding the lowest altitude (AMSL) that clears the
+//! terrain and every obstacle in or near the cell by a fixed margin. It is a coarse, conservative
+//! lower bound: a route below a cell's MSA is unsafe there, while one above it may still need the
+//! full planner to clear obstacles precisely. Terrain is supplied by the caller as an elevation
+//! sampler, so this module stays independent of any particular elevation source.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::route_engine::RouteObstacle;
+use crate::spatial::{meters_per_deg_lat, meters_per_deg_lon};
+
+/// Grids with more cells than this are built at a coarser spacing.
+const MAX_CELLS: usize = 250_000;
+
+/// Geographic bounding box.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MsaBounds {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+}
+
+impl MsaBounds {
+    /// Parse `min_lat,min_lon,max_lat,max_lon`, the format of `RID_VIEW_BBOX`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let values: Vec<f64> = value
+            .split(',')
+            .map(|part| part.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        let [min_lat, min_lon, max_lat, max_lon] = values[..] else {
+            return None;
+        };
+        let bounds = Self {
+            min_lat,
+            min_lon,
+            max_lat,
+            max_lon,
+        };
+        bounds.is_valid().then_some(bounds)
+    }
+
+    /// Whether a point lies inside the box, edges included.
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        (self.min_lat..=self.max_lat).contains(&lat) && (self.min_lon..=self.max_lon).contains(&lon)
+    }
+
+    fn is_valid(&self) -> bool {
+        self.min_lat < self.max_lat
+            && self.min_lon < self.max_lon
+            && self.min_lat >= -90.0
+            && self.max_lat <= 90.0
+            && self.min_lon >= -180.0
+            && self.max_lon <= 180.0
+    }
+}
+
+/// Minimum safe altitudes over a regular lat/lon grid.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MsaGrid {
+    /// South-west corner of the first cell
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub lat_step_deg: f64,
+    pub lon_step_deg: f64,
+    pub rows: usize,
+    pub cols: usize,
+    /// Margin above terrain and obstacles included in every cell
+    pub clearance_m: f64,
+    /// Cell MSAs in meters AMSL, row-major from the south-west corner
+    pub msa_m: Vec<f64>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl MsaGrid {
+    /// Build a grid of roughly `spacing_m` cells over `bounds`.
+    ///
+    /// A cell's MSA is `clearance_m` above the highest of the terrain at its corners and centre
+    /// and the top of any obstacle whose keep-out area reaches into the cell.
+    pub fn build<F>(
+        bounds: &MsaBounds,
+        spacing_m: f64,
+        clearance_m: f64,
+        obstacles: &[RouteObstacle],
+        terrain_height: F,
+        generated_at: DateTime<Utc>,
+    ) -> Self
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let mean_lat = (bounds.min_lat + bounds.max_lat) / 2.0;
+        let meters_lat = meters_per_deg_lat(mean_lat);
+        let meters_lon = meters_per_deg_lon(mean_lat).max(1.0);
+        let height_m = (bounds.max_lat - bounds.min_lat) * meters_lat;
+        let width_m = (bounds.max_lon - bounds.min_lon) * meters_lon;
+
+        let mut spacing_m = spacing_m.max(1.0);
+        let (rows, cols) = loop {
+            let rows = ((height_m / spacing_m).ceil() as usize).max(1);
+            let cols = ((width_m / spacing_m).ceil() as usize).max(1);
+            if rows.saturating_mul(cols) <= MAX_CELLS {
+                break (rows, cols);
+            }
+            spacing_m *= ((rows * cols) as f64 / MAX_CELLS as f64).sqrt().max(1.01);
+        };
+        let lat_step_deg = (bounds.max_lat - bounds.min_lat) / rows as f64;
+        let lon_step_deg = (bounds.max_lon - bounds.min_lon) / cols as f64;
+        let ground = |lat: f64, lon: f64| Some(terrain_height(lat, lon)).filter(|h| h.is_finite());
+
+        let mut msa_m = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            let south = bounds.min_lat + row as f64 * lat_step_deg;
+            for col in 0..cols {
+                let west = bounds.min_lon + col as f64 * lon_step_deg;
+                let highest = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0), (0.5, 0.5)]
+                    .iter()
+                    .filter_map(|(dy, dx)| {
+                        ground(south + dy * lat_step_deg, west + dx * lon_step_deg)
+                    })
+                    .fold(f64::NEG_INFINITY, f64::max);
+                let highest = if highest.is_finite() { highest } else { 0.0 };
+                msa_m.push(highest + clearance_m);
+            }
+        }
+
+        // An obstacle reaches into a cell when its keep-out area comes within half a cell
+        // diagonal of the cell centre.
+        let half_diagonal_m = 0.5 * (lat_step_deg * meters_lat).hypot(lon_step_deg * meters_lon);
+        for obstacle in obstacles {
+            let height = obstacle.height_m.unwrap_or(0.0);
+            if !height.is_finite() || height <= 0.0 {
+                continue;
+            }
+            let top_m = ground(obstacle.lat, obstacle.lon).unwrap_or(0.0) + height + clearance_m;
+            let centre = [[obstacle.lat, obstacle.lon]];
+            let footprint = obstacle
+                .polygon
+                .as_deref()
+                .filter(|polygon| polygon.len() >= 3)
+                .unwrap_or(&centre);
+            let reach_m = obstacle.radius_m.max(0.0) + half_diagonal_m;
+            let (lat_lo, lat_hi, lon_lo, lon_hi) = footprint.iter().fold(
+                (
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                ),
+                |(lat_lo, lat_hi, lon_lo, lon_hi), [lat, lon]| {
+                    (
+                        lat_lo.min(*lat),
+                        lat_hi.max(*lat),
+                        lon_lo.min(*lon),
+                        lon_hi.max(*lon),
+                    )
+                },
+            );
+            let row_range = cell_range(
+                lat_lo - reach_m / meters_lat,
+                lat_hi + reach_m / meters_lat,
+                bounds.min_lat,
+                lat_step_deg,
+                rows,
+            );
+            let col_range = cell_range(
+                lon_lo - reach_m / meters_lon,
+                lon_hi + reach_m / meters_lon,
+                bounds.min_lon,
+                lon_step_deg,
+                cols,
+            );
+            for row in row_range {
+                let center_lat = bounds.min_lat + (row as f64 + 0.5) * lat_step_deg;
+                for col in col_range.clone() {
+                    let center_lon = bounds.min_lon + (col as f64 + 0.5) * lon_step_deg;
+                    if obstacle.distance_m(center_lat, center_lon) <= half_diagonal_m {
+                        let cell = &mut msa_m[row * cols + col];
+                        *cell = cell.max(top_m);
+                    }
+                }
+            }
+        }
+
+        Self {
+            min_lat: bounds.min_lat,
+            min_lon: bounds.min_lon,
+            lat_step_deg,
+            lon_step_deg,
+            rows,
+            cols,
+            clearance_m,
+            msa_m,
+            generated_at,
+        }
+    }
+
+    fn max_lat(&self) -> f64 {
+        self.min_lat + self.rows as f64 * self.lat_step_deg
+    }
+
+    fn max_lon(&self) -> f64 {
+        self.min_lon + self.cols as f64 * self.lon_step_deg
+    }
+
+    /// MSA of the cell containing a point, or `None` outside the grid.
+    pub fn sample(&self, lat: f64, lon: f64) -> Option<f64> {
+        if !(self.min_lat..=self.max_lat()).contains(&lat)
+            || !(self.min_lon..=self.max_lon()).contains(&lon)
+        {
+            return None;
+        }
+        let row = (((lat - self.min_lat) / self.lat_step_deg) as usize).min(self.rows - 1);
+        let col = (((lon - self.min_lon) / self.lon_step_deg) as usize).min(self.cols - 1);
+        self.msa_m.get(row * self.cols + col).copied()
+    }
+
+    /// The cells overlapping `bounds`, or `None` when they miss the grid.
+    pub fn crop(&self, bounds: &MsaBounds) -> Option<Self> {
+        if bounds.max_lat <= self.min_lat
+            || bounds.min_lat >= self.max_lat()
+            || bounds.max_lon <= self.min_lon
+            || bounds.min_lon >= self.max_lon()
+        {
+            return None;
+        }
+        let rows = cell_range(
+            bounds.min_lat,
+            bounds.max_lat,
+            self.min_lat,
+            self.lat_step_deg,
+            self.rows,
+        );
+        let cols = cell_range(
+            bounds.min_lon,
+            bounds.max_lon,
+            self.min_lon,
+            self.lon_step_deg,
+            self.cols,
+        );
+        let msa_m = rows
+            .clone()
+            .flat_map(|row| self.msa_m[row * self.cols..][cols.clone()].iter().copied())
+            .collect();
+        Some(Self {
+            min_lat: self.min_lat + rows.start as f64 * self.lat_step_deg,
+            min_lon: self.min_lon + cols.start as f64 * self.lon_step_deg,
+            lat_step_deg: self.lat_step_deg,
+            lon_step_deg: self.lon_step_deg,
+            rows: rows.len(),
+            cols: cols.len(),
+            clearance_m: self.clearance_m,
+            msa_m,
+            generated_at: self.generated_at,
+        })
+    }
+}
+
+/// Indices of the `count` cells of size `step` starting at `origin` that overlap `lo..=hi`.
+fn cell_range(lo: f64, hi: f64, origin: f64, step: f64, count: usize) -> std::ops::Range<usize> {
+    let first = ((lo - origin) / step).floor().max(0.0) as usize;
+    let last = ((hi - origin) / step).floor().max(0.0) as usize;
+    first.min(count)..(last + 1).min(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn msa_clears_terrain_and_nearby_obstacles() {
+        let bounds = MsaBounds::parse("33.60,-117.90,33.62,-117.88").expect("bounds");
+        let tower = RouteObstacle {
+            lat: 33.615,
+            lon: -117.885,
+            radius_m: 20.0,
+            height_m: Some(90.0),
+            polygon: None,
+        };
+        // Terrain rises from 100 m in the west to 200 m in the east.
+        let grid = MsaGrid::build(
+            &bounds,
+            100.0,
+            30.0,
+            &[tower],
+            |_, lon| 100.0 + (lon + 117.90) / 0.02 * 100.0,
+            Utc::now(),
+        );
+        assert!(
+            grid.rows >= 20 && grid.cols >= 18,
+            "{}x{}",
+            grid.rows,
+            grid.cols
+        );
+
+        let west = grid.sample(33.605, -117.8999).expect("west cell");
+        assert!((130.0..140.0).contains(&west), "{}", west);
+        let tower_cell = grid.sample(33.615, -117.885).expect("tower cell");
+        assert!((290.0..300.0).contains(&tower_cell), "{}", tower_cell);
+        let beside_tower = grid.sample(33.615, -117.8825).expect("cell beside tower");
+        assert!(beside_tower < 240.0, "{}", beside_tower);
+        assert!(grid.sample(33.63, -117.885).is_none());
+
+        let crop = grid
+            .crop(&MsaBounds::parse("33.614,-117.886,33.616,-117.884").expect("crop"))
+            .expect("overlapping crop");
+        assert!(
+            crop.rows <= 4 && crop.cols <= 4,
+            "{}x{}",
+            crop.rows,
+            crop.cols
+        );
+        assert_eq!(crop.sample(33.615, -117.885), Some(tower_cell));
+        assert!(grid
+            .crop(&MsaBounds::parse("34.0,-117.0,34.1,-116.9").expect("far"))
+            .is_none());
+        assert!(MsaBounds::parse("33.62,-117.90,33.60,-117.88").is_none());
+    }
+}
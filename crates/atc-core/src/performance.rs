@@ -0,0 +1,228 @@
+//! Per-drone performance envelopes.
+//!
+//! An envelope records what an airframe can physically fly: how fast it climbs, its top speed,
+//! how quickly it turns and the strongest wind it can hold position in. Commands are checked
+//! against it before they are issued, so a drone is never told to fly a route it cannot follow.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Waypoint;
+use crate::spatial::{bearing, haversine_distance};
+
+/// Legs shorter than this are treated as vertical and have no heading.
+const MIN_LEG_M: f64 = 1.0;
+/// Slack for floating-point error when comparing against the envelope.
+const TOLERANCE: f64 = 1e-6;
+
+/// Physical limits of a drone.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DronePerformance {
+    pub max_climb_rate_mps: f64,
+    /// Maximum descent rate; the climb rate when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_descent_rate_mps: Option<f64>,
+    /// Maximum airspeed.
+    pub max_speed_mps: f64,
+    /// Sustained rate of turn at cruise speed.
+    pub turn_rate_deg_s: f64,
+    /// Strongest wind the drone can hold position and track a route in.
+    pub max_wind_mps: f64,
+}
+
+impl Default for DronePerformance {
+    /// A typical small multirotor.
+    fn default() -> Self {
+        Self {
+            max_climb_rate_mps: 3.0,
+            max_descent_rate_mps: None,
+            max_speed_mps: 20.0,
+            turn_rate_deg_s: 45.0,
+            max_wind_mps: 12.0,
+        }
+    }
+}
+
+impl DronePerformance {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, value) in [
+            ("max_climb_rate_mps", self.max_climb_rate_mps),
+            ("max_speed_mps", self.max_speed_mps),
+            ("turn_rate_deg_s", self.turn_rate_deg_s),
+            ("max_wind_mps", self.max_wind_mps),
+        ]
+        .into_iter()
+        .chain(
+            self.max_descent_rate_mps
+                .map(|rate| ("max_descent_rate_mps", rate)),
+        ) {
+            if !value.is_finite() || value <= 0.0 {
+                errors.push(format!("{} must be a positive number", name));
+            }
+        }
+        errors
+    }
+
+    /// Fastest sustained descent.
+    pub fn descent_rate_mps(&self) -> f64 {
+        self.max_descent_rate_mps.unwrap_or(self.max_climb_rate_mps)
+    }
+
+    /// Radius of a coordinated turn at `speed_mps`.
+    pub fn turn_radius_m(&self, speed_mps: f64) -> f64 {
+        speed_mps.max(0.0) / self.turn_rate_deg_s.to_radians()
+    }
+
+    /// Whether the drone can hold position in `wind_mps`.
+    pub fn can_hold(&self, wind_mps: f64) -> bool {
+        wind_mps <= self.max_wind_mps + TOLERANCE
+    }
+
+    /// Reasons the drone cannot fly `waypoints` in `wind_mps`; empty when it can.
+    ///
+    /// Legs without a speed are flown at `default_speed_mps`. Each leg must stay within the
+    /// maximum speed and climb rate, and each turn must fit inside its adjacent legs at the
+    /// drone's turn radius.
+    pub fn route_violations(
+        &self,
+        waypoints: &[Waypoint],
+        default_speed_mps: f64,
+        wind_mps: f64,
+    ) -> Vec<String> {
+        let mut violations = Vec::new();
+        if !self.can_hold(wind_mps) {
+            violations.push(format!(
+                "wind {:.1} m/s exceeds the {:.1} m/s tolerance",
+                wind_mps, self.max_wind_mps
+            ));
+        }
+
+        // (length, bearing, speed) per leg
+        let mut legs = Vec::with_capacity(waypoints.len().saturating_sub(1));
+        for (idx, pair) in waypoints.windows(2).enumerate() {
+            let (from, to) = (&pair[0], &pair[1]);
+            let speed_mps = to
+                .speed_mps
+                .or(from.speed_mps)
+                .unwrap_or(default_speed_mps)
+                .max(0.0);
+            if speed_mps > self.max_speed_mps + TOLERANCE {
+                violations.push(format!(
+                    "leg {} speed {:.1} m/s exceeds the {:.1} m/s maximum",
+                    idx + 1,
+                    speed_mps,
+                    self.max_speed_mps
+                ));
+            }
+            let length_m = haversine_distance(from.lat, from.lon, to.lat, to.lon);
+            let climb_m = to.altitude_m - from.altitude_m;
+            if length_m >= MIN_LEG_M && speed_mps > 0.0 && climb_m > 0.0 {
+                let climb_rate_mps = climb_m * speed_mps / length_m;
+                if climb_rate_mps > self.max_climb_rate_mps + TOLERANCE {
+                    violations.push(format!(
+                        "leg {} needs a {:.1} m/s climb (maximum {:.1} m/s)",
+                        idx + 1,
+                        climb_rate_mps,
+                        self.max_climb_rate_mps
+                    ));
+                }
+            }
+            legs.push((
+                length_m,
+                bearing(from.lat, from.lon, to.lat, to.lon).to_degrees(),
+                speed_mps,
+            ));
+        }
+
+        for (idx, pair) in legs.windows(2).enumerate() {
+            let ((in_m, in_deg, _), (out_m, out_deg, speed_mps)) = (pair[0], pair[1]);
+            if in_m < MIN_LEG_M || out_m < MIN_LEG_M {
+                continue;
+            }
+            let turn_deg = (out_deg - in_deg + 180.0).rem_euclid(360.0) - 180.0;
+            let turn_deg = turn_deg.abs();
+            // Distance before the waypoint where the turn must start (and after it where it ends).
+            let lead_m = self.turn_radius_m(speed_mps) * (turn_deg.to_radians() / 2.0).tan();
+            if turn_deg >= 180.0 - TOLERANCE || lead_m > in_m.min(out_m) + TOLERANCE {
+                violations.push(format!(
+                    "{:.0}° turn at waypoint {} is too tight at {:.1} m/s",
+                    turn_deg,
+                    idx + 2,
+                    speed_mps
+                ));
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::offset_position;
+
+    fn waypoint(north_m: f64, east_m: f64, altitude_m: f64, speed_mps: f64) -> Waypoint {
+        let (lat, lon) = offset_position(37.0, -122.0, north_m, east_m);
+        Waypoint {
+            lat,
+            lon,
+            altitude_m,
+            speed_mps: Some(speed_mps),
+        }
+    }
+
+    #[test]
+    fn checks_routes_against_the_envelope() {
+        let performance = DronePerformance::default();
+        assert!(performance.validate().is_empty());
+
+        // 300 m north then a right angle east at 10 m/s, climbing 30 m on the first leg (1 m/s).
+        let route = vec![
+            waypoint(0.0, 0.0, 50.0, 10.0),
+            waypoint(300.0, 0.0, 80.0, 10.0),
+            waypoint(300.0, 300.0, 80.0, 10.0),
+        ];
+        assert!(performance.route_violations(&route, 10.0, 5.0).is_empty());
+        assert_eq!(performance.route_violations(&route, 10.0, 15.0).len(), 1);
+
+        // A slow climber cannot make the first leg.
+        let slow_climber = DronePerformance {
+            max_climb_rate_mps: 0.5,
+            ..performance
+        };
+        let violations = slow_climber.route_violations(&route, 10.0, 0.0);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].contains("climb"));
+
+        // Too fast, and at 30 m/s a 10°/s turn (r ≈ 172 m) still fits a 300 m leg but not 100 m.
+        let sluggish = DronePerformance {
+            turn_rate_deg_s: 10.0,
+            max_speed_mps: 25.0,
+            ..performance
+        };
+        let fast = vec![
+            waypoint(0.0, 0.0, 50.0, 30.0),
+            waypoint(300.0, 0.0, 50.0, 30.0),
+            waypoint(300.0, 100.0, 50.0, 30.0),
+        ];
+        let violations = sluggish.route_violations(&fast, 30.0, 0.0);
+        assert_eq!(violations.len(), 3, "{:?}", violations);
+        assert!(violations.iter().any(|v| v.contains("turn at waypoint 2")));
+
+        // Reversing course is never flyable as a waypoint turn.
+        let reverse = vec![
+            waypoint(0.0, 0.0, 50.0, 5.0),
+            waypoint(300.0, 0.0, 50.0, 5.0),
+            waypoint(0.0, 0.0, 50.0, 5.0),
+        ];
+        assert_eq!(performance.route_violations(&reverse, 5.0, 0.0).len(), 1);
+
+        let invalid = DronePerformance {
+            turn_rate_deg_s: 0.0,
+            max_wind_mps: f64::NAN,
+            ..performance
+        };
+        assert_eq!(invalid.validate().len(), 2);
+    }
+}
@@ -0,0 +1,256 @@
+//! Planned routes as moving obstacles.
+//!
+//! When a fleet is planned in one go, every route already planned is timed along its waypoints
+//! and the grids of the routes planned after it charge for passing within separation of it at
+//! the time both drones would be there, so the batch comes out mutually deconflicted instead of
+//! as independent routes that cross each other.
+
+use serde::{Deserialize, Serialize};
+
+use crate::route_engine::{RouteEngineWaypoint, RouteGrid};
+use crate::spatial::haversine_distance;
+
+/// Planned routes are sampled at this interval.
+const SAMPLE_INTERVAL_S: f64 = 1.0;
+const METERS_PER_DEG_LAT: f64 = 111_320.0;
+
+/// How far apart planned routes must stay, and what the planner charges for coming closer.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrafficSeparation {
+    pub horizontal_m: f64,
+    pub vertical_m: f64,
+    /// Timing slack either side of when a drone is expected at a point.
+    pub time_buffer_s: f64,
+    /// Extra cost per second flown within separation of another route.
+    pub penalty: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrafficSample {
+    time_s: f64,
+    lat: f64,
+    lon: f64,
+    altitude_m: f64,
+}
+
+/// A planned route, timed from its departure.
+#[derive(Debug, Clone)]
+pub struct PlannedTraffic {
+    samples: Vec<TrafficSample>,
+}
+
+impl PlannedTraffic {
+    /// Time `waypoints` from `departure_s` (Unix seconds), flying each leg's 3D length at
+    /// `ground_speed_mps`.
+    pub fn new(waypoints: &[RouteEngineWaypoint], departure_s: f64, ground_speed_mps: f64) -> Self {
+        let speed = ground_speed_mps.max(1.0);
+        let mut samples = Vec::new();
+        let mut time_s = departure_s;
+        for (idx, wp) in waypoints.iter().enumerate() {
+            let Some(prev) = idx.checked_sub(1).map(|prev| &waypoints[prev]) else {
+                samples.push(TrafficSample {
+                    time_s,
+                    lat: wp.lat,
+                    lon: wp.lon,
+                    altitude_m: wp.altitude_m,
+                });
+                continue;
+            };
+            let length_m = haversine_distance(prev.lat, prev.lon, wp.lat, wp.lon)
+                .hypot(wp.altitude_m - prev.altitude_m);
+            let steps = (length_m / (speed * SAMPLE_INTERVAL_S)).ceil().max(1.0) as usize;
+            for step in 1..=steps {
+                let fraction = step as f64 / steps as f64;
+                samples.push(TrafficSample {
+                    time_s: time_s + fraction * length_m / speed,
+                    lat: prev.lat + fraction * (wp.lat - prev.lat),
+                    lon: prev.lon + fraction * (wp.lon - prev.lon),
+                    altitude_m: prev.altitude_m + fraction * (wp.altitude_m - prev.altitude_m),
+                });
+            }
+            time_s += length_m / speed;
+        }
+        Self { samples }
+    }
+
+    /// Whether the route passes within separation of a point between `from_s` and `to_s`.
+    fn is_near(
+        &self,
+        lat: f64,
+        lon: f64,
+        altitude_m: f64,
+        (from_s, to_s): (f64, f64),
+        separation: &TrafficSeparation,
+    ) -> bool {
+        let buffer_s = separation.time_buffer_s.max(SAMPLE_INTERVAL_S / 2.0);
+        let start = self
+            .samples
+            .partition_point(|sample| sample.time_s < from_s - buffer_s);
+        self.samples[start..]
+            .iter()
+            .take_while(|sample| sample.time_s <= to_s + buffer_s)
+            .any(|sample| {
+                (sample.altitude_m - altitude_m).abs() < separation.vertical_m
+                    && haversine_distance(sample.lat, sample.lon, lat, lon)
+                        < separation.horizontal_m
+            })
+    }
+
+    /// `[min_lat, min_lon, max_lat, max_lon]` of the route grown by `margin_m`.
+    fn bounds(&self, margin_m: f64) -> Option<[f64; 4]> {
+        let first = self.samples.first()?;
+        let mut bounds = [first.lat, first.lon, first.lat, first.lon];
+        for sample in &self.samples {
+            bounds[0] = bounds[0].min(sample.lat);
+            bounds[1] = bounds[1].min(sample.lon);
+            bounds[2] = bounds[2].max(sample.lat);
+            bounds[3] = bounds[3].max(sample.lon);
+        }
+        let lat_margin = margin_m / METERS_PER_DEG_LAT;
+        let max_abs_lat = bounds[0].abs().max(bounds[2].abs()).min(89.0);
+        let lon_margin = lat_margin / max_abs_lat.to_radians().cos();
+        Some([
+            bounds[0] - lat_margin,
+            bounds[1] - lon_margin,
+            bounds[2] + lat_margin,
+            bounds[3] + lon_margin,
+        ])
+    }
+
+    /// First time (Unix seconds) this route comes within separation of `other`.
+    pub fn conflict_time(
+        &self,
+        other: &PlannedTraffic,
+        separation: &TrafficSeparation,
+    ) -> Option<f64> {
+        self.samples
+            .iter()
+            .find(|sample| {
+                other.is_near(
+                    sample.lat,
+                    sample.lon,
+                    sample.altitude_m,
+                    (sample.time_s, sample.time_s),
+                    separation,
+                )
+            })
+            .map(|sample| sample.time_s)
+    }
+}
+
+/// Mark grid points the drone would reach within separation of a planned route.
+///
+/// Arrival times are estimated as for [`crate::weather::apply_weather`], except that a point
+/// off the centre lane may be reached later, by up to the time taken to fly its lateral
+/// offset. Points are compared at their terrain-following floor altitude, so altitude layers
+/// above it do not buy separation.
+pub fn apply_traffic(
+    grid: &mut RouteGrid,
+    traffic: &[PlannedTraffic],
+    separation: &TrafficSeparation,
+    departure_s: f64,
+    ground_speed_mps: f64,
+) {
+    let speed = ground_speed_mps.max(1.0);
+    let step_times = grid.step_times_s(departure_s, speed);
+    let Some(center) = grid.lanes.get(grid.lanes.len() / 2).cloned() else {
+        return;
+    };
+    let nearby: Vec<(&PlannedTraffic, [f64; 4])> = traffic
+        .iter()
+        .filter_map(|route| Some((route, route.bounds(separation.horizontal_m)?)))
+        .collect();
+
+    for lane in &mut grid.lanes {
+        for ((point, &time_s), center) in lane.iter_mut().zip(&step_times).zip(&center) {
+            let lag_s = haversine_distance(center.lat, center.lon, point.lat, point.lon) / speed;
+            let conflicts = nearby
+                .iter()
+                .any(|(route, [min_lat, min_lon, max_lat, max_lon])| {
+                    (*min_lat..=*max_lat).contains(&point.lat)
+                        && (*min_lon..=*max_lon).contains(&point.lon)
+                        && route.is_near(
+                            point.lat,
+                            point.lon,
+                            point.altitude_m,
+                            (time_s, time_s + lag_s),
+                            separation,
+                        )
+                });
+            point.traffic_cost = if conflicts { separation.penalty } else { 0.0 };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Waypoint;
+    use crate::route_engine::{
+        build_lane_offsets, generate_grid_samples, optimize_flight_path, RouteEngineConfig,
+    };
+    use crate::spatial::offset_position;
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    const SEPARATION: TrafficSeparation = TrafficSeparation {
+        horizontal_m: 50.0,
+        vertical_m: 30.0,
+        time_buffer_s: 10.0,
+        penalty: 500.0,
+    };
+
+    fn waypoint(north_m: f64, east_m: f64) -> Waypoint {
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m, east_m);
+        Waypoint {
+            lat,
+            lon,
+            altitude_m: 60.0,
+            speed_mps: None,
+        }
+    }
+
+    fn plan(waypoints: &[Waypoint], traffic: &[PlannedTraffic]) -> PlannedTraffic {
+        let config = RouteEngineConfig::default();
+        let mut grid =
+            generate_grid_samples(waypoints, 25.0, &build_lane_offsets(600.0, 50.0), 0.0).unwrap();
+        apply_traffic(
+            &mut grid,
+            traffic,
+            &SEPARATION,
+            0.0,
+            config.ground_speed_mps(),
+        );
+        let result = optimize_flight_path(waypoints, &grid, &[], &config);
+        assert!(result.success, "{:?}", result.errors);
+        PlannedTraffic::new(&result.waypoints, 0.0, config.ground_speed_mps())
+    }
+
+    #[test]
+    fn later_route_in_a_batch_steers_clear_of_an_earlier_one() {
+        // Two 2 km routes crossing at their midpoints, departing together.
+        let northbound = [waypoint(-1_000.0, 0.0), waypoint(1_000.0, 0.0)];
+        let eastbound = [waypoint(0.0, -1_000.0), waypoint(0.0, 1_000.0)];
+
+        let first = plan(&northbound, &[]);
+        let independent = plan(&eastbound, &[]);
+        assert!(independent.conflict_time(&first, &SEPARATION).is_some());
+
+        let deconflicted = plan(&eastbound, std::slice::from_ref(&first));
+        assert_eq!(deconflicted.conflict_time(&first, &SEPARATION), None);
+        assert_eq!(first.conflict_time(&deconflicted, &SEPARATION), None);
+
+        // The same crossing an hour later is no conflict.
+        let later = PlannedTraffic::new(
+            &northbound.clone().map(|wp| RouteEngineWaypoint {
+                lat: wp.lat,
+                lon: wp.lon,
+                altitude_m: wp.altitude_m,
+                phase: None,
+            }),
+            3_600.0,
+            15.0,
+        );
+        assert_eq!(independent.conflict_time(&later, &SEPARATION), None);
+    }
+}
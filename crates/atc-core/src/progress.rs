@@ -0,0 +1,201 @@
+//! Progress of a flight along its planned trajectory.
+//!
+//! A live position is matched to the closest leg of the plan's timed trajectory: how far along
+//! the route that point is gives the share of the flight completed, the time the plan expected
+//! the drone there gives the schedule drift, and the distance off the leg is the cross-track
+//! error.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conflict::DronePosition;
+use crate::models::FlightPlan;
+use crate::spatial::{build_timed_path, haversine_distance, lat_to_meters, lon_to_meters};
+
+/// Legs whose distances to the drone differ by less than this are told apart by schedule, so
+/// an out-and-back route matches the leg the drone is due on.
+const LEG_TIE_M: f64 = 1.0;
+
+/// Where a drone is relative to its plan.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FlightProgress {
+    /// Share of the planned route flown, 0–100.
+    pub percent_complete: f64,
+    /// Zero-based index of the trajectory leg the drone is on.
+    pub current_segment: usize,
+    pub segment_count: usize,
+    pub distance_flown_m: f64,
+    pub distance_remaining_m: f64,
+    /// Horizontal distance from the planned leg.
+    pub cross_track_error_m: f64,
+    /// Height above (positive) or below the planned altitude at that point.
+    pub vertical_error_m: f64,
+    /// When the plan expected the drone at its current point (Unix seconds).
+    pub planned_time_s: f64,
+    /// Seconds behind schedule; negative when ahead.
+    pub eta_drift_s: f64,
+    /// Planned end of the trajectory (Unix seconds).
+    pub planned_arrival_s: f64,
+    /// Planned arrival shifted by the drift.
+    pub estimated_arrival_s: f64,
+}
+
+/// Progress of `position` along `plan`, or `None` when the plan has no timed trajectory.
+pub fn flight_progress(plan: &FlightPlan, position: &DronePosition) -> Option<FlightProgress> {
+    let path = build_timed_path(plan)?;
+    let legs: Vec<f64> = path
+        .windows(2)
+        .map(|leg| haversine_distance(leg[0].lat, leg[0].lon, leg[1].lat, leg[1].lon))
+        .collect();
+    let total_m: f64 = legs.iter().sum();
+
+    // (leg index, fraction along it, distance off it, planned time there)
+    let mut best: Option<(usize, f64, f64, f64)> = None;
+    for (index, leg) in path.windows(2).enumerate() {
+        let (start, end) = (&leg[0], &leg[1]);
+        let px = lon_to_meters(position.lon - start.lon, start.lat);
+        let py = lat_to_meters(position.lat - start.lat, start.lat);
+        let sx = lon_to_meters(end.lon - start.lon, start.lat);
+        let sy = lat_to_meters(end.lat - start.lat, start.lat);
+        let length_sq = sx * sx + sy * sy;
+        let fraction = if length_sq > f64::EPSILON {
+            ((px * sx + py * sy) / length_sq).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let off_m = (px - fraction * sx).hypot(py - fraction * sy);
+        let time_s = start.time_s + fraction * (end.time_s - start.time_s);
+
+        let better = match best {
+            None => true,
+            Some((_, _, best_off_m, best_time_s)) => {
+                if (off_m - best_off_m).abs() < LEG_TIE_M {
+                    (time_s - position.timestamp).abs() < (best_time_s - position.timestamp).abs()
+                } else {
+                    off_m < best_off_m
+                }
+            }
+        };
+        if better {
+            best = Some((index, fraction, off_m, time_s));
+        }
+    }
+    let (index, fraction, cross_track_error_m, planned_time_s) = best?;
+
+    let (start, end) = (&path[index], &path[index + 1]);
+    let planned_altitude_m = start.altitude_m + fraction * (end.altitude_m - start.altitude_m);
+    let distance_flown_m = legs[..index].iter().sum::<f64>() + fraction * legs[index];
+    let percent_complete = if total_m > 0.0 {
+        (distance_flown_m / total_m * 100.0).clamp(0.0, 100.0)
+    } else {
+        100.0
+    };
+    let eta_drift_s = position.timestamp - planned_time_s;
+    let planned_arrival_s = path.last()?.time_s;
+
+    Some(FlightProgress {
+        percent_complete,
+        current_segment: index,
+        segment_count: legs.len(),
+        distance_flown_m,
+        distance_remaining_m: (total_m - distance_flown_m).max(0.0),
+        cross_track_error_m,
+        vertical_error_m: position.altitude_m - planned_altitude_m,
+        planned_time_s,
+        eta_drift_s,
+        planned_arrival_s,
+        estimated_arrival_s: planned_arrival_s + eta_drift_s,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FlightStatus, TrajectoryPoint};
+    use crate::spatial::offset_position;
+    use chrono::{TimeZone, Utc};
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    fn point(north_m: f64, east_m: f64, time_offset_s: f64) -> TrajectoryPoint {
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m, east_m);
+        TrajectoryPoint {
+            lat,
+            lon,
+            altitude_m: 50.0,
+            time_offset_s: Some(time_offset_s),
+        }
+    }
+
+    fn plan(trajectory: Vec<TrajectoryPoint>) -> FlightPlan {
+        FlightPlan {
+            flight_id: "F1".to_string(),
+            drone_id: "D1".to_string(),
+            owner_id: None,
+            waypoints: Vec::new(),
+            trajectory_log: Some(trajectory),
+            metadata: None,
+            status: FlightStatus::Active,
+            departure_time: Utc.timestamp_opt(1_000, 0).unwrap(),
+            arrival_time: None,
+            created_at: Utc.timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    fn at(north_m: f64, east_m: f64, altitude_m: f64, timestamp: f64) -> DronePosition {
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m, east_m);
+        let mut position = DronePosition::new("D1", lat, lon, altitude_m);
+        position.timestamp = timestamp;
+        position
+    }
+
+    #[test]
+    fn measures_progress_drift_and_cross_track_error() {
+        // 400 m north in 40 s, then 400 m east in 40 s.
+        let plan = plan(vec![
+            point(0.0, 0.0, 0.0),
+            point(400.0, 0.0, 40.0),
+            point(400.0, 400.0, 80.0),
+        ]);
+
+        // Halfway up the first leg, 30 m east of it and 10 s late.
+        let progress = flight_progress(&plan, &at(200.0, 30.0, 55.0, 1_030.0)).unwrap();
+        assert_eq!(progress.current_segment, 0);
+        assert_eq!(progress.segment_count, 2);
+        assert!((progress.percent_complete - 25.0).abs() < 0.5);
+        assert!((progress.cross_track_error_m - 30.0).abs() < 0.5);
+        assert!((progress.vertical_error_m - 5.0).abs() < 1e-6);
+        assert!((progress.planned_time_s - 1_020.0).abs() < 0.1);
+        assert!((progress.eta_drift_s - 10.0).abs() < 0.1);
+        assert!((progress.estimated_arrival_s - 1_090.0).abs() < 0.1);
+
+        // Early on the second leg.
+        let progress = flight_progress(&plan, &at(400.0, 300.0, 50.0, 1_060.0)).unwrap();
+        assert_eq!(progress.current_segment, 1);
+        assert!((progress.percent_complete - 87.5).abs() < 0.5);
+        assert!((progress.eta_drift_s + 10.0).abs() < 0.1);
+        assert!((progress.distance_remaining_m - 100.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn out_and_back_matches_the_leg_due_now() {
+        let plan = plan(vec![
+            point(0.0, 0.0, 0.0),
+            point(400.0, 0.0, 40.0),
+            point(0.0, 0.0, 80.0),
+        ]);
+        let outbound = flight_progress(&plan, &at(100.0, 0.0, 50.0, 1_010.0)).unwrap();
+        assert_eq!(outbound.current_segment, 0);
+        let inbound = flight_progress(&plan, &at(100.0, 0.0, 50.0, 1_070.0)).unwrap();
+        assert_eq!(inbound.current_segment, 1);
+        assert!((inbound.percent_complete - 87.5).abs() < 0.5);
+        assert!(inbound.eta_drift_s.abs() < 0.5);
+    }
+
+    #[test]
+    fn untimed_plan_has_no_progress() {
+        let mut untimed = point(400.0, 0.0, 40.0);
+        untimed.time_offset_s = None;
+        let plan = plan(vec![point(0.0, 0.0, 0.0), untimed]);
+        assert!(flight_progress(&plan, &at(0.0, 0.0, 50.0, 1_000.0)).is_none());
+    }
+}
@@ -0,0 +1,262 @@
+//! Mission rehearsal.
+//!
+//! Flies a timed flight plan on a virtual clock: the plan is sampled into the telemetry the
+//! drone would report, which can then be replayed through a sandboxed detector alongside other
+//! traffic and checked against geofences and the drone's tether.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conflict::DronePosition;
+use crate::home::DroneHome;
+use crate::messages::{codes, Message};
+use crate::models::{FlightPlan, Geofence, GeofenceType};
+use crate::spatial::{bearing, build_timed_path, haversine_distance, interpolate_position};
+
+/// Plan-time spacing used to derive heading and speed from the trajectory.
+const VELOCITY_PROBE_S: f64 = 0.5;
+
+/// Start and end of a plan's timed trajectory (Unix seconds).
+pub fn plan_time_window(plan: &FlightPlan) -> Option<(f64, f64)> {
+    let path = build_timed_path(plan)?;
+    Some((path.first()?.time_s, path.last()?.time_s))
+}
+
+/// Telemetry a drone flying `plan` would report every `step_s` seconds between `from_s` and
+/// `to_s`, clipped to the plan. The final sample is reported at rest, as after landing.
+pub fn virtual_telemetry(
+    plan: &FlightPlan,
+    from_s: f64,
+    to_s: f64,
+    step_s: f64,
+) -> Vec<DronePosition> {
+    let Some(path) = build_timed_path(plan) else {
+        return Vec::new();
+    };
+    let (Some(first), Some(last)) = (path.first(), path.last()) else {
+        return Vec::new();
+    };
+    let start = from_s.max(first.time_s);
+    let end = to_s.min(last.time_s);
+    if start > end || !step_s.is_finite() || step_s <= 0.0 {
+        return Vec::new();
+    }
+
+    let sample = |t: f64| {
+        let mut index = 0usize;
+        interpolate_position(&path, t, &mut index)
+    };
+    let steps = ((end - start) / step_s).ceil() as usize;
+    let mut samples = Vec::with_capacity(steps + 1);
+    for step in 0..=steps {
+        let t = (start + step as f64 * step_s).min(end);
+        let Some(here) = sample(t) else {
+            continue;
+        };
+        let mut position =
+            DronePosition::new(plan.drone_id.clone(), here.lat, here.lon, here.altitude_m);
+        position.timestamp = t;
+        if t < last.time_s {
+            let probe_t = (t + VELOCITY_PROBE_S).min(last.time_s);
+            if let Some(next) = sample(probe_t) {
+                let dt = probe_t - t;
+                let distance_m = haversine_distance(here.lat, here.lon, next.lat, next.lon);
+                if distance_m > 0.0 {
+                    position.heading_deg = bearing(here.lat, here.lon, next.lat, next.lon)
+                        .to_degrees()
+                        .rem_euclid(360.0);
+                }
+                position.speed_mps = distance_m / dt;
+                position.velocity_z = (next.altitude_m - here.altitude_m) / dt;
+            }
+        }
+        samples.push(position);
+    }
+    samples
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RehearsalIssueKind {
+    /// Inside an active geofence.
+    Geofence,
+    /// Beyond the drone's home tether.
+    Tether,
+}
+
+/// A stretch of the rehearsed flight that would not conform.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RehearsalIssue {
+    #[serde(rename = "type")]
+    pub kind: RehearsalIssueKind,
+    /// Geofence involved, for geofence issues.
+    pub related_id: Option<String>,
+    pub message: String,
+    /// Stable code and values behind `message`.
+    pub message_code: Message,
+    /// First and last sample (Unix seconds) with the issue.
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+/// Conformance issues along a virtual track, one per continuous stretch.
+///
+/// Advisory geofences are skipped, as in route validation.
+pub fn conformance_issues(
+    track: &[DronePosition],
+    geofences: &[Geofence],
+    home: Option<&DroneHome>,
+) -> Vec<RehearsalIssue> {
+    let mut issues: Vec<RehearsalIssue> = Vec::new();
+    // Index into `issues` of each issue still open at the previous sample.
+    let mut open: Vec<usize> = Vec::new();
+
+    for position in track {
+        let mut current: Vec<(RehearsalIssueKind, Option<String>, Message)> = geofences
+            .iter()
+            .filter(|geofence| {
+                geofence.active
+                    && geofence.geofence_type != GeofenceType::Advisory
+                    && geofence.contains_point(position.lat, position.lon, position.altitude_m)
+            })
+            .map(|geofence| {
+                (
+                    RehearsalIssueKind::Geofence,
+                    Some(geofence.id.clone()),
+                    Message::new(codes::REHEARSAL_GEOFENCE)
+                        .with("geofence_name", geofence.name.as_str()),
+                )
+            })
+            .collect();
+        if let Some(radius_m) = home
+            .filter(|home| home.tether_excess_m(position.lat, position.lon).is_some())
+            .and_then(|home| home.tether_radius_m)
+        {
+            current.push((
+                RehearsalIssueKind::Tether,
+                None,
+                Message::new(codes::REHEARSAL_TETHER).with("tether_radius_m", radius_m),
+            ));
+        }
+
+        let mut still_open = Vec::new();
+        for (kind, related_id, message) in current {
+            let existing = open.iter().copied().find(|&index| {
+                issues[index].kind == kind && issues[index].related_id == related_id
+            });
+            match existing {
+                Some(index) => {
+                    issues[index].end_time = position.timestamp;
+                    still_open.push(index);
+                }
+                None => {
+                    issues.push(RehearsalIssue {
+                        kind,
+                        related_id,
+                        message: message.to_string(),
+                        message_code: message,
+                        start_time: position.timestamp,
+                        end_time: position.timestamp,
+                    });
+                    still_open.push(issues.len() - 1);
+                }
+            }
+        }
+        open = still_open;
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{FlightStatus, TrajectoryPoint};
+    use crate::spatial::offset_position;
+    use chrono::{TimeZone, Utc};
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    #[test]
+    fn samples_plan_and_reports_issue_stretches() {
+        // 400 m north at 10 m/s, climbing 20 m.
+        let (end_lat, end_lon) = offset_position(ORIGIN.0, ORIGIN.1, 400.0, 0.0);
+        let plan = FlightPlan {
+            flight_id: "F1".to_string(),
+            drone_id: "D1".to_string(),
+            owner_id: None,
+            waypoints: Vec::new(),
+            trajectory_log: Some(vec![
+                TrajectoryPoint {
+                    lat: ORIGIN.0,
+                    lon: ORIGIN.1,
+                    altitude_m: 50.0,
+                    time_offset_s: Some(0.0),
+                },
+                TrajectoryPoint {
+                    lat: end_lat,
+                    lon: end_lon,
+                    altitude_m: 70.0,
+                    time_offset_s: Some(40.0),
+                },
+            ]),
+            metadata: None,
+            status: FlightStatus::Approved,
+            departure_time: Utc.timestamp_opt(1_000, 0).unwrap(),
+            arrival_time: None,
+            created_at: Utc.timestamp_opt(0, 0).unwrap(),
+        };
+        assert_eq!(plan_time_window(&plan), Some((1_000.0, 1_040.0)));
+
+        let track = virtual_telemetry(&plan, 0.0, f64::INFINITY, 5.0);
+        assert_eq!(track.len(), 9);
+        assert!((track[0].speed_mps - 10.0).abs() < 0.1);
+        assert!(track[0].heading_deg < 1.0 || track[0].heading_deg > 359.0);
+        assert!((track[0].velocity_z - 0.5).abs() < 0.01);
+        assert_eq!(track[8].timestamp, 1_040.0);
+        assert_eq!(track[8].speed_mps, 0.0);
+
+        // A fence across 160–260 m north, and a 320 m tether.
+        let corner = |north_m: f64, east_m: f64| {
+            let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m, east_m);
+            [lat, lon]
+        };
+        let fence = Geofence {
+            id: "tfr".to_string(),
+            name: "TFR".to_string(),
+            geofence_type: GeofenceType::TemporaryRestriction,
+            polygon: vec![
+                corner(160.0, -50.0),
+                corner(160.0, 50.0),
+                corner(260.0, 50.0),
+                corner(260.0, -50.0),
+                corner(160.0, -50.0),
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 120.0,
+            active: true,
+            created_at: Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
+        };
+        let home = DroneHome {
+            lat: ORIGIN.0,
+            lon: ORIGIN.1,
+            altitude_m: 0.0,
+            tether_radius_m: Some(320.0),
+            auto_rth: false,
+        };
+        let issues = conformance_issues(&track, &[fence], Some(&home));
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert_eq!(issues[0].kind, RehearsalIssueKind::Geofence);
+        assert_eq!(issues[0].related_id.as_deref(), Some("tfr"));
+        assert_eq!(
+            (issues[0].start_time, issues[0].end_time),
+            (1_020.0, 1_025.0)
+        );
+        assert_eq!(issues[1].kind, RehearsalIssueKind::Tether);
+        assert_eq!(
+            (issues[1].start_time, issues[1].end_time),
+            (1_035.0, 1_040.0)
+        );
+    }
+}
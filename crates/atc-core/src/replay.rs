@@ -0,0 +1,622 @@
+//! Deterministic conflict detector replay.
+//!
+//! Feeds a recorded (or synthetic) stream of time-stamped `DronePosition` updates through a
+//! `ConflictDetector` on a fixed clock and returns the full conflict timeline, so detector
+//! tuning can be unit-tested against recorded incidents. Replay time comes only from the
+//! update timestamps, never from the wall clock.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::conflict::{Conflict, ConflictDetector, ConflictSeverity, DronePosition};
+use crate::spatial::{offset_by_bearing, offset_position};
+
+/// Default evaluation step, matching the server's conflict loop.
+const DEFAULT_STEP_S: f64 = 1.0;
+/// Default age after which a silent drone is dropped, matching the default drone timeout.
+const DEFAULT_MAX_TRACK_AGE_S: f64 = 10.0;
+
+/// Conflicts reported at one evaluation instant, sorted by drone pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub time: f64,
+    pub conflicts: Vec<Conflict>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConflictEventKind {
+    Started {
+        severity: ConflictSeverity,
+    },
+    SeverityChanged {
+        from: ConflictSeverity,
+        to: ConflictSeverity,
+    },
+    Ended,
+}
+
+/// A change in the conflict state of a drone pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictEvent {
+    pub time: f64,
+    pub drone1_id: String,
+    pub drone2_id: String,
+    #[serde(flatten)]
+    pub kind: ConflictEventKind,
+}
+
+/// One continuous conflict between a drone pair.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictEpisode {
+    pub drone1_id: String,
+    pub drone2_id: String,
+    pub start_time: f64,
+    /// First instant the pair was clear again; `None` if still in conflict when the replay ended.
+    pub end_time: Option<f64>,
+    pub peak_severity: ConflictSeverity,
+    /// First instant each severity was reported.
+    pub first_warning_time: Option<f64>,
+    pub first_critical_time: Option<f64>,
+    /// Smallest actual separation observed during the episode (meters).
+    pub min_distance_m: f64,
+    /// Smallest predicted closest-approach distance during the episode (meters).
+    pub min_predicted_distance_m: f64,
+}
+
+/// Output of a replay run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayTimeline {
+    pub frames: Vec<ReplayFrame>,
+    pub events: Vec<ConflictEvent>,
+    /// Episodes in start order.
+    pub episodes: Vec<ConflictEpisode>,
+}
+
+impl ReplayTimeline {
+    /// Episodes involving both drones, in either order.
+    pub fn episodes_between(&self, drone_a: &str, drone_b: &str) -> Vec<&ConflictEpisode> {
+        let (first, second) = sorted_pair(drone_a, drone_b);
+        self.episodes
+            .iter()
+            .filter(|episode| episode.drone1_id == first && episode.drone2_id == second)
+            .collect()
+    }
+
+    /// First instant the pair was reported at `severity` or above.
+    pub fn first_alert(
+        &self,
+        drone_a: &str,
+        drone_b: &str,
+        severity: ConflictSeverity,
+    ) -> Option<f64> {
+        let (first, second) = sorted_pair(drone_a, drone_b);
+        self.frames.iter().find_map(|frame| {
+            frame
+                .conflicts
+                .iter()
+                .any(|conflict| {
+                    conflict.drone1_id == first
+                        && conflict.drone2_id == second
+                        && conflict.severity.rank() >= severity.rank()
+                })
+                .then_some(frame.time)
+        })
+    }
+}
+
+/// Replays position updates through a conflict detector on a fixed clock.
+///
+/// At each step every drone's latest update is dead-reckoned to the step time along its
+/// reported heading, speed and vertical rate, so sparse recordings replay smoothly. Drones
+/// whose latest update is older than the maximum track age are dropped, as the server does
+/// with lost drones.
+pub struct ConflictReplay {
+    detector: ConflictDetector,
+    step_s: f64,
+    max_track_age_s: f64,
+}
+
+impl ConflictReplay {
+    pub fn new(detector: ConflictDetector) -> Self {
+        Self {
+            detector,
+            step_s: DEFAULT_STEP_S,
+            max_track_age_s: DEFAULT_MAX_TRACK_AGE_S,
+        }
+    }
+
+    /// Set the evaluation step (seconds).
+    pub fn with_step(mut self, step_s: f64) -> Self {
+        self.step_s = step_s;
+        self
+    }
+
+    /// Set how long a drone is tracked without updates (seconds).
+    pub fn with_max_track_age(mut self, max_track_age_s: f64) -> Self {
+        self.max_track_age_s = max_track_age_s;
+        self
+    }
+
+    /// Replay `updates` from the earliest to the latest timestamp.
+    pub fn run(&mut self, updates: impl IntoIterator<Item = DronePosition>) -> ReplayTimeline {
+        let mut updates: Vec<DronePosition> = updates
+            .into_iter()
+            .filter(|update| update.timestamp.is_finite())
+            .collect();
+        updates.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+
+        let mut timeline = ReplayTimeline::default();
+        let (Some(first), Some(last)) = (updates.first(), updates.last()) else {
+            return timeline;
+        };
+        let (start, end) = (first.timestamp, last.timestamp);
+        let step_s = if self.step_s.is_finite() && self.step_s > 0.0 {
+            self.step_s
+        } else {
+            DEFAULT_STEP_S
+        };
+
+        let tracked: Vec<String> = self
+            .detector
+            .get_all_positions()
+            .iter()
+            .map(|position| position.drone_id.clone())
+            .collect();
+        for drone_id in tracked {
+            self.detector.remove_drone(&drone_id);
+        }
+
+        let mut latest: BTreeMap<String, DronePosition> = BTreeMap::new();
+        let mut open: HashMap<(String, String), usize> = HashMap::new();
+        let mut next_update = 0;
+        let steps = ((end - start) / step_s).ceil() as usize;
+
+        for step in 0..=steps {
+            let time = (start + step as f64 * step_s).min(end);
+            while next_update < updates.len() && updates[next_update].timestamp <= time {
+                let update = &updates[next_update];
+                latest.insert(update.drone_id.clone(), update.clone());
+                next_update += 1;
+            }
+
+            latest.retain(|drone_id, position| {
+                let fresh = time - position.timestamp <= self.max_track_age_s;
+                if !fresh {
+                    self.detector.remove_drone(drone_id);
+                }
+                fresh
+            });
+            for position in latest.values() {
+                self.detector.update_position(dead_reckon(position, time));
+            }
+
+            let mut conflicts = self.detector.detect_conflicts();
+            for conflict in &mut conflicts {
+                conflict.timestamp = time;
+                conflict.cpa_time = time + conflict.time_to_closest;
+            }
+            conflicts
+                .sort_by(|a, b| (&a.drone1_id, &a.drone2_id).cmp(&(&b.drone1_id, &b.drone2_id)));
+
+            record_transitions(&mut timeline, &mut open, time, &conflicts);
+            timeline.frames.push(ReplayFrame { time, conflicts });
+        }
+
+        timeline
+    }
+}
+
+fn record_transitions(
+    timeline: &mut ReplayTimeline,
+    open: &mut HashMap<(String, String), usize>,
+    time: f64,
+    conflicts: &[Conflict],
+) {
+    let mut ended: Vec<(String, String)> = open
+        .keys()
+        .filter(|key| {
+            !conflicts
+                .iter()
+                .any(|conflict| conflict.drone1_id == key.0 && conflict.drone2_id == key.1)
+        })
+        .cloned()
+        .collect();
+    ended.sort();
+    for key in ended {
+        if let Some(index) = open.remove(&key) {
+            timeline.episodes[index].end_time = Some(time);
+        }
+        timeline.events.push(ConflictEvent {
+            time,
+            drone1_id: key.0,
+            drone2_id: key.1,
+            kind: ConflictEventKind::Ended,
+        });
+    }
+
+    for conflict in conflicts {
+        let key = (conflict.drone1_id.clone(), conflict.drone2_id.clone());
+        let severity = conflict.severity;
+        let event = match open.get(&key) {
+            Some(&index) => {
+                let episode = &mut timeline.episodes[index];
+                episode.min_distance_m = episode.min_distance_m.min(conflict.distance_m);
+                episode.min_predicted_distance_m = episode
+                    .min_predicted_distance_m
+                    .min(conflict.closest_distance_m);
+                note_severity(episode, severity, time);
+                let previous = timeline
+                    .frames
+                    .last()
+                    .and_then(|frame| {
+                        frame.conflicts.iter().find(|prior| {
+                            prior.drone1_id == conflict.drone1_id
+                                && prior.drone2_id == conflict.drone2_id
+                        })
+                    })
+                    .map(|prior| prior.severity)
+                    .unwrap_or(severity);
+                (previous != severity).then_some(ConflictEventKind::SeverityChanged {
+                    from: previous,
+                    to: severity,
+                })
+            }
+            None => {
+                let mut episode = ConflictEpisode {
+                    drone1_id: key.0.clone(),
+                    drone2_id: key.1.clone(),
+                    start_time: time,
+                    end_time: None,
+                    peak_severity: severity,
+                    first_warning_time: None,
+                    first_critical_time: None,
+                    min_distance_m: conflict.distance_m,
+                    min_predicted_distance_m: conflict.closest_distance_m,
+                };
+                note_severity(&mut episode, severity, time);
+                open.insert(key.clone(), timeline.episodes.len());
+                timeline.episodes.push(episode);
+                Some(ConflictEventKind::Started { severity })
+            }
+        };
+        if let Some(kind) = event {
+            timeline.events.push(ConflictEvent {
+                time,
+                drone1_id: key.0,
+                drone2_id: key.1,
+                kind,
+            });
+        }
+    }
+}
+
+fn note_severity(episode: &mut ConflictEpisode, severity: ConflictSeverity, time: f64) {
+    if severity.rank() > episode.peak_severity.rank() {
+        episode.peak_severity = severity;
+    }
+    if severity.rank() >= ConflictSeverity::Warning.rank() {
+        episode.first_warning_time.get_or_insert(time);
+    }
+    if severity == ConflictSeverity::Critical {
+        episode.first_critical_time.get_or_insert(time);
+    }
+}
+
+fn sorted_pair<'a>(a: &'a str, b: &'a str) -> (&'a str, &'a str) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Extrapolate a position report to `time` along its reported velocity.
+fn dead_reckon(position: &DronePosition, time: f64) -> DronePosition {
+    let elapsed_s = time - position.timestamp;
+    let mut projected = position.clone();
+    if elapsed_s > 0.0 {
+        if position.speed_mps > 0.0 {
+            let (lat, lon) = offset_by_bearing(
+                position.lat,
+                position.lon,
+                position.speed_mps * elapsed_s,
+                position.heading_deg.to_radians(),
+            );
+            projected.lat = lat;
+            projected.lon = lon;
+        }
+        projected.altitude_m += position.velocity_z * elapsed_s;
+    }
+    projected.timestamp = time;
+    projected
+}
+
+/// One aircraft in a synthetic encounter, described by its state at the encounter's CPA time.
+#[derive(Debug, Clone)]
+struct EncounterAircraft {
+    drone_id: String,
+    /// Offset from the encounter center at CPA time (meters).
+    east_m: f64,
+    north_m: f64,
+    up_m: f64,
+    heading_deg: f64,
+    speed_mps: f64,
+    velocity_z: f64,
+}
+
+/// Builds position streams for synthetic encounter geometries.
+///
+/// Aircraft are placed by where they are at the closest point of approach, relative to the
+/// encounter center, and flown in straight lines before and after it. The resulting stream
+/// starts at `start_time` and runs for `duration_s`.
+///
+/// ```
+/// use atc_core::replay::{ConflictReplay, EncounterBuilder};
+/// use atc_core::ConflictDetector;
+///
+/// let updates = EncounterBuilder::new(33.68, -117.82, 60.0)
+///     .duration(60.0)
+///     .cpa_at(30.0)
+///     .head_on("A", "B", 15.0, 0.0)
+///     .build();
+/// let timeline = ConflictReplay::new(ConflictDetector::default()).run(updates);
+/// assert_eq!(timeline.episodes_between("A", "B").len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EncounterBuilder {
+    center_lat: f64,
+    center_lon: f64,
+    altitude_m: f64,
+    start_time: f64,
+    duration_s: f64,
+    cpa_time_s: f64,
+    sample_interval_s: f64,
+    aircraft: Vec<EncounterAircraft>,
+}
+
+impl EncounterBuilder {
+    /// Encounter centered on a point, by default 60 s long with CPA halfway and 1 s samples.
+    pub fn new(center_lat: f64, center_lon: f64, altitude_m: f64) -> Self {
+        Self {
+            center_lat,
+            center_lon,
+            altitude_m,
+            start_time: 0.0,
+            duration_s: 60.0,
+            cpa_time_s: 30.0,
+            sample_interval_s: 1.0,
+            aircraft: Vec::new(),
+        }
+    }
+
+    /// Timestamp of the first sample.
+    pub fn start_time(mut self, start_time: f64) -> Self {
+        self.start_time = start_time;
+        self
+    }
+
+    pub fn duration(mut self, duration_s: f64) -> Self {
+        self.duration_s = duration_s;
+        self
+    }
+
+    /// Seconds after the start at which the aircraft reach their CPA positions.
+    pub fn cpa_at(mut self, cpa_time_s: f64) -> Self {
+        self.cpa_time_s = cpa_time_s;
+        self
+    }
+
+    pub fn sample_interval(mut self, sample_interval_s: f64) -> Self {
+        self.sample_interval_s = sample_interval_s;
+        self
+    }
+
+    /// Add an aircraft by its offset from the center at CPA time and its velocity.
+    #[allow(clippy::too_many_arguments)]
+    pub fn aircraft(
+        mut self,
+        drone_id: impl Into<String>,
+        east_m: f64,
+        north_m: f64,
+        up_m: f64,
+        heading_deg: f64,
+        speed_mps: f64,
+        velocity_z: f64,
+    ) -> Self {
+        self.aircraft.push(EncounterAircraft {
+            drone_id: drone_id.into(),
+            east_m,
+            north_m,
+            up_m,
+            heading_deg,
+            speed_mps,
+            velocity_z,
+        });
+        self
+    }
+
+    /// Two aircraft on reciprocal north/south tracks passing `miss_distance_m` apart laterally.
+    pub fn head_on(
+        self,
+        drone_a: impl Into<String>,
+        drone_b: impl Into<String>,
+        speed_mps: f64,
+        miss_distance_m: f64,
+    ) -> Self {
+        let half = miss_distance_m / 2.0;
+        self.aircraft(drone_a, -half, 0.0, 0.0, 0.0, speed_mps, 0.0)
+            .aircraft(drone_b, half, 0.0, 0.0, 180.0, speed_mps, 0.0)
+    }
+
+    /// `drone_a` northbound through the center, `drone_b` crossing at `angle_deg` to it and
+    /// passing `miss_distance_m` east of the center.
+    pub fn crossing(
+        self,
+        drone_a: impl Into<String>,
+        drone_b: impl Into<String>,
+        angle_deg: f64,
+        speed_mps: f64,
+        miss_distance_m: f64,
+    ) -> Self {
+        self.aircraft(drone_a, 0.0, 0.0, 0.0, 0.0, speed_mps, 0.0)
+            .aircraft(
+                drone_b,
+                miss_distance_m,
+                0.0,
+                0.0,
+                angle_deg,
+                speed_mps,
+                0.0,
+            )
+    }
+
+    /// `drone_a` overtaking the slower `drone_b` on the same northbound track, with a lateral
+    /// miss distance.
+    pub fn overtaking(
+        self,
+        drone_a: impl Into<String>,
+        drone_b: impl Into<String>,
+        fast_speed_mps: f64,
+        slow_speed_mps: f64,
+        miss_distance_m: f64,
+    ) -> Self {
+        let half = miss_distance_m / 2.0;
+        self.aircraft(drone_a, -half, 0.0, 0.0, 0.0, fast_speed_mps, 0.0)
+            .aircraft(drone_b, half, 0.0, 0.0, 0.0, slow_speed_mps, 0.0)
+    }
+
+    /// `drone_a` climbing through the level `drone_b`'s altitude at CPA, co-located laterally.
+    pub fn climb_through(
+        self,
+        drone_a: impl Into<String>,
+        drone_b: impl Into<String>,
+        speed_mps: f64,
+        climb_rate_mps: f64,
+    ) -> Self {
+        self.aircraft(drone_a, 0.0, 0.0, 0.0, 90.0, speed_mps, climb_rate_mps)
+            .aircraft(drone_b, 0.0, 0.0, 0.0, 270.0, speed_mps, 0.0)
+    }
+
+    /// Position updates for every aircraft, sorted by time then drone ID.
+    pub fn build(&self) -> Vec<DronePosition> {
+        let interval_s = if self.sample_interval_s.is_finite() && self.sample_interval_s > 0.0 {
+            self.sample_interval_s
+        } else {
+            1.0
+        };
+        let samples = (self.duration_s.max(0.0) / interval_s).floor() as usize;
+        let mut aircraft = self.aircraft.clone();
+        aircraft.sort_by(|a, b| a.drone_id.cmp(&b.drone_id));
+
+        let mut updates = Vec::with_capacity((samples + 1) * aircraft.len());
+        for sample in 0..=samples {
+            let elapsed_s = sample as f64 * interval_s;
+            let from_cpa_s = elapsed_s - self.cpa_time_s;
+            for craft in &aircraft {
+                let heading_rad = craft.heading_deg.to_radians();
+                let east_m = craft.east_m + craft.speed_mps * heading_rad.sin() * from_cpa_s;
+                let north_m = craft.north_m + craft.speed_mps * heading_rad.cos() * from_cpa_s;
+                let (lat, lon) = offset_position(self.center_lat, self.center_lon, north_m, east_m);
+                let altitude_m = self.altitude_m + craft.up_m + craft.velocity_z * from_cpa_s;
+                let mut position = DronePosition::new(craft.drone_id.clone(), lat, lon, altitude_m)
+                    .with_velocity(craft.heading_deg, craft.speed_mps, craft.velocity_z);
+                position.timestamp = self.start_time + elapsed_s;
+                updates.push(position);
+            }
+        }
+        updates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_on_encounter_produces_escalating_episode() {
+        let updates = EncounterBuilder::new(33.68, -117.82, 60.0)
+            .start_time(1_700_000_000.0)
+            .duration(80.0)
+            .cpa_at(40.0)
+            .head_on("B", "A", 15.0, 0.0)
+            .build();
+
+        let timeline = ConflictReplay::new(ConflictDetector::default()).run(updates.clone());
+        assert_eq!(timeline.frames.len(), 81);
+
+        let episodes = timeline.episodes_between("A", "B");
+        assert_eq!(episodes.len(), 1);
+        let episode = episodes[0];
+        assert_eq!(episode.drone1_id, "A");
+        assert_eq!(episode.peak_severity, ConflictSeverity::Critical);
+        let warning = episode.first_warning_time.unwrap();
+        let critical = episode.first_critical_time.unwrap();
+        assert!(warning < critical);
+        assert!(critical <= 1_700_000_040.0);
+        assert!(episode.min_distance_m < 1.0);
+        assert!(episode.end_time.is_some_and(|end| end > 1_700_000_040.0));
+        assert_eq!(
+            timeline.first_alert("B", "A", ConflictSeverity::Critical),
+            Some(critical)
+        );
+        assert!(matches!(
+            timeline.events.first().map(|event| event.kind),
+            Some(ConflictEventKind::Started { .. })
+        ));
+        assert_eq!(
+            timeline.events.last().map(|event| event.kind),
+            Some(ConflictEventKind::Ended)
+        );
+
+        // Same input, same timeline.
+        let again = ConflictReplay::new(ConflictDetector::default()).run(updates);
+        assert_eq!(again.events, timeline.events);
+        assert_eq!(again.episodes, timeline.episodes);
+    }
+
+    #[test]
+    fn wide_crossing_and_vertical_separation_stay_clear() {
+        let updates = EncounterBuilder::new(33.68, -117.82, 60.0)
+            .crossing("A", "B", 90.0, 10.0, 500.0)
+            .aircraft("C", 0.0, 0.0, 200.0, 180.0, 10.0, 0.0)
+            .build();
+        let timeline = ConflictReplay::new(ConflictDetector::default()).run(updates);
+        assert!(timeline.episodes.is_empty());
+        assert!(timeline.events.is_empty());
+    }
+
+    #[test]
+    fn sparse_updates_are_dead_reckoned_and_stale_tracks_dropped() {
+        // Reports every 5 s; the replay still evaluates every second.
+        let updates = EncounterBuilder::new(33.68, -117.82, 60.0)
+            .duration(60.0)
+            .sample_interval(5.0)
+            .head_on("A", "B", 10.0, 0.0)
+            .build();
+        let sparse = ConflictReplay::new(ConflictDetector::default()).run(updates.clone());
+        assert_eq!(sparse.frames.len(), 61);
+        let dense = ConflictReplay::new(ConflictDetector::default()).run(
+            EncounterBuilder::new(33.68, -117.82, 60.0)
+                .duration(60.0)
+                .head_on("A", "B", 10.0, 0.0)
+                .build(),
+        );
+        assert_eq!(
+            sparse.first_alert("A", "B", ConflictSeverity::Critical),
+            dense.first_alert("A", "B", ConflictSeverity::Critical)
+        );
+
+        // B stops reporting after 20 s and is dropped before the CPA.
+        let truncated: Vec<DronePosition> = updates
+            .into_iter()
+            .filter(|update| update.drone_id == "A" || update.timestamp <= 20.0)
+            .collect();
+        let timeline = ConflictReplay::new(ConflictDetector::default())
+            .with_max_track_age(5.0)
+            .run(truncated);
+        let episodes = timeline.episodes_between("A", "B");
+        assert_eq!(episodes.len(), 1);
+        assert_eq!(episodes[0].end_time, Some(26.0));
+    }
+}
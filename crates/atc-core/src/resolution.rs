@@ -0,0 +1,394 @@
+//! Conflict resolution advisories.
+//!
+//! Given a conflict and the current state of both drones, evaluate a small set of maneuvers for
+//! the drone that gives way (climb, descend, turn left/right, speed up, slow down), predict the
+//! separation each one leaves against the intruder over the lookahead, and rank them by cost.
+//! The intruder is assumed to hold its current velocity. When the give-way drone's performance
+//! envelope is known, climbs and descents use its climb and descent rates, turns are flown at its
+//! turn rate and speed increases beyond its maximum speed are not offered.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conflict::{Conflict, ConflictSeverity, DronePosition};
+use crate::performance::DronePerformance;
+use crate::rules::SafetyRules;
+use crate::spatial::{lat_to_meters, lon_to_meters};
+
+/// Vertical rate assumed while climbing or descending to a new altitude without an envelope.
+const VERTICAL_RATE_MPS: f64 = 3.0;
+/// Altitude offsets tried, as multiples of the vertical clearance being restored.
+const ALTITUDE_STEPS: [f64; 2] = [1.2, 2.4];
+/// Heading changes tried for turns.
+const TURN_STEPS_DEG: [f64; 3] = [30.0, 60.0, 90.0];
+/// Speed changes tried, as fractions of the current speed.
+const SPEED_STEPS: [f64; 2] = [0.25, 0.5];
+/// Sampling step for predicted separation.
+const SAMPLE_STEP_S: f64 = 0.5;
+/// Cost added to options that do not restore separation, so they always rank last.
+const UNRESOLVED_PENALTY: f64 = 100.0;
+
+/// Maneuver applied to the give-way drone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Maneuver {
+    Climb,
+    Descend,
+    TurnRight,
+    TurnLeft,
+    SpeedUp,
+    SlowDown,
+}
+
+impl Maneuver {
+    /// Evaluation order; equal-cost options keep it, so right turns win ties as in head-on rules.
+    pub const ALL: [Maneuver; 6] = [
+        Maneuver::Climb,
+        Maneuver::Descend,
+        Maneuver::TurnRight,
+        Maneuver::TurnLeft,
+        Maneuver::SpeedUp,
+        Maneuver::SlowDown,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Maneuver::Climb => "climb",
+            Maneuver::Descend => "descend",
+            Maneuver::TurnLeft => "turn left",
+            Maneuver::TurnRight => "turn right",
+            Maneuver::SpeedUp => "speed up",
+            Maneuver::SlowDown => "slow down",
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Maneuver::Climb | Maneuver::Descend)
+    }
+
+    /// Relative cost of one unit of this maneuver (one step of its ladder).
+    fn unit_cost(self) -> f64 {
+        match self {
+            // Descending is cheapest; climbing costs energy; turns leave the planned track.
+            Maneuver::Descend => 1.0,
+            Maneuver::Climb => 1.2,
+            Maneuver::SlowDown => 1.4,
+            Maneuver::SpeedUp => 1.6,
+            Maneuver::TurnLeft | Maneuver::TurnRight => 1.8,
+        }
+    }
+}
+
+/// One candidate maneuver with its predicted outcome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolutionOption {
+    pub maneuver: Maneuver,
+    pub target_heading_deg: f64,
+    pub target_speed_mps: f64,
+    pub target_altitude_m: f64,
+    /// Horizontal separation at the worst point of the lookahead after the maneuver.
+    pub predicted_horizontal_m: f64,
+    /// Vertical separation at that point.
+    pub predicted_vertical_m: f64,
+    /// Seconds from now until that point.
+    pub predicted_time_s: f64,
+    /// Whether horizontal or vertical clearance holds throughout the lookahead. A pair already
+    /// inside it is never resolved; its options rank by how close they come.
+    pub resolves: bool,
+    /// Lower is better; unresolved options carry a large penalty.
+    pub cost: f64,
+}
+
+/// Ranked maneuvers for `own` (the give-way drone) against `intruder`, best first.
+///
+/// A critical conflict is resolved once the separation minima hold; a warning once the pair
+/// stays outside the warning band.
+/// Each maneuver type is tried at increasing magnitude and reported at the smallest magnitude
+/// that restores separation, or at the largest one tried if none does. Climbs and descents that
+/// would leave the rule altitude limits are skipped. Without a `performance` envelope turns are
+/// treated as instantaneous.
+pub fn resolution_options(
+    conflict: &Conflict,
+    own: &DronePosition,
+    intruder: &DronePosition,
+    rules: &SafetyRules,
+    performance: Option<&DronePerformance>,
+) -> Vec<ResolutionOption> {
+    // Clear the band the conflict was raised for: the minima for a critical conflict, the
+    // warning band for a warning.
+    let (scale_horizontal, scale_vertical) = if conflict.severity == ConflictSeverity::Critical {
+        (1.0, 1.0)
+    } else {
+        let (horizontal, vertical) = rules.warning_multipliers();
+        (horizontal.max(1.0), vertical.max(1.0))
+    };
+    let clearance = (
+        (rules.min_horizontal_separation_m * scale_horizontal).max(1e-6),
+        (rules.min_vertical_separation_m * scale_vertical).max(1e-6),
+    );
+    let horizon_s = rules
+        .lookahead_seconds
+        .max(conflict.time_to_closest)
+        .max(SAMPLE_STEP_S);
+
+    let mut options: Vec<ResolutionOption> = Maneuver::ALL
+        .iter()
+        .filter_map(|&maneuver| {
+            let mut best = None;
+            for step in 0..ladder_len(maneuver) {
+                let Some(option) = evaluate(
+                    maneuver,
+                    step,
+                    own,
+                    intruder,
+                    rules,
+                    performance,
+                    clearance,
+                    horizon_s,
+                ) else {
+                    break;
+                };
+                let resolves = option.resolves;
+                best = Some(option);
+                if resolves {
+                    break;
+                }
+            }
+            best
+        })
+        .collect();
+
+    options.sort_by(|a, b| a.cost.total_cmp(&b.cost));
+    options
+}
+
+fn ladder_len(maneuver: Maneuver) -> usize {
+    match maneuver {
+        Maneuver::Climb | Maneuver::Descend => ALTITUDE_STEPS.len(),
+        Maneuver::TurnLeft | Maneuver::TurnRight => TURN_STEPS_DEG.len(),
+        Maneuver::SpeedUp | Maneuver::SlowDown => SPEED_STEPS.len(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn evaluate(
+    maneuver: Maneuver,
+    step: usize,
+    own: &DronePosition,
+    intruder: &DronePosition,
+    rules: &SafetyRules,
+    performance: Option<&DronePerformance>,
+    clearance: (f64, f64),
+    horizon_s: f64,
+) -> Option<ResolutionOption> {
+    let mut heading_deg = own.heading_deg;
+    let mut speed_mps = own.speed_mps;
+    let mut altitude_m = own.altitude_m;
+    match maneuver {
+        Maneuver::Climb => {
+            altitude_m += clearance.1 * ALTITUDE_STEPS[step];
+            if altitude_m > rules.max_altitude_m {
+                return None;
+            }
+        }
+        Maneuver::Descend => {
+            altitude_m -= clearance.1 * ALTITUDE_STEPS[step];
+            if altitude_m < rules.min_altitude_m {
+                return None;
+            }
+        }
+        Maneuver::TurnLeft => heading_deg = (heading_deg - TURN_STEPS_DEG[step]).rem_euclid(360.0),
+        Maneuver::TurnRight => heading_deg = (heading_deg + TURN_STEPS_DEG[step]).rem_euclid(360.0),
+        Maneuver::SpeedUp => {
+            speed_mps *= 1.0 + SPEED_STEPS[step];
+            if performance.is_some_and(|perf| speed_mps > perf.max_speed_mps) {
+                return None;
+            }
+        }
+        Maneuver::SlowDown => speed_mps *= 1.0 - SPEED_STEPS[step],
+    }
+    if !maneuver.is_vertical() && own.speed_mps <= 0.0 {
+        // Turns and speed changes do nothing for a hovering drone.
+        return None;
+    }
+
+    let ref_lat = (own.lat + intruder.lat) / 2.0;
+    let ref_lon = (own.lon + intruder.lon) / 2.0;
+    let mut own_xy = local_xy(own, ref_lat, ref_lon);
+    let intruder_xy = local_xy(intruder, ref_lat, ref_lon);
+    let intruder_vel = velocity_xy(intruder.heading_deg, intruder.speed_mps);
+    let vertical_rate_mps = match (maneuver, performance) {
+        (_, None) => VERTICAL_RATE_MPS,
+        (Maneuver::Descend, Some(perf)) => perf.descent_rate_mps(),
+        (_, Some(perf)) => perf.max_climb_rate_mps,
+    };
+    let turn_rate_deg_s = performance.map_or(f64::INFINITY, |perf| perf.turn_rate_deg_s);
+    // Heading change still to be flown.
+    let mut turn_remaining_deg = (heading_deg - own.heading_deg + 180.0).rem_euclid(360.0) - 180.0;
+    let mut own_heading_deg = own.heading_deg;
+
+    let mut worst = (f64::INFINITY, 0.0, 0.0, 0.0);
+    let mut t = 0.0;
+    loop {
+        let dx = (intruder_xy.0 + intruder_vel.0 * t) - own_xy.0;
+        let dy = (intruder_xy.1 + intruder_vel.1 * t) - own_xy.1;
+        let own_alt = if maneuver.is_vertical() {
+            let change =
+                (altitude_m - own.altitude_m).clamp(-vertical_rate_mps * t, vertical_rate_mps * t);
+            own.altitude_m + change
+        } else {
+            own.altitude_m + own.velocity_z * t
+        };
+        let horizontal_m = (dx * dx + dy * dy).sqrt();
+        let vertical_m = ((intruder.altitude_m + intruder.velocity_z * t) - own_alt).abs();
+        // Separation is held when either minimum holds; the larger ratio is the margin.
+        let margin = (horizontal_m / clearance.0).max(vertical_m / clearance.1);
+        if margin < worst.0 {
+            worst = (margin, horizontal_m, vertical_m, t);
+        }
+        if t >= horizon_s {
+            break;
+        }
+        let next_t = (t + SAMPLE_STEP_S).min(horizon_s);
+        let dt = next_t - t;
+        let max_turn_deg = turn_rate_deg_s * dt;
+        let turn_deg = turn_remaining_deg.clamp(-max_turn_deg, max_turn_deg);
+        own_heading_deg += turn_deg;
+        turn_remaining_deg -= turn_deg;
+        let own_vel = velocity_xy(own_heading_deg, speed_mps);
+        own_xy = (own_xy.0 + own_vel.0 * dt, own_xy.1 + own_vel.1 * dt);
+        t = next_t;
+    }
+
+    let (margin, predicted_horizontal_m, predicted_vertical_m, predicted_time_s) = worst;
+    let resolves = margin >= 1.0;
+    let mut cost = maneuver.unit_cost() * (step + 1) as f64;
+    if !resolves {
+        cost += UNRESOLVED_PENALTY * (1.0 - margin).max(0.0) + UNRESOLVED_PENALTY;
+    }
+
+    Some(ResolutionOption {
+        maneuver,
+        target_heading_deg: heading_deg,
+        target_speed_mps: speed_mps,
+        target_altitude_m: altitude_m,
+        predicted_horizontal_m,
+        predicted_vertical_m,
+        predicted_time_s,
+        resolves,
+        cost,
+    })
+}
+
+fn local_xy(drone: &DronePosition, ref_lat: f64, ref_lon: f64) -> (f64, f64) {
+    (
+        lon_to_meters(drone.lon - ref_lon, ref_lat),
+        lat_to_meters(drone.lat - ref_lat, ref_lat),
+    )
+}
+
+/// Local ENU velocity: x = east, y = north; heading is 0=north, 90=east.
+fn velocity_xy(heading_deg: f64, speed_mps: f64) -> (f64, f64) {
+    let heading_rad = heading_deg.to_radians();
+    (speed_mps * heading_rad.sin(), speed_mps * heading_rad.cos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conflict::ConflictDetector;
+    use crate::spatial::{meters_to_lat, meters_to_lon};
+
+    fn conflict_for(own: &DronePosition, intruder: &DronePosition) -> Conflict {
+        let mut detector = ConflictDetector::new(90.0, 50.0, 30.0, 2.0);
+        detector.update_position(own.clone());
+        detector.update_position(intruder.clone());
+        detector
+            .detect_conflicts()
+            .into_iter()
+            .next()
+            .expect("conflict")
+    }
+
+    #[test]
+    fn ranks_resolving_maneuvers_by_cost() {
+        let rules = SafetyRules::default();
+        // Crossing traffic from the east, 60s out at the same altitude.
+        let own = DronePosition::new("A", 0.0, 0.0, 60.0).with_velocity(0.0, 5.0, 0.0);
+        let intruder = DronePosition::new(
+            "B",
+            meters_to_lat(300.0, 0.0),
+            meters_to_lon(300.0, 0.0),
+            60.0,
+        )
+        .with_velocity(270.0, 5.0, 0.0);
+        let conflict = conflict_for(&own, &intruder);
+
+        let options = resolution_options(&conflict, &own, &intruder, &rules, None);
+        assert_eq!(options.len(), Maneuver::ALL.len());
+        assert!(options.windows(2).all(|w| w[0].cost <= w[1].cost));
+
+        let best = &options[0];
+        assert!(best.resolves);
+        assert_eq!(best.maneuver, Maneuver::Descend);
+        assert!((best.target_altitude_m - 24.0).abs() < 1e-6);
+        assert!(best.predicted_vertical_m >= rules.min_vertical_separation_m);
+
+        // Every resolving option ranks ahead of every unresolved one.
+        let first_unresolved = options.iter().position(|o| !o.resolves);
+        if let Some(idx) = first_unresolved {
+            assert!(options[idx..].iter().all(|o| !o.resolves));
+        }
+    }
+
+    #[test]
+    fn respects_the_performance_envelope() {
+        let rules = SafetyRules::default();
+        let own = DronePosition::new("A", 0.0, 0.0, 60.0).with_velocity(0.0, 12.0, 0.0);
+        let intruder = DronePosition::new("B", meters_to_lat(600.0, 0.0), 0.0, 60.0)
+            .with_velocity(180.0, 12.0, 0.0);
+        let conflict = conflict_for(&own, &intruder);
+        let option = |options: &[ResolutionOption], maneuver| {
+            options
+                .iter()
+                .find(|o| o.maneuver == maneuver)
+                .cloned()
+                .unwrap()
+        };
+
+        let unconstrained = resolution_options(&conflict, &own, &intruder, &rules, None);
+        let nimble = DronePerformance {
+            max_speed_mps: 14.0,
+            ..Default::default()
+        };
+        let constrained = resolution_options(&conflict, &own, &intruder, &rules, Some(&nimble));
+        // 12 m/s sped up by a quarter exceeds 14 m/s.
+        assert!(constrained.iter().all(|o| o.maneuver != Maneuver::SpeedUp));
+        assert!(unconstrained
+            .iter()
+            .any(|o| o.maneuver == Maneuver::SpeedUp));
+
+        // A slow-turning airframe gains less separation from the same turn.
+        let sluggish = DronePerformance {
+            turn_rate_deg_s: 3.0,
+            ..Default::default()
+        };
+        let slow = resolution_options(&conflict, &own, &intruder, &rules, Some(&sluggish));
+        let fast_turn = option(&constrained, Maneuver::TurnRight);
+        let slow_turn = option(&slow, Maneuver::TurnRight);
+        assert_eq!(slow_turn.target_heading_deg, fast_turn.target_heading_deg);
+        assert!(slow_turn.predicted_horizontal_m < fast_turn.predicted_horizontal_m - 10.0);
+    }
+
+    #[test]
+    fn skips_vertical_maneuvers_outside_altitude_limits() {
+        let rules = SafetyRules::default();
+        let own = DronePosition::new("A", 0.0, 0.0, 115.0).with_velocity(0.0, 10.0, 0.0);
+        let intruder = DronePosition::new("B", meters_to_lat(200.0, 0.0), 0.0, 115.0)
+            .with_velocity(180.0, 10.0, 0.0);
+        let conflict = conflict_for(&own, &intruder);
+
+        let options = resolution_options(&conflict, &own, &intruder, &rules, None);
+        assert!(options.iter().all(|o| o.maneuver != Maneuver::Climb));
+        assert!(options.iter().any(|o| o.maneuver == Maneuver::Descend));
+    }
+}
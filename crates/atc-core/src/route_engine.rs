@@ -2,13 +2,113 @@
 //!
 //! This module keeps routing logic server-side so ATC is the source of truth.
 
+use crate::ground_risk::GroundRiskExposure;
 use crate::models::{Geofence, GeofenceType, Waypoint};
 use crate::spatial::{
-    bearing, haversine_distance, meters_per_deg_lat, meters_per_deg_lon, offset_by_bearing,
+    bearing, distance_to_segment_m, haversine_distance, meters_per_deg_lat, meters_per_deg_lon,
+    offset_by_bearing, point_in_polygon,
 };
+use crate::wind::ground_speed_in_wind;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::cmp::{Ordering, Reverse};
 use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Turns gentler than this are flown as they are.
+const MIN_FILLET_TURN_RAD: f64 = 0.02;
+/// Angle swept between consecutive points of a rounded corner.
+const FILLET_ARC_STEP_RAD: f64 = 0.175;
+/// Tightest arc tried before a corner is left sharp.
+const MIN_FILLET_RADIUS_M: f64 = 1.0;
+/// Spacing of the clearance checks along an arc.
+const FILLET_CHECK_STEP_M: f64 = 2.0;
+
+/// How the route engine searches the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteSearch {
+    /// A* between neighbouring grid points, then shortcut where the line of sight is clear.
+    #[default]
+    Grid,
+    /// Theta*: a point may link straight back to any earlier point it can see, so routes are
+    /// built from direct legs instead of lane-by-lane steps.
+    AnyAngle,
+}
+
+/// Which search produced a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutePlanner {
+    /// A* over the lane grid ([`RouteSearch::Grid`]).
+    #[default]
+    Grid,
+    /// Theta* over the lane grid ([`RouteSearch::AnyAngle`]).
+    AnyAngle,
+    /// The sampling fallback, [`optimize_flight_path_rrt`].
+    RrtStar,
+}
+
+impl From<RouteSearch> for RoutePlanner {
+    fn from(search: RouteSearch) -> Self {
+        match search {
+            RouteSearch::Grid => Self::Grid,
+            RouteSearch::AnyAngle => Self::AnyAngle,
+        }
+    }
+}
+
+/// Chance an RRT* sample is drawn at the goal instead of anywhere in the corridor.
+const RRT_GOAL_BIAS: f64 = 0.1;
+/// Furthest, in grid steps, the RRT* tree grows towards a sample at once.
+const RRT_STEER_STEPS: f64 = 10.0;
+/// Radius, in multiples of the steer distance, within which new RRT* vertices are rewired.
+const RRT_REWIRE_FACTOR: f64 = 2.0;
+
+/// Error a search reports when its [`SearchBudget`] runs out.
+pub const SEARCH_TIMED_OUT: &str = "route search timed out";
+/// Nodes expanded between budget checks.
+const BUDGET_CHECK_INTERVAL: usize = 256;
+
+/// Limits how long a route search may run: until a deadline, or until another thread cancels
+/// it. Clones share cancellation.
+#[derive(Debug, Clone, Default)]
+pub struct SearchBudget {
+    deadline: Option<Instant>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl SearchBudget {
+    /// A budget that runs out after `timeout`; `None` only stops on cancellation.
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+            cancelled: Arc::default(),
+        }
+    }
+
+    /// Stop every search sharing this budget at its next check.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, AtomicOrdering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(AtomicOrdering::Relaxed)
+    }
+
+    pub fn deadline_passed(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Whether searches should stop now.
+    pub fn is_exhausted(&self) -> bool {
+        self.is_cancelled() || self.deadline_passed()
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RouteEngineConfig {
@@ -23,6 +123,34 @@ pub struct RouteEngineConfig {
     pub cost_lane_change: f64,
     pub cost_proximity_penalty: f64,
     pub geofence_sample_step_m: f64,
+    /// Distance (m) routes keep from restricted geofences, horizontally and vertically, on top
+    /// of the obstacle `safety_buffer_m`; 0 lets routes run along a fence's edge.
+    #[serde(default)]
+    pub geofence_standoff_m: f64,
+    /// Spacing of the altitude layers the search may cruise at above the terrain-following
+    /// floor (see [`apply_altitude_layers`]); 0 searches laterally only.
+    #[serde(default)]
+    pub altitude_step_m: f64,
+    /// Steepest climb allowed between grid points, as rise over run; 0 is unlimited. Climbs
+    /// move one layer per grid step, so keep `altitude_step_m` within this times the spacing.
+    #[serde(default)]
+    pub max_climb_gradient: f64,
+    /// Radius (m) cruise corners are rounded to so the route can be flown without stopping to
+    /// turn; 0 keeps sharp corners.
+    #[serde(default)]
+    pub turn_radius_m: f64,
+    #[serde(default)]
+    pub search: RouteSearch,
+    /// Samples [`optimize_flight_path_rrt`] draws per leg between route waypoints.
+    #[serde(default = "default_rrt_samples")]
+    pub rrt_samples: usize,
+    /// Seeds [`optimize_flight_path_rrt`]'s sampler so a plan can be reproduced; a random
+    /// seed is drawn when unset.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// When the search gives up with [`SEARCH_TIMED_OUT`]; unlimited by default.
+    #[serde(skip)]
+    pub budget: SearchBudget,
 }
 
 impl Default for RouteEngineConfig {
@@ -41,16 +169,103 @@ impl Default for RouteEngineConfig {
             cost_lane_change: 50.0,
             cost_proximity_penalty: 100.0,
             geofence_sample_step_m: 25.0,
+            geofence_standoff_m: 0.0,
+            altitude_step_m: 0.0,
+            max_climb_gradient: 0.0,
+            turn_radius_m: 0.0,
+            search: RouteSearch::Grid,
+            rrt_samples: default_rrt_samples(),
+            seed: None,
+            budget: SearchBudget::default(),
         }
     }
 }
 
+fn default_rrt_samples() -> usize {
+    2_000
+}
+
+impl RouteEngineConfig {
+    /// Ground speed the planner assumes into a headwind of `wind_mps`.
+    pub fn ground_speed_mps(&self) -> f64 {
+        (self.cruise_speed_mps - self.wind_mps).max(1.0)
+    }
+
+    /// Seconds to fly `dist` metres between two grid points. When both carry a forecast wind
+    /// the leg is flown at cruise airspeed in their mean wind, otherwise into `wind_mps`.
+    fn leg_time_s(&self, from: &RouteGridPoint, to: &RouteGridPoint, dist: f64) -> f64 {
+        match (from.wind_uv_mps, to.wind_uv_mps) {
+            (Some([u1, v1]), Some([u2, v2])) if dist > 0.0 => {
+                let track = bearing(from.lat, from.lon, to.lat, to.lon);
+                let speed = ground_speed_in_wind(
+                    self.cruise_speed_mps,
+                    (u1 + u2) / 2.0,
+                    (v1 + v2) / 2.0,
+                    track,
+                );
+                dist / speed
+            }
+            _ => dist / self.ground_speed_mps(),
+        }
+    }
+
+    /// Fastest ground speed possible anywhere on `grid`, so time-to-go estimates never
+    /// overestimate.
+    fn max_ground_speed_mps(&self, grid: &RouteGrid) -> f64 {
+        let max_wind = grid
+            .lanes
+            .iter()
+            .flatten()
+            .filter_map(|point| point.wind_uv_mps)
+            .map(|[u, v]| u.hypot(v))
+            .reduce(f64::max);
+        match max_wind {
+            Some(wind) => (self.cruise_speed_mps + wind).max(self.ground_speed_mps()),
+            None => self.ground_speed_mps(),
+        }
+    }
+
+    /// Whether a climb of `climb_m` over `distance_m` is within the gradient limit.
+    fn climb_allowed(&self, climb_m: f64, distance_m: f64) -> bool {
+        self.max_climb_gradient <= 0.0 || climb_m <= self.max_climb_gradient * distance_m + 1e-6
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RouteObstacle {
     pub lat: f64,
     pub lon: f64,
     pub radius_m: f64,
     pub height_m: Option<f64>,
+    /// `[lat, lon]` footprint vertices. When set the obstacle is the footprint grown by
+    /// `radius_m` instead of a circle of `radius_m` around `lat`/`lon`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polygon: Option<Vec<[f64; 2]>>,
+}
+
+impl RouteObstacle {
+    fn footprint(&self) -> Option<&[[f64; 2]]> {
+        self.polygon.as_deref().filter(|polygon| polygon.len() >= 3)
+    }
+
+    /// Horizontal distance (m) from a point to the obstacle's keep-out area; 0 inside it.
+    pub fn distance_m(&self, lat: f64, lon: f64) -> f64 {
+        let radius = self.radius_m.max(0.0);
+        let Some(polygon) = self.footprint() else {
+            return (haversine_distance(lat, lon, self.lat, self.lon) - radius).max(0.0);
+        };
+        if point_in_polygon(lat, lon, polygon) {
+            return 0.0;
+        }
+        let edge = (0..polygon.len())
+            .map(|i| {
+                let [lat1, lon1] = polygon[i];
+                let [lat2, lon2] = polygon[(i + 1) % polygon.len()];
+                distance_to_segment_m(lat, lon, lat1, lon1, lat2, lon2)
+            })
+            .fold(f64::INFINITY, f64::min);
+        (edge - radius).max(0.0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,12 +275,72 @@ pub struct RouteGridPoint {
     pub altitude_m: f64,
     pub terrain_height_m: f64,
     pub obstacle_height_m: f64,
+    /// Extra cost per second flown here from forecast weather; infinite when the weather
+    /// excludes the point (see [`crate::weather::apply_weather`]).
+    #[serde(default)]
+    pub weather_cost: f64,
+    /// Extra cost per second flown here outside C2 link coverage; infinite when coverage is
+    /// required (see [`crate::coverage::apply_coverage`]).
+    #[serde(default)]
+    pub coverage_cost: f64,
+    /// Extra cost per second flown here within separation of another planned route (see
+    /// [`crate::planned_traffic::apply_traffic`]).
+    #[serde(default)]
+    pub traffic_cost: f64,
+    /// Extra cost per second flown here over populated ground (see
+    /// [`crate::ground_risk::apply_ground_risk`]).
+    #[serde(default)]
+    pub ground_risk_cost: f64,
+    /// Forecast `[u, v]` wind (m/s) here; unset falls back to the scalar `wind_mps` (see
+    /// [`crate::wind::apply_wind`]).
+    #[serde(default)]
+    pub wind_uv_mps: Option<[f64; 2]>,
+}
+
+impl RouteGridPoint {
+    /// Extra cost per second flown here; infinite when the point is excluded.
+    pub fn penalty(&self) -> f64 {
+        self.weather_cost + self.coverage_cost + self.traffic_cost + self.ground_risk_cost
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RouteGrid {
     pub lanes: Vec<Vec<RouteGridPoint>>,
     pub waypoint_indices: Vec<usize>,
+    /// Heights above the terrain-following floor the search may cruise at; empty searches the
+    /// floor only.
+    #[serde(default)]
+    pub altitude_layers: Vec<f64>,
+}
+
+impl RouteGrid {
+    /// Time (s) the drone reaches each step when it departs at `departure_s` and progresses
+    /// along the centre lane at `ground_speed_mps`; every lane at a step shares its time.
+    pub fn step_times_s(&self, departure_s: f64, ground_speed_mps: f64) -> Vec<f64> {
+        let Some(center) = self.lanes.get(self.lanes.len() / 2) else {
+            return Vec::new();
+        };
+        let speed = ground_speed_mps.max(1.0);
+        let mut step_times = Vec::with_capacity(center.len());
+        let mut time_s = departure_s;
+        for (idx, point) in center.iter().enumerate() {
+            if idx > 0 {
+                let prev = &center[idx - 1];
+                time_s += haversine_distance(prev.lat, prev.lon, point.lat, point.lon) / speed;
+            }
+            step_times.push(time_s);
+        }
+        step_times
+    }
+
+    fn layers(&self) -> &[f64] {
+        if self.altitude_layers.is_empty() {
+            &[0.0]
+        } else {
+            &self.altitude_layers
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +357,12 @@ pub struct RouteEngineStats {
     pub avg_agl: f64,
     pub max_agl: f64,
     pub max_altitude: f64,
+    /// Closest the route comes to a restricted geofence; `None` without any.
+    #[serde(default)]
+    pub min_geofence_clearance_m: Option<f64>,
+    /// Population overflown; set by the server when a density source is configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ground_risk: Option<GroundRiskExposure>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,12 +373,15 @@ pub struct RouteEngineResult {
     pub nodes_visited: usize,
     pub stats: Option<RouteEngineStats>,
     pub errors: Vec<String>,
+    #[serde(default)]
+    pub planner: RoutePlanner,
 }
 
 #[derive(Debug, Clone)]
 struct Node {
     step: usize,
     lane: usize,
+    layer: usize,
     g_score: f64,
     alt: f64,
 }
@@ -106,6 +390,7 @@ struct Node {
 struct NodeKey {
     step: usize,
     lane: usize,
+    layer: usize,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -135,6 +420,7 @@ impl Ord for FloatOrd {
 struct OpenNode {
     step: usize,
     lane: usize,
+    layer: usize,
     g_score: FloatOrd,
     f_score: FloatOrd,
     alt: f64,
@@ -145,6 +431,7 @@ impl OpenNode {
         NodeKey {
             step: self.step,
             lane: self.lane,
+            layer: self.layer,
         }
     }
 }
@@ -153,6 +440,7 @@ impl PartialEq for OpenNode {
     fn eq(&self, other: &Self) -> bool {
         self.step == other.step
             && self.lane == other.lane
+            && self.layer == other.layer
             && self.g_score == other.g_score
             && self.f_score == other.f_score
             && self.alt.to_bits() == other.alt.to_bits()
@@ -174,6 +462,7 @@ impl Ord for OpenNode {
             .then_with(|| self.g_score.cmp(&other.g_score))
             .then_with(|| self.step.cmp(&other.step))
             .then_with(|| self.lane.cmp(&other.lane))
+            .then_with(|| self.layer.cmp(&other.layer))
             .then_with(|| self.alt.total_cmp(&other.alt))
     }
 }
@@ -189,6 +478,7 @@ impl Node {
         NodeKey {
             step: self.step,
             lane: self.lane,
+            layer: self.layer,
         }
     }
 }
@@ -207,6 +497,21 @@ pub fn build_lane_offsets(radius_m: f64, spacing_m: f64) -> Vec<f64> {
     offsets
 }
 
+/// Layer heights from 0 to `max_height_m` every `step_m`; just the floor when `step_m` is 0.
+pub fn build_altitude_layers(max_height_m: f64, step_m: f64) -> Vec<f64> {
+    if step_m <= 0.0 || max_height_m <= 0.0 {
+        return vec![0.0];
+    }
+    let steps = (max_height_m / step_m).floor() as usize;
+    (0..=steps).map(|i| i as f64 * step_m).collect()
+}
+
+/// Give the grid altitude layers every `config.altitude_step_m` up to the AGL limit, so the
+/// search can resolve obstacles and geofences by climbing as well as by changing lanes.
+pub fn apply_altitude_layers(grid: &mut RouteGrid, config: &RouteEngineConfig) {
+    grid.altitude_layers = build_altitude_layers(config.faa_limit_agl, config.altitude_step_m);
+}
+
 pub fn resolve_grid_spacing(waypoints: &[Waypoint], default_spacing: f64) -> f64 {
     if waypoints.len() < 2 {
         return default_spacing;
@@ -286,6 +591,11 @@ pub fn generate_grid_samples(
                     altitude_m,
                     terrain_height_m: 0.0,
                     obstacle_height_m: 0.0,
+                    weather_cost: 0.0,
+                    coverage_cost: 0.0,
+                    traffic_cost: 0.0,
+                    ground_risk_cost: 0.0,
+                    wind_uv_mps: None,
                 });
             }
         }
@@ -305,6 +615,7 @@ pub fn generate_grid_samples(
     Some(RouteGrid {
         lanes,
         waypoint_indices,
+        altitude_layers: Vec::new(),
     })
 }
 
@@ -329,6 +640,8 @@ where
         y_m: f64,
         radius2_m: f64,
         height_m: f64,
+        /// Index into `footprints` for polygon obstacles.
+        footprint: Option<usize>,
     }
 
     const CELL_SIZE_M: f64 = 100.0;
@@ -348,28 +661,50 @@ where
     let meters_lon = meters_per_deg_lon(mean_lat).max(1.0);
 
     let mut obstacle_index: HashMap<(i32, i32), Vec<IndexedObstacle>> = HashMap::new();
+    let mut footprints: Vec<Vec<(f64, f64)>> = Vec::new();
     for obstacle in obstacles {
         let radius = obstacle.radius_m.max(0.0);
-        if radius <= 0.0 {
+        let footprint: Option<Vec<(f64, f64)>> = obstacle.footprint().map(|polygon| {
+            polygon
+                .iter()
+                .map(|[lat, lon]| (lon * meters_lon, lat * meters_lat))
+                .collect()
+        });
+        if radius <= 0.0 && footprint.is_none() {
             continue;
         }
         let height_m = obstacle.height_m.unwrap_or(0.0).max(0.0);
         let x_m = obstacle.lon * meters_lon;
         let y_m = obstacle.lat * meters_lat;
-        let cell_x = (x_m * inv_cell).floor() as i32;
-        let cell_y = (y_m * inv_cell).floor() as i32;
-        let mut cell_radius = (radius * inv_cell).ceil() as i32;
-        cell_radius = cell_radius.clamp(0, 16);
+        // Index every cell the keep-out area (footprint or centre, grown by the radius) touches.
+        let (min_x, min_y, max_x, max_y) =
+            footprint.as_deref().unwrap_or(&[(x_m, y_m)]).iter().fold(
+                (
+                    f64::INFINITY,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NEG_INFINITY,
+                ),
+                |(min_x, min_y, max_x, max_y), &(x, y)| {
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                },
+            );
+        let reach = radius.min(16.0 * CELL_SIZE_M);
         let entry = IndexedObstacle {
             x_m,
             y_m,
             radius2_m: radius * radius,
             height_m,
+            footprint: footprint.map(|footprint| {
+                footprints.push(footprint);
+                footprints.len() - 1
+            }),
         };
-        for dx in -cell_radius..=cell_radius {
-            for dy in -cell_radius..=cell_radius {
+        let cell = |value: f64| (value * inv_cell).floor() as i32;
+        for cell_x in cell(min_x - reach)..=cell(max_x + reach) {
+            for cell_y in cell(min_y - reach)..=cell(max_y + reach) {
                 obstacle_index
-                    .entry((cell_x + dx, cell_y + dy))
+                    .entry((cell_x, cell_y))
                     .or_default()
                     .push(entry);
             }
@@ -388,9 +723,17 @@ where
             let cell_y = (y_m * inv_cell).floor() as i32;
             if let Some(obstacles) = obstacle_index.get(&(cell_x, cell_y)) {
                 for obstacle in obstacles {
-                    let dx = obstacle.x_m - x_m;
-                    let dy = obstacle.y_m - y_m;
-                    if dx * dx + dy * dy > obstacle.radius2_m {
+                    let hit = match obstacle.footprint {
+                        Some(idx) => {
+                            footprint_contains(&footprints[idx], x_m, y_m, obstacle.radius2_m)
+                        }
+                        None => {
+                            let dx = obstacle.x_m - x_m;
+                            let dy = obstacle.y_m - y_m;
+                            dx * dx + dy * dy <= obstacle.radius2_m
+                        }
+                    };
+                    if !hit {
                         continue;
                     }
                     let obstacle_alt = terrain + obstacle.height_m;
@@ -403,6 +746,31 @@ where
     }
 }
 
+/// Whether `(x, y)` lies inside a projected footprint or within `sqrt(radius2)` of its edges.
+fn footprint_contains(footprint: &[(f64, f64)], x: f64, y: f64, radius2: f64) -> bool {
+    let mut inside = false;
+    let mut j = footprint.len() - 1;
+    for (i, &(xi, yi)) in footprint.iter().enumerate() {
+        let (xj, yj) = footprint[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        let (sx, sy) = (xj - xi, yj - yi);
+        let len2 = sx * sx + sy * sy;
+        let t = if len2 > 0.0 {
+            (((x - xi) * sx + (y - yi) * sy) / len2).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (dx, dy) = (xi + t * sx - x, yi + t * sy - y);
+        if dx * dx + dy * dy <= radius2 {
+            return true;
+        }
+        j = i;
+    }
+    inside
+}
+
 pub fn optimize_flight_path(
     waypoints: &[Waypoint],
     grid: &RouteGrid,
@@ -419,6 +787,7 @@ pub fn optimize_flight_path(
                 nodes_visited: 0,
                 stats: None,
                 errors,
+                planner: config.search.into(),
             };
         }
     };
@@ -546,10 +915,18 @@ pub fn optimize_flight_path(
     }
 
     let ground = grid.lanes[center_lane_idx][0].terrain_height_m;
+    let active_geofences = blocking_geofences(geofences);
+    let final_waypoints = round_corners(final_waypoints, grid, &active_geofences, config);
     let stats = RouteEngineStats {
         avg_agl: result.max_cruise_alt - ground,
         max_agl: result.max_cruise_alt - ground,
         max_altitude: result.max_cruise_alt,
+        min_geofence_clearance_m: min_geofence_clearance(
+            &final_waypoints,
+            &active_geofences,
+            config.geofence_sample_step_m,
+        ),
+        ground_risk: None,
     };
 
     RouteEngineResult {
@@ -559,6 +936,7 @@ pub fn optimize_flight_path(
         nodes_visited: result.nodes_visited,
         stats: Some(stats),
         errors: Vec::new(),
+        planner: config.search.into(),
     }
 }
 
@@ -581,7 +959,8 @@ pub fn optimize_airborne_path(
         .unwrap_or(start_point.altitude_m)
         .max(min_safe_start);
 
-    let result = match compute_path_nodes(waypoints, grid, geofences, config, Some(start_override)) {
+    let result = match compute_path_nodes(waypoints, grid, geofences, config, Some(start_override))
+    {
         Ok(result) => result,
         Err(errors) => {
             return RouteEngineResult {
@@ -591,6 +970,7 @@ pub fn optimize_airborne_path(
                 nodes_visited: 0,
                 stats: None,
                 errors,
+                planner: config.search.into(),
             };
         }
     };
@@ -623,10 +1003,18 @@ pub fn optimize_airborne_path(
     }
     let avg_agl = if count > 0.0 { sum_agl / count } else { 0.0 };
 
+    let active_geofences = blocking_geofences(geofences);
+    let waypoints_out = round_corners(waypoints_out, grid, &active_geofences, config);
     let stats = RouteEngineStats {
         avg_agl,
         max_agl,
         max_altitude,
+        min_geofence_clearance_m: min_geofence_clearance(
+            &waypoints_out,
+            &active_geofences,
+            config.geofence_sample_step_m,
+        ),
+        ground_risk: None,
     };
 
     RouteEngineResult {
@@ -636,16 +1024,501 @@ pub fn optimize_airborne_path(
         nodes_visited: result.nodes_visited,
         stats: Some(stats),
         errors: Vec::new(),
+        planner: config.search.into(),
+    }
+}
+
+/// Plan a ground-start route with RRT* instead of the grid search, for corridors too cluttered
+/// for A* to thread. The tree grows through the same lanes the grid covers but samples any
+/// grid point at any altitude between its safe floor and the AGL ceiling, and joins them with
+/// straight legs of any length and direction; each leg between route waypoints is planned on
+/// its own, stopping on the ground at the waypoint like [`optimize_flight_path`].
+pub fn optimize_flight_path_rrt(
+    waypoints: &[Waypoint],
+    grid: &RouteGrid,
+    geofences: &[Geofence],
+    config: &RouteEngineConfig,
+) -> RouteEngineResult {
+    let failed = |errors: Vec<String>, nodes_visited: usize| RouteEngineResult {
+        success: false,
+        waypoints: Vec::new(),
+        optimized_points: 0,
+        nodes_visited,
+        stats: None,
+        errors,
+        planner: RoutePlanner::RrtStar,
+    };
+    if waypoints.len() < 2 {
+        return failed(vec!["need at least 2 waypoints".to_string()], 0);
+    }
+    if grid.lanes.is_empty() || grid.lanes[0].is_empty() {
+        return failed(vec!["grid is empty".to_string()], 0);
+    }
+
+    let active_geofences = blocking_geofences(geofences);
+    let num_steps = grid.lanes[0].len();
+    let center_lane_idx = grid.lanes.len() / 2;
+    let waypoint_indices = if grid.waypoint_indices.is_empty() {
+        vec![0, num_steps - 1]
+    } else {
+        grid.waypoint_indices.clone()
+    };
+
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rand::rng()),
+    };
+    let mut nodes_visited = 0usize;
+    let mut final_waypoints = Vec::new();
+    let mut cruise_nodes = Vec::new();
+    for (idx, step_idx) in waypoint_indices.iter().copied().enumerate() {
+        let point = &grid.lanes[center_lane_idx][step_idx];
+        let is_first = idx == 0;
+        let is_last = idx + 1 == waypoint_indices.len();
+        final_waypoints.push(RouteEngineWaypoint {
+            lat: point.lat,
+            lon: point.lon,
+            altitude_m: point.terrain_height_m,
+            phase: Some(
+                if is_first {
+                    "GROUND_START"
+                } else if is_last {
+                    "GROUND_END"
+                } else {
+                    "GROUND_WAYPOINT"
+                }
+                .to_string(),
+            ),
+        });
+        if is_last {
+            break;
+        }
+
+        let path = match rrt_star_leg(
+            grid,
+            &active_geofences,
+            config,
+            step_idx,
+            waypoint_indices[idx + 1],
+            &mut rng,
+            &mut nodes_visited,
+        ) {
+            Ok(path) => path,
+            Err(errors) => return failed(errors, nodes_visited),
+        };
+        let last = path.len() - 1;
+        for (node_idx, node) in path.iter().enumerate() {
+            let node_point = &grid.lanes[node.lane][node.step];
+            let phase = if node_idx == 0 {
+                "VERTICAL_ASCENT"
+            } else if node_idx == last {
+                "VERTICAL_DESCENT"
+            } else {
+                "CRUISE"
+            };
+            final_waypoints.push(RouteEngineWaypoint {
+                lat: node_point.lat,
+                lon: node_point.lon,
+                altitude_m: node.alt,
+                phase: Some(phase.to_string()),
+            });
+        }
+        cruise_nodes.extend(path);
+    }
+
+    let mut max_altitude = 0.0_f64;
+    let mut max_agl = 0.0_f64;
+    let mut sum_agl = 0.0_f64;
+    for node in &cruise_nodes {
+        let point = &grid.lanes[node.lane][node.step];
+        max_altitude = max_altitude.max(node.alt);
+        let agl = (node.alt - point.terrain_height_m).max(0.0);
+        max_agl = max_agl.max(agl);
+        sum_agl += agl;
+    }
+    let final_waypoints = round_corners(final_waypoints, grid, &active_geofences, config);
+    let stats = RouteEngineStats {
+        avg_agl: sum_agl / cruise_nodes.len().max(1) as f64,
+        max_agl,
+        max_altitude,
+        min_geofence_clearance_m: min_geofence_clearance(
+            &final_waypoints,
+            &active_geofences,
+            config.geofence_sample_step_m,
+        ),
+        ground_risk: None,
+    };
+    RouteEngineResult {
+        success: true,
+        optimized_points: final_waypoints.len(),
+        waypoints: final_waypoints,
+        nodes_visited,
+        stats: Some(stats),
+        errors: Vec::new(),
+        planner: RoutePlanner::RrtStar,
+    }
+}
+
+/// Altitudes a route may cross a grid point at, or `None` when the point is excluded.
+fn altitude_band(point: &RouteGridPoint, config: &RouteEngineConfig) -> Option<(f64, f64)> {
+    if point.penalty().is_infinite() {
+        return None;
+    }
+    let floor = (point.obstacle_height_m.max(point.terrain_height_m) + config.safety_buffer_m)
+        .max(point.altitude_m);
+    let ceiling = point.terrain_height_m + config.faa_limit_agl;
+    (floor <= ceiling).then_some((floor, ceiling))
+}
+
+struct RrtVertex {
+    node: Node,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// RRT* from the centre lane at `start_step` to the centre lane at `end_step`, sampling only the
+/// grid between them. Vertices carry their cost from the start in `g_score`.
+fn rrt_star_leg(
+    grid: &RouteGrid,
+    geofences: &[&Geofence],
+    config: &RouteEngineConfig,
+    start_step: usize,
+    end_step: usize,
+    rng: &mut impl Rng,
+    nodes_visited: &mut usize,
+) -> Result<Vec<Node>, Vec<String>> {
+    let num_lanes = grid.lanes.len();
+    let center_lane_idx = num_lanes / 2;
+    let start_point = &grid.lanes[center_lane_idx][start_step];
+    let end_point = &grid.lanes[center_lane_idx][end_step];
+    let (Some((start_floor, _)), Some(_)) = (
+        altitude_band(start_point, config),
+        altitude_band(end_point, config),
+    ) else {
+        return Err(vec![
+            "RRT* leg starts or ends in blocked airspace".to_string()
+        ]);
+    };
+
+    // Planar metres around the start, for nearest-vertex lookups.
+    let m_lat = meters_per_deg_lat(start_point.lat);
+    let m_lon = meters_per_deg_lon(start_point.lat);
+    let position = |node: &Node| {
+        let point = &grid.lanes[node.lane][node.step];
+        (
+            (point.lon - start_point.lon) * m_lon,
+            (point.lat - start_point.lat) * m_lat,
+            node.alt,
+        )
+    };
+    let distance = |a: &Node, b: &Node| {
+        let (ax, ay, az) = position(a);
+        let (bx, by, bz) = position(b);
+        ((ax - bx).powi(2) + (ay - by).powi(2) + (az - bz).powi(2)).sqrt()
+    };
+    let step_m = if end_step > start_step {
+        haversine_distance(
+            start_point.lat,
+            start_point.lon,
+            end_point.lat,
+            end_point.lon,
+        ) / (end_step - start_step) as f64
+    } else {
+        1.0
+    };
+    let steer_m = (step_m * RRT_STEER_STEPS).max(1.0);
+    let rewire_m = steer_m * RRT_REWIRE_FACTOR;
+
+    let mut tree = vec![RrtVertex {
+        node: Node {
+            step: start_step,
+            lane: center_lane_idx,
+            layer: 0,
+            g_score: 0.0,
+            alt: start_floor,
+        },
+        parent: None,
+        children: Vec::new(),
+    }];
+    let leg = |from: &Node, to: &Node| straight_leg_cost(from, to, grid, geofences, config, true);
+
+    for sample_idx in 0..config.rrt_samples {
+        if sample_idx.is_multiple_of(BUDGET_CHECK_INTERVAL) && config.budget.is_exhausted() {
+            return Err(vec![SEARCH_TIMED_OUT.to_string()]);
+        }
+        *nodes_visited += 1;
+
+        let (step, lane) = if rng.random_bool(RRT_GOAL_BIAS) {
+            (end_step, center_lane_idx)
+        } else {
+            (
+                rng.random_range(start_step..=end_step),
+                rng.random_range(0..num_lanes),
+            )
+        };
+        let Some((floor, ceiling)) = altitude_band(&grid.lanes[lane][step], config) else {
+            continue;
+        };
+        let sample = Node {
+            step,
+            lane,
+            layer: 0,
+            g_score: 0.0,
+            alt: rng.random_range(floor..=ceiling),
+        };
+
+        let Some((nearest, nearest_m)) = tree
+            .iter()
+            .enumerate()
+            .map(|(idx, vertex)| (idx, distance(&vertex.node, &sample)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            continue;
+        };
+        let from = &tree[nearest].node;
+        let new = if nearest_m > steer_m {
+            let t = steer_m / nearest_m;
+            Node {
+                step: (from.step as f64 + t * (sample.step as f64 - from.step as f64)).round()
+                    as usize,
+                lane: (from.lane as f64 + t * (sample.lane as f64 - from.lane as f64)).round()
+                    as usize,
+                layer: 0,
+                g_score: 0.0,
+                alt: from.alt + t * (sample.alt - from.alt),
+            }
+        } else {
+            sample
+        };
+        if new.step == from.step && new.lane == from.lane {
+            continue;
+        }
+        let new_point = &grid.lanes[new.lane][new.step];
+        if !altitude_band(new_point, config)
+            .is_some_and(|(floor, ceiling)| new.alt >= floor && new.alt <= ceiling)
+            || geofence_blocks_point(
+                geofences,
+                new_point.lat,
+                new_point.lon,
+                new.alt,
+                config.geofence_standoff_m,
+            )
+        {
+            continue;
+        }
+
+        // Connect through whichever nearby vertex reaches the new one cheapest.
+        let near: Vec<usize> = tree
+            .iter()
+            .enumerate()
+            .filter(|(idx, vertex)| *idx == nearest || distance(&vertex.node, &new) <= rewire_m)
+            .map(|(idx, _)| idx)
+            .collect();
+        let Some((parent, cost)) = near
+            .iter()
+            .filter_map(|&idx| {
+                let vertex = &tree[idx].node;
+                leg(vertex, &new).map(|cost| (idx, vertex.g_score + cost))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            continue;
+        };
+        let new_idx = tree.len();
+        tree.push(RrtVertex {
+            node: Node {
+                g_score: cost,
+                ..new
+            },
+            parent: Some(parent),
+            children: Vec::new(),
+        });
+        tree[parent].children.push(new_idx);
+
+        // Rewire neighbours that are cheaper to reach through the new vertex.
+        for idx in near {
+            if idx == parent {
+                continue;
+            }
+            let Some(leg_cost) = leg(&tree[new_idx].node, &tree[idx].node) else {
+                continue;
+            };
+            let delta = cost + leg_cost - tree[idx].node.g_score;
+            if delta >= -1e-9 {
+                continue;
+            }
+            if let Some(old_parent) = tree[idx].parent {
+                tree[old_parent].children.retain(|&child| child != idx);
+            }
+            tree[idx].parent = Some(new_idx);
+            tree[new_idx].children.push(idx);
+            let mut stack = vec![idx];
+            while let Some(descendant) = stack.pop() {
+                tree[descendant].node.g_score += delta;
+                stack.extend(tree[descendant].children.iter().copied());
+            }
+        }
+    }
+
+    let Some(goal) = tree
+        .iter()
+        .enumerate()
+        .filter(|(_, vertex)| vertex.node.step == end_step && vertex.node.lane == center_lane_idx)
+        .min_by(|a, b| a.1.node.g_score.total_cmp(&b.1.node.g_score))
+        .map(|(idx, _)| idx)
+    else {
+        return Err(vec!["RRT* failed to find a path".to_string()]);
+    };
+
+    let mut path = Vec::new();
+    let mut current = Some(goal);
+    while let Some(idx) = current {
+        path.push(tree[idx].node.clone());
+        current = tree[idx].parent;
+    }
+    path.reverse();
+
+    // Skip vertices wherever a straight leg costs no more than the legs it replaces.
+    let mut shortcut = vec![path[0].clone()];
+    let mut current_idx = 0usize;
+    while current_idx + 1 < path.len() {
+        let from = &path[current_idx];
+        let furthest = ((current_idx + 1)..path.len())
+            .rev()
+            .find(|&target_idx| {
+                let target = &path[target_idx];
+                target_idx == current_idx + 1
+                    || leg(from, target)
+                        .is_some_and(|cost| from.g_score + cost <= target.g_score + 1e-6)
+            })
+            .unwrap_or(current_idx + 1);
+        shortcut.push(path[furthest].clone());
+        current_idx = furthest;
+    }
+    Ok(shortcut)
+}
+
+/// Margins of a route corridor around the nominal path.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorridorConfig {
+    /// Lateral distance (m) either side of each leg, also added beyond its ends.
+    pub half_width_m: f64,
+    /// Added below and above each leg's altitudes (m).
+    pub vertical_buffer_m: f64,
+    /// Added before and after each leg's nominal time window (s).
+    pub time_buffer_s: f64,
+}
+
+impl Default for CorridorConfig {
+    fn default() -> Self {
+        Self {
+            half_width_m: 30.0,
+            vertical_buffer_m: 15.0,
+            time_buffer_s: 30.0,
+        }
     }
 }
 
-fn smooth_airborne_altitudes(nodes: &[Node], grid: &RouteGrid, config: &RouteEngineConfig) -> Vec<Node> {
+/// One volume of a route corridor: the area around a leg, its altitude band and the time the
+/// drone may occupy it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorridorVolume {
+    /// Closed `[lat, lon]` ring.
+    pub polygon: Vec<[f64; 2]>,
+    /// Same altitude reference as the route.
+    pub altitude_lower_m: f64,
+    pub altitude_upper_m: f64,
+    /// On the clock of the departure time passed to [`route_corridor`].
+    pub time_start_s: f64,
+    pub time_end_s: f64,
+}
+
+/// Convert a path into a 4D corridor, one volume per leg, for submission as an operational
+/// intent.
+///
+/// Legs are flown at the next waypoint's speed, or the engine's ground speed when it has none;
+/// climbs and descents take at least the engine's vertical speeds. Legs that neither move nor
+/// change altitude are merged into the next one.
+pub fn route_corridor(
+    waypoints: &[Waypoint],
+    departure_s: f64,
+    engine: &RouteEngineConfig,
+    corridor: &CorridorConfig,
+) -> Vec<CorridorVolume> {
+    let half_width_m = corridor.half_width_m.max(0.0);
+    let vertical_buffer_m = corridor.vertical_buffer_m.max(0.0);
+    let time_buffer_s = corridor.time_buffer_s.max(0.0);
+    let mut volumes = Vec::with_capacity(waypoints.len().saturating_sub(1));
+    let mut time_s = departure_s;
+    for leg in waypoints.windows(2) {
+        let (from, to) = (&leg[0], &leg[1]);
+        let horizontal_m = haversine_distance(from.lat, from.lon, to.lat, to.lon);
+        let climb_m = to.altitude_m - from.altitude_m;
+        if horizontal_m < 0.01 && climb_m.abs() < 0.01 {
+            continue;
+        }
+        let speed_mps = to
+            .speed_mps
+            .filter(|speed| speed.is_finite() && *speed > 0.0)
+            .unwrap_or_else(|| engine.ground_speed_mps());
+        let vertical_speed_mps = if climb_m >= 0.0 {
+            engine.climb_speed_mps
+        } else {
+            engine.descent_speed_mps
+        }
+        .max(0.1);
+        let leg_s = (horizontal_m / speed_mps).max(climb_m.abs() / vertical_speed_mps);
+
+        // A vertical leg has no track of its own; its volume is a square around the point.
+        let track = if horizontal_m < 0.01 {
+            0.0
+        } else {
+            bearing(from.lat, from.lon, to.lat, to.lon)
+        };
+        let (start_lat, start_lon) = offset_by_bearing(
+            from.lat,
+            from.lon,
+            half_width_m,
+            track + std::f64::consts::PI,
+        );
+        let (end_lat, end_lon) = offset_by_bearing(to.lat, to.lon, half_width_m, track);
+        let side = |lat: f64, lon: f64, offset_rad: f64| {
+            let (lat, lon) = offset_by_bearing(lat, lon, half_width_m, track + offset_rad);
+            [lat, lon]
+        };
+        let left = -std::f64::consts::FRAC_PI_2;
+        let right = std::f64::consts::FRAC_PI_2;
+        let start_left = side(start_lat, start_lon, left);
+        let polygon = vec![
+            start_left,
+            side(end_lat, end_lon, left),
+            side(end_lat, end_lon, right),
+            side(start_lat, start_lon, right),
+            start_left,
+        ];
+
+        volumes.push(CorridorVolume {
+            polygon,
+            altitude_lower_m: (from.altitude_m.min(to.altitude_m) - vertical_buffer_m).max(0.0),
+            altitude_upper_m: from.altitude_m.max(to.altitude_m) + vertical_buffer_m,
+            time_start_s: time_s - time_buffer_s,
+            time_end_s: time_s + leg_s + time_buffer_s,
+        });
+        time_s += leg_s;
+    }
+    volumes
+}
+
+fn smooth_airborne_altitudes(
+    nodes: &[Node],
+    grid: &RouteGrid,
+    config: &RouteEngineConfig,
+) -> Vec<Node> {
     if nodes.len() < 2 {
         return nodes.to_vec();
     }
 
-    let effective_speed = (config.cruise_speed_mps - config.wind_mps).max(1.0);
-
     let mut min_safe: Vec<f64> = Vec::with_capacity(nodes.len());
     let mut ceiling: Vec<f64> = Vec::with_capacity(nodes.len());
     let mut lats: Vec<f64> = Vec::with_capacity(nodes.len());
@@ -655,7 +1528,9 @@ fn smooth_airborne_altitudes(nodes: &[Node], grid: &RouteGrid, config: &RouteEng
     for node in nodes {
         let pt = &grid.lanes[node.lane][node.step];
         let obstacle_height = pt.obstacle_height_m.max(pt.terrain_height_m);
-        let min_alt = obstacle_height + config.safety_buffer_m;
+        // Altitude the search climbed to on purpose (e.g. over a geofence) is kept as a floor.
+        let layer_floor = if node.layer > 0 { node.alt } else { 0.0 };
+        let min_alt = (obstacle_height + config.safety_buffer_m).max(layer_floor);
         let max_alt = pt.terrain_height_m + config.faa_limit_agl;
         min_safe.push(min_alt);
         ceiling.push(max_alt);
@@ -689,8 +1564,15 @@ fn smooth_airborne_altitudes(nodes: &[Node], grid: &RouteGrid, config: &RouteEng
     let mut max_down: Vec<f64> = vec![0.0; nodes.len()];
     for i in 1..nodes.len() {
         let dist = haversine_distance(lats[i - 1], lons[i - 1], lats[i], lons[i]).max(0.0);
-        let time_s = dist / effective_speed;
+        let time_s = config.leg_time_s(
+            &grid.lanes[nodes[i - 1].lane][nodes[i - 1].step],
+            &grid.lanes[nodes[i].lane][nodes[i].step],
+            dist,
+        );
         max_up[i] = config.climb_speed_mps.max(0.0) * time_s;
+        if config.max_climb_gradient > 0.0 {
+            max_up[i] = max_up[i].min(config.max_climb_gradient * dist);
+        }
         max_down[i] = config.descent_speed_mps.max(0.0) * time_s;
     }
 
@@ -725,7 +1607,7 @@ fn smooth_airborne_altitudes(nodes: &[Node], grid: &RouteGrid, config: &RouteEng
 
     nodes
         .iter()
-        .zip(alts.into_iter())
+        .zip(alts)
         .map(|(node, alt)| Node {
             alt,
             ..node.clone()
@@ -747,10 +1629,7 @@ fn compute_path_nodes(
         return Err(vec!["grid is empty".to_string()]);
     }
 
-    let active_geofences: Vec<&Geofence> = geofences
-        .iter()
-        .filter(|fence| fence.active && fence.geofence_type != GeofenceType::Advisory)
-        .collect();
+    let active_geofences = blocking_geofences(geofences);
 
     let num_lanes = grid.lanes.len();
     let num_steps = grid.lanes[0].len();
@@ -762,11 +1641,16 @@ fn compute_path_nodes(
     let start_node = Node {
         step: 0,
         lane: center_lane_idx,
+        layer: 0,
         g_score: 0.0,
         alt: start_alt,
     };
+    let layers = grid.layers();
+    // A ground start climbs vertically to cruise, so the gradient limit applies once airborne.
+    let airborne_start = start_altitude_override.is_some();
 
-    let effective_speed = (config.cruise_speed_mps - config.wind_mps).max(1.0);
+    // Heuristic speed: never slower than any leg can be flown, so the estimate stays admissible.
+    let effective_speed = config.max_ground_speed_mps(grid);
     let end_point = &grid.lanes[center_lane_idx][num_steps - 1];
     let start_h = haversine_distance(
         start_point.lat,
@@ -779,6 +1663,7 @@ fn compute_path_nodes(
     open_set.push(Reverse(OpenNode {
         step: start_node.step,
         lane: start_node.lane,
+        layer: start_node.layer,
         g_score: FloatOrd(start_node.g_score),
         f_score: FloatOrd(start_node.g_score + start_h),
         alt: start_node.alt,
@@ -801,12 +1686,16 @@ fn compute_path_nodes(
             continue;
         }
 
+        if nodes_visited.is_multiple_of(BUDGET_CHECK_INTERVAL) && config.budget.is_exhausted() {
+            return Err(vec![SEARCH_TIMED_OUT.to_string()]);
+        }
         nodes_visited += 1;
 
         if current.step == num_steps - 1 && current.lane == center_lane_idx {
             final_node = Some(Node {
                 step: current.step,
                 lane: current.lane,
+                layer: current.layer,
                 g_score: best_g,
                 alt: current.alt,
             });
@@ -821,35 +1710,34 @@ fn compute_path_nodes(
 
         let curr_point = &grid.lanes[current.lane][current.step];
         let candidate_lanes = [current.lane.wrapping_sub(1), current.lane, current.lane + 1];
+        // Theta*: the current node's parent, which successors may link to directly.
+        let line_parent = match config.search {
+            RouteSearch::Grid => None,
+            RouteSearch::AnyAngle => came_from.get(&current_key).cloned(),
+        };
+        let current_alt = current.alt;
+        let faa_ceiling_curr = curr_point.terrain_height_m + config.faa_limit_agl;
+        // FAA 400ft AGL constraint: ensure the altitude at each grid point is within the local ceiling.
+        //
+        // Note: we allow descending between points even if current_alt is above next ceiling, because the
+        // model assumes a smooth transition between points and `target_alt` will be within the next ceiling.
+        if current_alt > faa_ceiling_curr {
+            continue;
+        }
 
         for next_lane in candidate_lanes.iter().copied() {
             if next_lane >= num_lanes {
                 continue;
             }
-            let next_key = NodeKey {
-                step: next_step,
-                lane: next_lane,
-            };
-            if closed_set.contains(&next_key) {
+            let next_point = &grid.lanes[next_lane][next_step];
+            if next_point.penalty().is_infinite() {
                 continue;
             }
-
-            let next_point = &grid.lanes[next_lane][next_step];
             let feature_height = next_point
                 .obstacle_height_m
                 .max(next_point.terrain_height_m);
-            let target_alt = (feature_height + config.safety_buffer_m).max(next_point.altitude_m);
-            let current_alt = current.alt;
-
-            // FAA 400ft AGL constraint: ensure the altitude at each grid point is within the local ceiling.
-            //
-            // Note: we allow descending between points even if current_alt is above next ceiling, because the
-            // model assumes a smooth transition between points and `target_alt` will be within the next ceiling.
-            let faa_ceiling_curr = curr_point.terrain_height_m + config.faa_limit_agl;
+            let floor_alt = (feature_height + config.safety_buffer_m).max(next_point.altitude_m);
             let faa_ceiling_next = next_point.terrain_height_m + config.faa_limit_agl;
-            if current_alt > faa_ceiling_curr || target_alt > faa_ceiling_next {
-                continue;
-            }
 
             let dist = haversine_distance(
                 curr_point.lat,
@@ -857,81 +1745,139 @@ fn compute_path_nodes(
                 next_point.lat,
                 next_point.lon,
             );
-            let time_to_travel = dist / effective_speed;
-
-            let mut alt_cost = 0.0;
-            if current_alt < target_alt {
-                let alt_change = target_alt - current_alt;
-                alt_cost = alt_change * config.cost_climb_penalty;
-            }
-
-            let cruise_alt = current_alt.max(target_alt);
-            if !active_geofences.is_empty()
-                && geofence_blocks_segment(
-                    &active_geofences,
-                    curr_point,
-                    next_point,
-                    current_alt,
-                    target_alt,
-                    config.geofence_sample_step_m,
-                )
-            {
-                continue;
-            }
-
+            let time_to_travel = config.leg_time_s(curr_point, next_point, dist);
             let lane_change_cost =
                 (next_lane as i32 - current.lane as i32).abs() as f64 * config.cost_lane_change;
+            let penalty_cost = time_to_travel * next_point.penalty();
+
+            // Hold the current altitude, climb or descend one layer, or drop to the floor.
+            let hold_layer = nearest_layer(layers, current_alt - floor_alt);
+            let mut candidate_layers =
+                [0, hold_layer.saturating_sub(1), hold_layer, hold_layer + 1];
+            candidate_layers.sort_unstable();
+            for (idx, next_layer) in candidate_layers.iter().copied().enumerate() {
+                if idx > 0 && candidate_layers[idx - 1] == next_layer {
+                    continue;
+                }
+                let Some(layer_height) = layers.get(next_layer) else {
+                    continue;
+                };
+                let next_key = NodeKey {
+                    step: next_step,
+                    lane: next_lane,
+                    layer: next_layer,
+                };
+                if closed_set.contains(&next_key) {
+                    continue;
+                }
 
-            let mut proximity_cost = 0.0;
-            if next_lane > 0 {
-                let left = &grid.lanes[next_lane - 1][next_step];
-                let left_min_safe =
-                    left.obstacle_height_m.max(left.terrain_height_m) + config.safety_buffer_m;
-                if left_min_safe > cruise_alt {
-                    proximity_cost += config.cost_proximity_penalty;
+                let target_alt = floor_alt + layer_height;
+                if target_alt > faa_ceiling_next {
+                    continue;
                 }
-            }
-            if next_lane + 1 < num_lanes {
-                let right = &grid.lanes[next_lane + 1][next_step];
-                let right_min_safe =
-                    right.obstacle_height_m.max(right.terrain_height_m) + config.safety_buffer_m;
-                if right_min_safe > cruise_alt {
-                    proximity_cost += config.cost_proximity_penalty;
+                if (airborne_start || current.step > 0)
+                    && !config.climb_allowed(target_alt - current_alt, dist)
+                {
+                    continue;
                 }
-            }
 
-            let step_cost = time_to_travel + alt_cost + lane_change_cost + proximity_cost;
-            let tentative_g = best_g + step_cost;
-            if tentative_g < g_score.get(&next_key).copied().unwrap_or(f64::INFINITY) {
-                came_from.insert(
-                    next_key,
-                    Node {
-                        step: current.step,
-                        lane: current.lane,
-                        g_score: best_g,
-                        alt: current.alt,
-                    },
-                );
-                g_score.insert(next_key, tentative_g);
+                let mut alt_cost = 0.0;
+                if current_alt < target_alt {
+                    let alt_change = target_alt - current_alt;
+                    alt_cost = alt_change * config.cost_climb_penalty;
+                }
 
-                let dist_to_end = haversine_distance(
-                    next_point.lat,
-                    next_point.lon,
-                    end_point.lat,
-                    end_point.lon,
-                );
-                let h_score = dist_to_end / effective_speed;
+                let cruise_alt = current_alt.max(target_alt);
+                if !active_geofences.is_empty()
+                    && geofence_blocks_segment(
+                        &active_geofences,
+                        curr_point,
+                        next_point,
+                        current_alt,
+                        target_alt,
+                        config.geofence_sample_step_m,
+                        config.geofence_standoff_m,
+                    )
+                {
+                    continue;
+                }
 
-                // Allow altitude to decrease when it is safe to do so (e.g. when terrain drops).
-                // This keeps paths feasible under an AGL ceiling without requiring "segment reset" tricks.
-                let new_alt = target_alt;
-                open_set.push(Reverse(OpenNode {
-                    step: next_step,
-                    lane: next_lane,
-                    g_score: FloatOrd(tentative_g),
-                    f_score: FloatOrd(tentative_g + h_score),
-                    alt: new_alt,
-                }));
+                let mut proximity_cost = 0.0;
+                if next_lane > 0 {
+                    let left = &grid.lanes[next_lane - 1][next_step];
+                    let left_min_safe =
+                        left.obstacle_height_m.max(left.terrain_height_m) + config.safety_buffer_m;
+                    if left_min_safe > cruise_alt {
+                        proximity_cost += config.cost_proximity_penalty;
+                    }
+                }
+                if next_lane + 1 < num_lanes {
+                    let right = &grid.lanes[next_lane + 1][next_step];
+                    let right_min_safe = right.obstacle_height_m.max(right.terrain_height_m)
+                        + config.safety_buffer_m;
+                    if right_min_safe > cruise_alt {
+                        proximity_cost += config.cost_proximity_penalty;
+                    }
+                }
+
+                let step_cost =
+                    time_to_travel + alt_cost + lane_change_cost + proximity_cost + penalty_cost;
+                let mut tentative_g = best_g + step_cost;
+                let mut parent = Node {
+                    step: current.step,
+                    lane: current.lane,
+                    layer: current.layer,
+                    g_score: best_g,
+                    alt: current.alt,
+                };
+                if let Some(line_parent) = &line_parent {
+                    let next_node = Node {
+                        step: next_step,
+                        lane: next_lane,
+                        layer: next_layer,
+                        g_score: 0.0,
+                        alt: target_alt,
+                    };
+                    let direct_cost = direct_leg_cost(
+                        line_parent,
+                        &next_node,
+                        grid,
+                        &active_geofences,
+                        config,
+                        airborne_start || line_parent.step > 0,
+                    );
+                    if let Some(direct_cost) = direct_cost {
+                        let direct_g = line_parent.g_score + direct_cost + proximity_cost;
+                        // Prefer the straight leg when it costs the same as the step.
+                        if direct_g <= tentative_g + 1e-6 {
+                            tentative_g = direct_g;
+                            parent = line_parent.clone();
+                        }
+                    }
+                }
+                if tentative_g < g_score.get(&next_key).copied().unwrap_or(f64::INFINITY) {
+                    came_from.insert(next_key, parent);
+                    g_score.insert(next_key, tentative_g);
+
+                    let dist_to_end = haversine_distance(
+                        next_point.lat,
+                        next_point.lon,
+                        end_point.lat,
+                        end_point.lon,
+                    );
+                    let h_score = dist_to_end / effective_speed;
+
+                    // Allow altitude to decrease when it is safe to do so (e.g. when terrain drops).
+                    // This keeps paths feasible under an AGL ceiling without requiring "segment reset" tricks.
+                    open_set.push(Reverse(OpenNode {
+                        step: next_step,
+                        lane: next_lane,
+                        layer: next_layer,
+                        g_score: FloatOrd(tentative_g),
+                        f_score: FloatOrd(tentative_g + h_score),
+                        alt: target_alt,
+                    }));
+                }
             }
         }
     }
@@ -948,7 +1894,13 @@ fn compute_path_nodes(
     }
     path_nodes.reverse();
 
-    let smoothed_path = smooth_path(&path_nodes, grid, &active_geofences, config);
+    // Any-angle paths are already made of straight legs.
+    let smoothed_path = match config.search {
+        RouteSearch::Grid => {
+            smooth_path(&path_nodes, grid, &active_geofences, config, airborne_start)
+        }
+        RouteSearch::AnyAngle => path_nodes.clone(),
+    };
     let mut max_cruise_alt: f64 = 0.0;
     for node in &path_nodes {
         max_cruise_alt = max_cruise_alt.max(node.alt);
@@ -961,11 +1913,22 @@ fn compute_path_nodes(
     })
 }
 
+/// Index of the layer closest to `height_m` above the floor.
+fn nearest_layer(layers: &[f64], height_m: f64) -> usize {
+    layers
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - height_m).abs().total_cmp(&(*b - height_m).abs()))
+        .map(|(idx, _)| idx)
+        .unwrap_or(0)
+}
+
 fn smooth_path(
     path_nodes: &[Node],
     grid: &RouteGrid,
     geofences: &[&Geofence],
     config: &RouteEngineConfig,
+    airborne_start: bool,
 ) -> Vec<Node> {
     if path_nodes.len() <= 2 {
         return path_nodes.to_vec();
@@ -989,6 +1952,7 @@ fn smooth_path(
                 grid,
                 geofences,
                 config,
+                airborne_start || current_idx > 0,
             ) {
                 furthest_valid = target_idx;
             }
@@ -1005,12 +1969,13 @@ fn smooth_path(
 fn is_line_of_sight_clear(
     start: &Node,
     end: &Node,
-    _all_nodes: &[Node],
+    all_nodes: &[Node],
     start_idx: usize,
     end_idx: usize,
     grid: &RouteGrid,
     geofences: &[&Geofence],
     config: &RouteEngineConfig,
+    limit_climb: bool,
 ) -> bool {
     let num_lanes = grid.lanes.len();
     let num_steps = grid.lanes[0].len();
@@ -1020,6 +1985,17 @@ fn is_line_of_sight_clear(
     let step_delta = end.step as i32 - start.step as i32;
     let lane_delta = end.lane as i32 - start.lane as i32;
 
+    if limit_climb && config.max_climb_gradient > 0.0 {
+        let from = &grid.lanes[start.lane][start.step];
+        let to = &grid.lanes[end.lane][end.step];
+        let distance_m = haversine_distance(from.lat, from.lon, to.lat, to.lon);
+        if !config.climb_allowed(end.alt - start.alt, distance_m) {
+            return false;
+        }
+    }
+
+    let mut wind_sum = [0.0, 0.0];
+    let mut wind_samples = 0usize;
     for i in 1..num_samples {
         let t = i as f64 / num_samples as f64;
         let mid_step = ((start.step as f64) + t * (step_delta as f64)).round() as i32;
@@ -1031,6 +2007,11 @@ fn is_line_of_sight_clear(
             return false;
         }
         let grid_point = &grid.lanes[mid_lane as usize][mid_step as usize];
+        if let Some([u, v]) = grid_point.wind_uv_mps {
+            wind_sum[0] += u;
+            wind_sum[1] += v;
+            wind_samples += 1;
+        }
         let sample_alt = start.alt + t * (end.alt - start.alt);
         let obstacle_height = grid_point
             .obstacle_height_m
@@ -1042,22 +2023,438 @@ fn is_line_of_sight_clear(
             return false;
         }
         if !geofences.is_empty()
-            && geofence_blocks_point(geofences, grid_point.lat, grid_point.lon, sample_alt)
+            && geofence_blocks_point(
+                geofences,
+                grid_point.lat,
+                grid_point.lon,
+                sample_alt,
+                config.geofence_standoff_m,
+            )
         {
             return false;
         }
+        // Shortcuts may not take the route through worse weather or coverage than the path
+        // they replace. The line passes between grid points, so the worst neighbour counts.
+        let lane_f = start.lane as f64 + t * lane_delta as f64;
+        let step_f = start.step as f64 + t * step_delta as f64;
+        let penalty = [lane_f.floor(), lane_f.ceil()]
+            .into_iter()
+            .flat_map(|lane| [(lane, step_f.floor()), (lane, step_f.ceil())])
+            .filter_map(|(lane, step)| grid.lanes.get(lane as usize)?.get(step as usize))
+            .map(RouteGridPoint::penalty)
+            .fold(0.0, f64::max);
+        if penalty > 0.0 {
+            let replaced = all_nodes
+                .get(start_idx + (mid_step as usize).saturating_sub(start.step))
+                .map(|node| grid.lanes[node.lane][node.step].penalty())
+                .unwrap_or(f64::INFINITY);
+            if penalty > replaced {
+                return false;
+            }
+        }
+    }
+
+    // Nor may they take longer to fly than the path they replace when the wind varies.
+    if wind_samples > 0 {
+        let from = &grid.lanes[start.lane][start.step];
+        let to = &grid.lanes[end.lane][end.step];
+        let samples = wind_samples as f64;
+        let shortcut_s = haversine_distance(from.lat, from.lon, to.lat, to.lon)
+            / ground_speed_in_wind(
+                config.cruise_speed_mps,
+                wind_sum[0] / samples,
+                wind_sum[1] / samples,
+                bearing(from.lat, from.lon, to.lat, to.lon),
+            );
+        let replaced_s: f64 = all_nodes[start_idx..=end_idx]
+            .windows(2)
+            .map(|pair| {
+                let a = &grid.lanes[pair[0].lane][pair[0].step];
+                let b = &grid.lanes[pair[1].lane][pair[1].step];
+                config.leg_time_s(a, b, haversine_distance(a.lat, a.lon, b.lat, b.lon))
+            })
+            .sum();
+        if shortcut_s > replaced_s + 1e-6 {
+            return false;
+        }
     }
 
     true
 }
 
-fn geofence_blocks_point(geofences: &[&Geofence], lat: f64, lon: f64, altitude_m: f64) -> bool {
-    for geofence in geofences {
-        if geofence.contains_point(lat, lon, altitude_m) {
-            return true;
+/// Cost of flying straight from `from` to `to`, or `None` when the line is blocked, climbs too
+/// steeply, or bends round a route waypoint (lanes only run straight between waypoints).
+fn direct_leg_cost(
+    from: &Node,
+    to: &Node,
+    grid: &RouteGrid,
+    geofences: &[&Geofence],
+    config: &RouteEngineConfig,
+    limit_climb: bool,
+) -> Option<f64> {
+    let (first_step, last_step) = (from.step.min(to.step), from.step.max(to.step));
+    let inner_waypoints = grid
+        .waypoint_indices
+        .get(1..grid.waypoint_indices.len().saturating_sub(1))
+        .unwrap_or_default();
+    if inner_waypoints
+        .iter()
+        .any(|&step| first_step <= step && step < last_step)
+    {
+        return None;
+    }
+    straight_leg_cost(from, to, grid, geofences, config, limit_climb)
+}
+
+/// Cost of flying straight from `from` to `to` wherever they are on the grid, or `None` when
+/// the line is blocked or climbs too steeply. Only the points between them are checked.
+fn straight_leg_cost(
+    from: &Node,
+    to: &Node,
+    grid: &RouteGrid,
+    geofences: &[&Geofence],
+    config: &RouteEngineConfig,
+    limit_climb: bool,
+) -> Option<f64> {
+    let from_point = &grid.lanes[from.lane][from.step];
+    let to_point = &grid.lanes[to.lane][to.step];
+    let distance_m = haversine_distance(from_point.lat, from_point.lon, to_point.lat, to_point.lon);
+    if limit_climb && !config.climb_allowed(to.alt - from.alt, distance_m) {
+        return None;
+    }
+
+    let step_delta = to.step as f64 - from.step as f64;
+    let lane_delta = to.lane as f64 - from.lane as f64;
+    // Two samples per grid step, the even ones on grid points along the longer axis.
+    let grid_steps = (step_delta.abs().max(lane_delta.abs()) as usize).max(1);
+    let num_samples = grid_steps * 2;
+    let mut penalty_sum = 0.0;
+    let mut wind_sum = [0.0, 0.0];
+    let mut wind_samples = 0usize;
+    for i in 0..=num_samples {
+        let t = i as f64 / num_samples as f64;
+        let lane_f = from.lane as f64 + t * lane_delta;
+        let step_f = from.step as f64 + t * step_delta;
+        let grid_point = &grid.lanes[lane_f.round() as usize][step_f.round() as usize];
+        if let Some([u, v]) = grid_point.wind_uv_mps {
+            wind_sum[0] += u;
+            wind_sum[1] += v;
+            wind_samples += 1;
+        }
+        if i == 0 {
+            continue;
+        }
+        // Charged per grid step, like the search's own steps. The line passes between grid
+        // points, so the worst neighbour counts.
+        if i % 2 == 0 {
+            penalty_sum += [lane_f.floor(), lane_f.ceil()]
+                .into_iter()
+                .flat_map(|lane| [(lane, step_f.floor()), (lane, step_f.ceil())])
+                .map(|(lane, step)| grid.lanes[lane as usize][step as usize].penalty())
+                .fold(0.0, f64::max);
+        }
+        if i == num_samples {
+            continue;
+        }
+        let sample_alt = from.alt + t * (to.alt - from.alt);
+        let min_safe_alt = grid_point
+            .obstacle_height_m
+            .max(grid_point.terrain_height_m)
+            + config.safety_buffer_m;
+        let faa_ceiling = grid_point.terrain_height_m + config.faa_limit_agl;
+        if sample_alt < min_safe_alt || sample_alt > faa_ceiling {
+            return None;
+        }
+        if !geofences.is_empty()
+            && geofence_blocks_point(
+                geofences,
+                grid_point.lat,
+                grid_point.lon,
+                sample_alt,
+                config.geofence_standoff_m,
+            )
+        {
+            return None;
         }
     }
-    false
+    let penalty = penalty_sum / grid_steps as f64;
+    if penalty.is_infinite() {
+        return None;
+    }
+
+    let time_s = if wind_samples > 0 {
+        let samples = wind_samples as f64;
+        distance_m
+            / ground_speed_in_wind(
+                config.cruise_speed_mps,
+                wind_sum[0] / samples,
+                wind_sum[1] / samples,
+                bearing(from_point.lat, from_point.lon, to_point.lat, to_point.lon),
+            )
+    } else {
+        distance_m / config.ground_speed_mps()
+    };
+    let climb_cost = (to.alt - from.alt).max(0.0) * config.cost_climb_penalty;
+    // A straight leg turns once however many lanes it crosses.
+    let lane_change_cost = if from.lane == to.lane {
+        0.0
+    } else {
+        config.cost_lane_change
+    };
+    Some(time_s + time_s * penalty + climb_cost + lane_change_cost)
+}
+
+/// Grid points bucketed by position, to look up the grid point nearest an off-grid sample.
+struct GridIndex<'a> {
+    grid: &'a RouteGrid,
+    origin: (f64, f64),
+    scale: (f64, f64),
+    cell_m: f64,
+    cells: HashMap<(i64, i64), Vec<(usize, usize)>>,
+}
+
+impl<'a> GridIndex<'a> {
+    fn new(grid: &'a RouteGrid) -> Self {
+        let first = &grid.lanes[0][0];
+        let spacing =
+            |a: &RouteGridPoint, b: &RouteGridPoint| haversine_distance(a.lat, a.lon, b.lat, b.lon);
+        let step_m = grid.lanes[0]
+            .windows(2)
+            .map(|pair| spacing(&pair[0], &pair[1]))
+            .fold(0.0, f64::max);
+        let lane_m = grid
+            .lanes
+            .get(1)
+            .map_or(0.0, |lane| spacing(first, &lane[0]));
+        let mut index = Self {
+            grid,
+            origin: (first.lat, first.lon),
+            scale: (meters_per_deg_lat(first.lat), meters_per_deg_lon(first.lat)),
+            cell_m: step_m.max(lane_m).max(5.0),
+            cells: HashMap::new(),
+        };
+        for (lane_idx, lane) in grid.lanes.iter().enumerate() {
+            for (step_idx, point) in lane.iter().enumerate() {
+                let cell = index.cell(point.lat, point.lon);
+                index
+                    .cells
+                    .entry(cell)
+                    .or_default()
+                    .push((lane_idx, step_idx));
+            }
+        }
+        index
+    }
+
+    fn xy(&self, lat: f64, lon: f64) -> (f64, f64) {
+        (
+            (lon - self.origin.1) * self.scale.1,
+            (lat - self.origin.0) * self.scale.0,
+        )
+    }
+
+    fn cell(&self, lat: f64, lon: f64) -> (i64, i64) {
+        let (x, y) = self.xy(lat, lon);
+        (
+            (x / self.cell_m).floor() as i64,
+            (y / self.cell_m).floor() as i64,
+        )
+    }
+
+    /// Grid point nearest the position; `None` off the grid.
+    fn nearest(&self, lat: f64, lon: f64) -> Option<&'a RouteGridPoint> {
+        let (cx, cy) = self.cell(lat, lon);
+        let (x, y) = self.xy(lat, lon);
+        let grid = self.grid;
+        (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (cx + dx, cy + dy)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .map(|&(lane, step)| &grid.lanes[lane][step])
+            .map(|point| {
+                let (px, py) = self.xy(point.lat, point.lon);
+                (point, (px - x).hypot(py - y))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(point, _)| point)
+    }
+}
+
+/// Replace the sharp cruise corners of a route with arcs of `config.turn_radius_m`, so fixed-wing
+/// and fast multirotor platforms can fly it. A corner whose legs are too short for the radius
+/// gets the widest arc that fits in half of each leg, and one whose arc would leave clear
+/// airspace is tightened until it stays clear, or left sharp.
+fn round_corners(
+    waypoints: Vec<RouteEngineWaypoint>,
+    grid: &RouteGrid,
+    geofences: &[&Geofence],
+    config: &RouteEngineConfig,
+) -> Vec<RouteEngineWaypoint> {
+    if config.turn_radius_m <= 0.0 || waypoints.len() < 3 {
+        return waypoints;
+    }
+    let index = GridIndex::new(grid);
+    let clear = |wp: &RouteEngineWaypoint| {
+        let Some(point) = index.nearest(wp.lat, wp.lon) else {
+            return false;
+        };
+        let floor = point.obstacle_height_m.max(point.terrain_height_m) + config.safety_buffer_m;
+        let ceiling = point.terrain_height_m + config.faa_limit_agl;
+        wp.altitude_m >= floor - 1e-6
+            && wp.altitude_m <= ceiling + 1e-6
+            && !geofence_blocks_point(
+                geofences,
+                wp.lat,
+                wp.lon,
+                wp.altitude_m,
+                config.geofence_standoff_m,
+            )
+    };
+
+    let mut rounded = Vec::with_capacity(waypoints.len());
+    rounded.push(waypoints[0].clone());
+    for window in waypoints.windows(3) {
+        let (prev, corner, next) = (&window[0], &window[1], &window[2]);
+        let cruising = corner
+            .phase
+            .as_deref()
+            .is_some_and(|phase| phase.starts_with("CRUISE"));
+        match cruising
+            .then(|| fillet(prev, corner, next, config.turn_radius_m, clear))
+            .flatten()
+        {
+            Some(arc) => rounded.extend(arc),
+            None => rounded.push(corner.clone()),
+        }
+    }
+    rounded.extend(waypoints.last().cloned());
+    rounded
+}
+
+/// Arc replacing `corner` between the legs from `prev` and to `next`, at `radius_m` or as
+/// tight as it must be to fit in half of each leg with every point `clear`. `None` for a
+/// straight or reversing corner, or when no arc is clear.
+fn fillet(
+    prev: &RouteEngineWaypoint,
+    corner: &RouteEngineWaypoint,
+    next: &RouteEngineWaypoint,
+    radius_m: f64,
+    clear: impl Fn(&RouteEngineWaypoint) -> bool,
+) -> Option<Vec<RouteEngineWaypoint>> {
+    let scale = (
+        meters_per_deg_lat(corner.lat),
+        meters_per_deg_lon(corner.lat),
+    );
+    let xy = |wp: &RouteEngineWaypoint| {
+        (
+            (wp.lon - corner.lon) * scale.1,
+            (wp.lat - corner.lat) * scale.0,
+        )
+    };
+    let ((ax, ay), (cx, cy)) = (xy(prev), xy(next));
+    let (in_m, out_m) = (ax.hypot(ay), cx.hypot(cy));
+    if in_m < 1e-6 || out_m < 1e-6 {
+        return None;
+    }
+    // Direction of travel into and out of the corner, east/north.
+    let (u_in, u_out) = ((-ax / in_m, -ay / in_m), (cx / out_m, cy / out_m));
+    // Signed turn, positive to the left.
+    let turn = (u_in.0 * u_out.1 - u_in.1 * u_out.0).atan2(u_in.0 * u_out.0 + u_in.1 * u_out.1);
+    if turn.abs() < MIN_FILLET_TURN_RAD || turn.abs() > std::f64::consts::PI - MIN_FILLET_TURN_RAD {
+        return None;
+    }
+    let half_tan = (turn.abs() / 2.0).tan();
+    let segments = (turn.abs() / FILLET_ARC_STEP_RAD).ceil().max(2.0) as usize;
+    let mut radius = radius_m.min(in_m.min(out_m) / 2.0 / half_tan);
+    while radius >= MIN_FILLET_RADIUS_M {
+        let lead = radius * half_tan;
+        let start = (-u_in.0 * lead, -u_in.1 * lead);
+        let start_alt = corner.altitude_m + (prev.altitude_m - corner.altitude_m) * lead / in_m;
+        let end_alt = corner.altitude_m + (next.altitude_m - corner.altitude_m) * lead / out_m;
+        // The centre sits on the inside of the turn, square to the incoming leg.
+        let side = turn.signum();
+        let centre = (
+            start.0 - u_in.1 * side * radius,
+            start.1 + u_in.0 * side * radius,
+        );
+        let start_angle = (start.1 - centre.1).atan2(start.0 - centre.0);
+        // Point a fraction `f` of the way around the arc.
+        let point = |f: f64| {
+            let angle = start_angle + turn * f;
+            RouteEngineWaypoint {
+                lat: corner.lat + (centre.1 + radius * angle.sin()) / scale.0,
+                lon: corner.lon + (centre.0 + radius * angle.cos()) / scale.1,
+                altitude_m: start_alt + (end_alt - start_alt) * f,
+                phase: Some("CRUISE_TURN".to_string()),
+            }
+        };
+        // Check the whole arc, not just the points the route will carry.
+        let checks = ((radius * turn.abs() / FILLET_CHECK_STEP_M).ceil() as usize).max(segments);
+        if (0..=checks).all(|k| clear(&point(k as f64 / checks as f64))) {
+            return Some(
+                (0..=segments)
+                    .map(|k| point(k as f64 / segments as f64))
+                    .collect(),
+            );
+        }
+        radius /= 2.0;
+    }
+    None
+}
+
+/// Geofences a route must stay out of.
+fn blocking_geofences(geofences: &[Geofence]) -> Vec<&Geofence> {
+    geofences
+        .iter()
+        .filter(|fence| fence.active && fence.geofence_type != GeofenceType::Advisory)
+        .collect()
+}
+
+/// Whether a point is inside a geofence or within `standoff_m` of one.
+fn geofence_blocks_point(
+    geofences: &[&Geofence],
+    lat: f64,
+    lon: f64,
+    altitude_m: f64,
+    standoff_m: f64,
+) -> bool {
+    geofences.iter().any(|geofence| {
+        geofence.contains_point(lat, lon, altitude_m)
+            || (standoff_m > 0.0 && geofence.distance_m(lat, lon, altitude_m) < standoff_m)
+    })
+}
+
+/// Closest a route comes to any of `geofences`, sampling each leg every `step_m`.
+pub fn min_geofence_clearance(
+    waypoints: &[RouteEngineWaypoint],
+    geofences: &[&Geofence],
+    step_m: f64,
+) -> Option<f64> {
+    if geofences.is_empty() || waypoints.is_empty() {
+        return None;
+    }
+    let clearance = |lat: f64, lon: f64, altitude_m: f64| {
+        geofences
+            .iter()
+            .map(|geofence| geofence.distance_m(lat, lon, altitude_m))
+            .fold(f64::INFINITY, f64::min)
+    };
+    let first = &waypoints[0];
+    let mut min_m = clearance(first.lat, first.lon, first.altitude_m);
+    for leg in waypoints.windows(2) {
+        let (start, end) = (&leg[0], &leg[1]);
+        let distance_m = haversine_distance(start.lat, start.lon, end.lat, end.lon);
+        let steps = ((distance_m / step_m.max(1.0)).ceil() as usize).clamp(1, 1000);
+        for i in 1..=steps {
+            let t = i as f64 / steps as f64;
+            min_m = min_m.min(clearance(
+                start.lat + t * (end.lat - start.lat),
+                start.lon + t * (end.lon - start.lon),
+                start.altitude_m + t * (end.altitude_m - start.altitude_m),
+            ));
+        }
+    }
+    Some(min_m)
 }
 
 fn geofence_blocks_segment(
@@ -1067,6 +2464,7 @@ fn geofence_blocks_segment(
     start_alt: f64,
     end_alt: f64,
     step_m: f64,
+    standoff_m: f64,
 ) -> bool {
     if geofences.is_empty() {
         return false;
@@ -1079,7 +2477,7 @@ fn geofence_blocks_segment(
         let lat = start.lat + t * (end.lat - start.lat);
         let lon = start.lon + t * (end.lon - start.lon);
         let alt = start_alt + t * (end_alt - start_alt);
-        if geofence_blocks_point(geofences, lat, lon, alt) {
+        if geofence_blocks_point(geofences, lat, lon, alt, standoff_m) {
             return true;
         }
     }
@@ -1117,6 +2515,11 @@ mod tests {
                     altitude_m: 0.0,
                     terrain_height_m: 200.0,
                     obstacle_height_m: 200.0,
+                    weather_cost: 0.0,
+                    coverage_cost: 0.0,
+                    traffic_cost: 0.0,
+                    ground_risk_cost: 0.0,
+                    wind_uv_mps: None,
                 },
                 RouteGridPoint {
                     lat: 33.0,
@@ -1124,9 +2527,15 @@ mod tests {
                     altitude_m: 0.0,
                     terrain_height_m: 0.0,
                     obstacle_height_m: 0.0,
+                    weather_cost: 0.0,
+                    coverage_cost: 0.0,
+                    traffic_cost: 0.0,
+                    ground_risk_cost: 0.0,
+                    wind_uv_mps: None,
                 },
             ]],
             waypoint_indices: vec![0, 1],
+            altitude_layers: Vec::new(),
         };
 
         let result =
@@ -1165,6 +2574,11 @@ mod tests {
                     altitude_m: 0.0,
                     terrain_height_m: 100.0,
                     obstacle_height_m: 100.0,
+                    weather_cost: 0.0,
+                    coverage_cost: 0.0,
+                    traffic_cost: 0.0,
+                    ground_risk_cost: 0.0,
+                    wind_uv_mps: None,
                 },
                 RouteGridPoint {
                     lat: 33.0,
@@ -1172,9 +2586,15 @@ mod tests {
                     altitude_m: 0.0,
                     terrain_height_m: 100.0,
                     obstacle_height_m: 100.0,
+                    weather_cost: 0.0,
+                    coverage_cost: 0.0,
+                    traffic_cost: 0.0,
+                    ground_risk_cost: 0.0,
+                    wind_uv_mps: None,
                 },
             ]],
             waypoint_indices: vec![0, 1],
+            altitude_layers: Vec::new(),
         };
 
         let config = RouteEngineConfig {
@@ -1182,8 +2602,499 @@ mod tests {
             ..Default::default()
         };
         let result = optimize_airborne_path(&waypoints, &grid, &[], &config, None);
-        assert!(result.success, "expected path to be feasible: {:?}", result.errors);
+        assert!(
+            result.success,
+            "expected path to be feasible: {:?}",
+            result.errors
+        );
         assert_eq!(result.waypoints.len(), 2);
         assert!(result.waypoints[0].altitude_m >= 120.0);
     }
+
+    fn northbound(length_m: f64, altitude_m: f64) -> Vec<Waypoint> {
+        let (end_lat, end_lon) = crate::spatial::offset_position(33.0, -117.0, length_m, 0.0);
+        vec![
+            Waypoint {
+                lat: 33.0,
+                lon: -117.0,
+                altitude_m,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: end_lat,
+                lon: end_lon,
+                altitude_m,
+                speed_mps: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn altitude_layers_climb_over_a_geofence_too_wide_to_go_around() {
+        let corner = |north_m: f64, east_m: f64| {
+            let (lat, lon) = crate::spatial::offset_position(33.0, -117.0, north_m, east_m);
+            [lat, lon]
+        };
+        let fence = Geofence {
+            id: "tfr".to_string(),
+            name: "TFR".to_string(),
+            geofence_type: GeofenceType::TemporaryRestriction,
+            polygon: vec![
+                corner(400.0, -500.0),
+                corner(400.0, 500.0),
+                corner(600.0, 500.0),
+                corner(600.0, -500.0),
+                corner(400.0, -500.0),
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 80.0,
+            active: true,
+            created_at: chrono::Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
+        };
+        let waypoints = northbound(1_000.0, 60.0);
+        let mut grid =
+            generate_grid_samples(&waypoints, 25.0, &build_lane_offsets(100.0, 50.0), 0.0).unwrap();
+        let mut config = RouteEngineConfig::default();
+        let geofences = [fence];
+        assert!(!optimize_flight_path(&waypoints, &grid, &geofences, &config).success);
+
+        config.altitude_step_m = 10.0;
+        apply_altitude_layers(&mut grid, &config);
+        assert_eq!(grid.altitude_layers.len(), 13);
+        let result = optimize_flight_path(&waypoints, &grid, &geofences, &config);
+        assert!(result.success, "{:?}", result.errors);
+        assert!(result.stats.unwrap().max_altitude > 80.0);
+    }
+
+    #[test]
+    fn geofence_standoff_keeps_routes_off_the_fence_edge() {
+        let corner = |north_m: f64, east_m: f64| {
+            let (lat, lon) = crate::spatial::offset_position(33.0, -117.0, north_m, east_m);
+            [lat, lon]
+        };
+        // Blocks the direct route and every lane east of it.
+        let fence = Geofence {
+            id: "stadium".to_string(),
+            name: "Stadium".to_string(),
+            geofence_type: GeofenceType::NoFlyZone,
+            polygon: vec![
+                corner(400.0, -20.0),
+                corner(400.0, 500.0),
+                corner(600.0, 500.0),
+                corner(600.0, -20.0),
+                corner(400.0, -20.0),
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 500.0,
+            active: true,
+            created_at: chrono::Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
+        };
+        let waypoints = northbound(1_000.0, 60.0);
+        let grid =
+            generate_grid_samples(&waypoints, 25.0, &build_lane_offsets(100.0, 50.0), 0.0).unwrap();
+        let geofences = [fence];
+        let clearance = |config: &RouteEngineConfig| {
+            let result = optimize_flight_path(&waypoints, &grid, &geofences, config);
+            assert!(result.success, "{:?}", result.errors);
+            result
+                .stats
+                .and_then(|stats| stats.min_geofence_clearance_m)
+                .expect("clearance")
+        };
+
+        let grazing = clearance(&RouteEngineConfig::default());
+        assert!(grazing < 35.0, "{}", grazing);
+        let standoff = clearance(&RouteEngineConfig {
+            geofence_standoff_m: 40.0,
+            ..Default::default()
+        });
+        assert!(standoff >= 39.0, "{}", standoff);
+        let unfenced = optimize_flight_path(&waypoints, &grid, &[], &RouteEngineConfig::default());
+        assert!(unfenced.stats.unwrap().min_geofence_clearance_m.is_none());
+    }
+
+    #[test]
+    fn max_climb_gradient_makes_the_route_climb_early() {
+        let waypoints = northbound(1_200.0, 30.0);
+        let (obstacle_lat, obstacle_lon) =
+            crate::spatial::offset_position(33.0, -117.0, 800.0, 0.0);
+        let mut grid = generate_grid_samples(&waypoints, 25.0, &[0.0], 0.0).unwrap();
+        apply_obstacles(
+            &mut grid,
+            &[RouteObstacle {
+                lat: obstacle_lat,
+                lon: obstacle_lon,
+                radius_m: 30.0,
+                height_m: Some(60.0),
+                polygon: None,
+            }],
+            |_, _| 0.0,
+        );
+        let mut config = RouteEngineConfig {
+            max_climb_gradient: 0.2,
+            ..Default::default()
+        };
+        let without_layers = optimize_airborne_path(&waypoints, &grid, &[], &config, Some(30.0));
+        assert!(!without_layers.success);
+
+        config.altitude_step_m = 4.0;
+        apply_altitude_layers(&mut grid, &config);
+        let result = optimize_airborne_path(&waypoints, &grid, &[], &config, Some(30.0));
+        assert!(result.success, "{:?}", result.errors);
+        assert!(result.stats.unwrap().max_altitude >= 80.0);
+        for pair in result.waypoints.windows(2) {
+            let run_m = haversine_distance(pair[0].lat, pair[0].lon, pair[1].lat, pair[1].lon);
+            let climb_m = pair[1].altitude_m - pair[0].altitude_m;
+            assert!(climb_m <= 0.2 * run_m + 0.01, "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn rrt_fallback_climbs_early_where_the_grid_search_cannot() {
+        let waypoints = northbound(1_200.0, 30.0);
+        let (obstacle_lat, obstacle_lon) =
+            crate::spatial::offset_position(33.0, -117.0, 800.0, 0.0);
+        let mut grid =
+            generate_grid_samples(&waypoints, 25.0, &build_lane_offsets(50.0, 25.0), 0.0).unwrap();
+        apply_obstacles(
+            &mut grid,
+            &[RouteObstacle {
+                lat: obstacle_lat,
+                lon: obstacle_lon,
+                radius_m: 200.0,
+                height_m: Some(60.0),
+                polygon: None,
+            }],
+            |_, _| 0.0,
+        );
+        let config = RouteEngineConfig {
+            max_climb_gradient: 0.2,
+            ..Default::default()
+        };
+        let grid_result = optimize_flight_path(&waypoints, &grid, &[], &config);
+        assert!(!grid_result.success);
+        assert_eq!(grid_result.planner, RoutePlanner::Grid);
+
+        let config = RouteEngineConfig {
+            seed: Some(7),
+            ..config
+        };
+        let result = optimize_flight_path_rrt(&waypoints, &grid, &[], &config);
+        assert!(result.success, "{:?}", result.errors);
+        assert_eq!(result.planner, RoutePlanner::RrtStar);
+        // The same seed samples the same tree.
+        let replay = optimize_flight_path_rrt(&waypoints, &grid, &[], &config);
+        let points = |result: &RouteEngineResult| -> Vec<(u64, u64, u64)> {
+            result
+                .waypoints
+                .iter()
+                .map(|wp| (wp.lat.to_bits(), wp.lon.to_bits(), wp.altitude_m.to_bits()))
+                .collect()
+        };
+        assert_eq!(points(&replay), points(&result));
+        assert!(result.stats.unwrap().max_altitude >= 80.0);
+        let phases: Vec<&str> = result
+            .waypoints
+            .iter()
+            .filter_map(|wp| wp.phase.as_deref())
+            .collect();
+        assert_eq!(phases.first(), Some(&"GROUND_START"));
+        assert_eq!(phases.last(), Some(&"GROUND_END"));
+        // Cruise legs keep to the climb gradient; the vertical ends are exempt.
+        let cruise = &result.waypoints[1..result.waypoints.len() - 1];
+        for pair in cruise.windows(2) {
+            let run_m = haversine_distance(pair[0].lat, pair[0].lon, pair[1].lat, pair[1].lon);
+            let climb_m = pair[1].altitude_m - pair[0].altitude_m;
+            assert!(climb_m <= 0.2 * run_m + 0.01, "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn polygon_obstacles_block_their_footprint_not_a_circle_around_it() {
+        let corner = |north_m: f64, east_m: f64| {
+            let (lat, lon) = crate::spatial::offset_position(33.0, -117.0, north_m, east_m);
+            [lat, lon]
+        };
+        // A 20 m x 200 m building 30 m east of a northbound route.
+        let [lat, lon] = corner(500.0, 40.0);
+        let building = RouteObstacle {
+            lat,
+            lon,
+            radius_m: 10.0,
+            height_m: Some(80.0),
+            polygon: Some(vec![
+                corner(400.0, 30.0),
+                corner(400.0, 50.0),
+                corner(600.0, 50.0),
+                corner(600.0, 30.0),
+            ]),
+        };
+        let [lat, lon] = corner(450.0, 45.0);
+        assert_eq!(building.distance_m(lat, lon), 0.0);
+        let [lat, lon] = corner(500.0, 0.0);
+        assert!((building.distance_m(lat, lon) - 20.0).abs() < 0.5);
+
+        let lane_offsets = build_lane_offsets(40.0, 20.0);
+        let center_lane = lane_offsets.len() / 2;
+        let heights = |obstacle: &RouteObstacle| {
+            let mut grid =
+                generate_grid_samples(&northbound(1_000.0, 30.0), 25.0, &lane_offsets, 0.0)
+                    .unwrap();
+            apply_obstacles(&mut grid, std::slice::from_ref(obstacle), |_, _| 0.0);
+            grid.lanes
+                .iter()
+                .map(|lane| {
+                    lane.iter()
+                        .map(|point| point.obstacle_height_m)
+                        .fold(0.0, f64::max)
+                })
+                .collect::<Vec<f64>>()
+        };
+        let lane_heights = heights(&building);
+        assert_eq!(lane_heights[center_lane], 0.0, "{:?}", lane_heights);
+        assert!(lane_heights.contains(&80.0), "{:?}", lane_heights);
+
+        // The same building as a circle enclosing its footprint blocks the route itself.
+        let circle = RouteObstacle {
+            radius_m: 110.0,
+            polygon: None,
+            ..building
+        };
+        assert_eq!(heights(&circle)[center_lane], 80.0);
+    }
+
+    #[test]
+    fn any_angle_search_flies_straight_legs() {
+        let waypoints = northbound(1_000.0, 60.0);
+        let open_grid =
+            generate_grid_samples(&waypoints, 10.0, &build_lane_offsets(100.0, 10.0), 0.0).unwrap();
+        let plan = |grid: &RouteGrid, search: RouteSearch| {
+            let config = RouteEngineConfig {
+                search,
+                ..Default::default()
+            };
+            let result = optimize_airborne_path(&waypoints, grid, &[], &config, Some(60.0));
+            assert!(result.success, "{:?}", result.errors);
+            result
+        };
+        let length_m = |result: &RouteEngineResult| -> f64 {
+            result
+                .waypoints
+                .windows(2)
+                .map(|pair| haversine_distance(pair[0].lat, pair[0].lon, pair[1].lat, pair[1].lon))
+                .sum()
+        };
+
+        // Light weather over the middle of the route: the grid search's shortcuts stop at its
+        // edge, while a straight leg through it costs no more than the steps it replaces.
+        let mut weather = open_grid.clone();
+        let (lat, lon) = crate::spatial::offset_position(33.0, -117.0, 500.0, 30.0);
+        for point in weather.lanes.iter_mut().flatten() {
+            if haversine_distance(point.lat, point.lon, lat, lon) < 150.0 {
+                point.weather_cost = 2.0;
+            }
+        }
+        let grid_result = plan(&weather, RouteSearch::Grid);
+        let any_angle = plan(&weather, RouteSearch::AnyAngle);
+        assert_eq!(any_angle.optimized_points, 2, "{:?}", any_angle.waypoints);
+        assert!(grid_result.optimized_points > any_angle.optimized_points);
+
+        // Round a mast just off the route, the legs run tangent to it instead of along lanes.
+        let mut mast = open_grid.clone();
+        let (lat, lon) = crate::spatial::offset_position(33.0, -117.0, 500.0, 10.0);
+        apply_obstacles(
+            &mut mast,
+            &[RouteObstacle {
+                lat,
+                lon,
+                radius_m: 40.0,
+                height_m: Some(200.0),
+                polygon: None,
+            }],
+            |_, _| 0.0,
+        );
+        let grid_result = plan(&mast, RouteSearch::Grid);
+        let any_angle = plan(&mast, RouteSearch::AnyAngle);
+        assert_eq!(any_angle.optimized_points, 3, "{:?}", any_angle.waypoints);
+        assert!(length_m(&any_angle) < length_m(&grid_result) - 1.0);
+    }
+
+    #[test]
+    fn searches_stop_when_their_budget_runs_out() {
+        let waypoints = northbound(1_000.0, 60.0);
+        let grid =
+            generate_grid_samples(&waypoints, 10.0, &build_lane_offsets(100.0, 10.0), 0.0).unwrap();
+        let budget = SearchBudget::new(None);
+        let config = RouteEngineConfig {
+            budget: budget.clone(),
+            ..Default::default()
+        };
+        assert!(optimize_airborne_path(&waypoints, &grid, &[], &config, Some(60.0)).success);
+
+        // Cancelling a clone stops the search that holds the budget.
+        budget.cancel();
+        let result = optimize_airborne_path(&waypoints, &grid, &[], &config, Some(60.0));
+        assert!(!result.success);
+        assert_eq!(result.errors, [SEARCH_TIMED_OUT]);
+
+        let expired = RouteEngineConfig {
+            budget: SearchBudget::new(Some(Duration::ZERO)),
+            ..Default::default()
+        };
+        assert!(expired.budget.deadline_passed());
+        assert!(!optimize_flight_path(&waypoints, &grid, &[], &expired).success);
+    }
+
+    #[test]
+    fn cruise_corners_are_rounded_to_the_turn_radius() {
+        let at = |north_m: f64, east_m: f64| {
+            let (lat, lon) = crate::spatial::offset_position(33.0, -117.0, north_m, east_m);
+            Waypoint {
+                lat,
+                lon,
+                altitude_m: 60.0,
+                speed_mps: None,
+            }
+        };
+        // North 1 km, then a right angle east for 1 km.
+        let waypoints = vec![at(0.0, 0.0), at(1_000.0, 0.0), at(1_000.0, 1_000.0)];
+        let route: Vec<RouteEngineWaypoint> = waypoints
+            .iter()
+            .map(|wp| RouteEngineWaypoint {
+                lat: wp.lat,
+                lon: wp.lon,
+                altitude_m: wp.altitude_m,
+                phase: Some("CRUISE".to_string()),
+            })
+            .collect();
+        let round = |turn_radius_m: f64, obstacles: &[RouteObstacle]| {
+            let mut grid =
+                generate_grid_samples(&waypoints, 10.0, &build_lane_offsets(100.0, 10.0), 0.0)
+                    .unwrap();
+            apply_obstacles(&mut grid, obstacles, |_, _| 0.0);
+            let config = RouteEngineConfig {
+                turn_radius_m,
+                ..Default::default()
+            };
+            round_corners(route.clone(), &grid, &[], &config)
+        };
+        // Largest heading change between consecutive legs, in degrees.
+        let sharpest_turn = |route: &[RouteEngineWaypoint]| {
+            let headings: Vec<f64> = route
+                .windows(2)
+                .map(|pair| bearing(pair[0].lat, pair[0].lon, pair[1].lat, pair[1].lon))
+                .collect();
+            headings
+                .windows(2)
+                .map(|pair| {
+                    let turn = (pair[1] - pair[0]).to_degrees();
+                    ((turn + 180.0).rem_euclid(360.0) - 180.0).abs()
+                })
+                .fold(0.0, f64::max)
+        };
+        let corner = at(1_000.0, 0.0);
+        let closest_to_corner = |route: &[RouteEngineWaypoint]| {
+            route
+                .iter()
+                .map(|wp| haversine_distance(wp.lat, wp.lon, corner.lat, corner.lon))
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        assert_eq!(round(0.0, &[]).len(), 3);
+        let rounded = round(150.0, &[]);
+        assert!(sharpest_turn(&rounded) < 12.0, "{:?}", rounded);
+        // The arc leaves the first leg 150 m before the corner and passes ~62 m inside it.
+        let turn_in = at(850.0, 0.0);
+        assert!(haversine_distance(rounded[1].lat, rounded[1].lon, turn_in.lat, turn_in.lon) < 1.0);
+        let inside_m = closest_to_corner(&rounded);
+        assert!(
+            (inside_m - 150.0 * (2.0_f64.sqrt() - 1.0)).abs() < 3.0,
+            "{}",
+            inside_m
+        );
+
+        // A mast inside the corner tightens the arc to pass outside it.
+        let mast = at(950.0, 50.0);
+        let obstacle = RouteObstacle {
+            lat: mast.lat,
+            lon: mast.lon,
+            radius_m: 10.0,
+            height_m: Some(200.0),
+            polygon: None,
+        };
+        let tightened = round(150.0, std::slice::from_ref(&obstacle));
+        assert!(sharpest_turn(&tightened) < 12.0, "{:?}", tightened);
+        assert!(closest_to_corner(&tightened) < inside_m - 20.0);
+        assert!(tightened
+            .iter()
+            .all(|wp| obstacle.distance_m(wp.lat, wp.lon) > 5.0));
+
+        // A leg too short for the radius gets the widest arc that fits in half of it.
+        let mut short = route.clone();
+        let end = at(1_000.0, 100.0);
+        (short[2].lat, short[2].lon) = (end.lat, end.lon);
+        let grid =
+            generate_grid_samples(&waypoints, 10.0, &build_lane_offsets(100.0, 10.0), 0.0).unwrap();
+        let config = RouteEngineConfig {
+            turn_radius_m: 150.0,
+            ..Default::default()
+        };
+        let short = round_corners(short, &grid, &[], &config);
+        let turn_in = at(950.0, 0.0);
+        assert!(haversine_distance(short[1].lat, short[1].lon, turn_in.lat, turn_in.lon) < 1.0);
+    }
+
+    #[test]
+    fn corridor_volumes_follow_each_leg_in_space_and_time() {
+        let at = |north_m: f64, altitude_m: f64| {
+            let (lat, lon) = crate::spatial::offset_position(33.0, -117.0, north_m, 0.0);
+            Waypoint {
+                lat,
+                lon,
+                altitude_m,
+                speed_mps: None,
+            }
+        };
+        let mut cruise_end = at(1_500.0, 60.0);
+        cruise_end.speed_mps = Some(10.0);
+        let route = [
+            at(0.0, 0.0),
+            at(0.0, 60.0),
+            at(0.0, 60.0),
+            cruise_end,
+            at(1_500.0, 0.0),
+        ];
+        let corridor = CorridorConfig::default();
+        let volumes = route_corridor(&route, 1_000.0, &RouteEngineConfig::default(), &corridor);
+
+        // The repeated waypoint adds no volume.
+        assert_eq!(volumes.len(), 3);
+        let (climb, cruise, descent) = (&volumes[0], &volumes[1], &volumes[2]);
+        // 60 m at 2 m/s, 1500 m at the waypoint's 10 m/s, then 60 m down at 3 m/s.
+        assert_eq!(climb.time_start_s, 1_000.0 - corridor.time_buffer_s);
+        assert!((climb.time_end_s - (1_030.0 + corridor.time_buffer_s)).abs() < 1e-6);
+        assert!((cruise.time_end_s - (1_180.0 + corridor.time_buffer_s)).abs() < 0.1);
+        assert!((descent.time_end_s - (1_200.0 + corridor.time_buffer_s)).abs() < 0.1);
+        assert_eq!(climb.altitude_lower_m, 0.0);
+        assert_eq!(climb.altitude_upper_m, 75.0);
+        assert_eq!(
+            (cruise.altitude_lower_m, cruise.altitude_upper_m),
+            (45.0, 75.0)
+        );
+
+        let inside = |volume: &CorridorVolume, north_m: f64, east_m: f64| {
+            let (lat, lon) = crate::spatial::offset_position(33.0, -117.0, north_m, east_m);
+            point_in_polygon(lat, lon, &volume.polygon)
+        };
+        assert_eq!(cruise.polygon.first(), cruise.polygon.last());
+        assert!(inside(cruise, 750.0, 25.0) && inside(cruise, 1_520.0, -25.0));
+        assert!(!inside(cruise, 750.0, 35.0) && !inside(cruise, 1_540.0, 0.0));
+        assert!(inside(climb, 20.0, -20.0) && !inside(climb, 0.0, 40.0));
+    }
 }
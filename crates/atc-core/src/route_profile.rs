@@ -0,0 +1,177 @@
+//! Along-track elevation profile for planned routes.
+//!
+//! Samples a route at a fixed spacing and reports terrain, route altitude, AGL and the nearest
+//! obstacle at each station so clients can draw a side-view chart without refetching terrain.
+
+use crate::route_engine::{RouteEngineWaypoint, RouteObstacle};
+use crate::spatial::{bearing, haversine_distance, offset_by_bearing};
+use serde::{Deserialize, Serialize};
+
+/// Obstacles farther than this from a station (edge distance) are not reported.
+pub const PROFILE_OBSTACLE_WINDOW_M: f64 = 200.0;
+
+/// One sample of the route elevation profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteProfileStation {
+    /// Along-track distance from the route start (meters).
+    pub distance_m: f64,
+    pub lat: f64,
+    pub lon: f64,
+    /// Terrain elevation at the station (meters).
+    pub terrain_m: f64,
+    /// Planned route altitude at the station (meters).
+    pub altitude_m: f64,
+    pub agl_m: f64,
+    /// Height of the nearest obstacle above its ground, if one is within the window.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub obstacle_height_m: Option<f64>,
+    /// Top of the nearest obstacle in the route altitude reference.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub obstacle_top_m: Option<f64>,
+    /// Horizontal distance from the station to the nearest obstacle footprint (0 when inside).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub obstacle_distance_m: Option<f64>,
+}
+
+/// Build an along-track profile. Every route vertex is a station; legs are sampled every
+/// `spacing_m` (widened so the profile never exceeds `max_stations`).
+pub fn build_route_profile<F>(
+    waypoints: &[RouteEngineWaypoint],
+    obstacles: &[RouteObstacle],
+    terrain_height: F,
+    spacing_m: f64,
+    max_stations: usize,
+) -> Vec<RouteProfileStation>
+where
+    F: Fn(f64, f64) -> f64,
+{
+    if waypoints.is_empty() {
+        return Vec::new();
+    }
+    let total_m: f64 = waypoints
+        .windows(2)
+        .map(|pair| haversine_distance(pair[0].lat, pair[0].lon, pair[1].lat, pair[1].lon))
+        .sum();
+    let mut spacing_m = if spacing_m.is_finite() && spacing_m > 0.0 {
+        spacing_m
+    } else {
+        25.0
+    };
+    if max_stations > 1 {
+        // Leave room for the vertices themselves.
+        let budget = max_stations.saturating_sub(waypoints.len()).max(1);
+        spacing_m = spacing_m.max(total_m / budget as f64);
+    }
+
+    let station = |distance_m: f64, lat: f64, lon: f64, altitude_m: f64| {
+        let terrain_m = terrain_height(lat, lon);
+        let nearest = obstacles
+            .iter()
+            .map(|obstacle| (obstacle, obstacle.distance_m(lat, lon)))
+            .filter(|(_, edge)| *edge <= PROFILE_OBSTACLE_WINDOW_M)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        let (obstacle_height_m, obstacle_top_m, obstacle_distance_m) = match nearest {
+            Some((obstacle, edge)) => {
+                let height = obstacle.height_m.unwrap_or(0.0).max(0.0);
+                let ground = terrain_height(obstacle.lat, obstacle.lon);
+                (Some(height), Some(ground + height), Some(edge))
+            }
+            None => (None, None, None),
+        };
+        RouteProfileStation {
+            distance_m,
+            lat,
+            lon,
+            terrain_m,
+            altitude_m,
+            agl_m: altitude_m - terrain_m,
+            obstacle_height_m,
+            obstacle_top_m,
+            obstacle_distance_m,
+        }
+    };
+
+    let first = &waypoints[0];
+    let mut stations = vec![station(0.0, first.lat, first.lon, first.altitude_m)];
+    let mut travelled_m = 0.0;
+    for pair in waypoints.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        let leg_m = haversine_distance(start.lat, start.lon, end.lat, end.lon);
+        if leg_m > f64::EPSILON {
+            let heading = bearing(start.lat, start.lon, end.lat, end.lon);
+            let steps = (leg_m / spacing_m).ceil().max(1.0) as usize;
+            for step in 1..steps {
+                let frac = step as f64 / steps as f64;
+                let (lat, lon) = offset_by_bearing(start.lat, start.lon, leg_m * frac, heading);
+                let altitude_m = start.altitude_m + frac * (end.altitude_m - start.altitude_m);
+                stations.push(station(travelled_m + leg_m * frac, lat, lon, altitude_m));
+            }
+        }
+        travelled_m += leg_m;
+        stations.push(station(travelled_m, end.lat, end.lon, end.altitude_m));
+    }
+    stations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wp(lat: f64, lon: f64, altitude_m: f64) -> RouteEngineWaypoint {
+        RouteEngineWaypoint {
+            lat,
+            lon,
+            altitude_m,
+            phase: None,
+        }
+    }
+
+    #[test]
+    fn samples_legs_and_keeps_vertical_segments() {
+        let waypoints = vec![
+            wp(33.0, -117.0, 10.0),
+            wp(33.0, -117.0, 60.0),
+            wp(33.001, -117.0, 60.0),
+        ];
+        let profile = build_route_profile(&waypoints, &[], |_, _| 10.0, 25.0, 1000);
+
+        assert_eq!(profile.first().unwrap().agl_m, 0.0);
+        assert_eq!(profile[1].distance_m, 0.0);
+        assert_eq!(profile[1].altitude_m, 60.0);
+        let last = profile.last().unwrap();
+        assert!((last.distance_m - 111.2).abs() < 0.5);
+        assert_eq!(last.agl_m, 50.0);
+        // 111m leg at 25m spacing = 5 steps.
+        assert_eq!(profile.len(), 2 + 5);
+        assert!(profile
+            .windows(2)
+            .all(|pair| pair[1].distance_m >= pair[0].distance_m));
+    }
+
+    #[test]
+    fn reports_nearest_obstacle_within_window() {
+        let waypoints = vec![wp(33.0, -117.0, 60.0), wp(33.002, -117.0, 60.0)];
+        let (lat, lon) = offset_by_bearing(33.0, -117.0, 50.0, std::f64::consts::FRAC_PI_2);
+        let obstacles = vec![RouteObstacle {
+            lat,
+            lon,
+            radius_m: 10.0,
+            height_m: Some(25.0),
+            polygon: None,
+        }];
+        let profile = build_route_profile(&waypoints, &obstacles, |_, _| 5.0, 50.0, 1000);
+
+        let start = &profile[0];
+        assert_eq!(start.obstacle_height_m, Some(25.0));
+        assert_eq!(start.obstacle_top_m, Some(30.0));
+        assert!((start.obstacle_distance_m.unwrap() - 40.0).abs() < 0.5);
+        assert!(profile.last().unwrap().obstacle_height_m.is_none());
+    }
+
+    #[test]
+    fn caps_station_count() {
+        let waypoints = vec![wp(33.0, -117.0, 60.0), wp(33.1, -117.0, 60.0)];
+        let profile = build_route_profile(&waypoints, &[], |_, _| 0.0, 5.0, 100);
+        assert!(profile.len() <= 100);
+    }
+}
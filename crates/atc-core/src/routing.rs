@@ -1,7 +1,11 @@
 //! Simple route suggestion logic.
 
 use crate::models::Waypoint;
-use rand::Rng;
+use crate::performance::DronePerformance;
+use crate::rules::SafetyRules;
+use crate::spatial::{bearing, offset_by_bearing};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 const IRVINE_LAT: f64 = 33.6846;
@@ -83,17 +87,99 @@ pub fn generate_route_options(start: Waypoint, end: Waypoint, altitude_m: f64) -
     ]
 }
 
+/// Detours of a route for a planner to try as alternatives to it.
+///
+/// Each detour bends the route through a via point beside its halfway mark: `offset_m` to the
+/// left, then to the right, then twice as far to each side, and so on, `count` in all.
+pub fn generate_detour_options(
+    waypoints: &[Waypoint],
+    count: usize,
+    offset_m: f64,
+) -> Vec<RouteOption> {
+    let legs: Vec<f64> = waypoints
+        .windows(2)
+        .map(|leg| haversine_distance(leg[0].lat, leg[0].lon, leg[1].lat, leg[1].lon))
+        .collect();
+    let total_m: f64 = legs.iter().sum();
+    if total_m <= f64::EPSILON || !offset_m.is_finite() || offset_m <= 0.0 {
+        return Vec::new();
+    }
+
+    // Leg containing the halfway mark, and how far along it that is.
+    let mut remaining_m = total_m / 2.0;
+    let mut leg_idx = 0;
+    while leg_idx + 1 < legs.len() && remaining_m > legs[leg_idx] {
+        remaining_m -= legs[leg_idx];
+        leg_idx += 1;
+    }
+    let (from, to) = (&waypoints[leg_idx], &waypoints[leg_idx + 1]);
+    let t = if legs[leg_idx] > 0.0 {
+        (remaining_m / legs[leg_idx]).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let track = bearing(from.lat, from.lon, to.lat, to.lon);
+    let (mid_lat, mid_lon) = (
+        from.lat + (to.lat - from.lat) * t,
+        from.lon + (to.lon - from.lon) * t,
+    );
+    let altitude_m = from.altitude_m + (to.altitude_m - from.altitude_m) * t;
+    let duration_secs = (total_m / DEFAULT_SPEED) as u32;
+
+    (0..count)
+        .map(|idx| {
+            let step = idx / 2 + 1;
+            let (side, turn) = if idx % 2 == 0 {
+                ("left", -std::f64::consts::FRAC_PI_2)
+            } else {
+                ("right", std::f64::consts::FRAC_PI_2)
+            };
+            let distance_m = offset_m * step as f64;
+            let (lat, lon) = offset_by_bearing(mid_lat, mid_lon, distance_m, track + turn);
+            let mut detour = waypoints.to_vec();
+            detour.insert(
+                leg_idx + 1,
+                Waypoint {
+                    lat,
+                    lon,
+                    altitude_m,
+                    speed_mps: to.speed_mps,
+                },
+            );
+            RouteOption {
+                option_id: format!("{}_{}", side, step),
+                name: format!("Detour {} {}", side, step),
+                description: format!("Via a point {:.0}m {} of the route", distance_m, side),
+                waypoints: detour,
+                estimated_duration_secs: duration_secs,
+                conflict_risk: ConflictRisk::Low,
+            }
+        })
+        .collect()
+}
+
 /// Generate a random route with a start and end point near Irvine.
 pub fn generate_random_route() -> Vec<Waypoint> {
-    let start = random_point_near_irvine();
-    let end = random_point_near_irvine();
+    generate_seeded_route(random_seed())
+}
+
+/// Generate the random route for `seed`; the same seed always gives the same route.
+pub fn generate_seeded_route(seed: u64) -> Vec<Waypoint> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let start = random_point_near_irvine(&mut rng);
+    let end = random_point_near_irvine(&mut rng);
 
     // Simple 2-point route
     vec![start, end]
 }
 
-fn random_point_near_irvine() -> Waypoint {
-    let mut rng = rand::rng();
+/// A fresh seed for [`generate_seeded_route`] or `RouteEngineConfig::seed`, to record with the
+/// plan it produced.
+pub fn random_seed() -> u64 {
+    rand::rng().random()
+}
+
+fn random_point_near_irvine(rng: &mut impl Rng) -> Waypoint {
     let lat_offset = rng.random_range(-RADIUS_DEG..RADIUS_DEG);
     let lon_offset = rng.random_range(-RADIUS_DEG..RADIUS_DEG);
 
@@ -110,6 +196,11 @@ use crate::spatial::haversine_distance;
 
 // ==== Conflict Avoidance ====
 
+/// Altitude change flown by a vertical avoidance.
+const VERTICAL_OFFSET_M: f64 = 30.0;
+/// Share of the lateral and vertical offsets flown by a combined avoidance.
+const COMBINED_OFFSET_SCALE: f64 = 0.7;
+
 /// Type of avoidance maneuver to perform.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -143,7 +234,6 @@ pub fn generate_avoidance_route(
 
     // Constants for avoidance (in METERS, not degrees)
     const LATERAL_OFFSET_M: f64 = 100.0; // 100m lateral offset
-    const VERTICAL_OFFSET_M: f64 = 30.0; // 30m altitude change
     const BUFFER_DISTANCE_M: f64 = 50.0; // 50m before/after conflict point
 
     // Calculate bearing from current to destination (radians, 0 = north)
@@ -224,9 +314,13 @@ pub fn generate_avoidance_route(
             ]
         }
         AvoidanceType::Vertical => {
-            let new_altitude = current_pos.altitude_m + VERTICAL_OFFSET_M;
+            // The conflict point lies between the two drones, so it is above the drone exactly
+            // when the other drone is.
+            let new_altitude = current_pos.altitude_m
+                + VERTICAL_OFFSET_M
+                    * vertical_avoidance_sign(current_pos.altitude_m, conflict_point.altitude_m);
 
-            // Pre-conflict: begin climb
+            // Pre-conflict: begin altitude change
             let (pre_lat, pre_lon) = offset_position(
                 conflict_point.lat,
                 conflict_point.lon,
@@ -265,8 +359,10 @@ pub fn generate_avoidance_route(
             ]
         }
         AvoidanceType::Combined => {
-            let lateral_offset = LATERAL_OFFSET_M * 0.7;
-            let vertical_offset = VERTICAL_OFFSET_M * 0.7;
+            let lateral_offset = LATERAL_OFFSET_M * COMBINED_OFFSET_SCALE;
+            let vertical_offset = VERTICAL_OFFSET_M
+                * COMBINED_OFFSET_SCALE
+                * vertical_avoidance_sign(current_pos.altitude_m, conflict_point.altitude_m);
             let new_altitude = current_pos.altitude_m + vertical_offset;
 
             let (mid_lat, mid_lon) = offset_position(
@@ -290,11 +386,27 @@ pub fn generate_avoidance_route(
     }
 }
 
+/// Vertical avoidance moves away from the other aircraft: up unless it is above.
+fn vertical_avoidance_sign(altitude_m: f64, other_altitude_m: f64) -> f64 {
+    if other_altitude_m > altitude_m {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
 /// Determine the best avoidance type based on circumstances.
+///
+/// A vertical maneuver is only chosen when the drone can finish its altitude change before the
+/// closest point of approach, at its climb or descent rate (the default envelope's without
+/// `performance`), and stay within the rule altitude limits. Otherwise the smaller altitude
+/// change of a combined maneuver is tried, and lateral avoidance is the fallback.
 pub fn select_avoidance_type(
     altitude_m: f64,
     other_altitude_m: f64,
-    has_ceiling: bool,
+    time_to_cpa_s: f64,
+    rules: &SafetyRules,
+    performance: Option<&DronePerformance>,
 ) -> AvoidanceType {
     // If significant altitude difference exists, use lateral
     let alt_diff = (altitude_m - other_altitude_m).abs();
@@ -302,13 +414,26 @@ pub fn select_avoidance_type(
         return AvoidanceType::Lateral;
     }
 
-    // If near altitude limits, use lateral
-    if altitude_m > 100.0 || has_ceiling {
-        return AvoidanceType::Lateral;
+    let performance = performance.copied().unwrap_or_default();
+    let sign = vertical_avoidance_sign(altitude_m, other_altitude_m);
+    let rate_mps = if sign > 0.0 {
+        performance.max_climb_rate_mps
+    } else {
+        performance.descent_rate_mps()
+    };
+    let achievable = |offset_m: f64| {
+        let target_m = altitude_m + sign * offset_m;
+        (rules.min_altitude_m..=rules.max_altitude_m).contains(&target_m)
+            && offset_m <= rate_mps * time_to_cpa_s.max(0.0)
+    };
+
+    if achievable(VERTICAL_OFFSET_M) {
+        AvoidanceType::Vertical
+    } else if achievable(VERTICAL_OFFSET_M * COMBINED_OFFSET_SCALE) {
+        AvoidanceType::Combined
+    } else {
+        AvoidanceType::Lateral
     }
-
-    // Default: vertical is often simpler
-    AvoidanceType::Vertical
 }
 
 #[cfg(test)]
@@ -372,4 +497,110 @@ mod tests {
             "Should change altitude"
         );
     }
+
+    #[test]
+    fn vertical_avoidance_must_be_flyable_before_cpa() {
+        let rules = SafetyRules::default();
+        // The default envelope climbs 3 m/s: 30 m takes 10 s, the combined 21 m 7 s.
+        assert_eq!(
+            select_avoidance_type(60.0, 55.0, 20.0, &rules, None),
+            AvoidanceType::Vertical
+        );
+        assert_eq!(
+            select_avoidance_type(60.0, 55.0, 8.0, &rules, None),
+            AvoidanceType::Combined
+        );
+        assert_eq!(
+            select_avoidance_type(60.0, 55.0, 5.0, &rules, None),
+            AvoidanceType::Lateral
+        );
+        assert_eq!(
+            select_avoidance_type(60.0, 90.0, 20.0, &rules, None),
+            AvoidanceType::Lateral
+        );
+
+        // A slow climber that descends quickly only avoids vertically downwards.
+        let performance = DronePerformance {
+            max_climb_rate_mps: 1.0,
+            max_descent_rate_mps: Some(4.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            select_avoidance_type(60.0, 55.0, 20.0, &rules, Some(&performance)),
+            AvoidanceType::Lateral
+        );
+        assert_eq!(
+            select_avoidance_type(60.0, 65.0, 20.0, &rules, Some(&performance)),
+            AvoidanceType::Vertical
+        );
+
+        // Altitude margins: no room for 30 m above 100 m or below 35 m, but room for 21 m.
+        assert_eq!(
+            select_avoidance_type(100.0, 95.0, 20.0, &rules, None),
+            AvoidanceType::Combined
+        );
+        assert_eq!(
+            select_avoidance_type(35.0, 40.0, 20.0, &rules, None),
+            AvoidanceType::Combined
+        );
+        assert_eq!(
+            select_avoidance_type(25.0, 30.0, 20.0, &rules, None),
+            AvoidanceType::Lateral
+        );
+
+        // The generated route moves away from the other drone.
+        let waypoint = |lat: f64, altitude_m: f64| Waypoint {
+            lat,
+            lon: -117.82,
+            altitude_m,
+            speed_mps: Some(10.0),
+        };
+        let route = generate_avoidance_route(
+            &waypoint(33.68, 60.0),
+            &waypoint(33.69, 60.0),
+            &waypoint(33.685, 62.5),
+            AvoidanceType::Vertical,
+        );
+        assert_eq!(route[1].altitude_m, 30.0);
+    }
+
+    #[test]
+    fn detour_options_alternate_sides_of_the_route() {
+        let waypoint = |lat: f64, lon: f64| Waypoint {
+            lat,
+            lon,
+            altitude_m: 60.0,
+            speed_mps: None,
+        };
+        // Due north, 2 km.
+        let route = vec![waypoint(33.0, -117.0), waypoint(33.018, -117.0)];
+        let options = generate_detour_options(&route, 3, 200.0);
+        let ids: Vec<&str> = options.iter().map(|o| o.option_id.as_str()).collect();
+        assert_eq!(ids, ["left_1", "right_1", "left_2"]);
+
+        for (option, east_m) in options.iter().zip([-200.0_f64, 200.0, -400.0]) {
+            assert_eq!(option.waypoints.len(), 3);
+            let via = &option.waypoints[1];
+            assert!((via.lat - 33.009).abs() < 1e-4, "{}", via.lat);
+            let offset_m = haversine_distance(via.lat, via.lon, via.lat, -117.0);
+            assert!((offset_m - east_m.abs()).abs() < 1.0, "{}", offset_m);
+            assert_eq!(via.lon > -117.0, east_m > 0.0);
+            assert_eq!(via.altitude_m, 60.0);
+        }
+        assert!(generate_detour_options(&route[..1], 2, 200.0).is_empty());
+    }
+
+    #[test]
+    fn seeded_routes_are_reproducible() {
+        let route = generate_seeded_route(42);
+        let replay = generate_seeded_route(42);
+        assert_eq!(route.len(), 2);
+        for (a, b) in route.iter().zip(&replay) {
+            assert_eq!((a.lat, a.lon), (b.lat, b.lon));
+            assert!((a.lat - IRVINE_LAT).abs() <= RADIUS_DEG);
+            assert!((a.lon - IRVINE_LON).abs() <= RADIUS_DEG);
+        }
+        let other = generate_seeded_route(43);
+        assert_ne!((route[0].lat, route[0].lon), (other[0].lat, other[0].lon));
+    }
 }
@@ -2,6 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::conflict::{ConflictDetector, DetectionMode};
+use crate::crewed_traffic::CrewedProtection;
+use crate::well_clear::WellClearParams;
+
 /// Configuration for safety rules.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyRules {
@@ -13,6 +17,19 @@ pub struct SafetyRules {
     pub lookahead_seconds: f64,
     /// Multiplier for warning threshold (warning at separation * multiplier)
     pub warning_multiplier: f64,
+    /// Horizontal warning multiplier; defaults to `warning_multiplier`
+    #[serde(default)]
+    pub warning_horizontal_multiplier: Option<f64>,
+    /// Vertical warning multiplier; defaults to `warning_multiplier`
+    #[serde(default)]
+    pub warning_vertical_multiplier: Option<f64>,
+    /// Horizontal info band multiplier (beyond the warning band); an unset axis uses its
+    /// warning multiplier, and leaving both unset disables info conflicts
+    #[serde(default)]
+    pub info_horizontal_multiplier: Option<f64>,
+    /// Vertical info band multiplier
+    #[serde(default)]
+    pub info_vertical_multiplier: Option<f64>,
     /// Timeout before drone is marked as lost (seconds)
     pub drone_timeout_secs: u64,
     /// Maximum allowed altitude in meters
@@ -21,6 +38,15 @@ pub struct SafetyRules {
     pub min_altitude_m: f64,
     /// Altitude bands for separation (each band is min_vertical_separation_m tall)
     pub altitude_bands: Vec<AltitudeBand>,
+    /// Separation overrides inside specific airspace volumes (first match wins)
+    #[serde(default)]
+    pub volume_rules: Vec<VolumeSeparationRule>,
+    /// Use the DAA well-clear model instead of the separation minima when set
+    #[serde(default)]
+    pub well_clear: Option<WellClearParams>,
+    /// Protection volume around crewed traffic; `None` separates it like a drone
+    #[serde(default)]
+    pub crewed_protection: Option<CrewedProtection>,
 }
 
 impl Default for SafetyRules {
@@ -30,6 +56,10 @@ impl Default for SafetyRules {
             min_vertical_separation_m: 30.0,
             lookahead_seconds: 20.0,
             warning_multiplier: 2.0,
+            warning_horizontal_multiplier: None,
+            warning_vertical_multiplier: None,
+            info_horizontal_multiplier: None,
+            info_vertical_multiplier: None,
             drone_timeout_secs: 10,
             max_altitude_m: 121.0, // FAA Part 107 limit (~400ft)
             min_altitude_m: 10.0,
@@ -50,7 +80,65 @@ impl Default for SafetyRules {
                     max_m: 121.0,
                 },
             ],
+            volume_rules: Vec::new(),
+            well_clear: None,
+            crewed_protection: Some(CrewedProtection::default()),
+        }
+    }
+}
+
+impl SafetyRules {
+    /// Conflict definition the detector should use under these rules.
+    pub fn detection_mode(&self) -> DetectionMode {
+        self.well_clear
+            .map(DetectionMode::WellClear)
+            .unwrap_or_default()
+    }
+
+    /// Horizontal and vertical warning multipliers.
+    pub fn warning_multipliers(&self) -> (f64, f64) {
+        (
+            self.warning_horizontal_multiplier
+                .unwrap_or(self.warning_multiplier),
+            self.warning_vertical_multiplier
+                .unwrap_or(self.warning_multiplier),
+        )
+    }
+
+    /// Horizontal and vertical info band multipliers, never inside the warning band; `None`
+    /// when no info band is configured.
+    pub fn info_multipliers(&self) -> Option<(f64, f64)> {
+        if self.info_horizontal_multiplier.is_none() && self.info_vertical_multiplier.is_none() {
+            return None;
         }
+        let (warning_horizontal, warning_vertical) = self.warning_multipliers();
+        Some((
+            self.info_horizontal_multiplier
+                .unwrap_or(warning_horizontal)
+                .max(warning_horizontal),
+            self.info_vertical_multiplier
+                .unwrap_or(warning_vertical)
+                .max(warning_vertical),
+        ))
+    }
+
+    /// Conflict detector configured with these minima, alert bands and detection mode.
+    pub fn conflict_detector(&self) -> ConflictDetector {
+        let mut detector = ConflictDetector::new(
+            self.lookahead_seconds,
+            self.min_horizontal_separation_m,
+            self.min_vertical_separation_m,
+            self.warning_multiplier,
+        );
+        let (warning_horizontal, warning_vertical) = self.warning_multipliers();
+        detector.set_alert_bands(
+            warning_horizontal,
+            warning_vertical,
+            self.info_multipliers(),
+        );
+        detector.set_detection_mode(self.detection_mode());
+        detector.set_crewed_protection(self.crewed_protection);
+        detector
     }
 }
 
@@ -60,3 +148,106 @@ pub struct AltitudeBand {
     pub min_m: f64,
     pub max_m: f64,
 }
+
+/// Conflict thresholds applied inside an airspace volume instead of the global rules.
+///
+/// The volume is the polygon and altitude limits of the referenced geofence, e.g. tighter
+/// minima inside a corridor. Unset fields fall back to the global value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VolumeSeparationRule {
+    pub geofence_id: String,
+    #[serde(default)]
+    pub min_horizontal_separation_m: Option<f64>,
+    #[serde(default)]
+    pub min_vertical_separation_m: Option<f64>,
+    #[serde(default)]
+    pub lookahead_seconds: Option<f64>,
+    #[serde(default)]
+    pub warning_multiplier: Option<f64>,
+    /// Per-axis warning multipliers; take precedence over `warning_multiplier`.
+    #[serde(default)]
+    pub warning_horizontal_multiplier: Option<f64>,
+    #[serde(default)]
+    pub warning_vertical_multiplier: Option<f64>,
+}
+
+/// Runtime-tuned values applied on top of the configured rules; unset fields keep the
+/// configured value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SafetyRulesOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_horizontal_separation_m: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_vertical_separation_m: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lookahead_seconds: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning_multiplier: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drone_timeout_secs: Option<u64>,
+}
+
+impl SafetyRulesOverride {
+    /// Problems with the tuned values, one message per field.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (field, value) in [
+            (
+                "min_horizontal_separation_m",
+                self.min_horizontal_separation_m,
+            ),
+            ("min_vertical_separation_m", self.min_vertical_separation_m),
+            ("lookahead_seconds", self.lookahead_seconds),
+        ] {
+            if value.is_some_and(|value| !value.is_finite() || value <= 0.0) {
+                errors.push(format!("{} must be a positive number", field));
+            }
+        }
+        if self
+            .warning_multiplier
+            .is_some_and(|value| !value.is_finite() || value < 1.0)
+        {
+            errors.push("warning_multiplier must be at least 1".to_string());
+        }
+        if self.drone_timeout_secs == Some(0) {
+            errors.push("drone_timeout_secs must be at least 1".to_string());
+        }
+        errors
+    }
+
+    /// `other`'s set fields replace these.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            min_horizontal_separation_m: other
+                .min_horizontal_separation_m
+                .or(self.min_horizontal_separation_m),
+            min_vertical_separation_m: other
+                .min_vertical_separation_m
+                .or(self.min_vertical_separation_m),
+            lookahead_seconds: other.lookahead_seconds.or(self.lookahead_seconds),
+            warning_multiplier: other.warning_multiplier.or(self.warning_multiplier),
+            drone_timeout_secs: other.drone_timeout_secs.or(self.drone_timeout_secs),
+        }
+    }
+
+    /// `rules` with the tuned values.
+    pub fn apply(&self, rules: &SafetyRules) -> SafetyRules {
+        let mut rules = rules.clone();
+        if let Some(value) = self.min_horizontal_separation_m {
+            rules.min_horizontal_separation_m = value;
+        }
+        if let Some(value) = self.min_vertical_separation_m {
+            rules.min_vertical_separation_m = value;
+        }
+        if let Some(value) = self.lookahead_seconds {
+            rules.lookahead_seconds = value;
+        }
+        if let Some(value) = self.warning_multiplier {
+            rules.warning_multiplier = value;
+        }
+        if let Some(value) = self.drone_timeout_secs {
+            rules.drone_timeout_secs = value;
+        }
+        rules
+    }
+}
@@ -1,8 +1,11 @@
 //! Spatial math for conflict detection and distance calculations.
 
-use crate::models::{FlightPlan, TrajectoryPoint};
+use crate::models::{FlightPlan, TrajectoryPoint, Waypoint};
 use crate::rules::SafetyRules;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::ops::ControlFlow;
 
 const TIME_SAMPLE_SECS_MIN: f64 = 0.5;
 const TIME_SAMPLE_SECS_MAX: f64 = 2.0;
@@ -33,19 +36,8 @@ pub fn check_plan_conflict_with_rules(
     }
 
     // 1. Check time overlap window
-    let start1 = new_plan.departure_time;
-    let end1 = new_plan
-        .waypoints
-        .last()
-        .map(|_| start1 + chrono::Duration::seconds(600))
-        .unwrap_or(start1);
-
-    let start2 = existing_plan.departure_time;
-    let end2 = existing_plan
-        .waypoints
-        .last()
-        .map(|_| start2 + chrono::Duration::seconds(600))
-        .unwrap_or(start2);
+    let (start1, end1) = untimed_window(new_plan);
+    let (start2, end2) = untimed_window(existing_plan);
 
     // No time overlap = no conflict
     if end1 < start2 || start1 > end2 {
@@ -99,15 +91,235 @@ pub fn check_plan_conflict_with_rules(
     false
 }
 
+/// Assumed airborne window of a plan without a timed trajectory.
+fn untimed_window(plan: &FlightPlan) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = plan.departure_time;
+    let end = plan
+        .waypoints
+        .last()
+        .map(|_| start + chrono::Duration::seconds(600))
+        .unwrap_or(start);
+    (start, end)
+}
+
+/// Why one flight plan conflicts with another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PlanConflictDetail {
+    /// Window in which both flights are airborne
+    pub overlap_start: DateTime<Utc>,
+    pub overlap_end: DateTime<Utc>,
+    /// Closest point at which separation is lost
+    pub closest_approach: ClosestApproach,
+}
+
+/// Geometry of the closest loss of separation between two plans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ClosestApproach {
+    /// When it happens; `None` when either plan has no timed trajectory
+    pub time: Option<DateTime<Utc>>,
+    pub horizontal_m: f64,
+    pub vertical_m: f64,
+    /// Where the new plan is
+    pub position: Waypoint,
+    /// Where the existing plan is
+    pub other_position: Waypoint,
+}
+
+impl ClosestApproach {
+    fn between(time: Option<DateTime<Utc>>, a: [f64; 3], b: [f64; 3]) -> Self {
+        let waypoint = |[lat, lon, altitude_m]: [f64; 3]| Waypoint {
+            lat,
+            lon,
+            altitude_m,
+            speed_mps: None,
+        };
+        Self {
+            time,
+            horizontal_m: haversine_distance(a[0], a[1], b[0], b[1]),
+            vertical_m: (a[2] - b[2]).abs(),
+            position: waypoint(a),
+            other_position: waypoint(b),
+        }
+    }
+}
+
+/// Explain a conflict found by [`check_plan_conflict_with_rules`].
+///
+/// Returns `None` exactly when that check finds no conflict. Otherwise reports when both flights
+/// are airborne and where, among the points it flags, they come closest horizontally.
+pub fn explain_plan_conflict(
+    new_plan: &FlightPlan,
+    existing_plan: &FlightPlan,
+    rules: &SafetyRules,
+) -> Option<PlanConflictDetail> {
+    let min_sep_m = rules.min_horizontal_separation_m;
+    let min_vert_sep_m = rules.min_vertical_separation_m;
+
+    if let (Some(path1), Some(path2)) =
+        (build_timed_path(new_plan), build_timed_path(existing_plan))
+    {
+        let mut closest: Option<ClosestApproach> = None;
+        let (start, end) = sample_timed_overlap(&path1, &path2, |p1, p2| {
+            let dist = haversine_distance(p1.lat, p1.lon, p2.lat, p2.lon);
+            let alt_diff = (p1.altitude_m - p2.altitude_m).abs();
+            if dist < min_sep_m
+                && alt_diff < min_vert_sep_m
+                && closest.as_ref().is_none_or(|best| dist < best.horizontal_m)
+            {
+                closest = Some(ClosestApproach::between(
+                    timestamp(p1.time_s),
+                    [p1.lat, p1.lon, p1.altitude_m],
+                    [p2.lat, p2.lon, p2.altitude_m],
+                ));
+            }
+            ControlFlow::Continue(())
+        })?;
+        return Some(PlanConflictDetail {
+            overlap_start: timestamp(start)?,
+            overlap_end: timestamp(end)?,
+            closest_approach: closest?,
+        });
+    }
+
+    let (start1, end1) = untimed_window(new_plan);
+    let (start2, end2) = untimed_window(existing_plan);
+    if end1 < start2 || start1 > end2 {
+        return None;
+    }
+
+    let new_wps = &new_plan.waypoints;
+    let existing_wps = &existing_plan.waypoints;
+    let mut closest: Option<ClosestApproach> = None;
+    let mut consider = |candidate: ClosestApproach| {
+        if closest
+            .as_ref()
+            .is_none_or(|best| candidate.horizontal_m < best.horizontal_m)
+        {
+            closest = Some(candidate);
+        }
+    };
+
+    for a in new_wps.windows(2) {
+        for b in existing_wps.windows(2) {
+            let (a1, a2, b1, b2) = (&a[0], &a[1], &b[0], &b[1]);
+            let min_dist = segment_to_segment_distance(
+                a1.lat, a1.lon, a2.lat, a2.lon, b1.lat, b1.lon, b2.lat, b2.lon,
+            );
+            let alt_overlap = altitude_ranges_overlap(
+                a1.altitude_m,
+                a2.altitude_m,
+                b1.altitude_m,
+                b2.altitude_m,
+                min_vert_sep_m,
+            );
+            if min_dist < min_sep_m && alt_overlap {
+                let (s, t) = closest_segment_fractions(a1, a2, b1, b2);
+                consider(ClosestApproach::between(
+                    None,
+                    lerp_waypoint(a1, a2, s),
+                    lerp_waypoint(b1, b2, t),
+                ));
+            }
+        }
+    }
+    for wp1 in new_wps {
+        for wp2 in existing_wps {
+            let dist = haversine_distance(wp1.lat, wp1.lon, wp2.lat, wp2.lon);
+            let alt_diff = (wp1.altitude_m - wp2.altitude_m).abs();
+            if dist < min_sep_m && alt_diff < min_vert_sep_m {
+                consider(ClosestApproach::between(
+                    None,
+                    [wp1.lat, wp1.lon, wp1.altitude_m],
+                    [wp2.lat, wp2.lon, wp2.altitude_m],
+                ));
+            }
+        }
+    }
+
+    Some(PlanConflictDetail {
+        overlap_start: start1.max(start2),
+        overlap_end: end1.min(end2),
+        closest_approach: closest?,
+    })
+}
+
+fn timestamp(time_s: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis((time_s * 1000.0).round() as i64)
+}
+
+fn lerp_waypoint(a: &Waypoint, b: &Waypoint, t: f64) -> [f64; 3] {
+    [
+        a.lat + (b.lat - a.lat) * t,
+        a.lon + (b.lon - a.lon) * t,
+        a.altitude_m + (b.altitude_m - a.altitude_m) * t,
+    ]
+}
+
+/// Fractions along segments `a` and `b` of their horizontally closest points.
+fn closest_segment_fractions(
+    a1: &Waypoint,
+    a2: &Waypoint,
+    b1: &Waypoint,
+    b2: &Waypoint,
+) -> (f64, f64) {
+    let ref_lat = (a1.lat + a2.lat + b1.lat + b2.lat) / 4.0;
+    let ref_lon = (a1.lon + a2.lon + b1.lon + b2.lon) / 4.0;
+    let xy = |wp: &Waypoint| {
+        (
+            lon_to_meters(wp.lon - ref_lon, ref_lat),
+            lat_to_meters(wp.lat - ref_lat, ref_lat),
+        )
+    };
+    let (p, p2, q, q2) = (xy(a1), xy(a2), xy(b1), xy(b2));
+    let r = (p2.0 - p.0, p2.1 - p.1);
+    let d = (q2.0 - q.0, q2.1 - q.1);
+    let cross = |u: (f64, f64), v: (f64, f64)| u.0 * v.1 - u.1 * v.0;
+
+    // Crossing segments meet at a single point.
+    let denom = cross(r, d);
+    if denom.abs() > 1e-9 && segments_intersect_2d(p, p2, q, q2) {
+        let qp = (q.0 - p.0, q.1 - p.1);
+        return (
+            (cross(qp, d) / denom).clamp(0.0, 1.0),
+            (cross(qp, r) / denom).clamp(0.0, 1.0),
+        );
+    }
+
+    // Otherwise one of the four endpoints is closest to the other segment.
+    let project = |point: (f64, f64), start: (f64, f64), dir: (f64, f64)| {
+        let len_sq = dir.0 * dir.0 + dir.1 * dir.1;
+        if len_sq < 1e-9 {
+            return 0.0;
+        }
+        (((point.0 - start.0) * dir.0 + (point.1 - start.1) * dir.1) / len_sq).clamp(0.0, 1.0)
+    };
+    let distance = |s: f64, t: f64| {
+        let pa = (p.0 + r.0 * s, p.1 + r.1 * s);
+        let pb = (q.0 + d.0 * t, q.1 + d.1 * t);
+        (pa.0 - pb.0).hypot(pa.1 - pb.1)
+    };
+    [
+        (0.0, project(p, q, d)),
+        (1.0, project(p2, q, d)),
+        (project(q, p, r), 0.0),
+        (project(q2, p, r), 1.0),
+    ]
+    .into_iter()
+    .min_by(|(s1, t1), (s2, t2)| distance(*s1, *t1).total_cmp(&distance(*s2, *t2)))
+    .unwrap_or((0.0, 0.0))
+}
+
 #[derive(Debug, Clone)]
-struct TimedPoint {
-    time_s: f64,
-    lat: f64,
-    lon: f64,
-    altitude_m: f64,
+pub(crate) struct TimedPoint {
+    pub(crate) time_s: f64,
+    pub(crate) lat: f64,
+    pub(crate) lon: f64,
+    pub(crate) altitude_m: f64,
 }
 
-fn build_timed_path(plan: &FlightPlan) -> Option<Vec<TimedPoint>> {
+pub(crate) fn build_timed_path(plan: &FlightPlan) -> Option<Vec<TimedPoint>> {
     let trajectory = plan.trajectory_log.as_ref()?;
     let base_time = plan.departure_time.timestamp_millis() as f64 / 1000.0;
 
@@ -143,23 +355,34 @@ fn check_timed_conflict(
     min_sep_m: f64,
     min_vert_sep_m: f64,
 ) -> bool {
-    let Some(first1) = path1.first() else {
-        return false;
-    };
-    let Some(first2) = path2.first() else {
-        return false;
-    };
-    let Some(last1) = path1.last() else {
-        return false;
-    };
-    let Some(last2) = path2.last() else {
-        return false;
-    };
+    let mut conflict = false;
+    sample_timed_overlap(path1, path2, |p1, p2| {
+        let dist = haversine_distance(p1.lat, p1.lon, p2.lat, p2.lon);
+        let alt_diff = (p1.altitude_m - p2.altitude_m).abs();
+        if dist < min_sep_m && alt_diff < min_vert_sep_m {
+            conflict = true;
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    });
+    conflict
+}
 
-    let start = first1.time_s.max(first2.time_s);
-    let end = last1.time_s.min(last2.time_s);
+/// Walk both paths in step over the window they share, until `visit` breaks.
+///
+/// Returns that window (Unix seconds), or `None` when the paths do not overlap in time.
+fn sample_timed_overlap<F>(
+    path1: &[TimedPoint],
+    path2: &[TimedPoint],
+    mut visit: F,
+) -> Option<(f64, f64)>
+where
+    F: FnMut(&TimedPoint, &TimedPoint) -> ControlFlow<()>,
+{
+    let start = path1.first()?.time_s.max(path2.first()?.time_s);
+    let end = path1.last()?.time_s.min(path2.last()?.time_s);
     if start > end {
-        return false;
+        return None;
     }
 
     let step1 = derive_sample_step(path1);
@@ -177,17 +400,15 @@ fn check_timed_conflict(
         let pos2 = interpolate_position(path2, t, &mut idx2);
 
         if let (Some(p1), Some(p2)) = (pos1, pos2) {
-            let dist = haversine_distance(p1.lat, p1.lon, p2.lat, p2.lon);
-            let alt_diff = (p1.altitude_m - p2.altitude_m).abs();
-            if dist < min_sep_m && alt_diff < min_vert_sep_m {
-                return true;
+            if visit(&p1, &p2).is_break() {
+                break;
             }
         }
 
         t += step;
     }
 
-    false
+    Some((start, end))
 }
 
 fn derive_sample_step(path: &[TimedPoint]) -> f64 {
@@ -202,7 +423,11 @@ fn derive_sample_step(path: &[TimedPoint]) -> f64 {
     }
 }
 
-fn interpolate_position(path: &[TimedPoint], t: f64, index: &mut usize) -> Option<TimedPoint> {
+pub(crate) fn interpolate_position(
+    path: &[TimedPoint],
+    t: f64,
+    index: &mut usize,
+) -> Option<TimedPoint> {
     if path.is_empty() {
         return None;
     }
@@ -362,6 +587,30 @@ pub fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     2.0 * EARTH_RADIUS_M * a.sqrt().atan2((1.0 - a).sqrt())
 }
 
+/// Ray-casting point-in-polygon test for a `[lat, lon]` polygon.
+pub fn point_in_polygon(lat: f64, lon: f64, polygon: &[[f64; 2]]) -> bool {
+    let n = polygon.len();
+    if n < 3 {
+        return false;
+    }
+
+    let mut inside = false;
+    let mut j = n - 1;
+    for i in 0..n {
+        let yi = polygon[i][0];
+        let xi = polygon[i][1];
+        let yj = polygon[j][0];
+        let xj = polygon[j][1];
+
+        if ((yi > lat) != (yj > lat)) && (lon < (xj - xi) * (lat - yi) / (yj - yi) + xi) {
+            inside = !inside;
+        }
+        j = i;
+    }
+
+    inside
+}
+
 // ==== ENU (East-North-Up) Coordinate Conversion ====
 // These functions convert between meters and degrees using latitude-aware scaling.
 
@@ -621,11 +870,68 @@ mod tests {
             created_at: now,
         };
 
-        let mut rules = SafetyRules::default();
-        rules.min_horizontal_separation_m = 50.0;
+        let mut rules = SafetyRules {
+            min_horizontal_separation_m: 50.0,
+            ..SafetyRules::default()
+        };
         assert!(check_plan_conflict_with_rules(&plan1, &plan2, &rules));
 
         rules.min_horizontal_separation_m = 10.0;
         assert!(!check_plan_conflict_with_rules(&plan1, &plan2, &rules));
     }
+
+    #[test]
+    fn explain_plan_conflict_locates_the_closest_approach() {
+        let now = chrono::Utc::now();
+        let waypoint = |lat: f64, lon: f64| Waypoint {
+            lat,
+            lon,
+            altitude_m: 50.0,
+            speed_mps: None,
+        };
+        let plan = |id: &str, waypoints: Vec<Waypoint>, offset_s: i64| FlightPlan {
+            flight_id: id.to_string(),
+            drone_id: id.to_string(),
+            owner_id: None,
+            waypoints,
+            trajectory_log: None,
+            metadata: None,
+            status: crate::models::FlightStatus::Pending,
+            departure_time: now + chrono::Duration::seconds(offset_s),
+            arrival_time: None,
+            created_at: now,
+        };
+        // Two legs crossing like an "X" around (33.0005, -117.0005).
+        let new_plan = plan(
+            "new",
+            vec![waypoint(33.0, -117.0), waypoint(33.001, -116.999)],
+            0,
+        );
+        let existing = plan(
+            "existing",
+            vec![waypoint(33.001, -117.0), waypoint(33.0, -116.999)],
+            120,
+        );
+        let rules = SafetyRules::default();
+
+        let detail = explain_plan_conflict(&new_plan, &existing, &rules).expect("conflict");
+        assert_eq!(detail.overlap_start, existing.departure_time);
+        assert_eq!(
+            detail.overlap_end,
+            new_plan.departure_time + chrono::Duration::seconds(600)
+        );
+        let approach = &detail.closest_approach;
+        assert!(approach.time.is_none());
+        assert!(approach.horizontal_m < 1.0, "{}", approach.horizontal_m);
+        assert!((approach.position.lat - 33.0005).abs() < 1e-5);
+        assert!((approach.position.lon + 116.9995).abs() < 1e-5);
+
+        let later = plan(
+            "later",
+            vec![waypoint(33.001, -117.0), waypoint(33.0, -116.999)],
+            900,
+        );
+        assert!(!check_plan_conflict_with_rules(&new_plan, &later, &rules));
+        assert!(explain_plan_conflict(&new_plan, &later, &rules).is_none());
+    }
 }
@@ -0,0 +1,137 @@
+//! Cross-checking reported positions against independent surveillance.
+//!
+//! A drone's own telemetry and an external observation of it (Remote ID, ADS-B) should agree.
+//! When both are available the older fix is dead-reckoned to the newer one's timestamp and the
+//! remaining horizontal and vertical gap is measured; a gap beyond the thresholds points at GPS
+//! spoofing, a misconfigured Remote ID module, or a track attributed to the wrong drone.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conflict::DronePosition;
+use crate::spatial::{haversine_distance, offset_by_bearing};
+
+/// Limits beyond which reported and observed positions are considered to disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CrosscheckThresholds {
+    pub horizontal_m: f64,
+    pub vertical_m: f64,
+    /// Fixes further apart in time than this are not compared.
+    pub max_time_skew_s: f64,
+}
+
+impl Default for CrosscheckThresholds {
+    fn default() -> Self {
+        Self {
+            horizontal_m: 150.0,
+            vertical_m: 50.0,
+            max_time_skew_s: 10.0,
+        }
+    }
+}
+
+/// Gap between a drone's reported position and an external observation of it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SurveillanceDivergence {
+    pub horizontal_m: f64,
+    pub vertical_m: f64,
+    /// Seconds between the two fixes before alignment.
+    pub time_skew_s: f64,
+}
+
+impl SurveillanceDivergence {
+    /// Whether the gap exceeds either limit.
+    pub fn exceeds(&self, thresholds: &CrosscheckThresholds) -> bool {
+        self.horizontal_m > thresholds.horizontal_m || self.vertical_m > thresholds.vertical_m
+    }
+
+    /// How many times over its limit the worse axis is; below 1.0 the sources agree.
+    pub fn ratio(&self, thresholds: &CrosscheckThresholds) -> f64 {
+        let horizontal = self.horizontal_m / thresholds.horizontal_m.max(f64::EPSILON);
+        let vertical = self.vertical_m / thresholds.vertical_m.max(f64::EPSILON);
+        horizontal.max(vertical)
+    }
+}
+
+/// Divergence between `reported` and `observed` at a common time, or `None` when the fixes are
+/// too far apart in time (or not finite) to compare.
+pub fn crosscheck(
+    reported: &DronePosition,
+    observed: &DronePosition,
+    max_time_skew_s: f64,
+) -> Option<SurveillanceDivergence> {
+    let time_skew_s = (reported.timestamp - observed.timestamp).abs();
+    if !time_skew_s.is_finite() || time_skew_s > max_time_skew_s {
+        return None;
+    }
+    let (older, newer) = if reported.timestamp <= observed.timestamp {
+        (reported, observed)
+    } else {
+        (observed, reported)
+    };
+    let (lat, lon, altitude_m) = dead_reckon(older, time_skew_s);
+    let horizontal_m = haversine_distance(lat, lon, newer.lat, newer.lon);
+    let vertical_m = (altitude_m - newer.altitude_m).abs();
+    if !horizontal_m.is_finite() || !vertical_m.is_finite() {
+        return None;
+    }
+    Some(SurveillanceDivergence {
+        horizontal_m,
+        vertical_m,
+        time_skew_s,
+    })
+}
+
+fn dead_reckon(position: &DronePosition, dt_s: f64) -> (f64, f64, f64) {
+    let (lat, lon) = if position.speed_mps > 0.0 && dt_s > 0.0 {
+        offset_by_bearing(
+            position.lat,
+            position.lon,
+            position.speed_mps * dt_s,
+            position.heading_deg.to_radians(),
+        )
+    } else {
+        (position.lat, position.lon)
+    };
+    (lat, lon, position.altitude_m + position.velocity_z * dt_s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(lat: f64, lon: f64, altitude_m: f64, timestamp: f64) -> DronePosition {
+        let mut position =
+            DronePosition::new("D1", lat, lon, altitude_m).with_velocity(0.0, 10.0, 0.0);
+        position.timestamp = timestamp;
+        position
+    }
+
+    #[test]
+    fn aligns_fixes_in_time_before_comparing() {
+        let thresholds = CrosscheckThresholds::default();
+
+        // Northbound at 10 m/s; the observation 5 s later is 50 m further north.
+        let reported = fix(37.0, -122.0, 100.0, 1000.0);
+        let (lat, lon) = offset_by_bearing(37.0, -122.0, 50.0, 0.0);
+        let observed = fix(lat, lon, 100.0, 1005.0);
+        let divergence =
+            crosscheck(&reported, &observed, thresholds.max_time_skew_s).expect("comparable");
+        assert!(divergence.horizontal_m < 1.0, "{:?}", divergence);
+        assert!(!divergence.exceeds(&thresholds));
+
+        let (lat, lon) = offset_by_bearing(37.0, -122.0, 400.0, 90f64.to_radians());
+        let spoofed = fix(lat, lon, 100.0, 1000.0);
+        let divergence =
+            crosscheck(&spoofed, &reported, thresholds.max_time_skew_s).expect("comparable");
+        assert!(divergence.exceeds(&thresholds));
+        assert!(divergence.ratio(&thresholds) > 2.0);
+
+        let misreported_altitude = fix(37.0, -122.0, 180.0, 1000.0);
+        let divergence = crosscheck(&misreported_altitude, &reported, thresholds.max_time_skew_s)
+            .expect("comparable");
+        assert!(divergence.exceeds(&thresholds));
+
+        let stale = fix(37.0, -122.0, 100.0, 900.0);
+        assert!(crosscheck(&reported, &stale, thresholds.max_time_skew_s).is_none());
+    }
+}
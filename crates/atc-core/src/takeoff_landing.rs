@@ -0,0 +1,658 @@
+//! Takeoff and landing profiles applied at route endpoints.
+//!
+//! The route engine always emits a vertical climb at the origin and a vertical descent at the
+//! destination. These helpers replace those legs with a configurable terminal procedure
+//! (vertical, spiral climb/descent, or a sloped climb-out/approach at a fixed glide angle or
+//! climb gradient), optionally flown on a fixed heading such as a vertiport's approach path.
+
+use crate::route_engine::{RouteEngineWaypoint, RouteObstacle};
+use crate::spatial::{bearing, haversine_distance, offset_by_bearing};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const SPIRAL_POINTS_PER_TURN: usize = 12;
+/// Points closer than this to the pad are treated as directly above it.
+const PAD_HORIZONTAL_TOLERANCE_M: f64 = 1.0;
+const OBSTACLE_SAMPLE_STEP_M: f64 = 10.0;
+
+/// Lateral shape of a terminal (takeoff or landing) leg.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TerminalPath {
+    /// Straight up at the origin / straight down at the destination.
+    #[default]
+    Vertical,
+    /// Climb or descend on a circle that passes over the pad.
+    Spiral {
+        radius_m: f64,
+        climb_per_turn_m: f64,
+    },
+    /// Climb out or approach along the first/last leg at a constant angle, given either as
+    /// `glide_angle_deg` or as `climb_gradient` (height gained per meter flown).
+    Sloped {
+        #[serde(default)]
+        glide_angle_deg: f64,
+        #[serde(default)]
+        climb_gradient: Option<f64>,
+    },
+}
+
+/// Terminal procedure for one end of a route.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct TerminalProfile {
+    #[serde(flatten)]
+    pub path: TerminalPath,
+    /// Height above the pad reached vertically before any lateral motion starts (the
+    /// transition altitude).
+    #[serde(default, alias = "transition_agl_m")]
+    pub min_lateral_agl_m: f64,
+    /// Heading in degrees true of the lateral leg: flown away from the pad on takeoff and toward
+    /// it on landing. Unset, the leg follows the route's first/last leg.
+    #[serde(default)]
+    pub direction_deg: Option<f64>,
+}
+
+impl TerminalProfile {
+    pub fn is_vertical(&self) -> bool {
+        self.path == TerminalPath::Vertical
+    }
+
+    /// Validate profile parameters. `label` prefixes each message (e.g. "takeoff").
+    pub fn validate(&self, label: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !self.min_lateral_agl_m.is_finite() || !(0.0..=500.0).contains(&self.min_lateral_agl_m) {
+            errors.push(format!("{label}.min_lateral_agl_m must be within 0-500m"));
+        }
+        if let Some(direction_deg) = self.direction_deg {
+            if !direction_deg.is_finite() || !(0.0..360.0).contains(&direction_deg) {
+                errors.push(format!(
+                    "{label}.direction_deg must be within 0-360 degrees"
+                ));
+            }
+            if self.is_vertical() {
+                errors.push(format!(
+                    "{label}.direction_deg requires a spiral or sloped profile"
+                ));
+            }
+        }
+        match self.path {
+            TerminalPath::Vertical => {}
+            TerminalPath::Spiral {
+                radius_m,
+                climb_per_turn_m,
+            } => {
+                if !radius_m.is_finite() || !(5.0..=500.0).contains(&radius_m) {
+                    errors.push(format!("{label}.radius_m must be within 5-500m"));
+                }
+                if !climb_per_turn_m.is_finite() || !(1.0..=200.0).contains(&climb_per_turn_m) {
+                    errors.push(format!("{label}.climb_per_turn_m must be within 1-200m"));
+                }
+            }
+            TerminalPath::Sloped {
+                glide_angle_deg,
+                climb_gradient: Some(climb_gradient),
+            } => {
+                if glide_angle_deg != 0.0 {
+                    errors.push(format!(
+                        "{label} sets both glide_angle_deg and climb_gradient"
+                    ));
+                }
+                if !climb_gradient.is_finite() || !(0.035..=1.7).contains(&climb_gradient) {
+                    errors.push(format!("{label}.climb_gradient must be within 0.035-1.7"));
+                }
+            }
+            TerminalPath::Sloped {
+                glide_angle_deg,
+                climb_gradient: None,
+            } => {
+                if !glide_angle_deg.is_finite() || !(2.0..=60.0).contains(&glide_angle_deg) {
+                    errors.push(format!(
+                        "{label}.glide_angle_deg must be within 2-60 degrees"
+                    ));
+                }
+            }
+        }
+        errors
+    }
+
+    /// Lateral heading (radians) of the leg flown away from the pad, if fixed. Landing legs are
+    /// built outward from the pad, so their heading is the reciprocal of the approach.
+    fn outbound_heading(&self, landing: bool) -> Option<f64> {
+        let direction_deg = self.direction_deg?;
+        let direction_deg = if landing {
+            direction_deg + 180.0
+        } else {
+            direction_deg
+        };
+        Some(direction_deg.to_radians())
+    }
+}
+
+/// Takeoff/landing overrides. Unset ends fall back to the vertiport profile, then vertical.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct TakeoffLandingProfile {
+    #[serde(default)]
+    pub takeoff: Option<TerminalProfile>,
+    #[serde(default)]
+    pub landing: Option<TerminalProfile>,
+}
+
+impl TakeoffLandingProfile {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Some(takeoff) = self.takeoff.as_ref() {
+            errors.extend(takeoff.validate("takeoff"));
+        }
+        if let Some(landing) = self.landing.as_ref() {
+            errors.extend(landing.validate("landing"));
+        }
+        errors
+    }
+}
+
+/// A takeoff/landing site with its default terminal procedures.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vertiport {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub lat: f64,
+    pub lon: f64,
+    /// Route endpoints within this distance of the vertiport use its profiles.
+    pub radius_m: f64,
+    #[serde(flatten)]
+    pub profile: TakeoffLandingProfile,
+}
+
+/// Closest vertiport whose radius contains the point.
+pub fn find_vertiport(vertiports: &[Vertiport], lat: f64, lon: f64) -> Option<&Vertiport> {
+    vertiports
+        .iter()
+        .map(|vertiport| {
+            (
+                vertiport,
+                haversine_distance(vertiport.lat, vertiport.lon, lat, lon),
+            )
+        })
+        .filter(|(vertiport, distance)| *distance <= vertiport.radius_m.max(0.0))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(vertiport, _)| vertiport)
+}
+
+/// Replace the vertical takeoff and landing legs of a planned route with the given profiles.
+///
+/// The route must start with `GROUND_START`/`VERTICAL_ASCENT` and end with
+/// `VERTICAL_DESCENT`/`GROUND_END` (as produced by the planner); other routes are returned
+/// unchanged.
+pub fn apply_takeoff_landing_profile(
+    waypoints: &[RouteEngineWaypoint],
+    takeoff: &TerminalProfile,
+    landing: &TerminalProfile,
+) -> Result<Vec<RouteEngineWaypoint>, String> {
+    let n = waypoints.len();
+    if n < 4
+        || !has_phase(&waypoints[0], "GROUND_START")
+        || !has_phase(&waypoints[1], "VERTICAL_ASCENT")
+        || !has_phase(&waypoints[n - 2], "VERTICAL_DESCENT")
+        || !has_phase(&waypoints[n - 1], "GROUND_END")
+    {
+        return Ok(waypoints.to_vec());
+    }
+
+    let origin = &waypoints[0];
+    let destination = &waypoints[n - 1];
+    // Airborne path from above the origin pad to above the destination pad.
+    let airborne = &waypoints[1..n - 1];
+    let last = airborne.len() - 1;
+
+    let toward_idx = airborne
+        .iter()
+        .position(|wp| horizontal_distance(origin, wp) > PAD_HORIZONTAL_TOLERANCE_M);
+    let from_idx = airborne
+        .iter()
+        .rposition(|wp| horizontal_distance(destination, wp) > PAD_HORIZONTAL_TOLERANCE_M);
+
+    let departure = build_terminal_leg(
+        origin,
+        airborne[0].altitude_m,
+        toward_idx.map(|idx| &airborne[idx]),
+        takeoff,
+        takeoff.outbound_heading(false),
+    )
+    .map_err(|err| format!("takeoff profile: {err}"))?;
+    let mut arrival = build_terminal_leg(
+        destination,
+        airborne[last].altitude_m,
+        from_idx.map(|idx| &airborne[idx]),
+        landing,
+        landing.outbound_heading(true),
+    )
+    .map_err(|err| format!("landing profile: {err}"))?;
+    arrival.reverse();
+    for wp in &mut arrival {
+        wp.phase = wp.phase.as_deref().map(descent_phase);
+    }
+
+    let keep_from = match (takeoff.path, toward_idx) {
+        (TerminalPath::Sloped { .. }, Some(idx)) => idx,
+        _ => 1,
+    };
+    let keep_to = match (landing.path, from_idx) {
+        (TerminalPath::Sloped { .. }, Some(idx)) => idx,
+        _ => last.saturating_sub(1),
+    };
+    if keep_from > keep_to + 1 {
+        return Err("route is too short for the requested takeoff/landing profiles".to_string());
+    }
+
+    let mut output = departure;
+    if keep_from <= keep_to {
+        output.extend_from_slice(&airborne[keep_from..=keep_to]);
+    }
+    output.extend(arrival);
+    Ok(output)
+}
+
+/// Check lateral terminal segments (spiral/sloped legs, and the legs joining them to the route)
+/// against nearby obstacles.
+///
+/// Vertical legs directly above the pad are not checked, matching the planner's existing
+/// behavior for pads. Returns one message per offending obstacle.
+pub fn terminal_obstacle_violations<F>(
+    waypoints: &[RouteEngineWaypoint],
+    obstacles: &[RouteObstacle],
+    terrain_height: F,
+    clearance_m: f64,
+) -> Vec<String>
+where
+    F: Fn(f64, f64) -> f64,
+{
+    let mut violations = Vec::new();
+    let mut reported: HashSet<usize> = HashSet::new();
+    let clearance_m = clearance_m.max(0.0);
+
+    for pair in waypoints.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        // A leg flown on a fixed heading rejoins the route off the planned path.
+        let checked = is_lateral_terminal_phase(start)
+            || is_lateral_terminal_phase(end)
+            || (is_terminal_phase(start) && is_terminal_phase(end));
+        if !checked {
+            continue;
+        }
+        let distance_m = horizontal_distance(start, end);
+        if distance_m <= PAD_HORIZONTAL_TOLERANCE_M {
+            continue;
+        }
+        let label = if is_descent(start) || is_descent(end) {
+            "landing"
+        } else {
+            "takeoff"
+        };
+        let heading = bearing(start.lat, start.lon, end.lat, end.lon);
+        let samples = (distance_m / OBSTACLE_SAMPLE_STEP_M).ceil().max(1.0) as usize;
+        for step in 0..=samples {
+            let frac = step as f64 / samples as f64;
+            let (lat, lon) = offset_by_bearing(start.lat, start.lon, distance_m * frac, heading);
+            let altitude_m = start.altitude_m + frac * (end.altitude_m - start.altitude_m);
+            let ground = terrain_height(lat, lon).max(0.0);
+            for (idx, obstacle) in obstacles.iter().enumerate() {
+                if reported.contains(&idx) {
+                    continue;
+                }
+                if obstacle.distance_m(lat, lon) > 0.0 {
+                    continue;
+                }
+                let top = ground + obstacle.height_m.unwrap_or(0.0).max(0.0);
+                if altitude_m < top + clearance_m {
+                    reported.insert(idx);
+                    violations.push(format!(
+                        "{label} profile passes within {:.0}m clearance of obstacle at ({:.6}, {:.6}) ({:.0}m tall)",
+                        clearance_m,
+                        obstacle.lat,
+                        obstacle.lon,
+                        obstacle.height_m.unwrap_or(0.0)
+                    ));
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Build a leg from the pad (ground) up to cruise altitude, ordered as a takeoff.
+///
+/// The lateral part heads toward `toward` (the first route point off the pad) unless
+/// `fixed_heading` is given.
+fn build_terminal_leg(
+    pad: &RouteEngineWaypoint,
+    cruise_alt_m: f64,
+    toward: Option<&RouteEngineWaypoint>,
+    profile: &TerminalProfile,
+    fixed_heading: Option<f64>,
+) -> Result<Vec<RouteEngineWaypoint>, String> {
+    let ground_m = pad.altitude_m;
+    let lateral_alt_m = (ground_m + profile.min_lateral_agl_m.max(0.0)).min(cruise_alt_m);
+    let mut leg = vec![point(pad.lat, pad.lon, ground_m, "GROUND_START")];
+
+    let climb_m = cruise_alt_m - lateral_alt_m;
+    if profile.is_vertical() || climb_m <= f64::EPSILON {
+        leg.push(point(pad.lat, pad.lon, cruise_alt_m, "VERTICAL_ASCENT"));
+        return Ok(leg);
+    }
+
+    leg.push(point(pad.lat, pad.lon, lateral_alt_m, "VERTICAL_ASCENT"));
+    let heading = fixed_heading
+        .or_else(|| toward.map(|wp| bearing(pad.lat, pad.lon, wp.lat, wp.lon)))
+        .unwrap_or(0.0);
+
+    match profile.path {
+        TerminalPath::Vertical => {}
+        TerminalPath::Spiral {
+            radius_m,
+            climb_per_turn_m,
+        } => {
+            // The circle sits behind the pad relative to the route so the pad lies on it.
+            let (center_lat, center_lon) =
+                offset_by_bearing(pad.lat, pad.lon, radius_m, heading + std::f64::consts::PI);
+            let turns = (climb_m / climb_per_turn_m.max(1.0)).ceil().max(1.0) as usize;
+            let total = turns * SPIRAL_POINTS_PER_TURN;
+            let step_rad = std::f64::consts::TAU / SPIRAL_POINTS_PER_TURN as f64;
+            for k in 1..=total {
+                let altitude_m = lateral_alt_m + climb_m * (k as f64 / total as f64);
+                let (lat, lon) = if k == total {
+                    (pad.lat, pad.lon)
+                } else {
+                    offset_by_bearing(
+                        center_lat,
+                        center_lon,
+                        radius_m,
+                        heading + k as f64 * step_rad,
+                    )
+                };
+                leg.push(point(lat, lon, altitude_m, "SPIRAL_ASCENT"));
+            }
+        }
+        TerminalPath::Sloped {
+            glide_angle_deg,
+            climb_gradient,
+        } => {
+            let gradient = climb_gradient.unwrap_or_else(|| glide_angle_deg.to_radians().tan());
+            let run_m = climb_m / gradient;
+            if fixed_heading.is_some() {
+                let (lat, lon) = offset_by_bearing(pad.lat, pad.lon, run_m, heading);
+                leg.push(point(lat, lon, cruise_alt_m, "SLOPED_ASCENT"));
+                return Ok(leg);
+            }
+            let Some(toward) = toward else {
+                return Err("sloped profile requires a lateral leg".to_string());
+            };
+            let leg_m = haversine_distance(pad.lat, pad.lon, toward.lat, toward.lon);
+            if run_m > leg_m {
+                return Err(format!(
+                    "sloped profile needs {:.0}m of straight leg but only {:.0}m is available",
+                    run_m, leg_m
+                ));
+            }
+            let (lat, lon) = offset_by_bearing(pad.lat, pad.lon, run_m, heading);
+            leg.push(point(lat, lon, cruise_alt_m, "SLOPED_ASCENT"));
+        }
+    }
+    Ok(leg)
+}
+
+fn point(lat: f64, lon: f64, altitude_m: f64, phase: &str) -> RouteEngineWaypoint {
+    RouteEngineWaypoint {
+        lat,
+        lon,
+        altitude_m,
+        phase: Some(phase.to_string()),
+    }
+}
+
+fn descent_phase(phase: &str) -> String {
+    match phase {
+        "GROUND_START" => "GROUND_END".to_string(),
+        other => other.replace("_ASCENT", "_DESCENT"),
+    }
+}
+
+fn has_phase(wp: &RouteEngineWaypoint, phase: &str) -> bool {
+    wp.phase.as_deref() == Some(phase)
+}
+
+fn is_terminal_phase(wp: &RouteEngineWaypoint) -> bool {
+    matches!(
+        wp.phase.as_deref(),
+        Some(
+            "VERTICAL_ASCENT"
+                | "VERTICAL_DESCENT"
+                | "SPIRAL_ASCENT"
+                | "SPIRAL_DESCENT"
+                | "SLOPED_ASCENT"
+                | "SLOPED_DESCENT"
+        )
+    )
+}
+
+fn is_lateral_terminal_phase(wp: &RouteEngineWaypoint) -> bool {
+    matches!(
+        wp.phase.as_deref(),
+        Some("SPIRAL_ASCENT" | "SPIRAL_DESCENT" | "SLOPED_ASCENT" | "SLOPED_DESCENT")
+    )
+}
+
+fn is_descent(wp: &RouteEngineWaypoint) -> bool {
+    wp.phase
+        .as_deref()
+        .map(|phase| phase.ends_with("_DESCENT"))
+        .unwrap_or(false)
+}
+
+fn horizontal_distance(a: &RouteEngineWaypoint, b: &RouteEngineWaypoint) -> f64 {
+    haversine_distance(a.lat, a.lon, b.lat, b.lon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route() -> Vec<RouteEngineWaypoint> {
+        // ~1.1km due north at 100m cruise.
+        vec![
+            point(33.0, -117.0, 10.0, "GROUND_START"),
+            point(33.0, -117.0, 100.0, "VERTICAL_ASCENT"),
+            point(33.005, -117.0, 100.0, "CRUISE"),
+            point(33.01, -117.0, 100.0, "VERTICAL_DESCENT"),
+            point(33.01, -117.0, 20.0, "GROUND_END"),
+        ]
+    }
+
+    #[test]
+    fn vertical_profiles_keep_route_shape() {
+        let waypoints = route();
+        let result = apply_takeoff_landing_profile(
+            &waypoints,
+            &TerminalProfile::default(),
+            &TerminalProfile::default(),
+        )
+        .expect("apply");
+        assert_eq!(result.len(), waypoints.len());
+        assert_eq!(result[1].phase.as_deref(), Some("VERTICAL_ASCENT"));
+        assert_eq!(result[3].phase.as_deref(), Some("VERTICAL_DESCENT"));
+        assert_eq!(result[4].phase.as_deref(), Some("GROUND_END"));
+    }
+
+    #[test]
+    fn spiral_takeoff_climbs_vertically_before_circling() {
+        let takeoff = TerminalProfile {
+            path: TerminalPath::Spiral {
+                radius_m: 30.0,
+                climb_per_turn_m: 40.0,
+            },
+            min_lateral_agl_m: 15.0,
+            direction_deg: None,
+        };
+        let result = apply_takeoff_landing_profile(&route(), &takeoff, &TerminalProfile::default())
+            .expect("apply");
+
+        assert_eq!(result[1].phase.as_deref(), Some("VERTICAL_ASCENT"));
+        assert!((result[1].altitude_m - 25.0).abs() < 1e-6);
+        let spiral: Vec<_> = result
+            .iter()
+            .filter(|wp| wp.phase.as_deref() == Some("SPIRAL_ASCENT"))
+            .collect();
+        // 75m of climb at 40m per turn = 2 turns.
+        assert_eq!(spiral.len(), 2 * SPIRAL_POINTS_PER_TURN);
+        let top = spiral.last().unwrap();
+        assert!((top.altitude_m - 100.0).abs() < 1e-6);
+        assert!((top.lat - 33.0).abs() < 1e-9);
+        for wp in &spiral {
+            assert!(haversine_distance(33.0, -117.0, wp.lat, wp.lon) <= 60.5);
+        }
+    }
+
+    #[test]
+    fn sloped_landing_starts_descent_along_final_leg() {
+        let landing = TerminalProfile {
+            path: TerminalPath::Sloped {
+                glide_angle_deg: 45.0,
+                climb_gradient: None,
+            },
+            min_lateral_agl_m: 10.0,
+            direction_deg: None,
+        };
+        let result = apply_takeoff_landing_profile(&route(), &TerminalProfile::default(), &landing)
+            .expect("apply");
+        let n = result.len();
+        assert_eq!(result[n - 1].phase.as_deref(), Some("GROUND_END"));
+        assert_eq!(result[n - 2].phase.as_deref(), Some("VERTICAL_DESCENT"));
+        assert!((result[n - 2].altitude_m - 30.0).abs() < 1e-6);
+        let slope = &result[n - 3];
+        assert_eq!(slope.phase.as_deref(), Some("SLOPED_DESCENT"));
+        // 70m to lose at 45 degrees starts 70m out from the pad.
+        let run = haversine_distance(slope.lat, slope.lon, 33.01, -117.0);
+        assert!((run - 70.0).abs() < 0.5, "run {run}");
+    }
+
+    #[test]
+    fn sloped_profile_rejects_short_legs() {
+        let takeoff = TerminalProfile {
+            path: TerminalPath::Sloped {
+                glide_angle_deg: 3.0,
+                climb_gradient: None,
+            },
+            min_lateral_agl_m: 0.0,
+            direction_deg: None,
+        };
+        let err = apply_takeoff_landing_profile(&route(), &takeoff, &TerminalProfile::default())
+            .unwrap_err();
+        assert!(err.contains("takeoff profile"));
+    }
+
+    #[test]
+    fn approach_direction_fixes_the_sloped_leg_heading() {
+        // Approach flown eastbound onto the pad at a 1:2 gradient, whatever the route's heading.
+        let landing: TerminalProfile = serde_json::from_value(serde_json::json!({
+            "type": "sloped",
+            "climb_gradient": 0.5,
+            "transition_agl_m": 10.0,
+            "direction_deg": 90.0
+        }))
+        .expect("profile");
+        assert!(landing.validate("landing").is_empty());
+        let result = apply_takeoff_landing_profile(&route(), &TerminalProfile::default(), &landing)
+            .expect("apply");
+        let n = result.len();
+        let slope = &result[n - 3];
+        assert_eq!(slope.phase.as_deref(), Some("SLOPED_DESCENT"));
+        // 70m to lose at 0.5 starts 140m west of the pad.
+        let run = haversine_distance(slope.lat, slope.lon, 33.01, -117.0);
+        assert!((run - 140.0).abs() < 0.5, "run {run}");
+        assert!(slope.lon < -117.0 && (slope.lat - 33.01).abs() < 1e-6);
+        assert_eq!(result[n - 4].phase.as_deref(), Some("CRUISE"));
+
+        // The leg rejoining the route is checked too.
+        let (lat, lon) = offset_by_bearing(33.01, -117.0, 280.0, std::f64::consts::PI);
+        let (lat, lon) = offset_by_bearing(lat, lon, 70.0, 1.5 * std::f64::consts::PI);
+        let obstacles = vec![RouteObstacle {
+            lat,
+            lon,
+            radius_m: 30.0,
+            height_m: Some(120.0),
+            polygon: None,
+        }];
+        let violations = terminal_obstacle_violations(&result, &obstacles, |_, _| 10.0, 5.0);
+        assert_eq!(violations.len(), 1, "{:?}", violations);
+        assert!(violations[0].starts_with("landing"));
+
+        let vertical = TerminalProfile {
+            direction_deg: Some(90.0),
+            ..TerminalProfile::default()
+        };
+        assert_eq!(vertical.validate("takeoff").len(), 1);
+        let both = TerminalProfile {
+            path: TerminalPath::Sloped {
+                glide_angle_deg: 10.0,
+                climb_gradient: Some(0.2),
+            },
+            ..TerminalProfile::default()
+        };
+        assert_eq!(both.validate("takeoff").len(), 1);
+    }
+
+    #[test]
+    fn lateral_terminal_legs_are_checked_against_obstacles() {
+        let takeoff = TerminalProfile {
+            path: TerminalPath::Sloped {
+                glide_angle_deg: 10.0,
+                climb_gradient: None,
+            },
+            min_lateral_agl_m: 5.0,
+            direction_deg: None,
+        };
+        let result = apply_takeoff_landing_profile(&route(), &takeoff, &TerminalProfile::default())
+            .expect("apply");
+        let (lat, lon) = offset_by_bearing(33.0, -117.0, 100.0, 0.0);
+        let obstacles = vec![RouteObstacle {
+            lat,
+            lon,
+            radius_m: 15.0,
+            height_m: Some(40.0),
+            polygon: None,
+        }];
+        let violations = terminal_obstacle_violations(&result, &obstacles, |_, _| 10.0, 5.0);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].starts_with("takeoff"));
+
+        let vertical = route();
+        assert!(terminal_obstacle_violations(&vertical, &obstacles, |_, _| 10.0, 5.0).is_empty());
+    }
+
+    #[test]
+    fn finds_nearest_vertiport_within_radius() {
+        let vertiports = vec![
+            Vertiport {
+                id: "far".to_string(),
+                name: None,
+                lat: 33.1,
+                lon: -117.0,
+                radius_m: 100.0,
+                profile: TakeoffLandingProfile::default(),
+            },
+            Vertiport {
+                id: "pad-a".to_string(),
+                name: None,
+                lat: 33.0001,
+                lon: -117.0,
+                radius_m: 50.0,
+                profile: TakeoffLandingProfile::default(),
+            },
+        ];
+        assert_eq!(
+            find_vertiport(&vertiports, 33.0, -117.0).map(|v| v.id.as_str()),
+            Some("pad-a")
+        );
+        assert!(find_vertiport(&vertiports, 34.0, -117.0).is_none());
+    }
+}
@@ -0,0 +1,153 @@
+//! Terrain clearance (CFIT) detection.
+//!
+//! Flags drones whose height above ground is below a minimum floor now, or will be within the
+//! lookahead window if they hold their current heading, speed and vertical rate. Terrain is
+//! supplied by the caller as an elevation sampler, so this module stays independent of any
+//! particular elevation source.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conflict::{ConflictSeverity, DronePosition};
+use crate::spatial::offset_by_bearing;
+
+/// Sampling step along the projected track.
+const PROJECTION_STEP_S: f64 = 1.0;
+
+/// A predicted or current loss of terrain clearance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerrainConflict {
+    pub drone_id: String,
+    /// Critical when already below the floor, Warning when predicted within the lookahead.
+    pub severity: ConflictSeverity,
+    pub floor_agl_m: f64,
+    pub current_agl_m: f64,
+    /// Lowest clearance over the projection window.
+    pub min_agl_m: f64,
+    /// Seconds until clearance first drops below the floor (0 when already below).
+    pub time_to_floor_s: f64,
+    /// Where clearance first drops below the floor.
+    pub lat: f64,
+    pub lon: f64,
+    pub terrain_elevation_m: f64,
+}
+
+/// Positions sampled along the drone's projected track, starting with the current one.
+/// Each entry is `(time_offset_s, lat, lon, altitude_m)`.
+pub fn projected_track(drone: &DronePosition, lookahead_s: f64) -> Vec<(f64, f64, f64, f64)> {
+    let lookahead_s = if lookahead_s.is_finite() {
+        lookahead_s.max(0.0)
+    } else {
+        0.0
+    };
+    let steps = (lookahead_s / PROJECTION_STEP_S).ceil() as usize;
+    (0..=steps)
+        .map(|step| {
+            let t = (step as f64 * PROJECTION_STEP_S).min(lookahead_s);
+            let (lat, lon) = if drone.speed_mps > 0.0 {
+                offset_by_bearing(
+                    drone.lat,
+                    drone.lon,
+                    drone.speed_mps * t,
+                    drone.heading_deg.to_radians(),
+                )
+            } else {
+                (drone.lat, drone.lon)
+            };
+            (t, lat, lon, drone.altitude_m + drone.velocity_z * t)
+        })
+        .collect()
+}
+
+/// Check a drone's current and projected clearance against `floor_agl_m`.
+///
+/// `drone.altitude_m` and the values returned by `terrain_elevation_m` must share a vertical
+/// datum (normally AMSL).
+pub fn detect_terrain_conflict(
+    drone: &DronePosition,
+    floor_agl_m: f64,
+    lookahead_s: f64,
+    terrain_elevation_m: impl Fn(f64, f64) -> f64,
+) -> Option<TerrainConflict> {
+    let mut current_agl_m = f64::NAN;
+    let mut min_agl_m = f64::INFINITY;
+    let mut breach: Option<(f64, f64, f64, f64)> = None;
+
+    for (t, lat, lon, altitude_m) in projected_track(drone, lookahead_s) {
+        let terrain_m = terrain_elevation_m(lat, lon);
+        let agl_m = altitude_m - terrain_m;
+        if !agl_m.is_finite() {
+            continue;
+        }
+        if t == 0.0 {
+            current_agl_m = agl_m;
+        }
+        min_agl_m = min_agl_m.min(agl_m);
+        if breach.is_none() && agl_m < floor_agl_m {
+            breach = Some((t, lat, lon, terrain_m));
+        }
+    }
+
+    let (time_to_floor_s, lat, lon, terrain_elevation_m) = breach?;
+    let severity = if time_to_floor_s == 0.0 {
+        ConflictSeverity::Critical
+    } else {
+        ConflictSeverity::Warning
+    };
+    Some(TerrainConflict {
+        drone_id: drone.drone_id.clone(),
+        severity,
+        floor_agl_m,
+        current_agl_m,
+        min_agl_m,
+        time_to_floor_s,
+        lat,
+        lon,
+        terrain_elevation_m,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat ground at 100 m rising to a 250 m ridge north of 37.01°.
+    fn ridge(lat: f64, _lon: f64) -> f64 {
+        if lat >= 37.01 {
+            250.0
+        } else {
+            100.0
+        }
+    }
+
+    #[test]
+    fn flags_current_and_projected_floor_violations() {
+        // 200 m AMSL over flat ground: 100 m AGL, clear while hovering.
+        let hovering = DronePosition::new("D1", 37.0, -122.0, 200.0);
+        assert!(detect_terrain_conflict(&hovering, 60.0, 30.0, ridge).is_none());
+
+        // Already below the floor.
+        let low = DronePosition::new("D2", 37.0, -122.0, 140.0);
+        let conflict = detect_terrain_conflict(&low, 60.0, 30.0, ridge).unwrap();
+        assert_eq!(conflict.severity, ConflictSeverity::Critical);
+        assert_eq!(conflict.time_to_floor_s, 0.0);
+        assert!((conflict.current_agl_m - 40.0).abs() < 1e-9);
+
+        // Heading north at 50 m/s reaches the ridge (~1.1 km away) in about 23 s.
+        let northbound =
+            DronePosition::new("D3", 37.0, -122.0, 280.0).with_velocity(0.0, 50.0, 0.0);
+        let conflict = detect_terrain_conflict(&northbound, 60.0, 30.0, ridge).unwrap();
+        assert_eq!(conflict.severity, ConflictSeverity::Warning);
+        assert!((20.0..=25.0).contains(&conflict.time_to_floor_s));
+        assert!((conflict.min_agl_m - 30.0).abs() < 1e-9);
+        assert_eq!(conflict.terrain_elevation_m, 250.0);
+        // Outside a short lookahead the ridge is not a factor.
+        assert!(detect_terrain_conflict(&northbound, 60.0, 10.0, ridge).is_none());
+
+        // Descending over flat ground.
+        let descending =
+            DronePosition::new("D4", 37.0, -122.0, 200.0).with_velocity(180.0, 5.0, -3.0);
+        let conflict = detect_terrain_conflict(&descending, 60.0, 30.0, ridge).unwrap();
+        assert_eq!(conflict.severity, ConflictSeverity::Warning);
+        assert_eq!(conflict.time_to_floor_s, 14.0);
+    }
+}
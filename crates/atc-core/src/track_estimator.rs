@@ -0,0 +1,210 @@
+//! External traffic track smoothing.
+//!
+//! Remote ID and ADS-B tracks arrive at low, irregular rates. Taken straight from the reports,
+//! a track sits still between updates and then jumps, so conflicts against it flicker. Each
+//! track runs an alpha-beta filter instead: reports are blended into a predicted position, the
+//! velocity is estimated from the blend, and the track is extrapolated between reports.
+
+use serde::{Deserialize, Serialize};
+
+use crate::spatial::{lat_to_meters, lon_to_meters, offset_position};
+
+/// Weight of the newest miss in the running mean miss distance.
+const RESIDUAL_SMOOTHING: f64 = 0.3;
+
+/// Filter gains and extrapolation limit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrackEstimatorConfig {
+    /// Share of each position miss applied to the position (0-1); lower is smoother.
+    pub alpha: f64,
+    /// Share of each position miss per second applied to the velocity (0-1).
+    pub beta: f64,
+    /// How far past the last report (seconds) a track is extrapolated.
+    pub max_extrapolation_s: f64,
+    /// Reports further from the prediction than the track could have flown restart the filter.
+    pub max_speed_mps: f64,
+}
+
+impl Default for TrackEstimatorConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.5,
+            beta: 0.2,
+            max_extrapolation_s: 5.0,
+            max_speed_mps: 150.0,
+        }
+    }
+}
+
+/// Position and velocity of a track at `timestamp_s` (Unix seconds).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrackFix {
+    pub lat: f64,
+    pub lon: f64,
+    pub altitude_m: f64,
+    pub heading_deg: f64,
+    pub speed_mps: f64,
+    pub vertical_speed_mps: f64,
+    pub timestamp_s: f64,
+}
+
+impl TrackFix {
+    fn velocity(&self) -> (f64, f64) {
+        let heading = self.heading_deg.to_radians();
+        (
+            self.speed_mps * heading.cos(),
+            self.speed_mps * heading.sin(),
+        )
+    }
+
+    /// Dead-reckon the fix `dt_s` seconds ahead.
+    fn advanced(&self, dt_s: f64) -> Self {
+        let (north_mps, east_mps) = self.velocity();
+        let (lat, lon) = offset_position(self.lat, self.lon, north_mps * dt_s, east_mps * dt_s);
+        Self {
+            lat,
+            lon,
+            altitude_m: self.altitude_m + self.vertical_speed_mps * dt_s,
+            timestamp_s: self.timestamp_s + dt_s,
+            ..*self
+        }
+    }
+}
+
+/// Smoothed state of one track.
+#[derive(Debug, Clone, Default)]
+pub struct TrackEstimator {
+    fix: Option<TrackFix>,
+    residual_m: Option<f64>,
+}
+
+impl TrackEstimator {
+    /// Blend a report into the track and return the smoothed fix; `None` for reports older
+    /// than the last, which are ignored. The first report, a report correcting the last one
+    /// (same timestamp) and a report the track could not have reached restart the filter from
+    /// the report.
+    pub fn observe(&mut self, report: TrackFix, config: &TrackEstimatorConfig) -> Option<TrackFix> {
+        let Some(last) = self.fix else {
+            self.fix = Some(report);
+            return Some(report);
+        };
+        let dt_s = report.timestamp_s - last.timestamp_s;
+        if dt_s < 0.0 {
+            return None;
+        }
+        let predicted = last.advanced(dt_s);
+        let miss_north = lat_to_meters(report.lat - predicted.lat, predicted.lat);
+        let miss_east = lon_to_meters(report.lon - predicted.lon, predicted.lat);
+        let miss_up = report.altitude_m - predicted.altitude_m;
+        let miss_m = miss_north.hypot(miss_east);
+        if dt_s == 0.0 || miss_m / dt_s > config.max_speed_mps {
+            self.fix = Some(report);
+            return Some(report);
+        }
+
+        let (lat, lon) = offset_position(
+            predicted.lat,
+            predicted.lon,
+            config.alpha * miss_north,
+            config.alpha * miss_east,
+        );
+        let (north_mps, east_mps) = last.velocity();
+        let north_mps = north_mps + config.beta * miss_north / dt_s;
+        let east_mps = east_mps + config.beta * miss_east / dt_s;
+        let fix = TrackFix {
+            lat,
+            lon,
+            altitude_m: predicted.altitude_m + config.alpha * miss_up,
+            heading_deg: east_mps.atan2(north_mps).to_degrees().rem_euclid(360.0),
+            speed_mps: north_mps.hypot(east_mps),
+            vertical_speed_mps: last.vertical_speed_mps + config.beta * miss_up / dt_s,
+            timestamp_s: report.timestamp_s,
+        };
+        self.residual_m = Some(match self.residual_m {
+            Some(mean) => mean + RESIDUAL_SMOOTHING * (miss_m - mean),
+            None => miss_m,
+        });
+        self.fix = Some(fix);
+        Some(fix)
+    }
+
+    /// The track extrapolated to `timestamp_s`, at most `max_extrapolation_s` past its last
+    /// report; `None` before the first report.
+    pub fn predict(&self, timestamp_s: f64, config: &TrackEstimatorConfig) -> Option<TrackFix> {
+        let fix = self.fix?;
+        let dt_s = (timestamp_s - fix.timestamp_s).clamp(0.0, config.max_extrapolation_s);
+        Some(fix.advanced(dt_s))
+    }
+
+    /// Running mean distance (meters) between reports and where the filter expected them.
+    pub fn residual_m(&self) -> Option<f64> {
+        self.residual_m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::haversine_distance;
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    fn report(north_m: f64, timestamp_s: f64) -> TrackFix {
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m, 0.0);
+        TrackFix {
+            lat,
+            lon,
+            altitude_m: 100.0,
+            heading_deg: 0.0,
+            speed_mps: 0.0,
+            vertical_speed_mps: 0.0,
+            timestamp_s,
+        }
+    }
+
+    #[test]
+    fn smooths_noise_and_learns_velocity() {
+        let config = TrackEstimatorConfig::default();
+        let mut estimator = TrackEstimator::default();
+        // Flying north at 20 m/s, reported every 3 s with ±15 m of alternating noise.
+        let mut fix = report(0.0, 0.0);
+        for step in 0..20 {
+            let t = step as f64 * 3.0;
+            let noise = if step % 2 == 0 { 15.0 } else { -15.0 };
+            fix = estimator
+                .observe(report(20.0 * t + noise, t), &config)
+                .unwrap();
+        }
+        assert!((fix.speed_mps - 20.0).abs() < 2.0, "{:?}", fix);
+        assert!(fix.heading_deg < 5.0 || fix.heading_deg > 355.0);
+        let truth = report(20.0 * 57.0, 57.0);
+        assert!(haversine_distance(fix.lat, fix.lon, truth.lat, truth.lon) < 15.0);
+        assert!(estimator.residual_m().unwrap() > 0.0);
+
+        // Between reports the track keeps moving, up to the extrapolation limit.
+        let ahead = estimator.predict(59.0, &config).unwrap();
+        assert!((haversine_distance(fix.lat, fix.lon, ahead.lat, ahead.lon) - 40.0).abs() < 5.0);
+        let capped = estimator.predict(120.0, &config).unwrap();
+        assert_eq!(capped.timestamp_s, 57.0 + config.max_extrapolation_s);
+    }
+
+    #[test]
+    fn jumps_and_corrections_restart_the_filter() {
+        let config = TrackEstimatorConfig::default();
+        let mut estimator = TrackEstimator::default();
+        estimator.observe(report(0.0, 0.0), &config);
+        estimator.observe(report(10.0, 2.0), &config);
+
+        // 1 km in 2 s is no aircraft.
+        let jumped = estimator.observe(report(1_010.0, 4.0), &config);
+        assert_eq!(jumped, Some(report(1_010.0, 4.0)));
+
+        // A corrected report for the same instant replaces the track outright.
+        let corrected = estimator.observe(report(20.0, 4.0), &config);
+        assert_eq!(corrected, Some(report(20.0, 4.0)));
+
+        // Late reports are ignored.
+        assert_eq!(estimator.observe(report(0.0, 1.0), &config), None);
+        assert_eq!(estimator.predict(4.0, &config), corrected);
+    }
+}
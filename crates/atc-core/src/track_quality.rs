@@ -0,0 +1,196 @@
+//! External traffic track quality.
+//!
+//! ADS-B and Remote ID feeds carry ghost tracks: targets that report rarely, go stale, or jump
+//! between positions no aircraft could fly between. Each track is scored from 0 to 1 on its
+//! update rate, age and position jumps so low-quality tracks can be filtered from displays and
+//! kept from raising nuisance conflicts.
+
+use serde::{Deserialize, Serialize};
+
+use crate::conflict::{Conflict, ConflictSeverity};
+use crate::spatial::haversine_distance;
+
+/// Weight of the newest interval in the running mean update interval.
+const INTERVAL_SMOOTHING: f64 = 0.3;
+/// Fraction of the jump weight kept after each consistent update.
+const JUMP_DECAY: f64 = 0.7;
+/// Rate score of a track with a single report, before its update rate is known.
+const UNKNOWN_RATE_SCORE: f64 = 0.5;
+
+/// What to do with conflicts involving a track below the quality threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackQualityMode {
+    /// Treat every track alike.
+    #[default]
+    Off,
+    /// Keep the conflict at `Info` severity so it is shown but not acted on.
+    Downgrade,
+    /// Drop the conflict.
+    Ignore,
+}
+
+/// What a good track looks like.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrackQualityConfig {
+    /// Update interval of a healthy track (seconds); slower tracks score lower.
+    pub expected_interval_s: f64,
+    /// Age (seconds) at which a track scores zero.
+    pub stale_after_s: f64,
+    /// Implied speed between reports above which the move counts as a position jump.
+    pub max_speed_mps: f64,
+}
+
+impl Default for TrackQualityConfig {
+    fn default() -> Self {
+        Self {
+            expected_interval_s: 2.0,
+            stale_after_s: 30.0,
+            max_speed_mps: 150.0,
+        }
+    }
+}
+
+/// Quality of one track at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrackQuality {
+    /// 0 (ghost) to 1 (healthy).
+    pub score: f64,
+    /// Running mean seconds between reports; unknown until the second report.
+    pub update_interval_s: Option<f64>,
+    /// Seconds since the last report.
+    pub age_s: f64,
+    /// Reports that jumped further than the track could have flown.
+    pub position_jumps: u32,
+    pub updates: u32,
+    /// Running mean distance (meters) between reports and the smoothed track's prediction;
+    /// unknown until the track has been smoothed.
+    #[serde(default)]
+    pub residual_m: Option<f64>,
+}
+
+/// Report history of one track.
+#[derive(Debug, Clone, Default)]
+pub struct TrackHistory {
+    last_fix: Option<(f64, f64, f64)>,
+    mean_interval_s: Option<f64>,
+    jump_weight: f64,
+    position_jumps: u32,
+    updates: u32,
+}
+
+impl TrackHistory {
+    /// Record a report at `timestamp_s` (Unix seconds). Repeats of the last report, or reports
+    /// older than it, are ignored.
+    pub fn observe(&mut self, lat: f64, lon: f64, timestamp_s: f64, config: &TrackQualityConfig) {
+        if let Some((last_lat, last_lon, last_s)) = self.last_fix {
+            let interval_s = timestamp_s - last_s;
+            if interval_s <= 0.0 {
+                return;
+            }
+            self.mean_interval_s = Some(match self.mean_interval_s {
+                Some(mean) => mean + INTERVAL_SMOOTHING * (interval_s - mean),
+                None => interval_s,
+            });
+            let implied_speed = haversine_distance(last_lat, last_lon, lat, lon) / interval_s;
+            self.jump_weight *= JUMP_DECAY;
+            if implied_speed > config.max_speed_mps {
+                self.jump_weight += 1.0;
+                self.position_jumps += 1;
+            }
+        }
+        self.last_fix = Some((lat, lon, timestamp_s));
+        self.updates += 1;
+    }
+
+    /// Score the track as of `now_s` (Unix seconds).
+    pub fn quality(&self, now_s: f64, config: &TrackQualityConfig) -> TrackQuality {
+        let age_s = self
+            .last_fix
+            .map(|(_, _, last_s)| (now_s - last_s).max(0.0))
+            .unwrap_or(f64::INFINITY);
+        let rate_score = match self.mean_interval_s {
+            Some(interval_s) => (config.expected_interval_s / interval_s).min(1.0),
+            None => UNKNOWN_RATE_SCORE,
+        };
+        let age_score = if config.stale_after_s > 0.0 {
+            (1.0 - age_s / config.stale_after_s).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let jump_score = 1.0 / (1.0 + self.jump_weight);
+        TrackQuality {
+            score: rate_score * age_score * jump_score,
+            update_interval_s: self.mean_interval_s,
+            age_s,
+            position_jumps: self.position_jumps,
+            updates: self.updates,
+            residual_m: None,
+        }
+    }
+}
+
+/// Apply `mode` to a conflict involving a low-quality track; `None` when it is ignored.
+pub fn apply_track_quality_filter(
+    mut conflict: Conflict,
+    mode: TrackQualityMode,
+) -> Option<Conflict> {
+    match mode {
+        TrackQualityMode::Off => Some(conflict),
+        TrackQualityMode::Ignore => None,
+        TrackQualityMode::Downgrade => {
+            conflict.severity = ConflictSeverity::Info;
+            conflict.low_quality_track = true;
+            Some(conflict)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::offset_position;
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    /// Track flying north at `speed_mps`, reporting every `interval_s` seconds.
+    fn track(reports: u32, interval_s: f64, speed_mps: f64) -> TrackHistory {
+        let config = TrackQualityConfig::default();
+        let mut history = TrackHistory::default();
+        for i in 0..reports {
+            let t = i as f64 * interval_s;
+            let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, speed_mps * t, 0.0);
+            history.observe(lat, lon, 1_000.0 + t, &config);
+        }
+        history
+    }
+
+    #[test]
+    fn scores_rate_age_and_jumps() {
+        let config = TrackQualityConfig::default();
+        let healthy = track(10, 2.0, 20.0);
+        let quality = healthy.quality(1_019.0, &config);
+        assert!(quality.score > 0.9, "{:?}", quality);
+        assert_eq!(quality.update_interval_s, Some(2.0));
+        assert_eq!(quality.updates, 10);
+
+        // The same track gone quiet for 24 of its 30 seconds.
+        assert!(healthy.quality(1_042.0, &config).score < 0.25);
+
+        // Reporting every 10 s scores a fifth of the rate of a healthy track.
+        let slow = track(5, 10.0, 20.0).quality(1_040.0, &config);
+        assert!((slow.score - 0.2).abs() < 0.01, "{:?}", slow);
+
+        // A ghost hopping ~1 km between 2 s reports.
+        let ghost = track(6, 2.0, 500.0).quality(1_010.0, &config);
+        assert_eq!(ghost.position_jumps, 5);
+        assert!(ghost.score < 0.4, "{:?}", ghost);
+
+        // Repeated reports do not count as updates.
+        let mut repeated = track(1, 2.0, 20.0);
+        repeated.observe(ORIGIN.0, ORIGIN.1, 1_000.0, &config);
+        let quality = repeated.quality(1_000.0, &config);
+        assert_eq!(quality.updates, 1);
+        assert_eq!(quality.score, UNKNOWN_RATE_SCORE);
+    }
+}
@@ -0,0 +1,243 @@
+//! Forecast weather for route planning.
+//!
+//! Gridded precipitation and wind forecasts arrive as cells, each valid for a time window. The
+//! route planner treats them as soft obstacles: grid points the drone would reach while a cell
+//! above the operating limits covers them are excluded, and points in marginal weather cost
+//! extra, so long routes are planned around weather instead of failing compliance afterwards.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::route_engine::RouteGrid;
+
+/// One forecast cell: a lat/lon box with the weather expected in it over a time window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherCell {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+    pub valid_from: DateTime<Utc>,
+    pub valid_to: DateTime<Utc>,
+    /// Sustained wind (m/s).
+    #[serde(default)]
+    pub wind_mps: Option<f64>,
+    /// Wind gusts (m/s).
+    #[serde(default)]
+    pub gust_mps: Option<f64>,
+    /// Precipitation (mm per hour).
+    #[serde(default)]
+    pub precip_mm: Option<f64>,
+}
+
+impl WeatherCell {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let lat_ok = |lat: f64| lat.is_finite() && (-90.0..=90.0).contains(&lat);
+        let lon_ok = |lon: f64| lon.is_finite() && (-180.0..=180.0).contains(&lon);
+        if !lat_ok(self.min_lat) || !lat_ok(self.max_lat) || self.min_lat >= self.max_lat {
+            errors
+                .push("min_lat/max_lat must be within -90..90 with min_lat < max_lat".to_string());
+        }
+        if !lon_ok(self.min_lon) || !lon_ok(self.max_lon) || self.min_lon >= self.max_lon {
+            errors.push(
+                "min_lon/max_lon must be within -180..180 with min_lon < max_lon".to_string(),
+            );
+        }
+        if self.valid_from >= self.valid_to {
+            errors.push("valid_from must be before valid_to".to_string());
+        }
+        for (name, value) in [
+            ("wind_mps", self.wind_mps),
+            ("gust_mps", self.gust_mps),
+            ("precip_mm", self.precip_mm),
+        ] {
+            if value.is_some_and(|value| !value.is_finite() || value < 0.0) {
+                errors.push(format!("{} must be a non-negative number", name));
+            }
+        }
+        errors
+    }
+
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        (self.min_lat..=self.max_lat).contains(&lat) && (self.min_lon..=self.max_lon).contains(&lon)
+    }
+
+    /// Whether the forecast applies at `time_s` (Unix seconds).
+    pub fn is_valid_at(&self, time_s: f64) -> bool {
+        time_s >= timestamp_s(self.valid_from) && time_s < timestamp_s(self.valid_to)
+    }
+}
+
+fn timestamp_s(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 1000.0
+}
+
+/// Operating limits the planner holds forecast weather to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeatherLimits {
+    /// Cells above any maximum are excluded.
+    pub max_wind_mps: f64,
+    pub max_gust_mps: f64,
+    pub max_precip_mm: f64,
+    /// Cells above this fraction of any maximum are marginal.
+    pub warn_ratio: f64,
+    /// Extra cost per second flown through marginal weather, in seconds.
+    pub penalty_weight: f64,
+}
+
+impl WeatherLimits {
+    /// Extra cost per second flown in `cell`: zero in benign weather, `penalty_weight` in
+    /// marginal weather and infinite beyond the limits.
+    pub fn cell_cost(&self, cell: &WeatherCell) -> f64 {
+        let readings = [
+            (cell.wind_mps, self.max_wind_mps),
+            (cell.gust_mps, self.max_gust_mps),
+            (cell.precip_mm, self.max_precip_mm),
+        ];
+        let above = |ratio: f64| {
+            readings
+                .iter()
+                .any(|(value, max)| value.is_some_and(|value| value > max * ratio))
+        };
+        if above(1.0) {
+            f64::INFINITY
+        } else if above(self.warn_ratio) {
+            self.penalty_weight.max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Mark grid points with the weather cost at the time the drone would reach them.
+///
+/// Arrival times assume departure at `departure_s` (Unix seconds) and progress along the
+/// route's centre line at `ground_speed_mps`; every lane at the same step shares a time.
+pub fn apply_weather(
+    grid: &mut RouteGrid,
+    cells: &[WeatherCell],
+    limits: &WeatherLimits,
+    departure_s: f64,
+    ground_speed_mps: f64,
+) {
+    let step_times = grid.step_times_s(departure_s, ground_speed_mps);
+    let (Some(&first_s), Some(&last_s)) = (step_times.first(), step_times.last()) else {
+        return;
+    };
+
+    let costed: Vec<(&WeatherCell, f64)> = cells
+        .iter()
+        .filter(|cell| {
+            timestamp_s(cell.valid_to) > first_s && timestamp_s(cell.valid_from) <= last_s
+        })
+        .map(|cell| (cell, limits.cell_cost(cell)))
+        .filter(|(_, cost)| *cost > 0.0)
+        .collect();
+
+    for lane in &mut grid.lanes {
+        for (point, &time_s) in lane.iter_mut().zip(&step_times) {
+            point.weather_cost = costed
+                .iter()
+                .filter(|(cell, _)| cell.is_valid_at(time_s) && cell.contains(point.lat, point.lon))
+                .map(|(_, cost)| *cost)
+                .fold(0.0, f64::max);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Waypoint;
+    use crate::route_engine::{
+        build_lane_offsets, generate_grid_samples, optimize_flight_path, RouteEngineConfig,
+    };
+    use crate::spatial::{haversine_distance, offset_position};
+    use chrono::{Duration, TimeZone};
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    fn cell(from_s: i64, to_s: i64, precip_mm: f64) -> WeatherCell {
+        // 400 m x 400 m box over the middle of a 2 km northbound route.
+        let (min_lat, min_lon) = offset_position(ORIGIN.0, ORIGIN.1, 800.0, -200.0);
+        let (max_lat, max_lon) = offset_position(ORIGIN.0, ORIGIN.1, 1_200.0, 200.0);
+        let epoch = Utc.timestamp_opt(0, 0).unwrap();
+        WeatherCell {
+            min_lat,
+            min_lon,
+            max_lat,
+            max_lon,
+            valid_from: epoch + Duration::seconds(from_s),
+            valid_to: epoch + Duration::seconds(to_s),
+            wind_mps: None,
+            gust_mps: None,
+            precip_mm: Some(precip_mm),
+        }
+    }
+
+    /// Largest lateral deviation (m) of the planned route from the straight line.
+    fn plan_deviation(cells: &[WeatherCell]) -> Option<f64> {
+        let (end_lat, end_lon) = offset_position(ORIGIN.0, ORIGIN.1, 2_000.0, 0.0);
+        let waypoints = vec![
+            Waypoint {
+                lat: ORIGIN.0,
+                lon: ORIGIN.1,
+                altitude_m: 60.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: end_lat,
+                lon: end_lon,
+                altitude_m: 60.0,
+                speed_mps: None,
+            },
+        ];
+        let limits = WeatherLimits {
+            max_wind_mps: 12.0,
+            max_gust_mps: 15.0,
+            max_precip_mm: 2.0,
+            warn_ratio: 0.8,
+            penalty_weight: 2.0,
+        };
+        let mut grid =
+            generate_grid_samples(&waypoints, 25.0, &build_lane_offsets(600.0, 50.0), 0.0)?;
+        let config = RouteEngineConfig::default();
+        apply_weather(&mut grid, cells, &limits, 0.0, config.cruise_speed_mps);
+        let result = optimize_flight_path(&waypoints, &grid, &[], &config);
+        result.success.then(|| {
+            result
+                .waypoints
+                .iter()
+                .map(|wp| haversine_distance(wp.lat, ORIGIN.1, wp.lat, wp.lon))
+                .fold(0.0, f64::max)
+        })
+    }
+
+    #[test]
+    fn routes_around_weather_while_it_is_forecast() {
+        assert!(plan_deviation(&[]).unwrap() < 1.0);
+
+        // Heavy rain over the middle of the route when the drone gets there: fly around it.
+        let deviation = plan_deviation(&[cell(0, 3_600, 5.0)]).unwrap();
+        assert!(deviation > 200.0, "{}", deviation);
+
+        // The same cell forecast to clear before the drone arrives (~53 s in) is ignored.
+        assert!(plan_deviation(&[cell(0, 30, 5.0)]).unwrap() < 1.0);
+
+        // Light rain is below the limits.
+        assert!(plan_deviation(&[cell(0, 3_600, 1.0)]).unwrap() < 1.0);
+
+        // Marginal rain costs extra to fly through; rain above the limit is excluded.
+        let limits = WeatherLimits {
+            max_wind_mps: 12.0,
+            max_gust_mps: 15.0,
+            max_precip_mm: 2.0,
+            warn_ratio: 0.8,
+            penalty_weight: 2.0,
+        };
+        assert_eq!(limits.cell_cost(&cell(0, 1, 1.8)), 2.0);
+        assert!(limits.cell_cost(&cell(0, 1, 2.5)).is_infinite());
+        assert_eq!(cell(1, 0, 1.0).validate().len(), 1);
+    }
+}
@@ -0,0 +1,166 @@
+//! Well-clear volume model for Detect and Avoid.
+//!
+//! Implements the DO-365 style well-clear definition: a pair is not well clear when the
+//! horizontal miss distance is within `HMD*`, the aircraft are either inside `DMOD` or closing
+//! with a modified tau within `tau*`, and the vertical separation is within `ZTHR`. Unlike the
+//! plain distance thresholds, this accounts for closure rate, so fast head-on encounters are
+//! flagged earlier than slow overtakes at the same range.
+
+use serde::{Deserialize, Serialize};
+
+const FEET_TO_M: f64 = 0.3048;
+const TAU_EPS: f64 = 1e-9;
+
+/// Well-clear thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WellClearParams {
+    /// Distance modification used in modified tau (meters).
+    pub dmod_m: f64,
+    /// Modified tau threshold (seconds).
+    pub tau_mod_s: f64,
+    /// Horizontal miss distance threshold (meters).
+    pub hmd_m: f64,
+    /// Vertical separation threshold (meters).
+    pub zthr_m: f64,
+}
+
+impl Default for WellClearParams {
+    /// DO-365 values: DMOD = HMD* = 4000 ft, tau* = 35 s, ZTHR = 450 ft.
+    fn default() -> Self {
+        Self {
+            dmod_m: 4000.0 * FEET_TO_M,
+            tau_mod_s: 35.0,
+            hmd_m: 4000.0 * FEET_TO_M,
+            zthr_m: 450.0 * FEET_TO_M,
+        }
+    }
+}
+
+impl WellClearParams {
+    /// Largest horizontal range at which a pair can be in violation right now.
+    pub fn horizontal_reach_m(&self) -> f64 {
+        self.dmod_m.max(self.hmd_m)
+    }
+}
+
+/// Well-clear metrics for one pair at one instant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WellClearState {
+    pub horizontal_m: f64,
+    pub vertical_m: f64,
+    /// Modified tau in seconds; `None` when the pair is not closing horizontally.
+    pub tau_mod_s: Option<f64>,
+    /// Horizontal miss distance at the horizontal closest point of approach.
+    pub hmd_m: f64,
+    pub violation: bool,
+}
+
+/// Evaluate well clear from relative position and velocity (intruder minus ownship).
+///
+/// Positions are local east/north/up meters, velocities meters per second.
+pub fn evaluate(
+    rel_pos: (f64, f64, f64),
+    rel_vel: (f64, f64),
+    params: &WellClearParams,
+) -> WellClearState {
+    let (sx, sy, sz) = rel_pos;
+    let (vx, vy) = rel_vel;
+    let range_sq = sx * sx + sy * sy;
+    let horizontal_m = range_sq.sqrt();
+    let vertical_m = sz.abs();
+    let closure = sx * vx + sy * vy;
+    let speed_sq = vx * vx + vy * vy;
+
+    let tau_mod_s = if closure < -TAU_EPS {
+        let dmod_sq = params.dmod_m * params.dmod_m;
+        Some(((dmod_sq - range_sq) / closure).max(0.0))
+    } else {
+        None
+    };
+
+    let t_cpa = if speed_sq > TAU_EPS {
+        (-closure / speed_sq).max(0.0)
+    } else {
+        0.0
+    };
+    let hmd_m = ((sx + vx * t_cpa).powi(2) + (sy + vy * t_cpa).powi(2)).sqrt();
+
+    let horizontal_violation = hmd_m <= params.hmd_m
+        && (horizontal_m <= params.dmod_m || tau_mod_s.is_some_and(|tau| tau <= params.tau_mod_s));
+    let violation = horizontal_violation && vertical_m <= params.zthr_m;
+
+    WellClearState {
+        horizontal_m,
+        vertical_m,
+        tau_mod_s,
+        hmd_m,
+        violation,
+    }
+}
+
+/// Earliest time within `lookahead_s` at which the pair loses well clear, extrapolating
+/// constant velocity (`rel_vel` includes the vertical rate).
+pub fn time_to_loss_of_well_clear(
+    rel_pos: (f64, f64, f64),
+    rel_vel: (f64, f64, f64),
+    params: &WellClearParams,
+    lookahead_s: f64,
+    step_s: f64,
+) -> Option<f64> {
+    let step_s = step_s.max(0.1);
+    let mut t = 0.0;
+    loop {
+        let pos = (
+            rel_pos.0 + rel_vel.0 * t,
+            rel_pos.1 + rel_vel.1 * t,
+            rel_pos.2 + rel_vel.2 * t,
+        );
+        if evaluate(pos, (rel_vel.0, rel_vel.1), params).violation {
+            return Some(t);
+        }
+        if t >= lookahead_s {
+            return None;
+        }
+        t = (t + step_s).min(lookahead_s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closure_rate_and_altitude_decide_well_clear() {
+        let params = WellClearParams {
+            dmod_m: 100.0,
+            tau_mod_s: 20.0,
+            hmd_m: 100.0,
+            zthr_m: 40.0,
+        };
+
+        // 500m apart, closing head-on at 30 m/s: modified tau is (100^2 - 500^2) / -15000 = 16s.
+        let head_on = evaluate((0.0, 500.0, 0.0), (0.0, -30.0), &params);
+        assert!(head_on.violation);
+        assert!((head_on.tau_mod_s.unwrap() - 16.0).abs() < 1e-6);
+
+        // Same range but closing slowly is still well clear.
+        assert!(!evaluate((0.0, 500.0, 0.0), (0.0, -5.0), &params).violation);
+
+        // Diverging outside DMOD has no tau and stays well clear.
+        let diverging = evaluate((0.0, 500.0, 0.0), (0.0, 30.0), &params);
+        assert!(diverging.tau_mod_s.is_none());
+        assert!(!diverging.violation);
+
+        // Passing well to the side misses by more than HMD*.
+        assert!(!evaluate((300.0, 500.0, 0.0), (0.0, -30.0), &params).violation);
+
+        // Vertically separated beyond ZTHR.
+        assert!(!evaluate((0.0, 500.0, 60.0), (0.0, -30.0), &params).violation);
+
+        // Slow closure: tau drops below tau* at ~241m range, about 26s out.
+        let t =
+            time_to_loss_of_well_clear((0.0, 500.0, 0.0), (0.0, -10.0, 0.0), &params, 60.0, 1.0)
+                .expect("predicted loss");
+        assert!((25.0..=27.0).contains(&t), "t = {}", t);
+    }
+}
@@ -0,0 +1,239 @@
+//! Wind fields for route planning.
+//!
+//! A single headwind figure slows every leg alike. Forecast winds vary along a long route and
+//! with height, and a leg flown downwind is quicker than the same leg flown upwind, so the
+//! planner can take a [`WindField`] of u/v samples instead: [`apply_wind`] stamps each grid point
+//! with the wind at its position and height, and the route engine costs each edge at the ground
+//! speed the drone makes good along it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::route_engine::RouteGrid;
+use crate::spatial::haversine_distance;
+
+/// One metre of height difference counts as this many metres of horizontal distance when
+/// interpolating: forecast samples sit kilometres apart but only tens of metres apart in height.
+const VERTICAL_DISTANCE_WEIGHT: f64 = 20.0;
+/// Samples closer than this are used as they are.
+const SNAP_DISTANCE_M: f64 = 1.0;
+
+/// Wind at one point.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindSample {
+    pub lat: f64,
+    pub lon: f64,
+    /// Height above ground the sample applies at; unset applies at every height.
+    #[serde(default)]
+    pub height_agl_m: Option<f64>,
+    /// Eastward component (m/s).
+    pub u_mps: f64,
+    /// Northward component (m/s).
+    pub v_mps: f64,
+}
+
+impl WindSample {
+    /// Sample from a meteorological report: `direction_deg` is where the wind blows from.
+    pub fn from_direction(
+        lat: f64,
+        lon: f64,
+        height_agl_m: Option<f64>,
+        speed_mps: f64,
+        direction_deg: f64,
+    ) -> Self {
+        let towards = direction_deg.to_radians();
+        Self {
+            lat,
+            lon,
+            height_agl_m,
+            u_mps: -speed_mps * towards.sin(),
+            v_mps: -speed_mps * towards.cos(),
+        }
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !self.lat.is_finite()
+            || !self.lon.is_finite()
+            || !(-90.0..=90.0).contains(&self.lat)
+            || !(-180.0..=180.0).contains(&self.lon)
+        {
+            errors.push("lat/lon must be a valid position".to_string());
+        }
+        if self.height_agl_m.is_some_and(|height| !height.is_finite()) {
+            errors.push("height_agl_m must be a finite number".to_string());
+        }
+        if !self.u_mps.is_finite() || !self.v_mps.is_finite() {
+            errors.push("u_mps/v_mps must be finite numbers".to_string());
+        }
+        errors
+    }
+
+    pub fn speed_mps(&self) -> f64 {
+        self.u_mps.hypot(self.v_mps)
+    }
+}
+
+/// Scattered wind samples, interpolated by inverse distance.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindField {
+    pub samples: Vec<WindSample>,
+}
+
+impl WindField {
+    /// `(u, v)` wind (m/s) at a position and height above ground; `None` without samples.
+    pub fn wind_at(&self, lat: f64, lon: f64, height_agl_m: f64) -> Option<(f64, f64)> {
+        let mut weight_sum = 0.0;
+        let mut u_sum = 0.0;
+        let mut v_sum = 0.0;
+        for sample in &self.samples {
+            let horizontal_m = haversine_distance(lat, lon, sample.lat, sample.lon);
+            let vertical_m = sample.height_agl_m.map_or(0.0, |height| {
+                (height - height_agl_m) * VERTICAL_DISTANCE_WEIGHT
+            });
+            let distance_m = horizontal_m.hypot(vertical_m);
+            if distance_m < SNAP_DISTANCE_M {
+                return Some((sample.u_mps, sample.v_mps));
+            }
+            let weight = 1.0 / (distance_m * distance_m);
+            weight_sum += weight;
+            u_sum += weight * sample.u_mps;
+            v_sum += weight * sample.v_mps;
+        }
+        (weight_sum > 0.0).then(|| (u_sum / weight_sum, v_sum / weight_sum))
+    }
+
+    /// Strongest wind in the field (m/s).
+    pub fn max_speed_mps(&self) -> f64 {
+        self.samples
+            .iter()
+            .map(WindSample::speed_mps)
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Ground speed made good along a track of `track_rad` (radians from north) at `airspeed_mps`,
+/// crabbing into the crosswind. Never below 1 m/s, matching the scalar headwind model.
+pub fn ground_speed_in_wind(airspeed_mps: f64, u_mps: f64, v_mps: f64, track_rad: f64) -> f64 {
+    let (east, north) = track_rad.sin_cos();
+    let tailwind = u_mps * east + v_mps * north;
+    let crosswind = u_mps * north - v_mps * east;
+    let along_air = (airspeed_mps * airspeed_mps - crosswind * crosswind)
+        .max(0.0)
+        .sqrt();
+    (along_air + tailwind).max(1.0)
+}
+
+/// Stamp every grid point with the field's wind at its position and height above terrain.
+pub fn apply_wind(grid: &mut RouteGrid, field: &WindField) {
+    if field.samples.is_empty() {
+        return;
+    }
+    for lane in &mut grid.lanes {
+        for point in lane.iter_mut() {
+            let height_agl_m = (point.altitude_m - point.terrain_height_m).max(0.0);
+            point.wind_uv_mps = field
+                .wind_at(point.lat, point.lon, height_agl_m)
+                .map(|(u, v)| [u, v]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Waypoint;
+    use crate::route_engine::{
+        build_lane_offsets, generate_grid_samples, optimize_flight_path, RouteEngineConfig,
+    };
+    use crate::spatial::offset_position;
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    #[test]
+    fn ground_speed_follows_the_wind_along_the_track() {
+        // 5 m/s from the north (blowing south).
+        let sample = WindSample::from_direction(ORIGIN.0, ORIGIN.1, None, 5.0, 0.0);
+        assert!(sample.u_mps.abs() < 1e-9);
+        assert!((sample.v_mps + 5.0).abs() < 1e-9);
+
+        let north = 0.0;
+        let south = std::f64::consts::PI;
+        let east = std::f64::consts::FRAC_PI_2;
+        let (u, v) = (sample.u_mps, sample.v_mps);
+        assert!((ground_speed_in_wind(15.0, u, v, north) - 10.0).abs() < 1e-9);
+        assert!((ground_speed_in_wind(15.0, u, v, south) - 20.0).abs() < 1e-9);
+        // Crabbing into a crosswind costs airspeed along the track.
+        assert!((ground_speed_in_wind(15.0, u, v, east) - 200.0_f64.sqrt()).abs() < 1e-9);
+        assert_eq!(ground_speed_in_wind(3.0, u, v, north), 1.0);
+
+        // Samples at 10 m and 120 m: the wind at 110 m is mostly the upper one.
+        let field = WindField {
+            samples: vec![
+                WindSample::from_direction(ORIGIN.0, ORIGIN.1, Some(10.0), 2.0, 0.0),
+                WindSample::from_direction(ORIGIN.0, ORIGIN.1, Some(120.0), 12.0, 0.0),
+            ],
+        };
+        let (_, v) = field.wind_at(ORIGIN.0, ORIGIN.1, 110.0).unwrap();
+        assert!(v < -10.0 && v > -12.0, "{}", v);
+        assert_eq!(field.max_speed_mps(), 12.0);
+        assert!(WindField::default()
+            .wind_at(ORIGIN.0, ORIGIN.1, 0.0)
+            .is_none());
+    }
+
+    /// Largest eastward deviation (m) of a route 10 km north under a 5 m/s northerly, except
+    /// for a strip 225-375 m east of the direct line where the wind blows north at
+    /// `strip_tailwind_mps`.
+    fn strip_deviation(strip_tailwind_mps: f64) -> f64 {
+        let (end_lat, end_lon) = offset_position(ORIGIN.0, ORIGIN.1, 10_000.0, 0.0);
+        let waypoints = vec![
+            Waypoint {
+                lat: ORIGIN.0,
+                lon: ORIGIN.1,
+                altitude_m: 60.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: end_lat,
+                lon: end_lon,
+                altitude_m: 60.0,
+                speed_mps: None,
+            },
+        ];
+        let mut samples = Vec::new();
+        for north_m in (-200..=10_200).step_by(100) {
+            for east_m in (-600..=600).step_by(75) {
+                let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m as f64, east_m as f64);
+                let in_strip = (225..=375).contains(&east_m);
+                samples.push(WindSample {
+                    lat,
+                    lon,
+                    height_agl_m: None,
+                    u_mps: 0.0,
+                    v_mps: if in_strip { strip_tailwind_mps } else { -5.0 },
+                });
+            }
+        }
+        let mut grid =
+            generate_grid_samples(&waypoints, 25.0, &build_lane_offsets(450.0, 150.0), 0.0)
+                .unwrap();
+        apply_wind(&mut grid, &WindField { samples });
+        let result = optimize_flight_path(&waypoints, &grid, &[], &RouteEngineConfig::default());
+        assert!(result.success);
+        result
+            .waypoints
+            .iter()
+            .map(|wp| haversine_distance(wp.lat, ORIGIN.1, wp.lat, wp.lon))
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn routes_into_a_tailwind_when_it_pays() {
+        // A 10 m/s tailwind more than makes up for the lane changes out of the headwind.
+        let deviation = strip_deviation(10.0);
+        assert!(deviation > 250.0, "{}", deviation);
+        // Under a uniform headwind the direct line is quickest.
+        let uniform = strip_deviation(-5.0);
+        assert!(uniform < 1.0, "{}", uniform);
+    }
+}
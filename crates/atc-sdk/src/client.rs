@@ -1,6 +1,7 @@
 //! ATC SDK client for drone registration and communication.
 
 use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use futures_util::StreamExt;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
@@ -10,6 +11,9 @@ use tokio_tungstenite::tungstenite::http::HeaderValue;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
+use crate::signing::{CommandTrust, CommandVerifier, TelemetrySignature};
+use atc_core::models::{CommandSigningKey, SignedCommand};
+
 #[derive(Debug, Deserialize)]
 struct PlanErrorWrapper {
     plan: atc_core::models::FlightPlan,
@@ -48,8 +52,16 @@ pub struct AtcClient {
     pub(crate) drone_id: Option<String>,
     pub(crate) owner_id: Option<String>,
     pub(crate) session_token: Option<String>,
+    pub(crate) session_expires_at: Option<DateTime<Utc>>,
+    /// Keys telemetry MACs; issued with the session token and never sent back.
+    pub(crate) telemetry_mac_secret: Option<String>,
     pub(crate) registration_token: Option<String>,
     pub(crate) admin_token: Option<String>,
+    pub(crate) command_trust: CommandTrust,
+    /// Set when the caller pinned a signing key; registration won't replace it.
+    pub(crate) command_key_pinned: bool,
+    /// Attach nonce/timestamp/HMAC headers to telemetry (on by default).
+    pub(crate) sign_telemetry: bool,
     pub(crate) client: reqwest::Client,
 }
 
@@ -65,11 +77,21 @@ pub struct RegisterRequest {
 pub struct RegisterResponse {
     pub drone_id: String,
     pub session_token: String,
+    /// Secret keying telemetry MACs, issued with the session token; `None` from older servers.
+    #[serde(default)]
+    pub telemetry_mac_secret: Option<String>,
+    /// When the session token expires; `None` if it never does.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Key the server signs commands with; `None` if commands are unsigned.
+    #[serde(default)]
+    pub command_signing_key: Option<CommandSigningKey>,
 }
 
 /// WebSocket command stream for a drone.
 pub struct CommandStream {
     socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    trust: CommandTrust,
 }
 
 #[derive(Debug, Serialize)]
@@ -85,8 +107,13 @@ impl AtcClient {
             drone_id: None,
             owner_id: None,
             session_token: None,
+            session_expires_at: None,
+            telemetry_mac_secret: None,
             registration_token: None,
             admin_token: None,
+            command_trust: CommandTrust::default(),
+            command_key_pinned: false,
+            sign_telemetry: true,
             client: reqwest::Client::new(),
         }
     }
@@ -101,6 +128,27 @@ impl AtcClient {
         self.admin_token = token;
     }
 
+    /// Pin the server's command signing key instead of learning it at registration.
+    ///
+    /// Once a key is known, unsigned or mis-signed commands are rejected.
+    pub fn set_command_signing_key(&mut self, key: &CommandSigningKey) -> Result<()> {
+        self.command_trust.verifier = Some(CommandVerifier::new(key)?);
+        self.command_key_pinned = true;
+        Ok(())
+    }
+
+    /// Reject unsigned commands even if the server never advertised a signing key.
+    pub fn require_signed_commands(&mut self, required: bool) {
+        self.command_trust.require_signed = required;
+    }
+
+    /// Enable or disable anti-replay signatures on telemetry.
+    ///
+    /// Telemetry is only signed once the server has issued a telemetry MAC secret.
+    pub fn set_telemetry_signing(&mut self, enabled: bool) {
+        self.sign_telemetry = enabled;
+    }
+
     /// Rotate the session token for a registered drone using an admin token.
     ///
     /// If the rotated drone matches this client's current `drone_id`, the client's cached
@@ -123,10 +171,53 @@ impl AtcClient {
         let response: RegisterResponse = parse_json_or_error(builder.send().await?).await?;
         if self.drone_id.as_deref() == Some(&response.drone_id) {
             self.session_token = Some(response.session_token.clone());
+            self.session_expires_at = response.expires_at;
+            self.telemetry_mac_secret = response.telemetry_mac_secret.clone();
         }
         Ok(response)
     }
 
+    /// Rotate this drone's session token using the current token.
+    ///
+    /// The server keeps the old token valid for a short overlap, so requests already in
+    /// flight with it still succeed.
+    pub async fn rotate_session_token(&mut self) -> Result<RegisterResponse> {
+        let auth = self
+            .session_token
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Drone not registered"))?;
+        let url = format!("{}/v1/drones/token/rotate", self.base_url);
+
+        let builder = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", auth));
+
+        let response: RegisterResponse = parse_json_or_error(builder.send().await?).await?;
+        self.session_token = Some(response.session_token.clone());
+        self.session_expires_at = response.expires_at;
+        self.telemetry_mac_secret = response.telemetry_mac_secret.clone();
+        Ok(response)
+    }
+
+    /// Rotate the session token if it expires within `refresh_before`.
+    ///
+    /// Returns `true` when a rotation happened.
+    pub async fn ensure_fresh_session(
+        &mut self,
+        refresh_before: std::time::Duration,
+    ) -> Result<bool> {
+        let Some(expires_at) = self.session_expires_at else {
+            return Ok(false);
+        };
+        let margin = ChronoDuration::from_std(refresh_before).unwrap_or(ChronoDuration::MAX);
+        if Utc::now() + margin < expires_at {
+            return Ok(false);
+        }
+        self.rotate_session_token().await?;
+        Ok(true)
+    }
+
     /// Register this drone with the ATC server.
     pub async fn register(&mut self, drone_id: Option<&str>) -> Result<RegisterResponse> {
         self.register_with_owner(drone_id, None).await
@@ -158,6 +249,23 @@ impl AtcClient {
             self.owner_id = Some(owner_id.to_string());
         }
         self.session_token = Some(response.session_token.clone());
+        self.session_expires_at = response.expires_at;
+        self.telemetry_mac_secret = response.telemetry_mac_secret.clone();
+        if let Some(key) = response.command_signing_key.as_ref() {
+            if !self.command_key_pinned {
+                self.command_trust.verifier = Some(CommandVerifier::new(key)?);
+            } else if self
+                .command_trust
+                .verifier
+                .as_ref()
+                .is_some_and(|verifier| verifier.key_id() != key.key_id)
+            {
+                tracing::warn!(
+                    "Server advertised command key {} but a different key is pinned",
+                    key.key_id
+                );
+            }
+        }
         Ok(response)
     }
 
@@ -176,6 +284,11 @@ impl AtcClient {
         self.session_token.as_deref()
     }
 
+    /// Get the current session token's expiry, if the server set one.
+    pub fn session_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.session_expires_at
+    }
+
     /// Set or update the owner ID for this client.
     pub fn set_owner_id(&mut self, owner_id: Option<String>) {
         self.owner_id = owner_id;
@@ -189,13 +302,27 @@ impl AtcClient {
             .as_deref()
             .ok_or_else(|| anyhow::anyhow!("Drone not registered"))?;
 
-        let response = self
+        let body = serde_json::to_vec(telemetry)?;
+        let mut builder = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", auth))
-            .json(telemetry)
-            .send()
-            .await?;
+            .header("Content-Type", "application/json");
+        let mac_secret = self
+            .telemetry_mac_secret
+            .as_deref()
+            .filter(|_| self.sign_telemetry);
+        if let Some(mac_secret) = mac_secret {
+            let signed = TelemetrySignature::new(mac_secret, Utc::now().timestamp_millis(), &body)?;
+            builder = builder
+                .header(
+                    TelemetrySignature::TIMESTAMP_HEADER,
+                    signed.timestamp_ms.to_string(),
+                )
+                .header(TelemetrySignature::NONCE_HEADER, signed.nonce)
+                .header(TelemetrySignature::SIGNATURE_HEADER, signed.signature);
+        }
+        let response = builder.body(body).send().await?;
 
         if !response.status().is_success() {
             anyhow::bail!("Failed to send telemetry: {}", response.status());
@@ -329,7 +456,7 @@ impl AtcClient {
     // ========== COMMAND HANDLING ==========
 
     /// Poll for the next pending command for this drone.
-    /// Returns None if no command is pending.
+    /// Returns None if no command is pending. Fails if the command's signature doesn't verify.
     pub async fn get_next_command(&self) -> Result<Option<atc_core::models::Command>> {
         let drone_id = self
             .drone_id
@@ -342,7 +469,7 @@ impl AtcClient {
 
         let url = format!("{}/v1/commands/next?drone_id={}", self.base_url, drone_id);
 
-        let response: Option<atc_core::models::Command> = self
+        let response: Option<SignedCommand> = self
             .client
             .get(&url)
             .header("Authorization", format!("Bearer {}", auth))
@@ -351,7 +478,9 @@ impl AtcClient {
             .json()
             .await?;
 
-        Ok(response)
+        response
+            .map(|signed| self.command_trust.accept(signed))
+            .transpose()
     }
 
     /// Acknowledge a command by ID (marks it as executed).
@@ -398,24 +527,28 @@ impl AtcClient {
         );
 
         let (socket, _) = connect_async(request).await?;
-        Ok(CommandStream { socket })
+        Ok(CommandStream {
+            socket,
+            trust: self.command_trust.clone(),
+        })
     }
 }
 
 impl CommandStream {
     /// Read the next command from the stream (returns None on close).
+    /// Fails if the command's signature doesn't verify.
     pub async fn next_command(&mut self) -> Result<Option<atc_core::models::Command>> {
         while let Some(msg) = self.socket.next().await {
             let msg = msg?;
             match msg {
                 Message::Text(text) => {
-                    let command = serde_json::from_str(&text)?;
-                    return Ok(Some(command));
+                    let signed: SignedCommand = serde_json::from_str(&text)?;
+                    return self.trust.accept(signed).map(Some);
                 }
                 Message::Binary(data) => {
                     if let Ok(text) = String::from_utf8(data) {
-                        if let Ok(command) = serde_json::from_str(&text) {
-                            return Ok(Some(command));
+                        if let Ok(signed) = serde_json::from_str::<SignedCommand>(&text) {
+                            return self.trust.accept(signed).map(Some);
                         }
                     }
                 }
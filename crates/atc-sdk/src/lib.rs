@@ -4,7 +4,10 @@
 
 pub mod client;
 pub mod commands;
+pub mod remote_id;
+pub mod signing;
 pub mod telemetry;
 
 pub use atc_core::models::Telemetry;
 pub use client::AtcClient;
+pub use remote_id::RemoteIdBroadcaster;
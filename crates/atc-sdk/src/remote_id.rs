@@ -0,0 +1,392 @@
+//! ASTM F3411 broadcast Remote ID frames.
+//!
+//! Encodes the telemetry a drone already reports to ATC, plus its UAS ID and operator location,
+//! into the 25-byte Basic ID, Location/Vector and System messages (protocol version 2,
+//! F3411-22a) that a companion computer hands to its Bluetooth or Wi-Fi beacon. Fields the
+//! telemetry does not carry (pressure altitude, accuracies) are sent as unknown.
+
+use anyhow::Result;
+use atc_core::models::Telemetry;
+use chrono::{DateTime, TimeZone, Timelike, Utc};
+
+/// Length of every Remote ID message.
+pub const MESSAGE_LEN: usize = 25;
+/// Longest UAS ID a Basic ID message carries.
+pub const MAX_ID_LEN: usize = 20;
+
+const PROTOCOL_VERSION: u8 = 2;
+const MESSAGE_TYPE_BASIC_ID: u8 = 0x0;
+const MESSAGE_TYPE_LOCATION: u8 = 0x1;
+const MESSAGE_TYPE_SYSTEM: u8 = 0x4;
+const MESSAGE_TYPE_PACK: u8 = 0xF;
+
+/// Horizontal speed is sent in 0.25 m/s steps up to this, then in 0.75 m/s steps.
+const SPEED_FINE_MAX_MPS: f64 = 255.0 * 0.25;
+const SPEED_UNKNOWN: u8 = 255;
+const DIRECTION_UNKNOWN_DEG: u16 = 361;
+const VERTICAL_SPEED_MAX_MPS: f64 = 62.0;
+const VERTICAL_SPEED_UNKNOWN: i8 = 126;
+const TIMESTAMP_UNKNOWN: u16 = 0xFFFF;
+
+/// What the UAS ID in the Basic ID message is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UasIdType {
+    None = 0,
+    /// ANSI/CTA-2063-A serial number
+    SerialNumber = 1,
+    /// Registration ID issued by the civil aviation authority
+    CaaRegistration = 2,
+    /// UUID assigned by the UTM service
+    UtmAssigned = 3,
+    SpecificSession = 4,
+}
+
+/// Kind of aircraft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UaType {
+    None = 0,
+    Aeroplane = 1,
+    /// Helicopter or multirotor
+    Rotorcraft = 2,
+    Gyroplane = 3,
+    /// Fixed-wing VTOL
+    HybridLift = 4,
+    Ornithopter = 5,
+    Glider = 6,
+    Kite = 7,
+    FreeBalloon = 8,
+    CaptiveBalloon = 9,
+    Airship = 10,
+    Parachute = 11,
+    Rocket = 12,
+    TetheredPowered = 13,
+    GroundObstacle = 14,
+    Other = 15,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationalStatus {
+    Undeclared = 0,
+    Ground = 1,
+    Airborne = 2,
+    Emergency = 3,
+    RemoteIdFailure = 4,
+}
+
+/// Where the operator position in the System message comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatorLocationType {
+    Takeoff = 0,
+    LiveGnss = 1,
+    Fixed = 2,
+}
+
+/// Operator position reported in the System message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperatorLocation {
+    pub lat: f64,
+    pub lon: f64,
+    /// Geodetic altitude, if known
+    pub altitude_m: Option<f64>,
+    pub location_type: OperatorLocationType,
+}
+
+/// Static identity of the aircraft and its operator, combined with telemetry per broadcast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteIdBroadcaster {
+    uas_id: String,
+    id_type: UasIdType,
+    ua_type: UaType,
+    operator: OperatorLocation,
+    takeoff_altitude_m: Option<f64>,
+}
+
+/// One broadcast cycle's messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BroadcastFrames {
+    pub basic_id: [u8; MESSAGE_LEN],
+    pub location: [u8; MESSAGE_LEN],
+    pub system: [u8; MESSAGE_LEN],
+}
+
+impl BroadcastFrames {
+    /// The messages in one Message Pack, for Bluetooth 5 long range and Wi-Fi beacons.
+    pub fn message_pack(&self) -> Vec<u8> {
+        let messages = [self.basic_id, self.location, self.system];
+        let mut pack = vec![
+            header(MESSAGE_TYPE_PACK),
+            MESSAGE_LEN as u8,
+            messages.len() as u8,
+        ];
+        for message in messages {
+            pack.extend_from_slice(&message);
+        }
+        pack
+    }
+}
+
+impl RemoteIdBroadcaster {
+    /// Fails if `uas_id` is not printable ASCII of at most [`MAX_ID_LEN`] characters.
+    pub fn new(
+        uas_id: impl Into<String>,
+        id_type: UasIdType,
+        ua_type: UaType,
+        operator: OperatorLocation,
+    ) -> Result<Self> {
+        let uas_id = uas_id.into();
+        if uas_id.is_empty()
+            || uas_id.len() > MAX_ID_LEN
+            || !uas_id.bytes().all(|b| b.is_ascii_graphic())
+        {
+            anyhow::bail!(
+                "UAS ID must be 1-{} printable ASCII characters, got '{}'",
+                MAX_ID_LEN,
+                uas_id
+            );
+        }
+        Ok(Self {
+            uas_id,
+            id_type,
+            ua_type,
+            operator,
+            takeoff_altitude_m: None,
+        })
+    }
+
+    /// Report height above the takeoff point, which is at this geodetic altitude.
+    pub fn with_takeoff_altitude(mut self, altitude_m: f64) -> Self {
+        self.takeoff_altitude_m = Some(altitude_m);
+        self
+    }
+
+    /// Move the operator, e.g. when `OperatorLocationType::LiveGnss` tracks the ground station.
+    pub fn set_operator_location(&mut self, operator: OperatorLocation) {
+        self.operator = operator;
+    }
+
+    /// Encode a telemetry sample into the messages to broadcast.
+    pub fn frames(&self, telemetry: &Telemetry, status: OperationalStatus) -> BroadcastFrames {
+        BroadcastFrames {
+            basic_id: self.basic_id(),
+            location: self.location(telemetry, status),
+            system: self.system(telemetry.timestamp),
+        }
+    }
+
+    fn basic_id(&self) -> [u8; MESSAGE_LEN] {
+        let mut message = [0u8; MESSAGE_LEN];
+        message[0] = header(MESSAGE_TYPE_BASIC_ID);
+        message[1] = ((self.id_type as u8) << 4) | self.ua_type as u8;
+        message[2..2 + self.uas_id.len()].copy_from_slice(self.uas_id.as_bytes());
+        message
+    }
+
+    fn location(&self, telemetry: &Telemetry, status: OperationalStatus) -> [u8; MESSAGE_LEN] {
+        let mut message = [0u8; MESSAGE_LEN];
+        message[0] = header(MESSAGE_TYPE_LOCATION);
+
+        let speed_mps = telemetry.velocity_x.hypot(telemetry.velocity_y);
+        let (speed_mps, track_deg) = if speed_mps > 0.0 {
+            let track = telemetry
+                .velocity_x
+                .atan2(telemetry.velocity_y)
+                .to_degrees();
+            (speed_mps, Some(track.rem_euclid(360.0)))
+        } else {
+            let heading = telemetry.heading_deg;
+            let track = heading.is_finite().then(|| heading.rem_euclid(360.0));
+            (telemetry.speed_mps, track)
+        };
+        let (direction, east_west) = encode_direction(track_deg);
+        let (speed, multiplier) = encode_speed(speed_mps);
+        // Height is measured above takeoff (height type bit clear).
+        message[1] = ((status as u8) << 4) | (east_west << 1) | multiplier;
+        message[2] = direction;
+        message[3] = speed;
+        message[4] = encode_vertical_speed(telemetry.velocity_z) as u8;
+        message[5..9].copy_from_slice(&encode_degrees(telemetry.lat).to_le_bytes());
+        message[9..13].copy_from_slice(&encode_degrees(telemetry.lon).to_le_bytes());
+        message[13..15].copy_from_slice(&encode_altitude(None).to_le_bytes());
+        message[15..17].copy_from_slice(&encode_altitude(Some(telemetry.altitude_m)).to_le_bytes());
+        let height_m = self
+            .takeoff_altitude_m
+            .map(|takeoff| telemetry.altitude_m - takeoff);
+        message[17..19].copy_from_slice(&encode_altitude(height_m).to_le_bytes());
+        // Accuracy bytes 19-20 and 23 stay 0 (unknown).
+        message[21..23]
+            .copy_from_slice(&encode_tenths_past_hour(telemetry.timestamp).to_le_bytes());
+        message
+    }
+
+    fn system(&self, timestamp: DateTime<Utc>) -> [u8; MESSAGE_LEN] {
+        let mut message = [0u8; MESSAGE_LEN];
+        message[0] = header(MESSAGE_TYPE_SYSTEM);
+        // Classification type undeclared (bits 4-2 clear).
+        message[1] = self.operator.location_type as u8;
+        message[2..6].copy_from_slice(&encode_degrees(self.operator.lat).to_le_bytes());
+        message[6..10].copy_from_slice(&encode_degrees(self.operator.lon).to_le_bytes());
+        // A single aircraft: area count 1, radius 0, ceiling and floor unknown.
+        message[10..12].copy_from_slice(&1u16.to_le_bytes());
+        message[13..15].copy_from_slice(&encode_altitude(None).to_le_bytes());
+        message[15..17].copy_from_slice(&encode_altitude(None).to_le_bytes());
+        message[18..20].copy_from_slice(&encode_altitude(self.operator.altitude_m).to_le_bytes());
+        message[20..24].copy_from_slice(&encode_seconds_since_2019(timestamp).to_le_bytes());
+        message
+    }
+}
+
+fn header(message_type: u8) -> u8 {
+    (message_type << 4) | PROTOCOL_VERSION
+}
+
+/// Degrees as a 1e-7 fixed-point integer.
+fn encode_degrees(degrees: f64) -> i32 {
+    if degrees.is_finite() {
+        (degrees * 1e7).round() as i32
+    } else {
+        0
+    }
+}
+
+/// Altitudes are 0.5 m steps above -1000 m, which doubles as "unknown".
+fn encode_altitude(altitude_m: Option<f64>) -> u16 {
+    altitude_m
+        .filter(|altitude| altitude.is_finite())
+        .map(|altitude| ((altitude + 1000.0) / 0.5).round().clamp(1.0, 65535.0) as u16)
+        .unwrap_or(0)
+}
+
+/// Track as 0-179 degrees plus an east-west flag adding 180.
+fn encode_direction(track_deg: Option<f64>) -> (u8, u8) {
+    let degrees = track_deg
+        .map(|track| (track.round() as u16) % 360)
+        .unwrap_or(DIRECTION_UNKNOWN_DEG);
+    if degrees < 180 {
+        (degrees as u8, 0)
+    } else {
+        ((degrees - 180) as u8, 1)
+    }
+}
+
+/// Horizontal speed and its multiplier flag.
+fn encode_speed(speed_mps: f64) -> (u8, u8) {
+    if !speed_mps.is_finite() || speed_mps < 0.0 {
+        return (SPEED_UNKNOWN, 1);
+    }
+    if speed_mps <= SPEED_FINE_MAX_MPS {
+        ((speed_mps / 0.25).round() as u8, 0)
+    } else {
+        let coarse = ((speed_mps - SPEED_FINE_MAX_MPS) / 0.75).round();
+        (coarse.min(254.0) as u8, 1)
+    }
+}
+
+/// Climb rate in 0.5 m/s steps, positive up.
+fn encode_vertical_speed(climb_mps: f64) -> i8 {
+    if !climb_mps.is_finite() {
+        return VERTICAL_SPEED_UNKNOWN;
+    }
+    let clamped = climb_mps.clamp(-VERTICAL_SPEED_MAX_MPS, VERTICAL_SPEED_MAX_MPS);
+    (clamped / 0.5).round() as i8
+}
+
+fn encode_tenths_past_hour(timestamp: DateTime<Utc>) -> u16 {
+    let tenths = (timestamp.minute() * 60 + timestamp.second()) * 10
+        + timestamp.timestamp_subsec_millis() / 100;
+    u16::try_from(tenths).unwrap_or(TIMESTAMP_UNKNOWN)
+}
+
+fn encode_seconds_since_2019(timestamp: DateTime<Utc>) -> u32 {
+    let epoch = Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap();
+    u32::try_from((timestamp - epoch).num_seconds()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn broadcaster() -> RemoteIdBroadcaster {
+        RemoteIdBroadcaster::new(
+            "1596F3502220931",
+            UasIdType::SerialNumber,
+            UaType::Rotorcraft,
+            OperatorLocation {
+                lat: 32.7,
+                lon: -117.16,
+                altitude_m: Some(20.0),
+                location_type: OperatorLocationType::Takeoff,
+            },
+        )
+        .unwrap()
+        .with_takeoff_altitude(20.0)
+    }
+
+    fn telemetry() -> Telemetry {
+        Telemetry {
+            drone_id: "DRONE-1".to_string(),
+            owner_id: None,
+            lat: 32.7012345,
+            lon: -117.1598765,
+            altitude_m: 80.0,
+            velocity_x: 0.0,
+            velocity_y: -10.0,
+            velocity_z: 1.5,
+            heading_deg: 0.0,
+            speed_mps: 10.0,
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 1, 14, 5, 30).unwrap(),
+        }
+    }
+
+    #[test]
+    fn frames_encode_telemetry_and_operator() {
+        let frames = broadcaster().frames(&telemetry(), OperationalStatus::Airborne);
+
+        let basic_id = frames.basic_id;
+        assert_eq!(basic_id[0], 0x02);
+        assert_eq!(basic_id[1], 0x12);
+        assert_eq!(&basic_id[2..17], b"1596F3502220931");
+        assert!(basic_id[17..].iter().all(|b| *b == 0));
+
+        let location = frames.location;
+        assert_eq!(location[0], 0x12);
+        // Airborne, heading south (180 degrees, east-west flag set), fine speed steps.
+        assert_eq!(location[1], 0x22);
+        assert_eq!(location[2], 0);
+        assert_eq!(location[3], 40);
+        assert_eq!(location[4], 3);
+        let i32_at =
+            |bytes: &[u8], at: usize| i32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let u16_at = |bytes: &[u8], at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+        assert_eq!(i32_at(&location, 5), 327_012_345);
+        assert_eq!(i32_at(&location, 9), -1_171_598_765);
+        assert_eq!(u16_at(&location, 13), 0);
+        assert_eq!(u16_at(&location, 15), 2160);
+        assert_eq!(u16_at(&location, 17), 2120);
+        assert_eq!(u16_at(&location, 21), 3300);
+
+        let system = frames.system;
+        assert_eq!(system[0], 0x42);
+        assert_eq!(i32_at(&system, 2), 327_000_000);
+        assert_eq!(u16_at(&system, 10), 1);
+        assert_eq!(u16_at(&system, 18), 2040);
+
+        let pack = frames.message_pack();
+        assert_eq!(&pack[..3], &[0xF2, 25, 3]);
+        assert_eq!(pack.len(), 3 + 3 * MESSAGE_LEN);
+    }
+
+    #[test]
+    fn speeds_and_ids_outside_the_fine_range() {
+        assert_eq!(encode_speed(70.0), (8, 1));
+        assert_eq!(encode_speed(f64::NAN), (SPEED_UNKNOWN, 1));
+        assert_eq!(encode_direction(None), (181, 1));
+        assert_eq!(encode_vertical_speed(-100.0), -124);
+        assert!(RemoteIdBroadcaster::new(
+            "ID-LONGER-THAN-TWENTY-CHARS",
+            UasIdType::CaaRegistration,
+            UaType::Aeroplane,
+            broadcaster().operator,
+        )
+        .is_err());
+    }
+}
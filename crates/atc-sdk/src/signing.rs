@@ -0,0 +1,209 @@
+//! Verification of server-signed commands and signing of outgoing telemetry.
+
+use anyhow::{Context, Result};
+use atc_core::models::{Command, CommandSigningKey, SignedCommand};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{UnparsedPublicKey, ED25519};
+
+/// Verifies command signatures against the server's Ed25519 public key.
+#[derive(Debug, Clone)]
+pub struct CommandVerifier {
+    key_id: String,
+    public_key: Vec<u8>,
+}
+
+impl CommandVerifier {
+    pub fn new(key: &CommandSigningKey) -> Result<Self> {
+        if key.algorithm != "ed25519" {
+            anyhow::bail!("Unsupported command signing algorithm '{}'", key.algorithm);
+        }
+        let public_key = BASE64
+            .decode(key.public_key.trim())
+            .context("Command signing public key is not valid base64")?;
+        if public_key.len() != 32 {
+            anyhow::bail!("Command signing public key must be 32 bytes");
+        }
+        Ok(Self {
+            key_id: key.key_id.clone(),
+            public_key,
+        })
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Check a delivered command and return it if the signature is valid.
+    pub fn verify(&self, signed: SignedCommand) -> Result<Command> {
+        let signature = signed.signature.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("Command {} is not signed", signed.command.command_id)
+        })?;
+        if signature.key_id != self.key_id {
+            anyhow::bail!(
+                "Command {} signed with unknown key {}",
+                signed.command.command_id,
+                signature.key_id
+            );
+        }
+        let bytes = BASE64
+            .decode(signature.signature.trim())
+            .context("Command signature is not valid base64")?;
+        let payload = BASE64
+            .decode(signature.payload.trim())
+            .context("Command signed payload is not valid base64")?;
+        UnparsedPublicKey::new(&ED25519, &self.public_key)
+            .verify(&payload, &bytes)
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Command {} failed signature verification",
+                    signed.command.command_id
+                )
+            })?;
+        // The signed bytes are authoritative; the unsigned copy alongside them is ignored.
+        serde_json::from_slice(&payload).with_context(|| {
+            format!(
+                "Command {} signed payload is not a command",
+                signed.command.command_id
+            )
+        })
+    }
+}
+
+/// How a client treats incoming commands.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CommandTrust {
+    pub(crate) verifier: Option<CommandVerifier>,
+    /// Reject unsigned commands even before a key is known.
+    pub(crate) require_signed: bool,
+}
+
+impl CommandTrust {
+    pub(crate) fn accept(&self, signed: SignedCommand) -> Result<Command> {
+        match self.verifier.as_ref() {
+            Some(verifier) => verifier.verify(signed),
+            None if self.require_signed => anyhow::bail!(
+                "Command {} rejected: no command signing key known",
+                signed.command.command_id
+            ),
+            None => Ok(signed.command),
+        }
+    }
+}
+
+/// Anti-replay headers for a telemetry body: timestamp (ms), a random nonce and an
+/// HMAC-SHA256 over `"{timestamp}.{nonce}." + body`, keyed by the telemetry MAC secret.
+pub(crate) struct TelemetrySignature {
+    pub timestamp_ms: i64,
+    pub nonce: String,
+    pub signature: String,
+}
+
+impl TelemetrySignature {
+    pub(crate) const TIMESTAMP_HEADER: &'static str = "X-ATC-Timestamp";
+    pub(crate) const NONCE_HEADER: &'static str = "X-ATC-Nonce";
+    pub(crate) const SIGNATURE_HEADER: &'static str = "X-ATC-Signature";
+
+    pub(crate) fn new(mac_secret: &str, timestamp_ms: i64, body: &[u8]) -> Result<Self> {
+        let mut nonce_bytes = [0u8; 16];
+        SystemRandom::new()
+            .fill(&mut nonce_bytes)
+            .map_err(|_| anyhow::anyhow!("Failed to generate telemetry nonce"))?;
+        let nonce = to_hex(&nonce_bytes);
+
+        let key = hmac::Key::new(hmac::HMAC_SHA256, mac_secret.as_bytes());
+        let mut context = hmac::Context::with_key(&key);
+        context.update(format!("{}.{}.", timestamp_ms, nonce).as_bytes());
+        context.update(body);
+        Ok(Self {
+            timestamp_ms,
+            nonce,
+            signature: to_hex(context.sign().as_ref()),
+        })
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atc_core::models::{CommandSignature, CommandType};
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    fn key_pair(seed: u8) -> Ed25519KeyPair {
+        Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap()
+    }
+
+    fn verifier(key_pair: &Ed25519KeyPair) -> CommandVerifier {
+        CommandVerifier::new(&CommandSigningKey {
+            key_id: "key-1".to_string(),
+            algorithm: "ed25519".to_string(),
+            public_key: BASE64.encode(key_pair.public_key().as_ref()),
+        })
+        .unwrap()
+    }
+
+    fn signed(key_pair: &Ed25519KeyPair) -> SignedCommand {
+        let command = Command {
+            command_id: "CMD-1".to_string(),
+            drone_id: "DRONE-1".to_string(),
+            command_type: CommandType::AltitudeChange {
+                target_altitude_m: 90.0,
+            },
+            issued_at: chrono::Utc::now(),
+            expires_at: None,
+            acknowledged: false,
+        };
+        let payload = command.signing_payload();
+        SignedCommand {
+            signature: Some(CommandSignature {
+                key_id: "key-1".to_string(),
+                algorithm: "ed25519".to_string(),
+                signature: BASE64.encode(key_pair.sign(&payload).as_ref()),
+                payload: BASE64.encode(payload),
+            }),
+            command,
+        }
+    }
+
+    #[test]
+    fn valid_signature_returns_the_signed_command() {
+        let key_pair = key_pair(7);
+        let mut delivered = signed(&key_pair);
+        // The unsigned copy is ignored in favour of the signed bytes.
+        delivered.command.command_type = CommandType::Land;
+
+        let command = verifier(&key_pair).verify(delivered).unwrap();
+        assert_eq!(command.command_id, "CMD-1");
+        assert!(matches!(
+            command.command_type,
+            CommandType::AltitudeChange { target_altitude_m } if target_altitude_m == 90.0
+        ));
+    }
+
+    #[test]
+    fn tampered_payload_is_rejected() {
+        let key_pair = key_pair(7);
+        let mut delivered = signed(&key_pair);
+        let signature = delivered.signature.as_mut().unwrap();
+        let tampered = String::from_utf8(BASE64.decode(&signature.payload).unwrap())
+            .unwrap()
+            .replace("90.0", "9.0");
+        signature.payload = BASE64.encode(tampered);
+
+        let err = verifier(&key_pair).verify(delivered).unwrap_err();
+        assert!(err.to_string().contains("failed signature verification"));
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        let delivered = signed(&key_pair(7));
+        let err = verifier(&key_pair(8)).verify(delivered).unwrap_err();
+        assert!(err.to_string().contains("failed signature verification"));
+    }
+}
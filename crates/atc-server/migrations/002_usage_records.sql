@@ -0,0 +1,11 @@
+-- Monthly usage per organization (billing metering)
+CREATE TABLE IF NOT EXISTS usage_records (
+    organization TEXT NOT NULL,
+    month TEXT NOT NULL,
+    flight_hours REAL NOT NULL DEFAULT 0,
+    plans_submitted INTEGER NOT NULL DEFAULT 0,
+    planner_cpu_seconds REAL NOT NULL DEFAULT 0,
+    api_calls INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (organization, month)
+);
@@ -0,0 +1,9 @@
+-- Per-drone performance envelopes (what each airframe can physically fly)
+CREATE TABLE IF NOT EXISTS drone_performance (
+    drone_id TEXT PRIMARY KEY,
+    max_climb_rate_mps REAL NOT NULL,
+    max_speed_mps REAL NOT NULL,
+    turn_rate_deg_s REAL NOT NULL,
+    max_wind_mps REAL NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,10 @@
+-- Per-drone home points and optional distance tethers
+CREATE TABLE IF NOT EXISTS drone_homes (
+    drone_id TEXT PRIMARY KEY,
+    lat REAL NOT NULL,
+    lon REAL NOT NULL,
+    altitude_m REAL NOT NULL,
+    tether_radius_m REAL,
+    auto_rth INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
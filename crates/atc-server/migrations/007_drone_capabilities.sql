@@ -0,0 +1,9 @@
+-- Commands and equipment each drone declared at registration
+CREATE TABLE IF NOT EXISTS drone_capabilities (
+    drone_id TEXT PRIMARY KEY,
+    supports_reroute INTEGER NOT NULL,
+    supports_hold INTEGER NOT NULL,
+    max_climb_rate_mps REAL,
+    rid_module INTEGER NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,17 @@
+-- Flown tracks: one sample per drone per telemetry flush, for track replay and post-flight review
+CREATE TABLE IF NOT EXISTS telemetry_history (
+    drone_id TEXT NOT NULL,
+    recorded_at_ms INTEGER NOT NULL,
+    lat REAL NOT NULL,
+    lon REAL NOT NULL,
+    altitude_m REAL NOT NULL,
+    heading_deg REAL NOT NULL,
+    speed_mps REAL NOT NULL,
+    velocity_x REAL NOT NULL,
+    velocity_y REAL NOT NULL,
+    velocity_z REAL NOT NULL,
+    status TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_telemetry_history_drone_time ON telemetry_history(drone_id, recorded_at_ms);
+CREATE INDEX IF NOT EXISTS idx_telemetry_history_time ON telemetry_history(recorded_at_ms);
@@ -0,0 +1,9 @@
+-- Operator callback URLs for flight, conflict, command and drone events
+CREATE TABLE IF NOT EXISTS webhooks (
+    webhook_id TEXT PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT NOT NULL,
+    owner_id TEXT,
+    created_at TEXT NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Safety rule values tuned at runtime through the admin API, applied over the configured rules
+CREATE TABLE IF NOT EXISTS safety_rules_override (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    overrides TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+-- Deregistered drones keep their row (flight plan history refers to it) but are no longer loaded
+ALTER TABLE drones ADD COLUMN archived_at TEXT;
@@ -0,0 +1,14 @@
+-- Append-only trail of issued commands and how each one ended, kept after the command rows are deleted
+CREATE TABLE IF NOT EXISTS command_audit (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    command_id TEXT NOT NULL,
+    drone_id TEXT NOT NULL,
+    event TEXT NOT NULL, -- issued, acknowledged, expired, timed_out, cancelled
+    command_type TEXT, -- JSON, on issued rows
+    cause TEXT, -- on issued rows
+    cause_id TEXT,
+    recorded_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_command_audit_command ON command_audit(command_id, event);
+CREATE INDEX IF NOT EXISTS idx_command_audit_drone_time ON command_audit(drone_id, recorded_at);
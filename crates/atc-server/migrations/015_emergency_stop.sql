@@ -0,0 +1,6 @@
+-- The emergency all-stop in force, if any; kept so a restart doesn't resume plan approvals
+CREATE TABLE IF NOT EXISTS emergency_stop (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    stop TEXT NOT NULL,
+    declared_at TEXT NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Conformance escalation policy tuned through the admin API, replacing the built-in default
+CREATE TABLE IF NOT EXISTS conformance_policy (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    policy TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,4 @@
+-- Per-drone secret keying telemetry message MACs, issued with the session token but never sent
+-- on requests; the previous one stays valid with the previous token during a rotation overlap
+ALTER TABLE drone_tokens ADD COLUMN mac_secret TEXT;
+ALTER TABLE drone_tokens ADD COLUMN previous_mac_secret TEXT;
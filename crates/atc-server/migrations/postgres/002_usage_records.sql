@@ -0,0 +1,11 @@
+-- Monthly usage per organization (billing metering)
+CREATE TABLE IF NOT EXISTS usage_records (
+    organization TEXT NOT NULL,
+    month TEXT NOT NULL,
+    flight_hours DOUBLE PRECISION NOT NULL DEFAULT 0,
+    plans_submitted BIGINT NOT NULL DEFAULT 0,
+    planner_cpu_seconds DOUBLE PRECISION NOT NULL DEFAULT 0,
+    api_calls BIGINT NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT CAST(CURRENT_TIMESTAMP AS TEXT),
+    PRIMARY KEY (organization, month)
+);
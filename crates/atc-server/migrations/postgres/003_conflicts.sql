@@ -0,0 +1,16 @@
+-- Ended conflicts (resolved or expired) for post-incident review
+CREATE TABLE IF NOT EXISTS conflicts (
+    conflict_id TEXT PRIMARY KEY,
+    drone1_id TEXT NOT NULL,
+    drone2_id TEXT NOT NULL,
+    peak_severity TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    ended_at TEXT NOT NULL,
+    min_separation_m DOUBLE PRECISION NOT NULL,
+    sector_id TEXT,
+    outcome TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_conflicts_started_at ON conflicts(started_at);
+CREATE INDEX IF NOT EXISTS idx_conflicts_drone1 ON conflicts(drone1_id);
+CREATE INDEX IF NOT EXISTS idx_conflicts_drone2 ON conflicts(drone2_id);
@@ -0,0 +1,10 @@
+-- Per-drone home points and optional distance tethers
+CREATE TABLE IF NOT EXISTS drone_homes (
+    drone_id TEXT PRIMARY KEY,
+    lat DOUBLE PRECISION NOT NULL,
+    lon DOUBLE PRECISION NOT NULL,
+    altitude_m DOUBLE PRECISION NOT NULL,
+    tether_radius_m DOUBLE PRECISION,
+    auto_rth BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TEXT NOT NULL DEFAULT CAST(CURRENT_TIMESTAMP AS TEXT)
+);
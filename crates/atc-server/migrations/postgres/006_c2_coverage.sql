@@ -0,0 +1,8 @@
+-- C2 link coverage areas used for BVLOS route planning and compliance
+CREATE TABLE IF NOT EXISTS c2_coverage (
+    id TEXT PRIMARY KEY,
+    name TEXT,
+    link_type TEXT,
+    polygon TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CAST(CURRENT_TIMESTAMP AS TEXT)
+);
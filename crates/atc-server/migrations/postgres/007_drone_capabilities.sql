@@ -0,0 +1,9 @@
+-- Commands and equipment each drone declared at registration
+CREATE TABLE IF NOT EXISTS drone_capabilities (
+    drone_id TEXT PRIMARY KEY,
+    supports_reroute BOOLEAN NOT NULL,
+    supports_hold BOOLEAN NOT NULL,
+    max_climb_rate_mps DOUBLE PRECISION,
+    rid_module BOOLEAN NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CAST(CURRENT_TIMESTAMP AS TEXT)
+);
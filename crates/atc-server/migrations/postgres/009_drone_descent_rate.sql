@@ -0,0 +1,2 @@
+-- Descent rate of a drone's performance envelope; the climb rate applies when NULL
+ALTER TABLE drone_performance ADD COLUMN max_descent_rate_mps DOUBLE PRECISION;
@@ -0,0 +1,17 @@
+-- Flown tracks: one sample per drone per telemetry flush, for track replay and post-flight review
+CREATE TABLE IF NOT EXISTS telemetry_history (
+    drone_id TEXT NOT NULL,
+    recorded_at_ms BIGINT NOT NULL,
+    lat DOUBLE PRECISION NOT NULL,
+    lon DOUBLE PRECISION NOT NULL,
+    altitude_m DOUBLE PRECISION NOT NULL,
+    heading_deg DOUBLE PRECISION NOT NULL,
+    speed_mps DOUBLE PRECISION NOT NULL,
+    velocity_x DOUBLE PRECISION NOT NULL,
+    velocity_y DOUBLE PRECISION NOT NULL,
+    velocity_z DOUBLE PRECISION NOT NULL,
+    status TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_telemetry_history_drone_time ON telemetry_history(drone_id, recorded_at_ms);
+CREATE INDEX IF NOT EXISTS idx_telemetry_history_time ON telemetry_history(recorded_at_ms);
@@ -0,0 +1,18 @@
+-- Tenants sharing the server: their API tokens (hashed), member operators and private geofences
+CREATE TABLE IF NOT EXISTS organizations (
+    org_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL
+);
+
+-- An operator (owner ID) belongs to at most one organization
+CREATE TABLE IF NOT EXISTS organization_members (
+    owner_id TEXT PRIMARY KEY,
+    org_id TEXT NOT NULL,
+    added_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_members_org ON organization_members(org_id);
+
+ALTER TABLE geofences ADD COLUMN org_id TEXT;
@@ -0,0 +1,251 @@
+//! Manned traffic from an ADS-B feed.
+//!
+//! The ADS-B loop polls `ATC_ADSB_FEED_URL`, either a dump1090/readsb `aircraft.json` or the
+//! OpenSky `states/all` API, and feeds each airborne aircraft below `ATC_ADSB_MAX_ALTITUDE_M`
+//! in `ATC_ADSB_AREA` into the conflict detector as an `ADSB-<icao>` external track. Aircraft
+//! are crewed unless their emitter category says UAV, so they get the crewed protection volume
+//! rather than the drone minima.
+
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use serde_json::Value;
+
+use atc_core::msa::MsaBounds;
+use atc_core::AircraftCategory;
+
+use crate::state::ExternalTraffic;
+
+const FEET_TO_M: f64 = 0.3048;
+const KNOTS_TO_MPS: f64 = 0.514_444;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdsbFeedFormat {
+    /// dump1090/readsb `aircraft.json`: feet and knots.
+    Dump1090,
+    /// OpenSky `states/all`: metres and metres per second.
+    OpenSky,
+}
+
+impl AdsbFeedFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "dump1090" | "readsb" | "tar1090" => Some(Self::Dump1090),
+            "opensky" => Some(Self::OpenSky),
+            _ => None,
+        }
+    }
+}
+
+/// Which aircraft in the feed are of interest.
+#[derive(Debug, Clone, Copy)]
+pub struct AdsbFilter {
+    pub area: Option<MsaBounds>,
+    /// Aircraft above this (AMSL) are ignored.
+    pub max_altitude_m: f64,
+    /// Converts geometric (WGS84) altitudes to AMSL.
+    pub geoid_offset_m: f64,
+}
+
+impl AdsbFilter {
+    fn keeps(&self, track: &ExternalTraffic) -> bool {
+        track.altitude_m <= self.max_altitude_m
+            && self
+                .area
+                .is_none_or(|area| area.contains(track.lat, track.lon))
+    }
+}
+
+type EntryParser = fn(&Value, &AdsbFilter, DateTime<Utc>) -> Option<ExternalTraffic>;
+
+/// Airborne aircraft in a feed document, with AMSL altitudes.
+pub fn parse_feed(
+    format: AdsbFeedFormat,
+    document: &Value,
+    filter: &AdsbFilter,
+    now: DateTime<Utc>,
+) -> Result<Vec<ExternalTraffic>, String> {
+    let (field, parse): (&str, EntryParser) = match format {
+        AdsbFeedFormat::Dump1090 => ("aircraft", dump1090_track),
+        AdsbFeedFormat::OpenSky => ("states", opensky_track),
+    };
+    let entries = match document.get(field) {
+        Some(Value::Array(entries)) => entries,
+        // OpenSky returns `"states": null` when nothing is in view.
+        Some(Value::Null) => return Ok(Vec::new()),
+        _ => return Err(format!("Feed has no {field} array")),
+    };
+    let feed_time = document
+        .get("now")
+        .or_else(|| document.get("time"))
+        .and_then(Value::as_f64)
+        .and_then(epoch)
+        .unwrap_or(now);
+    Ok(entries
+        .iter()
+        .filter_map(|entry| parse(entry, filter, feed_time))
+        .filter(|track| filter.keeps(track))
+        .collect())
+}
+
+fn dump1090_track(
+    aircraft: &Value,
+    filter: &AdsbFilter,
+    feed_time: DateTime<Utc>,
+) -> Option<ExternalTraffic> {
+    let hex = aircraft.get("hex")?.as_str()?.trim_start_matches('~');
+    // `alt_baro` is "ground" for aircraft on the surface.
+    let altitude_m = match (number(aircraft.get("alt_geom")), aircraft.get("alt_baro")) {
+        (_, Some(Value::String(ground))) if ground == "ground" => return None,
+        (Some(geometric_ft), _) => geometric_ft * FEET_TO_M - filter.geoid_offset_m,
+        (None, baro) => number(baro)? * FEET_TO_M,
+    };
+    let seen_s = number(aircraft.get("seen_pos")).unwrap_or(0.0);
+    Some(ExternalTraffic {
+        traffic_id: traffic_id(hex)?,
+        source: "adsb".to_string(),
+        lat: number(aircraft.get("lat"))?,
+        lon: number(aircraft.get("lon"))?,
+        altitude_m,
+        heading_deg: number(aircraft.get("track")).unwrap_or(0.0),
+        speed_mps: number(aircraft.get("gs")).unwrap_or(0.0) * KNOTS_TO_MPS,
+        last_update: feed_time - ChronoDuration::milliseconds((seen_s * 1000.0) as i64),
+        category: category(aircraft.get("category")),
+        quality: None,
+        extrapolated_s: 0.0,
+    })
+}
+
+/// An OpenSky state vector: `[icao24, callsign, origin_country, time_position, last_contact,
+/// longitude, latitude, baro_altitude, on_ground, velocity, true_track, vertical_rate, sensors,
+/// geo_altitude, squawk, spi, position_source, category]`.
+fn opensky_track(
+    state: &Value,
+    filter: &AdsbFilter,
+    feed_time: DateTime<Utc>,
+) -> Option<ExternalTraffic> {
+    let state = state.as_array()?;
+    let field = |index: usize| state.get(index);
+    if field(8).and_then(Value::as_bool) == Some(true) {
+        return None;
+    }
+    let altitude_m = match number(field(13)) {
+        Some(geometric_m) => geometric_m - filter.geoid_offset_m,
+        None => number(field(7))?,
+    };
+    Some(ExternalTraffic {
+        traffic_id: traffic_id(field(0)?.as_str()?)?,
+        source: "adsb".to_string(),
+        lat: number(field(6))?,
+        lon: number(field(5))?,
+        altitude_m,
+        heading_deg: number(field(10)).unwrap_or(0.0),
+        speed_mps: number(field(9)).unwrap_or(0.0),
+        last_update: number(field(3))
+            .or_else(|| number(field(4)))
+            .and_then(epoch)
+            .unwrap_or(feed_time),
+        category: category(field(17)),
+        quality: None,
+        extrapolated_s: 0.0,
+    })
+}
+
+fn traffic_id(icao: &str) -> Option<String> {
+    let icao = icao.trim();
+    (!icao.is_empty()).then(|| format!("ADSB-{}", icao.to_ascii_uppercase()))
+}
+
+/// ADS-B out is carried by crewed aircraft unless the emitter category says otherwise.
+fn category(emitter: Option<&Value>) -> AircraftCategory {
+    let code = match emitter {
+        Some(Value::String(code)) => Some(code.clone()),
+        Some(Value::Number(code)) => Some(code.to_string()),
+        _ => None,
+    };
+    match code.map(|code| AircraftCategory::from_adsb_emitter(&code)) {
+        Some(AircraftCategory::Uncrewed) => AircraftCategory::Uncrewed,
+        _ => AircraftCategory::Crewed,
+    }
+}
+
+fn number(value: Option<&Value>) -> Option<f64> {
+    value?.as_f64().filter(|value| value.is_finite())
+}
+
+fn epoch(seconds: f64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_millis_opt((seconds * 1000.0) as i64).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filter() -> AdsbFilter {
+        AdsbFilter {
+            area: MsaBounds::parse("33.0,-118.5,34.5,-117.0"),
+            max_altitude_m: 3_000.0,
+            geoid_offset_m: -35.0,
+        }
+    }
+
+    #[test]
+    fn dump1090_aircraft_become_crewed_tracks() {
+        let feed = json!({
+            "now": 1_800_000_000.0,
+            "aircraft": [
+                { "hex": "a1b2c3", "flight": "N123AB  ", "alt_baro": 1500, "gs": 100.0,
+                  "track": 270.0, "lat": 33.70, "lon": -117.80, "seen_pos": 2.0 },
+                { "hex": "a1b2c4", "alt_baro": 1400, "alt_geom": 1500, "lat": 33.71,
+                  "lon": -117.81, "category": "B6" },
+                { "hex": "a1b2c5", "alt_baro": "ground", "lat": 33.67, "lon": -117.86 },
+                { "hex": "a1b2c6", "alt_baro": 35000, "lat": 33.72, "lon": -117.82 },
+                { "hex": "a1b2c7", "alt_baro": 1500, "lat": 40.0, "lon": -117.82 },
+                { "hex": "a1b2c8", "alt_baro": 1500 }
+            ]
+        });
+        let tracks = parse_feed(AdsbFeedFormat::Dump1090, &feed, &filter(), Utc::now()).unwrap();
+        assert_eq!(tracks.len(), 2);
+
+        let cessna = &tracks[0];
+        assert_eq!(cessna.traffic_id, "ADSB-A1B2C3");
+        assert_eq!(cessna.source, "adsb");
+        assert_eq!(cessna.category, AircraftCategory::Crewed);
+        assert!((cessna.altitude_m - 457.2).abs() < 1e-6);
+        assert!((cessna.speed_mps - 51.4444).abs() < 1e-3);
+        assert_eq!(cessna.last_update.timestamp(), 1_799_999_998);
+
+        // Geometric altitude is preferred, less the geoid offset; B6 is a UAV.
+        let uav = &tracks[1];
+        assert!((uav.altitude_m - (457.2 + 35.0)).abs() < 1e-6);
+        assert_eq!(uav.category, AircraftCategory::Uncrewed);
+    }
+
+    #[test]
+    fn opensky_states_become_tracks() {
+        let feed = json!({
+            "time": 1_800_000_000,
+            "states": [
+                ["abc123", "SKW42  ", "United States", 1_799_999_995, 1_799_999_999,
+                 -117.8, 33.7, 900.0, false, 60.0, 45.0, 0.0, null, null, "1200", false, 0, 4],
+                ["abc124", "", "United States", null, 1_799_999_999,
+                 -117.8, 33.7, 0.0, true, 0.0, 0.0, 0.0, null, 10.0, null, false, 0, 0],
+                ["abc125", "", "United States", null, 1_799_999_999,
+                 -117.8, 33.7, null, false, 20.0, 0.0, 0.0, null, 300.0, null, false, 0, 14]
+            ]
+        });
+        let tracks = parse_feed(AdsbFeedFormat::OpenSky, &feed, &filter(), Utc::now()).unwrap();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].traffic_id, "ADSB-ABC123");
+        assert_eq!(tracks[0].altitude_m, 900.0);
+        assert_eq!(tracks[0].speed_mps, 60.0);
+        assert_eq!(tracks[0].last_update.timestamp(), 1_799_999_995);
+        assert_eq!(tracks[0].category, AircraftCategory::Crewed);
+        assert_eq!(tracks[1].altitude_m, 335.0);
+        assert_eq!(tracks[1].category, AircraftCategory::Uncrewed);
+
+        let empty = json!({ "time": 1_800_000_000, "states": null });
+        let tracks = parse_feed(AdsbFeedFormat::OpenSky, &empty, &filter(), Utc::now()).unwrap();
+        assert!(tracks.is_empty());
+        assert!(parse_feed(AdsbFeedFormat::OpenSky, &json!({}), &filter(), Utc::now()).is_err());
+    }
+}
@@ -0,0 +1,500 @@
+//! Real-geography fixtures for the route planner and compliance checks.
+//!
+//! Each fixture under `tests/airspace/` describes a curated piece of real airspace: the
+//! OpenStreetMap obstacles Overpass would return, a terrain model, current weather and any
+//! geofences, together with a route request and the planner and compliance outcomes expected
+//! for it. The harness serves the data from a local stand-in for the Overpass, elevation and
+//! weather providers, so `plan_route` and `evaluate_compliance` run unchanged and any drift in
+//! their behaviour over these places fails here rather than silently in production.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use atc_core::models::{FlightPlanMetadata, FlightPlanRequest, Geofence, GeofenceType, Waypoint};
+use atc_core::rules::SafetyRules;
+use atc_core::spatial::distance_to_segment_m;
+use axum::extract::State;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::altitude::AltitudeReference;
+use crate::compliance::{evaluate_compliance, RoutePoint};
+use crate::config::Config;
+use crate::route_planner::{plan_route, RoutePlanRequest, RoutePlanResponse};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+struct AirspaceFixture {
+    name: String,
+    terrain: FixtureTerrain,
+    /// Overpass elements for the area, as the Overpass API returns them.
+    #[serde(default)]
+    osm: Vec<Value>,
+    /// Open-Meteo `current` block.
+    weather: Value,
+    #[serde(default)]
+    geofences: Vec<FixtureGeofence>,
+    route: RoutePlanRequest,
+    /// Plan metadata for the compliance evaluation of `route.waypoints`.
+    #[serde(default)]
+    metadata: FlightPlanMetadata,
+    expected_route: ExpectedRoute,
+    expected_compliance: ExpectedCompliance,
+}
+
+/// Ground elevation: `base_m` everywhere, raised along each ridge.
+#[derive(Debug, Clone, Deserialize)]
+struct FixtureTerrain {
+    base_m: f64,
+    #[serde(default)]
+    ridges: Vec<Ridge>,
+}
+
+/// A ridge whose crest runs between two `[lat, lon]` points and falls linearly to the base
+/// `half_width_m` either side.
+#[derive(Debug, Clone, Deserialize)]
+struct Ridge {
+    crest: [[f64; 2]; 2],
+    crest_m: f64,
+    half_width_m: f64,
+}
+
+impl FixtureTerrain {
+    fn elevation_m(&self, lat: f64, lon: f64) -> f64 {
+        self.ridges.iter().fold(self.base_m, |elevation, ridge| {
+            let [[lat1, lon1], [lat2, lon2]] = ridge.crest;
+            let distance_m = distance_to_segment_m(lat, lon, lat1, lon1, lat2, lon2);
+            let fraction = (1.0 - distance_m / ridge.half_width_m).max(0.0);
+            elevation.max(self.base_m + (ridge.crest_m - self.base_m) * fraction)
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FixtureGeofence {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    geofence_type: GeofenceType,
+    polygon: Vec<[f64; 2]>,
+    #[serde(default)]
+    lower_altitude_m: f64,
+    upper_altitude_m: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExpectedRoute {
+    ok: bool,
+    /// Substrings of the planner errors when planning is expected to fail.
+    #[serde(default)]
+    errors_contain: Vec<String>,
+    /// Upper bound on the route's height above the terrain beneath it.
+    #[serde(default)]
+    max_agl_m: Option<f64>,
+    /// The route must climb at least this high somewhere (e.g. to cross a ridge).
+    #[serde(default)]
+    min_peak_altitude_m: Option<f64>,
+    /// ...and no higher than this.
+    #[serde(default)]
+    max_peak_altitude_m: Option<f64>,
+    /// Lower bound on the route's closest approach to a restricted geofence.
+    #[serde(default)]
+    min_geofence_clearance_m: Option<f64>,
+    /// Hazard IDs the planner must report.
+    #[serde(default)]
+    hazards: Vec<String>,
+    /// Places the route must pass no closer than `min_distance_m` to.
+    #[serde(default)]
+    keep_clear: Vec<KeepClear>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeepClear {
+    name: String,
+    lat: f64,
+    lon: f64,
+    min_distance_m: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExpectedCompliance {
+    overall_status: String,
+    /// Status of each named check (`weather`, `battery`, `population`, `obstacles`, `c2_link`).
+    checks: BTreeMap<String, String>,
+    /// Blocking checks, in report order.
+    blocking: Vec<String>,
+    /// IDs of obstacles the route conflicts with, in any order.
+    #[serde(default)]
+    obstacle_conflicts: Vec<String>,
+    #[serde(default)]
+    population_classification: Option<String>,
+}
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/airspace")
+}
+
+fn load_fixtures() -> Vec<(PathBuf, AirspaceFixture)> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(fixture_dir())
+        .expect("read fixture dir")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let raw = std::fs::read_to_string(&path).expect("read fixture");
+            let fixture: AirspaceFixture = serde_json::from_str(&raw)
+                .unwrap_or_else(|err| panic!("parse {}: {}", path.display(), err));
+            (path, fixture)
+        })
+        .collect()
+}
+
+/// What the stand-in providers answer with.
+#[derive(Clone)]
+struct Providers {
+    terrain: FixtureTerrain,
+    osm: Arc<Vec<Value>>,
+    weather: Value,
+}
+
+#[derive(Deserialize)]
+struct ElevationRequest {
+    latitude: Vec<f64>,
+    longitude: Vec<f64>,
+}
+
+/// Serve the fixture's Overpass, elevation and weather data; returns the base URL.
+async fn serve_providers(fixture: &AirspaceFixture) -> String {
+    let providers = Providers {
+        terrain: fixture.terrain.clone(),
+        osm: Arc::new(fixture.osm.clone()),
+        weather: fixture.weather.clone(),
+    };
+    let app = Router::new()
+        .route(
+            "/overpass",
+            post(|State(providers): State<Providers>| async move {
+                Json(json!({ "elements": *providers.osm }))
+            }),
+        )
+        .route(
+            "/elevation",
+            post(
+                |State(providers): State<Providers>, Json(request): Json<ElevationRequest>| async move {
+                    let elevation: Vec<f64> = request
+                        .latitude
+                        .iter()
+                        .zip(&request.longitude)
+                        .map(|(lat, lon)| providers.terrain.elevation_m(*lat, *lon))
+                        .collect();
+                    Json(json!({ "elevation": elevation }))
+                },
+            ),
+        )
+        .route(
+            "/weather",
+            get(|State(providers): State<Providers>| async move {
+                Json(json!({ "current": providers.weather }))
+            }),
+        )
+        .with_state(providers);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind providers");
+    let addr = listener.local_addr().expect("provider addr");
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("serve providers");
+    });
+    format!("http://{}", addr)
+}
+
+fn fixture_config(providers_url: &str) -> Config {
+    let mut config = Config::from_env();
+    config.altitude_reference = AltitudeReference::Amsl;
+    config.geoid_offset_m = 0.0;
+    config.compliance_overpass_url = format!("{}/overpass", providers_url);
+    config.compliance_weather_url = format!("{}/weather", providers_url);
+    config.terrain_provider_url = format!("{}/elevation", providers_url);
+    config.terrain_use_post = true;
+    config.terrain_max_points_per_request = 5_000;
+    config.terrain_request_min_interval_ms = 0;
+    config.terrain_require = true;
+    config.route_planner_require_obstacles = true;
+    config.route_planner_wind_field = false;
+    config
+}
+
+fn status_label<T: serde::Serialize>(status: &T) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn check_route(label: &str, fixture: &AirspaceFixture, state: &AppState, plan: &RoutePlanResponse) {
+    let expected = &fixture.expected_route;
+    assert_eq!(
+        plan.ok, expected.ok,
+        "route ok for {}: {:?}",
+        label, plan.errors
+    );
+    for needle in &expected.errors_contain {
+        assert!(
+            plan.errors
+                .iter()
+                .any(|error| error.contains(needle.as_str())),
+            "{}: no planner error contains '{}': {:?}",
+            label,
+            needle,
+            plan.errors
+        );
+    }
+    let mut hazards: Vec<&str> = plan
+        .hazards
+        .iter()
+        .map(|hazard| hazard.id.as_str())
+        .collect();
+    hazards.sort_unstable();
+    for id in &expected.hazards {
+        assert!(
+            hazards.binary_search(&id.as_str()).is_ok(),
+            "{}: hazard {} not reported in {:?}",
+            label,
+            id,
+            hazards
+        );
+    }
+    if !plan.ok {
+        return;
+    }
+
+    let stats = plan.stats.as_ref().expect("route stats");
+    let peak_m = plan
+        .waypoints
+        .iter()
+        .map(|wp| wp.altitude_m)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let max_agl_m = plan
+        .profile
+        .iter()
+        .map(|station| station.agl_m)
+        .fold(f64::NEG_INFINITY, f64::max);
+    if let Some(limit_m) = expected.max_agl_m {
+        assert!(
+            max_agl_m <= limit_m,
+            "{}: route reaches {:.1} m AGL, above {:.1} m",
+            label,
+            max_agl_m,
+            limit_m
+        );
+    }
+    if let Some(min_peak_m) = expected.min_peak_altitude_m {
+        assert!(
+            peak_m >= min_peak_m,
+            "{}: route peaks at {:.1} m, below {:.1} m",
+            label,
+            peak_m,
+            min_peak_m
+        );
+    }
+    if let Some(max_peak_m) = expected.max_peak_altitude_m {
+        assert!(
+            peak_m <= max_peak_m,
+            "{}: route peaks at {:.1} m, above {:.1} m",
+            label,
+            peak_m,
+            max_peak_m
+        );
+    }
+    if let Some(min_clearance_m) = expected.min_geofence_clearance_m {
+        let clearance_m = stats.min_geofence_clearance_m.expect("geofence clearance");
+        assert!(
+            clearance_m >= min_clearance_m,
+            "{}: route passes {:.1} m from a geofence, under {:.1} m",
+            label,
+            clearance_m,
+            min_clearance_m
+        );
+    }
+
+    for place in &expected.keep_clear {
+        let closest_m = plan
+            .waypoints
+            .windows(2)
+            .map(|leg| {
+                distance_to_segment_m(
+                    place.lat, place.lon, leg[0].lat, leg[0].lon, leg[1].lat, leg[1].lon,
+                )
+            })
+            .fold(f64::INFINITY, f64::min);
+        assert!(
+            closest_m >= place.min_distance_m,
+            "{}: route passes {:.1} m from {}, under {:.1} m",
+            label,
+            closest_m,
+            place.name,
+            place.min_distance_m
+        );
+    }
+
+    // Whatever the fixture, a planned route never flies through a building or a no-fly zone.
+    for station in &plan.profile {
+        if let (Some(0.0), Some(top_m)) = (station.obstacle_distance_m, station.obstacle_top_m) {
+            assert!(
+                station.altitude_m > top_m,
+                "{}: route at {:.1} m inside an obstacle topping out at {:.1} m ({}, {})",
+                label,
+                station.altitude_m,
+                top_m,
+                station.lat,
+                station.lon
+            );
+        }
+    }
+    for geofence in state.get_geofences() {
+        if geofence.geofence_type == GeofenceType::Advisory {
+            continue;
+        }
+        for leg in plan.waypoints.windows(2) {
+            assert!(
+                !geofence.intersects_segment(
+                    leg[0].lat,
+                    leg[0].lon,
+                    leg[0].altitude_m,
+                    leg[1].lat,
+                    leg[1].lon,
+                    leg[1].altitude_m,
+                ),
+                "{}: route crosses geofence {}",
+                label,
+                geofence.id
+            );
+        }
+    }
+}
+
+async fn check_compliance(label: &str, fixture: &AirspaceFixture, config: &Config) {
+    let waypoints: Vec<Waypoint> = fixture.route.waypoints.clone();
+    let points: Vec<RoutePoint> = waypoints
+        .iter()
+        .map(|wp| RoutePoint {
+            lat: wp.lat,
+            lon: wp.lon,
+            altitude_m: wp.altitude_m,
+        })
+        .collect();
+    let request = FlightPlanRequest {
+        drone_id: "FIXTURE".to_string(),
+        owner_id: None,
+        waypoints: Some(waypoints),
+        trajectory_log: None,
+        metadata: Some(fixture.metadata.clone()),
+        origin: None,
+        destination: None,
+        departure_time: None,
+    };
+    let evaluation = evaluate_compliance(config, &request, &points, &[]).await;
+    let report = &evaluation.report;
+    let expected = &fixture.expected_compliance;
+
+    let checks = &report.checks;
+    let actual: BTreeMap<String, String> = [
+        ("weather", status_label(&checks.weather.status)),
+        ("battery", status_label(&checks.battery.status)),
+        ("population", status_label(&checks.population.status)),
+        ("obstacles", status_label(&checks.obstacles.status)),
+        ("c2_link", status_label(&checks.c2_link.status)),
+    ]
+    .into_iter()
+    .filter(|(name, _)| expected.checks.contains_key(*name))
+    .map(|(name, status)| (name.to_string(), status))
+    .collect();
+    assert_eq!(actual, expected.checks, "compliance checks for {}", label);
+    assert_eq!(
+        status_label(&report.overall_status),
+        expected.overall_status,
+        "overall compliance for {}",
+        label
+    );
+    assert_eq!(
+        evaluation.blocking, expected.blocking,
+        "blocking checks for {}",
+        label
+    );
+
+    let mut conflicts: Vec<String> = checks
+        .obstacles
+        .conflicts
+        .iter()
+        .filter(|conflict| conflict.severity == "conflict")
+        .map(|conflict| conflict.id.clone())
+        .collect();
+    conflicts.sort();
+    let mut expected_conflicts = expected.obstacle_conflicts.clone();
+    expected_conflicts.sort();
+    assert_eq!(
+        conflicts, expected_conflicts,
+        "obstacle conflicts for {}",
+        label
+    );
+    if let Some(classification) = &expected.population_classification {
+        assert_eq!(
+            checks.population.classification.as_ref(),
+            Some(classification),
+            "population classification for {}",
+            label
+        );
+    }
+}
+
+async fn run_fixture(path: &Path, fixture: AirspaceFixture) {
+    let label = format!("{} ({})", fixture.name, path.display());
+    let providers_url = serve_providers(&fixture).await;
+    let config = fixture_config(&providers_url);
+    let state = AppState::with_rules_and_config(SafetyRules::default(), config.clone());
+    for geofence in &fixture.geofences {
+        state
+            .add_geofence(Geofence {
+                name: geofence.name.clone().unwrap_or_else(|| geofence.id.clone()),
+                id: geofence.id.clone(),
+                geofence_type: geofence.geofence_type,
+                polygon: geofence.polygon.clone(),
+                lower_altitude_m: geofence.lower_altitude_m,
+                upper_altitude_m: geofence.upper_altitude_m,
+                active: true,
+                created_at: Utc::now(),
+                breach_response: None,
+                schedule: None,
+                org_id: None,
+            })
+            .await
+            .expect("add geofence");
+    }
+
+    let mut request = fixture.route.clone();
+    // Fixed so the planner's random choices cannot make a fixture flaky.
+    request.seed.get_or_insert(1);
+    let plan = plan_route(&state, &config, request).await;
+    check_route(&label, &fixture, &state, &plan);
+    check_compliance(&label, &fixture, &config).await;
+}
+
+#[tokio::test]
+async fn airspace_fixtures_match_expectations() {
+    let fixtures = load_fixtures();
+    assert!(
+        !fixtures.is_empty(),
+        "no fixtures in {}",
+        fixture_dir().display()
+    );
+    for (path, fixture) in fixtures {
+        run_fixture(&path, fixture).await;
+    }
+}
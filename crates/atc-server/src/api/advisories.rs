@@ -0,0 +1,226 @@
+//! Pre-flight advisory acknowledgment.
+//!
+//! Advisory geofences are not enforced and a route may pass right alongside a temporary flight
+//! restriction (TFR), so neither stops a plan in validation. Operators must still have seen them:
+//! a plan whose route crosses an in-force advisory, or passes within
+//! `ATC_ADVISORY_TFR_ADJACENT_M` of a TFR, is answered with a 409 listing them and an
+//! acknowledgment token. Resubmitting with the token in `metadata.advisory_acknowledgment`
+//! approves the plan and records the acknowledgment on it. The token is a digest of the drone
+//! and the advisories as shown, so it goes stale if any of them changes or a new one appears.
+
+use axum::{http::StatusCode, Json};
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use atc_core::models::{AdvisoryAcknowledgment, FlightPlanRequest, Geofence, GeofenceType};
+use atc_core::spatial::haversine_distance;
+
+use crate::compliance::RoutePoint;
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// Route segments are sampled this often when measuring the distance to a TFR.
+const SAMPLE_SPACING_M: f64 = 25.0;
+
+/// An advisory or nearby TFR the operator must acknowledge.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RouteAdvisory {
+    #[serde(skip)]
+    geofence: Geofence,
+    pub geofence_id: String,
+    pub name: String,
+    pub geofence_type: GeofenceType,
+    /// Closest approach of the route to the geofence (0 when it passes through)
+    pub distance_m: f64,
+}
+
+/// Advisories in force during the flight that cross the route, and TFRs it passes near.
+pub(crate) fn route_advisories(
+    state: &AppState,
+    request: &FlightPlanRequest,
+    points: &[RoutePoint],
+) -> Vec<RouteAdvisory> {
+    let config = state.config();
+    if points.len() < 2 {
+        return Vec::new();
+    }
+    let (window_start, window_end) = super::flights::flight_window(state, request);
+    let mut advisories: Vec<RouteAdvisory> = state
+        .get_geofences()
+        .into_iter()
+        .filter(|geofence| geofence.in_force_during(window_start, window_end))
+        .filter_map(|geofence| {
+            let reach_m = match geofence.geofence_type {
+                GeofenceType::Advisory => 0.0,
+                GeofenceType::TemporaryRestriction => config.advisory_tfr_adjacent_m,
+                _ => return None,
+            };
+            let distance_m = route_distance_m(&geofence, points);
+            (distance_m <= reach_m).then(|| RouteAdvisory {
+                geofence_id: geofence.id.clone(),
+                name: geofence.name.clone(),
+                geofence_type: geofence.geofence_type,
+                distance_m,
+                geofence,
+            })
+        })
+        .collect();
+    advisories.sort_by(|a, b| a.geofence_id.cmp(&b.geofence_id));
+    advisories
+}
+
+/// Closest approach of the route to the geofence's volume.
+fn route_distance_m(geofence: &Geofence, points: &[RoutePoint]) -> f64 {
+    let mut closest = f64::INFINITY;
+    for pair in points.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        if geofence.intersects_segment(
+            start.lat,
+            start.lon,
+            start.altitude_m,
+            end.lat,
+            end.lon,
+            end.altitude_m,
+        ) {
+            return 0.0;
+        }
+        let length_m = haversine_distance(start.lat, start.lon, end.lat, end.lon);
+        let steps = (length_m / SAMPLE_SPACING_M).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            let distance_m = geofence.distance_m(
+                start.lat + t * (end.lat - start.lat),
+                start.lon + t * (end.lon - start.lon),
+                start.altitude_m + t * (end.altitude_m - start.altitude_m),
+            );
+            closest = closest.min(distance_m);
+        }
+    }
+    closest
+}
+
+/// Token the operator returns to acknowledge exactly these advisories for this drone.
+pub(crate) fn acknowledgment_token(drone_id: &str, advisories: &[RouteAdvisory]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(drone_id.as_bytes());
+    for advisory in advisories {
+        hasher.update(b"\n");
+        hasher.update(serde_json::to_vec(&advisory.geofence).unwrap_or_default());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Require an up-to-date acknowledgment before a plan crossing advisories is approved.
+///
+/// An accepted acknowledgment is recorded in the plan metadata; one submitted when nothing needs
+/// acknowledging is dropped.
+pub(crate) fn require_acknowledgment(
+    state: &AppState,
+    request: &mut FlightPlanRequest,
+    points: &[RoutePoint],
+) -> Result<(), ApiError> {
+    let submitted = request
+        .metadata
+        .as_mut()
+        .and_then(|metadata| metadata.advisory_acknowledgment.take());
+    if !state.config().advisory_ack_required {
+        return Ok(());
+    }
+    let advisories = route_advisories(state, request, points);
+    if advisories.is_empty() {
+        return Ok(());
+    }
+
+    let token = acknowledgment_token(&request.drone_id, &advisories);
+    if submitted.is_none_or(|ack| ack.token != token) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "Advisory acknowledgment required",
+                "message": "Resubmit with metadata.advisory_acknowledgment.token to acknowledge these advisories",
+                "advisories": advisories,
+                "acknowledgment_token": token
+            })),
+        ));
+    }
+
+    let acknowledgment = AdvisoryAcknowledgment {
+        token,
+        geofence_ids: advisories
+            .iter()
+            .map(|advisory| advisory.geofence_id.clone())
+            .collect(),
+        acknowledged_at: Some(Utc::now()),
+        acknowledged_by: request.owner_id.clone(),
+    };
+    tracing::info!(
+        drone_id = %request.drone_id,
+        owner_id = ?acknowledgment.acknowledged_by,
+        geofence_ids = ?acknowledgment.geofence_ids,
+        "Advisories acknowledged for flight plan"
+    );
+    request
+        .metadata
+        .get_or_insert_with(Default::default)
+        .advisory_acknowledgment = Some(acknowledgment);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geofence(id: &str, geofence_type: GeofenceType, lat: f64, lon: f64) -> Geofence {
+        let d = 0.001;
+        Geofence {
+            id: id.to_string(),
+            name: id.to_string(),
+            geofence_type,
+            polygon: vec![
+                [lat - d, lon - d],
+                [lat - d, lon + d],
+                [lat + d, lon + d],
+                [lat + d, lon - d],
+                [lat - d, lon - d],
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 200.0,
+            active: true,
+            created_at: Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
+        }
+    }
+
+    #[test]
+    fn route_distance_samples_between_waypoints() {
+        let route = [
+            RoutePoint {
+                lat: 32.70,
+                lon: -117.17,
+                altitude_m: 60.0,
+            },
+            RoutePoint {
+                lat: 32.70,
+                lon: -117.15,
+                altitude_m: 60.0,
+            },
+        ];
+        let crossing = geofence("crossing", GeofenceType::Advisory, 32.70, -117.16);
+        assert_eq!(route_distance_m(&crossing, &route), 0.0);
+
+        // About 300 m north of the route's midpoint, far from both waypoints.
+        let beside = geofence(
+            "beside",
+            GeofenceType::TemporaryRestriction,
+            32.7037,
+            -117.16,
+        );
+        let distance_m = route_distance_m(&beside, &route);
+        assert!((250.0..350.0).contains(&distance_m), "{distance_m}");
+    }
+}
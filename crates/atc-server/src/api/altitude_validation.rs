@@ -1,6 +1,7 @@
-use crate::compliance::RoutePoint;
+use crate::compliance::{self, RoutePoint};
 use crate::state::store::AppState;
 use crate::terrain::fetch_terrain_grid;
+use atc_core::messages::{codes, Message};
 use reqwest::Client;
 use serde_json::json;
 use std::time::Duration;
@@ -31,13 +32,11 @@ pub async fn validate_route_altitudes(
         {
             Ok(terrain) => terrain,
             Err(err) => {
-                violations.push(json!({
-                    "type": "terrain",
-                    "message": format!(
-                        "Terrain fetch failed (required for AGL altitude checks): {}",
-                        err
-                    )
-                }));
+                violations.push(compliance::violation(
+                    config,
+                    Message::new(codes::ROUTE_TERRAIN_UNAVAILABLE).with("error", err.to_string()),
+                    json!({ "type": "terrain" }),
+                ));
                 return;
             }
         };
@@ -60,35 +59,37 @@ pub async fn validate_route_altitudes(
             let agl_m = point.altitude_m - ground_m;
 
             if agl_m > rules.max_altitude_m {
-                violations.push(json!({
-                    "type": "altitude_agl",
-                    "point_index": idx,
-                    "altitude_amsl_m": point.altitude_m,
-                    "ground_elevation_m": ground_m,
-                    "agl_m": agl_m,
-                    "max_agl_m": rules.max_altitude_m,
-                    "message": format!(
-                        "Altitude AGL {:.1}m exceeds max AGL {:.1}m",
-                        agl_m,
-                        rules.max_altitude_m
-                    )
-                }));
+                violations.push(compliance::violation(
+                    config,
+                    Message::new(codes::ROUTE_AGL_ABOVE_MAX)
+                        .with("agl_m", agl_m)
+                        .with("max_agl_m", rules.max_altitude_m),
+                    json!({
+                        "type": "altitude_agl",
+                        "point_index": idx,
+                        "altitude_amsl_m": point.altitude_m,
+                        "ground_elevation_m": ground_m,
+                        "agl_m": agl_m,
+                        "max_agl_m": rules.max_altitude_m
+                    }),
+                ));
             }
 
             if agl_m < rules.min_altitude_m {
-                violations.push(json!({
-                    "type": "altitude_agl",
-                    "point_index": idx,
-                    "altitude_amsl_m": point.altitude_m,
-                    "ground_elevation_m": ground_m,
-                    "agl_m": agl_m,
-                    "min_agl_m": rules.min_altitude_m,
-                    "message": format!(
-                        "Altitude AGL {:.1}m is below min AGL {:.1}m",
-                        agl_m,
-                        rules.min_altitude_m
-                    )
-                }));
+                violations.push(compliance::violation(
+                    config,
+                    Message::new(codes::ROUTE_AGL_BELOW_MIN)
+                        .with("agl_m", agl_m)
+                        .with("min_agl_m", rules.min_altitude_m),
+                    json!({
+                        "type": "altitude_agl",
+                        "point_index": idx,
+                        "altitude_amsl_m": point.altitude_m,
+                        "ground_elevation_m": ground_m,
+                        "agl_m": agl_m,
+                        "min_agl_m": rules.min_altitude_m
+                    }),
+                ));
             }
         }
         return;
@@ -106,31 +107,33 @@ pub async fn validate_route_altitudes(
         }
 
         if point.altitude_m > rules.max_altitude_m {
-            violations.push(json!({
-                "type": "altitude",
-                "point_index": idx,
-                "altitude_m": point.altitude_m,
-                "max_m": rules.max_altitude_m,
-                "message": format!(
-                    "Altitude {:.1}m exceeds max altitude {:.1}m",
-                    point.altitude_m,
-                    rules.max_altitude_m
-                )
-            }));
+            violations.push(compliance::violation(
+                config,
+                Message::new(codes::ROUTE_ALTITUDE_ABOVE_MAX)
+                    .with("altitude_m", point.altitude_m)
+                    .with("max_m", rules.max_altitude_m),
+                json!({
+                    "type": "altitude",
+                    "point_index": idx,
+                    "altitude_m": point.altitude_m,
+                    "max_m": rules.max_altitude_m
+                }),
+            ));
         }
 
         if point.altitude_m < rules.min_altitude_m {
-            violations.push(json!({
-                "type": "altitude",
-                "point_index": idx,
-                "altitude_m": point.altitude_m,
-                "min_m": rules.min_altitude_m,
-                "message": format!(
-                    "Altitude {:.1}m is below min altitude {:.1}m",
-                    point.altitude_m,
-                    rules.min_altitude_m
-                )
-            }));
+            violations.push(compliance::violation(
+                config,
+                Message::new(codes::ROUTE_ALTITUDE_BELOW_MIN)
+                    .with("altitude_m", point.altitude_m)
+                    .with("min_m", rules.min_altitude_m),
+                json!({
+                    "type": "altitude",
+                    "point_index": idx,
+                    "altitude_m": point.altitude_m,
+                    "min_m": rules.min_altitude_m
+                }),
+            ));
         }
     }
 }
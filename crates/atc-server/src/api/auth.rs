@@ -1,8 +1,9 @@
 //! Authentication middleware for protected endpoints.
 
-use axum::http::HeaderMap;
+use axum::http::{request::Parts, HeaderMap};
 use axum::{
-    extract::{ConnectInfo, Request, State},
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, Request, State},
     http::{header, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
@@ -15,11 +16,15 @@ use std::sync::{
 };
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::config::Config;
 use crate::state::AppState;
+use atc_core::models::Geofence;
 
 /// Extractor for admin token from config.
+///
+/// Resolved per request so tokens rotated in the secret backend apply immediately.
 #[derive(Clone)]
-pub struct AdminToken(pub Arc<String>);
+pub struct AdminToken(pub Arc<Config>);
 
 fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     if a.len() != b.len() {
@@ -32,6 +37,97 @@ fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     diff == 0
 }
 
+/// Who is making a request, resolved from its bearer token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Caller {
+    /// Holder of the admin token; sees every organization.
+    Admin,
+    /// Holder of an organization token; sees its members' drones, plans and commands.
+    Organization(String),
+    /// No recognised token; sees shared airspace only.
+    Anonymous,
+}
+
+impl Caller {
+    pub fn org_id(&self) -> Option<&str> {
+        match self {
+            Caller::Organization(org_id) => Some(org_id),
+            _ => None,
+        }
+    }
+
+    /// Whether resources of `owner_id` are visible to this caller.
+    pub fn sees_owner(&self, state: &AppState, owner_id: Option<&str>) -> bool {
+        match self {
+            Caller::Admin => true,
+            Caller::Organization(org_id) => owner_id
+                .and_then(|owner_id| state.organization_of_owner(owner_id))
+                .is_some_and(|owner_org| &owner_org == org_id),
+            Caller::Anonymous => false,
+        }
+    }
+
+    /// Whether a drone is visible to this caller (through its owner).
+    pub fn sees_drone(&self, state: &AppState, drone_id: &str) -> bool {
+        match self {
+            Caller::Admin => true,
+            _ => state
+                .get_drone(drone_id)
+                .is_some_and(|drone| self.sees_owner(state, drone.owner_id.as_deref())),
+        }
+    }
+
+    /// Shared geofences are visible to everyone; private ones only to their organization.
+    pub fn sees_geofence(&self, geofence: &Geofence) -> bool {
+        match (&geofence.org_id, self) {
+            (None, _) | (Some(_), Caller::Admin) => true,
+            (Some(owner_org), Caller::Organization(org_id)) => owner_org == org_id,
+            (Some(_), Caller::Anonymous) => false,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Caller {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<Caller>()
+            .cloned()
+            .unwrap_or(Caller::Anonymous))
+    }
+}
+
+/// Resolve a bearer token to the caller it identifies.
+pub fn caller_for_token(state: &AppState, token: &str) -> Caller {
+    if constant_time_eq(token.as_bytes(), state.config().admin_token().as_bytes()) {
+        Caller::Admin
+    } else if let Some(org_id) = state.organization_for_token(token) {
+        Caller::Organization(org_id)
+    } else {
+        Caller::Anonymous
+    }
+}
+
+/// Middleware attaching the [`Caller`] for the request's bearer token.
+pub async fn identify_caller(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let caller = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "))
+        .map(|token| caller_for_token(&state, token))
+        .unwrap_or(Caller::Anonymous);
+    request.extensions_mut().insert(caller);
+    next.run(request).await
+}
+
 /// Middleware that requires a valid admin token in the Authorization header.
 ///
 /// Expected header format: `Authorization: Bearer <admin_token>`
@@ -39,6 +135,26 @@ pub async fn require_admin(
     State(admin_token): State<AdminToken>,
     request: Request,
     next: Next,
+) -> Response {
+    check_bearer(admin_token, request, next, false).await
+}
+
+/// Middleware that accepts the admin token or an organization token.
+///
+/// Handlers behind it scope their results with the [`Caller`] extractor.
+pub async fn require_operator(
+    State(admin_token): State<AdminToken>,
+    request: Request,
+    next: Next,
+) -> Response {
+    check_bearer(admin_token, request, next, true).await
+}
+
+async fn check_bearer(
+    admin_token: AdminToken,
+    mut request: Request,
+    next: Next,
+    allow_organization: bool,
 ) -> Response {
     // Extract Authorization header
     let auth_header = request
@@ -49,7 +165,15 @@ pub async fn require_admin(
     match auth_header {
         Some(auth) if auth.starts_with("Bearer ") => {
             let token = auth.trim_start_matches("Bearer ");
-            if constant_time_eq(token.as_bytes(), admin_token.0.as_bytes()) {
+            if constant_time_eq(token.as_bytes(), admin_token.0.admin_token().as_bytes()) {
+                request.extensions_mut().insert(Caller::Admin);
+                next.run(request).await
+            } else if allow_organization
+                && matches!(
+                    request.extensions().get::<Caller>(),
+                    Some(Caller::Organization(_))
+                )
+            {
                 next.run(request).await
             } else {
                 (
@@ -0,0 +1,56 @@
+//! Billing usage endpoint.
+
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::metering::usage_csv;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Organization (operator `owner_id`) to report on; all when omitted.
+    pub organization: Option<String>,
+    /// Calendar month `YYYY-MM`; all months when omitted.
+    pub month: Option<String>,
+    /// `json` (default) or `csv`.
+    pub format: Option<String>,
+}
+
+/// Monthly usage records per organization, as JSON or CSV.
+pub async fn usage(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    if let Some(month) = query.month.as_deref() {
+        if NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").is_err() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "month must be formatted YYYY-MM" })),
+            ));
+        }
+    }
+    let records = state
+        .usage_meter()
+        .usage(query.organization.as_deref(), query.month.as_deref());
+
+    match query.format.as_deref().unwrap_or("json") {
+        "json" => Ok(Json(records).into_response()),
+        "csv" => Ok((
+            [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            usage_csv(&records),
+        )
+            .into_response()),
+        other => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "format must be json or csv", "format": other })),
+        )),
+    }
+}
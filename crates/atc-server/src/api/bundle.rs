@@ -0,0 +1,182 @@
+//! Environment bundle export/import.
+//!
+//! A bundle is a single versioned JSON document holding the airspace setup of a server
+//! (local geofences, safety rules, vertiports and scheduled flight plans) so staging can be
+//! cloned from production. Live state such as telemetry, conflicts and commands is never
+//! included.
+
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::state::AppState;
+use atc_core::models::{FlightPlan, FlightStatus, Geofence};
+use atc_core::rules::SafetyRules;
+use atc_core::takeoff_landing::Vertiport;
+
+pub const BUNDLE_FORMAT: &str = "atc-environment-bundle";
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    #[serde(default)]
+    pub rules: Option<SafetyRules>,
+    #[serde(default)]
+    pub geofences: Vec<Geofence>,
+    #[serde(default)]
+    pub vertiports: Vec<Vertiport>,
+    /// Reserved, pending and approved plans; flown or closed plans are history, not setup.
+    #[serde(default)]
+    pub flight_plans: Vec<FlightPlan>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportSummary {
+    pub geofences: usize,
+    pub flight_plans: usize,
+    /// Items that were rejected, with the reason.
+    pub skipped: Vec<String>,
+    /// Config-driven sections that differ from this server and were not applied.
+    pub warnings: Vec<String>,
+}
+
+fn is_scheduled(plan: &FlightPlan) -> bool {
+    matches!(
+        plan.status,
+        FlightStatus::Reserved | FlightStatus::Pending | FlightStatus::Approved
+    )
+}
+
+/// Export the server's airspace setup as a bundle.
+pub async fn export_bundle(State(state): State<Arc<AppState>>) -> Json<EnvironmentBundle> {
+    let mut geofences = state.get_local_geofences();
+    geofences.sort_by(|a, b| a.id.cmp(&b.id));
+    let mut flight_plans: Vec<FlightPlan> = state
+        .get_flight_plans()
+        .into_iter()
+        .filter(is_scheduled)
+        .collect();
+    flight_plans.sort_by(|a, b| a.flight_id.cmp(&b.flight_id));
+
+    Json(EnvironmentBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        rules: Some(state.rules().as_ref().clone()),
+        geofences,
+        vertiports: state.config().vertiports.clone(),
+        flight_plans,
+    })
+}
+
+/// Import a bundle, upserting geofences and flight plans by ID.
+///
+/// Rules and vertiports come from the environment configuration, so they are compared rather
+/// than applied; any mismatch is reported as a warning.
+pub async fn import_bundle(
+    State(state): State<Arc<AppState>>,
+    Json(bundle): Json<EnvironmentBundle>,
+) -> Result<Json<ImportSummary>, (StatusCode, Json<serde_json::Value>)> {
+    if bundle.format != BUNDLE_FORMAT {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Unsupported bundle format",
+                "format": bundle.format,
+                "expected": BUNDLE_FORMAT
+            })),
+        ));
+    }
+    if bundle.version == 0 || bundle.version > BUNDLE_VERSION {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Unsupported bundle version",
+                "version": bundle.version,
+                "max_version": BUNDLE_VERSION
+            })),
+        ));
+    }
+
+    let mut summary = ImportSummary::default();
+
+    if let Some(rules) = bundle.rules.as_ref() {
+        let incoming = serde_json::to_value(rules).unwrap_or_default();
+        let current = serde_json::to_value(state.rules()).unwrap_or_default();
+        if incoming != current {
+            summary
+                .warnings
+                .push("Safety rules differ from this server; set ATC_RULES_* to match".to_string());
+        }
+    }
+    if bundle.vertiports != state.config().vertiports {
+        summary.warnings.push(
+            "Vertiports differ from this server; update ATC_VERTIPORTS_PATH to match".to_string(),
+        );
+    }
+
+    for geofence in bundle.geofences {
+        if state.is_external_geofence(&geofence.id) {
+            summary.skipped.push(format!(
+                "geofence {}: conflicts with an external geofence",
+                geofence.id
+            ));
+            continue;
+        }
+        let errors = geofence.validate();
+        if !errors.is_empty() {
+            summary
+                .skipped
+                .push(format!("geofence {}: {}", geofence.id, errors.join("; ")));
+            continue;
+        }
+        let id = geofence.id.clone();
+        if let Err(err) = state.add_geofence(geofence).await {
+            tracing::error!("Failed to import geofence {}: {}", id, err);
+            summary
+                .skipped
+                .push(format!("geofence {}: failed to persist", id));
+            continue;
+        }
+        summary.geofences += 1;
+    }
+
+    for plan in bundle.flight_plans {
+        if !is_scheduled(&plan) {
+            summary.skipped.push(format!(
+                "flight plan {}: status {:?} is not importable",
+                plan.flight_id, plan.status
+            ));
+            continue;
+        }
+        if plan.waypoints.len() < 2 {
+            summary.skipped.push(format!(
+                "flight plan {}: fewer than 2 waypoints",
+                plan.flight_id
+            ));
+            continue;
+        }
+        let flight_id = plan.flight_id.clone();
+        if let Err(err) = state.add_flight_plan(plan).await {
+            tracing::error!("Failed to import flight plan {}: {}", flight_id, err);
+            summary
+                .skipped
+                .push(format!("flight plan {}: failed to persist", flight_id));
+            continue;
+        }
+        summary.flight_plans += 1;
+    }
+
+    tracing::info!(
+        "Imported environment bundle: {} geofences, {} flight plans, {} skipped",
+        summary.geofences,
+        summary.flight_plans,
+        summary.skipped.len()
+    );
+    Ok(Json(summary))
+}
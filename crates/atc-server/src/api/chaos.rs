@@ -0,0 +1,40 @@
+//! Fault injection control endpoints (`chaos` feature).
+
+use axum::{http::StatusCode, Json};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::chaos::{chaos, ChaosSettings, ChaosStats};
+
+#[derive(Debug, Serialize)]
+pub struct ChaosStatus {
+    pub settings: ChaosSettings,
+    pub stats: ChaosStats,
+}
+
+fn status() -> ChaosStatus {
+    ChaosStatus {
+        settings: chaos().settings(),
+        stats: chaos().stats(),
+    }
+}
+
+pub async fn get_chaos() -> Json<ChaosStatus> {
+    Json(status())
+}
+
+/// Replace the fault rates; send all-zero settings to turn injection off.
+pub async fn update_chaos(
+    Json(settings): Json<ChaosSettings>,
+) -> Result<Json<ChaosStatus>, (StatusCode, Json<serde_json::Value>)> {
+    let errors = settings.validate();
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "Invalid chaos settings", "errors": errors })),
+        ));
+    }
+    tracing::warn!(?settings, "Chaos fault injection settings updated");
+    chaos().set_settings(settings);
+    Ok(Json(status()))
+}
@@ -5,22 +5,32 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        Query, State,
+        Path, Query, State,
     },
     http::{HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
 
-use crate::api::auth;
-use crate::state::AppState;
-use atc_core::models::{Command, CommandType};
+use crate::api::auth::{self, Caller};
+use crate::api::pagination::{self, Page};
+use crate::chaos::chaos;
+use crate::persistence::command_audit::{
+    query_command_audit, CommandAuditEntry, CommandAuditFilter, CommandCause, CommandCauseKind,
+};
+use crate::persistence::ReadTimeout;
+use crate::state::{AppState, BroadcastScope, BroadcastTarget, CommandBroadcast};
+use crate::throttle::CommandBudgetStatus;
+use atc_core::models::{Command, CommandSigningKey, CommandType, DroneStatus, SignedCommand};
+use atc_core::spatial::point_in_polygon;
 
 /// Request to issue a new command.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct IssueCommandRequest {
     pub drone_id: String,
     /// Owner/operator ID for access enforcement
@@ -32,40 +42,142 @@ pub struct IssueCommandRequest {
 }
 
 /// Response after issuing a command.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct IssueCommandResponse {
     pub command_id: String,
     pub drone_id: String,
     pub status: String,
 }
 
+/// Request to issue one command to every drone in a scope.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BroadcastCommandRequest {
+    #[serde(flatten)]
+    pub scope: BroadcastScope,
+    #[serde(flatten)]
+    pub command_type: CommandType,
+    /// Command expiry in seconds (default: 60)
+    pub expires_in_secs: Option<u32>,
+}
+
+/// Per-drone delivery state of a broadcast.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BroadcastTargetStatus {
+    pub drone_id: String,
+    pub command_id: String,
+    /// `acknowledged`, `pending` or `expired`
+    pub status: &'static str,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+/// Broadcast summary with acknowledgement counts.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BroadcastStatus {
+    pub broadcast_id: String,
+    pub command_type: CommandType,
+    pub scope: BroadcastScope,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// `complete` when every drone acknowledged, `pending` while acknowledgements are
+    /// outstanding, `incomplete` once the remaining commands expired unacknowledged
+    pub status: &'static str,
+    pub total: usize,
+    pub acknowledged: usize,
+    pub pending: usize,
+    pub expired: usize,
+    pub targets: Vec<BroadcastTargetStatus>,
+}
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
 /// Query params for getting next command.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct NextCommandQuery {
     pub drone_id: String,
 }
 
 /// Request to acknowledge a command.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct AckCommandRequest {
     pub command_id: String,
 }
 
 /// Query params for command streaming.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct CommandStreamQuery {
     pub drone_id: Option<String>,
     pub token: Option<String>,
 }
 
+/// Query params for listing pending commands.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CommandListQuery {
+    pub drone_id: Option<String>,
+    /// Comma-separated command types, e.g. `HOLD,REROUTE`
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    pub command_type: Option<String>,
+    /// `pending` (not yet expired) or `expired`
+    #[param(value_type = Option<String>)]
+    pub status: Option<CommandStatus>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// `X-Next-Cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+/// Query params for the command audit trail.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CommandAuditQuery {
+    pub drone_id: Option<String>,
+    /// What triggered the command, e.g. `conflict` or `failsafe`
+    #[param(value_type = Option<String>)]
+    pub cause: Option<CommandCauseKind>,
+    /// Conflict, cluster, geofence, flight or broadcast ID behind the command
+    pub source_id: Option<String>,
+    /// Commands issued at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Commands issued before this time
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+/// Whether an unacknowledged command can still be acted on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandStatus {
+    Pending,
+    Expired,
+}
+
+const COMMAND_TYPES: [&str; 5] = ["HOLD", "ALTITUDE_CHANGE", "REROUTE", "RESUME", "LAND"];
+
 /// Issue a new command to a drone.
-/// POST /v1/commands
+#[utoipa::path(
+    post,
+    path = "/v1/commands",
+    tag = "Commands",
+    request_body = IssueCommandRequest,
+    responses(
+        (status = 200, description = "Command queued", body = IssueCommandResponse),
+        (status = 403, description = "The owner does not match the drone's owner"),
+        (status = 404, description = "Drone not found"),
+        (status = 422, description = "The drone's advertised capabilities do not include this command"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn issue_command(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(request): Json<IssueCommandRequest>,
 ) -> Result<Json<IssueCommandResponse>, StatusCode> {
     let drone = state
         .get_drone(&request.drone_id)
+        .filter(|drone| caller.sees_owner(&state, drone.owner_id.as_deref()))
         .ok_or(StatusCode::NOT_FOUND)?;
     if let Some(expected_owner) = drone.owner_id {
         if request.owner_id.as_deref() != Some(expected_owner.as_str()) {
@@ -73,6 +185,14 @@ pub async fn issue_command(
         }
     }
 
+    if let Some(reason) = drone
+        .capabilities
+        .and_then(|capabilities| capabilities.unsupported(&request.command_type))
+    {
+        tracing::warn!("Refused command for drone {}: {}", request.drone_id, reason);
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
     let now = Utc::now();
     let expires_in = request.expires_in_secs.unwrap_or(60);
 
@@ -89,7 +209,10 @@ pub async fn issue_command(
     };
 
     let command_id = command.command_id.clone();
-    if let Err(err) = state.enqueue_command(command).await {
+    if let Err(err) = state
+        .enqueue_command(command, CommandCause::new(CommandCauseKind::Operator))
+        .await
+    {
         tracing::error!(
             "Failed to persist command for drone {}: {}",
             request.drone_id,
@@ -112,20 +235,293 @@ pub async fn issue_command(
     }))
 }
 
+/// Issue a command to every drone in a polygon, sector and/or owner scope.
+#[utoipa::path(
+    post,
+    path = "/v1/commands/broadcast",
+    tag = "Commands",
+    request_body = BroadcastCommandRequest,
+    responses(
+        (status = 200, description = "Broadcast queued", body = BroadcastStatus),
+        (status = 400, description = "Missing or invalid scope, or a REROUTE command"),
+        (status = 404, description = "Unknown sector or no drones in scope"),
+        (status = 422, description = "No drone in scope accepts the command"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn broadcast_command(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<BroadcastCommandRequest>,
+) -> Result<Json<BroadcastStatus>, ApiError> {
+    let bad_request = |message: &str| (StatusCode::BAD_REQUEST, Json(json!({ "error": message })));
+    let scope = request.scope;
+    if scope.polygon.is_none() && scope.owner_id.is_none() && scope.sector_id.is_none() {
+        return Err(bad_request(
+            "Broadcast scope requires polygon, sector_id or owner_id",
+        ));
+    }
+    if let Some(polygon) = scope.polygon.as_ref() {
+        let finite = polygon
+            .iter()
+            .all(|[lat, lon]| lat.is_finite() && lon.is_finite());
+        if polygon.len() < 3 || !finite {
+            return Err(bad_request(
+                "polygon must have at least 3 valid [lat, lon] points",
+            ));
+        }
+    }
+    if let Some(sector_id) = scope.sector_id.as_deref() {
+        if !state
+            .config()
+            .sectors
+            .iter()
+            .any(|sector| sector.id == sector_id)
+        {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "Unknown sector", "sector_id": sector_id })),
+            ));
+        }
+    }
+    // Waypoints are specific to one aircraft.
+    if matches!(request.command_type, CommandType::Reroute { .. }) {
+        return Err(bad_request("REROUTE commands cannot be broadcast"));
+    }
+
+    let drones: Vec<_> = state
+        .get_all_drones()
+        .into_iter()
+        .filter(|drone| drone.status != DroneStatus::Inactive)
+        .filter(|drone| {
+            scope
+                .owner_id
+                .as_deref()
+                .is_none_or(|owner| drone.owner_id.as_deref() == Some(owner))
+        })
+        .filter(|drone| {
+            scope
+                .polygon
+                .as_deref()
+                .is_none_or(|polygon| point_in_polygon(drone.lat, drone.lon, polygon))
+        })
+        .filter(|drone| {
+            scope.sector_id.as_deref().is_none_or(|sector_id| {
+                state
+                    .sector_at(drone.lat, drone.lon)
+                    .is_some_and(|sector| sector.id == sector_id)
+            })
+        })
+        .collect();
+    if drones.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "No drones in broadcast scope" })),
+        ));
+    }
+    // Drones that advertised they cannot execute the command are left out.
+    let (mut drones, incapable): (Vec<_>, Vec<_>) = drones.into_iter().partition(|drone| {
+        drone
+            .capabilities
+            .is_none_or(|capabilities| capabilities.unsupported(&request.command_type).is_none())
+    });
+    if drones.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "error": "No drone in broadcast scope accepts this command",
+                "drone_ids": incapable.iter().map(|drone| &drone.drone_id).collect::<Vec<_>>(),
+            })),
+        ));
+    }
+    if !incapable.is_empty() {
+        tracing::warn!(
+            "Broadcast skips {} drones that do not accept the command",
+            incapable.len()
+        );
+    }
+    drones.sort_by(|a, b| a.drone_id.cmp(&b.drone_id));
+
+    let now = Utc::now();
+    let expires_at = now + Duration::seconds(request.expires_in_secs.unwrap_or(60) as i64);
+    let commands: Vec<Command> = drones
+        .iter()
+        .map(|drone| Command {
+            command_id: format!(
+                "CMD-{}",
+                uuid::Uuid::new_v4().to_string()[..8].to_uppercase()
+            ),
+            drone_id: drone.drone_id.clone(),
+            command_type: request.command_type.clone(),
+            issued_at: now,
+            expires_at: Some(expires_at),
+            acknowledged: false,
+        })
+        .collect();
+    let broadcast = CommandBroadcast {
+        broadcast_id: format!(
+            "BCAST-{}",
+            uuid::Uuid::new_v4().to_string()[..8].to_uppercase()
+        ),
+        command_type: request.command_type,
+        scope,
+        issued_at: now,
+        expires_at,
+        targets: commands
+            .iter()
+            .map(|command| BroadcastTarget {
+                drone_id: command.drone_id.clone(),
+                command_id: command.command_id.clone(),
+                acknowledged_at: None,
+            })
+            .collect(),
+    };
+    let broadcast_id = broadcast.broadcast_id.clone();
+
+    if let Err(err) = state
+        .issue_command_broadcast(broadcast.clone(), commands)
+        .await
+    {
+        tracing::error!("Failed to persist command broadcast: {}", err);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to queue broadcast commands" })),
+        ));
+    }
+    for drone in &drones {
+        state.mark_command_issued(&drone.drone_id);
+    }
+
+    tracing::info!(
+        "Issued broadcast {} to {} drones",
+        broadcast_id,
+        drones.len()
+    );
+    Ok(Json(broadcast_status(broadcast, now)))
+}
+
+/// Acknowledgement summary for a broadcast.
+#[utoipa::path(
+    get,
+    path = "/v1/commands/broadcast/{broadcast_id}",
+    tag = "Commands",
+    params(("broadcast_id" = String, Path)),
+    responses(
+        (status = 200, description = "Broadcast status", body = BroadcastStatus),
+        (status = 404, description = "Broadcast not found"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn get_broadcast_status(
+    State(state): State<Arc<AppState>>,
+    Path(broadcast_id): Path<String>,
+) -> Result<Json<BroadcastStatus>, ApiError> {
+    state
+        .get_command_broadcast(&broadcast_id)
+        .map(|broadcast| Json(broadcast_status(broadcast, Utc::now())))
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "Broadcast not found", "broadcast_id": broadcast_id })),
+            )
+        })
+}
+
+pub(crate) fn broadcast_status(broadcast: CommandBroadcast, now: DateTime<Utc>) -> BroadcastStatus {
+    let expired = broadcast.expires_at <= now;
+    let targets: Vec<BroadcastTargetStatus> = broadcast
+        .targets
+        .into_iter()
+        .map(|target| BroadcastTargetStatus {
+            status: match (target.acknowledged_at, expired) {
+                (Some(_), _) => "acknowledged",
+                (None, false) => "pending",
+                (None, true) => "expired",
+            },
+            drone_id: target.drone_id,
+            command_id: target.command_id,
+            acknowledged_at: target.acknowledged_at,
+        })
+        .collect();
+    let count = |status: &str| targets.iter().filter(|t| t.status == status).count();
+    let (acknowledged, pending, expired) =
+        (count("acknowledged"), count("pending"), count("expired"));
+    BroadcastStatus {
+        broadcast_id: broadcast.broadcast_id,
+        command_type: broadcast.command_type,
+        scope: broadcast.scope,
+        issued_at: broadcast.issued_at,
+        expires_at: broadcast.expires_at,
+        status: if pending > 0 {
+            "pending"
+        } else if expired > 0 {
+            "incomplete"
+        } else {
+            "complete"
+        },
+        total: targets.len(),
+        acknowledged,
+        pending,
+        expired,
+        targets,
+    }
+}
+
 /// Get the next pending command for a drone.
-/// GET /v1/commands/next?drone_id=DRONE001
+#[utoipa::path(
+    get,
+    path = "/v1/commands/next",
+    tag = "Commands",
+    params(NextCommandQuery),
+    responses(
+        (status = 200, description = "The oldest pending command, or null", body = Option<SignedCommand>),
+        (status = 401, description = "Missing or invalid drone session token"),
+    ),
+    security(("droneToken" = [])),
+)]
 pub async fn get_next_command(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Query(query): Query<NextCommandQuery>,
-) -> Result<Json<Option<Command>>, StatusCode> {
+) -> Result<Json<Option<SignedCommand>>, StatusCode> {
     auth::authorize_drone_for(state.as_ref(), &query.drone_id, &headers)?;
-    let command = state.peek_command(&query.drone_id);
+    let command = state
+        .peek_command(&query.drone_id)
+        .map(|command| state.sign_command(command));
     Ok(Json(command))
 }
 
+/// Public key drones use to verify command signatures.
+#[utoipa::path(
+    get,
+    path = "/v1/commands/signing-key",
+    tag = "Commands",
+    responses(
+        (status = 200, description = "Signing key", body = CommandSigningKey),
+        (status = 404, description = "Command signing is disabled"),
+    ),
+)]
+pub async fn get_signing_key(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<CommandSigningKey>, StatusCode> {
+    state
+        .command_signing_key()
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
 /// Acknowledge (and remove) a command.
-/// POST /v1/commands/ack
+#[utoipa::path(
+    post,
+    path = "/v1/commands/ack",
+    tag = "Commands",
+    request_body = AckCommandRequest,
+    responses(
+        (status = 200, description = "`acknowledged`, or `not_found` when the command is unknown", body = Object),
+        (status = 401, description = "Missing or invalid drone session token"),
+        (status = 403, description = "The command belongs to another drone"),
+    ),
+    security(("droneToken" = [])),
+)]
 pub async fn ack_command(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -163,14 +559,174 @@ pub async fn ack_command(
     }
 }
 
-/// Get all pending commands (for debugging/UI).
-/// GET /v1/commands
-pub async fn get_all_commands(State(state): State<Arc<AppState>>) -> Json<Vec<Command>> {
-    Json(state.get_all_pending_commands())
+/// Get pending commands (for debugging/UI), oldest first.
+#[utoipa::path(
+    get,
+    path = "/v1/commands",
+    tag = "Commands",
+    params(CommandListQuery),
+    responses(
+        (status = 200, description = "Pending commands; X-Total-Count and X-Next-Cursor headers", body = Vec<Command>),
+        (status = 400, description = "Unknown filter value, limit too large or invalid cursor"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn get_all_commands(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Query(query): Query<CommandListQuery>,
+) -> Result<Page<Command>, (StatusCode, Json<serde_json::Value>)> {
+    let config = state.config();
+    let limit = pagination::resolve_limit(
+        query.limit,
+        config.list_default_limit,
+        config.list_max_limit,
+    )?;
+    let command_types = match query.command_type.as_deref() {
+        Some(raw) => {
+            let types: Vec<String> = raw
+                .split(',')
+                .map(|value| value.trim().to_ascii_uppercase())
+                .filter(|value| !value.is_empty())
+                .collect();
+            if let Some(unknown) = types
+                .iter()
+                .find(|value| !COMMAND_TYPES.contains(&value.as_str()))
+            {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({
+                        "error": format!("Unknown type '{}'", unknown),
+                        "field": "type"
+                    })),
+                ));
+            }
+            Some(types)
+        }
+        None => None,
+    };
+
+    let now = Utc::now();
+    let mut commands = state.get_all_pending_commands();
+    commands.retain(|command| {
+        let expired = command.expires_at.is_some_and(|at| at <= now);
+        caller.sees_drone(&state, &command.drone_id)
+            && query
+                .drone_id
+                .as_ref()
+                .is_none_or(|drone_id| &command.drone_id == drone_id)
+            && command_types
+                .as_ref()
+                .is_none_or(|types| types.iter().any(|t| t == command_type_name(command)))
+            && query.status.is_none_or(|status| match status {
+                CommandStatus::Pending => !expired,
+                CommandStatus::Expired => expired,
+            })
+    });
+
+    // Oldest first, ties by ID.
+    pagination::paginate(
+        commands,
+        |command| {
+            pagination::sort_key([
+                &pagination::oldest_first(command.issued_at),
+                &command.command_id,
+            ])
+        },
+        limit,
+        query.offset.unwrap_or(0),
+        query.cursor.as_deref(),
+    )
+}
+
+/// Issued commands with what triggered them and how they ended, newest first, for incident
+/// review. Served from the read pool.
+#[utoipa::path(
+    get,
+    path = "/v1/commands/audit",
+    tag = "Commands",
+    params(CommandAuditQuery),
+    responses(
+        (status = 200, description = "Audited commands, newest first", body = Vec<CommandAuditEntry>),
+        (status = 400, description = "since is not before until, or limit too large"),
+        (status = 503, description = "No database configured"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn get_command_audit(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CommandAuditQuery>,
+) -> Result<Json<Vec<CommandAuditEntry>>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(db) = state.database() else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "Command audit requires a database" })),
+        ));
+    };
+    if let (Some(since), Some(until)) = (query.since, query.until) {
+        if since >= until {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "since must be before until" })),
+            ));
+        }
+    }
+    let config = state.config();
+    let limit = pagination::resolve_limit(
+        query.limit,
+        config.list_default_limit,
+        config.list_max_limit,
+    )?;
+    let filter = CommandAuditFilter {
+        drone_id: query.drone_id,
+        cause: query.cause,
+        source_id: query.source_id,
+        since: query.since,
+        until: query.until,
+        limit: u32::try_from(limit).unwrap_or(u32::MAX),
+    };
+
+    match db
+        .read_with_timeout(query_command_audit(db.read_pool(), &filter))
+        .await
+    {
+        Ok(entries) => Ok(Json(entries)),
+        Err(err) if err.is::<ReadTimeout>() => Err((
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(json!({ "error": err.to_string() })),
+        )),
+        Err(err) => {
+            tracing::warn!("Command audit query failed: {}", err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Command audit query failed" })),
+            ))
+        }
+    }
+}
+
+fn command_type_name(command: &Command) -> &'static str {
+    match command.command_type {
+        CommandType::Hold { .. } => "HOLD",
+        CommandType::AltitudeChange { .. } => "ALTITUDE_CHANGE",
+        CommandType::Reroute { .. } => "REROUTE",
+        CommandType::Resume => "RESUME",
+        CommandType::Land => "LAND",
+    }
 }
 
 /// WebSocket stream of commands for a single drone.
-/// GET /v1/commands/ws
+#[utoipa::path(
+    get,
+    path = "/v1/commands/ws",
+    tag = "Commands",
+    params(CommandStreamQuery),
+    responses(
+        (status = 101, description = "WebSocket upgrade; each message is a signed command", body = SignedCommand),
+        (status = 401, description = "Missing or invalid drone session token"),
+    ),
+    security(("droneToken" = [])),
+)]
 pub async fn command_stream_ws(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
@@ -196,7 +752,10 @@ pub async fn command_stream_ws(
 async fn handle_command_socket(mut socket: WebSocket, state: Arc<AppState>, drone_id: String) {
     let pending = state.get_pending_commands(&drone_id);
     for command in pending {
-        if let Ok(payload) = serde_json::to_string(&command) {
+        if chaos().drop_ws_frame() {
+            continue;
+        }
+        if let Ok(payload) = serde_json::to_string(&state.sign_command(command)) {
             if socket.send(Message::Text(payload)).await.is_err() {
                 return;
             }
@@ -208,10 +767,26 @@ async fn handle_command_socket(mut socket: WebSocket, state: Arc<AppState>, dron
         if command.drone_id != drone_id {
             continue;
         }
-        if let Ok(payload) = serde_json::to_string(&command) {
+        if chaos().drop_ws_frame() {
+            continue;
+        }
+        if let Ok(payload) = serde_json::to_string(&state.sign_command(command)) {
             if socket.send(Message::Text(payload)).await.is_err() {
                 break;
             }
         }
     }
 }
+
+/// Automatic-command budgets: the configured limits, and the tokens, trips and withheld
+/// commands of the global budget and each region's.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/command-budget",
+    tag = "Commands",
+    responses((status = 200, description = "Budgets and their buckets", body = CommandBudgetStatus)),
+    security(("bearerAuth" = [])),
+)]
+pub async fn command_budget(State(state): State<Arc<AppState>>) -> Json<CommandBudgetStatus> {
+    Json(state.command_throttle().status(std::time::Instant::now()))
+}
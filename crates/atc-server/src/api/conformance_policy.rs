@@ -0,0 +1,38 @@
+//! Conformance escalation policy administration.
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::conformance_policy::ConformancePolicy;
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+pub async fn get_policy(State(state): State<Arc<AppState>>) -> Json<ConformancePolicy> {
+    Json(state.conformance_policy())
+}
+
+/// Replace the default and per-operator escalation ladders.
+pub async fn set_policy(
+    State(state): State<Arc<AppState>>,
+    Json(policy): Json<ConformancePolicy>,
+) -> Result<Json<ConformancePolicy>, ApiError> {
+    let errors = policy.validate();
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "Invalid conformance policy", "details": errors })),
+        ));
+    }
+
+    if let Err(err) = state.set_conformance_policy(policy.clone()).await {
+        tracing::error!("Failed to persist conformance policy: {}", err);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to save conformance policy" })),
+        ));
+    }
+    tracing::info!("Conformance policy updated: {:?}", policy);
+    Ok(Json(policy))
+}
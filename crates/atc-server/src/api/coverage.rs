@@ -0,0 +1,71 @@
+//! C2 link coverage areas.
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use atc_core::CoverageArea;
+
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// Upper bound on areas per upload, to keep per-grid-point lookups cheap.
+const MAX_COVERAGE_AREAS: usize = 1_000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoverageMap {
+    pub areas: Vec<CoverageArea>,
+}
+
+pub async fn get_coverage(State(state): State<Arc<AppState>>) -> Json<CoverageMap> {
+    Json(CoverageMap {
+        areas: state.coverage_areas(),
+    })
+}
+
+/// Replace the coverage map.
+pub async fn set_coverage(
+    State(state): State<Arc<AppState>>,
+    Json(coverage): Json<CoverageMap>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if coverage.areas.len() > MAX_COVERAGE_AREAS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("At most {} coverage areas are accepted", MAX_COVERAGE_AREAS)
+            })),
+        ));
+    }
+    let mut ids = HashSet::new();
+    let mut errors = Vec::new();
+    for (idx, area) in coverage.areas.iter().enumerate() {
+        errors.extend(
+            area.validate()
+                .into_iter()
+                .map(|error| format!("areas[{}]: {}", idx, error)),
+        );
+        if !ids.insert(area.id.as_str()) {
+            errors.push(format!("areas[{}]: duplicate id '{}'", idx, area.id));
+        }
+    }
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "Invalid coverage areas", "details": errors })),
+        ));
+    }
+
+    let count = coverage.areas.len();
+    if let Err(err) = state.set_coverage_areas(coverage.areas).await {
+        tracing::error!("Failed to persist C2 coverage: {}", err);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to save coverage areas" })),
+        ));
+    }
+    tracing::info!("C2 coverage replaced with {} areas", count);
+    Ok(Json(json!({ "areas": count })))
+}
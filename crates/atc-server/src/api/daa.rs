@@ -6,33 +6,63 @@ use axum::{
 };
 use serde::Deserialize;
 use std::sync::Arc;
+use utoipa::IntoParams;
 
 use atc_core::models::DaaAdvisory;
 
+use crate::api::auth::Caller;
 use crate::state::AppState;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct DaaQuery {
     /// Filter advisories by owner ID
     pub owner_id: Option<String>,
     /// Only return unresolved advisories
     pub active_only: Option<bool>,
+    /// Filter advisories by airspace sector
+    pub sector_id: Option<String>,
+    /// Render descriptions in this locale instead of the server's
+    pub locale: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/daa",
+    tag = "Drones",
+    params(DaaQuery),
+    responses((status = 200, description = "Advisories, most recently updated first", body = Vec<DaaAdvisory>)),
+    security(("bearerAuth" = [])),
+)]
 pub async fn list_daa(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Query(query): Query<DaaQuery>,
 ) -> Json<Vec<DaaAdvisory>> {
     let mut advisories = state.get_daa_advisories();
+    advisories.retain(|advisory| caller.sees_owner(&state, advisory.owner_id.as_deref()));
 
     if let Some(owner_id) = query.owner_id {
         advisories.retain(|advisory| advisory.owner_id.as_ref() == Some(&owner_id));
     }
 
+    if let Some(sector_id) = query.sector_id {
+        advisories.retain(|advisory| advisory.sector_id.as_ref() == Some(&sector_id));
+    }
+
     if query.active_only.unwrap_or(false) {
         advisories.retain(|advisory| !advisory.resolved);
     }
 
-    advisories.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    if let Some(locale) = query.locale.as_deref() {
+        let messages = &state.config().messages;
+        for advisory in &mut advisories {
+            if let Some(message) = advisory.message_code.as_ref() {
+                advisory.description = messages.format(message, Some(locale));
+            }
+        }
+    }
+
+    advisories.sort_by_key(|advisory| std::cmp::Reverse(advisory.updated_at));
     Json(advisories)
 }
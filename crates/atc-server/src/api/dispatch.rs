@@ -0,0 +1,102 @@
+//! Airspace sector and dispatcher work-queue endpoints.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use atc_core::models::{DaaAdvisory, FlightPlan, FlightStatus};
+use atc_core::Conflict;
+
+use crate::sectors::Sector;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct DispatchQueueQuery {
+    /// Dispatcher principal whose sectors to return
+    pub dispatcher: String,
+}
+
+/// Open items in a dispatcher's sectors.
+#[derive(Debug, Serialize)]
+pub struct DispatchQueue {
+    pub dispatcher: String,
+    pub sector_ids: Vec<String>,
+    pub conflicts: Vec<Conflict>,
+    pub advisories: Vec<DaaAdvisory>,
+    /// Reserved or pending flight plans departing from these sectors.
+    pub approvals: Vec<FlightPlan>,
+}
+
+pub async fn list_sectors(State(state): State<Arc<AppState>>) -> Json<Vec<Sector>> {
+    Json(state.config().sectors.clone())
+}
+
+/// Current conflicts, active advisories and approval items for one dispatcher, so a
+/// dispatcher connecting to the stream can catch up on what is already open.
+pub async fn dispatch_queue(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<DispatchQueueQuery>,
+) -> Result<Json<DispatchQueue>, (StatusCode, Json<serde_json::Value>)> {
+    let sector_ids: HashSet<String> = state
+        .config()
+        .sectors
+        .iter()
+        .filter(|sector| sector.dispatcher == query.dispatcher)
+        .map(|sector| sector.id.clone())
+        .collect();
+    if sector_ids.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "No sectors assigned to dispatcher",
+                "dispatcher": query.dispatcher
+            })),
+        ));
+    }
+    let in_sector =
+        |sector_id: Option<&String>| sector_id.is_some_and(|id| sector_ids.contains(id));
+
+    let mut conflicts: Vec<Conflict> = state
+        .get_conflicts()
+        .into_iter()
+        .filter(|conflict| in_sector(conflict.sector_id.as_ref()))
+        .collect();
+    conflicts.sort_by(|a, b| a.time_to_closest.total_cmp(&b.time_to_closest));
+
+    let mut advisories: Vec<DaaAdvisory> = state
+        .get_daa_advisories()
+        .into_iter()
+        .filter(|advisory| !advisory.resolved && in_sector(advisory.sector_id.as_ref()))
+        .collect();
+    advisories.sort_by_key(|advisory| std::cmp::Reverse(advisory.updated_at));
+
+    let mut approvals: Vec<FlightPlan> = state
+        .get_flight_plans()
+        .into_iter()
+        .filter(|plan| matches!(plan.status, FlightStatus::Reserved | FlightStatus::Pending))
+        .filter(|plan| {
+            in_sector(
+                plan.metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.sector_id.as_ref()),
+            )
+        })
+        .collect();
+    approvals.sort_by_key(|plan| plan.departure_time);
+
+    let mut sector_ids: Vec<String> = sector_ids.into_iter().collect();
+    sector_ids.sort();
+    Ok(Json(DispatchQueue {
+        dispatcher: query.dispatcher,
+        sector_ids,
+        conflicts,
+        advisories,
+        approvals,
+    }))
+}
@@ -0,0 +1,243 @@
+//! Emergency all-stop.
+//!
+//! Declaring an emergency sends HOLD or LAND to every active drone in scope at once, ahead of
+//! anything already queued and regardless of command cooldowns, and stops flight plans being
+//! approved until an operator clears it.
+
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::api::commands::{broadcast_status, BroadcastStatus};
+use crate::persistence::emergency::EmergencyStop;
+use crate::state::{AppState, BroadcastScope, BroadcastTarget, CommandBroadcast};
+use atc_core::models::{Command, CommandType, DroneStatus};
+use atc_core::MsaBounds;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// Request to declare an emergency all-stop.
+#[derive(Debug, Deserialize)]
+pub struct EmergencyRequest {
+    /// HOLD or LAND
+    #[serde(flatten)]
+    pub command_type: CommandType,
+    /// Only drones of this owner; every owner when omitted
+    pub owner_id: Option<String>,
+    /// Only drones inside `min_lat,min_lon,max_lat,max_lon`
+    pub bbox: Option<String>,
+    pub reason: Option<String>,
+    /// Command expiry in seconds (default: 60)
+    pub expires_in_secs: Option<u32>,
+}
+
+/// The emergency in force, with the acknowledgements of its commands.
+#[derive(Debug, Serialize)]
+pub struct EmergencyStatus {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emergency: Option<EmergencyStop>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast: Option<BroadcastStatus>,
+}
+
+fn emergency_status(state: &AppState, emergency: Option<EmergencyStop>) -> EmergencyStatus {
+    let broadcast = emergency
+        .as_ref()
+        .and_then(|stop| state.get_command_broadcast(&stop.broadcast_id))
+        .map(|broadcast| broadcast_status(broadcast, Utc::now()));
+    EmergencyStatus {
+        active: emergency.is_some(),
+        emergency,
+        broadcast,
+    }
+}
+
+/// Rejects plan approvals while an emergency all-stop is in force.
+pub(crate) fn ensure_no_emergency(state: &AppState) -> Result<(), ApiError> {
+    match state.emergency_stop() {
+        Some(stop) => Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({
+                "error": "Emergency stop in force",
+                "emergency_id": stop.emergency_id,
+            })),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The emergency in force, if any.
+pub async fn get_emergency(State(state): State<Arc<AppState>>) -> Json<EmergencyStatus> {
+    Json(emergency_status(&state, state.emergency_stop()))
+}
+
+/// Declare an emergency all-stop.
+pub async fn declare_emergency(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<EmergencyRequest>,
+) -> Result<Json<EmergencyStatus>, ApiError> {
+    let bad_request = |message: &str| (StatusCode::BAD_REQUEST, Json(json!({ "error": message })));
+    if !matches!(
+        request.command_type,
+        CommandType::Hold { .. } | CommandType::Land
+    ) {
+        return Err(bad_request("An emergency stop must be HOLD or LAND"));
+    }
+    let bounds = match request.bbox.as_deref() {
+        Some(bbox) => Some(MsaBounds::parse(bbox).ok_or_else(|| {
+            bad_request("Invalid bounding box: bbox must be min_lat,min_lon,max_lat,max_lon")
+        })?),
+        None => None,
+    };
+    if let Some(stop) = state.emergency_stop() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "An emergency stop is already in force",
+                "emergency_id": stop.emergency_id,
+            })),
+        ));
+    }
+
+    let mut drones: Vec<_> = state
+        .get_all_drones()
+        .into_iter()
+        .filter(|drone| drone.status != DroneStatus::Inactive)
+        .filter(|drone| {
+            request
+                .owner_id
+                .as_deref()
+                .is_none_or(|owner| drone.owner_id.as_deref() == Some(owner))
+        })
+        .filter(|drone| {
+            bounds
+                .as_ref()
+                .is_none_or(|bounds| bounds.contains(drone.lat, drone.lon))
+        })
+        .collect();
+    if drones.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "No active drones in emergency scope" })),
+        ));
+    }
+    drones.sort_by(|a, b| a.drone_id.cmp(&b.drone_id));
+
+    let now = Utc::now();
+    let expires_at = now + Duration::seconds(request.expires_in_secs.unwrap_or(60) as i64);
+    // A drone that cannot hold is landed instead.
+    let commands: Vec<Command> = drones
+        .iter()
+        .map(|drone| {
+            let holds = drone.capabilities.as_ref().is_none_or(|capabilities| {
+                capabilities.unsupported(&request.command_type).is_none()
+            });
+            Command {
+                command_id: format!(
+                    "CMD-{}",
+                    uuid::Uuid::new_v4().to_string()[..8].to_uppercase()
+                ),
+                drone_id: drone.drone_id.clone(),
+                command_type: if holds {
+                    request.command_type.clone()
+                } else {
+                    CommandType::Land
+                },
+                issued_at: now,
+                expires_at: Some(expires_at),
+                acknowledged: false,
+            }
+        })
+        .collect();
+    let broadcast = CommandBroadcast {
+        broadcast_id: format!(
+            "BCAST-{}",
+            uuid::Uuid::new_v4().to_string()[..8].to_uppercase()
+        ),
+        command_type: request.command_type.clone(),
+        scope: BroadcastScope {
+            polygon: bounds.map(|b| {
+                vec![
+                    [b.min_lat, b.min_lon],
+                    [b.min_lat, b.max_lon],
+                    [b.max_lat, b.max_lon],
+                    [b.max_lat, b.min_lon],
+                    [b.min_lat, b.min_lon],
+                ]
+            }),
+            owner_id: request.owner_id.clone(),
+            sector_id: None,
+        },
+        issued_at: now,
+        expires_at,
+        targets: commands
+            .iter()
+            .map(|command| BroadcastTarget {
+                drone_id: command.drone_id.clone(),
+                command_id: command.command_id.clone(),
+                acknowledged_at: None,
+            })
+            .collect(),
+    };
+    let stop = EmergencyStop {
+        emergency_id: format!(
+            "EMERG-{}",
+            uuid::Uuid::new_v4().to_string()[..8].to_uppercase()
+        ),
+        command_type: request.command_type,
+        owner_id: request.owner_id,
+        bbox: request.bbox,
+        reason: request.reason,
+        declared_at: now,
+        broadcast_id: broadcast.broadcast_id.clone(),
+        drone_ids: drones.iter().map(|drone| drone.drone_id.clone()).collect(),
+    };
+
+    if let Err(err) = state
+        .declare_emergency(stop.clone(), broadcast, commands)
+        .await
+    {
+        tracing::error!("Failed to persist emergency stop: {}", err);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to declare emergency stop" })),
+        ));
+    }
+
+    tracing::warn!(
+        "Emergency stop {} declared for {} drones{}",
+        stop.emergency_id,
+        stop.drone_ids.len(),
+        stop.reason
+            .as_deref()
+            .map(|reason| format!(": {}", reason))
+            .unwrap_or_default()
+    );
+    Ok(Json(emergency_status(&state, Some(stop))))
+}
+
+/// Clear the emergency all-stop, allowing plan approvals again.
+pub async fn clear_emergency(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<EmergencyStatus>, ApiError> {
+    match state.clear_emergency().await {
+        Ok(Some(stop)) => {
+            tracing::warn!("Emergency stop {} cleared", stop.emergency_id);
+            Ok(Json(emergency_status(&state, None)))
+        }
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "No emergency stop in force" })),
+        )),
+        Err(err) => {
+            tracing::error!("Failed to clear emergency stop: {}", err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to clear emergency stop" })),
+            ))
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Server-Sent Events stream for dashboards that can't hold a WebSocket open.
+use crate::api::auth::Caller;
+use crate::api::ws::{extract_bearer, stream_token_accepted};
+use crate::state::store::WsDroneEvent;
+use crate::state::AppState;
+use atc_core::models::Command;
+use atc_core::Conflict;
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EventsQuery {
+    token: Option<String>,
+    /// Only events for drones registered to this owner
+    owner_id: Option<String>,
+    /// Only events for this drone (conflicts where it is either party)
+    drone_id: Option<String>,
+    /// Comma-separated event types: `drone`, `conflict`, `command` (default: all)
+    types: Option<String>,
+}
+
+/// Stream drone state, conflict and command updates as Server-Sent Events.
+#[utoipa::path(
+    get,
+    path = "/v1/events",
+    tag = "Drones",
+    params(EventsQuery),
+    responses(
+        (status = 200, description = "text/event-stream; each event is named `drone`, `conflict` or `command` and carries the same JSON as the WebSocket streams"),
+        (status = 400, description = "Unknown event type"),
+        (status = 401, description = "Missing or invalid stream token"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn events_handler(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    headers: HeaderMap,
+    Query(params): Query<EventsQuery>,
+) -> axum::response::Response {
+    let provided = params.token.clone().or_else(|| extract_bearer(&headers));
+    let org_filter = caller.org_id().map(str::to_string);
+    if org_filter.is_none() && !stream_token_accepted(state.config(), provided.as_deref()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let kinds = match params.types.as_deref() {
+        Some(types) => {
+            let mut kinds = Vec::new();
+            for name in types
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                let Some(kind) = EventKind::parse(name) else {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        format!("unknown event type '{}'", name),
+                    )
+                        .into_response();
+                };
+                kinds.push(kind);
+            }
+            kinds
+        }
+        None => vec![EventKind::Drone, EventKind::Conflict, EventKind::Command],
+    };
+
+    // Subscribe before responding so nothing published after the request is missed.
+    let stream = EventStream {
+        drones: kinds
+            .contains(&EventKind::Drone)
+            .then(|| state.tx.subscribe()),
+        conflicts: kinds
+            .contains(&EventKind::Conflict)
+            .then(|| state.subscribe_conflicts()),
+        commands: kinds
+            .contains(&EventKind::Command)
+            .then(|| state.subscribe_commands()),
+        owner_filter: params.owner_id,
+        drone_filter: params.drone_id,
+        org_filter,
+        state,
+    };
+    let events = futures::stream::unfold(stream, |mut stream| async move {
+        let event = stream.next_event().await?;
+        Some((Ok::<_, Infallible>(event), stream))
+    });
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventKind {
+    Drone,
+    Conflict,
+    Command,
+}
+
+impl EventKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "drone" => Some(Self::Drone),
+            "conflict" => Some(Self::Conflict),
+            "command" => Some(Self::Command),
+            _ => None,
+        }
+    }
+}
+
+struct EventStream {
+    state: Arc<AppState>,
+    drones: Option<broadcast::Receiver<WsDroneEvent>>,
+    conflicts: Option<broadcast::Receiver<Conflict>>,
+    commands: Option<broadcast::Receiver<Command>>,
+    owner_filter: Option<String>,
+    drone_filter: Option<String>,
+    /// Organization token holders only see their members' drones.
+    org_filter: Option<String>,
+}
+
+impl EventStream {
+    /// Next event that passes the filters; `None` once the server shuts the channels down.
+    async fn next_event(&mut self) -> Option<Event> {
+        loop {
+            let event = tokio::select! {
+                msg = recv(&mut self.drones) => match msg {
+                    Ok(msg) => self.drone_event(msg),
+                    // Drop missed updates; a newer snapshot will arrive soon.
+                    Err(RecvError::Lagged(_)) => None,
+                    Err(RecvError::Closed) => return None,
+                },
+                conflict = recv(&mut self.conflicts) => match conflict {
+                    Ok(conflict) => self.conflict_event(conflict),
+                    Err(RecvError::Lagged(_)) => None,
+                    Err(RecvError::Closed) => return None,
+                },
+                command = recv(&mut self.commands) => match command {
+                    Ok(command) => self.command_event(command),
+                    Err(RecvError::Lagged(_)) => None,
+                    Err(RecvError::Closed) => return None,
+                },
+            };
+            if event.is_some() {
+                return event;
+            }
+        }
+    }
+
+    fn drone_event(&self, msg: WsDroneEvent) -> Option<Event> {
+        if !self.matches(&[msg.drone_id.as_str()], |_| msg.owner_id.clone()) {
+            return None;
+        }
+        Some(Event::default().event("drone").data(msg.payload.as_ref()))
+    }
+
+    fn conflict_event(&self, conflict: Conflict) -> Option<Event> {
+        let drones = [conflict.drone1_id.as_str(), conflict.drone2_id.as_str()];
+        if !self.matches(&drones, |drone_id| self.state.drone_owner(drone_id)) {
+            return None;
+        }
+        let payload = serde_json::to_string(&conflict).ok()?;
+        Some(Event::default().event("conflict").data(payload))
+    }
+
+    fn command_event(&self, command: Command) -> Option<Event> {
+        if !self.matches(&[command.drone_id.as_str()], |drone_id| {
+            self.state.drone_owner(drone_id)
+        }) {
+            return None;
+        }
+        let payload = serde_json::to_string(&command).ok()?;
+        Some(Event::default().event("command").data(payload))
+    }
+
+    /// Whether any of an event's drones passes the drone, owner and organization filters.
+    fn matches(&self, drones: &[&str], owner_of: impl Fn(&str) -> Option<String>) -> bool {
+        drones.iter().any(|drone_id| {
+            if let Some(wanted) = self.drone_filter.as_deref() {
+                if *drone_id != wanted {
+                    return false;
+                }
+            }
+            if let Some(org_id) = self.org_filter.as_deref() {
+                let org_of = owner_of(drone_id)
+                    .and_then(|owner_id| self.state.organization_of_owner(&owner_id));
+                if org_of.as_deref() != Some(org_id) {
+                    return false;
+                }
+            }
+            match self.owner_filter.as_deref() {
+                Some(wanted) => owner_of(drone_id).as_deref() == Some(wanted),
+                None => true,
+            }
+        })
+    }
+}
+
+/// Receive from a subscription, or wait forever when its event type wasn't requested.
+async fn recv<T: Clone>(rx: &mut Option<broadcast::Receiver<T>>) -> Result<T, RecvError> {
+    match rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
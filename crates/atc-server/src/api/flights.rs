@@ -1,24 +1,36 @@
 use crate::altitude::altitude_to_amsl;
+use crate::api::auth::Caller;
+use crate::api::pagination::{self, Page};
 use crate::blender_auth::BlenderAuthManager;
 use crate::compliance::{self, ComplianceEvaluation, RoutePoint};
 use crate::config::Config;
+use crate::fairness::{operator_key, FairnessTally, QuotaExceeded};
+use crate::loops::flight_declaration_sync_loop::declare_flight_plan;
+use crate::persistence::flight_plans::{query_flight_plan_history, FlightPlanHistoryFilter};
+use crate::persistence::ReadTimeout;
+use crate::rejection::{explain_slot, PlanRejection};
 use crate::state::store::AppState;
 use atc_blender::BlenderClient;
+use atc_core::dependencies::expected_clear_time;
+use atc_core::is_approved_landing_point;
+use atc_core::messages::{codes, Message};
 use atc_core::models::{
-    FlightPlan, FlightPlanMetadata, FlightPlanRequest, FlightStatus, GeofenceType, TrajectoryPoint,
-    Waypoint,
+    AdvisoryAcknowledgment, FlightPlan, FlightPlanMetadata, FlightPlanRequest, FlightStatus,
+    GeofenceType, PlanDependency, TrajectoryPoint, Waypoint,
 };
-use atc_core::routing::generate_random_route;
+use atc_core::routing::{generate_seeded_route, random_seed};
+use atc_core::rules::SafetyRules;
 use axum::{
     extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     Json,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde_json::json;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
@@ -66,6 +78,10 @@ pub(crate) struct PlannerMetadata {
     compliance_override_enabled: Option<bool>,
     #[serde(default)]
     compliance_override_notes: Option<String>,
+    #[serde(default)]
+    planning_seed: Option<u64>,
+    #[serde(default)]
+    advisory_acknowledgment: Option<AdvisoryAcknowledgment>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,28 +99,61 @@ pub(crate) struct PlannerFlightRequest {
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub(crate) enum FlightPlanSubmission {
-    Atc(FlightPlanRequest),
-    Planner(PlannerFlightRequest),
+    Atc(Box<FlightPlanRequest>),
+    Planner(Box<PlannerFlightRequest>),
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct FlightPlansQuery {
     pub owner_id: Option<String>,
     #[serde(default)]
     pub limit: Option<usize>,
     #[serde(default)]
     pub offset: Option<usize>,
+    /// `X-Next-Cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FlightPlanHistoryQuery {
+    pub drone_id: Option<String>,
+    pub owner_id: Option<String>,
+    pub status: Option<FlightStatus>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<usize>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/flights/plan",
+    tag = "Flights",
+    request_body = FlightPlanRequest,
+    responses(
+        (status = 201, description = "Plan approved and scheduled", body = FlightPlan),
+        (status = 403, description = "The owner does not match the drone's owner"),
+        (status = 409, description = "No conflict-free slot was found, or route advisories must be acknowledged"),
+        (status = 422, description = "The plan failed validation"),
+        (status = 503, description = "An emergency all-stop is in force"),
+        (status = 429, description = "The operator is at one of its quotas"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn create_flight_plan(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     headers: HeaderMap,
     Json(payload): Json<FlightPlanRequest>,
 ) -> Result<(StatusCode, Json<FlightPlan>), (StatusCode, Json<serde_json::Value>)> {
+    super::emergency::ensure_no_emergency(state.as_ref())?;
     let mut payload = payload;
     let request_id = request_id_from_headers(&headers);
     enforce_owner_for_drone(
         state.as_ref(),
+        &caller,
         &payload.drone_id,
         payload.owner_id.as_deref(),
     )?;
@@ -119,18 +168,12 @@ pub async fn create_flight_plan(
             })),
         ));
     }
+    let points = extract_route_points(&payload);
+    super::advisories::require_acknowledgment(state.as_ref(), &mut payload, &points)?;
     apply_compliance_metadata(&mut payload.metadata, validation.compliance.as_ref());
     let plan = build_plan(state.as_ref(), payload, None, FlightStatus::Approved)
         .await
-        .map_err(|err| {
-            tracing::error!("Failed to persist flight plan: {}", err);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": "Failed to persist flight plan"
-                })),
-            )
-        })?;
+        .map_err(|err| build_plan_failed(state.as_ref(), err, "Failed to persist flight plan"))?;
     if plan.status == FlightStatus::Rejected {
         return Err((
             StatusCode::CONFLICT,
@@ -141,20 +184,89 @@ pub async fn create_flight_plan(
             })),
         ));
     }
+    let plan = ensure_blender_declaration(state.as_ref(), plan, request_id.as_deref()).await?;
     Ok((StatusCode::CREATED, Json(plan)))
 }
 
+/// Run the plan validation and compliance pipeline without creating or scheduling a plan.
+///
+/// Answers 200 whether or not the plan would be accepted, so operator UIs can pre-check routes
+/// while they are being drawn.
+#[utoipa::path(
+    post,
+    path = "/v1/flights/validate",
+    tag = "Flights",
+    request_body = FlightPlanRequest,
+    responses(
+        (status = 200, description = "Violations, compliance report and advisories", body = Object),
+        (status = 403, description = "The owner does not match the drone's owner"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn validate_flight_plan_request(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(payload): Json<FlightPlanRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let mut payload = payload;
+    let request_id = request_id_from_headers(&headers);
+    enforce_owner_for_drone(
+        state.as_ref(),
+        &caller,
+        &payload.drone_id,
+        payload.owner_id.as_deref(),
+    )?;
+    normalize_flight_plan_request(&mut payload, state.config());
+    let validation = validate_flight_plan(state.as_ref(), &payload, request_id.as_deref()).await;
+    let (compliance, blocking_checks) = match validation.compliance {
+        Some(evaluation) => (Some(evaluation.report), evaluation.blocking),
+        None => (None, Vec::new()),
+    };
+    let points = extract_route_points(&payload);
+    let advisories = super::advisories::route_advisories(state.as_ref(), &payload, &points);
+    let acknowledgment_token = (!advisories.is_empty())
+        .then(|| super::advisories::acknowledgment_token(&payload.drone_id, &advisories));
+    Ok(Json(json!({
+        "valid": validation.violations.is_empty(),
+        "violations": validation.violations,
+        "compliance": compliance,
+        "blocking_checks": blocking_checks,
+        "advisories": advisories,
+        "acknowledgment_token": acknowledgment_token
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/v1/flights",
+    tag = "Flights",
+    request_body(
+        content = FlightPlanRequest,
+        description = "An ATC flight plan request, or a planner submission with a `flight_id` and trajectory"
+    ),
+    responses(
+        (status = 201, description = "Plan approved and scheduled", body = FlightPlan),
+        (status = 400, description = "A planner submission without a trajectory"),
+        (status = 409, description = "No conflict-free slot was found, or route advisories must be acknowledged"),
+        (status = 422, description = "The plan failed validation"),
+        (status = 429, description = "The operator is at one of its quotas"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub(crate) async fn create_flight_plan_compat(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     headers: HeaderMap,
     Json(payload): Json<FlightPlanSubmission>,
 ) -> Result<(StatusCode, Json<FlightPlan>), (StatusCode, Json<serde_json::Value>)> {
+    super::emergency::ensure_no_emergency(state.as_ref())?;
     let request_id = request_id_from_headers(&headers);
     let (mut request, requested_flight_id, requires_trajectory) = match payload {
-        FlightPlanSubmission::Atc(request) => (request, None, false),
+        FlightPlanSubmission::Atc(request) => (*request, None, false),
         FlightPlanSubmission::Planner(request) => {
             let flight_id = request.flight_id.clone();
-            (planner_to_request(request), Some(flight_id), true)
+            (planner_to_request(*request), Some(flight_id), true)
         }
     };
 
@@ -180,6 +292,7 @@ pub(crate) async fn create_flight_plan_compat(
 
     enforce_owner_for_drone(
         state.as_ref(),
+        &caller,
         &request.drone_id,
         request.owner_id.as_deref(),
     )?;
@@ -194,6 +307,8 @@ pub(crate) async fn create_flight_plan_compat(
             })),
         ));
     }
+    let points = extract_route_points(&request);
+    super::advisories::require_acknowledgment(state.as_ref(), &mut request, &points)?;
     apply_compliance_metadata(&mut request.metadata, validation.compliance.as_ref());
     let plan = build_plan(
         state.as_ref(),
@@ -202,15 +317,7 @@ pub(crate) async fn create_flight_plan_compat(
         FlightStatus::Approved,
     )
     .await
-    .map_err(|err| {
-        tracing::error!("Failed to persist flight plan: {}", err);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "error": "Failed to persist flight plan"
-            })),
-        )
-    })?;
+    .map_err(|err| build_plan_failed(state.as_ref(), err, "Failed to persist flight plan"))?;
     if plan.status == FlightStatus::Rejected {
         return Err((
             StatusCode::CONFLICT,
@@ -221,6 +328,7 @@ pub(crate) async fn create_flight_plan_compat(
             })),
         ));
     }
+    let plan = ensure_blender_declaration(state.as_ref(), plan, request_id.as_deref()).await?;
     Ok((StatusCode::CREATED, Json(plan)))
 }
 
@@ -269,6 +377,7 @@ fn planner_metadata_to_flight_metadata(metadata: PlannerMetadata) -> FlightPlanM
         faa_compliant: metadata.faa_compliant,
         submitted_at: metadata.submitted_at,
         blender_declaration_id: metadata.blender_declaration_id,
+        blender_declaration_state: None,
         operation_type: metadata.operation_type,
         battery_capacity_min: metadata.battery_capacity_min,
         battery_reserve_min: metadata.battery_reserve_min,
@@ -279,7 +388,12 @@ fn planner_metadata_to_flight_metadata(metadata: PlannerMetadata) -> FlightPlanM
         scheduling_priority: None,
         requested_departure_time: None,
         scheduled_delay_s: None,
+        scheduled_altitude_offset_m: None,
         reservation_expires_at: None,
+        sector_id: None,
+        planning_seed: metadata.planning_seed,
+        advisory_acknowledgment: metadata.advisory_acknowledgment,
+        depends_on: Vec::new(),
     }
 }
 
@@ -400,119 +514,14 @@ async fn validate_flight_plan(
     request: &FlightPlanRequest,
     request_id: Option<&str>,
 ) -> ValidationOutcome {
-    let mut violations = Vec::new();
-    let points = extract_route_points(request);
-    if points.is_empty() {
-        violations.push(json!({
-            "type": "route",
-            "message": "Route is required for compliance checks"
-        }));
-        return ValidationOutcome {
-            violations,
-            compliance: None,
-        };
-    }
-
-    if points.len() < 2 {
-        violations.push(json!({
-            "type": "route",
-            "message": "At least 2 waypoints are required"
-        }));
+    let mut violations = validate_route(state, request).await;
+    if !violations.is_empty() {
         return ValidationOutcome {
             violations,
             compliance: None,
         };
     }
-
-    for (idx, point) in points.iter().enumerate() {
-        if !point.lat.is_finite() || !point.lon.is_finite() {
-            violations.push(json!({
-                "type": "coordinate",
-                "point_index": idx,
-                "lat": point.lat,
-                "lon": point.lon,
-                "message": "Latitude/longitude must be finite numbers"
-            }));
-            continue;
-        }
-
-        if !(-90.0..=90.0).contains(&point.lat) || !(-180.0..=180.0).contains(&point.lon) {
-            violations.push(json!({
-                "type": "coordinate",
-                "point_index": idx,
-                "lat": point.lat,
-                "lon": point.lon,
-                "message": "Latitude/longitude out of range"
-            }));
-        }
-
-        if !point.altitude_m.is_finite() {
-            violations.push(json!({
-                "type": "altitude",
-                "point_index": idx,
-                "altitude_m": point.altitude_m,
-                "message": "Altitude must be a finite number"
-            }));
-            continue;
-        }
-    }
-
-    super::altitude_validation::validate_route_altitudes(state, &points, &mut violations).await;
-
-    let geofences = state.get_geofences();
-    for i in 0..points.len().saturating_sub(1) {
-        let start = points[i];
-        let end = points[i + 1];
-        for geofence in geofences
-            .iter()
-            .filter(|g| g.active && g.geofence_type != GeofenceType::Advisory)
-        {
-            if geofence.intersects_segment(
-                start.lat,
-                start.lon,
-                start.altitude_m,
-                end.lat,
-                end.lon,
-                end.altitude_m,
-            ) {
-                violations.push(json!({
-                    "type": "geofence",
-                    "segment_index": i,
-                    "geofence_id": geofence.id,
-                    "geofence_name": geofence.name,
-                    "geofence_type": geofence.geofence_type,
-                    "message": format!("Route intersects geofence '{}'", geofence.name)
-                }));
-            }
-        }
-    }
-
-    if let Some(log) = request.trajectory_log.as_ref() {
-        let mut last_offset: Option<f64> = None;
-        for (idx, point) in log.iter().enumerate() {
-            if let Some(offset) = point.time_offset_s {
-                if !offset.is_finite() || offset < 0.0 {
-                    violations.push(json!({
-                        "type": "trajectory",
-                        "point_index": idx,
-                        "time_offset_s": offset,
-                        "message": "Trajectory time_offset_s must be a non-negative finite number"
-                    }));
-                }
-                if let Some(prev) = last_offset {
-                    if offset < prev {
-                        violations.push(json!({
-                            "type": "trajectory",
-                            "point_index": idx,
-                            "time_offset_s": offset,
-                            "message": "Trajectory time_offset_s must be non-decreasing"
-                        }));
-                    }
-                }
-                last_offset = Some(offset);
-            }
-        }
-    }
+    let points = extract_route_points(request);
 
     let require_blender = state.config().require_blender_declaration;
     if violations.is_empty() && require_blender {
@@ -554,6 +563,8 @@ async fn validate_flight_plan(
                     }
                 }
             }
+            // The declaration is created by the server once the plan is approved.
+            None if state.config().blender_auto_declare => {}
             None => violations.push(json!({
                 "type": "blender",
                 "message": "Missing Blender declaration ID for flight plan",
@@ -568,7 +579,9 @@ async fn validate_flight_plan(
         };
     }
 
-    let compliance = compliance::evaluate_compliance(state.config(), request, &points).await;
+    let compliance =
+        compliance::evaluate_compliance(state.config(), request, &points, &state.coverage_areas())
+            .await;
     if !compliance.ok {
         let report = serde_json::to_value(&compliance.report).unwrap_or_else(|_| json!({}));
         violations.push(json!({
@@ -585,6 +598,284 @@ async fn validate_flight_plan(
     }
 }
 
+/// Route-level checks (coordinates, altitude limits, geofences, trajectory timing).
+///
+/// These depend only on local state, so the scenario harness runs them directly.
+pub(crate) async fn validate_route(
+    state: &AppState,
+    request: &FlightPlanRequest,
+) -> Vec<serde_json::Value> {
+    let mut violations = Vec::new();
+    let points = extract_route_points(request);
+    if points.is_empty() {
+        violations.push(compliance::violation(
+            state.config(),
+            Message::new(codes::ROUTE_MISSING),
+            json!({ "type": "route" }),
+        ));
+        return violations;
+    }
+
+    if points.len() < 2 {
+        violations.push(compliance::violation(
+            state.config(),
+            Message::new(codes::ROUTE_TOO_SHORT),
+            json!({ "type": "route" }),
+        ));
+        return violations;
+    }
+
+    for (idx, point) in points.iter().enumerate() {
+        if !point.lat.is_finite() || !point.lon.is_finite() {
+            violations.push(compliance::violation(
+                state.config(),
+                Message::new(codes::ROUTE_COORDINATE_NOT_FINITE),
+                json!({
+                    "type": "coordinate",
+                    "point_index": idx,
+                    "lat": point.lat,
+                    "lon": point.lon
+                }),
+            ));
+            continue;
+        }
+
+        if !(-90.0..=90.0).contains(&point.lat) || !(-180.0..=180.0).contains(&point.lon) {
+            violations.push(compliance::violation(
+                state.config(),
+                Message::new(codes::ROUTE_COORDINATE_OUT_OF_RANGE),
+                json!({
+                    "type": "coordinate",
+                    "point_index": idx,
+                    "lat": point.lat,
+                    "lon": point.lon
+                }),
+            ));
+        }
+
+        if !point.altitude_m.is_finite() {
+            violations.push(compliance::violation(
+                state.config(),
+                Message::new(codes::ROUTE_ALTITUDE_NOT_FINITE),
+                json!({
+                    "type": "altitude",
+                    "point_index": idx,
+                    "altitude_m": point.altitude_m
+                }),
+            ));
+            continue;
+        }
+    }
+
+    super::altitude_validation::validate_route_altitudes(state, &points, &mut violations).await;
+    super::msa::validate_route_msa(state, &points, &mut violations);
+
+    let (window_start, window_end) = flight_window(state, request);
+    let geofences = state.get_geofences();
+    for i in 0..points.len().saturating_sub(1) {
+        let start = points[i];
+        let end = points[i + 1];
+        for geofence in geofences.iter().filter(|g| {
+            g.in_force_during(window_start, window_end) && g.geofence_type != GeofenceType::Advisory
+        }) {
+            if geofence.intersects_segment(
+                start.lat,
+                start.lon,
+                start.altitude_m,
+                end.lat,
+                end.lon,
+                end.altitude_m,
+            ) {
+                violations.push(compliance::violation(
+                    state.config(),
+                    Message::new(codes::ROUTE_GEOFENCE)
+                        .with("geofence_name", geofence.name.as_str()),
+                    json!({
+                        "type": "geofence",
+                        "segment_index": i,
+                        "geofence_id": geofence.id,
+                        "geofence_name": geofence.name,
+                        "geofence_type": geofence.geofence_type
+                    }),
+                ));
+            }
+        }
+    }
+
+    if let Some(home) = state.drone_home(&request.drone_id) {
+        for (idx, point) in points.iter().enumerate() {
+            if let Some(excess_m) = home.tether_excess_m(point.lat, point.lon) {
+                violations.push(compliance::violation(
+                    state.config(),
+                    Message::new(codes::ROUTE_TETHER).with("excess_m", excess_m),
+                    json!({
+                        "type": "tether",
+                        "point_index": idx,
+                        "excess_m": excess_m
+                    }),
+                ));
+            }
+        }
+        let last = points[points.len() - 1];
+        if !is_approved_landing_point(
+            Some(&home),
+            &state.config().vertiports,
+            last.lat,
+            last.lon,
+            state.config().landing_point_max_distance_m,
+        ) {
+            violations.push(compliance::violation(
+                state.config(),
+                Message::new(codes::ROUTE_LANDING_POINT),
+                json!({
+                    "type": "landing_point",
+                    "point_index": points.len() - 1,
+                    "distance_from_home_m": home.distance_m(last.lat, last.lon)
+                }),
+            ));
+        }
+    }
+
+    if let Some(log) = request.trajectory_log.as_ref() {
+        let mut last_offset: Option<f64> = None;
+        for (idx, point) in log.iter().enumerate() {
+            if let Some(offset) = point.time_offset_s {
+                if !offset.is_finite() || offset < 0.0 {
+                    violations.push(compliance::violation(
+                        state.config(),
+                        Message::new(codes::ROUTE_TIME_OFFSET_INVALID),
+                        json!({
+                            "type": "trajectory",
+                            "point_index": idx,
+                            "time_offset_s": offset
+                        }),
+                    ));
+                }
+                if let Some(prev) = last_offset {
+                    if offset < prev {
+                        violations.push(compliance::violation(
+                            state.config(),
+                            Message::new(codes::ROUTE_TIME_OFFSET_DECREASING),
+                            json!({
+                                "type": "trajectory",
+                                "point_index": idx,
+                                "time_offset_s": offset
+                            }),
+                        ));
+                    }
+                }
+                last_offset = Some(offset);
+            }
+        }
+    }
+
+    let dependencies = request
+        .metadata
+        .as_ref()
+        .map(|meta| meta.depends_on.as_slice())
+        .unwrap_or_default();
+    for dependency in dependencies {
+        match dependency {
+            PlanDependency::FlightLanded { flight_id } => {
+                let status = state.flight_plans.get(flight_id).map(|plan| plan.status);
+                let message = match status {
+                    None => Message::new(codes::PLAN_DEPENDENCY_UNKNOWN_FLIGHT),
+                    Some(status @ (FlightStatus::Rejected | FlightStatus::Cancelled)) => {
+                        Message::new(codes::PLAN_DEPENDENCY_FLIGHT_ENDED)
+                            .with("status", json!(status))
+                    }
+                    Some(_) => continue,
+                };
+                violations.push(compliance::violation(
+                    state.config(),
+                    message.with("flight_id", flight_id.as_str()),
+                    json!({
+                        "type": "dependency",
+                        "flight_id": flight_id,
+                        "status": status
+                    }),
+                ));
+            }
+            PlanDependency::GeofenceLifted { geofence_id } => {
+                if state.get_geofence(geofence_id).is_none() {
+                    violations.push(compliance::violation(
+                        state.config(),
+                        Message::new(codes::PLAN_DEPENDENCY_UNKNOWN_GEOFENCE)
+                            .with("geofence_id", geofence_id.as_str()),
+                        json!({ "type": "dependency", "geofence_id": geofence_id }),
+                    ));
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+/// Create the Blender declaration for a newly approved plan when auto-declare is enabled.
+///
+/// If Blender declarations are required and creation fails, the plan is rejected; otherwise
+/// the flight declaration sync loop retries later.
+async fn ensure_blender_declaration(
+    state: &AppState,
+    plan: FlightPlan,
+    request_id: Option<&str>,
+) -> Result<FlightPlan, (StatusCode, Json<serde_json::Value>)> {
+    let config = state.config();
+    let declared = plan
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.blender_declaration_id.as_deref())
+        .is_some_and(|id| !id.trim().is_empty());
+    if !config.blender_auto_declare || plan.status != FlightStatus::Approved || declared {
+        return Ok(plan);
+    }
+
+    let auth = BlenderAuthManager::new(config);
+    let mut blender = BlenderClient::new(
+        &config.blender_url,
+        &config.blender_session_id,
+        &config.blender_auth_token,
+    );
+    blender.set_request_id(request_id.map(str::to_string));
+    let result = match auth.apply(&mut blender).await {
+        Ok(()) => declare_flight_plan(state, &blender, &plan).await,
+        Err(err) => Err(err),
+    };
+
+    match result {
+        Ok(updated) => Ok(updated),
+        Err(err) if config.require_blender_declaration => {
+            tracing::warn!(
+                "Rejecting flight {}: Blender declaration failed: {}",
+                plan.flight_id,
+                err
+            );
+            let mut rejected = plan;
+            rejected.status = FlightStatus::Rejected;
+            if let Err(persist_err) = state.add_flight_plan(rejected.clone()).await {
+                tracing::error!("Failed to persist rejected flight plan: {}", persist_err);
+            }
+            Err((
+                StatusCode::BAD_GATEWAY,
+                Json(json!({
+                    "error": "Blender declaration failed",
+                    "message": err.to_string(),
+                    "flight_id": rejected.flight_id
+                })),
+            ))
+        }
+        Err(err) => {
+            tracing::warn!(
+                "Blender declaration for flight {} failed, will retry: {}",
+                plan.flight_id,
+                err
+            );
+            Ok(plan)
+        }
+    }
+}
+
 fn request_id_from_headers(headers: &HeaderMap) -> Option<String> {
     headers
         .get("x-request-id")
@@ -614,6 +905,14 @@ fn apply_compliance_metadata(
     }
 }
 
+/// The plan's `planning_seed`, drawing and recording one if the request had none.
+fn seed_metadata(metadata: &mut Option<FlightPlanMetadata>) -> u64 {
+    *metadata
+        .get_or_insert_with(FlightPlanMetadata::default)
+        .planning_seed
+        .get_or_insert_with(random_seed)
+}
+
 pub(crate) async fn build_plan(
     state: &AppState,
     payload: FlightPlanRequest,
@@ -633,6 +932,10 @@ pub(crate) async fn build_plan(
     } = payload;
     let departure = departure_time.unwrap_or_else(Utc::now);
     let mut metadata = metadata;
+    // Offsets are relative to the route as submitted; a stale one is not carried over.
+    if let Some(meta) = metadata.as_mut() {
+        meta.scheduled_altitude_offset_m = None;
+    }
     let _booking_guard = state.flight_plan_booking_lock().lock().await;
 
     let pool = state.database().map(|db| db.pool().clone());
@@ -653,12 +956,13 @@ pub(crate) async fn build_plan(
     } else if let (Some(origin), Some(dest)) = (origin, destination) {
         atc_core::routing::generate_route_options(origin, dest, 50.0)
     } else {
-        // Fallback to random
+        // Fallback to random, recording the seed so the route can be reproduced
+        let seed = seed_metadata(&mut metadata);
         vec![atc_core::routing::RouteOption {
             option_id: "random".to_string(),
             name: "Random".to_string(),
             description: "Randomly generated".to_string(),
-            waypoints: generate_random_route(),
+            waypoints: generate_seeded_route(seed),
             estimated_duration_secs: 0,
             conflict_risk: atc_core::routing::ConflictRisk::High,
         }]
@@ -669,6 +973,7 @@ pub(crate) async fn build_plan(
     let mut flight_status = FlightStatus::Rejected;
     let mut selected_log: Option<Vec<TrajectoryPoint>> = None;
     let mut selected_departure = departure;
+    let mut selected_altitude_offset_m = 0.0;
 
     // Gather existing active plans for deconfliction.
     let mut scheduling_tx = if let Some(pool) = pool.as_ref() {
@@ -686,6 +991,23 @@ pub(crate) async fn build_plan(
         state.get_flight_plans()
     };
 
+    // Checked against the plans loaded under the booking lock, so concurrent submissions from
+    // one operator cannot both slip under a quota.
+    if let Err(exceeded) = state.config().operator_quotas.check_submission(
+        owner_id.as_deref(),
+        ok_status,
+        &existing_plans,
+        Utc::now(),
+    ) {
+        if let Some(tx) = scheduling_tx.take() {
+            tx.rollback().await.ok();
+        }
+        return Err(exceeded.into());
+    }
+    state
+        .usage_meter()
+        .record_plan_submitted(owner_id.as_deref(), Utc::now());
+
     let active_plans: Vec<FlightPlan> = existing_plans
         .iter()
         .filter(|plan| {
@@ -698,6 +1020,16 @@ pub(crate) async fn build_plan(
         .cloned()
         .collect();
 
+    // Flights this plan waits for push its earliest slot back to when they should have landed.
+    let earliest_departure = metadata
+        .as_ref()
+        .and_then(|meta| {
+            expected_clear_time(&meta.depends_on, |id| {
+                existing_plans.iter().find(|plan| plan.flight_id == id)
+            })
+        })
+        .map_or(departure, |clear| departure.max(clear));
+
     // For reserved operational intents, run a prioritized batch rescheduler so this reservation
     // can move other lower-priority reservations if needed.
     if ok_status == FlightStatus::Reserved {
@@ -712,7 +1044,7 @@ pub(crate) async fn build_plan(
                 trajectory_log.as_ref(),
                 metadata.clone(),
                 has_custom_waypoints,
-                departure,
+                earliest_departure,
             ) {
                 ReservedBatchOutcome::Accepted { new_plan, updates } => {
                     for plan in &updates {
@@ -720,10 +1052,8 @@ pub(crate) async fn build_plan(
                             .await?;
                     }
                     tx.commit().await?;
-                    for plan in &updates {
-                        state
-                            .flight_plans
-                            .insert(plan.flight_id.clone(), plan.clone());
+                    for plan in updates {
+                        state.cache_flight_plan(plan);
                     }
                     return Ok(new_plan);
                 }
@@ -741,51 +1071,78 @@ pub(crate) async fn build_plan(
         0
     };
     let delay_step_secs = state.config().strategic_delay_step_secs.max(1);
+    let altitude_offsets =
+        altitude_layer_offsets(state, trajectory_log.as_ref(), has_custom_waypoints);
 
+    // Slots found in conflict, explained if no slot is found at all.
+    let mut tried_slots: Vec<(FlightPlan, &str)> = Vec::new();
     let mut delay_secs = 0u64;
     'schedule: while delay_secs <= max_delay_secs {
-        let scheduled_departure = departure + chrono::Duration::seconds(delay_secs as i64);
+        let scheduled_departure = earliest_departure + chrono::Duration::seconds(delay_secs as i64);
         for option in &candidates {
-            let candidate_log = resolve_candidate_trajectory(
-                &option.waypoints,
-                trajectory_log.as_ref(),
-                metadata.as_ref(),
-                has_custom_waypoints,
-            );
-            // Create a temp plan to test against
-            let test_plan = FlightPlan {
-                flight_id: flight_id.clone(),
-                drone_id: drone_id.clone(),
-                owner_id: owner_id.clone(),
-                waypoints: option.waypoints.clone(),
-                trajectory_log: candidate_log.clone(),
-                metadata: metadata.clone(),
-                status: FlightStatus::Pending,
-                departure_time: scheduled_departure,
-                arrival_time: None,
-                created_at: Utc::now(),
-            };
-
-            let has_conflict = active_plans.iter().any(|existing| {
-                atc_core::spatial::check_plan_conflict_with_rules(
-                    &test_plan,
-                    existing,
-                    state.rules(),
-                )
-            });
+            for (altitude_offset_m, waypoints) in
+                altitude_layers(&option.waypoints, altitude_offsets, &state.rules())
+            {
+                let candidate_log = resolve_candidate_trajectory(
+                    &waypoints,
+                    trajectory_log.as_ref(),
+                    metadata.as_ref(),
+                    has_custom_waypoints,
+                );
+                // Create a temp plan to test against
+                let test_plan = FlightPlan {
+                    flight_id: flight_id.clone(),
+                    drone_id: drone_id.clone(),
+                    owner_id: owner_id.clone(),
+                    waypoints: waypoints.clone(),
+                    trajectory_log: candidate_log.clone(),
+                    metadata: metadata.clone(),
+                    status: FlightStatus::Pending,
+                    departure_time: scheduled_departure,
+                    arrival_time: None,
+                    created_at: Utc::now(),
+                };
 
-            if !has_conflict {
-                selected_waypoints = Some(option.waypoints.clone());
-                selected_log = candidate_log;
-                selected_departure = scheduled_departure;
-                flight_status = ok_status;
-                break 'schedule; // Found earliest available slot!
+                let has_conflict = active_plans.iter().any(|existing| {
+                    atc_core::spatial::check_plan_conflict_with_rules(
+                        &test_plan,
+                        existing,
+                        &state.rules(),
+                    )
+                });
+
+                if !has_conflict {
+                    selected_waypoints = Some(waypoints);
+                    selected_log = candidate_log;
+                    selected_departure = scheduled_departure;
+                    selected_altitude_offset_m = altitude_offset_m;
+                    flight_status = ok_status;
+                    break 'schedule; // Found earliest available slot!
+                }
+                // Rejection detail explains the routes as submitted.
+                if altitude_offset_m == 0.0 {
+                    tried_slots.push((test_plan, option.option_id.as_str()));
+                }
             }
         }
 
         delay_secs = delay_secs.saturating_add(delay_step_secs);
     }
 
+    if flight_status == FlightStatus::Rejected {
+        state.record_plan_rejection(PlanRejection {
+            flight_id: flight_id.clone(),
+            drone_id: drone_id.clone(),
+            owner_id: owner_id.clone(),
+            requested_departure: departure,
+            slots: tried_slots
+                .iter()
+                .map(|(plan, option)| explain_slot(plan, option, &active_plans, &state.rules()))
+                .collect(),
+            rejected_at: Utc::now(),
+        });
+    }
+
     // If approved, use selected. If rejected, use first option (but mark rejected) or empty?
     // We should probably return the rejected plan so user sees why (or which path failed).
     let final_waypoints = selected_waypoints.unwrap_or_else(|| {
@@ -820,6 +1177,8 @@ pub(crate) async fn build_plan(
                 .num_seconds()
                 .max(0) as u64;
             meta.scheduled_delay_s = Some(delay);
+            meta.scheduled_altitude_offset_m =
+                (selected_altitude_offset_m != 0.0).then_some(selected_altitude_offset_m);
             if flight_status == FlightStatus::Reserved {
                 let ttl_secs = state.config().operational_intent_ttl_secs as i64;
                 let expires_at = chrono::Utc::now() + chrono::Duration::seconds(ttl_secs);
@@ -853,9 +1212,7 @@ pub(crate) async fn build_plan(
         if let Some(mut tx) = scheduling_tx {
             crate::persistence::flight_plans::upsert_flight_plan_tx(&mut tx, &plan).await?;
             tx.commit().await?;
-            state
-                .flight_plans
-                .insert(plan.flight_id.clone(), plan.clone());
+            state.cache_flight_plan(plan.clone());
         } else {
             state.add_flight_plan(plan.clone()).await?;
         }
@@ -898,6 +1255,7 @@ fn schedule_reserved_batch(
         0
     };
     let delay_step_secs = state.config().strategic_delay_step_secs.max(1);
+    let altitude_offsets = altitude_layer_offsets(state, payload_trajectory, allow_payload_log);
 
     let fixed_obstacles: Vec<FlightPlan> = existing_plans
         .iter()
@@ -926,11 +1284,25 @@ fn schedule_reserved_batch(
         created_at: now,
     };
 
-    // Sort by priority then requested time (earliest first).
+    // Sort by priority, then (with weighted delay sharing) by how much delay the operator has
+    // absorbed, most first, then requested time (earliest first).
+    let fairness = &state.config().strategic_fairness;
+    let shares = if fairness.weighted_delay_sharing {
+        fairness.delay_shares(existing_plans)
+    } else {
+        HashMap::new()
+    };
+    let delay_share = |plan: &FlightPlan| {
+        shares
+            .get(&operator_key(plan.owner_id.as_deref()))
+            .copied()
+            .unwrap_or(0.0)
+    };
     reserved_plans.sort_by(|a, b| {
         let pa = scheduling_priority(a.metadata.as_ref());
         let pb = scheduling_priority(b.metadata.as_ref());
         pa.cmp(&pb)
+            .then_with(|| delay_share(b).total_cmp(&delay_share(a)))
             .then_with(|| {
                 earliest_departure_for_reserved(a).cmp(&earliest_departure_for_reserved(b))
             })
@@ -938,98 +1310,192 @@ fn schedule_reserved_batch(
             .then_with(|| a.flight_id.cmp(&b.flight_id))
     });
 
-    let mut scheduled: Vec<FlightPlan> = fixed_obstacles.clone();
-    let mut updates: Vec<FlightPlan> = Vec::new();
+    let schedule = |order: Vec<FlightPlan>| -> (ReservedBatchOutcome, FairnessTally) {
+        let mut tally = FairnessTally::default();
+        let mut scheduled: Vec<FlightPlan> = fixed_obstacles.clone();
+        let mut updates: Vec<FlightPlan> = Vec::new();
+
+        for mut plan in order {
+            let is_new = plan.flight_id == flight_id;
+            let earliest = if is_new {
+                requested_departure
+            } else {
+                let requested = earliest_departure_for_reserved(&plan);
+                plan.metadata
+                    .as_ref()
+                    .and_then(|meta| {
+                        expected_clear_time(&meta.depends_on, |id| {
+                            updates
+                                .iter()
+                                .chain(existing_plans)
+                                .find(|other| other.flight_id == id)
+                        })
+                    })
+                    .map_or(requested, |clear| requested.max(clear))
+            };
+
+            let route_options: Vec<atc_core::routing::RouteOption> = if is_new {
+                candidates.to_vec()
+            } else {
+                vec![atc_core::routing::RouteOption {
+                    option_id: "existing".to_string(),
+                    name: "Existing".to_string(),
+                    description: "Existing reserved route".to_string(),
+                    waypoints: plan.waypoints.clone(),
+                    estimated_duration_secs: 0,
+                    conflict_risk: atc_core::routing::ConflictRisk::High,
+                }]
+            };
+
+            let payload_log = if is_new {
+                payload_trajectory
+            } else {
+                plan.trajectory_log.as_ref()
+            };
+            let allow_log = if is_new { allow_payload_log } else { true };
+            // Only the new reservation is layered; existing ones keep the altitudes they were given.
+            let offsets = if is_new { altitude_offsets } else { &[] };
+
+            let Some((scheduled_plan, accepted)) = try_schedule_plan(
+                state,
+                &plan,
+                &route_options,
+                payload_log,
+                allow_log,
+                earliest,
+                &scheduled,
+                max_delay_secs,
+                delay_step_secs,
+                offsets,
+                &mut tally,
+            ) else {
+                if is_new {
+                    // Reject without impacting existing reservations.
+                    let rejected = build_rejected_plan(
+                        &new_plan_template,
+                        candidates,
+                        payload_trajectory,
+                        payload_metadata.as_ref(),
+                        allow_payload_log,
+                        requested_departure,
+                    );
+                    let outcome = ReservedBatchOutcome::Rejected {
+                        rejected_plan: rejected,
+                    };
+                    return (outcome, tally);
+                }
+
+                plan.status = FlightStatus::Rejected;
+                updates.push(plan);
+                continue;
+            };
+
+            if accepted {
+                updates.push(scheduled_plan.clone());
+                scheduled.push(scheduled_plan);
+            } else if is_new {
+                let outcome = ReservedBatchOutcome::Rejected {
+                    rejected_plan: scheduled_plan,
+                };
+                return (outcome, tally);
+            } else {
+                updates.push(scheduled_plan);
+            }
+        }
+
+        // Determine the final scheduled version of the new plan.
+        let new_plan = updates
+            .iter()
+            .find(|plan| plan.flight_id == flight_id)
+            .cloned()
+            .unwrap_or_else(|| new_plan_template.clone());
+
+        (ReservedBatchOutcome::Accepted { new_plan, updates }, tally)
+    };
 
     // Schedule existing reservations first (stable), then insert the new one according to priority.
     // If the new reservation has higher priority than some existing reservations, it will be
-    // scheduled earlier and may push lower-priority reservations later.
+    // scheduled earlier and may push lower-priority reservations later. With delay sharing it
+    // also goes ahead of equal-priority reservations of operators that have absorbed less delay.
     let new_priority = scheduling_priority(payload_metadata.as_ref());
-    let insert_index = reserved_plans
+    let new_share = delay_share(&new_plan_template);
+    let by_priority = reserved_plans
         .iter()
         .position(|plan| scheduling_priority(plan.metadata.as_ref()) > new_priority)
         .unwrap_or(reserved_plans.len());
-    reserved_plans.insert(insert_index, new_plan_template.clone());
-
-    for mut plan in reserved_plans {
-        let is_new = plan.flight_id == flight_id;
-        let earliest = if is_new {
-            requested_departure
-        } else {
-            earliest_departure_for_reserved(&plan)
-        };
+    let insert_index = reserved_plans
+        .iter()
+        .position(|plan| {
+            let priority = scheduling_priority(plan.metadata.as_ref());
+            priority > new_priority || (priority == new_priority && delay_share(plan) < new_share)
+        })
+        .unwrap_or(reserved_plans.len());
 
-        let route_options: Vec<atc_core::routing::RouteOption> = if is_new {
-            candidates.to_vec()
-        } else {
-            vec![atc_core::routing::RouteOption {
-                option_id: "existing".to_string(),
-                name: "Existing".to_string(),
-                description: "Existing reserved route".to_string(),
-                waypoints: plan.waypoints.clone(),
-                estimated_duration_secs: 0,
-                conflict_risk: atc_core::routing::ConflictRisk::High,
-            }]
-        };
+    let mut order = reserved_plans.clone();
+    order.insert(insert_index, new_plan_template.clone());
+    let (mut outcome, mut tally) = schedule(order);
+    if insert_index < by_priority {
+        tally.delay_sharing_promotion(owner_id);
+    }
 
-        let payload_log = if is_new {
-            payload_trajectory
-        } else {
-            plan.trajectory_log.as_ref()
-        };
-        let allow_log = if is_new { allow_payload_log } else { true };
-
-        let Some((scheduled_plan, accepted)) = try_schedule_plan(
-            state,
-            &plan,
-            &route_options,
-            payload_log,
-            allow_log,
-            earliest,
-            &scheduled,
-            max_delay_secs,
-            delay_step_secs,
-        ) else {
-            if is_new {
-                // Reject without impacting existing reservations.
-                let rejected = build_rejected_plan(
-                    &new_plan_template,
-                    candidates,
-                    payload_trajectory,
-                    payload_metadata.as_ref(),
-                    allow_payload_log,
-                    requested_departure,
-                );
-                return ReservedBatchOutcome::Rejected {
-                    rejected_plan: rejected,
-                };
+    if insert_index < reserved_plans.len() {
+        if let ReservedBatchOutcome::Accepted { updates, .. } = &outcome {
+            let inflicted =
+                preemption_delays(existing_plans, updates, flight_id, owner_id, max_delay_secs);
+            let total_secs: u64 = inflicted.iter().map(|(_, secs)| secs).sum();
+            if fairness.max_preemption_delay_secs > 0
+                && total_secs > fairness.max_preemption_delay_secs
+            {
+                // Too costly for the others: queue behind every existing reservation instead.
+                let mut order = reserved_plans;
+                order.push(new_plan_template.clone());
+                (outcome, tally) = schedule(order);
+                tally.preemption_limited(owner_id);
+            } else {
+                for (suffered_by, secs) in inflicted {
+                    tally.preemption_delay(owner_id, suffered_by, secs);
+                }
             }
-
-            plan.status = FlightStatus::Rejected;
-            updates.push(plan);
-            continue;
-        };
-
-        if accepted {
-            updates.push(scheduled_plan.clone());
-            scheduled.push(scheduled_plan);
-        } else if is_new {
-            return ReservedBatchOutcome::Rejected {
-                rejected_plan: scheduled_plan,
-            };
-        } else {
-            updates.push(scheduled_plan);
         }
     }
 
-    // Determine the final scheduled version of the new plan.
-    let new_plan = updates
-        .iter()
-        .find(|plan| plan.flight_id == flight_id)
-        .cloned()
-        .unwrap_or_else(|| new_plan_template.clone());
+    if matches!(outcome, ReservedBatchOutcome::Accepted { .. }) {
+        state.fairness_metrics().record(tally);
+    }
+    outcome
+}
 
-    ReservedBatchOutcome::Accepted { new_plan, updates }
+/// Delay the new reservation `flight_id` inflicted on other operators' reservations, per
+/// rescheduled reservation; one pushed out of the delay window counts the whole window.
+fn preemption_delays<'a>(
+    existing_plans: &'a [FlightPlan],
+    updates: &[FlightPlan],
+    flight_id: &str,
+    owner_id: Option<&str>,
+    max_delay_secs: u64,
+) -> Vec<(Option<&'a str>, u64)> {
+    let operator = operator_key(owner_id);
+    updates
+        .iter()
+        .filter(|plan| plan.flight_id != flight_id)
+        .filter_map(|plan| {
+            let previous = existing_plans
+                .iter()
+                .find(|existing| existing.flight_id == plan.flight_id)?;
+            if operator_key(previous.owner_id.as_deref()) == operator {
+                return None;
+            }
+            let delay_secs = if plan.status == FlightStatus::Rejected {
+                max_delay_secs
+            } else {
+                plan.departure_time
+                    .signed_duration_since(previous.departure_time)
+                    .num_seconds()
+                    .max(0) as u64
+            };
+            (delay_secs > 0).then_some((previous.owner_id.as_deref(), delay_secs))
+        })
+        .collect()
 }
 
 fn scheduling_priority(metadata: Option<&FlightPlanMetadata>) -> u32 {
@@ -1060,14 +1526,22 @@ fn try_schedule_plan(
     obstacles: &[FlightPlan],
     max_delay_secs: u64,
     delay_step_secs: u64,
+    altitude_offsets: &[f64],
+    tally: &mut FairnessTally,
 ) -> Option<(FlightPlan, bool)> {
+    let fairness = &state.config().strategic_fairness;
+    // First delay at which a conflict-free slot was passed over for the concurrency cap.
+    let mut capped_at: Option<u64> = None;
     let mut delay_secs = 0u64;
     while delay_secs <= max_delay_secs {
         let scheduled_departure = earliest_departure + chrono::Duration::seconds(delay_secs as i64);
 
-        for option in route_options {
+        let layers = route_options.iter().flat_map(|option| {
+            altitude_layers(&option.waypoints, altitude_offsets, &state.rules())
+        });
+        for (altitude_offset_m, waypoints) in layers {
             let candidate_log = resolve_candidate_trajectory(
-                &option.waypoints,
+                &waypoints,
                 payload_log,
                 plan_template.metadata.as_ref(),
                 allow_payload_log,
@@ -1076,7 +1550,7 @@ fn try_schedule_plan(
                 flight_id: plan_template.flight_id.clone(),
                 drone_id: plan_template.drone_id.clone(),
                 owner_id: plan_template.owner_id.clone(),
-                waypoints: option.waypoints.clone(),
+                waypoints: waypoints.clone(),
                 trajectory_log: candidate_log.clone(),
                 metadata: plan_template.metadata.clone(),
                 status: FlightStatus::Pending,
@@ -1089,7 +1563,7 @@ fn try_schedule_plan(
                 atc_core::spatial::check_plan_conflict_with_rules(
                     &test_plan,
                     existing,
-                    state.rules(),
+                    &state.rules(),
                 )
             });
             if has_conflict {
@@ -1097,7 +1571,7 @@ fn try_schedule_plan(
             }
 
             let mut metadata = plan_template.metadata.clone();
-            fill_flight_metadata(&mut metadata, &option.waypoints, candidate_log.as_ref());
+            fill_flight_metadata(&mut metadata, &waypoints, candidate_log.as_ref());
             if metadata.is_none() {
                 metadata = Some(FlightPlanMetadata::default());
             }
@@ -1116,33 +1590,41 @@ fn try_schedule_plan(
                     .num_seconds()
                     .max(0) as u64;
                 meta.scheduled_delay_s = Some(total_delay_s);
+                if altitude_offset_m != 0.0 {
+                    meta.scheduled_altitude_offset_m = Some(altitude_offset_m);
+                }
                 let ttl_secs = state.config().operational_intent_ttl_secs as i64;
                 let expires_at = chrono::Utc::now() + chrono::Duration::seconds(ttl_secs);
                 meta.reservation_expires_at = Some(expires_at.to_rfc3339());
             }
 
             let arrival_time = estimate_arrival_time(
-                &option.waypoints,
+                &waypoints,
                 candidate_log.as_ref(),
                 metadata.as_ref(),
                 scheduled_departure,
             );
 
-            return Some((
-                FlightPlan {
-                    flight_id: plan_template.flight_id.clone(),
-                    drone_id: plan_template.drone_id.clone(),
-                    owner_id: plan_template.owner_id.clone(),
-                    waypoints: option.waypoints.clone(),
-                    trajectory_log: candidate_log,
-                    metadata,
-                    status: FlightStatus::Reserved,
-                    departure_time: scheduled_departure,
-                    arrival_time,
-                    created_at: chrono::Utc::now(),
-                },
-                true,
-            ));
+            let plan = FlightPlan {
+                flight_id: plan_template.flight_id.clone(),
+                drone_id: plan_template.drone_id.clone(),
+                owner_id: plan_template.owner_id.clone(),
+                waypoints: waypoints.clone(),
+                trajectory_log: candidate_log,
+                metadata,
+                status: FlightStatus::Reserved,
+                departure_time: scheduled_departure,
+                arrival_time,
+                created_at: chrono::Utc::now(),
+            };
+            if fairness.exceeds_concurrency(&plan, obstacles) {
+                capped_at.get_or_insert(delay_secs);
+                continue;
+            }
+            if let Some(capped_at) = capped_at {
+                tally.concurrency_deferral(plan.owner_id.as_deref(), delay_secs - capped_at);
+            }
+            return Some((plan, true));
         }
 
         delay_secs = delay_secs.saturating_add(delay_step_secs);
@@ -1183,11 +1665,65 @@ fn build_rejected_plan(
     }
 }
 
+/// Response for a failed `build_plan`: 429 when the operator is at a quota, 500 otherwise.
+fn build_plan_failed(
+    state: &AppState,
+    err: anyhow::Error,
+    message: &str,
+) -> (StatusCode, Json<serde_json::Value>) {
+    match err.downcast::<QuotaExceeded>() {
+        Ok(exceeded) => quota_exceeded(state, exceeded),
+        Err(err) => {
+            tracing::error!("{}: {}", message, err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": message })),
+            )
+        }
+    }
+}
+
+fn quota_exceeded(
+    state: &AppState,
+    exceeded: QuotaExceeded,
+) -> (StatusCode, Json<serde_json::Value>) {
+    tracing::warn!(
+        "Operator {} is at its {} quota ({})",
+        exceeded.owner_id,
+        exceeded.quota,
+        exceeded.limit
+    );
+    let mut tally = FairnessTally::default();
+    tally.quota_rejection(Some(&exceeded.owner_id));
+    state.fairness_metrics().record(tally);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({
+            "error": "Operator quota exceeded",
+            "quota": exceeded
+        })),
+    )
+}
+
 fn enforce_owner_for_drone(
     state: &AppState,
+    caller: &Caller,
     drone_id: &str,
     owner_id: Option<&str>,
 ) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    // Organization tokens only act for the organization's own operators.
+    if *caller != Caller::Admin && !caller.sees_owner(state, owner_id) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({
+                "error": "Forbidden",
+                "message": "Owner is not a member of the caller's organization",
+                "owner_id": owner_id,
+                "drone_id": drone_id
+            })),
+        ));
+    }
+
     let drone = match state.get_drone(drone_id) {
         Some(drone) => drone,
         None => return Ok(()),
@@ -1214,38 +1750,68 @@ fn enforce_owner_for_drone(
     Ok(())
 }
 
+/// Whether a plan belongs to one of the caller's operators, by owner or by drone.
+pub(crate) fn caller_sees_plan(state: &AppState, caller: &Caller, plan: &FlightPlan) -> bool {
+    caller.sees_owner(state, plan.owner_id.as_deref()) || caller.sees_drone(state, &plan.drone_id)
+}
+
+/// 404 for plans of other organizations, so their IDs aren't confirmed to exist.
+fn ensure_caller_sees_plan(
+    state: &AppState,
+    caller: &Caller,
+    flight_id: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let hidden = state
+        .flight_plans
+        .get(flight_id)
+        .is_some_and(|plan| !caller_sees_plan(state, caller, &plan));
+    if hidden {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "Not found",
+                "message": "Operational intent not found",
+                "flight_id": flight_id
+            })),
+        ));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/v1/flights",
+    tag = "Flights",
+    params(FlightPlansQuery),
+    responses(
+        (status = 200, description = "Flight plans, newest first; X-Total-Count and X-Next-Cursor headers", body = Vec<FlightPlan>),
+        (status = 400, description = "Limit too large or invalid cursor"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn get_flight_plans(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Query(query): Query<FlightPlansQuery>,
-) -> Result<Json<Vec<FlightPlan>>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Page<FlightPlan>, (StatusCode, Json<serde_json::Value>)> {
     let FlightPlansQuery {
         owner_id,
         limit,
         offset,
+        cursor,
     } = query;
 
     let config = state.config();
-    let max_limit = if config.flights_list_max_limit == 0 {
-        usize::MAX
-    } else {
-        config.flights_list_max_limit
-    };
-    let requested_limit = match limit {
-        Some(value) => value,
-        None => config.flights_list_default_limit.min(max_limit),
-    };
-    if limit.is_some() && requested_limit > max_limit {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "limit too large",
-                "max_limit": max_limit
-            })),
-        ));
-    }
+    let limit = pagination::resolve_limit(
+        limit,
+        config.flights_list_default_limit,
+        config.flights_list_max_limit,
+    )?;
 
-    let offset = offset.unwrap_or(0);
     let mut plans = state.get_flight_plans();
+    if caller != Caller::Admin {
+        plans.retain(|plan| caller_sees_plan(&state, &caller, plan));
+    }
     if let Some(owner_id) = owner_id {
         let owner_drone_ids: HashSet<String> = state
             .get_all_drones()
@@ -1258,33 +1824,142 @@ pub async fn get_flight_plans(
         });
     }
 
-    plans.sort_by(|a, b| {
-        b.created_at
-            .cmp(&a.created_at)
-            .then_with(|| a.flight_id.cmp(&b.flight_id))
-    });
+    // Newest first, ties by ID.
+    pagination::paginate(
+        plans,
+        |plan| pagination::sort_key([&pagination::newest_first(plan.created_at), &plan.flight_id]),
+        limit,
+        offset.unwrap_or(0),
+        cursor.as_deref(),
+    )
+}
 
-    let page: Vec<FlightPlan> = plans
-        .into_iter()
-        .skip(offset)
-        .take(requested_limit)
-        .collect();
-    Ok(Json(page))
+/// Persisted flight plan history, served from the read-only analytics pool.
+#[utoipa::path(
+    get,
+    path = "/v1/flights/history",
+    tag = "Flights",
+    params(FlightPlanHistoryQuery),
+    responses(
+        (status = 200, description = "Flight plans, newest departure first", body = Vec<FlightPlan>),
+        (status = 504, description = "History query timed out"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn get_flight_plan_history(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Query(query): Query<FlightPlanHistoryQuery>,
+) -> Result<Json<Vec<FlightPlan>>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(db) = state.database() else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "Flight history requires a database" })),
+        ));
+    };
+
+    let config = state.config();
+    let max_limit = if config.flights_list_max_limit == 0 {
+        usize::MAX
+    } else {
+        config.flights_list_max_limit
+    };
+    let limit = query
+        .limit
+        .unwrap_or(config.flights_list_default_limit)
+        .min(max_limit);
+    let filter = FlightPlanHistoryFilter {
+        drone_id: query.drone_id,
+        owner_id: query.owner_id,
+        status: query.status,
+        since: query.since,
+        until: query.until,
+        limit: u32::try_from(limit).unwrap_or(u32::MAX),
+    };
+
+    match db
+        .read_with_timeout(query_flight_plan_history(db.read_pool(), &filter))
+        .await
+    {
+        Ok(mut plans) => {
+            if caller != Caller::Admin {
+                plans.retain(|plan| caller_sees_plan(&state, &caller, plan));
+            }
+            Ok(Json(plans))
+        }
+        Err(err) if err.is::<ReadTimeout>() => Err((
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(json!({ "error": err.to_string() })),
+        )),
+        Err(err) => {
+            tracing::warn!("Flight history query failed: {}", err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Flight history query failed" })),
+            ))
+        }
+    }
+}
+
+/// Why the scheduler rejected a plan: the plans blocking each slot it tried.
+#[utoipa::path(
+    get,
+    path = "/v1/flights/{flight_id}/rejection-detail",
+    tag = "Flights",
+    params(("flight_id" = String, Path)),
+    responses(
+        (status = 200, description = "Slots tried and the plans blocking each", body = PlanRejection),
+        (status = 404, description = "No rejection recorded for the flight"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn get_rejection_detail(
+    State(state): State<Arc<AppState>>,
+    Path(flight_id): Path<String>,
+) -> Result<Json<PlanRejection>, (StatusCode, Json<serde_json::Value>)> {
+    // A plan accepted under the same ID since its rejection makes the explanation stale.
+    let accepted = state
+        .flight_plans
+        .get(&flight_id)
+        .is_some_and(|plan| plan.status != FlightStatus::Rejected);
+    match state.plan_rejection(&flight_id) {
+        Some(rejection) if !accepted => Ok(Json(rejection)),
+        _ => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "No rejection recorded for this flight plan" })),
+        )),
+    }
 }
 
 // =============================
 // Operational intent endpoints
 // =============================
 
+#[utoipa::path(
+    post,
+    path = "/v1/operational_intents/reserve",
+    tag = "Flights",
+    request_body = FlightPlanRequest,
+    responses(
+        (status = 201, description = "Slot reserved", body = FlightPlan),
+        (status = 409, description = "No conflict-free slot was found"),
+        (status = 422, description = "The plan failed validation"),
+        (status = 429, description = "The operator is at one of its quotas"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn reserve_operational_intent(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     headers: HeaderMap,
     Json(payload): Json<FlightPlanRequest>,
 ) -> Result<(StatusCode, Json<FlightPlan>), (StatusCode, Json<serde_json::Value>)> {
+    super::emergency::ensure_no_emergency(state.as_ref())?;
     let mut payload = payload;
     let request_id = request_id_from_headers(&headers);
     enforce_owner_for_drone(
         state.as_ref(),
+        &caller,
         &payload.drone_id,
         payload.owner_id.as_deref(),
     )?;
@@ -1303,13 +1978,7 @@ pub async fn reserve_operational_intent(
     let plan = build_plan(state.as_ref(), payload, None, FlightStatus::Reserved)
         .await
         .map_err(|err| {
-            tracing::error!("Failed to persist operational intent: {}", err);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": "Failed to persist operational intent"
-                })),
-            )
+            build_plan_failed(state.as_ref(), err, "Failed to persist operational intent")
         })?;
     if plan.status == FlightStatus::Rejected {
         return Err((
@@ -1324,10 +1993,26 @@ pub async fn reserve_operational_intent(
     Ok((StatusCode::CREATED, Json(plan)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/operational_intents/{flight_id}/confirm",
+    tag = "Flights",
+    params(("flight_id" = String, Path)),
+    responses(
+        (status = 200, description = "Reservation confirmed", body = FlightPlan),
+        (status = 404, description = "Flight plan not found"),
+        (status = 409, description = "The plan is not reserved or its reservation expired"),
+        (status = 429, description = "The operator is at one of its quotas"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn confirm_operational_intent(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(flight_id): Path<String>,
 ) -> Result<(StatusCode, Json<FlightPlan>), (StatusCode, Json<serde_json::Value>)> {
+    super::emergency::ensure_no_emergency(state.as_ref())?;
+    ensure_caller_sees_plan(state.as_ref(), &caller, &flight_id)?;
     let _booking_guard = state.flight_plan_booking_lock().lock().await;
     let pool = state.database().map(|db| db.pool().clone());
 
@@ -1402,13 +2087,22 @@ pub async fn confirm_operational_intent(
     let existing_plans = crate::persistence::flight_plans::load_all_flight_plans_tx(&mut tx)
         .await
         .unwrap_or_default();
+    if let Err(exceeded) = state.config().operator_quotas.check_status(
+        updated.owner_id.as_deref(),
+        FlightStatus::Approved,
+        &existing_plans,
+        Utc::now(),
+    ) {
+        tx.rollback().await.ok();
+        return Err(quota_exceeded(state.as_ref(), exceeded));
+    }
     let has_conflict = existing_plans.iter().any(|plan| {
         plan.flight_id != updated.flight_id
             && matches!(
                 plan.status,
                 FlightStatus::Reserved | FlightStatus::Approved | FlightStatus::Active
             )
-            && atc_core::spatial::check_plan_conflict_with_rules(&updated, plan, state.rules())
+            && atc_core::spatial::check_plan_conflict_with_rules(&updated, plan, &state.rules())
     });
     if has_conflict {
         tx.rollback().await.ok();
@@ -1446,14 +2140,30 @@ pub async fn confirm_operational_intent(
     state
         .flight_plans
         .insert(updated.flight_id.clone(), updated.clone());
+    drop(_booking_guard);
 
+    let updated = ensure_blender_declaration(state.as_ref(), updated, None).await?;
     Ok((StatusCode::OK, Json(updated)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/operational_intents/{flight_id}/cancel",
+    tag = "Flights",
+    params(("flight_id" = String, Path)),
+    responses(
+        (status = 200, description = "Plan cancelled", body = FlightPlan),
+        (status = 404, description = "Flight plan not found"),
+        (status = 409, description = "The plan can no longer be cancelled"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn cancel_operational_intent(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(flight_id): Path<String>,
 ) -> Result<(StatusCode, Json<FlightPlan>), (StatusCode, Json<serde_json::Value>)> {
+    ensure_caller_sees_plan(state.as_ref(), &caller, &flight_id)?;
     let _booking_guard = state.flight_plan_booking_lock().lock().await;
     let pool = state.database().map(|db| db.pool().clone());
 
@@ -1535,12 +2245,29 @@ pub async fn cancel_operational_intent(
     Ok((StatusCode::OK, Json(updated)))
 }
 
+#[utoipa::path(
+    put,
+    path = "/v1/operational_intents/{flight_id}",
+    tag = "Flights",
+    params(("flight_id" = String, Path)),
+    request_body = FlightPlanRequest,
+    responses(
+        (status = 200, description = "Plan updated", body = FlightPlan),
+        (status = 404, description = "Flight plan not found"),
+        (status = 409, description = "The updated plan conflicts or the plan can no longer change"),
+        (status = 422, description = "The plan failed validation"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn update_operational_intent(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     headers: HeaderMap,
     Path(flight_id): Path<String>,
     Json(payload): Json<FlightPlanRequest>,
 ) -> Result<(StatusCode, Json<FlightPlan>), (StatusCode, Json<serde_json::Value>)> {
+    super::emergency::ensure_no_emergency(state.as_ref())?;
+    ensure_caller_sees_plan(state.as_ref(), &caller, &flight_id)?;
     let request_id = request_id_from_headers(&headers);
     let _booking_guard = state.flight_plan_booking_lock().lock().await;
     let pool = state.database().map(|db| db.pool().clone());
@@ -1642,6 +2369,7 @@ pub async fn update_operational_intent(
 
     enforce_owner_for_drone(
         state.as_ref(),
+        &caller,
         &payload.drone_id,
         payload.owner_id.as_deref(),
     )?;
@@ -1663,7 +2391,7 @@ pub async fn update_operational_intent(
         owner_id,
         waypoints,
         trajectory_log,
-        metadata,
+        mut metadata,
         origin,
         destination,
         departure_time,
@@ -1692,11 +2420,12 @@ pub async fn update_operational_intent(
     } else if let (Some(origin), Some(dest)) = (origin, destination) {
         atc_core::routing::generate_route_options(origin, dest, 50.0)
     } else {
+        let seed = seed_metadata(&mut metadata);
         vec![atc_core::routing::RouteOption {
             option_id: "random".to_string(),
             name: "Random".to_string(),
             description: "Randomly generated".to_string(),
-            waypoints: generate_random_route(),
+            waypoints: generate_seeded_route(seed),
             estimated_duration_secs: 0,
             conflict_risk: atc_core::routing::ConflictRisk::High,
         }]
@@ -1744,10 +2473,8 @@ pub async fn update_operational_intent(
                 )
             })?;
 
-            for plan in &updates {
-                state
-                    .flight_plans
-                    .insert(plan.flight_id.clone(), plan.clone());
+            for plan in updates {
+                state.cache_flight_plan(plan);
             }
 
             Ok((StatusCode::OK, Json(new_plan)))
@@ -1767,6 +2494,36 @@ pub async fn update_operational_intent(
 }
 
 /// Estimate arrival time based on trajectory timing or waypoint distances.
+/// Time span a flight plan request may occupy: its requested departure (or now) until its
+/// estimated arrival, widened by the longest delay strategic scheduling could add.
+pub(crate) fn flight_window(
+    state: &AppState,
+    request: &FlightPlanRequest,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let departure = request.departure_time.unwrap_or_else(Utc::now);
+    let waypoints = match (&request.waypoints, &request.origin, &request.destination) {
+        (Some(waypoints), _, _) => waypoints.clone(),
+        (None, Some(origin), Some(destination)) => vec![origin.clone(), destination.clone()],
+        _ => Vec::new(),
+    };
+    let arrival = estimate_arrival_time(
+        &waypoints,
+        request.trajectory_log.as_ref(),
+        request.metadata.as_ref(),
+        departure,
+    )
+    .unwrap_or(departure);
+    let max_delay_secs = if state.config().strategic_scheduling_enabled {
+        state.config().strategic_max_delay_secs
+    } else {
+        0
+    };
+    (
+        departure,
+        arrival + chrono::Duration::seconds(max_delay_secs as i64),
+    )
+}
+
 fn estimate_arrival_time(
     waypoints: &[atc_core::models::Waypoint],
     trajectory_log: Option<&Vec<TrajectoryPoint>>,
@@ -1806,6 +2563,68 @@ use atc_core::haversine_distance;
 const DEFAULT_TRAJECTORY_SPEED_MPS: f64 = 10.0;
 const MIN_TRAJECTORY_SPEED_MPS: f64 = 1.0;
 
+/// Cruise altitude offsets the scheduler may try for a plan. Plans flying their own submitted
+/// trajectory log are never layered, since the log fixes their altitudes.
+fn altitude_layer_offsets<'a>(
+    state: &'a AppState,
+    payload_log: Option<&Vec<TrajectoryPoint>>,
+    allow_payload_log: bool,
+) -> &'a [f64] {
+    let config = state.config();
+    let flies_payload_log = allow_payload_log && payload_log.is_some_and(|log| log.len() >= 2);
+    if !config.strategic_scheduling_enabled || flies_payload_log {
+        return &[];
+    }
+    &config.strategic_altitude_offsets_m
+}
+
+/// Waypoints this far below a route's top altitude still count as its cruise layer.
+const CRUISE_LAYER_TOLERANCE_M: f64 = 1.0;
+
+/// The route as submitted, then once per offset with its cruise layer (the waypoints at its top
+/// altitude) moved by that offset, skipping offsets that leave the altitude limits or drop the
+/// cruise to the altitude of a climb or descent waypoint.
+fn altitude_layers(
+    waypoints: &[Waypoint],
+    offsets: &[f64],
+    rules: &SafetyRules,
+) -> Vec<(f64, Vec<Waypoint>)> {
+    let mut layers = vec![(0.0, waypoints.to_vec())];
+    let Some(cruise_m) = waypoints.iter().map(|wp| wp.altitude_m).reduce(f64::max) else {
+        return layers;
+    };
+    let in_cruise = |wp: &Waypoint| wp.altitude_m >= cruise_m - CRUISE_LAYER_TOLERANCE_M;
+    let below_cruise_m = waypoints
+        .iter()
+        .filter(|wp| !in_cruise(wp))
+        .map(|wp| wp.altitude_m)
+        .reduce(f64::max);
+    for &offset_m in offsets {
+        let fits = waypoints.iter().filter(|wp| in_cruise(wp)).all(|wp| {
+            let altitude_m = wp.altitude_m + offset_m;
+            altitude_m >= rules.min_altitude_m
+                && altitude_m <= rules.max_altitude_m
+                && below_cruise_m.is_none_or(|below_m| altitude_m > below_m)
+        });
+        if !fits {
+            continue;
+        }
+        let layered = waypoints
+            .iter()
+            .map(|wp| Waypoint {
+                altitude_m: if in_cruise(wp) {
+                    wp.altitude_m + offset_m
+                } else {
+                    wp.altitude_m
+                },
+                ..wp.clone()
+            })
+            .collect();
+        layers.push((offset_m, layered));
+    }
+    layers
+}
+
 fn resolve_candidate_trajectory(
     waypoints: &[Waypoint],
     payload_log: Option<&Vec<TrajectoryPoint>>,
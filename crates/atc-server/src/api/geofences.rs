@@ -1,46 +1,52 @@
 //! Geofence API endpoints.
 //!
-//! Provides CRUD operations for no-fly zones and restricted areas.
+//! Provides CRUD operations for no-fly zones and restricted areas, and GeoJSON import/export
+//! of whole sets of them.
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
     Json,
 };
 use chrono::Utc;
 use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::altitude::altitude_to_amsl;
+use crate::altitude::{altitude_to_amsl, AltitudeReference};
+use crate::api::auth::Caller;
+use crate::api::pagination::{self, Page};
 use crate::state::AppState;
-use atc_core::{CreateGeofenceRequest, Geofence, GeofenceType, UpdateGeofenceRequest};
+use atc_core::{
+    geofence_from_feature, geofences_to_feature_collection, CreateGeofenceRequest, Geofence,
+    GeofenceType, UpdateGeofenceRequest,
+};
 
-/// Create a new geofence.
+/// Create a new geofence; one created with an organization token is private to it.
+#[utoipa::path(
+    post,
+    path = "/v1/geofences",
+    tag = "Geofences",
+    request_body = CreateGeofenceRequest,
+    responses(
+        (status = 201, description = "Geofence created", body = Geofence),
+        (status = 400, description = "Invalid geofence"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn create_geofence(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(req): Json<CreateGeofenceRequest>,
 ) -> Result<(StatusCode, Json<Geofence>), (StatusCode, Json<serde_json::Value>)> {
-    let config = state.config();
-    let lower_altitude_m = altitude_to_amsl(
-        req.lower_altitude_m.unwrap_or(0.0),
-        config.altitude_reference,
-        config.geoid_offset_m,
+    let geofence = new_geofence(
+        &state,
+        &caller,
+        Uuid::new_v4().to_string(),
+        req,
+        state.config().altitude_reference,
     );
-    let upper_altitude_m = altitude_to_amsl(
-        req.upper_altitude_m.unwrap_or(120.0),
-        config.altitude_reference,
-        config.geoid_offset_m,
-    );
-    let geofence = Geofence {
-        id: Uuid::new_v4().to_string(),
-        name: req.name,
-        geofence_type: req.geofence_type,
-        polygon: req.polygon,
-        lower_altitude_m,
-        upper_altitude_m, // Default 120m ceiling
-        active: true,
-        created_at: Utc::now(),
-    };
 
     // Validate geofence before saving
     let errors = geofence.validate();
@@ -69,25 +75,156 @@ pub async fn create_geofence(
     Ok((StatusCode::CREATED, Json(geofence)))
 }
 
-/// List all geofences.
-pub async fn list_geofences(State(state): State<Arc<AppState>>) -> Json<Vec<Geofence>> {
-    Json(state.get_geofences())
+/// A geofence built from a create request, owned by the caller's organization.
+fn new_geofence(
+    state: &AppState,
+    caller: &Caller,
+    id: String,
+    req: CreateGeofenceRequest,
+    altitude_reference: AltitudeReference,
+) -> Geofence {
+    let geoid_offset_m = state.config().geoid_offset_m;
+    Geofence {
+        id,
+        name: req.name,
+        geofence_type: req.geofence_type,
+        polygon: req.polygon,
+        lower_altitude_m: altitude_to_amsl(
+            req.lower_altitude_m.unwrap_or(0.0),
+            altitude_reference,
+            geoid_offset_m,
+        ),
+        // Default 120m ceiling
+        upper_altitude_m: altitude_to_amsl(
+            req.upper_altitude_m.unwrap_or(120.0),
+            altitude_reference,
+            geoid_offset_m,
+        ),
+        active: true,
+        created_at: Utc::now(),
+        breach_response: req.breach_response,
+        schedule: req.schedule,
+        org_id: caller.org_id().map(str::to_string),
+    }
+}
+
+/// Query params for listing geofences.
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GeofenceListQuery {
+    /// Comma-separated geofence types, e.g. `no_fly_zone,temporary_restriction`
+    #[serde(rename = "type")]
+    #[param(rename = "type")]
+    pub geofence_type: Option<String>,
+    /// `active`, `inactive`, or `in_force` (active and within its schedule now)
+    #[param(value_type = Option<String>)]
+    pub status: Option<GeofenceStatus>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// `X-Next-Cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeofenceStatus {
+    Active,
+    Inactive,
+    InForce,
+}
+
+/// List the geofences visible to the caller, oldest first.
+#[utoipa::path(
+    get,
+    path = "/v1/geofences",
+    tag = "Geofences",
+    params(GeofenceListQuery),
+    responses(
+        (status = 200, description = "Geofences; X-Total-Count and X-Next-Cursor headers", body = Vec<Geofence>),
+        (status = 400, description = "Unknown filter value, limit too large or invalid cursor"),
+    ),
+)]
+pub async fn list_geofences(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Query(query): Query<GeofenceListQuery>,
+) -> Result<Page<Geofence>, (StatusCode, Json<serde_json::Value>)> {
+    let config = state.config();
+    let limit = pagination::resolve_limit(
+        query.limit,
+        config.list_default_limit,
+        config.list_max_limit,
+    )?;
+    let types: Option<Vec<GeofenceType>> =
+        pagination::parse_filter(query.geofence_type.as_deref(), "type")?;
+
+    let now = Utc::now();
+    let mut geofences = state.get_geofences();
+    geofences.retain(|geofence| {
+        caller.sees_geofence(geofence)
+            && types
+                .as_ref()
+                .is_none_or(|types| types.contains(&geofence.geofence_type))
+            && query.status.is_none_or(|status| match status {
+                GeofenceStatus::Active => geofence.active,
+                GeofenceStatus::Inactive => !geofence.active,
+                GeofenceStatus::InForce => geofence.in_force_at(now),
+            })
+    });
+
+    // Oldest first, ties by ID.
+    pagination::paginate(
+        geofences,
+        |geofence| {
+            pagination::sort_key([&pagination::oldest_first(geofence.created_at), &geofence.id])
+        },
+        limit,
+        query.offset.unwrap_or(0),
+        query.cursor.as_deref(),
+    )
 }
 
 /// Get a specific geofence by ID.
+#[utoipa::path(
+    get,
+    path = "/v1/geofences/{id}",
+    tag = "Geofences",
+    params(("id" = String, Path)),
+    responses(
+        (status = 200, description = "Geofence", body = Geofence),
+        (status = 404, description = "Geofence not found"),
+    ),
+)]
 pub async fn get_geofence(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(id): Path<String>,
 ) -> Result<Json<Geofence>, StatusCode> {
     state
         .get_geofence(&id)
+        .filter(|geofence| caller.sees_geofence(geofence))
         .map(Json)
         .ok_or(StatusCode::NOT_FOUND)
 }
 
 /// Update a geofence by ID.
+#[utoipa::path(
+    put,
+    path = "/v1/geofences/{id}",
+    tag = "Geofences",
+    params(("id" = String, Path)),
+    request_body = UpdateGeofenceRequest,
+    responses(
+        (status = 200, description = "Geofence updated", body = Geofence),
+        (status = 400, description = "Invalid geofence"),
+        (status = 404, description = "Geofence not found"),
+        (status = 403, description = "External and shared geofences are read-only to this caller"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn update_geofence(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(id): Path<String>,
     Json(req): Json<UpdateGeofenceRequest>,
 ) -> Result<Json<Geofence>, (StatusCode, Json<serde_json::Value>)> {
@@ -101,7 +238,10 @@ pub async fn update_geofence(
         ));
     }
 
-    let mut geofence = match state.get_geofence(&id) {
+    let mut geofence = match state
+        .get_geofence(&id)
+        .filter(|existing| caller.sees_geofence(existing))
+    {
         Some(existing) => existing,
         None => {
             return Err((
@@ -113,6 +253,15 @@ pub async fn update_geofence(
             ));
         }
     };
+    if !may_modify(&caller, &geofence) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "Shared geofences can only be changed with the admin token",
+                "id": id
+            })),
+        ));
+    }
 
     let config = state.config();
     if let Some(name) = req.name {
@@ -141,6 +290,12 @@ pub async fn update_geofence(
     if let Some(active) = req.active {
         geofence.active = active;
     }
+    if let Some(breach_response) = req.breach_response {
+        geofence.breach_response = Some(breach_response);
+    }
+    if let Some(schedule) = req.schedule {
+        geofence.schedule = Some(schedule);
+    }
 
     let errors = geofence.validate();
     if !errors.is_empty() {
@@ -169,13 +324,31 @@ pub async fn update_geofence(
 }
 
 /// Delete a geofence by ID.
+#[utoipa::path(
+    delete,
+    path = "/v1/geofences/{id}",
+    tag = "Geofences",
+    params(("id" = String, Path)),
+    responses(
+        (status = 204, description = "Geofence deleted"),
+        (status = 404, description = "Geofence not found"),
+        (status = 403, description = "External and shared geofences are read-only to this caller"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn delete_geofence(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(id): Path<String>,
 ) -> StatusCode {
     if state.is_external_geofence(&id) {
         return StatusCode::FORBIDDEN;
     }
+    match state.get_geofence(&id) {
+        Some(geofence) if !caller.sees_geofence(&geofence) => return StatusCode::NOT_FOUND,
+        Some(geofence) if !may_modify(&caller, &geofence) => return StatusCode::FORBIDDEN,
+        _ => {}
+    }
 
     match state.remove_geofence(&id).await {
         Ok(true) => {
@@ -190,22 +363,187 @@ pub async fn delete_geofence(
     }
 }
 
+/// Organizations may only change their own private geofences.
+fn may_modify(caller: &Caller, geofence: &Geofence) -> bool {
+    match caller {
+        Caller::Admin => true,
+        _ => geofence.org_id.is_some() && geofence.org_id.as_deref() == caller.org_id(),
+    }
+}
+
+/// Export the geofences visible to the caller as a GeoJSON FeatureCollection, oldest first.
+#[utoipa::path(
+    get,
+    path = "/v1/geofences/export",
+    tag = "Geofences",
+    responses((status = 200, description = "GeoJSON FeatureCollection with AMSL altitudes", content_type = "application/geo+json")),
+)]
+pub async fn export_geofences(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let mut geofences = state.get_geofences();
+    geofences.retain(|geofence| caller.sees_geofence(geofence));
+    geofences.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+    (
+        [(header::CONTENT_TYPE, "application/geo+json")],
+        Json(geofences_to_feature_collection(&geofences)),
+    )
+}
+
+#[derive(Debug, Default, serde::Serialize, ToSchema)]
+pub struct GeofenceImportSummary {
+    /// IDs of the geofences created.
+    pub created: Vec<String>,
+    /// IDs of existing geofences replaced by a feature with the same ID.
+    pub updated: Vec<String>,
+    /// Features that were rejected, with the reason.
+    pub skipped: Vec<String>,
+}
+
+/// Import geofences from a GeoJSON FeatureCollection.
+///
+/// A feature whose ID matches a geofence the caller may change replaces it; other features
+/// create geofences, private to the caller's organization when imported with its token.
+/// Invalid features are skipped and reported without failing the rest.
+#[utoipa::path(
+    post,
+    path = "/v1/geofences/import",
+    tag = "Geofences",
+    request_body(content = serde_json::Value, description = "GeoJSON FeatureCollection of Polygon features", content_type = "application/geo+json"),
+    responses(
+        (status = 200, description = "Created, updated and skipped features", body = GeofenceImportSummary),
+        (status = 400, description = "Not a GeoJSON FeatureCollection or Feature"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn import_geofences(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Json(document): Json<serde_json::Value>,
+) -> Result<Json<GeofenceImportSummary>, (StatusCode, Json<serde_json::Value>)> {
+    let features = atc_core::geojson::features(&document).map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Invalid GeoJSON",
+                "details": err
+            })),
+        )
+    })?;
+
+    let mut summary = GeofenceImportSummary::default();
+    for (index, feature) in features.into_iter().enumerate() {
+        let label = |id: Option<&str>| match id {
+            Some(id) => format!("feature {index} ({id})"),
+            None => format!("feature {index}"),
+        };
+        let imported = match geofence_from_feature(feature) {
+            Ok(imported) => imported,
+            Err(err) => {
+                summary.skipped.push(format!("{}: {}", label(None), err));
+                continue;
+            }
+        };
+        let label = label(imported.id.as_deref());
+        let altitude_reference = match imported.altitude_reference.as_deref() {
+            None => state.config().altitude_reference,
+            Some(value) => match AltitudeReference::parse(value) {
+                Some(reference) => reference,
+                None => {
+                    summary
+                        .skipped
+                        .push(format!("{label}: unknown altitude_reference {value}"));
+                    continue;
+                }
+            },
+        };
+
+        let existing = match imported.id.as_deref() {
+            Some(id) if state.is_external_geofence(id) => {
+                summary
+                    .skipped
+                    .push(format!("{label}: conflicts with an external geofence"));
+                continue;
+            }
+            Some(id) => state.get_geofence(id),
+            None => None,
+        };
+        if let Some(existing) = &existing {
+            if !caller.sees_geofence(existing) {
+                summary
+                    .skipped
+                    .push(format!("{label}: ID is already in use"));
+                continue;
+            }
+            if !may_modify(&caller, existing) {
+                summary.skipped.push(format!(
+                    "{label}: shared geofences can only be changed with the admin token"
+                ));
+                continue;
+            }
+        }
+
+        let id = imported.id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let mut geofence = new_geofence(&state, &caller, id, imported.request, altitude_reference);
+        geofence.active = imported.active;
+        if let Some(existing) = &existing {
+            geofence.created_at = existing.created_at;
+            geofence.org_id = existing.org_id.clone();
+        }
+        let errors = geofence.validate();
+        if !errors.is_empty() {
+            summary
+                .skipped
+                .push(format!("{label}: {}", errors.join("; ")));
+            continue;
+        }
+        if let Err(err) = state.add_geofence(geofence.clone()).await {
+            tracing::error!("Failed to import geofence {}: {}", geofence.id, err);
+            summary.skipped.push(format!("{label}: failed to persist"));
+            continue;
+        }
+        if existing.is_some() {
+            summary.updated.push(geofence.id);
+        } else {
+            summary.created.push(geofence.id);
+        }
+    }
+    tracing::info!(
+        "Imported geofences: {} created, {} updated, {} skipped",
+        summary.created.len(),
+        summary.updated.len(),
+        summary.skipped.len()
+    );
+
+    Ok(Json(summary))
+}
+
 /// Check if a point is inside any active geofence.
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PointCheckQuery {
     pub lat: f64,
     pub lon: f64,
     pub altitude_m: Option<f64>,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, ToSchema)]
 pub struct PointCheckResponse {
     pub inside_geofence: bool,
     pub geofence_ids: Vec<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/v1/geofences/check",
+    tag = "Geofences",
+    params(PointCheckQuery),
+    responses((status = 200, description = "Active geofences containing the point", body = PointCheckResponse)),
+)]
 pub async fn check_point(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     axum::extract::Query(query): axum::extract::Query<PointCheckQuery>,
 ) -> Json<PointCheckResponse> {
     let config = state.config();
@@ -215,7 +553,12 @@ pub async fn check_point(
         config.geoid_offset_m,
     );
 
-    let matching = state.check_point_in_geofences(query.lat, query.lon, altitude);
+    let mut matching = state.check_point_in_geofences(query.lat, query.lon, altitude);
+    matching.retain(|id| {
+        state
+            .get_geofence(id)
+            .is_none_or(|geofence| caller.sees_geofence(&geofence))
+    });
 
     Json(PointCheckResponse {
         inside_geofence: !matching.is_empty(),
@@ -224,26 +567,38 @@ pub async fn check_point(
 }
 
 /// Check if a route conflicts with any active geofences.
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, ToSchema)]
 pub struct RouteCheckRequest {
     pub waypoints: Vec<atc_core::Waypoint>,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, ToSchema)]
 pub struct RouteCheckResponse {
     pub conflicts: bool,
     pub conflicting_geofences: Vec<GeofenceConflict>,
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, ToSchema)]
 pub struct GeofenceConflict {
     pub geofence_id: String,
     pub geofence_name: String,
     pub segment_index: usize,
 }
 
+#[utoipa::path(
+    post,
+    path = "/v1/geofences/check-route",
+    tag = "Geofences",
+    request_body = RouteCheckRequest,
+    responses(
+        (status = 200, description = "Route segments crossing active geofences", body = RouteCheckResponse),
+        (status = 400, description = "Too few, too many or invalid waypoints"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn check_route(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Json(req): Json<RouteCheckRequest>,
 ) -> Result<Json<RouteCheckResponse>, (StatusCode, Json<serde_json::Value>)> {
     let config = state.config();
@@ -305,17 +660,19 @@ pub async fn check_route(
             ..wp
         })
         .collect();
-    let geofences = state.get_geofences();
+    let mut geofences = state.get_geofences();
+    geofences.retain(|geofence| caller.sees_geofence(geofence));
+    let now = Utc::now();
     let mut conflicts = Vec::new();
 
-    // Check each segment of the route against all active geofences
+    // Check each segment of the route against all geofences in force now
     for i in 0..waypoints.len().saturating_sub(1) {
         let wp1 = &waypoints[i];
         let wp2 = &waypoints[i + 1];
 
         for geofence in geofences
             .iter()
-            .filter(|g| g.active && g.geofence_type != GeofenceType::Advisory)
+            .filter(|g| g.in_force_at(now) && g.geofence_type != GeofenceType::Advisory)
         {
             if geofence.intersects_segment(
                 wp1.lat,
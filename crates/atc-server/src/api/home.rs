@@ -0,0 +1,71 @@
+//! Per-drone home points and tethers.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use atc_core::DroneHome;
+
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// Reject home points the tether monitor and plan validation cannot use.
+pub(crate) fn validate_home(home: &DroneHome) -> Result<(), ApiError> {
+    let errors = home.validate();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err((
+        StatusCode::BAD_REQUEST,
+        Json(json!({ "error": "Invalid home point", "details": errors })),
+    ))
+}
+
+fn drone_not_found(drone_id: String) -> ApiError {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({ "error": "Drone not found", "drone_id": drone_id })),
+    )
+}
+
+pub async fn get_drone_home(
+    State(state): State<Arc<AppState>>,
+    Path(drone_id): Path<String>,
+) -> Result<Json<DroneHome>, ApiError> {
+    if state.get_drone(&drone_id).is_none() {
+        return Err(drone_not_found(drone_id));
+    }
+    state.drone_home(&drone_id).map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "No home point registered",
+                "drone_id": drone_id,
+            })),
+        )
+    })
+}
+
+pub async fn set_drone_home(
+    State(state): State<Arc<AppState>>,
+    Path(drone_id): Path<String>,
+    Json(home): Json<DroneHome>,
+) -> Result<Json<DroneHome>, ApiError> {
+    if state.get_drone(&drone_id).is_none() {
+        return Err(drone_not_found(drone_id));
+    }
+    validate_home(&home)?;
+    if let Err(err) = state.set_drone_home(&drone_id, home).await {
+        tracing::error!("Failed to persist home point for {}: {}", drone_id, err);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to save home point" })),
+        ));
+    }
+    Ok(Json(home))
+}
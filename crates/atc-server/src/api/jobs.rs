@@ -0,0 +1,84 @@
+//! Background job status, dead letters and manual retries.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::jobs::{self, Job, JobCounts, JobFilter, JobKind, JobStatus, RetryError};
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+#[derive(Debug, Deserialize)]
+pub struct JobsQuery {
+    /// `dead` lists the dead letters.
+    pub status: Option<JobStatus>,
+    pub kind: Option<JobKind>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobList {
+    pub counts: JobCounts,
+    pub jobs: Vec<Job>,
+}
+
+/// Jobs newest first, with counts by status.
+pub async fn list_jobs(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<JobsQuery>,
+) -> Json<JobList> {
+    let config = state.config();
+    let max_limit = if config.flights_list_max_limit == 0 {
+        usize::MAX
+    } else {
+        config.flights_list_max_limit
+    };
+    let filter = JobFilter {
+        status: query.status,
+        kind: query.kind,
+        limit: query
+            .limit
+            .unwrap_or(config.flights_list_default_limit)
+            .min(max_limit),
+    };
+    Json(JobList {
+        counts: state.jobs().counts(),
+        jobs: state.jobs().list(&filter),
+    })
+}
+
+pub async fn get_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<String>,
+) -> Result<Json<Job>, ApiError> {
+    state.jobs().get(&job_id).map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Job not found" })),
+        )
+    })
+}
+
+/// Queue a dead job for another round of attempts.
+pub async fn retry_job(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<String>,
+) -> Result<(StatusCode, Json<Job>), ApiError> {
+    match jobs::retry(&state, &job_id).await {
+        Ok(job) => Ok((StatusCode::ACCEPTED, Json(job))),
+        Err(err @ RetryError::NotFound) => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": err.to_string() })),
+        )),
+        Err(err @ RetryError::NotDead(_)) => Err((
+            StatusCode::CONFLICT,
+            Json(json!({ "error": err.to_string() })),
+        )),
+    }
+}
@@ -0,0 +1,99 @@
+//! Admin controls for pausing and resuming background loops.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::loops::LOOP_NAMES;
+use crate::state::{AppState, LoopPause};
+
+/// Pause length when the request does not give one.
+const DEFAULT_PAUSE_SECS: u64 = 3600;
+/// Longest pause an operator can request; loops always resume on their own.
+const MAX_PAUSE_SECS: u64 = 24 * 3600;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PauseLoopRequest {
+    /// Seconds until the loop resumes automatically.
+    pub duration_secs: Option<u64>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoopControlStatus {
+    pub name: &'static str,
+    pub paused: bool,
+    #[serde(flatten)]
+    pub pause: Option<LoopPause>,
+}
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn loop_name(name: &str) -> Result<&'static str, ApiError> {
+    LOOP_NAMES
+        .iter()
+        .copied()
+        .find(|known| *known == name)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "Unknown loop", "loop": name, "loops": LOOP_NAMES })),
+            )
+        })
+}
+
+fn status(state: &AppState, name: &'static str) -> LoopControlStatus {
+    let pause = state.loop_pause(name);
+    LoopControlStatus {
+        name,
+        paused: pause.is_some(),
+        pause,
+    }
+}
+
+pub async fn list_loops(State(state): State<Arc<AppState>>) -> Json<Vec<LoopControlStatus>> {
+    Json(LOOP_NAMES.iter().map(|name| status(&state, name)).collect())
+}
+
+pub async fn pause_loop(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    request: Option<Json<PauseLoopRequest>>,
+) -> Result<Json<LoopControlStatus>, ApiError> {
+    let name = loop_name(&name)?;
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let duration_secs = request.duration_secs.unwrap_or(DEFAULT_PAUSE_SECS);
+    if duration_secs == 0 || duration_secs > MAX_PAUSE_SECS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("duration_secs must be between 1 and {}", MAX_PAUSE_SECS)
+            })),
+        ));
+    }
+    let reason = request
+        .reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+    state.pause_loop(
+        name,
+        Utc::now() + Duration::seconds(duration_secs as i64),
+        reason,
+    );
+    Ok(Json(status(&state, name)))
+}
+
+pub async fn resume_loop(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<LoopControlStatus>, ApiError> {
+    let name = loop_name(&name)?;
+    state.resume_loop(name);
+    Ok(Json(status(&state, name)))
+}
@@ -0,0 +1,43 @@
+//! Message catalogs for rendering localized text.
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogQuery {
+    /// Locale to return, e.g. `de` or `pt-BR`; defaults to the server's locale.
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MessageCatalogResponse {
+    pub locale: String,
+    pub default_locale: String,
+    /// Locales with a loaded catalog.
+    pub locales: Vec<String>,
+    /// Template per message code, with English filling codes the locale lacks.
+    pub messages: BTreeMap<String, String>,
+}
+
+pub async fn get_message_catalog(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CatalogQuery>,
+) -> Json<MessageCatalogResponse> {
+    let messages = &state.config().messages;
+    Json(MessageCatalogResponse {
+        locale: query
+            .locale
+            .clone()
+            .unwrap_or_else(|| messages.default_locale().to_string()),
+        default_locale: messages.default_locale().to_string(),
+        locales: messages.locales(),
+        messages: messages.templates(query.locale.as_deref()),
+    })
+}
@@ -0,0 +1,20 @@
+//! Prometheus metrics endpoint.
+
+use axum::{extract::State, http::header, response::IntoResponse};
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+/// Server metrics in the Prometheus text exposition format.
+pub async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut body = String::new();
+    state.telemetry_persist_metrics().render(&mut body);
+    state.planner_pool().render(&mut body);
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        body,
+    )
+}
@@ -1,18 +1,49 @@
 //! API routes for the ATC server.
 
+mod advisories;
 mod altitude_validation;
 pub mod auth;
+pub mod billing;
+pub mod bundle;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod commands;
+pub mod conformance_policy;
+pub mod coverage;
 pub mod daa;
+pub mod dispatch;
+pub mod emergency;
+pub mod events;
 pub mod flights;
 pub mod geofences;
+pub mod home;
+pub mod jobs;
+pub mod loop_control;
+pub mod messages;
+pub mod metrics;
+pub mod msa;
+pub mod openapi;
+pub mod organizations;
+pub mod owner_data;
+pub mod pagination;
+pub mod performance;
+pub mod planner_warmup;
+pub mod progress;
+pub mod rehearsal;
 pub mod request_id;
 mod routes;
+pub mod rules;
+pub mod scheduler;
+pub mod units;
+pub mod weather;
+pub mod webhooks;
 pub mod ws;
 
 use crate::config::Config;
 use axum::Router;
 
+pub(crate) use routes::validate_telemetry;
+
 pub fn routes(config: &Config) -> Router<std::sync::Arc<crate::state::AppState>> {
     routes::create_router(config)
 }
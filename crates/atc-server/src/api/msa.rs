@@ -0,0 +1,136 @@
+//! Minimum safe altitude grid publication and route checks.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+use atc_core::messages::{codes, Message};
+use atc_core::spatial::{haversine_distance, meters_per_deg_lat, meters_per_deg_lon};
+use atc_core::{MsaBounds, MsaGrid};
+
+use crate::compliance::{self, RoutePoint};
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// Points this close (horizontally) to the route's start or end are taking off or landing and
+/// are not held to the MSA.
+const TERMINAL_RADIUS_M: f64 = 30.0;
+
+#[derive(Debug, Deserialize)]
+pub struct MsaQuery {
+    /// `min_lat,min_lon,max_lat,max_lon`; the whole grid when omitted
+    pub bbox: Option<String>,
+}
+
+/// The MSA grid, cropped to `bbox`, for map shading.
+pub async fn get_msa(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<MsaQuery>,
+) -> Result<Json<MsaGrid>, ApiError> {
+    let Some(grid) = state.msa_grid() else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "Minimum safe altitude grid is not available" })),
+        ));
+    };
+    let Some(bbox) = query.bbox else {
+        return Ok(Json(grid.as_ref().clone()));
+    };
+    let Some(bounds) = MsaBounds::parse(&bbox) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Invalid bounding box",
+                "details": "bbox must be min_lat,min_lon,max_lat,max_lon"
+            })),
+        ));
+    };
+    grid.crop(&bounds).map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Bounding box is outside the MSA grid" })),
+        )
+    })
+}
+
+/// Flag route legs that dip below the published MSA grid.
+///
+/// This is a cheap lower bound run before the obstacle and compliance checks; parts of the route
+/// outside the grid, and its take-off and landing, are not checked.
+pub(crate) fn validate_route_msa(
+    state: &AppState,
+    points: &[RoutePoint],
+    violations: &mut Vec<serde_json::Value>,
+) {
+    let Some(grid) = state.msa_grid() else {
+        return;
+    };
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return;
+    };
+    let cell_m = (grid.lat_step_deg * meters_per_deg_lat(first.lat))
+        .min(grid.lon_step_deg * meters_per_deg_lon(first.lat))
+        .max(1.0);
+    let terminal = |lat: f64, lon: f64| {
+        haversine_distance(lat, lon, first.lat, first.lon) <= TERMINAL_RADIUS_M
+            || haversine_distance(lat, lon, last.lat, last.lon) <= TERMINAL_RADIUS_M
+    };
+
+    for (idx, leg) in points.windows(2).enumerate() {
+        let (start, end) = (&leg[0], &leg[1]);
+        if ![
+            start.lat,
+            start.lon,
+            start.altitude_m,
+            end.lat,
+            end.lon,
+            end.altitude_m,
+        ]
+        .iter()
+        .all(|value| value.is_finite())
+        {
+            continue;
+        }
+        let length_m = haversine_distance(start.lat, start.lon, end.lat, end.lon);
+        let samples = ((length_m / (cell_m / 2.0)).ceil() as usize).max(1);
+        let lowest = (0..=samples)
+            .filter_map(|step| {
+                let t = step as f64 / samples as f64;
+                let lat = start.lat + t * (end.lat - start.lat);
+                let lon = start.lon + t * (end.lon - start.lon);
+                if terminal(lat, lon) {
+                    return None;
+                }
+                let altitude_m = start.altitude_m + t * (end.altitude_m - start.altitude_m);
+                let msa_m = grid.sample(lat, lon)?;
+                Some((altitude_m - msa_m, lat, lon, altitude_m, msa_m))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        let Some((margin_m, lat, lon, altitude_m, msa_m)) = lowest else {
+            continue;
+        };
+        if margin_m >= 0.0 {
+            continue;
+        }
+        violations.push(compliance::violation(
+            state.config(),
+            Message::new(codes::ROUTE_BELOW_MSA)
+                .with("altitude_m", altitude_m)
+                .with("msa_m", msa_m),
+            json!({
+                "type": "msa",
+                "segment_index": idx,
+                "lat": lat,
+                "lon": lon,
+                "altitude_m": altitude_m,
+                "msa_m": msa_m
+            }),
+        ));
+    }
+}
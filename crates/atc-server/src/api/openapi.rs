@@ -0,0 +1,85 @@
+//! OpenAPI document generated from the handler annotations.
+//!
+//! Served at `/openapi.json` with Swagger UI at `/docs`, so SDK clients can be generated from
+//! the handlers themselves. Covers flights, geofences, commands, DAA and the WebSocket streams;
+//! `openapi.yaml` at the repository root still documents the rest of the API.
+
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::api::{commands, daa, events, flights, geofences, ws};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "ATC-Drone API",
+        description = "Lengths are in metres (`_m` fields) and speeds in metres per second (`_mps` fields)."
+    ),
+    paths(
+        flights::create_flight_plan,
+        flights::validate_flight_plan_request,
+        flights::create_flight_plan_compat,
+        flights::get_flight_plans,
+        flights::get_flight_plan_history,
+        flights::get_rejection_detail,
+        flights::reserve_operational_intent,
+        flights::confirm_operational_intent,
+        flights::cancel_operational_intent,
+        flights::update_operational_intent,
+        geofences::create_geofence,
+        geofences::list_geofences,
+        geofences::get_geofence,
+        geofences::update_geofence,
+        geofences::delete_geofence,
+        geofences::export_geofences,
+        geofences::import_geofences,
+        geofences::check_point,
+        geofences::check_route,
+        commands::issue_command,
+        commands::get_all_commands,
+        commands::get_command_audit,
+        commands::broadcast_command,
+        commands::get_broadcast_status,
+        commands::get_next_command,
+        commands::get_signing_key,
+        commands::ack_command,
+        commands::command_stream_ws,
+        commands::command_budget,
+        daa::list_daa,
+        ws::ws_handler,
+        events::events_handler,
+        ws::dispatch_ws_handler,
+    ),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "Flights"),
+        (name = "Geofences"),
+        (name = "Commands"),
+        (name = "Drones"),
+        (name = "Dispatch"),
+    )
+)]
+pub struct ApiDoc;
+
+/// `bearerAuth` is the admin token; `droneToken` a drone's session token.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearerAuth",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+        components.add_security_scheme(
+            "droneToken",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+    }
+}
+
+/// `/openapi.json` and the Swagger UI at `/docs`.
+pub fn swagger_ui() -> SwaggerUi {
+    SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi())
+}
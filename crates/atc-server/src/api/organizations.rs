@@ -0,0 +1,145 @@
+//! Organization management: tenants, their operators and their API tokens.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::organizations::Organization;
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+#[derive(Debug, Deserialize)]
+pub struct CreateOrganizationRequest {
+    /// Display name.
+    pub name: String,
+}
+
+/// A new organization, with the API token its operators authenticate with.
+#[derive(Debug, Serialize)]
+pub struct CreatedOrganization {
+    #[serde(flatten)]
+    pub organization: Organization,
+    /// Shown only once; the server keeps a hash.
+    pub token: String,
+}
+
+pub async fn create_organization(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CreateOrganizationRequest>,
+) -> Result<(StatusCode, Json<CreatedOrganization>), ApiError> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "name must not be empty", "field": "name" })),
+        ));
+    }
+    let (organization, token) = state.create_organization(name).await.map_err(|err| {
+        tracing::error!("Failed to persist organization: {}", err);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to save organization" })),
+        )
+    })?;
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedOrganization {
+            organization,
+            token,
+        }),
+    ))
+}
+
+/// Organizations with their members, without tokens.
+pub async fn list_organizations(State(state): State<Arc<AppState>>) -> Json<Vec<Organization>> {
+    Json(state.list_organizations())
+}
+
+pub async fn delete_organization(
+    State(state): State<Arc<AppState>>,
+    Path(org_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match state.delete_organization(&org_id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(organization_not_found(&org_id)),
+        Err(err) => {
+            tracing::error!("Failed to delete organization {}: {}", org_id, err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to delete organization" })),
+            ))
+        }
+    }
+}
+
+/// Put an operator in an organization, moving it out of any other.
+pub async fn add_member(
+    State(state): State<Arc<AppState>>,
+    Path((org_id, owner_id)): Path<(String, String)>,
+) -> Result<Json<Organization>, ApiError> {
+    let owner_id = owner_id.trim();
+    if owner_id.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "owner_id must not be empty", "field": "owner_id" })),
+        ));
+    }
+    match state.add_organization_member(&org_id, owner_id).await {
+        Ok(Some(organization)) => Ok(Json(organization)),
+        Ok(None) => Err(organization_not_found(&org_id)),
+        Err(err) => {
+            tracing::error!(
+                "Failed to add {} to organization {}: {}",
+                owner_id,
+                org_id,
+                err
+            );
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to save membership" })),
+            ))
+        }
+    }
+}
+
+pub async fn remove_member(
+    State(state): State<Arc<AppState>>,
+    Path((org_id, owner_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    match state.remove_organization_member(&org_id, &owner_id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "Operator is not a member of this organization",
+                "org_id": org_id,
+                "owner_id": owner_id,
+            })),
+        )),
+        Err(err) => {
+            tracing::error!(
+                "Failed to remove {} from organization {}: {}",
+                owner_id,
+                org_id,
+                err
+            );
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to remove membership" })),
+            ))
+        }
+    }
+}
+
+fn organization_not_found(org_id: &str) -> ApiError {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({ "error": "Organization not found", "org_id": org_id })),
+    )
+}
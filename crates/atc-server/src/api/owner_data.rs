@@ -0,0 +1,295 @@
+//! Per-owner data export and purge, for data-retention and records requests.
+//!
+//! The export is a ZIP archive with one JSON file per record kind (drones and their last
+//! telemetry, flight plans with their flown trajectories, commands, conflicts, breach events and
+//! scheduler rejections) and a `manifest.json` listing what was included. The purge removes the
+//! same records; it defaults to a dry run that only reports counts.
+
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+use zip::write::SimpleFileOptions;
+
+use atc_core::models::DroneStatus;
+
+use crate::persistence::{
+    commands as commands_db, conflicts as conflicts_db, flight_plans as flight_plans_db,
+    ReadTimeout,
+};
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+pub const OWNER_EXPORT_FORMAT: &str = "atc-owner-export";
+pub const OWNER_EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+struct OwnerExportManifest<'a> {
+    format: &'static str,
+    version: u32,
+    owner_id: &'a str,
+    exported_at: chrono::DateTime<Utc>,
+    drone_ids: &'a [String],
+    /// Records per archive file
+    files: BTreeMap<&'static str, usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OwnerPurgeRequest {
+    /// Only report what would be removed (default true).
+    #[serde(default)]
+    pub dry_run: Option<bool>,
+    /// Must equal the owner ID to run a real purge.
+    #[serde(default)]
+    pub confirm: Option<String>,
+}
+
+fn database_required() -> ApiError {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({ "error": "Owner data export and purge require a database" })),
+    )
+}
+
+fn owner_lookup_failed(err: anyhow::Error) -> ApiError {
+    tracing::warn!("Owner drone lookup failed: {}", err);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "error": "Owner drone lookup failed" })),
+    )
+}
+
+/// Bundle every record held for an owner into a ZIP archive.
+pub async fn export_owner_data(
+    State(state): State<Arc<AppState>>,
+    Path(owner_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Some(db) = state.database() else {
+        return Err(database_required());
+    };
+    let drone_ids = state
+        .owner_drone_ids(&owner_id)
+        .await
+        .map_err(owner_lookup_failed)?;
+    let pool = db.read_pool();
+    let records = db
+        .read_with_timeout(async {
+            Ok((
+                flight_plans_db::load_owner_flight_plans(pool, &owner_id, &drone_ids).await?,
+                commands_db::load_drone_commands(pool, &drone_ids).await?,
+                conflicts_db::load_drone_conflicts(pool, &drone_ids).await?,
+            ))
+        })
+        .await;
+    let (flight_plans, commands, conflicts) = match records {
+        Ok(records) => records,
+        Err(err) if err.is::<ReadTimeout>() => {
+            return Err((
+                StatusCode::GATEWAY_TIMEOUT,
+                Json(json!({ "error": err.to_string() })),
+            ));
+        }
+        Err(err) => {
+            tracing::warn!("Owner export query failed: {}", err);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Owner export query failed" })),
+            ));
+        }
+    };
+    if drone_ids.is_empty() && flight_plans.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "No records for owner", "owner_id": owner_id })),
+        ));
+    }
+
+    let mut drones: Vec<_> = drone_ids
+        .iter()
+        .filter_map(|drone_id| state.get_drone(drone_id))
+        .collect();
+    drones.sort_by(|a, b| a.drone_id.cmp(&b.drone_id));
+    let performance: BTreeMap<_, _> = drone_ids
+        .iter()
+        .filter_map(|drone_id| Some((drone_id, state.drone_performance(drone_id)?)))
+        .collect();
+    let homes: BTreeMap<_, _> = drone_ids
+        .iter()
+        .filter_map(|drone_id| Some((drone_id, state.drone_home(drone_id)?)))
+        .collect();
+    let breaches: Vec<_> = state
+        .breach_events()
+        .into_iter()
+        .filter(|event| drone_ids.contains(&event.drone_id))
+        .collect();
+    let rejections = state.owner_plan_rejections(&owner_id, &drone_ids);
+
+    let files = [
+        (
+            "drones.json",
+            drones.len(),
+            serde_json::to_vec_pretty(&drones),
+        ),
+        (
+            "flight_plans.json",
+            flight_plans.len(),
+            serde_json::to_vec_pretty(&flight_plans),
+        ),
+        (
+            "commands.json",
+            commands.len(),
+            serde_json::to_vec_pretty(&commands),
+        ),
+        (
+            "conflicts.json",
+            conflicts.len(),
+            serde_json::to_vec_pretty(&conflicts),
+        ),
+        (
+            "breaches.json",
+            breaches.len(),
+            serde_json::to_vec_pretty(&breaches),
+        ),
+        (
+            "plan_rejections.json",
+            rejections.len(),
+            serde_json::to_vec_pretty(&rejections),
+        ),
+        (
+            "drone_performance.json",
+            performance.len(),
+            serde_json::to_vec_pretty(&performance),
+        ),
+        (
+            "drone_homes.json",
+            homes.len(),
+            serde_json::to_vec_pretty(&homes),
+        ),
+    ];
+    let manifest = OwnerExportManifest {
+        format: OWNER_EXPORT_FORMAT,
+        version: OWNER_EXPORT_VERSION,
+        owner_id: &owner_id,
+        exported_at: Utc::now(),
+        drone_ids: &drone_ids,
+        files: files
+            .iter()
+            .map(|(name, count, _)| (*name, *count))
+            .collect(),
+    };
+
+    let archive = (|| -> anyhow::Result<Vec<u8>> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        writer.start_file("manifest.json", options)?;
+        writer.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+        for (name, _, contents) in files {
+            writer.start_file(name, options)?;
+            writer.write_all(&contents?)?;
+        }
+        Ok(writer.finish()?.into_inner())
+    })()
+    .map_err(|err| {
+        tracing::error!("Failed to build owner export: {}", err);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to build owner export" })),
+        )
+    })?;
+
+    let file_stem: String = owner_id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    tracing::info!(
+        "Exported owner {}: {} drones, {} flight plans",
+        owner_id,
+        drone_ids.len(),
+        manifest.files["flight_plans.json"]
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"owner-{}.zip\"", file_stem),
+            ),
+        ],
+        archive,
+    ))
+}
+
+/// Remove every record held for an owner, or report what would be removed.
+pub async fn purge_owner_data(
+    State(state): State<Arc<AppState>>,
+    Path(owner_id): Path<String>,
+    Json(req): Json<OwnerPurgeRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if state.database().is_none() {
+        return Err(database_required());
+    }
+    let dry_run = req.dry_run.unwrap_or(true);
+    if !dry_run && req.confirm.as_deref() != Some(owner_id.as_str()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "Purge requires confirm set to the owner ID" })),
+        ));
+    }
+
+    let drone_ids = state
+        .owner_drone_ids(&owner_id)
+        .await
+        .map_err(owner_lookup_failed)?;
+    let airborne: Vec<&String> = drone_ids
+        .iter()
+        .filter(|drone_id| {
+            state.get_drone(drone_id).is_some_and(|drone| {
+                matches!(drone.status, DroneStatus::Active | DroneStatus::Holding)
+            })
+        })
+        .collect();
+    if !airborne.is_empty() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "Owner has drones in flight",
+                "drone_ids": airborne,
+                "hint": "Land the drones before purging their records"
+            })),
+        ));
+    }
+
+    let breach_events = state
+        .breach_events()
+        .iter()
+        .filter(|event| drone_ids.contains(&event.drone_id))
+        .count();
+    let counts = state
+        .purge_owner(&owner_id, &drone_ids, dry_run)
+        .await
+        .map_err(|err| {
+            tracing::error!("Owner purge failed for {}: {}", owner_id, err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Owner purge failed" })),
+            )
+        })?;
+
+    Ok(Json(json!({
+        "owner_id": owner_id,
+        "dry_run": dry_run,
+        "drone_ids": drone_ids,
+        "records": counts,
+        "breach_events": breach_events
+    })))
+}
@@ -0,0 +1,212 @@
+//! Limit/offset/cursor paging shared by the list endpoints.
+//!
+//! Pages are plain JSON arrays so existing clients keep working; the total number of matching
+//! items is sent in `X-Total-Count`, and `X-Next-Cursor` carries an opaque cursor for the next
+//! page when more items follow. A cursor resumes right after the last item of its page even if
+//! items were added or removed in between, which an offset can't.
+
+use axum::{
+    http::{HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::json;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+pub const TOTAL_COUNT_HEADER: &str = "x-total-count";
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+/// Separates the parts of a sort key; sorts below any printable character so keys compare part by
+/// part.
+const KEY_SEPARATOR: char = '\u{1f}';
+
+/// One page of a list endpoint.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Matching items across all pages
+    pub total: usize,
+    pub next_cursor: Option<String>,
+}
+
+impl<T: Serialize> IntoResponse for Page<T> {
+    fn into_response(self) -> Response {
+        let mut response = Json(self.items).into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            HeaderName::from_static(TOTAL_COUNT_HEADER),
+            HeaderValue::from(self.total),
+        );
+        if let Some(cursor) = self
+            .next_cursor
+            .and_then(|c| HeaderValue::from_str(&c).ok())
+        {
+            headers.insert(HeaderName::from_static(NEXT_CURSOR_HEADER), cursor);
+        }
+        response
+    }
+}
+
+/// The page size for a request: `default_limit` when none is given, and a 400 when one above
+/// `max_limit` (0 for no cap) is asked for.
+pub fn resolve_limit(
+    limit: Option<usize>,
+    default_limit: usize,
+    max_limit: usize,
+) -> Result<usize, ApiError> {
+    let max_limit = if max_limit == 0 {
+        usize::MAX
+    } else {
+        max_limit
+    };
+    match limit {
+        Some(limit) if limit > max_limit => Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "limit too large",
+                "max_limit": max_limit
+            })),
+        )),
+        Some(limit) => Ok(limit),
+        None => Ok(default_limit.min(max_limit)),
+    }
+}
+
+/// Sort `items` by `sort_key` and cut out the page after `cursor`, skipping `offset` more.
+///
+/// Sort keys must be unique (end them with the item's ID) and order items the way the endpoint
+/// lists them; build them with [`sort_key`].
+pub fn paginate<T>(
+    items: Vec<T>,
+    sort_key: impl Fn(&T) -> String,
+    limit: usize,
+    offset: usize,
+    cursor: Option<&str>,
+) -> Result<Page<T>, ApiError> {
+    let after = cursor.map(decode_cursor).transpose()?;
+    let total = items.len();
+    let mut keyed: Vec<(String, T)> = items
+        .into_iter()
+        .map(|item| (sort_key(&item), item))
+        .collect();
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut remaining = keyed
+        .into_iter()
+        .filter(|(key, _)| after.as_ref().is_none_or(|after| key > after))
+        .skip(offset)
+        .peekable();
+    let mut items = Vec::new();
+    let mut last_key = None;
+    while items.len() < limit {
+        let Some((key, item)) = remaining.next() else {
+            break;
+        };
+        items.push(item);
+        last_key = Some(key);
+    }
+    let next_cursor = match (remaining.peek(), last_key) {
+        (Some(_), Some(key)) => Some(BASE64.encode(key)),
+        _ => None,
+    };
+    Ok(Page {
+        items,
+        total,
+        next_cursor,
+    })
+}
+
+/// Join sort key parts.
+pub fn sort_key<const N: usize>(parts: [&str; N]) -> String {
+    parts.join(&KEY_SEPARATOR.to_string())
+}
+
+/// A sort key part ordering times oldest first.
+pub fn oldest_first(at: DateTime<Utc>) -> String {
+    format!("{:020}", at.timestamp_micros().max(0))
+}
+
+/// A sort key part ordering times newest first.
+pub fn newest_first(at: DateTime<Utc>) -> String {
+    format!("{:020}", i64::MAX - at.timestamp_micros().max(0))
+}
+
+/// Parse a comma-separated filter such as `severity=warning,critical` into its values.
+pub fn parse_filter<T: DeserializeOwned>(
+    raw: Option<&str>,
+    field: &str,
+) -> Result<Option<Vec<T>>, ApiError> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+    raw.split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            serde_json::from_value(serde_json::Value::String(value.to_string())).map_err(|_| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({
+                        "error": format!("Unknown {} '{}'", field, value),
+                        "field": field
+                    })),
+                )
+            })
+        })
+        .collect::<Result<Vec<T>, _>>()
+        .map(Some)
+}
+
+fn decode_cursor(cursor: &str) -> Result<String, ApiError> {
+    BASE64
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "Invalid cursor", "field": "cursor" })),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(page: &Page<(u32, &'static str)>) -> Vec<&'static str> {
+        page.items.iter().map(|(_, id)| *id).collect()
+    }
+
+    #[test]
+    fn cursor_resumes_after_the_last_item_when_items_are_removed() {
+        let key = |(rank, id): &(u32, &str)| sort_key([&rank.to_string(), id]);
+        let items = vec![(2, "c"), (1, "b"), (1, "a"), (3, "d")];
+
+        let first = paginate(items.clone(), key, 2, 0, None).unwrap();
+        assert_eq!(ids(&first), ["a", "b"]);
+        assert_eq!(first.total, 4);
+        let cursor = first.next_cursor.expect("more items follow");
+
+        let without_b = items.into_iter().filter(|(_, id)| *id != "b").collect();
+        let second = paginate(without_b, key, 2, 0, Some(&cursor)).unwrap();
+        assert_eq!(ids(&second), ["c", "d"]);
+        assert!(second.next_cursor.is_none());
+
+        assert!(paginate(Vec::new(), key, 2, 0, Some("not base64!")).is_err());
+    }
+
+    #[test]
+    fn limit_defaults_and_caps() {
+        assert_eq!(resolve_limit(None, 200, 1000).unwrap(), 200);
+        assert_eq!(resolve_limit(None, 200, 50).unwrap(), 50);
+        assert_eq!(resolve_limit(Some(5000), 200, 0).unwrap(), 5000);
+        assert!(resolve_limit(Some(1001), 200, 1000).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+//! Per-drone performance envelopes.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+use atc_core::DronePerformance;
+
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// Reject envelopes the conflict loop and planner cannot use.
+pub(crate) fn validate_performance(performance: &DronePerformance) -> Result<(), ApiError> {
+    let errors = performance.validate();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err((
+        StatusCode::BAD_REQUEST,
+        Json(json!({ "error": "Invalid performance envelope", "details": errors })),
+    ))
+}
+
+fn drone_not_found(drone_id: String) -> ApiError {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({ "error": "Drone not found", "drone_id": drone_id })),
+    )
+}
+
+pub async fn get_drone_performance(
+    State(state): State<Arc<AppState>>,
+    Path(drone_id): Path<String>,
+) -> Result<Json<DronePerformance>, ApiError> {
+    if state.get_drone(&drone_id).is_none() {
+        return Err(drone_not_found(drone_id));
+    }
+    state.drone_performance(&drone_id).map(Json).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "No performance envelope registered",
+                "drone_id": drone_id,
+            })),
+        )
+    })
+}
+
+pub async fn set_drone_performance(
+    State(state): State<Arc<AppState>>,
+    Path(drone_id): Path<String>,
+    Json(performance): Json<DronePerformance>,
+) -> Result<Json<DronePerformance>, ApiError> {
+    if state.get_drone(&drone_id).is_none() {
+        return Err(drone_not_found(drone_id));
+    }
+    validate_performance(&performance)?;
+    if let Err(err) = state.set_drone_performance(&drone_id, performance).await {
+        tracing::error!("Failed to persist performance for {}: {}", drone_id, err);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to save performance envelope" })),
+        ));
+    }
+    Ok(Json(performance))
+}
@@ -0,0 +1,36 @@
+//! Admin trigger and status for the planner cache warmup.
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::state::AppState;
+use crate::warmup::{self, WarmupStatus, WarmupTrigger};
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+pub async fn get_warmup(State(state): State<Arc<AppState>>) -> Json<WarmupStatus> {
+    Json(state.planner_warmup().status(state.config(), state.jobs()))
+}
+
+/// Queue a job prefetching obstacles and terrain for the operating areas.
+pub async fn start_warmup(
+    State(state): State<Arc<AppState>>,
+) -> Result<(StatusCode, Json<WarmupStatus>), ApiError> {
+    if state.config().operating_areas.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "No operating areas configured (ATC_OPERATING_AREAS_PATH)" })),
+        ));
+    }
+    if !warmup::enqueue(&state, WarmupTrigger::Admin).await {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({ "error": "Planner warmup already running" })),
+        ));
+    }
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(state.planner_warmup().status(state.config(), state.jobs())),
+    ))
+}
@@ -0,0 +1,116 @@
+//! Live progress of an active flight against its plan.
+//!
+//! The drone's latest telemetry is matched to the plan's timed trajectory to report how much of
+//! the route is flown, which leg it is on, how far it has drifted from the schedule and how far
+//! off the planned leg it is.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+
+use atc_core::models::FlightStatus;
+use atc_core::{flight_progress, DronePosition};
+
+use crate::api::auth::Caller;
+use crate::api::flights::caller_sees_plan;
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+#[derive(Debug, Serialize)]
+pub struct FlightProgressReport {
+    pub flight_id: String,
+    pub drone_id: String,
+    /// Time of the telemetry the progress is computed from.
+    pub telemetry_time: DateTime<Utc>,
+    /// Share of the planned route flown, 0–100.
+    pub percent_complete: f64,
+    /// Zero-based index of the trajectory leg the drone is on.
+    pub current_segment: usize,
+    pub segment_count: usize,
+    pub distance_flown_m: f64,
+    pub distance_remaining_m: f64,
+    pub cross_track_error_m: f64,
+    /// Above (positive) or below the planned altitude.
+    pub vertical_error_m: f64,
+    /// Seconds behind schedule; negative when ahead.
+    pub eta_drift_s: f64,
+    pub planned_arrival: Option<DateTime<Utc>>,
+    pub estimated_arrival: Option<DateTime<Utc>>,
+}
+
+fn to_datetime(time_s: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis((time_s * 1000.0).round() as i64)
+}
+
+pub async fn get_flight_progress(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(flight_id): Path<String>,
+) -> Result<Json<FlightProgressReport>, ApiError> {
+    let plan = state
+        .flight_plans
+        .get(&flight_id)
+        .map(|entry| entry.value().clone())
+        .filter(|plan| caller_sees_plan(&state, &caller, plan))
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "Flight plan not found", "flight_id": flight_id })),
+            )
+        })?;
+    if plan.status != FlightStatus::Active {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "Only active flights have progress",
+                "flight_id": flight_id,
+                "status": plan.status
+            })),
+        ));
+    }
+    let drone = state.get_drone(&plan.drone_id).ok_or_else(|| {
+        (
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "No telemetry from the flight's drone",
+                "flight_id": flight_id,
+                "drone_id": plan.drone_id
+            })),
+        )
+    })?;
+
+    let mut position = DronePosition::new(&drone.drone_id, drone.lat, drone.lon, drone.altitude_m);
+    position.timestamp = drone.last_update.timestamp_millis() as f64 / 1000.0;
+    let progress = flight_progress(&plan, &position).ok_or_else(|| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "error": "Flight plan has no timed trajectory to follow",
+                "flight_id": flight_id
+            })),
+        )
+    })?;
+
+    Ok(Json(FlightProgressReport {
+        flight_id: plan.flight_id,
+        drone_id: plan.drone_id,
+        telemetry_time: drone.last_update,
+        percent_complete: progress.percent_complete,
+        current_segment: progress.current_segment,
+        segment_count: progress.segment_count,
+        distance_flown_m: progress.distance_flown_m,
+        distance_remaining_m: progress.distance_remaining_m,
+        cross_track_error_m: progress.cross_track_error_m,
+        vertical_error_m: progress.vertical_error_m,
+        eta_drift_s: progress.eta_drift_s,
+        planned_arrival: to_datetime(progress.planned_arrival_s),
+        estimated_arrival: to_datetime(progress.estimated_arrival_s),
+    }))
+}
@@ -0,0 +1,192 @@
+//! Mission rehearsal.
+//!
+//! Flies a flight plan on a virtual clock before it is flown for real: the plan and every other
+//! booked or active plan are turned into virtual telemetry, replayed through a sandboxed
+//! detector configured like the live one, and the plan's track is checked against geofences
+//! and the drone's tether. Live conflict state is never touched.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use atc_core::models::FlightStatus;
+use atc_core::rehearsal::{conformance_issues, plan_time_window, virtual_telemetry};
+use atc_core::{ConflictReplay, ConflictSeverity, RehearsalIssue};
+
+use crate::api::auth::Caller;
+use crate::api::flights::caller_sees_plan;
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// Default simulation speed (plan seconds per virtual telemetry report).
+const DEFAULT_SPEED: f64 = 5.0;
+const MAX_SPEED: f64 = 60.0;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RehearseRequest {
+    /// Simulation speed: telemetry is reported every `speed` plan seconds, as a drone
+    /// reporting once a second would at `speed`x real time.
+    #[serde(default)]
+    pub speed: Option<f64>,
+}
+
+/// A conflict the rehearsed flight would run into.
+#[derive(Debug, Serialize)]
+pub struct RehearsalConflict {
+    pub other_drone_id: String,
+    pub other_flight_id: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub peak_severity: ConflictSeverity,
+    pub first_critical_time: Option<DateTime<Utc>>,
+    pub min_distance_m: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RehearsalReport {
+    pub flight_id: String,
+    pub drone_id: String,
+    pub speed: f64,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    /// Virtual telemetry reports generated for the plan.
+    pub samples: usize,
+    pub conflicts: Vec<RehearsalConflict>,
+    pub conformance_issues: Vec<RehearsalIssue>,
+    /// No conflicts and no conformance issues.
+    pub clear: bool,
+}
+
+fn to_datetime(time_s: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis((time_s * 1000.0).round() as i64)
+}
+
+pub async fn rehearse_flight_plan(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(flight_id): Path<String>,
+    request: Option<Json<RehearseRequest>>,
+) -> Result<Json<RehearsalReport>, ApiError> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let speed = request.speed.unwrap_or(DEFAULT_SPEED);
+    if !speed.is_finite() || !(1.0..=MAX_SPEED).contains(&speed) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": format!("speed must be between 1 and {}", MAX_SPEED) })),
+        ));
+    }
+
+    let plan = state
+        .flight_plans
+        .get(&flight_id)
+        .map(|entry| entry.value().clone())
+        .filter(|plan| caller_sees_plan(&state, &caller, plan))
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "Flight plan not found", "flight_id": flight_id })),
+            )
+        })?;
+    if !matches!(
+        plan.status,
+        FlightStatus::Reserved
+            | FlightStatus::Pending
+            | FlightStatus::Approved
+            | FlightStatus::Active
+    ) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "Only pending, reserved, approved or active plans can be rehearsed",
+                "flight_id": flight_id,
+                "status": plan.status
+            })),
+        ));
+    }
+    let (start_s, end_s) = plan_time_window(&plan).ok_or_else(|| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "error": "Flight plan has no timed trajectory to rehearse",
+                "flight_id": flight_id
+            })),
+        )
+    })?;
+
+    let track = virtual_telemetry(&plan, start_s, end_s, speed);
+    let mut traffic_flights: HashMap<String, String> = HashMap::new();
+    let mut updates = track.clone();
+    for other in state.get_flight_plans() {
+        if other.flight_id == plan.flight_id
+            || other.drone_id == plan.drone_id
+            || !matches!(
+                other.status,
+                FlightStatus::Reserved | FlightStatus::Approved | FlightStatus::Active
+            )
+        {
+            continue;
+        }
+        let other_track = virtual_telemetry(&other, start_s, end_s, speed);
+        if other_track.is_empty() {
+            continue;
+        }
+        traffic_flights.insert(other.drone_id.clone(), other.flight_id.clone());
+        updates.extend(other_track);
+    }
+
+    let timeline = ConflictReplay::new(state.sandbox_detector())
+        .with_step(speed)
+        .with_max_track_age(speed)
+        .run(updates);
+    let conflicts: Vec<RehearsalConflict> = timeline
+        .episodes
+        .into_iter()
+        .filter_map(|episode| {
+            let other_drone_id = if episode.drone1_id == plan.drone_id {
+                episode.drone2_id
+            } else if episode.drone2_id == plan.drone_id {
+                episode.drone1_id
+            } else {
+                return None;
+            };
+            Some(RehearsalConflict {
+                other_flight_id: traffic_flights.get(&other_drone_id).cloned(),
+                other_drone_id,
+                start_time: to_datetime(episode.start_time),
+                end_time: episode.end_time.and_then(to_datetime),
+                peak_severity: episode.peak_severity,
+                first_critical_time: episode.first_critical_time.and_then(to_datetime),
+                min_distance_m: episode.min_distance_m,
+            })
+        })
+        .collect();
+
+    let mut issues = conformance_issues(
+        &track,
+        &state.get_geofences(),
+        state.drone_home(&plan.drone_id).as_ref(),
+    );
+    for issue in &mut issues {
+        issue.message = state.config().messages.format(&issue.message_code, None);
+    }
+
+    Ok(Json(RehearsalReport {
+        clear: conflicts.is_empty() && issues.is_empty(),
+        flight_id: plan.flight_id,
+        drone_id: plan.drone_id,
+        speed,
+        start_time: to_datetime(start_s),
+        end_time: to_datetime(end_s),
+        samples: track.len(),
+        conflicts,
+        conformance_issues: issues,
+    }))
+}
@@ -1,10 +1,11 @@
 //! REST API routes.
 
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap, StatusCode, Uri},
     middleware,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::{delete, get, post, put},
     Json, Router,
 };
@@ -15,17 +16,41 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::altitude::altitude_to_amsl;
-use crate::api::auth::{self, AdminToken, RateLimiter};
-use crate::api::{commands, daa, flights, geofences, request_id, ws};
+use crate::api::auth::{self, AdminToken, Caller, RateLimiter};
+use crate::api::pagination::{self, Page};
+use crate::api::{
+    billing, bundle, commands, conformance_policy, coverage, daa, dispatch, emergency, events,
+    flights, geofences, home, jobs, loop_control, messages, metrics, msa, openapi, organizations,
+    owner_data, performance, planner_warmup, progress, rehearsal, request_id, rules, scheduler,
+    units, weather, webhooks, ws,
+};
+use crate::breach::BreachEvent;
 use crate::compliance::{self, ComplianceReport, RoutePoint};
 use crate::config::Config;
-use crate::route_planner::{plan_route, RoutePlanRequest, RoutePlanResponse};
-use crate::state::store::RegisterDroneOutcome;
+use crate::metering;
+use crate::persistence::conflicts::{
+    query_conflict_history, ConflictHistoryFilter, ConflictRecord,
+};
+use crate::persistence::drone_tokens::DroneSessionToken;
+use crate::persistence::telemetry_history::{query_track, TelemetrySample, TrackFilter};
+use crate::persistence::ReadTimeout;
+use crate::planner_pool::PlannerSaturated;
+use crate::route_planner::{
+    plan_route, plan_route_batch, RoutePlanBatchRequest, RoutePlanRequest, RoutePlanResponse,
+};
+use crate::state::store::{DroneArchive, RegisterDroneOutcome};
 use crate::state::{AppState, ExternalTraffic};
+use crate::telemetry_auth::{self, RejectionStats};
+use crate::wpml::{self, WpmlMission, WpmlMissionOptions, WpmlWaypointActions};
+use atc_core::messages::{codes, Message};
 use atc_core::models::{
     ConformanceStatus, DroneStatus, FlightPlanMetadata, FlightPlanRequest, GeofenceType, Telemetry,
     TrajectoryPoint, Waypoint,
 };
+use atc_core::{
+    route_corridor, ConflictSeverity, CorridorConfig, CorridorVolume, DroneCapabilities, DroneHome,
+    DronePerformance, MsaBounds, RouteEngineConfig,
+};
 
 /// Create the API router.
 pub fn create_router(config: &Config) -> Router<Arc<AppState>> {
@@ -53,29 +78,60 @@ pub fn create_router(config: &Config) -> Router<Arc<AppState>> {
     );
 
     // Create admin token extractor
-    let admin_token = AdminToken(Arc::new(config.admin_token.clone()));
+    let admin_token = AdminToken(Arc::new(config.clone()));
 
     // Public routes (no auth required)
     let public_routes = Router::new()
         .route("/v1/compliance/limits", get(get_compliance_limits))
+        .route("/v1/messages", get(messages::get_message_catalog))
         // Command polling routes
         .route("/v1/commands/next", get(commands::get_next_command))
         .route("/v1/commands/ack", post(commands::ack_command))
         .route("/v1/commands/ws", get(commands::command_stream_ws))
+        .route("/v1/commands/signing-key", get(commands::get_signing_key))
         // Geofence routes
         .route("/v1/geofences", get(geofences::list_geofences))
         .route("/v1/geofences/:id", get(geofences::get_geofence))
-        .route("/v1/geofences/check", get(geofences::check_point));
+        .route("/v1/geofences/check", get(geofences::check_point))
+        .route("/v1/geofences/export", get(geofences::export_geofences))
+        .route("/v1/msa", get(msa::get_msa))
+        .merge(openapi::swagger_ui());
 
-    let admin_read_routes = Router::new()
+    // Readable with an organization token too; handlers scope results to the caller's org.
+    let operator_read_routes = Router::new()
         .route("/v1/drones", get(list_drones))
         .route("/v1/drones/:drone_id", get(get_drone))
-        .route("/v1/traffic", get(list_traffic))
         .route("/v1/conflicts", get(list_conflicts))
         .route("/v1/conformance", get(list_conformance))
         .route("/v1/daa", get(daa::list_daa))
         .route("/v1/flights", get(flights::get_flight_plans))
+        .route("/v1/flights/history", get(flights::get_flight_plan_history))
+        .route(
+            "/v1/flights/:flight_id/progress",
+            get(progress::get_flight_progress),
+        )
         .route("/v1/ws", get(ws::ws_handler))
+        .route("/v1/events", get(events::events_handler))
+        .layer(middleware::from_fn_with_state(
+            admin_token.clone(),
+            auth::require_operator,
+        ));
+
+    let admin_read_routes = Router::new()
+        .route("/v1/drones/:drone_id/telemetry", get(telemetry_history))
+        .route("/v1/traffic", get(list_traffic))
+        .route("/v1/conflicts/history", get(conflict_history))
+        .route("/v1/conflicts/geofences", get(list_geofence_breaches))
+        .route(
+            "/v1/flights/:flight_id/rejection-detail",
+            get(flights::get_rejection_detail),
+        )
+        .route("/v1/sectors", get(dispatch::list_sectors))
+        .route("/v1/dispatch/queue", get(dispatch::dispatch_queue))
+        .route("/v1/dispatch/ws", get(ws::dispatch_ws_handler))
+        .route("/v1/billing/usage", get(billing::usage))
+        .route("/v1/scheduler/fairness", get(scheduler::fairness))
+        .route("/metrics", get(metrics::metrics))
         .layer(middleware::from_fn_with_state(
             admin_token.clone(),
             auth::require_admin,
@@ -83,14 +139,27 @@ pub fn create_router(config: &Config) -> Router<Arc<AppState>> {
 
     let registration_routes = Router::new()
         .route("/v1/drones/register", post(register_drone))
+        .route("/v1/drones/token/rotate", post(rotate_own_drone_token))
         .layer(middleware::from_fn_with_state(
             registration_limiter,
             auth::rate_limit,
         ));
 
-    let admin_command_routes = Router::new()
+    let operator_command_routes = Router::new()
         .route("/v1/commands", post(commands::issue_command))
         .route("/v1/commands", get(commands::get_all_commands))
+        .layer(middleware::from_fn_with_state(
+            admin_token.clone(),
+            auth::require_operator,
+        ));
+
+    let admin_command_routes = Router::new()
+        .route("/v1/commands/audit", get(commands::get_command_audit))
+        .route("/v1/commands/broadcast", post(commands::broadcast_command))
+        .route(
+            "/v1/commands/broadcast/:broadcast_id",
+            get(commands::get_broadcast_status),
+        )
         .layer(middleware::from_fn_with_state(
             admin_token.clone(),
             auth::require_admin,
@@ -98,6 +167,10 @@ pub fn create_router(config: &Config) -> Router<Arc<AppState>> {
 
     let admin_flight_routes = Router::new()
         .route("/v1/flights/plan", post(flights::create_flight_plan))
+        .route(
+            "/v1/flights/validate",
+            post(flights::validate_flight_plan_request),
+        )
         .route("/v1/flights", post(flights::create_flight_plan_compat))
         .route(
             "/v1/operational_intents/reserve",
@@ -117,7 +190,7 @@ pub fn create_router(config: &Config) -> Router<Arc<AppState>> {
         )
         .layer(middleware::from_fn_with_state(
             admin_token.clone(),
-            auth::require_admin,
+            auth::require_operator,
         ));
 
     // Rate-limited telemetry route
@@ -132,37 +205,128 @@ pub fn create_router(config: &Config) -> Router<Arc<AppState>> {
         .route("/v1/compliance/evaluate", post(evaluate_compliance))
         .route("/v1/geofences/check-route", post(geofences::check_route))
         .route("/v1/routes/plan", post(plan_route_handler))
+        .route("/v1/routes/plan/batch", post(plan_route_batch_handler))
+        .route("/v1/routes/export/wpml", post(export_wpml_handler))
+        .route("/v1/routes/corridor", post(route_corridor_handler))
+        .route(
+            "/v1/flights/:flight_id/rehearse",
+            post(rehearsal::rehearse_flight_plan),
+        )
         .layer(middleware::from_fn_with_state(
             expensive_limiter,
             auth::rate_limit,
         ))
         .layer(middleware::from_fn_with_state(
             admin_token.clone(),
-            auth::require_admin,
+            auth::require_operator,
         ));
 
     let admin_state_mutation_routes = Router::new()
         // RID view controls the server's external RID ingest viewport and mutates global state.
         .route("/v1/rid/view", post(update_rid_view))
-        // Geofence CRUD mutates shared airspace state.
+        .layer(middleware::from_fn_with_state(
+            admin_token.clone(),
+            auth::require_admin,
+        ));
+
+    let operator_state_mutation_routes = Router::new()
+        // Geofence CRUD mutates shared airspace, or an organization's private geofences.
         .route("/v1/geofences", post(geofences::create_geofence))
+        .route("/v1/geofences/import", post(geofences::import_geofences))
         .route("/v1/geofences/:id", put(geofences::update_geofence))
         .route("/v1/geofences/:id", delete(geofences::delete_geofence))
+        // Deregistration cancels the drone's commands and plans and archives it.
+        .route("/v1/drones/:drone_id", delete(deregister_drone))
         .layer(middleware::from_fn_with_state(
             admin_token.clone(),
-            auth::require_admin,
+            auth::require_operator,
         ));
 
     // Admin routes (preferred: /v1/admin/... prefix)
     let admin_prefixed_routes = Router::new()
         .route("/reset", post(admin_reset))
+        .route("/export", get(bundle::export_bundle))
+        .route("/import", post(bundle::import_bundle))
+        .route(
+            "/export/owner/:owner_id",
+            post(owner_data::export_owner_data),
+        )
+        .route("/purge/owner/:owner_id", post(owner_data::purge_owner_data))
         .route(
             "/drones/:drone_id/token/rotate",
             post(admin_rotate_drone_token),
         )
+        .route("/drones/:drone_id/token", delete(admin_revoke_drone_token))
+        .route(
+            "/drones/:drone_id/performance",
+            get(performance::get_drone_performance).put(performance::set_drone_performance),
+        )
+        .route(
+            "/drones/:drone_id/home",
+            get(home::get_drone_home).put(home::set_drone_home),
+        )
+        .route(
+            "/coverage",
+            get(coverage::get_coverage).put(coverage::set_coverage),
+        )
+        .route(
+            "/weather",
+            get(weather::get_weather).put(weather::set_weather),
+        )
+        .route("/rules", get(rules::get_rules).put(rules::set_rules))
+        .route(
+            "/conformance/policy",
+            get(conformance_policy::get_policy).put(conformance_policy::set_policy),
+        )
+        .route(
+            "/emergency",
+            get(emergency::get_emergency)
+                .post(emergency::declare_emergency)
+                .delete(emergency::clear_emergency),
+        )
+        .route("/telemetry/rejections", get(admin_telemetry_rejections))
+        .route("/breaches", get(admin_breach_events))
+        .route("/loops", get(loop_control::list_loops))
+        .route("/loops/:name/pause", post(loop_control::pause_loop))
+        .route("/loops/:name/resume", post(loop_control::resume_loop))
+        .route(
+            "/planner/warmup",
+            get(planner_warmup::get_warmup).post(planner_warmup::start_warmup),
+        )
+        .route("/jobs", get(jobs::list_jobs))
+        .route("/jobs/:job_id", get(jobs::get_job))
+        .route("/jobs/:job_id/retry", post(jobs::retry_job))
+        .route(
+            "/webhooks",
+            get(webhooks::list_webhooks).post(webhooks::create_webhook),
+        )
+        .route("/webhooks/:webhook_id", delete(webhooks::delete_webhook))
+        .route(
+            "/organizations",
+            get(organizations::list_organizations).post(organizations::create_organization),
+        )
+        .route(
+            "/organizations/:org_id",
+            delete(organizations::delete_organization),
+        )
+        .route(
+            "/organizations/:org_id/members/:owner_id",
+            put(organizations::add_member).delete(organizations::remove_member),
+        )
         .route("/commands", post(commands::issue_command))
         .route("/commands", get(commands::get_all_commands))
+        .route("/commands/audit", get(commands::get_command_audit))
+        .route("/commands/broadcast", post(commands::broadcast_command))
+        .route(
+            "/commands/broadcast/:broadcast_id",
+            get(commands::get_broadcast_status),
+        )
+        .route("/command-budget", get(commands::command_budget))
         .route("/flights/plan", post(flights::create_flight_plan))
+        .route(
+            "/flights/validate",
+            post(flights::validate_flight_plan_request),
+        )
         .route("/flights", post(flights::create_flight_plan_compat))
         .route(
             "/operational_intents/reserve",
@@ -179,21 +343,30 @@ pub fn create_router(config: &Config) -> Router<Arc<AppState>> {
         .route(
             "/operational_intents/:flight_id/cancel",
             post(flights::cancel_operational_intent),
-        )
-        .layer(middleware::from_fn_with_state(
-            admin_token.clone(),
-            auth::require_admin,
-        ));
+        );
+    #[cfg(feature = "chaos")]
+    let admin_prefixed_routes = admin_prefixed_routes.route(
+        "/chaos",
+        get(crate::api::chaos::get_chaos).put(crate::api::chaos::update_chaos),
+    );
+    let admin_prefixed_routes = admin_prefixed_routes.layer(middleware::from_fn_with_state(
+        admin_token.clone(),
+        auth::require_admin,
+    ));
 
     public_routes
         .merge(registration_routes)
         .merge(telemetry_route)
+        .merge(operator_read_routes)
         .merge(admin_read_routes)
         .merge(expensive_routes)
         .merge(admin_state_mutation_routes)
+        .merge(operator_state_mutation_routes)
+        .merge(operator_command_routes)
         .merge(admin_command_routes)
         .merge(admin_flight_routes)
         .nest("/v1/admin", admin_prefixed_routes)
+        .layer(middleware::from_fn(units::negotiate_units))
         .layer(middleware::from_fn(request_id::ensure_request_id))
 }
 
@@ -206,12 +379,25 @@ pub struct RegisterRequest {
     pub owner_id: Option<String>,
     #[allow(dead_code)] // Reserved for future drone type handling
     pub drone_type: Option<String>,
+    /// Physical limits used when issuing resolution commands
+    #[serde(default)]
+    pub performance: Option<DronePerformance>,
+    /// Launch/return point, optionally with a distance tether
+    #[serde(default)]
+    pub home: Option<DroneHome>,
+    /// Commands the drone accepts and equipment it carries
+    #[serde(default)]
+    pub capabilities: Option<DroneCapabilities>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ListDronesQuery {
     /// Filter drones by owner ID
     pub owner_id: Option<String>,
+    /// Only drones inside `min_lat,min_lon,max_lat,max_lon`
+    pub bbox: Option<String>,
+    /// Comma-separated statuses to keep (active, holding, lost, inactive)
+    pub status: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -230,6 +416,10 @@ pub struct TrafficQuery {
     pub source: Option<String>,
     /// Return only external traffic
     pub external_only: Option<bool>,
+    /// Hide external tracks scoring below this quality (0-1)
+    pub min_quality: Option<f64>,
+    /// Only traffic inside `min_lat,min_lon,max_lat,max_lon`
+    pub bbox: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -244,12 +434,72 @@ pub struct TrafficState {
     pub last_update: chrono::DateTime<chrono::Utc>,
     pub status: DroneStatus,
     pub traffic_source: String,
+    /// Track quality, for external traffic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<atc_core::TrackQuality>,
+    /// Aircraft category, for external traffic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<atc_core::AircraftCategory>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ConflictQuery {
     /// Filter conflicts by owner ID
     pub owner_id: Option<String>,
+    /// Filter conflicts by airspace sector
+    pub sector_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConflictListQuery {
+    /// Filter conflicts by owner ID
+    pub owner_id: Option<String>,
+    /// Filter conflicts by airspace sector
+    pub sector_id: Option<String>,
+    /// Conflicts involving this drone
+    pub drone_id: Option<String>,
+    /// Comma-separated severities, e.g. `warning,critical`
+    pub severity: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// `X-Next-Cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConflictHistoryQuery {
+    /// Conflicts involving this drone
+    pub drone_id: Option<String>,
+    /// Conflicts still active at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Conflicts that started before this time
+    pub until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelemetryHistoryQuery {
+    /// Samples at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Samples before this time
+    pub to: Option<DateTime<Utc>>,
+    /// Keep one sample per bucket of this many seconds
+    pub downsample: Option<u64>,
+}
+
+/// Most samples returned by one telemetry history query.
+const MAX_TRACK_SAMPLES: u32 = 10_000;
+
+#[derive(Debug, Serialize)]
+pub struct TelemetryTrack {
+    pub drone_id: String,
+    /// Bucket size the samples were thinned to (seconds), if any
+    pub downsample_s: Option<u64>,
+    /// Oldest first
+    pub samples: Vec<TelemetrySample>,
+    /// More samples matched than `MAX_TRACK_SAMPLES`; narrow the range or downsample
+    pub truncated: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -282,6 +532,36 @@ pub struct ComplianceEvaluateResponse {
     pub report: Option<ComplianceReport>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WpmlExportRequest {
+    /// Route to export (planner output or flight plan waypoints).
+    pub waypoints: Vec<Waypoint>,
+    /// Actions to run at specific waypoints.
+    #[serde(default)]
+    pub actions: Vec<WpmlWaypointActions>,
+    #[serde(default)]
+    pub mission: WpmlMissionOptions,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RouteCorridorRequest {
+    /// Route to convert (planner output or flight plan waypoints).
+    pub waypoints: Vec<Waypoint>,
+    /// Start of the first volume's time window; defaults to now.
+    #[serde(default)]
+    pub departure_time: Option<DateTime<Utc>>,
+    /// Corridor margins; unset fields use the defaults.
+    #[serde(default)]
+    pub corridor: CorridorConfig,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RouteCorridorResponse {
+    pub departure_time: DateTime<Utc>,
+    /// One volume per leg, with time windows in Unix seconds.
+    pub volumes: Vec<CorridorVolume>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RidViewResponse {
     pub view: String,
@@ -296,7 +576,7 @@ async fn register_drone(
 ) -> impl IntoResponse {
     let config = state.config();
     if config.require_registration_token {
-        let expected = match config.registration_token.as_deref() {
+        let expected = match config.registration_token() {
             Some(token) => token,
             None => {
                 return (
@@ -331,6 +611,29 @@ async fn register_drone(
         }
     }
 
+    if let Some(envelope) = req.performance.as_ref() {
+        if let Err(err) = performance::validate_performance(envelope) {
+            return err;
+        }
+    }
+    if let Some(home_point) = req.home.as_ref() {
+        if let Err(err) = home::validate_home(home_point) {
+            return err;
+        }
+    }
+    if let Some(capabilities) = req.capabilities.as_ref() {
+        let errors = capabilities.validate();
+        if !errors.is_empty() {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": "Invalid capabilities",
+                    "details": errors,
+                })),
+            );
+        }
+    }
+
     let drone_id = req
         .drone_id
         .unwrap_or_else(|| format!("DRONE{:04}", state.next_drone_id()));
@@ -390,8 +693,18 @@ async fn register_drone(
     }
 
     let session_token = uuid::Uuid::new_v4().to_string();
+    let telemetry_mac_secret = telemetry_auth::generate_mac_secret();
+    let expires_at = state.drone_token_expiry(Utc::now());
     match state
-        .register_drone_with_token(&drone_id, req.owner_id.clone(), session_token.clone())
+        .register_drone_with_token(
+            &drone_id,
+            req.owner_id.clone(),
+            DroneSessionToken::new(
+                session_token.clone(),
+                telemetry_mac_secret.clone(),
+                expires_at,
+            ),
+        )
         .await
     {
         Ok(RegisterDroneOutcome::Registered) => {}
@@ -417,6 +730,22 @@ async fn register_drone(
         }
     }
 
+    if let Some(envelope) = req.performance {
+        if let Err(err) = state.set_drone_performance(&drone_id, envelope).await {
+            tracing::warn!("Failed to persist performance for {}: {}", drone_id, err);
+        }
+    }
+    if let Some(home_point) = req.home {
+        if let Err(err) = state.set_drone_home(&drone_id, home_point).await {
+            tracing::warn!("Failed to persist home point for {}: {}", drone_id, err);
+        }
+    }
+    if let Some(capabilities) = req.capabilities {
+        if let Err(err) = state.set_drone_capabilities(&drone_id, capabilities).await {
+            tracing::warn!("Failed to persist capabilities for {}: {}", drone_id, err);
+        }
+    }
+
     tracing::info!("Registered drone {}", drone_id);
 
     (
@@ -424,6 +753,12 @@ async fn register_drone(
         Json(serde_json::json!({
             "drone_id": drone_id,
             "session_token": session_token,
+            "telemetry_mac_secret": telemetry_mac_secret,
+            "expires_at": expires_at,
+            "command_signing_key": state.command_signing_key(),
+            "performance": state.drone_performance(&drone_id),
+            "home": state.drone_home(&drone_id),
+            "capabilities": state.get_drone(&drone_id).and_then(|drone| drone.capabilities),
         })),
     )
 }
@@ -444,14 +779,113 @@ async fn admin_rotate_drone_token(
 
     let session_token = uuid::Uuid::new_v4().to_string();
     match state
-        .set_drone_token(&drone_id, session_token.clone())
+        .set_drone_token(
+            &drone_id,
+            session_token.clone(),
+            telemetry_auth::generate_mac_secret(),
+        )
+        .await
+    {
+        Ok(session) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "drone_id": drone_id,
+                "session_token": session.token,
+                "telemetry_mac_secret": session.mac_secret,
+                "expires_at": session.expires_at,
+            })),
+        ),
+        Err(err) => {
+            tracing::error!(
+                "Failed to rotate session token for drone {}: {}",
+                drone_id,
+                err
+            );
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to rotate session token",
+                    "drone_id": drone_id,
+                })),
+            )
+        }
+    }
+}
+
+async fn admin_revoke_drone_token(
+    State(state): State<Arc<AppState>>,
+    Path(drone_id): Path<String>,
+) -> impl IntoResponse {
+    match state.revoke_drone_token(&drone_id).await {
+        Ok(revoked) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "drone_id": drone_id,
+                "revoked": revoked,
+            })),
+        ),
+        Err(err) => {
+            tracing::error!(
+                "Failed to revoke session token for drone {}: {}",
+                drone_id,
+                err
+            );
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Failed to revoke session token",
+                    "drone_id": drone_id,
+                })),
+            )
+        }
+    }
+}
+
+/// Drone-initiated rotation: swap the presented token for a fresh one.
+///
+/// The presented token stays valid for the configured overlap so in-flight requests
+/// signed with it are not rejected.
+async fn rotate_own_drone_token(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let drone_id = match auth::authorize_drone_from_headers(state.as_ref(), &headers) {
+        Ok(drone_id) => drone_id,
+        Err(status) => {
+            return (
+                status,
+                Json(serde_json::json!({ "error": "Invalid or missing drone token" })),
+            );
+        }
+    };
+    if auth::extract_drone_token(&headers).as_deref() != state.drone_token(&drone_id).as_deref() {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "Token already rotated",
+                "hint": "Use the most recently issued session token",
+                "drone_id": drone_id,
+            })),
+        );
+    }
+
+    let session_token = uuid::Uuid::new_v4().to_string();
+    match state
+        .rotate_drone_token(
+            &drone_id,
+            session_token,
+            telemetry_auth::generate_mac_secret(),
+        )
         .await
     {
-        Ok(_) => (
+        Ok(session) => (
             StatusCode::OK,
             Json(serde_json::json!({
                 "drone_id": drone_id,
-                "session_token": session_token,
+                "session_token": session.token,
+                "telemetry_mac_secret": session.mac_secret,
+                "expires_at": session.expires_at,
+                "previous_token_expires_at": session.previous_expires_at,
             })),
         ),
         Err(err) => {
@@ -474,16 +908,46 @@ async fn admin_rotate_drone_token(
 async fn receive_telemetry(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
-    Json(telemetry): Json<Telemetry>,
+    body: Bytes,
 ) -> (StatusCode, Json<serde_json::Value>) {
+    // Parse from raw bytes so the signature can be checked over exactly what was sent.
+    let Json(mut telemetry) = match Json::<Telemetry>::from_bytes(&body) {
+        Ok(telemetry) => telemetry,
+        Err(rejection) => {
+            return (
+                rejection.status(),
+                Json(serde_json::json!({"error": rejection.body_text()})),
+            );
+        }
+    };
     if let Err(status) = auth::authorize_drone_for(state.as_ref(), &telemetry.drone_id, &headers) {
         return (
             status,
             Json(serde_json::json!({"error": "Authorization failed"})),
         );
     }
-    let mut telemetry = telemetry;
     let now = Utc::now();
+    let token = auth::extract_drone_token(&headers).unwrap_or_default();
+    let mac_secret = state.telemetry_mac_secret(&telemetry.drone_id, &token);
+    let guard = state.telemetry_guard();
+    if let Err(reason) = guard.verify(
+        state.config().telemetry_auth_mode,
+        state.config().telemetry_freshness_window_s,
+        &telemetry.drone_id,
+        mac_secret.as_deref(),
+        &headers,
+        &body,
+        now,
+    ) {
+        guard.record_rejection(&telemetry.drone_id, reason, now);
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({
+                "error": reason.message(),
+                "reason": reason,
+            })),
+        );
+    }
     if let Err(response) = validate_telemetry(&telemetry, state.config(), now) {
         return response;
     }
@@ -494,39 +958,127 @@ async fn receive_telemetry(
     (StatusCode::ACCEPTED, Json(serde_json::json!({})))
 }
 
+/// Audit log of automatic geofence breach responses, oldest first.
+async fn admin_breach_events(State(state): State<Arc<AppState>>) -> Json<Vec<BreachEvent>> {
+    Json(state.breach_events())
+}
+
+/// Per-drone counters of telemetry rejected by signature/replay checks.
+async fn admin_telemetry_rejections(
+    State(state): State<Arc<AppState>>,
+) -> Json<Vec<RejectionStats>> {
+    Json(state.telemetry_guard().rejections())
+}
+
 async fn list_drones(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Query(query): Query<ListDronesQuery>,
-) -> Json<Vec<atc_core::models::DroneState>> {
-    let all_drones = state.get_all_drones();
+) -> Result<Json<Vec<atc_core::models::DroneState>>, (StatusCode, Json<serde_json::Value>)> {
+    let bounds = parse_bbox(query.bbox.as_deref())?;
+    let statuses = query
+        .status
+        .as_deref()
+        .map(parse_drone_statuses)
+        .transpose()?;
+
+    let mut drones = match bounds {
+        Some(bounds) => state.drones_in_bounds(&bounds),
+        None => state.get_all_drones(),
+    };
 
+    drones.retain(|d| caller.sees_owner(&state, d.owner_id.as_deref()));
     // Filter by owner_id if provided
-    let filtered = if let Some(owner_id) = query.owner_id {
-        all_drones
-            .into_iter()
-            .filter(|d| d.owner_id.as_ref() == Some(&owner_id))
-            .collect()
-    } else {
-        all_drones
-    };
+    if let Some(owner_id) = query.owner_id {
+        drones.retain(|d| d.owner_id.as_ref() == Some(&owner_id));
+    }
+    if let Some(statuses) = statuses {
+        drones.retain(|d| statuses.contains(&d.status));
+    }
+
+    Ok(Json(drones))
+}
+
+/// Parse an optional `min_lat,min_lon,max_lat,max_lon` query parameter.
+fn parse_bbox(
+    bbox: Option<&str>,
+) -> Result<Option<MsaBounds>, (StatusCode, Json<serde_json::Value>)> {
+    bbox.map(|bbox| {
+        MsaBounds::parse(bbox).ok_or_else(|| {
+            bad_request(
+                "Invalid bounding box; expected min_lat,min_lon,max_lat,max_lon",
+                Some("bbox"),
+            )
+        })
+    })
+    .transpose()
+}
 
-    Json(filtered)
+fn parse_drone_statuses(
+    value: &str,
+) -> Result<Vec<DroneStatus>, (StatusCode, Json<serde_json::Value>)> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| match name.to_ascii_lowercase().as_str() {
+            "active" => Ok(DroneStatus::Active),
+            "holding" => Ok(DroneStatus::Holding),
+            "lost" => Ok(DroneStatus::Lost),
+            "inactive" => Ok(DroneStatus::Inactive),
+            _ => Err(bad_request(
+                "Invalid status; expected active, holding, lost or inactive",
+                Some("status"),
+            )),
+        })
+        .collect()
 }
 
 async fn get_drone(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Path(drone_id): Path<String>,
 ) -> Result<Json<atc_core::models::DroneState>, StatusCode> {
     state
         .get_drone(&drone_id)
+        .filter(|drone| caller.sees_owner(&state, drone.owner_id.as_deref()))
         .map(Json)
         .ok_or(StatusCode::NOT_FOUND)
 }
 
+/// Deregister a drone and archive its record.
+async fn deregister_drone(
+    State(state): State<Arc<AppState>>,
+    caller: Caller,
+    Path(drone_id): Path<String>,
+) -> Result<Json<DroneArchive>, (StatusCode, Json<serde_json::Value>)> {
+    if state.get_drone(&drone_id).is_some() && !caller.sees_drone(&state, &drone_id) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Drone not found", "drone_id": drone_id })),
+        ));
+    }
+    match state.archive_drone(&drone_id).await {
+        Ok(Some(archive)) => Ok(Json(archive)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Drone not found", "drone_id": drone_id })),
+        )),
+        Err(err) => {
+            tracing::error!("Failed to deregister drone {}: {}", drone_id, err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to deregister drone", "drone_id": drone_id })),
+            ))
+        }
+    }
+}
+
 async fn list_traffic(
     State(state): State<Arc<AppState>>,
     Query(query): Query<TrafficQuery>,
-) -> Json<Vec<TrafficState>> {
+) -> Result<Json<Vec<TrafficState>>, (StatusCode, Json<serde_json::Value>)> {
+    let bounds = parse_bbox(query.bbox.as_deref())?;
     let mut traffic: Vec<TrafficState> = Vec::new();
 
     let source_filter = query.source.as_ref().map(|s| s.to_lowercase());
@@ -549,7 +1101,10 @@ async fn list_traffic(
     }
 
     if include_local {
-        let drones = state.get_all_drones();
+        let drones = match bounds.as_ref() {
+            Some(bounds) => state.drones_in_bounds(bounds),
+            None => state.get_all_drones(),
+        };
         let filtered = if let Some(owner_id) = query.owner_id.clone() {
             drones
                 .into_iter()
@@ -570,12 +1125,25 @@ async fn list_traffic(
             last_update: drone.last_update,
             status: drone.status,
             traffic_source: "local".to_string(),
+            quality: None,
+            category: None,
         }));
     }
 
     if include_external {
-        let external = state.get_external_traffic();
-        traffic.extend(external.into_iter().map(external_to_traffic));
+        let external = match bounds.as_ref() {
+            Some(bounds) => state.external_traffic_in_bounds(bounds),
+            None => state.get_external_traffic(),
+        };
+        traffic.extend(
+            external
+                .into_iter()
+                .map(external_to_traffic)
+                .filter(|entry| match (query.min_quality, entry.quality) {
+                    (Some(min_quality), Some(quality)) => quality.score >= min_quality,
+                    _ => true,
+                }),
+        );
     }
 
     if let Some(source) = source_filter {
@@ -586,7 +1154,7 @@ async fn list_traffic(
         }
     }
 
-    Json(traffic)
+    Ok(Json(traffic))
 }
 
 fn bad_request(message: &str, field: Option<&str>) -> (StatusCode, Json<serde_json::Value>) {
@@ -597,7 +1165,7 @@ fn bad_request(message: &str, field: Option<&str>) -> (StatusCode, Json<serde_js
     (StatusCode::BAD_REQUEST, Json(payload))
 }
 
-fn validate_telemetry(
+pub(crate) fn validate_telemetry(
     telemetry: &Telemetry,
     config: &Config,
     now: DateTime<Utc>,
@@ -688,14 +1256,47 @@ fn external_to_traffic(external: ExternalTraffic) -> TrafficState {
         last_update: external.last_update,
         status: DroneStatus::Active,
         traffic_source: external.source,
+        quality: external.quality,
+        category: Some(external.category),
     }
 }
 
+/// Detected conflicts, most severe first, then by drone pair.
 async fn list_conflicts(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<ConflictQuery>,
-) -> Json<Vec<atc_core::Conflict>> {
-    let conflicts = state.get_conflicts();
+    caller: Caller,
+    Query(query): Query<ConflictListQuery>,
+) -> Result<Page<atc_core::Conflict>, (StatusCode, Json<serde_json::Value>)> {
+    let config = state.config();
+    let limit = pagination::resolve_limit(
+        query.limit,
+        config.list_default_limit,
+        config.list_max_limit,
+    )?;
+    let severities: Option<Vec<ConflictSeverity>> =
+        pagination::parse_filter(query.severity.as_deref(), "severity")?;
+
+    let mut conflicts = state.get_conflicts();
+    // Organizations see conflicts involving any of their drones.
+    if caller != Caller::Admin {
+        conflicts.retain(|conflict| {
+            caller.sees_drone(&state, &conflict.drone1_id)
+                || caller.sees_drone(&state, &conflict.drone2_id)
+        });
+    }
+
+    if let Some(sector_id) = query.sector_id {
+        conflicts.retain(|conflict| conflict.sector_id.as_ref() == Some(&sector_id));
+    }
+
+    if let Some(drone_id) = query.drone_id {
+        conflicts
+            .retain(|conflict| conflict.drone1_id == drone_id || conflict.drone2_id == drone_id);
+    }
+
+    if let Some(severities) = severities {
+        conflicts.retain(|conflict| severities.contains(&conflict.severity));
+    }
 
     if let Some(owner_id) = query.owner_id {
         let owner_drone_ids: HashSet<String> = state
@@ -704,24 +1305,182 @@ async fn list_conflicts(
             .filter(|drone| drone.owner_id.as_ref() == Some(&owner_id))
             .map(|drone| drone.drone_id)
             .collect();
-        let filtered = conflicts
+        conflicts.retain(|conflict| {
+            owner_drone_ids.contains(&conflict.drone1_id)
+                || owner_drone_ids.contains(&conflict.drone2_id)
+        });
+    }
+
+    pagination::paginate(
+        conflicts,
+        |conflict| {
+            let rank = match conflict.severity {
+                ConflictSeverity::Critical => "0",
+                ConflictSeverity::Warning => "1",
+                ConflictSeverity::Info => "2",
+            };
+            pagination::sort_key([rank, &conflict.drone1_id, &conflict.drone2_id])
+        },
+        limit,
+        query.offset.unwrap_or(0),
+        query.cursor.as_deref(),
+    )
+}
+
+/// Drones inside or projected to enter a restricted geofence within the conflict lookahead.
+async fn list_geofence_breaches(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ConflictQuery>,
+) -> Json<Vec<atc_core::GeofenceBreach>> {
+    let mut breaches = state.get_geofence_breaches();
+
+    if let Some(sector_id) = query.sector_id {
+        breaches.retain(|breach| {
+            state
+                .sector_at(breach.entry_lat, breach.entry_lon)
+                .is_some_and(|sector| sector.id == sector_id)
+        });
+    }
+
+    if let Some(owner_id) = query.owner_id {
+        let owner_drone_ids: HashSet<String> = state
+            .get_all_drones()
             .into_iter()
-            .filter(|conflict| {
-                owner_drone_ids.contains(&conflict.drone1_id)
-                    || owner_drone_ids.contains(&conflict.drone2_id)
-            })
+            .filter(|drone| drone.owner_id.as_ref() == Some(&owner_id))
+            .map(|drone| drone.drone_id)
             .collect();
-        return Json(filtered);
+        breaches.retain(|breach| owner_drone_ids.contains(&breach.drone_id));
     }
 
-    Json(conflicts)
+    Json(breaches)
+}
+
+/// Ended conflicts from the persisted history, served from the read-only analytics pool.
+async fn conflict_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ConflictHistoryQuery>,
+) -> Result<Json<Vec<ConflictRecord>>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(db) = state.database() else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "Conflict history requires a database" })),
+        ));
+    };
+    if let (Some(since), Some(until)) = (query.since, query.until) {
+        if since >= until {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "since must be before until" })),
+            ));
+        }
+    }
+
+    let config = state.config();
+    let max_limit = if config.flights_list_max_limit == 0 {
+        usize::MAX
+    } else {
+        config.flights_list_max_limit
+    };
+    let limit = query
+        .limit
+        .unwrap_or(config.flights_list_default_limit)
+        .min(max_limit);
+    let filter = ConflictHistoryFilter {
+        drone_id: query.drone_id,
+        since: query.since,
+        until: query.until,
+        limit: u32::try_from(limit).unwrap_or(u32::MAX),
+    };
+
+    match db
+        .read_with_timeout(query_conflict_history(db.read_pool(), &filter))
+        .await
+    {
+        Ok(records) => Ok(Json(records)),
+        Err(err) if err.is::<ReadTimeout>() => Err((
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(json!({ "error": err.to_string() })),
+        )),
+        Err(err) => {
+            tracing::warn!("Conflict history query failed: {}", err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Conflict history query failed" })),
+            ))
+        }
+    }
+}
+
+/// A drone's flown track from the persisted telemetry history, served from the read pool.
+async fn telemetry_history(
+    State(state): State<Arc<AppState>>,
+    Path(drone_id): Path<String>,
+    Query(query): Query<TelemetryHistoryQuery>,
+) -> Result<Json<TelemetryTrack>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(db) = state.database() else {
+        return Err((
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "error": "Telemetry history requires a database" })),
+        ));
+    };
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from >= to {
+            return Err(bad_request("from must be before to", Some("from")));
+        }
+    }
+    if state.get_drone(&drone_id).is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Drone not found" })),
+        ));
+    }
+
+    let downsample_s = query.downsample.filter(|secs| *secs > 0);
+    let filter = TrackFilter {
+        drone_id: drone_id.clone(),
+        from: query.from,
+        to: query.to,
+        bucket_ms: downsample_s.map_or(0, |secs| {
+            i64::try_from(secs.saturating_mul(1_000)).unwrap_or(i64::MAX)
+        }),
+        limit: MAX_TRACK_SAMPLES + 1,
+    };
+
+    match db
+        .read_with_timeout(query_track(db.read_pool(), &filter))
+        .await
+    {
+        Ok(mut samples) => {
+            let truncated = samples.len() > MAX_TRACK_SAMPLES as usize;
+            samples.truncate(MAX_TRACK_SAMPLES as usize);
+            Ok(Json(TelemetryTrack {
+                drone_id,
+                downsample_s,
+                samples,
+                truncated,
+            }))
+        }
+        Err(err) if err.is::<ReadTimeout>() => Err((
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(json!({ "error": err.to_string() })),
+        )),
+        Err(err) => {
+            tracing::warn!("Telemetry history query failed: {}", err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Telemetry history query failed" })),
+            ))
+        }
+    }
 }
 
 async fn list_conformance(
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     Query(query): Query<ConformanceQuery>,
 ) -> Json<Vec<ConformanceStatus>> {
     let mut statuses = state.get_conformance_statuses();
+    statuses.retain(|status| caller.sees_owner(&state, status.owner_id.as_deref()));
 
     if let Some(owner_id) = query.owner_id {
         statuses.retain(|status| status.owner_id.as_ref() == Some(&owner_id));
@@ -766,10 +1525,11 @@ async fn evaluate_compliance(
     let mut violations: Vec<serde_json::Value> = Vec::new();
 
     if points.is_empty() {
-        violations.push(json!({
-            "type": "route",
-            "message": "Route is required for compliance checks"
-        }));
+        violations.push(compliance::violation(
+            state.config(),
+            Message::new(codes::ROUTE_MISSING),
+            json!({ "type": "route" }),
+        ));
         return Json(ComplianceEvaluateResponse {
             ok: false,
             blocking: Vec::new(),
@@ -779,10 +1539,11 @@ async fn evaluate_compliance(
     }
 
     if points.len() < 2 {
-        violations.push(json!({
-            "type": "route",
-            "message": "At least 2 waypoints are required"
-        }));
+        violations.push(compliance::violation(
+            state.config(),
+            Message::new(codes::ROUTE_TOO_SHORT),
+            json!({ "type": "route" }),
+        ));
         return Json(ComplianceEvaluateResponse {
             ok: false,
             blocking: Vec::new(),
@@ -793,47 +1554,57 @@ async fn evaluate_compliance(
 
     for (idx, point) in points.iter().enumerate() {
         if !point.lat.is_finite() || !point.lon.is_finite() {
-            violations.push(json!({
-                "type": "coordinate",
-                "point_index": idx,
-                "lat": point.lat,
-                "lon": point.lon,
-                "message": "Latitude/longitude must be finite numbers"
-            }));
+            violations.push(compliance::violation(
+                state.config(),
+                Message::new(codes::ROUTE_COORDINATE_NOT_FINITE),
+                json!({
+                    "type": "coordinate",
+                    "point_index": idx,
+                    "lat": point.lat,
+                    "lon": point.lon
+                }),
+            ));
             continue;
         }
 
         if !(-90.0..=90.0).contains(&point.lat) || !(-180.0..=180.0).contains(&point.lon) {
-            violations.push(json!({
-                "type": "coordinate",
-                "point_index": idx,
-                "lat": point.lat,
-                "lon": point.lon,
-                "message": "Latitude/longitude out of range"
-            }));
+            violations.push(compliance::violation(
+                state.config(),
+                Message::new(codes::ROUTE_COORDINATE_OUT_OF_RANGE),
+                json!({
+                    "type": "coordinate",
+                    "point_index": idx,
+                    "lat": point.lat,
+                    "lon": point.lon
+                }),
+            ));
         }
 
         if !point.altitude_m.is_finite() {
-            violations.push(json!({
-                "type": "altitude",
-                "point_index": idx,
-                "altitude_m": point.altitude_m,
-                "message": "Altitude must be a finite number"
-            }));
+            violations.push(compliance::violation(
+                state.config(),
+                Message::new(codes::ROUTE_ALTITUDE_NOT_FINITE),
+                json!({
+                    "type": "altitude",
+                    "point_index": idx,
+                    "altitude_m": point.altitude_m
+                }),
+            ));
             continue;
         }
     }
 
     super::altitude_validation::validate_route_altitudes(&state, &points, &mut violations).await;
+    msa::validate_route_msa(&state, &points, &mut violations);
 
+    let (window_start, window_end) = flights::flight_window(&state, &request);
     let geofences = state.get_geofences();
     for i in 0..points.len().saturating_sub(1) {
         let start = points[i];
         let end = points[i + 1];
-        for geofence in geofences
-            .iter()
-            .filter(|g| g.active && g.geofence_type != GeofenceType::Advisory)
-        {
+        for geofence in geofences.iter().filter(|g| {
+            g.in_force_during(window_start, window_end) && g.geofence_type != GeofenceType::Advisory
+        }) {
             if geofence.intersects_segment(
                 start.lat,
                 start.lon,
@@ -842,14 +1613,18 @@ async fn evaluate_compliance(
                 end.lon,
                 end.altitude_m,
             ) {
-                violations.push(json!({
-                    "type": "geofence",
-                    "segment_index": i,
-                    "geofence_id": geofence.id,
-                    "geofence_name": geofence.name,
-                    "geofence_type": geofence.geofence_type,
-                    "message": format!("Route intersects geofence '{}'", geofence.name)
-                }));
+                violations.push(compliance::violation(
+                    state.config(),
+                    Message::new(codes::ROUTE_GEOFENCE)
+                        .with("geofence_name", geofence.name.as_str()),
+                    json!({
+                        "type": "geofence",
+                        "segment_index": i,
+                        "geofence_id": geofence.id,
+                        "geofence_name": geofence.name,
+                        "geofence_type": geofence.geofence_type
+                    }),
+                ));
             }
         }
     }
@@ -863,7 +1638,9 @@ async fn evaluate_compliance(
         });
     }
 
-    let compliance = compliance::evaluate_compliance(state.config(), &request, &points).await;
+    let compliance =
+        compliance::evaluate_compliance(state.config(), &request, &points, &state.coverage_areas())
+            .await;
     Json(ComplianceEvaluateResponse {
         ok: compliance.ok,
         blocking: compliance.blocking,
@@ -935,17 +1712,168 @@ fn extract_route_points(request: &FlightPlanRequest, config: &Config) -> Vec<Rou
     points
 }
 
+/// 503 for a planning request arriving while the planner queue is full.
+fn planner_saturated(saturated: PlannerSaturated) -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, "1")],
+        Json(json!({
+            "error": "Route planner is at capacity",
+            "queue_depth": saturated.queue_depth
+        })),
+    )
+        .into_response()
+}
+
 async fn plan_route_handler(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
     Json(request): Json<RoutePlanRequest>,
-) -> impl IntoResponse {
+) -> Response {
+    if let Err(saturated) = state.planner_pool().admit() {
+        return planner_saturated(saturated);
+    }
+    let started_at = std::time::Instant::now();
     let response: RoutePlanResponse = plan_route(state.as_ref(), state.config(), request).await;
+    // Planning occupies planner workers for its whole duration, so elapsed time is billed as
+    // planner compute.
+    let organization = metering::request_organization(&state, &headers, &uri);
+    state.usage_meter().record_planner_cpu(
+        organization.as_deref(),
+        started_at.elapsed().as_secs_f64(),
+        Utc::now(),
+    );
+    let status = if response.ok {
+        StatusCode::OK
+    } else if response.timed_out {
+        StatusCode::GATEWAY_TIMEOUT
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    (status, Json(response)).into_response()
+}
+
+async fn plan_route_batch_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    uri: Uri,
+    Json(request): Json<RoutePlanBatchRequest>,
+) -> Response {
+    if let Err(saturated) = state.planner_pool().admit() {
+        return planner_saturated(saturated);
+    }
+    let started_at = std::time::Instant::now();
+    let response = plan_route_batch(state.as_ref(), state.config(), request).await;
+    let organization = metering::request_organization(&state, &headers, &uri);
+    state.usage_meter().record_planner_cpu(
+        organization.as_deref(),
+        started_at.elapsed().as_secs_f64(),
+        Utc::now(),
+    );
     let status = if response.ok {
         StatusCode::OK
     } else {
         StatusCode::BAD_REQUEST
     };
-    (status, Json(response))
+    (status, Json(response)).into_response()
+}
+
+async fn export_wpml_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<WpmlExportRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let config = state.config();
+    // WPML executes in WGS84 ellipsoid heights; route altitudes are normalized to AMSL first.
+    let waypoints: Vec<Waypoint> = request
+        .waypoints
+        .iter()
+        .map(|wp| Waypoint {
+            altitude_m: altitude_to_amsl(
+                wp.altitude_m,
+                config.altitude_reference,
+                config.geoid_offset_m,
+            ) + config.geoid_offset_m,
+            ..wp.clone()
+        })
+        .collect();
+    let mission = WpmlMission {
+        waypoints: &waypoints,
+        actions: &request.actions,
+        options: &request.mission,
+        created_at_ms: Utc::now().timestamp_millis(),
+    };
+
+    let errors = wpml::validate_mission(&mission);
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Invalid WPML export request",
+                "errors": errors
+            })),
+        ));
+    }
+
+    let kmz = wpml::build_kmz(&mission).map_err(|err| {
+        tracing::error!("Failed to build WPML mission: {}", err);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Failed to build WPML mission"
+            })),
+        )
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, wpml::KMZ_CONTENT_TYPE),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"mission.kmz\"",
+            ),
+        ],
+        kmz,
+    ))
+}
+
+/// Convert a route into a 4D corridor for an operational intent.
+async fn route_corridor_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<RouteCorridorRequest>,
+) -> Result<Json<RouteCorridorResponse>, (StatusCode, Json<serde_json::Value>)> {
+    if request.waypoints.len() < 2 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "need at least 2 waypoints" })),
+        ));
+    }
+    let corridor = request.corridor;
+    if [
+        corridor.half_width_m,
+        corridor.vertical_buffer_m,
+        corridor.time_buffer_s,
+    ]
+    .iter()
+    .any(|value| !value.is_finite() || *value < 0.0)
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "corridor margins must be finite and non-negative" })),
+        ));
+    }
+
+    let departure_time = request.departure_time.unwrap_or_else(Utc::now);
+    let engine = RouteEngineConfig {
+        wind_mps: state.config().route_planner_wind_mps.max(0.0),
+        ..Default::default()
+    };
+    let departure_s = departure_time.timestamp_millis() as f64 / 1000.0;
+    let volumes = route_corridor(&request.waypoints, departure_s, &engine, &corridor);
+    Ok(Json(RouteCorridorResponse {
+        departure_time,
+        volumes,
+    }))
 }
 
 async fn update_rid_view(
@@ -0,0 +1,55 @@
+//! Runtime tuning of the safety rules.
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+
+use atc_core::rules::{SafetyRules, SafetyRulesOverride};
+
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// The live rules and the values tuned over the configured ones.
+#[derive(Debug, Serialize)]
+pub struct RulesResponse {
+    pub rules: SafetyRules,
+    pub overrides: SafetyRulesOverride,
+}
+
+pub async fn get_rules(State(state): State<Arc<AppState>>) -> Json<RulesResponse> {
+    Json(RulesResponse {
+        rules: state.rules().as_ref().clone(),
+        overrides: state.rules_override(),
+    })
+}
+
+/// Tune separation minima, lookahead, warning multiplier or drone timeout; fields left out keep
+/// their current value.
+pub async fn set_rules(
+    State(state): State<Arc<AppState>>,
+    Json(update): Json<SafetyRulesOverride>,
+) -> Result<Json<RulesResponse>, ApiError> {
+    let errors = update.validate();
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "Invalid safety rules", "details": errors })),
+        ));
+    }
+
+    let overrides = state.rules_override().merge(&update);
+    if let Err(err) = state.set_rules_override(overrides.clone()).await {
+        tracing::error!("Failed to persist safety rules: {}", err);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to save safety rules" })),
+        ));
+    }
+    tracing::info!("Safety rules tuned: {:?}", overrides);
+    Ok(Json(RulesResponse {
+        rules: state.rules().as_ref().clone(),
+        overrides,
+    }))
+}
@@ -0,0 +1,16 @@
+//! Scheduler analytics endpoint.
+
+use axum::{extract::State, Json};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+/// Fairness policies and quotas in force and what each has done per operator since startup.
+pub async fn fairness(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    Json(json!({
+        "policy": state.config().strategic_fairness,
+        "quotas": state.config().operator_quotas,
+        "operators": state.fairness_metrics().operators(),
+    }))
+}
@@ -1,4 +1,4 @@
-use atc_core::models::{FlightPlanMetadata, FlightPlanRequest, FlightStatus, Waypoint};
+use atc_core::models::{FlightPlan, FlightPlanMetadata, FlightPlanRequest, FlightStatus, Waypoint};
 use axum::{
     body::Body,
     http::{Request, StatusCode},
@@ -8,6 +8,7 @@ use serde_json::{json, Value};
 use std::sync::Arc;
 use tower::ServiceExt;
 
+use crate::persistence::command_audit::{CommandCause, CommandCauseKind};
 use crate::{api, config::Config, persistence, state::AppState};
 
 async fn setup_app_with(overrides: impl FnOnce(&mut Config)) -> (axum::Router, Arc<AppState>) {
@@ -17,6 +18,7 @@ async fn setup_app_with(overrides: impl FnOnce(&mut Config)) -> (axum::Router, A
         .to_string_lossy()
         .to_string();
     config.require_blender_declaration = false;
+    config.blender_auto_declare = false;
     config.require_registration_token = true;
     config.registration_token = Some("test-registration-token".to_string());
     config.allow_admin_reset = true;
@@ -26,11 +28,28 @@ async fn setup_app_with(overrides: impl FnOnce(&mut Config)) -> (axum::Router, A
 
     let db = persistence::init_database(&config.database_path, config.database_max_connections)
         .await
-        .expect("init db");
+        .expect("init db")
+        .with_read_pool(
+            &config.database_path,
+            None,
+            1,
+            std::time::Duration::from_millis(config.database_read_timeout_ms),
+        )
+        .await
+        .expect("init read pool");
     let state = Arc::new(AppState::with_database(db, config.clone()));
     state.load_from_database().await.expect("load db");
 
-    let app = api::routes(&config).with_state(state.clone());
+    let app = api::routes(&config)
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::metering::track_api_calls,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            api::auth::identify_caller,
+        ))
+        .with_state(state.clone());
     (app, state)
 }
 
@@ -237,6 +256,72 @@ async fn rotating_drone_token_invalidates_old_token() {
     assert_eq!(telemetry_res_new.status(), StatusCode::ACCEPTED);
 }
 
+#[tokio::test]
+async fn self_rotated_token_overlaps_then_expires() {
+    let (app, state) = setup_app_with(|config| {
+        config.drone_token_ttl_secs = 86_400;
+    })
+    .await;
+
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/register")
+        .header("content-type", "application/json")
+        .header("X-Registration-Token", "test-registration-token")
+        .body(Body::from(
+            json!({
+                "drone_id": "DRONE_SELF_ROTATE",
+                "owner_id": "owner-1"
+            })
+            .to_string(),
+        ))
+        .unwrap();
+
+    let register_res = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_res.status(), StatusCode::CREATED);
+    let register_body = read_json(register_res).await;
+    let old_token = register_body["session_token"]
+        .as_str()
+        .expect("session token")
+        .to_string();
+    assert!(register_body["expires_at"].is_string());
+
+    let rotate_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/token/rotate")
+        .header("authorization", format!("Bearer {}", old_token))
+        .body(Body::empty())
+        .unwrap();
+    let rotate_res = app.clone().oneshot(rotate_req).await.unwrap();
+    assert_eq!(rotate_res.status(), StatusCode::OK);
+    let rotate_body = read_json(rotate_res).await;
+    let new_token = rotate_body["session_token"]
+        .as_str()
+        .expect("new session token")
+        .to_string();
+    assert_ne!(old_token, new_token);
+
+    // The old token keeps working during the overlap window.
+    assert!(state.validate_drone_token("DRONE_SELF_ROTATE", &old_token));
+    assert!(state.validate_drone_token("DRONE_SELF_ROTATE", &new_token));
+
+    // Rotating again with the superseded token is refused.
+    let stale_rotate_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/token/rotate")
+        .header("authorization", format!("Bearer {}", old_token))
+        .body(Body::empty())
+        .unwrap();
+    let stale_rotate_res = app.clone().oneshot(stale_rotate_req).await.unwrap();
+    assert_eq!(stale_rotate_res.status(), StatusCode::CONFLICT);
+
+    let expired = state
+        .expire_drone_tokens(Utc::now() + chrono::Duration::days(2))
+        .await;
+    assert_eq!(expired, vec!["DRONE_SELF_ROTATE".to_string()]);
+    assert!(!state.validate_drone_token("DRONE_SELF_ROTATE", &new_token));
+}
+
 #[tokio::test]
 async fn create_geofence_and_check_route() {
     let (app, _state) = setup_app().await;
@@ -289,6 +374,111 @@ async fn create_geofence_and_check_route() {
     assert_eq!(route_body["conflicts"], Value::Bool(true));
 }
 
+#[tokio::test]
+async fn flight_plan_validation_does_not_create_a_plan() {
+    let (app, state) = setup_app().await;
+    state
+        .register_drone("DRONE_CHECK", None)
+        .await
+        .expect("register drone");
+    let create_req = Request::builder()
+        .method("POST")
+        .uri("/v1/geofences")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(
+            json!({
+                "name": "Validation Zone",
+                "geofence_type": "no_fly_zone",
+                "polygon": [
+                    [33.0, -117.0],
+                    [33.0, -116.9],
+                    [33.1, -116.9],
+                    [33.1, -117.0],
+                    [33.0, -117.0]
+                ],
+                "lower_altitude_m": 0.0,
+                "upper_altitude_m": 120.0
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let create_res = app.clone().oneshot(create_req).await.unwrap();
+    assert_eq!(create_res.status(), StatusCode::CREATED);
+
+    let validate_req = Request::builder()
+        .method("POST")
+        .uri("/v1/flights/validate")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(
+            json!({
+                "drone_id": "DRONE_CHECK",
+                "waypoints": [
+                    { "lat": 33.05, "lon": -117.05, "altitude_m": 50.0 },
+                    { "lat": 33.05, "lon": -116.95, "altitude_m": 50.0 }
+                ]
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let validate_res = app.oneshot(validate_req).await.unwrap();
+    assert_eq!(validate_res.status(), StatusCode::OK);
+    let body = read_json(validate_res).await;
+    assert_eq!(body["valid"], false);
+    assert_eq!(body["violations"][0]["type"], "geofence");
+    assert_eq!(body["violations"][0]["geofence_name"], "Validation Zone");
+    assert!(body["compliance"].is_null());
+    assert!(state.get_flight_plans().is_empty());
+}
+
+#[tokio::test]
+async fn route_corridor_has_a_timed_volume_per_leg() {
+    let (app, _state) = setup_app().await;
+
+    let corridor_req = |corridor: Value| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/routes/corridor")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(
+                json!({
+                    "waypoints": [
+                        { "lat": 33.0, "lon": -117.0, "altitude_m": 0.0 },
+                        { "lat": 33.0, "lon": -117.0, "altitude_m": 50.0 },
+                        { "lat": 33.01, "lon": -117.0, "altitude_m": 50.0, "speed_mps": 10.0 }
+                    ],
+                    "departure_time": "2030-01-01T12:00:00Z",
+                    "corridor": corridor
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(corridor_req(json!({ "time_buffer_s": 0.0 })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    let volumes = body["volumes"].as_array().unwrap();
+    assert_eq!(volumes.len(), 2);
+    let departure_s = 1_893_499_200.0;
+    assert_eq!(volumes[0]["time_start_s"], departure_s);
+    assert_eq!(volumes[1]["time_start_s"], volumes[0]["time_end_s"]);
+    assert_eq!(volumes[1]["altitude_lower_m"], 35.0);
+    assert_eq!(volumes[1]["polygon"].as_array().unwrap().len(), 5);
+
+    let res = app
+        .oneshot(corridor_req(json!({ "half_width_m": -1.0 })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn reject_invalid_telemetry() {
     let (app, _state) = setup_app().await;
@@ -339,6 +529,84 @@ async fn reject_invalid_telemetry() {
     assert_eq!(telemetry_res.status(), StatusCode::BAD_REQUEST);
 }
 
+#[tokio::test]
+async fn lifecycle_gate_covers_startup_and_lame_duck() {
+    let (_app, state) = setup_app().await;
+    let config = state.config().clone();
+    let app = api::routes(&config)
+        .route(
+            "/startupz",
+            axum::routing::get(crate::lifecycle::startup_handler),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            crate::lifecycle::gate_requests,
+        ))
+        .with_state(state.clone());
+    let request = |method: &str, uri: &str| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from("{}"))
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/startupz"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(read_json(res).await["database_loaded"], false);
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/drones"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    state.lifecycle().mark_database_loaded();
+    state.lifecycle().mark_blender_reconciled();
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/startupz"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/drones"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    state.lifecycle().begin_draining();
+    let res = app
+        .clone()
+        .oneshot(request("POST", "/v1/routes/plan"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(res.headers()["retry-after"], "1");
+    let res = app
+        .clone()
+        .oneshot(request("POST", "/v1/flights/plan"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    // Telemetry still reaches its handler, which rejects the empty report.
+    let res = app
+        .clone()
+        .oneshot(request("POST", "/v1/telemetry"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let res = app.oneshot(request("GET", "/v1/drones")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
 #[tokio::test]
 async fn admin_reset_requires_confirmation() {
     let (app, _state) = setup_app().await;
@@ -434,6 +702,436 @@ async fn strategic_scheduling_delays_conflicting_flight_plan() {
     assert!(plan2.departure_time <= departure + chrono::Duration::seconds(30));
 }
 
+#[tokio::test]
+async fn strategic_scheduling_layers_crossing_plan_instead_of_delaying() {
+    let (_app, state) = setup_app_with(|config| {
+        config.strategic_scheduling_enabled = true;
+        config.strategic_max_delay_secs = 30;
+        config.strategic_delay_step_secs = 1;
+        // 45 m down is below the altitude floor and 20 m down is inside vertical separation.
+        config.strategic_altitude_offsets_m = vec![-45.0, -20.0, 40.0];
+    })
+    .await;
+
+    let departure = Utc::now() + chrono::Duration::seconds(60);
+    let waypoints = vec![
+        Waypoint {
+            lat: 33.0,
+            lon: -117.0,
+            altitude_m: 50.0,
+            speed_mps: None,
+        },
+        Waypoint {
+            lat: 33.0,
+            lon: -116.999,
+            altitude_m: 50.0,
+            speed_mps: None,
+        },
+    ];
+    let request = |drone_id: &str| FlightPlanRequest {
+        drone_id: drone_id.to_string(),
+        owner_id: None,
+        waypoints: Some(waypoints.clone()),
+        trajectory_log: None,
+        metadata: Some(FlightPlanMetadata {
+            drone_speed_mps: Some(10.0),
+            ..Default::default()
+        }),
+        origin: None,
+        destination: None,
+        departure_time: Some(departure),
+    };
+    for drone_id in ["DRONE_A", "DRONE_B"] {
+        state
+            .register_drone(drone_id, None)
+            .await
+            .expect("register");
+    }
+
+    let build = |request| {
+        crate::api::flights::build_plan(state.as_ref(), request, None, FlightStatus::Approved)
+    };
+    let plan1 = build(request("DRONE_A")).await.expect("plan1");
+    assert_eq!(plan1.status, FlightStatus::Approved);
+    let meta1 = plan1.metadata.as_ref().unwrap();
+    assert_eq!(meta1.scheduled_altitude_offset_m, None);
+
+    let plan2 = build(request("DRONE_B")).await.expect("plan2");
+    assert_eq!(plan2.status, FlightStatus::Approved);
+    assert_eq!(plan2.departure_time, departure);
+    assert!(plan2.waypoints.iter().all(|wp| wp.altitude_m == 90.0));
+    let meta2 = plan2.metadata.as_ref().unwrap();
+    assert_eq!(meta2.scheduled_altitude_offset_m, Some(40.0));
+    assert_eq!(meta2.scheduled_delay_s, Some(0));
+}
+
+#[tokio::test]
+async fn random_flight_plans_record_a_reproducible_seed() {
+    let (_app, state) = setup_app().await;
+    let request = |drone_id: &str, hours: i64, planning_seed: Option<u64>| FlightPlanRequest {
+        drone_id: drone_id.to_string(),
+        owner_id: None,
+        waypoints: None,
+        trajectory_log: None,
+        metadata: planning_seed.map(|seed| FlightPlanMetadata {
+            planning_seed: Some(seed),
+            ..Default::default()
+        }),
+        origin: None,
+        destination: None,
+        departure_time: Some(Utc::now() + chrono::Duration::hours(hours)),
+    };
+    let build = |request| {
+        crate::api::flights::build_plan(state.as_ref(), request, None, FlightStatus::Approved)
+    };
+
+    let unseeded = build(request("DRONE_A", 1, None)).await.expect("unseeded");
+    let seed = unseeded
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.planning_seed)
+        .expect("seed recorded");
+
+    let replay = build(request("DRONE_B", 3, Some(seed)))
+        .await
+        .expect("replay");
+    assert_eq!(replay.metadata.as_ref().unwrap().planning_seed, Some(seed));
+    let points = |plan: &atc_core::models::FlightPlan| -> Vec<(f64, f64)> {
+        plan.waypoints.iter().map(|wp| (wp.lat, wp.lon)).collect()
+    };
+    assert_eq!(points(&replay), points(&unseeded));
+}
+
+#[tokio::test]
+async fn advisories_on_the_route_need_an_acknowledgment_token() {
+    use atc_core::models::{AdvisoryAcknowledgment, Geofence, GeofenceType};
+    use axum::Json;
+
+    let (_app, state) = setup_app().await;
+    let square = |id: &str, geofence_type: GeofenceType, lat: f64, lon: f64| Geofence {
+        id: id.to_string(),
+        name: id.to_string(),
+        geofence_type,
+        polygon: vec![
+            [lat - 0.001, lon - 0.001],
+            [lat - 0.001, lon + 0.001],
+            [lat + 0.001, lon + 0.001],
+            [lat + 0.001, lon - 0.001],
+            [lat - 0.001, lon - 0.001],
+        ],
+        lower_altitude_m: 0.0,
+        upper_altitude_m: 120.0,
+        active: true,
+        created_at: Utc::now(),
+        breach_response: None,
+        schedule: None,
+        org_id: None,
+    };
+    // An advisory on the route, a TFR just beside it and one well clear of it.
+    for geofence in [
+        square("advisory-stadium", GeofenceType::Advisory, 33.0, -117.005),
+        square(
+            "tfr-beside",
+            GeofenceType::TemporaryRestriction,
+            33.003,
+            -117.005,
+        ),
+        square(
+            "tfr-clear",
+            GeofenceType::TemporaryRestriction,
+            33.05,
+            -117.005,
+        ),
+    ] {
+        state.add_geofence(geofence).await.expect("add geofence");
+    }
+
+    let mut request = FlightPlanRequest {
+        drone_id: "DRONE_ACK".to_string(),
+        owner_id: Some("owner-1".to_string()),
+        waypoints: Some(vec![
+            Waypoint {
+                lat: 33.0,
+                lon: -117.01,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: 33.0,
+                lon: -117.0,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+        ]),
+        trajectory_log: None,
+        metadata: None,
+        origin: None,
+        destination: None,
+        departure_time: None,
+    };
+    let points: Vec<_> = request
+        .waypoints
+        .iter()
+        .flatten()
+        .map(|wp| crate::compliance::RoutePoint {
+            lat: wp.lat,
+            lon: wp.lon,
+            altitude_m: wp.altitude_m,
+        })
+        .collect();
+    let require = |request: &mut FlightPlanRequest| {
+        crate::api::advisories::require_acknowledgment(state.as_ref(), request, &points)
+    };
+
+    let (status, Json(body)) = require(&mut request).unwrap_err();
+    assert_eq!(status, StatusCode::CONFLICT);
+    let ids: Vec<_> = body["advisories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|advisory| advisory["geofence_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["advisory-stadium", "tfr-beside"]);
+    let token = body["acknowledgment_token"].as_str().unwrap().to_string();
+
+    let acknowledge = |token: &str| {
+        Some(FlightPlanMetadata {
+            advisory_acknowledgment: Some(AdvisoryAcknowledgment {
+                token: token.to_string(),
+                geofence_ids: Vec::new(),
+                acknowledged_at: None,
+                acknowledged_by: None,
+            }),
+            ..Default::default()
+        })
+    };
+    request.metadata = acknowledge("stale");
+    assert_eq!(require(&mut request).unwrap_err().0, StatusCode::CONFLICT);
+
+    request.metadata = acknowledge(&token);
+    require(&mut request).expect("acknowledged");
+    let recorded = request
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.advisory_acknowledgment.as_ref())
+        .expect("acknowledgment recorded");
+    assert_eq!(
+        recorded.geofence_ids,
+        vec!["advisory-stadium", "tfr-beside"]
+    );
+    assert_eq!(recorded.acknowledged_by.as_deref(), Some("owner-1"));
+    assert!(recorded.acknowledged_at.is_some());
+
+    // A changed advisory invalidates the token.
+    let mut moved = square("advisory-stadium", GeofenceType::Advisory, 33.0, -117.004);
+    moved.name = "Stadium (extended)".to_string();
+    state.add_geofence(moved).await.expect("update geofence");
+    request.metadata = acknowledge(&token);
+    assert_eq!(require(&mut request).unwrap_err().0, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn rejected_flight_plan_explains_each_blocked_slot() {
+    let (app, state) = setup_app_with(|config| {
+        config.strategic_scheduling_enabled = true;
+        config.strategic_max_delay_secs = 2;
+        config.strategic_delay_step_secs = 1;
+    })
+    .await;
+
+    let departure = Utc::now() + chrono::Duration::seconds(60);
+    let request = |drone_id: &str| FlightPlanRequest {
+        drone_id: drone_id.to_string(),
+        owner_id: None,
+        waypoints: Some(vec![
+            Waypoint {
+                lat: 33.0,
+                lon: -117.0,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: 33.0,
+                lon: -116.999,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+        ]),
+        trajectory_log: None,
+        metadata: Some(FlightPlanMetadata {
+            drone_speed_mps: Some(10.0),
+            ..Default::default()
+        }),
+        origin: None,
+        destination: None,
+        departure_time: Some(departure),
+    };
+    let first = crate::api::flights::build_plan(
+        state.as_ref(),
+        request("DRONE_A"),
+        None,
+        FlightStatus::Approved,
+    )
+    .await
+    .expect("first plan");
+    assert_eq!(first.status, FlightStatus::Approved);
+    let second = crate::api::flights::build_plan(
+        state.as_ref(),
+        request("DRONE_B"),
+        None,
+        FlightStatus::Approved,
+    )
+    .await
+    .expect("second plan");
+    assert_eq!(second.status, FlightStatus::Rejected);
+
+    let get = |flight_id: &str| {
+        Request::builder()
+            .uri(format!("/v1/flights/{}/rejection-detail", flight_id))
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let res = app.clone().oneshot(get(&second.flight_id)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["drone_id"], "DRONE_B");
+    let slots = body["slots"].as_array().expect("slots");
+    assert_eq!(slots.len(), 3);
+    for (delay, slot) in slots.iter().enumerate() {
+        let departure_time: chrono::DateTime<Utc> =
+            serde_json::from_value(slot["departure_time"].clone()).expect("departure time");
+        assert_eq!(
+            departure_time,
+            departure + chrono::Duration::seconds(delay as i64)
+        );
+        assert_eq!(slot["route_option"], "user");
+        let blocking = slot["blocking"].as_array().expect("blocking");
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0]["flight_id"], first.flight_id.as_str());
+        assert!(blocking[0]["overlap_start"].is_string());
+        assert!(blocking[0]["overlap_end"].is_string());
+        let approach = &blocking[0]["closest_approach"];
+        assert!(
+            approach["horizontal_m"].as_f64().expect("horizontal") < 30.0,
+            "{}",
+            approach
+        );
+        assert_eq!(approach["vertical_m"].as_f64(), Some(0.0));
+    }
+
+    let res = app.clone().oneshot(get(&first.flight_id)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn dependent_plan_is_slotted_after_the_flight_it_waits_for() {
+    use atc_core::messages::codes;
+    use atc_core::models::PlanDependency;
+
+    let (_app, state) = setup_app_with(|config| {
+        config.altitude_reference = crate::altitude::AltitudeReference::Amsl;
+        config.geoid_offset_m = 0.0;
+        config.terrain_require = false;
+    })
+    .await;
+    for drone_id in ["DRONE_LEG1", "DRONE_LEG2"] {
+        state
+            .register_drone(drone_id, None)
+            .await
+            .expect("register");
+    }
+    let request = |drone_id: &str, lat: f64, depends_on: Vec<PlanDependency>| FlightPlanRequest {
+        drone_id: drone_id.to_string(),
+        owner_id: None,
+        waypoints: Some(vec![
+            Waypoint {
+                lat,
+                lon: -117.0,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat,
+                lon: -116.99,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+        ]),
+        trajectory_log: None,
+        metadata: Some(FlightPlanMetadata {
+            drone_speed_mps: Some(10.0),
+            depends_on,
+            ..Default::default()
+        }),
+        origin: None,
+        destination: None,
+        departure_time: Some(Utc::now()),
+    };
+
+    let leg1 = crate::api::flights::build_plan(
+        state.as_ref(),
+        request("DRONE_LEG1", 33.0, Vec::new()),
+        None,
+        FlightStatus::Approved,
+    )
+    .await
+    .expect("leg1");
+    assert_eq!(leg1.status, FlightStatus::Approved);
+    let leg1_arrival = leg1.arrival_time.expect("leg1 arrival");
+
+    let after_leg1 = vec![PlanDependency::FlightLanded {
+        flight_id: leg1.flight_id.clone(),
+    }];
+    let leg2_request = request("DRONE_LEG2", 33.1, after_leg1);
+    let violations = crate::api::flights::validate_route(&state, &leg2_request).await;
+    assert!(violations.is_empty(), "{:?}", violations);
+    let leg2 = crate::api::flights::build_plan(
+        state.as_ref(),
+        leg2_request.clone(),
+        None,
+        FlightStatus::Approved,
+    )
+    .await
+    .expect("leg2");
+    assert_eq!(leg2.status, FlightStatus::Approved);
+    assert!(leg2.departure_time >= leg1_arrival);
+
+    let unknown = request(
+        "DRONE_LEG2",
+        33.1,
+        vec![
+            PlanDependency::FlightLanded {
+                flight_id: "NO_SUCH_FLIGHT".to_string(),
+            },
+            PlanDependency::GeofenceLifted {
+                geofence_id: "NO_SUCH_GEOFENCE".to_string(),
+            },
+        ],
+    );
+    let violations = crate::api::flights::validate_route(&state, &unknown).await;
+    let codes_seen: Vec<&Value> = violations
+        .iter()
+        .map(|violation| &violation["message_code"]["code"])
+        .collect();
+    assert_eq!(
+        codes_seen,
+        vec![
+            codes::PLAN_DEPENDENCY_UNKNOWN_FLIGHT,
+            codes::PLAN_DEPENDENCY_UNKNOWN_GEOFENCE
+        ]
+    );
+
+    state.flight_plans.get_mut(&leg1.flight_id).unwrap().status = FlightStatus::Cancelled;
+    let violations = crate::api::flights::validate_route(&state, &leg2_request).await;
+    assert_eq!(violations.len(), 1, "{:?}", violations);
+    assert_eq!(
+        violations[0]["message_code"]["code"],
+        codes::PLAN_DEPENDENCY_FLIGHT_ENDED
+    );
+    assert_eq!(violations[0]["status"], "cancelled");
+}
+
 #[tokio::test]
 async fn reserved_scheduler_moves_lower_priority_reservations() {
     let (_app, state) = setup_app_with(|config| {
@@ -525,3 +1223,4944 @@ async fn reserved_scheduler_moves_lower_priority_reservations() {
     assert_eq!(updated_a.status, FlightStatus::Reserved);
     assert!(updated_a.departure_time > departure);
 }
+
+#[tokio::test]
+async fn fairness_policies_cap_operator_concurrency_and_preemption() {
+    let (app, state) = setup_app_with(|config| {
+        config.strategic_scheduling_enabled = true;
+        config.strategic_max_delay_secs = 60;
+        config.strategic_delay_step_secs = 1;
+        config.strategic_fairness.max_concurrent_per_operator = 1;
+        config.strategic_fairness.max_preemption_delay_secs = 1;
+    })
+    .await;
+    for drone_id in ["DRONE_A1", "DRONE_A2", "DRONE_B"] {
+        state
+            .register_drone(drone_id, None)
+            .await
+            .expect("register");
+    }
+
+    let departure = Utc::now() + chrono::Duration::seconds(60);
+    let reserve = |drone_id: &str, owner_id: &str, lat: f64, priority: u32| FlightPlanRequest {
+        drone_id: drone_id.to_string(),
+        owner_id: Some(owner_id.to_string()),
+        waypoints: Some(vec![
+            Waypoint {
+                lat,
+                lon: -117.0,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat,
+                lon: -116.999,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+        ]),
+        trajectory_log: None,
+        metadata: Some(FlightPlanMetadata {
+            drone_speed_mps: Some(10.0),
+            scheduling_priority: Some(priority),
+            ..Default::default()
+        }),
+        origin: None,
+        destination: None,
+        departure_time: Some(departure),
+    };
+    let build = |request: FlightPlanRequest| {
+        crate::api::flights::build_plan(state.as_ref(), request, None, FlightStatus::Reserved)
+    };
+
+    // Operator A's second flight is clear of the first but waits for it to land.
+    let a1 = build(reserve("DRONE_A1", "op-a", 33.0, 200))
+        .await
+        .expect("a1");
+    let a2 = build(reserve("DRONE_A2", "op-a", 33.1, 200))
+        .await
+        .expect("a2");
+    assert_eq!(a1.departure_time, departure);
+    assert_eq!(a2.status, FlightStatus::Reserved);
+    assert!(a2.departure_time >= a1.arrival_time.expect("a1 arrival"));
+
+    // Operator B outranks A on A1's route, but pushing A1 back would cost A more than the
+    // preemption budget, so B goes behind it instead.
+    let b = build(reserve("DRONE_B", "op-b", 33.0, 10))
+        .await
+        .expect("b");
+    assert_eq!(b.status, FlightStatus::Reserved);
+    assert!(b.departure_time > departure);
+    let a1_now = state.flight_plans.get(&a1.flight_id).unwrap().clone();
+    assert_eq!(a1_now.departure_time, departure);
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/v1/scheduler/fairness")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let fairness = read_json(app.oneshot(req).await.unwrap()).await;
+    assert_eq!(fairness["policy"]["max_concurrent_per_operator"], 1);
+    let operators = fairness["operators"].as_array().unwrap();
+    assert_eq!(operators[0]["operator"], "op-a");
+    assert_eq!(operators[0]["concurrency_deferrals"], 1);
+    assert_eq!(operators[0]["preemption_delay_suffered_secs"], 0);
+    assert_eq!(operators[1]["operator"], "op-b");
+    assert_eq!(operators[1]["preemptions_limited"], 1);
+}
+
+#[tokio::test]
+async fn automatic_commands_over_budget_become_advisories() {
+    let (app, state) = setup_app_with(|config| {
+        config.auto_command_budget.region_per_min = 1;
+    })
+    .await;
+    state
+        .register_drone("DRONE-T", None)
+        .await
+        .expect("register drone");
+    let hold = |id: &str| atc_core::models::Command {
+        command_id: id.to_string(),
+        drone_id: "DRONE-T".to_string(),
+        command_type: atc_core::models::CommandType::Hold { duration_secs: 10 },
+        issued_at: Utc::now(),
+        expires_at: None,
+        acknowledged: false,
+    };
+    let cause = CommandCause::new(CommandCauseKind::Failsafe);
+
+    assert!(state
+        .enqueue_auto_command(hold("AUTO-1"), cause.clone())
+        .await
+        .unwrap());
+    assert!(!state
+        .enqueue_auto_command(hold("AUTO-2"), cause)
+        .await
+        .unwrap());
+    assert_eq!(state.get_pending_commands("DRONE-T").len(), 1);
+    let advisory = state
+        .get_daa_advisories()
+        .into_iter()
+        .find(|advisory| advisory.source == "throttle")
+        .expect("throttle advisory");
+    assert_eq!(advisory.action, "hold");
+    assert_eq!(advisory.related_id.as_deref(), Some("AUTO-2"));
+    assert!(!advisory.resolved);
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/v1/admin/command-budget")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let budget = read_json(app.oneshot(req).await.unwrap()).await;
+    assert_eq!(budget["budget"]["region_per_min"], 1);
+    assert_eq!(budget["global"]["withheld"], 0);
+    assert_eq!(budget["regions"][0]["scope"], "unsectored");
+    assert_eq!(budget["regions"][0]["tripped"], true);
+    assert_eq!(budget["regions"][0]["trips"], 1);
+    assert_eq!(budget["regions"][0]["withheld"], 1);
+}
+
+#[tokio::test]
+async fn flight_history_reads_persisted_plans() {
+    let (app, state) = setup_app().await;
+
+    let now = Utc::now();
+    for (flight_id, status, offset_mins) in [
+        ("FLIGHT-DONE", FlightStatus::Completed, -120),
+        ("FLIGHT-NEXT", FlightStatus::Approved, 30),
+    ] {
+        state
+            .add_flight_plan(FlightPlan {
+                flight_id: flight_id.to_string(),
+                drone_id: "DRONE_HIST".to_string(),
+                owner_id: Some("owner-1".to_string()),
+                waypoints: vec![
+                    Waypoint {
+                        lat: 33.0,
+                        lon: -117.0,
+                        altitude_m: 50.0,
+                        speed_mps: None,
+                    },
+                    Waypoint {
+                        lat: 33.001,
+                        lon: -117.0,
+                        altitude_m: 50.0,
+                        speed_mps: None,
+                    },
+                ],
+                trajectory_log: None,
+                metadata: None,
+                status,
+                departure_time: now + chrono::Duration::minutes(offset_mins),
+                arrival_time: None,
+                created_at: now,
+            })
+            .await
+            .expect("add plan");
+    }
+
+    let history_req = Request::builder()
+        .method("GET")
+        .uri("/v1/flights/history?drone_id=DRONE_HIST&status=completed")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let history_res = app.clone().oneshot(history_req).await.unwrap();
+    assert_eq!(history_res.status(), StatusCode::OK);
+    let body = read_json(history_res).await;
+    let plans = body.as_array().expect("plans array");
+    assert_eq!(plans.len(), 1);
+    assert_eq!(plans[0]["flight_id"], "FLIGHT-DONE");
+
+    let all_req = Request::builder()
+        .method("GET")
+        .uri("/v1/flights/history?owner_id=owner-1&limit=10")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let all_res = app.oneshot(all_req).await.unwrap();
+    let body = read_json(all_res).await;
+    let ids: Vec<&str> = body
+        .as_array()
+        .expect("plans array")
+        .iter()
+        .filter_map(|plan| plan["flight_id"].as_str())
+        .collect();
+    assert_eq!(ids, vec!["FLIGHT-NEXT", "FLIGHT-DONE"]);
+}
+
+#[tokio::test]
+async fn environment_bundle_round_trip() {
+    let (source_app, _source_state) = setup_app().await;
+    let create_req = Request::builder()
+        .method("POST")
+        .uri("/v1/geofences")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(
+            json!({
+                "name": "Stadium",
+                "geofence_type": "no_fly_zone",
+                "polygon": [[33.0, -117.0], [33.0, -116.99], [33.01, -116.99], [33.0, -117.0]],
+                "lower_altitude_m": 0.0,
+                "upper_altitude_m": 120.0
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let create_res = source_app.clone().oneshot(create_req).await.unwrap();
+    assert_eq!(create_res.status(), StatusCode::CREATED);
+    let geofence_id = read_json(create_res).await["id"]
+        .as_str()
+        .expect("geofence id")
+        .to_string();
+
+    let export_req = Request::builder()
+        .method("GET")
+        .uri("/v1/admin/export")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let export_res = source_app.oneshot(export_req).await.unwrap();
+    assert_eq!(export_res.status(), StatusCode::OK);
+    let bundle = read_json(export_res).await;
+    assert_eq!(bundle["format"], "atc-environment-bundle");
+    assert_eq!(bundle["geofences"].as_array().map(Vec::len), Some(1));
+
+    let (target_app, target_state) = setup_app().await;
+    let import_req = Request::builder()
+        .method("POST")
+        .uri("/v1/admin/import")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(bundle.to_string()))
+        .unwrap();
+    let import_res = target_app.clone().oneshot(import_req).await.unwrap();
+    assert_eq!(import_res.status(), StatusCode::OK);
+    let summary = read_json(import_res).await;
+    assert_eq!(summary["geofences"], 1);
+    assert_eq!(summary["warnings"].as_array().map(Vec::len), Some(0));
+    assert!(target_state.get_geofence(&geofence_id).is_some());
+
+    let mut future_bundle = bundle.clone();
+    future_bundle["version"] = json!(99);
+    let reject_req = Request::builder()
+        .method("POST")
+        .uri("/v1/admin/import")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(future_bundle.to_string()))
+        .unwrap();
+    let reject_res = target_app.oneshot(reject_req).await.unwrap();
+    assert_eq!(reject_res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn owner_data_is_exported_and_purged() {
+    use crate::persistence::conflicts::{ConflictOutcome, ConflictRecord};
+    use atc_core::models::{Command, CommandType};
+    use atc_core::ConflictSeverity;
+    use std::io::Read;
+
+    let (app, state) = setup_app().await;
+    for (drone_id, owner_id) in [("DRONE_GDPR", "owner-gdpr"), ("DRONE_KEEP", "owner-keep")] {
+        let register_req = Request::builder()
+            .method("POST")
+            .uri("/v1/drones/register")
+            .header("content-type", "application/json")
+            .header("X-Registration-Token", "test-registration-token")
+            .body(Body::from(
+                json!({ "drone_id": drone_id, "owner_id": owner_id }).to_string(),
+            ))
+            .unwrap();
+        let register_res = app.clone().oneshot(register_req).await.unwrap();
+        assert_eq!(register_res.status(), StatusCode::CREATED);
+    }
+
+    let now = Utc::now();
+    for (flight_id, drone_id, owner_id) in [
+        ("FLIGHT-GDPR", "DRONE_GDPR", "owner-gdpr"),
+        ("FLIGHT-KEEP", "DRONE_KEEP", "owner-keep"),
+    ] {
+        state
+            .add_flight_plan(FlightPlan {
+                flight_id: flight_id.to_string(),
+                drone_id: drone_id.to_string(),
+                owner_id: Some(owner_id.to_string()),
+                waypoints: vec![
+                    Waypoint {
+                        lat: 33.0,
+                        lon: -117.0,
+                        altitude_m: 50.0,
+                        speed_mps: None,
+                    },
+                    Waypoint {
+                        lat: 33.001,
+                        lon: -117.0,
+                        altitude_m: 50.0,
+                        speed_mps: None,
+                    },
+                ],
+                trajectory_log: None,
+                metadata: None,
+                status: FlightStatus::Completed,
+                departure_time: now - chrono::Duration::minutes(60),
+                arrival_time: None,
+                created_at: now,
+            })
+            .await
+            .expect("add plan");
+    }
+    state
+        .enqueue_command(
+            Command {
+                command_id: "CMD-GDPR".to_string(),
+                drone_id: "DRONE_GDPR".to_string(),
+                command_type: CommandType::Hold { duration_secs: 10 },
+                issued_at: now,
+                expires_at: None,
+                acknowledged: false,
+            },
+            CommandCause::new(CommandCauseKind::Operator),
+        )
+        .await
+        .expect("enqueue command");
+    persistence::conflicts::insert_conflicts(
+        state.database().unwrap().pool(),
+        &[ConflictRecord {
+            conflict_id: "C-GDPR".to_string(),
+            drone1_id: "DRONE_KEEP".to_string(),
+            drone2_id: "DRONE_GDPR".to_string(),
+            peak_severity: ConflictSeverity::Warning,
+            started_at: now - chrono::Duration::minutes(30),
+            ended_at: now - chrono::Duration::minutes(29),
+            min_separation_m: 40.0,
+            sector_id: None,
+            outcome: ConflictOutcome::Resolved,
+        }],
+    )
+    .await
+    .unwrap();
+
+    let post = |uri: &str, body: Value| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let export_res = app
+        .clone()
+        .oneshot(post("/v1/admin/export/owner/owner-gdpr", json!({})))
+        .await
+        .unwrap();
+    assert_eq!(export_res.status(), StatusCode::OK);
+    assert_eq!(export_res.headers()["content-type"], "application/zip");
+    let bytes = axum::body::to_bytes(export_res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).expect("open zip");
+    let mut read_entry = |name: &str| -> Value {
+        let mut contents = String::new();
+        archive
+            .by_name(name)
+            .expect(name)
+            .read_to_string(&mut contents)
+            .unwrap();
+        serde_json::from_str(&contents).unwrap()
+    };
+    let manifest = read_entry("manifest.json");
+    assert_eq!(manifest["owner_id"], "owner-gdpr");
+    assert_eq!(manifest["drone_ids"], json!(["DRONE_GDPR"]));
+    assert_eq!(manifest["files"]["commands.json"], 1);
+    assert_eq!(read_entry("drones.json")[0]["drone_id"], "DRONE_GDPR");
+    let plans = read_entry("flight_plans.json");
+    assert_eq!(plans.as_array().map(Vec::len), Some(1));
+    assert_eq!(plans[0]["flight_id"], "FLIGHT-GDPR");
+    assert_eq!(read_entry("commands.json")[0]["command_id"], "CMD-GDPR");
+    assert_eq!(read_entry("conflicts.json")[0]["conflict_id"], "C-GDPR");
+
+    let dry_run_res = app
+        .clone()
+        .oneshot(post("/v1/admin/purge/owner/owner-gdpr", json!({})))
+        .await
+        .unwrap();
+    assert_eq!(dry_run_res.status(), StatusCode::OK);
+    let body = read_json(dry_run_res).await;
+    assert_eq!(body["dry_run"], true);
+    assert_eq!(body["records"]["drones"], 1);
+    assert_eq!(body["records"]["flight_plans"], 1);
+    assert_eq!(body["records"]["commands"], 1);
+    assert_eq!(body["records"]["command_audit"], 1);
+    assert_eq!(body["records"]["conflicts"], 1);
+    assert_eq!(body["records"]["drone_tokens"], 1);
+    assert!(state.get_drone("DRONE_GDPR").is_some());
+
+    let unconfirmed_res = app
+        .clone()
+        .oneshot(post(
+            "/v1/admin/purge/owner/owner-gdpr",
+            json!({ "dry_run": false }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(unconfirmed_res.status(), StatusCode::BAD_REQUEST);
+
+    let purge_res = app
+        .clone()
+        .oneshot(post(
+            "/v1/admin/purge/owner/owner-gdpr",
+            json!({ "dry_run": false, "confirm": "owner-gdpr" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(purge_res.status(), StatusCode::OK);
+    assert_eq!(read_json(purge_res).await["records"]["flight_plans"], 1);
+    assert!(state.get_drone("DRONE_GDPR").is_none());
+    assert!(state.get_drone("DRONE_KEEP").is_some());
+    let remaining: Vec<String> = state
+        .get_flight_plans()
+        .into_iter()
+        .map(|plan| plan.flight_id)
+        .collect();
+    assert_eq!(remaining, vec!["FLIGHT-KEEP".to_string()]);
+
+    let export_res = app
+        .oneshot(post("/v1/admin/export/owner/owner-gdpr", json!({})))
+        .await
+        .unwrap();
+    assert_eq!(export_res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn purge_removes_deregistered_drone_records() {
+    let (app, state) = setup_app().await;
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/register")
+        .header("content-type", "application/json")
+        .header("X-Registration-Token", "test-registration-token")
+        .body(Body::from(
+            json!({ "drone_id": "DRONE_GONE", "owner_id": "owner-gone" }).to_string(),
+        ))
+        .unwrap();
+    let register_res = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_res.status(), StatusCode::CREATED);
+
+    let deregister_req = Request::builder()
+        .method("DELETE")
+        .uri("/v1/drones/DRONE_GONE")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let deregister_res = app.clone().oneshot(deregister_req).await.unwrap();
+    assert_eq!(deregister_res.status(), StatusCode::OK);
+    assert!(state.get_drone("DRONE_GONE").is_none());
+
+    let purge_req = Request::builder()
+        .method("POST")
+        .uri("/v1/admin/purge/owner/owner-gone")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(
+            json!({ "dry_run": false, "confirm": "owner-gone" }).to_string(),
+        ))
+        .unwrap();
+    let purge_res = app.oneshot(purge_req).await.unwrap();
+    assert_eq!(purge_res.status(), StatusCode::OK);
+    let body = read_json(purge_res).await;
+    assert_eq!(body["drone_ids"], json!(["DRONE_GONE"]));
+    assert_eq!(body["records"]["drones"], 1);
+
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM drones WHERE drone_id = $1")
+        .bind("DRONE_GONE")
+        .fetch_one(state.database().unwrap().pool())
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
+
+#[tokio::test]
+async fn reregistered_drone_is_unarchived() {
+    use atc_core::models::{DroneState, DroneStatus};
+
+    let (_app, state) = setup_app().await;
+    let drone = DroneState {
+        drone_id: "DRONE-0007".to_string(),
+        owner_id: Some("owner-1".to_string()),
+        lat: 33.0,
+        lon: -117.0,
+        altitude_m: 50.0,
+        heading_deg: 0.0,
+        speed_mps: 0.0,
+        velocity_x: 0.0,
+        velocity_y: 0.0,
+        velocity_z: 0.0,
+        status: DroneStatus::Inactive,
+        last_update: Utc::now(),
+        scheduling_priority: None,
+        capabilities: None,
+    };
+    let pool = state.database().unwrap().pool().clone();
+    persistence::drones::upsert_drone(&pool, &drone)
+        .await
+        .unwrap();
+    persistence::drones::archive_drone(&pool, "DRONE-0007", Utc::now(), &[])
+        .await
+        .unwrap();
+
+    // A telemetry flush queued before deregistration leaves the archive in place.
+    let mut tx = pool.begin().await.unwrap();
+    persistence::drones::upsert_drone_tx(&mut tx, &drone)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+    state.load_from_database().await.unwrap();
+    assert!(state.get_drone("DRONE-0007").is_none());
+    assert_eq!(state.next_drone_id(), 8);
+
+    persistence::drones::upsert_drone(&pool, &drone)
+        .await
+        .unwrap();
+    state.load_from_database().await.unwrap();
+    assert!(state.get_drone("DRONE-0007").is_some());
+}
+
+#[tokio::test]
+async fn delivered_commands_are_signed() {
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+    use ring::signature::{UnparsedPublicKey, ED25519};
+
+    let (app, _state) = setup_app_with(|config| {
+        config.command_signing_key = Some(BASE64.encode([7u8; 32]));
+    })
+    .await;
+
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/register")
+        .header("content-type", "application/json")
+        .header("X-Registration-Token", "test-registration-token")
+        .body(Body::from(
+            json!({ "drone_id": "DRONE_SIGNED", "owner_id": "owner-1" }).to_string(),
+        ))
+        .unwrap();
+    let register_res = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_res.status(), StatusCode::CREATED);
+    let register_body = read_json(register_res).await;
+    let token = register_body["session_token"].as_str().unwrap().to_string();
+    let key: atc_core::models::CommandSigningKey =
+        serde_json::from_value(register_body["command_signing_key"].clone())
+            .expect("signing key in registration response");
+
+    let issue_req = Request::builder()
+        .method("POST")
+        .uri("/v1/admin/commands")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(
+            json!({
+                "drone_id": "DRONE_SIGNED",
+                "owner_id": "owner-1",
+                "type": "ALTITUDE_CHANGE",
+                "target_altitude_m": 75.5
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let issue_res = app.clone().oneshot(issue_req).await.unwrap();
+    assert_eq!(issue_res.status(), StatusCode::OK);
+
+    let next_req = Request::builder()
+        .method("GET")
+        .uri("/v1/commands/next?drone_id=DRONE_SIGNED")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+    let next_res = app.clone().oneshot(next_req).await.unwrap();
+    assert_eq!(next_res.status(), StatusCode::OK);
+    let mut signed: atc_core::models::SignedCommand =
+        serde_json::from_value(read_json(next_res).await).expect("signed command");
+    let signature = signed.signature.clone().expect("command signature");
+    assert_eq!(signature.key_id, key.key_id);
+
+    let public_key = BASE64.decode(&key.public_key).unwrap();
+    let signature_bytes = BASE64.decode(&signature.signature).unwrap();
+    let payload = BASE64.decode(&signature.payload).unwrap();
+    let verifier = UnparsedPublicKey::new(&ED25519, &public_key);
+    assert!(verifier.verify(&payload, &signature_bytes).is_ok());
+    assert_eq!(payload, signed.command.signing_payload());
+
+    // A relay altering the command invalidates the signature.
+    signed.command.command_type = atc_core::models::CommandType::Hold { duration_secs: 600 };
+    assert!(verifier
+        .verify(&signed.command.signing_payload(), &signature_bytes)
+        .is_err());
+}
+
+#[tokio::test]
+async fn replayed_telemetry_is_rejected_and_counted() {
+    use hmac::{Hmac, Mac};
+
+    let (app, _state) = setup_app_with(|config| {
+        config.telemetry_auth_mode = crate::telemetry_auth::TelemetryAuthMode::Required;
+    })
+    .await;
+
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/register")
+        .header("content-type", "application/json")
+        .header("X-Registration-Token", "test-registration-token")
+        .body(Body::from(json!({"drone_id": "DRONE_NONCE"}).to_string()))
+        .unwrap();
+    let register_res = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(register_res.status(), StatusCode::CREATED);
+    let register_body = read_json(register_res).await;
+    let token = register_body["session_token"]
+        .as_str()
+        .expect("session token")
+        .to_string();
+    let mac_secret = register_body["telemetry_mac_secret"]
+        .as_str()
+        .expect("telemetry MAC secret")
+        .to_string();
+    assert_ne!(mac_secret, token);
+
+    let body = json!({
+        "drone_id": "DRONE_NONCE",
+        "lat": 33.6846,
+        "lon": -117.8265,
+        "altitude_m": 90.0,
+        "timestamp": Utc::now().to_rfc3339()
+    })
+    .to_string();
+    let timestamp_ms = Utc::now().timestamp_millis();
+    let sign = |key: &str| {
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(key.as_bytes()).unwrap();
+        mac.update(format!("{}.nonce-1.{}", timestamp_ms, body).as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    };
+
+    let send = |signature: Option<String>| {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/v1/telemetry")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", token));
+        if let Some(signature) = signature {
+            builder = builder
+                .header("X-ATC-Timestamp", timestamp_ms.to_string())
+                .header("X-ATC-Nonce", "nonce-1")
+                .header("X-ATC-Signature", signature);
+        }
+        builder.body(Body::from(body.clone())).unwrap()
+    };
+
+    // The bearer token travels on every request, so it must not be able to sign telemetry.
+    let res = app.clone().oneshot(send(Some(sign(&token)))).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(read_json(res).await["reason"], "bad_signature");
+
+    let res = app
+        .clone()
+        .oneshot(send(Some(sign(&mac_secret))))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+
+    let res = app
+        .clone()
+        .oneshot(send(Some(sign(&mac_secret))))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(read_json(res).await["reason"], "replay");
+
+    let res = app.clone().oneshot(send(None)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(read_json(res).await["reason"], "missing_signature");
+
+    let stats_req = Request::builder()
+        .method("GET")
+        .uri("/v1/admin/telemetry/rejections")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let stats = read_json(app.clone().oneshot(stats_req).await.unwrap()).await;
+    assert_eq!(stats[0]["drone_id"], "DRONE_NONCE");
+    assert_eq!(stats[0]["replay"], 1);
+    assert_eq!(stats[0]["missing_signature"], 1);
+    assert_eq!(stats[0]["bad_signature"], 1);
+}
+
+#[tokio::test]
+async fn sector_items_are_routed_to_their_dispatcher() {
+    let sector = |id: &str, dispatcher: &str, min_lon: f64| crate::sectors::Sector {
+        id: id.to_string(),
+        name: None,
+        polygon: vec![
+            [33.0, min_lon],
+            [33.0, min_lon + 1.0],
+            [34.0, min_lon + 1.0],
+            [34.0, min_lon],
+            [33.0, min_lon],
+        ],
+        dispatcher: dispatcher.to_string(),
+    };
+    let (app, state) = setup_app_with(|config| {
+        config.sectors = vec![
+            sector("west", "alice", -119.0),
+            sector("east", "bob", -118.0),
+        ];
+    })
+    .await;
+    let mut dispatch_rx = state.subscribe_dispatch();
+
+    let now = Utc::now();
+    for (drone_id, lat) in [("DRONE_1", 33.5), ("DRONE_2", 33.5001)] {
+        state
+            .update_telemetry(atc_core::models::Telemetry {
+                drone_id: drone_id.to_string(),
+                owner_id: None,
+                lat,
+                lon: -117.5,
+                altitude_m: 50.0,
+                velocity_x: 0.0,
+                velocity_y: 0.0,
+                velocity_z: 0.0,
+                heading_deg: 0.0,
+                speed_mps: 0.0,
+                timestamp: now,
+            })
+            .await;
+    }
+    state.refresh_conflicts().await;
+    state
+        .add_flight_plan(FlightPlan {
+            flight_id: "FLIGHT-WEST".to_string(),
+            drone_id: "DRONE_3".to_string(),
+            owner_id: None,
+            waypoints: vec![Waypoint {
+                lat: 33.5,
+                lon: -118.5,
+                altitude_m: 50.0,
+                speed_mps: None,
+            }],
+            trajectory_log: None,
+            metadata: None,
+            status: FlightStatus::Reserved,
+            departure_time: now,
+            arrival_time: None,
+            created_at: now,
+        })
+        .await
+        .expect("add plan");
+    // Re-detecting the same conflict must not notify again.
+    state.refresh_conflicts().await;
+
+    let first = dispatch_rx.try_recv().expect("conflict notification");
+    assert_eq!(first.dispatcher, "bob");
+    assert_eq!(first.sector_id, "east");
+    assert_eq!(first.kind, crate::sectors::DispatchItemKind::Conflict);
+    let second = dispatch_rx.try_recv().expect("approval notification");
+    assert_eq!(second.dispatcher, "alice");
+    assert_eq!(second.kind, crate::sectors::DispatchItemKind::Approval);
+    assert_eq!(second.item_id, "FLIGHT-WEST");
+    assert!(dispatch_rx.try_recv().is_err());
+
+    let queue_req = Request::builder()
+        .method("GET")
+        .uri("/v1/dispatch/queue?dispatcher=alice")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let queue = read_json(app.clone().oneshot(queue_req).await.unwrap()).await;
+    assert_eq!(queue["sector_ids"], json!(["west"]));
+    assert_eq!(queue["conflicts"], json!([]));
+    assert_eq!(queue["approvals"][0]["flight_id"], "FLIGHT-WEST");
+    assert_eq!(queue["approvals"][0]["metadata"]["sector_id"], "west");
+
+    let conflicts_req = Request::builder()
+        .method("GET")
+        .uri("/v1/conflicts?sector_id=east")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let conflicts = read_json(app.oneshot(conflicts_req).await.unwrap()).await;
+    assert_eq!(conflicts.as_array().map(Vec::len), Some(1));
+    assert_eq!(conflicts[0]["sector_id"], "east");
+}
+
+#[tokio::test]
+async fn billing_usage_reports_api_calls_per_organization() {
+    let (app, state) = setup_app().await;
+
+    for _ in 0..2 {
+        let req = Request::builder()
+            .method("GET")
+            .uri("/v1/drones?owner_id=acme")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let month = crate::metering::month_key(Utc::now());
+    let usage_req = Request::builder()
+        .method("GET")
+        .uri(format!(
+            "/v1/billing/usage?organization=acme&month={}",
+            month
+        ))
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let usage = read_json(app.clone().oneshot(usage_req).await.unwrap()).await;
+    assert_eq!(usage[0]["organization"], "acme");
+    assert_eq!(usage[0]["api_calls"], 2);
+
+    let csv_req = Request::builder()
+        .method("GET")
+        .uri("/v1/billing/usage?organization=acme&format=csv")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.clone().oneshot(csv_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    assert!(csv.contains(&format!("acme,{},", month)));
+
+    let bad_req = Request::builder()
+        .method("GET")
+        .uri("/v1/billing/usage?month=2026-13")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(bad_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // Flushed records survive a restart.
+    let db = state.database().unwrap();
+    let dirty = state.usage_meter().take_dirty();
+    persistence::usage::upsert_usage_records(db.pool(), &dirty)
+        .await
+        .unwrap();
+    let restored = persistence::usage::load_usage_records(db.pool())
+        .await
+        .unwrap();
+    let acme = restored
+        .iter()
+        .find(|record| record.organization == "acme")
+        .unwrap();
+    assert_eq!(acme.api_calls, 2);
+}
+
+#[tokio::test]
+async fn admin_can_pause_and_resume_loops() {
+    let (app, state) = setup_app().await;
+
+    let pause_req = Request::builder()
+        .method("POST")
+        .uri("/v1/admin/loops/blender-sync/pause")
+        .header("authorization", "Bearer test-admin-token")
+        .header("content-type", "application/json")
+        .body(Body::from(
+            json!({ "duration_secs": 600, "reason": "Blender upgrade" }).to_string(),
+        ))
+        .unwrap();
+    let paused = read_json(app.clone().oneshot(pause_req).await.unwrap()).await;
+    assert_eq!(paused["paused"], true);
+    assert_eq!(paused["reason"], "Blender upgrade");
+    assert!(state.loop_paused("blender-sync"));
+    assert!(!state.loop_paused("conflict"));
+
+    let list_req = Request::builder()
+        .method("GET")
+        .uri("/v1/admin/loops")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let loops = read_json(app.clone().oneshot(list_req).await.unwrap()).await;
+    let paused_names: Vec<&str> = loops
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|entry| entry["paused"] == true)
+        .filter_map(|entry| entry["name"].as_str())
+        .collect();
+    assert_eq!(paused_names, vec!["blender-sync"]);
+
+    let resume_req = Request::builder()
+        .method("POST")
+        .uri("/v1/admin/loops/blender-sync/resume")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let resumed = read_json(app.clone().oneshot(resume_req).await.unwrap()).await;
+    assert_eq!(resumed["paused"], false);
+    assert!(!state.loop_paused("blender-sync"));
+
+    for (uri, body, expected) in [
+        ("/v1/admin/loops/unknown/pause", "{}", StatusCode::NOT_FOUND),
+        (
+            "/v1/admin/loops/rid/pause",
+            r#"{"duration_secs":0}"#,
+            StatusCode::BAD_REQUEST,
+        ),
+    ] {
+        let req = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("authorization", "Bearer test-admin-token")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap().status(), expected);
+    }
+
+    // Expired pauses resume on their own.
+    state.pause_loop("rid", Utc::now() - chrono::Duration::seconds(1), None);
+    assert!(!state.loop_paused("rid"));
+}
+
+#[tokio::test]
+async fn admin_planner_warmup_reports_each_operating_area() {
+    use crate::warmup::OperatingArea;
+
+    let (app, _state) = setup_app().await;
+    let start = |app: axum::Router| async move {
+        let req = Request::builder()
+            .method("POST")
+            .uri("/v1/admin/planner/warmup")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(req).await.unwrap()
+    };
+    assert_eq!(start(app).await.status(), StatusCode::BAD_REQUEST);
+
+    let (app, _state) = setup_app_with(|config| {
+        config.operating_areas = vec![OperatingArea {
+            id: "harbor".to_string(),
+            name: None,
+            min_lat: 32.705,
+            min_lon: -117.17,
+            max_lat: 32.715,
+            max_lon: -117.16,
+        }];
+        // Nothing listens here, so the prefetch fails fast without touching the network.
+        config.compliance_overpass_url = "http://127.0.0.1:9/api/interpreter".to_string();
+        config.compliance_overpass_retries = 0;
+        config.terrain_provider_url = String::new();
+        config.planner_warmup_snapshot_path = None;
+    })
+    .await;
+    let started = start(app.clone()).await;
+    assert_eq!(started.status(), StatusCode::ACCEPTED);
+    assert_eq!(read_json(started).await["operating_areas"], 1);
+
+    let status = loop {
+        let req = Request::builder()
+            .method("GET")
+            .uri("/v1/admin/planner/warmup")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap();
+        let status = read_json(app.clone().oneshot(req).await.unwrap()).await;
+        if status["running"] == false {
+            break status;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    };
+    let report = &status["last"];
+    assert_eq!(report["trigger"], "admin");
+    assert!(report["finished_at"].is_string());
+    assert_eq!(report["areas"][0]["area_id"], "harbor");
+    assert_eq!(report["areas"][0]["obstacle_tiles"], 0);
+    assert_eq!(report["areas"][0]["obstacle_tiles_failed"], 1);
+    assert_eq!(report["areas"][0]["terrain_chunks"], 0);
+}
+
+#[tokio::test]
+async fn failed_jobs_are_dead_lettered_and_retried() {
+    use crate::warmup::OperatingArea;
+
+    let (app, state) = setup_app_with(|config| {
+        config.operating_areas = vec![OperatingArea {
+            id: "harbor".to_string(),
+            name: None,
+            min_lat: 32.705,
+            min_lon: -117.17,
+            max_lat: 32.715,
+            max_lon: -117.16,
+        }];
+        config.compliance_overpass_url = "http://127.0.0.1:9/api/interpreter".to_string();
+        config.compliance_overpass_retries = 0;
+        config.terrain_provider_url = String::new();
+        // The database is a file, so the snapshot cannot be written under it.
+        config.planner_warmup_snapshot_path =
+            Some(format!("{}/snapshot.json", config.database_path));
+        config.job_max_attempts = 1;
+    })
+    .await;
+    let admin = |method: &str, uri: &str| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(admin("POST", "/v1/admin/planner/warmup"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+    let dead = loop {
+        let res = app
+            .clone()
+            .oneshot(admin("GET", "/v1/admin/jobs?status=dead"))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = read_json(res).await;
+        if body["counts"]["dead"] == 1 {
+            break body["jobs"][0].clone();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    };
+    assert_eq!(dead["kind"], "planner_warmup");
+    assert_eq!(dead["attempts"], 1);
+    assert!(dead["last_error"]
+        .as_str()
+        .unwrap()
+        .contains("planner warmup snapshot"));
+    let job_id = dead["id"].as_str().unwrap().to_string();
+
+    // Dead letters survive a reload from the database.
+    state.load_from_database().await.expect("reload db");
+    let res = app
+        .clone()
+        .oneshot(admin("GET", &format!("/v1/admin/jobs/{job_id}")))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["status"], "dead");
+
+    let res = app
+        .clone()
+        .oneshot(admin("POST", &format!("/v1/admin/jobs/{job_id}/retry")))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+    let retried = read_json(res).await;
+    assert_eq!(retried["id"], job_id.as_str());
+    assert_ne!(retried["status"], "dead");
+
+    let res = app
+        .oneshot(admin("POST", "/v1/admin/jobs/missing/retry"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn broadcast_command_tracks_acks_per_drone() {
+    let (app, _state) = setup_app().await;
+
+    let mut tokens = std::collections::HashMap::new();
+    for (drone_id, owner_id, lat) in [
+        ("DRONE_IN_A", "owner-1", 33.685),
+        ("DRONE_IN_B", "owner-2", 33.686),
+        ("DRONE_OUT", "owner-1", 33.72),
+    ] {
+        let register_req = Request::builder()
+            .method("POST")
+            .uri("/v1/drones/register")
+            .header("content-type", "application/json")
+            .header("X-Registration-Token", "test-registration-token")
+            .body(Body::from(
+                json!({ "drone_id": drone_id, "owner_id": owner_id }).to_string(),
+            ))
+            .unwrap();
+        let register_res = app.clone().oneshot(register_req).await.unwrap();
+        assert_eq!(register_res.status(), StatusCode::CREATED);
+        let token = read_json(register_res).await["session_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let telemetry_req = Request::builder()
+            .method("POST")
+            .uri("/v1/telemetry")
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {}", token))
+            .body(Body::from(
+                json!({
+                    "drone_id": drone_id,
+                    "lat": lat,
+                    "lon": -117.826,
+                    "altitude_m": 80.0,
+                    "heading_deg": 0.0,
+                    "speed_mps": 5.0,
+                    "timestamp": Utc::now().to_rfc3339()
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let telemetry_res = app.clone().oneshot(telemetry_req).await.unwrap();
+        assert_eq!(telemetry_res.status(), StatusCode::ACCEPTED);
+        tokens.insert(drone_id, token);
+    }
+
+    let broadcast = |body: Value| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/admin/commands/broadcast")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(broadcast(json!({ "type": "HOLD", "duration_secs": 30 })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let polygon = json!([
+        [33.68, -117.83],
+        [33.69, -117.83],
+        [33.69, -117.82],
+        [33.68, -117.82],
+        [33.68, -117.83]
+    ]);
+    let res = app
+        .clone()
+        .oneshot(broadcast(
+            json!({ "polygon": polygon, "type": "HOLD", "duration_secs": 30 }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["status"], "pending");
+    let broadcast_id = body["broadcast_id"].as_str().unwrap().to_string();
+    let target_a = body["targets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|target| target["drone_id"] == "DRONE_IN_A")
+        .expect("drone A targeted");
+    let command_id = target_a["command_id"].as_str().unwrap().to_string();
+
+    let ack_req = Request::builder()
+        .method("POST")
+        .uri("/v1/commands/ack")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", tokens["DRONE_IN_A"]))
+        .body(Body::from(json!({ "command_id": command_id }).to_string()))
+        .unwrap();
+    let ack_res = app.clone().oneshot(ack_req).await.unwrap();
+    assert_eq!(read_json(ack_res).await["status"], "acknowledged");
+
+    let status_req = Request::builder()
+        .method("GET")
+        .uri(format!("/v1/admin/commands/broadcast/{}", broadcast_id))
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let status = read_json(app.clone().oneshot(status_req).await.unwrap()).await;
+    assert_eq!(status["acknowledged"], 1);
+    assert_eq!(status["pending"], 1);
+    let statuses: Vec<(&str, &str)> = status["targets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| {
+            (
+                t["drone_id"].as_str().unwrap(),
+                t["status"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![("DRONE_IN_A", "acknowledged"), ("DRONE_IN_B", "pending")]
+    );
+
+    // Owner scope combines with the polygon.
+    let res = app
+        .clone()
+        .oneshot(broadcast(
+            json!({ "polygon": polygon, "owner_id": "owner-2", "type": "LAND" }),
+        ))
+        .await
+        .unwrap();
+    let body = read_json(res).await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["targets"][0]["drone_id"], "DRONE_IN_B");
+}
+
+#[tokio::test]
+async fn conflict_history_filters_by_drone_and_time() {
+    use crate::persistence::conflicts::{ConflictOutcome, ConflictRecord};
+    use atc_core::ConflictSeverity;
+
+    let (app, state) = setup_app().await;
+    let now = Utc::now();
+    let record = |id: &str, drone1: &str, drone2: &str, started_mins: i64| ConflictRecord {
+        conflict_id: id.to_string(),
+        drone1_id: drone1.to_string(),
+        drone2_id: drone2.to_string(),
+        peak_severity: ConflictSeverity::Warning,
+        started_at: now + chrono::Duration::minutes(started_mins),
+        ended_at: now + chrono::Duration::minutes(started_mins + 2),
+        min_separation_m: 42.0,
+        sector_id: None,
+        outcome: ConflictOutcome::Resolved,
+    };
+    let db = state.database().unwrap();
+    persistence::conflicts::insert_conflicts(
+        db.pool(),
+        &[
+            record("C-OLD", "DRONE_A", "DRONE_B", -120),
+            record("C-NEW", "DRONE_C", "DRONE_A", -10),
+            record("C-OTHER", "DRONE_C", "DRONE_D", -5),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let ids = |body: Value| -> Vec<String> {
+        body.as_array()
+            .expect("conflicts array")
+            .iter()
+            .filter_map(|record| record["conflict_id"].as_str().map(str::to_string))
+            .collect()
+    };
+    let get = |uri: String| {
+        Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(get("/v1/conflicts/history?drone_id=DRONE_A".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(ids(read_json(res).await), vec!["C-NEW", "C-OLD"]);
+
+    let since = (now - chrono::Duration::minutes(30)).to_rfc3339();
+    let res = app
+        .clone()
+        .oneshot(get(format!(
+            "/v1/conflicts/history?drone_id=DRONE_A&since={}",
+            since.replace('+', "%2B")
+        )))
+        .await
+        .unwrap();
+    let body = read_json(res).await;
+    assert_eq!(ids(body.clone()), vec!["C-NEW"]);
+    assert_eq!(body[0]["outcome"], "resolved");
+    assert_eq!(body[0]["min_separation_m"], 42.0);
+
+    let res = app
+        .oneshot(get(format!(
+            "/v1/conflicts/history?since={}&until={}",
+            since.replace('+', "%2B"),
+            since.replace('+', "%2B")
+        )))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn drone_performance_is_registered_and_persisted() {
+    let (app, state) = setup_app().await;
+
+    let register = |performance: Value| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/drones/register")
+            .header("content-type", "application/json")
+            .header("X-Registration-Token", "test-registration-token")
+            .body(Body::from(
+                json!({ "drone_id": "DRONE_PERF", "performance": performance }).to_string(),
+            ))
+            .unwrap()
+    };
+    let res = app
+        .clone()
+        .oneshot(register(json!({
+            "max_climb_rate_mps": 0.0,
+            "max_speed_mps": 18.0,
+            "turn_rate_deg_s": 30.0,
+            "max_wind_mps": 10.0
+        })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert!(state.get_drone("DRONE_PERF").is_none());
+
+    let res = app
+        .clone()
+        .oneshot(register(json!({
+            "max_climb_rate_mps": 2.5,
+            "max_speed_mps": 18.0,
+            "turn_rate_deg_s": 30.0,
+            "max_wind_mps": 10.0
+        })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    assert_eq!(read_json(res).await["performance"]["max_speed_mps"], 18.0);
+
+    let update_req = Request::builder()
+        .method("PUT")
+        .uri("/v1/admin/drones/DRONE_PERF/performance")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(
+            json!({
+                "max_climb_rate_mps": 4.0,
+                "max_descent_rate_mps": 5.0,
+                "max_speed_mps": 22.0,
+                "turn_rate_deg_s": 60.0,
+                "max_wind_mps": 14.0
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let res = app.clone().oneshot(update_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Envelopes survive a reload from the database.
+    state.load_from_database().await.unwrap();
+    let get_req = Request::builder()
+        .method("GET")
+        .uri("/v1/admin/drones/DRONE_PERF/performance")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.clone().oneshot(get_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["max_climb_rate_mps"], 4.0);
+    assert_eq!(body["max_descent_rate_mps"], 5.0);
+    assert_eq!(body["turn_rate_deg_s"], 60.0);
+
+    let missing_req = Request::builder()
+        .method("GET")
+        .uri("/v1/admin/drones/DRONE_NONE/performance")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(missing_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn commands_a_drone_does_not_accept_are_refused() {
+    let (app, state) = setup_app().await;
+
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/register")
+        .header("content-type", "application/json")
+        .header("X-Registration-Token", "test-registration-token")
+        .body(Body::from(
+            json!({
+                "drone_id": "DRONE_CAPS",
+                "capabilities": {
+                    "supports_hold": false,
+                    "max_climb_rate_mps": 1.5,
+                    "rid_module": true
+                }
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let res = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = read_json(res).await;
+    assert_eq!(body["capabilities"]["supports_reroute"], true);
+    assert_eq!(body["capabilities"]["supports_hold"], false);
+
+    let command = |command: Value| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/commands")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(command.to_string()))
+            .unwrap()
+    };
+    let res = app
+        .clone()
+        .oneshot(command(json!({
+            "drone_id": "DRONE_CAPS",
+            "type": "HOLD",
+            "duration_secs": 30
+        })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let res = app
+        .clone()
+        .oneshot(command(json!({ "drone_id": "DRONE_CAPS", "type": "LAND" })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // Capabilities survive a reload and keep the conflict loop from commanding a HOLD.
+    state.load_from_database().await.unwrap();
+    let drone = state.get_drone("DRONE_CAPS").expect("drone");
+    let capabilities = drone.capabilities.expect("capabilities");
+    assert!(capabilities.rid_module);
+    assert!(!state.drone_can_hold("DRONE_CAPS", 0.0));
+    let violations = crate::loops::conflict_loop::envelope_violations(
+        &atc_core::models::CommandType::Hold { duration_secs: 15 },
+        &drone,
+        None,
+        0.0,
+    );
+    assert_eq!(violations.len(), 1);
+}
+
+#[tokio::test]
+async fn surveillance_divergence_raises_integrity_advisory() {
+    use crate::state::ExternalTraffic;
+
+    let (app, state) = setup_app_with(|config| {
+        config.altitude_reference = crate::altitude::AltitudeReference::Amsl;
+        config.geoid_offset_m = 0.0;
+        config.terrain_require = false;
+    })
+    .await;
+
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/register")
+        .header("content-type", "application/json")
+        .header("X-Registration-Token", "test-registration-token")
+        .body(Body::from(json!({ "drone_id": "DRONE_RID" }).to_string()))
+        .unwrap();
+    let res = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let token = read_json(res).await["session_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let telemetry_req = Request::builder()
+        .method("POST")
+        .uri("/v1/telemetry")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(
+            json!({
+                "drone_id": "DRONE_RID",
+                "lat": 33.69,
+                "lon": -117.82,
+                "altitude_m": 60.0,
+                "heading_deg": 0.0,
+                "speed_mps": 0.0,
+                "timestamp": Utc::now().to_rfc3339()
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let res = app.clone().oneshot(telemetry_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+    let drone = state.get_drone("DRONE_RID").expect("drone");
+
+    let rid_track = |lat: f64| ExternalTraffic {
+        traffic_id: "RID-DRONE_RID".to_string(),
+        source: "rid".to_string(),
+        lat,
+        lon: -117.82,
+        altitude_m: drone.altitude_m,
+        heading_deg: 0.0,
+        speed_mps: 0.0,
+        last_update: drone.last_update,
+        category: Default::default(),
+        quality: None,
+        extrapolated_s: 0.0,
+    };
+
+    // Remote ID places the drone ~550 m north of where its telemetry says it is.
+    state.upsert_external_traffic(rid_track(33.695)).await;
+    let mut active = std::collections::HashSet::new();
+    crate::surveillance::monitor_surveillance(&state, &mut active);
+    let advisory = state
+        .get_daa_advisories()
+        .into_iter()
+        .find(|advisory| advisory.advisory_id == "surveillance-DRONE_RID")
+        .expect("surveillance advisory");
+    assert_eq!(advisory.source, "surveillance");
+    assert_eq!(advisory.related_id.as_deref(), Some("RID-DRONE_RID"));
+    assert!(matches!(
+        advisory.severity,
+        atc_core::models::DaaSeverity::Critical
+    ));
+    assert!(!advisory.resolved);
+
+    // Once the sources agree again, the advisory resolves.
+    state.upsert_external_traffic(rid_track(33.69)).await;
+    crate::surveillance::monitor_surveillance(&state, &mut active);
+    assert!(active.is_empty());
+    assert!(state
+        .get_daa_advisories()
+        .iter()
+        .any(|advisory| advisory.advisory_id == "surveillance-DRONE_RID" && advisory.resolved));
+}
+
+#[tokio::test]
+async fn home_tether_triggers_return_home_and_limits_landing_points() {
+    let (app, state) = setup_app_with(|config| {
+        config.altitude_reference = crate::altitude::AltitudeReference::Amsl;
+        config.geoid_offset_m = 0.0;
+        config.terrain_require = false;
+    })
+    .await;
+
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/register")
+        .header("content-type", "application/json")
+        .header("X-Registration-Token", "test-registration-token")
+        .body(Body::from(
+            json!({
+                "drone_id": "DRONE_HOME",
+                "home": {
+                    "lat": 33.68,
+                    "lon": -117.82,
+                    "altitude_m": 20.0,
+                    "tether_radius_m": 500.0,
+                    "auto_rth": true
+                }
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let res = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = read_json(res).await;
+    assert_eq!(body["home"]["tether_radius_m"], 500.0);
+    let token = body["session_token"].as_str().unwrap().to_string();
+
+    let invalid_req = Request::builder()
+        .method("PUT")
+        .uri("/v1/admin/drones/DRONE_HOME/home")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(
+            json!({ "lat": 33.68, "lon": -117.82, "altitude_m": 20.0, "auto_rth": true })
+                .to_string(),
+        ))
+        .unwrap();
+    let res = app.clone().oneshot(invalid_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    // Plans must stay inside the tether and end back near home.
+    let plan = |end_lat: f64| FlightPlanRequest {
+        drone_id: "DRONE_HOME".to_string(),
+        owner_id: None,
+        waypoints: Some(vec![
+            Waypoint {
+                lat: 33.68,
+                lon: -117.82,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: end_lat,
+                lon: -117.82,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+        ]),
+        trajectory_log: None,
+        metadata: None,
+        origin: None,
+        destination: None,
+        departure_time: None,
+    };
+    let violations = crate::api::flights::validate_route(&state, &plan(33.6803)).await;
+    assert!(violations.is_empty(), "{:?}", violations);
+    let violations = crate::api::flights::validate_route(&state, &plan(33.683)).await;
+    assert_eq!(violations.len(), 1, "{:?}", violations);
+    assert_eq!(violations[0]["type"], "landing_point");
+    let violations = crate::api::flights::validate_route(&state, &plan(33.69)).await;
+    assert!(violations.iter().any(|v| v["type"] == "tether"));
+
+    // ~1.1 km north of home: beyond the tether.
+    let telemetry_req = Request::builder()
+        .method("POST")
+        .uri("/v1/telemetry")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(
+            json!({
+                "drone_id": "DRONE_HOME",
+                "lat": 33.69,
+                "lon": -117.82,
+                "altitude_m": 60.0,
+                "heading_deg": 0.0,
+                "speed_mps": 8.0,
+                "timestamp": Utc::now().to_rfc3339()
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let res = app.clone().oneshot(telemetry_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+
+    let mut active = std::collections::HashSet::new();
+    crate::tether::monitor_tethers(&state, &mut active).await;
+    let advisory = state
+        .get_daa_advisories()
+        .into_iter()
+        .find(|advisory| advisory.advisory_id == "tether-DRONE_HOME")
+        .expect("tether advisory");
+    assert_eq!(advisory.action, "return_home");
+    assert!(!advisory.resolved);
+    let commands = state.get_pending_commands("DRONE_HOME");
+    assert_eq!(commands.len(), 1);
+    match &commands[0].command_type {
+        atc_core::models::CommandType::Reroute { waypoints, .. } => {
+            assert_eq!(waypoints.len(), 2);
+            assert_eq!(waypoints[0].altitude_m, 60.0);
+            assert_eq!(waypoints[1].altitude_m, 20.0);
+        }
+        other => panic!("expected a reroute, got {:?}", other),
+    }
+
+    // Back inside the tether, the advisory resolves.
+    state
+        .set_drone_home(
+            "DRONE_HOME",
+            atc_core::DroneHome {
+                lat: 33.68,
+                lon: -117.82,
+                altitude_m: 20.0,
+                tether_radius_m: Some(2000.0),
+                auto_rth: false,
+            },
+        )
+        .await
+        .unwrap();
+    crate::tether::monitor_tethers(&state, &mut active).await;
+    assert!(active.is_empty());
+    assert!(state
+        .get_daa_advisories()
+        .iter()
+        .any(|advisory| advisory.advisory_id == "tether-DRONE_HOME" && advisory.resolved));
+
+    // Homes survive a reload from the database.
+    state.load_from_database().await.unwrap();
+    let get_req = Request::builder()
+        .method("GET")
+        .uri("/v1/admin/drones/DRONE_HOME/home")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(get_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["tether_radius_m"], 2000.0);
+}
+
+#[tokio::test]
+async fn rehearsal_reports_conflicts_and_geofence_entries() {
+    use atc_core::models::{Geofence, GeofenceType, TrajectoryPoint};
+    use atc_core::spatial::offset_position;
+
+    let (app, state) = setup_app().await;
+    let origin = (33.68, -117.82);
+    let departure = Utc::now() + chrono::Duration::minutes(10);
+    // Both plans cross the point 300 m north of the origin at t = 30 s.
+    let plan = |flight_id: &str, drone_id: &str, from: (f64, f64), to: (f64, f64)| {
+        let point = |(north_m, east_m): (f64, f64), t: f64| {
+            let (lat, lon) = offset_position(origin.0, origin.1, north_m, east_m);
+            TrajectoryPoint {
+                lat,
+                lon,
+                altitude_m: 60.0,
+                time_offset_s: Some(t),
+            }
+        };
+        FlightPlan {
+            flight_id: flight_id.to_string(),
+            drone_id: drone_id.to_string(),
+            owner_id: None,
+            waypoints: Vec::new(),
+            trajectory_log: Some(vec![point(from, 0.0), point(to, 60.0)]),
+            metadata: None,
+            status: FlightStatus::Approved,
+            departure_time: departure,
+            arrival_time: None,
+            created_at: Utc::now(),
+        }
+    };
+    state
+        .add_flight_plan(plan(
+            "FLIGHT-REHEARSE",
+            "DRONE_R1",
+            (0.0, 0.0),
+            (600.0, 0.0),
+        ))
+        .await
+        .expect("add plan");
+    state
+        .add_flight_plan(plan(
+            "FLIGHT-CROSS",
+            "DRONE_R2",
+            (300.0, 300.0),
+            (300.0, -300.0),
+        ))
+        .await
+        .expect("add plan");
+    let corner = |north_m: f64, east_m: f64| {
+        let (lat, lon) = offset_position(origin.0, origin.1, north_m, east_m);
+        [lat, lon]
+    };
+    state
+        .add_geofence(Geofence {
+            id: "tfr-rehearsal".to_string(),
+            name: "Rehearsal TFR".to_string(),
+            geofence_type: GeofenceType::TemporaryRestriction,
+            polygon: vec![
+                corner(450.0, -50.0),
+                corner(450.0, 50.0),
+                corner(520.0, 50.0),
+                corner(520.0, -50.0),
+                corner(450.0, -50.0),
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 120.0,
+            active: true,
+            created_at: Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
+        })
+        .await
+        .expect("add geofence");
+
+    let rehearse = |flight_id: &str, body: Value| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/v1/flights/{}/rehearse", flight_id))
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let res = app
+        .clone()
+        .oneshot(rehearse("FLIGHT-REHEARSE", json!({ "speed": 2 })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["clear"], false);
+    assert_eq!(body["samples"], 31);
+    let conflicts = body["conflicts"].as_array().expect("conflicts");
+    assert_eq!(conflicts.len(), 1, "{}", body);
+    assert_eq!(conflicts[0]["other_drone_id"], "DRONE_R2");
+    assert_eq!(conflicts[0]["other_flight_id"], "FLIGHT-CROSS");
+    assert_eq!(conflicts[0]["peak_severity"], "critical");
+    let issues = body["conformance_issues"].as_array().expect("issues");
+    assert_eq!(issues.len(), 1, "{}", body);
+    assert_eq!(issues[0]["type"], "geofence");
+    assert_eq!(issues[0]["related_id"], "tfr-rehearsal");
+
+    // Live conflict state is untouched.
+    assert!(state.get_conflicts().is_empty());
+
+    let res = app
+        .clone()
+        .oneshot(rehearse("FLIGHT-REHEARSE", json!({ "speed": 0 })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let res = app
+        .oneshot(rehearse("FLIGHT-MISSING", json!({})))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn weather_forecast_replaces_cells_and_rejects_invalid_ones() {
+    let (app, state) = setup_app().await;
+    let now = Utc::now();
+    let weather = |cells: Value| {
+        Request::builder()
+            .method("PUT")
+            .uri("/v1/admin/weather")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(json!({ "cells": cells }).to_string()))
+            .unwrap()
+    };
+    let cell = |valid_to: chrono::DateTime<Utc>| {
+        json!({
+            "min_lat": 33.68,
+            "min_lon": -117.83,
+            "max_lat": 33.69,
+            "max_lon": -117.82,
+            "valid_from": (now - chrono::Duration::hours(1)).to_rfc3339(),
+            "valid_to": valid_to.to_rfc3339(),
+            "precip_mm": 6.0
+        })
+    };
+
+    let res = app
+        .clone()
+        .oneshot(weather(json!([
+            cell(now + chrono::Duration::hours(1)),
+            cell(now - chrono::Duration::minutes(1))
+        ])))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["cells"], 2);
+    // Expired cells are no longer reported or planned around.
+    assert_eq!(state.weather_cells().len(), 1);
+
+    let list_req = Request::builder()
+        .uri("/v1/admin/weather")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.clone().oneshot(list_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["cells"].as_array().map(Vec::len), Some(1));
+    assert_eq!(body["cells"][0]["precip_mm"], 6.0);
+
+    let mut inverted = cell(now + chrono::Duration::hours(1));
+    inverted["max_lat"] = json!(33.6);
+    let res = app
+        .clone()
+        .oneshot(weather(json!([inverted])))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = read_json(res).await;
+    assert!(body["details"][0]
+        .as_str()
+        .is_some_and(|detail| detail.starts_with("cells[0]:")));
+    assert_eq!(state.weather_cells().len(), 1);
+}
+
+#[tokio::test]
+async fn c2_coverage_is_persisted_and_validated() {
+    let (app, state) = setup_app().await;
+    let coverage = |areas: Value| {
+        Request::builder()
+            .method("PUT")
+            .uri("/v1/admin/coverage")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(json!({ "areas": areas }).to_string()))
+            .unwrap()
+    };
+    let area = |id: &str| {
+        json!({
+            "id": id,
+            "name": "Tower",
+            "link_type": "lte",
+            "polygon": [[33.68, -117.83], [33.68, -117.82], [33.69, -117.82], [33.69, -117.83]]
+        })
+    };
+
+    let res = app
+        .clone()
+        .oneshot(coverage(json!([area("tower-1"), area("tower-2")])))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["areas"], 2);
+
+    // Survives a reload from the database.
+    state.load_from_database().await.expect("reload");
+    let list_req = Request::builder()
+        .uri("/v1/admin/coverage")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.clone().oneshot(list_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["areas"].as_array().map(Vec::len), Some(2));
+    assert_eq!(body["areas"][0]["id"], "tower-1");
+    assert_eq!(body["areas"][0]["link_type"], "lte");
+
+    let res = app
+        .clone()
+        .oneshot(coverage(json!([area("tower-1"), area("tower-1")])))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = read_json(res).await;
+    assert_eq!(body["details"][0], "areas[1]: duplicate id 'tower-1'");
+    assert_eq!(state.coverage_areas().len(), 2);
+}
+
+#[tokio::test]
+async fn lists_predicted_geofence_incursions() {
+    use atc_core::models::{Geofence, GeofenceType, Telemetry};
+    use atc_core::spatial::offset_position;
+
+    let (app, state) = setup_app().await;
+    let origin = (33.6846, -117.8265);
+    let corner = |north_m: f64, east_m: f64| {
+        let (lat, lon) = offset_position(origin.0, origin.1, north_m, east_m);
+        [lat, lon]
+    };
+    state
+        .add_geofence(Geofence {
+            id: "nfz-stadium".to_string(),
+            name: "Stadium".to_string(),
+            geofence_type: GeofenceType::NoFlyZone,
+            polygon: vec![
+                corner(100.0, -50.0),
+                corner(100.0, 50.0),
+                corner(200.0, 50.0),
+                corner(200.0, -50.0),
+                corner(100.0, -50.0),
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 120.0,
+            active: true,
+            created_at: Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
+        })
+        .await
+        .expect("add geofence");
+
+    // Northbound at 10 m/s, 100 m short of the fence; a second drone flies away from it.
+    for (drone_id, owner_id, heading_deg) in
+        [("DRONE_N", "owner-a", 0.0), ("DRONE_S", "owner-b", 180.0)]
+    {
+        state
+            .update_telemetry(Telemetry {
+                drone_id: drone_id.to_string(),
+                owner_id: Some(owner_id.to_string()),
+                lat: origin.0,
+                lon: origin.1,
+                altitude_m: 50.0,
+                velocity_x: 0.0,
+                velocity_y: 0.0,
+                velocity_z: 0.0,
+                heading_deg,
+                speed_mps: 10.0,
+                timestamp: Utc::now(),
+            })
+            .await;
+    }
+    state.refresh_conflicts().await;
+
+    let list = |uri: &str| {
+        Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let breaches = read_json(
+        app.clone()
+            .oneshot(list("/v1/conflicts/geofences"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(breaches.as_array().map(Vec::len), Some(1), "{}", breaches);
+    assert_eq!(breaches[0]["drone_id"], "DRONE_N");
+    assert_eq!(breaches[0]["geofence_id"], "nfz-stadium");
+    assert_eq!(breaches[0]["severity"], "warning");
+    let time_to_breach_s = breaches[0]["time_to_breach_s"].as_f64().unwrap();
+    assert!(
+        (9.0..=11.0).contains(&time_to_breach_s),
+        "{}",
+        time_to_breach_s
+    );
+
+    let filtered = read_json(
+        app.oneshot(list("/v1/conflicts/geofences?owner_id=owner-b"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(filtered, json!([]));
+}
+
+#[tokio::test]
+async fn low_quality_traffic_is_scored_and_downgraded() {
+    use crate::state::ExternalTraffic;
+    use atc_core::models::Telemetry;
+    use atc_core::spatial::offset_position;
+
+    let (app, state) = setup_app_with(|config| {
+        config.traffic_quality_mode = atc_core::TrackQualityMode::Downgrade;
+    })
+    .await;
+    let origin = (33.6846, -117.8265);
+    let now = Utc::now();
+
+    // A ghost hopping a kilometre between reports, ending on top of a local drone, and a
+    // healthy track reporting every 2 s well away from it.
+    for step in 0..5i64 {
+        let at = now - chrono::Duration::seconds(8 - 2 * step);
+        let (ghost_lat, ghost_lon) = offset_position(
+            origin.0,
+            origin.1,
+            if step % 2 == 0 { 0.0 } else { 1_000.0 },
+            0.0,
+        );
+        let (good_lat, good_lon) =
+            offset_position(origin.0, origin.1, 5_000.0 + 30.0 * step as f64, 0.0);
+        for (traffic_id, lat, lon) in [
+            ("RID-GHOST", ghost_lat, ghost_lon),
+            ("RID-GOOD", good_lat, good_lon),
+        ] {
+            state
+                .upsert_external_traffic(ExternalTraffic {
+                    traffic_id: traffic_id.to_string(),
+                    source: "rid".to_string(),
+                    lat,
+                    lon,
+                    altitude_m: 50.0,
+                    heading_deg: 0.0,
+                    speed_mps: 0.0,
+                    last_update: at,
+                    category: Default::default(),
+                    quality: None,
+                    extrapolated_s: 0.0,
+                })
+                .await;
+        }
+    }
+    state
+        .update_telemetry(Telemetry {
+            drone_id: "DRONE_LOCAL".to_string(),
+            owner_id: None,
+            lat: origin.0,
+            lon: origin.1,
+            altitude_m: 50.0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            velocity_z: 0.0,
+            heading_deg: 0.0,
+            speed_mps: 0.0,
+            timestamp: now,
+        })
+        .await;
+    state.refresh_conflicts().await;
+
+    let conflicts = state.get_conflicts();
+    assert_eq!(conflicts.len(), 1, "{:?}", conflicts);
+    assert_eq!(conflicts[0].severity, atc_core::ConflictSeverity::Info);
+    assert!(conflicts[0].low_quality_track);
+
+    let list = |uri: &str| {
+        Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let traffic = read_json(
+        app.clone()
+            .oneshot(list("/v1/traffic?source=external"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let quality = |id: &str| {
+        traffic
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["drone_id"] == id)
+            .map(|entry| entry["quality"].clone())
+            .unwrap()
+    };
+    assert_eq!(quality("RID-GHOST")["position_jumps"], 4);
+    assert!(quality("RID-GHOST")["score"].as_f64().unwrap() < 0.4);
+    assert!(quality("RID-GOOD")["score"].as_f64().unwrap() > 0.8);
+
+    let filtered = read_json(
+        app.oneshot(list("/v1/traffic?source=external&min_quality=0.5"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(filtered.as_array().map(Vec::len), Some(1));
+    assert_eq!(filtered[0]["drone_id"], "RID-GOOD");
+}
+
+#[tokio::test]
+async fn lost_drones_are_dead_reckoned_with_widened_separation() {
+    use atc_core::models::Telemetry;
+    use atc_core::spatial::offset_position;
+    use atc_core::{ConflictSeverity, DeadReckoningConfig, DeadReckoningMode};
+
+    let (_app, state) = setup_app_with(|config| {
+        config.lost_dead_reckoning = DeadReckoningConfig {
+            mode: DeadReckoningMode::Velocity,
+            horizon_s: 25.0,
+            uncertainty_growth_mps: 5.0,
+        };
+    })
+    .await;
+    let origin = (33.6846, -117.8265);
+    let now = Utc::now();
+    let report = |drone_id: &str, north_m: f64, east_m: f64, speed_mps: f64, seconds_ago: i64| {
+        let (lat, lon) = offset_position(origin.0, origin.1, north_m, east_m);
+        Telemetry {
+            drone_id: drone_id.to_string(),
+            owner_id: None,
+            lat,
+            lon,
+            altitude_m: 50.0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            velocity_z: 0.0,
+            heading_deg: 90.0,
+            speed_mps,
+            timestamp: now - chrono::Duration::seconds(seconds_ago),
+        }
+    };
+
+    // LOST went silent 20 s ago flying east at 10 m/s; by now it should be 120 m south of
+    // HOVER, outside the 50 m minimum but inside it once widened by 100 m of uncertainty.
+    // GONE went silent right next to HOVER, but longer ago than the 25 s horizon.
+    state
+        .update_telemetry(report("LOST", 0.0, 0.0, 10.0, 20))
+        .await;
+    state
+        .update_telemetry(report("HOVER", 120.0, 200.0, 0.0, 0))
+        .await;
+    state
+        .update_telemetry(report("GONE", 125.0, 200.0, 0.0, 30))
+        .await;
+
+    let mut lost = state.check_timeouts().await;
+    lost.sort();
+    assert_eq!(lost, vec!["GONE".to_string(), "LOST".to_string()]);
+    state.dead_reckon_lost_drones().await;
+    state.refresh_conflicts().await;
+
+    let conflicts = state.get_conflicts();
+    let involves = |drone_id: &str| {
+        conflicts
+            .iter()
+            .find(|c| c.drone1_id == drone_id || c.drone2_id == drone_id)
+    };
+    let conflict = involves("LOST").expect("dead-reckoned conflict");
+    assert!(matches!(conflict.severity, ConflictSeverity::Critical));
+    assert!(
+        (conflict.distance_m - 120.0).abs() < 5.0,
+        "{}",
+        conflict.distance_m
+    );
+    assert!(involves("GONE").is_none());
+}
+
+#[tokio::test]
+async fn external_tracks_are_smoothed_and_extrapolated() {
+    use crate::state::ExternalTraffic;
+    use atc_core::spatial::{haversine_distance, offset_position};
+
+    let (_app, state) = setup_app().await;
+    let origin = (33.6846, -117.8265);
+    let now = Utc::now();
+    let report = |north_m: f64, seconds_ago: i64| {
+        let (lat, lon) = offset_position(origin.0, origin.1, north_m, 0.0);
+        ExternalTraffic {
+            traffic_id: "RID-SLOW".to_string(),
+            source: "rid".to_string(),
+            lat,
+            lon,
+            altitude_m: 50.0,
+            heading_deg: 0.0,
+            speed_mps: 20.0,
+            last_update: now - chrono::Duration::seconds(seconds_ago),
+            category: Default::default(),
+            quality: None,
+            extrapolated_s: 0.0,
+        }
+    };
+
+    // Flying north at 20 m/s; the last report is 10 m ahead of where the track should be.
+    state.upsert_external_traffic(report(0.0, 4)).await;
+    state.upsert_external_traffic(report(50.0, 2)).await;
+    let track = state.get_external_traffic().pop().expect("track");
+    let (reported_lat, reported_lon) = offset_position(origin.0, origin.1, 50.0, 0.0);
+    let (expected_lat, expected_lon) = offset_position(origin.0, origin.1, 45.0, 0.0);
+    assert!(haversine_distance(track.lat, track.lon, reported_lat, reported_lon) > 4.0);
+    assert!(haversine_distance(track.lat, track.lon, expected_lat, expected_lon) < 1.0);
+    assert!(track.speed_mps > 20.0);
+    assert_eq!(track.extrapolated_s, 0.0);
+    let quality = track.quality.expect("quality");
+    assert!((quality.residual_m.expect("residual") - 10.0).abs() < 0.5);
+
+    // Between reports the track keeps moving.
+    state.extrapolate_external_traffic().await;
+    let moved = state.get_external_traffic().pop().expect("track");
+    assert!(moved.extrapolated_s >= 2.0);
+    assert_eq!(moved.last_update, track.last_update);
+    let ahead_m = haversine_distance(origin.0, origin.1, moved.lat, moved.lon);
+    assert!(ahead_m > 85.0 && ahead_m < 110.0, "{ahead_m}");
+}
+
+#[tokio::test]
+async fn violations_carry_codes_and_render_in_the_configured_locale() {
+    use atc_core::messages::{codes, MessageCatalog};
+    use std::collections::HashMap;
+
+    let (app, _state) = setup_app_with(|config| {
+        config.messages.add_catalog(MessageCatalog::new(
+            "de",
+            HashMap::from([(
+                codes::ROUTE_TOO_SHORT.to_string(),
+                "Mindestens 2 Wegpunkte erforderlich".to_string(),
+            )]),
+        ));
+        config.messages.set_default_locale("de");
+    })
+    .await;
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/compliance/evaluate")
+                .header("content-type", "application/json")
+                .header("authorization", "Bearer test-admin-token")
+                .body(Body::from(
+                    json!({
+                        "waypoints": [{ "lat": 33.68, "lon": -117.82, "altitude_m": 60.0 }]
+                    })
+                    .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    let violation = &body["violations"][0];
+    assert_eq!(violation["message_code"]["code"], codes::ROUTE_TOO_SHORT);
+    assert_eq!(violation["message"], "Mindestens 2 Wegpunkte erforderlich");
+
+    let catalog = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+    let body = read_json(app.clone().oneshot(catalog("/v1/messages")).await.unwrap()).await;
+    assert_eq!(body["locale"], "de");
+    assert_eq!(body["locales"], json!(["de", "en"]));
+    assert_eq!(
+        body["messages"][codes::ROUTE_TOO_SHORT],
+        "Mindestens 2 Wegpunkte erforderlich"
+    );
+    // Codes without a German template fall back to English.
+    assert_eq!(
+        body["messages"][codes::ROUTE_MISSING],
+        "Route is required for compliance checks"
+    );
+    let body = read_json(
+        app.oneshot(catalog("/v1/messages?locale=en"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(
+        body["messages"][codes::ROUTE_TOO_SHORT],
+        "At least 2 waypoints are required"
+    );
+}
+
+#[tokio::test]
+async fn imperial_fields_are_converted_at_the_api_boundary() {
+    let (app, _state) = setup_app().await;
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap()
+    };
+    let zone = |altitudes: Value| {
+        let mut zone = json!({
+            "name": "Stadium",
+            "geofence_type": "no_fly_zone",
+            "polygon": [
+                [33.0, -117.0],
+                [33.0, -116.9],
+                [33.1, -116.9],
+                [33.1, -117.0],
+                [33.0, -117.0]
+            ]
+        });
+        zone.as_object_mut()
+            .unwrap()
+            .extend(altitudes.as_object().unwrap().clone());
+        zone
+    };
+
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/geofences",
+            Some(zone(
+                json!({ "lower_altitude_ft": 0, "upper_altitude_ft": 400 }),
+            )),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let created = read_json(res).await;
+    assert!((created["upper_altitude_m"].as_f64().unwrap() - 121.92).abs() < 1e-6);
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/geofences?units=imperial", None))
+        .await
+        .unwrap();
+    let listed = read_json(res).await;
+    assert!((listed[0]["upper_altitude_ft"].as_f64().unwrap() - 400.0).abs() < 1e-6);
+    assert!(listed[0].get("upper_altitude_m").is_none());
+
+    for (altitude_ft, inside) in [(200, true), (1_000, false)] {
+        let uri = format!("/v1/geofences/check?lat=33.05&lon=-116.95&altitude_ft={altitude_ft}");
+        let res = app
+            .clone()
+            .oneshot(request("GET", &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(read_json(res).await["inside_geofence"], inside);
+    }
+
+    // Both forms of a field, non-numeric values and unknown unit systems are rejected.
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/geofences",
+            Some(zone(
+                json!({ "upper_altitude_ft": 400, "upper_altitude_m": 120 }),
+            )),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = read_json(res).await;
+    assert_eq!(
+        body["details"][0],
+        "upper_altitude_ft: give either upper_altitude_ft or upper_altitude_m, not both"
+    );
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/geofences",
+            Some(zone(json!({ "upper_altitude_ft": "400ft" }))),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let res = app
+        .oneshot(request("GET", "/v1/geofences?units=furlongs", None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn api_client_round_trips_against_the_server() {
+    use atc_api_client::{ApiError, AtcApiClient, ConflictHistoryQuery, IssueCommandRequest};
+    use atc_core::models::CommandType;
+    use atc_core::{CreateGeofenceRequest, GeofenceType};
+
+    let (app, state) = setup_app().await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+    let client = AtcApiClient::new(format!("http://{}", addr)).with_admin_token("test-admin-token");
+
+    let geofence = client
+        .create_geofence(&CreateGeofenceRequest {
+            name: "Depot".to_string(),
+            geofence_type: GeofenceType::NoFlyZone,
+            polygon: vec![
+                [33.68, -117.83],
+                [33.68, -117.82],
+                [33.69, -117.82],
+                [33.69, -117.83],
+                [33.68, -117.83],
+            ],
+            lower_altitude_m: Some(0.0),
+            upper_altitude_m: Some(120.0),
+            breach_response: None,
+            schedule: None,
+        })
+        .await
+        .expect("create geofence");
+    let inside = client
+        .check_point(33.685, -117.825, Some(50.0))
+        .await
+        .expect("check point");
+    assert_eq!(inside.geofence_ids, vec![geofence.id.clone()]);
+
+    state
+        .register_drone("DRONE_API", None)
+        .await
+        .expect("register drone");
+    let issued = client
+        .issue_command(&IssueCommandRequest::new(
+            "DRONE_API",
+            CommandType::Hold { duration_secs: 30 },
+        ))
+        .await
+        .expect("issue command");
+    let commands = client.list_commands().await.expect("list commands");
+    assert!(commands
+        .iter()
+        .any(|command| command.command_id == issued.command_id));
+    assert!(client
+        .conflict_history(&ConflictHistoryQuery::default())
+        .await
+        .expect("conflict history")
+        .is_empty());
+
+    client
+        .delete_geofence(&geofence.id)
+        .await
+        .expect("delete geofence");
+    let missing = client.get_geofence(&geofence.id).await.unwrap_err();
+    assert_eq!(missing.status(), Some(reqwest::StatusCode::NOT_FOUND));
+
+    let unauthorized = AtcApiClient::new(format!("http://{}", addr))
+        .list_drones(None)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        unauthorized,
+        ApiError::Status { status, .. } if status == reqwest::StatusCode::UNAUTHORIZED
+    ));
+}
+
+#[tokio::test]
+async fn scheduled_geofence_only_blocks_flights_during_its_window() {
+    let (app, state) = setup_app().await;
+    let window_start = Utc::now() + chrono::Duration::hours(2);
+    let create_req = Request::builder()
+        .method("POST")
+        .uri("/v1/geofences")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(
+            json!({
+                "name": "Stadium TFR",
+                "geofence_type": "temporary_restriction",
+                "polygon": [
+                    [33.684, -117.83],
+                    [33.684, -117.81],
+                    [33.686, -117.81],
+                    [33.686, -117.83],
+                    [33.684, -117.83]
+                ],
+                "schedule": {
+                    "start": window_start,
+                    "end": window_start + chrono::Duration::hours(3),
+                    "recurrence": "daily"
+                }
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let res = app.clone().oneshot(create_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body = read_json(res).await;
+    assert_eq!(body["schedule"]["recurrence"], "daily");
+
+    let plan = |departure_time| FlightPlanRequest {
+        drone_id: "DRONE_TFR".to_string(),
+        owner_id: None,
+        waypoints: Some(vec![
+            Waypoint {
+                lat: 33.68,
+                lon: -117.82,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: 33.69,
+                lon: -117.82,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+        ]),
+        trajectory_log: None,
+        metadata: None,
+        origin: None,
+        destination: None,
+        departure_time,
+    };
+    let geofence_violations = |violations: Vec<Value>| {
+        violations
+            .into_iter()
+            .filter(|v| v["type"] == "geofence")
+            .count()
+    };
+
+    let violations = crate::api::flights::validate_route(&state, &plan(None)).await;
+    assert_eq!(geofence_violations(violations), 0);
+    let during = window_start + chrono::Duration::hours(1);
+    let violations = crate::api::flights::validate_route(&state, &plan(Some(during))).await;
+    assert_eq!(geofence_violations(violations), 1);
+    let next_day = during + chrono::Duration::days(1);
+    let violations = crate::api::flights::validate_route(&state, &plan(Some(next_day))).await;
+    assert_eq!(geofence_violations(violations), 1);
+    assert!(state
+        .check_point_in_geofences(33.685, -117.82, 50.0)
+        .is_empty());
+
+    let invalid_req = Request::builder()
+        .method("POST")
+        .uri("/v1/geofences")
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::from(
+            json!({
+                "name": "Backwards",
+                "geofence_type": "no_fly_zone",
+                "polygon": [
+                    [33.684, -117.83],
+                    [33.684, -117.81],
+                    [33.686, -117.81],
+                    [33.684, -117.83]
+                ],
+                "schedule": { "start": window_start, "end": window_start }
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let res = app.oneshot(invalid_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn msa_grid_is_published_and_checked_during_validation() {
+    let (app, state) = setup_app().await;
+    let msa_req = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+    let res = app.clone().oneshot(msa_req("/v1/msa")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // Flat 20 m terrain with an 80 m mast in the middle of the route.
+    let bounds = atc_core::MsaBounds::parse("33.67,-117.83,33.70,-117.81").unwrap();
+    let mast = atc_core::route_engine::RouteObstacle {
+        lat: 33.685,
+        lon: -117.82,
+        radius_m: 10.0,
+        height_m: Some(80.0),
+        polygon: None,
+    };
+    state.set_msa_grid(atc_core::MsaGrid::build(
+        &bounds,
+        100.0,
+        30.0,
+        &[mast],
+        |_, _| 20.0,
+        Utc::now(),
+    ));
+
+    let res = app
+        .clone()
+        .oneshot(msa_req("/v1/msa?bbox=33.684,-117.821,33.686,-117.819"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    let cells = body["msa_m"].as_array().expect("cells");
+    assert_eq!(
+        cells.len(),
+        body["rows"].as_u64().unwrap() as usize * body["cols"].as_u64().unwrap() as usize
+    );
+    assert!(
+        cells.iter().any(|cell| cell.as_f64() == Some(130.0)),
+        "{}",
+        body
+    );
+    let res = app
+        .clone()
+        .oneshot(msa_req("/v1/msa?bbox=nope"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let plan = |cruise_m: f64| FlightPlanRequest {
+        drone_id: "DRONE_MSA".to_string(),
+        owner_id: None,
+        waypoints: Some(
+            [
+                (33.675, 20.0),
+                (33.675, cruise_m),
+                (33.695, cruise_m),
+                (33.695, 20.0),
+            ]
+            .into_iter()
+            .map(|(lat, altitude_m)| Waypoint {
+                lat,
+                lon: -117.82,
+                altitude_m,
+                speed_mps: None,
+            })
+            .collect(),
+        ),
+        trajectory_log: None,
+        metadata: None,
+        origin: None,
+        destination: None,
+        departure_time: None,
+    };
+    let msa_violations = |violations: Vec<Value>| {
+        violations
+            .into_iter()
+            .filter(|v| v["type"] == "msa")
+            .collect::<Vec<_>>()
+    };
+    let violations = crate::api::flights::validate_route(&state, &plan(140.0)).await;
+    assert!(msa_violations(violations).is_empty());
+    let violations = crate::api::flights::validate_route(&state, &plan(100.0)).await;
+    let violations = msa_violations(violations);
+    assert_eq!(violations.len(), 1, "{:?}", violations);
+    assert_eq!(violations[0]["segment_index"], 1);
+    assert_eq!(violations[0]["msa_m"], 130.0);
+}
+
+#[tokio::test]
+async fn metrics_report_telemetry_flushes_and_overflow() {
+    use atc_core::models::Telemetry;
+
+    let (app, state) = setup_app_with(|config| {
+        config.telemetry_flush_ms = 50;
+        config.max_overflow_entries = 1;
+    })
+    .await;
+    let (shutdown_tx, _) = tokio::sync::broadcast::channel(1);
+    let persist = tokio::spawn(
+        crate::loops::telemetry_persist_loop::run_telemetry_persist_loop(
+            state.database().cloned().expect("database"),
+            state.clone(),
+            state.take_telemetry_receiver().expect("telemetry receiver"),
+            shutdown_tx.subscribe(),
+        ),
+    );
+    let telemetry = |drone_id: &str| Telemetry {
+        drone_id: drone_id.to_string(),
+        owner_id: None,
+        lat: 33.6846,
+        lon: -117.8265,
+        altitude_m: 50.0,
+        velocity_x: 0.0,
+        velocity_y: 0.0,
+        velocity_z: 0.0,
+        heading_deg: 0.0,
+        speed_mps: 0.0,
+        timestamp: Utc::now(),
+    };
+
+    state.update_telemetry(telemetry("DRONE_A")).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    shutdown_tx.send(()).expect("shutdown");
+    persist.await.expect("persist loop");
+    // With the loop gone the queue is closed: one snapshot fits the overflow map, the next is
+    // dropped.
+    state.update_telemetry(telemetry("DRONE_B")).await;
+    state.update_telemetry(telemetry("DRONE_C")).await;
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/metrics")
+                .header("authorization", "Bearer test-admin-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .expect("read body");
+    let text = String::from_utf8(bytes.to_vec()).expect("utf-8");
+    for line in [
+        "atc_telemetry_flushes_total 1",
+        "atc_telemetry_flush_failures_total 0",
+        "atc_telemetry_rows_written_total 1",
+        "atc_telemetry_overflow_stashed_total 1",
+        "atc_telemetry_overflow_dropped_total 1",
+        "atc_telemetry_flush_interval_ms 50",
+        "atc_telemetry_flush_duration_seconds_count 1",
+    ] {
+        assert!(text.lines().any(|l| l == line), "{:?}\n{}", line, text);
+    }
+}
+
+#[tokio::test]
+async fn openapi_spec_is_served_with_swagger_ui() {
+    let (app, _state) = setup_app().await;
+    let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let res = app.clone().oneshot(get("/openapi.json")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let spec = read_json(res).await;
+    for (path, method) in [
+        ("/v1/flights/plan", "post"),
+        ("/v1/operational_intents/{flight_id}", "put"),
+        ("/v1/geofences/{id}", "delete"),
+        ("/v1/commands/next", "get"),
+        ("/v1/daa", "get"),
+        ("/v1/ws", "get"),
+    ] {
+        assert!(spec["paths"][path][method].is_object(), "{method} {path}");
+    }
+    let schemas = &spec["components"]["schemas"];
+    for schema in ["FlightPlan", "Geofence", "CommandType", "DaaAdvisory"] {
+        assert!(schemas[schema].is_object(), "{schema}");
+    }
+    assert_eq!(
+        spec["paths"]["/v1/flights/plan"]["post"]["security"],
+        json!([{ "bearerAuth": [] }])
+    );
+
+    let res = app.oneshot(get("/docs/")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn event_stream_sends_filtered_drone_and_conflict_events() {
+    use futures::StreamExt;
+
+    let (app, state) = setup_app().await;
+    let get = |uri: &str| {
+        Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(get("/v1/events?types=drone,weather"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .oneshot(get("/v1/events?drone_id=DRONE_1&types=drone,conflict"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()["content-type"].to_str().unwrap(),
+        "text/event-stream"
+    );
+    let mut body = res.into_body().into_data_stream();
+
+    let now = Utc::now();
+    for (drone_id, lat, lon) in [
+        ("DRONE_3", 34.0, -118.0),
+        ("DRONE_1", 33.5, -117.5),
+        ("DRONE_2", 33.5001, -117.5),
+    ] {
+        state
+            .update_telemetry(atc_core::models::Telemetry {
+                drone_id: drone_id.to_string(),
+                owner_id: None,
+                lat,
+                lon,
+                altitude_m: 50.0,
+                velocity_x: 0.0,
+                velocity_y: 0.0,
+                velocity_z: 0.0,
+                heading_deg: 0.0,
+                speed_mps: 0.0,
+                timestamp: now,
+            })
+            .await;
+    }
+    state.refresh_conflicts().await;
+
+    let mut received = String::new();
+    while !(received.contains("event: conflict") && received.contains("event: drone")) {
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .expect("event before timeout")
+            .expect("stream open")
+            .expect("chunk");
+        received.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+    assert!(received.contains("event: drone\ndata: {"), "{}", received);
+    assert!(received.contains("\"DRONE_1\""), "{}", received);
+    assert!(received.contains("\"DRONE_2\""), "{}", received);
+    assert!(!received.contains("DRONE_3"), "{}", received);
+}
+
+#[tokio::test]
+async fn drone_and_traffic_lists_filter_by_bbox_and_status() {
+    use crate::state::ExternalTraffic;
+
+    let (app, state) = setup_app().await;
+    let now = Utc::now();
+    for (drone_id, lat, lon) in [
+        ("DRONE_IRVINE", 33.6846, -117.8265),
+        ("DRONE_TUSTIN", 33.7400, -117.8200),
+        ("DRONE_LA", 34.0522, -118.2437),
+    ] {
+        state
+            .update_telemetry(atc_core::models::Telemetry {
+                drone_id: drone_id.to_string(),
+                owner_id: None,
+                lat,
+                lon,
+                altitude_m: 50.0,
+                velocity_x: 0.0,
+                velocity_y: 0.0,
+                velocity_z: 0.0,
+                heading_deg: 0.0,
+                speed_mps: 0.0,
+                timestamp: now,
+            })
+            .await;
+    }
+    for (traffic_id, lat, lon) in [
+        ("RID-NEAR", 33.6900, -117.8300),
+        ("RID-FAR", 34.0500, -118.2400),
+    ] {
+        state
+            .upsert_external_traffic(ExternalTraffic {
+                traffic_id: traffic_id.to_string(),
+                source: "rid".to_string(),
+                lat,
+                lon,
+                altitude_m: 50.0,
+                heading_deg: 0.0,
+                speed_mps: 0.0,
+                last_update: now,
+                category: Default::default(),
+                quality: None,
+                extrapolated_s: 0.0,
+            })
+            .await;
+    }
+
+    let get = |uri: &str| {
+        Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let ids = |value: Value, field: &str| {
+        let mut ids: Vec<String> = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry[field].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+    let orange_county = "33.6,-117.9,33.8,-117.7";
+
+    let drones = read_json(
+        app.clone()
+            .oneshot(get(&format!("/v1/drones?bbox={}", orange_county)))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(ids(drones, "drone_id"), ["DRONE_IRVINE", "DRONE_TUSTIN"]);
+
+    let drones = read_json(
+        app.clone()
+            .oneshot(get(&format!(
+                "/v1/drones?bbox={}&status=active,holding",
+                orange_county
+            )))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(ids(drones, "drone_id"), ["DRONE_IRVINE", "DRONE_TUSTIN"]);
+    let drones = read_json(
+        app.clone()
+            .oneshot(get("/v1/drones?status=lost"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert!(drones.as_array().unwrap().is_empty());
+
+    let traffic = read_json(
+        app.clone()
+            .oneshot(get(&format!(
+                "/v1/traffic?bbox={}&include_external=true",
+                orange_county
+            )))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(
+        ids(traffic, "drone_id"),
+        ["DRONE_IRVINE", "DRONE_TUSTIN", "RID-NEAR"]
+    );
+
+    for uri in [
+        "/v1/drones?bbox=33.8,-117.9,33.6,-117.7",
+        "/v1/drones?status=flying",
+        "/v1/traffic?bbox=nope",
+    ] {
+        let res = app.clone().oneshot(get(uri)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn telemetry_history_returns_downsampled_track() {
+    let (app, state) = setup_app().await;
+    // Whole 10 s past the epoch, so downsampling buckets line up with the offsets below.
+    let start =
+        chrono::DateTime::from_timestamp((Utc::now().timestamp() / 10 - 60) * 10, 0).unwrap();
+    let telemetry = |offset_s: i64| atc_core::models::Telemetry {
+        drone_id: "DRONE_TRACK".to_string(),
+        owner_id: None,
+        lat: 33.6846 + offset_s as f64 * 1e-5,
+        lon: -117.8265,
+        altitude_m: 60.0,
+        velocity_x: 0.0,
+        velocity_y: 1.0,
+        velocity_z: 0.0,
+        heading_deg: 0.0,
+        speed_mps: 1.0,
+        timestamp: start + chrono::Duration::seconds(offset_s),
+    };
+    state.update_telemetry(telemetry(0)).await;
+
+    let db = state.database().unwrap();
+    let mut tx = db.pool().begin().await.unwrap();
+    for offset_s in 0..60 {
+        let sample = atc_core::models::DroneState::from_telemetry(&telemetry(offset_s));
+        persistence::telemetry_history::insert_sample_tx(&mut tx, &sample)
+            .await
+            .unwrap();
+    }
+    tx.commit().await.unwrap();
+
+    let get = |uri: String| {
+        Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let at = |offset_s: i64| {
+        (start + chrono::Duration::seconds(offset_s))
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+    };
+
+    let track = read_json(
+        app.clone()
+            .oneshot(get("/v1/drones/DRONE_TRACK/telemetry".to_string()))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(track["samples"].as_array().unwrap().len(), 60);
+    assert_eq!(track["truncated"], false);
+
+    let track = read_json(
+        app.clone()
+            .oneshot(get(format!(
+                "/v1/drones/DRONE_TRACK/telemetry?from={}&to={}&downsample=10",
+                at(15),
+                at(45)
+            )))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(track["downsample_s"], 10);
+    let samples = track["samples"].as_array().unwrap();
+    // 15-45 s falls in four 10 s buckets; each keeps its first sample.
+    let times: Vec<&str> = samples
+        .iter()
+        .map(|sample| sample["timestamp"].as_str().unwrap())
+        .collect();
+    assert_eq!(samples.len(), 4, "{:?}", times);
+    assert_eq!(samples[0]["status"], "active");
+    let first = chrono::DateTime::parse_from_rfc3339(times[0]).unwrap();
+    assert_eq!(
+        first.timestamp_millis(),
+        (start + chrono::Duration::seconds(15)).timestamp_millis()
+    );
+
+    for (uri, status) in [
+        (
+            format!(
+                "/v1/drones/DRONE_TRACK/telemetry?from={}&to={}",
+                at(45),
+                at(15)
+            ),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            "/v1/drones/DRONE_NOPE/telemetry".to_string(),
+            StatusCode::NOT_FOUND,
+        ),
+    ] {
+        let res = app.clone().oneshot(get(uri.clone())).await.unwrap();
+        assert_eq!(res.status(), status, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn webhooks_receive_signed_events_they_subscribed_to() {
+    let (app, state) = setup_app().await;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let receiver = axum::Router::new().route(
+        "/hook",
+        axum::routing::post(
+            move |headers: axum::http::HeaderMap, body: axum::body::Bytes| async move {
+                tx.send((headers, body)).ok();
+                StatusCode::OK
+            },
+        ),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    tokio::spawn(async move {
+        axum::serve(listener, receiver).await.expect("serve");
+    });
+
+    let admin = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(admin(
+            "POST",
+            "/v1/admin/webhooks",
+            Some(json!({ "url": "ftp://example.com/hook" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .clone()
+        .oneshot(admin(
+            "POST",
+            "/v1/admin/webhooks",
+            Some(json!({
+                "url": format!("http://{}/hook", addr),
+                "secret": "shh",
+                "events": ["command_issued"]
+            })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let created = read_json(res).await;
+    assert_eq!(created["secret"], "shh");
+    let webhook_id = created["id"].as_str().unwrap().to_string();
+
+    let res = app
+        .clone()
+        .oneshot(admin("GET", "/v1/admin/webhooks", None))
+        .await
+        .unwrap();
+    let listed = read_json(res).await;
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert!(listed[0].get("secret").is_none());
+
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/register")
+        .header("content-type", "application/json")
+        .header("X-Registration-Token", "test-registration-token")
+        .body(Body::from(json!({ "drone_id": "DRONE_HOOK" }).to_string()))
+        .unwrap();
+    let res = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let res = app
+        .clone()
+        .oneshot(admin(
+            "POST",
+            "/v1/commands",
+            Some(json!({ "drone_id": "DRONE_HOOK", "type": "LAND" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let command = read_json(res).await;
+
+    // Only the subscribed event type was queued.
+    crate::jobs::dispatch(&state);
+    let (headers, body) = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+        .await
+        .expect("delivery before timeout")
+        .expect("receiver open");
+    assert_eq!(headers["x-atc-event"], "command_issued");
+    assert_eq!(
+        headers["x-atc-signature"].to_str().unwrap(),
+        crate::webhooks::signature("shh", &body)
+    );
+    let event: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(event["event"], "command_issued");
+    assert_eq!(event["drone_ids"], json!(["DRONE_HOOK"]));
+    assert_eq!(event["data"]["command_id"], command["command_id"]);
+    assert_eq!(headers["x-atc-delivery"], event["id"].as_str().unwrap());
+
+    let uri = format!("/v1/admin/webhooks/{}", webhook_id);
+    let res = app
+        .clone()
+        .oneshot(admin("DELETE", &uri, None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    let res = app.oneshot(admin("DELETE", &uri, None)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn grpc_streams_telemetry_and_commands() {
+    use crate::grpc::proto::drone_stream_client::DroneStreamClient;
+    use crate::grpc::proto::drone_stream_server::DroneStreamServer;
+    use crate::grpc::proto::{command, CommandAck, Telemetry, TelemetryFrame};
+    use crate::grpc::DroneStreamService;
+    use futures::StreamExt;
+
+    let (app, state) = setup_app().await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    let incoming =
+        tonic::transport::server::TcpIncoming::from_listener(listener, true, None).unwrap();
+    let service = DroneStreamServer::new(DroneStreamService::new(state.clone()));
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_incoming(incoming),
+    );
+    let mut client = DroneStreamClient::connect(format!("http://{}", addr))
+        .await
+        .expect("connect");
+
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/v1/drones/register")
+        .header("content-type", "application/json")
+        .header("X-Registration-Token", "test-registration-token")
+        .body(Body::from(json!({ "drone_id": "DRONE_GRPC" }).to_string()))
+        .unwrap();
+    let res = app.clone().oneshot(register_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let token = read_json(res).await["session_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    fn with_token<T>(message: T, token: &str) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        request.metadata_mut().insert(
+            "authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        request
+    }
+
+    let frame = |sequence, lat| TelemetryFrame {
+        telemetry: Some(Telemetry {
+            drone_id: "DRONE_GRPC".to_string(),
+            lat,
+            lon: -117.8265,
+            altitude_m: 50.0,
+            speed_mps: 5.0,
+            timestamp_ms: Utc::now().timestamp_millis(),
+            ..Default::default()
+        }),
+        sequence,
+        ..Default::default()
+    };
+    let frames = || futures::stream::iter(vec![frame(1, 33.6846), frame(2, 200.0)]);
+
+    // Streams are refused until startup completes, and without a token.
+    let status = client
+        .stream_telemetry(with_token(frames(), &token))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Unavailable);
+    state.lifecycle().mark_database_loaded();
+    state.lifecycle().mark_blender_reconciled();
+    let status = client
+        .stream_telemetry(tonic::Request::new(frames()))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+    let acks: Vec<_> = client
+        .stream_telemetry(with_token(frames(), &token))
+        .await
+        .expect("telemetry stream")
+        .into_inner()
+        .collect()
+        .await;
+    let acks: Vec<_> = acks.into_iter().map(Result::unwrap).collect();
+    assert_eq!(acks.len(), 2);
+    assert!(acks[0].accepted, "{:?}", acks[0]);
+    assert_eq!(acks[1].sequence, 2);
+    assert!(!acks[1].accepted);
+    assert_eq!(acks[1].error, "Latitude out of range");
+    assert_eq!(state.get_drone("DRONE_GRPC").unwrap().lat, 33.6846);
+
+    let issue = |command: Value| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/commands")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(command.to_string()))
+            .unwrap()
+    };
+    let res = app
+        .clone()
+        .oneshot(issue(json!({ "drone_id": "DRONE_GRPC", "type": "LAND" })))
+        .await
+        .unwrap();
+    let queued_id = read_json(res).await["command_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // The queued command comes first, then commands issued while connected.
+    let (ack_tx, ack_rx) = futures::channel::mpsc::unbounded();
+    let mut commands = client
+        .stream_commands(with_token(ack_rx, &token))
+        .await
+        .expect("command stream")
+        .into_inner();
+    async fn next_frame(
+        commands: &mut tonic::Streaming<crate::grpc::proto::CommandFrame>,
+    ) -> crate::grpc::proto::CommandFrame {
+        tokio::time::timeout(std::time::Duration::from_secs(5), commands.next())
+            .await
+            .expect("command before timeout")
+            .expect("stream open")
+            .expect("command frame")
+    }
+    let queued = next_frame(&mut commands).await;
+    let queued_command = queued.command.unwrap();
+    assert_eq!(queued_command.command_id, queued_id);
+    assert!(matches!(
+        queued_command.command_type,
+        Some(command::CommandType::Land(_))
+    ));
+    let signed: Value = serde_json::from_slice(&queued.signed_payload).unwrap();
+    assert_eq!(signed["command_id"], queued_id.as_str());
+
+    let res = app
+        .clone()
+        .oneshot(issue(json!({
+            "drone_id": "DRONE_GRPC",
+            "type": "HOLD",
+            "duration_secs": 30
+        })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let issued = next_frame(&mut commands).await.command.unwrap();
+    assert!(matches!(
+        issued.command_type,
+        Some(command::CommandType::Hold(ref hold)) if hold.duration_secs == 30
+    ));
+
+    ack_tx
+        .unbounded_send(CommandAck {
+            command_id: queued_id.clone(),
+        })
+        .unwrap();
+    for _ in 0..50 {
+        if state
+            .get_pending_commands("DRONE_GRPC")
+            .iter()
+            .all(|command| command.command_id != queued_id)
+        {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    panic!("command was not acknowledged");
+}
+
+#[tokio::test]
+async fn list_endpoints_filter_and_page_with_cursors() {
+    use atc_core::models::{Command, CommandType, Geofence, GeofenceType};
+
+    let (app, state) = setup_app().await;
+    let now = Utc::now();
+
+    let get = |uri: String| {
+        Request::builder()
+            .uri(uri)
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let ids = |page: &Value, field: &str| -> Vec<String> {
+        page.as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item[field].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    for (id, geofence_type, active, age_mins) in [
+        ("GF-OLD", GeofenceType::NoFlyZone, true, 30),
+        ("GF-ADVISORY", GeofenceType::Advisory, true, 20),
+        ("GF-NEW", GeofenceType::NoFlyZone, true, 10),
+        ("GF-OFF", GeofenceType::NoFlyZone, false, 5),
+    ] {
+        state
+            .add_geofence(Geofence {
+                id: id.to_string(),
+                name: id.to_string(),
+                geofence_type,
+                polygon: vec![
+                    [33.0, -117.0],
+                    [33.0, -116.9],
+                    [33.1, -116.9],
+                    [33.0, -117.0],
+                ],
+                lower_altitude_m: 0.0,
+                upper_altitude_m: 120.0,
+                active,
+                created_at: now - chrono::Duration::minutes(age_mins),
+                breach_response: None,
+                schedule: None,
+                org_id: None,
+            })
+            .await
+            .expect("add geofence");
+    }
+
+    // Oldest first, one per page, following the cursor.
+    let res = app
+        .clone()
+        .oneshot(get(
+            "/v1/geofences?type=no_fly_zone&status=active&limit=1".to_string()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-total-count"], "2");
+    let cursor = res.headers()["x-next-cursor"].to_str().unwrap().to_string();
+    assert_eq!(ids(&read_json(res).await, "id"), ["GF-OLD"]);
+
+    // A geofence removed between pages doesn't shift the next one.
+    state.remove_geofence("GF-OLD").await.expect("remove");
+    let res = app
+        .clone()
+        .oneshot(get(format!(
+            "/v1/geofences?type=no_fly_zone&status=active&limit=1&cursor={}",
+            cursor
+        )))
+        .await
+        .unwrap();
+    assert!(res.headers().get("x-next-cursor").is_none());
+    assert_eq!(ids(&read_json(res).await, "id"), ["GF-NEW"]);
+
+    let res = app
+        .clone()
+        .oneshot(get("/v1/geofences?offset=1&limit=1".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(ids(&read_json(res).await, "id"), ["GF-NEW"]);
+
+    let res = app
+        .clone()
+        .oneshot(get("/v1/geofences?type=volcano".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(read_json(res).await["field"], "type");
+
+    for drone_id in ["DRONE_A", "DRONE_B"] {
+        let register_req = Request::builder()
+            .method("POST")
+            .uri("/v1/drones/register")
+            .header("content-type", "application/json")
+            .header("X-Registration-Token", "test-registration-token")
+            .body(Body::from(json!({ "drone_id": drone_id }).to_string()))
+            .unwrap();
+        let register_res = app.clone().oneshot(register_req).await.unwrap();
+        assert_eq!(register_res.status(), StatusCode::CREATED);
+    }
+    // Listed by issue time, not ID.
+    let hold = CommandType::Hold { duration_secs: 10 };
+    for (id, drone_id, command_type, age_secs, expires_in_secs) in [
+        ("CMD-1", "DRONE_A", hold.clone(), 30, 60),
+        ("CMD-2", "DRONE_A", CommandType::Land, 20, -60),
+        ("CMD-3", "DRONE_B", hold, 40, 60),
+    ] {
+        state
+            .enqueue_command(
+                Command {
+                    command_id: id.to_string(),
+                    drone_id: drone_id.to_string(),
+                    command_type,
+                    issued_at: now - chrono::Duration::seconds(age_secs),
+                    expires_at: Some(now + chrono::Duration::seconds(expires_in_secs)),
+                    acknowledged: false,
+                },
+                CommandCause::new(CommandCauseKind::Operator),
+            )
+            .await
+            .expect("enqueue command");
+    }
+
+    let res = app
+        .clone()
+        .oneshot(get("/v1/commands?type=hold".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(ids(&read_json(res).await, "command_id"), ["CMD-3", "CMD-1"]);
+    let res = app
+        .clone()
+        .oneshot(get(
+            "/v1/commands?drone_id=DRONE_A&status=expired".to_string()
+        ))
+        .await
+        .unwrap();
+    assert_eq!(ids(&read_json(res).await, "command_id"), ["CMD-2"]);
+    let res = app
+        .clone()
+        .oneshot(get("/v1/commands?limit=100000".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(read_json(res).await["max_limit"], 1000);
+
+    for (drone_id, lat) in [("DRONE_1", 33.5), ("DRONE_2", 33.5001)] {
+        state
+            .update_telemetry(atc_core::models::Telemetry {
+                drone_id: drone_id.to_string(),
+                owner_id: None,
+                lat,
+                lon: -117.5,
+                altitude_m: 50.0,
+                velocity_x: 0.0,
+                velocity_y: 0.0,
+                velocity_z: 0.0,
+                heading_deg: 0.0,
+                speed_mps: 0.0,
+                timestamp: now,
+            })
+            .await;
+    }
+    state.refresh_conflicts().await;
+    let severity = state.get_conflicts()[0].severity;
+    let severity = serde_json::to_value(severity).unwrap();
+    let other = if severity == "info" {
+        "critical"
+    } else {
+        "info"
+    };
+
+    let res = app
+        .clone()
+        .oneshot(get(format!(
+            "/v1/conflicts?drone_id=DRONE_2&severity={}",
+            severity.as_str().unwrap()
+        )))
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["x-total-count"], "1");
+    let res = app
+        .clone()
+        .oneshot(get(format!("/v1/conflicts?severity={}", other)))
+        .await
+        .unwrap();
+    assert_eq!(read_json(res).await, json!([]));
+    let res = app
+        .clone()
+        .oneshot(get("/v1/conflicts?cursor=***".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn safety_rules_are_tuned_live_and_survive_a_restart() {
+    let (app, state) = setup_app().await;
+    let put_rules = |body: Value| {
+        Request::builder()
+            .method("PUT")
+            .uri("/v1/admin/rules")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    // Two drones 200 m apart are well clear of the default 50 m minimum.
+    let now = Utc::now();
+    for (drone_id, lon) in [("DRONE_1", -117.5), ("DRONE_2", -117.49785)] {
+        state
+            .update_telemetry(atc_core::models::Telemetry {
+                drone_id: drone_id.to_string(),
+                owner_id: None,
+                lat: 33.5,
+                lon,
+                altitude_m: 50.0,
+                velocity_x: 0.0,
+                velocity_y: 0.0,
+                velocity_z: 0.0,
+                heading_deg: 0.0,
+                speed_mps: 0.0,
+                timestamp: now,
+            })
+            .await;
+    }
+    state.refresh_conflicts().await;
+    assert!(state.get_conflicts().is_empty());
+
+    let res = app
+        .clone()
+        .oneshot(put_rules(json!({ "min_horizontal_separation_m": -5.0 })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = app
+        .clone()
+        .oneshot(put_rules(json!({
+            "min_horizontal_separation_m": 300.0,
+            "drone_timeout_secs": 30
+        })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["rules"]["min_horizontal_separation_m"], 300.0);
+    assert_eq!(body["rules"]["min_vertical_separation_m"], 30.0);
+
+    // A later update keeps the earlier tuned values.
+    let res = app
+        .clone()
+        .oneshot(put_rules(json!({ "lookahead_seconds": 45.0 })))
+        .await
+        .unwrap();
+    assert_eq!(
+        read_json(res).await["overrides"],
+        json!({
+            "min_horizontal_separation_m": 300.0,
+            "lookahead_seconds": 45.0,
+            "drone_timeout_secs": 30
+        })
+    );
+
+    // The rebuilt detector kept the tracked drones and applies the new minimum.
+    state.refresh_conflicts().await;
+    assert_eq!(state.get_conflicts().len(), 1);
+
+    let restarted =
+        AppState::with_database(state.database().unwrap().clone(), state.config().clone());
+    restarted.load_from_database().await.expect("reload db");
+    assert_eq!(restarted.rules().min_horizontal_separation_m, 300.0);
+    assert_eq!(restarted.rules().lookahead_seconds, 45.0);
+    assert_eq!(restarted.rules().drone_timeout_secs, 30);
+}
+
+#[tokio::test]
+async fn deregistered_drone_is_archived_and_its_work_cancelled() {
+    use atc_core::models::{Command, CommandType};
+
+    let (app, state) = setup_app().await;
+    let register = || {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/drones/register")
+            .header("content-type", "application/json")
+            .header("X-Registration-Token", "test-registration-token")
+            .body(Body::from(
+                json!({ "drone_id": "DRONE_RETIRED", "owner_id": "owner-1" }).to_string(),
+            ))
+            .unwrap()
+    };
+    let deregister = || {
+        Request::builder()
+            .method("DELETE")
+            .uri("/v1/drones/DRONE_RETIRED")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let res = app.clone().oneshot(register()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let token = read_json(res).await["session_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let now = Utc::now();
+    for (flight_id, status) in [
+        ("FLIGHT-ACTIVE", FlightStatus::Active),
+        ("FLIGHT-DONE", FlightStatus::Completed),
+    ] {
+        state
+            .add_flight_plan(FlightPlan {
+                flight_id: flight_id.to_string(),
+                drone_id: "DRONE_RETIRED".to_string(),
+                owner_id: Some("owner-1".to_string()),
+                waypoints: vec![
+                    Waypoint {
+                        lat: 33.0,
+                        lon: -117.0,
+                        altitude_m: 50.0,
+                        speed_mps: None,
+                    },
+                    Waypoint {
+                        lat: 33.001,
+                        lon: -117.0,
+                        altitude_m: 50.0,
+                        speed_mps: None,
+                    },
+                ],
+                trajectory_log: None,
+                metadata: None,
+                status,
+                departure_time: now - chrono::Duration::minutes(5),
+                arrival_time: None,
+                created_at: now,
+            })
+            .await
+            .expect("add plan");
+    }
+    state
+        .enqueue_command(
+            Command {
+                command_id: "CMD-RETIRED".to_string(),
+                drone_id: "DRONE_RETIRED".to_string(),
+                command_type: CommandType::Hold { duration_secs: 10 },
+                issued_at: now,
+                expires_at: None,
+                acknowledged: false,
+            },
+            CommandCause::new(CommandCauseKind::Operator),
+        )
+        .await
+        .expect("enqueue command");
+
+    let unauthorized = Request::builder()
+        .method("DELETE")
+        .uri("/v1/drones/DRONE_RETIRED")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.clone().oneshot(unauthorized).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = app.clone().oneshot(deregister()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["cancelled_commands"], json!(["CMD-RETIRED"]));
+    assert_eq!(body["cancelled_flights"], json!(["FLIGHT-ACTIVE"]));
+
+    assert!(state.get_drone("DRONE_RETIRED").is_none());
+    assert!(state.get_pending_commands("DRONE_RETIRED").is_empty());
+    let status_of = |plans: Vec<FlightPlan>, flight_id: &str| {
+        plans
+            .into_iter()
+            .find(|plan| plan.flight_id == flight_id)
+            .map(|plan| plan.status)
+    };
+    assert_eq!(
+        status_of(state.get_flight_plans(), "FLIGHT-ACTIVE"),
+        Some(FlightStatus::Cancelled)
+    );
+    assert_eq!(
+        status_of(state.get_flight_plans(), "FLIGHT-DONE"),
+        Some(FlightStatus::Completed)
+    );
+
+    // The revoked session token no longer authenticates telemetry.
+    let telemetry = Request::builder()
+        .method("POST")
+        .uri("/v1/telemetry")
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", token))
+        .body(Body::from(
+            json!({
+                "drone_id": "DRONE_RETIRED",
+                "lat": 33.0,
+                "lon": -117.0,
+                "altitude_m": 50.0,
+                "heading_deg": 0.0,
+                "speed_mps": 0.0,
+                "timestamp": Utc::now().to_rfc3339()
+            })
+            .to_string(),
+        ))
+        .unwrap();
+    let res = app.clone().oneshot(telemetry).await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    let res = app.clone().oneshot(deregister()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    // The archived drone stays out of state after a restart, plan history included.
+    let restarted =
+        AppState::with_database(state.database().unwrap().clone(), state.config().clone());
+    restarted.load_from_database().await.expect("reload db");
+    assert!(restarted.get_drone("DRONE_RETIRED").is_none());
+    assert_eq!(
+        status_of(restarted.get_flight_plans(), "FLIGHT-ACTIVE"),
+        Some(FlightStatus::Cancelled)
+    );
+
+    // Registering the ID again brings the drone back.
+    let res = app.clone().oneshot(register()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let restarted =
+        AppState::with_database(state.database().unwrap().clone(), state.config().clone());
+    restarted.load_from_database().await.expect("reload db");
+    assert!(restarted.get_drone("DRONE_RETIRED").is_some());
+}
+
+#[tokio::test]
+async fn command_audit_keeps_causes_and_outcomes() {
+    use atc_core::models::{Command, CommandType};
+
+    let (app, state) = setup_app().await;
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/drones/register")
+                .header("content-type", "application/json")
+                .header("X-Registration-Token", "test-registration-token")
+                .body(Body::from(
+                    json!({ "drone_id": "DRONE_AUDIT", "owner_id": "owner-1" }).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let token = read_json(res).await["session_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let audit = |query: &str| {
+        Request::builder()
+            .uri(format!("/v1/commands/audit?{}", query))
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // Operator command, acknowledged by the drone.
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/commands")
+                .header("content-type", "application/json")
+                .header("authorization", "Bearer test-admin-token")
+                .body(Body::from(
+                    json!({ "drone_id": "DRONE_AUDIT", "owner_id": "owner-1", "type": "LAND" })
+                        .to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let operator_id = read_json(res).await["command_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/commands/ack")
+                .header("content-type", "application/json")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::from(json!({ "command_id": operator_id }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(read_json(res).await["status"], "acknowledged");
+
+    // Conflict resolution that expires unacknowledged, and a failsafe hold still queued.
+    let now = Utc::now();
+    let hold = |command_id: &str, expires_at| Command {
+        command_id: command_id.to_string(),
+        drone_id: "DRONE_AUDIT".to_string(),
+        command_type: CommandType::Hold { duration_secs: 10 },
+        issued_at: now,
+        expires_at,
+        acknowledged: false,
+    };
+    state
+        .enqueue_auto_command(
+            hold(
+                "RESOLVE-AUDIT",
+                Some(now + chrono::Duration::milliseconds(50)),
+            ),
+            CommandCause::new(CommandCauseKind::Conflict).with_source("DRONE_AUDIT-DRONE_X-1"),
+        )
+        .await
+        .expect("enqueue resolution");
+    state
+        .enqueue_auto_command(
+            hold("FAILSAFE-AUDIT", None),
+            CommandCause::new(CommandCauseKind::Failsafe),
+        )
+        .await
+        .expect("enqueue failsafe");
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    state.purge_expired_commands().await.expect("purge");
+
+    let res = app
+        .clone()
+        .oneshot(audit("drone_id=DRONE_AUDIT"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let entries = read_json(res).await;
+    let entry = |command_id: &str| {
+        entries
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["command_id"] == command_id)
+            .cloned()
+            .unwrap_or_else(|| panic!("{} audited", command_id))
+    };
+    assert_eq!(entries.as_array().map(Vec::len), Some(3));
+    assert_eq!(
+        entry(&operator_id)["cause"],
+        json!({ "kind": "operator", "source_id": null })
+    );
+    assert_eq!(entry(&operator_id)["outcome"], "acknowledged");
+    assert_eq!(
+        entry("RESOLVE-AUDIT")["cause"]["source_id"],
+        "DRONE_AUDIT-DRONE_X-1"
+    );
+    assert_eq!(entry("RESOLVE-AUDIT")["outcome"], "expired");
+    assert!(entry("FAILSAFE-AUDIT")["outcome"].is_null());
+
+    // The trail outlives the commands: deregistering cancels the queued hold.
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri("/v1/drones/DRONE_AUDIT")
+                .header("authorization", "Bearer test-admin-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app.clone().oneshot(audit("cause=failsafe")).await.unwrap();
+    let entries = read_json(res).await;
+    assert_eq!(entries.as_array().map(Vec::len), Some(1));
+    assert_eq!(entries[0]["command_id"], "FAILSAFE-AUDIT");
+    assert_eq!(entries[0]["outcome"], "cancelled");
+
+    let res = app.clone().oneshot(audit("cause=weather")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn emergency_stop_preempts_queues_and_blocks_plans_until_cleared() {
+    use atc_core::models::{Command, CommandType, Telemetry};
+
+    let (app, state) = setup_app().await;
+    for (drone_id, owner_id) in [("DRONE_EM_A", "owner-a"), ("DRONE_EM_B", "owner-b")] {
+        state
+            .register_drone(drone_id, Some(owner_id.to_string()))
+            .await
+            .expect("register drone");
+        state
+            .update_telemetry(Telemetry {
+                drone_id: drone_id.to_string(),
+                owner_id: Some(owner_id.to_string()),
+                lat: 33.6846,
+                lon: -117.8265,
+                altitude_m: 50.0,
+                velocity_x: 0.0,
+                velocity_y: 0.0,
+                velocity_z: 0.0,
+                heading_deg: 0.0,
+                speed_mps: 0.0,
+                timestamp: Utc::now(),
+            })
+            .await;
+    }
+    // Cooldowns and queued work don't hold the stop back.
+    state
+        .enqueue_command(
+            Command {
+                command_id: "CMD-QUEUED".to_string(),
+                drone_id: "DRONE_EM_A".to_string(),
+                command_type: CommandType::Resume,
+                issued_at: Utc::now(),
+                expires_at: None,
+                acknowledged: false,
+            },
+            CommandCause::new(CommandCauseKind::Operator),
+        )
+        .await
+        .expect("enqueue");
+    state.mark_command_issued("DRONE_EM_A");
+
+    let admin = |method: &str, body: Option<Value>| {
+        Request::builder()
+            .method(method)
+            .uri("/v1/admin/emergency")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap()
+    };
+    let plan = || {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/flights/plan")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(
+                json!({
+                    "drone_id": "DRONE_EM_B",
+                    "owner_id": "owner-b",
+                    "waypoints": [
+                        { "lat": 33.70, "lon": -117.80, "altitude_m": 50.0 },
+                        { "lat": 33.71, "lon": -117.80, "altitude_m": 50.0 }
+                    ]
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(admin("POST", Some(json!({ "type": "RESUME" }))))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let res = app
+        .clone()
+        .oneshot(admin(
+            "POST",
+            Some(json!({ "type": "LAND", "owner_id": "owner-a", "reason": "Airspace closure" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["active"], true);
+    assert_eq!(body["emergency"]["drone_ids"], json!(["DRONE_EM_A"]));
+    assert_eq!(body["broadcast"]["pending"], 1);
+    let emergency_id = body["emergency"]["emergency_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let queue = state.get_pending_commands("DRONE_EM_A");
+    assert!(matches!(queue[0].command_type, CommandType::Land));
+    assert_eq!(queue[1].command_id, "CMD-QUEUED");
+    assert!(state.get_pending_commands("DRONE_EM_B").is_empty());
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/v1/commands/audit?cause=emergency")
+                .header("authorization", "Bearer test-admin-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let entries = read_json(res).await;
+    assert_eq!(entries.as_array().map(Vec::len), Some(1));
+    assert_eq!(entries[0]["cause"]["source_id"], emergency_id.as_str());
+
+    let res = app
+        .clone()
+        .oneshot(admin("POST", Some(json!({ "type": "LAND" }))))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    let res = app.clone().oneshot(plan()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(read_json(res).await["emergency_id"], emergency_id.as_str());
+
+    // The stop survives a restart.
+    let restarted = AppState::with_database(
+        state.database().cloned().expect("database"),
+        state.config().clone(),
+    );
+    restarted.load_from_database().await.expect("reload db");
+    assert_eq!(
+        restarted.emergency_stop().map(|stop| stop.emergency_id),
+        Some(emergency_id)
+    );
+
+    let res = app.clone().oneshot(admin("DELETE", None)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(read_json(res).await["active"], false);
+    let res = app.clone().oneshot(admin("DELETE", None)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let res = app.clone().oneshot(plan()).await.unwrap();
+    assert_ne!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn operator_quotas_refuse_submissions_over_the_limit() {
+    let (app, state) = setup_app_with(|config| {
+        config.operator_quotas.max_active_flights = 1;
+        config.operator_quotas.max_reserved_intents = 1;
+        config.operator_quotas.max_daily_plans = 2;
+    })
+    .await;
+    for drone_id in ["DRONE_Q1", "DRONE_Q2"] {
+        state
+            .register_drone(drone_id, Some("op-q".to_string()))
+            .await
+            .expect("register");
+    }
+    let submit = |uri: &str, drone_id: &str, lat: f64| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(
+                json!({
+                    "drone_id": drone_id,
+                    "owner_id": "op-q",
+                    "waypoints": [
+                        { "lat": lat, "lon": -117.0, "altitude_m": 50.0 },
+                        { "lat": lat, "lon": -116.999, "altitude_m": 50.0 }
+                    ],
+                    "departure_time": (Utc::now() + chrono::Duration::seconds(60)).to_rfc3339(),
+                    // Compliance data sources are out of reach in tests.
+                    "metadata": {
+                        "drone_speed_mps": 10.0,
+                        "compliance_override_enabled": true,
+                        "compliance_override_notes": "Quota test flight"
+                    }
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let res = app
+        .clone()
+        .oneshot(submit("/v1/flights/plan", "DRONE_Q1", 33.0))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let res = app
+        .clone()
+        .oneshot(submit("/v1/flights/plan", "DRONE_Q2", 33.1))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        read_json(res).await["quota"],
+        json!({ "owner_id": "op-q", "quota": "max_active_flights", "limit": 1, "current": 1 })
+    );
+
+    // Reservations have their own cap; cancelling one frees it but not the day's allowance.
+    let res = app
+        .clone()
+        .oneshot(submit("/v1/operational_intents/reserve", "DRONE_Q2", 33.1))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let reserved_id = read_json(res).await["flight_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let res = app
+        .clone()
+        .oneshot(submit("/v1/operational_intents/reserve", "DRONE_Q2", 33.2))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        read_json(res).await["quota"]["quota"],
+        "max_reserved_intents"
+    );
+    let res = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/v1/operational_intents/{reserved_id}/cancel"))
+                .header("authorization", "Bearer test-admin-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app
+        .clone()
+        .oneshot(submit("/v1/operational_intents/reserve", "DRONE_Q2", 33.2))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(read_json(res).await["quota"]["quota"], "max_daily_plans");
+
+    let res = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/scheduler/fairness")
+                .header("authorization", "Bearer test-admin-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let fairness = read_json(res).await;
+    assert_eq!(fairness["quotas"]["max_daily_plans"], 2);
+    assert_eq!(fairness["operators"][0]["operator"], "op-q");
+    assert_eq!(fairness["operators"][0]["quota_rejections"], 3);
+}
+
+#[tokio::test]
+async fn concurrent_submissions_cannot_overshoot_a_quota() {
+    let (_app, state) = setup_app_with(|config| {
+        config.operator_quotas.max_active_flights = 1;
+    })
+    .await;
+    let request = |drone_id: &str, lat: f64| FlightPlanRequest {
+        drone_id: drone_id.to_string(),
+        owner_id: Some("op-race".to_string()),
+        waypoints: Some(vec![
+            Waypoint {
+                lat,
+                lon: -117.0,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat,
+                lon: -116.999,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+        ]),
+        trajectory_log: None,
+        metadata: Some(FlightPlanMetadata {
+            drone_speed_mps: Some(10.0),
+            ..Default::default()
+        }),
+        origin: None,
+        destination: None,
+        departure_time: Some(Utc::now() + chrono::Duration::seconds(60)),
+    };
+    let build = |request| {
+        crate::api::flights::build_plan(state.as_ref(), request, None, FlightStatus::Approved)
+    };
+
+    let (first, second) = tokio::join!(
+        build(request("DRONE_R1", 33.0)),
+        build(request("DRONE_R2", 33.1))
+    );
+    let refused: Vec<_> = [first, second]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
+    assert_eq!(refused.len(), 1);
+    assert_eq!(
+        refused[0]
+            .downcast_ref::<crate::fairness::QuotaExceeded>()
+            .map(|exceeded| exceeded.quota),
+        Some("max_active_flights")
+    );
+}
+
+#[tokio::test]
+async fn organizations_only_see_their_own_drones_and_geofences() {
+    let (app, state) = setup_app().await;
+    let request = |method: &str, uri: &str, token: &str, body: Option<Value>| {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("authorization", format!("Bearer {token}"));
+        match body {
+            Some(body) => builder.body(Body::from(body.to_string())).unwrap(),
+            None => builder.body(Body::empty()).unwrap(),
+        }
+    };
+
+    let mut tokens = Vec::new();
+    for (name, owner_id, drone_id) in [("Alpha", "op-a", "DRONE_A"), ("Bravo", "op-b", "DRONE_B")] {
+        let res = app
+            .clone()
+            .oneshot(request(
+                "POST",
+                "/v1/admin/organizations",
+                "test-admin-token",
+                Some(json!({ "name": name })),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let created = read_json(res).await;
+        let org_id = created["org_id"].as_str().unwrap().to_string();
+        let res = app
+            .clone()
+            .oneshot(request(
+                "PUT",
+                &format!("/v1/admin/organizations/{org_id}/members/{owner_id}"),
+                "test-admin-token",
+                None,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(read_json(res).await["members"], json!([owner_id]));
+        state
+            .register_drone(drone_id, Some(owner_id.to_string()))
+            .await
+            .expect("register");
+        tokens.push((org_id, created["token"].as_str().unwrap().to_string()));
+    }
+    let (alpha_id, alpha) = &tokens[0];
+    let (_, bravo) = &tokens[1];
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/drones", alpha, None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let drones = read_json(res).await;
+    assert_eq!(drones.as_array().unwrap().len(), 1);
+    assert_eq!(drones[0]["drone_id"], "DRONE_A");
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/drones/DRONE_B", alpha, None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/drones", "not-a-token", None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+    // Commands can't be sent to another organization's drones.
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/commands",
+            alpha,
+            Some(json!({ "drone_id": "DRONE_B", "owner_id": "op-b", "type": "HOLD", "duration_secs": 30 })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    // A geofence created with an organization token is private to it.
+    let polygon = json!([
+        [33.0, -117.0],
+        [33.0, -116.9],
+        [33.1, -116.9],
+        [33.1, -117.0],
+        [33.0, -117.0]
+    ]);
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/geofences",
+            alpha,
+            Some(json!({
+                "name": "Alpha yard",
+                "geofence_type": "no_fly_zone",
+                "polygon": polygon,
+                "lower_altitude_m": 0.0,
+                "upper_altitude_m": 120.0
+            })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let private = read_json(res).await;
+    assert_eq!(private["org_id"], json!(alpha_id));
+    let res = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/v1/geofences",
+            "test-admin-token",
+            Some(json!({
+                "name": "Shared zone",
+                "geofence_type": "no_fly_zone",
+                "polygon": polygon,
+                "lower_altitude_m": 0.0,
+                "upper_altitude_m": 120.0
+            })),
+        ))
+        .await
+        .unwrap();
+    let shared = read_json(res).await;
+
+    let res = app
+        .clone()
+        .oneshot(request("GET", "/v1/geofences", bravo, None))
+        .await
+        .unwrap();
+    let visible = read_json(res).await;
+    let names: Vec<&str> = visible
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|geofence| geofence["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Shared zone"]);
+    let private_uri = format!("/v1/geofences/{}", private["id"].as_str().unwrap());
+    let res = app
+        .clone()
+        .oneshot(request("DELETE", &private_uri, bravo, None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let shared_uri = format!("/v1/geofences/{}", shared["id"].as_str().unwrap());
+    let res = app
+        .clone()
+        .oneshot(request("DELETE", &shared_uri, alpha, None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let res = app
+        .clone()
+        .oneshot(request("DELETE", &private_uri, alpha, None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    // Deleting the organization revokes its token.
+    let res = app
+        .clone()
+        .oneshot(request(
+            "DELETE",
+            &format!("/v1/admin/organizations/{alpha_id}"),
+            "test-admin-token",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    let res = app
+        .oneshot(request("GET", "/v1/drones", alpha, None))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn active_flight_progress_tracks_the_trajectory() {
+    use atc_core::models::{Telemetry, TrajectoryPoint};
+    use atc_core::spatial::offset_position;
+
+    let (app, state) = setup_app().await;
+    let origin = (33.68, -117.82);
+    let now = Utc::now();
+    let point = |north_m: f64, t: f64| {
+        let (lat, lon) = offset_position(origin.0, origin.1, north_m, 0.0);
+        TrajectoryPoint {
+            lat,
+            lon,
+            altitude_m: 60.0,
+            time_offset_s: Some(t),
+        }
+    };
+    // 600 m north in 60 s, departed 30 s ago: due 300 m out now.
+    for (flight_id, drone_id, status) in [
+        ("FLIGHT-PROGRESS", "DRONE_P1", FlightStatus::Active),
+        ("FLIGHT-BOOKED", "DRONE_P2", FlightStatus::Approved),
+    ] {
+        state
+            .add_flight_plan(FlightPlan {
+                flight_id: flight_id.to_string(),
+                drone_id: drone_id.to_string(),
+                owner_id: None,
+                waypoints: Vec::new(),
+                trajectory_log: Some(vec![
+                    point(0.0, 0.0),
+                    point(300.0, 30.0),
+                    point(600.0, 60.0),
+                ]),
+                metadata: None,
+                status,
+                departure_time: now - chrono::Duration::seconds(30),
+                arrival_time: None,
+                created_at: now,
+            })
+            .await
+            .expect("add plan");
+    }
+    // 10 s behind schedule and 20 m east of the track, 5 m high.
+    let (lat, lon) = offset_position(origin.0, origin.1, 200.0, 20.0);
+    state
+        .update_telemetry(Telemetry {
+            drone_id: "DRONE_P1".to_string(),
+            owner_id: None,
+            lat,
+            lon,
+            altitude_m: 65.0,
+            velocity_x: 0.0,
+            velocity_y: 10.0,
+            velocity_z: 0.0,
+            heading_deg: 0.0,
+            speed_mps: 10.0,
+            timestamp: now,
+        })
+        .await;
+
+    let progress = |flight_id: &str| {
+        Request::builder()
+            .uri(format!("/v1/flights/{flight_id}/progress"))
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::empty())
+            .unwrap()
+    };
+    let res = app
+        .clone()
+        .oneshot(progress("FLIGHT-PROGRESS"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["current_segment"], 0);
+    assert_eq!(body["segment_count"], 2);
+    let number = |field: &str| body[field].as_f64().unwrap();
+    assert!((number("percent_complete") - 33.3).abs() < 0.5);
+    assert!((number("cross_track_error_m") - 20.0).abs() < 0.5);
+    assert!((number("vertical_error_m") - 5.0).abs() < 0.01);
+    assert!((number("eta_drift_s") - 10.0).abs() < 0.1);
+
+    let res = app
+        .clone()
+        .oneshot(progress("FLIGHT-BOOKED"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    let res = app.oneshot(progress("FLIGHT-MISSING")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn conformance_escalates_through_the_operator_ladder() {
+    use atc_core::models::{
+        CommandType, ConformanceAction, ConformanceStatus, FlightPlan, Telemetry,
+    };
+    use atc_core::spatial::offset_position;
+
+    let (app, state) = setup_app().await;
+    let put_policy = |policy: Value| {
+        Request::builder()
+            .method("PUT")
+            .uri("/v1/admin/conformance/policy")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(policy.to_string()))
+            .unwrap()
+    };
+
+    let invalid = json!({
+        "owners": {
+            "op-strict": {
+                "minor": [{ "action": "warn" }],
+                "major": [{ "action": "hold", "after_s": 30 }]
+            }
+        }
+    });
+    let res = app.clone().oneshot(put_policy(invalid)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = read_json(res).await;
+    assert_eq!(
+        body["details"][0],
+        "owners.op-strict.major must start with a step at after_s 0"
+    );
+
+    let policy = json!({
+        "owners": {
+            "op-strict": {
+                "minor": [{ "action": "warn" }],
+                "major": [
+                    { "action": "hold" },
+                    { "action": "reroute", "after_s": 60 },
+                    { "action": "notify", "after_s": 180 }
+                ]
+            }
+        }
+    });
+    let res = app.clone().oneshot(put_policy(policy)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let get_req = Request::builder()
+        .uri("/v1/admin/conformance/policy")
+        .header("authorization", "Bearer test-admin-token")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.oneshot(get_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = read_json(res).await;
+    assert_eq!(body["default"]["major"][0]["action"], "hold");
+    assert_eq!(body["owners"]["op-strict"]["major"][1]["after_s"], 60);
+
+    // 250 m east of a 1 km northbound leg.
+    let origin = (33.6846, -117.8265);
+    let waypoint = |north_m: f64| {
+        let (lat, lon) = offset_position(origin.0, origin.1, north_m, 0.0);
+        Waypoint {
+            lat,
+            lon,
+            altitude_m: 60.0,
+            speed_mps: None,
+        }
+    };
+    let now = Utc::now();
+    state
+        .register_drone("DRONE_OFF", Some("op-strict".to_string()))
+        .await
+        .unwrap();
+    state
+        .add_flight_plan(FlightPlan {
+            flight_id: "FLIGHT-OFF".to_string(),
+            drone_id: "DRONE_OFF".to_string(),
+            owner_id: Some("op-strict".to_string()),
+            waypoints: vec![waypoint(0.0), waypoint(500.0), waypoint(1000.0)],
+            trajectory_log: None,
+            metadata: None,
+            status: FlightStatus::Active,
+            departure_time: now,
+            arrival_time: None,
+            created_at: now,
+        })
+        .await
+        .unwrap();
+    let (lat, lon) = offset_position(origin.0, origin.1, 200.0, 250.0);
+    state
+        .update_telemetry(Telemetry {
+            drone_id: "DRONE_OFF".to_string(),
+            owner_id: None,
+            lat,
+            lon,
+            altitude_m: 60.0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            velocity_z: 0.0,
+            heading_deg: 0.0,
+            speed_mps: 0.0,
+            timestamp: now,
+        })
+        .await;
+    let drone = state.get_drone("DRONE_OFF").unwrap();
+    let status = |off_for_s: i64| ConformanceStatus {
+        drone_id: "DRONE_OFF".to_string(),
+        owner_id: Some("op-strict".to_string()),
+        status: "nonconforming".to_string(),
+        last_checked: now,
+        record: None,
+        action: None,
+        nonconforming_since: Some(now - chrono::Duration::seconds(off_for_s)),
+    };
+    let escalate = |off_for_s: i64, previous: Option<ConformanceAction>| {
+        let (state, drone) = (state.clone(), drone.clone());
+        async move {
+            crate::loops::conformance_loop::apply_escalation(
+                &state,
+                &drone,
+                &status(off_for_s),
+                previous,
+            )
+            .await
+        }
+    };
+
+    assert_eq!(escalate(0, None).await, ConformanceAction::Hold);
+    let commands = state.get_pending_commands("DRONE_OFF");
+    assert_eq!(commands.len(), 1);
+    assert!(matches!(commands[0].command_type, CommandType::Hold { .. }));
+    state.ack_command(&commands[0].command_id).await.unwrap();
+
+    // A minute off the plan: fly back to the next plan waypoint.
+    assert_eq!(
+        escalate(90, Some(ConformanceAction::Hold)).await,
+        ConformanceAction::Reroute
+    );
+    let commands = state.get_pending_commands("DRONE_OFF");
+    assert_eq!(commands.len(), 1);
+    match &commands[0].command_type {
+        CommandType::Reroute { waypoints, .. } => {
+            assert_eq!(waypoints.len(), 2);
+            assert_eq!(waypoints[1].lat, waypoint(1000.0).lat);
+        }
+        other => panic!("expected a reroute, got {:?}", other),
+    }
+    state.ack_command(&commands[0].command_id).await.unwrap();
+
+    // Still off after three minutes: hand over to the operator without a new command.
+    assert_eq!(
+        escalate(200, Some(ConformanceAction::Reroute)).await,
+        ConformanceAction::Notify
+    );
+    assert!(state.get_pending_commands("DRONE_OFF").is_empty());
+    let advisory = state
+        .get_daa_advisories()
+        .into_iter()
+        .find(|advisory| advisory.advisory_id == "conformance-DRONE_OFF")
+        .expect("conformance advisory");
+    assert_eq!(advisory.action, "notify");
+}
+
+#[tokio::test]
+async fn geofences_round_trip_through_geojson() {
+    let (app, _state) = setup_app().await;
+    let import = |document: Value| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/geofences/import")
+            .header("content-type", "application/geo+json")
+            .header("authorization", "Bearer test-admin-token")
+            .body(Body::from(document.to_string()))
+            .unwrap()
+    };
+    let square = json!([[
+        [-117.83, 33.68],
+        [-117.82, 33.68],
+        [-117.82, 33.69],
+        [-117.83, 33.69]
+    ]]);
+    let collection = json!({
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "id": "SITE-1",
+                "geometry": { "type": "Polygon", "coordinates": square },
+                "properties": {
+                    "name": "Stadium",
+                    "geofence_type": "temporary_restriction",
+                    "lower_limit": 0,
+                    "upper_limit": 150,
+                    "altitude_reference": "amsl",
+                    "start_time": "2030-05-01T18:00:00Z",
+                    "end_time": "2030-05-01T22:00:00Z"
+                }
+            },
+            {
+                "type": "Feature",
+                "geometry": { "type": "Polygon", "coordinates": square },
+                "properties": { "name": "Depot" }
+            },
+            {
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [-117.83, 33.68] },
+                "properties": { "name": "Pin" }
+            },
+            {
+                "type": "Feature",
+                "geometry": { "type": "Polygon", "coordinates": square },
+                "properties": {
+                    "name": "Upside down",
+                    "lower_altitude_m": 100,
+                    "upper_altitude_m": 50,
+                    "altitude_reference": "amsl"
+                }
+            }
+        ]
+    });
+    let res = app.clone().oneshot(import(collection)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let summary = read_json(res).await;
+    assert_eq!(summary["created"].as_array().unwrap().len(), 2);
+    assert_eq!(summary["created"][0], "SITE-1");
+    assert_eq!(
+        summary["skipped"],
+        json!([
+            "feature 2: Unsupported geometry type Point",
+            "feature 3: Lower altitude (100) must be less than upper altitude (50)"
+        ])
+    );
+
+    let get_req = Request::builder()
+        .uri("/v1/geofences/SITE-1")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.clone().oneshot(get_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let site = read_json(res).await;
+    assert_eq!(site["upper_altitude_m"], 150.0);
+    assert_eq!(site["polygon"].as_array().unwrap().len(), 5);
+    assert_eq!(site["schedule"]["end"], "2030-05-01T22:00:00Z");
+
+    let export_req = Request::builder()
+        .uri("/v1/geofences/export")
+        .body(Body::empty())
+        .unwrap();
+    let res = app.clone().oneshot(export_req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()["content-type"].to_str().unwrap(),
+        "application/geo+json"
+    );
+    let mut exported = read_json(res).await;
+    assert_eq!(exported["type"], "FeatureCollection");
+    assert_eq!(exported["features"].as_array().unwrap().len(), 2);
+    assert_eq!(exported["features"][0]["id"], "SITE-1");
+    assert_eq!(
+        exported["features"][0]["geometry"]["coordinates"][0][1],
+        json!([-117.82, 33.68])
+    );
+
+    // Edited in a GIS tool and loaded back: the same geofences are updated in place.
+    exported["features"][0]["properties"]["upper_altitude_m"] = json!(200.0);
+    let res = app.clone().oneshot(import(exported)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let summary = read_json(res).await;
+    assert_eq!(summary["created"], json!([]));
+    assert_eq!(summary["updated"].as_array().unwrap().len(), 2);
+    assert_eq!(summary["skipped"], json!([]));
+    let get_req = Request::builder()
+        .uri("/v1/geofences/SITE-1")
+        .body(Body::empty())
+        .unwrap();
+    let site = read_json(app.clone().oneshot(get_req).await.unwrap()).await;
+    assert_eq!(site["upper_altitude_m"], 200.0);
+    assert_eq!(site["schedule"]["start"], "2030-05-01T18:00:00Z");
+
+    let res = app
+        .oneshot(import(json!({ "type": "Polygon", "coordinates": square })))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn tfr_feed_geofences_block_routes_during_their_window() {
+    use crate::tfr::{parse_feed, TfrFeedFormat, TFR_SOURCE};
+
+    let (_app, state) = setup_app_with(|config| {
+        config.altitude_reference = crate::altitude::AltitudeReference::Amsl;
+        config.geoid_offset_m = 0.0;
+        config.terrain_require = false;
+    })
+    .await;
+    let now = Utc::now();
+    let area = json!([[
+        [-117.83, 33.68],
+        [-117.81, 33.68],
+        [-117.81, 33.70],
+        [-117.83, 33.70],
+        [-117.83, 33.68]
+    ]]);
+    let feature = |notam_id: &str, start: chrono::DateTime<Utc>| {
+        json!({
+            "type": "Feature",
+            "geometry": { "type": "Polygon", "coordinates": area },
+            "properties": {
+                "notam_id": notam_id,
+                "upper_ft": 3000,
+                "effective_start": start.to_rfc3339(),
+                "effective_end": (start + chrono::Duration::hours(2)).to_rfc3339()
+            }
+        })
+    };
+    let document = json!({
+        "type": "FeatureCollection",
+        "features": [
+            feature("6/1111", now - chrono::Duration::minutes(30)),
+            feature("6/2222", now + chrono::Duration::days(1))
+        ]
+    });
+    let feed = parse_feed(TfrFeedFormat::GeoJson, &document, now).unwrap();
+    assert_eq!(feed.geofences.len(), 2);
+
+    // TFRs and Blender geofences are replaced independently.
+    let mut blender = feed.geofences[0].clone();
+    blender.id = "blender:BG-1".to_string();
+    blender.polygon = vec![[10.0, 10.0], [10.0, 10.1], [10.1, 10.1], [10.0, 10.0]];
+    state.set_external_geofences("blender", vec![blender]);
+    state.set_external_geofences(TFR_SOURCE, feed.geofences);
+    assert!(state.is_external_geofence("blender:BG-1"));
+    assert!(state.is_external_geofence("tfr:6/1111"));
+
+    let crossing = FlightPlanRequest {
+        drone_id: "DRONE_TFR".to_string(),
+        owner_id: None,
+        waypoints: Some(vec![
+            Waypoint {
+                lat: 33.69,
+                lon: -117.85,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+            Waypoint {
+                lat: 33.69,
+                lon: -117.79,
+                altitude_m: 50.0,
+                speed_mps: None,
+            },
+        ]),
+        trajectory_log: None,
+        metadata: None,
+        origin: None,
+        destination: None,
+        departure_time: None,
+    };
+    let violations = crate::api::flights::validate_route(&state, &crossing).await;
+    let blocking: Vec<&Value> = violations
+        .iter()
+        .filter(|v| v["type"] == "geofence")
+        .collect();
+    assert_eq!(blocking.len(), 1, "{:?}", violations);
+    assert_eq!(blocking[0]["geofence_id"], "tfr:6/1111");
+
+    // A later pull without the first TFR lifts it and leaves Blender's alone.
+    state.set_external_geofences(TFR_SOURCE, Vec::new());
+    assert!(!state.is_external_geofence("tfr:6/1111"));
+    assert!(state.is_external_geofence("blender:BG-1"));
+    let violations = crate::api::flights::validate_route(&state, &crossing).await;
+    assert!(
+        !violations.iter().any(|v| v["type"] == "geofence"),
+        "{:?}",
+        violations
+    );
+}
@@ -0,0 +1,253 @@
+//! Unit negotiation for API requests and responses.
+//!
+//! The API works in metres and metres per second, and every length or speed field says so in
+//! its name (`altitude_m`, `speed_mps`). Clients may instead send the imperial twin of any such
+//! field (`altitude_ft`, `speed_kt`), in JSON bodies or query strings, and ask for responses in
+//! imperial units with `?units=imperial`. Conversion happens here and nowhere else, so handlers
+//! only ever see metric fields.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, uri::PathAndQuery, StatusCode, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::{json, Map, Value};
+
+/// Largest JSON body converted; matches the server's request body limit.
+pub const MAX_REQUEST_BODY_BYTES: usize = 1024 * 1024;
+
+/// Metric field suffix, its imperial twin and the metric value of one imperial unit.
+const UNIT_SUFFIXES: [(&str, &str, f64); 2] =
+    [("_m", "_ft", 0.3048), ("_mps", "_kt", 1852.0 / 3600.0)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnitSystem {
+    Metric,
+    Imperial,
+}
+
+impl UnitSystem {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "metric" | "si" => Some(Self::Metric),
+            "imperial" => Some(Self::Imperial),
+            _ => None,
+        }
+    }
+}
+
+/// Imperial field name of a metric one (`altitude_m` → `altitude_ft`), with the metres per unit.
+fn imperial_name(key: &str) -> Option<(String, f64)> {
+    UNIT_SUFFIXES.iter().find_map(|(metric, imperial, factor)| {
+        key.strip_suffix(metric)
+            .filter(|stem| !stem.is_empty())
+            .map(|stem| (format!("{stem}{imperial}"), *factor))
+    })
+}
+
+/// Metric field name of an imperial one (`speed_kt` → `speed_mps`), with the metres per unit.
+fn metric_name(key: &str) -> Option<(String, f64)> {
+    UNIT_SUFFIXES.iter().find_map(|(metric, imperial, factor)| {
+        key.strip_suffix(imperial)
+            .filter(|stem| !stem.is_empty())
+            .map(|stem| (format!("{stem}{metric}"), *factor))
+    })
+}
+
+/// Multiply a number, or every number in an array, by `factor`; `None` for any other value.
+fn scale(value: Value, factor: f64) -> Option<Value> {
+    match value {
+        Value::Null => Some(Value::Null),
+        Value::Number(number) => {
+            let scaled = number.as_f64()? * factor;
+            scaled.is_finite().then(|| json!(scaled))
+        }
+        Value::Array(items) => items
+            .into_iter()
+            .map(|item| scale(item, factor))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array),
+        _ => None,
+    }
+}
+
+/// Replace imperial fields with their metric twins, throughout a request body.
+fn to_metric(value: Value, path: &str, errors: &mut Vec<String>) -> Value {
+    match value {
+        Value::Object(fields) => {
+            let mut converted = Map::new();
+            let mut imperial = Vec::new();
+            for (key, value) in fields {
+                let field_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match metric_name(&key) {
+                    Some(_) => imperial.push((key, field_path, value)),
+                    None => {
+                        let value = to_metric(value, &field_path, errors);
+                        converted.insert(key, value);
+                    }
+                }
+            }
+            for (key, field_path, value) in imperial {
+                let Some((metric_key, factor)) = metric_name(&key) else {
+                    continue;
+                };
+                if converted.contains_key(&metric_key) {
+                    errors.push(format!(
+                        "{field_path}: give either {key} or {metric_key}, not both"
+                    ));
+                    continue;
+                }
+                match scale(value, factor) {
+                    Some(value) => {
+                        converted.insert(metric_key, value);
+                    }
+                    None => errors.push(format!("{field_path} must be a finite number")),
+                }
+            }
+            Value::Object(converted)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(idx, item)| to_metric(item, &format!("{path}[{idx}]"), errors))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Replace metric fields with their imperial twins, throughout a response body.
+fn to_imperial(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| match imperial_name(&key) {
+                    Some((imperial_key, factor)) => match scale(value.clone(), 1.0 / factor) {
+                        Some(value) => (imperial_key, value),
+                        None => (key, value),
+                    },
+                    None => (key, to_imperial(value)),
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(to_imperial).collect()),
+        other => other,
+    }
+}
+
+fn bad_request(details: Vec<String>) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({ "error": "Invalid units", "details": details })),
+    )
+        .into_response()
+}
+
+fn is_json(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim_start().starts_with("application/json"))
+}
+
+/// Rewrite imperial query parameters to metric ones; the unit system requested, if any.
+fn convert_query(uri: &Uri, errors: &mut Vec<String>) -> (Option<String>, Option<UnitSystem>) {
+    let Some(query) = uri.query() else {
+        return (None, None);
+    };
+    let keys: Vec<&str> = query
+        .split('&')
+        .map(|pair| pair.split_once('=').map_or(pair, |(key, _)| key))
+        .collect();
+    let mut units = None;
+    let mut changed = false;
+    let mut converted = Vec::with_capacity(keys.len());
+    for pair in query.split('&') {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        if key == "units" {
+            units = UnitSystem::parse(value);
+            if units.is_none() {
+                errors.push(format!("units must be metric or imperial, got '{value}'"));
+            }
+        }
+        let Some((metric_key, factor)) = metric_name(key) else {
+            converted.push(pair.to_string());
+            continue;
+        };
+        changed = true;
+        if keys.contains(&metric_key.as_str()) {
+            errors.push(format!("give either {key} or {metric_key}, not both"));
+            continue;
+        }
+        match value.parse::<f64>().map(|value| value * factor) {
+            Ok(value) if value.is_finite() => converted.push(format!("{metric_key}={value}")),
+            _ => errors.push(format!("{key} must be a finite number")),
+        }
+    }
+    (changed.then(|| converted.join("&")), units)
+}
+
+/// Middleware: convert imperial request fields to metric and, for `?units=imperial`, metric
+/// response fields to imperial.
+pub async fn negotiate_units(request: Request, next: Next) -> Response {
+    let mut errors = Vec::new();
+    let (query, units) = convert_query(request.uri(), &mut errors);
+    let (mut parts, body) = request.into_parts();
+    if let Some(query) = query {
+        let path_and_query = format!("{}?{}", parts.uri.path(), query);
+        let mut uri_parts = parts.uri.clone().into_parts();
+        uri_parts.path_and_query = PathAndQuery::try_from(path_and_query).ok();
+        if let Ok(uri) = Uri::from_parts(uri_parts) {
+            parts.uri = uri;
+        }
+    }
+
+    let body = if is_json(&parts.headers) {
+        let Ok(bytes) = to_bytes(body, MAX_REQUEST_BODY_BYTES).await else {
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(json!({ "error": "Request body too large" })),
+            )
+                .into_response();
+        };
+        match serde_json::from_slice::<Value>(&bytes) {
+            Ok(value) => {
+                let value = to_metric(value, "", &mut errors);
+                parts.headers.remove(header::CONTENT_LENGTH);
+                Body::from(value.to_string())
+            }
+            // Leave malformed JSON for the handler to reject as usual.
+            Err(_) => Body::from(bytes),
+        }
+    } else {
+        body
+    };
+    if !errors.is_empty() {
+        return bad_request(errors);
+    }
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+    if units != Some(UnitSystem::Imperial) || !is_json(response.headers()) {
+        return response;
+    }
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    match serde_json::from_slice::<Value>(&bytes) {
+        Ok(value) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(to_imperial(value).to_string()))
+        }
+        Err(_) => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
@@ -0,0 +1,62 @@
+//! Forecast weather the route planner avoids.
+
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use atc_core::WeatherCell;
+
+use crate::state::AppState;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// Upper bound on cells per forecast, to keep per-grid-point lookups cheap.
+const MAX_WEATHER_CELLS: usize = 5_000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WeatherForecast {
+    pub cells: Vec<WeatherCell>,
+}
+
+pub async fn get_weather(State(state): State<Arc<AppState>>) -> Json<WeatherForecast> {
+    Json(WeatherForecast {
+        cells: state.weather_cells(),
+    })
+}
+
+/// Replace the forecast. Cells are held in memory and dropped once they expire.
+pub async fn set_weather(
+    State(state): State<Arc<AppState>>,
+    Json(forecast): Json<WeatherForecast>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if forecast.cells.len() > MAX_WEATHER_CELLS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("At most {} weather cells are accepted", MAX_WEATHER_CELLS)
+            })),
+        ));
+    }
+    let errors: Vec<String> = forecast
+        .cells
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, cell)| {
+            cell.validate()
+                .into_iter()
+                .map(move |error| format!("cells[{}]: {}", idx, error))
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "Invalid weather cells", "details": errors })),
+        ));
+    }
+
+    let count = forecast.cells.len();
+    state.set_weather_cells(forecast.cells);
+    tracing::info!("Weather forecast replaced with {} cells", count);
+    Ok(Json(json!({ "cells": count })))
+}
@@ -0,0 +1,112 @@
+//! Webhook registration for operator event callbacks.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use crate::state::AppState;
+use crate::webhooks::{Webhook, WebhookEventType};
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    /// `http` or `https` URL the events are POSTed to.
+    pub url: String,
+    /// Signing secret; one is generated when omitted.
+    pub secret: Option<String>,
+    /// Event types to deliver; all when omitted or empty.
+    #[serde(default)]
+    pub events: Vec<WebhookEventType>,
+    /// Only events for this owner's drones.
+    pub owner_id: Option<String>,
+}
+
+/// A new webhook, with the secret it is signed with.
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+pub async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreatedWebhook>), ApiError> {
+    let url = request.url.trim();
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "url must be an http or https URL", "field": "url" })),
+            ))
+        }
+    }
+    let secret = match request.secret.map(|secret| secret.trim().to_string()) {
+        Some(secret) if secret.is_empty() => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "secret must not be empty", "field": "secret" })),
+            ))
+        }
+        Some(secret) => secret,
+        None => uuid::Uuid::new_v4().simple().to_string(),
+    };
+    let mut events = request.events;
+    events.dedup();
+    let webhook = Webhook {
+        id: uuid::Uuid::new_v4().to_string(),
+        url: url.to_string(),
+        secret: secret.clone(),
+        events,
+        owner_id: request
+            .owner_id
+            .map(|owner_id| owner_id.trim().to_string())
+            .filter(|owner_id| !owner_id.is_empty()),
+        created_at: Utc::now(),
+    };
+    state.add_webhook(webhook.clone()).await.map_err(|err| {
+        tracing::error!("Failed to persist webhook: {}", err);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": "Failed to save webhook" })),
+        )
+    })?;
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedWebhook { webhook, secret }),
+    ))
+}
+
+/// Registered webhooks, oldest first, without their secrets.
+pub async fn list_webhooks(State(state): State<Arc<AppState>>) -> Json<Vec<Webhook>> {
+    Json(state.list_webhooks())
+}
+
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    Path(webhook_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    match state.remove_webhook(&webhook_id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Webhook not found" })),
+        )),
+        Err(err) => {
+            tracing::error!("Failed to delete webhook {}: {}", webhook_id, err);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "Failed to delete webhook" })),
+            ))
+        }
+    }
+}
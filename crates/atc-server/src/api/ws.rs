@@ -1,4 +1,8 @@
 //! WebSocket streaming for real-time updates.
+use crate::api::auth::Caller;
+use crate::chaos::chaos;
+use crate::config::Config;
+use crate::persistence::emergency::EmergencyStop;
 use crate::state::AppState;
 use axum::{
     extract::{
@@ -9,46 +13,69 @@ use axum::{
     response::IntoResponse,
 };
 use serde::Deserialize;
+use serde_json::json;
 use std::sync::Arc;
+use utoipa::IntoParams;
 
 /// Handler for WebSocket connections.
+#[utoipa::path(
+    get,
+    path = "/v1/ws",
+    tag = "Drones",
+    params(WsQuery),
+    responses(
+        (status = 101, description = "WebSocket upgrade; each message is a drone state update or an emergency frame"),
+        (status = 401, description = "Missing or invalid WebSocket token"),
+    ),
+    security(("bearerAuth" = [])),
+)]
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
+    caller: Caller,
     headers: HeaderMap,
     Query(params): Query<WsQuery>,
 ) -> axum::response::Response {
-    let config = state.config();
     let provided = params.token.clone().or_else(|| extract_bearer(&headers));
-    let admin_token = config.admin_token.as_str();
-
-    if config.require_ws_token {
-        let expected = config.ws_token.as_deref().unwrap_or_default();
-        if provided.as_deref() != Some(expected) && provided.as_deref() != Some(admin_token) {
-            return StatusCode::UNAUTHORIZED.into_response();
-        }
-    } else if let Some(expected) = config.ws_token.as_deref() {
-        if let Some(token) = provided.as_deref() {
-            if token != expected && token != admin_token {
-                return StatusCode::UNAUTHORIZED.into_response();
-            }
-        }
+    let org_filter = caller.org_id().map(str::to_string);
+    if org_filter.is_none() && !stream_token_accepted(state.config(), provided.as_deref()) {
+        return StatusCode::UNAUTHORIZED.into_response();
     }
 
-    let owner_filter = params.owner_id.clone();
-    let drone_filter = params.drone_id.clone();
-    ws.on_upgrade(move |socket| handle_socket(socket, state, owner_filter, drone_filter))
+    let filters = StreamFilters {
+        owner_id: params.owner_id.clone(),
+        drone_id: params.drone_id.clone(),
+        org_id: org_filter,
+    };
+    ws.on_upgrade(move |socket| handle_socket(socket, state, filters))
         .into_response()
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct WsQuery {
     token: Option<String>,
     owner_id: Option<String>,
     drone_id: Option<String>,
 }
 
-fn extract_bearer(headers: &HeaderMap) -> Option<String> {
+/// Check a realtime stream token against `ATC_WS_TOKEN` (or the admin token).
+pub(super) fn stream_token_accepted(config: &Config, provided: Option<&str>) -> bool {
+    let admin_token = config.admin_token();
+    let admin_token = admin_token.as_str();
+    let ws_token = config.ws_token();
+
+    if config.require_ws_token {
+        let expected = ws_token.as_deref().unwrap_or_default();
+        provided == Some(expected) || provided == Some(admin_token)
+    } else if let (Some(expected), Some(token)) = (ws_token.as_deref(), provided) {
+        token == expected || token == admin_token
+    } else {
+        true
+    }
+}
+
+pub(super) fn extract_bearer(headers: &HeaderMap) -> Option<String> {
     let value = headers.get(AUTHORIZATION)?.to_str().ok()?;
     let token = value.strip_prefix("Bearer ")?;
     let trimmed = token.trim();
@@ -59,13 +86,28 @@ fn extract_bearer(headers: &HeaderMap) -> Option<String> {
     }
 }
 
-async fn handle_socket(
-    mut socket: WebSocket,
-    state: Arc<AppState>,
-    owner_filter: Option<String>,
-    drone_filter: Option<String>,
-) {
+/// Which drone updates a socket receives.
+struct StreamFilters {
+    owner_id: Option<String>,
+    drone_id: Option<String>,
+    /// Organization token holders only see their members' drones.
+    org_id: Option<String>,
+}
+
+async fn handle_socket(mut socket: WebSocket, state: Arc<AppState>, filters: StreamFilters) {
     let mut rx = state.tx.subscribe();
+    let mut emergency_rx = state.subscribe_emergency();
+
+    // Clients connecting during an emergency learn of it straight away.
+    if let Some(stop) = state.emergency_stop() {
+        if socket
+            .send(Message::Text(emergency_frame(Some(stop))))
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
 
     loop {
         tokio::select! {
@@ -84,16 +126,28 @@ async fn handle_socket(
             event = rx.recv() => {
                 match event {
                     Ok(msg) => {
-                        if let Some(owner_id) = owner_filter.as_deref() {
+                        if let Some(owner_id) = filters.owner_id.as_deref() {
                             if msg.owner_id.as_deref() != Some(owner_id) {
                                 continue;
                             }
                         }
-                        if let Some(drone_id) = drone_filter.as_deref() {
+                        if let Some(org_id) = filters.org_id.as_deref() {
+                            let org_of = msg
+                                .owner_id
+                                .as_deref()
+                                .and_then(|owner_id| state.organization_of_owner(owner_id));
+                            if org_of.as_deref() != Some(org_id) {
+                                continue;
+                            }
+                        }
+                        if let Some(drone_id) = filters.drone_id.as_deref() {
                             if msg.drone_id != drone_id {
                                 continue;
                             }
                         }
+                        if chaos().drop_ws_frame() {
+                            continue;
+                        }
                         if socket.send(Message::Text(msg.payload.as_ref().to_owned())).await.is_err() {
                             break;
                         }
@@ -105,6 +159,102 @@ async fn handle_socket(
                     Err(_) => break,
                 }
             }
+            event = emergency_rx.recv() => {
+                // Emergency frames go to every client, whatever its filters.
+                let stop = match event {
+                    Ok(stop) => stop,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => state.emergency_stop(),
+                    Err(_) => break,
+                };
+                if socket.send(Message::Text(emergency_frame(stop))).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// WS frame announcing an emergency all-stop (`active: true`) or its clearance.
+fn emergency_frame(stop: Option<EmergencyStop>) -> String {
+    json!({ "type": "emergency", "active": stop.is_some(), "emergency": stop }).to_string()
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DispatchWsQuery {
+    /// Dispatcher principal whose sectors to stream
+    dispatcher: String,
+}
+
+/// Stream sector-tagged conflicts, advisories and approval items to one dispatcher.
+#[utoipa::path(
+    get,
+    path = "/v1/dispatch/ws",
+    tag = "Dispatch",
+    params(DispatchWsQuery),
+    responses(
+        (status = 101, description = "WebSocket upgrade; each message is a dispatch notification"),
+        (status = 400, description = "Missing dispatcher"),
+    ),
+    security(("bearerAuth" = [])),
+)]
+pub async fn dispatch_ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<DispatchWsQuery>,
+) -> axum::response::Response {
+    let dispatcher = params.dispatcher.trim().to_string();
+    if dispatcher.is_empty() {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    ws.on_upgrade(move |socket| handle_dispatch_socket(socket, state, dispatcher))
+        .into_response()
+}
+
+async fn handle_dispatch_socket(mut socket: WebSocket, state: Arc<AppState>, dispatcher: String) {
+    let mut rx = state.subscribe_dispatch();
+
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(Message::Ping(payload))) => {
+                        if socket.send(Message::Pong(payload)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => break,
+                }
+            }
+            event = rx.recv() => {
+                match event {
+                    Ok(notification) => {
+                        if notification.dispatcher != dispatcher {
+                            continue;
+                        }
+                        let Ok(payload) = serde_json::to_string(&notification) else {
+                            continue;
+                        };
+                        if chaos().drop_ws_frame() {
+                            continue;
+                        }
+                        if socket.send(Message::Text(payload)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            "Dispatch stream for {} lagged; {} notifications dropped",
+                            dispatcher,
+                            skipped
+                        );
+                        continue;
+                    }
+                    Err(_) => break,
+                }
+            }
         }
     }
 }
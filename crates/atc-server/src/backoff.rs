@@ -44,7 +44,7 @@ impl Backoff {
     }
 }
 
-fn add_jitter(delay: Duration, ratio: f64) -> Duration {
+pub(crate) fn add_jitter(delay: Duration, ratio: f64) -> Duration {
     if !(0.0..=1.0).contains(&ratio) {
         return delay;
     }
@@ -62,6 +62,15 @@ async fn main() {
             safety_buffer_m: None,
             max_lane_radius_m: route.max_lane_radius_m,
             lane_expansion_step_m: None,
+            takeoff_landing: None,
+            departure_time: None,
+            c2_coverage: None,
+            turn_radius_m: None,
+            geofence_standoff_m: None,
+            search: None,
+            timeout_ms: None,
+            n_alternatives: None,
+            seed: None,
         };
 
         let result = plan_route(&state, &config, request).await;
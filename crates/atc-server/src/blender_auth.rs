@@ -16,6 +16,8 @@ const TOKEN_REFRESH_LEEWAY_SECS: u64 = 60;
 struct CachedToken {
     access_token: String,
     expires_at: Instant,
+    /// Secret store generation the token was fetched with.
+    secrets_generation: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -35,8 +37,8 @@ struct OAuthTokenResponse {
 }
 
 pub struct BlenderAuthManager {
-    oauth: Option<BlenderOAuthConfig>,
-    static_token: String,
+    /// Credentials are read from config on every resolve so secret rotation applies.
+    config: Config,
     allow_dummy: bool,
     client: Client,
     cached: RwLock<Option<CachedToken>>,
@@ -45,8 +47,7 @@ pub struct BlenderAuthManager {
 impl BlenderAuthManager {
     pub fn new(config: &Config) -> Self {
         Self {
-            oauth: config.blender_oauth_config(),
-            static_token: config.blender_auth_token.clone(),
+            config: config.clone(),
             allow_dummy: config.allow_dummy_blender_auth,
             client: Client::new(),
             cached: RwLock::new(None),
@@ -63,18 +64,19 @@ impl BlenderAuthManager {
     }
 
     async fn resolve_token(&self) -> Result<Option<String>> {
-        if let Some(oauth) = &self.oauth {
+        if let Some(oauth) = self.config.blender_oauth_config() {
             if let Some(token) = self.cached_token().await {
                 return Ok(Some(token));
             }
-            let fresh = self.fetch_oauth_token(oauth).await?;
+            let fresh = self.fetch_oauth_token(&oauth).await?;
             let token = fresh.access_token.clone();
             let mut guard = self.cached.write().await;
             *guard = Some(fresh);
             return Ok(Some(token));
         }
 
-        let token = self.static_token.trim();
+        let token = self.config.blender_auth_token();
+        let token = token.trim();
         if token.is_empty() {
             return Ok(None);
         }
@@ -84,7 +86,9 @@ impl BlenderAuthManager {
     async fn cached_token(&self) -> Option<String> {
         let guard = self.cached.read().await;
         guard.as_ref().and_then(|cached| {
-            if cached.expires_at > Instant::now() {
+            if cached.expires_at > Instant::now()
+                && cached.secrets_generation == self.config.secrets.generation()
+            {
                 Some(cached.access_token.clone())
             } else {
                 None
@@ -93,6 +97,7 @@ impl BlenderAuthManager {
     }
 
     async fn fetch_oauth_token(&self, oauth: &BlenderOAuthConfig) -> Result<CachedToken> {
+        let secrets_generation = self.config.secrets.generation();
         let request = OAuthTokenRequest {
             grant_type: "client_credentials",
             client_id: oauth.client_id.as_str(),
@@ -124,6 +129,7 @@ impl BlenderAuthManager {
         Ok(CachedToken {
             access_token: payload.access_token,
             expires_at: Instant::now() + Duration::from_secs(ttl),
+            secrets_generation,
         })
     }
 }
@@ -0,0 +1,432 @@
+//! Geofence breach auto-response.
+//!
+//! Each geofence type maps to a response (advisory, hold, reroute or land) that an individual
+//! geofence can override. Breaches are detected from live telemetry in the conflict loop and
+//! reported by Blender conformance in the conformance loop; both paths pick the command here,
+//! share the per-drone command cooldown, and write to the breach audit log.
+
+use std::collections::HashMap;
+use std::env;
+
+use atc_core::messages::{codes, Message};
+use atc_core::models::{
+    BreachResponse, Command, CommandType, DaaAdvisory, DaaSeverity, DroneState, DroneStatus,
+    Geofence, GeofenceType,
+};
+use atc_core::{predict_geofence_breach, ConflictSeverity, DronePosition};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+
+use crate::loops::conformance_loop::{
+    compute_geofence_exit, CONFORMANCE_COMMAND_COOLDOWN_SECS, CONFORMANCE_HOLD_SECS,
+};
+use crate::persistence::command_audit::{CommandCause, CommandCauseKind};
+use crate::state::AppState;
+
+/// Breach audit entries kept in memory.
+pub const BREACH_LOG_CAPACITY: usize = 500;
+
+/// Default response per geofence type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreachPolicy {
+    pub no_fly_zone: BreachResponse,
+    pub restricted_area: BreachResponse,
+    pub temporary_restriction: BreachResponse,
+    pub advisory: BreachResponse,
+}
+
+impl Default for BreachPolicy {
+    fn default() -> Self {
+        Self {
+            no_fly_zone: BreachResponse::Reroute,
+            restricted_area: BreachResponse::Reroute,
+            temporary_restriction: BreachResponse::Reroute,
+            advisory: BreachResponse::Advisory,
+        }
+    }
+}
+
+impl BreachPolicy {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let read = |name: &str, default: BreachResponse| {
+            env::var(name)
+                .ok()
+                .and_then(|value| parse_breach_response(&value))
+                .unwrap_or(default)
+        };
+        Self {
+            no_fly_zone: read("ATC_BREACH_RESPONSE_NO_FLY_ZONE", defaults.no_fly_zone),
+            restricted_area: read(
+                "ATC_BREACH_RESPONSE_RESTRICTED_AREA",
+                defaults.restricted_area,
+            ),
+            temporary_restriction: read(
+                "ATC_BREACH_RESPONSE_TEMPORARY_RESTRICTION",
+                defaults.temporary_restriction,
+            ),
+            advisory: read("ATC_BREACH_RESPONSE_ADVISORY", defaults.advisory),
+        }
+    }
+
+    /// Response for a geofence: its own override, else the default for its type.
+    pub fn response_for(&self, geofence: &Geofence) -> BreachResponse {
+        geofence
+            .breach_response
+            .unwrap_or(match geofence.geofence_type {
+                GeofenceType::NoFlyZone => self.no_fly_zone,
+                GeofenceType::RestrictedArea => self.restricted_area,
+                GeofenceType::TemporaryRestriction => self.temporary_restriction,
+                GeofenceType::Advisory => self.advisory,
+            })
+    }
+}
+
+pub fn parse_breach_response(value: &str) -> Option<BreachResponse> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "advisory" | "monitor" => Some(BreachResponse::Advisory),
+        "hold" => Some(BreachResponse::Hold),
+        "reroute" => Some(BreachResponse::Reroute),
+        "land" => Some(BreachResponse::Land),
+        _ => None,
+    }
+}
+
+fn response_label(response: BreachResponse) -> &'static str {
+    match response {
+        BreachResponse::Advisory => "monitor",
+        BreachResponse::Hold => "hold",
+        BreachResponse::Reroute => "reroute",
+        BreachResponse::Land => "land",
+    }
+}
+
+/// Stronger responses are handled first when a drone breaches several geofences.
+fn response_rank(response: BreachResponse) -> u8 {
+    match response {
+        BreachResponse::Advisory => 0,
+        BreachResponse::Hold => 1,
+        BreachResponse::Reroute => 2,
+        BreachResponse::Land => 3,
+    }
+}
+
+/// Whether a drone is inside a geofence or projected to enter it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BreachKind {
+    Inside,
+    Imminent { time_to_breach_s: f64 },
+}
+
+/// Check a drone against a geofence, projecting its velocity up to `lookahead_s` ahead.
+pub fn detect_breach(
+    drone: &DroneState,
+    geofence: &Geofence,
+    lookahead_s: f64,
+) -> Option<BreachKind> {
+    let position = DronePosition::new(&drone.drone_id, drone.lat, drone.lon, drone.altitude_m)
+        .with_velocity(drone.heading_deg, drone.speed_mps, drone.velocity_z);
+    predict_geofence_breach(&position, geofence, lookahead_s).map(|breach| match breach.severity {
+        ConflictSeverity::Critical => BreachKind::Inside,
+        _ => BreachKind::Imminent {
+            time_to_breach_s: breach.time_to_breach_s,
+        },
+    })
+}
+
+/// Command for a breach response; `None` means advisory only.
+///
+/// A reroute flies the drone out of the geofence when it is already inside. Without an exit
+/// (or before entry) the drone holds so it stops short of the boundary.
+pub fn breach_command_type(
+    response: BreachResponse,
+    kind: BreachKind,
+    drone: &DroneState,
+    geofence: &Geofence,
+) -> Option<CommandType> {
+    let hold = CommandType::Hold {
+        duration_secs: CONFORMANCE_HOLD_SECS,
+    };
+    match response {
+        BreachResponse::Advisory => None,
+        BreachResponse::Hold => Some(hold),
+        BreachResponse::Land => Some(CommandType::Land),
+        BreachResponse::Reroute => match kind {
+            BreachKind::Inside => Some(
+                compute_geofence_exit(drone, geofence)
+                    .map(|exit| CommandType::Reroute {
+                        waypoints: vec![exit],
+                        reason: Some("Geofence breach: exit route".to_string()),
+                    })
+                    .unwrap_or(hold),
+            ),
+            BreachKind::Imminent { .. } => Some(hold),
+        },
+    }
+}
+
+/// One automatic breach response, as recorded in the audit log.
+#[derive(Debug, Clone, Serialize)]
+pub struct BreachEvent {
+    pub drone_id: String,
+    pub geofence_id: String,
+    pub geofence_type: GeofenceType,
+    #[serde(flatten)]
+    pub kind: BreachKind,
+    pub response: BreachResponse,
+    /// `monitor` (local telemetry) or `conformance` (Blender report).
+    pub source: String,
+    /// Command issued for this breach, if any.
+    pub command_id: Option<String>,
+    /// `issued`, `advisory`, `suppressed` (cooldown or pending command), `throttled`
+    /// (automatic-command budget exhausted) or `failed`.
+    pub outcome: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+fn advisory_id(drone_id: &str, geofence_id: &str) -> String {
+    format!("geofence-{}-{}", drone_id, geofence_id)
+}
+
+/// Apply the policy for one breach: raise the advisory, issue the command if the drone is not
+/// cooling down, and write the audit entry.
+pub async fn respond_to_breach(
+    state: &AppState,
+    drone: &DroneState,
+    geofence: &Geofence,
+    kind: BreachKind,
+    source: &str,
+) -> BreachEvent {
+    let now = Utc::now();
+    let response = state.config().breach_policy.response_for(geofence);
+    let (severity, message) = match kind {
+        BreachKind::Inside => (
+            DaaSeverity::Critical,
+            Message::new(codes::ADVISORY_GEOFENCE_INSIDE),
+        ),
+        BreachKind::Imminent { time_to_breach_s } => (
+            DaaSeverity::Warning,
+            Message::new(codes::ADVISORY_GEOFENCE_IMMINENT)
+                .with("time_to_breach_s", time_to_breach_s),
+        ),
+    };
+    let message = message
+        .with("geofence_name", geofence.name.as_str())
+        .with("geofence_type", format!("{:?}", geofence.geofence_type));
+    state.set_daa_advisory(DaaAdvisory {
+        advisory_id: advisory_id(&drone.drone_id, &geofence.id),
+        drone_id: drone.drone_id.clone(),
+        owner_id: drone.owner_id.clone(),
+        source: "geofence".to_string(),
+        severity,
+        action: response_label(response).to_string(),
+        description: state.config().messages.format(&message, None),
+        message_code: Some(message),
+        related_id: Some(geofence.id.clone()),
+        record: None,
+        sector_id: None,
+        created_at: now,
+        updated_at: now,
+        resolved: false,
+    });
+
+    let mut command_id = None;
+    let outcome = match breach_command_type(response, kind, drone, geofence) {
+        None => "advisory",
+        Some(_) if !state.can_issue_command(&drone.drone_id, CONFORMANCE_COMMAND_COOLDOWN_SECS) => {
+            "suppressed"
+        }
+        Some(command_type) => {
+            let id = format!(
+                "BREACH-{}-{}-{}",
+                response_label(response).to_ascii_uppercase(),
+                drone.drone_id,
+                now.timestamp()
+            );
+            let cmd = Command {
+                command_id: id.clone(),
+                drone_id: drone.drone_id.clone(),
+                command_type,
+                issued_at: now,
+                expires_at: Some(now + ChronoDuration::seconds(CONFORMANCE_HOLD_SECS as i64)),
+                acknowledged: false,
+            };
+            match state
+                .enqueue_auto_command(
+                    cmd,
+                    CommandCause::new(CommandCauseKind::Geofence).with_source(&geofence.id),
+                )
+                .await
+            {
+                Ok(true) => {
+                    state.mark_command_issued(&drone.drone_id);
+                    command_id = Some(id);
+                    "issued"
+                }
+                Ok(false) => {
+                    state.mark_command_issued(&drone.drone_id);
+                    "throttled"
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to enqueue breach response for {}: {}",
+                        drone.drone_id,
+                        err
+                    );
+                    "failed"
+                }
+            }
+        }
+    };
+
+    let event = BreachEvent {
+        drone_id: drone.drone_id.clone(),
+        geofence_id: geofence.id.clone(),
+        geofence_type: geofence.geofence_type,
+        kind,
+        response,
+        source: source.to_string(),
+        command_id,
+        outcome: outcome.to_string(),
+        occurred_at: now,
+    };
+    state.record_breach_event(event.clone());
+    event
+}
+
+/// Check live drones against geofences and respond to breaches.
+///
+/// `active` tracks breaches seen on earlier ticks so each new breach is audited once, responses
+/// are retried while it lasts, and the advisory is resolved when it clears.
+pub async fn monitor_breaches(
+    state: &AppState,
+    active: &mut HashMap<(String, String), BreachKind>,
+) {
+    let lookahead_s = state.config().breach_lookahead_secs;
+    let policy = state.config().breach_policy;
+    let mut geofences: Vec<Geofence> = state
+        .get_geofences()
+        .into_iter()
+        .filter(|geofence| geofence.in_force_at(Utc::now()))
+        .collect();
+    geofences
+        .sort_by_key(|geofence| std::cmp::Reverse(response_rank(policy.response_for(geofence))));
+
+    let mut current: HashMap<(String, String), BreachKind> = HashMap::new();
+    for drone in state.get_all_drones() {
+        if matches!(drone.status, DroneStatus::Lost | DroneStatus::Inactive) {
+            continue;
+        }
+        for geofence in &geofences {
+            let Some(kind) = detect_breach(&drone, geofence, lookahead_s) else {
+                continue;
+            };
+            let key = (drone.drone_id.clone(), geofence.id.clone());
+            let changed = active.get(&key).is_none_or(|previous| {
+                std::mem::discriminant(previous) != std::mem::discriminant(&kind)
+            });
+            current.insert(key, kind);
+            // Re-run quietly while a breach persists; only new breaches or issued commands are audited.
+            let would_act = changed
+                || (state.can_issue_command(&drone.drone_id, CONFORMANCE_COMMAND_COOLDOWN_SECS)
+                    && breach_command_type(policy.response_for(geofence), kind, &drone, geofence)
+                        .is_some());
+            if would_act {
+                respond_to_breach(state, &drone, geofence, kind, "monitor").await;
+            }
+        }
+    }
+
+    for (drone_id, geofence_id) in active.keys() {
+        if !current.contains_key(&(drone_id.clone(), geofence_id.clone())) {
+            state.resolve_daa_advisory(&advisory_id(drone_id, geofence_id));
+            tracing::info!("Drone {} cleared geofence {}", drone_id, geofence_id);
+        }
+    }
+    *active = current;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fence(response: Option<BreachResponse>) -> Geofence {
+        Geofence {
+            id: "fence".to_string(),
+            name: "Fence".to_string(),
+            geofence_type: GeofenceType::NoFlyZone,
+            polygon: vec![
+                [0.0, 0.001],
+                [0.0, 0.002],
+                [0.001, 0.002],
+                [0.001, 0.001],
+                [0.0, 0.001],
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 120.0,
+            active: true,
+            created_at: Utc::now(),
+            breach_response: response,
+            schedule: None,
+            org_id: None,
+        }
+    }
+
+    fn drone(lon: f64, speed_mps: f64) -> DroneState {
+        DroneState {
+            drone_id: "D1".to_string(),
+            owner_id: None,
+            lat: 0.0005,
+            lon,
+            altitude_m: 50.0,
+            heading_deg: 90.0,
+            speed_mps,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            velocity_z: 0.0,
+            last_update: Utc::now(),
+            status: DroneStatus::Active,
+            scheduling_priority: None,
+            capabilities: None,
+        }
+    }
+
+    #[test]
+    fn policy_and_override_pick_the_response() {
+        let policy = BreachPolicy::default();
+        let inside = drone(0.0015, 0.0);
+        let default_fence = fence(None);
+        assert_eq!(policy.response_for(&default_fence), BreachResponse::Reroute);
+        assert!(matches!(
+            breach_command_type(
+                policy.response_for(&default_fence),
+                BreachKind::Inside,
+                &inside,
+                &default_fence
+            ),
+            Some(CommandType::Reroute { .. })
+        ));
+
+        let land_fence = fence(Some(BreachResponse::Land));
+        assert!(matches!(
+            breach_command_type(
+                policy.response_for(&land_fence),
+                BreachKind::Inside,
+                &inside,
+                &land_fence
+            ),
+            Some(CommandType::Land)
+        ));
+
+        // ~55m west of the fence at 10 m/s: imminent within 10s, clear with a 3s lookahead.
+        let approaching = drone(0.0005, 10.0);
+        match detect_breach(&approaching, &default_fence, 10.0) {
+            Some(BreachKind::Imminent { time_to_breach_s }) => {
+                assert!((5.0..=7.0).contains(&time_to_breach_s))
+            }
+            other => panic!("expected imminent breach, got {:?}", other),
+        }
+        assert_eq!(detect_breach(&approaching, &default_fence, 3.0), None);
+    }
+}
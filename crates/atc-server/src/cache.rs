@@ -1,11 +1,44 @@
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use std::hash::Hash;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub trait CacheEntry {
     fn fetched_at(&self) -> Instant;
 }
 
+/// A cache entry as written to disk, with its fetch time as a Unix timestamp.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry<V> {
+    pub key: String,
+    pub fetched_at_unix_s: u64,
+    pub value: V,
+}
+
+impl<V> SnapshotEntry<V> {
+    pub fn new(key: String, fetched_at: Instant, value: V) -> Self {
+        let now_unix_s = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            key,
+            fetched_at_unix_s: now_unix_s.saturating_sub(fetched_at.elapsed().as_secs()),
+            value,
+        }
+    }
+
+    /// When the entry was fetched, or `None` once it is older than `max_age`.
+    pub fn restored_at(&self, max_age: Duration) -> Option<Instant> {
+        let now_unix_s = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        let age = Duration::from_secs(now_unix_s.saturating_sub(self.fetched_at_unix_s));
+        if age > max_age {
+            return None;
+        }
+        Instant::now().checked_sub(age)
+    }
+}
+
 pub fn prune_cache<K, V>(cache: &DashMap<K, V>, max_entries: usize, max_age: Duration)
 where
     K: Clone + Eq + Hash,
@@ -0,0 +1,224 @@
+//! Fault injection for staging.
+//!
+//! Injection points sit on the paths whose recovery logic is otherwise only exercised by real
+//! outages: Blender calls (loop supervision and retries), telemetry and usage DB flushes
+//! (backoff and re-queueing), Overpass obstacle fetches (timeouts and stale-cache fallback) and
+//! WebSocket frames (client resync). All rates default to zero, so the hooks are inert; they can
+//! only be turned on through `/v1/admin/chaos`, which exists when the server is built with the
+//! `chaos` cargo feature.
+
+// Without the feature nothing can change the settings, so the control surface is unused.
+#![cfg_attr(not(feature = "chaos"), allow(dead_code))]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Upper bound for injected Overpass delays.
+pub const MAX_OVERPASS_DELAY_MS: u64 = 60_000;
+
+/// Fault probabilities (0.0..=1.0) per injection point.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosSettings {
+    /// Fraction of Blender requests that fail before reaching the network.
+    pub blender_failure_rate: f64,
+    /// Fraction of telemetry/usage DB flushes that fail.
+    pub db_write_failure_rate: f64,
+    /// Fraction of Overpass fetches delayed by `overpass_delay_ms`.
+    pub overpass_delay_rate: f64,
+    pub overpass_delay_ms: u64,
+    /// Fraction of outbound WebSocket frames silently dropped.
+    pub ws_drop_rate: f64,
+}
+
+impl ChaosSettings {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, rate) in [
+            ("blender_failure_rate", self.blender_failure_rate),
+            ("db_write_failure_rate", self.db_write_failure_rate),
+            ("overpass_delay_rate", self.overpass_delay_rate),
+            ("ws_drop_rate", self.ws_drop_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                errors.push(format!("{} must be between 0 and 1", name));
+            }
+        }
+        if self.overpass_delay_ms > MAX_OVERPASS_DELAY_MS {
+            errors.push(format!(
+                "overpass_delay_ms must be at most {}",
+                MAX_OVERPASS_DELAY_MS
+            ));
+        }
+        errors
+    }
+}
+
+/// Faults injected since startup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ChaosStats {
+    pub blender_failures: u64,
+    pub db_write_failures: u64,
+    pub overpass_delays: u64,
+    pub ws_frames_dropped: u64,
+}
+
+#[derive(Debug)]
+pub struct Chaos {
+    settings: RwLock<ChaosSettings>,
+    rng: AtomicU64,
+    blender_failures: AtomicU64,
+    db_write_failures: AtomicU64,
+    overpass_delays: AtomicU64,
+    ws_frames_dropped: AtomicU64,
+}
+
+impl Default for Chaos {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::with_seed(seed)
+    }
+}
+
+impl Chaos {
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            settings: RwLock::new(ChaosSettings::default()),
+            // xorshift state must be non-zero.
+            rng: AtomicU64::new(seed | 1),
+            blender_failures: AtomicU64::new(0),
+            db_write_failures: AtomicU64::new(0),
+            overpass_delays: AtomicU64::new(0),
+            ws_frames_dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn settings(&self) -> ChaosSettings {
+        self.settings.read().map(|s| *s).unwrap_or_default()
+    }
+
+    pub fn set_settings(&self, settings: ChaosSettings) {
+        if let Ok(mut current) = self.settings.write() {
+            *current = settings;
+        }
+    }
+
+    pub fn stats(&self) -> ChaosStats {
+        ChaosStats {
+            blender_failures: self.blender_failures.load(Ordering::Relaxed),
+            db_write_failures: self.db_write_failures.load(Ordering::Relaxed),
+            overpass_delays: self.overpass_delays.load(Ordering::Relaxed),
+            ws_frames_dropped: self.ws_frames_dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Uniform sample in [0, 1) from a shared xorshift64 generator.
+    fn sample(&self) -> f64 {
+        let mut next = 0;
+        let _ = self
+            .rng
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |mut x| {
+                x ^= x << 13;
+                x ^= x >> 7;
+                x ^= x << 17;
+                next = x;
+                Some(x)
+            });
+        (next >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn roll(&self, rate: f64, counter: &AtomicU64) -> bool {
+        if rate <= 0.0 || self.sample() >= rate {
+            return false;
+        }
+        counter.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    pub fn fail_blender_request(&self) -> bool {
+        self.roll(self.settings().blender_failure_rate, &self.blender_failures)
+    }
+
+    pub fn fail_db_write(&self) -> bool {
+        self.roll(
+            self.settings().db_write_failure_rate,
+            &self.db_write_failures,
+        )
+    }
+
+    pub fn overpass_delay(&self) -> Option<Duration> {
+        let settings = self.settings();
+        if settings.overpass_delay_ms == 0
+            || !self.roll(settings.overpass_delay_rate, &self.overpass_delays)
+        {
+            return None;
+        }
+        Some(Duration::from_millis(settings.overpass_delay_ms))
+    }
+
+    pub fn drop_ws_frame(&self) -> bool {
+        self.roll(self.settings().ws_drop_rate, &self.ws_frames_dropped)
+    }
+}
+
+/// Process-wide fault injector.
+pub fn chaos() -> &'static Chaos {
+    static CHAOS: OnceLock<Chaos> = OnceLock::new();
+    CHAOS.get_or_init(Chaos::default)
+}
+
+/// Route Blender client fault checks through the process-wide injector.
+#[cfg(feature = "chaos")]
+pub fn install_blender_hook() {
+    atc_blender::set_fault_hook(Some(std::sync::Arc::new(|_operation| {
+        chaos()
+            .fail_blender_request()
+            .then(|| "chaos blender failure".to_string())
+    })));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faults_follow_configured_rates() {
+        let chaos = Chaos::with_seed(42);
+        for _ in 0..100 {
+            assert!(!chaos.fail_blender_request());
+            assert!(!chaos.drop_ws_frame());
+        }
+
+        chaos.set_settings(ChaosSettings {
+            blender_failure_rate: 1.0,
+            ws_drop_rate: 0.25,
+            overpass_delay_rate: 1.0,
+            overpass_delay_ms: 500,
+            ..Default::default()
+        });
+        assert!(chaos.fail_blender_request());
+        assert!(!chaos.fail_db_write());
+        assert_eq!(chaos.overpass_delay(), Some(Duration::from_millis(500)));
+
+        let dropped = (0..4000).filter(|_| chaos.drop_ws_frame()).count();
+        assert!((800..1200).contains(&dropped), "dropped = {}", dropped);
+
+        let stats = chaos.stats();
+        assert_eq!(stats.blender_failures, 1);
+        assert_eq!(stats.overpass_delays, 1);
+        assert_eq!(stats.ws_frames_dropped, dropped as u64);
+
+        let invalid = ChaosSettings {
+            db_write_failure_rate: 1.5,
+            overpass_delay_ms: MAX_OVERPASS_DELAY_MS + 1,
+            ..Default::default()
+        };
+        assert_eq!(invalid.validate().len(), 2);
+    }
+}
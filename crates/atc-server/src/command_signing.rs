@@ -0,0 +1,69 @@
+//! Ed25519 signing of commands delivered to drones.
+//!
+//! When `ATC_COMMAND_SIGNING_KEY` is set, every command handed to a drone carries a
+//! signature over its canonical JSON, together with those exact JSON bytes. Drones receive the public key when they register and
+//! reject commands that don't verify, so a relay between ATC and the drone can't forge or
+//! alter reroutes.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use sha2::{Digest, Sha256};
+
+use atc_core::models::{Command, CommandSignature, CommandSigningKey, SignedCommand};
+
+pub const SIGNING_ALGORITHM: &str = "ed25519";
+
+pub struct CommandSigner {
+    key_pair: Ed25519KeyPair,
+    key: CommandSigningKey,
+}
+
+impl CommandSigner {
+    /// Build a signer from a base64-encoded 32-byte Ed25519 seed.
+    pub fn from_seed_base64(seed: &str) -> Result<Self> {
+        let seed = BASE64
+            .decode(seed.trim())
+            .context("Command signing key is not valid base64")?;
+        if seed.len() != 32 {
+            anyhow::bail!(
+                "Command signing key must be a 32-byte Ed25519 seed (got {} bytes)",
+                seed.len()
+            );
+        }
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .map_err(|_| anyhow::anyhow!("Command signing key rejected"))?;
+        let public_key = key_pair.public_key().as_ref().to_vec();
+        // Short, stable identifier so drones can tell which key signed a command.
+        let key_id = hex::encode(&Sha256::digest(&public_key)[..8]);
+
+        Ok(Self {
+            key_pair,
+            key: CommandSigningKey {
+                key_id,
+                algorithm: SIGNING_ALGORITHM.to_string(),
+                public_key: BASE64.encode(public_key),
+            },
+        })
+    }
+
+    /// Public key info handed out to drones.
+    pub fn signing_key(&self) -> &CommandSigningKey {
+        &self.key
+    }
+
+    pub fn sign(&self, command: Command) -> SignedCommand {
+        let payload = command.signing_payload();
+        let signature = self.key_pair.sign(&payload);
+        SignedCommand {
+            command,
+            signature: Some(CommandSignature {
+                key_id: self.key.key_id.clone(),
+                algorithm: SIGNING_ALGORITHM.to_string(),
+                signature: BASE64.encode(signature.as_ref()),
+                payload: BASE64.encode(payload),
+            }),
+        }
+    }
+}
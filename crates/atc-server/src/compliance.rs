@@ -1,7 +1,10 @@
 //! Server-side compliance evaluation for flight plans.
 
 use crate::cache;
+use crate::chaos::chaos;
 use crate::config::Config;
+use atc_core::coverage::{coverage_gaps, CoverageArea, CoverageGap};
+use atc_core::messages::{codes, Message};
 use atc_core::models::FlightPlanRequest;
 use atc_core::spatial::{meters_per_deg_lat, meters_per_deg_lon};
 use chrono::Utc;
@@ -51,12 +54,15 @@ pub struct ComplianceChecks {
     pub battery: BatteryCheck,
     pub population: PopulationCheck,
     pub obstacles: ObstaclesCheck,
+    pub c2_link: C2LinkCheck,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct WeatherCheck {
     pub status: ComplianceStatus,
     pub message: String,
+    /// Stable code and values behind `message`.
+    pub message_code: Message,
     pub wind_mps: Option<f64>,
     pub gust_mps: Option<f64>,
     pub precip_mm: Option<f64>,
@@ -70,6 +76,8 @@ pub struct WeatherCheck {
 pub struct BatteryCheck {
     pub status: ComplianceStatus,
     pub message: String,
+    /// Stable code and values behind `message`.
+    pub message_code: Message,
     pub estimated_minutes: Option<f64>,
     pub capacity_min: Option<f64>,
     pub reserve_min: Option<f64>,
@@ -81,6 +89,8 @@ pub struct BatteryCheck {
 pub struct PopulationCheck {
     pub status: ComplianceStatus,
     pub message: String,
+    /// Stable code and values behind `message`.
+    pub message_code: Message,
     pub density: Option<f64>,
     pub classification: Option<String>,
     pub building_count: Option<usize>,
@@ -93,6 +103,8 @@ pub struct PopulationCheck {
 pub struct ObstaclesCheck {
     pub status: ComplianceStatus,
     pub message: String,
+    /// Stable code and values behind `message`.
+    pub message_code: Message,
     pub clearance_m: f64,
     pub conflicts: Vec<ObstacleConflict>,
     pub hazards: Vec<ObstacleHazard>,
@@ -101,6 +113,18 @@ pub struct ObstaclesCheck {
 }
 
 #[derive(Debug, Clone, Serialize)]
+pub struct C2LinkCheck {
+    pub status: ComplianceStatus,
+    pub message: String,
+    /// Stable code and values behind `message`.
+    pub message_code: Message,
+    /// Stretches of the route outside C2 coverage.
+    pub gaps: Vec<CoverageGap>,
+    pub longest_gap_s: Option<f64>,
+    pub max_gap_s: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ObstacleHazard {
     pub id: String,
     pub name: String,
@@ -128,12 +152,12 @@ pub struct ComplianceEvaluation {
     pub ok: bool,
 }
 
-#[derive(Debug)]
-struct Bounds {
-    min_lat: f64,
-    max_lat: f64,
-    min_lon: f64,
-    max_lon: f64,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Bounds {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lon: f64,
+    pub max_lon: f64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -153,7 +177,7 @@ impl ObstacleQueryMode {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct ObstacleAnalysis {
     pub(crate) candidates: Vec<ObstacleCandidate>,
     pub(crate) hazards: Vec<ObstacleHazard>,
@@ -190,7 +214,56 @@ fn obstacle_inflight() -> &'static DashMap<String, Arc<Mutex<()>>> {
 
 const OBSTACLE_CACHE_MAX_ENTRIES: usize = 256;
 
-#[derive(Debug, Clone)]
+/// Obstacle cache entries still usable (fresh or stale), for the planner warmup snapshot.
+pub(crate) fn export_obstacle_cache(
+    config: &Config,
+) -> Vec<cache::SnapshotEntry<ObstacleAnalysis>> {
+    let max_age = Duration::from_secs(config.obstacle_cache_ttl_s.max(30)).saturating_mul(2);
+    obstacle_cache()
+        .iter()
+        .filter(|entry| entry.fetched_at.elapsed() <= max_age)
+        .map(|entry| {
+            cache::SnapshotEntry::new(
+                entry.key().clone(),
+                entry.fetched_at,
+                entry.analysis.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Load snapshot entries into the obstacle cache, skipping expired ones; returns how many loaded.
+pub(crate) fn restore_obstacle_cache(
+    config: &Config,
+    entries: Vec<cache::SnapshotEntry<ObstacleAnalysis>>,
+) -> usize {
+    let max_age = Duration::from_secs(config.obstacle_cache_ttl_s.max(30)).saturating_mul(2);
+    let cache = obstacle_cache();
+    let mut restored = 0;
+    for entry in entries {
+        let Some(fetched_at) = entry.restored_at(max_age) else {
+            continue;
+        };
+        let newer = cache
+            .get(&entry.key)
+            .is_some_and(|existing| existing.fetched_at >= fetched_at);
+        if newer {
+            continue;
+        }
+        cache.insert(
+            entry.key,
+            ObstacleCacheEntry {
+                fetched_at,
+                analysis: entry.value,
+            },
+        );
+        restored += 1;
+    }
+    cache::prune_cache(cache, OBSTACLE_CACHE_MAX_ENTRIES, max_age);
+    restored
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct ObstacleCandidate {
     pub id: String,
     pub name: String,
@@ -203,7 +276,34 @@ pub(crate) struct ObstacleCandidate {
     pub polygon: Option<Vec<[f64; 2]>>,
 }
 
-#[derive(Debug, Clone)]
+impl ObstacleCandidate {
+    pub(crate) fn hazard(&self) -> ObstacleHazard {
+        ObstacleHazard {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            lat: self.lat,
+            lon: self.lon,
+            radius_m: self.radius_m,
+            height_m: Some(self.height_m),
+            hazard_type: self.hazard_type.clone(),
+            source: "OpenStreetMap".to_string(),
+            distance_m: self.distance_m,
+        }
+    }
+}
+
+/// How far from a route obstacles are still reported.
+pub(crate) fn obstacle_max_distance_m(clearance_m: f64, corridor_radius_m: Option<f64>) -> f64 {
+    let mut max_distance = 400.0_f64.max(clearance_m * 4.0);
+    if let Some(radius) = corridor_radius_m {
+        if radius.is_finite() {
+            max_distance = max_distance.max(radius.max(clearance_m * 1.5));
+        }
+    }
+    max_distance
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub(crate) struct ObstacleFootprint {
     pub id: String,
@@ -259,10 +359,30 @@ struct WeatherCurrentAlt {
     precipitation: Option<f64>,
 }
 
+/// Speed assumed when timing C2 coverage gaps for plans without a cruise speed.
+const DEFAULT_C2_GAP_SPEED_MPS: f64 = 15.0;
+
+/// A route violation: `fields` plus the stable `message_code` and the rendered `message`.
+pub(crate) fn violation(
+    config: &Config,
+    message: Message,
+    mut fields: serde_json::Value,
+) -> serde_json::Value {
+    if let Some(object) = fields.as_object_mut() {
+        object.insert(
+            "message".to_string(),
+            serde_json::Value::String(config.messages.format(&message, None)),
+        );
+        object.insert("message_code".to_string(), serde_json::json!(message));
+    }
+    fields
+}
+
 pub async fn evaluate_compliance(
     config: &Config,
     request: &FlightPlanRequest,
     points: &[RoutePoint],
+    coverage: &[CoverageArea],
 ) -> ComplianceEvaluation {
     let metadata = request.metadata.as_ref();
     let cruise_speed_mps = metadata.and_then(|m| m.drone_speed_mps);
@@ -298,46 +418,59 @@ pub async fn evaluate_compliance(
 
     let weather_check = match weather_result {
         Ok(weather) => evaluate_weather(config, &weather),
-        Err(err) => WeatherCheck {
-            status: ComplianceStatus::Pending,
-            message: format!("Weather fetch failed: {}", err),
-            wind_mps: None,
-            gust_mps: None,
-            precip_mm: None,
-            max_wind_mps: config.compliance_max_wind_mps,
-            max_gust_mps: config.compliance_max_gust_mps,
-            max_precip_mm: config.compliance_max_precip_mm,
-            source: "Open-Meteo".to_string(),
-        },
+        Err(err) => {
+            let message =
+                Message::new(codes::COMPLIANCE_WEATHER_UNAVAILABLE).with("error", err.to_string());
+            WeatherCheck {
+                status: ComplianceStatus::Pending,
+                message: config.messages.format(&message, None),
+                message_code: message,
+                wind_mps: None,
+                gust_mps: None,
+                precip_mm: None,
+                max_wind_mps: config.compliance_max_wind_mps,
+                max_gust_mps: config.compliance_max_gust_mps,
+                max_precip_mm: config.compliance_max_precip_mm,
+                source: "Open-Meteo".to_string(),
+            }
+        }
     };
 
     let (population_check, obstacles_check) = match obstacle_result {
         Ok(analysis) => {
             let population = evaluate_population(config, operation_type, &analysis);
-            let obstacles = evaluate_obstacles(points, clearance_m, analysis);
+            let obstacles = evaluate_obstacles(config, points, clearance_m, analysis);
             (population, obstacles)
         }
-        Err(err) => (
-            PopulationCheck {
-                status: ComplianceStatus::Pending,
-                message: format!("Population analysis failed: {}", err),
-                density: None,
-                classification: None,
-                building_count: None,
-                estimated_population: None,
-                area_km2: None,
-                source: None,
-            },
-            ObstaclesCheck {
-                status: ComplianceStatus::Pending,
-                message: format!("Obstacle analysis failed: {}", err),
-                clearance_m,
-                conflicts: Vec::new(),
-                hazards: default_hazards(),
-                obstacle_count: 0,
-                truncated: false,
-            },
-        ),
+        Err(err) => {
+            let population_message = Message::new(codes::COMPLIANCE_POPULATION_UNAVAILABLE)
+                .with("error", err.to_string());
+            let obstacles_message = Message::new(codes::COMPLIANCE_OBSTACLES_UNAVAILABLE)
+                .with("error", err.to_string());
+            (
+                PopulationCheck {
+                    status: ComplianceStatus::Pending,
+                    message: config.messages.format(&population_message, None),
+                    message_code: population_message,
+                    density: None,
+                    classification: None,
+                    building_count: None,
+                    estimated_population: None,
+                    area_km2: None,
+                    source: None,
+                },
+                ObstaclesCheck {
+                    status: ComplianceStatus::Pending,
+                    message: config.messages.format(&obstacles_message, None),
+                    message_code: obstacles_message,
+                    clearance_m,
+                    conflicts: Vec::new(),
+                    hazards: default_hazards(),
+                    obstacle_count: 0,
+                    truncated: false,
+                },
+            )
+        }
     };
 
     let wind_mps = weather_check
@@ -354,11 +487,20 @@ pub async fn evaluate_compliance(
         battery_reserve_min,
     );
 
+    let c2_link_check = evaluate_c2_link(
+        config,
+        operation_type,
+        points,
+        coverage,
+        (cruise_speed_mps.unwrap_or(DEFAULT_C2_GAP_SPEED_MPS) - wind_mps).max(1.0),
+    );
+
     let checks = ComplianceChecks {
         weather: weather_check.clone(),
         battery: battery_check.clone(),
         population: population_check.clone(),
         obstacles: obstacles_check.clone(),
+        c2_link: c2_link_check,
     };
 
     let overall_status = summarize_status(&checks);
@@ -368,6 +510,7 @@ pub async fn evaluate_compliance(
         ("battery", &checks.battery.status),
         ("population", &checks.population.status),
         ("obstacles", &checks.obstacles.status),
+        ("c2_link", &checks.c2_link.status),
     ] {
         if matches!(status, ComplianceStatus::Fail | ComplianceStatus::Pending) {
             blocking.push(key.to_string());
@@ -435,9 +578,11 @@ fn evaluate_weather(config: &Config, weather: &WeatherCurrent) -> WeatherCheck {
     let max_precip = config.compliance_max_precip_mm;
 
     if wind.is_none() || gust.is_none() || precip.is_none() {
+        let message = Message::new(codes::COMPLIANCE_WEATHER_MISSING);
         return WeatherCheck {
             status: ComplianceStatus::Pending,
-            message: "Weather values missing".to_string(),
+            message: config.messages.format(&message, None),
+            message_code: message,
             wind_mps: wind,
             gust_mps: gust,
             precip_mm: precip,
@@ -462,12 +607,15 @@ fn evaluate_weather(config: &Config, weather: &WeatherCurrent) -> WeatherCheck {
         status = ComplianceStatus::Warn;
     }
 
+    let message = Message::new(codes::COMPLIANCE_WEATHER_CONDITIONS)
+        .with("wind_mps", wind_value)
+        .with("gust_mps", gust_value)
+        .with("precip_mm", precip_value)
+        .with("source", "Open-Meteo");
     WeatherCheck {
         status,
-        message: format!(
-            "Wind {:.1} m/s, Gust {:.1} m/s, Precip {:.1} mm (Source: Open-Meteo)",
-            wind_value, gust_value, precip_value
-        ),
+        message: config.messages.format(&message, None),
+        message_code: message,
         wind_mps: Some(wind_value),
         gust_mps: Some(gust_value),
         precip_mm: Some(precip_value),
@@ -490,9 +638,11 @@ fn evaluate_battery(
         || reserve_min.is_none()
         || cruise_speed_mps.unwrap_or(0.0) <= 0.0
     {
+        let message = Message::new(codes::COMPLIANCE_BATTERY_MISSING);
         return BatteryCheck {
             status: ComplianceStatus::Pending,
-            message: "Battery inputs missing".to_string(),
+            message: config.messages.format(&message, None),
+            message_code: message,
             estimated_minutes: None,
             capacity_min,
             reserve_min,
@@ -513,12 +663,13 @@ fn evaluate_battery(
         status = ComplianceStatus::Warn;
     }
 
+    let message = Message::new(codes::COMPLIANCE_BATTERY_ESTIMATE)
+        .with("estimated_minutes", estimated_minutes)
+        .with("remaining_min", remaining);
     BatteryCheck {
         status,
-        message: format!(
-            "Est {:.1} min | Remaining {:.1} min",
-            estimated_minutes, remaining
-        ),
+        message: config.messages.format(&message, None),
+        message_code: message,
         estimated_minutes: Some(estimated_minutes),
         capacity_min: Some(capacity),
         reserve_min: Some(reserve),
@@ -545,9 +696,13 @@ fn evaluate_population(
         status = ComplianceStatus::Warn;
     }
 
+    let message = Message::new(codes::COMPLIANCE_POPULATION_DENSITY)
+        .with("density", density)
+        .with("classification", classification.as_str());
     PopulationCheck {
         status,
-        message: format!("Density {:.0} people/km^2 ({})", density, classification),
+        message: config.messages.format(&message, None),
+        message_code: message,
         density: Some(density),
         classification: Some(classification),
         building_count: Some(analysis.building_count),
@@ -557,7 +712,70 @@ fn evaluate_population(
     }
 }
 
+/// Coverage gaps along the route; only BVLOS operations (type 2) are held to the C2 limits.
+fn evaluate_c2_link(
+    config: &Config,
+    operation_type: u8,
+    points: &[RoutePoint],
+    coverage: &[CoverageArea],
+    ground_speed_mps: f64,
+) -> C2LinkCheck {
+    let max_gap_s = config.c2_max_gap_s;
+    if coverage.is_empty() {
+        let message = Message::new(codes::COMPLIANCE_C2_NO_COVERAGE_MAP);
+        return C2LinkCheck {
+            status: ComplianceStatus::Pass,
+            message: config.messages.format(&message, None),
+            message_code: message,
+            gaps: Vec::new(),
+            longest_gap_s: None,
+            max_gap_s,
+        };
+    }
+
+    let route: Vec<(f64, f64)> = points.iter().map(|point| (point.lat, point.lon)).collect();
+    let gaps = coverage_gaps(&route, coverage);
+    let longest_gap_s = gaps
+        .iter()
+        .map(|gap| gap.length_m / ground_speed_mps)
+        .reduce(f64::max);
+    let (status, message) = match longest_gap_s {
+        None => (
+            ComplianceStatus::Pass,
+            Message::new(codes::COMPLIANCE_C2_COVERED),
+        ),
+        Some(longest) if operation_type != 2 => (
+            ComplianceStatus::Pass,
+            Message::new(codes::COMPLIANCE_C2_GAPS_VLOS)
+                .with("gaps", gaps.len())
+                .with("longest_gap_s", longest),
+        ),
+        Some(longest) if longest > max_gap_s => (
+            ComplianceStatus::Fail,
+            Message::new(codes::COMPLIANCE_C2_GAP_EXCEEDED)
+                .with("longest_gap_s", longest)
+                .with("max_gap_s", max_gap_s),
+        ),
+        Some(longest) => (
+            ComplianceStatus::Warn,
+            Message::new(codes::COMPLIANCE_C2_GAPS)
+                .with("gaps", gaps.len())
+                .with("longest_gap_s", longest),
+        ),
+    };
+
+    C2LinkCheck {
+        status,
+        message: config.messages.format(&message, None),
+        message_code: message,
+        gaps,
+        longest_gap_s,
+        max_gap_s,
+    }
+}
+
 fn evaluate_obstacles(
+    config: &Config,
     points: &[RoutePoint],
     clearance_m: f64,
     mut analysis: ObstacleAnalysis,
@@ -571,9 +789,11 @@ fn evaluate_obstacles(
     };
 
     if points.is_empty() {
+        let message = Message::new(codes::COMPLIANCE_OBSTACLES_ROUTE_MISSING);
         return ObstaclesCheck {
             status: ComplianceStatus::Pending,
-            message: "Route missing".to_string(),
+            message: config.messages.format(&message, None),
+            message_code: message,
             clearance_m,
             conflicts: Vec::new(),
             hazards: hazard_list,
@@ -628,9 +848,12 @@ fn evaluate_obstacles(
     let mut conflicts_all = conflicts;
     conflicts_all.extend(warnings);
 
+    let message =
+        Message::new(codes::COMPLIANCE_OBSTACLES_CONFLICTS).with("count", conflicts_all.len());
     ObstaclesCheck {
         status,
-        message: format!("{} conflicts", conflicts_all.len()),
+        message: config.messages.format(&message, None),
+        message_code: message,
         clearance_m,
         conflicts: conflicts_all,
         hazards: hazard_list,
@@ -649,6 +872,7 @@ fn summarize_status(checks: &ComplianceChecks) -> ComplianceStatus {
         &checks.battery.status,
         &checks.population.status,
         &checks.obstacles.status,
+        &checks.c2_link.status,
     ] {
         match status {
             ComplianceStatus::Fail => has_fail = true,
@@ -728,8 +952,44 @@ pub(crate) async fn fetch_obstacles(
             }
         })
         .unwrap_or_else(|| expand_bounds(&base_bounds));
+    fetch_obstacles_within(
+        client,
+        config,
+        bounds,
+        points,
+        clearance_m,
+        corridor_radius_m,
+        mode,
+    )
+    .await
+}
+
+/// Every obstacle inside `bounds`, unfiltered by distance to a route.
+///
+/// Used to fill tile-aligned indexes, so the cache is shared by every route crossing the tile.
+pub(crate) async fn fetch_obstacles_in_bounds(
+    client: &Client,
+    config: &Config,
+    bounds: Bounds,
+    clearance_m: f64,
+    mode: ObstacleQueryMode,
+) -> Result<ObstacleAnalysis, String> {
+    fetch_obstacles_within(client, config, bounds, &[], clearance_m, None, mode).await
+}
+
+/// Overpass query for `bounds`, keeping obstacles near `points` (all of them when empty).
+async fn fetch_obstacles_within(
+    client: &Client,
+    config: &Config,
+    bounds: Bounds,
+    points: &[RoutePoint],
+    clearance_m: f64,
+    corridor_radius_m: Option<f64>,
+    mode: ObstacleQueryMode,
+) -> Result<ObstacleAnalysis, String> {
     let cache_key = obstacle_cache_key(
         &bounds,
+        points.is_empty(),
         clearance_m,
         corridor_radius_m,
         config.compliance_max_overpass_elements,
@@ -790,6 +1050,9 @@ pub(crate) async fn fetch_obstacles(
     let mut payload: Option<OverpassResponse> = None;
 
     for attempt in 0..max_attempts {
+        if let Some(delay) = chaos().overpass_delay() {
+            sleep(delay).await;
+        }
         let response = client
             .post(&config.compliance_overpass_url)
             .header("Content-Type", "text/plain")
@@ -841,12 +1104,7 @@ pub(crate) async fn fetch_obstacles(
     let mut candidates = Vec::new();
     let mut seen = HashSet::new();
     let mut building_count = 0usize;
-    let mut max_distance = 400.0_f64.max(clearance_m * 4.0);
-    if let Some(radius) = corridor_radius_m {
-        if radius.is_finite() {
-            max_distance = max_distance.max(radius.max(clearance_m * 1.5));
-        }
-    }
+    let max_distance = obstacle_max_distance_m(clearance_m, corridor_radius_m);
     let default_height = config.compliance_default_building_height_m.max(1.0);
 
     let empty_tags: HashMap<String, String> = HashMap::new();
@@ -974,17 +1232,7 @@ pub(crate) async fn fetch_obstacles(
     let hazards: Vec<ObstacleHazard> = route_candidates
         .iter()
         .take(config.compliance_max_obstacles_response)
-        .map(|candidate| ObstacleHazard {
-            id: candidate.id.clone(),
-            name: candidate.name.clone(),
-            lat: candidate.lat,
-            lon: candidate.lon,
-            radius_m: candidate.radius_m,
-            height_m: Some(candidate.height_m),
-            hazard_type: candidate.hazard_type.clone(),
-            source: "OpenStreetMap".to_string(),
-            distance_m: candidate.distance_m,
-        })
+        .map(ObstacleCandidate::hazard)
         .collect();
 
     let footprints: Vec<ObstacleFootprint> = route_candidates
@@ -1048,7 +1296,7 @@ fn route_center(points: &[RoutePoint]) -> Option<(f64, f64)> {
     Some((sum_lat / points.len() as f64, sum_lon / points.len() as f64))
 }
 
-fn compute_bounds(points: &[RoutePoint]) -> Option<Bounds> {
+pub(crate) fn compute_bounds(points: &[RoutePoint]) -> Option<Bounds> {
     if points.is_empty() {
         return None;
     }
@@ -1089,7 +1337,7 @@ fn expand_bounds(bounds: &Bounds) -> Bounds {
     }
 }
 
-fn expand_bounds_by_meters(bounds: &Bounds, padding_m: f64) -> Bounds {
+pub(crate) fn expand_bounds_by_meters(bounds: &Bounds, padding_m: f64) -> Bounds {
     let mean_lat = (bounds.min_lat + bounds.max_lat) / 2.0;
     let meters_per_deg_lat = meters_per_deg_lat(mean_lat);
     let meters_per_deg_lon = meters_per_deg_lon(mean_lat).max(1.0);
@@ -1115,6 +1363,7 @@ fn bounds_area_km2(bounds: &Bounds) -> f64 {
 
 fn obstacle_cache_key(
     bounds: &Bounds,
+    whole_area: bool,
     clearance_m: f64,
     corridor_radius_m: Option<f64>,
     max_elements: usize,
@@ -1122,8 +1371,9 @@ fn obstacle_cache_key(
 ) -> String {
     let radius = corridor_radius_m.unwrap_or(0.0);
     format!(
-        "obs:{}:{:.4}:{:.4}:{:.4}:{:.4}:{:.0}:{:.0}:{}",
+        "obs:{}:{}:{:.4}:{:.4}:{:.4}:{:.4}:{:.0}:{:.0}:{}",
         mode.cache_key_part(),
+        if whole_area { "area" } else { "route" },
         bounds.min_lat,
         bounds.min_lon,
         bounds.max_lat,
@@ -1258,7 +1508,11 @@ fn classify_density(density: f64) -> String {
     }
 }
 
-fn distance_to_route_meters(hazard_lat: f64, hazard_lon: f64, points: &[RoutePoint]) -> f64 {
+pub(crate) fn distance_to_route_meters(
+    hazard_lat: f64,
+    hazard_lon: f64,
+    points: &[RoutePoint],
+) -> f64 {
     if points.is_empty() {
         return f64::INFINITY;
     }
@@ -1362,3 +1616,77 @@ fn default_hazards() -> Vec<ObstacleHazard> {
         },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn c2_link_gaps_only_limit_bvlos() {
+        let mut config = Config::from_env();
+        config.c2_max_gap_s = 30.0;
+        // Coverage up to 0.005 deg north and again from 0.01 deg: a ~555 m hole.
+        let coverage = vec![
+            CoverageArea {
+                id: "south".to_string(),
+                name: None,
+                link_type: None,
+                polygon: vec![
+                    [-0.001, -0.001],
+                    [-0.001, 0.001],
+                    [0.005, 0.001],
+                    [0.005, -0.001],
+                ],
+            },
+            CoverageArea {
+                id: "north".to_string(),
+                name: None,
+                link_type: None,
+                polygon: vec![[0.01, -0.001], [0.01, 0.001], [0.02, 0.001], [0.02, -0.001]],
+            },
+        ];
+        let route = |north_deg: f64| {
+            vec![
+                RoutePoint {
+                    lat: 0.0,
+                    lon: 0.0,
+                    altitude_m: 50.0,
+                },
+                RoutePoint {
+                    lat: north_deg,
+                    lon: 0.0,
+                    altitude_m: 50.0,
+                },
+            ]
+        };
+
+        let check = evaluate_c2_link(&config, 2, &route(0.015), &coverage, 10.0);
+        assert!(
+            matches!(check.status, ComplianceStatus::Fail),
+            "{}",
+            check.message
+        );
+        assert_eq!(check.gaps.len(), 1);
+        // ~556 m at 10 m/s, resolved to the 25 m sample spacing.
+        let longest = check.longest_gap_s.unwrap();
+        assert!((50.0..=56.0).contains(&longest), "{}", longest);
+
+        let check = evaluate_c2_link(&config, 2, &route(0.015), &coverage, 25.0);
+        assert!(
+            matches!(check.status, ComplianceStatus::Warn),
+            "{}",
+            check.message
+        );
+
+        let check = evaluate_c2_link(&config, 1, &route(0.015), &coverage, 10.0);
+        assert!(matches!(check.status, ComplianceStatus::Pass));
+        assert_eq!(check.gaps.len(), 1);
+
+        let check = evaluate_c2_link(&config, 2, &route(0.004), &coverage, 10.0);
+        assert!(matches!(check.status, ComplianceStatus::Pass));
+        assert!(check.gaps.is_empty());
+
+        let check = evaluate_c2_link(&config, 2, &route(0.015), &[], 10.0);
+        assert!(matches!(check.status, ComplianceStatus::Pass));
+    }
+}
@@ -1,12 +1,38 @@
 //! Server configuration from environment.
 
+use crate::adsb::AdsbFeedFormat;
 use crate::altitude::AltitudeReference;
-use atc_core::rules::{AltitudeBand, SafetyRules};
+use crate::breach::BreachPolicy;
+use crate::fairness::{FairnessPolicy, OperatorQuotas};
+use crate::secrets::{SecretKey, SecretStore, SecretsBackend};
+use crate::sectors::Sector;
+use crate::telemetry_auth::TelemetryAuthMode;
+use crate::tfr::TfrFeedFormat;
+use crate::throttle::CommandBudget;
+use crate::warmup::OperatingArea;
+use atc_core::coverage::CoverageMode;
+use atc_core::crewed_traffic::CrewedProtection;
+use atc_core::dead_reckoning::{DeadReckoningConfig, DeadReckoningMode};
+use atc_core::ground_risk::PopulationRaster;
+use atc_core::intent::IntentFilterMode;
+use atc_core::messages::{MessageCatalog, MessageFormatter};
+use atc_core::msa::MsaBounds;
+use atc_core::route_engine::RouteSearch;
+use atc_core::rules::{AltitudeBand, SafetyRules, VolumeSeparationRule};
+use atc_core::surveillance::CrosscheckThresholds;
+use atc_core::takeoff_landing::Vertiport;
+use atc_core::track_estimator::TrackEstimatorConfig;
+use atc_core::track_quality::{TrackQualityConfig, TrackQualityMode};
+use atc_core::well_clear::WellClearParams;
+use std::collections::HashMap;
 use std::env;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub server_port: u16,
+    /// Port for the gRPC telemetry and command streams; unset disables them.
+    pub grpc_port: Option<u16>,
     pub blender_url: String,
     pub blender_session_id: String,
     pub rid_view_bbox: String,
@@ -29,6 +55,20 @@ pub struct Config {
     pub registration_token: Option<String>,
     /// Require registration token for /v1/drones/register.
     pub require_registration_token: bool,
+    /// Where admin/registration/WebSocket/Blender credentials are loaded from
+    pub secrets_backend: SecretsBackend,
+    /// Live values from `secrets_backend`; these take precedence over the plain fields
+    pub secrets: SecretStore,
+    /// How often the secret backend is re-read, in seconds
+    pub secrets_refresh_secs: u64,
+    /// Base64 Ed25519 seed used to sign commands delivered to drones (unset = unsigned)
+    pub command_signing_key: Option<String>,
+    /// Drone session token lifetime in seconds (0 = tokens never expire)
+    pub drone_token_ttl_secs: u64,
+    /// Extra time an expired token stays valid while its drone is airborne
+    pub drone_token_grace_secs: u64,
+    /// How long the replaced token keeps working after a drone rotates its token
+    pub drone_token_rotation_overlap_secs: u64,
     /// Enable rate limiting (default: true in prod)
     pub rate_limit_enabled: bool,
     /// Max requests per second per IP for telemetry
@@ -51,12 +91,34 @@ pub struct Config {
     pub flights_list_default_limit: usize,
     /// Hard cap on flight plans returned by GET /v1/flights (DoS protection). Set to 0 to disable.
     pub flights_list_max_limit: usize,
+    /// Default page size for GET /v1/conflicts, /v1/commands and /v1/geofences.
+    pub list_default_limit: usize,
+    /// Hard cap on the page size of those lists (DoS protection). Set to 0 to disable.
+    pub list_max_limit: usize,
     /// Trust X-Forwarded-For headers for rate limiting
     pub trust_proxy: bool,
     /// Path to SQLite database file
     pub database_path: String,
+    /// Postgres connection URL, used instead of `database_path` by `postgres` builds
+    pub database_url: Option<String>,
     /// Max connections for database pool
     pub database_max_connections: u32,
+    /// Apply pending schema migrations at startup (otherwise `--migrate-only` must run first)
+    pub database_auto_migrate: bool,
+    /// Optional replica database file for history/analytics reads (defaults to the primary)
+    pub database_read_replica_path: Option<String>,
+    /// Max connections for the read-only analytics pool (0 = share the primary pool)
+    pub database_read_max_connections: u32,
+    /// Timeout for history/analytics queries (milliseconds)
+    pub database_read_timeout_ms: u64,
+    /// How often coalesced telemetry is written to the database (milliseconds)
+    pub telemetry_flush_ms: u64,
+    /// Max drone rows written per telemetry transaction (0 = unlimited)
+    pub telemetry_batch_size: usize,
+    /// Stretch the telemetry flush interval and batch size under load
+    pub telemetry_adaptive_batching: bool,
+    /// How long flown-track samples are kept (hours, 0 = forever)
+    pub telemetry_history_retention_hours: u64,
     pub compliance_weather_url: String,
     pub compliance_overpass_url: String,
     pub compliance_population_per_building: f64,
@@ -79,14 +141,93 @@ pub struct Config {
     pub route_planner_require_obstacles: bool,
     pub route_planner_allow_truncated_obstacles: bool,
     pub route_planner_wind_mps: f64,
+    /// Cost route edges with forecast winds fetched along the route instead of the scalar
+    /// `route_planner_wind_mps` headwind.
+    pub route_planner_wind_field: bool,
+    /// Spacing of the forecast wind locations along a route.
+    pub route_planner_wind_spacing_m: f64,
+    /// Extra cost per second the route planner charges for flying through marginal forecast
+    /// weather (above `compliance_wind_warn_ratio` of the compliance limits).
+    pub route_planner_weather_penalty: f64,
+    /// Default C2 coverage constraint for planned routes; requests may override it.
+    pub route_planner_coverage_mode: CoverageMode,
+    /// Extra cost per second the route planner charges for flying outside C2 coverage in
+    /// `limit` mode.
+    pub route_planner_coverage_penalty: f64,
+    /// Extra cost per second the route planner charges for every 1000 people/km² overflown; 0
+    /// leaves ground risk out of routing.
+    pub ground_risk_cost: f64,
+    /// Population density raster (loaded from ATC_GROUND_RISK_RASTER_PATH); without one,
+    /// density is estimated from OpenStreetMap buildings when `ground_risk_cost` is set.
+    pub ground_risk_raster: Option<Arc<PopulationRaster>>,
+    /// Cell size of the building-derived density estimate.
+    pub ground_risk_cell_m: f64,
+    /// Longest stretch (seconds) a BVLOS route may spend outside C2 coverage.
+    pub c2_max_gap_s: f64,
+    /// Extra cost per second the route planner charges a route in a batch for flying within
+    /// separation of a route planned earlier in the batch.
+    pub route_planner_batch_penalty: f64,
+    /// Spacing of the altitude layers the route planner searches above the terrain-following
+    /// floor; 0 resolves obstacles laterally and by terrain following only.
+    pub route_planner_altitude_step_m: f64,
+    /// Steepest climb (rise over run) the route planner allows between grid points; 0 is
+    /// unlimited.
+    pub route_planner_max_climb_gradient: f64,
+    /// Radius planned routes round their cruise corners to; 0 keeps sharp corners.
+    pub route_planner_turn_radius_m: f64,
+    /// Distance planned routes keep from restricted geofences; requests may override it.
+    pub route_planner_geofence_standoff_m: f64,
+    /// Default grid search for planned routes; requests may override it.
+    pub route_planner_search: RouteSearch,
+    /// Samples per leg of the RRT* fallback run when the grid search finds no route within the
+    /// widest lane radius; 0 disables the fallback.
+    pub route_planner_rrt_samples: usize,
+    /// Threads in the route planner worker pool; 0 uses one per CPU.
+    pub route_planner_threads: usize,
+    /// Planner attempts allowed to wait for a worker before planning requests get 503; 0 never
+    /// refuses.
+    pub route_planner_max_queue: usize,
+    /// Areas whose obstacles and terrain are prefetched for the planner (loaded from
+    /// ATC_OPERATING_AREAS_PATH).
+    pub operating_areas: Vec<OperatingArea>,
+    /// Warm the planner caches for the operating areas when the server starts.
+    pub planner_warmup_on_startup: bool,
+    /// File the warmed planner caches are saved to and restored from at startup.
+    pub planner_warmup_snapshot_path: Option<String>,
+    /// Background jobs allowed to run at once.
+    pub job_concurrency: usize,
+    /// Attempts a background job gets before it is dead-lettered.
+    pub job_max_attempts: u32,
+    /// Delay before the first retry of a failed job; doubles with each further failure.
+    pub job_retry_base_secs: u64,
+    /// Longest delay between retries of a failed job.
+    pub job_retry_max_secs: u64,
+    /// How long succeeded jobs stay listed before they are pruned.
+    pub job_retention_hours: u64,
+    /// Longest a webhook endpoint may take to answer a delivery (milliseconds).
+    pub webhook_timeout_ms: u64,
     /// Minimum building height (meters) included in route-planner obstacle queries.
     pub route_planner_building_min_height_m: f64,
     /// Minimum building levels included in route-planner obstacle queries.
     pub route_planner_building_min_levels: u32,
     /// Hard cap on the number of waypoints accepted by the route planner (DoS protection).
     pub route_planner_max_waypoints: usize,
+    /// Hard cap on the number of routes in one batch planning request (DoS protection).
+    pub route_planner_max_batch: usize,
+    /// Most candidate routes one planning request may ask for with `n_alternatives`.
+    pub route_planner_max_alternatives: usize,
     /// Hard cap on the total route distance (meters) accepted by the route planner (DoS protection).
     pub route_planner_max_distance_m: f64,
+    /// Longest a route plan may search (milliseconds) before returning a timeout; 0 is unlimited.
+    pub route_planner_timeout_ms: u64,
+    /// How long obstacle-applied route grids are reused (seconds); 0 disables the cache.
+    pub route_graph_cache_ttl_s: u64,
+    /// Directory route grids are persisted to so they survive restarts; unset keeps them in memory.
+    pub route_graph_cache_dir: Option<String>,
+    /// Vertiports with default takeoff/landing profiles (loaded from ATC_VERTIPORTS_PATH).
+    pub vertiports: Vec<Vertiport>,
+    /// Airspace sectors with their dispatchers (loaded from ATC_SECTORS_PATH).
+    pub sectors: Vec<Sector>,
     pub altitude_reference: AltitudeReference,
     pub geoid_offset_m: f64,
     pub terrain_provider_url: String,
@@ -102,15 +243,58 @@ pub struct Config {
     pub terrain_require: bool,
     pub terrain_cache_ttl_s: u64,
     pub terrain_max_requests: usize,
+    /// Minimum height above ground for airborne drones (meters); 0 disables terrain clearance monitoring.
+    pub terrain_floor_agl_m: f64,
+    /// How far ahead (seconds) drone tracks are projected against terrain.
+    pub terrain_lookahead_s: f64,
+    /// Area covered by the published minimum safe altitude grid; `None` disables it.
+    pub msa_area: Option<MsaBounds>,
+    /// Target MSA grid cell size (meters).
+    pub msa_spacing_m: f64,
+    /// Margin above terrain and obstacles included in every MSA cell (meters).
+    pub msa_clearance_m: f64,
+    /// How often the MSA grid is rebuilt (seconds).
+    pub msa_refresh_secs: u64,
+    /// NOTAM/TFR feed turned into external geofences; `None` disables TFR ingestion.
+    pub tfr_feed_url: Option<String>,
+    pub tfr_feed_format: TfrFeedFormat,
+    /// Sent as `client_id`/`client_secret` headers, as the FAA NOTAM API expects.
+    pub tfr_feed_client_id: Option<String>,
+    pub tfr_feed_client_secret: Option<String>,
+    /// How often the TFR feed is pulled (seconds).
+    pub tfr_refresh_secs: u64,
+    /// ADS-B feed of manned traffic; `None` disables ADS-B ingestion.
+    pub adsb_feed_url: Option<String>,
+    pub adsb_feed_format: AdsbFeedFormat,
+    /// Only aircraft inside this box are tracked; everywhere when unset.
+    pub adsb_area: Option<MsaBounds>,
+    /// Aircraft above this altitude (AMSL) are ignored.
+    pub adsb_max_altitude_m: f64,
+    pub adsb_poll_secs: u64,
     pub telemetry_min_alt_m: f64,
     pub telemetry_max_alt_m: f64,
     pub telemetry_max_speed_mps: f64,
     pub telemetry_max_future_s: i64,
     pub telemetry_max_age_s: i64,
+    /// Enforcement of per-message telemetry signatures (nonce, timestamp, HMAC).
+    pub telemetry_auth_mode: TelemetryAuthMode,
+    /// Accepted clock skew for signed telemetry; also how long nonces are remembered.
+    pub telemetry_freshness_window_s: i64,
     pub command_ack_timeout_secs: i64,
     pub pull_blender_geofences: bool,
     pub allow_admin_reset: bool,
     pub require_blender_declaration: bool,
+    /// Hold plans crossing advisories or passing near TFRs until the operator acknowledges them.
+    pub advisory_ack_required: bool,
+    /// Routes passing this close to a temporary flight restriction need an acknowledgment.
+    pub advisory_tfr_adjacent_m: f64,
+    /// Create Flight Blender declarations for approved plans that do not reference one.
+    pub blender_auto_declare: bool,
+    /// Reconcile local state against Blender once at startup, before the sync loops start.
+    pub blender_startup_reconcile: bool,
+    /// Seconds between the shutdown signal and the actual shutdown, during which telemetry is
+    /// still accepted but planning is refused (0 = shut down immediately).
+    pub lame_duck_secs: u64,
     pub tls_cert_path: Option<String>,
     pub tls_key_path: Option<String>,
     pub require_tls: bool,
@@ -120,15 +304,67 @@ pub struct Config {
     pub strategic_max_delay_secs: u64,
     /// Increment used when searching for a conflict-free departure slot.
     pub strategic_delay_step_secs: u64,
+    /// Cruise altitude offsets (meters, tried in order) the scheduler may assign before delaying
+    /// a departure; empty disables altitude layering.
+    pub strategic_altitude_offsets_m: Vec<f64>,
+    /// Fairness policies between operators for reservation scheduling.
+    pub strategic_fairness: FairnessPolicy,
+    /// Per-operator caps on approved flights, reservations and daily submissions.
+    pub operator_quotas: OperatorQuotas,
     /// Reservation TTL for operational intents (seconds).
     pub operational_intent_ttl_secs: u64,
     pub rules_min_horizontal_separation_m: f64,
     pub rules_min_vertical_separation_m: f64,
     pub rules_lookahead_seconds: f64,
     pub rules_warning_multiplier: f64,
+    pub rules_warning_horizontal_multiplier: Option<f64>,
+    pub rules_warning_vertical_multiplier: Option<f64>,
+    pub rules_info_horizontal_multiplier: Option<f64>,
+    pub rules_info_vertical_multiplier: Option<f64>,
     pub rules_drone_timeout_secs: u64,
     pub rules_max_altitude_m: f64,
     pub rules_min_altitude_m: f64,
+    /// Automatic response per geofence type when a breach is detected.
+    pub breach_policy: BreachPolicy,
+    /// Check live telemetry against geofences in the conflict loop.
+    pub breach_monitor_enabled: bool,
+    /// How far ahead (seconds) a projected track counts as an imminent breach.
+    pub breach_lookahead_secs: f64,
+    /// Budget for automatically issued commands; commands over it become advisories.
+    pub auto_command_budget: CommandBudget,
+    /// Replan the rest of an active flight when a new geofence crosses it or a conflict
+    /// reroutes the drone.
+    pub inflight_replan_enabled: bool,
+    /// How close (meters) to a drone's registered home a plan must end to count as an approved
+    /// landing point; vertiports count within their own radius.
+    pub landing_point_max_distance_m: f64,
+    /// Per-volume separation overrides (loaded from ATC_RULES_VOLUMES_PATH).
+    pub rules_volume_rules: Vec<VolumeSeparationRule>,
+    /// DAA well-clear thresholds when ATC_CONFLICT_DETECTION_MODE=well_clear.
+    pub rules_well_clear: Option<WellClearParams>,
+    /// Protection volume around crewed external traffic; `None` when ATC_CREWED_PROTECTION=0.
+    pub rules_crewed_protection: Option<CrewedProtection>,
+    /// What to do with conflicts the drones' active flight plans already resolve.
+    pub conflict_intent_filter: IntentFilterMode,
+    /// How far (meters) a drone may be from its planned position and still count as on plan.
+    pub conflict_intent_conformance_m: f64,
+    /// What to do with conflicts involving an external track below `traffic_min_quality`.
+    pub traffic_quality_mode: TrackQualityMode,
+    /// Quality score (0-1) below which an external track counts as low quality.
+    pub traffic_min_quality: f64,
+    /// Update rate and jump limits external tracks are scored against.
+    pub traffic_quality: TrackQualityConfig,
+    /// Alpha-beta smoothing and extrapolation of external tracks; `None` when
+    /// ATC_TRAFFIC_SMOOTHING is `0`/`false`.
+    pub traffic_smoothing: Option<TrackEstimatorConfig>,
+    /// How lost drones are projected for conflict detection; off drops them from it.
+    pub lost_dead_reckoning: DeadReckoningConfig,
+    /// Limits for cross-checking drone telemetry against external surveillance of the same
+    /// drone; `None` when ATC_SURVEILLANCE_CROSSCHECK_M is 0.
+    pub surveillance_crosscheck: Option<CrosscheckThresholds>,
+    /// Message catalogs (ATC_MESSAGE_CATALOGS_PATH) and default locale (ATC_LOCALE) for
+    /// violation, advisory and compliance text.
+    pub messages: MessageFormatter,
 }
 
 #[derive(Debug, Clone)]
@@ -167,12 +403,16 @@ impl Config {
             .filter(|v| !v.is_empty());
 
         let default_rules = SafetyRules::default();
+        let secrets_backend = SecretsBackend::from_env();
 
         Self {
             server_port: env::var("ATC_PORT")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(3000),
+            grpc_port: env::var("ATC_GRPC_PORT")
+                .ok()
+                .and_then(|s| s.parse().ok()),
             blender_url: env::var("BLENDER_URL")
                 .unwrap_or_else(|_| "http://localhost:8000".to_string()),
             blender_session_id: env::var("BLENDER_SESSION_ID")
@@ -221,6 +461,28 @@ impl Config {
             require_registration_token: env::var("ATC_REQUIRE_REGISTRATION_TOKEN")
                 .map(|v| v != "0" && v.to_lowercase() != "false")
                 .unwrap_or(true),
+            secrets: SecretStore::from_backend(&secrets_backend),
+            secrets_backend,
+            secrets_refresh_secs: env::var("ATC_SECRETS_REFRESH_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60),
+            command_signing_key: env::var("ATC_COMMAND_SIGNING_KEY")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
+            drone_token_ttl_secs: env::var("ATC_DRONE_TOKEN_TTL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            drone_token_grace_secs: env::var("ATC_DRONE_TOKEN_GRACE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(900),
+            drone_token_rotation_overlap_secs: env::var("ATC_DRONE_TOKEN_ROTATION_OVERLAP_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60),
             rate_limit_enabled: env::var("ATC_RATE_LIMIT")
                 .map(|v| v != "0" && v.to_lowercase() != "false")
                 .unwrap_or(!is_dev), // Enabled by default in prod
@@ -264,15 +526,59 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(1000),
+            list_default_limit: env::var("ATC_LIST_DEFAULT_LIMIT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(200),
+            list_max_limit: env::var("ATC_LIST_MAX_LIMIT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1000),
             trust_proxy: env::var("ATC_TRUST_PROXY")
                 .map(|v| v == "1" || v.to_lowercase() == "true")
                 .unwrap_or(false),
             database_path: env::var("ATC_DATABASE_PATH")
                 .unwrap_or_else(|_| "data/atc.db".to_string()),
+            database_url: env::var("DATABASE_URL")
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty()),
             database_max_connections: env::var("ATC_DB_MAX_CONNECTIONS")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(10),
+            database_auto_migrate: env::var("ATC_DB_AUTO_MIGRATE")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(true),
+            database_read_replica_path: env::var("ATC_DATABASE_READ_REPLICA_PATH")
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty()),
+            database_read_max_connections: env::var("ATC_DB_READ_MAX_CONNECTIONS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(2),
+            database_read_timeout_ms: env::var("ATC_DB_READ_TIMEOUT_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|v: &u64| *v > 0)
+                .unwrap_or(5_000),
+            telemetry_flush_ms: env::var("ATC_TELEMETRY_FLUSH_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1_000)
+                .clamp(50, 5_000),
+            telemetry_batch_size: env::var("ATC_TELEMETRY_BATCH_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            telemetry_adaptive_batching: env::var("ATC_TELEMETRY_ADAPTIVE_BATCHING")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            telemetry_history_retention_hours: env::var("ATC_TELEMETRY_HISTORY_RETENTION_HOURS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(168),
             compliance_weather_url: env::var("ATC_COMPLIANCE_WEATHER_URL")
                 .unwrap_or_else(|_| "https://api.open-meteo.com/v1/forecast".to_string()),
             compliance_overpass_url: env::var("ATC_COMPLIANCE_OVERPASS_URL")
@@ -355,6 +661,124 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(0.0),
+            route_planner_wind_field: env::var("ATC_ROUTE_PLANNER_WIND_FIELD")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            route_planner_wind_spacing_m: env::var("ATC_ROUTE_PLANNER_WIND_SPACING_M")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value > 0.0)
+                .unwrap_or(5_000.0),
+            route_planner_weather_penalty: env::var("ATC_ROUTE_PLANNER_WEATHER_PENALTY")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(2.0),
+            route_planner_coverage_mode: load_coverage_mode(),
+            route_planner_coverage_penalty: env::var("ATC_ROUTE_PLANNER_C2_PENALTY")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(5.0),
+            ground_risk_cost: env::var("ATC_GROUND_RISK_COST")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(0.0),
+            ground_risk_raster: env::var("ATC_GROUND_RISK_RASTER_PATH")
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .and_then(|path| load_population_raster(&path))
+                .map(Arc::new),
+            ground_risk_cell_m: env::var("ATC_GROUND_RISK_CELL_M")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 10.0)
+                .unwrap_or(100.0),
+            route_planner_batch_penalty: env::var("ATC_ROUTE_PLANNER_BATCH_PENALTY")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(500.0),
+            c2_max_gap_s: env::var("ATC_C2_MAX_GAP_S")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(30.0),
+            route_planner_altitude_step_m: env::var("ATC_ROUTE_PLANNER_ALTITUDE_STEP_M")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(0.0),
+            route_planner_max_climb_gradient: env::var("ATC_ROUTE_PLANNER_MAX_CLIMB_GRADIENT")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(0.0),
+            route_planner_turn_radius_m: env::var("ATC_ROUTE_PLANNER_TURN_RADIUS_M")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(0.0),
+            route_planner_geofence_standoff_m: env::var("ATC_ROUTE_PLANNER_GEOFENCE_STANDOFF_M")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(0.0),
+            route_planner_search: load_route_search(),
+            route_planner_rrt_samples: env::var("ATC_ROUTE_PLANNER_RRT_SAMPLES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(2_000),
+            route_planner_threads: env::var("ATC_ROUTE_PLANNER_THREADS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            route_planner_max_queue: env::var("ATC_ROUTE_PLANNER_MAX_QUEUE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(64),
+            operating_areas: env::var("ATC_OPERATING_AREAS_PATH")
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .map(|path| load_operating_areas(&path))
+                .unwrap_or_default(),
+            planner_warmup_on_startup: env::var("ATC_PLANNER_WARMUP_ON_STARTUP")
+                .map(|v| v != "0" && v.to_lowercase() != "false")
+                .unwrap_or(true),
+            planner_warmup_snapshot_path: env::var("ATC_PLANNER_WARMUP_SNAPSHOT_PATH")
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty()),
+            job_concurrency: env::var("ATC_JOB_CONCURRENCY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(4),
+            job_max_attempts: env::var("ATC_JOB_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(5),
+            job_retry_base_secs: env::var("ATC_JOB_RETRY_BASE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            job_retry_max_secs: env::var("ATC_JOB_RETRY_MAX_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(900),
+            job_retention_hours: env::var("ATC_JOB_RETENTION_HOURS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(24),
+            webhook_timeout_ms: env::var("ATC_WEBHOOK_TIMEOUT_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|value| *value > 0)
+                .unwrap_or(5000),
             route_planner_building_min_height_m: env::var("ATC_ROUTE_PLANNER_BUILDING_MIN_HEIGHT_M")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -367,11 +791,31 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(256),
+            route_planner_max_batch: env::var("ATC_ROUTE_PLANNER_MAX_BATCH")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(20),
+            route_planner_max_alternatives: env::var("ATC_ROUTE_PLANNER_MAX_ALTERNATIVES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
             route_planner_max_distance_m: env::var("ATC_ROUTE_PLANNER_MAX_DISTANCE_M")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .filter(|v: &f64| v.is_finite())
                 .unwrap_or(200_000.0),
+            route_planner_timeout_ms: env::var("ATC_ROUTE_PLANNER_TIMEOUT_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30_000),
+            route_graph_cache_ttl_s: env::var("ATC_ROUTE_GRAPH_CACHE_TTL_S")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3600),
+            route_graph_cache_dir: env::var("ATC_ROUTE_GRAPH_CACHE_DIR")
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty()),
             altitude_reference: match env::var("ATC_ALTITUDE_REFERENCE") {
                 Ok(value) => {
                     if value.trim().eq_ignore_ascii_case("agl") {
@@ -437,6 +881,70 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(600),
+            terrain_floor_agl_m: env::var("ATC_TERRAIN_FLOOR_AGL_M")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            terrain_lookahead_s: env::var("ATC_TERRAIN_LOOKAHEAD_S")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30.0),
+            msa_area: env::var("ATC_MSA_AREA")
+                .ok()
+                .and_then(|value| MsaBounds::parse(&value)),
+            msa_spacing_m: env::var("ATC_MSA_SPACING_M")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|value: &f64| value.is_finite() && *value > 0.0)
+                .unwrap_or(100.0),
+            msa_clearance_m: env::var("ATC_MSA_CLEARANCE_M")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|value: &f64| value.is_finite() && *value >= 0.0)
+                .unwrap_or(30.0),
+            msa_refresh_secs: env::var("ATC_MSA_REFRESH_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3600)
+                .max(60),
+            tfr_feed_url: env::var("ATC_TFR_FEED_URL")
+                .ok()
+                .filter(|value| !value.trim().is_empty()),
+            tfr_feed_format: env::var("ATC_TFR_FEED_FORMAT")
+                .ok()
+                .and_then(|s| TfrFeedFormat::parse(&s))
+                .unwrap_or(TfrFeedFormat::GeoJson),
+            tfr_feed_client_id: env::var("ATC_TFR_FEED_CLIENT_ID")
+                .ok()
+                .filter(|value| !value.trim().is_empty()),
+            tfr_feed_client_secret: env::var("ATC_TFR_FEED_CLIENT_SECRET")
+                .ok()
+                .filter(|value| !value.trim().is_empty()),
+            tfr_refresh_secs: env::var("ATC_TFR_REFRESH_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(300)
+                .max(30),
+            adsb_feed_url: env::var("ATC_ADSB_FEED_URL")
+                .ok()
+                .filter(|value| !value.trim().is_empty()),
+            adsb_feed_format: env::var("ATC_ADSB_FEED_FORMAT")
+                .ok()
+                .and_then(|s| AdsbFeedFormat::parse(&s))
+                .unwrap_or(AdsbFeedFormat::Dump1090),
+            adsb_area: env::var("ATC_ADSB_AREA")
+                .ok()
+                .and_then(|value| MsaBounds::parse(&value)),
+            adsb_max_altitude_m: env::var("ATC_ADSB_MAX_ALTITUDE_M")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|value: &f64| value.is_finite())
+                .unwrap_or(3000.0),
+            adsb_poll_secs: env::var("ATC_ADSB_POLL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5)
+                .max(1),
             telemetry_min_alt_m: env::var("ATC_TELEMETRY_MIN_ALT_M")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -457,6 +965,14 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(300),
+            telemetry_auth_mode: env::var("ATC_TELEMETRY_AUTH_MODE")
+                .ok()
+                .and_then(|s| TelemetryAuthMode::parse(&s))
+                .unwrap_or(TelemetryAuthMode::Optional),
+            telemetry_freshness_window_s: env::var("ATC_TELEMETRY_FRESHNESS_WINDOW_S")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30),
             command_ack_timeout_secs: env::var("ATC_COMMAND_ACK_TIMEOUT_SECS")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -470,6 +986,24 @@ impl Config {
             require_blender_declaration: env::var("ATC_REQUIRE_BLENDER_DECLARATION")
                 .map(|v| v != "0" && v.to_lowercase() != "false")
                 .unwrap_or(true),
+            advisory_ack_required: env::var("ATC_ADVISORY_ACK_REQUIRED")
+                .map(|v| v != "0" && v.to_lowercase() != "false")
+                .unwrap_or(true),
+            advisory_tfr_adjacent_m: env::var("ATC_ADVISORY_TFR_ADJACENT_M")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(500.0),
+            blender_auto_declare: env::var("ATC_BLENDER_AUTO_DECLARE")
+                .map(|v| v != "0" && v.to_lowercase() != "false")
+                .unwrap_or(false),
+            blender_startup_reconcile: env::var("ATC_BLENDER_STARTUP_RECONCILE")
+                .map(|v| v != "0" && v.to_lowercase() != "false")
+                .unwrap_or(true),
+            lame_duck_secs: env::var("ATC_LAME_DUCK_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
             tls_cert_path: env::var("ATC_TLS_CERT_PATH")
                 .ok()
                 .and_then(|v| {
@@ -501,6 +1035,9 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(5),
+            strategic_altitude_offsets_m: load_altitude_offsets(),
+            strategic_fairness: load_fairness_policy(),
+            operator_quotas: load_operator_quotas(),
             operational_intent_ttl_secs: env::var("ATC_OI_RESERVATION_TTL_SECS")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -521,6 +1058,16 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(default_rules.warning_multiplier),
+            rules_warning_horizontal_multiplier: load_multiplier(
+                "ATC_RULES_WARNING_HORIZONTAL_MULTIPLIER",
+            ),
+            rules_warning_vertical_multiplier: load_multiplier(
+                "ATC_RULES_WARNING_VERTICAL_MULTIPLIER",
+            ),
+            rules_info_horizontal_multiplier: load_multiplier(
+                "ATC_RULES_INFO_HORIZONTAL_MULTIPLIER",
+            ),
+            rules_info_vertical_multiplier: load_multiplier("ATC_RULES_INFO_VERTICAL_MULTIPLIER"),
             rules_drone_timeout_secs: env::var("ATC_RULES_DRONE_TIMEOUT_SECS")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -533,18 +1080,92 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(default_rules.min_altitude_m),
+            breach_policy: BreachPolicy::from_env(),
+            breach_monitor_enabled: env::var("ATC_BREACH_MONITOR_ENABLED")
+                .map(|v| v != "0" && v.to_lowercase() != "false")
+                .unwrap_or(true),
+            breach_lookahead_secs: env::var("ATC_BREACH_LOOKAHEAD_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10.0),
+            auto_command_budget: CommandBudget {
+                global_per_min: env::var("ATC_AUTO_COMMAND_BUDGET_PER_MIN")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0),
+                region_per_min: env::var("ATC_AUTO_COMMAND_REGION_BUDGET_PER_MIN")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0),
+            },
+            inflight_replan_enabled: env::var("ATC_INFLIGHT_REPLAN_ENABLED")
+                .map(|v| v != "0" && v.to_lowercase() != "false")
+                .unwrap_or(true),
+            landing_point_max_distance_m: env::var("ATC_LANDING_POINT_MAX_DISTANCE_M")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(100.0),
+            rules_volume_rules: env::var("ATC_RULES_VOLUMES_PATH")
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .map(|path| load_volume_rules(&path))
+                .unwrap_or_default(),
+            rules_well_clear: load_well_clear(),
+            rules_crewed_protection: load_crewed_protection(),
+            conflict_intent_filter: load_intent_filter(),
+            conflict_intent_conformance_m: env::var("ATC_CONFLICT_INTENT_CONFORMANCE_M")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(25.0),
+            traffic_quality_mode: load_track_quality_mode(),
+            traffic_min_quality: env::var("ATC_TRAFFIC_MIN_QUALITY")
+                .ok()
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|value| (0.0..=1.0).contains(value))
+                .unwrap_or(0.4),
+            traffic_quality: load_track_quality(),
+            traffic_smoothing: load_traffic_smoothing(),
+            lost_dead_reckoning: load_lost_dead_reckoning(),
+            surveillance_crosscheck: load_surveillance_crosscheck(),
+            vertiports: env::var("ATC_VERTIPORTS_PATH")
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .map(|path| load_vertiports(&path))
+                .unwrap_or_default(),
+            sectors: env::var("ATC_SECTORS_PATH")
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .map(|path| load_sectors(&path))
+                .unwrap_or_default(),
+            messages: load_messages(),
         }
     }
 
+    /// Where the database lives: `DATABASE_URL` when set, else the SQLite file path.
+    pub fn database_location(&self) -> &str {
+        self.database_url.as_deref().unwrap_or(&self.database_path)
+    }
+
     pub fn safety_rules(&self) -> SafetyRules {
         let mut rules = SafetyRules {
             min_horizontal_separation_m: self.rules_min_horizontal_separation_m,
             min_vertical_separation_m: self.rules_min_vertical_separation_m,
             lookahead_seconds: self.rules_lookahead_seconds,
             warning_multiplier: self.rules_warning_multiplier,
+            warning_horizontal_multiplier: self.rules_warning_horizontal_multiplier,
+            warning_vertical_multiplier: self.rules_warning_vertical_multiplier,
+            info_horizontal_multiplier: self.rules_info_horizontal_multiplier,
+            info_vertical_multiplier: self.rules_info_vertical_multiplier,
             drone_timeout_secs: self.rules_drone_timeout_secs,
             max_altitude_m: self.rules_max_altitude_m,
             min_altitude_m: self.rules_min_altitude_m,
+            volume_rules: self.rules_volume_rules.clone(),
+            well_clear: self.rules_well_clear,
+            crewed_protection: self.rules_crewed_protection,
             ..Default::default()
         };
         if rules.max_altitude_m > rules.min_altitude_m {
@@ -570,10 +1191,42 @@ impl Config {
         rules
     }
 
+    /// Admin token, preferring the secret backend over `ATC_ADMIN_TOKEN`.
+    pub fn admin_token(&self) -> String {
+        self.secrets
+            .get(SecretKey::AdminToken)
+            .unwrap_or_else(|| self.admin_token.clone())
+    }
+
+    /// Registration token, preferring the secret backend over `ATC_REGISTRATION_TOKEN`.
+    pub fn registration_token(&self) -> Option<String> {
+        self.secrets
+            .get(SecretKey::RegistrationToken)
+            .or_else(|| self.registration_token.clone())
+    }
+
+    /// WebSocket token, preferring the secret backend over `ATC_WS_TOKEN`.
+    pub fn ws_token(&self) -> Option<String> {
+        self.secrets
+            .get(SecretKey::WsToken)
+            .or_else(|| self.ws_token.clone())
+    }
+
+    /// Blender static token, preferring the secret backend over `BLENDER_AUTH_TOKEN`.
+    pub fn blender_auth_token(&self) -> String {
+        self.secrets
+            .get(SecretKey::BlenderAuthToken)
+            .unwrap_or_else(|| self.blender_auth_token.clone())
+    }
+
     pub fn blender_oauth_config(&self) -> Option<BlenderOAuthConfig> {
         let token_url = self.blender_oauth_token_url.as_ref()?.trim();
         let client_id = self.blender_oauth_client_id.as_ref()?.trim();
-        let client_secret = self.blender_oauth_client_secret.as_ref()?.trim();
+        let client_secret = self
+            .secrets
+            .get(SecretKey::BlenderOAuthClientSecret)
+            .or_else(|| self.blender_oauth_client_secret.clone())?;
+        let client_secret = client_secret.trim();
         if token_url.is_empty() || client_id.is_empty() || client_secret.is_empty() {
             return None;
         }
@@ -585,3 +1238,487 @@ impl Config {
         })
     }
 }
+
+fn load_volume_rules(path: &str) -> Vec<VolumeSeparationRule> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            tracing::warn!("Failed to read volume rules from {}: {}", path, err);
+            return Vec::new();
+        }
+    };
+    match serde_json::from_str(&contents) {
+        Ok(rules) => rules,
+        Err(err) => {
+            tracing::warn!("Failed to parse volume rules from {}: {}", path, err);
+            Vec::new()
+        }
+    }
+}
+
+/// Optional alert band multiplier; values below 1 (inside the minima) are ignored.
+fn load_multiplier(name: &str) -> Option<f64> {
+    let value = env::var(name).ok()?;
+    match value.trim().parse::<f64>() {
+        Ok(multiplier) if multiplier.is_finite() && multiplier >= 1.0 => Some(multiplier),
+        _ => {
+            tracing::warn!("Ignoring invalid {}='{}'", name, value);
+            None
+        }
+    }
+}
+
+fn load_coverage_mode() -> CoverageMode {
+    let Ok(mode) = env::var("ATC_ROUTE_PLANNER_C2_COVERAGE") else {
+        return CoverageMode::Off;
+    };
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "off" | "" => CoverageMode::Off,
+        "limit" => CoverageMode::Limit,
+        "require" => CoverageMode::Require,
+        other => {
+            tracing::warn!(
+                "Unknown ATC_ROUTE_PLANNER_C2_COVERAGE '{}', coverage constraint disabled",
+                other
+            );
+            CoverageMode::Off
+        }
+    }
+}
+
+fn load_route_search() -> RouteSearch {
+    let Ok(search) = env::var("ATC_ROUTE_PLANNER_SEARCH") else {
+        return RouteSearch::Grid;
+    };
+    match search.trim().to_ascii_lowercase().as_str() {
+        "grid" | "" => RouteSearch::Grid,
+        "any_angle" => RouteSearch::AnyAngle,
+        other => {
+            tracing::warn!(
+                "Unknown ATC_ROUTE_PLANNER_SEARCH '{}', using grid search",
+                other
+            );
+            RouteSearch::Grid
+        }
+    }
+}
+
+fn load_intent_filter() -> IntentFilterMode {
+    let Ok(mode) = env::var("ATC_CONFLICT_INTENT_FILTER") else {
+        return IntentFilterMode::Off;
+    };
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "off" | "" => IntentFilterMode::Off,
+        "downgrade" => IntentFilterMode::Downgrade,
+        "suppress" => IntentFilterMode::Suppress,
+        other => {
+            tracing::warn!(
+                "Unknown ATC_CONFLICT_INTENT_FILTER '{}', intent filtering disabled",
+                other
+            );
+            IntentFilterMode::Off
+        }
+    }
+}
+
+fn load_track_quality_mode() -> TrackQualityMode {
+    let Ok(mode) = env::var("ATC_TRAFFIC_QUALITY_MODE") else {
+        return TrackQualityMode::Off;
+    };
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "off" | "" => TrackQualityMode::Off,
+        "downgrade" => TrackQualityMode::Downgrade,
+        "ignore" => TrackQualityMode::Ignore,
+        other => {
+            tracing::warn!(
+                "Unknown ATC_TRAFFIC_QUALITY_MODE '{}', track quality filtering disabled",
+                other
+            );
+            TrackQualityMode::Off
+        }
+    }
+}
+
+fn load_track_quality() -> TrackQualityConfig {
+    let defaults = TrackQualityConfig::default();
+    let read = |name: &str, default: f64| {
+        env::var(name)
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|value| value.is_finite() && *value > 0.0)
+            .unwrap_or(default)
+    };
+    TrackQualityConfig {
+        expected_interval_s: read(
+            "ATC_TRAFFIC_EXPECTED_INTERVAL_S",
+            defaults.expected_interval_s,
+        ),
+        stale_after_s: read("ATC_TRAFFIC_STALE_AFTER_S", defaults.stale_after_s),
+        max_speed_mps: read("ATC_TRAFFIC_MAX_SPEED_MPS", defaults.max_speed_mps),
+    }
+}
+
+/// External track smoothing, on unless ATC_TRAFFIC_SMOOTHING is `0`/`false`.
+fn load_traffic_smoothing() -> Option<TrackEstimatorConfig> {
+    let enabled = env::var("ATC_TRAFFIC_SMOOTHING")
+        .map(|v| v != "0" && v.to_lowercase() != "false")
+        .unwrap_or(true);
+    if !enabled {
+        return None;
+    }
+    let defaults = TrackEstimatorConfig::default();
+    let read = |name: &str, default: f64, max: f64| {
+        env::var(name)
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|value| value.is_finite() && *value > 0.0 && *value <= max)
+            .unwrap_or(default)
+    };
+    Some(TrackEstimatorConfig {
+        alpha: read("ATC_TRAFFIC_SMOOTHING_ALPHA", defaults.alpha, 1.0),
+        beta: read("ATC_TRAFFIC_SMOOTHING_BETA", defaults.beta, 1.0),
+        max_extrapolation_s: read(
+            "ATC_TRAFFIC_MAX_EXTRAPOLATION_S",
+            defaults.max_extrapolation_s,
+            f64::MAX,
+        ),
+        // Reports the quality scorer calls a jump also restart the filter.
+        max_speed_mps: load_track_quality().max_speed_mps,
+    })
+}
+
+fn load_lost_dead_reckoning() -> DeadReckoningConfig {
+    let defaults = DeadReckoningConfig::default();
+    let mode = match env::var("ATC_LOST_DEAD_RECKONING") {
+        Ok(value) => DeadReckoningMode::parse(&value).unwrap_or_else(|| {
+            tracing::warn!(
+                "Unknown ATC_LOST_DEAD_RECKONING '{}', lost drones are not dead-reckoned",
+                value
+            );
+            DeadReckoningMode::Off
+        }),
+        Err(_) => defaults.mode,
+    };
+    let read = |name: &str, default: f64| {
+        env::var(name)
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|value| value.is_finite() && *value >= 0.0)
+            .unwrap_or(default)
+    };
+    DeadReckoningConfig {
+        mode,
+        horizon_s: read("ATC_LOST_DEAD_RECKONING_HORIZON_SECS", defaults.horizon_s),
+        uncertainty_growth_mps: read(
+            "ATC_LOST_UNCERTAINTY_GROWTH_MPS",
+            defaults.uncertainty_growth_mps,
+        ),
+    }
+}
+
+fn load_surveillance_crosscheck() -> Option<CrosscheckThresholds> {
+    let defaults = CrosscheckThresholds::default();
+    let read = |name: &str, default: f64| {
+        env::var(name)
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|value| value.is_finite() && *value >= 0.0)
+            .unwrap_or(default)
+    };
+    let horizontal_m = read("ATC_SURVEILLANCE_CROSSCHECK_M", defaults.horizontal_m);
+    if horizontal_m <= 0.0 {
+        return None;
+    }
+    Some(CrosscheckThresholds {
+        horizontal_m,
+        vertical_m: read(
+            "ATC_SURVEILLANCE_CROSSCHECK_VERTICAL_M",
+            defaults.vertical_m,
+        ),
+        max_time_skew_s: read(
+            "ATC_SURVEILLANCE_CROSSCHECK_MAX_SKEW_S",
+            defaults.max_time_skew_s,
+        ),
+    })
+}
+
+/// Well-clear thresholds when the detector runs in `well_clear` mode; DO-365 defaults.
+fn load_well_clear() -> Option<WellClearParams> {
+    let mode = env::var("ATC_CONFLICT_DETECTION_MODE").ok()?;
+    match mode.trim().to_ascii_lowercase().as_str() {
+        "well_clear" | "well-clear" | "daa" => {}
+        "separation" | "" => return None,
+        other => {
+            tracing::warn!(
+                "Unknown ATC_CONFLICT_DETECTION_MODE '{}', using separation minima",
+                other
+            );
+            return None;
+        }
+    }
+    let defaults = WellClearParams::default();
+    let read = |key: &str, default: f64| {
+        env::var(key)
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|value| value.is_finite() && *value >= 0.0)
+            .unwrap_or(default)
+    };
+    Some(WellClearParams {
+        dmod_m: read("ATC_WELL_CLEAR_DMOD_M", defaults.dmod_m),
+        tau_mod_s: read("ATC_WELL_CLEAR_TAU_MOD_S", defaults.tau_mod_s),
+        hmd_m: read("ATC_WELL_CLEAR_HMD_M", defaults.hmd_m),
+        zthr_m: read("ATC_WELL_CLEAR_ZTHR_M", defaults.zthr_m),
+    })
+}
+
+/// Protection volume around crewed traffic, on unless ATC_CREWED_PROTECTION is `0`/`false`.
+fn load_crewed_protection() -> Option<CrewedProtection> {
+    let enabled = env::var("ATC_CREWED_PROTECTION")
+        .map(|v| v != "0" && v.to_lowercase() != "false")
+        .unwrap_or(true);
+    if !enabled {
+        return None;
+    }
+    let defaults = CrewedProtection::default();
+    let read = |key: &str, default: f64| {
+        env::var(key)
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|value| value.is_finite() && *value > 0.0)
+            .unwrap_or(default)
+    };
+    Some(CrewedProtection {
+        horizontal_m: read("ATC_CREWED_PROTECTION_HORIZONTAL_M", defaults.horizontal_m),
+        vertical_m: read("ATC_CREWED_PROTECTION_VERTICAL_M", defaults.vertical_m),
+        lookahead_seconds: read(
+            "ATC_CREWED_PROTECTION_LOOKAHEAD_S",
+            defaults.lookahead_seconds,
+        ),
+        warning_multiplier: read(
+            "ATC_CREWED_PROTECTION_WARNING_MULTIPLIER",
+            defaults.warning_multiplier,
+        )
+        .max(1.0),
+    })
+}
+
+/// Per-operator quotas; each is off unless its variable is set.
+fn load_operator_quotas() -> OperatorQuotas {
+    let limit = |name: &str| {
+        env::var(name)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0)
+    };
+    OperatorQuotas {
+        max_active_flights: limit("ATC_QUOTA_MAX_ACTIVE_FLIGHTS"),
+        max_reserved_intents: limit("ATC_QUOTA_MAX_RESERVED_INTENTS"),
+        max_daily_plans: limit("ATC_QUOTA_MAX_DAILY_PLANS"),
+    }
+}
+
+/// Scheduler fairness policies; each is off unless its variable is set.
+fn load_altitude_offsets() -> Vec<f64> {
+    let Ok(value) = env::var("ATC_STRATEGIC_ALTITUDE_OFFSETS_M") else {
+        return Vec::new();
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| match entry.parse::<f64>() {
+            Ok(offset) if offset.is_finite() && offset != 0.0 => Some(offset),
+            _ => {
+                tracing::warn!(
+                    "Ignoring ATC_STRATEGIC_ALTITUDE_OFFSETS_M entry '{}' (expected non-zero meters)",
+                    entry
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+fn load_fairness_policy() -> FairnessPolicy {
+    let mut operator_weights = HashMap::new();
+    if let Ok(value) = env::var("ATC_STRATEGIC_OPERATOR_WEIGHTS") {
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let parsed = entry.split_once('=').and_then(|(operator, weight)| {
+                let weight = weight.trim().parse::<f64>().ok()?;
+                (weight.is_finite() && weight > 0.0).then(|| (operator.trim().to_string(), weight))
+            });
+            match parsed {
+                Some((operator, weight)) => {
+                    operator_weights.insert(operator, weight);
+                }
+                None => tracing::warn!(
+                    "Ignoring ATC_STRATEGIC_OPERATOR_WEIGHTS entry '{}' (expected operator=weight)",
+                    entry
+                ),
+            }
+        }
+    }
+    FairnessPolicy {
+        max_concurrent_per_operator: env::var("ATC_STRATEGIC_MAX_CONCURRENT_PER_OPERATOR")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0),
+        weighted_delay_sharing: env::var("ATC_STRATEGIC_DELAY_SHARING")
+            .map(|v| v == "1" || v.to_lowercase() == "true")
+            .unwrap_or(false),
+        operator_weights,
+        max_preemption_delay_secs: env::var("ATC_STRATEGIC_MAX_PREEMPTION_DELAY_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0),
+    }
+}
+
+fn load_sectors(path: &str) -> Vec<Sector> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            tracing::warn!("Failed to read sectors from {}: {}", path, err);
+            return Vec::new();
+        }
+    };
+    let sectors: Vec<Sector> = match serde_json::from_str(&contents) {
+        Ok(sectors) => sectors,
+        Err(err) => {
+            tracing::warn!("Failed to parse sectors from {}: {}", path, err);
+            return Vec::new();
+        }
+    };
+    sectors
+        .into_iter()
+        .filter(|sector| {
+            let errors = sector.validate();
+            if !errors.is_empty() {
+                tracing::warn!("Ignoring sector '{}': {}", sector.id, errors.join("; "));
+            }
+            errors.is_empty()
+        })
+        .collect()
+}
+
+fn load_operating_areas(path: &str) -> Vec<OperatingArea> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            tracing::warn!("Failed to read operating areas from {}: {}", path, err);
+            return Vec::new();
+        }
+    };
+    let areas: Vec<OperatingArea> = match serde_json::from_str(&contents) {
+        Ok(areas) => areas,
+        Err(err) => {
+            tracing::warn!("Failed to parse operating areas from {}: {}", path, err);
+            return Vec::new();
+        }
+    };
+    areas
+        .into_iter()
+        .filter(|area| {
+            let errors = area.validate();
+            if !errors.is_empty() {
+                tracing::warn!(
+                    "Ignoring operating area '{}': {}",
+                    area.id,
+                    errors.join("; ")
+                );
+            }
+            errors.is_empty()
+        })
+        .collect()
+}
+
+fn load_population_raster(path: &str) -> Option<PopulationRaster> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            tracing::warn!("Failed to read population raster from {}: {}", path, err);
+            return None;
+        }
+    };
+    match PopulationRaster::from_esri_ascii(&contents) {
+        Ok(raster) => Some(raster),
+        Err(err) => {
+            tracing::warn!("Failed to parse population raster from {}: {}", path, err);
+            None
+        }
+    }
+}
+
+fn load_messages() -> MessageFormatter {
+    let mut messages = MessageFormatter::default();
+    if let Some(path) = env::var("ATC_MESSAGE_CATALOGS_PATH")
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        for catalog in load_message_catalogs(&path) {
+            messages.add_catalog(catalog);
+        }
+    }
+    if let Some(locale) = env::var("ATC_LOCALE")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+    {
+        messages.set_default_locale(&locale);
+    }
+    messages
+}
+
+/// Catalogs from a JSON object of locale to `{code: template}`.
+fn load_message_catalogs(path: &str) -> Vec<MessageCatalog> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            tracing::warn!("Failed to read message catalogs from {}: {}", path, err);
+            return Vec::new();
+        }
+    };
+    let catalogs: HashMap<String, HashMap<String, String>> = match serde_json::from_str(&contents) {
+        Ok(catalogs) => catalogs,
+        Err(err) => {
+            tracing::warn!("Failed to parse message catalogs from {}: {}", path, err);
+            return Vec::new();
+        }
+    };
+    catalogs
+        .into_iter()
+        .map(|(locale, templates)| MessageCatalog::new(&locale, templates))
+        .collect()
+}
+
+fn load_vertiports(path: &str) -> Vec<Vertiport> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            tracing::warn!("Failed to read vertiports from {}: {}", path, err);
+            return Vec::new();
+        }
+    };
+    let vertiports: Vec<Vertiport> = match serde_json::from_str(&contents) {
+        Ok(vertiports) => vertiports,
+        Err(err) => {
+            tracing::warn!("Failed to parse vertiports from {}: {}", path, err);
+            return Vec::new();
+        }
+    };
+    vertiports
+        .into_iter()
+        .filter(|vertiport| {
+            let errors = vertiport.profile.validate();
+            if !errors.is_empty() {
+                tracing::warn!(
+                    "Ignoring vertiport '{}': {}",
+                    vertiport.id,
+                    errors.join("; ")
+                );
+            }
+            errors.is_empty()
+        })
+        .collect()
+}
@@ -0,0 +1,190 @@
+//! Conformance escalation policy.
+//!
+//! A drone reported nonconforming is handled by a ladder of steps (warn, hold, reroute back
+//! onto the plan, notify the operator), each applying once the drone has been off its plan for
+//! `after_s` seconds. There is one ladder per severity: `major` for reports that call for
+//! recovery (out of bounds, altitude out of bounds, geofence breached) and `minor` for the
+//! rest. Operators can be given their own ladders. The conformance loop applies the policy and
+//! records the step on the drone's `ConformanceStatus`; the admin tunes it through
+//! `GET/PUT /v1/admin/conformance/policy`, and the tuned policy is persisted.
+
+use std::collections::BTreeMap;
+
+use atc_core::models::{ConformanceAction, ConformanceRecord};
+use serde::{Deserialize, Serialize};
+
+use crate::loops::conformance_loop::requires_hold;
+
+/// How serious a nonconforming report is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConformanceSeverity {
+    Minor,
+    Major,
+}
+
+impl ConformanceSeverity {
+    pub fn of(record: Option<&ConformanceRecord>) -> Self {
+        if requires_hold(record) {
+            Self::Major
+        } else {
+            Self::Minor
+        }
+    }
+}
+
+/// One rung of an escalation ladder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationStep {
+    pub action: ConformanceAction,
+    /// Seconds nonconforming before this step applies.
+    #[serde(default)]
+    pub after_s: u64,
+}
+
+impl EscalationStep {
+    pub const fn new(action: ConformanceAction, after_s: u64) -> Self {
+        Self { action, after_s }
+    }
+}
+
+/// Escalation steps for each severity, in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationLadder {
+    pub minor: Vec<EscalationStep>,
+    pub major: Vec<EscalationStep>,
+}
+
+impl Default for EscalationLadder {
+    fn default() -> Self {
+        Self {
+            minor: vec![EscalationStep::new(ConformanceAction::Warn, 0)],
+            major: vec![EscalationStep::new(ConformanceAction::Hold, 0)],
+        }
+    }
+}
+
+impl EscalationLadder {
+    /// The step reached after `elapsed_s` seconds nonconforming.
+    pub fn action_for(&self, severity: ConformanceSeverity, elapsed_s: u64) -> ConformanceAction {
+        let steps = match severity {
+            ConformanceSeverity::Minor => &self.minor,
+            ConformanceSeverity::Major => &self.major,
+        };
+        steps
+            .iter()
+            .take_while(|step| step.after_s <= elapsed_s)
+            .last()
+            .map(|step| step.action)
+            .unwrap_or(ConformanceAction::Warn)
+    }
+
+    fn validate(&self, label: &str, errors: &mut Vec<String>) {
+        for (severity, steps) in [("minor", &self.minor), ("major", &self.major)] {
+            match steps.first() {
+                None => errors.push(format!("{label}.{severity} must have at least one step")),
+                Some(first) if first.after_s != 0 => errors.push(format!(
+                    "{label}.{severity} must start with a step at after_s 0"
+                )),
+                Some(_) => {}
+            }
+            if steps
+                .windows(2)
+                .any(|pair| pair[1].after_s <= pair[0].after_s)
+            {
+                errors.push(format!(
+                    "{label}.{severity} steps must have increasing after_s"
+                ));
+            }
+        }
+    }
+}
+
+/// The default ladder and per-operator ladders.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformancePolicy {
+    #[serde(default)]
+    pub default: EscalationLadder,
+    /// Ladders by owner ID, replacing the default for that operator's drones.
+    #[serde(default)]
+    pub owners: BTreeMap<String, EscalationLadder>,
+}
+
+impl ConformancePolicy {
+    pub fn ladder_for(&self, owner_id: Option<&str>) -> &EscalationLadder {
+        owner_id
+            .and_then(|owner_id| self.owners.get(owner_id))
+            .unwrap_or(&self.default)
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        self.default.validate("default", &mut errors);
+        for (owner_id, ladder) in &self.owners {
+            if owner_id.trim().is_empty() {
+                errors.push("owner IDs must not be empty".to_string());
+            }
+            ladder.validate(&format!("owners.{owner_id}"), &mut errors);
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ConformanceAction::*;
+
+    #[test]
+    fn escalates_with_time_and_per_owner() {
+        let escalating = EscalationLadder {
+            minor: vec![
+                EscalationStep::new(Warn, 0),
+                EscalationStep::new(Notify, 300),
+            ],
+            major: vec![
+                EscalationStep::new(Hold, 0),
+                EscalationStep::new(Reroute, 60),
+                EscalationStep::new(Notify, 180),
+            ],
+        };
+        let policy = ConformancePolicy {
+            default: EscalationLadder::default(),
+            owners: BTreeMap::from([("op-strict".to_string(), escalating)]),
+        };
+        assert!(policy.validate().is_empty());
+
+        let strict = policy.ladder_for(Some("op-strict"));
+        assert_eq!(strict.action_for(ConformanceSeverity::Major, 0), Hold);
+        assert_eq!(strict.action_for(ConformanceSeverity::Major, 59), Hold);
+        assert_eq!(strict.action_for(ConformanceSeverity::Major, 60), Reroute);
+        assert_eq!(strict.action_for(ConformanceSeverity::Major, 600), Notify);
+        assert_eq!(strict.action_for(ConformanceSeverity::Minor, 120), Warn);
+
+        let other = policy.ladder_for(Some("op-other"));
+        assert_eq!(other.action_for(ConformanceSeverity::Major, 600), Hold);
+        assert_eq!(other.action_for(ConformanceSeverity::Minor, 600), Warn);
+    }
+
+    #[test]
+    fn rejects_ladders_without_an_immediate_step_or_out_of_order() {
+        let policy = ConformancePolicy {
+            default: EscalationLadder {
+                minor: Vec::new(),
+                major: vec![
+                    EscalationStep::new(Hold, 30),
+                    EscalationStep::new(Reroute, 10),
+                ],
+            },
+            owners: BTreeMap::new(),
+        };
+        assert_eq!(
+            policy.validate(),
+            vec![
+                "default.minor must have at least one step",
+                "default.major must start with a step at after_s 0",
+                "default.major steps must have increasing after_s",
+            ]
+        );
+    }
+}
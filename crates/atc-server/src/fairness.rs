@@ -0,0 +1,358 @@
+//! Fairness between operators in the reservation scheduler.
+//!
+//! Scheduling priority lets a higher-priority reservation push lower-priority ones later, so
+//! one busy operator can starve the others. Three policies bound that:
+//! - a cap on how many of an operator's flights may be scheduled to overlap in time;
+//! - weighted delay sharing: among reservations of equal priority, operators that have already
+//!   absorbed more delay (scaled by their weight) are scheduled first;
+//! - a cap on the delay one reservation may inflict on other operators' reservations by
+//!   preempting them; beyond it the reservation is scheduled behind them instead.
+//!
+//! On top of those, hard quotas cap how many approved flights, reserved intents and plans per
+//! day one operator may hold, so a single tenant cannot fill the schedule.
+//!
+//! What each policy did is counted per operator and served by `GET /v1/scheduler/fairness`.
+
+use std::collections::HashMap;
+
+use atc_core::models::{FlightPlan, FlightStatus};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+
+use crate::metering::UNATTRIBUTED;
+
+/// Scheduler fairness policies; the default leaves scheduling to priority alone.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FairnessPolicy {
+    /// Most of one operator's flights that may be scheduled to overlap in time; 0 is unlimited.
+    pub max_concurrent_per_operator: usize,
+    /// Order equal-priority reservations by the delay their operators have absorbed.
+    pub weighted_delay_sharing: bool,
+    /// Delay-sharing weight per operator; unlisted operators weigh 1. An operator with weight 2
+    /// is expected to absorb twice the delay of one with weight 1 before it is favoured.
+    pub operator_weights: HashMap<String, f64>,
+    /// Most delay (seconds) one reservation may inflict on other operators' reservations by
+    /// preempting them; 0 is unlimited.
+    pub max_preemption_delay_secs: u64,
+}
+
+impl FairnessPolicy {
+    fn weight(&self, operator: &str) -> f64 {
+        self.operator_weights
+            .get(operator)
+            .copied()
+            .filter(|weight| weight.is_finite() && *weight > 0.0)
+            .unwrap_or(1.0)
+    }
+
+    /// Scheduling delay each operator's booked plans have absorbed, divided by its weight.
+    pub fn delay_shares(&self, plans: &[FlightPlan]) -> HashMap<String, f64> {
+        let mut shares = HashMap::new();
+        for plan in plans.iter().filter(|plan| {
+            matches!(
+                plan.status,
+                FlightStatus::Reserved | FlightStatus::Approved | FlightStatus::Active
+            )
+        }) {
+            let delay_s = plan
+                .metadata
+                .as_ref()
+                .and_then(|meta| meta.scheduled_delay_s)
+                .unwrap_or(0);
+            *shares
+                .entry(operator_key(plan.owner_id.as_deref()))
+                .or_insert(0.0) += delay_s as f64;
+        }
+        for (operator, share) in shares.iter_mut() {
+            *share /= self.weight(operator);
+        }
+        shares
+    }
+
+    /// Whether flying `plan` would put its operator over the concurrency cap, given the
+    /// flights already `scheduled`. Plans without an operator are never capped.
+    pub fn exceeds_concurrency(&self, plan: &FlightPlan, scheduled: &[FlightPlan]) -> bool {
+        let Some(operator) = plan.owner_id.as_deref() else {
+            return false;
+        };
+        if self.max_concurrent_per_operator == 0 {
+            return false;
+        }
+        let (start, end) = flight_window(plan);
+        let overlapping = scheduled
+            .iter()
+            .filter(|other| other.flight_id != plan.flight_id)
+            .filter(|other| other.owner_id.as_deref() == Some(operator))
+            .filter(|other| {
+                let (other_start, other_end) = flight_window(other);
+                other_start <= end && start <= other_end
+            })
+            .count();
+        overlapping >= self.max_concurrent_per_operator
+    }
+}
+
+/// Hard per-operator limits on plan submissions; 0 leaves a limit off.
+///
+/// `max_active_flights` counts every approved or in-flight plan the operator holds, whenever it
+/// flies, and refuses the submission with a 429. [`FairnessPolicy::max_concurrent_per_operator`]
+/// only counts plans whose flight windows overlap the new one, and defers the reservation to a
+/// later slot instead of refusing it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OperatorQuotas {
+    /// Most approved or in-flight plans one operator may hold at once.
+    pub max_active_flights: usize,
+    /// Most unexpired operational intent reservations one operator may hold at once.
+    pub max_reserved_intents: usize,
+    /// Most plans one operator may submit per UTC day.
+    pub max_daily_plans: usize,
+}
+
+/// The quota a submission would exceed.
+#[derive(Debug, Clone, PartialEq, Serialize, thiserror::Error)]
+#[error("operator {owner_id} is at its {quota} quota ({limit})")]
+pub struct QuotaExceeded {
+    pub owner_id: String,
+    /// `max_active_flights`, `max_reserved_intents` or `max_daily_plans`
+    pub quota: &'static str,
+    pub limit: usize,
+    /// What the operator holds now, not counting the submission.
+    pub current: usize,
+}
+
+impl OperatorQuotas {
+    /// Check a new plan that would be stored with `status` against its operator's quotas.
+    /// Plans without an operator are never limited.
+    pub fn check_submission(
+        &self,
+        owner_id: Option<&str>,
+        status: FlightStatus,
+        plans: &[FlightPlan],
+        now: DateTime<Utc>,
+    ) -> Result<(), QuotaExceeded> {
+        self.check_status(owner_id, status, plans, now)?;
+        let Some(owner_id) = owner_id else {
+            return Ok(());
+        };
+        let day_start = now
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .map(|start| start.and_utc())
+            .unwrap_or(now);
+        let submitted_today = plans
+            .iter()
+            .filter(|plan| plan.owner_id.as_deref() == Some(owner_id))
+            .filter(|plan| plan.created_at >= day_start)
+            .count();
+        Self::enforce(
+            owner_id,
+            "max_daily_plans",
+            self.max_daily_plans,
+            submitted_today,
+        )
+    }
+
+    /// Check moving one of an operator's plans into `status`, e.g. confirming a reservation.
+    pub fn check_status(
+        &self,
+        owner_id: Option<&str>,
+        status: FlightStatus,
+        plans: &[FlightPlan],
+        now: DateTime<Utc>,
+    ) -> Result<(), QuotaExceeded> {
+        let Some(owner_id) = owner_id else {
+            return Ok(());
+        };
+        let owned = plans
+            .iter()
+            .filter(|plan| plan.owner_id.as_deref() == Some(owner_id));
+        match status {
+            FlightStatus::Approved | FlightStatus::Active => {
+                let active = owned
+                    .filter(|plan| {
+                        matches!(plan.status, FlightStatus::Approved | FlightStatus::Active)
+                    })
+                    .count();
+                Self::enforce(
+                    owner_id,
+                    "max_active_flights",
+                    self.max_active_flights,
+                    active,
+                )
+            }
+            FlightStatus::Reserved => {
+                let reserved = owned
+                    .filter(|plan| plan.status == FlightStatus::Reserved)
+                    .filter(|plan| !reservation_expired(plan, now))
+                    .count();
+                Self::enforce(
+                    owner_id,
+                    "max_reserved_intents",
+                    self.max_reserved_intents,
+                    reserved,
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn enforce(
+        owner_id: &str,
+        quota: &'static str,
+        limit: usize,
+        current: usize,
+    ) -> Result<(), QuotaExceeded> {
+        if limit == 0 || current < limit {
+            return Ok(());
+        }
+        Err(QuotaExceeded {
+            owner_id: owner_id.to_string(),
+            quota,
+            limit,
+            current,
+        })
+    }
+}
+
+fn reservation_expired(plan: &FlightPlan, now: DateTime<Utc>) -> bool {
+    plan.metadata
+        .as_ref()
+        .and_then(|meta| meta.reservation_expires_at.as_deref())
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        .is_some_and(|expires_at| expires_at <= now)
+}
+
+fn flight_window(plan: &FlightPlan) -> (DateTime<Utc>, DateTime<Utc>) {
+    let end = plan.arrival_time.unwrap_or(plan.departure_time);
+    (plan.departure_time, end.max(plan.departure_time))
+}
+
+/// Operator a plan is attributed to, with unowned plans pooled.
+pub fn operator_key(owner_id: Option<&str>) -> String {
+    owner_id
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(UNATTRIBUTED)
+        .to_string()
+}
+
+/// What the fairness policies did for one operator.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OperatorFairness {
+    pub operator: String,
+    /// Reservations pushed later because the operator was at its concurrency cap.
+    pub concurrency_deferrals: u64,
+    /// Delay those reservations took on beyond their first conflict-free slot.
+    pub concurrency_delay_secs: u64,
+    /// Reservations scheduled ahead of earlier equal-priority ones by weighted delay sharing.
+    pub delay_sharing_promotions: u64,
+    /// Reservations kept from preempting others because it would inflict too much delay.
+    pub preemptions_limited: u64,
+    /// Delay this operator's reservations inflicted on other operators' by preemption.
+    pub preemption_delay_inflicted_secs: u64,
+    /// Delay this operator's reservations suffered from other operators' preemption.
+    pub preemption_delay_suffered_secs: u64,
+    /// Submissions refused because the operator was at one of its quotas.
+    pub quota_rejections: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum FairnessEvent {
+    ConcurrencyDeferral { delay_secs: u64 },
+    DelaySharingPromotion,
+    PreemptionLimited,
+    PreemptionInflicted { delay_secs: u64 },
+    PreemptionSuffered { delay_secs: u64 },
+    QuotaRejection,
+}
+
+impl FairnessEvent {
+    fn apply(self, stats: &mut OperatorFairness) {
+        match self {
+            Self::ConcurrencyDeferral { delay_secs } => {
+                stats.concurrency_deferrals += 1;
+                stats.concurrency_delay_secs += delay_secs;
+            }
+            Self::DelaySharingPromotion => stats.delay_sharing_promotions += 1,
+            Self::PreemptionLimited => stats.preemptions_limited += 1,
+            Self::PreemptionInflicted { delay_secs } => {
+                stats.preemption_delay_inflicted_secs += delay_secs;
+            }
+            Self::PreemptionSuffered { delay_secs } => {
+                stats.preemption_delay_suffered_secs += delay_secs;
+            }
+            Self::QuotaRejection => stats.quota_rejections += 1,
+        }
+    }
+}
+
+/// Fairness events from one scheduling run, recorded only if the run's outcome is kept.
+#[derive(Debug, Default)]
+pub struct FairnessTally {
+    events: Vec<(String, FairnessEvent)>,
+}
+
+impl FairnessTally {
+    fn push(&mut self, owner_id: Option<&str>, event: FairnessEvent) {
+        self.events.push((operator_key(owner_id), event));
+    }
+
+    pub fn concurrency_deferral(&mut self, owner_id: Option<&str>, delay_secs: u64) {
+        self.push(owner_id, FairnessEvent::ConcurrencyDeferral { delay_secs });
+    }
+
+    pub fn delay_sharing_promotion(&mut self, owner_id: Option<&str>) {
+        self.push(owner_id, FairnessEvent::DelaySharingPromotion);
+    }
+
+    pub fn preemption_limited(&mut self, owner_id: Option<&str>) {
+        self.push(owner_id, FairnessEvent::PreemptionLimited);
+    }
+
+    /// `by`'s reservation pushed one of `on`'s `delay_secs` later.
+    pub fn preemption_delay(&mut self, by: Option<&str>, on: Option<&str>, delay_secs: u64) {
+        self.push(by, FairnessEvent::PreemptionInflicted { delay_secs });
+        self.push(on, FairnessEvent::PreemptionSuffered { delay_secs });
+    }
+
+    pub fn quota_rejection(&mut self, owner_id: Option<&str>) {
+        self.push(owner_id, FairnessEvent::QuotaRejection);
+    }
+}
+
+/// Fairness counters per operator since the server started.
+#[derive(Default)]
+pub struct FairnessMetrics {
+    operators: DashMap<String, OperatorFairness>,
+}
+
+impl FairnessMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, tally: FairnessTally) {
+        for (operator, event) in tally.events {
+            let mut stats =
+                self.operators
+                    .entry(operator.clone())
+                    .or_insert_with(|| OperatorFairness {
+                        operator,
+                        ..Default::default()
+                    });
+            event.apply(&mut stats);
+        }
+    }
+
+    /// Counters per operator, sorted by operator.
+    pub fn operators(&self) -> Vec<OperatorFairness> {
+        let mut operators: Vec<OperatorFairness> = self
+            .operators
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        operators.sort_by(|a, b| a.operator.cmp(&b.operator));
+        operators
+    }
+}
@@ -0,0 +1,347 @@
+//! gRPC streaming for companion computers (`proto/atc/v1/drone_stream.proto`).
+//!
+//! Served on its own port (`ATC_GRPC_PORT`) next to the HTTP API and backed by the same
+//! [`AppState`], so telemetry sent here goes through the same checks as `POST /v1/telemetry` and
+//! commands are the ones `/v1/commands/ws` delivers. Streams authenticate with the drone session
+//! token in the call metadata, and are refused until the server has finished starting.
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use atc_core::models::{self, CommandType};
+use axum::http::{HeaderMap, HeaderValue};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::{Stream, StreamExt};
+use prost::Message;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tonic::metadata::MetadataMap;
+use tonic::transport::{Identity, Server, ServerTlsConfig};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::api::auth;
+use crate::api::validate_telemetry;
+use crate::config::Config;
+use crate::lifecycle::LifecyclePhase;
+use crate::state::AppState;
+use crate::telemetry_auth::{NONCE_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+pub mod proto {
+    tonic::include_proto!("atc.v1");
+}
+
+use proto::drone_stream_server::{DroneStream, DroneStreamServer};
+use proto::{command, CommandAck, CommandFrame, TelemetryAck, TelemetryFrame};
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// The `DroneStream` service.
+pub struct DroneStreamService {
+    state: Arc<AppState>,
+}
+
+impl DroneStreamService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// The drone and token a call is authenticated as.
+    #[allow(clippy::result_large_err)] // Handlers return tonic's Status as is
+    fn authenticate(&self, metadata: &MetadataMap) -> Result<(String, String), Status> {
+        if self.state.lifecycle().phase() == LifecyclePhase::Starting {
+            return Err(Status::unavailable("Server is starting"));
+        }
+        let token = auth::extract_drone_token(&metadata.clone().into_headers())
+            .ok_or_else(|| Status::unauthenticated("Missing drone session token"))?;
+        let drone_id = self
+            .state
+            .drone_id_for_token(&token)
+            .ok_or_else(|| Status::permission_denied("Invalid drone session token"))?;
+        Ok((drone_id, token))
+    }
+}
+
+#[tonic::async_trait]
+impl DroneStream for DroneStreamService {
+    type StreamTelemetryStream = ResponseStream<TelemetryAck>;
+    type StreamCommandsStream = ResponseStream<CommandFrame>;
+
+    async fn stream_telemetry(
+        &self,
+        request: Request<Streaming<TelemetryFrame>>,
+    ) -> Result<Response<Self::StreamTelemetryStream>, Status> {
+        let (_, token) = self.authenticate(request.metadata())?;
+        let state = self.state.clone();
+        let acks = request.into_inner().then(move |frame| {
+            let state = state.clone();
+            let token = token.clone();
+            async move { Ok(ingest(&state, &token, frame?).await) }
+        });
+        Ok(Response::new(Box::pin(acks)))
+    }
+
+    async fn stream_commands(
+        &self,
+        request: Request<Streaming<CommandAck>>,
+    ) -> Result<Response<Self::StreamCommandsStream>, Status> {
+        let (drone_id, _) = self.authenticate(request.metadata())?;
+
+        let state = self.state.clone();
+        let ack_drone_id = drone_id.clone();
+        let mut acks = request.into_inner();
+        tokio::spawn(async move {
+            while let Some(Ok(ack)) = acks.next().await {
+                acknowledge(&state, &ack_drone_id, &ack.command_id).await;
+            }
+        });
+
+        // Subscribe before reading the queue so a command issued in between isn't missed.
+        let mut commands = CommandFeed {
+            rx: self.state.subscribe_commands(),
+            pending: Vec::new(),
+            sent: HashSet::new(),
+            state: self.state.clone(),
+            drone_id,
+        };
+        commands.reload();
+        let frames = futures::stream::unfold(commands, |mut commands| async move {
+            let command = commands.next_command().await?;
+            let frame = command_frame(commands.state.sign_command(command));
+            Some((Ok(frame), commands))
+        });
+        Ok(Response::new(Box::pin(frames)))
+    }
+}
+
+/// Authenticate, validate and apply one telemetry frame.
+async fn ingest(state: &AppState, token: &str, frame: TelemetryFrame) -> TelemetryAck {
+    let reject = |error: &str, reason: &str| TelemetryAck {
+        sequence: frame.sequence,
+        accepted: false,
+        error: error.to_string(),
+        reason: reason.to_string(),
+    };
+    let Some(telemetry) = frame.telemetry.as_ref() else {
+        return reject("Frame has no telemetry", "");
+    };
+    // Checked per frame so a revoked or rotated token stops the stream's telemetry.
+    if !state.validate_drone_token(&telemetry.drone_id, token) {
+        return reject("Session token is not valid for this drone", "");
+    }
+
+    let now = Utc::now();
+    let mac_secret = state.telemetry_mac_secret(&telemetry.drone_id, token);
+    let guard = state.telemetry_guard();
+    if let Err(reason) = guard.verify(
+        state.config().telemetry_auth_mode,
+        state.config().telemetry_freshness_window_s,
+        &telemetry.drone_id,
+        mac_secret.as_deref(),
+        &signature_headers(&frame),
+        &telemetry.encode_to_vec(),
+        now,
+    ) {
+        guard.record_rejection(&telemetry.drone_id, reason, now);
+        let code = serde_json::to_value(reason)
+            .ok()
+            .and_then(|code| code.as_str().map(str::to_string))
+            .unwrap_or_default();
+        return reject(reason.message(), &code);
+    }
+
+    let Some(timestamp) = from_millis(telemetry.timestamp_ms) else {
+        return reject("Invalid timestamp", "");
+    };
+    let mut telemetry = models::Telemetry {
+        drone_id: telemetry.drone_id.clone(),
+        owner_id: telemetry.owner_id.clone(),
+        lat: telemetry.lat,
+        lon: telemetry.lon,
+        altitude_m: telemetry.altitude_m,
+        velocity_x: telemetry.velocity_x,
+        velocity_y: telemetry.velocity_y,
+        velocity_z: telemetry.velocity_z,
+        heading_deg: telemetry.heading_deg,
+        speed_mps: telemetry.speed_mps,
+        timestamp,
+    };
+    if let Err((_, body)) = validate_telemetry(&telemetry, state.config(), now) {
+        let error = body.0["error"].as_str().unwrap_or("Invalid telemetry");
+        return reject(error, "");
+    }
+    // Server receipt time, as on the HTTP API.
+    telemetry.timestamp = now;
+    state.update_telemetry(telemetry).await;
+    TelemetryAck {
+        sequence: frame.sequence,
+        accepted: true,
+        ..Default::default()
+    }
+}
+
+/// The frame's signature fields as the headers [`crate::telemetry_auth::ReplayGuard`] reads.
+fn signature_headers(frame: &TelemetryFrame) -> HeaderMap {
+    let signed_at = match frame.signed_at_ms {
+        0 => String::new(),
+        ms => ms.to_string(),
+    };
+    let mut headers = HeaderMap::new();
+    for (name, value) in [
+        (TIMESTAMP_HEADER, signed_at.as_str()),
+        (NONCE_HEADER, frame.nonce.as_str()),
+        (SIGNATURE_HEADER, frame.signature.as_str()),
+    ] {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            if !value.is_empty() {
+                headers.insert(name, value);
+            }
+        }
+    }
+    headers
+}
+
+async fn acknowledge(state: &AppState, drone_id: &str, command_id: &str) {
+    if state
+        .command_drone_id(command_id)
+        .is_some_and(|owner| owner != drone_id)
+    {
+        tracing::warn!(
+            "Drone {} tried to acknowledge command {} of another drone",
+            drone_id,
+            command_id
+        );
+        return;
+    }
+    match state.ack_command(command_id).await {
+        Ok(true) => tracing::info!("Command {} acknowledged", command_id),
+        Ok(false) => {}
+        Err(err) => tracing::error!("Failed to acknowledge command {}: {}", command_id, err),
+    }
+}
+
+/// One drone's commands: its queue at subscription, then newly issued ones.
+struct CommandFeed {
+    state: Arc<AppState>,
+    drone_id: String,
+    rx: broadcast::Receiver<models::Command>,
+    pending: Vec<models::Command>,
+    /// Commands already sent, so a queued command also seen on the channel goes out once.
+    sent: HashSet<String>,
+}
+
+impl CommandFeed {
+    /// Queue the drone's unacknowledged commands to be sent (again, unless already sent).
+    fn reload(&mut self) {
+        self.pending = self.state.get_pending_commands(&self.drone_id);
+        self.sent
+            .retain(|id| self.pending.iter().any(|command| &command.command_id == id));
+        self.pending.reverse();
+    }
+
+    async fn next_command(&mut self) -> Option<models::Command> {
+        loop {
+            let command = match self.pending.pop() {
+                Some(command) => command,
+                None => match self.rx.recv().await {
+                    Ok(command) if command.drone_id == self.drone_id => command,
+                    Ok(_) => continue,
+                    // Missed commands are still queued until acknowledged.
+                    Err(RecvError::Lagged(_)) => {
+                        self.reload();
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return None,
+                },
+            };
+            if self.sent.insert(command.command_id.clone()) {
+                return Some(command);
+            }
+        }
+    }
+}
+
+fn command_frame(signed: models::SignedCommand) -> CommandFrame {
+    let signed_payload = signed
+        .signature
+        .as_ref()
+        .and_then(|signature| BASE64.decode(&signature.payload).ok())
+        .unwrap_or_else(|| signed.command.signing_payload());
+    let command = signed.command;
+    let command_type = match command.command_type {
+        CommandType::Hold { duration_secs } => {
+            command::CommandType::Hold(proto::Hold { duration_secs })
+        }
+        CommandType::AltitudeChange { target_altitude_m } => {
+            command::CommandType::AltitudeChange(proto::AltitudeChange { target_altitude_m })
+        }
+        CommandType::Reroute { waypoints, reason } => {
+            command::CommandType::Reroute(proto::Reroute {
+                waypoints: waypoints
+                    .into_iter()
+                    .map(|waypoint| proto::Waypoint {
+                        lat: waypoint.lat,
+                        lon: waypoint.lon,
+                        altitude_m: waypoint.altitude_m,
+                        speed_mps: waypoint.speed_mps,
+                    })
+                    .collect(),
+                reason,
+            })
+        }
+        CommandType::Resume => command::CommandType::Resume(proto::Resume {}),
+        CommandType::Land => command::CommandType::Land(proto::Land {}),
+    };
+    CommandFrame {
+        command: Some(proto::Command {
+            command_id: command.command_id,
+            drone_id: command.drone_id,
+            command_type: Some(command_type),
+            issued_at_ms: command.issued_at.timestamp_millis(),
+            expires_at_ms: command.expires_at.map(|at| at.timestamp_millis()),
+        }),
+        signed_payload,
+        signature: signed.signature.map(|signature| proto::CommandSignature {
+            key_id: signature.key_id,
+            algorithm: signature.algorithm,
+            signature: signature.signature,
+        }),
+    }
+}
+
+fn from_millis(ms: i64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(ms).single()
+}
+
+/// Serve the gRPC API on `addr` in the background until `shutdown` fires, with the HTTP API's
+/// TLS certificate when one is configured.
+pub async fn spawn_server(
+    config: &Config,
+    state: Arc<AppState>,
+    addr: SocketAddr,
+    mut shutdown: broadcast::Receiver<()>,
+) -> Result<tokio::task::JoinHandle<Result<()>>> {
+    let mut server = Server::builder();
+    if let (Some(cert_path), Some(key_path)) = (&config.tls_cert_path, &config.tls_key_path) {
+        let identity = Identity::from_pem(
+            tokio::fs::read(cert_path).await?,
+            tokio::fs::read(key_path).await?,
+        );
+        server = server.tls_config(ServerTlsConfig::new().identity(identity))?;
+    } else if config.require_tls {
+        bail!("TLS required but ATC_TLS_CERT_PATH/ATC_TLS_KEY_PATH not set");
+    }
+    let router = server.add_service(DroneStreamServer::new(DroneStreamService::new(state)));
+    tracing::info!("gRPC listening on {}", addr);
+    Ok(tokio::spawn(async move {
+        router
+            .serve_with_shutdown(addr, async move {
+                let _ = shutdown.recv().await;
+            })
+            .await?;
+        Ok(())
+    }))
+}
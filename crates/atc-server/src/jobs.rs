@@ -0,0 +1,427 @@
+//! Durable background job queue.
+//!
+//! One-off work that should outlive the request that asked for it, survive a restart and be
+//! retried when it fails is queued here instead of being spawned from its own loop. Jobs are
+//! kept in memory and written through to the `jobs` table. Enqueuing a job starts it straight
+//! away when fewer than `ATC_JOB_CONCURRENCY` jobs are running; the job loop picks up the rest,
+//! along with retries once they fall due. A failed job is retried with exponential backoff
+//! (`ATC_JOB_RETRY_BASE_SECS` doubling up to `ATC_JOB_RETRY_MAX_SECS`) until it has been tried
+//! `ATC_JOB_MAX_ATTEMPTS` times, then kept as a dead letter until an operator retries it from
+//! `POST /v1/admin/jobs/{id}/retry`. Jobs a restart interrupted are queued again when the
+//! database is loaded, so handlers must be safe to run twice.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::backoff::add_jitter;
+use crate::config::Config;
+use crate::persistence::jobs as jobs_db;
+use crate::state::AppState;
+use crate::warmup;
+use crate::webhooks;
+
+/// What a job does; each kind has one handler in [`run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    /// Prefetch planner obstacles and terrain for the operating areas.
+    PlannerWarmup,
+    /// POST one event to one registered webhook.
+    WebhookDelivery,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Waiting for `run_at`, including between retries.
+    Queued,
+    Running,
+    Succeeded,
+    /// Failed on every attempt; kept until retried by hand.
+    Dead,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    pub payload: Value,
+    pub status: JobStatus,
+    /// Attempts started so far.
+    pub attempts: u32,
+    pub max_attempts: u32,
+    /// Earliest time the next attempt may start.
+    pub run_at: DateTime<Utc>,
+    /// Error from the latest failed attempt.
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Job {
+    fn new(kind: JobKind, payload: Value, max_attempts: u32) -> Self {
+        let now = Utc::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            payload,
+            status: JobStatus::Queued,
+            attempts: 0,
+            max_attempts: max_attempts.max(1),
+            run_at: now,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        matches!(self.status, JobStatus::Queued | JobStatus::Running)
+    }
+}
+
+/// Filters for job listings.
+#[derive(Debug, Clone, Default)]
+pub struct JobFilter {
+    pub status: Option<JobStatus>,
+    pub kind: Option<JobKind>,
+    pub limit: usize,
+}
+
+/// Job counts by status.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JobCounts {
+    pub queued: usize,
+    pub running: usize,
+    pub succeeded: usize,
+    pub dead: usize,
+}
+
+/// In-memory job table; [`enqueue`], [`dispatch`] and [`retry`] keep the database in step.
+#[derive(Debug, Default)]
+pub struct JobQueue {
+    jobs: Mutex<HashMap<String, Job>>,
+}
+
+impl JobQueue {
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.jobs.lock().ok()?.get(id).cloned()
+    }
+
+    /// Jobs matching `filter`, newest first.
+    pub fn list(&self, filter: &JobFilter) -> Vec<Job> {
+        let Ok(jobs) = self.jobs.lock() else {
+            return Vec::new();
+        };
+        let mut matching: Vec<Job> = jobs
+            .values()
+            .filter(|job| filter.status.is_none_or(|status| job.status == status))
+            .filter(|job| filter.kind.is_none_or(|kind| job.kind == kind))
+            .cloned()
+            .collect();
+        matching.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        matching.truncate(filter.limit);
+        matching
+    }
+
+    pub fn counts(&self) -> JobCounts {
+        let mut counts = JobCounts::default();
+        if let Ok(jobs) = self.jobs.lock() {
+            for job in jobs.values() {
+                match job.status {
+                    JobStatus::Queued => counts.queued += 1,
+                    JobStatus::Running => counts.running += 1,
+                    JobStatus::Succeeded => counts.succeeded += 1,
+                    JobStatus::Dead => counts.dead += 1,
+                }
+            }
+        }
+        counts
+    }
+
+    /// Whether a job of `kind` is queued or running.
+    pub fn has_active(&self, kind: JobKind) -> bool {
+        self.jobs
+            .lock()
+            .map(|jobs| jobs.values().any(|job| job.kind == kind && job.is_active()))
+            .unwrap_or(false)
+    }
+
+    /// Replace the table with jobs loaded from the database; jobs that were running when the
+    /// server stopped are queued again. Returns the jobs that changed.
+    pub(crate) fn load(&self, loaded: Vec<Job>) -> Vec<Job> {
+        let Ok(mut jobs) = self.jobs.lock() else {
+            return Vec::new();
+        };
+        jobs.clear();
+        let mut interrupted = Vec::new();
+        for mut job in loaded {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Queued;
+                job.updated_at = Utc::now();
+                interrupted.push(job.clone());
+            }
+            jobs.insert(job.id.clone(), job);
+        }
+        interrupted
+    }
+
+    /// Add a job; with `unique`, not when one of the same kind is already queued or running.
+    fn insert(&self, job: Job, unique: bool) -> Option<Job> {
+        let mut jobs = self.jobs.lock().ok()?;
+        if unique
+            && jobs
+                .values()
+                .any(|existing| existing.kind == job.kind && existing.is_active())
+        {
+            return None;
+        }
+        jobs.insert(job.id.clone(), job.clone());
+        Some(job)
+    }
+
+    /// Mark due jobs running, oldest first, until `concurrency` are running.
+    fn claim_due(&self, concurrency: usize, now: DateTime<Utc>) -> Vec<Job> {
+        let Ok(mut jobs) = self.jobs.lock() else {
+            return Vec::new();
+        };
+        let running = jobs
+            .values()
+            .filter(|job| job.status == JobStatus::Running)
+            .count();
+        let mut due: Vec<&mut Job> = jobs
+            .values_mut()
+            .filter(|job| job.status == JobStatus::Queued && job.run_at <= now)
+            .collect();
+        due.sort_by(|a, b| a.run_at.cmp(&b.run_at).then_with(|| a.id.cmp(&b.id)));
+        due.into_iter()
+            .take(concurrency.max(1).saturating_sub(running))
+            .map(|job| {
+                job.status = JobStatus::Running;
+                job.attempts += 1;
+                job.updated_at = now;
+                job.clone()
+            })
+            .collect()
+    }
+
+    /// Record the outcome of an attempt: done, queued for a retry, or dead.
+    fn finish(&self, id: &str, result: Result<(), String>, config: &Config) -> Option<Job> {
+        let mut jobs = self.jobs.lock().ok()?;
+        let job = jobs.get_mut(id)?;
+        let now = Utc::now();
+        job.updated_at = now;
+        match result {
+            Ok(()) => {
+                job.status = JobStatus::Succeeded;
+                job.last_error = None;
+            }
+            Err(err) => {
+                job.last_error = Some(err);
+                if job.attempts >= job.max_attempts {
+                    job.status = JobStatus::Dead;
+                } else {
+                    job.status = JobStatus::Queued;
+                    let delay = retry_delay(config, job.attempts);
+                    job.run_at = now
+                        + chrono::Duration::from_std(delay)
+                            .unwrap_or_else(|_| chrono::Duration::seconds(60));
+                }
+            }
+        }
+        Some(job.clone())
+    }
+
+    /// Queue a dead job for a fresh set of attempts.
+    fn revive(&self, id: &str) -> Result<Job, RetryError> {
+        let mut jobs = self.jobs.lock().map_err(|_| RetryError::NotFound)?;
+        let job = jobs.get_mut(id).ok_or(RetryError::NotFound)?;
+        if job.status != JobStatus::Dead {
+            return Err(RetryError::NotDead(job.status));
+        }
+        let now = Utc::now();
+        job.status = JobStatus::Queued;
+        job.attempts = 0;
+        job.run_at = now;
+        job.updated_at = now;
+        Ok(job.clone())
+    }
+
+    /// Drop succeeded jobs last updated before `before`.
+    fn prune(&self, before: DateTime<Utc>) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.retain(|_, job| job.status != JobStatus::Succeeded || job.updated_at >= before);
+        }
+    }
+}
+
+/// Why a job could not be retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum RetryError {
+    #[error("job not found")]
+    NotFound,
+    #[error("only dead jobs can be retried (job is {0:?})")]
+    NotDead(JobStatus),
+}
+
+/// Wait before attempt `attempts + 1`: the base delay doubled per failed attempt, capped.
+fn retry_delay(config: &Config, attempts: u32) -> Duration {
+    let base = Duration::from_secs(config.job_retry_base_secs.max(1));
+    let max = Duration::from_secs(config.job_retry_max_secs).max(base);
+    let doublings = attempts.saturating_sub(1).min(20);
+    add_jitter(base.saturating_mul(1 << doublings).min(max), 0.2)
+}
+
+/// Queue a job and start it if a worker slot is free.
+#[allow(dead_code)] // Every current job kind runs one at a time
+pub async fn enqueue(state: &Arc<AppState>, kind: JobKind, payload: Value) -> Job {
+    let job = Job::new(kind, payload, state.config().job_max_attempts);
+    state.jobs().insert(job.clone(), false);
+    persist(state, &job).await;
+    dispatch(state);
+    job
+}
+
+/// Like [`enqueue`], unless a job of `kind` is already queued or running.
+pub async fn enqueue_unique(state: &Arc<AppState>, kind: JobKind, payload: Value) -> Option<Job> {
+    let job = Job::new(kind, payload, state.config().job_max_attempts);
+    let job = state.jobs().insert(job, true)?;
+    persist(state, &job).await;
+    dispatch(state);
+    Some(job)
+}
+
+/// Queue a job for the job loop to start, from code that can't wait for the database. The
+/// job is persisted when its first attempt starts.
+pub(crate) fn enqueue_deferred(state: &AppState, kind: JobKind, payload: Value) -> Job {
+    let job = Job::new(kind, payload, state.config().job_max_attempts);
+    state.jobs().insert(job.clone(), false);
+    job
+}
+
+/// Queue a dead job again with a fresh set of attempts.
+pub async fn retry(state: &Arc<AppState>, id: &str) -> Result<Job, RetryError> {
+    let job = state.jobs().revive(id)?;
+    persist(state, &job).await;
+    dispatch(state);
+    Ok(job)
+}
+
+/// Start due jobs while worker slots are free.
+pub fn dispatch(state: &Arc<AppState>) {
+    let config = state.config();
+    for job in state.jobs().claim_due(config.job_concurrency, Utc::now()) {
+        tokio::spawn(execute(state.clone(), job));
+    }
+}
+
+/// Forget succeeded jobs older than `ATC_JOB_RETENTION_HOURS`.
+pub async fn prune(state: &AppState) {
+    let before = Utc::now() - chrono::Duration::hours(state.config().job_retention_hours as i64);
+    state.jobs().prune(before);
+    if let Some(db) = state.database() {
+        if let Err(err) = jobs_db::delete_jobs_before(db.pool(), JobStatus::Succeeded, before).await
+        {
+            tracing::warn!("Failed to prune finished jobs: {}", err);
+        }
+    }
+}
+
+async fn execute(state: Arc<AppState>, job: Job) {
+    persist(&state, &job).await;
+    tracing::info!(job_id = %job.id, kind = ?job.kind, attempt = job.attempts, "Job start");
+    // A panicking handler fails the attempt instead of leaving the job running.
+    let result = match tokio::spawn(run(state.clone(), job.clone())).await {
+        Ok(result) => result,
+        Err(err) => Err(format!("job task failed: {}", err)),
+    };
+    if let Err(err) = &result {
+        tracing::warn!(job_id = %job.id, kind = ?job.kind, attempt = job.attempts, "Job failed: {}", err);
+    }
+    if let Some(finished) = state.jobs().finish(&job.id, result, state.config()) {
+        if finished.status == JobStatus::Dead {
+            tracing::error!(job_id = %finished.id, kind = ?finished.kind, "Job dead-lettered after {} attempts", finished.attempts);
+        }
+        persist(&state, &finished).await;
+    }
+    dispatch(&state);
+}
+
+async fn run(state: Arc<AppState>, job: Job) -> Result<(), String> {
+    match job.kind {
+        JobKind::PlannerWarmup => warmup::run_job(&state, &job.payload).await,
+        JobKind::WebhookDelivery => webhooks::run_job(&state, &job.payload).await,
+    }
+}
+
+async fn persist(state: &AppState, job: &Job) {
+    let Some(db) = state.database() else {
+        return;
+    };
+    if let Err(err) = jobs_db::upsert_job(db.pool(), job).await {
+        tracing::warn!(job_id = %job.id, "Failed to persist job: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> Config {
+        let mut config = Config::from_env();
+        config.job_retry_base_secs = 10;
+        config.job_retry_max_secs = 25;
+        config
+    }
+
+    #[test]
+    fn retries_with_backoff_then_dead_letters() {
+        let config = config();
+        let queue = JobQueue::default();
+        let job = queue
+            .insert(Job::new(JobKind::PlannerWarmup, json!({}), 3), true)
+            .unwrap();
+        assert!(queue
+            .insert(Job::new(JobKind::PlannerWarmup, json!({}), 3), true)
+            .is_none());
+
+        let mut delays = Vec::new();
+        for attempt in 1..=3 {
+            let now = queue.get(&job.id).unwrap().run_at;
+            let claimed = queue.claim_due(4, now);
+            assert_eq!(claimed.len(), 1);
+            assert_eq!(claimed[0].attempts, attempt);
+            assert!(queue.claim_due(4, now).is_empty());
+            let finished = queue
+                .finish(&job.id, Err(format!("attempt {attempt}")), &config)
+                .unwrap();
+            delays.push((finished.run_at - finished.updated_at).num_seconds());
+        }
+        let dead = queue.get(&job.id).unwrap();
+        assert_eq!(dead.status, JobStatus::Dead);
+        assert_eq!(dead.last_error.as_deref(), Some("attempt 3"));
+        // Queued for 10 s, then 20 s (with up to 20% jitter); the dead job keeps its last run_at.
+        assert!((10..=12).contains(&delays[0]), "{:?}", delays);
+        assert!((20..=24).contains(&delays[1]), "{:?}", delays);
+        assert!(!queue.has_active(JobKind::PlannerWarmup));
+
+        let revived = queue.revive(&job.id).unwrap();
+        assert_eq!(revived.status, JobStatus::Queued);
+        assert_eq!(revived.attempts, 0);
+        assert_eq!(
+            queue.revive(&job.id).unwrap_err(),
+            RetryError::NotDead(JobStatus::Queued)
+        );
+        assert_eq!(queue.revive("missing").unwrap_err(), RetryError::NotFound);
+    }
+}
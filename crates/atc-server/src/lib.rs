@@ -1,14 +1,46 @@
 //! Shared library surface for ATC server utilities and tests.
 
+pub mod adsb;
 pub mod altitude;
 pub mod api;
 pub mod backoff;
 pub mod blender_auth;
+pub mod breach;
 pub mod cache;
+pub mod chaos;
+pub mod command_signing;
 pub mod compliance;
 pub mod config;
+pub mod conformance_policy;
+pub mod fairness;
+pub mod grpc;
+pub mod jobs;
+pub mod lifecycle;
 pub mod loops;
+pub mod metering;
+pub mod obstacle_index;
+pub mod organizations;
 pub mod persistence;
+pub mod planner_pool;
+pub mod reconcile;
+pub mod rejection;
+pub mod replan;
+pub mod route_graph;
 pub mod route_planner;
+pub mod secrets;
+pub mod sectors;
 pub mod state;
+pub mod surveillance;
+pub mod telemetry_auth;
 pub mod terrain;
+pub mod tether;
+pub mod tfr;
+pub mod throttle;
+pub mod track_index;
+pub mod warmup;
+pub mod webhooks;
+pub mod wind;
+pub mod wpml;
+
+#[cfg(test)]
+mod airspace_tests;
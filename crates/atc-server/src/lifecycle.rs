@@ -0,0 +1,131 @@
+//! Process lifecycle for orchestrated rollouts.
+//!
+//! The server starts listening before its state is loaded so `/startupz` can report progress; it
+//! answers 503 to everything but the probes until the database is loaded and the initial Blender
+//! reconciliation has run. On shutdown it enters a lame-duck window (`ATC_LAME_DUCK_SECS`):
+//! `/ready` fails so the pod leaves the load balancer, planning endpoints answer 503 so clients
+//! retry against another replica, and telemetry, commands and reads keep being served so the
+//! airspace picture stays live until the new pods take over.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::state::AppState;
+
+/// Paths served regardless of lifecycle phase.
+const PROBE_PATHS: &[&str] = &["/health", "/ready", "/startupz"];
+
+/// Startup progress and shutdown drain state.
+#[derive(Debug, Default)]
+pub struct Lifecycle {
+    database_loaded: AtomicBool,
+    blender_reconciled: AtomicBool,
+    draining: AtomicBool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecyclePhase {
+    Starting,
+    Serving,
+    Draining,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StartupStatus {
+    pub ok: bool,
+    pub database_loaded: bool,
+    pub blender_reconciled: bool,
+}
+
+impl Lifecycle {
+    pub fn mark_database_loaded(&self) {
+        self.database_loaded.store(true, Ordering::Release);
+    }
+
+    /// Record that the initial Blender reconciliation ran (or is disabled).
+    pub fn mark_blender_reconciled(&self) {
+        self.blender_reconciled.store(true, Ordering::Release);
+    }
+
+    /// Enter the lame-duck window.
+    pub fn begin_draining(&self) {
+        self.draining.store(true, Ordering::Release);
+    }
+
+    pub fn startup_status(&self) -> StartupStatus {
+        let database_loaded = self.database_loaded.load(Ordering::Acquire);
+        let blender_reconciled = self.blender_reconciled.load(Ordering::Acquire);
+        StartupStatus {
+            ok: database_loaded && blender_reconciled,
+            database_loaded,
+            blender_reconciled,
+        }
+    }
+
+    pub fn phase(&self) -> LifecyclePhase {
+        if self.draining.load(Ordering::Acquire) {
+            LifecyclePhase::Draining
+        } else if self.startup_status().ok {
+            LifecyclePhase::Serving
+        } else {
+            LifecyclePhase::Starting
+        }
+    }
+}
+
+/// Endpoints that plan or book airspace; refused while draining.
+fn is_planning_request(method: &Method, path: &str) -> bool {
+    path.starts_with("/v1/routes/")
+        || path.starts_with("/v1/operational_intents")
+        || path == "/v1/compliance/evaluate"
+        || path == "/v1/geofences/check-route"
+        || (method == Method::POST && path.starts_with("/v1/flights"))
+}
+
+/// Kubernetes startup probe: 200 once the state is loaded and reconciled.
+pub async fn startup_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let status = state.lifecycle().startup_status();
+    let code = if status.ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(status))
+}
+
+/// Middleware refusing requests the current lifecycle phase cannot serve.
+pub async fn gate_requests(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if PROBE_PATHS.contains(&path) {
+        return next.run(request).await;
+    }
+    let error = match state.lifecycle().phase() {
+        LifecyclePhase::Serving => return next.run(request).await,
+        LifecyclePhase::Starting => "Server is starting",
+        LifecyclePhase::Draining if is_planning_request(request.method(), path) => {
+            "Server is shutting down"
+        }
+        LifecyclePhase::Draining => return next.run(request).await,
+    };
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, "1")],
+        Json(json!({ "error": error })),
+    )
+        .into_response()
+}
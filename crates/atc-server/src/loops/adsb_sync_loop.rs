@@ -0,0 +1,117 @@
+//! ADS-B sync loop.
+//!
+//! Polls a dump1090 or OpenSky feed for manned aircraft and feeds them into the conflict
+//! detector as external traffic, so drones are warned about crewed aircraft and not only about
+//! each other and Remote ID tracks. The loop idles while no feed is configured.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::Client;
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+use crate::adsb::{parse_feed, AdsbFeedFormat, AdsbFilter};
+use crate::altitude::AltitudeReference;
+use crate::backoff::Backoff;
+use crate::config::Config;
+use crate::state::{AppState, ExternalTraffic};
+
+const ADSB_TRACK_TTL_SECS: i64 = 30;
+const ADSB_BACKOFF_MAX_SECS: u64 = 120;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Start the ADS-B polling loop.
+pub async fn run_adsb_loop(
+    state: Arc<AppState>,
+    config: Config,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .unwrap_or_else(|_| Client::new());
+    let poll = Duration::from_secs(config.adsb_poll_secs);
+    let mut ticker = interval(poll);
+    let mut backoff = Backoff::new(poll, Duration::from_secs(ADSB_BACKOFF_MAX_SECS));
+    let filter = AdsbFilter {
+        area: config.adsb_area,
+        max_altitude_m: config.adsb_max_altitude_m,
+        geoid_offset_m: config.geoid_offset_m,
+    };
+    state.mark_loop_heartbeat("adsb");
+
+    loop {
+        tokio::select! {
+            _ = shutdown.recv() => {
+                tracing::info!("ADS-B sync loop shutting down");
+                break;
+            }
+            _ = ticker.tick() => {
+                state.mark_loop_heartbeat("adsb");
+                let Some(url) = config.adsb_feed_url.as_deref() else {
+                    continue;
+                };
+                if state.loop_paused("adsb") || !backoff.ready() {
+                    continue;
+                }
+                match fetch_aircraft(&client, &config, url, &filter).await {
+                    Ok(tracks) => {
+                        backoff.reset();
+                        for track in tracks {
+                            state
+                                .upsert_external_traffic(in_config_reference(track, &config))
+                                .await;
+                        }
+                        state.purge_external_traffic(ADSB_TRACK_TTL_SECS).await;
+                    }
+                    Err(err) => {
+                        let delay = backoff.fail();
+                        tracing::warn!(
+                            "ADS-B feed pull failed: {} (backing off {:?})",
+                            err,
+                            delay
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn fetch_aircraft(
+    client: &Client,
+    config: &Config,
+    url: &str,
+    filter: &AdsbFilter,
+) -> Result<Vec<ExternalTraffic>, String> {
+    let mut request = client.get(url);
+    // OpenSky filters server-side by bounding box.
+    if let (AdsbFeedFormat::OpenSky, Some(area)) = (config.adsb_feed_format, filter.area) {
+        request = request.query(&[
+            ("lamin", area.min_lat),
+            ("lomin", area.min_lon),
+            ("lamax", area.max_lat),
+            ("lomax", area.max_lon),
+        ]);
+    }
+    let document: serde_json::Value = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| err.to_string())?
+        .json()
+        .await
+        .map_err(|err| err.to_string())?;
+    parse_feed(config.adsb_feed_format, &document, filter, Utc::now())
+}
+
+/// Tracks are parsed with AMSL altitudes; external traffic is stored from the configured
+/// altitude reference.
+fn in_config_reference(mut track: ExternalTraffic, config: &Config) -> ExternalTraffic {
+    if config.altitude_reference == AltitudeReference::Wgs84 {
+        track.altitude_m += config.geoid_offset_m;
+    }
+    track
+}
@@ -49,6 +49,9 @@ pub async fn run_blender_loop(
             }
             _ = ticker.tick() => {
                 state.mark_loop_heartbeat("blender-sync");
+                if state.loop_paused("blender-sync") {
+                    continue;
+                }
                 if !backoff.ready() {
                     continue;
                 }
@@ -2,7 +2,10 @@
 //!
 //! Runs in the background, periodically checking for conflicts
 //! and broadcasting updates as geofences to Blender.
-//! Issues REROUTE commands when critical conflicts are detected.
+//! Issues the lowest-cost resolution maneuver (or a REROUTE when none restores separation)
+//! when critical conflicts are detected. Commands are checked against the give-way drone's
+//! registered performance envelope and advertised capabilities, so it is only told to fly what
+//! it physically can and its autopilot accepts.
 
 use chrono::{Duration as ChronoDuration, Utc};
 use std::collections::{HashMap, HashSet};
@@ -13,24 +16,346 @@ use tokio::time::interval;
 
 use crate::backoff::Backoff;
 use crate::blender_auth::BlenderAuthManager;
+use crate::breach::{self, BreachKind};
 use crate::config::Config;
+use crate::persistence::command_audit::{CommandCause, CommandCauseKind};
+use crate::replan::{record_replan, replan_remaining_route};
 use crate::route_planner::plan_airborne_route;
-use crate::state::AppState;
-use atc_blender::{conflict_payload, conflict_to_geofence, BlenderClient};
+use crate::state::{AppState, ExternalTraffic};
+use crate::surveillance;
+use crate::tether;
+use atc_blender::{conflict_payload, conflict_to_geofence, AvoidanceRecommendation, BlenderClient};
 use atc_core::{
-    generate_avoidance_route,
-    models::{Command, CommandType, DaaAdvisory, DaaSeverity, Geofence, GeofenceType, Waypoint},
-    select_avoidance_type, Conflict, ConflictSeverity,
+    cluster_conflicts, generate_avoidance_route,
+    messages::{codes, Message},
+    models::{
+        Command, CommandType, DaaAdvisory, DaaSeverity, DroneState, Geofence, GeofenceType,
+        Waypoint,
+    },
+    resolution_options,
+    rules::SafetyRules,
+    select_avoidance_type, AvoidanceType, Conflict, ConflictCluster, ConflictSeverity,
+    DronePerformance, DronePosition, Maneuver, ResolutionOption,
 };
 
 /// Cooldown in seconds before issuing another command to the same drone.
 /// Set to 60s to cover full reroute execution (physics-based ~30-45s) plus buffer.
-const COMMAND_COOLDOWN_SECS: u64 = 60;
+pub(crate) const COMMAND_COOLDOWN_SECS: u64 = 60;
 const CONFLICT_TTL_SECS: i64 = 3600;
 const CONFLICT_REFRESH_GRACE_SECS: i64 = 300;
 const FAILSAFE_HOLD_SECS: u32 = 120;
 const RESOLUTION_COOLDOWN_SECS: i64 = 120;
 const CONFLICT_SUMMARY_LOG_INTERVAL_SECS: u64 = 30;
+/// Vertical spacing between cluster layers, as a multiple of the vertical separation minimum.
+const CLUSTER_LAYER_SPACING_FACTOR: f64 = 1.2;
+const CLUSTER_HOLD_SECS: u32 = 15;
+/// How long a resolution turn is flown before rejoining the original track.
+const RESOLUTION_TURN_LEG_SECS: f64 = 20.0;
+/// Distance ahead on the original heading where resolution maneuvers rejoin the track.
+const RESOLUTION_REJOIN_M: f64 = 500.0;
+/// Hold issued in place of a reroute the drone cannot fly.
+const ENVELOPE_HOLD_SECS: u32 = 15;
+
+/// Ordering key for flight priority: lower keeps its trajectory, and drones without a
+/// scheduling priority rank after every drone that has one.
+fn priority_rank(priority: Option<u32>) -> u32 {
+    priority.unwrap_or(u32::MAX)
+}
+
+/// Drone that gives way in a local conflict: the lower-priority flight yields (higher
+/// `scheduling_priority`), so emergency and medical flights keep their trajectory. Between equal
+/// priorities the higher (newer) ID yields.
+pub(crate) fn give_way_drone_id(
+    conflict: &Conflict,
+    priority1: Option<u32>,
+    priority2: Option<u32>,
+) -> &String {
+    match priority_rank(priority1).cmp(&priority_rank(priority2)) {
+        std::cmp::Ordering::Less => &conflict.drone2_id,
+        std::cmp::Ordering::Greater => &conflict.drone1_id,
+        std::cmp::Ordering::Equal if conflict.drone1_id < conflict.drone2_id => &conflict.drone2_id,
+        std::cmp::Ordering::Equal => &conflict.drone1_id,
+    }
+}
+
+/// Avoidance maneuver for the give-way drone that it can fly before the conflict's closest
+/// approach.
+pub(crate) fn avoidance_type_for(
+    conflict: &Conflict,
+    altitude_m: f64,
+    priority_altitude_m: f64,
+    rules: &SafetyRules,
+    performance: Option<&DronePerformance>,
+) -> AvoidanceType {
+    select_avoidance_type(
+        altitude_m,
+        priority_altitude_m,
+        conflict.time_to_closest,
+        rules,
+        performance,
+    )
+}
+
+/// Avoidance advertised on a conflict's geofence: the maneuver the give-way drone would be
+/// commanded, or the reroute type when no single maneuver restores separation. Maneuvers are
+/// only considered against another drone, as against external traffic the drone is rerouted.
+pub(crate) fn recommended_avoidance(
+    conflict: &Conflict,
+    give_way: &DroneState,
+    priority: Option<&DroneState>,
+    priority_altitude_m: f64,
+    rules: &SafetyRules,
+    performance: Option<&DronePerformance>,
+    wind_mps: f64,
+) -> AvoidanceRecommendation {
+    priority
+        .and_then(|pri| select_resolution(conflict, give_way, pri, rules, performance, wind_mps))
+        .map(|option| AvoidanceRecommendation::maneuver(give_way.drone_id.clone(), &option))
+        .unwrap_or_else(|| {
+            AvoidanceRecommendation::reroute(
+                give_way.drone_id.clone(),
+                avoidance_type_for(
+                    conflict,
+                    give_way.altitude_m,
+                    priority_altitude_m,
+                    rules,
+                    performance,
+                ),
+            )
+        })
+}
+
+/// A drone or external track taking part in a multi-aircraft cluster.
+#[derive(Debug, Clone)]
+pub(crate) struct ClusterParticipant {
+    pub drone_id: String,
+    pub altitude_m: f64,
+    /// False for external traffic, which cannot be commanded.
+    pub commandable: bool,
+    pub priority: Option<u32>,
+}
+
+/// Coordinated resolution for a multi-aircraft cluster.
+///
+/// One drone keeps its course: the lowest-ID external track if any (it cannot be commanded),
+/// otherwise the highest-priority drone (lowest ID among equals), matching the pairwise give-way
+/// rule. Every other commandable drone is assigned its own altitude layer above or below the
+/// anchor, nearest layers first, within the altitude limits. Layers are handed out in altitude
+/// order so drones never cross each other vertically. Drones left without a layer get `None` and
+/// should hold instead.
+pub(crate) fn plan_cluster_resolution(
+    participants: &[ClusterParticipant],
+    rules: &SafetyRules,
+) -> Vec<(String, Option<f64>)> {
+    let mut sorted: Vec<&ClusterParticipant> = participants.iter().collect();
+    sorted.sort_by(|a, b| {
+        priority_rank(a.priority)
+            .cmp(&priority_rank(b.priority))
+            .then(a.drone_id.cmp(&b.drone_id))
+    });
+    let Some(anchor) = sorted
+        .iter()
+        .filter(|participant| !participant.commandable)
+        .min_by(|a, b| a.drone_id.cmp(&b.drone_id))
+        .or_else(|| sorted.first())
+        .copied()
+    else {
+        return Vec::new();
+    };
+    let anchor = (&anchor.drone_id, anchor.altitude_m);
+
+    let mut give_way: Vec<(&String, f64)> = sorted
+        .iter()
+        .filter(|participant| participant.commandable && &participant.drone_id != anchor.0)
+        .map(|participant| (&participant.drone_id, participant.altitude_m))
+        .collect();
+    if give_way.is_empty() {
+        return Vec::new();
+    }
+
+    let spacing_m = (rules.min_vertical_separation_m * CLUSTER_LAYER_SPACING_FACTOR).max(1.0);
+    let mut layers = Vec::with_capacity(give_way.len());
+    let mut step = 1.0;
+    while layers.len() < give_way.len() {
+        let above = anchor.1 + spacing_m * step;
+        let below = anchor.1 - spacing_m * step;
+        let above_ok = above <= rules.max_altitude_m;
+        let below_ok = below >= rules.min_altitude_m;
+        if !above_ok && !below_ok {
+            break;
+        }
+        if above_ok {
+            layers.push(above);
+        }
+        if below_ok && layers.len() < give_way.len() {
+            layers.push(below);
+        }
+        step += 1.0;
+    }
+    layers.sort_by(f64::total_cmp);
+
+    // Drones nearest the anchor altitude get a layer first; the rest hold.
+    give_way.sort_by(|a, b| {
+        (a.1 - anchor.1)
+            .abs()
+            .total_cmp(&(b.1 - anchor.1).abs())
+            .then(a.0.cmp(b.0))
+    });
+    let (layered, holding) = give_way.split_at(layers.len().min(give_way.len()));
+    let mut layered = layered.to_vec();
+    layered.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(b.0)));
+
+    layered
+        .into_iter()
+        .zip(layers)
+        .map(|((id, _), layer)| (id.clone(), Some(layer)))
+        .chain(holding.iter().map(|(id, _)| ((*id).clone(), None)))
+        .collect()
+}
+
+fn drone_position(drone: &DroneState) -> DronePosition {
+    DronePosition::new(&drone.drone_id, drone.lat, drone.lon, drone.altitude_m).with_velocity(
+        drone.heading_deg,
+        drone.speed_mps,
+        drone.velocity_z,
+    )
+}
+
+/// Lowest-cost maneuver for the give-way drone that restores separation and that it can fly,
+/// if any does.
+pub(crate) fn select_resolution(
+    conflict: &Conflict,
+    give_way: &DroneState,
+    priority: &DroneState,
+    rules: &SafetyRules,
+    performance: Option<&DronePerformance>,
+    wind_mps: f64,
+) -> Option<ResolutionOption> {
+    let performance = give_way
+        .capabilities
+        .unwrap_or_default()
+        .limit_envelope(performance.copied());
+    resolution_options(
+        conflict,
+        &drone_position(give_way),
+        &drone_position(priority),
+        rules,
+        performance.as_ref(),
+    )
+    .into_iter()
+    .filter(|option| option.resolves)
+    .find(|option| {
+        let command = resolution_command(option, give_way, String::new());
+        envelope_violations(&command, give_way, performance.as_ref(), wind_mps).is_empty()
+    })
+}
+
+/// Reasons `drone` cannot fly `command` in `wind_mps`: commands it did not advertise support
+/// for, then its envelope with the advertised climb limit. Empty when it can.
+pub(crate) fn envelope_violations(
+    command: &CommandType,
+    drone: &DroneState,
+    performance: Option<&DronePerformance>,
+    wind_mps: f64,
+) -> Vec<String> {
+    let capabilities = drone.capabilities.unwrap_or_default();
+    if let Some(reason) = capabilities.unsupported(command) {
+        return vec![reason];
+    }
+    let Some(performance) = capabilities.limit_envelope(performance.copied()) else {
+        return Vec::new();
+    };
+    match command {
+        CommandType::Reroute { waypoints, .. } => {
+            performance.route_violations(waypoints, drone.speed_mps, wind_mps)
+        }
+        CommandType::Hold { .. } if !performance.can_hold(wind_mps) => vec![format!(
+            "cannot hold in {:.1} m/s wind (tolerance {:.1} m/s)",
+            wind_mps, performance.max_wind_mps
+        )],
+        _ => Vec::new(),
+    }
+}
+
+fn command_kind(command: &CommandType) -> &'static str {
+    if matches!(command, CommandType::Hold { .. }) {
+        "HOLD"
+    } else {
+        "REROUTE"
+    }
+}
+
+/// `command` if the drone can fly it. A reroute it cannot fly becomes a short HOLD when it can
+/// hold in the current wind; `None` when neither is flyable.
+pub(crate) fn executable_command(
+    command: CommandType,
+    drone: &DroneState,
+    performance: Option<&DronePerformance>,
+    wind_mps: f64,
+) -> Option<CommandType> {
+    let violations = envelope_violations(&command, drone, performance, wind_mps);
+    if violations.is_empty() {
+        return Some(command);
+    }
+    if !matches!(command, CommandType::Reroute { .. }) {
+        tracing::warn!("{} cannot HOLD: {}", drone.drone_id, violations.join("; "));
+        return None;
+    }
+    tracing::warn!(
+        "{} cannot fly the REROUTE: {}",
+        drone.drone_id,
+        violations.join("; ")
+    );
+    let hold = CommandType::Hold {
+        duration_secs: ENVELOPE_HOLD_SECS,
+    };
+    envelope_violations(&hold, drone, performance, wind_mps)
+        .is_empty()
+        .then_some(hold)
+}
+
+/// Command that flies a resolution maneuver.
+///
+/// Climbs and descents become altitude changes. Turns fly the new heading for a short leg and
+/// rejoin the original heading; speed changes continue straight ahead at the new speed.
+pub(crate) fn resolution_command(
+    option: &ResolutionOption,
+    drone: &DroneState,
+    reason: String,
+) -> CommandType {
+    use atc_core::spatial::offset_by_bearing;
+
+    if option.maneuver.is_vertical() {
+        return CommandType::AltitudeChange {
+            target_altitude_m: option.target_altitude_m,
+        };
+    }
+    let waypoint = |(lat, lon): (f64, f64)| Waypoint {
+        lat,
+        lon,
+        altitude_m: drone.altitude_m,
+        speed_mps: Some(option.target_speed_mps),
+    };
+    let mut waypoints = vec![waypoint((drone.lat, drone.lon))];
+    if matches!(option.maneuver, Maneuver::TurnLeft | Maneuver::TurnRight) {
+        waypoints.push(waypoint(offset_by_bearing(
+            drone.lat,
+            drone.lon,
+            option.target_speed_mps * RESOLUTION_TURN_LEG_SECS,
+            option.target_heading_deg.to_radians(),
+        )));
+    }
+    waypoints.push(waypoint(offset_by_bearing(
+        drone.lat,
+        drone.lon,
+        RESOLUTION_REJOIN_M,
+        drone.heading_deg.to_radians(),
+    )));
+    CommandType::Reroute {
+        waypoints,
+        reason: Some(reason),
+    }
+}
 
 #[derive(Debug, Clone)]
 struct BlenderConflictState {
@@ -56,9 +381,13 @@ pub async fn run_conflict_loop(
     );
     let mut tracked_conflicts: HashMap<String, BlenderConflictState> = HashMap::new();
     let mut resolution_cooldowns: HashMap<String, i64> = HashMap::new();
+    let mut active_breaches: HashMap<(String, String), BreachKind> = HashMap::new();
+    let mut active_tethers: HashSet<String> = HashSet::new();
+    let mut active_divergences: HashSet<String> = HashSet::new();
     let mut last_conflict_count: usize = 0;
     let mut last_conflict_log_at: Instant = Instant::now();
     let mut blender_backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(60));
+    let wind_mps = config.route_planner_wind_mps.max(0.0);
 
     loop {
         tokio::select! {
@@ -68,6 +397,9 @@ pub async fn run_conflict_loop(
             }
             _ = ticker.tick() => {
                 state.mark_loop_heartbeat("conflict");
+                if state.loop_paused("conflict") {
+                    continue;
+                }
                 let mut blender_available = false;
                 if blender_backoff.ready() {
                     match auth.apply(&mut blender).await {
@@ -99,7 +431,7 @@ pub async fn run_conflict_loop(
                             expires_at: Some(now + ChronoDuration::seconds(FAILSAFE_HOLD_SECS as i64)),
                             acknowledged: false,
                         };
-                        if let Err(err) = state.enqueue_command(cmd).await {
+                        if let Err(err) = state.enqueue_auto_command(cmd, CommandCause::new(CommandCauseKind::Failsafe)).await {
                             tracing::warn!("Failed to enqueue failsafe HOLD for {}: {}", drone_id, err);
                         } else {
                             state.mark_command_issued(drone_id);
@@ -113,7 +445,14 @@ pub async fn run_conflict_loop(
                     tracing::warn!("Failed to purge expired commands: {}", err);
                 }
                 state.purge_expired_active_holds();
+                state.extrapolate_external_traffic().await;
+                state.dead_reckon_lost_drones().await;
                 state.refresh_conflicts().await;
+                if config.breach_monitor_enabled {
+                    breach::monitor_breaches(state.as_ref(), &mut active_breaches).await;
+                }
+                tether::monitor_tethers(state.as_ref(), &mut active_tethers).await;
+                surveillance::monitor_surveillance(state.as_ref(), &mut active_divergences);
 
                 let conflicts = state.get_conflicts();
                 let loop_now = Utc::now();
@@ -199,6 +538,24 @@ pub async fn run_conflict_loop(
                     .map(|track| (track.traffic_id.clone(), track))
                     .collect();
 
+                // Clusters of three or more drones get one coordinated resolution instead of
+                // pairwise reroutes that could steer drones into each other.
+                let mut clustered_drones: HashSet<String> = HashSet::new();
+                for cluster in cluster_conflicts(&conflicts).iter().filter(|c| c.is_multi_aircraft()) {
+                    clustered_drones.extend(cluster.drone_ids.iter().cloned());
+                    if matches!(cluster.severity, ConflictSeverity::Critical | ConflictSeverity::Warning) {
+                        resolve_cluster(
+                            state.as_ref(),
+                            cluster,
+                            &drones,
+                            &external_by_id,
+                            &mut resolution_cooldowns,
+                            wind_mps,
+                        )
+                        .await;
+                    }
+                }
+
                 // Transform conflicts to geofences + issue REROUTE commands
                 let mut geofences = Vec::with_capacity(conflicts.len());
                 let mut active_conflict_ids: HashSet<String> = HashSet::new();
@@ -209,6 +566,11 @@ pub async fn run_conflict_loop(
                         (&conflict.drone2_id, &conflict.drone1_id)
                     };
                     let conflict_key = format!("{}-{}", id_a, id_b);
+                    let conflict_cause = CommandCause::new(CommandCauseKind::Conflict).with_source(
+                        state
+                            .conflict_history_id(&conflict.drone1_id, &conflict.drone2_id)
+                            .unwrap_or_else(|| conflict_key.clone()),
+                    );
                     let now = Utc::now();
                     // Find drone positions
                     let drone1 = drones.iter().find(|d| d.drone_id == conflict.drone1_id);
@@ -223,7 +585,48 @@ pub async fn run_conflict_loop(
                         .map(|d| (d.lat, d.lon, d.altitude_m))
                         .or_else(|| external2.map(|t| (t.lat, t.lon, t.altitude_m)));
 
-                    let gf = conflict_to_geofence(conflict, drone1_pos, drone2_pos);
+                    let recommendation = if conflict.severity == ConflictSeverity::Info
+                        || clustered_drones.contains(&conflict.drone1_id)
+                    {
+                        None
+                    } else {
+                        match (drone1, drone2) {
+                            (Some(d1), Some(d2)) => {
+                                let give_way_id = give_way_drone_id(
+                                    conflict,
+                                    d1.scheduling_priority,
+                                    d2.scheduling_priority,
+                                );
+                                let (gw, pri) = if give_way_id == &d1.drone_id { (d1, d2) } else { (d2, d1) };
+                                Some(recommended_avoidance(
+                                    conflict,
+                                    gw,
+                                    Some(pri),
+                                    pri.altitude_m,
+                                    &state.rules(),
+                                    state.drone_performance(&gw.drone_id).as_ref(),
+                                    wind_mps,
+                                ))
+                            }
+                            (Some(gw), None) | (None, Some(gw)) => {
+                                let other_pos = if drone1.is_some() { drone2_pos } else { drone1_pos };
+                                Some(recommended_avoidance(
+                                    conflict,
+                                    gw,
+                                    None,
+                                    other_pos.map(|pos| pos.2).unwrap_or(conflict.cpa_altitude_m),
+                                    &state.rules(),
+                                    state.drone_performance(&gw.drone_id).as_ref(),
+                                    wind_mps,
+                                ))
+                            }
+                            (None, None) => None,
+                        }
+                    };
+                    let mut gf = conflict_to_geofence(conflict, drone1_pos, drone2_pos);
+                    if let Some(recommendation) = recommendation {
+                        gf = gf.with_recommendation(recommendation);
+                    }
                     active_conflict_ids.insert(gf.id.clone());
 
                     tracing::warn!(
@@ -244,6 +647,7 @@ pub async fn run_conflict_loop(
                     if let Some(drone) = drone1 {
                         let advisory_id = format!("conflict-{}-{}", drone.drone_id, conflict.drone2_id);
                         active_daa_ids.insert(advisory_id.clone());
+                        let message = conflict_message(conflict, &conflict.drone2_id);
                         state.set_daa_advisory(DaaAdvisory {
                             advisory_id,
                             drone_id: drone.drone_id.clone(),
@@ -251,13 +655,11 @@ pub async fn run_conflict_loop(
                             source: "conflict".to_string(),
                             severity,
                             action: action.to_string(),
-                            description: format!(
-                                "Conflict with {} ({:.0}m separation)",
-                                conflict.drone2_id,
-                                conflict.distance_m
-                            ),
+                            description: state.config().messages.format(&message, None),
+                            message_code: Some(message),
                             related_id: Some(conflict_key.clone()),
                             record: None,
+                            sector_id: None,
                             created_at: now,
                             updated_at: now,
                             resolved: false,
@@ -267,6 +669,7 @@ pub async fn run_conflict_loop(
                     if let Some(drone) = drone2 {
                         let advisory_id = format!("conflict-{}-{}", drone.drone_id, conflict.drone1_id);
                         active_daa_ids.insert(advisory_id.clone());
+                        let message = conflict_message(conflict, &conflict.drone1_id);
                         state.set_daa_advisory(DaaAdvisory {
                             advisory_id,
                             drone_id: drone.drone_id.clone(),
@@ -274,13 +677,11 @@ pub async fn run_conflict_loop(
                             source: "conflict".to_string(),
                             severity,
                             action: action.to_string(),
-                            description: format!(
-                                "Conflict with {} ({:.0}m separation)",
-                                conflict.drone1_id,
-                                conflict.distance_m
-                            ),
+                            description: state.config().messages.format(&message, None),
+                            message_code: Some(message),
                             related_id: Some(conflict_key.clone()),
                             record: None,
+                            sector_id: None,
                             created_at: now,
                             updated_at: now,
                             resolved: false,
@@ -291,7 +692,9 @@ pub async fn run_conflict_loop(
 
                     // === AUTO-REROUTE COMMAND DISPATCH ===
                     // Issue REROUTE to the lower-priority drone (higher ID = newer = gives way)
-                    if matches!(conflict.severity, ConflictSeverity::Critical | ConflictSeverity::Warning) {
+                    if matches!(conflict.severity, ConflictSeverity::Critical | ConflictSeverity::Warning)
+                        && !clustered_drones.contains(&conflict.drone1_id)
+                    {
                         let now = Utc::now();
 
                         let drone1_external = drone1.is_none() && external1.is_some();
@@ -342,21 +745,41 @@ pub async fn run_conflict_loop(
                                     let priority_alt = external
                                         .map(|traffic| traffic.altitude_m)
                                         .unwrap_or(conflict.cpa_altitude_m);
-                                    let avoidance_type = select_avoidance_type(
+                                    let performance = state.drone_performance(&give_way_id);
+                                    let avoidance_type = avoidance_type_for(
+                                        conflict,
                                         gw.altitude_m,
                                         priority_alt,
-                                        gw.altitude_m > 100.0,
+                                        &state.rules(),
+                                        performance.as_ref(),
                                     );
-
                                     let conflict_geofence = build_conflict_geofence(conflict);
-                                    let planned = plan_airborne_route(
-                                        state.as_ref(),
-                                        &config,
-                                        &[current_pos.clone(), destination.clone()],
-                                        config.compliance_default_clearance_m,
-                                        std::slice::from_ref(&conflict_geofence),
-                                    )
-                                    .await;
+                                    // A drone flying a plan gets the rest of its route replanned around the
+                                    // conflict instead of a short detour.
+                                    let replan = if config.inflight_replan_enabled {
+                                        replan_remaining_route(
+                                            state.as_ref(),
+                                            gw,
+                                            std::slice::from_ref(&conflict_geofence),
+                                        )
+                                        .await
+                                    } else {
+                                        None
+                                    };
+                                    let planned = match &replan {
+                                        Some(replan) => Some(replan.waypoints.clone()),
+                                        None => {
+                                            plan_airborne_route(
+                                                state.as_ref(),
+                                                &config,
+                                                &[current_pos.clone(), destination.clone()],
+                                                config.compliance_default_clearance_m,
+                                                std::slice::from_ref(&conflict_geofence),
+                                                performance.as_ref(),
+                                            )
+                                            .await
+                                        }
+                                    };
                                     let avoidance_waypoints = planned.unwrap_or_else(|| {
                                         generate_avoidance_route(
                                             &current_pos,
@@ -366,33 +789,54 @@ pub async fn run_conflict_loop(
                                         )
                                     });
 
-                                    let cmd = Command {
-                                        command_id: format!("REROUTE-{}-{}", give_way_id, now.timestamp()),
-                                        drone_id: give_way_id.clone(),
-                                        command_type: CommandType::Reroute {
+                                    let Some(command_type) = executable_command(
+                                        CommandType::Reroute {
                                             waypoints: avoidance_waypoints,
-                                            reason: Some("Conflict avoidance (external traffic)".to_string()),
+                                            reason: Some(if conflict.crewed_traffic {
+                                                "Conflict avoidance (crewed traffic)".to_string()
+                                            } else {
+                                                "Conflict avoidance (external traffic)".to_string()
+                                            }),
                                         },
+                                        gw,
+                                        performance.as_ref(),
+                                        wind_mps,
+                                    ) else {
+                                        continue;
+                                    };
+                                    let kind = command_kind(&command_type);
+                                    let cmd = Command {
+                                        command_id: format!("{}-{}-{}", kind, give_way_id, now.timestamp()),
+                                        drone_id: give_way_id.clone(),
+                                        command_type,
                                         issued_at: now,
                                         expires_at: Some(now + ChronoDuration::seconds(60)),
                                         acknowledged: false,
                                     };
-                                    if let Err(err) = state.enqueue_command(cmd).await {
-                                        tracing::warn!(
-                                            "Failed to enqueue REROUTE for {}: {}",
-                                            give_way_id,
-                                            err
-                                        );
-                                    } else {
-                                        state.mark_command_issued(&give_way_id);
-                                        resolution_cooldowns.insert(
-                                            conflict_key.clone(),
-                                            now.timestamp() + RESOLUTION_COOLDOWN_SECS,
-                                        );
-                                        tracing::info!(
-                                            "Auto-issued REROUTE to {} due to external traffic",
-                                            give_way_id
-                                        );
+                                    match state.enqueue_auto_command(cmd, conflict_cause.clone()).await {
+                                        Err(err) => {
+                                            tracing::warn!(
+                                                "Failed to enqueue {} for {}: {}",
+                                                kind,
+                                                give_way_id,
+                                                err
+                                            );
+                                        }
+                                        Ok(sent) => {
+                                            state.mark_command_issued(&give_way_id);
+                                            if let Some(replan) = replan.as_ref().filter(|_| sent && kind == "REROUTE") {
+                                                record_replan(state.as_ref(), replan).await;
+                                            }
+                                            resolution_cooldowns.insert(
+                                                conflict_key.clone(),
+                                                now.timestamp() + RESOLUTION_COOLDOWN_SECS,
+                                            );
+                                            tracing::info!(
+                                                "Auto-issued {} to {} due to external traffic",
+                                                kind,
+                                                give_way_id
+                                            );
+                                        }
                                     }
                                 }
                             }
@@ -400,11 +844,12 @@ pub async fn run_conflict_loop(
                         }
 
                         // Determine which drone should give way
-                        let (give_way_id, priority_drone) = if conflict.drone1_id < conflict.drone2_id {
-                            (&conflict.drone2_id, drone1)
-                        } else {
-                            (&conflict.drone1_id, drone2)
-                        };
+                        let give_way_id = give_way_drone_id(
+                            conflict,
+                            drone1.and_then(|drone| drone.scheduling_priority),
+                            drone2.and_then(|drone| drone.scheduling_priority),
+                        );
+                        let priority_drone = if give_way_id == &conflict.drone1_id { drone2 } else { drone1 };
 
                         let give_way_drone = if give_way_id == &conflict.drone1_id { drone1 } else { drone2 };
 
@@ -452,6 +897,54 @@ pub async fn run_conflict_loop(
                                         continue;
                                     }
                                 }
+                                // Prefer the cheapest maneuver that restores separation; fall back to
+                                // a full avoidance reroute when none does.
+                                let performance = state.drone_performance(give_way_id);
+                                if let Some(option) = select_resolution(
+                                    conflict,
+                                    gw,
+                                    pri,
+                                    &state.rules(),
+                                    performance.as_ref(),
+                                    wind_mps,
+                                ) {
+                                    let reason = format!(
+                                        "Conflict resolution ({}) with {}",
+                                        option.maneuver.label(),
+                                        priority_id
+                                    );
+                                    let cmd = Command {
+                                        command_id: format!("RESOLVE-{}-{}", give_way_id, now.timestamp()),
+                                        drone_id: give_way_id.clone(),
+                                        command_type: resolution_command(&option, gw, reason),
+                                        issued_at: now,
+                                        expires_at: Some(now + ChronoDuration::seconds(60)),
+                                        acknowledged: false,
+                                    };
+                                    if let Err(err) = state.enqueue_auto_command(cmd, conflict_cause.clone()).await {
+                                        tracing::warn!(
+                                            "Failed to enqueue resolution for {}: {}",
+                                            give_way_id,
+                                            err
+                                        );
+                                    } else {
+                                        state.mark_command_issued(give_way_id);
+                                        resolution_cooldowns.insert(
+                                            conflict_key.clone(),
+                                            now.timestamp() + RESOLUTION_COOLDOWN_SECS,
+                                        );
+                                        tracing::info!(
+                                            "Auto-issued {} to {} (gives way to {}; predicted {:.0}m horizontal / {:.0}m vertical)",
+                                            option.maneuver.label(),
+                                            give_way_id,
+                                            priority_id,
+                                            option.predicted_horizontal_m,
+                                            option.predicted_vertical_m
+                                        );
+                                    }
+                                    continue;
+                                }
+
                                 // Create conflict point waypoint using predicted CPA (not current midpoint)
                                 let conflict_point = Waypoint {
                                     lat: conflict.cpa_lat,
@@ -484,23 +977,43 @@ pub async fn run_conflict_loop(
                                     speed_mps: Some(gw.speed_mps),
                                 };
 
-                                // Select avoidance type based on altitude context
-                                let avoidance_type = select_avoidance_type(
+                                // Select an avoidance type the drone can fly before the closest approach
+                                let avoidance_type = avoidance_type_for(
+                                    conflict,
                                     gw.altitude_m,
                                     pri.altitude_m,
-                                    gw.altitude_m > 100.0, // Basic ceiling check
+                                    &state.rules(),
+                                    performance.as_ref(),
                                 );
 
                                 // Generate avoidance route
                                 let conflict_geofence = build_conflict_geofence(conflict);
-                                let planned = plan_airborne_route(
-                                    state.as_ref(),
-                                    &config,
-                                    &[current_pos.clone(), destination.clone()],
-                                    config.compliance_default_clearance_m,
-                                    std::slice::from_ref(&conflict_geofence),
-                                )
-                                .await;
+                                // A drone flying a plan gets the rest of its route replanned around the
+                                // conflict instead of a short detour.
+                                let replan = if config.inflight_replan_enabled {
+                                    replan_remaining_route(
+                                        state.as_ref(),
+                                        gw,
+                                        std::slice::from_ref(&conflict_geofence),
+                                    )
+                                    .await
+                                } else {
+                                    None
+                                };
+                                let planned = match &replan {
+                                    Some(replan) => Some(replan.waypoints.clone()),
+                                    None => {
+                                        plan_airborne_route(
+                                            state.as_ref(),
+                                            &config,
+                                            &[current_pos.clone(), destination.clone()],
+                                            config.compliance_default_clearance_m,
+                                            std::slice::from_ref(&conflict_geofence),
+                                            performance.as_ref(),
+                                        )
+                                        .await
+                                    }
+                                };
                                 let avoidance_waypoints = planned.unwrap_or_else(|| {
                                     generate_avoidance_route(
                                         &current_pos,
@@ -510,10 +1023,8 @@ pub async fn run_conflict_loop(
                                     )
                                 });
 
-                                let cmd = Command {
-                                    command_id: format!("REROUTE-{}-{}", give_way_id, now.timestamp()),
-                                    drone_id: give_way_id.clone(),
-                                    command_type: CommandType::Reroute {
+                                let Some(command_type) = executable_command(
+                                    CommandType::Reroute {
                                         waypoints: avoidance_waypoints,
                                         reason: Some(format!(
                                             "Conflict avoidance ({:?}) with {}",
@@ -525,31 +1036,58 @@ pub async fn run_conflict_loop(
                                             }
                                         )),
                                     },
+                                    gw,
+                                    performance.as_ref(),
+                                    wind_mps,
+                                ) else {
+                                    continue;
+                                };
+                                let kind = command_kind(&command_type);
+                                let cmd = Command {
+                                    command_id: format!("{}-{}-{}", kind, give_way_id, now.timestamp()),
+                                    drone_id: give_way_id.clone(),
+                                    command_type,
                                     issued_at: now,
                                     expires_at: Some(now + ChronoDuration::seconds(60)),
                                     acknowledged: false,
                                 };
-                                if let Err(err) = state.enqueue_command(cmd).await {
+                                match state.enqueue_auto_command(cmd, conflict_cause.clone()).await {
+                                    Err(err) => {
+                                        tracing::warn!(
+                                            "Failed to enqueue {} for {}: {}",
+                                            kind,
+                                            give_way_id,
+                                            err
+                                        );
+                                    }
+                                    Ok(sent) => {
+                                        state.mark_command_issued(give_way_id);
+                                        if let Some(replan) = replan.as_ref().filter(|_| sent && kind == "REROUTE") {
+                                            record_replan(state.as_ref(), replan).await;
+                                        }
+                                        resolution_cooldowns.insert(
+                                            conflict_key.clone(),
+                                            now.timestamp() + RESOLUTION_COOLDOWN_SECS,
+                                        );
+                                        tracing::info!(
+                                            "Auto-issued {} ({:?}) to {} (gives way to {})",
+                                            kind,
+                                            avoidance_type,
+                                            give_way_id,
+                                            if give_way_id == &conflict.drone1_id { &conflict.drone2_id } else { &conflict.drone1_id }
+                                        );
+                                    }
+                                }
+                            } else {
+                                // Fallback: issue HOLD if we can't compute reroute
+                                if !state.drone_can_hold(give_way_id, wind_mps) {
                                     tracing::warn!(
-                                        "Failed to enqueue REROUTE for {}: {}",
+                                        "Fallback HOLD skipped: {} cannot hold in {:.1} m/s wind",
                                         give_way_id,
-                                        err
-                                    );
-                                } else {
-                                    state.mark_command_issued(give_way_id);
-                                    resolution_cooldowns.insert(
-                                        conflict_key.clone(),
-                                        now.timestamp() + RESOLUTION_COOLDOWN_SECS,
-                                    );
-                                    tracing::info!(
-                                        "Auto-issued REROUTE ({:?}) to {} (gives way to {})",
-                                        avoidance_type,
-                                        give_way_id,
-                                        if give_way_id == &conflict.drone1_id { &conflict.drone2_id } else { &conflict.drone1_id }
+                                        wind_mps
                                     );
+                                    continue;
                                 }
-                            } else {
-                                // Fallback: issue HOLD if we can't compute reroute
                                 let cmd = Command {
                                     command_id: format!("HOLD-{}-{}", give_way_id, now.timestamp()),
                                     drone_id: give_way_id.clone(),
@@ -558,7 +1096,7 @@ pub async fn run_conflict_loop(
                                     expires_at: Some(now + ChronoDuration::seconds(30)),
                                     acknowledged: false,
                                 };
-                                if let Err(err) = state.enqueue_command(cmd).await {
+                                if let Err(err) = state.enqueue_auto_command(cmd, conflict_cause.clone()).await {
                                     tracing::warn!(
                                         "Failed to enqueue fallback HOLD for {}: {}",
                                         give_way_id,
@@ -685,6 +1223,110 @@ pub async fn run_conflict_loop(
     }
 }
 
+/// Issue the coordinated altitude layering for a multi-aircraft cluster.
+async fn resolve_cluster(
+    state: &AppState,
+    cluster: &ConflictCluster,
+    drones: &[DroneState],
+    external_by_id: &HashMap<String, ExternalTraffic>,
+    resolution_cooldowns: &mut HashMap<String, i64>,
+    wind_mps: f64,
+) {
+    let now = Utc::now();
+    if resolution_cooldowns
+        .get(&cluster.cluster_id)
+        .is_some_and(|expires_at| *expires_at > now.timestamp())
+    {
+        return;
+    }
+
+    let participants: Vec<ClusterParticipant> = cluster
+        .drone_ids
+        .iter()
+        .filter_map(|id| {
+            drones
+                .iter()
+                .find(|drone| &drone.drone_id == id)
+                .map(|drone| ClusterParticipant {
+                    drone_id: id.clone(),
+                    altitude_m: drone.altitude_m,
+                    commandable: true,
+                    priority: drone.scheduling_priority,
+                })
+                .or_else(|| {
+                    external_by_id.get(id).map(|track| ClusterParticipant {
+                        drone_id: id.clone(),
+                        altitude_m: track.altitude_m,
+                        commandable: false,
+                        priority: None,
+                    })
+                })
+        })
+        .collect();
+
+    let mut issued = 0usize;
+    for (drone_id, target) in plan_cluster_resolution(&participants, &state.rules()) {
+        if !state.can_issue_command(&drone_id, COMMAND_COOLDOWN_SECS) {
+            continue;
+        }
+        if target.is_none() && !state.drone_can_hold(&drone_id, wind_mps) {
+            tracing::warn!(
+                "Cluster HOLD skipped: {} cannot hold in {:.1} m/s wind",
+                drone_id,
+                wind_mps
+            );
+            continue;
+        }
+        let (command_id, command_type, expires_in_secs) = match target {
+            Some(target_altitude_m) => (
+                format!("CLUSTER-ALT-{}-{}", drone_id, now.timestamp()),
+                CommandType::AltitudeChange { target_altitude_m },
+                60,
+            ),
+            None => (
+                format!("CLUSTER-HOLD-{}-{}", drone_id, now.timestamp()),
+                CommandType::Hold {
+                    duration_secs: CLUSTER_HOLD_SECS,
+                },
+                30,
+            ),
+        };
+        let cmd = Command {
+            command_id,
+            drone_id: drone_id.clone(),
+            command_type,
+            issued_at: now,
+            expires_at: Some(now + ChronoDuration::seconds(expires_in_secs)),
+            acknowledged: false,
+        };
+        let cause = CommandCause::new(CommandCauseKind::Conflict).with_source(&cluster.cluster_id);
+        if let Err(err) = state.enqueue_auto_command(cmd, cause).await {
+            tracing::warn!(
+                "Failed to enqueue cluster resolution for {}: {}",
+                drone_id,
+                err
+            );
+            continue;
+        }
+        state.mark_command_issued(&drone_id);
+        issued += 1;
+    }
+
+    if issued > 0 {
+        resolution_cooldowns.insert(
+            cluster.cluster_id.clone(),
+            now.timestamp() + RESOLUTION_COOLDOWN_SECS,
+        );
+        tracing::info!(
+            "Coordinated resolution for {} ({} drones, {} conflicts): {} commands issued",
+            cluster.cluster_id,
+            cluster.drone_ids.len(),
+            cluster.conflicts.len(),
+            issued
+        );
+    }
+}
+
 fn resolve_inactive_conflict_advisories(state: &AppState, active_ids: &HashSet<String>) {
     for advisory in state.get_daa_advisories() {
         if advisory.source != "conflict" {
@@ -699,6 +1341,16 @@ fn resolve_inactive_conflict_advisories(state: &AppState, active_ids: &HashSet<S
     }
 }
 
+/// Advisory text for one side of a conflict with `other_drone_id`.
+fn conflict_message(conflict: &Conflict, other_drone_id: &str) -> Message {
+    Message::new(codes::ADVISORY_CONFLICT)
+        .with("other_drone_id", other_drone_id)
+        .with("distance_m", conflict.distance_m)
+        .with("cpa_horizontal_m", conflict.cpa_horizontal_m)
+        .with("cpa_vertical_m", conflict.cpa_vertical_m)
+        .with("time_to_closest_s", conflict.time_to_closest)
+}
+
 fn build_conflict_geofence(conflict: &Conflict) -> Geofence {
     use atc_core::spatial::offset_by_bearing;
 
@@ -725,5 +1377,8 @@ fn build_conflict_geofence(conflict: &Conflict) -> Geofence {
         upper_altitude_m: conflict.cpa_altitude_m + 50.0,
         active: true,
         created_at: Utc::now(),
+        breach_response: None,
+        schedule: None,
+        org_id: None,
     }
 }
@@ -1,7 +1,8 @@
 //! Periodic conformance monitoring loop.
 //!
-//! Polls Flight Blender for conformance status and issues HOLD commands
-//! for non-conforming drones.
+//! Polls Flight Blender for conformance status and escalates the response to non-conforming
+//! drones under the conformance policy. Reported geofence breaches follow the breach response
+//! policy.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -12,19 +13,25 @@ use tokio::sync::broadcast;
 use tokio::time::interval;
 
 use atc_blender::BlenderClient;
+use atc_core::messages::{codes, Message};
 use atc_core::models::{
-    Command, CommandType, ConformanceRecord, ConformanceStatus, DaaAdvisory, DaaSeverity,
-    DroneState, Geofence, Waypoint,
+    Command, CommandType, ConformanceAction, ConformanceRecord, ConformanceStatus, DaaAdvisory,
+    DaaSeverity, DroneState, Geofence, Waypoint,
 };
 
 use crate::backoff::Backoff;
 use crate::blender_auth::BlenderAuthManager;
+use crate::breach::{self, BreachKind};
 use crate::config::Config;
+use crate::conformance_policy::ConformanceSeverity;
+use crate::persistence::command_audit::{CommandCause, CommandCauseKind};
+use crate::replan;
 use crate::state::AppState;
+use crate::webhooks::{self, WebhookEventType};
 
 const CONFORMANCE_POLL_SECS: u64 = 10;
-const CONFORMANCE_COMMAND_COOLDOWN_SECS: u64 = 120;
-const CONFORMANCE_HOLD_SECS: u32 = 60;
+pub(crate) const CONFORMANCE_COMMAND_COOLDOWN_SECS: u64 = 120;
+pub(crate) const CONFORMANCE_HOLD_SECS: u32 = 60;
 const GEOFENCE_EXIT_BUFFER_M: f64 = 50.0;
 
 /// Start the conformance monitoring loop.
@@ -56,6 +63,9 @@ pub async fn run_conformance_loop(
             }
             _ = ticker.tick() => {
                 state.mark_loop_heartbeat("conformance");
+                if state.loop_paused("conformance") {
+                    continue;
+                }
                 if !backoff.ready() {
                     continue;
                 }
@@ -87,13 +97,24 @@ pub async fn run_conformance_loop(
                     };
 
                     any_success = true;
-                    let status = ConformanceStatus {
+                    let earlier = state.get_conformance_status(&drone.drone_id);
+                    let mut status = ConformanceStatus {
                         drone_id: drone.drone_id.clone(),
                         owner_id: drone.owner_id.clone(),
                         status: payload.status.clone(),
                         last_checked: Utc::now(),
                         record: payload.record.clone(),
+                        action: None,
+                        nonconforming_since: None,
                     };
+                    if status.status == "nonconforming" {
+                        status.nonconforming_since = Some(
+                            earlier
+                                .as_ref()
+                                .and_then(|earlier| earlier.nonconforming_since)
+                                .unwrap_or(status.last_checked),
+                        );
+                    }
 
                     state.set_conformance_status(status.clone());
 
@@ -101,92 +122,28 @@ pub async fn run_conformance_loop(
                     let record = status.record.as_ref();
                     let advisory_id = format!("conformance-{}", drone.drone_id);
 
-                    if status.status == "nonconforming" && requires_hold(record) {
-                        let now = Utc::now();
-                        let geofence_exit = record
-                            .filter(|entry| entry.geofence_breach)
-                            .and_then(|entry| entry.geofence_id.as_deref())
-                            .and_then(|geofence_id| state.get_geofence(geofence_id))
-                            .and_then(|geofence| compute_geofence_exit(&drone, &geofence));
-                        let description = record
-                            .map(|entry| entry.description.clone())
-                            .unwrap_or_else(|| "Conformance issue detected".to_string());
-                        state.set_daa_advisory(DaaAdvisory {
-                            advisory_id,
-                            drone_id: drone.drone_id.clone(),
-                            owner_id: drone.owner_id.clone(),
-                            source: "conformance".to_string(),
-                            severity: DaaSeverity::Critical,
-                            action: if geofence_exit.is_some() { "reroute" } else { "hold" }.to_string(),
-                            description,
-                            related_id: record.and_then(|entry| entry.geofence_id.clone()),
-                            record: status.record.clone(),
-                            created_at: now,
-                            updated_at: now,
-                            resolved: false,
-                        });
-
-                        if !state.has_pending_command(&drone.drone_id)
-                            && state.can_issue_command(&drone.drone_id, CONFORMANCE_COMMAND_COOLDOWN_SECS)
-                        {
-                            let now = Utc::now();
-                            let cmd = if let Some(exit_waypoint) = geofence_exit {
-                                Command {
-                                    command_id: format!("CONFORMANCE-EXIT-{}-{}", drone.drone_id, now.timestamp()),
-                                    drone_id: drone.drone_id.clone(),
-                                    command_type: CommandType::Reroute {
-                                        waypoints: vec![exit_waypoint],
-                                        reason: Some("Geofence breach: exit route".to_string()),
-                                    },
-                                    issued_at: now,
-                                    expires_at: Some(now + ChronoDuration::seconds(CONFORMANCE_HOLD_SECS as i64)),
-                                    acknowledged: false,
-                                }
-                            } else {
-                                Command {
-                                    command_id: format!("CONFORMANCE-HOLD-{}-{}", drone.drone_id, now.timestamp()),
-                                    drone_id: drone.drone_id.clone(),
-                                    command_type: CommandType::Hold {
-                                        duration_secs: CONFORMANCE_HOLD_SECS,
-                                    },
-                                    issued_at: now,
-                                    expires_at: Some(now + ChronoDuration::seconds(CONFORMANCE_HOLD_SECS as i64)),
-                                    acknowledged: false,
-                                }
-                            };
-                            if let Err(err) = state.enqueue_command(cmd).await {
-                                tracing::warn!(
-                                    "Failed to enqueue conformance recovery for {}: {}",
-                                    drone.drone_id,
-                                    err
-                                );
-                            } else {
-                                state.mark_command_issued(&drone.drone_id);
-                                tracing::warn!(
-                                    "Issued conformance recovery for {}",
-                                    drone.drone_id,
-                                );
-                            }
-                        }
+                    let breached_geofence = record
+                        .filter(|entry| entry.geofence_breach)
+                        .and_then(|entry| entry.geofence_id.as_deref())
+                        .and_then(|geofence_id| state.get_geofence(geofence_id));
+
+                    let needs_recovery = status.status == "nonconforming" && requires_hold(record);
+
+                    if let Some(geofence) = breached_geofence.as_ref().filter(|_| needs_recovery) {
+                        // Geofence breaches follow the breach response policy.
+                        breach::respond_to_breach(
+                            state.as_ref(),
+                            &drone,
+                            geofence,
+                            BreachKind::Inside,
+                            "conformance",
+                        )
+                        .await;
                     } else if status.status == "nonconforming" {
-                        let now = Utc::now();
-                        let description = record
-                            .map(|entry| entry.description.clone())
-                            .unwrap_or_else(|| "Conformance issue detected".to_string());
-                        state.set_daa_advisory(DaaAdvisory {
-                            advisory_id,
-                            drone_id: drone.drone_id.clone(),
-                            owner_id: drone.owner_id.clone(),
-                            source: "conformance".to_string(),
-                            severity: DaaSeverity::Warning,
-                            action: "monitor".to_string(),
-                            description,
-                            related_id: record.and_then(|entry| entry.geofence_id.clone()),
-                            record: status.record.clone(),
-                            created_at: now,
-                            updated_at: now,
-                            resolved: false,
-                        });
+                        let previous_action = earlier.and_then(|earlier| earlier.action);
+                        status.action =
+                            Some(apply_escalation(state.as_ref(), &drone, &status, previous_action).await);
+                        state.set_conformance_status(status.clone());
                     } else if status.status == "conforming" && previous.as_deref() == Some("nonconforming") {
                         state.resolve_daa_advisory(&advisory_id);
                         if !state.has_active_command(&drone.drone_id)
@@ -201,7 +158,7 @@ pub async fn run_conformance_loop(
                                 expires_at: Some(now + ChronoDuration::seconds(CONFORMANCE_HOLD_SECS as i64)),
                                 acknowledged: false,
                             };
-                            if let Err(err) = state.enqueue_command(cmd).await {
+                            if let Err(err) = state.enqueue_auto_command(cmd, conformance_cause(record)).await {
                                 tracing::warn!(
                                     "Failed to enqueue RESUME for {}: {}",
                                     drone.drone_id,
@@ -232,7 +189,156 @@ pub async fn run_conformance_loop(
     }
 }
 
-fn requires_hold(record: Option<&ConformanceRecord>) -> bool {
+/// Apply the escalation step a nonconforming drone has reached under the conformance policy:
+/// raise the advisory, then issue the step's command or notify the operator. Commands share the
+/// per-drone cooldown, except on moving to a new step. Returns the step applied.
+pub(crate) async fn apply_escalation(
+    state: &AppState,
+    drone: &DroneState,
+    status: &ConformanceStatus,
+    previous_action: Option<ConformanceAction>,
+) -> ConformanceAction {
+    let now = Utc::now();
+    let record = status.record.as_ref();
+    let severity = ConformanceSeverity::of(record);
+    let elapsed_s = status
+        .nonconforming_since
+        .map(|since| (status.last_checked - since).num_seconds().max(0) as u64)
+        .unwrap_or(0);
+    let action = state
+        .conformance_policy()
+        .ladder_for(drone.owner_id.as_deref())
+        .action_for(severity, elapsed_s);
+    let escalated = previous_action != Some(action);
+
+    let (description, message_code) = conformance_description(state.config(), record);
+    state.set_daa_advisory(DaaAdvisory {
+        advisory_id: format!("conformance-{}", drone.drone_id),
+        drone_id: drone.drone_id.clone(),
+        owner_id: drone.owner_id.clone(),
+        source: "conformance".to_string(),
+        severity: match severity {
+            ConformanceSeverity::Major => DaaSeverity::Critical,
+            ConformanceSeverity::Minor => DaaSeverity::Warning,
+        },
+        action: action_label(action).to_string(),
+        description,
+        message_code,
+        related_id: record.and_then(|entry| entry.geofence_id.clone()),
+        record: status.record.clone(),
+        sector_id: None,
+        created_at: now,
+        updated_at: now,
+        resolved: false,
+    });
+
+    let hold = CommandType::Hold {
+        duration_secs: CONFORMANCE_HOLD_SECS,
+    };
+    let command_type = match action {
+        ConformanceAction::Warn => None,
+        ConformanceAction::Notify => {
+            if escalated {
+                tracing::warn!(
+                    "Conformance escalated to operator for {} after {}s",
+                    drone.drone_id,
+                    elapsed_s
+                );
+                webhooks::publish(
+                    state,
+                    WebhookEventType::ConformanceEscalated,
+                    &[&drone.drone_id],
+                    ConformanceStatus {
+                        action: Some(action),
+                        ..status.clone()
+                    },
+                );
+            }
+            None
+        }
+        ConformanceAction::Hold => Some(hold),
+        // Fly back onto the active plan; hold when there is no plan to return to.
+        ConformanceAction::Reroute => Some(
+            replan::active_plan(state, &drone.drone_id)
+                .map(|plan| replan::remaining_route(&plan.waypoints, drone))
+                .filter(|route| route.len() > 1)
+                .map(|route| CommandType::Reroute {
+                    waypoints: route[1..].to_vec(),
+                    reason: Some("Conformance: rejoin flight plan".to_string()),
+                })
+                .unwrap_or(hold),
+        ),
+    };
+    let Some(command_type) = command_type else {
+        return action;
+    };
+    if state.has_pending_command(&drone.drone_id)
+        || !(escalated
+            || state.can_issue_command(&drone.drone_id, CONFORMANCE_COMMAND_COOLDOWN_SECS))
+    {
+        return action;
+    }
+
+    let cmd = Command {
+        command_id: format!(
+            "CONFORMANCE-{}-{}-{}",
+            action_label(action).to_ascii_uppercase(),
+            drone.drone_id,
+            now.timestamp()
+        ),
+        drone_id: drone.drone_id.clone(),
+        command_type,
+        issued_at: now,
+        expires_at: Some(now + ChronoDuration::seconds(CONFORMANCE_HOLD_SECS as i64)),
+        acknowledged: false,
+    };
+    if let Err(err) = state
+        .enqueue_auto_command(cmd, conformance_cause(record))
+        .await
+    {
+        tracing::warn!(
+            "Failed to enqueue conformance {} for {}: {}",
+            action_label(action),
+            drone.drone_id,
+            err
+        );
+    } else {
+        state.mark_command_issued(&drone.drone_id);
+        tracing::warn!(
+            "Issued conformance {} for {}",
+            action_label(action),
+            drone.drone_id
+        );
+    }
+    action
+}
+
+/// Advisory action label for an escalation step.
+fn action_label(action: ConformanceAction) -> &'static str {
+    match action {
+        ConformanceAction::Warn => "monitor",
+        ConformanceAction::Hold => "hold",
+        ConformanceAction::Reroute => "reroute",
+        ConformanceAction::Notify => "notify",
+    }
+}
+
+/// Advisory text for a nonconforming drone: the backend's own description when it sent one,
+/// otherwise a generic message with a code.
+fn conformance_description(
+    config: &Config,
+    record: Option<&ConformanceRecord>,
+) -> (String, Option<Message>) {
+    match record {
+        Some(entry) => (entry.description.clone(), None),
+        None => {
+            let message = Message::new(codes::ADVISORY_CONFORMANCE);
+            (config.messages.format(&message, None), Some(message))
+        }
+    }
+}
+
+pub(crate) fn requires_hold(record: Option<&ConformanceRecord>) -> bool {
     let Some(record) = record else {
         return true;
     };
@@ -253,7 +359,7 @@ fn requires_hold(record: Option<&ConformanceRecord>) -> bool {
     }
 }
 
-fn compute_geofence_exit(drone: &DroneState, geofence: &Geofence) -> Option<Waypoint> {
+pub(crate) fn compute_geofence_exit(drone: &DroneState, geofence: &Geofence) -> Option<Waypoint> {
     if geofence.polygon.len() < 2 {
         return None;
     }
@@ -327,3 +433,12 @@ fn closest_point_on_segment(px: f64, py: f64, x1: f64, y1: f64, x2: f64, y2: f64
     let t = t.clamp(0.0, 1.0);
     (x1 + t * dx, y1 + t * dy)
 }
+
+/// Audit cause for a conformance command, naming the flight declaration when known.
+fn conformance_cause(record: Option<&ConformanceRecord>) -> CommandCause {
+    let cause = CommandCause::new(CommandCauseKind::Conformance);
+    match record {
+        Some(record) => cause.with_source(&record.flight_declaration_id),
+        None => cause,
+    }
+}
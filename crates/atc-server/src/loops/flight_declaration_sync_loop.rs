@@ -1,12 +1,14 @@
 //! Flight declaration sync loop.
 //!
-//! Imports Flight Blender declarations into ATC flight plans for visibility.
+//! Imports Flight Blender declarations into ATC flight plans for visibility, keeps linked
+//! plans in step with their declaration state, and (optionally) declares approved plans
+//! that do not reference a declaration yet.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
-use atc_blender::BlenderClient;
+use atc_blender::{flight_declaration_payload, BlenderClient};
 use atc_core::models::{FlightPlan, FlightPlanMetadata, FlightStatus, Waypoint};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
@@ -19,6 +21,8 @@ use crate::config::Config;
 use crate::state::AppState;
 
 const LOOP_INTERVAL_SECS: u64 = 30;
+/// Originating party reported on declarations created by this server.
+const ORIGINATING_PARTY: &str = "ATC Drone";
 
 #[derive(Debug, Clone, serde::Deserialize)]
 struct AtcPlanEmbed {
@@ -69,6 +73,9 @@ pub async fn run_flight_declaration_sync_loop(
             }
             _ = ticker.tick() => {
                 state.mark_loop_heartbeat("flight-declaration-sync");
+                if state.loop_paused("flight-declaration-sync") {
+                    continue;
+                }
                 if !backoff.ready() {
                     continue;
                 }
@@ -81,7 +88,13 @@ pub async fn run_flight_declaration_sync_loop(
                     );
                     continue;
                 }
-                if let Err(err) = sync_flight_declarations(state.as_ref(), &blender).await {
+                if let Err(err) = sync_flight_declarations(
+                    state.as_ref(),
+                    &blender,
+                    config.blender_auto_declare,
+                )
+                .await
+                {
                     let delay = backoff.fail();
                     tracing::warn!(
                         "Flight declaration sync failed: {} (backing off {:?})",
@@ -96,22 +109,25 @@ pub async fn run_flight_declaration_sync_loop(
     }
 }
 
-async fn sync_flight_declarations(state: &AppState, blender: &BlenderClient) -> anyhow::Result<()> {
+async fn sync_flight_declarations(
+    state: &AppState,
+    blender: &BlenderClient,
+    auto_declare: bool,
+) -> anyhow::Result<()> {
     let declarations = blender.fetch_flight_declarations().await?;
-    if declarations.is_empty() {
-        return Ok(());
-    }
 
     let existing_plans = state.get_flight_plans();
     let mut known_declarations = HashSet::new();
+    let mut linked_plans: HashMap<String, FlightPlan> = HashMap::new();
     let mut known_flight_ids = HashSet::new();
-    for plan in existing_plans {
+    for plan in &existing_plans {
         if let Some(declaration_id) = plan
             .metadata
             .as_ref()
             .and_then(|meta| meta.blender_declaration_id.as_ref())
         {
             known_declarations.insert(declaration_id.clone());
+            linked_plans.insert(declaration_id.clone(), plan.clone());
         }
         known_flight_ids.insert(plan.flight_id.clone());
     }
@@ -121,6 +137,10 @@ async fn sync_flight_declarations(state: &AppState, blender: &BlenderClient) ->
             Some(id) => id,
             None => continue,
         };
+        if let Some(plan) = linked_plans.get(&declaration_id) {
+            sync_declaration_state(state, plan, &declaration).await?;
+            continue;
+        }
         if known_declarations.contains(&declaration_id) {
             continue;
         }
@@ -138,10 +158,125 @@ async fn sync_flight_declarations(state: &AppState, blender: &BlenderClient) ->
         state.add_flight_plan(plan).await?;
     }
 
+    if auto_declare {
+        declare_pending_plans(state, blender, &existing_plans).await;
+    }
+
     Ok(())
 }
 
-fn extract_declaration_id(declaration: &Value) -> Option<String> {
+/// Create a Blender declaration for an approved plan and persist the returned ID.
+pub(crate) async fn declare_flight_plan(
+    state: &AppState,
+    blender: &BlenderClient,
+    plan: &FlightPlan,
+) -> anyhow::Result<FlightPlan> {
+    let payload = flight_declaration_payload(plan, ORIGINATING_PARTY)?;
+    let declaration_id = blender.create_flight_declaration(&payload).await?;
+
+    // Re-read the plan so status changes made while the request was in flight are kept.
+    let mut updated = state
+        .flight_plans
+        .get(&plan.flight_id)
+        .map(|entry| entry.clone())
+        .unwrap_or_else(|| plan.clone());
+    updated
+        .metadata
+        .get_or_insert_with(Default::default)
+        .blender_declaration_id = Some(declaration_id.clone());
+    state.add_flight_plan(updated.clone()).await?;
+    tracing::info!(
+        "Created Blender declaration {} for flight {}",
+        declaration_id,
+        plan.flight_id
+    );
+    Ok(updated)
+}
+
+/// Retry declarations for approved plans that still lack one (e.g. Blender was down at approval).
+async fn declare_pending_plans(state: &AppState, blender: &BlenderClient, plans: &[FlightPlan]) {
+    let now = Utc::now();
+    for plan in plans {
+        if plan.status != FlightStatus::Approved {
+            continue;
+        }
+        if plan.arrival_time.is_some_and(|arrival| arrival < now) {
+            continue;
+        }
+        let declared = plan
+            .metadata
+            .as_ref()
+            .and_then(|meta| meta.blender_declaration_id.as_deref())
+            .is_some_and(|id| !id.trim().is_empty());
+        if declared {
+            continue;
+        }
+        if let Err(err) = declare_flight_plan(state, blender, plan).await {
+            tracing::warn!(
+                "Failed to create Blender declaration for flight {}: {}",
+                plan.flight_id,
+                err
+            );
+        }
+    }
+}
+
+async fn sync_declaration_state(
+    state: &AppState,
+    plan: &FlightPlan,
+    declaration: &Value,
+) -> anyhow::Result<()> {
+    let Some(blender_state) = declaration
+        .get("state")
+        .and_then(|v| v.as_i64())
+        .and_then(|v| u8::try_from(v).ok())
+    else {
+        return Ok(());
+    };
+    let last_seen = plan
+        .metadata
+        .as_ref()
+        .and_then(|meta| meta.blender_declaration_state);
+    if last_seen == Some(blender_state) {
+        return Ok(());
+    }
+
+    let mut updated = plan.clone();
+    if let Some(status) = declaration_state_transition(plan.status, blender_state) {
+        tracing::info!(
+            "Flight {} status {:?} -> {:?} (Blender declaration state {})",
+            plan.flight_id,
+            plan.status,
+            status,
+            blender_state
+        );
+        updated.status = status;
+    }
+    updated
+        .metadata
+        .get_or_insert_with(Default::default)
+        .blender_declaration_state = Some(blender_state);
+    state.add_flight_plan(updated).await
+}
+
+/// Map a Blender declaration state onto a linked plan. Only moves plans forward
+/// (approved -> active -> ended); terminal plans are left alone.
+fn declaration_state_transition(current: FlightStatus, blender_state: u8) -> Option<FlightStatus> {
+    let target = match blender_state {
+        2..=4 => FlightStatus::Active,
+        5 => FlightStatus::Completed,
+        6 | 7 => FlightStatus::Cancelled,
+        8 => FlightStatus::Rejected,
+        _ => return None,
+    };
+    match current {
+        FlightStatus::Approved if target != current => Some(target),
+        FlightStatus::Active if target != FlightStatus::Active => Some(target),
+        _ => None,
+    }
+}
+
+pub(crate) fn extract_declaration_id(declaration: &Value) -> Option<String> {
     first_string(&[
         declaration.get("id"),
         declaration.get("flight_declaration_id"),
@@ -275,6 +410,18 @@ fn declaration_to_plan(declaration: &Value, declaration_id: &str) -> Option<Flig
     })
 }
 
+/// ID of the ATC plan embedded in a declaration, i.e. the plan it was declared for.
+pub(crate) fn declared_flight_id(declaration: &Value) -> Option<String> {
+    let geojson = extract_geojson(declaration)?;
+    let compliance = geojson
+        .get("features")?
+        .as_array()?
+        .first()?
+        .get("properties")?
+        .get("compliance")?;
+    extract_atc_plan_id(compliance)
+}
+
 fn extract_geojson(declaration: &Value) -> Option<Value> {
     let raw = declaration
         .get("flight_declaration_geojson")
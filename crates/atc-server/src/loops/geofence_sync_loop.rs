@@ -31,11 +31,11 @@ const GEOFENCE_TTL_HOURS: i64 = 6;
 const GEOFENCE_REFRESH_GRACE_SECS: i64 = 600;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct BlenderGeofenceState {
-    blender_id: String,
-    fingerprint: u64,
+pub(crate) struct BlenderGeofenceState {
+    pub blender_id: String,
+    pub fingerprint: u64,
     #[serde(default)]
-    expires_at: i64,
+    pub expires_at: i64,
 }
 
 /// Start the geofence sync loop.
@@ -58,28 +58,7 @@ pub async fn run_geofence_sync_loop(
     );
     let state_path = PathBuf::from(config.geofence_sync_state_path.clone());
     let db = state.database().cloned();
-    let mut tracked = match db.as_ref() {
-        Some(db) => match load_tracking_state_db(db).await {
-            Ok(existing) => existing,
-            Err(err) => {
-                tracing::warn!("Geofence sync DB state load failed: {}", err);
-                match load_tracking_state(&state_path).await {
-                    Ok(existing) => existing,
-                    Err(err) => {
-                        tracing::warn!("Geofence sync state file load failed: {}", err);
-                        HashMap::new()
-                    }
-                }
-            }
-        },
-        None => match load_tracking_state(&state_path).await {
-            Ok(existing) => existing,
-            Err(err) => {
-                tracing::warn!("Geofence sync state file load failed: {}", err);
-                HashMap::new()
-            }
-        },
-    };
+    let mut tracked = load_tracked(db.as_ref(), &state_path).await;
     state.mark_loop_heartbeat("geofence-sync");
 
     loop {
@@ -90,6 +69,9 @@ pub async fn run_geofence_sync_loop(
             }
             _ = ticker.tick() => {
                 state.mark_loop_heartbeat("geofence-sync");
+                if state.loop_paused("geofence-sync") {
+                    continue;
+                }
                 if !backoff.ready() {
                     continue;
                 }
@@ -208,14 +190,7 @@ pub async fn run_geofence_sync_loop(
                 }
 
                 if dirty {
-                    let persisted = if let Some(db) = db.as_ref() {
-                        persist_tracking_state_db(db, &tracked).await
-                    } else {
-                        persist_tracking_state(&state_path, &tracked).await
-                    };
-                    if let Err(err) = persisted {
-                        tracing::warn!("Failed to persist geofence sync state: {}", err);
-                    }
+                    persist_tracked(db.as_ref(), &state_path, &tracked).await;
                 }
                 }
 
@@ -276,13 +251,19 @@ async fn sync_external_geofences(
         if conflict_ids.contains(&parsed.blender_id) {
             continue;
         }
+        // Pushed by this server but not tracked (yet); startup reconciliation adopts or removes it.
+        if blender_feature_properties(&entry)
+            .is_some_and(|properties| properties.get("atc_geofence_id").is_some())
+        {
+            continue;
+        }
         if is_conflict_geofence(&parsed.geofence) {
             continue;
         }
         external_geofences.push(parsed.geofence);
     }
 
-    state.set_external_geofences(external_geofences);
+    state.set_external_geofences("blender", external_geofences);
     Ok(())
 }
 
@@ -355,10 +336,23 @@ fn parse_blender_geofence(
             upper_altitude_m: upper.max(lower),
             active,
             created_at,
+            breach_response: None,
+            schedule: None,
+            org_id: None,
         },
     })
 }
 
+/// Properties of the first feature of a Blender geofence's GeoJSON.
+pub(crate) fn blender_feature_properties(entry: &serde_json::Value) -> Option<serde_json::Value> {
+    extract_json(entry.get("raw_geo_fence"))?
+        .get("features")?
+        .as_array()?
+        .first()?
+        .get("properties")
+        .cloned()
+}
+
 fn extract_json(value: Option<&serde_json::Value>) -> Option<serde_json::Value> {
     let value = value?;
     if value.is_null() {
@@ -502,6 +496,8 @@ fn build_geofence_payload(
     let end_time = (start_time + ChronoDuration::hours(GEOFENCE_TTL_HOURS)).to_rfc3339();
     let start_time = start_time.to_rfc3339();
 
+    // The local ID and fingerprint let startup reconciliation recognise geofences pushed
+    // before a crash, even when their Blender IDs were never stored.
     json!({
         "type": "FeatureCollection",
         "features": [{
@@ -511,7 +507,9 @@ fn build_geofence_payload(
                 "upper_limit": geofence.upper_altitude_m.round() as i32,
                 "lower_limit": geofence.lower_altitude_m.round() as i32,
                 "start_time": start_time,
-                "end_time": end_time
+                "end_time": end_time,
+                "atc_geofence_id": geofence.id,
+                "atc_fingerprint": fingerprint_geofence(geofence)
             },
             "geometry": {
                 "type": "Polygon",
@@ -521,7 +519,7 @@ fn build_geofence_payload(
     })
 }
 
-fn fingerprint_geofence(geofence: &Geofence) -> u64 {
+pub(crate) fn fingerprint_geofence(geofence: &Geofence) -> u64 {
     let mut hasher = std::collections::hash_map::DefaultHasher::new();
     geofence.id.hash(&mut hasher);
     geofence.name.hash(&mut hasher);
@@ -538,6 +536,40 @@ fn fingerprint_geofence(geofence: &Geofence) -> u64 {
 
 type SyncResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+/// Load the local-to-Blender geofence tracking, from the database when there is one.
+pub(crate) async fn load_tracked(
+    db: Option<&Database>,
+    path: &Path,
+) -> HashMap<String, BlenderGeofenceState> {
+    if let Some(db) = db {
+        match load_tracking_state_db(db).await {
+            Ok(existing) => return existing,
+            Err(err) => tracing::warn!("Geofence sync DB state load failed: {}", err),
+        }
+    }
+    match load_tracking_state(path).await {
+        Ok(existing) => existing,
+        Err(err) => {
+            tracing::warn!("Geofence sync state file load failed: {}", err);
+            HashMap::new()
+        }
+    }
+}
+
+pub(crate) async fn persist_tracked(
+    db: Option<&Database>,
+    path: &Path,
+    tracked: &HashMap<String, BlenderGeofenceState>,
+) {
+    let persisted = match db {
+        Some(db) => persist_tracking_state_db(db, tracked).await,
+        None => persist_tracking_state(path, tracked).await,
+    };
+    if let Err(err) = persisted {
+        tracing::warn!("Failed to persist geofence sync state: {}", err);
+    }
+}
+
 async fn load_tracking_state(path: &Path) -> SyncResult<HashMap<String, BlenderGeofenceState>> {
     if !path.exists() {
         return Ok(HashMap::new());
@@ -0,0 +1,43 @@
+//! Background job worker loop.
+//!
+//! Starts queued jobs once they fall due, including retries and jobs recovered at startup, and
+//! prunes old succeeded jobs. Enqueuing a job starts it directly when a worker slot is free, so
+//! this loop only has to catch up. See [`crate::jobs`].
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+use crate::jobs;
+use crate::state::AppState;
+
+const LOOP_INTERVAL_SECS: u64 = 1;
+const PRUNE_INTERVAL_TICKS: u64 = 600;
+
+pub async fn run_job_loop(state: Arc<AppState>, mut shutdown: broadcast::Receiver<()>) {
+    let mut ticker = interval(Duration::from_secs(LOOP_INTERVAL_SECS));
+    let mut ticks = 0u64;
+    state.mark_loop_heartbeat("jobs");
+
+    loop {
+        tokio::select! {
+            _ = shutdown.recv() => {
+                tracing::info!("Job loop shutting down");
+                break;
+            }
+            _ = ticker.tick() => {
+                state.mark_loop_heartbeat("jobs");
+                if state.loop_paused("jobs") {
+                    continue;
+                }
+                jobs::dispatch(&state);
+                if ticks.is_multiple_of(PRUNE_INTERVAL_TICKS) {
+                    jobs::prune(&state).await;
+                }
+                ticks += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Usage metering flush loop.
+//!
+//! Periodically writes changed monthly usage records to the database, and once more on
+//! shutdown so in-flight counts are not lost.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+use crate::chaos::chaos;
+use crate::persistence::usage as usage_db;
+use crate::state::AppState;
+
+const LOOP_INTERVAL_SECS: u64 = 60;
+
+pub async fn run_metering_loop(state: Arc<AppState>, mut shutdown: broadcast::Receiver<()>) {
+    let mut ticker = interval(Duration::from_secs(LOOP_INTERVAL_SECS));
+    state.mark_loop_heartbeat("metering");
+
+    loop {
+        tokio::select! {
+            _ = shutdown.recv() => {
+                flush_usage(&state).await;
+                tracing::info!("Metering loop shutting down");
+                break;
+            }
+            _ = ticker.tick() => {
+                state.mark_loop_heartbeat("metering");
+                if state.loop_paused("metering") {
+                    continue;
+                }
+                flush_usage(&state).await;
+            }
+        }
+    }
+}
+
+async fn flush_usage(state: &AppState) {
+    let Some(db) = state.database() else {
+        return;
+    };
+    let records = state.usage_meter().take_dirty();
+    if records.is_empty() {
+        return;
+    }
+    let result = if chaos().fail_db_write() {
+        Err(anyhow!("injected usage write failure"))
+    } else {
+        usage_db::upsert_usage_records(db.pool(), &records).await
+    };
+    if let Err(err) = result {
+        tracing::warn!("Failed to persist {} usage records: {}", records.len(), err);
+        state.usage_meter().mark_dirty(&records);
+    }
+}
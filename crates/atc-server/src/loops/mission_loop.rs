@@ -3,13 +3,16 @@
 //! Activates approved flight plans and marks them complete based on telemetry.
 
 use chrono::Utc;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
 use tokio::time::interval;
 use uuid::Uuid;
 
+use crate::persistence::command_audit::{CommandCause, CommandCauseKind};
 use crate::state::AppState;
+use atc_core::dependencies::{dependency_status, DependencyStatus};
 use atc_core::haversine_distance;
 use atc_core::models::{Command, CommandType, DroneStatus, FlightStatus};
 
@@ -30,7 +33,17 @@ pub async fn run_mission_loop(state: Arc<AppState>, mut shutdown: broadcast::Rec
             }
             _ = ticker.tick() => {
                 state.mark_loop_heartbeat("mission");
+                if state.loop_paused("mission") {
+                    continue;
+                }
                 let now = Utc::now();
+                // Snapshot statuses up front: looking plans up while iterating them mutably
+                // would deadlock on a shared shard.
+                let statuses: HashMap<String, FlightStatus> = state
+                    .flight_plans
+                    .iter()
+                    .map(|entry| (entry.key().clone(), entry.value().status))
+                    .collect();
 
                 for mut entry in state.flight_plans.iter_mut() {
                     let plan = entry.value_mut();
@@ -45,6 +58,29 @@ pub async fn run_mission_loop(state: Arc<AppState>, mut shutdown: broadcast::Rec
                                 continue;
                             }
 
+                            let dependencies = plan
+                                .metadata
+                                .as_ref()
+                                .map(|meta| meta.depends_on.as_slice())
+                                .unwrap_or_default();
+                            match dependency_status(
+                                dependencies,
+                                |id| statuses.get(id).copied(),
+                                |id| state.get_geofence(id).is_some_and(|fence| fence.active),
+                            ) {
+                                DependencyStatus::Met => {}
+                                DependencyStatus::Waiting => continue,
+                                DependencyStatus::Broken { flight_id } => {
+                                    tracing::warn!(
+                                        "Cancelling flight {}: it depends on flight {}, which will not land",
+                                        plan.flight_id,
+                                        flight_id
+                                    );
+                                    plan.status = FlightStatus::Cancelled;
+                                    continue;
+                                }
+                            }
+
                             let drone = match state.get_drone(&plan.drone_id) {
                                 Some(drone) => drone,
                                 None => continue,
@@ -74,7 +110,7 @@ pub async fn run_mission_loop(state: Arc<AppState>, mut shutdown: broadcast::Rec
                                 acknowledged: false,
                             };
 
-                            if let Err(err) = state.enqueue_command(cmd).await {
+                            if let Err(err) = state.enqueue_command(cmd, CommandCause::new(CommandCauseKind::Mission).with_source(&plan.flight_id)).await {
                                 tracing::warn!(
                                     "Failed to enqueue mission command for {}: {}",
                                     plan.drone_id,
@@ -84,6 +120,7 @@ pub async fn run_mission_loop(state: Arc<AppState>, mut shutdown: broadcast::Rec
                             }
                             state.mark_command_issued(&plan.drone_id);
                             plan.status = FlightStatus::Active;
+                            state.notify_plan_status(plan);
                         }
                         FlightStatus::Active => {
                             let drone = match state.get_drone(&plan.drone_id) {
@@ -107,11 +144,13 @@ pub async fn run_mission_loop(state: Arc<AppState>, mut shutdown: broadcast::Rec
                             if distance <= ARRIVAL_DISTANCE_M && altitude_delta <= ARRIVAL_ALTITUDE_M {
                                 plan.status = FlightStatus::Completed;
                                 plan.arrival_time = Some(now);
+                                state.notify_plan_status(plan);
                             }
                         }
                         _ => {}
                     }
                 }
+                state.sync_drone_priorities();
             }
         }
     }
@@ -1,11 +1,44 @@
 //! Background loops for continuous processing.
 
+pub mod adsb_sync_loop;
 pub mod blender_sync_loop;
 pub mod conflict_loop;
 pub mod conformance_loop;
 pub mod flight_declaration_sync_loop;
 pub mod geofence_sync_loop;
+pub mod job_loop;
+pub mod metering_loop;
 pub mod mission_loop;
+pub mod msa_loop;
 pub mod operational_intent_expiry_loop;
+pub mod replan_loop;
 pub mod rid_sync_loop;
+pub mod secrets_refresh_loop;
 pub mod telemetry_persist_loop;
+pub mod terrain_clearance_loop;
+pub mod tfr_loop;
+pub mod token_expiry_loop;
+
+/// Supervised background loops, by the name used for heartbeats and pausing.
+pub const LOOP_NAMES: [&str; 17] = [
+    "conflict",
+    "conformance",
+    "mission",
+    "oi-expiry",
+    "secrets-refresh",
+    "token-expiry",
+    "metering",
+    "terrain",
+    "msa",
+    "rid",
+    "adsb",
+    "geofence-sync",
+    "tfr",
+    "flight-declaration-sync",
+    "blender-sync",
+    "replan",
+    "jobs",
+];
+
+#[cfg(test)]
+mod scenario_tests;
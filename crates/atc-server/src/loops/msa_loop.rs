@@ -0,0 +1,135 @@
+//! Minimum safe altitude grid loop.
+//!
+//! Rebuilds the MSA grid over `ATC_MSA_AREA` from terrain and obstacle data every
+//! `ATC_MSA_REFRESH_SECS` and publishes it for `/v1/msa` and flight plan validation. The loop
+//! idles while no area is configured; a failed rebuild keeps the previous grid.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use reqwest::Client;
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+use atc_core::route_engine::RouteObstacle;
+use atc_core::{MsaBounds, MsaGrid};
+
+use crate::compliance::{fetch_obstacles, ObstacleQueryMode, RoutePoint};
+use crate::config::Config;
+use crate::state::AppState;
+use crate::terrain::fetch_terrain_grid;
+
+const LOOP_INTERVAL_SECS: u64 = 30;
+
+/// Start the MSA loop.
+pub async fn run_msa_loop(
+    state: Arc<AppState>,
+    config: Config,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(config.terrain_request_timeout_s.max(3)))
+        .build()
+        .unwrap_or_else(|_| Client::new());
+    let mut ticker = interval(Duration::from_secs(LOOP_INTERVAL_SECS));
+    let refresh = Duration::from_secs(config.msa_refresh_secs);
+    let mut last_attempt: Option<Instant> = None;
+    state.mark_loop_heartbeat("msa");
+
+    loop {
+        tokio::select! {
+            _ = shutdown.recv() => {
+                tracing::info!("MSA loop shutting down");
+                break;
+            }
+            _ = ticker.tick() => {
+                state.mark_loop_heartbeat("msa");
+                let Some(area) = config.msa_area else {
+                    continue;
+                };
+                if state.loop_paused("msa")
+                    || last_attempt.is_some_and(|at| at.elapsed() < refresh)
+                {
+                    continue;
+                }
+                last_attempt = Some(Instant::now());
+                match build_msa_grid(&client, &config, &area).await {
+                    Ok(grid) => {
+                        tracing::info!(
+                            "MSA grid rebuilt: {}x{} cells, {:.0} m clearance",
+                            grid.rows,
+                            grid.cols,
+                            grid.clearance_m
+                        );
+                        state.set_msa_grid(grid);
+                    }
+                    Err(err) => tracing::warn!("MSA grid rebuild failed: {}", err),
+                }
+            }
+        }
+    }
+}
+
+async fn build_msa_grid(
+    client: &Client,
+    config: &Config,
+    area: &MsaBounds,
+) -> Result<MsaGrid, String> {
+    let corners = [
+        RoutePoint {
+            lat: area.min_lat,
+            lon: area.min_lon,
+            altitude_m: 0.0,
+        },
+        RoutePoint {
+            lat: area.max_lat,
+            lon: area.max_lon,
+            altitude_m: 0.0,
+        },
+    ];
+    let terrain = fetch_terrain_grid(client, config, &corners, config.msa_spacing_m).await?;
+    let analysis = fetch_obstacles(
+        client,
+        config,
+        &corners,
+        config.msa_clearance_m,
+        None,
+        ObstacleQueryMode::RoutePlanner,
+    )
+    .await?;
+    if analysis.truncated {
+        tracing::warn!("Obstacle dataset truncated; MSA grid may miss obstacles");
+    }
+    let obstacles: Vec<RouteObstacle> = analysis
+        .candidates
+        .iter()
+        .map(|candidate| {
+            // Building footprints are used as-is; only point obstacles need a radius.
+            let polygon = candidate
+                .polygon
+                .clone()
+                .filter(|polygon| polygon.len() >= 3);
+            RouteObstacle {
+                lat: candidate.lat,
+                lon: candidate.lon,
+                radius_m: if polygon.is_some() {
+                    0.0
+                } else {
+                    candidate.radius_m
+                },
+                height_m: Some(candidate.height_m),
+                polygon,
+            }
+        })
+        .collect();
+
+    Ok(MsaGrid::build(
+        area,
+        config.msa_spacing_m,
+        config.msa_clearance_m,
+        &obstacles,
+        |lat, lon| terrain.as_ref().map_or(0.0, |grid| grid.sample(lat, lon)),
+        Utc::now(),
+    ))
+}
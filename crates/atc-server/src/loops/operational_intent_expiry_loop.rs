@@ -43,6 +43,9 @@ pub async fn run_operational_intent_expiry_loop(
             }
             _ = ticker.tick() => {
                 state.mark_loop_heartbeat("oi-expiry");
+                if state.loop_paused("oi-expiry") {
+                    continue;
+                }
                 if !backoff.ready() {
                     continue;
                 }
@@ -129,7 +132,7 @@ pub async fn run_operational_intent_expiry_loop(
 
                 backoff.reset();
                 for plan in expired {
-                    state.flight_plans.insert(plan.flight_id.clone(), plan);
+                    state.cache_flight_plan(plan);
                 }
             }
         }
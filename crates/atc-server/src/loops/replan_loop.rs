@@ -0,0 +1,39 @@
+//! In-flight replanning loop.
+//!
+//! Watches for geofences that appear while flights are airborne and replans any active flight
+//! whose remaining route crosses one. See [`crate::replan`].
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+use crate::replan::replan_for_new_geofences;
+use crate::state::AppState;
+
+const LOOP_INTERVAL_SECS: u64 = 2;
+
+/// Start the replanning loop. It idles while ATC_INFLIGHT_REPLAN_ENABLED is off.
+pub async fn run_replan_loop(state: Arc<AppState>, mut shutdown: broadcast::Receiver<()>) {
+    let mut ticker = interval(Duration::from_secs(LOOP_INTERVAL_SECS));
+    let mut known_geofences: Option<HashSet<String>> = None;
+    state.mark_loop_heartbeat("replan");
+
+    loop {
+        tokio::select! {
+            _ = shutdown.recv() => {
+                tracing::info!("Replan loop shutting down");
+                break;
+            }
+            _ = ticker.tick() => {
+                state.mark_loop_heartbeat("replan");
+                if state.loop_paused("replan") || !state.config().inflight_replan_enabled {
+                    continue;
+                }
+                replan_for_new_geofences(&state, &mut known_geofences).await;
+            }
+        }
+    }
+}
@@ -17,6 +17,7 @@ use crate::blender_auth::BlenderAuthManager;
 use crate::config::Config;
 use crate::state::{AppState, ExternalTraffic};
 use atc_blender::BlenderClient;
+use atc_core::AircraftCategory;
 
 const RID_POLL_SECS: u64 = 2;
 const RID_SUBSCRIPTION_TTL_SECS: u64 = 20;
@@ -56,6 +57,9 @@ pub async fn run_rid_loop(
             }
             _ = ticker.tick() => {
                 state.mark_loop_heartbeat("rid");
+                if state.loop_paused("rid") {
+                    continue;
+                }
                 if !backoff.ready() {
                     continue;
                 }
@@ -234,6 +238,39 @@ fn normalize_observation(observation: &Value) -> Option<ExternalTraffic> {
         heading_deg,
         speed_mps,
         last_update,
+        category: aircraft_category(observation, metadata),
+        quality: None,
+        extrapolated_s: 0.0,
+    })
+}
+
+/// Category from an ADS-B emitter category if the feed carries one, else the Remote ID UA type.
+fn aircraft_category(observation: &Value, metadata: &Value) -> AircraftCategory {
+    let emitter = first_code(&[
+        observation.get("emitter_category"),
+        metadata.get("emitter_category"),
+        metadata.get("category"),
+    ])
+    .map(|code| AircraftCategory::from_adsb_emitter(&code))
+    .filter(|category| *category != AircraftCategory::Unknown);
+    emitter
+        .or_else(|| {
+            first_code(&[
+                metadata.get("ua_type"),
+                metadata.get("aircraft_type"),
+                observation.get("aircraft_type"),
+            ])
+            .map(|code| AircraftCategory::from_rid_ua_type(&code))
+        })
+        .unwrap_or_default()
+}
+
+/// First string or integer among the candidates, as text.
+fn first_code(candidates: &[Option<&Value>]) -> Option<String> {
+    candidates.iter().find_map(|value| match (*value)? {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(num) => num.as_u64().map(|code| code.to_string()),
+        _ => None,
     })
 }
 
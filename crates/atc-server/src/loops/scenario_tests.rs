@@ -0,0 +1,654 @@
+//! Declarative acceptance scenarios for the rules engine.
+//!
+//! Each fixture under `tests/scenarios/` describes safety rules, geofences, a flight plan and a
+//! timed telemetry trace, together with the violations, conflicts and commands the server is
+//! expected to produce. The harness runs the plan through route validation, replays the trace
+//! through the conflict detector, and applies the conflict and conformance loop decisions on a
+//! simulated clock so command cooldowns are deterministic.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use atc_blender::{
+    conflict_payload, conflict_to_geofence, AvoidanceDirection, CONFLICT_PROPERTIES_VERSION,
+};
+use atc_core::models::{
+    BreachResponse, CommandType, ConformanceRecord, FlightPlan, FlightPlanMetadata,
+    FlightPlanRequest, FlightStatus, Geofence, GeofenceType, Telemetry, TrajectoryPoint,
+};
+use atc_core::rules::SafetyRules;
+use atc_core::{
+    cluster_conflicts, AvoidanceType, ConflictSeverity, DroneCapabilities, DronePerformance,
+    IntentFilterMode, Maneuver,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::conflict_loop::{
+    avoidance_type_for, executable_command, give_way_drone_id, plan_cluster_resolution,
+    recommended_avoidance, resolution_command, select_resolution, ClusterParticipant,
+    COMMAND_COOLDOWN_SECS,
+};
+use super::conformance_loop::{
+    requires_hold, CONFORMANCE_COMMAND_COOLDOWN_SECS, CONFORMANCE_HOLD_SECS,
+};
+use crate::altitude::AltitudeReference;
+use crate::api::flights::validate_route;
+use crate::breach::{breach_command_type, BreachKind};
+use crate::config::Config;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+struct Scenario {
+    name: String,
+    /// Overrides applied on top of `SafetyRules::default()`.
+    #[serde(default)]
+    rules: serde_json::Map<String, Value>,
+    #[serde(default)]
+    geofences: Vec<ScenarioGeofence>,
+    #[serde(default)]
+    plan: Option<FlightPlanRequest>,
+    /// Scheduling priority of each drone's active flight plan (lower = higher priority).
+    #[serde(default)]
+    scheduling_priorities: HashMap<String, u32>,
+    /// Registered performance envelopes per drone.
+    #[serde(default)]
+    performance: HashMap<String, DronePerformance>,
+    /// Capabilities each drone advertised at registration.
+    #[serde(default)]
+    capabilities: HashMap<String, DroneCapabilities>,
+    /// Timed trajectory of each drone's active flight plan, offsets from the scenario start.
+    #[serde(default)]
+    active_plans: HashMap<String, Vec<TrajectoryPoint>>,
+    /// `ATC_CONFLICT_INTENT_FILTER` for the scenario.
+    #[serde(default)]
+    intent_filter: IntentFilterMode,
+    /// Violation types reported by route validation, in any order.
+    #[serde(default)]
+    expected_violations: Vec<String>,
+    #[serde(default)]
+    trace: Vec<TraceStep>,
+    #[serde(default)]
+    expected_conflicts: Vec<ExpectedConflict>,
+    #[serde(default)]
+    expected_commands: Vec<ExpectedCommand>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScenarioGeofence {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    geofence_type: GeofenceType,
+    polygon: Vec<[f64; 2]>,
+    #[serde(default)]
+    lower_altitude_m: f64,
+    #[serde(default = "default_upper_altitude_m")]
+    upper_altitude_m: f64,
+    #[serde(default)]
+    breach_response: Option<BreachResponse>,
+}
+
+fn default_upper_altitude_m() -> f64 {
+    120.0
+}
+
+#[derive(Debug, Deserialize)]
+struct TraceStep {
+    /// Seconds since the start of the scenario.
+    t: u64,
+    #[serde(default)]
+    telemetry: Vec<TraceTelemetry>,
+    /// Conformance states reported by Blender at this step, beyond geofence breaches
+    /// (which the harness derives from the telemetry itself).
+    #[serde(default)]
+    conformance: Vec<TraceConformance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TraceTelemetry {
+    drone_id: String,
+    lat: f64,
+    lon: f64,
+    altitude_m: f64,
+    #[serde(default)]
+    heading_deg: f64,
+    #[serde(default)]
+    speed_mps: f64,
+    #[serde(default)]
+    velocity_z: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TraceConformance {
+    drone_id: String,
+    state_code: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+struct ExpectedConflict {
+    t: u64,
+    /// Drone IDs in either order.
+    drones: [String; 2],
+    severity: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+struct ExpectedCommand {
+    t: u64,
+    drone_id: String,
+    source: String,
+    command: String,
+    #[serde(default)]
+    avoidance: Option<String>,
+    /// Resolution maneuver chosen for a pairwise conflict.
+    #[serde(default)]
+    maneuver: Option<String>,
+}
+
+fn scenario_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scenarios")
+}
+
+fn load_scenarios() -> Vec<(PathBuf, Scenario)> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(scenario_dir())
+        .expect("read scenario dir")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let raw = std::fs::read_to_string(&path).expect("read scenario");
+            let scenario: Scenario = serde_json::from_str(&raw)
+                .unwrap_or_else(|err| panic!("parse {}: {}", path.display(), err));
+            (path, scenario)
+        })
+        .collect()
+}
+
+fn scenario_rules(overrides: &serde_json::Map<String, Value>) -> SafetyRules {
+    let mut rules = serde_json::to_value(SafetyRules::default()).expect("serialize rules");
+    let fields = rules.as_object_mut().expect("rules object");
+    for (key, value) in overrides {
+        assert!(fields.contains_key(key), "unknown rule override '{}'", key);
+        fields.insert(key.clone(), value.clone());
+    }
+    serde_json::from_value(rules).expect("deserialize rules")
+}
+
+fn severity_label(severity: ConflictSeverity) -> String {
+    match severity {
+        ConflictSeverity::Info => "info",
+        ConflictSeverity::Warning => "warning",
+        ConflictSeverity::Critical => "critical",
+    }
+    .to_string()
+}
+
+fn avoidance_label(avoidance: AvoidanceType) -> String {
+    match avoidance {
+        AvoidanceType::Lateral => "lateral",
+        AvoidanceType::Vertical => "vertical",
+        AvoidanceType::Combined => "combined",
+    }
+    .to_string()
+}
+
+fn maneuver_label(maneuver: Maneuver) -> String {
+    match maneuver {
+        Maneuver::Climb => "climb",
+        Maneuver::Descend => "descend",
+        Maneuver::TurnLeft => "turn_left",
+        Maneuver::TurnRight => "turn_right",
+        Maneuver::SpeedUp => "speed_up",
+        Maneuver::SlowDown => "slow_down",
+    }
+    .to_string()
+}
+
+fn command_label(command: &CommandType) -> String {
+    match command {
+        CommandType::Reroute { .. } => "reroute",
+        CommandType::Hold { .. } => "hold",
+        CommandType::AltitudeChange { .. } => "altitude_change",
+        CommandType::Land => "land",
+        _ => "other",
+    }
+    .to_string()
+}
+
+fn breach_record(drone_id: &str, state_code: &str, geofence_id: Option<&str>) -> ConformanceRecord {
+    let now = Utc::now().to_rfc3339();
+    ConformanceRecord {
+        id: format!("scenario-{}", drone_id),
+        flight_declaration_id: String::new(),
+        aircraft_id: drone_id.to_string(),
+        conformance_state: 0,
+        conformance_state_label: String::new(),
+        conformance_state_code: Some(state_code.to_string()),
+        timestamp: now.clone(),
+        description: format!("Scenario conformance state {}", state_code),
+        event_type: String::new(),
+        geofence_breach: geofence_id.is_some(),
+        geofence_id: geofence_id.map(str::to_string),
+        resolved: false,
+        created_at: now.clone(),
+        updated_at: now,
+    }
+}
+
+/// Issues commands on the scenario clock, honouring the same cooldowns as the loops.
+#[derive(Default)]
+struct CommandLog {
+    last_issued: HashMap<String, u64>,
+    issued: Vec<ExpectedCommand>,
+}
+
+impl CommandLog {
+    #[allow(clippy::too_many_arguments)]
+    fn issue(
+        &mut self,
+        t: u64,
+        drone_id: &str,
+        cooldown_secs: u64,
+        source: &str,
+        command: &CommandType,
+        avoidance: Option<AvoidanceType>,
+        maneuver: Option<Maneuver>,
+    ) {
+        if let Some(last) = self.last_issued.get(drone_id) {
+            if t < last + cooldown_secs {
+                return;
+            }
+        }
+        self.last_issued.insert(drone_id.to_string(), t);
+        self.issued.push(ExpectedCommand {
+            t,
+            drone_id: drone_id.to_string(),
+            source: source.to_string(),
+            command: command_label(command),
+            avoidance: avoidance.map(avoidance_label),
+            maneuver: maneuver.map(maneuver_label),
+        });
+    }
+}
+
+async fn run_scenario(path: &Path, scenario: Scenario) {
+    let label = format!("{} ({})", scenario.name, path.display());
+    let mut config = Config::from_env();
+    config.altitude_reference = AltitudeReference::Amsl;
+    config.geoid_offset_m = 0.0;
+    config.terrain_require = false;
+    config.require_blender_declaration = false;
+    config.conflict_intent_filter = scenario.intent_filter;
+    let state = Arc::new(AppState::with_rules_and_config(
+        scenario_rules(&scenario.rules),
+        config,
+    ));
+
+    for geofence in scenario.geofences {
+        state
+            .add_geofence(Geofence {
+                name: geofence.name.unwrap_or_else(|| geofence.id.clone()),
+                id: geofence.id,
+                geofence_type: geofence.geofence_type,
+                polygon: geofence.polygon,
+                lower_altitude_m: geofence.lower_altitude_m,
+                upper_altitude_m: geofence.upper_altitude_m,
+                active: true,
+                created_at: Utc::now(),
+                breach_response: geofence.breach_response,
+                schedule: None,
+                org_id: None,
+            })
+            .await
+            .expect("add geofence");
+    }
+
+    if let Some(plan) = scenario.plan.as_ref() {
+        let mut actual: Vec<String> = validate_route(&state, plan)
+            .await
+            .iter()
+            .filter_map(|violation| violation["type"].as_str().map(str::to_string))
+            .collect();
+        let mut expected = scenario.expected_violations.clone();
+        actual.sort();
+        expected.sort();
+        assert_eq!(actual, expected, "violations for {}", label);
+    } else {
+        assert!(
+            scenario.expected_violations.is_empty(),
+            "{} expects violations but has no plan",
+            label
+        );
+    }
+
+    for (drone_id, priority) in &scenario.scheduling_priorities {
+        let flight_id = format!("PLAN-{}", drone_id);
+        state.flight_plans.insert(
+            flight_id.clone(),
+            FlightPlan {
+                flight_id,
+                drone_id: drone_id.clone(),
+                owner_id: None,
+                waypoints: Vec::new(),
+                trajectory_log: None,
+                metadata: Some(FlightPlanMetadata {
+                    scheduling_priority: Some(*priority),
+                    ..Default::default()
+                }),
+                status: FlightStatus::Active,
+                departure_time: Utc::now(),
+                arrival_time: None,
+                created_at: Utc::now(),
+            },
+        );
+    }
+
+    for (drone_id, performance) in &scenario.performance {
+        state
+            .set_drone_performance(drone_id, *performance)
+            .await
+            .expect("set performance");
+    }
+    for (drone_id, capabilities) in &scenario.capabilities {
+        state
+            .register_drone(drone_id, None)
+            .await
+            .expect("register drone");
+        state
+            .set_drone_capabilities(drone_id, *capabilities)
+            .await
+            .expect("set capabilities");
+    }
+
+    let mut conflicts = Vec::new();
+    let mut commands = CommandLog::default();
+    for step in &scenario.trace {
+        // Plans depart `t` seconds before now so their offsets follow the scenario clock.
+        let departure_time = Utc::now() - chrono::Duration::seconds(step.t as i64);
+        for (drone_id, trajectory) in &scenario.active_plans {
+            let flight_id = format!("PLAN-{}", drone_id);
+            let mut plan = state
+                .flight_plans
+                .get(&flight_id)
+                .map(|entry| entry.value().clone())
+                .unwrap_or_else(|| FlightPlan {
+                    flight_id: flight_id.clone(),
+                    drone_id: drone_id.clone(),
+                    owner_id: None,
+                    waypoints: Vec::new(),
+                    trajectory_log: None,
+                    metadata: None,
+                    status: FlightStatus::Active,
+                    departure_time,
+                    arrival_time: None,
+                    created_at: Utc::now(),
+                });
+            plan.trajectory_log = Some(trajectory.clone());
+            plan.departure_time = departure_time;
+            state.flight_plans.insert(flight_id, plan);
+        }
+        for sample in &step.telemetry {
+            state
+                .update_telemetry(Telemetry {
+                    drone_id: sample.drone_id.clone(),
+                    owner_id: None,
+                    lat: sample.lat,
+                    lon: sample.lon,
+                    altitude_m: sample.altitude_m,
+                    velocity_x: 0.0,
+                    velocity_y: 0.0,
+                    velocity_z: sample.velocity_z,
+                    heading_deg: sample.heading_deg,
+                    speed_mps: sample.speed_mps,
+                    timestamp: Utc::now(),
+                })
+                .await;
+        }
+        state.sync_drone_priorities();
+        state.refresh_conflicts().await;
+
+        let mut step_conflicts = state.get_conflicts();
+        step_conflicts
+            .sort_by(|a, b| (&a.drone1_id, &a.drone2_id).cmp(&(&b.drone1_id, &b.drone2_id)));
+        let mut clustered_drones: HashSet<String> = HashSet::new();
+        for cluster in cluster_conflicts(&step_conflicts)
+            .iter()
+            .filter(|cluster| cluster.is_multi_aircraft())
+        {
+            clustered_drones.extend(cluster.drone_ids.iter().cloned());
+            if !matches!(
+                cluster.severity,
+                ConflictSeverity::Critical | ConflictSeverity::Warning
+            ) {
+                continue;
+            }
+            let participants: Vec<ClusterParticipant> = cluster
+                .drone_ids
+                .iter()
+                .filter_map(|id| {
+                    state.get_drone(id).map(|drone| ClusterParticipant {
+                        drone_id: id.clone(),
+                        altitude_m: drone.altitude_m,
+                        commandable: true,
+                        priority: drone.scheduling_priority,
+                    })
+                })
+                .collect();
+            for (drone_id, target) in plan_cluster_resolution(&participants, &state.rules()) {
+                let command = match target {
+                    Some(target_altitude_m) => CommandType::AltitudeChange { target_altitude_m },
+                    None => CommandType::Hold { duration_secs: 0 },
+                };
+                commands.issue(
+                    step.t,
+                    &drone_id,
+                    COMMAND_COOLDOWN_SECS,
+                    "cluster",
+                    &command,
+                    None,
+                    None,
+                );
+            }
+        }
+
+        for conflict in &step_conflicts {
+            let mut drones = [conflict.drone1_id.clone(), conflict.drone2_id.clone()];
+            drones.sort();
+            conflicts.push(ExpectedConflict {
+                t: step.t,
+                drones,
+                severity: severity_label(conflict.severity),
+            });
+
+            if !matches!(
+                conflict.severity,
+                ConflictSeverity::Critical | ConflictSeverity::Warning
+            ) || clustered_drones.contains(&conflict.drone1_id)
+            {
+                continue;
+            }
+            let give_way_id = give_way_drone_id(
+                conflict,
+                state
+                    .get_drone(&conflict.drone1_id)
+                    .and_then(|drone| drone.scheduling_priority),
+                state
+                    .get_drone(&conflict.drone2_id)
+                    .and_then(|drone| drone.scheduling_priority),
+            );
+            let priority_id = if give_way_id == &conflict.drone1_id {
+                &conflict.drone2_id
+            } else {
+                &conflict.drone1_id
+            };
+            let (Some(give_way), Some(priority)) =
+                (state.get_drone(give_way_id), state.get_drone(priority_id))
+            else {
+                continue;
+            };
+            let performance = state.drone_performance(give_way_id);
+            // The geofence pushed to Blender advertises the avoidance that is commanded.
+            let recommendation = recommended_avoidance(
+                conflict,
+                &give_way,
+                Some(&priority),
+                priority.altitude_m,
+                &state.rules(),
+                performance.as_ref(),
+                state.config().route_planner_wind_mps,
+            );
+            let geofence = conflict_to_geofence(conflict, None, None)
+                .with_recommendation(recommendation.clone());
+            let properties =
+                &conflict_payload(&geofence).unwrap()["features"][0]["properties"]["conflict"];
+            assert_eq!(
+                properties["properties_version"],
+                CONFLICT_PROPERTIES_VERSION
+            );
+            let gives_way: Vec<&Value> = properties["aircraft"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|aircraft| aircraft["gives_way"] == true)
+                .map(|aircraft| &aircraft["id"])
+                .collect();
+            assert_eq!(gives_way, [give_way_id.as_str()]);
+            if let Some(option) = select_resolution(
+                conflict,
+                &give_way,
+                &priority,
+                &state.rules(),
+                performance.as_ref(),
+                state.config().route_planner_wind_mps,
+            ) {
+                assert_eq!(
+                    recommendation.direction,
+                    AvoidanceDirection::Maneuver(option.maneuver)
+                );
+                commands.issue(
+                    step.t,
+                    give_way_id,
+                    COMMAND_COOLDOWN_SECS,
+                    "conflict",
+                    &resolution_command(&option, &give_way, String::new()),
+                    None,
+                    Some(option.maneuver),
+                );
+                continue;
+            }
+            let avoidance = avoidance_type_for(
+                conflict,
+                give_way.altitude_m,
+                priority.altitude_m,
+                &state.rules(),
+                performance.as_ref(),
+            );
+            assert_eq!(
+                recommendation.direction,
+                AvoidanceDirection::Reroute(avoidance)
+            );
+            let Some(command) = executable_command(
+                CommandType::Reroute {
+                    waypoints: Vec::new(),
+                    reason: None,
+                },
+                &give_way,
+                performance.as_ref(),
+                state.config().route_planner_wind_mps,
+            ) else {
+                continue;
+            };
+            let avoidance = matches!(command, CommandType::Reroute { .. }).then_some(avoidance);
+            commands.issue(
+                step.t,
+                give_way_id,
+                COMMAND_COOLDOWN_SECS,
+                "conflict",
+                &command,
+                avoidance,
+                None,
+            );
+        }
+
+        let mut drones = state.get_all_drones();
+        drones.sort_by(|a, b| a.drone_id.cmp(&b.drone_id));
+        for drone in drones {
+            let breached = state
+                .get_local_geofences()
+                .into_iter()
+                .filter(|geofence| {
+                    geofence.active && geofence.geofence_type != GeofenceType::Advisory
+                })
+                .find(|geofence| geofence.contains_point(drone.lat, drone.lon, drone.altitude_m));
+            let record = match breached.as_ref() {
+                Some(geofence) => Some(breach_record(&drone.drone_id, "C8", Some(&geofence.id))),
+                None => step
+                    .conformance
+                    .iter()
+                    .find(|report| report.drone_id == drone.drone_id)
+                    .map(|report| breach_record(&drone.drone_id, &report.state_code, None)),
+            };
+            let Some(record) = record else {
+                continue;
+            };
+            if !requires_hold(Some(&record)) {
+                continue;
+            }
+            let command = match breached.as_ref() {
+                Some(geofence) => breach_command_type(
+                    state.config().breach_policy.response_for(geofence),
+                    BreachKind::Inside,
+                    &drone,
+                    geofence,
+                ),
+                None => Some(CommandType::Hold {
+                    duration_secs: CONFORMANCE_HOLD_SECS,
+                }),
+            };
+            let Some(command) = command else {
+                continue;
+            };
+            commands.issue(
+                step.t,
+                &drone.drone_id,
+                CONFORMANCE_COMMAND_COOLDOWN_SECS,
+                "conformance",
+                &command,
+                None,
+                None,
+            );
+        }
+    }
+
+    let mut expected_conflicts = scenario.expected_conflicts.clone();
+    for conflict in &mut expected_conflicts {
+        conflict.drones.sort();
+    }
+    expected_conflicts.sort();
+    conflicts.sort();
+    assert_eq!(conflicts, expected_conflicts, "conflicts for {}", label);
+
+    let mut expected_commands = scenario.expected_commands.clone();
+    expected_commands.sort();
+    commands.issued.sort();
+    assert_eq!(commands.issued, expected_commands, "commands for {}", label);
+}
+
+#[tokio::test]
+async fn rule_scenarios_match_expectations() {
+    let scenarios = load_scenarios();
+    assert!(
+        !scenarios.is_empty(),
+        "no scenarios in {}",
+        scenario_dir().display()
+    );
+    for (path, scenario) in scenarios {
+        run_scenario(&path, scenario).await;
+    }
+}
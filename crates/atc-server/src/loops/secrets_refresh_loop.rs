@@ -0,0 +1,69 @@
+//! Secret refresh loop.
+//!
+//! Re-reads the configured secret backend so rotated credentials take effect without a
+//! restart. Failed refreshes keep serving the last known values.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+use crate::backoff::Backoff;
+use crate::config::Config;
+use crate::state::AppState;
+
+const BACKOFF_MAX_SECS: u64 = 300;
+
+pub async fn run_secrets_refresh_loop(
+    state: Arc<AppState>,
+    config: Config,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    if !config.secrets.is_enabled() {
+        tracing::info!("Secret refresh loop disabled (secrets come from environment)");
+        return;
+    }
+
+    let refresh_secs = config.secrets_refresh_secs.max(1);
+    let mut ticker = interval(Duration::from_secs(refresh_secs));
+    let mut backoff = Backoff::new(
+        Duration::from_secs(refresh_secs),
+        Duration::from_secs(BACKOFF_MAX_SECS.max(refresh_secs)),
+    );
+    state.mark_loop_heartbeat("secrets-refresh");
+
+    loop {
+        tokio::select! {
+            _ = shutdown.recv() => {
+                tracing::info!("Secret refresh loop shutting down");
+                break;
+            }
+            _ = ticker.tick() => {
+                state.mark_loop_heartbeat("secrets-refresh");
+                if state.loop_paused("secrets-refresh") {
+                    continue;
+                }
+                if !backoff.ready() {
+                    continue;
+                }
+                match config.secrets.refresh().await {
+                    Ok(changed) => {
+                        backoff.reset();
+                        if !changed.is_empty() {
+                            tracing::info!("Rotated secrets: {}", changed.join(", "));
+                        }
+                    }
+                    Err(err) => {
+                        let delay = backoff.fail();
+                        tracing::warn!(
+                            "Secret refresh failed: {} (backing off {:?})",
+                            err,
+                            delay
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
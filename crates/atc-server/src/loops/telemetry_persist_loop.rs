@@ -1,22 +1,250 @@
 //! Telemetry persistence loop.
 //!
-//! Coalesces high-frequency telemetry into periodic DB writes.
+//! Coalesces high-frequency telemetry into periodic DB writes: every `ATC_TELEMETRY_FLUSH_MS`
+//! the latest snapshot of each drone is written in transactions of at most
+//! `ATC_TELEMETRY_BATCH_SIZE` rows. With `ATC_TELEMETRY_ADAPTIVE_BATCHING` the loop stretches
+//! both while the queue backs up or flushes overrun the interval, and keeps draining the queue
+//! between transactions, so bursts are coalesced here instead of spilling to the overflow map.
+//! Flush latency, rows written and overflow counters are exported at `/metrics`.
+//!
+//! Each written snapshot is also appended to the drone's flown track, and samples older than
+//! `ATC_TELEMETRY_HISTORY_RETENTION_HOURS` are pruned hourly.
 
 use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use tokio::sync::{broadcast, mpsc};
-use tokio::time::interval;
+use tokio::time::{sleep_until, Instant};
 
 use atc_core::models::DroneState;
 
 use crate::backoff::Backoff;
-use crate::persistence::{drones as drones_db, Database};
+use crate::chaos::chaos;
+use crate::config::Config;
+use crate::persistence::{drones as drones_db, telemetry_history as history_db, Database};
 use crate::state::AppState;
 
-const TELEMETRY_FLUSH_SECS: u64 = 1;
 const TELEMETRY_DB_BACKOFF_MAX_SECS: u64 = 30;
+/// How often flown-track samples past their retention are deleted.
+const HISTORY_PRUNE_INTERVAL: Duration = Duration::from_secs(3_600);
+/// Adaptive batching stretches the interval and batch size up to this many times.
+const MAX_ADAPTIVE_FACTOR: u32 = 8;
+/// Stretched flush intervals stay well inside the `/ready` heartbeat limit.
+const MAX_FLUSH_INTERVAL_MS: u64 = 8_000;
+/// Queue fill at which adaptive batching stretches.
+const QUEUE_HIGH_WATER: f64 = 0.5;
+/// Queue fill below which adaptive batching relaxes again.
+const QUEUE_LOW_WATER: f64 = 0.1;
+/// Upper bounds (seconds) of the flush duration histogram buckets.
+const FLUSH_SECONDS_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// Telemetry persistence counters and gauges since the server started.
+#[derive(Debug, Default)]
+pub struct TelemetryPersistMetrics {
+    flushes: AtomicU64,
+    flush_failures: AtomicU64,
+    transactions: AtomicU64,
+    rows_written: AtomicU64,
+    flush_buckets: [AtomicU64; FLUSH_SECONDS_BUCKETS.len()],
+    flush_micros_sum: AtomicU64,
+    pending_rows: AtomicU64,
+    queue_depth: AtomicU64,
+    overflow_stashed: AtomicU64,
+    overflow_dropped: AtomicU64,
+    flush_interval_ms: AtomicU64,
+    batch_size: AtomicU64,
+}
+
+impl TelemetryPersistMetrics {
+    fn record_flush(&self, elapsed: Duration, ok: bool) {
+        self.flushes.fetch_add(1, Ordering::Relaxed);
+        if !ok {
+            self.flush_failures.fetch_add(1, Ordering::Relaxed);
+        }
+        let secs = elapsed.as_secs_f64();
+        if let Some(bucket) = FLUSH_SECONDS_BUCKETS.iter().position(|le| secs <= *le) {
+            self.flush_buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+        self.flush_micros_sum
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn record_transaction(&self, rows: usize) {
+        self.transactions.fetch_add(1, Ordering::Relaxed);
+        self.rows_written.fetch_add(rows as u64, Ordering::Relaxed);
+    }
+
+    fn set_backlog(&self, pending_rows: usize, queue_depth: usize) {
+        self.pending_rows
+            .store(pending_rows as u64, Ordering::Relaxed);
+        self.queue_depth
+            .store(queue_depth as u64, Ordering::Relaxed);
+    }
+
+    fn set_pacing(&self, pacing: &FlushPacing) {
+        self.flush_interval_ms
+            .store(pacing.interval().as_millis() as u64, Ordering::Relaxed);
+        let batch_size = pacing.batch_size();
+        self.batch_size.store(
+            if batch_size == usize::MAX {
+                0
+            } else {
+                batch_size as u64
+            },
+            Ordering::Relaxed,
+        );
+    }
+
+    /// A snapshot that didn't fit in the queue was kept in the overflow map.
+    pub fn record_overflow_stashed(&self) {
+        self.overflow_stashed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A snapshot that didn't fit in the queue or the overflow map was dropped.
+    pub fn record_overflow_dropped(&self) {
+        self.overflow_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Append the metrics in Prometheus text format.
+    pub fn render(&self, out: &mut String) {
+        let load = |value: &AtomicU64| value.load(Ordering::Relaxed);
+        let metrics = [
+            (
+                "atc_telemetry_flushes_total",
+                "counter",
+                "Telemetry flushes with rows to write",
+                load(&self.flushes),
+            ),
+            (
+                "atc_telemetry_flush_failures_total",
+                "counter",
+                "Telemetry flushes that failed and were retried",
+                load(&self.flush_failures),
+            ),
+            (
+                "atc_telemetry_transactions_total",
+                "counter",
+                "Telemetry write transactions committed",
+                load(&self.transactions),
+            ),
+            (
+                "atc_telemetry_rows_written_total",
+                "counter",
+                "Drone rows written by the telemetry persistence loop",
+                load(&self.rows_written),
+            ),
+            (
+                "atc_telemetry_overflow_stashed_total",
+                "counter",
+                "Snapshots kept in the overflow map because the queue was full or closed",
+                load(&self.overflow_stashed),
+            ),
+            (
+                "atc_telemetry_overflow_dropped_total",
+                "counter",
+                "Snapshots dropped because the overflow map was full",
+                load(&self.overflow_dropped),
+            ),
+            (
+                "atc_telemetry_pending_rows",
+                "gauge",
+                "Coalesced drone snapshots waiting for the next flush",
+                load(&self.pending_rows),
+            ),
+            (
+                "atc_telemetry_queue_depth",
+                "gauge",
+                "Snapshots waiting in the telemetry persistence queue",
+                load(&self.queue_depth),
+            ),
+            (
+                "atc_telemetry_flush_interval_ms",
+                "gauge",
+                "Current telemetry flush interval",
+                load(&self.flush_interval_ms),
+            ),
+            (
+                "atc_telemetry_batch_size",
+                "gauge",
+                "Current max rows per telemetry transaction (0 = unlimited)",
+                load(&self.batch_size),
+            ),
+        ];
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+
+        let name = "atc_telemetry_flush_duration_seconds";
+        let _ = writeln!(out, "# HELP {} Telemetry flush latency", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        let mut cumulative = 0;
+        for (le, bucket) in FLUSH_SECONDS_BUCKETS.iter().zip(&self.flush_buckets) {
+            cumulative += load(bucket);
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, le, cumulative);
+        }
+        let count = load(&self.flushes);
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, count);
+        let _ = writeln!(
+            out,
+            "{}_sum {}",
+            name,
+            load(&self.flush_micros_sum) as f64 / 1e6
+        );
+        let _ = writeln!(out, "{}_count {}", name, count);
+    }
+}
+
+/// Flush interval and batch size, stretched by adaptive batching under load.
+#[derive(Debug, Clone)]
+struct FlushPacing {
+    interval_ms: u64,
+    batch_size: usize,
+    adaptive: bool,
+    factor: u32,
+}
+
+impl FlushPacing {
+    fn new(config: &Config) -> Self {
+        Self {
+            interval_ms: config.telemetry_flush_ms.max(1),
+            batch_size: config.telemetry_batch_size,
+            adaptive: config.telemetry_adaptive_batching,
+            factor: 1,
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(
+            (self.interval_ms * u64::from(self.factor)).min(MAX_FLUSH_INTERVAL_MS),
+        )
+    }
+
+    /// Rows per transaction; `usize::MAX` when unlimited.
+    fn batch_size(&self) -> usize {
+        if self.batch_size == 0 {
+            usize::MAX
+        } else {
+            self.batch_size.saturating_mul(self.factor as usize)
+        }
+    }
+
+    /// Adapt to the queue fill (0-1) left after a flush that took `elapsed`.
+    fn observe(&mut self, queue_fill: f64, elapsed: Duration) {
+        if !self.adaptive {
+            return;
+        }
+        if queue_fill >= QUEUE_HIGH_WATER || elapsed >= self.interval() {
+            self.factor = (self.factor * 2).min(MAX_ADAPTIVE_FACTOR);
+        } else if queue_fill < QUEUE_LOW_WATER && elapsed * 4 <= self.interval() {
+            self.factor = (self.factor / 2).max(1);
+        }
+    }
+}
 
 pub async fn run_telemetry_persist_loop(
     db: Database,
@@ -24,12 +252,16 @@ pub async fn run_telemetry_persist_loop(
     mut rx: mpsc::Receiver<DroneState>,
     mut shutdown: broadcast::Receiver<()>,
 ) {
-    let mut ticker = interval(Duration::from_secs(TELEMETRY_FLUSH_SECS));
+    let metrics = app_state.telemetry_persist_metrics();
+    let mut pacing = FlushPacing::new(app_state.config());
     let mut backoff = Backoff::new(
-        Duration::from_secs(TELEMETRY_FLUSH_SECS),
+        pacing.interval(),
         Duration::from_secs(TELEMETRY_DB_BACKOFF_MAX_SECS),
     );
     let mut pending: HashMap<String, DroneState> = HashMap::new();
+    let mut next_flush = Instant::now() + pacing.interval();
+    let mut next_prune = Instant::now();
+    metrics.set_pacing(&pacing);
     app_state.mark_loop_heartbeat("telemetry-persist");
 
     loop {
@@ -52,28 +284,44 @@ pub async fn run_telemetry_persist_loop(
                     }
                 }
             }
-            _ = ticker.tick() => {
+            _ = sleep_until(next_flush) => {
                 app_state.mark_loop_heartbeat("telemetry-persist");
                 merge_overflow(&app_state, &mut pending);
-                if !backoff.ready() {
-                    continue;
+                let mut elapsed = Duration::ZERO;
+                if backoff.ready() && !pending.is_empty() {
+                    let started = Instant::now();
+                    let queue = pacing.adaptive.then_some(&mut rx);
+                    let result =
+                        flush_pending(&db, &mut pending, pacing.batch_size(), queue, metrics).await;
+                    elapsed = started.elapsed();
+                    metrics.record_flush(elapsed, result.is_ok());
+                    if let Err(err) = result {
+                        let delay = backoff.fail();
+                        tracing::warn!(
+                            "Telemetry persistence flush failed: {} (backing off {:?})",
+                            err,
+                            delay
+                        );
+                    } else {
+                        backoff.reset();
+                    }
                 }
-                if let Err(err) = flush_pending(&db, &mut pending).await {
-                    let delay = backoff.fail();
-                    tracing::warn!(
-                        "Telemetry persistence flush failed: {} (backing off {:?})",
-                        err,
-                        delay
-                    );
-                } else {
-                    backoff.reset();
+                let queue_fill = rx.len() as f64 / rx.max_capacity().max(1) as f64;
+                pacing.observe(queue_fill, elapsed);
+                metrics.set_pacing(&pacing);
+                metrics.set_backlog(pending.len(), rx.len());
+                next_flush = Instant::now() + pacing.interval();
+                if Instant::now() >= next_prune {
+                    prune_history(&db, app_state.config().telemetry_history_retention_hours).await;
+                    next_prune = Instant::now() + HISTORY_PRUNE_INTERVAL;
                 }
             }
         }
     }
 
     merge_overflow(&app_state, &mut pending);
-    if let Err(err) = flush_pending(&db, &mut pending).await {
+    drain_queue(&mut pending, &mut rx);
+    if let Err(err) = flush_pending(&db, &mut pending, pacing.batch_size(), None, metrics).await {
         tracing::warn!("Telemetry persistence final flush failed: {}", err);
     }
 }
@@ -94,38 +342,111 @@ fn merge_overflow(state: &AppState, pending: &mut HashMap<String, DroneState>) {
     }
 }
 
-async fn flush_pending(db: &Database, pending: &mut HashMap<String, DroneState>) -> Result<()> {
+/// Write `pending` in transactions of at most `batch_size` rows, draining `queue` into
+/// `pending` between them. Rows of a failed transaction go back to `pending` unless a newer
+/// snapshot of the drone arrived meanwhile.
+async fn flush_pending(
+    db: &Database,
+    pending: &mut HashMap<String, DroneState>,
+    batch_size: usize,
+    mut queue: Option<&mut mpsc::Receiver<DroneState>>,
+    metrics: &TelemetryPersistMetrics,
+) -> Result<()> {
     if pending.is_empty() {
         return Ok(());
     }
+    if chaos().fail_db_write() {
+        bail!("injected telemetry write failure");
+    }
 
-    let batch = std::mem::take(pending);
-    let mut tx = match db.pool().begin().await {
-        Ok(tx) => tx,
-        Err(err) => {
-            pending.extend(batch);
-            return Err(err.into());
+    let mut unwritten: Vec<DroneState> = std::mem::take(pending).into_values().collect();
+    while !unwritten.is_empty() {
+        let batch = unwritten.split_off(unwritten.len().saturating_sub(batch_size.max(1)));
+        if let Err(err) = write_batch(db, &batch).await {
+            for state in batch.into_iter().chain(unwritten) {
+                pending.entry(state.drone_id.clone()).or_insert(state);
+            }
+            return Err(err);
         }
-    };
-
-    let mut write_error: Option<anyhow::Error> = None;
-    for (_, state) in batch.iter() {
-        if let Err(err) = drones_db::upsert_drone_tx(&mut tx, state).await {
-            write_error = Some(err);
-            break;
+        metrics.record_transaction(batch.len());
+        if let Some(rx) = queue.as_deref_mut() {
+            drain_queue(pending, rx);
         }
     }
 
-    if let Some(err) = write_error {
-        tx.rollback().await.ok();
-        pending.extend(batch);
-        return Err(err);
+    Ok(())
+}
+
+async fn write_batch(db: &Database, batch: &[DroneState]) -> Result<()> {
+    let mut tx = db.pool().begin().await?;
+    for state in batch {
+        let written = match drones_db::upsert_drone_tx(&mut tx, state).await {
+            Ok(()) => history_db::insert_sample_tx(&mut tx, state).await,
+            Err(err) => Err(err),
+        };
+        if let Err(err) = written {
+            tx.rollback().await.ok();
+            return Err(err);
+        }
     }
+    tx.commit().await?;
+    Ok(())
+}
 
-    if let Err(err) = tx.commit().await {
-        pending.extend(batch);
-        return Err(err.into());
+/// Delete flown-track samples older than the retention (0 keeps them forever).
+async fn prune_history(db: &Database, retention_hours: u64) {
+    if retention_hours == 0 {
+        return;
+    }
+    let before = chrono::Utc::now() - chrono::Duration::hours(retention_hours as i64);
+    match history_db::delete_samples_before(db.pool(), before).await {
+        Ok(0) => {}
+        Ok(removed) => tracing::info!("Pruned {} telemetry history samples", removed),
+        Err(err) => tracing::warn!("Failed to prune telemetry history: {}", err),
     }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adaptive_pacing_stretches_under_load_and_relaxes_after() {
+        let mut pacing = FlushPacing {
+            interval_ms: 1_000,
+            batch_size: 100,
+            adaptive: true,
+            factor: 1,
+        };
+        let quick = Duration::from_millis(10);
+
+        pacing.observe(0.6, quick);
+        assert_eq!(pacing.interval(), Duration::from_millis(2_000));
+        assert_eq!(pacing.batch_size(), 200);
+        // A flush that overruns the interval stretches too, up to the cap.
+        pacing.observe(0.0, Duration::from_secs(3));
+        pacing.observe(0.9, quick);
+        pacing.observe(0.9, quick);
+        assert_eq!(pacing.factor, MAX_ADAPTIVE_FACTOR);
+        assert_eq!(
+            pacing.interval(),
+            Duration::from_millis(MAX_FLUSH_INTERVAL_MS)
+        );
+        assert_eq!(pacing.batch_size(), 800);
+
+        // Moderate load holds the current pacing; an idle queue relaxes it step by step.
+        pacing.observe(0.3, quick);
+        assert_eq!(pacing.factor, MAX_ADAPTIVE_FACTOR);
+        for _ in 0..5 {
+            pacing.observe(0.0, quick);
+        }
+        assert_eq!(pacing.interval(), Duration::from_millis(1_000));
+        assert_eq!(pacing.batch_size(), 100);
+
+        pacing.adaptive = false;
+        pacing.batch_size = 0;
+        pacing.observe(1.0, Duration::from_secs(5));
+        assert_eq!(pacing.interval(), Duration::from_millis(1_000));
+        assert_eq!(pacing.batch_size(), usize::MAX);
+    }
 }
@@ -0,0 +1,206 @@
+//! Terrain clearance monitoring loop.
+//!
+//! Projects airborne drones along their current track and raises DAA advisories (source
+//! `terrain`) when their height above ground is, or is about to be, below
+//! `ATC_TERRAIN_FLOOR_AGL_M`. Advisories resolve once clearance is restored.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::Client;
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+use atc_core::messages::{codes, Message};
+use atc_core::models::{DaaAdvisory, DaaSeverity, DroneStatus};
+use atc_core::terrain_clearance::{detect_terrain_conflict, projected_track};
+use atc_core::{ConflictSeverity, DronePosition, TerrainConflict};
+
+use crate::altitude::altitude_to_amsl;
+use crate::compliance::RoutePoint;
+use crate::config::Config;
+use crate::state::AppState;
+use crate::terrain::{fetch_terrain_grid, TerrainGrid};
+
+const LOOP_INTERVAL_SECS: u64 = 2;
+/// Terrain is fetched in tiles of this size (degrees) so grids are reused across ticks.
+const TERRAIN_TILE_DEG: f64 = 0.01;
+/// Below the floor at less than this ground speed, a drone is treated as taking off, landing
+/// or parked rather than flying into terrain.
+const TERMINAL_MAX_GROUND_SPEED_MPS: f64 = 2.0;
+
+type TileKey = (i64, i64);
+
+/// Start the terrain clearance loop. It idles while the AGL floor is not configured.
+pub async fn run_terrain_clearance_loop(
+    state: Arc<AppState>,
+    config: Config,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(config.terrain_request_timeout_s.max(3)))
+        .build()
+        .unwrap_or_else(|_| Client::new());
+    let mut ticker = interval(Duration::from_secs(LOOP_INTERVAL_SECS));
+    state.mark_loop_heartbeat("terrain");
+
+    loop {
+        tokio::select! {
+            _ = shutdown.recv() => {
+                tracing::info!("Terrain clearance loop shutting down");
+                break;
+            }
+            _ = ticker.tick() => {
+                state.mark_loop_heartbeat("terrain");
+                if state.loop_paused("terrain") || config.terrain_floor_agl_m <= 0.0 {
+                    continue;
+                }
+                check_terrain_clearance(&state, &config, &client).await;
+            }
+        }
+    }
+}
+
+async fn check_terrain_clearance(state: &AppState, config: &Config, client: &Client) {
+    let mut tiles: HashMap<TileKey, Option<TerrainGrid>> = HashMap::new();
+    let mut active_ids: HashSet<String> = HashSet::new();
+
+    for drone in state.get_all_drones() {
+        if !matches!(drone.status, DroneStatus::Active | DroneStatus::Holding) {
+            continue;
+        }
+        let position = DronePosition::new(
+            drone.drone_id.clone(),
+            drone.lat,
+            drone.lon,
+            altitude_to_amsl(
+                drone.altitude_m,
+                config.altitude_reference,
+                config.geoid_offset_m,
+            ),
+        )
+        .with_velocity(drone.heading_deg, drone.speed_mps, drone.velocity_z);
+
+        for (_, lat, lon, _) in projected_track(&position, config.terrain_lookahead_s) {
+            let key = tile_key(lat, lon);
+            if let Entry::Vacant(entry) = tiles.entry(key) {
+                entry.insert(fetch_tile(client, config, key).await);
+            }
+        }
+
+        // Points without terrain data are skipped rather than guessed.
+        let conflict = detect_terrain_conflict(
+            &position,
+            config.terrain_floor_agl_m,
+            config.terrain_lookahead_s,
+            |lat, lon| {
+                tiles
+                    .get(&tile_key(lat, lon))
+                    .and_then(|grid| grid.as_ref())
+                    .map(|grid| grid.sample(lat, lon))
+                    .unwrap_or(f64::NAN)
+            },
+        );
+        let Some(conflict) = conflict else {
+            continue;
+        };
+        if conflict.current_agl_m < conflict.floor_agl_m
+            && drone.speed_mps < TERMINAL_MAX_GROUND_SPEED_MPS
+        {
+            continue;
+        }
+
+        let advisory_id = format!("terrain-{}", drone.drone_id);
+        active_ids.insert(advisory_id.clone());
+        let now = Utc::now();
+        let message = describe(&conflict);
+        state.set_daa_advisory(DaaAdvisory {
+            advisory_id,
+            drone_id: drone.drone_id.clone(),
+            owner_id: drone.owner_id.clone(),
+            source: "terrain".to_string(),
+            severity: match conflict.severity {
+                ConflictSeverity::Critical => DaaSeverity::Critical,
+                ConflictSeverity::Warning => DaaSeverity::Warning,
+                ConflictSeverity::Info => DaaSeverity::Advisory,
+            },
+            action: "climb".to_string(),
+            description: state.config().messages.format(&message, None),
+            message_code: Some(message),
+            related_id: None,
+            record: None,
+            sector_id: None,
+            created_at: now,
+            updated_at: now,
+            resolved: false,
+        });
+    }
+
+    for advisory in state.get_daa_advisories() {
+        if advisory.source == "terrain"
+            && !advisory.resolved
+            && !active_ids.contains(&advisory.advisory_id)
+        {
+            state.resolve_daa_advisory(&advisory.advisory_id);
+        }
+    }
+}
+
+fn tile_key(lat: f64, lon: f64) -> TileKey {
+    (
+        (lat / TERRAIN_TILE_DEG).floor() as i64,
+        (lon / TERRAIN_TILE_DEG).floor() as i64,
+    )
+}
+
+async fn fetch_tile(client: &Client, config: &Config, key: TileKey) -> Option<TerrainGrid> {
+    let min_lat = key.0 as f64 * TERRAIN_TILE_DEG;
+    let min_lon = key.1 as f64 * TERRAIN_TILE_DEG;
+    let corners = [
+        RoutePoint {
+            lat: min_lat,
+            lon: min_lon,
+            altitude_m: 0.0,
+        },
+        RoutePoint {
+            lat: min_lat + TERRAIN_TILE_DEG,
+            lon: min_lon + TERRAIN_TILE_DEG,
+            altitude_m: 0.0,
+        },
+    ];
+    match fetch_terrain_grid(
+        client,
+        config,
+        &corners,
+        config.terrain_sample_spacing_m.max(5.0),
+    )
+    .await
+    {
+        Ok(grid) => grid,
+        Err(err) => {
+            tracing::warn!(
+                "Terrain clearance check could not fetch terrain near {:.3},{:.3}: {}",
+                min_lat,
+                min_lon,
+                err
+            );
+            None
+        }
+    }
+}
+
+fn describe(conflict: &TerrainConflict) -> Message {
+    if conflict.time_to_floor_s <= 0.0 {
+        Message::new(codes::ADVISORY_TERRAIN_BELOW_FLOOR)
+            .with("current_agl_m", conflict.current_agl_m)
+            .with("floor_agl_m", conflict.floor_agl_m)
+    } else {
+        Message::new(codes::ADVISORY_TERRAIN_PREDICTED)
+            .with("min_agl_m", conflict.min_agl_m)
+            .with("floor_agl_m", conflict.floor_agl_m)
+            .with("time_to_floor_s", conflict.time_to_floor_s)
+    }
+}
@@ -0,0 +1,90 @@
+//! TFR ingestion loop.
+//!
+//! Pulls `ATC_TFR_FEED_URL` every `ATC_TFR_REFRESH_SECS` and replaces the `tfr:` external
+//! geofences with the restrictions it lists. The loop idles while no feed is configured; a
+//! failed pull keeps the previous restrictions.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use reqwest::Client;
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+use crate::config::Config;
+use crate::state::AppState;
+use crate::tfr::{parse_feed, TfrFeed, TFR_SOURCE};
+
+const LOOP_INTERVAL_SECS: u64 = 30;
+const REQUEST_TIMEOUT_SECS: u64 = 20;
+
+/// Start the TFR loop.
+pub async fn run_tfr_loop(
+    state: Arc<AppState>,
+    config: Config,
+    mut shutdown: broadcast::Receiver<()>,
+) {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .unwrap_or_else(|_| Client::new());
+    let mut ticker = interval(Duration::from_secs(LOOP_INTERVAL_SECS));
+    let refresh = Duration::from_secs(config.tfr_refresh_secs);
+    let mut last_attempt: Option<Instant> = None;
+    state.mark_loop_heartbeat("tfr");
+
+    loop {
+        tokio::select! {
+            _ = shutdown.recv() => {
+                tracing::info!("TFR loop shutting down");
+                break;
+            }
+            _ = ticker.tick() => {
+                state.mark_loop_heartbeat("tfr");
+                let Some(url) = config.tfr_feed_url.as_deref() else {
+                    continue;
+                };
+                if state.loop_paused("tfr")
+                    || last_attempt.is_some_and(|at| at.elapsed() < refresh)
+                {
+                    continue;
+                }
+                last_attempt = Some(Instant::now());
+                match fetch_tfrs(&client, &config, url).await {
+                    Ok(feed) => {
+                        tracing::info!(
+                            "Loaded {} TFR geofences ({} feed entries skipped)",
+                            feed.geofences.len(),
+                            feed.skipped
+                        );
+                        state.set_external_geofences(TFR_SOURCE, feed.geofences);
+                    }
+                    Err(err) => tracing::warn!("TFR feed pull failed: {}", err),
+                }
+            }
+        }
+    }
+}
+
+async fn fetch_tfrs(client: &Client, config: &Config, url: &str) -> Result<TfrFeed, String> {
+    let mut request = client.get(url);
+    // The FAA NOTAM API authenticates with these headers.
+    if let (Some(client_id), Some(client_secret)) = (
+        config.tfr_feed_client_id.as_deref(),
+        config.tfr_feed_client_secret.as_deref(),
+    ) {
+        request = request
+            .header("client_id", client_id)
+            .header("client_secret", client_secret);
+    }
+    let document: serde_json::Value = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| err.to_string())?
+        .json()
+        .await
+        .map_err(|err| err.to_string())?;
+    parse_feed(config.tfr_feed_format, &document, Utc::now())
+}
@@ -0,0 +1,39 @@
+//! Drone session token expiry loop.
+//!
+//! Drops tokens past their TTL (plus the in-flight grace period) and clears
+//! rotation overlaps once they lapse.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+use crate::state::AppState;
+
+const LOOP_INTERVAL_SECS: u64 = 30;
+
+pub async fn run_token_expiry_loop(state: Arc<AppState>, mut shutdown: broadcast::Receiver<()>) {
+    let mut ticker = interval(Duration::from_secs(LOOP_INTERVAL_SECS));
+    state.mark_loop_heartbeat("token-expiry");
+
+    loop {
+        tokio::select! {
+            _ = shutdown.recv() => {
+                tracing::info!("Token expiry loop shutting down");
+                break;
+            }
+            _ = ticker.tick() => {
+                state.mark_loop_heartbeat("token-expiry");
+                if state.loop_paused("token-expiry") {
+                    continue;
+                }
+                let expired = state.expire_drone_tokens(Utc::now()).await;
+                for drone_id in expired {
+                    tracing::info!("Session token for drone {} expired", drone_id);
+                }
+            }
+        }
+    }
+}
@@ -1,17 +1,46 @@
 //! ATC Server - Always-on backend for drone traffic management
 
+mod adsb;
 mod altitude;
 mod api;
 mod backoff;
 mod blender_auth;
+mod breach;
 mod cache;
+mod chaos;
+mod command_signing;
 mod compliance;
 mod config;
+mod conformance_policy;
+mod fairness;
+mod grpc;
+mod jobs;
+mod lifecycle;
 mod loops;
+mod metering;
+mod obstacle_index;
+mod organizations;
 mod persistence;
+mod planner_pool;
+mod reconcile;
+mod rejection;
+mod replan;
+mod route_graph;
 mod route_planner;
+mod secrets;
+mod sectors;
 mod state;
+mod surveillance;
+mod telemetry_auth;
 mod terrain;
+mod tether;
+mod tfr;
+mod throttle;
+mod track_index;
+mod warmup;
+mod webhooks;
+mod wind;
+mod wpml;
 
 use anyhow::{bail, Result};
 use axum::http::StatusCode;
@@ -38,11 +67,17 @@ struct LoopStatus {
     age_secs: u64,
     max_age_secs: u64,
     last_tick_secs: Option<u64>,
+    /// Paused by an operator; paused loops still tick and do not fail readiness.
+    paused: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    paused_until: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Serialize)]
 struct ReadyResponse {
     ok: bool,
+    /// `starting`, `serving` or `draining`; only `serving` is ready.
+    phase: lifecycle::LifecyclePhase,
     db_ok: bool,
     loops_ok: bool,
     db_latency_ms: Option<u128>,
@@ -56,16 +91,23 @@ async fn ready_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse
         .map(|d| d.as_secs())
         .unwrap_or(0);
 
-    let loop_limits: [(&'static str, u64); 9] = [
+    let loop_limits: [(&'static str, u64); 16] = [
         ("conflict", 5),
         ("blender-sync", 5),
         ("telemetry-persist", 10),
         ("rid", 10),
+        ("adsb", 120),
         ("mission", 10),
         ("oi-expiry", 20),
         ("conformance", 45),
+        ("token-expiry", 90),
         ("geofence-sync", 60),
+        ("tfr", 120),
         ("flight-declaration-sync", 120),
+        ("metering", 180),
+        ("terrain", 120),
+        ("msa", 600),
+        ("replan", 120),
     ];
 
     let mut loops = Vec::with_capacity(loop_limits.len());
@@ -82,12 +124,15 @@ async fn ready_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse
         if !ok {
             loops_ok = false;
         }
+        let paused_until = state.loop_pause(name).map(|pause| pause.resume_at);
         loops.push(LoopStatus {
             name,
             ok,
             age_secs,
             max_age_secs,
             last_tick_secs,
+            paused: paused_until.is_some(),
+            paused_until,
         });
     }
 
@@ -116,14 +161,19 @@ async fn ready_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse
         None => (true, None, None),
     };
 
-    let ok = db_ok && loops_ok;
+    let phase = state.lifecycle().phase();
+    let ok = db_ok && loops_ok && phase == lifecycle::LifecyclePhase::Serving;
     let status = if ok {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
     };
 
-    let error = if let Some(err) = db_error {
+    let error = if phase == lifecycle::LifecyclePhase::Starting {
+        Some("server is starting".to_string())
+    } else if phase == lifecycle::LifecyclePhase::Draining {
+        Some("server is draining for shutdown".to_string())
+    } else if let Some(err) = db_error {
         Some(err)
     } else if !loops_ok {
         let stale = loops
@@ -141,6 +191,7 @@ async fn ready_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse
         status,
         Json(ReadyResponse {
             ok,
+            phase,
             db_ok,
             loops_ok,
             db_latency_ms,
@@ -150,8 +201,6 @@ async fn ready_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse
     )
 }
 
-const MAX_REQUEST_BODY_BYTES: usize = 1 * 1024 * 1024; // 1 MiB
-
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize tracing
@@ -172,48 +221,110 @@ async fn main() -> Result<()> {
     let config = Config::from_env();
     let port = config.server_port;
 
-    if config.require_registration_token && config.registration_token.is_none() {
+    if config.secrets.is_enabled() {
+        let loaded = config.secrets.refresh().await?;
+        tracing::info!(
+            "Loaded {} secrets from {:?}",
+            loaded.len(),
+            config.secrets_backend
+        );
+    }
+
+    if std::env::args().skip(1).any(|arg| arg == "--migrate-only") {
+        tracing::info!("Migrating database");
+        let db = persistence::init_database(config.database_location(), 1).await?;
+        let status = persistence::schema_status(db.pool()).await?;
+        tracing::info!(
+            "Database schema at version {} (release supports {})",
+            status.database_version.unwrap_or_default(),
+            status.supported_version
+        );
+        return Ok(());
+    }
+
+    if config.require_registration_token && config.registration_token().is_none() {
         bail!("ATC_REGISTRATION_TOKEN is required when ATC_REQUIRE_REGISTRATION_TOKEN is enabled");
     }
     if !config.allow_dummy_blender_auth
-        && config.blender_auth_token.trim().is_empty()
+        && config.blender_auth_token().trim().is_empty()
         && config.blender_oauth_config().is_none()
     {
         bail!("BLENDER_AUTH_TOKEN or BLENDER_OAUTH_* is required when ATC_ENV is not development");
     }
-    if !config.allow_dummy_blender_auth && config.admin_token.trim().is_empty() {
+    if !config.allow_dummy_blender_auth && config.admin_token().trim().is_empty() {
         bail!("ATC_ADMIN_TOKEN must be set when ATC_ENV is not development");
     }
-    if !config.allow_dummy_blender_auth && config.admin_token == "change-me-admin" {
+    if !config.allow_dummy_blender_auth && config.admin_token() == "change-me-admin" {
         bail!("ATC_ADMIN_TOKEN is still set to the insecure default 'change-me-admin'");
     }
     if !config.allow_dummy_blender_auth {
-        if let Some(token) = config.registration_token.as_deref() {
+        if let Some(token) = config.registration_token().as_deref() {
             if token == "change-me-registration-token" {
                 bail!("ATC_REGISTRATION_TOKEN is still set to the insecure default 'change-me-registration-token'");
             }
         }
 
-        if config.require_ws_token && config.ws_token.is_none() {
+        if config.require_ws_token && config.ws_token().is_none() {
             bail!("ATC_WS_TOKEN must be set when ATC_REQUIRE_WS_TOKEN is enabled");
         }
-        if let Some(token) = config.ws_token.as_deref() {
+        if let Some(token) = config.ws_token().as_deref() {
             if token == "change-me-ws-token" {
                 bail!("ATC_WS_TOKEN is still set to the insecure default 'change-me-ws-token'");
             }
         }
     }
 
+    if let Some(seed) = config.command_signing_key.as_deref() {
+        let signer = command_signing::CommandSigner::from_seed_base64(seed)?;
+        tracing::info!(
+            "Command signing enabled (key id {})",
+            signer.signing_key().key_id
+        );
+    }
+
     // Initialize database
-    tracing::info!("Initializing database: {}", config.database_path);
-    let db =
-        persistence::init_database(&config.database_path, config.database_max_connections).await?;
+    tracing::info!("Initializing database");
+    let db = persistence::open_database(
+        config.database_location(),
+        config.database_max_connections,
+        config.database_auto_migrate,
+    )
+    .await?
+    .with_read_pool(
+        config.database_location(),
+        config.database_read_replica_path.as_deref(),
+        config.database_read_max_connections,
+        Duration::from_millis(config.database_read_timeout_ms),
+    )
+    .await?;
     tracing::info!("Database initialized successfully");
 
     // Create application state with database
     let state = Arc::new(AppState::with_database(db, config.clone()));
     state.set_rid_view_bbox(config.rid_view_bbox.clone());
+    let (shutdown_tx, _) = broadcast::channel(1);
+
+    // Serve before the state is loaded so the startup probe can report progress; the lifecycle
+    // gate answers 503 to everything but the probes until startup completes.
+    let app = build_app(&config, state.clone());
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let shutdown = shutdown_signal(state.clone(), config.lame_duck_secs, shutdown_tx.clone());
+    let server = spawn_server(&config, app, addr, shutdown).await?;
+    let grpc_server = match config.grpc_port {
+        Some(grpc_port) => Some(
+            grpc::spawn_server(
+                &config,
+                state.clone(),
+                SocketAddr::from(([0, 0, 0, 0], grpc_port)),
+                shutdown_tx.subscribe(),
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
     state.load_from_database().await?;
+    state.lifecycle().mark_database_loaded();
 
     // Log security config
     tracing::info!("Admin token loaded");
@@ -228,7 +339,16 @@ async fn main() -> Result<()> {
     );
     tracing::info!("CORS origins: {:?}", config.allowed_origins);
 
-    let (shutdown_tx, _) = broadcast::channel(1);
+    #[cfg(feature = "chaos")]
+    {
+        tracing::warn!("Chaos fault injection enabled (configure via /v1/admin/chaos)");
+        chaos::install_blender_hook();
+    }
+
+    if config.blender_startup_reconcile {
+        reconcile::reconcile_with_blender(&state, &config).await;
+    }
+    state.lifecycle().mark_blender_reconciled();
 
     if let (Some(db), Some(telemetry_rx)) =
         (state.database().cloned(), state.take_telemetry_receiver())
@@ -246,6 +366,12 @@ async fn main() -> Result<()> {
         });
     }
 
+    // Prefetch planner obstacles and terrain so the first plans in an operating area are warm.
+    warmup::restore_snapshot(&config);
+    if config.planner_warmup_on_startup && !config.operating_areas.is_empty() {
+        warmup::enqueue(&state, warmup::WarmupTrigger::Startup).await;
+    }
+
     // Start background loops with supervision
     {
         let state = state.clone();
@@ -267,6 +393,18 @@ async fn main() -> Result<()> {
             loops::mission_loop::run_mission_loop(state.clone(), shutdown)
         });
     }
+    {
+        let state = state.clone();
+        spawn_supervised_loop("replan", shutdown_tx.clone(), move |shutdown| {
+            loops::replan_loop::run_replan_loop(state.clone(), shutdown)
+        });
+    }
+    {
+        let state = state.clone();
+        spawn_supervised_loop("jobs", shutdown_tx.clone(), move |shutdown| {
+            loops::job_loop::run_job_loop(state.clone(), shutdown)
+        });
+    }
     {
         let state = state.clone();
         spawn_supervised_loop("oi-expiry", shutdown_tx.clone(), move |shutdown| {
@@ -276,6 +414,47 @@ async fn main() -> Result<()> {
             )
         });
     }
+    if config.secrets.is_enabled() {
+        let state = state.clone();
+        let config = config.clone();
+        spawn_supervised_loop("secrets-refresh", shutdown_tx.clone(), move |shutdown| {
+            loops::secrets_refresh_loop::run_secrets_refresh_loop(
+                state.clone(),
+                config.clone(),
+                shutdown,
+            )
+        });
+    }
+    {
+        let state = state.clone();
+        spawn_supervised_loop("token-expiry", shutdown_tx.clone(), move |shutdown| {
+            loops::token_expiry_loop::run_token_expiry_loop(state.clone(), shutdown)
+        });
+    }
+    {
+        let state = state.clone();
+        spawn_supervised_loop("metering", shutdown_tx.clone(), move |shutdown| {
+            loops::metering_loop::run_metering_loop(state.clone(), shutdown)
+        });
+    }
+    {
+        let state = state.clone();
+        let config = config.clone();
+        spawn_supervised_loop("terrain", shutdown_tx.clone(), move |shutdown| {
+            loops::terrain_clearance_loop::run_terrain_clearance_loop(
+                state.clone(),
+                config.clone(),
+                shutdown,
+            )
+        });
+    }
+    {
+        let state = state.clone();
+        let config = config.clone();
+        spawn_supervised_loop("msa", shutdown_tx.clone(), move |shutdown| {
+            loops::msa_loop::run_msa_loop(state.clone(), config.clone(), shutdown)
+        });
+    }
     {
         let state = state.clone();
         let config = config.clone();
@@ -283,6 +462,13 @@ async fn main() -> Result<()> {
             loops::rid_sync_loop::run_rid_loop(state.clone(), config.clone(), shutdown)
         });
     }
+    {
+        let state = state.clone();
+        let config = config.clone();
+        spawn_supervised_loop("adsb", shutdown_tx.clone(), move |shutdown| {
+            loops::adsb_sync_loop::run_adsb_loop(state.clone(), config.clone(), shutdown)
+        });
+    }
     {
         let state = state.clone();
         let config = config.clone();
@@ -294,6 +480,13 @@ async fn main() -> Result<()> {
             )
         });
     }
+    {
+        let state = state.clone();
+        let config = config.clone();
+        spawn_supervised_loop("tfr", shutdown_tx.clone(), move |shutdown| {
+            loops::tfr_loop::run_tfr_loop(state.clone(), config.clone(), shutdown)
+        });
+    }
     {
         let state = state.clone();
         let config = config.clone();
@@ -317,15 +510,35 @@ async fn main() -> Result<()> {
         });
     }
 
-    // Build CORS layer
-    // Build the app
-    let app = api::routes(&config)
+    server.await??;
+    if let Some(grpc_server) = grpc_server {
+        grpc_server.await??;
+    }
+    Ok(())
+}
+
+/// Build the router with probes, metering, the lifecycle gate and CORS.
+fn build_app(config: &Config, state: Arc<AppState>) -> axum::Router {
+    let app = api::routes(config)
         .route("/health", get(|| async { "OK" }))
         .route("/ready", get(ready_handler))
+        .route("/startupz", get(lifecycle::startup_handler))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            metering::track_api_calls,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            api::auth::identify_caller,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            lifecycle::gate_requests,
+        ))
         .with_state(state)
-        .layer(DefaultBodyLimit::max(MAX_REQUEST_BODY_BYTES));
+        .layer(DefaultBodyLimit::max(api::units::MAX_REQUEST_BODY_BYTES));
 
-    let app = if config.allowed_origins.is_empty() {
+    if config.allowed_origins.is_empty() {
         tracing::warn!("No CORS origins configured - CORS disabled (same-origin only)");
         app
     } else {
@@ -340,43 +553,76 @@ async fn main() -> Result<()> {
                 .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
                 .allow_headers(Any),
         )
-    };
-
-    // Run server
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    tracing::info!("Listening on {}", addr);
+    }
+}
 
+/// Bind the listener (TLS when configured) and serve `app` in the background until `shutdown`.
+async fn spawn_server(
+    config: &Config,
+    app: axum::Router,
+    addr: SocketAddr,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<tokio::task::JoinHandle<Result<()>>> {
     if let (Some(cert_path), Some(key_path)) = (&config.tls_cert_path, &config.tls_key_path) {
         let tls_config = RustlsConfig::from_pem_file(cert_path, key_path).await?;
         let handle = axum_server::Handle::new();
         let server = axum_server::bind_rustls(addr, tls_config)
             .handle(handle.clone())
             .serve(app.into_make_service_with_connect_info::<SocketAddr>());
-        let shutdown = shutdown_signal(shutdown_tx.clone());
-
-        tokio::select! {
-            result = server => result?,
-            _ = shutdown => {
-                handle.graceful_shutdown(Some(Duration::from_secs(10)));
+        tracing::info!("Listening on {}", addr);
+        return Ok(tokio::spawn(async move {
+            tokio::select! {
+                result = server => result?,
+                _ = shutdown => {
+                    handle.graceful_shutdown(Some(Duration::from_secs(10)));
+                }
             }
-        }
-    } else if config.require_tls {
+            Ok(())
+        }));
+    }
+    if config.require_tls {
         bail!("TLS required but ATC_TLS_CERT_PATH/ATC_TLS_KEY_PATH not set");
-    } else {
-        let listener = tokio::net::TcpListener::bind(addr).await?;
+    }
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("Listening on {}", addr);
+    Ok(tokio::spawn(async move {
         axum::serve(
             listener,
             app.into_make_service_with_connect_info::<SocketAddr>(),
         )
-        .with_graceful_shutdown(shutdown_signal(shutdown_tx.clone()))
+        .with_graceful_shutdown(shutdown)
         .await?;
-    }
+        Ok(())
+    }))
+}
 
-    Ok(())
+/// Wait for a shutdown signal, hold the lame-duck window, then stop the loops and the server.
+async fn shutdown_signal(
+    state: Arc<AppState>,
+    lame_duck_secs: u64,
+    shutdown_tx: broadcast::Sender<()>,
+) {
+    wait_for_signal().await;
+    tracing::info!("Shutdown signal received");
+    if lame_duck_secs > 0 {
+        state.lifecycle().begin_draining();
+        tracing::info!(
+            "Lame duck for {}s: accepting telemetry, refusing planning",
+            lame_duck_secs
+        );
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(lame_duck_secs)) => {}
+            _ = wait_for_signal() => {
+                tracing::info!("Second shutdown signal received; ending lame duck early");
+            }
+        }
+    }
+    let _ = shutdown_tx.send(());
 }
 
 #[cfg(unix)]
-async fn shutdown_signal(shutdown_tx: broadcast::Sender<()>) {
+async fn wait_for_signal() {
     match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
         Ok(mut sigterm) => {
             tokio::select! {
@@ -389,8 +635,6 @@ async fn shutdown_signal(shutdown_tx: broadcast::Sender<()>) {
             let _ = tokio::signal::ctrl_c().await;
         }
     }
-    tracing::info!("Shutdown signal received");
-    let _ = shutdown_tx.send(());
 }
 
 fn spawn_supervised_loop<F, Fut>(
@@ -432,8 +676,6 @@ fn spawn_supervised_loop<F, Fut>(
 }
 
 #[cfg(not(unix))]
-async fn shutdown_signal(shutdown_tx: broadcast::Sender<()>) {
+async fn wait_for_signal() {
     let _ = tokio::signal::ctrl_c().await;
-    tracing::info!("Shutdown signal received");
-    let _ = shutdown_tx.send(());
 }
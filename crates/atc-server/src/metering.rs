@@ -0,0 +1,276 @@
+//! Per-organization usage metering for billing.
+//!
+//! Usage is attributed to the operator (`owner_id`) and accumulated into calendar-month (UTC)
+//! records: flight hours from live telemetry, flight plans submitted for scheduling, route
+//! planner compute time, and API calls. Records live in memory and are flushed to the database
+//! periodically by the metering loop.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{HeaderMap, Uri},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use dashmap::{DashMap, DashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::api::auth::extract_drone_token;
+use crate::state::AppState;
+
+/// Organization used when a request or drone has no owner.
+pub const UNATTRIBUTED: &str = "unattributed";
+
+/// Telemetry gaps longer than this are not counted as flight time.
+const MAX_FLIGHT_SAMPLE_GAP_SECS: f64 = 30.0;
+
+/// Usage for one organization in one month.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub organization: String,
+    /// Calendar month, `YYYY-MM` (UTC).
+    pub month: String,
+    pub flight_hours: f64,
+    pub plans_submitted: u64,
+    pub planner_cpu_seconds: f64,
+    pub api_calls: u64,
+}
+
+pub fn month_key(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m").to_string()
+}
+
+fn organization_key(organization: Option<&str>) -> String {
+    organization
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(UNATTRIBUTED)
+        .to_string()
+}
+
+/// Accumulates usage records and remembers which changed since the last flush.
+#[derive(Default)]
+pub struct UsageMeter {
+    records: DashMap<(String, String), UsageRecord>,
+    dirty: DashSet<(String, String)>,
+    /// Last airborne telemetry time per drone, for flight-time deltas.
+    last_airborne: DashMap<String, DateTime<Utc>>,
+}
+
+impl UsageMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn update(
+        &self,
+        organization: Option<&str>,
+        at: DateTime<Utc>,
+        apply: impl FnOnce(&mut UsageRecord),
+    ) {
+        let key = (organization_key(organization), month_key(at));
+        let mut record = self
+            .records
+            .entry(key.clone())
+            .or_insert_with(|| UsageRecord {
+                organization: key.0.clone(),
+                month: key.1.clone(),
+                ..Default::default()
+            });
+        apply(&mut record);
+        self.dirty.insert(key);
+    }
+
+    /// Count flight time between consecutive airborne telemetry samples of a drone.
+    pub fn record_flight_sample(
+        &self,
+        organization: Option<&str>,
+        drone_id: &str,
+        airborne: bool,
+        at: DateTime<Utc>,
+    ) {
+        if !airborne {
+            self.last_airborne.remove(drone_id);
+            return;
+        }
+        let previous = self.last_airborne.insert(drone_id.to_string(), at);
+        let Some(previous) = previous else {
+            return;
+        };
+        let gap_secs = (at - previous).num_milliseconds() as f64 / 1000.0;
+        if gap_secs <= 0.0 || gap_secs > MAX_FLIGHT_SAMPLE_GAP_SECS {
+            return;
+        }
+        self.update(organization, at, |record| {
+            record.flight_hours += gap_secs / 3600.0;
+        });
+    }
+
+    pub fn record_plan_submitted(&self, organization: Option<&str>, at: DateTime<Utc>) {
+        self.update(organization, at, |record| record.plans_submitted += 1);
+    }
+
+    pub fn record_planner_cpu(&self, organization: Option<&str>, seconds: f64, at: DateTime<Utc>) {
+        if seconds <= 0.0 || !seconds.is_finite() {
+            return;
+        }
+        self.update(organization, at, |record| {
+            record.planner_cpu_seconds += seconds;
+        });
+    }
+
+    pub fn record_api_call(&self, organization: Option<&str>, at: DateTime<Utc>) {
+        self.update(organization, at, |record| record.api_calls += 1);
+    }
+
+    /// Stop tracking a drone's flight samples (e.g. after it is removed).
+    pub fn forget_drone(&self, drone_id: &str) {
+        self.last_airborne.remove(drone_id);
+    }
+
+    /// Records matching the filters, sorted by month then organization.
+    pub fn usage(&self, organization: Option<&str>, month: Option<&str>) -> Vec<UsageRecord> {
+        let mut records: Vec<UsageRecord> = self
+            .records
+            .iter()
+            .filter(|entry| organization.is_none_or(|org| entry.key().0 == org))
+            .filter(|entry| month.is_none_or(|month| entry.key().1 == month))
+            .map(|entry| entry.value().clone())
+            .collect();
+        records.sort_by(|a, b| (&a.month, &a.organization).cmp(&(&b.month, &b.organization)));
+        records
+    }
+
+    /// Records changed since the last call, for persistence.
+    pub fn take_dirty(&self) -> Vec<UsageRecord> {
+        let keys: Vec<(String, String)> = self.dirty.iter().map(|key| key.clone()).collect();
+        keys.into_iter()
+            .filter_map(|key| {
+                self.dirty.remove(&key);
+                self.records.get(&key).map(|record| record.value().clone())
+            })
+            .collect()
+    }
+
+    /// Mark records dirty again after a failed flush.
+    pub fn mark_dirty(&self, records: &[UsageRecord]) {
+        for record in records {
+            self.dirty
+                .insert((record.organization.clone(), record.month.clone()));
+        }
+    }
+
+    /// Replace in-memory records with persisted ones (on startup).
+    pub fn restore(&self, records: Vec<UsageRecord>) {
+        self.records.clear();
+        self.dirty.clear();
+        for record in records {
+            self.records
+                .insert((record.organization.clone(), record.month.clone()), record);
+        }
+    }
+}
+
+/// Usage records as CSV with a header row.
+pub fn usage_csv(records: &[UsageRecord]) -> String {
+    let mut csv = String::from(
+        "organization,month,flight_hours,plans_submitted,planner_cpu_seconds,api_calls\n",
+    );
+    for record in records {
+        csv.push_str(&format!(
+            "{},{},{:.4},{},{:.3},{}\n",
+            csv_field(&record.organization),
+            record.month,
+            record.flight_hours,
+            record.plans_submitted,
+            record.planner_cpu_seconds,
+            record.api_calls
+        ));
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Organization a request is billed to: the owner of the calling drone, else the `owner_id`
+/// query parameter.
+pub fn request_organization(state: &AppState, headers: &HeaderMap, uri: &Uri) -> Option<String> {
+    let drone_owner = extract_drone_token(headers)
+        .and_then(|token| state.drone_id_for_token(&token))
+        .and_then(|drone_id| state.get_drone(&drone_id))
+        .and_then(|drone| drone.owner_id);
+    drone_owner.or_else(|| {
+        Query::<HashMap<String, String>>::try_from_uri(uri)
+            .ok()
+            .and_then(|Query(mut params)| params.remove("owner_id"))
+            .filter(|value| !value.trim().is_empty())
+    })
+}
+
+/// Middleware counting API calls per organization.
+pub async fn track_api_calls(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.uri().path().starts_with("/v1/") {
+        let organization = request_organization(&state, request.headers(), request.uri());
+        state
+            .usage_meter()
+            .record_api_call(organization.as_deref(), Utc::now());
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn accumulates_usage_per_organization_and_month() {
+        let meter = UsageMeter::new();
+        let t0 = Utc.with_ymd_and_hms(2026, 1, 31, 23, 59, 0).unwrap();
+
+        meter.record_flight_sample(Some("acme"), "D1", true, t0);
+        meter.record_flight_sample(Some("acme"), "D1", true, t0 + chrono::Duration::seconds(18));
+        // Rolls into February; a long gap is not counted as flight time.
+        meter.record_flight_sample(Some("acme"), "D1", true, t0 + chrono::Duration::seconds(90));
+        meter.record_flight_sample(
+            Some("acme"),
+            "D1",
+            true,
+            t0 + chrono::Duration::seconds(108),
+        );
+        meter.record_plan_submitted(Some("acme"), t0);
+        meter.record_planner_cpu(Some("acme"), 1.5, t0);
+        meter.record_api_call(None, t0);
+
+        let january = meter.usage(None, Some("2026-01"));
+        assert_eq!(january.len(), 2);
+        let acme = january.iter().find(|r| r.organization == "acme").unwrap();
+        assert!((acme.flight_hours - 18.0 / 3600.0).abs() < 1e-9);
+        assert_eq!(acme.plans_submitted, 1);
+        assert_eq!(acme.planner_cpu_seconds, 1.5);
+        assert_eq!(meter.usage(Some(UNATTRIBUTED), None)[0].api_calls, 1);
+
+        let february = meter.usage(Some("acme"), Some("2026-02"));
+        assert!((february[0].flight_hours - 18.0 / 3600.0).abs() < 1e-9);
+
+        assert_eq!(meter.take_dirty().len(), 3);
+        assert!(meter.take_dirty().is_empty());
+
+        let csv = usage_csv(&january);
+        assert!(csv.starts_with("organization,month,"));
+        assert!(csv.contains("acme,2026-01,0.0050,1,1.500,0"));
+    }
+}
@@ -0,0 +1,354 @@
+//! Shared obstacle index for segmented route planning.
+//!
+//! Long routes are planned segment by segment, and the corridors of neighbouring segments (and of
+//! the shorter segments retried after a truncated dataset) overlap. Instead of every segment
+//! asking Overpass for its own bounding box, obstacles are fetched once per grid-aligned tile
+//! into an index shared by the whole plan, and each segment filters the tiles it overlaps
+//! locally. Aligned tiles also hit the obstacle cache for other routes crossing the same area. A
+//! tile whose dataset came back truncated is split into quadrants, down to a minimum size.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use futures::future::try_join_all;
+use reqwest::Client;
+use tokio::sync::OnceCell;
+
+use crate::compliance::{
+    compute_bounds, distance_to_route_meters, expand_bounds_by_meters, fetch_obstacles_in_bounds,
+    obstacle_max_distance_m, Bounds, ObstacleCandidate, ObstacleQueryMode, RoutePoint,
+};
+use crate::config::Config;
+
+/// Top-level tiles are this many degrees across (about 2.8 km north-south).
+const TILE_DEG: f64 = 0.025;
+/// Truncated tiles are split into quadrants at most this many times.
+const MAX_TILE_DEPTH: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct TileKey {
+    depth: u8,
+    lat: i64,
+    lon: i64,
+}
+
+impl TileKey {
+    fn size_deg(depth: u8) -> f64 {
+        TILE_DEG / f64::from(1u32 << depth)
+    }
+
+    fn bounds(self) -> Bounds {
+        let size = Self::size_deg(self.depth);
+        Bounds {
+            min_lat: self.lat as f64 * size,
+            max_lat: (self.lat + 1) as f64 * size,
+            min_lon: self.lon as f64 * size,
+            max_lon: (self.lon + 1) as f64 * size,
+        }
+    }
+
+    fn children(self) -> [TileKey; 4] {
+        let depth = self.depth + 1;
+        let (lat, lon) = (self.lat * 2, self.lon * 2);
+        [
+            TileKey { depth, lat, lon },
+            TileKey {
+                depth,
+                lat,
+                lon: lon + 1,
+            },
+            TileKey {
+                depth,
+                lat: lat + 1,
+                lon,
+            },
+            TileKey {
+                depth,
+                lat: lat + 1,
+                lon: lon + 1,
+            },
+        ]
+    }
+}
+
+/// Top-level tiles overlapping `bounds`.
+fn tiles_covering(bounds: &Bounds) -> Vec<TileKey> {
+    let index = |deg: f64| (deg / TILE_DEG).floor() as i64;
+    let mut tiles = Vec::new();
+    for lat in index(bounds.min_lat)..=index(bounds.max_lat) {
+        for lon in index(bounds.min_lon)..=index(bounds.max_lon) {
+            tiles.push(TileKey { depth: 0, lat, lon });
+        }
+    }
+    tiles
+}
+
+fn intersects(a: &Bounds, b: &Bounds) -> bool {
+    a.min_lat <= b.max_lat
+        && b.min_lat <= a.max_lat
+        && a.min_lon <= b.max_lon
+        && b.min_lon <= a.max_lon
+}
+
+#[derive(Debug)]
+struct Tile {
+    candidates: Vec<ObstacleCandidate>,
+    truncated: bool,
+}
+
+type TileCell = Arc<OnceCell<Result<Arc<Tile>, String>>>;
+
+/// Tiles fetched by [`prefetch_area`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct AreaPrefetch {
+    pub tiles: usize,
+    pub failed: usize,
+}
+
+/// Fetch the tiles covering `area` into the obstacle cache, splitting truncated tiles the way a
+/// route plan would. Tiles are fetched one at a time so a large area does not flood Overpass; a
+/// failed tile is counted and skipped.
+pub(crate) async fn prefetch_area(
+    client: &Client,
+    config: &Config,
+    area: &Bounds,
+    clearance_m: f64,
+) -> AreaPrefetch {
+    let index = CorridorObstacles::new(client.clone(), config.clone(), clearance_m);
+    let mut prefetch = AreaPrefetch::default();
+    let mut pending = tiles_covering(area);
+    while !pending.is_empty() {
+        let mut split = Vec::new();
+        for key in pending {
+            match index.tile(key).await {
+                Ok(tile) if tile.truncated && key.depth < MAX_TILE_DEPTH => split.extend(
+                    key.children()
+                        .into_iter()
+                        .filter(|child| intersects(&child.bounds(), area)),
+                ),
+                Ok(_) => prefetch.tiles += 1,
+                Err(err) => {
+                    tracing::warn!(?key, "Obstacle tile prefetch failed: {}", err);
+                    prefetch.failed += 1;
+                }
+            }
+        }
+        pending = split;
+    }
+    prefetch
+}
+
+/// Obstacles near one segment, nearest first.
+#[derive(Debug)]
+pub(crate) struct SegmentObstacles {
+    pub candidates: Vec<ObstacleCandidate>,
+    /// Some tile was still truncated at the minimum tile size, or the segment has more
+    /// obstacles than `ATC_COMPLIANCE_MAX_OVERPASS_ELEMENTS`.
+    pub truncated: bool,
+}
+
+/// Obstacle tiles fetched so far for one route plan.
+#[derive(Debug)]
+pub(crate) struct CorridorObstacles {
+    client: Client,
+    config: Config,
+    clearance_m: f64,
+    tiles: DashMap<TileKey, TileCell>,
+}
+
+impl CorridorObstacles {
+    pub(crate) fn new(client: Client, config: Config, clearance_m: f64) -> Self {
+        Self {
+            client,
+            config,
+            clearance_m,
+            tiles: DashMap::new(),
+        }
+    }
+
+    /// Obstacles within `corridor_radius_m` of `points`, fetching the tiles not yet indexed.
+    pub(crate) async fn query(
+        &self,
+        points: &[RoutePoint],
+        corridor_radius_m: f64,
+    ) -> Result<SegmentObstacles, String> {
+        let base_bounds = compute_bounds(points).ok_or("invalid route bounds")?;
+        let bounds = expand_bounds_by_meters(&base_bounds, corridor_radius_m * 1.2);
+        let max_distance = obstacle_max_distance_m(self.clearance_m, Some(corridor_radius_m));
+
+        let mut candidates: Vec<ObstacleCandidate> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut truncated = false;
+        let mut pending = tiles_covering(&bounds);
+        while !pending.is_empty() {
+            let tiles = try_join_all(pending.iter().map(|key| self.tile(*key))).await?;
+            let mut split = Vec::new();
+            for (key, tile) in pending.into_iter().zip(tiles) {
+                if tile.truncated && key.depth < MAX_TILE_DEPTH {
+                    split.extend(
+                        key.children()
+                            .into_iter()
+                            .filter(|child| intersects(&child.bounds(), &bounds)),
+                    );
+                    continue;
+                }
+                truncated |= tile.truncated;
+                for candidate in &tile.candidates {
+                    let distance_m = distance_to_route_meters(candidate.lat, candidate.lon, points);
+                    if distance_m > max_distance || !seen.insert(candidate.id.clone()) {
+                        continue;
+                    }
+                    candidates.push(ObstacleCandidate {
+                        distance_m: distance_m.is_finite().then_some(distance_m),
+                        ..candidate.clone()
+                    });
+                }
+            }
+            pending = split;
+        }
+
+        candidates.sort_by(|a, b| {
+            a.distance_m
+                .partial_cmp(&b.distance_m)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        if candidates.len() > self.config.compliance_max_overpass_elements {
+            candidates.truncate(self.config.compliance_max_overpass_elements);
+            truncated = true;
+        }
+        Ok(SegmentObstacles {
+            candidates,
+            truncated,
+        })
+    }
+
+    /// Number of tiles fetched (or being fetched) for this plan.
+    pub(crate) fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// The tile's obstacles; concurrent segments wait on a single fetch.
+    async fn tile(&self, key: TileKey) -> Result<Arc<Tile>, String> {
+        let cell = self.tiles.entry(key).or_default().clone();
+        cell.get_or_init(|| async {
+            let analysis = fetch_obstacles_in_bounds(
+                &self.client,
+                &self.config,
+                key.bounds(),
+                self.clearance_m,
+                ObstacleQueryMode::RoutePlanner,
+            )
+            .await?;
+            Ok(Arc::new(Tile {
+                candidates: analysis.candidates,
+                truncated: analysis.truncated,
+            }))
+        })
+        .await
+        .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str, lat: f64, lon: f64) -> ObstacleCandidate {
+        ObstacleCandidate {
+            id: id.to_string(),
+            name: id.to_string(),
+            lat,
+            lon,
+            radius_m: 50.0,
+            height_m: 60.0,
+            hazard_type: "tower".to_string(),
+            distance_m: None,
+            polygon: None,
+        }
+    }
+
+    fn point(lat: f64, lon: f64) -> RoutePoint {
+        RoutePoint {
+            lat,
+            lon,
+            altitude_m: 60.0,
+        }
+    }
+
+    fn index_with(tiles: Vec<(TileKey, Vec<ObstacleCandidate>, bool)>) -> CorridorObstacles {
+        let index = CorridorObstacles::new(Client::new(), Config::from_env(), 30.0);
+        for (key, candidates, truncated) in tiles {
+            let tile = Tile {
+                candidates,
+                truncated,
+            };
+            index
+                .tiles
+                .insert(key, Arc::new(OnceCell::new_with(Some(Ok(Arc::new(tile))))));
+        }
+        index
+    }
+
+    #[test]
+    fn tiles_are_grid_aligned_and_split_into_quadrants() {
+        let bounds = Bounds {
+            min_lat: 32.701,
+            max_lat: 32.726,
+            min_lon: -117.174,
+            max_lon: -117.149,
+        };
+        let tiles = tiles_covering(&bounds);
+        assert_eq!(tiles.len(), 4);
+        assert!(tiles.iter().all(|tile| intersects(&tile.bounds(), &bounds)));
+
+        let parent = tiles[0];
+        let children = parent.children();
+        let parent_bounds = parent.bounds();
+        for child in children {
+            let child_bounds = child.bounds();
+            assert_eq!(child.depth, 1);
+            assert!(child_bounds.min_lat >= parent_bounds.min_lat - 1e-9);
+            assert!(child_bounds.max_lat <= parent_bounds.max_lat + 1e-9);
+            assert!(child_bounds.min_lon >= parent_bounds.min_lon - 1e-9);
+            assert!(child_bounds.max_lon <= parent_bounds.max_lon + 1e-9);
+        }
+    }
+
+    #[tokio::test]
+    async fn segments_query_indexed_tiles_locally() {
+        let segment = [point(32.7050, -117.1600), point(32.7050, -117.1550)];
+        // The segment's tile came back truncated; its quadrants hold the obstacles.
+        let key_at = |depth: u8, lat: f64, lon: f64| {
+            let size = TileKey::size_deg(depth);
+            TileKey {
+                depth,
+                lat: (lat / size).floor() as i64,
+                lon: (lon / size).floor() as i64,
+            }
+        };
+        let parent = key_at(0, 32.7050, -117.1575);
+        let quadrant = key_at(1, 32.7050, -117.1575);
+        let mut tiles = vec![(parent, Vec::new(), true)];
+        for child in parent.children() {
+            let candidates = if child == quadrant {
+                vec![
+                    candidate("far", 32.7100, -117.1575),
+                    candidate("near", 32.7052, -117.1575),
+                ]
+            } else {
+                Vec::new()
+            };
+            tiles.push((child, candidates, false));
+        }
+        let index = index_with(tiles);
+
+        let result = index.query(&segment, 200.0).await.unwrap();
+        assert!(!result.truncated);
+        let ids: Vec<_> = result.candidates.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["near"]);
+        assert!(result.candidates[0].distance_m.unwrap() < 50.0);
+        // Served from the index without fetching more tiles.
+        assert_eq!(index.tile_count(), 5);
+    }
+}
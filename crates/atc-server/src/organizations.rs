@@ -0,0 +1,31 @@
+//! Organizations: tenants sharing one ATC server.
+//!
+//! An organization groups operators (`owner_id`s); each operator belongs to at most one. The
+//! platform admin creates organizations with `POST /v1/admin/organizations` and hands each its
+//! API token. A request bearing an organization token only sees the drones, flight plans,
+//! commands, conflicts and streams of its members' drones, plus shared geofences and its own
+//! private ones, and may only act on its members' drones and plans.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// A tenant and its member operators.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Organization {
+    pub org_id: String,
+    pub name: String,
+    /// Owner IDs of the member operators, sorted.
+    pub members: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A new API token for an organization; only its hash is stored.
+pub fn generate_token() -> String {
+    format!("org_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// The stored form of an organization token.
+pub fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
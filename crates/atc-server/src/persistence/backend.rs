@@ -0,0 +1,50 @@
+//! The database engine behind the persistence layer.
+//!
+//! SQLite by default. Built with the `postgres` feature, the server stores everything in the
+//! Postgres database at `DATABASE_URL` instead, so several nodes can share one store. Queries
+//! are written in the SQL both engines accept (`$N` placeholders, `TRUE`/`FALSE`,
+//! `ON CONFLICT`); the few expressions that differ come from the helpers below.
+
+#[cfg(not(feature = "postgres"))]
+pub type Backend = sqlx::Sqlite;
+#[cfg(feature = "postgres")]
+pub type Backend = sqlx::Postgres;
+
+pub type DbPool = sqlx::Pool<Backend>;
+pub type DbTransaction<'c> = sqlx::Transaction<'c, Backend>;
+
+/// The strings in the JSON array bound at `param`, as a table with a `value` column.
+pub fn json_array_values(param: &str) -> String {
+    if cfg!(feature = "postgres") {
+        format!("json_array_elements_text(CAST({} AS json))", param)
+    } else {
+        format!("json_each({})", param)
+    }
+}
+
+/// An RFC 3339 text column as a comparable timestamp.
+pub fn timestamp(column: &str) -> String {
+    if cfg!(feature = "postgres") {
+        format!("CAST({} AS timestamptz)", column)
+    } else {
+        format!("datetime({})", column)
+    }
+}
+
+/// The current time, offset by the interval bound at `param` (e.g. `-30 seconds`).
+pub fn now_offset(param: &str) -> String {
+    if cfg!(feature = "postgres") {
+        format!("(now() + CAST({} AS interval))", param)
+    } else {
+        format!("datetime('now', {})", param)
+    }
+}
+
+/// The current time, comparable with [`timestamp`].
+pub fn now() -> &'static str {
+    if cfg!(feature = "postgres") {
+        "now()"
+    } else {
+        "datetime('now')"
+    }
+}
@@ -0,0 +1,59 @@
+//! C2 link coverage persistence.
+
+use anyhow::Result;
+use atc_core::CoverageArea;
+
+use super::backend::DbPool;
+
+#[derive(sqlx::FromRow)]
+struct CoverageRow {
+    id: String,
+    name: Option<String>,
+    link_type: Option<String>,
+    polygon: String,
+}
+
+/// Replace every stored coverage area (all or none are stored).
+pub async fn replace_coverage_areas(pool: &DbPool, areas: &[CoverageArea]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM c2_coverage")
+        .execute(&mut *tx)
+        .await?;
+    for area in areas {
+        let polygon_json = serde_json::to_string(&area.polygon)?;
+        sqlx::query(
+            r#"
+            INSERT INTO c2_coverage (id, name, link_type, polygon, updated_at)
+            VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP)
+            "#,
+        )
+        .bind(&area.id)
+        .bind(&area.name)
+        .bind(&area.link_type)
+        .bind(&polygon_json)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Load every stored coverage area.
+pub async fn load_coverage_areas(pool: &DbPool) -> Result<Vec<CoverageArea>> {
+    let rows = sqlx::query_as::<_, CoverageRow>(
+        "SELECT id, name, link_type, polygon FROM c2_coverage ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(CoverageArea {
+                id: row.id,
+                name: row.name,
+                link_type: row.link_type,
+                polygon: serde_json::from_str(&row.polygon)?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,202 @@
+//! Command audit trail persistence.
+//!
+//! Commands are deleted once acknowledged or expired; the audit table keeps one `issued` row per
+//! command with what triggered it, plus a row for how it ended. Rows are only ever appended
+//! (owner purges aside).
+
+use anyhow::{anyhow, Result};
+use atc_core::models::{Command, CommandType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::backend::{Backend, DbPool};
+
+/// What made the server issue a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandCauseKind {
+    /// Issued through the command API.
+    Operator,
+    /// Part of an area broadcast.
+    Broadcast,
+    /// Resolution of a conflict or multi-aircraft cluster.
+    Conflict,
+    /// Geofence breach response or replan around a new geofence.
+    Geofence,
+    /// Response to a drone leaving its flight plan.
+    Conformance,
+    /// Return-to-home after leaving the home tether.
+    Tether,
+    /// Hold for a drone that stopped sending telemetry.
+    Failsafe,
+    /// Mission step of a flight plan.
+    Mission,
+    /// Emergency all-stop.
+    Emergency,
+}
+
+/// The trigger recorded with an issued command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CommandCause {
+    pub kind: CommandCauseKind,
+    /// Conflict, cluster, geofence, flight, broadcast or emergency ID behind the command
+    pub source_id: Option<String>,
+}
+
+impl CommandCause {
+    pub fn new(kind: CommandCauseKind) -> Self {
+        Self {
+            kind,
+            source_id: None,
+        }
+    }
+
+    pub fn with_source(mut self, source_id: impl Into<String>) -> Self {
+        self.source_id = Some(source_id.into());
+        self
+    }
+}
+
+/// How a command left the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandOutcome {
+    Acknowledged,
+    /// Its `expires_at` passed before it was acknowledged.
+    Expired,
+    /// No acknowledgement within the ack timeout.
+    TimedOut,
+    /// Dropped when its drone was deregistered.
+    Cancelled,
+}
+
+/// An issued command with its cause and outcome.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommandAuditEntry {
+    pub command_id: String,
+    pub drone_id: String,
+    pub command_type: CommandType,
+    pub cause: CommandCause,
+    pub issued_at: DateTime<Utc>,
+    /// `None` while the command is still queued
+    pub outcome: Option<CommandOutcome>,
+    pub outcome_at: Option<DateTime<Utc>>,
+}
+
+/// Filters for command audit queries.
+#[derive(Debug, Clone, Default)]
+pub struct CommandAuditFilter {
+    pub drone_id: Option<String>,
+    pub cause: Option<CommandCauseKind>,
+    /// Commands triggered by this conflict, geofence, flight or broadcast.
+    pub source_id: Option<String>,
+    /// Only commands issued at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only commands issued before this time.
+    pub until: Option<DateTime<Utc>>,
+    pub limit: u32,
+}
+
+#[derive(sqlx::FromRow)]
+struct CommandAuditRow {
+    command_id: String,
+    drone_id: String,
+    command_type: String,
+    cause: String,
+    cause_id: Option<String>,
+    issued_at: String,
+    outcome: Option<String>,
+    outcome_at: Option<String>,
+}
+
+impl TryFrom<CommandAuditRow> for CommandAuditEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(row: CommandAuditRow) -> Result<Self> {
+        let parse_time = |value: &str| -> Result<DateTime<Utc>> {
+            Ok(DateTime::parse_from_rfc3339(value)?.with_timezone(&Utc))
+        };
+        Ok(Self {
+            command_id: row.command_id,
+            drone_id: row.drone_id,
+            command_type: serde_json::from_str(&row.command_type)?,
+            cause: CommandCause {
+                kind: serde_json::from_value(serde_json::Value::String(row.cause))?,
+                source_id: row.cause_id,
+            },
+            issued_at: parse_time(&row.issued_at)?,
+            outcome: row
+                .outcome
+                .map(|outcome| serde_json::from_value(serde_json::Value::String(outcome)))
+                .transpose()?,
+            outcome_at: row.outcome_at.as_deref().map(parse_time).transpose()?,
+        })
+    }
+}
+
+pub(crate) fn enum_str<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_value(value)?
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("expected a string enum"))
+}
+
+/// Record that a command was issued.
+pub async fn record_issued<'e, E>(executor: E, cmd: &Command, cause: &CommandCause) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = Backend>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO command_audit (command_id, drone_id, event, command_type, cause, cause_id, recorded_at)
+        VALUES ($1, $2, 'issued', $3, $4, $5, $6)
+        "#,
+    )
+    .bind(&cmd.command_id)
+    .bind(&cmd.drone_id)
+    .bind(serde_json::to_string(&cmd.command_type)?)
+    .bind(enum_str(&cause.kind)?)
+    .bind(&cause.source_id)
+    .bind(cmd.issued_at.to_rfc3339())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Query issued commands with their outcome, newest first.
+pub async fn query_command_audit(
+    pool: &DbPool,
+    filter: &CommandAuditFilter,
+) -> Result<Vec<CommandAuditEntry>> {
+    let cause = filter.cause.as_ref().map(enum_str).transpose()?;
+    let rows = sqlx::query_as::<_, CommandAuditRow>(
+        r#"
+        SELECT issued.command_id, issued.drone_id, issued.command_type, issued.cause, issued.cause_id,
+               issued.recorded_at AS issued_at, ended.event AS outcome, ended.recorded_at AS outcome_at
+        FROM command_audit AS issued
+        LEFT JOIN command_audit AS ended ON ended.id = (
+            SELECT MIN(id) FROM command_audit
+            WHERE command_id = issued.command_id AND event != 'issued' AND id > issued.id
+        )
+        WHERE issued.event = 'issued'
+          AND ($1 IS NULL OR issued.drone_id = $1)
+          AND ($2 IS NULL OR issued.cause = $2)
+          AND ($3 IS NULL OR issued.cause_id = $3)
+          AND ($4 IS NULL OR issued.recorded_at >= $4)
+          AND ($5 IS NULL OR issued.recorded_at < $5)
+        ORDER BY issued.recorded_at DESC, issued.id DESC
+        LIMIT $6
+        "#,
+    )
+    .bind(&filter.drone_id)
+    .bind(cause)
+    .bind(&filter.source_id)
+    .bind(filter.since.map(|t| t.to_rfc3339()))
+    .bind(filter.until.map(|t| t.to_rfc3339()))
+    .bind(filter.limit as i64)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
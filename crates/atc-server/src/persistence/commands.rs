@@ -3,18 +3,52 @@
 use anyhow::Result;
 use atc_core::models::{Command, CommandType};
 use chrono::{DateTime, Utc};
-use sqlx::SqlitePool;
 
-/// Insert a command into the database.
-pub async fn insert_command(pool: &SqlitePool, cmd: &Command) -> Result<()> {
+use super::backend::{self, json_array_values, Backend, DbPool, DbTransaction};
+use super::command_audit::{self, CommandCause, CommandOutcome};
+
+/// Insert a command into the database and record it in the audit trail.
+pub async fn insert_command(pool: &DbPool, cmd: &Command, cause: &CommandCause) -> Result<()> {
+    insert_commands(pool, std::slice::from_ref(cmd), cause).await
+}
+
+/// Insert several commands in one transaction (all or none are stored).
+pub async fn insert_commands(
+    pool: &DbPool,
+    commands: &[Command],
+    cause: &CommandCause,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    insert_commands_tx(&mut tx, commands, cause).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Insert commands and their audit records within a transaction.
+pub async fn insert_commands_tx(
+    tx: &mut DbTransaction<'_>,
+    commands: &[Command],
+    cause: &CommandCause,
+) -> Result<()> {
+    for cmd in commands {
+        upsert_command(&mut **tx, cmd).await?;
+        command_audit::record_issued(&mut **tx, cmd, cause).await?;
+    }
+    Ok(())
+}
+
+async fn upsert_command<'e, E>(executor: E, cmd: &Command) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = Backend>,
+{
     let command_type_json = serde_json::to_string(&cmd.command_type)?;
 
     sqlx::query(
         r#"
         INSERT INTO commands (command_id, drone_id, command_type, issued_at, expires_at, acknowledged)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        VALUES ($1, $2, $3, $4, $5, $6)
         ON CONFLICT(command_id) DO UPDATE SET
-            acknowledged = ?6, acked_at = CASE WHEN ?6 = 1 THEN CURRENT_TIMESTAMP ELSE acked_at END
+            acknowledged = $6, acked_at = CASE WHEN $6 THEN CAST(CURRENT_TIMESTAMP AS TEXT) ELSE commands.acked_at END
         "#,
     )
     .bind(&cmd.command_id)
@@ -23,48 +57,131 @@ pub async fn insert_command(pool: &SqlitePool, cmd: &Command) -> Result<()> {
     .bind(cmd.issued_at.to_rfc3339())
     .bind(cmd.expires_at.map(|t| t.to_rfc3339()))
     .bind(cmd.acknowledged)
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
 }
 
 /// Mark a command as acknowledged.
-pub async fn ack_command(pool: &SqlitePool, command_id: &str) -> Result<bool> {
+pub async fn ack_command(pool: &DbPool, command_id: &str) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    record_outcomes(
+        &mut *tx,
+        CommandOutcome::Acknowledged,
+        "command_id = $2 AND acknowledged = FALSE",
+        command_id,
+    )
+    .await?;
     let result = sqlx::query(
-        "UPDATE commands SET acknowledged = 1, acked_at = CURRENT_TIMESTAMP WHERE command_id = ?1",
+        "UPDATE commands SET acknowledged = TRUE, acked_at = CURRENT_TIMESTAMP WHERE command_id = $1",
     )
     .bind(command_id)
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
+    tx.commit().await?;
 
     Ok(result.rows_affected() > 0)
 }
 
+/// Append an audit outcome for every command matching `condition`, which can refer to `param`
+/// as `$2`.
+async fn record_outcomes<'e, E>(
+    executor: E,
+    outcome: CommandOutcome,
+    condition: &str,
+    param: &str,
+) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = Backend>,
+{
+    sqlx::query(&format!(
+        r#"
+        INSERT INTO command_audit (command_id, drone_id, event, recorded_at)
+        SELECT command_id, drone_id, $1, $3 FROM commands WHERE {}
+        "#,
+        condition
+    ))
+    .bind(command_audit::enum_str(&outcome)?)
+    .bind(param)
+    .bind(Utc::now().to_rfc3339())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Delete a drone's unacknowledged commands within a transaction, recording them as cancelled.
+pub async fn cancel_pending_commands_tx(tx: &mut DbTransaction<'_>, drone_id: &str) -> Result<()> {
+    record_outcomes(
+        &mut **tx,
+        CommandOutcome::Cancelled,
+        "drone_id = $2 AND acknowledged = FALSE",
+        drone_id,
+    )
+    .await?;
+    sqlx::query("DELETE FROM commands WHERE drone_id = $1 AND acknowledged = FALSE")
+        .bind(drone_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
 /// Load all pending commands (for all drones).
-pub async fn load_all_pending_commands(pool: &SqlitePool) -> Result<Vec<Command>> {
-    let rows = sqlx::query_as::<_, CommandRow>(
+pub async fn load_all_pending_commands(pool: &DbPool) -> Result<Vec<Command>> {
+    let rows = sqlx::query_as::<_, CommandRow>(&format!(
         r#"
         SELECT command_id, drone_id, command_type, issued_at, expires_at, acknowledged
         FROM commands
-        WHERE acknowledged = 0
-        AND (expires_at IS NULL OR datetime(expires_at) > datetime('now'))
+        WHERE acknowledged = FALSE
+        AND (expires_at IS NULL OR {} > {})
         ORDER BY drone_id, issued_at ASC
         "#,
-    )
+        backend::timestamp("expires_at"),
+        backend::now()
+    ))
     .fetch_all(pool)
     .await?;
 
     rows.into_iter().map(|r| r.try_into()).collect()
 }
 
-/// Delete expired commands.
-pub async fn delete_expired_commands(pool: &SqlitePool) -> Result<u64> {
-    let result = sqlx::query(
-        "DELETE FROM commands WHERE expires_at IS NOT NULL AND (datetime(expires_at) < datetime('now') OR datetime(expires_at) IS NULL)",
+/// Load every command, acknowledged or not, sent to one of `drone_ids`.
+pub async fn load_drone_commands(pool: &DbPool, drone_ids: &[String]) -> Result<Vec<Command>> {
+    let rows = sqlx::query_as::<_, CommandRow>(&format!(
+        r#"
+        SELECT command_id, drone_id, command_type, issued_at, expires_at, acknowledged
+        FROM commands
+        WHERE drone_id IN (SELECT value FROM {})
+        ORDER BY drone_id, issued_at ASC
+        "#,
+        json_array_values("$1")
+    ))
+    .bind(serde_json::to_string(drone_ids)?)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
+
+/// Delete expired commands, recording the unacknowledged ones as expired.
+pub async fn delete_expired_commands(pool: &DbPool) -> Result<u64> {
+    let expired = format!(
+        "expires_at IS NOT NULL AND ({expires} < {now} OR {expires} IS NULL)",
+        expires = backend::timestamp("expires_at"),
+        now = backend::now()
+    );
+    let mut tx = pool.begin().await?;
+    record_outcomes(
+        &mut *tx,
+        CommandOutcome::Expired,
+        &format!("acknowledged = FALSE AND {}", expired),
+        "",
     )
-    .execute(pool)
     .await?;
+    let result = sqlx::query(&format!("DELETE FROM commands WHERE {}", expired))
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
 
     Ok(result.rows_affected())
 }
@@ -97,6 +214,8 @@ mod tests {
             velocity_z: 0.0,
             last_update: now,
             status: DroneStatus::Inactive,
+            scheduling_priority: None,
+            capabilities: None,
         };
         drones_db::upsert_drone(pool, &drone)
             .await
@@ -111,9 +230,13 @@ mod tests {
             acknowledged: false,
         };
 
-        insert_command(pool, &expired)
-            .await
-            .expect("insert command");
+        insert_command(
+            pool,
+            &expired,
+            &CommandCause::new(super::command_audit::CommandCauseKind::Operator),
+        )
+        .await
+        .expect("insert command");
 
         let pending = load_all_pending_commands(pool).await.expect("load pending");
         assert!(pending.is_empty(), "expired command should not be pending");
@@ -124,18 +247,33 @@ mod tests {
 }
 
 /// Delete commands that never received an acknowledgement within the timeout.
-pub async fn delete_stale_commands(pool: &SqlitePool, ack_timeout_secs: i64) -> Result<u64> {
+pub async fn delete_stale_commands(pool: &DbPool, ack_timeout_secs: i64) -> Result<u64> {
     if ack_timeout_secs <= 0 {
         return Ok(0);
     }
 
     let modifier = format!("-{} seconds", ack_timeout_secs);
-    let result = sqlx::query(
-        "DELETE FROM commands WHERE acknowledged = 0 AND datetime(issued_at) < datetime('now', ?1)",
+    let mut tx = pool.begin().await?;
+    record_outcomes(
+        &mut *tx,
+        CommandOutcome::TimedOut,
+        &format!(
+            "acknowledged = FALSE AND {} < {}",
+            backend::timestamp("issued_at"),
+            backend::now_offset("$2")
+        ),
+        &modifier,
     )
+    .await?;
+    let result = sqlx::query(&format!(
+        "DELETE FROM commands WHERE acknowledged = FALSE AND {} < {}",
+        backend::timestamp("issued_at"),
+        backend::now_offset("$1")
+    ))
     .bind(modifier)
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
+    tx.commit().await?;
 
     Ok(result.rows_affected())
 }
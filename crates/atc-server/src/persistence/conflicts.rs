@@ -0,0 +1,158 @@
+//! Conflict history persistence.
+
+use anyhow::{anyhow, Result};
+use atc_core::ConflictSeverity;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::backend::{json_array_values, DbPool};
+
+/// How a conflict ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictOutcome {
+    /// The pair regained separation while both drones were still tracked.
+    Resolved,
+    /// One of the drones stopped being tracked (lost, landed or removed).
+    Expired,
+}
+
+/// A conflict from first detection until it cleared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictRecord {
+    pub conflict_id: String,
+    pub drone1_id: String,
+    pub drone2_id: String,
+    pub peak_severity: ConflictSeverity,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// Smallest separation observed while the conflict was active (meters).
+    pub min_separation_m: f64,
+    pub sector_id: Option<String>,
+    pub outcome: ConflictOutcome,
+}
+
+/// Filters for conflict history queries.
+#[derive(Debug, Clone, Default)]
+pub struct ConflictHistoryFilter {
+    /// Either participant.
+    pub drone_id: Option<String>,
+    /// Only conflicts still active at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only conflicts that started before this time.
+    pub until: Option<DateTime<Utc>>,
+    pub limit: u32,
+}
+
+#[derive(sqlx::FromRow)]
+struct ConflictRow {
+    conflict_id: String,
+    drone1_id: String,
+    drone2_id: String,
+    peak_severity: String,
+    started_at: String,
+    ended_at: String,
+    min_separation_m: f64,
+    sector_id: Option<String>,
+    outcome: String,
+}
+
+impl TryFrom<ConflictRow> for ConflictRecord {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ConflictRow) -> Result<Self> {
+        Ok(Self {
+            conflict_id: row.conflict_id,
+            drone1_id: row.drone1_id,
+            drone2_id: row.drone2_id,
+            peak_severity: serde_json::from_value(serde_json::Value::String(row.peak_severity))?,
+            started_at: DateTime::parse_from_rfc3339(&row.started_at)?.with_timezone(&Utc),
+            ended_at: DateTime::parse_from_rfc3339(&row.ended_at)?.with_timezone(&Utc),
+            min_separation_m: row.min_separation_m,
+            sector_id: row.sector_id,
+            outcome: serde_json::from_value(serde_json::Value::String(row.outcome))?,
+        })
+    }
+}
+
+fn enum_str<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_value(value)?
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("expected a string enum"))
+}
+
+/// Insert ended conflicts in one transaction.
+pub async fn insert_conflicts(pool: &DbPool, records: &[ConflictRecord]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    for record in records {
+        sqlx::query(
+            r#"
+            INSERT INTO conflicts (conflict_id, drone1_id, drone2_id, peak_severity, started_at, ended_at, min_separation_m, sector_id, outcome)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT(conflict_id) DO NOTHING
+            "#,
+        )
+        .bind(&record.conflict_id)
+        .bind(&record.drone1_id)
+        .bind(&record.drone2_id)
+        .bind(enum_str(&record.peak_severity)?)
+        .bind(record.started_at.to_rfc3339())
+        .bind(record.ended_at.to_rfc3339())
+        .bind(record.min_separation_m)
+        .bind(&record.sector_id)
+        .bind(enum_str(&record.outcome)?)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Query persisted conflicts overlapping the time range, newest first.
+pub async fn query_conflict_history(
+    pool: &DbPool,
+    filter: &ConflictHistoryFilter,
+) -> Result<Vec<ConflictRecord>> {
+    let rows = sqlx::query_as::<_, ConflictRow>(
+        r#"
+        SELECT conflict_id, drone1_id, drone2_id, peak_severity, started_at, ended_at, min_separation_m, sector_id, outcome
+        FROM conflicts
+        WHERE ($1 IS NULL OR drone1_id = $1 OR drone2_id = $1)
+          AND ($2 IS NULL OR ended_at >= $2)
+          AND ($3 IS NULL OR started_at < $3)
+        ORDER BY started_at DESC, conflict_id
+        LIMIT $4
+        "#,
+    )
+    .bind(&filter.drone_id)
+    .bind(filter.since.map(|t| t.to_rfc3339()))
+    .bind(filter.until.map(|t| t.to_rfc3339()))
+    .bind(filter.limit as i64)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
+
+/// Load every persisted conflict involving one of `drone_ids`, oldest first.
+pub async fn load_drone_conflicts(
+    pool: &DbPool,
+    drone_ids: &[String],
+) -> Result<Vec<ConflictRecord>> {
+    let rows = sqlx::query_as::<_, ConflictRow>(&format!(
+        r#"
+        SELECT conflict_id, drone1_id, drone2_id, peak_severity, started_at, ended_at, min_separation_m, sector_id, outcome
+        FROM conflicts
+        WHERE drone1_id IN (SELECT value FROM {ids})
+           OR drone2_id IN (SELECT value FROM {ids})
+        ORDER BY started_at, conflict_id
+        "#,
+        ids = json_array_values("$1")
+    ))
+    .bind(serde_json::to_string(drone_ids)?)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
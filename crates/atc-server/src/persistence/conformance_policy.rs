@@ -0,0 +1,34 @@
+//! Persistence of the conformance escalation policy.
+
+use anyhow::Result;
+
+use super::backend::DbPool;
+use crate::conformance_policy::ConformancePolicy;
+
+/// Store the policy, replacing the previous one.
+pub async fn save_policy(pool: &DbPool, policy: &ConformancePolicy) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO conformance_policy (id, policy, updated_at)
+        VALUES (1, $1, CURRENT_TIMESTAMP)
+        ON CONFLICT(id) DO UPDATE SET
+            policy = excluded.policy,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(serde_json::to_string(policy)?)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// The stored policy, if one was saved.
+pub async fn load_policy(pool: &DbPool) -> Result<Option<ConformancePolicy>> {
+    let policy: Option<String> =
+        sqlx::query_scalar("SELECT policy FROM conformance_policy WHERE id = 1")
+            .fetch_optional(pool)
+            .await?;
+    policy
+        .map(|policy| Ok(serde_json::from_str(&policy)?))
+        .transpose()
+}
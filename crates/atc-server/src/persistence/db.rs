@@ -1,25 +1,79 @@
 //! Database connection and initialization.
 
-use anyhow::Result;
-use sqlx::{sqlite::SqlitePoolOptions, Row, SqlitePool};
-use std::path::Path;
+use anyhow::{bail, Result};
+use std::future::Future;
+use std::time::Duration;
 use tracing::info;
 
+use super::backend::DbPool;
+use super::schema;
+
+/// Default timeout for history/analytics queries on the read pool.
+const DEFAULT_READ_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Database connection wrapper.
+///
+/// Operational writes go through `pool`; history and analytics endpoints use `read_pool`,
+/// a separate read-only pool so long reports cannot starve the scheduler of connections.
 #[derive(Clone)]
 pub struct Database {
-    pool: SqlitePool,
+    pool: DbPool,
+    read_pool: DbPool,
+    read_timeout: Duration,
 }
 
 impl Database {
     /// Get the underlying connection pool.
-    pub fn pool(&self) -> &SqlitePool {
+    pub fn pool(&self) -> &DbPool {
         &self.pool
     }
+
+    /// Get the read-only pool used for history/analytics queries.
+    pub fn read_pool(&self) -> &DbPool {
+        &self.read_pool
+    }
+
+    /// Run a read-pool query, failing if it exceeds the configured read timeout.
+    pub async fn read_with_timeout<T, F>(&self, query: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        match tokio::time::timeout(self.read_timeout, query).await {
+            Ok(result) => result,
+            Err(_) => Err(ReadTimeout(self.read_timeout).into()),
+        }
+    }
+
+    /// Open a dedicated read-only pool for analytics queries.
+    ///
+    /// Reads from `replica_path` when set (e.g. a litestream/rsync replica, or a Postgres
+    /// standby URL), otherwise opens the primary database read-only. In-memory SQLite databases
+    /// keep sharing the primary pool.
+    pub async fn with_read_pool(
+        mut self,
+        primary_path: &str,
+        replica_path: Option<&str>,
+        max_connections: u32,
+        query_timeout: Duration,
+    ) -> Result<Self> {
+        self.read_timeout = query_timeout;
+        let path = replica_path.unwrap_or(primary_path);
+        if path == ":memory:" || max_connections == 0 {
+            return Ok(self);
+        }
+
+        self.read_pool = connect_read_only(path, max_connections, query_timeout).await?;
+        Ok(self)
+    }
 }
 
+/// Returned by [`Database::read_with_timeout`] when a read query runs too long.
+#[derive(Debug, thiserror::Error)]
+#[error("read query timed out after {0:?}")]
+pub struct ReadTimeout(pub Duration);
+
 /// Clear all persisted state (drones, geofences, flight plans, commands).
-pub async fn clear_all(pool: &SqlitePool) -> Result<()> {
+pub async fn clear_all(pool: &DbPool) -> Result<()> {
     let mut tx = pool.begin().await?;
     sqlx::query("DELETE FROM commands")
         .execute(&mut *tx)
@@ -36,18 +90,73 @@ pub async fn clear_all(pool: &SqlitePool) -> Result<()> {
     sqlx::query("DELETE FROM drone_tokens")
         .execute(&mut *tx)
         .await?;
+    sqlx::query("DELETE FROM drone_performance")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM drone_capabilities")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM drone_homes")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM c2_coverage")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM telemetry_history")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM emergency_stop")
+        .execute(&mut *tx)
+        .await?;
     sqlx::query("DELETE FROM drones").execute(&mut *tx).await?;
     tx.commit().await?;
     Ok(())
 }
 
-/// Initialize the SQLite database.
+/// Initialize the database.
 ///
 /// Creates the database file if it doesn't exist, runs migrations,
 /// and returns a connection pool.
-pub async fn init_database(db_path: &str, max_connections: u32) -> Result<Database> {
+pub async fn init_database(location: &str, max_connections: u32) -> Result<Database> {
+    open_database(location, max_connections, true).await
+}
+
+/// Open the database at `location` (a SQLite file path, or a Postgres URL in `postgres`
+/// builds), applying pending migrations only if `auto_migrate` is set.
+///
+/// Fails if the schema is newer than this release, or has pending migrations that
+/// `auto_migrate` does not allow applying.
+pub async fn open_database(
+    location: &str,
+    max_connections: u32,
+    auto_migrate: bool,
+) -> Result<Database> {
+    let pool = connect(location, max_connections).await?;
+
+    // Run migrations
+    run_migrations(&pool, auto_migrate).await?;
+
+    Ok(Database {
+        read_pool: pool.clone(),
+        pool,
+        read_timeout: DEFAULT_READ_QUERY_TIMEOUT,
+    })
+}
+
+fn is_postgres_url(location: &str) -> bool {
+    location.starts_with("postgres://") || location.starts_with("postgresql://")
+}
+
+#[cfg(not(feature = "postgres"))]
+async fn connect(db_path: &str, max_connections: u32) -> Result<DbPool> {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    if is_postgres_url(db_path) {
+        bail!("DATABASE_URL selects Postgres, but this build lacks the `postgres` feature");
+    }
+
     // Ensure parent directory exists
-    if let Some(parent) = Path::new(db_path).parent() {
+    if let Some(parent) = std::path::Path::new(db_path).parent() {
         std::fs::create_dir_all(parent)?;
     }
 
@@ -81,27 +190,107 @@ pub async fn init_database(db_path: &str, max_connections: u32) -> Result<Databa
         })
         .connect(&db_url)
         .await?;
+    Ok(pool)
+}
 
-    // Run migrations
-    run_migrations(&pool).await?;
+#[cfg(feature = "postgres")]
+async fn connect(url: &str, max_connections: u32) -> Result<DbPool> {
+    use sqlx::postgres::PgPoolOptions;
+
+    if !is_postgres_url(url) {
+        bail!("Postgres builds need DATABASE_URL set to a postgres:// URL");
+    }
+
+    info!("Connecting to Postgres database");
+    let pool = PgPoolOptions::new()
+        .max_connections(max_connections)
+        .connect(url)
+        .await?;
+    Ok(pool)
+}
+
+#[cfg(not(feature = "postgres"))]
+async fn connect_read_only(
+    path: &str,
+    max_connections: u32,
+    query_timeout: Duration,
+) -> Result<DbPool> {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    info!("Opening read-only database pool: {}", path);
+    let busy_timeout_ms = query_timeout.as_millis().min(u32::MAX as u128);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(max_connections)
+        .after_connect(move |conn, _meta| {
+            Box::pin(async move {
+                sqlx::query("PRAGMA query_only = ON;")
+                    .execute(&mut *conn)
+                    .await?;
+                sqlx::query(&format!("PRAGMA busy_timeout = {};", busy_timeout_ms))
+                    .execute(&mut *conn)
+                    .await?;
+                Ok(())
+            })
+        })
+        .connect(&format!("sqlite:{}?mode=ro", path))
+        .await?;
+    Ok(pool)
+}
+
+#[cfg(feature = "postgres")]
+async fn connect_read_only(
+    url: &str,
+    max_connections: u32,
+    query_timeout: Duration,
+) -> Result<DbPool> {
+    use sqlx::postgres::PgPoolOptions;
 
-    Ok(Database { pool })
+    info!("Opening read-only Postgres pool");
+    let statement_timeout_ms = query_timeout.as_millis().min(i32::MAX as u128);
+    let pool = PgPoolOptions::new()
+        .max_connections(max_connections)
+        .after_connect(move |conn, _meta| {
+            Box::pin(async move {
+                sqlx::query("SET default_transaction_read_only = on")
+                    .execute(&mut *conn)
+                    .await?;
+                sqlx::query(&format!("SET statement_timeout = {}", statement_timeout_ms))
+                    .execute(&mut *conn)
+                    .await?;
+                Ok(())
+            })
+        })
+        .connect(url)
+        .await?;
+    Ok(pool)
 }
 
 /// Run database migrations.
-async fn run_migrations(pool: &SqlitePool) -> Result<()> {
+async fn run_migrations(pool: &DbPool, auto_migrate: bool) -> Result<()> {
     info!("Running database migrations...");
 
-    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
-    MIGRATOR.run(pool).await?;
+    let status = schema::migrate(pool, auto_migrate).await?;
 
-    ensure_flight_plan_columns(pool).await?;
+    // Columns added to SQLite tables before migrations were versioned; the Postgres schema
+    // has always had them.
+    #[cfg(not(feature = "postgres"))]
+    if auto_migrate {
+        ensure_flight_plan_columns(pool).await?;
+        ensure_drone_token_columns(pool).await?;
+        ensure_geofence_columns(pool).await?;
+    }
 
-    info!("Database migrations complete");
+    info!(
+        "Database migrations complete (schema version {})",
+        status.database_version.unwrap_or_default()
+    );
     Ok(())
 }
 
-async fn ensure_flight_plan_columns(pool: &SqlitePool) -> Result<()> {
+#[cfg(not(feature = "postgres"))]
+async fn ensure_flight_plan_columns(pool: &DbPool) -> Result<()> {
+    use sqlx::Row;
+
     let rows = sqlx::query("PRAGMA table_info(flight_plans)")
         .fetch_all(pool)
         .await?;
@@ -137,7 +326,82 @@ async fn ensure_flight_plan_columns(pool: &SqlitePool) -> Result<()> {
     Ok(())
 }
 
-#[cfg(test)]
+#[cfg(not(feature = "postgres"))]
+async fn ensure_geofence_columns(pool: &DbPool) -> Result<()> {
+    use sqlx::Row;
+
+    let rows = sqlx::query("PRAGMA table_info(geofences)")
+        .fetch_all(pool)
+        .await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let mut columns = std::collections::HashSet::new();
+    for row in rows {
+        let name: String = row.try_get("name")?;
+        columns.insert(name);
+    }
+
+    if !columns.contains("breach_response") {
+        if let Err(err) = sqlx::query("ALTER TABLE geofences ADD COLUMN breach_response TEXT")
+            .execute(pool)
+            .await
+        {
+            if !err.to_string().contains("duplicate column") {
+                return Err(err.into());
+            }
+        }
+    }
+
+    if !columns.contains("schedule") {
+        if let Err(err) = sqlx::query("ALTER TABLE geofences ADD COLUMN schedule TEXT")
+            .execute(pool)
+            .await
+        {
+            if !err.to_string().contains("duplicate column") {
+                return Err(err.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "postgres"))]
+async fn ensure_drone_token_columns(pool: &DbPool) -> Result<()> {
+    use sqlx::Row;
+
+    let rows = sqlx::query("PRAGMA table_info(drone_tokens)")
+        .fetch_all(pool)
+        .await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let mut columns = std::collections::HashSet::new();
+    for row in rows {
+        let name: String = row.try_get("name")?;
+        columns.insert(name);
+    }
+
+    for name in ["expires_at", "previous_token", "previous_expires_at"] {
+        if columns.contains(name) {
+            continue;
+        }
+        let statement = format!("ALTER TABLE drone_tokens ADD COLUMN {} TEXT", name);
+        if let Err(err) = sqlx::query(&statement).execute(pool).await {
+            if err.to_string().contains("duplicate column") {
+                continue;
+            }
+            return Err(err.into());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, not(feature = "postgres")))]
 mod tests {
     use super::*;
 
@@ -155,4 +419,39 @@ mod tests {
 
         assert_eq!(result.0, 1);
     }
+
+    #[tokio::test]
+    async fn read_pool_rejects_writes() {
+        let path = std::env::temp_dir()
+            .join(format!("atc-read-pool-{}.db", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .to_string();
+        let db = init_database(&path, 1)
+            .await
+            .unwrap()
+            .with_read_pool(&path, None, 1, Duration::from_millis(500))
+            .await
+            .unwrap();
+
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM drones")
+            .fetch_one(db.read_pool())
+            .await
+            .unwrap();
+        assert_eq!(count.0, 0);
+
+        let write = sqlx::query("INSERT INTO drones (drone_id) VALUES ('D1')")
+            .execute(db.read_pool())
+            .await;
+        assert!(write.is_err());
+
+        let timed_out = db
+            .read_with_timeout(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            })
+            .await;
+        assert!(timed_out.unwrap_err().is::<ReadTimeout>());
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
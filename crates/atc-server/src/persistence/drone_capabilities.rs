@@ -0,0 +1,69 @@
+//! Drone capability persistence.
+
+use anyhow::Result;
+use atc_core::DroneCapabilities;
+
+use super::backend::DbPool;
+
+#[derive(sqlx::FromRow)]
+struct DroneCapabilitiesRow {
+    drone_id: String,
+    supports_reroute: bool,
+    supports_hold: bool,
+    max_climb_rate_mps: Option<f64>,
+    rid_module: bool,
+}
+
+impl From<DroneCapabilitiesRow> for (String, DroneCapabilities) {
+    fn from(row: DroneCapabilitiesRow) -> Self {
+        (
+            row.drone_id,
+            DroneCapabilities {
+                supports_reroute: row.supports_reroute,
+                supports_hold: row.supports_hold,
+                max_climb_rate_mps: row.max_climb_rate_mps,
+                rid_module: row.rid_module,
+            },
+        )
+    }
+}
+
+/// Insert or replace a drone's advertised capabilities.
+pub async fn upsert_drone_capabilities(
+    pool: &DbPool,
+    drone_id: &str,
+    capabilities: &DroneCapabilities,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO drone_capabilities (drone_id, supports_reroute, supports_hold, max_climb_rate_mps, rid_module, updated_at)
+        VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP)
+        ON CONFLICT(drone_id) DO UPDATE SET
+            supports_reroute = $2,
+            supports_hold = $3,
+            max_climb_rate_mps = $4,
+            rid_module = $5,
+            updated_at = CURRENT_TIMESTAMP
+        "#,
+    )
+    .bind(drone_id)
+    .bind(capabilities.supports_reroute)
+    .bind(capabilities.supports_hold)
+    .bind(capabilities.max_climb_rate_mps)
+    .bind(capabilities.rid_module)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Load every drone's advertised capabilities.
+pub async fn load_drone_capabilities(pool: &DbPool) -> Result<Vec<(String, DroneCapabilities)>> {
+    let rows = sqlx::query_as::<_, DroneCapabilitiesRow>(
+        "SELECT drone_id, supports_reroute, supports_hold, max_climb_rate_mps, rid_module FROM drone_capabilities",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
@@ -0,0 +1,69 @@
+//! Drone home point persistence.
+
+use anyhow::Result;
+use atc_core::DroneHome;
+
+use super::backend::DbPool;
+
+#[derive(sqlx::FromRow)]
+struct DroneHomeRow {
+    drone_id: String,
+    lat: f64,
+    lon: f64,
+    altitude_m: f64,
+    tether_radius_m: Option<f64>,
+    auto_rth: bool,
+}
+
+impl From<DroneHomeRow> for (String, DroneHome) {
+    fn from(row: DroneHomeRow) -> Self {
+        (
+            row.drone_id,
+            DroneHome {
+                lat: row.lat,
+                lon: row.lon,
+                altitude_m: row.altitude_m,
+                tether_radius_m: row.tether_radius_m,
+                auto_rth: row.auto_rth,
+            },
+        )
+    }
+}
+
+/// Insert or replace a drone's home point.
+pub async fn upsert_drone_home(pool: &DbPool, drone_id: &str, home: &DroneHome) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO drone_homes (drone_id, lat, lon, altitude_m, tether_radius_m, auto_rth, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP)
+        ON CONFLICT(drone_id) DO UPDATE SET
+            lat = $2,
+            lon = $3,
+            altitude_m = $4,
+            tether_radius_m = $5,
+            auto_rth = $6,
+            updated_at = CURRENT_TIMESTAMP
+        "#,
+    )
+    .bind(drone_id)
+    .bind(home.lat)
+    .bind(home.lon)
+    .bind(home.altitude_m)
+    .bind(home.tether_radius_m)
+    .bind(home.auto_rth)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Load every registered home point.
+pub async fn load_drone_homes(pool: &DbPool) -> Result<Vec<(String, DroneHome)>> {
+    let rows = sqlx::query_as::<_, DroneHomeRow>(
+        "SELECT drone_id, lat, lon, altitude_m, tether_radius_m, auto_rth FROM drone_homes",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
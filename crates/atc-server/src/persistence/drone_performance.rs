@@ -0,0 +1,73 @@
+//! Drone performance envelope persistence.
+
+use anyhow::Result;
+use atc_core::DronePerformance;
+
+use super::backend::DbPool;
+
+#[derive(sqlx::FromRow)]
+struct DronePerformanceRow {
+    drone_id: String,
+    max_climb_rate_mps: f64,
+    max_descent_rate_mps: Option<f64>,
+    max_speed_mps: f64,
+    turn_rate_deg_s: f64,
+    max_wind_mps: f64,
+}
+
+impl From<DronePerformanceRow> for (String, DronePerformance) {
+    fn from(row: DronePerformanceRow) -> Self {
+        (
+            row.drone_id,
+            DronePerformance {
+                max_climb_rate_mps: row.max_climb_rate_mps,
+                max_descent_rate_mps: row.max_descent_rate_mps,
+                max_speed_mps: row.max_speed_mps,
+                turn_rate_deg_s: row.turn_rate_deg_s,
+                max_wind_mps: row.max_wind_mps,
+            },
+        )
+    }
+}
+
+/// Insert or replace a drone's performance envelope.
+pub async fn upsert_drone_performance(
+    pool: &DbPool,
+    drone_id: &str,
+    performance: &DronePerformance,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO drone_performance (drone_id, max_climb_rate_mps, max_speed_mps, turn_rate_deg_s, max_wind_mps, max_descent_rate_mps, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP)
+        ON CONFLICT(drone_id) DO UPDATE SET
+            max_climb_rate_mps = $2,
+            max_speed_mps = $3,
+            turn_rate_deg_s = $4,
+            max_wind_mps = $5,
+            max_descent_rate_mps = $6,
+            updated_at = CURRENT_TIMESTAMP
+        "#,
+    )
+    .bind(drone_id)
+    .bind(performance.max_climb_rate_mps)
+    .bind(performance.max_speed_mps)
+    .bind(performance.turn_rate_deg_s)
+    .bind(performance.max_wind_mps)
+    .bind(performance.max_descent_rate_mps)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Load every registered performance envelope.
+pub async fn load_drone_performance(pool: &DbPool) -> Result<Vec<(String, DronePerformance)>> {
+    let rows = sqlx::query_as::<_, DronePerformanceRow>(
+        "SELECT drone_id, max_climb_rate_mps, max_descent_rate_mps, max_speed_mps, turn_rate_deg_s, max_wind_mps FROM drone_performance",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
@@ -1,40 +1,144 @@
 //! Drone session token persistence.
 
 use anyhow::Result;
-use sqlx::SqlitePool;
+use chrono::{DateTime, Utc};
+
+use super::backend::DbPool;
+
+/// A drone's current session token plus the token it replaced during a rotation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DroneSessionToken {
+    pub token: String,
+    /// `None` means the token never expires.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Secret keying the drone's telemetry MACs, issued with the token. `None` for tokens issued
+    /// before telemetry MAC secrets existed; such drones rotate their token to get one.
+    pub mac_secret: Option<String>,
+    /// Token replaced by the last self-rotation, accepted until `previous_expires_at`.
+    pub previous_token: Option<String>,
+    pub previous_expires_at: Option<DateTime<Utc>>,
+    /// MAC secret issued with `previous_token`.
+    pub previous_mac_secret: Option<String>,
+}
+
+impl DroneSessionToken {
+    pub fn new(token: String, mac_secret: String, expires_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            token,
+            expires_at,
+            mac_secret: Some(mac_secret),
+            previous_token: None,
+            previous_expires_at: None,
+            previous_mac_secret: None,
+        }
+    }
+
+    /// MAC secret that goes with `token`, the current or the previous one.
+    pub fn mac_secret_for(&self, token: &str) -> Option<&str> {
+        if self.token == token {
+            self.mac_secret.as_deref()
+        } else if self.previous_token.as_deref() == Some(token) {
+            self.previous_mac_secret.as_deref()
+        } else {
+            None
+        }
+    }
+}
 
 #[derive(sqlx::FromRow)]
 pub struct DroneTokenRow {
     pub drone_id: String,
     pub session_token: String,
+    pub expires_at: Option<String>,
+    pub mac_secret: Option<String>,
+    pub previous_token: Option<String>,
+    pub previous_expires_at: Option<String>,
+    pub previous_mac_secret: Option<String>,
+}
+
+fn parse_time(value: Option<String>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|raw| DateTime::parse_from_rfc3339(&raw).ok())
+        .map(|time| time.with_timezone(&Utc))
+}
+
+impl From<DroneTokenRow> for DroneSessionToken {
+    fn from(row: DroneTokenRow) -> Self {
+        Self {
+            token: row.session_token,
+            expires_at: parse_time(row.expires_at),
+            mac_secret: row.mac_secret,
+            previous_token: row.previous_token,
+            previous_expires_at: parse_time(row.previous_expires_at),
+            previous_mac_secret: row.previous_mac_secret,
+        }
+    }
 }
 
 /// Upsert a drone session token.
-#[allow(dead_code)]
-pub async fn upsert_drone_token(pool: &SqlitePool, drone_id: &str, token: &str) -> Result<()> {
+pub async fn upsert_drone_token(
+    pool: &DbPool,
+    drone_id: &str,
+    token: &DroneSessionToken,
+) -> Result<()> {
     sqlx::query(
         r#"
-        INSERT INTO drone_tokens (drone_id, session_token, updated_at)
-        VALUES (?1, ?2, CURRENT_TIMESTAMP)
+        INSERT INTO drone_tokens (drone_id, session_token, expires_at, mac_secret, previous_token, previous_expires_at, previous_mac_secret, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP)
         ON CONFLICT(drone_id) DO UPDATE SET
-            session_token = ?2,
+            session_token = $2,
+            expires_at = $3,
+            mac_secret = $4,
+            previous_token = $5,
+            previous_expires_at = $6,
+            previous_mac_secret = $7,
             updated_at = CURRENT_TIMESTAMP
         "#,
     )
     .bind(drone_id)
-    .bind(token)
+    .bind(&token.token)
+    .bind(token.expires_at.map(|t| t.to_rfc3339()))
+    .bind(&token.mac_secret)
+    .bind(&token.previous_token)
+    .bind(token.previous_expires_at.map(|t| t.to_rfc3339()))
+    .bind(&token.previous_mac_secret)
     .execute(pool)
     .await?;
 
     Ok(())
 }
 
+/// Delete a drone's session token.
+pub async fn delete_drone_token(pool: &DbPool, drone_id: &str) -> Result<()> {
+    sqlx::query("DELETE FROM drone_tokens WHERE drone_id = $1")
+        .bind(drone_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Delete a drone's token row only while it still holds `session_token`, so a token issued in
+/// the meantime survives.
+pub async fn delete_drone_token_if_current(
+    pool: &DbPool,
+    drone_id: &str,
+    session_token: &str,
+) -> Result<()> {
+    sqlx::query("DELETE FROM drone_tokens WHERE drone_id = $1 AND session_token = $2")
+        .bind(drone_id)
+        .bind(session_token)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 /// Load all persisted drone tokens.
-pub async fn load_all_drone_tokens(pool: &SqlitePool) -> Result<Vec<DroneTokenRow>> {
-    let rows =
-        sqlx::query_as::<_, DroneTokenRow>("SELECT drone_id, session_token FROM drone_tokens")
-            .fetch_all(pool)
-            .await?;
+pub async fn load_all_drone_tokens(pool: &DbPool) -> Result<Vec<DroneTokenRow>> {
+    let rows = sqlx::query_as::<_, DroneTokenRow>(
+        "SELECT drone_id, session_token, expires_at, mac_secret, previous_token, previous_expires_at, previous_mac_secret FROM drone_tokens",
+    )
+    .fetch_all(pool)
+    .await?;
 
     Ok(rows)
 }
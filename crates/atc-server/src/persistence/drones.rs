@@ -1,22 +1,24 @@
 //! Drone persistence operations.
 
 use anyhow::Result;
-use atc_core::models::{DroneState, DroneStatus};
+use atc_core::models::{DroneState, DroneStatus, FlightPlan};
 use chrono::{DateTime, Utc};
-use sqlx::{Sqlite, SqlitePool};
 
-/// Upsert a drone state into the database.
-pub async fn upsert_drone(pool: &SqlitePool, drone: &DroneState) -> Result<()> {
+use super::backend::{DbPool, DbTransaction};
+
+/// Upsert a drone state into the database, re-registering (un-archiving) a deregistered row.
+pub async fn upsert_drone(pool: &DbPool, drone: &DroneState) -> Result<()> {
     sqlx::query(
         r#"
         INSERT INTO drones (drone_id, owner_id, lat, lon, altitude_m, heading_deg, speed_mps, velocity_x, velocity_y, velocity_z, status, last_update)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         ON CONFLICT(drone_id) DO UPDATE SET
-            owner_id = COALESCE(?2, owner_id),
-            lat = ?3, lon = ?4, altitude_m = ?5,
-            heading_deg = ?6, speed_mps = ?7,
-            velocity_x = ?8, velocity_y = ?9, velocity_z = ?10,
-            status = ?11, last_update = ?12
+            owner_id = COALESCE($2, drones.owner_id),
+            lat = $3, lon = $4, altitude_m = $5,
+            heading_deg = $6, speed_mps = $7,
+            velocity_x = $8, velocity_y = $9, velocity_z = $10,
+            status = $11, last_update = $12,
+            archived_at = NULL
         "#,
     )
     .bind(&drone.drone_id)
@@ -38,20 +40,21 @@ pub async fn upsert_drone(pool: &SqlitePool, drone: &DroneState) -> Result<()> {
 }
 
 /// Upsert a drone state into the database within an existing transaction.
-pub async fn upsert_drone_tx(
-    tx: &mut sqlx::Transaction<'_, Sqlite>,
-    drone: &DroneState,
-) -> Result<()> {
+///
+/// Used by the telemetry flush, so an archived row is left alone: a snapshot queued before
+/// deregistration must not bring the drone back. Re-registration clears the archive instead.
+pub async fn upsert_drone_tx(tx: &mut DbTransaction<'_>, drone: &DroneState) -> Result<()> {
     sqlx::query(
         r#"
         INSERT INTO drones (drone_id, owner_id, lat, lon, altitude_m, heading_deg, speed_mps, velocity_x, velocity_y, velocity_z, status, last_update)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
         ON CONFLICT(drone_id) DO UPDATE SET
-            owner_id = COALESCE(?2, owner_id),
-            lat = ?3, lon = ?4, altitude_m = ?5,
-            heading_deg = ?6, speed_mps = ?7,
-            velocity_x = ?8, velocity_y = ?9, velocity_z = ?10,
-            status = ?11, last_update = ?12
+            owner_id = COALESCE($2, drones.owner_id),
+            lat = $3, lon = $4, altitude_m = $5,
+            heading_deg = $6, speed_mps = $7,
+            velocity_x = $8, velocity_y = $9, velocity_z = $10,
+            status = $11, last_update = $12
+        WHERE drones.archived_at IS NULL
         "#,
     )
     .bind(&drone.drone_id)
@@ -72,10 +75,54 @@ pub async fn upsert_drone_tx(
     Ok(())
 }
 
+/// Deregister a drone in one transaction: drop its pending commands, session token and
+/// per-drone settings, store its ended flight plans and mark the row archived.
+///
+/// Returns `false` when there is no unarchived row for the drone.
+pub async fn archive_drone(
+    pool: &DbPool,
+    drone_id: &str,
+    archived_at: DateTime<Utc>,
+    ended_plans: &[FlightPlan],
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let archived = sqlx::query(
+        "UPDATE drones SET status = 'Inactive', archived_at = $2 WHERE drone_id = $1 AND archived_at IS NULL",
+    )
+    .bind(drone_id)
+    .bind(archived_at.to_rfc3339())
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        > 0;
+    if !archived {
+        tx.rollback().await?;
+        return Ok(false);
+    }
+
+    super::commands::cancel_pending_commands_tx(&mut tx, drone_id).await?;
+    for table in [
+        "drone_tokens",
+        "drone_performance",
+        "drone_homes",
+        "drone_capabilities",
+    ] {
+        sqlx::query(&format!("DELETE FROM {} WHERE drone_id = $1", table))
+            .bind(drone_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for plan in ended_plans {
+        super::flight_plans::upsert_flight_plan_tx(&mut tx, plan).await?;
+    }
+    tx.commit().await?;
+    Ok(true)
+}
+
 /// Load all drones from the database.
-pub async fn load_all_drones(pool: &SqlitePool) -> Result<Vec<DroneState>> {
+pub async fn load_all_drones(pool: &DbPool) -> Result<Vec<DroneState>> {
     let rows = sqlx::query_as::<_, DroneRow>(
-        "SELECT drone_id, owner_id, lat, lon, altitude_m, heading_deg, speed_mps, velocity_x, velocity_y, velocity_z, status, last_update FROM drones"
+        "SELECT drone_id, owner_id, lat, lon, altitude_m, heading_deg, speed_mps, velocity_x, velocity_y, velocity_z, status, last_update FROM drones WHERE archived_at IS NULL"
     )
     .fetch_all(pool)
     .await?;
@@ -83,6 +130,23 @@ pub async fn load_all_drones(pool: &SqlitePool) -> Result<Vec<DroneState>> {
     Ok(rows.into_iter().map(|r| r.into()).collect())
 }
 
+/// IDs of every stored drone, archived ones included.
+pub async fn load_all_drone_ids(pool: &DbPool) -> Result<Vec<String>> {
+    Ok(sqlx::query_scalar("SELECT drone_id FROM drones")
+        .fetch_all(pool)
+        .await?)
+}
+
+/// IDs of the stored drones registered to an owner, archived ones included, sorted.
+pub async fn load_owner_drone_ids(pool: &DbPool, owner_id: &str) -> Result<Vec<String>> {
+    Ok(
+        sqlx::query_scalar("SELECT drone_id FROM drones WHERE owner_id = $1 ORDER BY drone_id")
+            .bind(owner_id)
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
 // Internal row type for SQLx
 #[derive(sqlx::FromRow)]
 struct DroneRow {
@@ -126,6 +190,8 @@ impl From<DroneRow> for DroneState {
             velocity_z: row.velocity_z,
             status,
             last_update,
+            scheduling_priority: None,
+            capabilities: None,
         }
     }
 }
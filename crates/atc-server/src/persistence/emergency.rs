@@ -0,0 +1,72 @@
+//! Persistence of the emergency all-stop.
+
+use anyhow::Result;
+use atc_core::models::{Command, CommandType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::backend::DbPool;
+use super::command_audit::CommandCause;
+
+/// An emergency all-stop in force: every drone in scope was sent the command, and flight plans
+/// are not approved until it is cleared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyStop {
+    pub emergency_id: String,
+    /// HOLD or LAND
+    pub command_type: CommandType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<String>,
+    /// `min_lat,min_lon,max_lat,max_lon`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub declared_at: DateTime<Utc>,
+    /// Broadcast tracking the drones' acknowledgements
+    pub broadcast_id: String,
+    pub drone_ids: Vec<String>,
+}
+
+/// Store a declared emergency together with its all-stop commands, in one transaction.
+pub async fn declare_emergency(
+    pool: &DbPool,
+    stop: &EmergencyStop,
+    commands: &[Command],
+    cause: &CommandCause,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    super::commands::insert_commands_tx(&mut tx, commands, cause).await?;
+    sqlx::query(
+        r#"
+        INSERT INTO emergency_stop (id, stop, declared_at)
+        VALUES (1, $1, $2)
+        ON CONFLICT(id) DO UPDATE SET
+            stop = excluded.stop,
+            declared_at = excluded.declared_at
+        "#,
+    )
+    .bind(serde_json::to_string(stop)?)
+    .bind(stop.declared_at.to_rfc3339())
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Remove the stored emergency.
+pub async fn clear_emergency(pool: &DbPool) -> Result<()> {
+    sqlx::query("DELETE FROM emergency_stop WHERE id = 1")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// The emergency in force when the server last stopped, if any.
+pub async fn load_emergency(pool: &DbPool) -> Result<Option<EmergencyStop>> {
+    let stop: Option<String> = sqlx::query_scalar("SELECT stop FROM emergency_stop WHERE id = 1")
+        .fetch_optional(pool)
+        .await?;
+    stop.map(|stop| Ok(serde_json::from_str(&stop)?))
+        .transpose()
+}
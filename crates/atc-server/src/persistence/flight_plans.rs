@@ -3,23 +3,31 @@
 use anyhow::Result;
 use atc_core::models::{FlightPlan, FlightPlanMetadata, FlightStatus, TrajectoryPoint, Waypoint};
 use chrono::{DateTime, Utc};
-use sqlx::{Sqlite, SqlitePool};
+
+use super::backend::{json_array_values, Backend, DbPool, DbTransaction};
 
 /// Serialize strategic scheduling across processes by taking a write lock early.
-pub async fn lock_scheduler(tx: &mut sqlx::Transaction<'_, Sqlite>) -> Result<()> {
-    sqlx::query("UPDATE scheduler_lock SET updated_at = CURRENT_TIMESTAMP WHERE id = 1")
-        .execute(&mut **tx)
-        .await?;
+///
+/// SQLite's first write takes the database lock for the rest of the transaction. Postgres locks
+/// only the rows written, so the scheduler row is locked `FOR UPDATE` instead: a second node's
+/// booking waits here until the first commits, then sees its reservations.
+pub async fn lock_scheduler(tx: &mut DbTransaction<'_>) -> Result<()> {
+    let statement = if cfg!(feature = "postgres") {
+        "SELECT id FROM scheduler_lock WHERE id = 1 FOR UPDATE"
+    } else {
+        "UPDATE scheduler_lock SET updated_at = CURRENT_TIMESTAMP WHERE id = 1"
+    };
+    sqlx::query(statement).execute(&mut **tx).await?;
     Ok(())
 }
 
 /// Upsert a flight plan into the database.
-pub async fn upsert_flight_plan(pool: &SqlitePool, plan: &FlightPlan) -> Result<()> {
+pub async fn upsert_flight_plan(pool: &DbPool, plan: &FlightPlan) -> Result<()> {
     // Ensure the referenced drone exists so the flight plan FK doesn't fail (e.g. Blender-synced IDs).
     sqlx::query(
         r#"
         INSERT INTO drones (drone_id, owner_id, last_update)
-        VALUES (?1, ?2, CURRENT_TIMESTAMP)
+        VALUES ($1, $2, CURRENT_TIMESTAMP)
         ON CONFLICT(drone_id) DO NOTHING
         "#,
     )
@@ -59,13 +67,13 @@ pub async fn upsert_flight_plan(pool: &SqlitePool, plan: &FlightPlan) -> Result<
             waypoints, trajectory_log, metadata,
             status, start_time, end_time, created_at
         )
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
         ON CONFLICT(flight_id) DO UPDATE SET
-            drone_id = ?2, owner_id = ?3,
-            origin_lat = ?4, origin_lon = ?5,
-            dest_lat = ?6, dest_lon = ?7,
-            waypoints = ?8, trajectory_log = ?9, metadata = ?10,
-            status = ?11, start_time = ?12, end_time = ?13
+            drone_id = $2, owner_id = $3,
+            origin_lat = $4, origin_lon = $5,
+            dest_lat = $6, dest_lon = $7,
+            waypoints = $8, trajectory_log = $9, metadata = $10,
+            status = $11, start_time = $12, end_time = $13
         "#,
     )
     .bind(&plan.flight_id)
@@ -89,15 +97,12 @@ pub async fn upsert_flight_plan(pool: &SqlitePool, plan: &FlightPlan) -> Result<
 }
 
 /// Upsert a flight plan into the database within an existing transaction.
-pub async fn upsert_flight_plan_tx(
-    tx: &mut sqlx::Transaction<'_, Sqlite>,
-    plan: &FlightPlan,
-) -> Result<()> {
+pub async fn upsert_flight_plan_tx(tx: &mut DbTransaction<'_>, plan: &FlightPlan) -> Result<()> {
     // Ensure the referenced drone exists so the flight plan FK doesn't fail (e.g. Blender-synced IDs).
     sqlx::query(
         r#"
         INSERT INTO drones (drone_id, owner_id, last_update)
-        VALUES (?1, ?2, CURRENT_TIMESTAMP)
+        VALUES ($1, $2, CURRENT_TIMESTAMP)
         ON CONFLICT(drone_id) DO NOTHING
         "#,
     )
@@ -137,13 +142,13 @@ pub async fn upsert_flight_plan_tx(
             waypoints, trajectory_log, metadata,
             status, start_time, end_time, created_at
         )
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
         ON CONFLICT(flight_id) DO UPDATE SET
-            drone_id = ?2, owner_id = ?3,
-            origin_lat = ?4, origin_lon = ?5,
-            dest_lat = ?6, dest_lon = ?7,
-            waypoints = ?8, trajectory_log = ?9, metadata = ?10,
-            status = ?11, start_time = ?12, end_time = ?13
+            drone_id = $2, owner_id = $3,
+            origin_lat = $4, origin_lon = $5,
+            dest_lat = $6, dest_lon = $7,
+            waypoints = $8, trajectory_log = $9, metadata = $10,
+            status = $11, start_time = $12, end_time = $13
         "#,
     )
     .bind(&plan.flight_id)
@@ -167,20 +172,18 @@ pub async fn upsert_flight_plan_tx(
 }
 
 /// Load all flight plans from the database.
-pub async fn load_all_flight_plans(pool: &SqlitePool) -> Result<Vec<FlightPlan>> {
+pub async fn load_all_flight_plans(pool: &DbPool) -> Result<Vec<FlightPlan>> {
     load_all_flight_plans_impl(pool).await
 }
 
 /// Load all flight plans from the database within an existing transaction.
-pub async fn load_all_flight_plans_tx(
-    tx: &mut sqlx::Transaction<'_, Sqlite>,
-) -> Result<Vec<FlightPlan>> {
+pub async fn load_all_flight_plans_tx(tx: &mut DbTransaction<'_>) -> Result<Vec<FlightPlan>> {
     load_all_flight_plans_impl(&mut **tx).await
 }
 
 async fn load_all_flight_plans_impl<'e, E>(executor: E) -> Result<Vec<FlightPlan>>
 where
-    E: sqlx::Executor<'e, Database = Sqlite>,
+    E: sqlx::Executor<'e, Database = Backend>,
 {
     let rows = sqlx::query_as::<_, FlightPlanRow>(
         "SELECT flight_id, drone_id, owner_id, waypoints, trajectory_log, metadata, status, start_time, end_time, created_at FROM flight_plans",
@@ -191,15 +194,83 @@ where
     rows.into_iter().map(|r| r.try_into()).collect()
 }
 
+/// Filters for flight plan history queries.
+#[derive(Debug, Clone, Default)]
+pub struct FlightPlanHistoryFilter {
+    pub drone_id: Option<String>,
+    pub owner_id: Option<String>,
+    pub status: Option<FlightStatus>,
+    /// Only plans departing at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only plans departing before this time.
+    pub until: Option<DateTime<Utc>>,
+    pub limit: u32,
+}
+
+/// Query persisted flight plans, newest departure first.
+///
+/// Intended for the read-only analytics pool; it never touches the in-memory scheduler state.
+pub async fn query_flight_plan_history(
+    pool: &DbPool,
+    filter: &FlightPlanHistoryFilter,
+) -> Result<Vec<FlightPlan>> {
+    let rows = sqlx::query_as::<_, FlightPlanRow>(
+        r#"
+        SELECT flight_id, drone_id, owner_id, waypoints, trajectory_log, metadata, status, start_time, end_time, created_at
+        FROM flight_plans
+        WHERE ($1 IS NULL OR drone_id = $1)
+          AND ($2 IS NULL OR owner_id = $2)
+          AND ($3 IS NULL OR status = $3)
+          AND ($4 IS NULL OR start_time >= $4)
+          AND ($5 IS NULL OR start_time < $5)
+        ORDER BY start_time DESC, flight_id
+        LIMIT $6
+        "#,
+    )
+    .bind(&filter.drone_id)
+    .bind(&filter.owner_id)
+    .bind(filter.status.map(|status| format!("{:?}", status)))
+    .bind(filter.since.map(|t| t.to_rfc3339()))
+    .bind(filter.until.map(|t| t.to_rfc3339()))
+    .bind(filter.limit as i64)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
+
+/// Load every plan filed by `owner_id` or flown by one of `drone_ids`, oldest departure first.
+pub async fn load_owner_flight_plans(
+    pool: &DbPool,
+    owner_id: &str,
+    drone_ids: &[String],
+) -> Result<Vec<FlightPlan>> {
+    let rows = sqlx::query_as::<_, FlightPlanRow>(&format!(
+        r#"
+        SELECT flight_id, drone_id, owner_id, waypoints, trajectory_log, metadata, status, start_time, end_time, created_at
+        FROM flight_plans
+        WHERE owner_id = $1 OR drone_id IN (SELECT value FROM {})
+        ORDER BY start_time, flight_id
+        "#,
+        json_array_values("$2")
+    ))
+    .bind(owner_id)
+    .bind(serde_json::to_string(drone_ids)?)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
+
 /// Load a single flight plan by ID.
 #[allow(dead_code)]
-pub async fn load_flight_plan(pool: &SqlitePool, flight_id: &str) -> Result<Option<FlightPlan>> {
+pub async fn load_flight_plan(pool: &DbPool, flight_id: &str) -> Result<Option<FlightPlan>> {
     load_flight_plan_impl(pool, flight_id).await
 }
 
 /// Load a single flight plan by ID within an existing transaction.
 pub async fn load_flight_plan_tx(
-    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    tx: &mut DbTransaction<'_>,
     flight_id: &str,
 ) -> Result<Option<FlightPlan>> {
     load_flight_plan_impl(&mut **tx, flight_id).await
@@ -207,10 +278,10 @@ pub async fn load_flight_plan_tx(
 
 async fn load_flight_plan_impl<'e, E>(executor: E, flight_id: &str) -> Result<Option<FlightPlan>>
 where
-    E: sqlx::Executor<'e, Database = Sqlite>,
+    E: sqlx::Executor<'e, Database = Backend>,
 {
     let row = sqlx::query_as::<_, FlightPlanRow>(
-        "SELECT flight_id, drone_id, owner_id, waypoints, trajectory_log, metadata, status, start_time, end_time, created_at FROM flight_plans WHERE flight_id = ?1",
+        "SELECT flight_id, drone_id, owner_id, waypoints, trajectory_log, metadata, status, start_time, end_time, created_at FROM flight_plans WHERE flight_id = $1",
     )
     .bind(flight_id)
     .fetch_optional(executor)
@@ -1,7 +1,8 @@
 //! Persistence for Blender geofence sync state.
 
 use anyhow::{Context, Result};
-use sqlx::SqlitePool;
+
+use super::backend::DbPool;
 
 #[derive(Debug, Clone)]
 pub struct GeofenceSyncState {
@@ -19,7 +20,7 @@ struct GeofenceSyncRow {
     expires_at: i64,
 }
 
-pub async fn load_geofence_sync_state(pool: &SqlitePool) -> Result<Vec<GeofenceSyncState>> {
+pub async fn load_geofence_sync_state(pool: &DbPool) -> Result<Vec<GeofenceSyncState>> {
     let rows = sqlx::query_as::<_, GeofenceSyncRow>(
         "SELECT local_id, blender_id, fingerprint, expires_at FROM geofence_sync_state",
     )
@@ -43,7 +44,7 @@ pub async fn load_geofence_sync_state(pool: &SqlitePool) -> Result<Vec<GeofenceS
 }
 
 pub async fn replace_geofence_sync_state(
-    pool: &SqlitePool,
+    pool: &DbPool,
     entries: &[GeofenceSyncState],
 ) -> Result<()> {
     let mut tx = pool.begin().await?;
@@ -55,7 +56,7 @@ pub async fn replace_geofence_sync_state(
         sqlx::query(
             r#"
             INSERT INTO geofence_sync_state (local_id, blender_id, fingerprint, expires_at)
-            VALUES (?1, ?2, ?3, ?4)
+            VALUES ($1, $2, $3, $4)
             "#,
         )
         .bind(&entry.local_id)
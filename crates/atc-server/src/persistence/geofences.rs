@@ -1,22 +1,32 @@
 //! Geofence persistence operations.
 
 use anyhow::Result;
-use atc_core::models::{Geofence, GeofenceType};
+use atc_core::models::{BreachResponse, Geofence, GeofenceType};
 use chrono::{DateTime, Utc};
-use sqlx::SqlitePool;
+
+use super::backend::DbPool;
 
 /// Upsert a geofence into the database.
-pub async fn upsert_geofence(pool: &SqlitePool, geofence: &Geofence) -> Result<()> {
+pub async fn upsert_geofence(pool: &DbPool, geofence: &Geofence) -> Result<()> {
     let polygon_json = serde_json::to_string(&geofence.polygon)?;
     let geofence_type = format!("{:?}", geofence.geofence_type);
+    let breach_response = geofence
+        .breach_response
+        .map(|response| format!("{:?}", response));
+    let schedule = geofence
+        .schedule
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()?;
 
     sqlx::query(
         r#"
-        INSERT INTO geofences (id, name, geofence_type, vertices, lower_altitude_m, upper_altitude_m, active, updated_at)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
+        INSERT INTO geofences (id, name, geofence_type, vertices, lower_altitude_m, upper_altitude_m, active, breach_response, schedule, org_id, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, CURRENT_TIMESTAMP)
         ON CONFLICT(id) DO UPDATE SET
-            name = ?2, geofence_type = ?3, vertices = ?4,
-            lower_altitude_m = ?5, upper_altitude_m = ?6, active = ?7,
+            name = $2, geofence_type = $3, vertices = $4,
+            lower_altitude_m = $5, upper_altitude_m = $6, active = $7,
+            breach_response = $8, schedule = $9, org_id = $10,
             updated_at = CURRENT_TIMESTAMP
         "#,
     )
@@ -27,6 +37,9 @@ pub async fn upsert_geofence(pool: &SqlitePool, geofence: &Geofence) -> Result<(
     .bind(geofence.lower_altitude_m)
     .bind(geofence.upper_altitude_m)
     .bind(geofence.active)
+    .bind(&breach_response)
+    .bind(&schedule)
+    .bind(&geofence.org_id)
     .execute(pool)
     .await?;
 
@@ -34,9 +47,9 @@ pub async fn upsert_geofence(pool: &SqlitePool, geofence: &Geofence) -> Result<(
 }
 
 /// Load all geofences from the database.
-pub async fn load_all_geofences(pool: &SqlitePool) -> Result<Vec<Geofence>> {
+pub async fn load_all_geofences(pool: &DbPool) -> Result<Vec<Geofence>> {
     let rows = sqlx::query_as::<_, GeofenceRow>(
-        "SELECT id, name, geofence_type, vertices, lower_altitude_m, upper_altitude_m, active, created_at FROM geofences"
+        "SELECT id, name, geofence_type, vertices, lower_altitude_m, upper_altitude_m, active, created_at, breach_response, schedule, org_id FROM geofences"
     )
     .fetch_all(pool)
     .await?;
@@ -45,8 +58,8 @@ pub async fn load_all_geofences(pool: &SqlitePool) -> Result<Vec<Geofence>> {
 }
 
 /// Delete a geofence by ID.
-pub async fn delete_geofence(pool: &SqlitePool, id: &str) -> Result<bool> {
-    let result = sqlx::query("DELETE FROM geofences WHERE id = ?1")
+pub async fn delete_geofence(pool: &DbPool, id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM geofences WHERE id = $1")
         .bind(id)
         .execute(pool)
         .await?;
@@ -65,6 +78,9 @@ struct GeofenceRow {
     upper_altitude_m: f64,
     active: bool,
     created_at: String,
+    breach_response: Option<String>,
+    schedule: Option<String>,
+    org_id: Option<String>,
 }
 
 impl TryFrom<GeofenceRow> for Geofence {
@@ -94,6 +110,26 @@ impl TryFrom<GeofenceRow> for Geofence {
             upper_altitude_m: row.upper_altitude_m,
             active: row.active,
             created_at,
+            breach_response: row
+                .breach_response
+                .as_deref()
+                .and_then(parse_breach_response),
+            schedule: row
+                .schedule
+                .as_deref()
+                .map(serde_json::from_str)
+                .transpose()?,
+            org_id: row.org_id,
         })
     }
 }
+
+fn parse_breach_response(value: &str) -> Option<BreachResponse> {
+    match value {
+        "Advisory" => Some(BreachResponse::Advisory),
+        "Hold" => Some(BreachResponse::Hold),
+        "Reroute" => Some(BreachResponse::Reroute),
+        "Land" => Some(BreachResponse::Land),
+        _ => None,
+    }
+}
@@ -0,0 +1,110 @@
+//! Background job persistence.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::backend::DbPool;
+use crate::jobs::{Job, JobStatus};
+
+#[derive(sqlx::FromRow)]
+struct JobRow {
+    job_id: String,
+    kind: String,
+    payload: String,
+    status: String,
+    attempts: i64,
+    max_attempts: i64,
+    run_at: String,
+    last_error: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+impl TryFrom<JobRow> for Job {
+    type Error = anyhow::Error;
+
+    fn try_from(row: JobRow) -> Result<Self> {
+        let timestamp = |value: &str| -> Result<DateTime<Utc>> {
+            Ok(DateTime::parse_from_rfc3339(value)?.with_timezone(&Utc))
+        };
+        Ok(Self {
+            id: row.job_id,
+            kind: serde_json::from_value(serde_json::Value::String(row.kind))?,
+            payload: serde_json::from_str(&row.payload)?,
+            status: serde_json::from_value(serde_json::Value::String(row.status))?,
+            attempts: row.attempts.max(0) as u32,
+            max_attempts: row.max_attempts.max(1) as u32,
+            run_at: timestamp(&row.run_at)?,
+            last_error: row.last_error,
+            created_at: timestamp(&row.created_at)?,
+            updated_at: timestamp(&row.updated_at)?,
+        })
+    }
+}
+
+fn enum_str<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_value(value)?
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("expected a string enum"))
+}
+
+/// Insert a job or overwrite its stored state.
+pub async fn upsert_job(pool: &DbPool, job: &Job) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO jobs (job_id, kind, payload, status, attempts, max_attempts, run_at, last_error, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        ON CONFLICT(job_id) DO UPDATE SET
+            status = excluded.status,
+            attempts = excluded.attempts,
+            max_attempts = excluded.max_attempts,
+            run_at = excluded.run_at,
+            last_error = excluded.last_error,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&job.id)
+    .bind(enum_str(&job.kind)?)
+    .bind(serde_json::to_string(&job.payload)?)
+    .bind(enum_str(&job.status)?)
+    .bind(job.attempts as i64)
+    .bind(job.max_attempts as i64)
+    .bind(job.run_at.to_rfc3339())
+    .bind(&job.last_error)
+    .bind(job.created_at.to_rfc3339())
+    .bind(job.updated_at.to_rfc3339())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Load every stored job, oldest first.
+pub async fn load_jobs(pool: &DbPool) -> Result<Vec<Job>> {
+    let rows = sqlx::query_as::<_, JobRow>(
+        r#"
+        SELECT job_id, kind, payload, status, attempts, max_attempts, run_at, last_error, created_at, updated_at
+        FROM jobs
+        ORDER BY created_at, job_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
+
+/// Delete jobs in `status` last updated before `before`; returns how many were removed.
+pub async fn delete_jobs_before(
+    pool: &DbPool,
+    status: JobStatus,
+    before: DateTime<Utc>,
+) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM jobs WHERE status = $1 AND updated_at < $2")
+        .bind(enum_str(&status)?)
+        .bind(before.to_rfc3339())
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
@@ -1,14 +1,33 @@
 //! Persistence layer for ATC Server.
 //!
-//! Provides SQLite-backed storage for drones, geofences, flight plans, and commands.
+//! Provides SQLite-backed (or, with the `postgres` feature, Postgres-backed) storage for
+//! drones, geofences, flight plans, and commands.
 //! Uses write-through caching with DashMap for hot data access.
 
+pub mod backend;
+pub mod c2_coverage;
+pub mod command_audit;
 pub mod commands;
+pub mod conflicts;
+pub mod conformance_policy;
 pub mod db;
+pub mod drone_capabilities;
+pub mod drone_homes;
+pub mod drone_performance;
 pub mod drone_tokens;
 pub mod drones;
+pub mod emergency;
 pub mod flight_plans;
 pub mod geofence_sync;
 pub mod geofences;
+pub mod jobs;
+pub mod organizations;
+pub mod owner_data;
+pub mod safety_rules;
+pub mod schema;
+pub mod telemetry_history;
+pub mod usage;
+pub mod webhooks;
 
-pub use db::{init_database, Database};
+pub use db::{init_database, open_database, Database, ReadTimeout};
+pub use schema::schema_status;
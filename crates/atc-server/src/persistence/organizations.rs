@@ -0,0 +1,110 @@
+//! Organization and membership persistence.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use super::backend::DbPool;
+use crate::organizations::Organization;
+
+#[derive(sqlx::FromRow)]
+struct OrganizationRow {
+    org_id: String,
+    name: String,
+    token_hash: String,
+    created_at: String,
+}
+
+/// Insert an organization with the hash of its API token.
+pub async fn insert_organization(
+    pool: &DbPool,
+    organization: &Organization,
+    token_hash: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO organizations (org_id, name, token_hash, created_at)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(&organization.org_id)
+    .bind(&organization.name)
+    .bind(token_hash)
+    .bind(organization.created_at.to_rfc3339())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Delete an organization and its memberships.
+pub async fn delete_organization(pool: &DbPool, org_id: &str) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM organization_members WHERE org_id = $1")
+        .bind(org_id)
+        .execute(&mut *tx)
+        .await?;
+    let result = sqlx::query("DELETE FROM organizations WHERE org_id = $1")
+        .bind(org_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Make an operator a member of an organization, moving it out of any other.
+pub async fn upsert_member(pool: &DbPool, org_id: &str, owner_id: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO organization_members (owner_id, org_id, added_at)
+        VALUES ($1, $2, $3)
+        ON CONFLICT(owner_id) DO UPDATE SET
+            org_id = excluded.org_id,
+            added_at = excluded.added_at
+        "#,
+    )
+    .bind(owner_id)
+    .bind(org_id)
+    .bind(Utc::now().to_rfc3339())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Remove an operator from an organization.
+pub async fn delete_member(pool: &DbPool, org_id: &str, owner_id: &str) -> Result<bool> {
+    let result =
+        sqlx::query("DELETE FROM organization_members WHERE org_id = $1 AND owner_id = $2")
+            .bind(org_id)
+            .bind(owner_id)
+            .execute(pool)
+            .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Load every organization with its members and token hash.
+pub async fn load_organizations(pool: &DbPool) -> Result<Vec<(Organization, String)>> {
+    let rows = sqlx::query_as::<_, OrganizationRow>(
+        "SELECT org_id, name, token_hash, created_at FROM organizations",
+    )
+    .fetch_all(pool)
+    .await?;
+    let members: Vec<(String, String)> =
+        sqlx::query_as("SELECT owner_id, org_id FROM organization_members ORDER BY owner_id")
+            .fetch_all(pool)
+            .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            let organization = Organization {
+                members: members
+                    .iter()
+                    .filter(|(_, org_id)| *org_id == row.org_id)
+                    .map(|(owner_id, _)| owner_id.clone())
+                    .collect(),
+                org_id: row.org_id,
+                name: row.name,
+                created_at: DateTime::parse_from_rfc3339(&row.created_at)?.with_timezone(&Utc),
+            };
+            Ok((organization, row.token_hash))
+        })
+        .collect()
+}
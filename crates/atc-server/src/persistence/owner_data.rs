@@ -0,0 +1,109 @@
+//! Per-owner record purges for data-retention and records requests.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::backend::{json_array_values, DbPool};
+
+/// Rows removed (or, on a dry run, that would be removed) per table.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct OwnerPurgeCounts {
+    pub drones: u64,
+    pub flight_plans: u64,
+    pub commands: u64,
+    pub command_audit: u64,
+    pub conflicts: u64,
+    pub drone_tokens: u64,
+    pub drone_performance: u64,
+    pub drone_homes: u64,
+    pub drone_capabilities: u64,
+    pub telemetry_history: u64,
+}
+
+/// Delete everything stored for `owner_id` and its drones in one transaction.
+///
+/// The owner's drones are `drone_ids` plus every stored row registered to the owner, archived
+/// (deregistered) drones included. Plans filed by the owner are removed even when flown by
+/// another owner's drone, and conflicts are removed when either participant is one of those
+/// drones. Billing usage is kept. A dry run performs the same deletes and rolls them back, so
+/// the counts are exact.
+pub async fn purge_owner_data(
+    pool: &DbPool,
+    owner_id: &str,
+    drone_ids: &[String],
+    dry_run: bool,
+) -> Result<OwnerPurgeCounts> {
+    let mut tx = pool.begin().await?;
+    let mut owned: Vec<String> =
+        sqlx::query_scalar("SELECT drone_id FROM drones WHERE owner_id = $1")
+            .bind(owner_id)
+            .fetch_all(&mut *tx)
+            .await?;
+    owned.extend(drone_ids.iter().cloned());
+    owned.sort();
+    owned.dedup();
+    let ids = serde_json::to_string(&owned)?;
+
+    let commands = sqlx::query(&format!(
+        "DELETE FROM commands WHERE drone_id IN (SELECT value FROM {})",
+        json_array_values("$1")
+    ))
+    .bind(&ids)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    let flight_plans = sqlx::query(&format!(
+        "DELETE FROM flight_plans WHERE owner_id = $1 OR drone_id IN (SELECT value FROM {})",
+        json_array_values("$2")
+    ))
+    .bind(owner_id)
+    .bind(&ids)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    let conflicts = sqlx::query(&format!(
+        r#"
+        DELETE FROM conflicts
+        WHERE drone1_id IN (SELECT value FROM {ids})
+           OR drone2_id IN (SELECT value FROM {ids})
+        "#,
+        ids = json_array_values("$1")
+    ))
+    .bind(&ids)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let mut counts = OwnerPurgeCounts {
+        commands,
+        flight_plans,
+        conflicts,
+        ..OwnerPurgeCounts::default()
+    };
+    for (table, count) in [
+        ("drone_tokens", &mut counts.drone_tokens),
+        ("drone_performance", &mut counts.drone_performance),
+        ("drone_homes", &mut counts.drone_homes),
+        ("drone_capabilities", &mut counts.drone_capabilities),
+        ("command_audit", &mut counts.command_audit),
+        ("telemetry_history", &mut counts.telemetry_history),
+        ("drones", &mut counts.drones),
+    ] {
+        *count = sqlx::query(&format!(
+            "DELETE FROM {} WHERE drone_id IN (SELECT value FROM {})",
+            table,
+            json_array_values("$1")
+        ))
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    if dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+    Ok(counts)
+}
@@ -0,0 +1,34 @@
+//! Persistence of runtime-tuned safety rules.
+
+use anyhow::Result;
+use atc_core::rules::SafetyRulesOverride;
+
+use super::backend::DbPool;
+
+/// Store the tuned values, replacing the previous ones.
+pub async fn save_rules_override(pool: &DbPool, overrides: &SafetyRulesOverride) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO safety_rules_override (id, overrides, updated_at)
+        VALUES (1, $1, CURRENT_TIMESTAMP)
+        ON CONFLICT(id) DO UPDATE SET
+            overrides = excluded.overrides,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(serde_json::to_string(overrides)?)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// The stored tuned values, if any were saved.
+pub async fn load_rules_override(pool: &DbPool) -> Result<Option<SafetyRulesOverride>> {
+    let overrides: Option<String> =
+        sqlx::query_scalar("SELECT overrides FROM safety_rules_override WHERE id = 1")
+            .fetch_optional(pool)
+            .await?;
+    overrides
+        .map(|overrides| Ok(serde_json::from_str(&overrides)?))
+        .transpose()
+}
@@ -0,0 +1,201 @@
+//! Versioned schema migrations.
+//!
+//! Migrations in `migrations/` (`migrations/postgres/` for the Postgres backend) are embedded at build time and applied in version order;
+//! `_sqlx_migrations` records when each was installed and its checksum. Before applying anything
+//! the server checks that the database was not migrated by a newer release, so a rolled-back
+//! binary refuses to run against a schema it does not know instead of writing rows the newer
+//! code cannot read. `atc-server --migrate-only` applies pending migrations and exits, for a
+//! release job that runs before new replicas start; with `ATC_DB_AUTO_MIGRATE=false` the
+//! replicas then refuse to start against a database the job has not migrated.
+
+use anyhow::Result;
+use sqlx::migrate::Migrator;
+use tracing::info;
+
+use super::backend::DbPool;
+
+#[cfg(not(feature = "postgres"))]
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+#[cfg(feature = "postgres")]
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+/// Finds the migration bookkeeping table, if the database has one.
+#[cfg(not(feature = "postgres"))]
+const MIGRATIONS_TABLE_QUERY: &str =
+    "SELECT name FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'";
+#[cfg(feature = "postgres")]
+const MIGRATIONS_TABLE_QUERY: &str =
+    "SELECT CAST(table_name AS TEXT) FROM information_schema.tables \
+     WHERE table_schema = current_schema() AND table_name = '_sqlx_migrations'";
+
+/// Where a database's schema stands relative to this release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaStatus {
+    /// Highest migration applied (None for a new database)
+    pub database_version: Option<i64>,
+    /// Highest migration this release ships
+    pub supported_version: i64,
+    /// Shipped migrations not yet applied
+    pub pending: Vec<i64>,
+    /// Applied migrations this release does not ship, i.e. from a newer release
+    pub unknown: Vec<i64>,
+    /// Migration that failed part-way on an earlier run
+    pub dirty: Option<i64>,
+}
+
+impl SchemaStatus {
+    /// Fail if this release must not touch the database.
+    pub fn check_compatible(&self) -> Result<(), SchemaError> {
+        if let Some(version) = self.dirty {
+            return Err(SchemaError::Dirty(version));
+        }
+        if !self.unknown.is_empty() {
+            return Err(SchemaError::Newer {
+                database: self.database_version.unwrap_or_default(),
+                supported: self.supported_version,
+                unknown: self.unknown.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Why the server refused to use a database.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SchemaError {
+    #[error(
+        "database schema is at version {database}, newer than this release supports \
+         ({supported}); refusing to run against unknown migrations {unknown:?}"
+    )]
+    Newer {
+        database: i64,
+        supported: i64,
+        unknown: Vec<i64>,
+    },
+    #[error(
+        "database has pending migrations {0:?}; run `atc-server --migrate-only` or set \
+         ATC_DB_AUTO_MIGRATE=true"
+    )]
+    Pending(Vec<i64>),
+    #[error(
+        "migration {0} failed part-way on an earlier run; restore the database before retrying"
+    )]
+    Dirty(i64),
+}
+
+/// Compare the migrations applied to the database with the ones this release ships.
+pub async fn schema_status(pool: &DbPool) -> Result<SchemaStatus> {
+    let tracked: Option<(String,)> = sqlx::query_as(MIGRATIONS_TABLE_QUERY)
+        .fetch_optional(pool)
+        .await?;
+    let applied: Vec<(i64, bool)> = if tracked.is_some() {
+        sqlx::query_as("SELECT version, success FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(pool)
+            .await?
+    } else {
+        Vec::new()
+    };
+
+    let shipped: Vec<i64> = MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| migration.version)
+        .collect();
+    let is_applied = |version: i64| applied.iter().any(|(applied, _)| *applied == version);
+
+    Ok(SchemaStatus {
+        database_version: applied.iter().map(|(version, _)| *version).max(),
+        supported_version: shipped.iter().copied().max().unwrap_or_default(),
+        pending: shipped
+            .iter()
+            .copied()
+            .filter(|version| !is_applied(*version))
+            .collect(),
+        unknown: applied
+            .iter()
+            .map(|(version, _)| *version)
+            .filter(|version| !shipped.contains(version))
+            .collect(),
+        dirty: applied
+            .iter()
+            .find(|(_, success)| !success)
+            .map(|(version, _)| *version),
+    })
+}
+
+/// Check the schema is compatible, then apply pending migrations if `apply` is set.
+pub async fn migrate(pool: &DbPool, apply: bool) -> Result<SchemaStatus> {
+    let status = schema_status(pool).await?;
+    status.check_compatible()?;
+    if status.pending.is_empty() {
+        return Ok(status);
+    }
+    if !apply {
+        return Err(SchemaError::Pending(status.pending).into());
+    }
+
+    MIGRATOR.run(pool).await?;
+    for migration in MIGRATOR
+        .iter()
+        .filter(|migration| status.pending.contains(&migration.version))
+    {
+        info!(
+            "Applied migration {} ({})",
+            migration.version, migration.description
+        );
+    }
+    schema_status(pool).await
+}
+
+#[cfg(all(test, not(feature = "postgres")))]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn memory_pool() -> DbPool {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn migrations_are_tracked_and_newer_schemas_refused() {
+        let pool = memory_pool().await;
+        let status = schema_status(&pool).await.unwrap();
+        assert_eq!(status.database_version, None);
+        assert!(!status.pending.is_empty());
+
+        let err = migrate(&pool, false).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<SchemaError>(),
+            Some(SchemaError::Pending(_))
+        ));
+
+        let status = migrate(&pool, true).await.unwrap();
+        assert!(status.pending.is_empty() && status.unknown.is_empty());
+        assert_eq!(status.database_version, Some(status.supported_version));
+        assert_eq!(migrate(&pool, false).await.unwrap(), status);
+
+        // A newer release applied a migration this one does not ship.
+        let newer = status.supported_version + 1;
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+             VALUES ($1, 'from the future', 1, x'00', 0)",
+        )
+        .bind(newer)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let err = migrate(&pool, true).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SchemaError>(),
+            Some(&SchemaError::Newer {
+                database: newer,
+                supported: status.supported_version,
+                unknown: vec![newer],
+            })
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Flown-track persistence.
+//!
+//! The telemetry persistence loop writes each drone's latest snapshot once per flush, so the
+//! history has one sample per drone per `ATC_TELEMETRY_FLUSH_MS` while it is reporting.
+
+use anyhow::Result;
+use atc_core::models::{DroneState, DroneStatus};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+
+use super::backend::{DbPool, DbTransaction};
+
+/// One recorded position of a drone.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TelemetrySample {
+    /// Telemetry timestamp of the snapshot
+    pub timestamp: DateTime<Utc>,
+    pub lat: f64,
+    pub lon: f64,
+    pub altitude_m: f64,
+    pub heading_deg: f64,
+    pub speed_mps: f64,
+    pub velocity_x: f64,
+    pub velocity_y: f64,
+    pub velocity_z: f64,
+    pub status: DroneStatus,
+}
+
+/// Filters for track queries.
+#[derive(Debug, Clone)]
+pub struct TrackFilter {
+    pub drone_id: String,
+    /// Samples at or after this time.
+    pub from: Option<DateTime<Utc>>,
+    /// Samples before this time.
+    pub to: Option<DateTime<Utc>>,
+    /// Keep the first sample of each bucket this long (0 = every sample).
+    pub bucket_ms: i64,
+    pub limit: u32,
+}
+
+#[derive(sqlx::FromRow)]
+struct SampleRow {
+    recorded_at_ms: i64,
+    lat: f64,
+    lon: f64,
+    altitude_m: f64,
+    heading_deg: f64,
+    speed_mps: f64,
+    velocity_x: f64,
+    velocity_y: f64,
+    velocity_z: f64,
+    status: String,
+}
+
+impl From<SampleRow> for TelemetrySample {
+    fn from(row: SampleRow) -> Self {
+        let status = match row.status.as_str() {
+            "Active" => DroneStatus::Active,
+            "Holding" => DroneStatus::Holding,
+            "Lost" => DroneStatus::Lost,
+            _ => DroneStatus::Inactive,
+        };
+        Self {
+            timestamp: Utc
+                .timestamp_millis_opt(row.recorded_at_ms)
+                .single()
+                .unwrap_or_default(),
+            lat: row.lat,
+            lon: row.lon,
+            altitude_m: row.altitude_m,
+            heading_deg: row.heading_deg,
+            speed_mps: row.speed_mps,
+            velocity_x: row.velocity_x,
+            velocity_y: row.velocity_y,
+            velocity_z: row.velocity_z,
+            status,
+        }
+    }
+}
+
+/// Append a drone snapshot to its track within an existing transaction.
+pub async fn insert_sample_tx(tx: &mut DbTransaction<'_>, drone: &DroneState) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO telemetry_history (drone_id, recorded_at_ms, lat, lon, altitude_m, heading_deg, speed_mps, velocity_x, velocity_y, velocity_z, status)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        "#,
+    )
+    .bind(&drone.drone_id)
+    .bind(drone.last_update.timestamp_millis())
+    .bind(drone.lat)
+    .bind(drone.lon)
+    .bind(drone.altitude_m)
+    .bind(drone.heading_deg)
+    .bind(drone.speed_mps)
+    .bind(drone.velocity_x)
+    .bind(drone.velocity_y)
+    .bind(drone.velocity_z)
+    .bind(format!("{:?}", drone.status))
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+// SQLite takes the other columns of an aggregate query from the row holding the MIN().
+#[cfg(not(feature = "postgres"))]
+const QUERY_TRACK: &str = r#"
+    SELECT MIN(h.recorded_at_ms) AS recorded_at_ms, lat, lon, altitude_m, heading_deg, speed_mps, velocity_x, velocity_y, velocity_z, status
+    FROM telemetry_history h
+    WHERE drone_id = $1
+      AND h.recorded_at_ms >= $2
+      AND h.recorded_at_ms < $3
+    GROUP BY h.recorded_at_ms / $4
+    ORDER BY recorded_at_ms
+    LIMIT $5
+"#;
+// Postgres keeps the first row of each bucket with DISTINCT ON.
+#[cfg(feature = "postgres")]
+const QUERY_TRACK: &str = r#"
+    SELECT DISTINCT ON (recorded_at_ms / $4) recorded_at_ms, lat, lon, altitude_m, heading_deg, speed_mps, velocity_x, velocity_y, velocity_z, status
+    FROM telemetry_history
+    WHERE drone_id = $1
+      AND recorded_at_ms >= $2
+      AND recorded_at_ms < $3
+    ORDER BY recorded_at_ms / $4, recorded_at_ms
+    LIMIT $5
+"#;
+
+/// A drone's recorded track in the time range, oldest first.
+pub async fn query_track(pool: &DbPool, filter: &TrackFilter) -> Result<Vec<TelemetrySample>> {
+    let rows = sqlx::query_as::<_, SampleRow>(QUERY_TRACK)
+        .bind(&filter.drone_id)
+        .bind(filter.from.map_or(i64::MIN, |t| t.timestamp_millis()))
+        .bind(filter.to.map_or(i64::MAX, |t| t.timestamp_millis()))
+        .bind(filter.bucket_ms.max(1))
+        .bind(filter.limit as i64)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+/// Delete samples recorded before `before`; returns the number removed.
+pub async fn delete_samples_before(pool: &DbPool, before: DateTime<Utc>) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM telemetry_history WHERE recorded_at_ms < $1")
+        .bind(before.timestamp_millis())
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,69 @@
+//! Usage metering persistence.
+
+use anyhow::Result;
+
+use super::backend::DbPool;
+use crate::metering::UsageRecord;
+
+#[derive(sqlx::FromRow)]
+struct UsageRow {
+    organization: String,
+    month: String,
+    flight_hours: f64,
+    plans_submitted: i64,
+    planner_cpu_seconds: f64,
+    api_calls: i64,
+}
+
+impl From<UsageRow> for UsageRecord {
+    fn from(row: UsageRow) -> Self {
+        Self {
+            organization: row.organization,
+            month: row.month,
+            flight_hours: row.flight_hours,
+            plans_submitted: row.plans_submitted.max(0) as u64,
+            planner_cpu_seconds: row.planner_cpu_seconds,
+            api_calls: row.api_calls.max(0) as u64,
+        }
+    }
+}
+
+/// Upsert usage records (totals, not deltas).
+pub async fn upsert_usage_records(pool: &DbPool, records: &[UsageRecord]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    for record in records {
+        sqlx::query(
+            r#"
+            INSERT INTO usage_records (organization, month, flight_hours, plans_submitted, planner_cpu_seconds, api_calls, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP)
+            ON CONFLICT(organization, month) DO UPDATE SET
+                flight_hours = $3,
+                plans_submitted = $4,
+                planner_cpu_seconds = $5,
+                api_calls = $6,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(&record.organization)
+        .bind(&record.month)
+        .bind(record.flight_hours)
+        .bind(record.plans_submitted as i64)
+        .bind(record.planner_cpu_seconds)
+        .bind(record.api_calls as i64)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Load all persisted usage records.
+pub async fn load_usage_records(pool: &DbPool) -> Result<Vec<UsageRecord>> {
+    let rows = sqlx::query_as::<_, UsageRow>(
+        "SELECT organization, month, flight_hours, plans_submitted, planner_cpu_seconds, api_calls FROM usage_records",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(UsageRecord::from).collect())
+}
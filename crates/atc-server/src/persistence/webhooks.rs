@@ -0,0 +1,71 @@
+//! Webhook subscription persistence.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use super::backend::DbPool;
+use crate::webhooks::Webhook;
+
+#[derive(sqlx::FromRow)]
+struct WebhookRow {
+    webhook_id: String,
+    url: String,
+    secret: String,
+    events: String,
+    owner_id: Option<String>,
+    created_at: String,
+}
+
+impl TryFrom<WebhookRow> for Webhook {
+    type Error = anyhow::Error;
+
+    fn try_from(row: WebhookRow) -> Result<Self> {
+        Ok(Self {
+            id: row.webhook_id,
+            url: row.url,
+            secret: row.secret,
+            events: serde_json::from_str(&row.events)?,
+            owner_id: row.owner_id,
+            created_at: DateTime::parse_from_rfc3339(&row.created_at)?.with_timezone(&Utc),
+        })
+    }
+}
+
+/// Insert a webhook subscription.
+pub async fn insert_webhook(pool: &DbPool, webhook: &Webhook) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO webhooks (webhook_id, url, secret, events, owner_id, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(&webhook.id)
+    .bind(&webhook.url)
+    .bind(&webhook.secret)
+    .bind(serde_json::to_string(&webhook.events)?)
+    .bind(&webhook.owner_id)
+    .bind(webhook.created_at.to_rfc3339())
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Delete a webhook subscription; returns whether it existed.
+pub async fn delete_webhook(pool: &DbPool, webhook_id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM webhooks WHERE webhook_id = $1")
+        .bind(webhook_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Load every webhook subscription.
+pub async fn load_webhooks(pool: &DbPool) -> Result<Vec<Webhook>> {
+    let rows = sqlx::query_as::<_, WebhookRow>(
+        "SELECT webhook_id, url, secret, events, owner_id, created_at FROM webhooks",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(|r| r.try_into()).collect()
+}
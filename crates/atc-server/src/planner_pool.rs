@@ -0,0 +1,206 @@
+//! Dedicated worker pool for route planning.
+//!
+//! Grid searches are CPU-bound and can run for seconds. On tokio's blocking pool they compete
+//! with sqlx and file I/O for the same threads, so a burst of plans could stall database writes.
+//! Planner attempts instead run on a fixed-size rayon pool (`ATC_ROUTE_PLANNER_THREADS`), and
+//! planning endpoints answer 503 while more than `ATC_ROUTE_PLANNER_MAX_QUEUE` attempts are
+//! waiting for a thread, rather than queueing work that would time out anyway.
+
+use std::fmt::Write as _;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::oneshot;
+
+use crate::config::Config;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PlannerTaskError {
+    #[error("planner task panicked")]
+    Panicked,
+}
+
+/// Too many planner attempts are already waiting for a thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannerSaturated {
+    pub queue_depth: usize,
+}
+
+#[derive(Debug, Default)]
+struct PoolCounters {
+    queued: AtomicUsize,
+    running: AtomicUsize,
+    completed: AtomicU64,
+    rejected: AtomicU64,
+}
+
+pub struct PlannerPool {
+    pool: rayon::ThreadPool,
+    max_queue: usize,
+    counters: Arc<PoolCounters>,
+}
+
+impl std::fmt::Debug for PlannerPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlannerPool")
+            .field("threads", &self.threads())
+            .field("max_queue", &self.max_queue)
+            .field("queue_depth", &self.queue_depth())
+            .finish()
+    }
+}
+
+impl PlannerPool {
+    /// `threads` of 0 uses one per CPU; `max_queue` of 0 never sheds load.
+    pub fn new(threads: usize, max_queue: usize) -> Self {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("route-planner-{}", index))
+            .build()
+            .expect("failed to start route planner threads");
+        Self {
+            pool,
+            max_queue,
+            counters: Arc::default(),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.route_planner_threads, config.route_planner_max_queue)
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Attempts waiting for a planner thread.
+    pub fn queue_depth(&self) -> usize {
+        self.counters.queued.load(Ordering::Relaxed)
+    }
+
+    /// Check a new planning request may start; counts a rejection if not.
+    pub fn admit(&self) -> Result<(), PlannerSaturated> {
+        let queue_depth = self.queue_depth();
+        if self.max_queue > 0 && queue_depth >= self.max_queue {
+            self.counters.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(PlannerSaturated { queue_depth });
+        }
+        Ok(())
+    }
+
+    /// Run CPU-bound planner work on the pool and wait for its result.
+    pub async fn run<T, F>(&self, work: F) -> Result<T, PlannerTaskError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let counters = self.counters.clone();
+        counters.queued.fetch_add(1, Ordering::Relaxed);
+        self.pool.spawn(move || {
+            counters.queued.fetch_sub(1, Ordering::Relaxed);
+            counters.running.fetch_add(1, Ordering::Relaxed);
+            let result = panic::catch_unwind(AssertUnwindSafe(work));
+            counters.running.fetch_sub(1, Ordering::Relaxed);
+            counters.completed.fetch_add(1, Ordering::Relaxed);
+            let _ = tx.send(result);
+        });
+        match rx.await {
+            Ok(Ok(value)) => Ok(value),
+            _ => Err(PlannerTaskError::Panicked),
+        }
+    }
+
+    /// Append the pool metrics in Prometheus text format.
+    pub fn render(&self, out: &mut String) {
+        let counters = &self.counters;
+        let metrics = [
+            (
+                "atc_route_planner_threads",
+                "gauge",
+                "Route planner worker threads",
+                self.threads() as u64,
+            ),
+            (
+                "atc_route_planner_queue_depth",
+                "gauge",
+                "Route planner attempts waiting for a worker thread",
+                counters.queued.load(Ordering::Relaxed) as u64,
+            ),
+            (
+                "atc_route_planner_running",
+                "gauge",
+                "Route planner attempts running",
+                counters.running.load(Ordering::Relaxed) as u64,
+            ),
+            (
+                "atc_route_planner_attempts_total",
+                "counter",
+                "Route planner attempts finished",
+                counters.completed.load(Ordering::Relaxed),
+            ),
+            (
+                "atc_route_planner_rejected_total",
+                "counter",
+                "Planning requests refused because the planner queue was full",
+                counters.rejected.load(Ordering::Relaxed),
+            ),
+        ];
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[tokio::test]
+    async fn full_queue_refuses_new_requests() {
+        let pool = Arc::new(PlannerPool::new(1, 1));
+        assert_eq!(pool.run(|| 2 + 2).await, Ok(4));
+        assert_eq!(
+            pool.run(|| -> u8 { panic!("search blew up") }).await,
+            Err(PlannerTaskError::Panicked)
+        );
+
+        // Hold the only thread so the next attempt has to queue.
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let (started_tx, started_rx) = oneshot::channel();
+        let blocker = tokio::spawn({
+            let pool = pool.clone();
+            async move {
+                pool.run(move || {
+                    let _ = started_tx.send(());
+                    let _ = release_rx.recv();
+                })
+                .await
+            }
+        });
+        started_rx.await.unwrap();
+        assert!(pool.admit().is_ok());
+        let queued = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.run(|| 7).await }
+        });
+        while pool.queue_depth() == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(pool.admit(), Err(PlannerSaturated { queue_depth: 1 }));
+
+        release_tx.send(()).unwrap();
+        blocker.await.unwrap().unwrap();
+        assert_eq!(queued.await.unwrap(), Ok(7));
+        assert!(pool.admit().is_ok());
+
+        let mut metrics = String::new();
+        pool.render(&mut metrics);
+        assert!(metrics.contains("atc_route_planner_rejected_total 1"));
+        assert!(metrics.contains("atc_route_planner_queue_depth 0"));
+    }
+}
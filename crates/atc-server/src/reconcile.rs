@@ -0,0 +1,433 @@
+//! One-shot reconciliation with Flight Blender at startup.
+//!
+//! A crash can leave Blender out of step with the database: a geofence or declaration created
+//! just before the crash whose Blender ID was never stored, a stored ID whose Blender object is
+//! gone, or conflict zones nobody is left to clear. Before the sync loops start, the geofences
+//! and declarations this server created in Blender are listed and diffed against local state.
+//! Untracked ones that still belong to a local geofence or plan are adopted, references to
+//! missing objects are dropped so the loops recreate them, and orphaned geofences are deleted.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use atc_blender::BlenderClient;
+use atc_core::models::{FlightPlan, FlightStatus};
+use serde_json::Value;
+
+use crate::blender_auth::BlenderAuthManager;
+use crate::config::Config;
+use crate::loops::flight_declaration_sync_loop::{declared_flight_id, extract_declaration_id};
+use crate::loops::geofence_sync_loop::{
+    blender_feature_properties, load_tracked, persist_tracked, BlenderGeofenceState,
+};
+use crate::state::AppState;
+
+/// Startup waits at most this long for Blender before the loops start anyway.
+const RECONCILE_TIMEOUT_SECS: u64 = 60;
+
+/// What one reconciliation pass changed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Reconciliation {
+    /// Blender objects linked to the local geofence or plan they were created for.
+    pub adopted: usize,
+    /// Local references to Blender objects that no longer exist, dropped so they are recreated.
+    pub repaired: usize,
+    /// Blender geofences with no local counterpart, deleted.
+    pub removed: usize,
+}
+
+/// Reconcile geofences and flight declarations with Blender. Failures are logged and leave the
+/// periodic loops to catch up as before.
+pub async fn reconcile_with_blender(state: &AppState, config: &Config) {
+    let run = async {
+        let auth = BlenderAuthManager::new(config);
+        let mut blender = BlenderClient::new(
+            &config.blender_url,
+            &config.blender_session_id,
+            &config.blender_auth_token,
+        );
+        if let Err(err) = auth.apply(&mut blender).await {
+            tracing::warn!("Blender reconciliation skipped, auth failed: {}", err);
+            return;
+        }
+        let geofences = reconcile_geofences(state, config, &blender).await;
+        let declarations = reconcile_declarations(state, &blender).await;
+        for (kind, result) in [("geofences", geofences), ("declarations", declarations)] {
+            match result {
+                Ok(done) => tracing::info!(
+                    "Blender {} reconciled: {} adopted, {} repaired, {} removed",
+                    kind,
+                    done.adopted,
+                    done.repaired,
+                    done.removed
+                ),
+                Err(err) => tracing::warn!("Blender {} reconciliation failed: {}", kind, err),
+            }
+        }
+    };
+    if tokio::time::timeout(Duration::from_secs(RECONCILE_TIMEOUT_SECS), run)
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "Blender reconciliation timed out after {}s; starting sync loops",
+            RECONCILE_TIMEOUT_SECS
+        );
+    }
+}
+
+async fn reconcile_geofences(
+    state: &AppState,
+    config: &Config,
+    blender: &BlenderClient,
+) -> Result<Reconciliation> {
+    let remote = blender.fetch_geofences(None).await?;
+    let listed: HashSet<String> = remote.iter().filter_map(blender_id).collect();
+    let owned: Vec<OwnedGeofence> = remote.iter().filter_map(owned_geofence).collect();
+    let active_local: HashSet<String> = state
+        .get_local_geofences()
+        .into_iter()
+        .filter(|geofence| geofence.active)
+        .map(|geofence| geofence.id)
+        .collect();
+
+    let path = PathBuf::from(&config.geofence_sync_state_path);
+    let db = state.database();
+    let mut tracked = load_tracked(db, &path).await;
+    let (mut done, delete) = reconcile_geofence_tracking(
+        &mut tracked,
+        &listed,
+        &owned,
+        &active_local,
+        &state.get_conflict_geofence_ids(),
+    );
+    if done.adopted > 0 || done.repaired > 0 {
+        persist_tracked(db, &path, &tracked).await;
+    }
+    done.removed = 0;
+    for blender_id in delete {
+        match blender.delete_geofence(&blender_id).await {
+            Ok(()) => done.removed += 1,
+            Err(err) => {
+                tracing::warn!("Failed to delete orphaned geofence {}: {}", blender_id, err)
+            }
+        }
+    }
+    Ok(done)
+}
+
+async fn reconcile_declarations(
+    state: &AppState,
+    blender: &BlenderClient,
+) -> Result<Reconciliation> {
+    let declarations = blender.fetch_flight_declarations().await?;
+    let plans = state.get_flight_plans();
+    let diff = diff_declarations(&plans, &declarations);
+    let by_id: HashMap<&str, &FlightPlan> = plans
+        .iter()
+        .map(|plan| (plan.flight_id.as_str(), plan))
+        .collect();
+    let mut done = Reconciliation::default();
+
+    for (flight_id, declaration_id) in &diff.adopt {
+        let Some(plan) = by_id.get(flight_id.as_str()) else {
+            continue;
+        };
+        let mut updated = (*plan).clone();
+        updated
+            .metadata
+            .get_or_insert_with(Default::default)
+            .blender_declaration_id = Some(declaration_id.clone());
+        state.add_flight_plan(updated).await?;
+        tracing::info!(
+            "Adopted Blender declaration {} for flight {}",
+            declaration_id,
+            flight_id
+        );
+        done.adopted += 1;
+    }
+
+    // The listing may be windowed, so confirm a declaration is gone before forgetting it.
+    for (flight_id, declaration_id) in &diff.missing {
+        if blender.flight_declaration_exists(declaration_id).await? {
+            continue;
+        }
+        let Some(plan) = by_id.get(flight_id.as_str()) else {
+            continue;
+        };
+        let mut updated = (*plan).clone();
+        if let Some(metadata) = updated.metadata.as_mut() {
+            metadata.blender_declaration_id = None;
+            metadata.blender_declaration_state = None;
+        }
+        state.add_flight_plan(updated).await?;
+        tracing::warn!(
+            "Blender declaration {} for flight {} no longer exists; cleared",
+            declaration_id,
+            flight_id
+        );
+        done.repaired += 1;
+    }
+    Ok(done)
+}
+
+/// A geofence in Blender that this server created.
+#[derive(Debug, Clone, PartialEq)]
+struct OwnedGeofence {
+    blender_id: String,
+    /// Local geofence it was pushed for; `None` for conflict zones.
+    local_id: Option<String>,
+    fingerprint: u64,
+}
+
+fn blender_id(entry: &Value) -> Option<String> {
+    entry.get("id")?.as_str().map(str::to_string)
+}
+
+fn owned_geofence(entry: &Value) -> Option<OwnedGeofence> {
+    let blender_id = blender_id(entry)?;
+    let properties = blender_feature_properties(entry)?;
+    if let Some(local_id) = properties.get("atc_geofence_id").and_then(Value::as_str) {
+        return Some(OwnedGeofence {
+            blender_id,
+            local_id: Some(local_id.to_string()),
+            fingerprint: properties
+                .get("atc_fingerprint")
+                .and_then(Value::as_u64)
+                .unwrap_or(0),
+        });
+    }
+    let conflict_zone = properties.get("conflict").is_some()
+        || properties
+            .get("name")
+            .and_then(Value::as_str)
+            .is_some_and(|name| name.starts_with("Conflict: "));
+    conflict_zone.then_some(OwnedGeofence {
+        blender_id,
+        local_id: None,
+        fingerprint: 0,
+    })
+}
+
+/// Diff geofence tracking against what Blender lists. Updates `tracked` in place and returns
+/// the Blender IDs to delete.
+///
+/// Tracked entries missing from Blender are dropped so the sync loop recreates them. Untracked
+/// geofences pushed for an active local geofence are adopted with the fingerprint they were
+/// pushed with, so the loop replaces them only if the geofence changed since. Everything else
+/// this server created is an orphan or a duplicate, as are conflict zones the conflict loop
+/// does not know about.
+fn reconcile_geofence_tracking(
+    tracked: &mut HashMap<String, BlenderGeofenceState>,
+    listed: &HashSet<String>,
+    owned: &[OwnedGeofence],
+    active_local: &HashSet<String>,
+    conflict_ids: &HashSet<String>,
+) -> (Reconciliation, Vec<String>) {
+    let mut done = Reconciliation::default();
+    let before = tracked.len();
+    tracked.retain(|_, entry| listed.contains(&entry.blender_id));
+    done.repaired = before - tracked.len();
+
+    let mut delete = Vec::new();
+    for geofence in owned {
+        let Some(local_id) = geofence.local_id.as_ref() else {
+            if !conflict_ids.contains(&geofence.blender_id) {
+                delete.push(geofence.blender_id.clone());
+            }
+            continue;
+        };
+        match tracked.get(local_id) {
+            Some(entry) if entry.blender_id == geofence.blender_id => {}
+            None if active_local.contains(local_id) => {
+                tracked.insert(
+                    local_id.clone(),
+                    BlenderGeofenceState {
+                        blender_id: geofence.blender_id.clone(),
+                        fingerprint: geofence.fingerprint,
+                        // Unknown; due for refresh, which also renews the Blender end time.
+                        expires_at: 0,
+                    },
+                );
+                done.adopted += 1;
+            }
+            _ => delete.push(geofence.blender_id.clone()),
+        }
+    }
+    done.removed = delete.len();
+    (done, delete)
+}
+
+/// Declarations to link to plans, and plan references to declarations Blender did not list.
+#[derive(Debug, Default, PartialEq)]
+struct DeclarationDiff {
+    /// `(flight_id, declaration_id)` of declarations created for a plan that lost the ID.
+    adopt: Vec<(String, String)>,
+    /// `(flight_id, declaration_id)` of referenced declarations missing from the listing.
+    missing: Vec<(String, String)>,
+}
+
+fn diff_declarations(plans: &[FlightPlan], declarations: &[Value]) -> DeclarationDiff {
+    let listed: HashSet<String> = declarations
+        .iter()
+        .filter_map(extract_declaration_id)
+        .collect();
+    let mut declared_for: HashMap<String, String> = HashMap::new();
+    for declaration in declarations {
+        if let (Some(declaration_id), Some(flight_id)) = (
+            extract_declaration_id(declaration),
+            declared_flight_id(declaration),
+        ) {
+            declared_for.entry(flight_id).or_insert(declaration_id);
+        }
+    }
+
+    let mut diff = DeclarationDiff::default();
+    for plan in plans {
+        if !matches!(
+            plan.status,
+            FlightStatus::Pending | FlightStatus::Approved | FlightStatus::Active
+        ) {
+            continue;
+        }
+        let linked = plan
+            .metadata
+            .as_ref()
+            .and_then(|meta| meta.blender_declaration_id.as_deref())
+            .filter(|id| !id.trim().is_empty());
+        match linked {
+            Some(id) if !listed.contains(id) => {
+                diff.missing.push((plan.flight_id.clone(), id.to_string()));
+            }
+            Some(_) => {}
+            None => {
+                if let Some(declaration_id) = declared_for.get(&plan.flight_id) {
+                    diff.adopt
+                        .push((plan.flight_id.clone(), declaration_id.clone()));
+                }
+            }
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atc_core::models::{FlightPlanMetadata, Waypoint};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn remote_geofence(blender_id: &str, properties: Value) -> Value {
+        json!({
+            "id": blender_id,
+            "raw_geo_fence": {
+                "type": "FeatureCollection",
+                "features": [{ "type": "Feature", "properties": properties }]
+            }
+        })
+    }
+
+    fn plan(flight_id: &str, status: FlightStatus, declaration_id: Option<&str>) -> FlightPlan {
+        FlightPlan {
+            flight_id: flight_id.to_string(),
+            drone_id: "D1".to_string(),
+            owner_id: None,
+            waypoints: vec![Waypoint {
+                lat: 33.0,
+                lon: -117.0,
+                altitude_m: 50.0,
+                speed_mps: None,
+            }],
+            trajectory_log: None,
+            metadata: Some(FlightPlanMetadata {
+                blender_declaration_id: declaration_id.map(str::to_string),
+                ..Default::default()
+            }),
+            status,
+            departure_time: Utc::now(),
+            arrival_time: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn geofences_are_adopted_repaired_and_removed() {
+        let remote = [
+            remote_geofence("b-kept", json!({ "atc_geofence_id": "kept" })),
+            // Pushed just before a crash, so never tracked.
+            remote_geofence(
+                "b-lost",
+                json!({ "atc_geofence_id": "lost", "atc_fingerprint": 42 }),
+            ),
+            remote_geofence("b-lost-dup", json!({ "atc_geofence_id": "lost" })),
+            remote_geofence("b-deleted", json!({ "atc_geofence_id": "deleted" })),
+            remote_geofence("b-conflict", json!({ "name": "Conflict: D1 vs D2" })),
+            remote_geofence("b-external", json!({ "name": "Stadium TFR" })),
+        ];
+        let listed: HashSet<String> = remote.iter().filter_map(blender_id).collect();
+        let owned: Vec<OwnedGeofence> = remote.iter().filter_map(owned_geofence).collect();
+        assert_eq!(owned.len(), 5);
+        let tracked_entry = |blender_id: &str| BlenderGeofenceState {
+            blender_id: blender_id.to_string(),
+            fingerprint: 7,
+            expires_at: 100,
+        };
+        let mut tracked = HashMap::from([
+            ("kept".to_string(), tracked_entry("b-kept")),
+            ("expired".to_string(), tracked_entry("b-gone")),
+        ]);
+        let active: HashSet<String> = ["kept", "lost", "expired"]
+            .map(str::to_string)
+            .into_iter()
+            .collect();
+
+        let (done, delete) =
+            reconcile_geofence_tracking(&mut tracked, &listed, &owned, &active, &HashSet::new());
+        assert_eq!(
+            done,
+            Reconciliation {
+                adopted: 1,
+                repaired: 1,
+                removed: 3
+            }
+        );
+        assert_eq!(delete, ["b-lost-dup", "b-deleted", "b-conflict"]);
+        assert_eq!(tracked["lost"].blender_id, "b-lost");
+        assert_eq!(tracked["lost"].fingerprint, 42);
+        assert_eq!(tracked["kept"].blender_id, "b-kept");
+        assert!(!tracked.contains_key("expired"));
+    }
+
+    #[test]
+    fn declarations_are_adopted_or_flagged_missing() {
+        let declaration = |id: &str, flight_id: &str| {
+            json!({
+                "id": id,
+                "flight_declaration_geo_json": {
+                    "features": [{
+                        "properties": { "compliance": { "atc_plan_id": flight_id } }
+                    }]
+                }
+            })
+        };
+        let declarations = [declaration("dec-1", "F1"), declaration("dec-2", "F2")];
+        let plans = [
+            plan("F1", FlightStatus::Approved, None),
+            plan("F2", FlightStatus::Active, Some("dec-2")),
+            plan("F3", FlightStatus::Approved, Some("dec-3")),
+            plan("F4", FlightStatus::Completed, Some("dec-4")),
+        ];
+
+        let diff = diff_declarations(&plans, &declarations);
+        assert_eq!(
+            diff,
+            DeclarationDiff {
+                adopt: vec![("F1".to_string(), "dec-1".to_string())],
+                missing: vec![("F3".to_string(), "dec-3".to_string())],
+            }
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Explanations for flight plans the strategic scheduler rejected.
+//!
+//! When no departure slot in the delay window is conflict-free, the scheduler records, for every
+//! slot and route option it tried, which plans blocked it, when both flights are airborne and
+//! where they come closest. Operators read this back from `/v1/flights/:id/rejection-detail` to
+//! adjust the route or departure time.
+
+use atc_core::models::FlightPlan;
+use atc_core::rules::SafetyRules;
+use atc_core::spatial::{explain_plan_conflict, PlanConflictDetail};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Rejections kept in memory.
+pub const REJECTION_LOG_CAPACITY: usize = 200;
+
+/// Why the scheduler found no slot for a plan.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PlanRejection {
+    pub flight_id: String,
+    pub drone_id: String,
+    pub owner_id: Option<String>,
+    pub requested_departure: DateTime<Utc>,
+    /// Every slot tried, earliest first
+    pub slots: Vec<RejectedSlot>,
+    pub rejected_at: DateTime<Utc>,
+}
+
+/// One departure time and route option the scheduler tried.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RejectedSlot {
+    pub departure_time: DateTime<Utc>,
+    pub route_option: String,
+    pub blocking: Vec<BlockingPlan>,
+}
+
+/// An accepted plan that conflicts with a tried slot.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BlockingPlan {
+    pub flight_id: String,
+    pub drone_id: String,
+    pub owner_id: Option<String>,
+    #[serde(flatten)]
+    pub conflict: PlanConflictDetail,
+}
+
+/// Explain why `candidate`, flying `route_option`, conflicts with `active_plans`.
+pub fn explain_slot(
+    candidate: &FlightPlan,
+    route_option: &str,
+    active_plans: &[FlightPlan],
+    rules: &SafetyRules,
+) -> RejectedSlot {
+    let blocking = active_plans
+        .iter()
+        .filter_map(|existing| {
+            let conflict = explain_plan_conflict(candidate, existing, rules)?;
+            Some(BlockingPlan {
+                flight_id: existing.flight_id.clone(),
+                drone_id: existing.drone_id.clone(),
+                owner_id: existing.owner_id.clone(),
+                conflict,
+            })
+        })
+        .collect();
+    RejectedSlot {
+        departure_time: candidate.departure_time,
+        route_option: route_option.to_string(),
+        blocking,
+    }
+}
@@ -0,0 +1,322 @@
+//! Dynamic in-flight replanning.
+//!
+//! When a new geofence crosses the rest of an active flight's route, or the conflict loop has to
+//! reroute a drone that is flying a plan, the remaining route is replanned from the drone's
+//! current position to the plan's destination with `plan_airborne_route`. The drone gets a
+//! REROUTE carrying the whole new path, and the plan's waypoints are replaced with it so later
+//! checks run against the route actually being flown.
+
+use std::collections::HashSet;
+
+use atc_core::models::{
+    Command, CommandType, DroneState, DroneStatus, FlightPlan, FlightStatus, Geofence,
+    GeofenceType, Waypoint,
+};
+use atc_core::spatial::distance_to_segment_m;
+use chrono::{Duration as ChronoDuration, Utc};
+
+use crate::loops::conflict_loop::executable_command;
+use crate::persistence::command_audit::{CommandCause, CommandCauseKind};
+use crate::route_planner::plan_airborne_route;
+use crate::state::AppState;
+
+/// Lifetime of a replanned-route command.
+const REPLAN_COMMAND_TTL_SECS: i64 = 120;
+
+/// A replanned remaining route for an active flight.
+#[derive(Debug, Clone)]
+pub struct Replan {
+    pub flight_id: String,
+    pub waypoints: Vec<Waypoint>,
+}
+
+/// The drone's active flight plan, if it is flying one.
+pub fn active_plan(state: &AppState, drone_id: &str) -> Option<FlightPlan> {
+    state
+        .get_flight_plans()
+        .into_iter()
+        .find(|plan| plan.drone_id == drone_id && plan.status == FlightStatus::Active)
+}
+
+/// What is left of `waypoints` for a drone at its current position: the position itself, then
+/// the end of the leg it is nearest to and every waypoint after it.
+pub fn remaining_route(waypoints: &[Waypoint], drone: &DroneState) -> Vec<Waypoint> {
+    let next = waypoints
+        .windows(2)
+        .map(|leg| {
+            distance_to_segment_m(
+                drone.lat, drone.lon, leg[0].lat, leg[0].lon, leg[1].lat, leg[1].lon,
+            )
+        })
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(idx, _)| idx + 1)
+        .unwrap_or(waypoints.len());
+    let mut route = vec![Waypoint {
+        lat: drone.lat,
+        lon: drone.lon,
+        altitude_m: drone.altitude_m,
+        speed_mps: waypoints.get(next).and_then(|wp| wp.speed_mps),
+    }];
+    route.extend_from_slice(&waypoints[next..]);
+    route
+}
+
+/// First of `geofences` that restricts flight, is in force now, and crosses `route`.
+pub fn blocking_geofence<'a>(
+    route: &[Waypoint],
+    geofences: &'a [Geofence],
+) -> Option<&'a Geofence> {
+    let now = Utc::now();
+    geofences
+        .iter()
+        .filter(|fence| fence.in_force_at(now) && fence.geofence_type != GeofenceType::Advisory)
+        .find(|fence| {
+            route.windows(2).any(|leg| {
+                fence.intersects_segment(
+                    leg[0].lat,
+                    leg[0].lon,
+                    leg[0].altitude_m,
+                    leg[1].lat,
+                    leg[1].lon,
+                    leg[1].altitude_m,
+                )
+            })
+        })
+}
+
+/// Replan the rest of `drone`'s active flight around the known geofences and
+/// `extra_geofences`. `None` when the drone is not flying a plan or no route was found.
+pub async fn replan_remaining_route(
+    state: &AppState,
+    drone: &DroneState,
+    extra_geofences: &[Geofence],
+) -> Option<Replan> {
+    let plan = active_plan(state, &drone.drone_id)?;
+    let destination = plan.waypoints.last()?.clone();
+    let remaining = remaining_route(&plan.waypoints, drone);
+    let config = state.config();
+    let mut waypoints = plan_airborne_route(
+        state,
+        config,
+        &remaining,
+        config.compliance_default_clearance_m,
+        extra_geofences,
+        state.drone_performance(&drone.drone_id).as_ref(),
+    )
+    .await?;
+    // Airborne routes end at cruise altitude; keep the plan's own descent to its landing point.
+    if waypoints
+        .last()
+        .is_some_and(|last| (last.altitude_m - destination.altitude_m).abs() > f64::EPSILON)
+    {
+        waypoints.push(destination);
+    }
+    Some(Replan {
+        flight_id: plan.flight_id,
+        waypoints,
+    })
+}
+
+/// Replace the plan's waypoints with the replanned route, if the flight is still active.
+pub async fn record_replan(state: &AppState, replan: &Replan) {
+    let Some(mut plan) = state
+        .get_flight_plans()
+        .into_iter()
+        .find(|plan| plan.flight_id == replan.flight_id && plan.status == FlightStatus::Active)
+    else {
+        return;
+    };
+    plan.waypoints = replan.waypoints.clone();
+    if let Err(err) = state.add_flight_plan(plan).await {
+        tracing::warn!(
+            "Failed to store replanned route for flight {}: {}",
+            replan.flight_id,
+            err
+        );
+    }
+}
+
+/// Replan active flights whose remaining route crosses a geofence that was not in `known`.
+///
+/// `known` holds the geofence IDs seen on the previous call; the first call only records them,
+/// as plans were checked against the geofences that existed when they were accepted.
+pub async fn replan_for_new_geofences(state: &AppState, known: &mut Option<HashSet<String>>) {
+    let geofences = state.get_geofences();
+    let current: HashSet<String> = geofences.iter().map(|fence| fence.id.clone()).collect();
+    let Some(previous) = known.replace(current) else {
+        return;
+    };
+    let new_geofences: Vec<Geofence> = geofences
+        .into_iter()
+        .filter(|fence| !previous.contains(&fence.id))
+        .collect();
+    if new_geofences.is_empty() {
+        return;
+    }
+
+    let wind_mps = state.config().route_planner_wind_mps.max(0.0);
+    for plan in state.get_flight_plans() {
+        if plan.status != FlightStatus::Active {
+            continue;
+        }
+        let Some(drone) = state.get_drone(&plan.drone_id) else {
+            continue;
+        };
+        if matches!(drone.status, DroneStatus::Lost | DroneStatus::Inactive) {
+            continue;
+        }
+        let remaining = remaining_route(&plan.waypoints, &drone);
+        let Some(fence) = blocking_geofence(&remaining, &new_geofences) else {
+            continue;
+        };
+        let Some(replan) = replan_remaining_route(state, &drone, &[]).await else {
+            tracing::warn!(
+                "No route found to replan flight {} around new geofence {}",
+                plan.flight_id,
+                fence.id
+            );
+            continue;
+        };
+        let Some(command_type) = executable_command(
+            CommandType::Reroute {
+                waypoints: replan.waypoints.clone(),
+                reason: Some(format!("Replanned around geofence '{}'", fence.name)),
+            },
+            &drone,
+            state.drone_performance(&drone.drone_id).as_ref(),
+            wind_mps,
+        ) else {
+            continue;
+        };
+        let rerouted = matches!(command_type, CommandType::Reroute { .. });
+        let now = Utc::now();
+        let cmd = Command {
+            command_id: format!("REPLAN-{}-{}", drone.drone_id, now.timestamp()),
+            drone_id: drone.drone_id.clone(),
+            command_type,
+            issued_at: now,
+            expires_at: Some(now + ChronoDuration::seconds(REPLAN_COMMAND_TTL_SECS)),
+            acknowledged: false,
+        };
+        let sent = match state
+            .enqueue_auto_command(
+                cmd,
+                CommandCause::new(CommandCauseKind::Geofence).with_source(&fence.id),
+            )
+            .await
+        {
+            Ok(sent) => sent,
+            Err(err) => {
+                tracing::warn!(
+                    "Failed to enqueue replanned route for {}: {}",
+                    drone.drone_id,
+                    err
+                );
+                continue;
+            }
+        };
+        state.mark_command_issued(&drone.drone_id);
+        if sent && rerouted {
+            record_replan(state, &replan).await;
+            tracing::info!(
+                "Replanned flight {} around new geofence {}",
+                plan.flight_id,
+                fence.id
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use atc_core::spatial::offset_position;
+
+    const ORIGIN: (f64, f64) = (33.6846, -117.8265);
+
+    fn waypoint(north_m: f64, altitude_m: f64) -> Waypoint {
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m, 0.0);
+        Waypoint {
+            lat,
+            lon,
+            altitude_m,
+            speed_mps: Some(12.0),
+        }
+    }
+
+    fn square(id: &str, north_m: f64, half_m: f64) -> Geofence {
+        let corner = |n: f64, e: f64| {
+            let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, north_m + n, e);
+            [lat, lon]
+        };
+        Geofence {
+            id: id.to_string(),
+            name: id.to_string(),
+            geofence_type: GeofenceType::TemporaryRestriction,
+            polygon: vec![
+                corner(-half_m, -half_m),
+                corner(-half_m, half_m),
+                corner(half_m, half_m),
+                corner(half_m, -half_m),
+                corner(-half_m, -half_m),
+            ],
+            lower_altitude_m: 0.0,
+            upper_altitude_m: 120.0,
+            active: true,
+            created_at: Utc::now(),
+            breach_response: None,
+            schedule: None,
+            org_id: None,
+        }
+    }
+
+    #[test]
+    fn remaining_route_starts_at_the_drone_and_skips_flown_legs() {
+        let plan = [
+            waypoint(0.0, 0.0),
+            waypoint(0.0, 60.0),
+            waypoint(1_000.0, 60.0),
+            waypoint(2_000.0, 60.0),
+            waypoint(2_000.0, 0.0),
+        ];
+        let (lat, lon) = offset_position(ORIGIN.0, ORIGIN.1, 1_200.0, 5.0);
+        let drone = DroneState {
+            drone_id: "D1".to_string(),
+            owner_id: None,
+            lat,
+            lon,
+            altitude_m: 61.0,
+            heading_deg: 0.0,
+            speed_mps: 12.0,
+            velocity_x: 0.0,
+            velocity_y: 12.0,
+            velocity_z: 0.0,
+            last_update: Utc::now(),
+            status: DroneStatus::Active,
+            scheduling_priority: None,
+            capabilities: None,
+        };
+
+        let remaining = remaining_route(&plan, &drone);
+        assert_eq!(remaining.len(), 3);
+        assert_eq!((remaining[0].lat, remaining[0].lon), (lat, lon));
+        assert_eq!(remaining[0].speed_mps, Some(12.0));
+        assert_eq!(remaining[1].lat, plan[3].lat);
+        assert_eq!(remaining[2].altitude_m, 0.0);
+
+        // A geofence over the flown part of the route does not block the rest of it.
+        let behind = [square("behind", 500.0, 100.0)];
+        assert!(blocking_geofence(&plan, &behind).is_some());
+        assert!(blocking_geofence(&remaining, &behind).is_none());
+
+        let mut advisory = square("ahead", 1_600.0, 100.0);
+        advisory.geofence_type = GeofenceType::Advisory;
+        assert!(blocking_geofence(&remaining, std::slice::from_ref(&advisory)).is_none());
+        advisory.geofence_type = GeofenceType::NoFlyZone;
+        assert_eq!(
+            blocking_geofence(&remaining, std::slice::from_ref(&advisory)).map(|f| f.id.as_str()),
+            Some("ahead")
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Cached routing graphs for repeated origin/destination pairs.
+//!
+//! Generating a route grid and applying obstacles and terrain to it is most of the cost of a
+//! plan, and fleets fly the same depot-to-depot routes all day. The obstacle-applied grid is
+//! kept per region and route geometry, in memory and optionally on disk, and reused while the
+//! obstacles and terrain it was built from are unchanged. Time-dependent costs (weather,
+//! coverage, traffic, wind) and altitude layers are applied to a copy per request.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use atc_core::models::Waypoint;
+use atc_core::route_engine::{apply_obstacles, generate_grid_samples, RouteGrid, RouteObstacle};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+use crate::config::Config;
+use crate::terrain::TerrainGrid;
+
+/// Grids can run to hundreds of thousands of points, so only the busiest routes stay in memory.
+const ROUTE_GRAPH_CACHE_MAX_ENTRIES: usize = 32;
+/// Bumped when the persisted format or grid construction changes.
+const ROUTE_GRAPH_FORMAT_VERSION: u32 = 1;
+/// Region cells are this many degrees across (about 1 km).
+const REGION_CELL_DEG: f64 = 0.01;
+
+#[derive(Debug, Clone)]
+struct RouteGraphEntry {
+    fetched_at: Instant,
+    grid: Arc<RouteGrid>,
+}
+
+impl cache::CacheEntry for RouteGraphEntry {
+    fn fetched_at(&self) -> Instant {
+        self.fetched_at
+    }
+}
+
+fn route_graph_cache() -> &'static DashMap<String, RouteGraphEntry> {
+    static CACHE: OnceLock<DashMap<String, RouteGraphEntry>> = OnceLock::new();
+    CACHE.get_or_init(DashMap::new)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedRouteGraph {
+    version: u32,
+    key: String,
+    grid: RouteGrid,
+}
+
+/// Why a grid could not be built.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteGraphError {
+    /// The route has too few waypoints to sample.
+    Empty,
+    /// The grid has more points than the planner allows.
+    TooLarge(usize),
+}
+
+/// Where and how long route graphs are kept.
+#[derive(Debug, Clone)]
+pub struct RouteGraphStore {
+    ttl: Duration,
+    dir: Option<PathBuf>,
+}
+
+impl RouteGraphStore {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            ttl: Duration::from_secs(config.route_graph_cache_ttl_s),
+            dir: config.route_graph_cache_dir.as_ref().map(PathBuf::from),
+        }
+    }
+
+    /// Grid for the route with obstacles and terrain applied, reused from the cache when one
+    /// was built for the same route, grid parameters, obstacles and terrain.
+    #[allow(clippy::too_many_arguments)]
+    pub fn grid(
+        &self,
+        waypoints: &[Waypoint],
+        spacing_m: f64,
+        lane_offsets: &[f64],
+        phase: f64,
+        obstacles: &[RouteObstacle],
+        terrain: Option<&TerrainGrid>,
+        max_points: usize,
+    ) -> Result<RouteGrid, RouteGraphError> {
+        let build = || {
+            let mut grid = generate_grid_samples(waypoints, spacing_m, lane_offsets, phase)
+                .ok_or(RouteGraphError::Empty)?;
+            let sample_points = grid
+                .lanes
+                .first()
+                .map(|lane| lane.len() * grid.lanes.len())
+                .unwrap_or(0);
+            if sample_points > max_points {
+                return Err(RouteGraphError::TooLarge(sample_points));
+            }
+            apply_obstacles(&mut grid, obstacles, |lat, lon| {
+                terrain.map(|grid| grid.sample(lat, lon)).unwrap_or(0.0)
+            });
+            Ok(grid)
+        };
+        if self.ttl.is_zero() {
+            return build();
+        }
+        let key = graph_key(
+            waypoints,
+            spacing_m,
+            lane_offsets,
+            phase,
+            obstacles,
+            terrain.map(TerrainGrid::fingerprint),
+        );
+        self.load_or_build(&key, build)
+            .map(|grid| grid.as_ref().clone())
+    }
+
+    fn load_or_build<F>(&self, key: &str, build: F) -> Result<Arc<RouteGrid>, RouteGraphError>
+    where
+        F: FnOnce() -> Result<RouteGrid, RouteGraphError>,
+    {
+        let cache = route_graph_cache();
+        if let Some(entry) = cache.get(key) {
+            if entry.fetched_at.elapsed() <= self.ttl {
+                tracing::debug!(key, "Route graph cache hit");
+                return Ok(entry.grid.clone());
+            }
+        }
+
+        let grid = match self.load_persisted(key) {
+            Some(grid) => {
+                tracing::debug!(key, "Route graph loaded from disk");
+                Arc::new(grid)
+            }
+            None => {
+                let grid = Arc::new(build()?);
+                self.persist(key, &grid);
+                grid
+            }
+        };
+        cache.insert(
+            key.to_string(),
+            RouteGraphEntry {
+                fetched_at: Instant::now(),
+                grid: grid.clone(),
+            },
+        );
+        cache::prune_cache(cache, ROUTE_GRAPH_CACHE_MAX_ENTRIES, self.ttl);
+        Ok(grid)
+    }
+
+    fn path_for(&self, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.json", key)))
+    }
+
+    fn load_persisted(&self, key: &str) -> Option<RouteGrid> {
+        let path = self.path_for(key)?;
+        let age = std::fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+        if age > self.ttl {
+            return None;
+        }
+        let contents = std::fs::read(&path).ok()?;
+        match serde_json::from_slice::<PersistedRouteGraph>(&contents) {
+            Ok(persisted)
+                if persisted.version == ROUTE_GRAPH_FORMAT_VERSION && persisted.key == key =>
+            {
+                Some(persisted.grid)
+            }
+            Ok(_) => None,
+            Err(err) => {
+                tracing::warn!(
+                    "Ignoring unreadable route graph {}: {}",
+                    path.display(),
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    fn persist(&self, key: &str, grid: &RouteGrid) {
+        let Some(path) = self.path_for(key) else {
+            return;
+        };
+        if let Err(err) = write_persisted(&path, key, grid) {
+            tracing::warn!("Failed to persist route graph {}: {}", path.display(), err);
+        }
+    }
+}
+
+fn write_persisted(path: &Path, key: &str, grid: &RouteGrid) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let persisted = PersistedRouteGraph {
+        version: ROUTE_GRAPH_FORMAT_VERSION,
+        key: key.to_string(),
+        grid: grid.clone(),
+    };
+    let contents = serde_json::to_vec(&persisted).map_err(std::io::Error::other)?;
+    // Write then rename so a concurrent reader never sees half a file.
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Cache key: the region cell of the route's origin, then a hash of everything the grid is
+/// built from.
+fn graph_key(
+    waypoints: &[Waypoint],
+    spacing_m: f64,
+    lane_offsets: &[f64],
+    phase: f64,
+    obstacles: &[RouteObstacle],
+    terrain_fingerprint: Option<u64>,
+) -> String {
+    let mut hasher = DefaultHasher::new();
+    ROUTE_GRAPH_FORMAT_VERSION.hash(&mut hasher);
+    for waypoint in waypoints {
+        waypoint.lat.to_bits().hash(&mut hasher);
+        waypoint.lon.to_bits().hash(&mut hasher);
+        waypoint.altitude_m.to_bits().hash(&mut hasher);
+    }
+    spacing_m.to_bits().hash(&mut hasher);
+    phase.to_bits().hash(&mut hasher);
+    for offset in lane_offsets {
+        offset.to_bits().hash(&mut hasher);
+    }
+    for obstacle in obstacles {
+        obstacle.lat.to_bits().hash(&mut hasher);
+        obstacle.lon.to_bits().hash(&mut hasher);
+        obstacle.radius_m.to_bits().hash(&mut hasher);
+        obstacle.height_m.map(f64::to_bits).hash(&mut hasher);
+        for [lat, lon] in obstacle.polygon.iter().flatten() {
+            lat.to_bits().hash(&mut hasher);
+            lon.to_bits().hash(&mut hasher);
+        }
+    }
+    terrain_fingerprint.hash(&mut hasher);
+
+    let (cell_lat, cell_lon) = waypoints
+        .first()
+        .map(|origin| {
+            (
+                (origin.lat / REGION_CELL_DEG).floor() as i64,
+                (origin.lon / REGION_CELL_DEG).floor() as i64,
+            )
+        })
+        .unwrap_or_default();
+    format!("{}_{}_{:016x}", cell_lat, cell_lon, hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waypoint(lat: f64, lon: f64) -> Waypoint {
+        Waypoint {
+            lat,
+            lon,
+            altitude_m: 60.0,
+            speed_mps: None,
+        }
+    }
+
+    fn store(dir: Option<PathBuf>) -> RouteGraphStore {
+        RouteGraphStore {
+            ttl: Duration::from_secs(600),
+            dir,
+        }
+    }
+
+    #[test]
+    fn repeated_routes_reuse_the_obstacle_applied_grid() {
+        let dir = std::env::temp_dir().join(format!("atc-route-graph-{}", uuid::Uuid::new_v4()));
+        let route = [waypoint(32.71, -117.16), waypoint(32.72, -117.15)];
+        let obstacle = RouteObstacle {
+            lat: 32.715,
+            lon: -117.155,
+            radius_m: 20.0,
+            height_m: Some(80.0),
+            polygon: None,
+        };
+        let offsets = [-15.0, 0.0, 15.0];
+        let key = graph_key(
+            &route,
+            10.0,
+            &offsets,
+            0.0,
+            std::slice::from_ref(&obstacle),
+            None,
+        );
+        assert!(key.starts_with("3271_-11716_"));
+        assert_ne!(
+            key,
+            graph_key(&route, 10.0, &offsets, 0.0, &[], None),
+            "obstacle changes invalidate the graph"
+        );
+
+        let store = store(Some(dir.clone()));
+        let first = store
+            .grid(
+                &route,
+                10.0,
+                &offsets,
+                0.0,
+                std::slice::from_ref(&obstacle),
+                None,
+                10_000,
+            )
+            .expect("grid");
+        assert!(first
+            .lanes
+            .iter()
+            .flatten()
+            .any(|point| point.obstacle_height_m > 0.0));
+
+        let build_again =
+            || -> Result<RouteGrid, RouteGraphError> { panic!("cached graph should be reused") };
+        let cached = store.load_or_build(&key, build_again).expect("memory");
+        assert_eq!(cached.lanes.len(), first.lanes.len());
+
+        // A restart keeps the persisted graph.
+        route_graph_cache().remove(&key);
+        let reloaded = store.load_or_build(&key, build_again).expect("disk");
+        assert_eq!(reloaded.waypoint_indices, first.waypoint_indices);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn oversized_grids_are_rejected_before_caching() {
+        let route = [waypoint(40.0, -105.0), waypoint(40.05, -105.0)];
+        let result = store(None).grid(&route, 5.0, &[0.0], 0.0, &[], None, 100);
+        assert!(matches!(result, Err(RouteGraphError::TooLarge(_))));
+    }
+}
@@ -1,16 +1,34 @@
 //! Server-side route planning using the backend A* engine.
 
+use atc_core::coverage::{apply_coverage, coverage_gaps, CoverageArea, CoverageMode};
+use atc_core::ground_risk::{apply_ground_risk, ground_risk_exposure, PopulationRaster};
 use atc_core::models::{Geofence, GeofenceType, Waypoint};
+use atc_core::performance::DronePerformance;
+use atc_core::planned_traffic::{apply_traffic, PlannedTraffic, TrafficSeparation};
 use atc_core::route_engine::{
-    apply_obstacles, build_lane_offsets, generate_grid_samples, optimize_airborne_path,
-    optimize_flight_path, resolve_grid_spacing, RouteEngineConfig, RouteEngineResult,
-    RouteEngineWaypoint, RouteObstacle,
+    apply_altitude_layers, apply_obstacles, build_lane_offsets, generate_grid_samples,
+    optimize_airborne_path, optimize_flight_path, optimize_flight_path_rrt, resolve_grid_spacing,
+    RouteEngineConfig, RouteEngineResult, RouteEngineWaypoint, RouteGrid, RouteObstacle,
+    RoutePlanner, RouteSearch, SearchBudget, SEARCH_TIMED_OUT,
 };
-use atc_core::spatial::{bearing, haversine_distance, offset_by_bearing};
+use atc_core::route_profile::{build_route_profile, RouteProfileStation};
+use atc_core::routing::{generate_detour_options, random_seed};
+use atc_core::rules::SafetyRules;
+use atc_core::spatial::{
+    bearing, distance_to_segment_m, haversine_distance, meters_to_lat, meters_to_lon,
+    offset_by_bearing,
+};
+use atc_core::takeoff_landing::{
+    apply_takeoff_landing_profile, find_vertiport, terminal_obstacle_violations,
+    TakeoffLandingProfile, TerminalProfile,
+};
+use atc_core::weather::{apply_weather, WeatherCell, WeatherLimits};
+use atc_core::wind::{apply_wind, WindField};
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 use tokio::task;
 
@@ -20,8 +38,12 @@ use crate::compliance::{
     RoutePoint,
 };
 use crate::config::Config;
+use crate::obstacle_index::{CorridorObstacles, SegmentObstacles};
+use crate::planner_pool::PlannerPool;
+use crate::route_graph::{RouteGraphError, RouteGraphStore};
 use crate::state::AppState;
 use crate::terrain::{fetch_terrain_grid, TerrainGrid};
+use crate::wind::fetch_route_wind;
 
 const DEFAULT_LANE_RADIUS_M: f64 = 90.0;
 const DEFAULT_LANE_SPACING_M: f64 = 15.0;
@@ -36,6 +58,16 @@ const MAX_SAMPLE_SPACING_M: f64 = 75.0;
 const SEGMENT_PREFETCH_CONCURRENCY: usize = 4;
 const HARD_MAX_LANE_RADIUS_M: f64 = 5_000.0;
 const HARD_MAX_SAFETY_BUFFER_M: f64 = 500.0;
+const HARD_MAX_GEOFENCE_STANDOFF_M: f64 = 500.0;
+const PROFILE_SAMPLE_SPACING_M: f64 = 25.0;
+const MAX_PROFILE_STATIONS: usize = 2_000;
+/// Detours for `n_alternatives` pass this fraction of the route length to its side, within
+/// the bounds below.
+const DETOUR_OFFSET_FRACTION: f64 = 0.15;
+const MIN_DETOUR_OFFSET_M: f64 = 100.0;
+const MAX_DETOUR_OFFSET_M: f64 = 1_000.0;
+/// Timing slack either side of when a batch route is expected at a point.
+const BATCH_TIME_BUFFER_S: f64 = 15.0;
 
 #[derive(Debug)]
 enum SegmentError {
@@ -54,6 +86,7 @@ struct SegmentPlan {
     optimized_points: usize,
     sample_points: usize,
     hazards: Vec<ObstacleHazard>,
+    obstacles: Arc<Vec<RouteObstacle>>,
     terrain: Option<Arc<TerrainGrid>>,
 }
 
@@ -73,6 +106,421 @@ pub struct RoutePlanRequest {
     pub safety_buffer_m: Option<f64>,
     pub max_lane_radius_m: Option<f64>,
     pub lane_expansion_step_m: Option<f64>,
+    /// Takeoff/landing overrides; unset ends use the matching vertiport profile, then vertical.
+    #[serde(default)]
+    pub takeoff_landing: Option<TakeoffLandingProfile>,
+    /// Planned departure, matched against forecast weather validity; defaults to now.
+    #[serde(default)]
+    pub departure_time: Option<DateTime<Utc>>,
+    /// C2 coverage constraint; defaults to `ATC_ROUTE_PLANNER_C2_COVERAGE`.
+    #[serde(default)]
+    pub c2_coverage: Option<CoverageMode>,
+    /// Radius cruise corners are rounded to; defaults to `ATC_ROUTE_PLANNER_TURN_RADIUS_M`.
+    #[serde(default)]
+    pub turn_radius_m: Option<f64>,
+    /// Distance kept from restricted geofences; defaults to
+    /// `ATC_ROUTE_PLANNER_GEOFENCE_STANDOFF_M`.
+    #[serde(default)]
+    pub geofence_standoff_m: Option<f64>,
+    /// Grid search to plan with; defaults to `ATC_ROUTE_PLANNER_SEARCH`.
+    #[serde(default)]
+    pub search: Option<RouteSearch>,
+    /// Planning deadline in milliseconds; may shorten but not extend
+    /// `ATC_ROUTE_PLANNER_TIMEOUT_MS`.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Candidate routes to return in `alternatives`, the planned route first; capped by
+    /// `ATC_ROUTE_PLANNER_MAX_ALTERNATIVES`.
+    #[serde(default)]
+    pub n_alternatives: Option<usize>,
+    /// Seed for the planner's random choices (the RRT* sampler); a random one is drawn when
+    /// unset and returned in the response.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl RoutePlanRequest {
+    fn turn_radius_m(&self, config: &Config) -> f64 {
+        self.turn_radius_m
+            .filter(|radius| radius.is_finite())
+            .unwrap_or(config.route_planner_turn_radius_m)
+            .max(0.0)
+    }
+
+    fn geofence_standoff_m(&self, config: &Config) -> f64 {
+        self.geofence_standoff_m
+            .unwrap_or(config.route_planner_geofence_standoff_m)
+            .clamp(0.0, HARD_MAX_GEOFENCE_STANDOFF_M)
+    }
+
+    fn timeout(&self, config: &Config) -> Option<Duration> {
+        let timeout_ms = match (self.timeout_ms, config.route_planner_timeout_ms) {
+            (Some(requested), 0) => requested,
+            (Some(requested), limit) => requested.min(limit),
+            (None, 0) => return None,
+            (None, limit) => limit,
+        };
+        Some(Duration::from_millis(timeout_ms))
+    }
+}
+
+/// Cancels a planning budget when dropped, so blocking searches stop once the request that
+/// started them is gone (e.g. the client disconnected).
+struct CancelOnDrop(SearchBudget);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Forecast weather the planner routes around, evaluated from a departure time.
+#[derive(Debug, Clone)]
+struct RouteWeather {
+    cells: Arc<Vec<WeatherCell>>,
+    limits: WeatherLimits,
+    departure_s: f64,
+}
+
+impl RouteWeather {
+    /// `None` when no forecast is loaded.
+    fn load(state: &AppState, config: &Config, departure: Option<DateTime<Utc>>) -> Option<Self> {
+        let cells = state.weather_cells();
+        if cells.is_empty() {
+            return None;
+        }
+        let departure = departure.unwrap_or_else(Utc::now);
+        Some(Self {
+            cells: Arc::new(cells),
+            limits: WeatherLimits {
+                max_wind_mps: config.compliance_max_wind_mps,
+                max_gust_mps: config.compliance_max_gust_mps,
+                max_precip_mm: config.compliance_max_precip_mm,
+                warn_ratio: config.compliance_wind_warn_ratio,
+                penalty_weight: config.route_planner_weather_penalty,
+            },
+            departure_s: timestamp_s(departure),
+        })
+    }
+
+    /// Cost a grid whose first step is `offset_m` along the route.
+    fn apply(&self, grid: &mut RouteGrid, offset_m: f64, ground_speed_mps: f64) {
+        let ground_speed_mps = ground_speed_mps.max(1.0);
+        apply_weather(
+            grid,
+            &self.cells,
+            &self.limits,
+            self.departure_s + offset_m / ground_speed_mps,
+            ground_speed_mps,
+        );
+    }
+}
+
+/// C2 link coverage the planner keeps routes inside, or charges for leaving.
+#[derive(Debug, Clone)]
+struct RouteCoverage {
+    areas: Arc<Vec<CoverageArea>>,
+    cost: f64,
+}
+
+impl RouteCoverage {
+    /// `None` when the constraint is off or no coverage is loaded.
+    fn load(state: &AppState, config: &Config, mode: CoverageMode) -> Option<Self> {
+        let cost = match mode {
+            CoverageMode::Off => return None,
+            CoverageMode::Limit => config.route_planner_coverage_penalty,
+            CoverageMode::Require => f64::INFINITY,
+        };
+        let areas = state.coverage_areas();
+        if areas.is_empty() {
+            return None;
+        }
+        Some(Self {
+            areas: Arc::new(areas),
+            cost,
+        })
+    }
+}
+
+/// Population density the planner charges for overflying.
+#[derive(Debug, Clone)]
+struct RouteGroundRisk {
+    raster: Arc<PopulationRaster>,
+    cost: f64,
+}
+
+impl RouteGroundRisk {
+    /// The configured raster; `None` without one.
+    fn configured(config: &Config) -> Option<Self> {
+        config.ground_risk_raster.clone().map(|raster| Self {
+            raster,
+            cost: config.ground_risk_cost,
+        })
+    }
+
+    /// The configured raster, or density estimated from the buildings within `radius_m` of
+    /// the route when ground risk is costed; `None` when neither is available.
+    async fn load(
+        client: &Client,
+        config: &Config,
+        points: &[RoutePoint],
+        radius_m: f64,
+    ) -> Option<Self> {
+        if let Some(ground_risk) = Self::configured(config) {
+            return Some(ground_risk);
+        }
+        if config.ground_risk_cost <= 0.0 || points.is_empty() {
+            return None;
+        }
+        let analysis = match fetch_obstacles(
+            client,
+            config,
+            points,
+            0.0,
+            Some(radius_m),
+            ObstacleQueryMode::Full,
+        )
+        .await
+        {
+            Ok(analysis) => analysis,
+            Err(err) => {
+                tracing::warn!("Ground risk buildings unavailable: {}", err);
+                return None;
+            }
+        };
+        if analysis.truncated {
+            tracing::warn!("Ground risk buildings truncated; density underestimated");
+        }
+        let buildings: Vec<(f64, f64)> = analysis
+            .candidates
+            .iter()
+            .filter(|candidate| candidate.hazard_type == "building")
+            .map(|candidate| (candidate.lat, candidate.lon))
+            .collect();
+
+        let mut bounds = [
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ];
+        for point in points {
+            bounds[0] = bounds[0].min(point.lat);
+            bounds[1] = bounds[1].min(point.lon);
+            bounds[2] = bounds[2].max(point.lat);
+            bounds[3] = bounds[3].max(point.lon);
+        }
+        let mid_lat = (bounds[0] + bounds[2]) / 2.0;
+        let pad_lat = meters_to_lat(radius_m, mid_lat);
+        let pad_lon = meters_to_lon(radius_m, mid_lat);
+        let raster = PopulationRaster::from_points(
+            [
+                bounds[0] - pad_lat,
+                bounds[1] - pad_lon,
+                bounds[2] + pad_lat,
+                bounds[3] + pad_lon,
+            ],
+            config.ground_risk_cell_m,
+            &buildings,
+            config.compliance_population_per_building,
+        )?;
+        Some(Self {
+            raster: Arc::new(raster),
+            cost: config.ground_risk_cost,
+        })
+    }
+}
+
+/// Routes planned earlier in a batch, flown as moving obstacles from a departure time.
+#[derive(Debug, Clone)]
+struct RouteTraffic {
+    routes: Arc<Vec<PlannedTraffic>>,
+    separation: TrafficSeparation,
+    departure_s: f64,
+}
+
+impl RouteTraffic {
+    /// `None` when there is nothing planned yet.
+    fn new(
+        separation: TrafficSeparation,
+        routes: &[PlannedTraffic],
+        departure: Option<DateTime<Utc>>,
+    ) -> Option<Self> {
+        if routes.is_empty() {
+            return None;
+        }
+        Some(Self {
+            routes: Arc::new(routes.to_vec()),
+            separation,
+            departure_s: timestamp_s(departure.unwrap_or_else(Utc::now)),
+        })
+    }
+
+    /// Cost a grid whose first step is `offset_m` along the route.
+    fn apply(&self, grid: &mut RouteGrid, offset_m: f64, ground_speed_mps: f64) {
+        let ground_speed_mps = ground_speed_mps.max(1.0);
+        apply_traffic(
+            grid,
+            &self.routes,
+            &self.separation,
+            self.departure_s + offset_m / ground_speed_mps,
+            ground_speed_mps,
+        );
+    }
+}
+
+fn batch_separation(config: &Config, rules: &SafetyRules) -> TrafficSeparation {
+    TrafficSeparation {
+        horizontal_m: rules.min_horizontal_separation_m,
+        vertical_m: rules.min_vertical_separation_m,
+        time_buffer_s: BATCH_TIME_BUFFER_S,
+        penalty: config.route_planner_batch_penalty,
+    }
+}
+
+fn timestamp_s(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 1000.0
+}
+
+/// Forecast winds along the route; `None` when the wind field is off or unavailable, in which
+/// case edges are costed into the scalar `route_planner_wind_mps` headwind.
+async fn load_wind(
+    client: &Client,
+    config: &Config,
+    points: &[RoutePoint],
+) -> Option<Arc<WindField>> {
+    if !config.route_planner_wind_field {
+        return None;
+    }
+    match fetch_route_wind(client, config, points).await {
+        Ok(field) if !field.samples.is_empty() => Some(Arc::new(field)),
+        Ok(_) => None,
+        Err(err) => {
+            tracing::warn!("Route wind forecast unavailable: {}", err);
+            None
+        }
+    }
+}
+
+/// Soft constraints layered onto every planning grid.
+#[derive(Debug, Clone)]
+struct GridCosts {
+    weather: Option<RouteWeather>,
+    coverage: Option<RouteCoverage>,
+    wind: Option<Arc<WindField>>,
+    traffic: Option<RouteTraffic>,
+    ground_risk: Option<RouteGroundRisk>,
+}
+
+impl GridCosts {
+    async fn load(
+        state: &AppState,
+        config: &Config,
+        request: &RoutePlanRequest,
+        client: &Client,
+        points: &[RoutePoint],
+        traffic: Option<RouteTraffic>,
+    ) -> Self {
+        Self {
+            weather: RouteWeather::load(state, config, request.departure_time),
+            coverage: RouteCoverage::load(
+                state,
+                config,
+                request
+                    .c2_coverage
+                    .unwrap_or(config.route_planner_coverage_mode),
+            ),
+            wind: load_wind(client, config, points).await,
+            traffic,
+            ground_risk: RouteGroundRisk::load(
+                client,
+                config,
+                points,
+                request
+                    .max_lane_radius_m
+                    .unwrap_or(DEFAULT_MAX_LANE_RADIUS_M),
+            )
+            .await,
+        }
+    }
+
+    /// Cost a grid whose first step is `offset_m` along the route.
+    fn apply(&self, grid: &mut RouteGrid, offset_m: f64, ground_speed_mps: f64) {
+        if let Some(weather) = &self.weather {
+            weather.apply(grid, offset_m, ground_speed_mps);
+        }
+        if let Some(coverage) = &self.coverage {
+            apply_coverage(grid, &coverage.areas, coverage.cost);
+        }
+        if let Some(wind) = &self.wind {
+            apply_wind(grid, wind);
+        }
+        if let Some(traffic) = &self.traffic {
+            traffic.apply(grid, offset_m, ground_speed_mps);
+        }
+        if let Some(ground_risk) = &self.ground_risk {
+            if ground_risk.cost > 0.0 {
+                apply_ground_risk(grid, &ground_risk.raster, ground_risk.cost);
+            }
+        }
+    }
+
+    /// Report the population a planned route overflies in its stats.
+    fn record_ground_risk(
+        &self,
+        waypoints: &[RouteEngineWaypoint],
+        stats: &mut Option<atc_core::route_engine::RouteEngineStats>,
+    ) {
+        let (Some(ground_risk), Some(stats)) = (&self.ground_risk, stats.as_mut()) else {
+            return;
+        };
+        let route: Vec<(f64, f64)> = waypoints.iter().map(|wp| (wp.lat, wp.lon)).collect();
+        stats.ground_risk = Some(ground_risk_exposure(&route, &ground_risk.raster));
+    }
+}
+
+/// Fail a planned route that stays outside C2 coverage for longer than `mode` allows.
+fn enforce_coverage(
+    state: &AppState,
+    config: &Config,
+    mode: CoverageMode,
+    response: &mut RoutePlanResponse,
+) {
+    let limit_s = match mode {
+        CoverageMode::Off => return,
+        CoverageMode::Limit => config.c2_max_gap_s,
+        CoverageMode::Require => 0.0,
+    };
+    let areas = state.coverage_areas();
+    if areas.is_empty() {
+        return;
+    }
+    let ground_speed_mps = RouteEngineConfig {
+        wind_mps: config.route_planner_wind_mps,
+        ..RouteEngineConfig::default()
+    }
+    .ground_speed_mps();
+    let route: Vec<(f64, f64)> = response
+        .waypoints
+        .iter()
+        .map(|wp| (wp.lat, wp.lon))
+        .collect();
+    let Some(gap) = coverage_gaps(&route, &areas)
+        .into_iter()
+        .find(|gap| gap.length_m / ground_speed_mps > limit_s)
+    else {
+        return;
+    };
+    response.ok = false;
+    response.waypoints.clear();
+    response.profile.clear();
+    response.errors.push(format!(
+        "route leaves C2 coverage for {:.0}s ({:.0}m starting {:.0}m along the route; limit {:.0}s)",
+        gap.length_m / ground_speed_mps,
+        gap.length_m,
+        gap.start_distance_m,
+        limit_s
+    ));
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -84,13 +532,261 @@ pub struct RoutePlanResponse {
     pub optimized_points: usize,
     pub sample_points: usize,
     pub hazards: Vec<ObstacleHazard>,
+    /// Along-track elevation profile of the planned route (empty when planning fails).
+    pub profile: Vec<RouteProfileStation>,
     pub errors: Vec<String>,
+    /// Planning stopped at its deadline; the other fields hold what was planned by then.
+    pub timed_out: bool,
+    /// Search that produced the route; unset when planning failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planner: Option<RoutePlanner>,
+    /// Candidate routes when `n_alternatives` was requested, the planned route first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<RouteAlternative>,
+    /// Seed the planner's random choices were drawn from; send it back as `seed` to reproduce
+    /// this plan.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+/// A candidate route and the figures to compare it by.
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteAlternative {
+    /// `primary` for the planned route, otherwise the detour it was planned through
+    /// (`left_1`, `right_1`, `left_2`, ...).
+    pub option_id: String,
+    pub description: String,
+    pub waypoints: Vec<RouteEngineWaypoint>,
+    /// Horizontal length along the route.
+    pub distance_m: f64,
+    pub max_agl_m: Option<f64>,
+    /// Seconds of level cruise: flight time plus the planner's climb penalty per metre climbed.
+    pub energy_s: f64,
+    /// Closest the route passes to the edge of a hazard; unset when no hazard is nearby.
+    pub hazard_proximity_m: Option<f64>,
+    pub planner: Option<RoutePlanner>,
 }
 
 pub async fn plan_route(
     state: &AppState,
     config: &Config,
     mut request: RoutePlanRequest,
+) -> RoutePlanResponse {
+    let seed = seed_request(&mut request);
+    let wanted = request
+        .n_alternatives
+        .unwrap_or(0)
+        .min(config.route_planner_max_alternatives);
+    if wanted == 0 {
+        let mut response = plan_route_among(state, config, request, None).await;
+        response.seed = Some(seed);
+        return response;
+    }
+
+    let started_at = Instant::now();
+    let timeout = request.timeout(config);
+    let mut response = plan_route_among(state, config, request.clone(), None).await;
+    response.seed = Some(seed);
+    if !response.ok {
+        return response;
+    }
+    let mut alternatives = vec![route_alternative(
+        config,
+        "primary",
+        "Planned route",
+        &response,
+    )];
+
+    // Every second detour may be unplannable (e.g. its via point sits in a building), so try
+    // twice as many as needed.
+    let offset_m = (route_distance_m(&request.waypoints) * DETOUR_OFFSET_FRACTION)
+        .clamp(MIN_DETOUR_OFFSET_M, MAX_DETOUR_OFFSET_M);
+    let detours = generate_detour_options(&request.waypoints, 2 * (wanted - 1), offset_m);
+    for detour in detours {
+        if alternatives.len() >= wanted {
+            break;
+        }
+        let mut detour_request = request.clone();
+        detour_request.waypoints = detour.waypoints;
+        // Detours share the deadline of the whole request.
+        if let Some(timeout) = timeout {
+            let remaining = timeout.saturating_sub(started_at.elapsed());
+            if remaining.is_zero() {
+                break;
+            }
+            detour_request.timeout_ms = Some((remaining.as_millis() as u64).max(1));
+        }
+        let planned = plan_route_among(state, config, detour_request, None).await;
+        if planned.ok {
+            alternatives.push(route_alternative(
+                config,
+                &detour.option_id,
+                &detour.description,
+                &planned,
+            ));
+        }
+    }
+    response.alternatives = alternatives;
+    response
+}
+
+/// Fix the seed of the request's random choices so the plan can be reproduced.
+fn seed_request(request: &mut RoutePlanRequest) -> u64 {
+    *request.seed.get_or_insert_with(random_seed)
+}
+
+fn route_alternative(
+    config: &Config,
+    option_id: &str,
+    description: &str,
+    response: &RoutePlanResponse,
+) -> RouteAlternative {
+    let engine = RouteEngineConfig {
+        wind_mps: config.route_planner_wind_mps.max(0.0),
+        ..Default::default()
+    };
+    let mut distance_m = 0.0;
+    let mut energy_s = 0.0;
+    for leg in response.waypoints.windows(2) {
+        let (from, to) = (&leg[0], &leg[1]);
+        let horizontal_m = haversine_distance(from.lat, from.lon, to.lat, to.lon);
+        let climb_m = to.altitude_m - from.altitude_m;
+        let vertical_speed_mps = if climb_m >= 0.0 {
+            engine.climb_speed_mps
+        } else {
+            engine.descent_speed_mps
+        };
+        distance_m += horizontal_m;
+        energy_s += (horizontal_m / engine.ground_speed_mps())
+            .max(climb_m.abs() / vertical_speed_mps.max(0.1))
+            + climb_m.max(0.0) * engine.cost_climb_penalty;
+    }
+    let hazard_proximity_m = response
+        .hazards
+        .iter()
+        .filter_map(|hazard| {
+            response
+                .waypoints
+                .windows(2)
+                .map(|leg| {
+                    distance_to_segment_m(
+                        hazard.lat, hazard.lon, leg[0].lat, leg[0].lon, leg[1].lat, leg[1].lon,
+                    )
+                })
+                .reduce(f64::min)
+                .map(|distance_m| (distance_m - hazard.radius_m).max(0.0))
+        })
+        .reduce(f64::min);
+    RouteAlternative {
+        option_id: option_id.to_string(),
+        description: description.to_string(),
+        waypoints: response.waypoints.clone(),
+        distance_m,
+        max_agl_m: response.stats.as_ref().map(|stats| stats.max_agl),
+        energy_s,
+        hazard_proximity_m,
+        planner: response.planner,
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RoutePlanBatchRequest {
+    pub routes: Vec<RoutePlanRequest>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutePlanBatchResponse {
+    /// Every route in the batch was planned.
+    pub ok: bool,
+    /// One result per requested route, in request order.
+    pub routes: Vec<BatchRoutePlan>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchRoutePlan {
+    #[serde(flatten)]
+    pub plan: RoutePlanResponse,
+    /// Indices of earlier routes in the batch this one still comes within separation of.
+    pub conflicts_with: Vec<usize>,
+}
+
+/// Plan several routes jointly: each route is planned around the ones before it in the batch,
+/// flown as moving obstacles from their departure times.
+pub async fn plan_route_batch(
+    state: &AppState,
+    config: &Config,
+    request: RoutePlanBatchRequest,
+) -> RoutePlanBatchResponse {
+    let mut errors = Vec::new();
+    if request.routes.is_empty() {
+        errors.push("need at least 1 route".to_string());
+    }
+    let max_batch = config.route_planner_max_batch;
+    if max_batch > 0 && request.routes.len() > max_batch {
+        errors.push(format!(
+            "too many routes ({} > max {})",
+            request.routes.len(),
+            max_batch
+        ));
+    }
+    if !errors.is_empty() {
+        return RoutePlanBatchResponse {
+            ok: false,
+            routes: Vec::new(),
+            errors,
+        };
+    }
+
+    let now = Utc::now();
+    let separation = batch_separation(config, &state.rules());
+    let ground_speed_mps = RouteEngineConfig {
+        wind_mps: config.route_planner_wind_mps.max(0.0),
+        ..RouteEngineConfig::default()
+    }
+    .ground_speed_mps();
+    // Routes planned so far, with their indices in the batch.
+    let mut planned: Vec<PlannedTraffic> = Vec::new();
+    let mut planned_indices: Vec<usize> = Vec::new();
+    let mut routes = Vec::with_capacity(request.routes.len());
+    for (idx, mut route) in request.routes.into_iter().enumerate() {
+        let departure = *route.departure_time.get_or_insert(now);
+        let traffic = RouteTraffic::new(separation, &planned, Some(departure));
+        let seed = seed_request(&mut route);
+        let mut plan = plan_route_among(state, config, route, traffic).await;
+        plan.seed = Some(seed);
+        let mut conflicts_with = Vec::new();
+        if plan.ok {
+            let track =
+                PlannedTraffic::new(&plan.waypoints, timestamp_s(departure), ground_speed_mps);
+            conflicts_with = planned
+                .iter()
+                .zip(&planned_indices)
+                .filter(|(earlier, _)| track.conflict_time(earlier, &separation).is_some())
+                .map(|(_, earlier_idx)| *earlier_idx)
+                .collect();
+            planned.push(track);
+            planned_indices.push(idx);
+        }
+        routes.push(BatchRoutePlan {
+            plan,
+            conflicts_with,
+        });
+    }
+
+    RoutePlanBatchResponse {
+        ok: routes.iter().all(|route| route.plan.ok),
+        routes,
+        errors,
+    }
+}
+
+/// Plan a route that keeps clear of `traffic` where it can.
+async fn plan_route_among(
+    state: &AppState,
+    config: &Config,
+    mut request: RoutePlanRequest,
+    traffic: Option<RouteTraffic>,
 ) -> RoutePlanResponse {
     if request.waypoints.len() < 2 {
         return RoutePlanResponse {
@@ -101,7 +797,12 @@ pub async fn plan_route(
             optimized_points: 0,
             sample_points: 0,
             hazards: Vec::new(),
+            profile: Vec::new(),
             errors: vec!["need at least 2 waypoints".to_string()],
+            timed_out: false,
+            planner: None,
+            alternatives: Vec::new(),
+            seed: None,
         };
     }
 
@@ -115,11 +816,16 @@ pub async fn plan_route(
             optimized_points: 0,
             sample_points: 0,
             hazards: Vec::new(),
+            profile: Vec::new(),
             errors: vec![format!(
                 "too many waypoints ({} > max {})",
                 request.waypoints.len(),
                 max_waypoints
             )],
+            timed_out: false,
+            planner: None,
+            alternatives: Vec::new(),
+            seed: None,
         };
     }
 
@@ -175,6 +881,12 @@ pub async fn plan_route(
                 .push("safety_buffer_m must be a non-negative finite number".to_string());
         }
     }
+    if let Some(standoff) = request.geofence_standoff_m {
+        if !standoff.is_finite() || standoff < 0.0 {
+            validation_errors
+                .push("geofence_standoff_m must be a non-negative finite number".to_string());
+        }
+    }
     if let Some(step) = request.lane_expansion_step_m {
         if !step.is_finite() || step <= 0.0 {
             validation_errors
@@ -182,6 +894,14 @@ pub async fn plan_route(
         }
     }
 
+    if request.timeout_ms == Some(0) {
+        validation_errors.push("timeout_ms must be positive".to_string());
+    }
+
+    if let Some(profile) = request.takeoff_landing.as_ref() {
+        validation_errors.extend(profile.validate());
+    }
+
     if !validation_errors.is_empty() {
         return RoutePlanResponse {
             ok: false,
@@ -191,7 +911,12 @@ pub async fn plan_route(
             optimized_points: 0,
             sample_points: 0,
             hazards: Vec::new(),
+            profile: Vec::new(),
             errors: validation_errors,
+            timed_out: false,
+            planner: None,
+            alternatives: Vec::new(),
+            seed: None,
         };
     }
 
@@ -219,10 +944,15 @@ pub async fn plan_route(
             optimized_points: 0,
             sample_points: 0,
             hazards: Vec::new(),
+            profile: Vec::new(),
             errors: vec![format!(
                 "route too long ({:.0}m > max {:.0}m)",
                 route_distance_total, max_distance_m
             )],
+            timed_out: false,
+            planner: None,
+            alternatives: Vec::new(),
+            seed: None,
         };
     }
     if route_distance_total <= f64::EPSILON {
@@ -234,34 +964,62 @@ pub async fn plan_route(
             optimized_points: 0,
             sample_points: 0,
             hazards: Vec::new(),
+            profile: Vec::new(),
             errors: vec![
                 "route distance is zero (start and end waypoints must differ)".to_string(),
             ],
+            timed_out: false,
+            planner: None,
+            alternatives: Vec::new(),
+            seed: None,
         };
     }
     let use_segments = route_distance_total > DEFAULT_SEGMENT_LENGTH_M;
-
-    if !use_segments {
-        let response = plan_route_single(state, config, &request).await;
-        if response.ok {
-            return response;
+    let coverage_mode = request
+        .c2_coverage
+        .unwrap_or(config.route_planner_coverage_mode);
+
+    let timeout = request.timeout(config);
+    let budget = SearchBudget::new(timeout);
+    let _cancel = CancelOnDrop(budget.clone());
+
+    let mut response = 'plan: {
+        if !use_segments {
+            let response =
+                plan_route_single(state, config, &request, traffic.clone(), &budget).await;
+            if response.ok || budget.is_exhausted() {
+                break 'plan response;
+            }
+            let retry = response
+                .errors
+                .iter()
+                .any(|err| err.contains("truncated") || err.contains("route grid too large"));
+            if !retry {
+                break 'plan response;
+            }
         }
-        let retry = response
-            .errors
-            .iter()
-            .any(|err| err.contains("truncated") || err.contains("route grid too large"));
-        if !retry {
-            return response;
+        plan_route_segmented(state, config, request, traffic, &budget).await
+    };
+    if response.ok {
+        enforce_coverage(state, config, coverage_mode, &mut response);
+    } else if budget.deadline_passed() {
+        response.timed_out = true;
+        if let Some(timeout) = timeout {
+            response.errors.push(format!(
+                "route planning timed out after {} ms",
+                timeout.as_millis()
+            ));
         }
     }
-
-    plan_route_segmented(state, config, request).await
+    response
 }
 
 async fn plan_route_single(
     state: &AppState,
     config: &Config,
     request: &RoutePlanRequest,
+    traffic: Option<RouteTraffic>,
+    budget: &SearchBudget,
 ) -> RoutePlanResponse {
     let started_at = Instant::now();
     let lane_radius = request.lane_radius_m.unwrap_or(DEFAULT_LANE_RADIUS_M);
@@ -323,7 +1081,12 @@ async fn plan_route_single(
                     optimized_points: 0,
                     sample_points: 0,
                     hazards: Vec::new(),
+                    profile: Vec::new(),
                     errors: vec![format!("obstacle fetch failed: {}", err)],
+                    timed_out: false,
+                    planner: None,
+                    alternatives: Vec::new(),
+                    seed: None,
                 };
             }
             tracing::warn!(
@@ -346,7 +1109,12 @@ async fn plan_route_single(
                     optimized_points: 0,
                     sample_points: 0,
                     hazards: Vec::new(),
+                    profile: Vec::new(),
                     errors: vec![format!("terrain fetch failed: {}", err)],
+                    timed_out: false,
+                    planner: None,
+                    alternatives: Vec::new(),
+                    seed: None,
                 };
             }
             tracing::warn!("Terrain fetch failed, continuing without terrain: {}", err);
@@ -383,10 +1151,15 @@ async fn plan_route_single(
                 optimized_points: 0,
                 sample_points: 0,
                 hazards: Vec::new(),
+                profile: Vec::new(),
                 errors: vec![
                     "obstacle dataset truncated; increase ATC_COMPLIANCE_MAX_OVERPASS_ELEMENTS"
                         .to_string(),
                 ],
+                timed_out: false,
+                planner: None,
+                alternatives: Vec::new(),
+                seed: None,
             };
         }
     }
@@ -397,28 +1170,34 @@ async fn plan_route_single(
         terrain_elapsed.as_millis()
     );
 
-    let geofences: Arc<Vec<Geofence>> = Arc::new(
-        state
-            .get_geofences()
-            .into_iter()
-            .filter(|fence| fence.active && fence.geofence_type != GeofenceType::Advisory)
-            .collect(),
-    );
+    let geofences: Arc<Vec<Geofence>> = Arc::new(blocking_geofences(
+        state.get_geofences(),
+        config,
+        request.departure_time.unwrap_or_else(Utc::now),
+        route_distance_total,
+    ));
 
     let waypoints: Arc<Vec<Waypoint>> = Arc::new(waypoints);
     let candidates: Arc<Vec<ObstacleCandidate>> = Arc::new(candidates);
+    let grid_costs = GridCosts::load(state, config, request, &client, &points, traffic).await;
+    let graphs = RouteGraphStore::from_config(config);
+    let pool = state.planner_pool();
 
     let mut last_errors = Vec::new();
     let mut last_sample_points = 0usize;
     let mut last_result = None;
+    let mut planned: Option<(RouteEngineResult, Arc<Vec<RouteObstacle>>)> = None;
     let spacing_candidates = lane_spacing_candidates(lane_spacing);
     let wind_mps = config.route_planner_wind_mps.max(0.0);
     let radius_candidates = lane_radius_candidates(lane_radius, max_lane_radius, expansion_step);
     let phase_all = grid_phase_candidates();
 
-    for (radius_idx, lane_radius) in radius_candidates.iter().copied().enumerate() {
-        let obstacles: Arc<Vec<RouteObstacle>> =
-            Arc::new(obstacles_for_lane_radius(&candidates, lane_radius));
+    'attempts: for (radius_idx, lane_radius) in radius_candidates.iter().copied().enumerate() {
+        let obstacles: Arc<Vec<RouteObstacle>> = Arc::new(obstacles_for_lane_radius(
+            &candidates,
+            lane_radius,
+            clearance_m,
+        ));
         let is_last_radius = radius_idx + 1 == radius_candidates.len();
         let primary_spacing = spacing_candidates.first().copied().unwrap_or(lane_spacing);
         let spacing_single = [primary_spacing];
@@ -450,43 +1229,61 @@ async fn plan_route_single(
             }
 
             for phase in phases.iter().copied() {
+                if budget.is_exhausted() {
+                    last_errors = vec![SEARCH_TIMED_OUT.to_string()];
+                    break 'attempts;
+                }
                 attempted = true;
                 let waypoints = waypoints.clone();
-                let obstacles = obstacles.clone();
+                let obstacles_for_task = obstacles.clone();
                 let geofences = geofences.clone();
-                let terrain = terrain.clone();
+                let terrain_for_task = terrain.clone();
+                let grid_costs = grid_costs.clone();
                 let lane_offsets = lane_offsets.clone();
                 let attempt_started_at = Instant::now();
                 let engine_config = RouteEngineConfig {
                     safety_buffer_m: clearance_m,
                     wind_mps,
                     geofence_sample_step_m: spacing.clamp(5.0, 25.0),
+                    altitude_step_m: config.route_planner_altitude_step_m,
+                    max_climb_gradient: config.route_planner_max_climb_gradient,
+                    turn_radius_m: request.turn_radius_m(config),
+                    geofence_standoff_m: request.geofence_standoff_m(config),
+                    search: request.search.unwrap_or(config.route_planner_search),
+                    seed: request.seed,
+                    budget: budget.clone(),
                     ..Default::default()
                 };
 
-                let handle = task::spawn_blocking(move || {
-                    let Some(mut grid) =
-                        generate_grid_samples(&waypoints, spacing, &lane_offsets, phase)
-                    else {
-                        return Err(vec!["failed to generate grid".to_string()]);
+                let graphs = graphs.clone();
+                let handle = pool.run(move || {
+                    let mut grid = match graphs.grid(
+                        &waypoints,
+                        spacing,
+                        &lane_offsets,
+                        phase,
+                        &obstacles_for_task,
+                        terrain_for_task.as_deref(),
+                        MAX_ROUTE_GRID_POINTS,
+                    ) {
+                        Ok(grid) => grid,
+                        Err(RouteGraphError::Empty) => {
+                            return Err(vec!["failed to generate grid".to_string()]);
+                        }
+                        Err(RouteGraphError::TooLarge(sample_points)) => {
+                            return Err(vec![format!(
+                                "route grid too large ({} points)",
+                                sample_points
+                            )]);
+                        }
                     };
                     let sample_points = grid
                         .lanes
                         .first()
                         .map(|lane| lane.len() * grid.lanes.len())
                         .unwrap_or(0);
-                    if sample_points > MAX_ROUTE_GRID_POINTS {
-                        return Err(vec![format!(
-                            "route grid too large ({} points)",
-                            sample_points
-                        )]);
-                    }
-                    apply_obstacles(&mut grid, &obstacles, |lat, lon| {
-                        terrain
-                            .as_ref()
-                            .map(|grid| grid.sample(lat, lon))
-                            .unwrap_or(0.0)
-                    });
+                    grid_costs.apply(&mut grid, 0.0, engine_config.ground_speed_mps());
+                    apply_altitude_layers(&mut grid, &engine_config);
                     let result =
                         optimize_flight_path(&waypoints, &grid, &geofences, &engine_config);
                     Ok((result, sample_points))
@@ -522,16 +1319,8 @@ async fn plan_route_single(
                     );
                 }
                 if result.success {
-                    let response = build_response(result, hazards, last_sample_points);
-                    tracing::info!(
-                        ok = response.ok,
-                        nodes_visited = response.nodes_visited,
-                        optimized_points = response.optimized_points,
-                        sample_points = response.sample_points,
-                        elapsed_ms = started_at.elapsed().as_millis() as u64,
-                        "RoutePlan completed"
-                    );
-                    return response;
+                    planned = Some((result, obstacles.clone()));
+                    break 'attempts;
                 }
 
                 last_errors = result.errors.clone();
@@ -544,6 +1333,135 @@ async fn plan_route_single(
         }
     }
 
+    // The grid search found nothing up to the widest corridor: sample that corridor instead.
+    let mut fallback_errors = Vec::new();
+    if planned.is_none() && config.route_planner_rrt_samples > 0 && !budget.is_exhausted() {
+        let lane_radius = radius_candidates.last().copied().unwrap_or(max_lane_radius);
+        let obstacles: Arc<Vec<RouteObstacle>> = Arc::new(obstacles_for_lane_radius(
+            &candidates,
+            lane_radius,
+            clearance_m,
+        ));
+        let lane_spacing = spacing_candidates.first().copied().unwrap_or(lane_spacing);
+        let lane_offsets = build_lane_offsets(lane_radius, lane_spacing);
+        let spacing =
+            resolve_spacing_for_grid(route_distance_total, base_spacing, lane_offsets.len());
+        let engine_config = RouteEngineConfig {
+            safety_buffer_m: clearance_m,
+            wind_mps,
+            geofence_sample_step_m: spacing.clamp(5.0, 25.0),
+            max_climb_gradient: config.route_planner_max_climb_gradient,
+            turn_radius_m: request.turn_radius_m(config),
+            geofence_standoff_m: request.geofence_standoff_m(config),
+            rrt_samples: config.route_planner_rrt_samples,
+            seed: request.seed,
+            budget: budget.clone(),
+            ..Default::default()
+        };
+        let waypoints = waypoints.clone();
+        let obstacles_for_task = obstacles.clone();
+        let geofences = geofences.clone();
+        let terrain_for_task = terrain.clone();
+        let grid_costs = grid_costs.clone();
+        let graphs = graphs.clone();
+        let attempt_started_at = Instant::now();
+        let handle = pool.run(move || {
+            let mut grid = match graphs.grid(
+                &waypoints,
+                spacing,
+                &lane_offsets,
+                0.0,
+                &obstacles_for_task,
+                terrain_for_task.as_deref(),
+                MAX_ROUTE_GRID_POINTS,
+            ) {
+                Ok(grid) => grid,
+                Err(RouteGraphError::Empty) => {
+                    return Err(vec!["failed to generate grid".to_string()]);
+                }
+                Err(RouteGraphError::TooLarge(sample_points)) => {
+                    return Err(vec![format!(
+                        "route grid too large ({} points)",
+                        sample_points
+                    )]);
+                }
+            };
+            let sample_points = grid
+                .lanes
+                .first()
+                .map(|lane| lane.len() * grid.lanes.len())
+                .unwrap_or(0);
+            grid_costs.apply(&mut grid, 0.0, engine_config.ground_speed_mps());
+            let result = optimize_flight_path_rrt(&waypoints, &grid, &geofences, &engine_config);
+            Ok((result, sample_points))
+        });
+        match handle.await {
+            Ok(Ok((result, sample_points))) => {
+                tracing::info!(
+                    lane_radius_m = lane_radius,
+                    grid_spacing_m = spacing,
+                    success = result.success,
+                    nodes_visited = result.nodes_visited,
+                    elapsed_ms = attempt_started_at.elapsed().as_millis() as u64,
+                    errors = ?result.errors,
+                    "RoutePlan RRT* fallback"
+                );
+                last_sample_points = sample_points;
+                if result.success {
+                    planned = Some((result, obstacles));
+                } else {
+                    fallback_errors = result.errors;
+                }
+            }
+            Ok(Err(errors)) => fallback_errors = errors,
+            Err(err) => fallback_errors = vec![format!("planner task failed: {}", err)],
+        }
+    }
+
+    if let Some((mut result, obstacles)) = planned {
+        let (takeoff, landing) = resolve_terminal_profiles(request, config);
+        match apply_terminal_profiles(
+            &result.waypoints,
+            &takeoff,
+            &landing,
+            &obstacles,
+            terrain.as_deref(),
+            clearance_m,
+        ) {
+            Ok(waypoints) => result.waypoints = waypoints,
+            Err(errors) => {
+                return RoutePlanResponse {
+                    ok: false,
+                    waypoints: Vec::new(),
+                    stats: result.stats,
+                    nodes_visited: result.nodes_visited,
+                    optimized_points: result.optimized_points,
+                    sample_points: last_sample_points,
+                    hazards,
+                    profile: Vec::new(),
+                    errors,
+                    timed_out: false,
+                    planner: None,
+                    alternatives: Vec::new(),
+                    seed: None,
+                };
+            }
+        }
+        let mut response = build_response(result, hazards, last_sample_points);
+        grid_costs.record_ground_risk(&response.waypoints, &mut response.stats);
+        response.profile = route_profile(&response.waypoints, &obstacles, terrain.as_deref());
+        tracing::info!(
+            ok = response.ok,
+            planner = ?response.planner,
+            nodes_visited = response.nodes_visited,
+            optimized_points = response.optimized_points,
+            sample_points = response.sample_points,
+            elapsed_ms = started_at.elapsed().as_millis() as u64,
+            "RoutePlan completed"
+        );
+        return response;
+    }
+
     let mut errors = last_errors;
     if errors.is_empty() {
         errors.push("A* failed to find a path".to_string());
@@ -552,6 +1470,7 @@ async fn plan_route_single(
         "no path within lane radius {:.1}m",
         max_lane_radius
     ));
+    errors.extend(fallback_errors);
 
     let (stats, nodes_visited, optimized_points) = match last_result.as_ref() {
         Some(result) => (
@@ -570,7 +1489,12 @@ async fn plan_route_single(
         optimized_points,
         sample_points: last_sample_points,
         hazards,
+        profile: Vec::new(),
         errors,
+        timed_out: false,
+        planner: None,
+        alternatives: Vec::new(),
+        seed: None,
     };
     tracing::info!(
         ok = response.ok,
@@ -588,6 +1512,8 @@ async fn plan_route_segmented(
     state: &AppState,
     config: &Config,
     request: RoutePlanRequest,
+    traffic: Option<RouteTraffic>,
+    budget: &SearchBudget,
 ) -> RoutePlanResponse {
     let lane_radius = request.lane_radius_m.unwrap_or(DEFAULT_LANE_RADIUS_M);
     let lane_spacing = request.lane_spacing_m.unwrap_or(DEFAULT_LANE_SPACING_M);
@@ -604,15 +1530,42 @@ async fn plan_route_segmented(
 
     let mut segment_length = DEFAULT_SEGMENT_LENGTH_M.min(route_distance_total);
     let mut last_error: Option<Vec<String>> = None;
-    let wind_mps = config.route_planner_wind_mps.max(0.0);
-    let geofences: Arc<Vec<Geofence>> = Arc::new(
-        state
-            .get_geofences()
-            .into_iter()
-            .filter(|fence| fence.active && fence.geofence_type != GeofenceType::Advisory)
-            .collect(),
-    );
+    let engine_base = RouteEngineConfig {
+        safety_buffer_m: clearance_m,
+        wind_mps: config.route_planner_wind_mps.max(0.0),
+        altitude_step_m: config.route_planner_altitude_step_m,
+        max_climb_gradient: config.route_planner_max_climb_gradient,
+        turn_radius_m: request.turn_radius_m(config),
+        geofence_standoff_m: request.geofence_standoff_m(config),
+        search: request.search.unwrap_or(config.route_planner_search),
+        seed: request.seed,
+        budget: budget.clone(),
+        ..Default::default()
+    };
+    let geofences: Arc<Vec<Geofence>> = Arc::new(blocking_geofences(
+        state.get_geofences(),
+        config,
+        request.departure_time.unwrap_or_else(Utc::now),
+        route_distance_total,
+    ));
     let client = Client::new();
+    let route_points: Vec<RoutePoint> = normalized_waypoints
+        .iter()
+        .map(|wp| RoutePoint {
+            lat: wp.lat,
+            lon: wp.lon,
+            altitude_m: wp.altitude_m,
+        })
+        .collect();
+    let grid_costs =
+        GridCosts::load(state, config, &request, &client, &route_points, traffic).await;
+    let graphs = RouteGraphStore::from_config(config);
+    // Shared by every segment and retry, so overlapping corridors fetch each tile once.
+    let corridor_obstacles = Arc::new(CorridorObstacles::new(
+        client.clone(),
+        config.clone(),
+        clearance_m,
+    ));
 
     for _attempt in 0..4 {
         let segments = build_segments(&normalized_waypoints, segment_length);
@@ -626,7 +1579,12 @@ async fn plan_route_segmented(
                 optimized_points: 0,
                 sample_points: 0,
                 hazards: Vec::new(),
+                profile: Vec::new(),
                 errors: vec!["failed to segment route".to_string()],
+                timed_out: false,
+                planner: None,
+                alternatives: Vec::new(),
+                seed: None,
             };
         }
 
@@ -639,8 +1597,11 @@ async fn plan_route_segmented(
         let mut stats = None;
         let mut start_terrain: Option<Arc<TerrainGrid>> = None;
         let mut end_terrain: Option<Arc<TerrainGrid>> = None;
+        let mut route_obstacles: Vec<RouteObstacle> = Vec::new();
+        let mut route_obstacles_seen = std::collections::HashSet::new();
         let mut truncated = false;
         let mut start_altitude_override: Option<f64> = None;
+        let mut segment_offset_m = 0.0;
 
         let semaphore = Arc::new(Semaphore::new(SEGMENT_PREFETCH_CONCURRENCY.max(1)));
         let mut join_set = task::JoinSet::new();
@@ -666,19 +1627,26 @@ async fn plan_route_segmented(
                         optimized_points: 0,
                         sample_points: 0,
                         hazards: Vec::new(),
+                        profile: Vec::new(),
                         errors: vec!["failed to acquire segment prefetch permit".to_string()],
+                        timed_out: false,
+                        planner: None,
+                        alternatives: Vec::new(),
+                        seed: None,
                     };
                 }
             };
 
             let client = client.clone();
             let config = config.clone();
+            let corridor_obstacles = corridor_obstacles.clone();
             let segment = segment.clone();
             join_set.spawn(async move {
                 let _permit = permit;
                 let inputs = fetch_segment_inputs(
                     &client,
                     &config,
+                    &corridor_obstacles,
                     &segment,
                     clearance_m,
                     base_spacing,
@@ -708,7 +1676,12 @@ async fn plan_route_segmented(
                         optimized_points: 0,
                         sample_points: 0,
                         hazards: Vec::new(),
+                        profile: Vec::new(),
                         errors: vec![format!("segment prefetch task failed: {}", err)],
+                        timed_out: false,
+                        planner: None,
+                        alternatives: Vec::new(),
+                        seed: None,
                     };
                 }
             }
@@ -737,7 +1710,12 @@ async fn plan_route_segmented(
                         optimized_points: 0,
                         sample_points: 0,
                         hazards: Vec::new(),
+                        profile: Vec::new(),
                         errors: vec![format!("obstacle fetch failed: {}", err)],
+                        timed_out: false,
+                        planner: None,
+                        alternatives: Vec::new(),
+                        seed: None,
                     };
                 }
                 Err(SegmentError::Terrain(err)) => {
@@ -749,7 +1727,12 @@ async fn plan_route_segmented(
                         optimized_points: 0,
                         sample_points: 0,
                         hazards: Vec::new(),
+                        profile: Vec::new(),
                         errors: vec![format!("terrain fetch failed: {}", err)],
+                        timed_out: false,
+                        planner: None,
+                        alternatives: Vec::new(),
+                        seed: None,
                     };
                 }
                 Err(err) => {
@@ -761,7 +1744,12 @@ async fn plan_route_segmented(
                         optimized_points: 0,
                         sample_points: 0,
                         hazards: Vec::new(),
+                        profile: Vec::new(),
                         errors: vec![format!("segment prefetch failed: {:?}", err)],
+                        timed_out: false,
+                        planner: None,
+                        alternatives: Vec::new(),
+                        seed: None,
                     };
                 }
             };
@@ -773,11 +1761,14 @@ async fn plan_route_segmented(
                 base_spacing,
                 max_lane_radius,
                 expansion_step,
-                clearance_m,
-                wind_mps,
+                &engine_base,
                 start_altitude_override,
                 inputs,
                 geofences.clone(),
+                grid_costs.clone(),
+                &graphs,
+                state.planner_pool(),
+                segment_offset_m,
             )
             .await
             {
@@ -791,7 +1782,30 @@ async fn plan_route_segmented(
                         optimized_points: 0,
                         sample_points: count,
                         hazards: Vec::new(),
+                        profile: Vec::new(),
                         errors: vec![format!("route grid too large ({} points)", count)],
+                        timed_out: false,
+                        planner: None,
+                        alternatives: Vec::new(),
+                        seed: None,
+                    };
+                }
+                // Out of time: return the segments planned so far.
+                Err(SegmentError::Path(errors)) if budget.is_exhausted() => {
+                    return RoutePlanResponse {
+                        ok: false,
+                        waypoints: combined_waypoints,
+                        stats,
+                        nodes_visited,
+                        optimized_points,
+                        sample_points,
+                        hazards,
+                        profile: Vec::new(),
+                        errors,
+                        timed_out: false,
+                        planner: None,
+                        alternatives: Vec::new(),
+                        seed: None,
                     };
                 }
                 Err(SegmentError::Path(errors)) => {
@@ -811,7 +1825,12 @@ async fn plan_route_segmented(
                                 optimized_points: 0,
                                 sample_points: 0,
                                 hazards: Vec::new(),
+                                profile: Vec::new(),
                                 errors,
+                                timed_out: false,
+                                planner: None,
+                                alternatives: Vec::new(),
+                                seed: None,
                             };
                         }
                     };
@@ -838,7 +1857,12 @@ async fn plan_route_segmented(
                         optimized_points: 0,
                         sample_points: 0,
                         hazards: Vec::new(),
+                        profile: Vec::new(),
                         errors,
+                        timed_out: false,
+                        planner: None,
+                        alternatives: Vec::new(),
+                        seed: None,
                     };
                 }
                 Err(err) => {
@@ -850,7 +1874,12 @@ async fn plan_route_segmented(
                         optimized_points: 0,
                         sample_points: 0,
                         hazards: Vec::new(),
+                        profile: Vec::new(),
                         errors: vec![format!("segment planning failed: {:?}", err)],
+                        timed_out: false,
+                        planner: None,
+                        alternatives: Vec::new(),
+                        seed: None,
                     };
                 }
             };
@@ -861,8 +1890,14 @@ async fn plan_route_segmented(
             if idx + 1 == segment_count {
                 end_terrain = plan.terrain.clone();
             }
+            for obstacle in plan.obstacles.iter() {
+                if route_obstacles_seen.insert((obstacle.lat.to_bits(), obstacle.lon.to_bits())) {
+                    route_obstacles.push(obstacle.clone());
+                }
+            }
 
             start_altitude_override = plan.waypoints.last().map(|wp| wp.altitude_m);
+            segment_offset_m += route_distance_m(&segment);
 
             for hazard in &plan.hazards {
                 if hazards_seen.insert(hazard.id.clone()) {
@@ -895,7 +1930,12 @@ async fn plan_route_segmented(
                 optimized_points,
                 sample_points,
                 hazards,
+                profile: Vec::new(),
                 errors: vec!["segment planning produced no waypoints".to_string()],
+                timed_out: false,
+                planner: None,
+                alternatives: Vec::new(),
+                seed: None,
             };
         }
 
@@ -920,8 +1960,39 @@ async fn plan_route_segmented(
             start_terrain.as_deref(),
             end_terrain.as_deref(),
         );
-        let stats =
+        let profile_terrain = full_terrain.as_deref().or(start_terrain.as_deref());
+        let (takeoff, landing) = resolve_terminal_profiles(&request, config);
+        let final_waypoints = match apply_terminal_profiles(
+            &final_waypoints,
+            &takeoff,
+            &landing,
+            &route_obstacles,
+            profile_terrain,
+            clearance_m,
+        ) {
+            Ok(waypoints) => waypoints,
+            Err(errors) => {
+                return RoutePlanResponse {
+                    ok: false,
+                    waypoints: Vec::new(),
+                    stats,
+                    nodes_visited,
+                    optimized_points,
+                    sample_points,
+                    hazards,
+                    profile: Vec::new(),
+                    errors,
+                    timed_out: false,
+                    planner: None,
+                    alternatives: Vec::new(),
+                    seed: None,
+                };
+            }
+        };
+        let mut stats =
             stats.or_else(|| compute_stats_with_terrain(&final_waypoints, full_terrain.as_deref()));
+        grid_costs.record_ground_risk(&final_waypoints, &mut stats);
+        let profile = route_profile(&final_waypoints, &route_obstacles, profile_terrain);
 
         return RoutePlanResponse {
             ok: true,
@@ -931,7 +2002,12 @@ async fn plan_route_segmented(
             optimized_points,
             sample_points,
             hazards,
+            profile,
             errors: Vec::new(),
+            timed_out: false,
+            planner: Some(engine_base.search.into()),
+            alternatives: Vec::new(),
+            seed: None,
         };
     }
 
@@ -943,13 +2019,19 @@ async fn plan_route_segmented(
         optimized_points: 0,
         sample_points: 0,
         hazards: Vec::new(),
+        profile: Vec::new(),
         errors: last_error.unwrap_or_else(|| vec!["route segmentation failed".to_string()]),
+        timed_out: false,
+        planner: None,
+        alternatives: Vec::new(),
+        seed: None,
     }
 }
 
 async fn fetch_segment_inputs(
     client: &Client,
     config: &Config,
+    corridor_obstacles: &CorridorObstacles,
     waypoints: &[Waypoint],
     safety_buffer_m: f64,
     base_spacing: f64,
@@ -964,18 +2046,12 @@ async fn fetch_segment_inputs(
         })
         .collect();
 
-    let ((analysis_result, obstacles_elapsed), (terrain_result, terrain_elapsed)) = tokio::join!(
+    let ((obstacles_result, obstacles_elapsed), (terrain_result, terrain_elapsed)) = tokio::join!(
         async {
             let started_at = Instant::now();
-            let result = fetch_obstacles(
-                client,
-                config,
-                &points,
-                safety_buffer_m,
-                Some(max_lane_radius + safety_buffer_m),
-                ObstacleQueryMode::RoutePlanner,
-            )
-            .await;
+            let result = corridor_obstacles
+                .query(&points, max_lane_radius + safety_buffer_m)
+                .await;
             (result, started_at.elapsed())
         },
         async {
@@ -985,7 +2061,7 @@ async fn fetch_segment_inputs(
         }
     );
 
-    let analysis = match analysis_result {
+    let segment_obstacles = match obstacles_result {
         Ok(result) => result,
         Err(err) => {
             if config.route_planner_require_obstacles {
@@ -995,17 +2071,20 @@ async fn fetch_segment_inputs(
                 "Obstacle fetch failed, continuing without obstacles: {}",
                 err
             );
-            empty_obstacle_analysis()
+            SegmentObstacles {
+                candidates: Vec::new(),
+                truncated: false,
+            }
         }
     };
     tracing::info!(
-        "RoutePlan segment obstacles: {} candidate(s), {} footprint(s), truncated={}, {} ms",
-        analysis.obstacle_count,
-        analysis.footprints.len(),
-        analysis.truncated,
+        "RoutePlan segment obstacles: {} candidate(s), {} corridor tile(s), truncated={}, {} ms",
+        segment_obstacles.candidates.len(),
+        corridor_obstacles.tile_count(),
+        segment_obstacles.truncated,
         obstacles_elapsed.as_millis()
     );
-    if analysis.truncated {
+    if segment_obstacles.truncated {
         if config.route_planner_allow_truncated_obstacles {
             tracing::warn!("Obstacle dataset truncated; continuing with partial data");
         } else {
@@ -1013,16 +2092,16 @@ async fn fetch_segment_inputs(
         }
     }
 
-    let hazards = analysis.hazards.clone();
-    let obstacles: Vec<RouteObstacle> = analysis
+    let hazards: Vec<ObstacleHazard> = segment_obstacles
         .candidates
         .iter()
-        .map(|candidate| RouteObstacle {
-            lat: candidate.lat,
-            lon: candidate.lon,
-            radius_m: candidate.radius_m,
-            height_m: Some(candidate.height_m),
-        })
+        .take(config.compliance_max_obstacles_response)
+        .map(ObstacleCandidate::hazard)
+        .collect();
+    let obstacles: Vec<RouteObstacle> = segment_obstacles
+        .candidates
+        .iter()
+        .map(|candidate| route_obstacle(candidate, safety_buffer_m))
         .collect();
 
     let terrain = match terrain_result {
@@ -1056,11 +2135,14 @@ async fn solve_airborne_segment(
     base_spacing: f64,
     max_lane_radius: f64,
     expansion_step: f64,
-    safety_buffer_m: f64,
-    wind_mps: f64,
+    engine_base: &RouteEngineConfig,
     start_altitude_override: Option<f64>,
     inputs: SegmentInputs,
     geofences: Arc<Vec<Geofence>>,
+    grid_costs: GridCosts,
+    graphs: &RouteGraphStore,
+    pool: &PlannerPool,
+    segment_offset_m: f64,
 ) -> Result<SegmentPlan, SegmentError> {
     let route_distance_m = route_distance_m(waypoints);
     let waypoints: Arc<Vec<Waypoint>> = Arc::new(waypoints.to_vec());
@@ -1105,43 +2187,54 @@ async fn solve_airborne_segment(
             }
 
             for phase in phases.iter().copied() {
+                if engine_base.budget.is_exhausted() {
+                    return Err(SegmentError::Path(vec![SEARCH_TIMED_OUT.to_string()]));
+                }
                 attempted = true;
                 let waypoints = waypoints.clone();
-                let obstacles = obstacles.clone();
+                let obstacles_for_task = obstacles.clone();
                 let geofences = geofences.clone();
                 let terrain_for_task = terrain.clone();
+                let grid_costs = grid_costs.clone();
                 let lane_offsets = lane_offsets.clone();
-                let start_altitude_override = start_altitude_override;
                 let engine_config = RouteEngineConfig {
-                    safety_buffer_m,
-                    wind_mps,
                     geofence_sample_step_m: spacing.clamp(5.0, 25.0),
-                    ..Default::default()
+                    ..engine_base.clone()
                 };
 
                 let attempt_started_at = Instant::now();
-                let handle = task::spawn_blocking(move || {
-                    let Some(mut grid) =
-                        generate_grid_samples(&waypoints, spacing, &lane_offsets, phase)
-                    else {
-                        return Err(SegmentError::Path(vec![
-                            "failed to generate grid".to_string()
-                        ]));
+                let graphs = graphs.clone();
+                let handle = pool.run(move || {
+                    let mut grid = match graphs.grid(
+                        &waypoints,
+                        spacing,
+                        &lane_offsets,
+                        phase,
+                        &obstacles_for_task,
+                        terrain_for_task.as_deref(),
+                        MAX_ROUTE_GRID_POINTS,
+                    ) {
+                        Ok(grid) => grid,
+                        Err(RouteGraphError::Empty) => {
+                            return Err(SegmentError::Path(vec![
+                                "failed to generate grid".to_string()
+                            ]));
+                        }
+                        Err(RouteGraphError::TooLarge(sample_points)) => {
+                            return Err(SegmentError::GridTooLarge(sample_points));
+                        }
                     };
                     let sample_points = grid
                         .lanes
                         .first()
                         .map(|lane| lane.len() * grid.lanes.len())
                         .unwrap_or(0);
-                    if sample_points > MAX_ROUTE_GRID_POINTS {
-                        return Err(SegmentError::GridTooLarge(sample_points));
-                    }
-                    apply_obstacles(&mut grid, &obstacles, |lat, lon| {
-                        terrain_for_task
-                            .as_ref()
-                            .map(|grid| grid.sample(lat, lon))
-                            .unwrap_or(0.0)
-                    });
+                    grid_costs.apply(
+                        &mut grid,
+                        segment_offset_m,
+                        engine_config.ground_speed_mps(),
+                    );
+                    apply_altitude_layers(&mut grid, &engine_config);
                     let result = optimize_airborne_path(
                         &waypoints,
                         &grid,
@@ -1195,6 +2288,7 @@ async fn solve_airborne_segment(
                         optimized_points: result.optimized_points,
                         sample_points,
                         hazards,
+                        obstacles: obstacles.clone(),
                         terrain,
                     });
                 }
@@ -1229,16 +2323,22 @@ fn empty_obstacle_analysis() -> ObstacleAnalysis {
     }
 }
 
+/// Plan a short airborne route, such as a conflict reroute. With a `performance` envelope,
+/// candidate routes the drone cannot fly are rejected.
 pub async fn plan_airborne_route(
     state: &AppState,
     config: &Config,
     waypoints: &[Waypoint],
     safety_buffer_m: f64,
     extra_geofences: &[Geofence],
+    performance: Option<&DronePerformance>,
 ) -> Option<Vec<Waypoint>> {
     if waypoints.len() < 2 {
         return None;
     }
+    if performance.is_some_and(|perf| !perf.can_hold(config.route_planner_wind_mps.max(0.0))) {
+        return None;
+    }
 
     let lane_radius = DEFAULT_LANE_RADIUS_M;
     let lane_spacing = DEFAULT_LANE_SPACING_M;
@@ -1290,12 +2390,7 @@ pub async fn plan_airborne_route(
     let obstacles: Vec<RouteObstacle> = analysis
         .candidates
         .iter()
-        .map(|candidate| RouteObstacle {
-            lat: candidate.lat,
-            lon: candidate.lon,
-            radius_m: candidate.radius_m,
-            height_m: Some(candidate.height_m),
-        })
+        .map(|candidate| route_obstacle(candidate, safety_buffer_m))
         .collect();
 
     let terrain = fetch_terrain_grid(&client, config, &points, base_spacing)
@@ -1305,11 +2400,19 @@ pub async fn plan_airborne_route(
 
     let mut geofences = state.get_geofences();
     geofences.extend(extra_geofences.iter().cloned());
-    let geofences: Vec<Geofence> = geofences
-        .into_iter()
-        .filter(|fence| fence.active && fence.geofence_type != GeofenceType::Advisory)
-        .collect();
-
+    let geofences = blocking_geofences(geofences, config, Utc::now(), route_distance_m);
+
+    let grid_costs = GridCosts {
+        weather: RouteWeather::load(state, config, None),
+        coverage: RouteCoverage::load(state, config, config.route_planner_coverage_mode),
+        wind: load_wind(&client, config, &points).await,
+        traffic: None,
+        ground_risk: RouteGroundRisk::configured(config),
+    };
+    let speed_mps = waypoints
+        .first()
+        .and_then(|wp| wp.speed_mps)
+        .map(|speed| performance.map_or(speed, |perf| speed.min(perf.max_speed_mps)));
     let mut lane_radius = lane_radius;
     let mut best_result: Option<RouteEngineResult> = None;
     let spacing_candidates = lane_spacing_candidates(lane_spacing);
@@ -1345,17 +2448,54 @@ pub async fn plan_airborne_route(
                     terrain_ref.map(|grid| grid.sample(lat, lon)).unwrap_or(0.0)
                 });
 
-                let engine_config = RouteEngineConfig {
+                let mut engine_config = RouteEngineConfig {
                     safety_buffer_m,
                     faa_limit_agl: state.rules().max_altitude_m.max(0.0),
                     wind_mps: config.route_planner_wind_mps.max(0.0),
                     geofence_sample_step_m: spacing.clamp(5.0, 25.0),
+                    altitude_step_m: config.route_planner_altitude_step_m,
+                    max_climb_gradient: config.route_planner_max_climb_gradient,
+                    turn_radius_m: config.route_planner_turn_radius_m,
+                    geofence_standoff_m: config.route_planner_geofence_standoff_m,
+                    search: config.route_planner_search,
                     ..Default::default()
                 };
+                if let Some(performance) = performance {
+                    engine_config.climb_speed_mps = engine_config
+                        .climb_speed_mps
+                        .min(performance.max_climb_rate_mps);
+                    engine_config.cruise_speed_mps = engine_config
+                        .cruise_speed_mps
+                        .min(performance.max_speed_mps);
+                    // Round corners to the drone's own turn so it can fly them at speed.
+                    engine_config.turn_radius_m =
+                        engine_config
+                            .turn_radius_m
+                            .max(performance.turn_radius_m(
+                                speed_mps.unwrap_or(engine_config.cruise_speed_mps),
+                            ));
+                }
+                grid_costs.apply(&mut grid, 0.0, engine_config.ground_speed_mps());
+                apply_altitude_layers(&mut grid, &engine_config);
 
                 let result =
                     optimize_airborne_path(waypoints, &grid, &geofences, &engine_config, None);
                 if result.success && !result.waypoints.is_empty() {
+                    // A route the drone cannot fly is no better than none; try another grid.
+                    if let Some(performance) = performance {
+                        let violations = performance.route_violations(
+                            &airborne_waypoints(&result, speed_mps),
+                            speed_mps.unwrap_or(engine_config.cruise_speed_mps),
+                            engine_config.wind_mps,
+                        );
+                        if !violations.is_empty() {
+                            tracing::debug!(
+                                "Airborne route outside performance envelope: {}",
+                                violations.join("; ")
+                            );
+                            continue;
+                        }
+                    }
                     best_result = Some(result);
                     break;
                 }
@@ -1370,20 +2510,20 @@ pub async fn plan_airborne_route(
         lane_radius += DEFAULT_LANE_EXPANSION_STEP_M;
     }
 
-    let result = best_result?;
-    let speed_mps = waypoints.first().and_then(|wp| wp.speed_mps);
-    Some(
-        result
-            .waypoints
-            .into_iter()
-            .map(|wp| Waypoint {
-                lat: wp.lat,
-                lon: wp.lon,
-                altitude_m: wp.altitude_m,
-                speed_mps,
-            })
-            .collect(),
-    )
+    best_result.map(|result| airborne_waypoints(&result, speed_mps))
+}
+
+fn airborne_waypoints(result: &RouteEngineResult, speed_mps: Option<f64>) -> Vec<Waypoint> {
+    result
+        .waypoints
+        .iter()
+        .map(|wp| Waypoint {
+            lat: wp.lat,
+            lon: wp.lon,
+            altitude_m: wp.altitude_m,
+            speed_mps,
+        })
+        .collect()
 }
 
 fn build_response(
@@ -1399,13 +2539,53 @@ fn build_response(
         optimized_points: result.optimized_points,
         sample_points,
         hazards,
+        profile: Vec::new(),
         errors: result.errors,
+        timed_out: false,
+        planner: result.success.then_some(result.planner),
+        alternatives: Vec::new(),
+        seed: None,
+    }
+}
+
+fn route_profile(
+    waypoints: &[RouteEngineWaypoint],
+    obstacles: &[RouteObstacle],
+    terrain: Option<&TerrainGrid>,
+) -> Vec<RouteProfileStation> {
+    build_route_profile(
+        waypoints,
+        obstacles,
+        |lat, lon| terrain.map(|grid| grid.sample(lat, lon)).unwrap_or(0.0),
+        PROFILE_SAMPLE_SPACING_M,
+        MAX_PROFILE_STATIONS,
+    )
+}
+
+/// Buildings with a known footprint keep it, grown by the clearance, so the streets between
+/// them stay open; other obstacles are circles.
+fn route_obstacle(candidate: &ObstacleCandidate, clearance_m: f64) -> RouteObstacle {
+    let polygon = candidate
+        .polygon
+        .clone()
+        .filter(|polygon| polygon.len() >= 3);
+    RouteObstacle {
+        lat: candidate.lat,
+        lon: candidate.lon,
+        radius_m: if polygon.is_some() {
+            clearance_m.max(0.0)
+        } else {
+            candidate.radius_m
+        },
+        height_m: Some(candidate.height_m),
+        polygon,
     }
 }
 
 fn obstacles_for_lane_radius(
     candidates: &[ObstacleCandidate],
     lane_radius_m: f64,
+    clearance_m: f64,
 ) -> Vec<RouteObstacle> {
     if candidates.is_empty() {
         return Vec::new();
@@ -1420,16 +2600,40 @@ fn obstacles_for_lane_radius(
                 continue;
             }
         }
-        obstacles.push(RouteObstacle {
-            lat: candidate.lat,
-            lon: candidate.lon,
-            radius_m: candidate.radius_m,
-            height_m: Some(candidate.height_m),
-        });
+        obstacles.push(route_obstacle(candidate, clearance_m));
     }
     obstacles
 }
 
+/// Flown distance assumed per metre of straight-line route when estimating how long a planned
+/// flight stays exposed to scheduled geofences.
+const FLIGHT_WINDOW_DETOUR_FACTOR: f64 = 1.5;
+
+/// Geofences the planner must avoid: enforced, and in force at some point between `departure`
+/// and the latest the flight is expected to land.
+fn blocking_geofences(
+    geofences: Vec<Geofence>,
+    config: &Config,
+    departure: DateTime<Utc>,
+    route_distance_m: f64,
+) -> Vec<Geofence> {
+    let ground_speed_mps = RouteEngineConfig {
+        wind_mps: config.route_planner_wind_mps,
+        ..RouteEngineConfig::default()
+    }
+    .ground_speed_mps()
+    .max(1.0);
+    let flight_secs = route_distance_m * FLIGHT_WINDOW_DETOUR_FACTOR / ground_speed_mps;
+    let arrival = departure + chrono::Duration::milliseconds((flight_secs * 1000.0) as i64);
+    geofences
+        .into_iter()
+        .filter(|fence| {
+            fence.in_force_during(departure, arrival)
+                && fence.geofence_type != GeofenceType::Advisory
+        })
+        .collect()
+}
+
 fn route_distance_m(waypoints: &[Waypoint]) -> f64 {
     let mut total = 0.0;
     for i in 1..waypoints.len() {
@@ -1652,6 +2856,52 @@ fn wrap_with_takeoff_landing(
     output
 }
 
+/// Resolve takeoff and landing profiles: request override, then vertiport default, then vertical.
+fn resolve_terminal_profiles(
+    request: &RoutePlanRequest,
+    config: &Config,
+) -> (TerminalProfile, TerminalProfile) {
+    let overrides = request.takeoff_landing.unwrap_or_default();
+    let vertiport_profile = |wp: Option<&Waypoint>| {
+        wp.and_then(|wp| find_vertiport(&config.vertiports, wp.lat, wp.lon))
+            .map(|vertiport| vertiport.profile)
+            .unwrap_or_default()
+    };
+    let origin = vertiport_profile(request.waypoints.first());
+    let destination = vertiport_profile(request.waypoints.last());
+    let takeoff = overrides.takeoff.or(origin.takeoff).unwrap_or_default();
+    let landing = overrides
+        .landing
+        .or(destination.landing)
+        .unwrap_or_default();
+    (takeoff, landing)
+}
+
+fn apply_terminal_profiles(
+    waypoints: &[RouteEngineWaypoint],
+    takeoff: &TerminalProfile,
+    landing: &TerminalProfile,
+    obstacles: &[RouteObstacle],
+    terrain: Option<&TerrainGrid>,
+    clearance_m: f64,
+) -> Result<Vec<RouteEngineWaypoint>, Vec<String>> {
+    if *takeoff == TerminalProfile::default() && *landing == TerminalProfile::default() {
+        return Ok(waypoints.to_vec());
+    }
+    let waypoints =
+        apply_takeoff_landing_profile(waypoints, takeoff, landing).map_err(|err| vec![err])?;
+    let violations = terminal_obstacle_violations(
+        &waypoints,
+        obstacles,
+        |lat, lon| terrain.map(|grid| grid.sample(lat, lon)).unwrap_or(0.0),
+        clearance_m,
+    );
+    if !violations.is_empty() {
+        return Err(violations);
+    }
+    Ok(waypoints)
+}
+
 fn sample_ground(
     primary: Option<&TerrainGrid>,
     fallback: Option<&TerrainGrid>,
@@ -1674,6 +2924,12 @@ fn merge_stats(
             avg_agl: (a.avg_agl + b.avg_agl) / 2.0,
             max_agl: a.max_agl.max(b.max_agl),
             max_altitude: a.max_altitude.max(b.max_altitude),
+            min_geofence_clearance_m: match (a.min_geofence_clearance_m, b.min_geofence_clearance_m)
+            {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+            ground_risk: a.ground_risk.or(b.ground_risk),
         }),
     }
 }
@@ -1710,5 +2966,7 @@ fn compute_stats_with_terrain(
         avg_agl: sum_agl / count,
         max_agl,
         max_altitude: max_alt,
+        min_geofence_clearance_m: None,
+        ground_risk: None,
     })
 }
@@ -0,0 +1,67 @@
+//! Airspace sectors and dispatcher assignment.
+//!
+//! A sector is a named polygon owned by one dispatcher principal. Conflicts, DAA advisories and
+//! flight plans awaiting approval are tagged with the sector they fall in, and each newly tagged
+//! item is published on the dispatch stream so the responsible dispatcher sees it.
+
+use atc_core::spatial::point_in_polygon;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A named airspace sector with its responsible dispatcher.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sector {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Closed `[lat, lon]` polygon.
+    pub polygon: Vec<[f64; 2]>,
+    /// Dispatcher principal responsible for this sector.
+    pub dispatcher: String,
+}
+
+impl Sector {
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        point_in_polygon(lat, lon, &self.polygon)
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.id.trim().is_empty() {
+            errors.push("id must not be empty".to_string());
+        }
+        if self.dispatcher.trim().is_empty() {
+            errors.push("dispatcher must not be empty".to_string());
+        }
+        if self.polygon.len() < 3 {
+            errors.push("polygon must have at least 3 vertices".to_string());
+        }
+        errors
+    }
+}
+
+/// First sector containing the point; sectors are expected not to overlap.
+pub fn sector_for(sectors: &[Sector], lat: f64, lon: f64) -> Option<&Sector> {
+    sectors.iter().find(|sector| sector.contains(lat, lon))
+}
+
+/// What a dispatch notification refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DispatchItemKind {
+    Conflict,
+    Advisory,
+    Approval,
+}
+
+/// A sector-tagged item routed to its dispatcher.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DispatchNotification {
+    pub sector_id: String,
+    pub dispatcher: String,
+    pub kind: DispatchItemKind,
+    /// Conflict key, advisory ID or flight ID.
+    pub item_id: String,
+    pub summary: String,
+    pub created_at: DateTime<Utc>,
+}
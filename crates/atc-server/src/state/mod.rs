@@ -2,4 +2,6 @@
 
 pub mod store;
 
-pub use store::{AppState, ExternalTraffic};
+pub use store::{
+    AppState, BroadcastScope, BroadcastTarget, CommandBroadcast, ExternalTraffic, LoopPause,
+};
@@ -1,29 +1,63 @@
 //! In-memory state store using DashMap.
 
 use anyhow::Result;
+use atc_core::messages::{codes, Message};
 use atc_core::models::{
-    Command, ConformanceStatus, DaaAdvisory, DroneState, DroneStatus, FlightPlan, Geofence,
-    Telemetry,
+    Command, CommandSigningKey, CommandType, ConformanceStatus, DaaAdvisory, DaaSeverity,
+    DroneState, DroneStatus, FlightPlan, FlightStatus, Geofence, SignedCommand, Telemetry,
+};
+use atc_core::rules::{SafetyRules, SafetyRulesOverride};
+use atc_core::{
+    apply_intent_filter, apply_track_quality_filter, dead_reckon, plans_resolve_conflict,
+    AircraftCategory, Conflict, ConflictDetector, ConflictSeverity, CoverageArea,
+    DeadReckoningMode, DroneCapabilities, DroneHome, DronePerformance, DronePosition,
+    GeofenceBreach, IntentFilterMode, MsaBounds, MsaGrid, PlannedDrone, SeparationVolume,
+    TrackEstimator, TrackFix, TrackHistory, TrackQuality, TrackQualityMode, WeatherCell,
 };
-use atc_core::rules::SafetyRules;
-use atc_core::{Conflict, ConflictDetector, DronePosition};
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{
-    atomic::{AtomicU32, AtomicU64, Ordering},
+    atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
     Arc, RwLock,
 };
 use std::time::{SystemTime, UNIX_EPOCH};
+use utoipa::ToSchema;
 
 use crate::altitude::altitude_to_amsl;
+use crate::breach::{BreachEvent, BREACH_LOG_CAPACITY};
+use crate::command_signing::CommandSigner;
 use crate::config::Config;
+use crate::conformance_policy::ConformancePolicy;
+use crate::fairness::FairnessMetrics;
+use crate::jobs::JobQueue;
+use crate::lifecycle::Lifecycle;
+use crate::loops::telemetry_persist_loop::TelemetryPersistMetrics;
+use crate::metering::UsageMeter;
+use crate::organizations::{self, Organization};
+use crate::persistence::command_audit::{CommandCause, CommandCauseKind};
+use crate::persistence::conflicts::{ConflictOutcome, ConflictRecord};
 use crate::persistence::db as db_persistence;
+use crate::persistence::drone_tokens::DroneSessionToken;
+use crate::persistence::emergency::EmergencyStop;
 use crate::persistence::{
-    commands as commands_db, drone_tokens as drone_tokens_db, drones as drones_db,
-    flight_plans as flight_plans_db, geofences as geofences_db, Database,
+    c2_coverage as c2_coverage_db, commands as commands_db, conflicts as conflicts_db,
+    conformance_policy as conformance_policy_db, drone_capabilities as drone_capabilities_db,
+    drone_homes as drone_homes_db, drone_performance as drone_performance_db,
+    drone_tokens as drone_tokens_db, drones as drones_db, emergency as emergency_db,
+    flight_plans as flight_plans_db, geofences as geofences_db, jobs as jobs_db,
+    organizations as organizations_db, owner_data as owner_data_db,
+    safety_rules as safety_rules_db, usage as usage_db, webhooks as webhooks_db, Database,
 };
+use crate::planner_pool::PlannerPool;
+use crate::rejection::{PlanRejection, REJECTION_LOG_CAPACITY};
+use crate::sectors::{sector_for, DispatchItemKind, DispatchNotification, Sector};
+use crate::telemetry_auth::ReplayGuard;
+use crate::throttle::{command_action, BudgetScope, CommandThrottle, UNSECTORED};
+use crate::track_index::TrackIndex;
+use crate::warmup::PlannerWarmup;
+use crate::webhooks::{self, Webhook, WebhookEventType};
 use tokio::sync::{broadcast, mpsc, Mutex};
 
 const TELEMETRY_QUEUE_DEPTH: usize = 4096;
@@ -41,10 +75,22 @@ pub struct WsDroneEvent {
 /// Application state - thread-safe store for drones and conflicts.
 pub struct AppState {
     drones: DashMap<String, DroneState>,
+    /// Grid index of drone positions (for bounding-box queries)
+    drone_index: TrackIndex,
     drone_owners: DashMap<String, String>,
-    drone_tokens: DashMap<String, String>,
+    drone_tokens: DashMap<String, DroneSessionToken>,
+    /// Registered performance envelopes per drone
+    drone_performance: DashMap<String, DronePerformance>,
+    /// Registered home points and tethers per drone
+    drone_homes: DashMap<String, DroneHome>,
     external_traffic: DashMap<String, ExternalTraffic>,
+    /// Grid index of external track positions (for bounding-box queries)
+    traffic_index: TrackIndex,
     external_traffic_cap_warn_last: AtomicU64,
+    /// Report history per external track, for quality scoring
+    traffic_history: DashMap<String, TrackHistory>,
+    /// Smoothing filter per external track
+    traffic_estimators: DashMap<String, TrackEstimator>,
     pub flight_plans: DashMap<String, FlightPlan>,
     flight_plan_booking_lock: Mutex<()>,
     detector: std::sync::Mutex<ConflictDetector>,
@@ -54,22 +100,46 @@ pub struct AppState {
     detector_queue_warn_last: AtomicU64,
     detector_overflow_warn_last: AtomicU64,
     conflicts: DashMap<String, Conflict>,
+    /// Start time, peak severity and closest separation of each active conflict
+    conflict_tracks: DashMap<String, ConflictTrack>,
+    /// Ended conflicts waiting to be written to the conflict history
+    ended_conflicts: std::sync::Mutex<Vec<ConflictRecord>>,
+    /// Lost drones dropped from the detector once past the dead-reckoning horizon
+    dead_reckoning_dropped: std::sync::Mutex<HashSet<String>>,
+    /// Drones inside or projected to enter a restricted geofence, from the last detector pass
+    geofence_breaches: RwLock<Vec<GeofenceBreach>>,
     /// Command queues per drone (FIFO)
     commands: DashMap<String, VecDeque<Command>>,
     /// Track recently issued commands to prevent spam
     command_cooldowns: DashMap<String, std::time::Instant>,
     /// Track active HOLD commands after acknowledgment
     active_holds: DashMap<String, DateTime<Utc>>,
+    /// Area/owner command broadcasts by ID
+    command_broadcasts: DashMap<String, CommandBroadcast>,
+    /// Command ID -> broadcast ID, for acknowledgement tracking
+    broadcast_commands: DashMap<String, String>,
+    /// Emergency all-stop in force; blocks plan approvals until cleared
+    emergency: RwLock<Option<EmergencyStop>>,
+    /// Emergency declarations and clearances (for WS streaming)
+    emergency_tx: broadcast::Sender<Option<EmergencyStop>>,
     drone_counter: AtomicU32,
     pub tx: broadcast::Sender<WsDroneEvent>, // For WS broadcasting (pre-serialized payload)
     command_tx: broadcast::Sender<Command>,
+    /// Conflicts from each detector pass (for SSE streaming)
+    conflict_tx: broadcast::Sender<Conflict>,
     /// Telemetry persistence queue (coalesced in background).
     telemetry_tx: mpsc::Sender<DroneState>,
     telemetry_rx: std::sync::Mutex<Option<mpsc::Receiver<DroneState>>>,
     telemetry_overflow: std::sync::Mutex<HashMap<String, DroneState>>,
     telemetry_overflow_warn_last: AtomicU64,
-    /// Safety rules (for timeout checks, etc.)
-    rules: SafetyRules,
+    /// Safety rules as configured at startup
+    configured_rules: SafetyRules,
+    /// Values tuned at runtime through the admin API
+    rules_override: RwLock<SafetyRulesOverride>,
+    /// Live safety rules: the configured ones with the tuned values applied
+    rules: RwLock<Arc<SafetyRules>>,
+    /// Set when the rules change, so the next refresh re-detects without waiting for telemetry
+    rules_changed: AtomicBool,
     /// Geofences/No-fly zones
     geofences: DashMap<String, Geofence>,
     /// External geofences pulled from Blender/DSS (not persisted)
@@ -78,14 +148,57 @@ pub struct AppState {
     conflict_geofences: DashMap<String, i64>,
     /// Latest conformance status per drone
     conformance: DashMap<String, ConformanceStatus>,
+    /// Escalation steps the conformance loop applies to nonconforming drones
+    conformance_policy: RwLock<ConformancePolicy>,
     /// Latest DAA advisories per drone
     daa_advisories: DashMap<String, DaaAdvisory>,
     /// Current RID viewport (min_lat,min_lon,max_lat,max_lon)
     rid_view_bbox: RwLock<String>,
+    /// Latest forecast weather cells the route planner avoids
+    weather_cells: RwLock<Vec<WeatherCell>>,
+    /// C2 link coverage areas for BVLOS planning and compliance
+    coverage_areas: RwLock<Vec<CoverageArea>>,
+    /// Latest minimum safe altitude grid over the operating area
+    msa_grid: RwLock<Option<Arc<MsaGrid>>>,
     /// Per-loop heartbeat timestamps (Unix seconds).
     loop_heartbeats: DashMap<&'static str, u64>,
+    /// Operator-requested loop pauses, keyed by loop name
+    loop_pauses: DashMap<&'static str, LoopPause>,
     /// SQLite database for persistence (optional for backwards compat)
     database: Option<Database>,
+    /// Signs commands delivered to drones (when a signing key is configured)
+    command_signer: Option<Arc<CommandSigner>>,
+    /// Telemetry nonce history and per-drone rejection counters
+    telemetry_guard: ReplayGuard,
+    /// Recent automatic geofence breach responses (audit log)
+    breach_log: std::sync::Mutex<VecDeque<BreachEvent>>,
+    /// Recent scheduler rejections, explained per tried slot
+    plan_rejections: std::sync::Mutex<VecDeque<PlanRejection>>,
+    /// Sector-tagged items routed to dispatchers (for WS streaming)
+    dispatch_tx: broadcast::Sender<DispatchNotification>,
+    /// Item key -> sector ID of the last dispatch notification, so each item is routed once
+    dispatch_tagged: DashMap<String, String>,
+    /// Per-organization monthly usage (billing metering)
+    usage: UsageMeter,
+    /// Per-operator counters of the scheduler fairness policies
+    fairness: FairnessMetrics,
+    /// Telemetry persistence flush and overflow counters
+    telemetry_persist: TelemetryPersistMetrics,
+    planner_pool: PlannerPool,
+    planner_warmup: PlannerWarmup,
+    jobs: JobQueue,
+    /// Registered webhooks by ID
+    webhooks: DashMap<String, Webhook>,
+    /// Tenant organizations by ID
+    organizations: DashMap<String, Organization>,
+    /// Organization token hash -> organization ID
+    organization_tokens: DashMap<String, String>,
+    /// Owner ID -> organization ID of the operator's organization
+    organization_members: DashMap<String, String>,
+    /// Startup progress and shutdown drain, for the orchestrator probes
+    lifecycle: Lifecycle,
+    /// Global and per-sector budgets for automatically issued commands
+    command_throttle: CommandThrottle,
     /// Server configuration (for compliance lookups, etc.)
     config: Config,
 }
@@ -100,6 +213,72 @@ pub struct ExternalTraffic {
     pub heading_deg: f64,
     pub speed_mps: f64,
     pub last_update: chrono::DateTime<chrono::Utc>,
+    /// Inferred from the ADS-B emitter category or Remote ID UA type.
+    #[serde(default)]
+    pub category: AircraftCategory,
+    /// Track quality when the track was read; filled in by the state store.
+    #[serde(default)]
+    pub quality: Option<TrackQuality>,
+    /// Seconds the position has been extrapolated past `last_update`.
+    #[serde(default)]
+    pub extrapolated_s: f64,
+}
+
+/// An operator-requested pause of a background loop; it resumes on its own at `resume_at`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoopPause {
+    pub paused_at: DateTime<Utc>,
+    pub resume_at: DateTime<Utc>,
+    pub reason: Option<String>,
+}
+
+/// Lifetime of an active conflict, folded into a `ConflictRecord` when it ends.
+#[derive(Debug, Clone)]
+struct ConflictTrack {
+    drone1_id: String,
+    drone2_id: String,
+    started_at: DateTime<Utc>,
+    peak_severity: ConflictSeverity,
+    min_separation_m: f64,
+    sector_id: Option<String>,
+}
+
+impl ConflictTrack {
+    /// ID of the conflict history record, `key` being the pair's conflict key.
+    fn conflict_id(&self, key: &str) -> String {
+        format!("{}-{}", key, self.started_at.timestamp_millis())
+    }
+}
+
+/// A command fanned out to every drone in a scope, with per-drone acknowledgement tracking.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandBroadcast {
+    pub broadcast_id: String,
+    pub command_type: CommandType,
+    pub scope: BroadcastScope,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub targets: Vec<BroadcastTarget>,
+}
+
+/// Drones a broadcast is addressed to; every criterion given must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct BroadcastScope {
+    /// Closed `[lat, lon]` polygon containing the drone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polygon: Option<Vec<[f64; 2]>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<String>,
+    /// Airspace sector the drone is in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sector_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastTarget {
+    pub drone_id: String,
+    pub command_id: String,
+    pub acknowledged_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -108,6 +287,17 @@ pub enum RegisterDroneOutcome {
     AlreadyRegistered,
 }
 
+/// What deregistering a drone cancelled.
+#[derive(Debug, Clone, Serialize)]
+pub struct DroneArchive {
+    pub drone_id: String,
+    pub archived_at: DateTime<Utc>,
+    /// Pending commands dropped from the drone's queue
+    pub cancelled_commands: Vec<String>,
+    /// Reserved, pending, approved or active flight plans now cancelled
+    pub cancelled_flights: Vec<String>,
+}
+
 #[derive(Debug)]
 enum DetectorUpdate {
     Upsert(DronePosition),
@@ -136,23 +326,27 @@ impl AppState {
     pub fn with_rules_and_config(rules: SafetyRules, config: Config) -> Self {
         let (tx, _) = broadcast::channel(100);
         let (command_tx, _) = broadcast::channel(100);
+        let (conflict_tx, _) = broadcast::channel(100);
+        let (dispatch_tx, _) = broadcast::channel(100);
+        let (emergency_tx, _) = broadcast::channel(16);
         let (telemetry_tx, telemetry_rx) = mpsc::channel(TELEMETRY_QUEUE_DEPTH);
         let (detector_tx, detector_rx) = mpsc::channel(DETECTOR_QUEUE_DEPTH);
 
         // Create detector with configurable thresholds from rules
-        let detector = ConflictDetector::new(
-            rules.lookahead_seconds,
-            rules.min_horizontal_separation_m,
-            rules.min_vertical_separation_m,
-            rules.warning_multiplier,
-        );
+        let detector = rules.conflict_detector();
 
         Self {
             drones: DashMap::new(),
+            drone_index: TrackIndex::default(),
             drone_owners: DashMap::new(),
+            drone_performance: DashMap::new(),
+            drone_homes: DashMap::new(),
             drone_tokens: DashMap::new(),
             external_traffic: DashMap::new(),
+            traffic_index: TrackIndex::default(),
             external_traffic_cap_warn_last: AtomicU64::new(0),
+            traffic_history: DashMap::new(),
+            traffic_estimators: DashMap::new(),
             flight_plans: DashMap::new(),
             flight_plan_booking_lock: Mutex::new(()),
             detector: std::sync::Mutex::new(detector),
@@ -162,25 +356,68 @@ impl AppState {
             detector_queue_warn_last: AtomicU64::new(0),
             detector_overflow_warn_last: AtomicU64::new(0),
             conflicts: DashMap::new(),
+            conflict_tracks: DashMap::new(),
+            ended_conflicts: std::sync::Mutex::new(Vec::new()),
+            dead_reckoning_dropped: std::sync::Mutex::new(HashSet::new()),
+            geofence_breaches: RwLock::new(Vec::new()),
             commands: DashMap::new(),
             command_cooldowns: DashMap::new(),
             active_holds: DashMap::new(),
+            command_broadcasts: DashMap::new(),
+            emergency: RwLock::new(None),
+            emergency_tx,
+            broadcast_commands: DashMap::new(),
             drone_counter: AtomicU32::new(1),
             tx,
             command_tx,
+            conflict_tx,
             telemetry_tx,
             telemetry_rx: std::sync::Mutex::new(Some(telemetry_rx)),
             telemetry_overflow: std::sync::Mutex::new(HashMap::new()),
             telemetry_overflow_warn_last: AtomicU64::new(0),
-            rules,
+            rules: RwLock::new(Arc::new(rules.clone())),
+            configured_rules: rules,
+            rules_override: RwLock::new(SafetyRulesOverride::default()),
+            rules_changed: AtomicBool::new(false),
             geofences: DashMap::new(),
             external_geofences: DashMap::new(),
             conflict_geofences: DashMap::new(),
             conformance: DashMap::new(),
+            conformance_policy: RwLock::new(ConformancePolicy::default()),
             daa_advisories: DashMap::new(),
             rid_view_bbox: RwLock::new(String::new()),
+            weather_cells: RwLock::new(Vec::new()),
+            coverage_areas: RwLock::new(Vec::new()),
+            msa_grid: RwLock::new(None),
             loop_heartbeats: DashMap::new(),
+            loop_pauses: DashMap::new(),
             database: None,
+            command_signer: config.command_signing_key.as_deref().and_then(|seed| {
+                match CommandSigner::from_seed_base64(seed) {
+                    Ok(signer) => Some(Arc::new(signer)),
+                    Err(err) => {
+                        tracing::error!("Command signing disabled: {}", err);
+                        None
+                    }
+                }
+            }),
+            telemetry_guard: ReplayGuard::new(),
+            breach_log: std::sync::Mutex::new(VecDeque::new()),
+            plan_rejections: std::sync::Mutex::new(VecDeque::new()),
+            dispatch_tx,
+            dispatch_tagged: DashMap::new(),
+            usage: UsageMeter::new(),
+            fairness: FairnessMetrics::new(),
+            telemetry_persist: TelemetryPersistMetrics::default(),
+            planner_pool: PlannerPool::from_config(&config),
+            planner_warmup: PlannerWarmup::default(),
+            jobs: JobQueue::default(),
+            webhooks: DashMap::new(),
+            organizations: DashMap::new(),
+            organization_tokens: DashMap::new(),
+            organization_members: DashMap::new(),
+            lifecycle: Lifecycle::default(),
+            command_throttle: CommandThrottle::new(config.auto_command_budget),
             config,
         }
     }
@@ -202,6 +439,52 @@ impl AppState {
         self.loop_heartbeats.get(name).map(|entry| *entry.value())
     }
 
+    /// Pause a loop until `resume_at`; paused loops keep their heartbeat but skip their work.
+    pub fn pause_loop(&self, name: &'static str, resume_at: DateTime<Utc>, reason: Option<String>) {
+        tracing::warn!(
+            "Loop {} paused until {}{}",
+            name,
+            resume_at.to_rfc3339(),
+            reason
+                .as_deref()
+                .map(|reason| format!(" ({})", reason))
+                .unwrap_or_default()
+        );
+        self.loop_pauses.insert(
+            name,
+            LoopPause {
+                paused_at: Utc::now(),
+                resume_at,
+                reason,
+            },
+        );
+    }
+
+    /// Resume a paused loop; returns false if it was not paused.
+    pub fn resume_loop(&self, name: &str) -> bool {
+        let resumed = self.loop_pauses.remove(name).is_some();
+        if resumed {
+            tracing::info!("Loop {} resumed", name);
+        }
+        resumed
+    }
+
+    /// Current pause of a loop, resuming it first if its timer has run out.
+    pub fn loop_pause(&self, name: &str) -> Option<LoopPause> {
+        let pause = self.loop_pauses.get(name)?.value().clone();
+        if pause.resume_at <= Utc::now() {
+            self.loop_pauses
+                .remove_if(name, |_, current| current.resume_at <= Utc::now());
+            tracing::info!("Loop {} resumed automatically", name);
+            return None;
+        }
+        Some(pause)
+    }
+
+    pub fn loop_paused(&self, name: &str) -> bool {
+        self.loop_pause(name).is_some()
+    }
+
     /// Take the telemetry receiver for the persistence loop.
     pub fn take_telemetry_receiver(&self) -> Option<mpsc::Receiver<DroneState>> {
         if let Ok(mut guard) = self.telemetry_rx.lock() {
@@ -222,18 +505,21 @@ impl AppState {
 
     fn store_telemetry_overflow(&self, state: DroneState) {
         if self.config.max_overflow_entries == 0 {
+            self.telemetry_persist.record_overflow_dropped();
             return;
         }
         if let Ok(mut guard) = self.telemetry_overflow.lock() {
             if guard.len() >= self.config.max_overflow_entries
                 && !guard.contains_key(&state.drone_id)
             {
+                self.telemetry_persist.record_overflow_dropped();
                 self.warn_state_cap(
                     &self.telemetry_overflow_warn_last,
                     "Telemetry overflow cap reached; dropping snapshots",
                 );
                 return;
             }
+            self.telemetry_persist.record_overflow_stashed();
             guard.insert(state.drone_id.clone(), state);
         }
     }
@@ -247,8 +533,11 @@ impl AppState {
         let pool = db.pool().clone();
 
         self.drones.clear();
+        self.drone_index.clear();
         self.drone_owners.clear();
         self.drone_tokens.clear();
+        self.drone_performance.clear();
+        self.drone_homes.clear();
         self.flight_plans.clear();
         self.geofences.clear();
         self.commands.clear();
@@ -259,10 +548,13 @@ impl AppState {
             if let Some(owner_id) = drone.owner_id.clone() {
                 self.drone_owners.insert(drone.drone_id.clone(), owner_id);
             }
+            self.drone_index
+                .upsert(&drone.drone_id, drone.lat, drone.lon);
             self.drones.insert(drone.drone_id.clone(), drone);
         }
 
-        self.seed_drone_counter();
+        let drone_ids = drones_db::load_all_drone_ids(&pool).await?;
+        self.seed_drone_counter(drone_ids.iter().map(String::as_str));
 
         if let Ok(mut detector) = self.detector.lock() {
             for drone in self.drones.iter() {
@@ -305,7 +597,70 @@ impl AppState {
         let tokens = drone_tokens_db::load_all_drone_tokens(&pool).await?;
         for token in tokens {
             self.drone_tokens
-                .insert(token.drone_id, token.session_token);
+                .insert(token.drone_id.clone(), DroneSessionToken::from(token));
+        }
+
+        for (drone_id, performance) in drone_performance_db::load_drone_performance(&pool).await? {
+            self.drone_performance.insert(drone_id, performance);
+        }
+
+        for (drone_id, capabilities) in
+            drone_capabilities_db::load_drone_capabilities(&pool).await?
+        {
+            if let Some(mut drone) = self.drones.get_mut(&drone_id) {
+                drone.capabilities = Some(capabilities);
+            }
+        }
+
+        for (drone_id, home) in drone_homes_db::load_drone_homes(&pool).await? {
+            self.drone_homes.insert(drone_id, home);
+        }
+
+        if let Some(overrides) = safety_rules_db::load_rules_override(&pool).await? {
+            self.apply_rules_override(overrides);
+        }
+
+        if let Some(policy) = conformance_policy_db::load_policy(&pool).await? {
+            if let Ok(mut guard) = self.conformance_policy.write() {
+                *guard = policy;
+            }
+        }
+
+        let emergency = emergency_db::load_emergency(&pool).await?;
+        if let Ok(mut guard) = self.emergency.write() {
+            *guard = emergency;
+        }
+
+        let coverage = c2_coverage_db::load_coverage_areas(&pool).await?;
+        if let Ok(mut guard) = self.coverage_areas.write() {
+            *guard = coverage;
+        }
+
+        self.usage
+            .restore(usage_db::load_usage_records(&pool).await?);
+
+        self.webhooks.clear();
+        for webhook in webhooks_db::load_webhooks(&pool).await? {
+            self.webhooks.insert(webhook.id.clone(), webhook);
+        }
+
+        self.organizations.clear();
+        self.organization_tokens.clear();
+        self.organization_members.clear();
+        for (organization, token_hash) in organizations_db::load_organizations(&pool).await? {
+            for owner_id in &organization.members {
+                self.organization_members
+                    .insert(owner_id.clone(), organization.org_id.clone());
+            }
+            self.organization_tokens
+                .insert(token_hash, organization.org_id.clone());
+            self.organizations
+                .insert(organization.org_id.clone(), organization);
+        }
+
+        // Jobs cut off by the restart run again.
+        for job in self.jobs.load(jobs_db::load_jobs(&pool).await?) {
+            jobs_db::upsert_job(&pool, &job).await?;
         }
 
         Ok(())
@@ -397,10 +752,50 @@ impl AppState {
         }
     }
 
-    /// Get the configured safety rules.
-    #[allow(dead_code)] // Available for API endpoints
-    pub fn rules(&self) -> &SafetyRules {
-        &self.rules
+    /// Get the live safety rules.
+    pub fn rules(&self) -> Arc<SafetyRules> {
+        self.rules
+            .read()
+            .map(|rules| rules.clone())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+    }
+
+    /// The values tuned at runtime over the configured rules.
+    pub fn rules_override(&self) -> SafetyRulesOverride {
+        self.rules_override
+            .read()
+            .map(|overrides| overrides.clone())
+            .unwrap_or_default()
+    }
+
+    /// Tune the safety rules at runtime, persisting the tuned values first so a restart keeps
+    /// them. `overrides` replaces the previously tuned values.
+    pub async fn set_rules_override(&self, overrides: SafetyRulesOverride) -> Result<()> {
+        if let Some(db) = self.database.clone() {
+            safety_rules_db::save_rules_override(db.pool(), &overrides).await?;
+        }
+        self.apply_rules_override(overrides);
+        Ok(())
+    }
+
+    fn apply_rules_override(&self, overrides: SafetyRulesOverride) {
+        let rules = overrides.apply(&self.configured_rules);
+        // Rebuild the detector with the new thresholds but keep the tracked positions, so the
+        // next pass re-evaluates every pair under the new rules.
+        if let Ok(mut detector) = self.detector.lock() {
+            let mut rebuilt = rules.conflict_detector();
+            for position in detector.get_all_positions() {
+                rebuilt.update_position(position.clone());
+            }
+            *detector = rebuilt;
+        }
+        if let Ok(mut guard) = self.rules.write() {
+            *guard = Arc::new(rules);
+        }
+        if let Ok(mut guard) = self.rules_override.write() {
+            *guard = overrides;
+        }
+        self.rules_changed.store(true, Ordering::Relaxed);
     }
 
     /// Serialize flight plan creation to avoid double-booking.
@@ -413,11 +808,262 @@ impl AppState {
         self.command_tx.subscribe()
     }
 
+    /// Subscribe to conflicts as each detector pass finds them (for SSE streaming).
+    pub fn subscribe_conflicts(&self) -> broadcast::Receiver<Conflict> {
+        self.conflict_tx.subscribe()
+    }
+
+    /// Subscribe to emergency declarations (`Some`) and clearances (`None`) (for WS streaming).
+    pub fn subscribe_emergency(&self) -> broadcast::Receiver<Option<EmergencyStop>> {
+        self.emergency_tx.subscribe()
+    }
+
+    /// Subscribe to dispatcher notifications (for WS streaming).
+    pub fn subscribe_dispatch(&self) -> broadcast::Receiver<DispatchNotification> {
+        self.dispatch_tx.subscribe()
+    }
+
+    /// Sector containing a point, if any.
+    pub fn sector_at(&self, lat: f64, lon: f64) -> Option<&Sector> {
+        sector_for(&self.config.sectors, lat, lon)
+    }
+
+    /// Publish a sector-tagged item to its dispatcher, once per item and sector.
+    fn route_to_dispatcher(
+        &self,
+        kind: DispatchItemKind,
+        item_id: &str,
+        sector: &Sector,
+        summary: impl FnOnce() -> String,
+    ) {
+        let key = format!("{:?}:{}", kind, item_id);
+        if self.dispatch_tagged.insert(key, sector.id.clone()).as_ref() == Some(&sector.id) {
+            return;
+        }
+        let _ = self.dispatch_tx.send(DispatchNotification {
+            sector_id: sector.id.clone(),
+            dispatcher: sector.dispatcher.clone(),
+            kind,
+            item_id: item_id.to_string(),
+            summary: summary(),
+            created_at: Utc::now(),
+        });
+    }
+
+    fn forget_dispatch_item(&self, kind: DispatchItemKind, item_id: &str) {
+        self.dispatch_tagged
+            .remove(&format!("{:?}:{}", kind, item_id));
+    }
+
     /// Get config reference (for compliance evaluation, etc.).
     pub fn config(&self) -> &Config {
         &self.config
     }
 
+    /// Public command signing key, if commands are signed.
+    pub fn command_signing_key(&self) -> Option<CommandSigningKey> {
+        self.command_signer
+            .as_ref()
+            .map(|signer| signer.signing_key().clone())
+    }
+
+    /// Wrap a command for delivery to a drone, signing it when a key is configured.
+    pub fn sign_command(&self, command: Command) -> SignedCommand {
+        match self.command_signer.as_ref() {
+            Some(signer) => signer.sign(command),
+            None => SignedCommand {
+                command,
+                signature: None,
+            },
+        }
+    }
+
+    /// Telemetry anti-replay state.
+    pub fn telemetry_guard(&self) -> &ReplayGuard {
+        &self.telemetry_guard
+    }
+
+    /// Billing usage meter.
+    pub fn usage_meter(&self) -> &UsageMeter {
+        &self.usage
+    }
+
+    /// Scheduler fairness counters.
+    pub fn fairness_metrics(&self) -> &FairnessMetrics {
+        &self.fairness
+    }
+
+    /// Telemetry persistence counters.
+    pub fn telemetry_persist_metrics(&self) -> &TelemetryPersistMetrics {
+        &self.telemetry_persist
+    }
+
+    /// Worker pool running route planner searches.
+    pub fn planner_pool(&self) -> &PlannerPool {
+        &self.planner_pool
+    }
+
+    /// Obstacle and terrain prefetch job for the operating areas.
+    pub fn planner_warmup(&self) -> &PlannerWarmup {
+        &self.planner_warmup
+    }
+
+    /// Background jobs and their retry state.
+    pub fn jobs(&self) -> &JobQueue {
+        &self.jobs
+    }
+
+    /// Register a webhook.
+    pub async fn add_webhook(&self, webhook: Webhook) -> Result<()> {
+        if let Some(db) = self.database.clone() {
+            webhooks_db::insert_webhook(db.pool(), &webhook).await?;
+        }
+        self.webhooks.insert(webhook.id.clone(), webhook);
+        Ok(())
+    }
+
+    /// Unregister a webhook; deliveries still queued for it are dropped.
+    pub async fn remove_webhook(&self, id: &str) -> Result<bool> {
+        if !self.webhooks.contains_key(id) {
+            return Ok(false);
+        }
+        if let Some(db) = self.database.clone() {
+            webhooks_db::delete_webhook(db.pool(), id).await?;
+        }
+        Ok(self.webhooks.remove(id).is_some())
+    }
+
+    pub fn get_webhook(&self, id: &str) -> Option<Webhook> {
+        self.webhooks.get(id).map(|entry| entry.value().clone())
+    }
+
+    /// Registered webhooks, oldest first.
+    pub fn list_webhooks(&self) -> Vec<Webhook> {
+        let mut webhooks: Vec<Webhook> = self.webhooks.iter().map(|e| e.value().clone()).collect();
+        webhooks.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        webhooks
+    }
+
+    /// Create an organization, returning it with its API token (which is not kept).
+    pub async fn create_organization(&self, name: &str) -> Result<(Organization, String)> {
+        let organization = Organization {
+            org_id: format!(
+                "ORG-{}",
+                uuid::Uuid::new_v4().to_string()[..8].to_uppercase()
+            ),
+            name: name.to_string(),
+            members: Vec::new(),
+            created_at: Utc::now(),
+        };
+        let token = organizations::generate_token();
+        let token_hash = organizations::token_hash(&token);
+        if let Some(db) = self.database.clone() {
+            organizations_db::insert_organization(db.pool(), &organization, &token_hash).await?;
+        }
+        self.organization_tokens
+            .insert(token_hash, organization.org_id.clone());
+        self.organizations
+            .insert(organization.org_id.clone(), organization.clone());
+        Ok((organization, token))
+    }
+
+    /// Delete an organization; its token stops working and its operators become unaffiliated.
+    pub async fn delete_organization(&self, org_id: &str) -> Result<bool> {
+        if !self.organizations.contains_key(org_id) {
+            return Ok(false);
+        }
+        if let Some(db) = self.database.clone() {
+            organizations_db::delete_organization(db.pool(), org_id).await?;
+        }
+        self.organization_tokens.retain(|_, id| id != org_id);
+        self.organization_members.retain(|_, id| id != org_id);
+        Ok(self.organizations.remove(org_id).is_some())
+    }
+
+    /// Make an operator a member of an organization, moving it out of any other. Returns the
+    /// updated organization, or `None` if it does not exist.
+    pub async fn add_organization_member(
+        &self,
+        org_id: &str,
+        owner_id: &str,
+    ) -> Result<Option<Organization>> {
+        if !self.organizations.contains_key(org_id) {
+            return Ok(None);
+        }
+        if let Some(db) = self.database.clone() {
+            organizations_db::upsert_member(db.pool(), org_id, owner_id).await?;
+        }
+        if let Some(previous) = self
+            .organization_members
+            .insert(owner_id.to_string(), org_id.to_string())
+        {
+            if let Some(mut organization) = self.organizations.get_mut(&previous) {
+                organization.members.retain(|member| member != owner_id);
+            }
+        }
+        Ok(self.organizations.get_mut(org_id).map(|mut organization| {
+            if !organization.members.iter().any(|member| member == owner_id) {
+                organization.members.push(owner_id.to_string());
+                organization.members.sort();
+            }
+            organization.clone()
+        }))
+    }
+
+    /// Remove an operator from an organization. Returns whether it was a member.
+    pub async fn remove_organization_member(&self, org_id: &str, owner_id: &str) -> Result<bool> {
+        if self.organization_of_owner(owner_id).as_deref() != Some(org_id) {
+            return Ok(false);
+        }
+        if let Some(db) = self.database.clone() {
+            organizations_db::delete_member(db.pool(), org_id, owner_id).await?;
+        }
+        self.organization_members.remove(owner_id);
+        if let Some(mut organization) = self.organizations.get_mut(org_id) {
+            organization.members.retain(|member| member != owner_id);
+        }
+        Ok(true)
+    }
+
+    /// Organizations, sorted by ID.
+    pub fn list_organizations(&self) -> Vec<Organization> {
+        let mut organizations: Vec<Organization> = self
+            .organizations
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        organizations.sort_by(|a, b| a.org_id.cmp(&b.org_id));
+        organizations
+    }
+
+    /// The organization an API token belongs to, if any.
+    pub fn organization_for_token(&self, token: &str) -> Option<String> {
+        self.organization_tokens
+            .get(&organizations::token_hash(token))
+            .map(|entry| entry.value().clone())
+    }
+
+    /// The organization an operator belongs to, if any.
+    pub fn organization_of_owner(&self, owner_id: &str) -> Option<String> {
+        self.organization_members
+            .get(owner_id)
+            .map(|entry| entry.value().clone())
+    }
+
+    /// Startup progress and shutdown drain state.
+    pub fn lifecycle(&self) -> &Lifecycle {
+        &self.lifecycle
+    }
+
+    /// Automatic-command budgets.
+    pub fn command_throttle(&self) -> &CommandThrottle {
+        &self.command_throttle
+    }
+
     /// Update the RID viewport used for DSS subscriptions.
     pub fn set_rid_view_bbox(&self, view: String) {
         if let Ok(mut guard) = self.rid_view_bbox.write() {
@@ -433,10 +1079,63 @@ impl AppState {
             .unwrap_or_default()
     }
 
-    fn seed_drone_counter(&self) {
+    /// Replace the forecast weather grid.
+    pub fn set_weather_cells(&self, cells: Vec<WeatherCell>) {
+        if let Ok(mut guard) = self.weather_cells.write() {
+            *guard = cells;
+        }
+    }
+
+    /// Forecast weather cells that have not yet expired.
+    pub fn weather_cells(&self) -> Vec<WeatherCell> {
+        let now = Utc::now();
+        self.weather_cells
+            .read()
+            .map(|guard| {
+                guard
+                    .iter()
+                    .filter(|cell| cell.valid_to > now)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Replace the C2 coverage areas, persisting them first.
+    pub async fn set_coverage_areas(&self, areas: Vec<CoverageArea>) -> Result<()> {
+        if let Some(db) = &self.database {
+            c2_coverage_db::replace_coverage_areas(db.pool(), &areas).await?;
+        }
+        if let Ok(mut guard) = self.coverage_areas.write() {
+            *guard = areas;
+        }
+        Ok(())
+    }
+
+    pub fn coverage_areas(&self) -> Vec<CoverageArea> {
+        self.coverage_areas
+            .read()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+
+    /// Publish a freshly built minimum safe altitude grid.
+    pub fn set_msa_grid(&self, grid: MsaGrid) {
+        if let Ok(mut guard) = self.msa_grid.write() {
+            *guard = Some(Arc::new(grid));
+        }
+    }
+
+    pub fn msa_grid(&self) -> Option<Arc<MsaGrid>> {
+        self.msa_grid.read().ok().and_then(|guard| guard.clone())
+    }
+
+    /// Start generated drone IDs past every stored one, archived drones included, so a new
+    /// registration never reuses a deregistered drone's ID and inherits its history.
+    fn seed_drone_counter<'a>(&self, drone_ids: impl IntoIterator<Item = &'a str>) {
         let mut max_id = 0u32;
-        for entry in self.drones.iter() {
-            if let Some(id) = Self::parse_drone_numeric_suffix(entry.key()) {
+        for drone_id in drone_ids {
+            if let Some(id) = Self::parse_drone_numeric_suffix(drone_id) {
                 max_id = max_id.max(id);
             }
         }
@@ -478,7 +1177,7 @@ impl AppState {
         &self,
         drone_id: &str,
         owner_id: Option<String>,
-        token: String,
+        token: DroneSessionToken,
     ) -> Result<RegisterDroneOutcome> {
         self.register_drone_internal(drone_id, owner_id, Some(token))
             .await
@@ -488,7 +1187,7 @@ impl AppState {
         &self,
         drone_id: &str,
         owner_id: Option<String>,
-        token: Option<String>,
+        token: Option<DroneSessionToken>,
     ) -> Result<RegisterDroneOutcome> {
         let owner_for_state = owner_id.clone().or_else(|| {
             self.drone_owners
@@ -513,6 +1212,8 @@ impl AppState {
                 velocity_z: 0.0,
                 last_update: now,
                 status: DroneStatus::Inactive,
+                scheduling_priority: None,
+                capabilities: None,
             });
 
         if state_for_db.owner_id.is_none() {
@@ -520,13 +1221,13 @@ impl AppState {
         }
 
         if let Some(db) = self.database.clone() {
-            if let Some(token_value) = token.as_deref() {
+            if let Some(token_value) = token.as_ref() {
                 let mut tx = db.pool().begin().await?;
                 sqlx::query(
                     r#"
                     INSERT INTO drones (drone_id, owner_id, lat, lon, altitude_m, heading_deg, speed_mps, velocity_x, velocity_y, velocity_z, status, last_update)
-                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
-                    ON CONFLICT(drone_id) DO NOTHING
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                    ON CONFLICT(drone_id) DO UPDATE SET archived_at = NULL
                     "#,
                 )
                 .bind(&state_for_db.drone_id)
@@ -546,13 +1247,15 @@ impl AppState {
 
                 let token_result = sqlx::query(
                     r#"
-                    INSERT INTO drone_tokens (drone_id, session_token, updated_at)
-                    VALUES (?1, ?2, CURRENT_TIMESTAMP)
+                    INSERT INTO drone_tokens (drone_id, session_token, expires_at, mac_secret, updated_at)
+                    VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP)
                     ON CONFLICT(drone_id) DO NOTHING
                     "#,
                 )
                 .bind(&state_for_db.drone_id)
-                .bind(token_value)
+                .bind(&token_value.token)
+                .bind(token_value.expires_at.map(|t| t.to_rfc3339()))
+                .bind(&token_value.mac_secret)
                 .execute(&mut *tx)
                 .await?;
 
@@ -565,8 +1268,8 @@ impl AppState {
                     sqlx::query(
                         r#"
                         UPDATE drones
-                        SET owner_id = COALESCE(owner_id, ?2)
-                        WHERE drone_id = ?1
+                        SET owner_id = COALESCE(owner_id, $2)
+                        WHERE drone_id = $1
                         "#,
                     )
                     .bind(&state_for_db.drone_id)
@@ -585,7 +1288,8 @@ impl AppState {
             self.drone_owners.insert(drone_id.to_string(), owner_id);
         }
 
-        self.drones
+        let registered = self
+            .drones
             .entry(drone_id.to_string())
             .and_modify(|state| {
                 if state.owner_id.is_none() {
@@ -593,6 +1297,9 @@ impl AppState {
                 }
             })
             .or_insert(state_for_db);
+        let (lat, lon) = (registered.lat, registered.lon);
+        drop(registered);
+        self.drone_index.upsert(drone_id, lat, lon);
 
         if let Some(token) = token {
             self.drone_tokens.insert(drone_id.to_string(), token);
@@ -601,64 +1308,305 @@ impl AppState {
         Ok(RegisterDroneOutcome::Registered)
     }
 
-    /// Store or update the session token for a drone.
-    #[allow(dead_code)]
-    pub async fn set_drone_token(&self, drone_id: &str, token: String) -> Result<()> {
+    /// Register or replace a drone's performance envelope.
+    pub async fn set_drone_performance(
+        &self,
+        drone_id: &str,
+        performance: DronePerformance,
+    ) -> Result<()> {
         if let Some(db) = self.database.clone() {
-            drone_tokens_db::upsert_drone_token(db.pool(), drone_id, &token).await?;
+            drone_performance_db::upsert_drone_performance(db.pool(), drone_id, &performance)
+                .await?;
         }
-        self.drone_tokens.insert(drone_id.to_string(), token);
+        self.drone_performance
+            .insert(drone_id.to_string(), performance);
         Ok(())
     }
 
-    /// Get the session token for a drone, if present.
-    pub fn drone_token(&self, drone_id: &str) -> Option<String> {
-        self.drone_tokens
+    /// Registered performance envelope for a drone, if any.
+    pub fn drone_performance(&self, drone_id: &str) -> Option<DronePerformance> {
+        self.drone_performance
             .get(drone_id)
-            .map(|entry| entry.value().clone())
+            .map(|entry| *entry.value())
     }
 
-    /// Check if a token matches the registered token for a drone.
-    pub fn validate_drone_token(&self, drone_id: &str, token: &str) -> bool {
-        self.drone_tokens
+    /// Record the capabilities a registered drone advertised.
+    pub async fn set_drone_capabilities(
+        &self,
+        drone_id: &str,
+        capabilities: DroneCapabilities,
+    ) -> Result<()> {
+        if let Some(db) = self.database.clone() {
+            drone_capabilities_db::upsert_drone_capabilities(db.pool(), drone_id, &capabilities)
+                .await?;
+        }
+        if let Some(mut drone) = self.drones.get_mut(drone_id) {
+            drone.capabilities = Some(capabilities);
+        }
+        Ok(())
+    }
+
+    /// Whether a drone accepts HOLD commands and can hold position in `wind_mps`.
+    pub fn drone_can_hold(&self, drone_id: &str, wind_mps: f64) -> bool {
+        let accepts_hold = self
+            .drones
             .get(drone_id)
-            .map(|entry| entry.value() == token)
-            .unwrap_or(false)
+            .and_then(|drone| drone.capabilities)
+            .is_none_or(|capabilities| capabilities.supports_hold);
+        accepts_hold
+            && self
+                .drone_performance(drone_id)
+                .is_none_or(|performance| performance.can_hold(wind_mps))
     }
 
-    /// Resolve a drone ID from a session token (if any).
-    pub fn drone_id_for_token(&self, token: &str) -> Option<String> {
-        for entry in self.drone_tokens.iter() {
-            if entry.value() == token {
-                return Some(entry.key().clone());
-            }
+    /// Register or replace a drone's home point and tether.
+    pub async fn set_drone_home(&self, drone_id: &str, home: DroneHome) -> Result<()> {
+        if let Some(db) = self.database.clone() {
+            drone_homes_db::upsert_drone_home(db.pool(), drone_id, &home).await?;
         }
-        None
+        self.drone_homes.insert(drone_id.to_string(), home);
+        Ok(())
     }
 
-    /// Update drone state from telemetry.
-    pub async fn update_telemetry(&self, telemetry: Telemetry) {
-        let drone_id = telemetry.drone_id.clone();
-        let mut telemetry = telemetry;
-        let hold_active = self.has_active_hold_command(&drone_id);
-        telemetry.altitude_m = altitude_to_amsl(
-            telemetry.altitude_m,
-            self.config.altitude_reference,
-            self.config.geoid_offset_m,
-        );
+    /// Registered home point for a drone, if any.
+    pub fn drone_home(&self, drone_id: &str) -> Option<DroneHome> {
+        self.drone_homes.get(drone_id).map(|entry| *entry.value())
+    }
 
-        // Treat owner_id as control-plane identity (set at registration / DB load), not telemetry
-        // data. Telemetry is not allowed to change ownership.
-        let cached_owner = self
-            .drone_owners
-            .get(&drone_id)
-            .map(|owner_id| owner_id.value().clone());
-        let state_owner = self
-            .drones
-            .get(&drone_id)
-            .and_then(|entry| entry.value().owner_id.clone());
+    /// All registered home points, by drone.
+    pub fn drone_homes(&self) -> Vec<(String, DroneHome)> {
+        self.drone_homes
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect()
+    }
 
-        telemetry.owner_id = cached_owner.or(state_owner);
+    /// Expiry for a token issued at `now`, per the configured TTL.
+    pub fn drone_token_expiry(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self.config.drone_token_ttl_secs {
+            0 => None,
+            ttl => Some(now + ChronoDuration::seconds(ttl as i64)),
+        }
+    }
+
+    /// Store or update the session token for a drone, invalidating any previous token.
+    pub async fn set_drone_token(
+        &self,
+        drone_id: &str,
+        token: String,
+        mac_secret: String,
+    ) -> Result<DroneSessionToken> {
+        let session =
+            DroneSessionToken::new(token, mac_secret, self.drone_token_expiry(Utc::now()));
+        if let Some(db) = self.database.clone() {
+            drone_tokens_db::upsert_drone_token(db.pool(), drone_id, &session).await?;
+        }
+        self.drone_tokens
+            .insert(drone_id.to_string(), session.clone());
+        Ok(session)
+    }
+
+    /// Replace a drone's token at the drone's request.
+    ///
+    /// The old token keeps working for the configured overlap so requests already in flight
+    /// with it are not rejected.
+    pub async fn rotate_drone_token(
+        &self,
+        drone_id: &str,
+        token: String,
+        mac_secret: String,
+    ) -> Result<DroneSessionToken> {
+        let now = Utc::now();
+        let overlap = ChronoDuration::seconds(self.config.drone_token_rotation_overlap_secs as i64);
+        let previous = self
+            .drone_tokens
+            .get(drone_id)
+            .map(|entry| entry.value().clone());
+        let session = DroneSessionToken {
+            token,
+            expires_at: self.drone_token_expiry(now),
+            mac_secret: Some(mac_secret),
+            previous_expires_at: previous.as_ref().map(|_| now + overlap),
+            previous_token: previous.as_ref().map(|session| session.token.clone()),
+            previous_mac_secret: previous.and_then(|session| session.mac_secret),
+        };
+        if let Some(db) = self.database.clone() {
+            drone_tokens_db::upsert_drone_token(db.pool(), drone_id, &session).await?;
+        }
+        self.drone_tokens
+            .insert(drone_id.to_string(), session.clone());
+        Ok(session)
+    }
+
+    /// Revoke a drone's session token (current and previous).
+    pub async fn revoke_drone_token(&self, drone_id: &str) -> Result<bool> {
+        if let Some(db) = self.database.clone() {
+            drone_tokens_db::delete_drone_token(db.pool(), drone_id).await?;
+        }
+        self.telemetry_guard.forget(drone_id);
+        self.usage.forget_drone(drone_id);
+        Ok(self.drone_tokens.remove(drone_id).is_some())
+    }
+
+    /// Telemetry MAC secret issued with `token`, if `token` is the drone's current or previous
+    /// session token.
+    pub fn telemetry_mac_secret(&self, drone_id: &str, token: &str) -> Option<String> {
+        self.drone_tokens
+            .get(drone_id)
+            .and_then(|entry| entry.value().mac_secret_for(token).map(str::to_string))
+    }
+
+    /// Get the current session token for a drone, if present.
+    pub fn drone_token(&self, drone_id: &str) -> Option<String> {
+        self.drone_tokens
+            .get(drone_id)
+            .map(|entry| entry.value().token.clone())
+    }
+
+    /// Airborne drones get the grace period on expired tokens.
+    fn drone_in_flight(&self, drone_id: &str) -> bool {
+        self.drones
+            .get(drone_id)
+            .map(|entry| matches!(entry.status, DroneStatus::Active | DroneStatus::Holding))
+            .unwrap_or(false)
+    }
+
+    fn token_deadline(&self, drone_id: &str, expires_at: DateTime<Utc>) -> DateTime<Utc> {
+        if self.drone_in_flight(drone_id) {
+            expires_at + ChronoDuration::seconds(self.config.drone_token_grace_secs as i64)
+        } else {
+            expires_at
+        }
+    }
+
+    fn token_matches(
+        &self,
+        drone_id: &str,
+        session: &DroneSessionToken,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if session.token == token {
+            return session
+                .expires_at
+                .map(|expires_at| now < self.token_deadline(drone_id, expires_at))
+                .unwrap_or(true);
+        }
+        match (&session.previous_token, session.previous_expires_at) {
+            (Some(previous), Some(until)) => previous == token && now < until,
+            _ => false,
+        }
+    }
+
+    /// Check if a token is currently valid for a drone.
+    pub fn validate_drone_token(&self, drone_id: &str, token: &str) -> bool {
+        let now = Utc::now();
+        self.drone_tokens
+            .get(drone_id)
+            .map(|entry| self.token_matches(drone_id, entry.value(), token, now))
+            .unwrap_or(false)
+    }
+
+    /// Resolve a drone ID from a valid session token (if any).
+    pub fn drone_id_for_token(&self, token: &str) -> Option<String> {
+        let now = Utc::now();
+        for entry in self.drone_tokens.iter() {
+            if self.token_matches(entry.key(), entry.value(), token, now) {
+                return Some(entry.key().clone());
+            }
+        }
+        None
+    }
+
+    /// Drop expired tokens and finished rotation overlaps. Returns drones whose token expired.
+    pub async fn expire_drone_tokens(&self, now: DateTime<Utc>) -> Vec<String> {
+        let mut expired = Vec::new();
+        let mut expired_tokens = Vec::new();
+        let mut trimmed = Vec::new();
+        for entry in self.drone_tokens.iter() {
+            let session = entry.value();
+            if let Some(expires_at) = session.expires_at {
+                if now >= self.token_deadline(entry.key(), expires_at) {
+                    expired.push(entry.key().clone());
+                    continue;
+                }
+            }
+            if session
+                .previous_expires_at
+                .is_some_and(|until| now >= until)
+            {
+                trimmed.push(entry.key().clone());
+            }
+        }
+
+        // The scan above holds no lock, so a drone may have rotated or re-registered since;
+        // re-check the deadline while holding the entry and only drop the token that expired.
+        expired.retain(|drone_id| {
+            let Some((_, session)) = self.drone_tokens.remove_if(drone_id, |drone_id, session| {
+                session
+                    .expires_at
+                    .is_some_and(|expires_at| now >= self.token_deadline(drone_id, expires_at))
+            }) else {
+                return false;
+            };
+            self.telemetry_guard.forget(drone_id);
+            self.usage.forget_drone(drone_id);
+            expired_tokens.push((drone_id.clone(), session.token));
+            true
+        });
+        if let Some(db) = self.database.as_ref() {
+            for (drone_id, token) in &expired_tokens {
+                if let Err(err) =
+                    drone_tokens_db::delete_drone_token_if_current(db.pool(), drone_id, token).await
+                {
+                    tracing::warn!("Failed to expire session token for {}: {}", drone_id, err);
+                }
+            }
+        }
+        for drone_id in trimmed {
+            let session = self.drone_tokens.get_mut(&drone_id).and_then(|mut entry| {
+                if entry.previous_expires_at.is_none_or(|until| now < until) {
+                    return None;
+                }
+                entry.previous_token = None;
+                entry.previous_expires_at = None;
+                entry.previous_mac_secret = None;
+                Some(entry.clone())
+            });
+            if let (Some(session), Some(db)) = (session, self.database.as_ref()) {
+                if let Err(err) =
+                    drone_tokens_db::upsert_drone_token(db.pool(), &drone_id, &session).await
+                {
+                    tracing::warn!("Failed to persist token cleanup for {}: {}", drone_id, err);
+                }
+            }
+        }
+        expired
+    }
+
+    /// Update drone state from telemetry.
+    pub async fn update_telemetry(&self, telemetry: Telemetry) {
+        let drone_id = telemetry.drone_id.clone();
+        let mut telemetry = telemetry;
+        let hold_active = self.has_active_hold_command(&drone_id);
+        telemetry.altitude_m = altitude_to_amsl(
+            telemetry.altitude_m,
+            self.config.altitude_reference,
+            self.config.geoid_offset_m,
+        );
+
+        // Treat owner_id as control-plane identity (set at registration / DB load), not telemetry
+        // data. Telemetry is not allowed to change ownership.
+        let cached_owner = self
+            .drone_owners
+            .get(&drone_id)
+            .map(|owner_id| owner_id.value().clone());
+        let state_owner = self
+            .drones
+            .get(&drone_id)
+            .and_then(|entry| entry.value().owner_id.clone());
+
+        telemetry.owner_id = cached_owner.or(state_owner);
 
         if let Some(owner_id) = telemetry.owner_id.clone() {
             self.drone_owners.insert(drone_id.clone(), owner_id);
@@ -666,6 +1614,9 @@ impl AppState {
         let mut updated_state = None;
 
         self.external_traffic.remove(&drone_id);
+        self.traffic_index.remove(&drone_id);
+        self.traffic_history.remove(&drone_id);
+        self.traffic_estimators.remove(&drone_id);
 
         // Update or create drone state
         self.drones
@@ -686,6 +1637,17 @@ impl AppState {
                 state
             });
 
+        if let Some(state) = updated_state.as_ref() {
+            self.drone_index
+                .upsert(&state.drone_id, state.lat, state.lon);
+            self.usage.record_flight_sample(
+                state.owner_id.as_deref(),
+                &drone_id,
+                state.status != DroneStatus::Inactive,
+                telemetry.timestamp,
+            );
+        }
+
         // Broadcast update via WebSocket
         if let Some(state) = updated_state {
             if let Ok(payload) = serde_json::to_string(&state) {
@@ -720,6 +1682,16 @@ impl AppState {
         self.drones.iter().map(|r| r.value().clone()).collect()
     }
 
+    /// Drones whose last position is inside `bounds`.
+    pub fn drones_in_bounds(&self, bounds: &MsaBounds) -> Vec<DroneState> {
+        self.drone_index
+            .candidates(bounds)
+            .into_iter()
+            .filter_map(|drone_id| self.drones.get(&drone_id).map(|r| r.value().clone()))
+            .filter(|drone| bounds.contains(drone.lat, drone.lon))
+            .collect()
+    }
+
     /// Upsert external traffic and feed it into conflict detection.
     pub async fn upsert_external_traffic(&self, traffic: ExternalTraffic) {
         let mut traffic = traffic;
@@ -740,24 +1712,155 @@ impl AppState {
             self.config.altitude_reference,
             self.config.geoid_offset_m,
         );
+        self.traffic_history
+            .entry(traffic_id.clone())
+            .or_default()
+            .observe(
+                traffic.lat,
+                traffic.lon,
+                traffic.last_update.timestamp_millis() as f64 / 1000.0,
+                &self.config.traffic_quality,
+            );
+        let mut vertical_speed_mps = 0.0;
+        if let Some(smoothing) = self.config.traffic_smoothing.as_ref() {
+            let report = TrackFix {
+                lat: traffic.lat,
+                lon: traffic.lon,
+                altitude_m: traffic.altitude_m,
+                heading_deg: traffic.heading_deg,
+                speed_mps: traffic.speed_mps,
+                vertical_speed_mps: 0.0,
+                timestamp_s: traffic.last_update.timestamp_millis() as f64 / 1000.0,
+            };
+            let Some(fix) = self
+                .traffic_estimators
+                .entry(traffic_id.clone())
+                .or_default()
+                .observe(report, smoothing)
+            else {
+                return;
+            };
+            traffic.lat = fix.lat;
+            traffic.lon = fix.lon;
+            traffic.altitude_m = fix.altitude_m;
+            traffic.heading_deg = fix.heading_deg;
+            traffic.speed_mps = fix.speed_mps;
+            vertical_speed_mps = fix.vertical_speed_mps;
+        }
+        traffic.quality = None;
+        traffic.extrapolated_s = 0.0;
+        self.traffic_index
+            .upsert(&traffic_id, traffic.lat, traffic.lon);
         self.external_traffic
             .insert(traffic_id.clone(), traffic.clone());
 
         self.queue_detector_update(DetectorUpdate::Upsert(
             DronePosition::new(&traffic_id, traffic.lat, traffic.lon, traffic.altitude_m)
-                .with_velocity(traffic.heading_deg, traffic.speed_mps, 0.0),
+                .with_velocity(traffic.heading_deg, traffic.speed_mps, vertical_speed_mps)
+                .with_category(traffic.category),
         ))
         .await;
     }
 
+    /// Move every smoothed external track to where its filter expects it now, so conflicts
+    /// against slow-reporting tracks do not wait for the next report.
+    pub async fn extrapolate_external_traffic(&self) {
+        let Some(smoothing) = self.config.traffic_smoothing.as_ref() else {
+            return;
+        };
+        let now_s = Utc::now().timestamp_millis() as f64 / 1000.0;
+        let traffic_ids: Vec<String> = self
+            .traffic_estimators
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect();
+        let mut moved = Vec::new();
+        for traffic_id in traffic_ids {
+            // Predict under the track's lock so a report landing meanwhile is not overwritten
+            // by an extrapolation of the previous one.
+            let Some(mut traffic) = self.external_traffic.get_mut(&traffic_id) else {
+                continue;
+            };
+            let Some(fix) = self
+                .traffic_estimators
+                .get(&traffic_id)
+                .and_then(|estimator| estimator.predict(now_s, smoothing))
+            else {
+                continue;
+            };
+            let reported_s = traffic.last_update.timestamp_millis() as f64 / 1000.0;
+            let extrapolated_s = fix.timestamp_s - reported_s;
+            if extrapolated_s <= traffic.extrapolated_s {
+                continue;
+            }
+            traffic.lat = fix.lat;
+            traffic.lon = fix.lon;
+            traffic.altitude_m = fix.altitude_m;
+            traffic.extrapolated_s = extrapolated_s;
+            moved.push(
+                DronePosition::new(&traffic_id, fix.lat, fix.lon, fix.altitude_m)
+                    .with_velocity(fix.heading_deg, fix.speed_mps, fix.vertical_speed_mps)
+                    .with_category(traffic.category),
+            );
+        }
+        for position in moved {
+            self.traffic_index
+                .upsert(&position.drone_id, position.lat, position.lon);
+            self.queue_detector_update(DetectorUpdate::Upsert(position))
+                .await;
+        }
+    }
+
     /// Get all external traffic tracks.
     pub fn get_external_traffic(&self) -> Vec<ExternalTraffic> {
         self.external_traffic
             .iter()
-            .map(|r| r.value().clone())
+            .map(|r| self.with_track_quality(r.value().clone()))
+            .collect()
+    }
+
+    /// External tracks whose last position is inside `bounds`.
+    pub fn external_traffic_in_bounds(&self, bounds: &MsaBounds) -> Vec<ExternalTraffic> {
+        self.traffic_index
+            .candidates(bounds)
+            .into_iter()
+            .filter_map(|traffic_id| {
+                self.external_traffic
+                    .get(&traffic_id)
+                    .map(|r| self.with_track_quality(r.value().clone()))
+            })
+            .filter(|traffic| bounds.contains(traffic.lat, traffic.lon))
             .collect()
     }
 
+    /// External surveillance track of a registered drone: its Remote ID track (`RID-<id>`) or a
+    /// track under the drone's own ID.
+    pub fn surveillance_track(&self, drone_id: &str) -> Option<ExternalTraffic> {
+        self.external_traffic
+            .get(&format!("RID-{}", drone_id))
+            .or_else(|| self.external_traffic.get(drone_id))
+            .map(|entry| self.with_track_quality(entry.value().clone()))
+    }
+
+    /// Current quality of an external track; `None` for unknown tracks.
+    pub fn external_track_quality(&self, traffic_id: &str) -> Option<TrackQuality> {
+        let now_s = Utc::now().timestamp_millis() as f64 / 1000.0;
+        let mut quality = self
+            .traffic_history
+            .get(traffic_id)
+            .map(|history| history.quality(now_s, &self.config.traffic_quality))?;
+        quality.residual_m = self
+            .traffic_estimators
+            .get(traffic_id)
+            .and_then(|estimator| estimator.residual_m());
+        Some(quality)
+    }
+
+    fn with_track_quality(&self, mut traffic: ExternalTraffic) -> ExternalTraffic {
+        traffic.quality = self.external_track_quality(&traffic.traffic_id);
+        traffic
+    }
+
     /// Remove stale external tracks and purge them from the conflict detector.
     pub async fn purge_external_traffic(&self, max_age_secs: i64) -> Vec<String> {
         let now = Utc::now();
@@ -776,6 +1879,9 @@ impl AppState {
 
         for id in &stale_ids {
             self.external_traffic.remove(id);
+            self.traffic_index.remove(id);
+            self.traffic_history.remove(id);
+            self.traffic_estimators.remove(id);
             self.queue_detector_update(DetectorUpdate::Remove(id.clone()))
                 .await;
         }
@@ -783,14 +1889,238 @@ impl AppState {
         stale_ids
     }
 
+    /// Fresh detector with the live rules, detection mode and separation volumes, for
+    /// simulations that must not touch live conflict state.
+    pub fn sandbox_detector(&self) -> ConflictDetector {
+        let rules = self.rules();
+        let mut detector = rules.conflict_detector();
+        if !rules.volume_rules.is_empty() {
+            let volumes = self.separation_volumes(&detector);
+            detector.set_volumes(volumes);
+        }
+        detector
+    }
+
+    /// Resolve volume rules against current geofences; rules for unknown geofences are skipped.
+    fn separation_volumes(&self, detector: &ConflictDetector) -> Vec<SeparationVolume> {
+        let defaults = detector.default_thresholds();
+        self.rules()
+            .volume_rules
+            .iter()
+            .filter_map(|rule| {
+                let volume = self.get_geofence(&rule.geofence_id)?;
+                Some(SeparationVolume {
+                    volume,
+                    thresholds: defaults.with_rule(rule),
+                })
+            })
+            .collect()
+    }
+
+    /// Suppress or downgrade conflicts that both drones' active flight plans already resolve.
+    fn filter_conflicts_by_intent(
+        &self,
+        conflicts: Vec<Conflict>,
+        detector: &ConflictDetector,
+    ) -> Vec<Conflict> {
+        let mode = self.config.conflict_intent_filter;
+        if mode == IntentFilterMode::Off || conflicts.is_empty() {
+            return conflicts;
+        }
+        let plans: HashMap<String, FlightPlan> = self
+            .flight_plans
+            .iter()
+            .filter(|entry| entry.value().status == FlightStatus::Active)
+            .map(|entry| (entry.value().drone_id.clone(), entry.value().clone()))
+            .collect();
+        if plans.len() < 2 {
+            return conflicts;
+        }
+        let positions: HashMap<&str, &DronePosition> = detector
+            .get_all_positions()
+            .into_iter()
+            .map(|position| (position.drone_id.as_str(), position))
+            .collect();
+        let planned = |drone_id: &str| {
+            Some(PlannedDrone {
+                position: positions.get(drone_id).copied()?,
+                plan: plans.get(drone_id)?,
+            })
+        };
+
+        conflicts
+            .into_iter()
+            .filter_map(|conflict| {
+                let (Some(drone1), Some(drone2)) =
+                    (planned(&conflict.drone1_id), planned(&conflict.drone2_id))
+                else {
+                    return Some(conflict);
+                };
+                let thresholds = detector
+                    .thresholds_at(drone1.position)
+                    .most_conservative(detector.thresholds_at(drone2.position));
+                if !plans_resolve_conflict(
+                    drone1,
+                    drone2,
+                    conflict.timestamp,
+                    &thresholds,
+                    self.config.conflict_intent_conformance_m,
+                ) {
+                    return Some(conflict);
+                }
+                tracing::debug!(
+                    "Conflict {} <-> {} is resolved by their flight plans ({:?})",
+                    conflict.drone1_id,
+                    conflict.drone2_id,
+                    mode
+                );
+                apply_intent_filter(conflict, mode)
+            })
+            .collect()
+    }
+
+    /// Weight or ignore conflicts involving external tracks below the quality threshold.
+    fn filter_conflicts_by_track_quality(&self, conflicts: Vec<Conflict>) -> Vec<Conflict> {
+        let mode = self.config.traffic_quality_mode;
+        if mode == TrackQualityMode::Off || conflicts.is_empty() || self.traffic_history.is_empty()
+        {
+            return conflicts;
+        }
+        let low_quality = |id: &str| {
+            self.external_track_quality(id)
+                .is_some_and(|quality| quality.score < self.config.traffic_min_quality)
+        };
+        conflicts
+            .into_iter()
+            .filter_map(|conflict| {
+                if !low_quality(&conflict.drone1_id) && !low_quality(&conflict.drone2_id) {
+                    return Some(conflict);
+                }
+                tracing::debug!(
+                    "Conflict {} <-> {} involves a low-quality track ({:?})",
+                    conflict.drone1_id,
+                    conflict.drone2_id,
+                    mode
+                );
+                apply_track_quality_filter(conflict, mode)
+            })
+            .collect()
+    }
+
     fn update_conflicts_from_detector(&self, detector: &mut ConflictDetector) {
-        let new_conflicts = detector.detect_conflicts();
+        if !self.rules().volume_rules.is_empty() {
+            let volumes = self.separation_volumes(detector);
+            detector.set_volumes(volumes);
+        }
+        let new_conflicts = self.filter_conflicts_by_track_quality(
+            self.filter_conflicts_by_intent(detector.detect_conflicts(), detector),
+        );
+        let now = Utc::now();
 
+        let previous: Vec<String> = self.conflicts.iter().map(|r| r.key().clone()).collect();
         self.conflicts.clear();
-        for conflict in new_conflicts {
+        for mut conflict in new_conflicts {
             let key = format!("{}-{}", conflict.drone1_id, conflict.drone2_id);
+            let sector = self.sector_at(conflict.cpa_lat, conflict.cpa_lon);
+            let mut detected = false;
+            self.conflict_tracks
+                .entry(key.clone())
+                .and_modify(|track| {
+                    if conflict.severity.rank() > track.peak_severity.rank() {
+                        track.peak_severity = conflict.severity;
+                    }
+                    track.min_separation_m = track.min_separation_m.min(conflict.distance_m);
+                })
+                .or_insert_with(|| {
+                    detected = true;
+                    ConflictTrack {
+                        drone1_id: conflict.drone1_id.clone(),
+                        drone2_id: conflict.drone2_id.clone(),
+                        started_at: now,
+                        peak_severity: conflict.severity,
+                        min_separation_m: conflict.distance_m,
+                        sector_id: sector.map(|sector| sector.id.clone()),
+                    }
+                });
+            if let Some(sector) = sector {
+                conflict.sector_id = Some(sector.id.clone());
+                self.route_to_dispatcher(DispatchItemKind::Conflict, &key, sector, || {
+                    format!(
+                        "{:?} conflict {} <-> {} ({:.0}m, closest approach in {:.0}s)",
+                        conflict.severity,
+                        conflict.drone1_id,
+                        conflict.drone2_id,
+                        conflict.distance_m,
+                        conflict.time_to_closest
+                    )
+                });
+            }
+            if detected {
+                webhooks::publish(
+                    self,
+                    WebhookEventType::ConflictDetected,
+                    &[conflict.drone1_id.as_str(), conflict.drone2_id.as_str()],
+                    &conflict,
+                );
+            }
+            let _ = self.conflict_tx.send(conflict.clone());
             self.conflicts.insert(key, conflict);
         }
+        let tracked: HashSet<&str> = detector
+            .get_all_positions()
+            .into_iter()
+            .map(|position| position.drone_id.as_str())
+            .collect();
+        let mut ended = Vec::new();
+        for key in previous {
+            if self.conflicts.contains_key(&key) {
+                continue;
+            }
+            self.forget_dispatch_item(DispatchItemKind::Conflict, &key);
+            let Some((_, track)) = self.conflict_tracks.remove(&key) else {
+                continue;
+            };
+            let outcome = if tracked.contains(track.drone1_id.as_str())
+                && tracked.contains(track.drone2_id.as_str())
+            {
+                ConflictOutcome::Resolved
+            } else {
+                ConflictOutcome::Expired
+            };
+            let record = ConflictRecord {
+                conflict_id: track.conflict_id(&key),
+                drone1_id: track.drone1_id,
+                drone2_id: track.drone2_id,
+                peak_severity: track.peak_severity,
+                started_at: track.started_at,
+                ended_at: now,
+                min_separation_m: track.min_separation_m,
+                sector_id: track.sector_id,
+                outcome,
+            };
+            webhooks::publish(
+                self,
+                WebhookEventType::ConflictResolved,
+                &[record.drone1_id.as_str(), record.drone2_id.as_str()],
+                &record,
+            );
+            ended.push(record);
+        }
+        if !ended.is_empty() {
+            if let Ok(mut pending) = self.ended_conflicts.lock() {
+                pending.extend(ended);
+            }
+        }
+
+        let geofences: Vec<Geofence> = self
+            .get_geofences()
+            .into_iter()
+            .filter(|geofence| geofence.in_force_at(now))
+            .collect();
+        let breaches = detector.detect_geofence_breaches(&geofences);
+        if let Ok(mut guard) = self.geofence_breaches.write() {
+            *guard = breaches;
+        }
     }
 
     /// Recompute conflicts from the latest detector state.
@@ -808,7 +2138,8 @@ impl AppState {
             std::mem::take(&mut *guard)
         };
 
-        if updates.is_empty() && overflow_updates.is_empty() {
+        let rules_changed = self.rules_changed.swap(false, Ordering::Relaxed);
+        if updates.is_empty() && overflow_updates.is_empty() && !rules_changed {
             return;
         }
 
@@ -838,6 +2169,31 @@ impl AppState {
         if let Err(err) = refresh_task.await {
             tracing::warn!("Conflict detector refresh task failed: {}", err);
         }
+        self.persist_ended_conflicts().await;
+    }
+
+    /// Write ended conflicts to the conflict history; failed writes are retried next refresh.
+    async fn persist_ended_conflicts(&self) {
+        let records = match self.ended_conflicts.lock() {
+            Ok(mut pending) => std::mem::take(&mut *pending),
+            Err(_) => return,
+        };
+        if records.is_empty() {
+            return;
+        }
+        let Some(db) = self.database.clone() else {
+            return;
+        };
+        if let Err(err) = conflicts_db::insert_conflicts(db.pool(), &records).await {
+            tracing::warn!(
+                "Failed to persist {} ended conflicts: {}",
+                records.len(),
+                err
+            );
+            if let Ok(mut pending) = self.ended_conflicts.lock() {
+                pending.extend(records);
+            }
+        }
     }
 
     /// Get a single drone state by ID.
@@ -851,7 +2207,7 @@ impl AppState {
         use atc_core::models::DroneStatus;
         use chrono::Utc;
 
-        let timeout_secs = self.rules.drone_timeout_secs as i64;
+        let timeout_secs = self.rules().drone_timeout_secs as i64;
         let now = Utc::now();
         let mut lost_drones = Vec::new();
 
@@ -867,23 +2223,114 @@ impl AppState {
             let elapsed = (now - drone.last_update).num_seconds();
             if elapsed > timeout_secs {
                 drone.status = DroneStatus::Lost;
-                lost_drones.push(drone.drone_id.clone());
+                lost_drones.push(drone.clone());
             }
         }
 
-        for drone_id in &lost_drones {
-            self.queue_detector_update(DetectorUpdate::Remove(drone_id.clone()))
-                .await;
+        // Published after the loop: looking up owners while holding a shard would deadlock.
+        for drone in &lost_drones {
+            webhooks::publish(
+                self,
+                WebhookEventType::DroneLost,
+                &[drone.drone_id.as_str()],
+                drone,
+            );
+        }
+        let lost_drones: Vec<String> = lost_drones
+            .into_iter()
+            .map(|drone| drone.drone_id)
+            .collect();
+        // Dead-reckoned drones stay in the detector until their horizon runs out.
+        if self.config.lost_dead_reckoning.mode == DeadReckoningMode::Off {
+            for drone_id in &lost_drones {
+                self.queue_detector_update(DetectorUpdate::Remove(drone_id.clone()))
+                    .await;
+            }
         }
 
         lost_drones
     }
 
+    /// Move lost drones in the detector to where dead-reckoning puts them, with separation
+    /// widened by their position uncertainty, and drop those silent past the horizon.
+    pub async fn dead_reckon_lost_drones(&self) {
+        use atc_core::models::DroneStatus;
+
+        let config = self.config.lost_dead_reckoning;
+        if config.mode == DeadReckoningMode::Off {
+            return;
+        }
+        let now_s = Utc::now().timestamp_millis() as f64 / 1000.0;
+        let lost: Vec<DroneState> = self
+            .drones
+            .iter()
+            .filter(|entry| entry.value().status == DroneStatus::Lost)
+            .map(|entry| entry.value().clone())
+            .collect();
+        let mut updates = Vec::with_capacity(lost.len());
+        {
+            let Ok(mut dropped) = self.dead_reckoning_dropped.lock() else {
+                return;
+            };
+            dropped.retain(|drone_id| lost.iter().any(|drone| &drone.drone_id == drone_id));
+            for drone in &lost {
+                let mut last =
+                    DronePosition::new(&drone.drone_id, drone.lat, drone.lon, drone.altitude_m)
+                        .with_velocity(drone.heading_deg, drone.speed_mps, drone.velocity_z);
+                last.timestamp = drone.last_update.timestamp_millis() as f64 / 1000.0;
+                let plan = self
+                    .flight_plans
+                    .iter()
+                    .find(|entry| {
+                        entry.value().drone_id == drone.drone_id
+                            && entry.value().status == FlightStatus::Active
+                    })
+                    .map(|entry| entry.value().clone());
+                match dead_reckon(&last, plan.as_ref(), now_s, &config) {
+                    Some(position) => {
+                        dropped.remove(&drone.drone_id);
+                        updates.push(DetectorUpdate::Upsert(position));
+                    }
+                    None => {
+                        if dropped.insert(drone.drone_id.clone()) {
+                            updates.push(DetectorUpdate::Remove(drone.drone_id.clone()));
+                        }
+                    }
+                }
+            }
+        }
+        for update in updates {
+            self.queue_detector_update(update).await;
+        }
+    }
+
     /// Get current conflicts.
     pub fn get_conflicts(&self) -> Vec<Conflict> {
         self.conflicts.iter().map(|r| r.value().clone()).collect()
     }
 
+    /// ID the active conflict between two drones will have in the conflict history.
+    pub fn conflict_history_id(&self, drone_a: &str, drone_b: &str) -> Option<String> {
+        [
+            format!("{}-{}", drone_a, drone_b),
+            format!("{}-{}", drone_b, drone_a),
+        ]
+        .into_iter()
+        .find_map(|key| {
+            self.conflict_tracks
+                .get(&key)
+                .map(|track| track.conflict_id(&key))
+        })
+    }
+
+    /// Get current geofence breach predictions, soonest first.
+    pub fn get_geofence_breaches(&self) -> Vec<GeofenceBreach> {
+        self.geofence_breaches
+            .read()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+
     // ========== CONFORMANCE METHODS ==========
 
     /// Store the latest conformance status for a drone.
@@ -896,10 +2343,101 @@ impl AppState {
         self.conformance.iter().map(|r| r.value().clone()).collect()
     }
 
+    /// Latest conformance status for one drone.
+    pub fn get_conformance_status(&self, drone_id: &str) -> Option<ConformanceStatus> {
+        self.conformance.get(drone_id).map(|r| r.value().clone())
+    }
+
+    /// The conformance escalation policy in force.
+    pub fn conformance_policy(&self) -> ConformancePolicy {
+        self.conformance_policy
+            .read()
+            .map(|policy| policy.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replace the conformance escalation policy, persisting it first so a restart keeps it.
+    pub async fn set_conformance_policy(&self, policy: ConformancePolicy) -> Result<()> {
+        if let Some(db) = self.database.clone() {
+            conformance_policy_db::save_policy(db.pool(), &policy).await?;
+        }
+        if let Ok(mut guard) = self.conformance_policy.write() {
+            *guard = policy;
+        }
+        Ok(())
+    }
+
     // ========== DAA METHODS ==========
 
+    /// Append a breach response to the audit log, dropping the oldest entries past capacity.
+    pub fn record_breach_event(&self, event: BreachEvent) {
+        tracing::warn!(
+            "Geofence breach audit: drone={} geofence={} ({:?}) kind={:?} response={:?} source={} outcome={} command={}",
+            event.drone_id,
+            event.geofence_id,
+            event.geofence_type,
+            event.kind,
+            event.response,
+            event.source,
+            event.outcome,
+            event.command_id.as_deref().unwrap_or("-")
+        );
+        if let Ok(mut log) = self.breach_log.lock() {
+            log.push_back(event);
+            while log.len() > BREACH_LOG_CAPACITY {
+                log.pop_front();
+            }
+        }
+    }
+
+    /// Breach audit log, oldest first.
+    pub fn breach_events(&self) -> Vec<BreachEvent> {
+        self.breach_log
+            .lock()
+            .map(|log| log.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Keep a plan's rejection explanation, replacing any earlier one for the same flight ID and
+    /// dropping the oldest past capacity.
+    pub fn record_plan_rejection(&self, rejection: PlanRejection) {
+        if let Ok(mut log) = self.plan_rejections.lock() {
+            log.retain(|entry| entry.flight_id != rejection.flight_id);
+            log.push_back(rejection);
+            while log.len() > REJECTION_LOG_CAPACITY {
+                log.pop_front();
+            }
+        }
+    }
+
+    /// Latest rejection explanation for a flight ID.
+    pub fn plan_rejection(&self, flight_id: &str) -> Option<PlanRejection> {
+        self.plan_rejections.lock().ok().and_then(|log| {
+            log.iter()
+                .find(|entry| entry.flight_id == flight_id)
+                .cloned()
+        })
+    }
+
     /// Store or update a DAA advisory.
-    pub fn set_daa_advisory(&self, advisory: DaaAdvisory) {
+    pub fn set_daa_advisory(&self, mut advisory: DaaAdvisory) {
+        if advisory.sector_id.is_none() {
+            let position = self
+                .drones
+                .get(&advisory.drone_id)
+                .map(|drone| (drone.lat, drone.lon));
+            if let Some(sector) = position.and_then(|(lat, lon)| self.sector_at(lat, lon)) {
+                advisory.sector_id = Some(sector.id.clone());
+                if !advisory.resolved {
+                    self.route_to_dispatcher(
+                        DispatchItemKind::Advisory,
+                        &advisory.advisory_id,
+                        sector,
+                        || format!("{}: {}", advisory.drone_id, advisory.description),
+                    );
+                }
+            }
+        }
         self.daa_advisories
             .insert(advisory.advisory_id.clone(), advisory);
     }
@@ -910,6 +2448,7 @@ impl AppState {
             entry.resolved = true;
             entry.updated_at = chrono::Utc::now();
         }
+        self.forget_dispatch_item(DispatchItemKind::Advisory, advisory_id);
     }
 
     /// Get all DAA advisories.
@@ -928,30 +2467,321 @@ impl AppState {
             .collect()
     }
 
+    /// Copy flight plan scheduling priorities onto live drone state for conflict resolution.
+    ///
+    /// A drone takes the priority of its active plan, else of its approved or pending plans
+    /// (the best one when several qualify); drones without such a plan have none.
+    pub fn sync_drone_priorities(&self) {
+        let mut priorities: HashMap<String, (u8, Option<u32>)> = HashMap::new();
+        for entry in self.flight_plans.iter() {
+            let plan = entry.value();
+            let rank = match plan.status {
+                FlightStatus::Active => 0,
+                FlightStatus::Approved | FlightStatus::Pending => 1,
+                _ => continue,
+            };
+            let priority = plan
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.scheduling_priority);
+            let candidate = (rank, priority);
+            priorities
+                .entry(plan.drone_id.clone())
+                .and_modify(|current| {
+                    let better_rank = candidate.0 < current.0;
+                    let better_priority = candidate.0 == current.0
+                        && candidate.1.unwrap_or(u32::MAX) < current.1.unwrap_or(u32::MAX);
+                    if better_rank || better_priority {
+                        *current = candidate;
+                    }
+                })
+                .or_insert(candidate);
+        }
+        for mut entry in self.drones.iter_mut() {
+            let priority = priorities
+                .get(entry.key())
+                .and_then(|(_, priority)| *priority);
+            entry.value_mut().scheduling_priority = priority;
+        }
+    }
+
     /// Add or update a flight plan, persisting before updating in-memory state.
-    pub async fn add_flight_plan(&self, plan: FlightPlan) -> Result<()> {
+    pub async fn add_flight_plan(&self, mut plan: FlightPlan) -> Result<()> {
+        let awaiting_approval =
+            matches!(plan.status, FlightStatus::Reserved | FlightStatus::Pending);
+        let sector = plan
+            .waypoints
+            .first()
+            .and_then(|origin| self.sector_at(origin.lat, origin.lon));
+        if let Some(sector) = sector {
+            plan.metadata.get_or_insert_with(Default::default).sector_id = Some(sector.id.clone());
+        }
         if let Some(db) = self.database.clone() {
             flight_plans_db::upsert_flight_plan(db.pool(), &plan).await?;
         }
-        self.flight_plans.insert(plan.flight_id.clone(), plan);
+        match sector.filter(|_| awaiting_approval) {
+            Some(sector) => self.route_to_dispatcher(
+                DispatchItemKind::Approval,
+                &plan.flight_id,
+                sector,
+                || {
+                    format!(
+                        "Flight {} ({}) awaiting approval, departing {}",
+                        plan.flight_id,
+                        plan.drone_id,
+                        plan.departure_time.to_rfc3339()
+                    )
+                },
+            ),
+            None => self.forget_dispatch_item(DispatchItemKind::Approval, &plan.flight_id),
+        }
+        self.cache_flight_plan(plan);
+        self.sync_drone_priorities();
         Ok(())
     }
 
+    /// Cache a plan that is already persisted, notifying webhooks when its status changed.
+    pub fn cache_flight_plan(&self, plan: FlightPlan) {
+        let previous = self
+            .flight_plans
+            .get(&plan.flight_id)
+            .map(|entry| entry.status);
+        if previous != Some(plan.status) {
+            self.notify_plan_status(&plan);
+        }
+        self.flight_plans.insert(plan.flight_id.clone(), plan);
+    }
+
+    /// Queue webhook deliveries for a plan that just reached its current status.
+    pub(crate) fn notify_plan_status(&self, plan: &FlightPlan) {
+        let event = match plan.status {
+            FlightStatus::Approved => WebhookEventType::PlanApproved,
+            FlightStatus::Rejected => WebhookEventType::PlanRejected,
+            FlightStatus::Active => WebhookEventType::PlanActivated,
+            FlightStatus::Completed => WebhookEventType::PlanCompleted,
+            _ => return,
+        };
+        webhooks::publish(self, event, &[plan.drone_id.as_str()], plan);
+    }
+
     // ========== COMMAND MANAGEMENT ==========
 
-    /// Enqueue a command for a drone.
-    pub async fn enqueue_command(&self, command: Command) -> Result<()> {
+    /// Enqueue a command for a drone, recording what triggered it in the command audit trail.
+    pub async fn enqueue_command(&self, command: Command, cause: CommandCause) -> Result<()> {
+        if let Some(db) = self.database.clone() {
+            commands_db::insert_command(db.pool(), &command, &cause).await?;
+        }
+        self.queue_command(command);
+        Ok(())
+    }
+
+    /// Enqueue a command issued by automation, subject to the automatic-command budget.
+    ///
+    /// A command over budget is not queued: the drone gets a `throttle` DAA advisory naming it
+    /// instead, and `Ok(false)` is returned. The advisory resolves once an automatic command for
+    /// the drone is issued again.
+    pub async fn enqueue_auto_command(
+        &self,
+        command: Command,
+        cause: CommandCause,
+    ) -> Result<bool> {
+        let region = self
+            .drones
+            .get(&command.drone_id)
+            .and_then(|drone| self.sector_at(drone.lat, drone.lon))
+            .map(|sector| sector.id.clone())
+            .unwrap_or_else(|| UNSECTORED.to_string());
+        let advisory_id = format!("throttle-{}", command.drone_id);
+        let scope = match self
+            .command_throttle
+            .try_acquire(&region, std::time::Instant::now())
+        {
+            Ok(()) => {
+                self.enqueue_command(command, cause).await?;
+                if self
+                    .daa_advisories
+                    .get(&advisory_id)
+                    .is_some_and(|advisory| !advisory.resolved)
+                {
+                    self.resolve_daa_advisory(&advisory_id);
+                }
+                return Ok(true);
+            }
+            Err(BudgetScope::Global) => "global".to_string(),
+            Err(BudgetScope::Region(region)) => format!("region {}", region),
+        };
+
+        let action = command_action(&command.command_type);
+        tracing::warn!(
+            "Withheld automatic {} for {}: {} command budget exhausted",
+            action,
+            command.drone_id,
+            scope
+        );
+        let message = Message::new(codes::ADVISORY_COMMAND_THROTTLED)
+            .with("command", action)
+            .with("scope", scope);
+        let now = Utc::now();
+        self.set_daa_advisory(DaaAdvisory {
+            advisory_id,
+            drone_id: command.drone_id.clone(),
+            owner_id: self
+                .drones
+                .get(&command.drone_id)
+                .and_then(|drone| drone.owner_id.clone()),
+            source: "throttle".to_string(),
+            severity: DaaSeverity::Warning,
+            action: action.to_string(),
+            description: self.config.messages.format(&message, None),
+            message_code: Some(message),
+            related_id: Some(command.command_id),
+            record: None,
+            sector_id: None,
+            created_at: now,
+            updated_at: now,
+            resolved: false,
+        });
+        Ok(false)
+    }
+
+    /// Record a broadcast and enqueue its per-drone commands. Commands are persisted in one
+    /// transaction before any is queued, so either every target gets its command or none does.
+    pub async fn issue_command_broadcast(
+        &self,
+        broadcast: CommandBroadcast,
+        commands: Vec<Command>,
+    ) -> Result<()> {
+        if let Some(db) = self.database.clone() {
+            let cause =
+                CommandCause::new(CommandCauseKind::Broadcast).with_source(&broadcast.broadcast_id);
+            commands_db::insert_commands(db.pool(), &commands, &cause).await?;
+        }
+        self.prune_command_broadcasts();
+        for target in &broadcast.targets {
+            self.broadcast_commands
+                .insert(target.command_id.clone(), broadcast.broadcast_id.clone());
+        }
+        self.command_broadcasts
+            .insert(broadcast.broadcast_id.clone(), broadcast);
+        for command in commands {
+            self.queue_command(command);
+        }
+        Ok(())
+    }
+
+    /// The emergency all-stop in force, if any.
+    pub fn emergency_stop(&self) -> Option<EmergencyStop> {
+        self.emergency
+            .read()
+            .map(|guard| guard.clone())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+    }
+
+    /// Declare an emergency all-stop: record its broadcast and queue each drone's command ahead
+    /// of anything already pending. The stop and its commands are persisted in one transaction
+    /// before any command is queued.
+    pub async fn declare_emergency(
+        &self,
+        stop: EmergencyStop,
+        broadcast: CommandBroadcast,
+        commands: Vec<Command>,
+    ) -> Result<()> {
         if let Some(db) = self.database.clone() {
-            commands_db::insert_command(db.pool(), &command).await?;
+            let cause =
+                CommandCause::new(CommandCauseKind::Emergency).with_source(&stop.emergency_id);
+            emergency_db::declare_emergency(db.pool(), &stop, &commands, &cause).await?;
+        }
+        self.prune_command_broadcasts();
+        for target in &broadcast.targets {
+            self.broadcast_commands
+                .insert(target.command_id.clone(), broadcast.broadcast_id.clone());
+        }
+        self.command_broadcasts
+            .insert(broadcast.broadcast_id.clone(), broadcast);
+        for command in commands {
+            self.mark_command_issued(&command.drone_id);
+            self.push_command(command, true);
+        }
+        if let Ok(mut guard) = self.emergency.write() {
+            *guard = Some(stop.clone());
+        }
+        let _ = self.emergency_tx.send(Some(stop));
+        Ok(())
+    }
+
+    /// Clear the emergency all-stop, returning the one that was in force.
+    pub async fn clear_emergency(&self) -> Result<Option<EmergencyStop>> {
+        if let Some(db) = self.database.clone() {
+            emergency_db::clear_emergency(db.pool()).await?;
+        }
+        let cleared = self
+            .emergency
+            .write()
+            .map(|mut guard| guard.take())
+            .unwrap_or_default();
+        if cleared.is_some() {
+            let _ = self.emergency_tx.send(None);
+        }
+        Ok(cleared)
+    }
+
+    pub fn get_command_broadcast(&self, broadcast_id: &str) -> Option<CommandBroadcast> {
+        self.command_broadcasts
+            .get(broadcast_id)
+            .map(|entry| entry.value().clone())
+    }
+
+    /// Drop broadcasts that expired more than an hour ago.
+    fn prune_command_broadcasts(&self) {
+        let cutoff = Utc::now() - ChronoDuration::hours(1);
+        self.command_broadcasts.retain(|_, broadcast| {
+            let keep = broadcast.expires_at > cutoff;
+            if !keep {
+                for target in &broadcast.targets {
+                    self.broadcast_commands.remove(&target.command_id);
+                }
+            }
+            keep
+        });
+    }
+
+    fn note_broadcast_ack(&self, command_id: &str) {
+        let Some((_, broadcast_id)) = self.broadcast_commands.remove(command_id) else {
+            return;
+        };
+        if let Some(mut broadcast) = self.command_broadcasts.get_mut(&broadcast_id) {
+            if let Some(target) = broadcast
+                .targets
+                .iter_mut()
+                .find(|target| target.command_id == command_id)
+            {
+                target.acknowledged_at = Some(Utc::now());
+            }
         }
+    }
+
+    fn queue_command(&self, command: Command) {
+        self.push_command(command, false);
+    }
+
+    /// Queue a command, at the front of the drone's queue if `urgent`.
+    fn push_command(&self, command: Command, urgent: bool) {
+        webhooks::publish(
+            self,
+            WebhookEventType::CommandIssued,
+            &[command.drone_id.as_str()],
+            &command,
+        );
         let drone_id = command.drone_id.clone();
         let command_for_broadcast = command.clone();
-        self.commands
-            .entry(drone_id)
-            .or_default()
-            .push_back(command);
+        let mut queue = self.commands.entry(drone_id).or_default();
+        if urgent {
+            queue.push_front(command);
+        } else {
+            queue.push_back(command);
+        }
+        drop(queue);
         let _ = self.command_tx.send(command_for_broadcast);
-        Ok(())
     }
 
     /// Check if a command was recently issued (cooldown check).
@@ -1010,6 +2840,7 @@ impl AppState {
                     cmd.command_type,
                     atc_core::models::CommandType::Hold { .. }
                         | atc_core::models::CommandType::Reroute { .. }
+                        | atc_core::models::CommandType::Land
                 );
                 let awaiting_ack = self.command_waiting_for_ack(cmd, now);
                 // Must not be expired
@@ -1148,6 +2979,13 @@ impl AppState {
         let removed = self.remove_command_by_id(command_id);
         let command_to_apply = removed.as_ref().unwrap_or(&command);
         self.apply_command_ack_effects(command_to_apply);
+        self.note_broadcast_ack(command_id);
+        webhooks::publish(
+            self,
+            WebhookEventType::CommandAcked,
+            &[command.drone_id.as_str()],
+            &command,
+        );
 
         Ok(true)
     }
@@ -1216,9 +3054,12 @@ impl AppState {
         Ok(())
     }
 
-    /// Replace external geofences from Blender/DSS.
-    pub fn set_external_geofences(&self, geofences: Vec<Geofence>) {
-        self.external_geofences.clear();
+    /// Replace the external geofences from one source (Blender/DSS, the TFR feed), whose IDs
+    /// are prefixed with `{source}:`.
+    pub fn set_external_geofences(&self, source: &str, geofences: Vec<Geofence>) {
+        let prefix = format!("{source}:");
+        self.external_geofences
+            .retain(|id, _| !id.starts_with(&prefix));
         for geofence in geofences {
             self.external_geofences
                 .insert(geofence.id.clone(), geofence);
@@ -1295,12 +3136,15 @@ impl AppState {
         Ok(self.geofences.remove(id).is_some())
     }
 
-    /// Check if a point is inside any active geofence.
+    /// Check if a point is inside any geofence in force now.
     pub fn check_point_in_geofences(&self, lat: f64, lon: f64, altitude_m: f64) -> Vec<String> {
+        let now = Utc::now();
         self.geofences
             .iter()
             .chain(self.external_geofences.iter())
-            .filter(|e| e.value().active && e.value().contains_point(lat, lon, altitude_m))
+            .filter(|e| {
+                e.value().in_force_at(now) && e.value().contains_point(lat, lon, altitude_m)
+            })
             .map(|e| e.key().clone())
             .collect()
     }
@@ -1315,13 +3159,37 @@ impl AppState {
         }
 
         self.drones.clear();
+        self.drone_index.clear();
         self.drone_owners.clear();
         self.drone_tokens.clear();
+        self.drone_performance.clear();
+        self.drone_homes.clear();
+        self.set_weather_cells(Vec::new());
+        if let Ok(mut guard) = self.coverage_areas.write() {
+            guard.clear();
+        }
         self.external_traffic.clear();
+        self.traffic_index.clear();
+        self.traffic_history.clear();
+        self.traffic_estimators.clear();
         self.conflicts.clear();
+        self.conflict_tracks.clear();
+        if let Ok(mut pending) = self.ended_conflicts.lock() {
+            pending.clear();
+        }
+        if let Ok(mut guard) = self.geofence_breaches.write() {
+            guard.clear();
+        }
         self.commands.clear();
         self.command_cooldowns.clear();
         self.active_holds.clear();
+        self.command_broadcasts.clear();
+        self.broadcast_commands.clear();
+        if let Ok(mut guard) = self.emergency.write() {
+            if guard.take().is_some() {
+                let _ = self.emergency_tx.send(None);
+            }
+        }
         self.flight_plans.clear();
         self.geofences.clear();
         self.external_geofences.clear();
@@ -1335,12 +3203,7 @@ impl AppState {
 
         // Reset the conflict detector
         if let Ok(mut detector) = self.detector.lock() {
-            *detector = ConflictDetector::new(
-                self.rules.lookahead_seconds,
-                self.rules.min_horizontal_separation_m,
-                self.rules.min_vertical_separation_m,
-                self.rules.warning_multiplier,
-            );
+            *detector = self.rules().conflict_detector();
         }
 
         // Reset drone counter
@@ -1349,4 +3212,199 @@ impl AppState {
         tracing::info!("All state cleared for demo reset");
         Ok(())
     }
+
+    /// Owner a drone is registered to, if any.
+    pub fn drone_owner(&self, drone_id: &str) -> Option<String> {
+        self.drone_owners
+            .get(drone_id)
+            .map(|entry| entry.value().clone())
+            .or_else(|| {
+                self.drones
+                    .get(drone_id)
+                    .and_then(|drone| drone.owner_id.clone())
+            })
+    }
+
+    /// IDs of the drones registered to an owner, sorted. With a database this includes
+    /// deregistered drones, whose archived rows still hold the owner's records.
+    pub async fn owner_drone_ids(&self, owner_id: &str) -> Result<Vec<String>> {
+        let archived = match self.database.as_ref() {
+            Some(db) => drones_db::load_owner_drone_ids(db.pool(), owner_id).await?,
+            None => Vec::new(),
+        };
+        let mut drone_ids: Vec<String> = self
+            .drone_owners
+            .iter()
+            .filter(|entry| entry.value() == owner_id)
+            .map(|entry| entry.key().clone())
+            .chain(
+                self.drones
+                    .iter()
+                    .filter(|entry| entry.owner_id.as_deref() == Some(owner_id))
+                    .map(|entry| entry.key().clone()),
+            )
+            .chain(archived)
+            .collect();
+        drone_ids.sort();
+        drone_ids.dedup();
+        Ok(drone_ids)
+    }
+
+    /// Kept rejection explanations for plans filed by an owner or for one of its drones.
+    pub fn owner_plan_rejections(
+        &self,
+        owner_id: &str,
+        drone_ids: &[String],
+    ) -> Vec<PlanRejection> {
+        self.plan_rejections
+            .lock()
+            .map(|log| {
+                log.iter()
+                    .filter(|entry| {
+                        entry.owner_id.as_deref() == Some(owner_id)
+                            || drone_ids.contains(&entry.drone_id)
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Deregister a drone: cancel its pending commands and unfinished flight plans, revoke its
+    /// session token, drop its detector track and archive its database row.
+    ///
+    /// The database is updated first, in one transaction; `None` when the drone isn't registered.
+    pub async fn archive_drone(&self, drone_id: &str) -> Result<Option<DroneArchive>> {
+        if !self.drones.contains_key(drone_id) {
+            return Ok(None);
+        }
+        // Keep the scheduler from booking a plan for the drone while its plans are cancelled.
+        let _booking = self.flight_plan_booking_lock.lock().await;
+        let archived_at = Utc::now();
+        let ended_plans: Vec<FlightPlan> = self
+            .flight_plans
+            .iter()
+            .filter(|entry| {
+                entry.drone_id == drone_id
+                    && matches!(
+                        entry.status,
+                        FlightStatus::Reserved
+                            | FlightStatus::Pending
+                            | FlightStatus::Approved
+                            | FlightStatus::Active
+                    )
+            })
+            .map(|entry| FlightPlan {
+                status: FlightStatus::Cancelled,
+                ..entry.value().clone()
+            })
+            .collect();
+
+        if let Some(db) = self.database.clone() {
+            if !drones_db::archive_drone(db.pool(), drone_id, archived_at, &ended_plans).await? {
+                tracing::warn!("Drone {} had no database row to archive", drone_id);
+            }
+        }
+
+        let cancelled_commands = self
+            .commands
+            .remove(drone_id)
+            .map(|(_, queue)| queue.into_iter().map(|cmd| cmd.command_id).collect())
+            .unwrap_or_default();
+        self.drones.remove(drone_id);
+        self.drone_index.remove(drone_id);
+        self.drone_owners.remove(drone_id);
+        self.drone_tokens.remove(drone_id);
+        self.telemetry_guard.forget(drone_id);
+        self.usage.forget_drone(drone_id);
+        self.drone_performance.remove(drone_id);
+        self.drone_homes.remove(drone_id);
+        self.command_cooldowns.remove(drone_id);
+        self.active_holds.remove(drone_id);
+        self.conformance.remove(drone_id);
+        self.daa_advisories.remove(drone_id);
+        if let Ok(mut guard) = self.telemetry_overflow.lock() {
+            guard.remove(drone_id);
+        }
+        self.queue_detector_update(DetectorUpdate::Remove(drone_id.to_string()))
+            .await;
+        let cancelled_flights = ended_plans
+            .iter()
+            .map(|plan| plan.flight_id.clone())
+            .collect();
+        for plan in ended_plans {
+            self.cache_flight_plan(plan);
+        }
+
+        tracing::info!("Drone {} deregistered and archived", drone_id);
+        Ok(Some(DroneArchive {
+            drone_id: drone_id.to_string(),
+            archived_at,
+            cancelled_commands,
+            cancelled_flights,
+        }))
+    }
+
+    /// Remove everything held for an owner and its drones, for data-retention requests.
+    ///
+    /// Database rows are deleted first, in one transaction, and memory is only cleared once they
+    /// are gone. A dry run reports the database counts and changes nothing.
+    pub async fn purge_owner(
+        &self,
+        owner_id: &str,
+        drone_ids: &[String],
+        dry_run: bool,
+    ) -> Result<owner_data_db::OwnerPurgeCounts> {
+        let counts = match self.database.clone() {
+            Some(db) => {
+                owner_data_db::purge_owner_data(db.pool(), owner_id, drone_ids, dry_run).await?
+            }
+            None => owner_data_db::OwnerPurgeCounts::default(),
+        };
+        if dry_run {
+            return Ok(counts);
+        }
+
+        for drone_id in drone_ids {
+            self.drones.remove(drone_id);
+            self.drone_index.remove(drone_id);
+            self.drone_owners.remove(drone_id);
+            self.drone_tokens.remove(drone_id);
+            self.telemetry_guard.forget(drone_id);
+            self.usage.forget_drone(drone_id);
+            self.drone_performance.remove(drone_id);
+            self.drone_homes.remove(drone_id);
+            self.commands.remove(drone_id);
+            self.command_cooldowns.remove(drone_id);
+            self.active_holds.remove(drone_id);
+            self.conformance.remove(drone_id);
+            self.daa_advisories.remove(drone_id);
+            if let Ok(mut guard) = self.telemetry_overflow.lock() {
+                guard.remove(drone_id);
+            }
+            self.queue_detector_update(DetectorUpdate::Remove(drone_id.clone()))
+                .await;
+        }
+        self.flight_plans.retain(|_, plan| {
+            plan.owner_id.as_deref() != Some(owner_id) && !drone_ids.contains(&plan.drone_id)
+        });
+        if let Ok(mut log) = self.breach_log.lock() {
+            log.retain(|event| !drone_ids.contains(&event.drone_id));
+        }
+        if let Ok(mut log) = self.plan_rejections.lock() {
+            log.retain(|entry| {
+                entry.owner_id.as_deref() != Some(owner_id) && !drone_ids.contains(&entry.drone_id)
+            });
+        }
+
+        tracing::info!(
+            "Purged owner {}: {} drones, {} flight plans, {} commands, {} conflicts",
+            owner_id,
+            counts.drones,
+            counts.flight_plans,
+            counts.commands,
+            counts.conflicts
+        );
+        Ok(counts)
+    }
 }
@@ -0,0 +1,128 @@
+//! Reported-versus-observed surveillance crosscheck.
+//!
+//! A registered drone that is also visible to external surveillance (its Remote ID broadcast or
+//! an ADS-B track under its ID) is checked on every conflict loop tick: the telemetry fix and the
+//! external fix are aligned in time and compared. A drone whose sources disagree beyond the
+//! configured thresholds gets an integrity advisory (source `surveillance`); it resolves once the
+//! sources agree again or one of them goes away.
+
+use std::collections::HashSet;
+
+use atc_core::conflict::DronePosition;
+use atc_core::messages::{codes, Message};
+use atc_core::models::{DaaAdvisory, DaaSeverity, DroneState, DroneStatus};
+use atc_core::surveillance::{crosscheck, CrosscheckThresholds, SurveillanceDivergence};
+use chrono::Utc;
+
+use crate::state::{AppState, ExternalTraffic};
+
+/// Divergence this many times over the threshold is treated as critical.
+const CRITICAL_RATIO: f64 = 3.0;
+
+fn advisory_id(drone_id: &str) -> String {
+    format!("surveillance-{}", drone_id)
+}
+
+fn reported_position(drone: &DroneState) -> DronePosition {
+    let mut position = DronePosition::new(&drone.drone_id, drone.lat, drone.lon, drone.altitude_m)
+        .with_velocity(drone.heading_deg, drone.speed_mps, drone.velocity_z);
+    position.timestamp = drone.last_update.timestamp_millis() as f64 / 1000.0;
+    position
+}
+
+fn observed_position(track: &ExternalTraffic) -> DronePosition {
+    let mut position =
+        DronePosition::new(&track.traffic_id, track.lat, track.lon, track.altitude_m)
+            .with_velocity(track.heading_deg, track.speed_mps, 0.0);
+    position.timestamp = track.last_update.timestamp_millis() as f64 / 1000.0;
+    position
+}
+
+/// Divergence between a drone's telemetry and its external track, when they are comparable.
+fn divergence(
+    drone: &DroneState,
+    track: &ExternalTraffic,
+    thresholds: &CrosscheckThresholds,
+) -> Option<SurveillanceDivergence> {
+    crosscheck(
+        &reported_position(drone),
+        &observed_position(track),
+        thresholds.max_time_skew_s,
+    )
+}
+
+/// Cross-check every drone seen by both telemetry and external surveillance.
+///
+/// `active` holds the drones whose sources disagreed on the previous tick, so advisories are
+/// resolved when they agree again.
+pub fn monitor_surveillance(state: &AppState, active: &mut HashSet<String>) {
+    let Some(thresholds) = state.config().surveillance_crosscheck else {
+        return;
+    };
+    let mut current = HashSet::new();
+
+    for drone in state.get_all_drones() {
+        if matches!(drone.status, DroneStatus::Lost | DroneStatus::Inactive) {
+            continue;
+        }
+        let Some(track) = state.surveillance_track(&drone.drone_id) else {
+            continue;
+        };
+        let Some(divergence) = divergence(&drone, &track, &thresholds) else {
+            // Too far apart in time to compare; keep whatever state the last comparison left.
+            if active.contains(&drone.drone_id) {
+                current.insert(drone.drone_id.clone());
+            }
+            continue;
+        };
+        if !divergence.exceeds(&thresholds) {
+            continue;
+        }
+        current.insert(drone.drone_id.clone());
+
+        let now = Utc::now();
+        let severity = if divergence.ratio(&thresholds) >= CRITICAL_RATIO {
+            DaaSeverity::Critical
+        } else {
+            DaaSeverity::Warning
+        };
+        let message = Message::new(codes::ADVISORY_SURVEILLANCE_DIVERGENCE)
+            .with("source", track.source.clone())
+            .with("horizontal_m", divergence.horizontal_m)
+            .with("vertical_m", divergence.vertical_m);
+        state.set_daa_advisory(DaaAdvisory {
+            advisory_id: advisory_id(&drone.drone_id),
+            drone_id: drone.drone_id.clone(),
+            owner_id: drone.owner_id.clone(),
+            source: "surveillance".to_string(),
+            severity,
+            action: "verify_position".to_string(),
+            description: state.config().messages.format(&message, None),
+            message_code: Some(message),
+            related_id: Some(track.traffic_id.clone()),
+            record: None,
+            sector_id: None,
+            created_at: now,
+            updated_at: now,
+            resolved: false,
+        });
+        if !active.contains(&drone.drone_id) {
+            tracing::warn!(
+                "Drone {} telemetry disagrees with {} track {}: {:.0} m horizontal, {:.0} m vertical",
+                drone.drone_id,
+                track.source,
+                track.traffic_id,
+                divergence.horizontal_m,
+                divergence.vertical_m
+            );
+        }
+    }
+
+    for drone_id in active.iter() {
+        if !current.contains(drone_id) {
+            state.resolve_daa_advisory(&advisory_id(drone_id));
+            tracing::info!("Drone {} surveillance sources agree again", drone_id);
+        }
+    }
+    *active = current;
+}
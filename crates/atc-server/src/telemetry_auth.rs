@@ -0,0 +1,316 @@
+//! Telemetry message authentication and anti-replay.
+//!
+//! Session tokens alone can be replayed by anyone who captures a telemetry request. Drones can
+//! additionally send a timestamp, a per-message nonce and an HMAC-SHA256 over
+//! `"{timestamp_ms}.{nonce}." + body`, keyed by the telemetry MAC secret issued with their
+//! session token. The secret is only returned when the token is issued or rotated and never
+//! travels on a request, so capturing a request is not enough to sign new ones. The server
+//! rejects messages outside the freshness window, nonces it has already seen, and bad
+//! signatures, and keeps per-drone counters of rejected messages as a spoofing signal.
+
+use std::collections::HashMap;
+
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Serialize;
+use sha2::Sha256;
+
+pub const TIMESTAMP_HEADER: &str = "x-atc-timestamp";
+pub const NONCE_HEADER: &str = "x-atc-nonce";
+pub const SIGNATURE_HEADER: &str = "x-atc-signature";
+
+/// Nonces longer than this are rejected so the replay cache stays bounded.
+const MAX_NONCE_LEN: usize = 128;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How strictly telemetry signatures are enforced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelemetryAuthMode {
+    /// Signature headers are ignored.
+    Off,
+    /// Signed messages are verified; unsigned messages are accepted.
+    Optional,
+    /// Every message must be signed.
+    Required,
+}
+
+impl TelemetryAuthMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "disabled" | "none" => Some(Self::Off),
+            "optional" => Some(Self::Optional),
+            "required" | "require" => Some(Self::Required),
+            _ => None,
+        }
+    }
+}
+
+/// Why a telemetry message failed authentication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectReason {
+    MissingSignature,
+    Malformed,
+    Stale,
+    Replay,
+    BadSignature,
+}
+
+impl RejectReason {
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::MissingSignature => "Telemetry signature required",
+            Self::Malformed => "Malformed telemetry signature headers",
+            Self::Stale => "Telemetry timestamp outside freshness window",
+            Self::Replay => "Telemetry nonce already used",
+            Self::BadSignature => "Telemetry signature mismatch",
+        }
+    }
+}
+
+/// Rejected-message counters for one drone.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RejectionStats {
+    pub drone_id: String,
+    pub missing_signature: u64,
+    pub malformed: u64,
+    pub stale: u64,
+    pub replay: u64,
+    pub bad_signature: u64,
+    pub last_reason: Option<RejectReason>,
+    pub last_rejected_at: Option<DateTime<Utc>>,
+}
+
+/// A fresh telemetry MAC secret: 32 random bytes, hex-encoded.
+pub fn generate_mac_secret() -> String {
+    let mut secret = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut secret)
+        .expect("system random source available");
+    hex::encode(secret)
+}
+
+/// Bytes covered by the telemetry HMAC.
+pub fn signing_message(timestamp_ms: i64, nonce: &str, body: &[u8]) -> Vec<u8> {
+    let mut message = format!("{}.{}.", timestamp_ms, nonce).into_bytes();
+    message.extend_from_slice(body);
+    message
+}
+
+fn message_mac(key: &str, timestamp_ms: i64, nonce: &str, body: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(&signing_message(timestamp_ms, nonce, body));
+    mac
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Recently seen nonces and rejection counters, per drone.
+#[derive(Default)]
+pub struct ReplayGuard {
+    /// Nonce -> message timestamp (ms); entries older than the freshness window are pruned.
+    seen: DashMap<String, HashMap<String, i64>>,
+    rejections: DashMap<String, RejectionStats>,
+}
+
+impl ReplayGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Authenticate a telemetry message body against the drone's telemetry MAC secret.
+    ///
+    /// `key` is `None` when the presented token has no MAC secret; signed messages then fail as
+    /// a bad signature. The signature is checked before the nonce is recorded so an
+    /// unauthenticated sender cannot burn nonces belonging to a legitimate drone.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify(
+        &self,
+        mode: TelemetryAuthMode,
+        freshness_window_secs: i64,
+        drone_id: &str,
+        key: Option<&str>,
+        headers: &HeaderMap,
+        body: &[u8],
+        now: DateTime<Utc>,
+    ) -> Result<(), RejectReason> {
+        if mode == TelemetryAuthMode::Off {
+            return Ok(());
+        }
+        let timestamp = header_str(headers, TIMESTAMP_HEADER);
+        let nonce = header_str(headers, NONCE_HEADER);
+        let signature = header_str(headers, SIGNATURE_HEADER);
+        let (timestamp, nonce, signature) = match (timestamp, nonce, signature) {
+            (Some(timestamp), Some(nonce), Some(signature)) => (timestamp, nonce, signature),
+            (None, None, None) if mode == TelemetryAuthMode::Optional => return Ok(()),
+            (None, None, None) => return Err(RejectReason::MissingSignature),
+            _ => return Err(RejectReason::Malformed),
+        };
+        let timestamp_ms: i64 = timestamp.parse().map_err(|_| RejectReason::Malformed)?;
+        if nonce.len() > MAX_NONCE_LEN {
+            return Err(RejectReason::Malformed);
+        }
+        let signature = hex::decode(signature).map_err(|_| RejectReason::Malformed)?;
+
+        let key = key.ok_or(RejectReason::BadSignature)?;
+        message_mac(key, timestamp_ms, nonce, body)
+            .verify_slice(&signature)
+            .map_err(|_| RejectReason::BadSignature)?;
+
+        let now_ms = now.timestamp_millis();
+        let window_ms = freshness_window_secs.max(0).saturating_mul(1000);
+        if (now_ms - timestamp_ms).abs() > window_ms {
+            return Err(RejectReason::Stale);
+        }
+
+        let mut seen = self.seen.entry(drone_id.to_string()).or_default();
+        seen.retain(|_, seen_ms| now_ms - *seen_ms <= window_ms);
+        if seen.contains_key(nonce) {
+            return Err(RejectReason::Replay);
+        }
+        seen.insert(nonce.to_string(), timestamp_ms);
+        Ok(())
+    }
+
+    /// Count and log a rejected message.
+    pub fn record_rejection(&self, drone_id: &str, reason: RejectReason, now: DateTime<Utc>) {
+        let mut stats = self
+            .rejections
+            .entry(drone_id.to_string())
+            .or_insert_with(|| RejectionStats {
+                drone_id: drone_id.to_string(),
+                ..Default::default()
+            });
+        match reason {
+            RejectReason::MissingSignature => stats.missing_signature += 1,
+            RejectReason::Malformed => stats.malformed += 1,
+            RejectReason::Stale => stats.stale += 1,
+            RejectReason::Replay => stats.replay += 1,
+            RejectReason::BadSignature => stats.bad_signature += 1,
+        }
+        stats.last_reason = Some(reason);
+        stats.last_rejected_at = Some(now);
+        tracing::warn!(
+            "Rejected telemetry for drone {} ({:?}); possible spoofing attempt ({} replays, {} bad signatures so far)",
+            drone_id,
+            reason,
+            stats.replay,
+            stats.bad_signature
+        );
+    }
+
+    /// Rejection counters for all drones, sorted by drone ID.
+    pub fn rejections(&self) -> Vec<RejectionStats> {
+        let mut stats: Vec<RejectionStats> = self
+            .rejections
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        stats.sort_by(|a, b| a.drone_id.cmp(&b.drone_id));
+        stats
+    }
+
+    /// Drop nonce history for a drone (e.g. after its token is revoked).
+    pub fn forget(&self, drone_id: &str) {
+        self.seen.remove(drone_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    const KEY: &str = "telemetry-mac-secret";
+
+    fn sign(key: &str, timestamp_ms: i64, nonce: &str, body: &[u8]) -> String {
+        hex::encode(
+            message_mac(key, timestamp_ms, nonce, body)
+                .finalize()
+                .into_bytes(),
+        )
+    }
+
+    fn signed_headers(timestamp_ms: i64, nonce: &str, body: &[u8], key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            TIMESTAMP_HEADER,
+            HeaderValue::from_str(&timestamp_ms.to_string()).unwrap(),
+        );
+        headers.insert(NONCE_HEADER, HeaderValue::from_str(nonce).unwrap());
+        headers.insert(
+            SIGNATURE_HEADER,
+            HeaderValue::from_str(&sign(key, timestamp_ms, nonce, body)).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn rejects_replayed_stale_and_forged_messages() {
+        let guard = ReplayGuard::new();
+        let now = Utc::now();
+        let body = br#"{"drone_id":"D1"}"#;
+        let mode = TelemetryAuthMode::Required;
+        let headers = signed_headers(now.timestamp_millis(), "n-1", body, KEY);
+
+        assert_eq!(
+            guard.verify(mode, 30, "D1", Some(KEY), &headers, body, now),
+            Ok(())
+        );
+        assert_eq!(
+            guard.verify(mode, 30, "D1", Some(KEY), &headers, body, now),
+            Err(RejectReason::Replay)
+        );
+
+        let stale = signed_headers(now.timestamp_millis() - 60_000, "n-2", body, KEY);
+        assert_eq!(
+            guard.verify(mode, 30, "D1", Some(KEY), &stale, body, now),
+            Err(RejectReason::Stale)
+        );
+
+        let forged = signed_headers(now.timestamp_millis(), "n-3", body, "wrong-key");
+        assert_eq!(
+            guard.verify(mode, 30, "D1", Some(KEY), &forged, body, now),
+            Err(RejectReason::BadSignature)
+        );
+        // A forged message must not burn the nonce for the real drone.
+        let genuine = signed_headers(now.timestamp_millis(), "n-3", body, KEY);
+        assert_eq!(
+            guard.verify(mode, 30, "D1", Some(KEY), &genuine, body, now),
+            Ok(())
+        );
+
+        assert_eq!(
+            guard.verify(mode, 30, "D1", Some(KEY), &HeaderMap::new(), body, now),
+            Err(RejectReason::MissingSignature)
+        );
+        // A token issued before MAC secrets existed cannot sign.
+        let unkeyed = signed_headers(now.timestamp_millis(), "n-4", body, KEY);
+        assert_eq!(
+            guard.verify(mode, 30, "D1", None, &unkeyed, body, now),
+            Err(RejectReason::BadSignature)
+        );
+        assert_eq!(
+            guard.verify(
+                TelemetryAuthMode::Optional,
+                30,
+                "D1",
+                Some(KEY),
+                &HeaderMap::new(),
+                body,
+                now
+            ),
+            Ok(())
+        );
+    }
+}
@@ -1,18 +1,20 @@
 //! Terrain sampling utilities for server-side routing.
 
 use crate::cache;
-use crate::compliance::RoutePoint;
+use crate::compliance::{Bounds, RoutePoint};
 use crate::config::Config;
 use atc_core::spatial::{meters_per_deg_lat, meters_per_deg_lon};
 use dashmap::DashMap;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio::time::sleep;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerrainGrid {
     min_lat: f64,
     min_lon: f64,
@@ -49,6 +51,74 @@ fn terrain_inflight() -> &'static DashMap<String, Arc<Mutex<()>>> {
 
 const TERRAIN_CACHE_MAX_ENTRIES: usize = 256;
 
+/// A cached grid may stand in for one whose steps are up to this much finer.
+const COVERING_STEP_TOLERANCE: f64 = 1.01;
+
+/// A fresh cached grid (such as a warmed operating-area grid) that covers `bounds` at least as
+/// finely as requested.
+fn covering_grid(
+    cache: &DashMap<String, TerrainCacheEntry>,
+    bounds: &TerrainBounds,
+    lat_step_deg: f64,
+    lon_step_deg: f64,
+    ttl: Duration,
+) -> Option<TerrainGrid> {
+    cache
+        .iter()
+        .filter(|entry| entry.fetched_at.elapsed() <= ttl)
+        .find(|entry| {
+            let grid = &entry.grid;
+            grid.min_lat <= bounds.min_lat
+                && grid.max_lat >= bounds.max_lat
+                && grid.min_lon <= bounds.min_lon
+                && grid.max_lon >= bounds.max_lon
+                && grid.lat_step_deg <= lat_step_deg * COVERING_STEP_TOLERANCE
+                && grid.lon_step_deg <= lon_step_deg * COVERING_STEP_TOLERANCE
+        })
+        .map(|entry| entry.grid.clone())
+}
+
+/// Terrain cache entries still usable (fresh or stale), for the planner warmup snapshot.
+pub(crate) fn export_terrain_cache(config: &Config) -> Vec<cache::SnapshotEntry<TerrainGrid>> {
+    let max_age = Duration::from_secs(config.terrain_cache_ttl_s.max(30)).saturating_mul(2);
+    terrain_cache()
+        .iter()
+        .filter(|entry| entry.fetched_at.elapsed() <= max_age)
+        .map(|entry| {
+            cache::SnapshotEntry::new(entry.key().clone(), entry.fetched_at, entry.grid.clone())
+        })
+        .collect()
+}
+
+/// Load snapshot entries into the terrain cache, skipping expired ones; returns how many loaded.
+pub(crate) fn restore_terrain_cache(
+    config: &Config,
+    entries: Vec<cache::SnapshotEntry<TerrainGrid>>,
+) -> usize {
+    let max_age = Duration::from_secs(config.terrain_cache_ttl_s.max(30)).saturating_mul(2);
+    let cache = terrain_cache();
+    let mut restored = 0;
+    for entry in entries {
+        let Some(fetched_at) = entry.restored_at(max_age) else {
+            continue;
+        };
+        let grid = entry.value;
+        if grid.rows == 0 || grid.cols == 0 || grid.elevations_m.len() != grid.rows * grid.cols {
+            continue;
+        }
+        let newer = cache
+            .get(&entry.key)
+            .is_some_and(|existing| existing.fetched_at >= fetched_at);
+        if newer {
+            continue;
+        }
+        cache.insert(entry.key, TerrainCacheEntry { fetched_at, grid });
+        restored += 1;
+    }
+    cache::prune_cache(cache, TERRAIN_CACHE_MAX_ENTRIES, max_age);
+    restored
+}
+
 impl TerrainGrid {
     pub fn sample(&self, lat: f64, lon: f64) -> f64 {
         if !lat.is_finite() || !lon.is_finite() {
@@ -93,6 +163,27 @@ impl TerrainGrid {
         v00.max(v10).max(v01).max(v11)
     }
 
+    /// Hash of the sampled area and elevations, to tell whether derived data is still current.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for value in [
+            self.min_lat,
+            self.min_lon,
+            self.max_lat,
+            self.max_lon,
+            self.lat_step_deg,
+            self.lon_step_deg,
+        ] {
+            value.to_bits().hash(&mut hasher);
+        }
+        self.rows.hash(&mut hasher);
+        self.cols.hash(&mut hasher);
+        for elevation in &self.elevations_m {
+            elevation.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
     fn value_at(&self, row: usize, col: usize) -> f64 {
         let idx = row.saturating_mul(self.cols) + col.min(self.cols - 1);
         self.elevations_m.get(idx).copied().unwrap_or(0.0)
@@ -134,6 +225,46 @@ pub async fn fetch_terrain_grid(
         None => return Ok(None),
     };
     let bounds = expand_bounds(&core_bounds, 0.2);
+    fetch_grid(
+        client,
+        config,
+        &core_bounds,
+        &bounds,
+        grid_spacing_m,
+        started_at,
+    )
+    .await
+}
+
+/// Fetch the grid for exactly `area` at the configured spacing, leaving it in the terrain cache
+/// where it also serves routes inside the area.
+pub(crate) async fn prefetch_terrain_area(
+    client: &Client,
+    config: &Config,
+    area: &Bounds,
+) -> Result<Option<TerrainGrid>, String> {
+    let started_at = Instant::now();
+    if config.terrain_provider_url.trim().is_empty() {
+        return Err("terrain provider URL is empty".to_string());
+    }
+    let bounds = TerrainBounds {
+        min_lat: area.min_lat,
+        max_lat: area.max_lat,
+        min_lon: area.min_lon,
+        max_lon: area.max_lon,
+    };
+    let spacing_m = config.terrain_sample_spacing_m;
+    fetch_grid(client, config, &bounds, &bounds, spacing_m, started_at).await
+}
+
+async fn fetch_grid(
+    client: &Client,
+    config: &Config,
+    core_bounds: &TerrainBounds,
+    bounds: &TerrainBounds,
+    grid_spacing_m: f64,
+    started_at: Instant,
+) -> Result<Option<TerrainGrid>, String> {
     let cache_ttl = Duration::from_secs(config.terrain_cache_ttl_s.max(30));
     let cache = terrain_cache();
     let mut stale_cache: Option<TerrainGrid> = None;
@@ -148,14 +279,14 @@ pub async fn fetch_terrain_grid(
     let meters_per_deg_lon = meters_per_deg_lon(mean_lat).max(1.0);
 
     let (rows, cols, lat_step_deg, lon_step_deg, spacing_m) = resolve_grid_dims(
-        &bounds,
+        bounds,
         spacing_target_m,
         meters_per_deg_lat,
         meters_per_deg_lon,
         config.terrain_max_grid_points,
     );
 
-    let cache_key = terrain_cache_key(&bounds, spacing_m);
+    let cache_key = terrain_cache_key(bounds, spacing_m);
     if let Some(entry) = cache.get(&cache_key) {
         let age = entry.fetched_at.elapsed();
         if age <= cache_ttl {
@@ -166,6 +297,10 @@ pub async fn fetch_terrain_grid(
         }
     }
 
+    if let Some(grid) = covering_grid(cache, bounds, lat_step_deg, lon_step_deg, cache_ttl) {
+        return Ok(Some(grid));
+    }
+
     // Coalesce concurrent fetches for the same grid so many route-plans don’t stampede terrain-api.
     let inflight_lock = terrain_inflight()
         .entry(cache_key.clone())
@@ -0,0 +1,127 @@
+//! Home-point tether monitoring.
+//!
+//! Drones with a registered tether are checked against it on every conflict loop tick. A drone
+//! beyond its tether gets a DAA advisory (source `tether`); when its home has `auto_rth` set it
+//! is also rerouted back home, subject to its performance envelope and the command cooldown.
+//! The advisory resolves once the drone is back inside the tether.
+
+use std::collections::HashSet;
+
+use atc_core::messages::{codes, Message};
+use atc_core::models::{Command, CommandType, DaaAdvisory, DaaSeverity, DroneState, DroneStatus};
+use atc_core::DroneHome;
+use chrono::{Duration as ChronoDuration, Utc};
+
+use crate::loops::conflict_loop::executable_command;
+use crate::loops::conformance_loop::CONFORMANCE_COMMAND_COOLDOWN_SECS;
+use crate::persistence::command_audit::{CommandCause, CommandCauseKind};
+use crate::state::AppState;
+
+/// Lifetime of a return-to-home command.
+const RTH_COMMAND_TTL_SECS: i64 = 120;
+
+fn advisory_id(drone_id: &str) -> String {
+    format!("tether-{}", drone_id)
+}
+
+/// Return-to-home command for a drone beyond its tether.
+pub fn return_home_command(home: &DroneHome, drone: &DroneState) -> CommandType {
+    CommandType::Reroute {
+        waypoints: home.return_waypoints(drone.altitude_m),
+        reason: Some("Return to home: tether exceeded".to_string()),
+    }
+}
+
+/// Check tethered drones and respond to any beyond their tether.
+///
+/// `active` holds the drones beyond their tether on the previous tick, so advisories are
+/// resolved when a drone returns inside it.
+pub async fn monitor_tethers(state: &AppState, active: &mut HashSet<String>) {
+    let wind_mps = state.config().route_planner_wind_mps.max(0.0);
+    let mut current = HashSet::new();
+
+    for (drone_id, home) in state.drone_homes() {
+        let Some(drone) = state.get_drone(&drone_id) else {
+            continue;
+        };
+        if matches!(drone.status, DroneStatus::Lost | DroneStatus::Inactive) {
+            continue;
+        }
+        let Some(excess_m) = home.tether_excess_m(drone.lat, drone.lon) else {
+            continue;
+        };
+        current.insert(drone_id.clone());
+
+        let now = Utc::now();
+        let message = Message::new(codes::ADVISORY_TETHER)
+            .with("excess_m", excess_m)
+            .with("tether_radius_m", home.tether_radius_m.unwrap_or_default());
+        state.set_daa_advisory(DaaAdvisory {
+            advisory_id: advisory_id(&drone_id),
+            drone_id: drone_id.clone(),
+            owner_id: drone.owner_id.clone(),
+            source: "tether".to_string(),
+            severity: DaaSeverity::Critical,
+            action: if home.auto_rth {
+                "return_home"
+            } else {
+                "monitor"
+            }
+            .to_string(),
+            description: state.config().messages.format(&message, None),
+            message_code: Some(message),
+            related_id: None,
+            record: None,
+            sector_id: None,
+            created_at: now,
+            updated_at: now,
+            resolved: false,
+        });
+        if !active.contains(&drone_id) {
+            tracing::warn!("Drone {} is {:.0} m beyond its tether", drone_id, excess_m);
+        }
+
+        if !home.auto_rth || !state.can_issue_command(&drone_id, CONFORMANCE_COMMAND_COOLDOWN_SECS)
+        {
+            continue;
+        }
+        let Some(command_type) = executable_command(
+            return_home_command(&home, &drone),
+            &drone,
+            state.drone_performance(&drone_id).as_ref(),
+            wind_mps,
+        ) else {
+            continue;
+        };
+        let cmd = Command {
+            command_id: format!("RTH-{}-{}", drone_id, now.timestamp()),
+            drone_id: drone_id.clone(),
+            command_type,
+            issued_at: now,
+            expires_at: Some(now + ChronoDuration::seconds(RTH_COMMAND_TTL_SECS)),
+            acknowledged: false,
+        };
+        match state
+            .enqueue_auto_command(cmd, CommandCause::new(CommandCauseKind::Tether))
+            .await
+        {
+            Ok(sent) => {
+                state.mark_command_issued(&drone_id);
+                if sent {
+                    tracing::warn!("Return-to-home queued for {}", drone_id);
+                }
+            }
+            Err(err) => {
+                tracing::warn!("Failed to enqueue return-to-home for {}: {}", drone_id, err);
+            }
+        }
+    }
+
+    for drone_id in active.iter() {
+        if !current.contains(drone_id) {
+            state.resolve_daa_advisory(&advisory_id(drone_id));
+            tracing::info!("Drone {} is back inside its tether", drone_id);
+        }
+    }
+    *active = current;
+}
@@ -0,0 +1,401 @@
+//! Temporary flight restrictions from a NOTAM/TFR feed.
+//!
+//! The TFR loop polls `ATC_TFR_FEED_URL` and turns each restriction area into an external
+//! `temporary_restriction` geofence (ID `tfr:<notam>`) in force over the NOTAM's effective
+//! window, so flight plan validation, the route planner and breach monitoring honour it like
+//! a Blender geofence. Two feed formats are understood:
+//!
+//! - `geojson`: a FeatureCollection of polygons with `notam_id`, `name`, `lower_ft`/`upper_ft`
+//!   (MSL feet, or `lower_altitude_m`/`upper_altitude_m`) and `effective_start`/`effective_end`
+//!   properties.
+//! - `faa_notam`: the FAA NOTAM API's GeoJSON `items`, keeping NOTAMs with a `QRT` selection
+//!   code or TFR text, with flight-level limits and `PERM` for open-ended windows.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde_json::{json, Value};
+
+use atc_core::geojson::polygon_from_geometry;
+use atc_core::models::{Geofence, GeofenceSchedule, GeofenceType};
+
+/// Prefix of the IDs of TFR geofences among the external geofences.
+pub const TFR_SOURCE: &str = "tfr";
+
+const FEET_TO_M: f64 = 0.3048;
+/// Geofence ceilings are capped here; NOTAMs use `999` for unlimited.
+const MAX_CEILING_M: f64 = 10_000.0;
+/// Window given to a restriction that starts later and has no end. The feed is pulled again
+/// long before it closes, so only its start matters.
+const OPEN_ENDED_WINDOW_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TfrFeedFormat {
+    GeoJson,
+    FaaNotam,
+}
+
+impl TfrFeedFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "geojson" | "geo_json" => Some(Self::GeoJson),
+            "faa_notam" | "faa" | "notam" => Some(Self::FaaNotam),
+            _ => None,
+        }
+    }
+}
+
+/// Geofences read from a feed, and how many entries could not be used.
+#[derive(Debug, Default)]
+pub struct TfrFeed {
+    pub geofences: Vec<Geofence>,
+    pub skipped: usize,
+}
+
+/// A restriction read from the feed.
+struct TfrRecord {
+    notam_id: String,
+    name: String,
+    lower_m: f64,
+    upper_m: f64,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    geometry: Value,
+}
+
+/// Geofences for the restrictions in a feed document that have not ended by `now`.
+pub fn parse_feed(
+    format: TfrFeedFormat,
+    document: &Value,
+    now: DateTime<Utc>,
+) -> Result<TfrFeed, String> {
+    let entries = match format {
+        TfrFeedFormat::GeoJson => document.get("features"),
+        TfrFeedFormat::FaaNotam => document.get("items"),
+    }
+    .and_then(Value::as_array)
+    .ok_or_else(|| match format {
+        TfrFeedFormat::GeoJson => "Feed is not a GeoJSON FeatureCollection".to_string(),
+        TfrFeedFormat::FaaNotam => "Feed has no NOTAM items".to_string(),
+    })?;
+
+    let mut feed = TfrFeed::default();
+    for entry in entries {
+        let record = match format {
+            TfrFeedFormat::GeoJson => geojson_record(entry),
+            TfrFeedFormat::FaaNotam => match notam_record(entry) {
+                Some(record) => Some(record),
+                // Not a TFR.
+                None if !is_tfr_notam(entry) => continue,
+                None => None,
+            },
+        };
+        match record.map(|record| to_geofences(record, now)) {
+            Some(Some(geofences)) => feed.geofences.extend(geofences),
+            _ => feed.skipped += 1,
+        }
+    }
+    Ok(feed)
+}
+
+fn geojson_record(feature: &Value) -> Option<TfrRecord> {
+    let properties = feature.get("properties")?;
+    let notam_id = text(feature.get("id"))
+        .or_else(|| text(properties.get("notam_id")))
+        .or_else(|| text(properties.get("NOTAM_KEY")))?;
+    let altitude = |feet: &str, meters: &str| {
+        number(properties.get(feet))
+            .map(|feet| feet * FEET_TO_M)
+            .or_else(|| number(properties.get(meters)))
+    };
+    Some(TfrRecord {
+        name: text(properties.get("name"))
+            .or_else(|| text(properties.get("TITLE")))
+            .unwrap_or_else(|| format!("TFR {notam_id}")),
+        lower_m: altitude("lower_ft", "lower_altitude_m").unwrap_or(0.0),
+        upper_m: altitude("upper_ft", "upper_altitude_m").unwrap_or(MAX_CEILING_M),
+        start: time(properties.get("effective_start")),
+        end: time(properties.get("effective_end")),
+        geometry: feature.get("geometry")?.clone(),
+        notam_id,
+    })
+}
+
+fn notam(item: &Value) -> Option<&Value> {
+    item.get("properties")?.get("coreNOTAMData")?.get("notam")
+}
+
+fn is_tfr_notam(item: &Value) -> bool {
+    let Some(notam) = notam(item) else {
+        return false;
+    };
+    text(notam.get("selectionCode")).is_some_and(|code| code.starts_with("QRT"))
+        || text(notam.get("text")).is_some_and(|text| {
+            text.to_ascii_uppercase()
+                .contains("TEMPORARY FLIGHT RESTRICTION")
+        })
+}
+
+fn notam_record(item: &Value) -> Option<TfrRecord> {
+    if !is_tfr_notam(item) {
+        return None;
+    }
+    let notam = notam(item)?;
+    let notam_id = text(notam.get("number")).or_else(|| text(notam.get("id")))?;
+    let flight_level = |field: &str| number(notam.get(field)).map(|fl| fl * 100.0 * FEET_TO_M);
+    let name = match text(notam.get("location")) {
+        Some(location) => format!("TFR {notam_id} ({location})"),
+        None => format!("TFR {notam_id}"),
+    };
+    Some(TfrRecord {
+        name,
+        lower_m: flight_level("minimumFL").unwrap_or(0.0),
+        upper_m: flight_level("maximumFL").unwrap_or(MAX_CEILING_M),
+        start: time(notam.get("effectiveStart")),
+        end: time(notam.get("effectiveEnd")),
+        geometry: item.get("geometry")?.clone(),
+        notam_id,
+    })
+}
+
+/// One geofence per polygon of the restriction, or `None` when it has no usable polygon.
+/// Restrictions that have already ended yield none.
+fn to_geofences(record: TfrRecord, now: DateTime<Utc>) -> Option<Vec<Geofence>> {
+    if record.end.is_some_and(|end| end <= now) {
+        return Some(Vec::new());
+    }
+    let schedule = match (record.start, record.end) {
+        (Some(start), Some(end)) => Some(GeofenceSchedule {
+            start,
+            end,
+            recurrence: None,
+            until: None,
+        }),
+        (None, Some(end)) => Some(GeofenceSchedule {
+            start: now,
+            end,
+            recurrence: None,
+            until: None,
+        }),
+        (Some(start), None) if start > now => Some(GeofenceSchedule {
+            start,
+            end: start + ChronoDuration::days(OPEN_ENDED_WINDOW_DAYS),
+            recurrence: None,
+            until: None,
+        }),
+        _ => None,
+    };
+
+    let mut polygons = Vec::new();
+    collect_polygons(&record.geometry, &mut polygons);
+    if polygons.is_empty() {
+        return None;
+    }
+    let lower_m = record.lower_m.clamp(0.0, MAX_CEILING_M);
+    let upper_m = record.upper_m.clamp(0.0, MAX_CEILING_M);
+    let single = polygons.len() == 1;
+    let geofences: Vec<Geofence> = polygons
+        .into_iter()
+        .enumerate()
+        .map(|(index, polygon)| Geofence {
+            id: if single {
+                format!("{TFR_SOURCE}:{}", record.notam_id)
+            } else {
+                format!("{TFR_SOURCE}:{}#{}", record.notam_id, index + 1)
+            },
+            name: record.name.clone(),
+            geofence_type: GeofenceType::TemporaryRestriction,
+            polygon,
+            lower_altitude_m: lower_m.min(upper_m),
+            upper_altitude_m: upper_m.max(lower_m),
+            active: true,
+            created_at: now,
+            breach_response: None,
+            schedule: schedule.clone(),
+            org_id: None,
+        })
+        .filter(Geofence::is_valid)
+        .collect();
+    (!geofences.is_empty()).then_some(geofences)
+}
+
+/// Rings of every polygon in a Polygon, MultiPolygon or GeometryCollection.
+fn collect_polygons(geometry: &Value, polygons: &mut Vec<Vec<[f64; 2]>>) {
+    match geometry.get("type").and_then(Value::as_str) {
+        Some("GeometryCollection") => {
+            for part in geometry
+                .get("geometries")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                collect_polygons(part, polygons);
+            }
+        }
+        Some("MultiPolygon") => {
+            for rings in geometry
+                .get("coordinates")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                // Holes are dropped: the outer ring is the conservative restriction.
+                let outer = rings.get(0).cloned().unwrap_or(Value::Null);
+                collect_polygons(
+                    &json!({ "type": "Polygon", "coordinates": [outer] }),
+                    polygons,
+                );
+            }
+        }
+        Some("Polygon") => {
+            let outer = geometry
+                .get("coordinates")
+                .and_then(|rings| rings.get(0))
+                .cloned()
+                .unwrap_or(Value::Null);
+            if let Ok(polygon) =
+                polygon_from_geometry(&json!({ "type": "Polygon", "coordinates": [outer] }))
+            {
+                polygons.push(polygon);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(text) if !text.trim().is_empty() => Some(text.trim().to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+    .filter(|value: &f64| value.is_finite())
+}
+
+/// RFC 3339 time; `PERM` and anything else unparseable is open-ended.
+fn time(value: Option<&Value>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?.as_str()?.trim())
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn square(lon: f64, lat: f64) -> Value {
+        json!([[
+            [lon, lat],
+            [lon + 0.01, lat],
+            [lon + 0.01, lat + 0.01],
+            [lon, lat + 0.01],
+            [lon, lat]
+        ]])
+    }
+
+    #[test]
+    fn geojson_feed_becomes_scheduled_restrictions() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+        let feed = json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Polygon", "coordinates": square(-117.83, 33.68) },
+                    "properties": {
+                        "notam_id": "6/1234",
+                        "name": "Stadium TFR",
+                        "lower_ft": 0,
+                        "upper_ft": "3000",
+                        "effective_start": "2026-10-17T18:00:00Z",
+                        "effective_end": "2026-10-17T22:00:00Z"
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Polygon", "coordinates": square(-117.9, 33.7) },
+                    "properties": {
+                        "notam_id": "6/0001",
+                        "effective_end": "2026-10-16T00:00:00Z"
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [-117.9, 33.7] },
+                    "properties": { "notam_id": "6/0002" }
+                }
+            ]
+        });
+        let feed = parse_feed(TfrFeedFormat::GeoJson, &feed, now).unwrap();
+        // The ended TFR is dropped quietly; the point cannot be a geofence.
+        assert_eq!(feed.skipped, 1);
+        assert_eq!(feed.geofences.len(), 1);
+        let stadium = &feed.geofences[0];
+        assert_eq!(stadium.id, "tfr:6/1234");
+        assert_eq!(stadium.name, "Stadium TFR");
+        assert_eq!(stadium.geofence_type, GeofenceType::TemporaryRestriction);
+        assert!((stadium.upper_altitude_m - 914.4).abs() < 1e-6);
+        assert!(!stadium.in_force_at(now));
+        assert!(stadium.in_force_at(Utc.with_ymd_and_hms(2026, 10, 17, 19, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn faa_notams_keep_only_tfrs_and_split_areas() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+        let feed = json!({
+            "items": [
+                {
+                    "type": "Feature",
+                    "properties": { "coreNOTAMData": { "notam": {
+                        "id": "NOTAM_1",
+                        "number": "6/5678",
+                        "location": "ZLA",
+                        "selectionCode": "QRTCA",
+                        "minimumFL": "000",
+                        "maximumFL": "999",
+                        "effectiveStart": "2026-10-17T00:00:00.000Z",
+                        "effectiveEnd": "PERM"
+                    }}},
+                    "geometry": {
+                        "type": "GeometryCollection",
+                        "geometries": [
+                            { "type": "Point", "coordinates": [-117.8, 33.6] },
+                            {
+                                "type": "MultiPolygon",
+                                "coordinates": [square(-117.83, 33.68), square(-117.8, 33.7)]
+                            }
+                        ]
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "properties": { "coreNOTAMData": { "notam": {
+                        "number": "6/9999",
+                        "selectionCode": "QMRLC",
+                        "text": "RWY 07/25 CLSD"
+                    }}},
+                    "geometry": { "type": "Polygon", "coordinates": square(-117.9, 33.7) }
+                }
+            ]
+        });
+        let feed = parse_feed(TfrFeedFormat::FaaNotam, &feed, now).unwrap();
+        assert_eq!(feed.skipped, 0);
+        let ids: Vec<&str> = feed.geofences.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(ids, ["tfr:6/5678#1", "tfr:6/5678#2"]);
+        let area = &feed.geofences[0];
+        assert_eq!(area.name, "TFR 6/5678 (ZLA)");
+        assert_eq!(area.lower_altitude_m, 0.0);
+        assert_eq!(area.upper_altitude_m, MAX_CEILING_M);
+        // Already in force and permanent: no window.
+        assert!(area.schedule.is_none());
+
+        assert!(parse_feed(TfrFeedFormat::FaaNotam, &json!({ "features": [] }), now).is_err());
+    }
+}
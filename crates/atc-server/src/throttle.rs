@@ -0,0 +1,262 @@
+//! Budgets for automatically issued commands.
+//!
+//! A detector misconfiguration can flood the fleet with REROUTEs. Commands issued by automation
+//! (conflict resolutions, breach, conformance and tether responses, failsafe holds and replans)
+//! draw a token from a global bucket and from the bucket of the airspace sector the drone is in;
+//! drones outside every sector share one bucket. When either bucket is empty the command is
+//! withheld and the drone gets a DAA advisory (source `throttle`) instead. Each time a budget
+//! trips an error is logged, and trips and withheld commands are served at
+//! `GET /v1/admin/command-budget`.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use atc_core::models::CommandType;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Region of drones outside every configured sector.
+pub const UNSECTORED: &str = "unsectored";
+
+/// Automatic commands allowed per minute; 0 leaves a scope unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, ToSchema)]
+pub struct CommandBudget {
+    /// Across the whole fleet.
+    pub global_per_min: u32,
+    /// Per airspace sector.
+    pub region_per_min: u32,
+}
+
+/// Which budget withheld a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetScope {
+    Global,
+    Region(String),
+}
+
+/// A token bucket holding up to a minute's budget, refilled continuously.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+    tripped: bool,
+    trips: u64,
+    withheld: u64,
+}
+
+impl TokenBucket {
+    fn new(per_min: u32, now: Instant) -> Self {
+        Self {
+            tokens: per_min as f64,
+            refilled_at: now,
+            tripped: false,
+            trips: 0,
+            withheld: 0,
+        }
+    }
+
+    fn refill(&mut self, per_min: u32, now: Instant) {
+        let elapsed_s = now
+            .saturating_duration_since(self.refilled_at)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed_s * per_min as f64 / 60.0).min(per_min as f64);
+        self.refilled_at = now;
+    }
+
+    fn has_token(&self, per_min: u32) -> bool {
+        per_min == 0 || self.tokens >= 1.0
+    }
+
+    fn take(&mut self, per_min: u32) {
+        if per_min > 0 {
+            self.tokens -= 1.0;
+        }
+        self.tripped = false;
+    }
+
+    /// Count a withheld command; true when this trips the budget.
+    fn withhold(&mut self) -> bool {
+        self.withheld += 1;
+        let trips = !self.tripped;
+        if trips {
+            self.tripped = true;
+            self.trips += 1;
+        }
+        trips
+    }
+
+    fn status(&self, scope: &str) -> BudgetStatus {
+        BudgetStatus {
+            scope: scope.to_string(),
+            tokens: self.tokens.floor() as u32,
+            tripped: self.tripped,
+            trips: self.trips,
+            withheld: self.withheld,
+        }
+    }
+}
+
+/// Where one budget stands.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct BudgetStatus {
+    /// `global` or the sector ID.
+    pub scope: String,
+    /// Commands that may be issued right now.
+    pub tokens: u32,
+    /// Whether the budget is exhausted and commands are being withheld.
+    pub tripped: bool,
+    /// Times the budget has been exhausted since the server started.
+    pub trips: u64,
+    /// Commands withheld by this budget since the server started.
+    pub withheld: u64,
+}
+
+/// Configured budget and the state of every bucket.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CommandBudgetStatus {
+    pub budget: CommandBudget,
+    pub global: BudgetStatus,
+    /// Per region, sorted by region.
+    pub regions: Vec<BudgetStatus>,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    global: TokenBucket,
+    regions: HashMap<String, TokenBucket>,
+}
+
+/// Global and per-region automatic-command budgets.
+#[derive(Debug)]
+pub struct CommandThrottle {
+    budget: CommandBudget,
+    buckets: Mutex<Buckets>,
+}
+
+impl CommandThrottle {
+    pub fn new(budget: CommandBudget) -> Self {
+        Self {
+            budget,
+            buckets: Mutex::new(Buckets {
+                global: TokenBucket::new(budget.global_per_min, Instant::now()),
+                regions: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Take a token for an automatic command in `region`, or report which budget is exhausted.
+    /// Tokens are only taken when both budgets have one.
+    pub fn try_acquire(&self, region: &str, now: Instant) -> Result<(), BudgetScope> {
+        let CommandBudget {
+            global_per_min,
+            region_per_min,
+        } = self.budget;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let Buckets { global, regions } = &mut *buckets;
+        let regional = regions
+            .entry(region.to_string())
+            .or_insert_with(|| TokenBucket::new(region_per_min, now));
+        global.refill(global_per_min, now);
+        regional.refill(region_per_min, now);
+
+        if !global.has_token(global_per_min) {
+            if global.withhold() {
+                tracing::error!(
+                    "Global automatic command budget ({}/min) exhausted; falling back to advisories",
+                    global_per_min
+                );
+            }
+            return Err(BudgetScope::Global);
+        }
+        if !regional.has_token(region_per_min) {
+            if regional.withhold() {
+                tracing::error!(
+                    "Automatic command budget for region {} ({}/min) exhausted; falling back to advisories",
+                    region,
+                    region_per_min
+                );
+            }
+            return Err(BudgetScope::Region(region.to_string()));
+        }
+        global.take(global_per_min);
+        regional.take(region_per_min);
+        Ok(())
+    }
+
+    pub fn status(&self, now: Instant) -> CommandBudgetStatus {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        buckets.global.refill(self.budget.global_per_min, now);
+        let mut regions: Vec<BudgetStatus> = buckets
+            .regions
+            .iter_mut()
+            .map(|(region, bucket)| {
+                bucket.refill(self.budget.region_per_min, now);
+                bucket.status(region)
+            })
+            .collect();
+        regions.sort_by(|a, b| a.scope.cmp(&b.scope));
+        CommandBudgetStatus {
+            budget: self.budget,
+            global: buckets.global.status("global"),
+            regions,
+        }
+    }
+}
+
+/// Advisory action naming a command, e.g. `reroute`.
+pub fn command_action(command: &CommandType) -> &'static str {
+    match command {
+        CommandType::Hold { .. } => "hold",
+        CommandType::AltitudeChange { .. } => "altitude_change",
+        CommandType::Reroute { .. } => "reroute",
+        CommandType::Resume => "resume",
+        CommandType::Land => "land",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn budgets_trip_once_and_refill_over_time() {
+        let throttle = CommandThrottle::new(CommandBudget {
+            global_per_min: 3,
+            region_per_min: 2,
+        });
+        let t0 = Instant::now();
+
+        assert_eq!(throttle.try_acquire("north", t0), Ok(()));
+        assert_eq!(throttle.try_acquire("north", t0), Ok(()));
+        for _ in 0..2 {
+            assert_eq!(
+                throttle.try_acquire("north", t0),
+                Err(BudgetScope::Region("north".to_string()))
+            );
+        }
+        // A withheld regional command leaves the global budget untouched.
+        assert_eq!(throttle.try_acquire("south", t0), Ok(()));
+        assert_eq!(throttle.try_acquire("south", t0), Err(BudgetScope::Global));
+
+        let status = throttle.status(t0);
+        assert!(status.global.tripped);
+        assert_eq!((status.global.trips, status.global.withheld), (1, 1));
+        let north = &status.regions[0];
+        assert_eq!(north.scope, "north");
+        assert_eq!((north.tokens, north.trips, north.withheld), (0, 1, 2));
+
+        // Half a minute refills one and a half global tokens and one regional token.
+        let later = t0 + Duration::from_secs(30);
+        assert_eq!(throttle.try_acquire("north", later), Ok(()));
+        let status = throttle.status(later);
+        assert!(!status.global.tripped && !status.regions[0].tripped);
+        assert_eq!(status.global.trips, 1);
+
+        let unlimited = CommandThrottle::new(CommandBudget::default());
+        for _ in 0..100 {
+            assert_eq!(unlimited.try_acquire(UNSECTORED, t0), Ok(()));
+        }
+    }
+}
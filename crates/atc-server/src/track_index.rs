@@ -0,0 +1,156 @@
+//! Grid index of drone and traffic positions for bounding-box queries.
+//!
+//! Map clients only want the tracks in their viewport. Positions are bucketed into fixed
+//! lat/lon cells, so a box query visits the cells it overlaps instead of every track. The index
+//! only remembers which cell each track was last seen in; callers recheck the exact position of
+//! the candidates it returns.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+use atc_core::MsaBounds;
+
+/// Cells are this many degrees across (about 1.1 km north-south).
+const CELL_DEG: f64 = 0.01;
+
+type Cell = (i64, i64);
+
+fn cell_index(deg: f64) -> i64 {
+    (deg / CELL_DEG).floor() as i64
+}
+
+#[derive(Debug, Default)]
+struct Grid {
+    cells: HashMap<Cell, HashSet<String>>,
+    positions: HashMap<String, Cell>,
+}
+
+impl Grid {
+    fn remove(&mut self, id: &str) {
+        let Some(cell) = self.positions.remove(id) else {
+            return;
+        };
+        if let Some(ids) = self.cells.get_mut(&cell) {
+            ids.remove(id);
+            if ids.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+}
+
+/// Track IDs by grid cell.
+#[derive(Debug, Default)]
+pub struct TrackIndex {
+    grid: RwLock<Grid>,
+}
+
+impl TrackIndex {
+    /// Record a track's latest position.
+    pub fn upsert(&self, id: &str, lat: f64, lon: f64) {
+        let cell = (cell_index(lat), cell_index(lon));
+        let Ok(mut grid) = self.grid.write() else {
+            return;
+        };
+        if grid.positions.get(id) == Some(&cell) {
+            return;
+        }
+        grid.remove(id);
+        grid.positions.insert(id.to_string(), cell);
+        grid.cells.entry(cell).or_default().insert(id.to_string());
+    }
+
+    pub fn remove(&self, id: &str) {
+        if let Ok(mut grid) = self.grid.write() {
+            grid.remove(id);
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut grid) = self.grid.write() {
+            *grid = Grid::default();
+        }
+    }
+
+    /// IDs of the tracks in cells overlapping `bounds`; some may lie just outside it.
+    pub fn candidates(&self, bounds: &MsaBounds) -> Vec<String> {
+        let Ok(grid) = self.grid.read() else {
+            return Vec::new();
+        };
+        let lat_range = cell_index(bounds.min_lat)..=cell_index(bounds.max_lat);
+        let lon_range = cell_index(bounds.min_lon)..=cell_index(bounds.max_lon);
+        let covered = (lat_range.end() - lat_range.start() + 1) as u128
+            * (lon_range.end() - lon_range.start() + 1) as u128;
+
+        // A box wider than the occupied area is cheaper to answer from the occupied cells.
+        if covered > grid.cells.len() as u128 {
+            return grid
+                .cells
+                .iter()
+                .filter(|((lat, lon), _)| lat_range.contains(lat) && lon_range.contains(lon))
+                .flat_map(|(_, ids)| ids.iter().cloned())
+                .collect();
+        }
+        let mut ids = Vec::new();
+        for lat in lat_range {
+            for lon in lon_range.clone() {
+                if let Some(cell) = grid.cells.get(&(lat, lon)) {
+                    ids.extend(cell.iter().cloned());
+                }
+            }
+        }
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> MsaBounds {
+        MsaBounds {
+            min_lat,
+            min_lon,
+            max_lat,
+            max_lon,
+        }
+    }
+
+    fn sorted(mut ids: Vec<String>) -> Vec<String> {
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn candidates_follow_moves_and_removals() {
+        let index = TrackIndex::default();
+        index.upsert("A", 33.6846, -117.8265);
+        index.upsert("B", 33.7000, -117.8000);
+        index.upsert("C", 34.0522, -118.2437);
+
+        let irvine = bounds(33.68, -117.83, 33.71, -117.79);
+        assert_eq!(sorted(index.candidates(&irvine)), vec!["A", "B"]);
+
+        index.upsert("B", 34.0530, -118.2440);
+        assert_eq!(index.candidates(&irvine), vec!["A"]);
+        let los_angeles = bounds(34.0, -118.3, 34.1, -118.2);
+        assert_eq!(sorted(index.candidates(&los_angeles)), vec!["B", "C"]);
+
+        index.remove("C");
+        assert_eq!(index.candidates(&los_angeles), vec!["B"]);
+        index.clear();
+        assert!(index.candidates(&los_angeles).is_empty());
+    }
+
+    #[test]
+    fn world_sized_box_scans_occupied_cells() {
+        let index = TrackIndex::default();
+        index.upsert("A", 33.6846, -117.8265);
+        index.upsert("B", -33.8688, 151.2093);
+
+        let world = bounds(-90.0, -180.0, 90.0, 180.0);
+        assert_eq!(sorted(index.candidates(&world)), vec!["A", "B"]);
+        let southern = bounds(-90.0, -180.0, 0.0, 180.0);
+        assert_eq!(index.candidates(&southern), vec!["B"]);
+    }
+}
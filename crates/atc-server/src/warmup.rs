@@ -0,0 +1,412 @@
+//! Planner cache warmup for configured operating areas.
+//!
+//! A cold route plan waits on Overpass for every obstacle tile along its corridor and on the
+//! elevation API for its terrain grid, which can take tens of seconds. The warmup job fetches
+//! both for each operating area (`ATC_OPERATING_AREAS_PATH`) ahead of time: obstacles tile by tile
+//! into the same cache the segmented planner reads, and terrain as overlapping area grids that
+//! serve any route grid they contain. It runs as a background job (see [`crate::jobs`]) queued
+//! at startup and on demand from `POST /v1/admin/planner/warmup`, and when `ATC_PLANNER_WARMUP_SNAPSHOT_PATH` is set the warmed
+//! caches are saved there and restored on the next start, so a restart does not go cold.
+
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use atc_core::spatial::{meters_per_deg_lat, meters_per_deg_lon};
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::cache::SnapshotEntry;
+use crate::compliance::{export_obstacle_cache, restore_obstacle_cache, Bounds, ObstacleAnalysis};
+use crate::config::Config;
+use crate::jobs::{self, JobKind, JobQueue};
+use crate::obstacle_index;
+use crate::state::AppState;
+use crate::terrain::{self, TerrainGrid};
+
+/// Neighbouring terrain chunks overlap by this much so short routes near a seam fit in one.
+const CHUNK_OVERLAP_M: f64 = 1000.0;
+/// Chunks are sized this far under the grid point limit to absorb rounding.
+const CHUNK_MARGIN: f64 = 0.95;
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// A rectangular area the planner should keep warm.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatingArea {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+}
+
+impl OperatingArea {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.id.trim().is_empty() {
+            errors.push("id must not be empty".to_string());
+        }
+        let lat_ok =
+            (-90.0..=90.0).contains(&self.min_lat) && (-90.0..=90.0).contains(&self.max_lat);
+        let lon_ok =
+            (-180.0..=180.0).contains(&self.min_lon) && (-180.0..=180.0).contains(&self.max_lon);
+        if !lat_ok || !lon_ok {
+            errors.push("bounds must be valid latitudes and longitudes".to_string());
+        } else if self.min_lat >= self.max_lat || self.min_lon >= self.max_lon {
+            errors.push("min_lat/min_lon must be below max_lat/max_lon".to_string());
+        }
+        errors
+    }
+
+    fn bounds(&self) -> Bounds {
+        Bounds {
+            min_lat: self.min_lat,
+            max_lat: self.max_lat,
+            min_lon: self.min_lon,
+            max_lon: self.max_lon,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupTrigger {
+    Startup,
+    Admin,
+}
+
+/// What the warmup fetched for one operating area.
+#[derive(Debug, Clone, Serialize)]
+pub struct AreaWarmup {
+    pub area_id: String,
+    pub obstacle_tiles: usize,
+    pub obstacle_tiles_failed: usize,
+    pub terrain_chunks: usize,
+    pub terrain_chunks_failed: usize,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupReport {
+    pub trigger: WarmupTrigger,
+    pub started_at: DateTime<Utc>,
+    /// Unset while the warmup is still running.
+    pub finished_at: Option<DateTime<Utc>>,
+    pub areas: Vec<AreaWarmup>,
+    pub snapshot_saved: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupStatus {
+    pub running: bool,
+    pub operating_areas: usize,
+    pub last: Option<WarmupReport>,
+}
+
+/// Progress of the latest warmup job.
+#[derive(Debug, Default)]
+pub struct PlannerWarmup {
+    last: RwLock<Option<WarmupReport>>,
+}
+
+impl PlannerWarmup {
+    pub fn status(&self, config: &Config, jobs: &JobQueue) -> WarmupStatus {
+        WarmupStatus {
+            running: jobs.has_active(JobKind::PlannerWarmup),
+            operating_areas: config.operating_areas.len(),
+            last: self.last.read().ok().and_then(|last| last.clone()),
+        }
+    }
+
+    fn record(&self, report: &WarmupReport) {
+        if let Ok(mut last) = self.last.write() {
+            *last = Some(report.clone());
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WarmupJob {
+    trigger: WarmupTrigger,
+}
+
+/// Queue a warmup job; false when one is already queued or running.
+pub async fn enqueue(state: &Arc<AppState>, trigger: WarmupTrigger) -> bool {
+    jobs::enqueue_unique(state, JobKind::PlannerWarmup, json!({ "trigger": trigger }))
+        .await
+        .is_some()
+}
+
+/// Handler for [`JobKind::PlannerWarmup`]; fails, to be retried, when the snapshot could not be
+/// saved.
+pub(crate) async fn run_job(state: &AppState, payload: &Value) -> Result<(), String> {
+    let job: WarmupJob = serde_json::from_value(payload.clone())
+        .map_err(|err| format!("invalid warmup job payload: {}", err))?;
+    run(state, job.trigger).await
+}
+
+async fn run(state: &AppState, trigger: WarmupTrigger) -> Result<(), String> {
+    let config = state.config();
+    let warmup = state.planner_warmup();
+    let client = Client::new();
+    let mut report = WarmupReport {
+        trigger,
+        started_at: Utc::now(),
+        finished_at: None,
+        areas: Vec::new(),
+        snapshot_saved: false,
+    };
+    warmup.record(&report);
+    tracing::info!(
+        areas = config.operating_areas.len(),
+        ?trigger,
+        "Planner warmup start"
+    );
+
+    for area in &config.operating_areas {
+        let area_warmup = warm_area(&client, config, area).await;
+        tracing::info!(
+            area_id = %area_warmup.area_id,
+            obstacle_tiles = area_warmup.obstacle_tiles,
+            obstacle_tiles_failed = area_warmup.obstacle_tiles_failed,
+            terrain_chunks = area_warmup.terrain_chunks,
+            terrain_chunks_failed = area_warmup.terrain_chunks_failed,
+            elapsed_ms = area_warmup.elapsed_ms,
+            "Planner warmup area complete"
+        );
+        report.areas.push(area_warmup);
+        warmup.record(&report);
+    }
+
+    let mut snapshot_error = None;
+    if let Some(path) = config.planner_warmup_snapshot_path.clone() {
+        let snapshot = WarmupSnapshot {
+            version: SNAPSHOT_FORMAT_VERSION,
+            obstacles: export_obstacle_cache(config),
+            terrain: terrain::export_terrain_cache(config),
+        };
+        let result =
+            tokio::task::spawn_blocking(move || write_snapshot(Path::new(&path), &snapshot)).await;
+        match result {
+            Ok(Ok(())) => report.snapshot_saved = true,
+            Ok(Err(err)) => {
+                snapshot_error = Some(format!("failed to save planner warmup snapshot: {}", err))
+            }
+            Err(err) => {
+                snapshot_error = Some(format!("planner warmup snapshot task failed: {}", err))
+            }
+        }
+    }
+
+    report.finished_at = Some(Utc::now());
+    warmup.record(&report);
+    tracing::info!(
+        snapshot_saved = report.snapshot_saved,
+        "Planner warmup complete"
+    );
+    snapshot_error.map_or(Ok(()), Err)
+}
+
+async fn warm_area(client: &Client, config: &Config, area: &OperatingArea) -> AreaWarmup {
+    let started_at = Instant::now();
+    let bounds = area.bounds();
+    let obstacles = obstacle_index::prefetch_area(
+        client,
+        config,
+        &bounds,
+        config.compliance_default_clearance_m,
+    )
+    .await;
+
+    let mut warmed_chunks = 0;
+    let mut failed_chunks = 0;
+    if !config.terrain_provider_url.trim().is_empty() {
+        for chunk in terrain_chunks(
+            &bounds,
+            config.terrain_sample_spacing_m,
+            config.terrain_max_grid_points,
+        ) {
+            match terrain::prefetch_terrain_area(client, config, &chunk).await {
+                Ok(Some(_)) => warmed_chunks += 1,
+                Ok(None) => {}
+                Err(err) => {
+                    tracing::warn!(area_id = %area.id, "Terrain prefetch failed: {}", err);
+                    failed_chunks += 1;
+                }
+            }
+        }
+    }
+
+    AreaWarmup {
+        area_id: area.id.clone(),
+        obstacle_tiles: obstacles.tiles,
+        obstacle_tiles_failed: obstacles.failed,
+        terrain_chunks: warmed_chunks,
+        terrain_chunks_failed: failed_chunks,
+        elapsed_ms: started_at.elapsed().as_millis() as u64,
+    }
+}
+
+/// Split `area` into overlapping chunks that each fit in one terrain grid at `spacing_m`.
+fn terrain_chunks(area: &Bounds, spacing_m: f64, max_grid_points: usize) -> Vec<Bounds> {
+    // Same floors as the terrain fetch applies.
+    let spacing_m = spacing_m.max(5.0);
+    let points_per_side = (max_grid_points.max(1000) as f64).sqrt().floor();
+    let side_m = spacing_m * (points_per_side - 1.0) * CHUNK_MARGIN;
+    let overlap_m = CHUNK_OVERLAP_M.min(side_m / 4.0);
+
+    // Degrees of longitude are widest nearest the equator, so size chunks there.
+    let widest_lat = if area.min_lat <= 0.0 && area.max_lat >= 0.0 {
+        0.0
+    } else {
+        area.min_lat.abs().min(area.max_lat.abs())
+    };
+    let lat_m = meters_per_deg_lat(widest_lat);
+    let lon_m = meters_per_deg_lon(widest_lat).max(1.0);
+
+    let lat_spans = spans(
+        area.min_lat,
+        area.max_lat,
+        side_m / lat_m,
+        overlap_m / lat_m,
+    );
+    let lon_spans = spans(
+        area.min_lon,
+        area.max_lon,
+        side_m / lon_m,
+        overlap_m / lon_m,
+    );
+    let mut chunks = Vec::with_capacity(lat_spans.len() * lon_spans.len());
+    for &(min_lat, max_lat) in &lat_spans {
+        for &(min_lon, max_lon) in &lon_spans {
+            chunks.push(Bounds {
+                min_lat,
+                max_lat,
+                min_lon,
+                max_lon,
+            });
+        }
+    }
+    chunks
+}
+
+/// Ranges of at most `size` covering `min..=max`, each overlapping the previous by `overlap`.
+fn spans(min: f64, max: f64, size: f64, overlap: f64) -> Vec<(f64, f64)> {
+    let mut spans = Vec::new();
+    let mut start = min;
+    loop {
+        let end = (start + size).min(max);
+        spans.push((start, end));
+        if end >= max {
+            return spans;
+        }
+        start = end - overlap;
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WarmupSnapshot {
+    version: u32,
+    obstacles: Vec<SnapshotEntry<ObstacleAnalysis>>,
+    terrain: Vec<SnapshotEntry<TerrainGrid>>,
+}
+
+fn write_snapshot(path: &Path, snapshot: &WarmupSnapshot) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let contents = serde_json::to_vec(snapshot).map_err(std::io::Error::other)?;
+    // Write then rename so a crash mid-write leaves the previous snapshot intact.
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Load the saved planner caches, skipping entries too old to be used.
+pub fn restore_snapshot(config: &Config) {
+    let Some(path) = config.planner_warmup_snapshot_path.as_deref() else {
+        return;
+    };
+    let contents = match std::fs::read(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return,
+        Err(err) => {
+            tracing::warn!("Failed to read planner warmup snapshot {}: {}", path, err);
+            return;
+        }
+    };
+    let snapshot = match serde_json::from_slice::<WarmupSnapshot>(&contents) {
+        Ok(snapshot) if snapshot.version == SNAPSHOT_FORMAT_VERSION => snapshot,
+        Ok(snapshot) => {
+            tracing::warn!(
+                "Ignoring planner warmup snapshot {} with format version {}",
+                path,
+                snapshot.version
+            );
+            return;
+        }
+        Err(err) => {
+            tracing::warn!(
+                "Ignoring unreadable planner warmup snapshot {}: {}",
+                path,
+                err
+            );
+            return;
+        }
+    };
+    let obstacles = restore_obstacle_cache(config, snapshot.obstacles);
+    let terrain = terrain::restore_terrain_cache(config, snapshot.terrain);
+    tracing::info!(
+        obstacle_entries = obstacles,
+        terrain_grids = terrain,
+        "Restored planner warmup snapshot"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terrain_chunks_cover_the_area_within_the_grid_limit() {
+        let area = Bounds {
+            min_lat: 32.60,
+            max_lat: 32.80,
+            min_lon: -117.30,
+            max_lon: -117.00,
+        };
+        let (spacing_m, max_grid_points) = (30.0, 50_000);
+        let chunks = terrain_chunks(&area, spacing_m, max_grid_points);
+        assert!(chunks.len() > 1);
+
+        for chunk in &chunks {
+            let mean_lat = (chunk.min_lat + chunk.max_lat) / 2.0;
+            let lat_step = spacing_m / meters_per_deg_lat(mean_lat);
+            let lon_step = spacing_m / meters_per_deg_lon(mean_lat);
+            let rows = ((chunk.max_lat - chunk.min_lat) / lat_step).ceil() as usize + 1;
+            let cols = ((chunk.max_lon - chunk.min_lon) / lon_step).ceil() as usize + 1;
+            assert!(rows * cols <= max_grid_points, "{rows}x{cols}");
+        }
+
+        // Every corner of the area and the chunk seams fall inside some chunk.
+        let inside = |lat: f64, lon: f64| {
+            chunks.iter().any(|chunk| {
+                (chunk.min_lat..=chunk.max_lat).contains(&lat)
+                    && (chunk.min_lon..=chunk.max_lon).contains(&lon)
+            })
+        };
+        for chunk in &chunks {
+            assert!(inside(chunk.max_lat, chunk.max_lon));
+            assert!(inside(chunk.min_lat, chunk.min_lon));
+        }
+        assert!(inside(area.max_lat, area.max_lon));
+        assert!(inside(area.min_lat, area.min_lon));
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.min_lat >= area.min_lat && chunk.max_lon <= area.max_lon));
+    }
+}
@@ -0,0 +1,236 @@
+//! Outbound webhooks for flight plan, conflict, command and drone events.
+//!
+//! Operators register callback URLs with `POST /v1/admin/webhooks`, optionally limited to some
+//! event types and to the drones of one owner. Each event is fanned out as one background job
+//! per matching webhook ([`crate::jobs`]), so a delivery survives a restart once it has started,
+//! is retried with exponential backoff while the endpoint fails, and is kept as a dead letter
+//! (`GET /v1/admin/jobs?status=dead&kind=webhook_delivery`) when every attempt failed.
+//!
+//! Each POST carries the event as JSON with `X-ATC-Event` (the event type), `X-ATC-Delivery`
+//! (the event ID, the same on every retry) and `X-ATC-Signature: sha256=<hex>`, the
+//! HMAC-SHA256 of the body under the webhook's secret.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::jobs::{self, JobKind};
+use crate::state::AppState;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    PlanApproved,
+    PlanRejected,
+    PlanActivated,
+    PlanCompleted,
+    ConflictDetected,
+    /// The conflict cleared; `outcome` says whether the pair separated or a drone stopped
+    /// being tracked.
+    ConflictResolved,
+    CommandIssued,
+    CommandAcked,
+    DroneLost,
+    /// A nonconforming drone reached a `notify` step of the conformance escalation policy.
+    ConformanceEscalated,
+}
+
+impl WebhookEventType {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::PlanApproved => "plan_approved",
+            Self::PlanRejected => "plan_rejected",
+            Self::PlanActivated => "plan_activated",
+            Self::PlanCompleted => "plan_completed",
+            Self::ConflictDetected => "conflict_detected",
+            Self::ConflictResolved => "conflict_resolved",
+            Self::CommandIssued => "command_issued",
+            Self::CommandAcked => "command_acked",
+            Self::DroneLost => "drone_lost",
+            Self::ConformanceEscalated => "conformance_escalated",
+        }
+    }
+}
+
+/// A registered callback URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    /// HMAC key for `X-ATC-Signature`; only returned when the webhook is created.
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// Event types to deliver; empty means all.
+    pub events: Vec<WebhookEventType>,
+    /// Only events for this owner's drones.
+    pub owner_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    fn wants(&self, event: &WebhookEvent, owners: &[Option<String>]) -> bool {
+        if !self.events.is_empty() && !self.events.contains(&event.event) {
+            return false;
+        }
+        match self.owner_id.as_deref() {
+            Some(owner_id) => owners
+                .iter()
+                .any(|owner| owner.as_deref() == Some(owner_id)),
+            None => true,
+        }
+    }
+}
+
+/// The body POSTed to a webhook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    pub event: WebhookEventType,
+    pub occurred_at: DateTime<Utc>,
+    /// Drones the event concerns (both for conflicts)
+    pub drone_ids: Vec<String>,
+    /// The plan, conflict, command or drone the event is about
+    pub data: Value,
+}
+
+/// Queue a delivery of an event to every webhook that wants it.
+pub(crate) fn publish(
+    state: &AppState,
+    event_type: WebhookEventType,
+    drone_ids: &[&str],
+    data: impl Serialize,
+) {
+    let webhooks = state.list_webhooks();
+    if webhooks.is_empty() {
+        return;
+    }
+    let event = WebhookEvent {
+        id: uuid::Uuid::new_v4().to_string(),
+        event: event_type,
+        occurred_at: Utc::now(),
+        drone_ids: drone_ids.iter().map(|id| id.to_string()).collect(),
+        data: serde_json::to_value(data).unwrap_or(Value::Null),
+    };
+    let owners: Vec<Option<String>> = drone_ids
+        .iter()
+        .map(|drone_id| state.drone_owner(drone_id))
+        .collect();
+    for webhook in webhooks
+        .iter()
+        .filter(|webhook| webhook.wants(&event, &owners))
+    {
+        jobs::enqueue_deferred(
+            state,
+            JobKind::WebhookDelivery,
+            json!({ "webhook_id": webhook.id, "event": event }),
+        );
+    }
+}
+
+/// `sha256=<hex HMAC-SHA256 of body>` under `secret`.
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Job handler: POST one event to one webhook.
+pub(crate) async fn run_job(state: &AppState, payload: &Value) -> Result<(), String> {
+    let webhook_id = payload
+        .get("webhook_id")
+        .and_then(Value::as_str)
+        .ok_or("payload has no webhook_id")?;
+    let event: WebhookEvent = payload
+        .get("event")
+        .cloned()
+        .ok_or("payload has no event")
+        .and_then(|event| serde_json::from_value(event).map_err(|_| "payload event is invalid"))?;
+    let Some(webhook) = state.get_webhook(webhook_id) else {
+        tracing::debug!(
+            webhook_id,
+            "Webhook removed; dropping delivery {}",
+            event.id
+        );
+        return Ok(());
+    };
+
+    let body = serde_json::to_vec(&event).map_err(|err| err.to_string())?;
+    let client = Client::builder()
+        .timeout(Duration::from_millis(state.config().webhook_timeout_ms))
+        .build()
+        .map_err(|err| err.to_string())?;
+    let response = client
+        .post(&webhook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-ATC-Event", event.event.as_str())
+        .header("X-ATC-Delivery", &event.id)
+        .header("X-ATC-Signature", signature(&webhook.secret, &body))
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| format!("POST {} failed: {}", webhook.url, err))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "POST {} returned {}",
+            webhook.url,
+            response.status()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(events: Vec<WebhookEventType>, owner_id: Option<&str>) -> Webhook {
+        Webhook {
+            id: "wh-1".to_string(),
+            url: "http://127.0.0.1:9/hook".to_string(),
+            secret: "secret".to_string(),
+            events,
+            owner_id: owner_id.map(str::to_string),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn event(event: WebhookEventType) -> WebhookEvent {
+        WebhookEvent {
+            id: "evt-1".to_string(),
+            event,
+            occurred_at: Utc::now(),
+            drone_ids: vec!["D1".to_string(), "D2".to_string()],
+            data: Value::Null,
+        }
+    }
+
+    #[test]
+    fn webhooks_filter_by_event_type_and_owner() {
+        let conflict = event(WebhookEventType::ConflictDetected);
+        let owners = [Some("acme".to_string()), None];
+
+        assert!(webhook(vec![], None).wants(&conflict, &owners));
+        assert!(webhook(vec![WebhookEventType::ConflictDetected], None).wants(&conflict, &owners));
+        assert!(!webhook(vec![WebhookEventType::DroneLost], None).wants(&conflict, &owners));
+        // Either drone of a conflict belonging to the owner is enough.
+        assert!(webhook(vec![], Some("acme")).wants(&conflict, &owners));
+        assert!(!webhook(vec![], Some("other")).wants(&conflict, &owners));
+    }
+
+    #[test]
+    fn signature_is_hex_hmac_of_body() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Forecast winds along planned routes.
+
+use atc_core::spatial::haversine_distance;
+use atc_core::wind::{WindField, WindSample};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::compliance::RoutePoint;
+use crate::config::Config;
+
+/// Upper bound on forecast locations per route, to keep the provider request small.
+const MAX_WIND_LOCATIONS: usize = 50;
+/// Heights above ground (m) the provider forecasts winds at.
+const WIND_HEIGHTS_M: [u32; 3] = [10, 80, 120];
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ForecastResponse {
+    Many(Vec<ForecastLocation>),
+    One(ForecastLocation),
+}
+
+#[derive(Debug, Deserialize)]
+struct ForecastLocation {
+    current: Option<CurrentWind>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CurrentWind {
+    wind_speed_10m: Option<f64>,
+    wind_direction_10m: Option<f64>,
+    wind_speed_80m: Option<f64>,
+    wind_direction_80m: Option<f64>,
+    wind_speed_120m: Option<f64>,
+    wind_direction_120m: Option<f64>,
+}
+
+impl CurrentWind {
+    fn at(&self, height_m: u32) -> Option<(f64, f64)> {
+        let (speed, direction) = match height_m {
+            10 => (self.wind_speed_10m, self.wind_direction_10m),
+            80 => (self.wind_speed_80m, self.wind_direction_80m),
+            120 => (self.wind_speed_120m, self.wind_direction_120m),
+            _ => return None,
+        };
+        Some((speed?, direction?)).filter(|(speed, direction)| {
+            speed.is_finite() && *speed >= 0.0 && direction.is_finite()
+        })
+    }
+}
+
+/// Fetch the current forecast wind at 10, 80 and 120 m above ground at locations every
+/// `route_planner_wind_spacing_m` along the route.
+pub async fn fetch_route_wind(
+    client: &Client,
+    config: &Config,
+    points: &[RoutePoint],
+) -> Result<WindField, String> {
+    let locations = wind_locations(points, config.route_planner_wind_spacing_m);
+    if locations.is_empty() {
+        return Ok(WindField::default());
+    }
+    let join = |values: Vec<String>| values.join(",");
+    let latitudes = join(locations.iter().map(|(lat, _)| lat.to_string()).collect());
+    let longitudes = join(locations.iter().map(|(_, lon)| lon.to_string()).collect());
+    let current = join(
+        WIND_HEIGHTS_M
+            .iter()
+            .map(|height| format!("wind_speed_{0}m,wind_direction_{0}m", height))
+            .collect(),
+    );
+    let response = client
+        .get(&config.compliance_weather_url)
+        .query(&[
+            ("latitude", latitudes),
+            ("longitude", longitudes),
+            ("current", current),
+            ("windspeed_unit", "ms".to_string()),
+            ("timezone", "UTC".to_string()),
+        ])
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if !response.status().is_success() {
+        return Err(format!("weather provider HTTP {}", response.status()));
+    }
+
+    let payload: ForecastResponse = response.json().await.map_err(|err| err.to_string())?;
+    wind_field(&locations, payload)
+}
+
+/// Route start, end and points every `spacing_m` in between, at most [`MAX_WIND_LOCATIONS`].
+fn wind_locations(points: &[RoutePoint], spacing_m: f64) -> Vec<(f64, f64)> {
+    let Some(first) = points.first() else {
+        return Vec::new();
+    };
+    let legs: Vec<f64> = points
+        .windows(2)
+        .map(|pair| haversine_distance(pair[0].lat, pair[0].lon, pair[1].lat, pair[1].lon))
+        .collect();
+    let total_m: f64 = legs.iter().sum();
+    let intervals =
+        ((total_m / spacing_m.max(1.0)).ceil() as usize).clamp(1, MAX_WIND_LOCATIONS - 1);
+
+    let mut locations = vec![(first.lat, first.lon)];
+    let mut leg = 0;
+    let mut leg_start_m = 0.0;
+    for interval in 1..=intervals {
+        let target_m = total_m * interval as f64 / intervals as f64;
+        while leg + 1 < legs.len() && leg_start_m + legs[leg] < target_m {
+            leg_start_m += legs[leg];
+            leg += 1;
+        }
+        let Some(leg_m) = legs.get(leg) else {
+            break;
+        };
+        let t = if *leg_m > 0.0 {
+            ((target_m - leg_start_m) / leg_m).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let (from, to) = (&points[leg], &points[leg + 1]);
+        locations.push((
+            from.lat + (to.lat - from.lat) * t,
+            from.lon + (to.lon - from.lon) * t,
+        ));
+    }
+    locations
+}
+
+fn wind_field(locations: &[(f64, f64)], payload: ForecastResponse) -> Result<WindField, String> {
+    let forecasts = match payload {
+        ForecastResponse::Many(forecasts) => forecasts,
+        ForecastResponse::One(forecast) => vec![forecast],
+    };
+    if forecasts.len() != locations.len() {
+        return Err(format!(
+            "weather provider returned {} forecasts for {} locations",
+            forecasts.len(),
+            locations.len()
+        ));
+    }
+    let mut samples = Vec::new();
+    for (&(lat, lon), forecast) in locations.iter().zip(forecasts) {
+        let current = forecast.current.unwrap_or_default();
+        for height_m in WIND_HEIGHTS_M {
+            if let Some((speed, direction)) = current.at(height_m) {
+                samples.push(WindSample::from_direction(
+                    lat,
+                    lon,
+                    Some(height_m as f64),
+                    speed,
+                    direction,
+                ));
+            }
+        }
+    }
+    Ok(WindField { samples })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_forecast_winds_along_the_route() {
+        let route = [
+            RoutePoint {
+                lat: 0.0,
+                lon: 0.0,
+                altitude_m: 60.0,
+            },
+            RoutePoint {
+                lat: 0.1,
+                lon: 0.0,
+                altitude_m: 60.0,
+            },
+        ];
+        // ~11 km every 5 km: start, end and two points between.
+        let locations = wind_locations(&route, 5_000.0);
+        assert_eq!(locations.len(), 4);
+        assert!((locations[1].0 - 0.1 / 3.0).abs() < 1e-9);
+        assert_eq!(locations[3], (0.1, 0.0));
+        assert_eq!(wind_locations(&route, 1.0).len(), MAX_WIND_LOCATIONS);
+
+        let payload: ForecastResponse = serde_json::from_value(serde_json::json!([
+            { "current": { "wind_speed_10m": 4.0, "wind_direction_10m": 270.0 } },
+            { "current": {
+                "wind_speed_10m": 4.0, "wind_direction_10m": 270.0,
+                "wind_speed_120m": 10.0, "wind_direction_120m": 0.0
+            } },
+            { "current": null },
+            {}
+        ]))
+        .unwrap();
+        let field = wind_field(&locations, payload).unwrap();
+        assert_eq!(field.samples.len(), 3);
+        // A westerly blows east.
+        assert!((field.samples[0].u_mps - 4.0).abs() < 1e-9);
+        assert!(field.samples[0].v_mps.abs() < 1e-9);
+        // A northerly at 120 m blows south.
+        assert_eq!(field.samples[2].height_agl_m, Some(120.0));
+        assert!((field.samples[2].v_mps + 10.0).abs() < 1e-9);
+
+        let single: ForecastResponse = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(wind_field(&locations, single).is_err());
+    }
+}
@@ -0,0 +1,634 @@
+//! DJI WPML (KMZ) mission export for planned routes.
+//!
+//! Produces a `.kmz` archive containing `wpmz/template.kml` and `wpmz/waylines.wpml`
+//! so DJI Pilot 2 / FlightHub 2 can import routes without manual re-entry.
+
+use atc_core::models::Waypoint;
+use atc_core::spatial::haversine_distance;
+use serde::Deserialize;
+use std::fmt::Write as _;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+
+pub const KMZ_CONTENT_TYPE: &str = "application/vnd.google-earth.kmz";
+
+const WPML_NAMESPACE: &str = "http://www.dji.com/wpmz/1.0.2";
+const DEFAULT_MISSION_SPEED_MPS: f64 = 10.0;
+/// DJI waypoint missions accept speeds in the 1-15 m/s range.
+const MIN_WPML_SPEED_MPS: f64 = 1.0;
+const MAX_WPML_SPEED_MPS: f64 = 15.0;
+const DEFAULT_TAKEOFF_SECURITY_HEIGHT_M: f64 = 20.0;
+/// DJI Matrice 30 series; overridable per export.
+const DEFAULT_DRONE_ENUM_VALUE: u32 = 67;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WpmlFinishAction {
+    #[default]
+    GoHome,
+    NoAction,
+    AutoLand,
+    GotoFirstWaypoint,
+}
+
+impl WpmlFinishAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::GoHome => "goHome",
+            Self::NoAction => "noAction",
+            Self::AutoLand => "autoLand",
+            Self::GotoFirstWaypoint => "gotoFirstWaypoint",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WpmlRcLostAction {
+    #[default]
+    GoBack,
+    Landing,
+    Hover,
+}
+
+impl WpmlRcLostAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::GoBack => "goBack",
+            Self::Landing => "landing",
+            Self::Hover => "hover",
+        }
+    }
+}
+
+/// Mission-level settings written to `wpml:missionConfig`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WpmlMissionOptions {
+    /// Default cruise speed for waypoints without `speed_mps`.
+    pub auto_flight_speed_mps: Option<f64>,
+    pub takeoff_security_height_m: Option<f64>,
+    #[serde(default)]
+    pub finish_action: WpmlFinishAction,
+    #[serde(default)]
+    pub rc_lost_action: WpmlRcLostAction,
+    /// DJI aircraft model enum (see DJI Cloud API `droneEnumValue`).
+    pub drone_enum_value: Option<u32>,
+    pub drone_sub_enum_value: Option<u32>,
+    /// DJI payload enum; when set, the payload is declared so camera actions bind to it.
+    pub payload_enum_value: Option<u32>,
+}
+
+/// Action executed when the aircraft reaches a waypoint.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WpmlAction {
+    Hover { hover_time_s: f64 },
+    TakePhoto,
+    StartRecord,
+    StopRecord,
+    RotateYaw { heading_deg: f64 },
+    GimbalPitch { pitch_deg: f64 },
+}
+
+/// Actions bound to a waypoint by index.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WpmlWaypointActions {
+    pub waypoint_index: usize,
+    pub actions: Vec<WpmlAction>,
+}
+
+/// Route to export. Altitudes are WGS84 ellipsoid heights, as required by
+/// `wpml:executeHeightMode=WGS84`.
+#[derive(Debug, Clone)]
+pub struct WpmlMission<'a> {
+    pub waypoints: &'a [Waypoint],
+    pub actions: &'a [WpmlWaypointActions],
+    pub options: &'a WpmlMissionOptions,
+    pub created_at_ms: i64,
+}
+
+/// Check the mission can be represented in WPML. Returns one message per problem.
+pub fn validate_mission(mission: &WpmlMission<'_>) -> Vec<String> {
+    let mut errors = Vec::new();
+    if mission.waypoints.len() < 2 {
+        errors.push("need at least 2 waypoints".to_string());
+    }
+    for (idx, wp) in mission.waypoints.iter().enumerate() {
+        if !wp.lat.is_finite() || !wp.lon.is_finite() || !wp.altitude_m.is_finite() {
+            errors.push(format!("waypoint[{idx}] lat/lon/altitude_m must be finite"));
+            continue;
+        }
+        if !(-90.0..=90.0).contains(&wp.lat) || !(-180.0..=180.0).contains(&wp.lon) {
+            errors.push(format!("waypoint[{idx}] lat/lon out of range"));
+        }
+        if let Some(speed) = wp.speed_mps {
+            if !speed.is_finite() || speed <= 0.0 {
+                errors.push(format!("waypoint[{idx}] speed_mps must be positive"));
+            }
+        }
+    }
+    if let Some(speed) = mission.options.auto_flight_speed_mps {
+        if !speed.is_finite() || speed <= 0.0 {
+            errors.push("auto_flight_speed_mps must be positive".to_string());
+        }
+    }
+    if let Some(height) = mission.options.takeoff_security_height_m {
+        if !height.is_finite() || !(1.5..=1500.0).contains(&height) {
+            errors.push("takeoff_security_height_m must be within 1.5-1500m".to_string());
+        }
+    }
+    for group in mission.actions {
+        if group.waypoint_index >= mission.waypoints.len() {
+            errors.push(format!(
+                "actions reference waypoint[{}] which does not exist",
+                group.waypoint_index
+            ));
+        }
+        for action in &group.actions {
+            match action {
+                WpmlAction::Hover { hover_time_s }
+                    if !hover_time_s.is_finite() || *hover_time_s <= 0.0 =>
+                {
+                    errors.push(format!(
+                        "waypoint[{}] hover_time_s must be positive",
+                        group.waypoint_index
+                    ));
+                }
+                WpmlAction::RotateYaw { heading_deg }
+                    if !heading_deg.is_finite() || !(-180.0..=180.0).contains(heading_deg) =>
+                {
+                    errors.push(format!(
+                        "waypoint[{}] heading_deg must be within -180..180",
+                        group.waypoint_index
+                    ));
+                }
+                WpmlAction::GimbalPitch { pitch_deg }
+                    if !pitch_deg.is_finite() || !(-90.0..=35.0).contains(pitch_deg) =>
+                {
+                    errors.push(format!(
+                        "waypoint[{}] pitch_deg must be within -90..35",
+                        group.waypoint_index
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+    errors
+}
+
+/// Package the mission as a KMZ archive.
+pub fn build_kmz(mission: &WpmlMission<'_>) -> anyhow::Result<Vec<u8>> {
+    let template = build_template_kml(mission);
+    let waylines = build_waylines_wpml(mission);
+
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    writer.start_file("wpmz/template.kml", options)?;
+    writer.write_all(template.as_bytes())?;
+    writer.start_file("wpmz/waylines.wpml", options)?;
+    writer.write_all(waylines.as_bytes())?;
+    Ok(writer.finish()?.into_inner())
+}
+
+/// Build `template.kml`, the editable route description.
+pub fn build_template_kml(mission: &WpmlMission<'_>) -> String {
+    let speed = mission_speed_mps(mission.options);
+    let mut out = String::new();
+    push_document_header(&mut out);
+    let _ = writeln!(
+        out,
+        "    <wpml:createTime>{}</wpml:createTime>",
+        mission.created_at_ms
+    );
+    let _ = writeln!(
+        out,
+        "    <wpml:updateTime>{}</wpml:updateTime>",
+        mission.created_at_ms
+    );
+    push_mission_config(&mut out, mission.options);
+    out.push_str("    <Folder>\n");
+    out.push_str("      <wpml:templateType>waypoint</wpml:templateType>\n");
+    out.push_str("      <wpml:templateId>0</wpml:templateId>\n");
+    out.push_str("      <wpml:waylineCoordinateSysParam>\n");
+    out.push_str("        <wpml:coordinateMode>WGS84</wpml:coordinateMode>\n");
+    out.push_str("        <wpml:heightMode>WGS84</wpml:heightMode>\n");
+    out.push_str("      </wpml:waylineCoordinateSysParam>\n");
+    let _ = writeln!(
+        out,
+        "      <wpml:autoFlightSpeed>{}</wpml:autoFlightSpeed>",
+        fmt_num(speed)
+    );
+    out.push_str("      <wpml:caliFlightEnable>0</wpml:caliFlightEnable>\n");
+    out.push_str("      <wpml:gimbalPitchMode>usePointSetting</wpml:gimbalPitchMode>\n");
+    out.push_str("      <wpml:globalWaypointHeadingParam>\n");
+    out.push_str("        <wpml:waypointHeadingMode>followWayline</wpml:waypointHeadingMode>\n");
+    out.push_str("      </wpml:globalWaypointHeadingParam>\n");
+    out.push_str(
+        "      <wpml:globalWaypointTurnMode>toPointAndStopWithDiscontinuityCurvature</wpml:globalWaypointTurnMode>\n",
+    );
+    out.push_str("      <wpml:globalUseStraightLine>1</wpml:globalUseStraightLine>\n");
+    for (idx, wp) in mission.waypoints.iter().enumerate() {
+        out.push_str("      <Placemark>\n");
+        push_point(&mut out, wp);
+        let _ = writeln!(out, "        <wpml:index>{idx}</wpml:index>");
+        let _ = writeln!(
+            out,
+            "        <wpml:ellipsoidHeight>{}</wpml:ellipsoidHeight>",
+            fmt_num(wp.altitude_m)
+        );
+        let _ = writeln!(
+            out,
+            "        <wpml:height>{}</wpml:height>",
+            fmt_num(wp.altitude_m)
+        );
+        out.push_str("        <wpml:useGlobalHeight>0</wpml:useGlobalHeight>\n");
+        out.push_str("        <wpml:useGlobalSpeed>0</wpml:useGlobalSpeed>\n");
+        let _ = writeln!(
+            out,
+            "        <wpml:waypointSpeed>{}</wpml:waypointSpeed>",
+            fmt_num(waypoint_speed_mps(wp, speed))
+        );
+        out.push_str("        <wpml:useGlobalHeadingParam>1</wpml:useGlobalHeadingParam>\n");
+        out.push_str("        <wpml:useGlobalTurnParam>1</wpml:useGlobalTurnParam>\n");
+        push_action_group(&mut out, mission, idx);
+        out.push_str("      </Placemark>\n");
+    }
+    out.push_str("    </Folder>\n");
+    push_document_footer(&mut out);
+    out
+}
+
+/// Build `waylines.wpml`, the executable wayline consumed by the aircraft.
+pub fn build_waylines_wpml(mission: &WpmlMission<'_>) -> String {
+    let speed = mission_speed_mps(mission.options);
+    let (distance_m, duration_s) = route_distance_and_duration(mission.waypoints, speed);
+    let mut out = String::new();
+    push_document_header(&mut out);
+    push_mission_config(&mut out, mission.options);
+    out.push_str("    <Folder>\n");
+    out.push_str("      <wpml:templateId>0</wpml:templateId>\n");
+    out.push_str("      <wpml:executeHeightMode>WGS84</wpml:executeHeightMode>\n");
+    out.push_str("      <wpml:waylineId>0</wpml:waylineId>\n");
+    let _ = writeln!(
+        out,
+        "      <wpml:distance>{}</wpml:distance>",
+        fmt_num(distance_m)
+    );
+    let _ = writeln!(
+        out,
+        "      <wpml:duration>{}</wpml:duration>",
+        fmt_num(duration_s)
+    );
+    let _ = writeln!(
+        out,
+        "      <wpml:autoFlightSpeed>{}</wpml:autoFlightSpeed>",
+        fmt_num(speed)
+    );
+    for (idx, wp) in mission.waypoints.iter().enumerate() {
+        out.push_str("      <Placemark>\n");
+        push_point(&mut out, wp);
+        let _ = writeln!(out, "        <wpml:index>{idx}</wpml:index>");
+        let _ = writeln!(
+            out,
+            "        <wpml:executeHeight>{}</wpml:executeHeight>",
+            fmt_num(wp.altitude_m)
+        );
+        let _ = writeln!(
+            out,
+            "        <wpml:waypointSpeed>{}</wpml:waypointSpeed>",
+            fmt_num(waypoint_speed_mps(wp, speed))
+        );
+        out.push_str("        <wpml:waypointHeadingParam>\n");
+        out.push_str(
+            "          <wpml:waypointHeadingMode>followWayline</wpml:waypointHeadingMode>\n",
+        );
+        out.push_str("        </wpml:waypointHeadingParam>\n");
+        out.push_str("        <wpml:waypointTurnParam>\n");
+        out.push_str(
+            "          <wpml:waypointTurnMode>toPointAndStopWithDiscontinuityCurvature</wpml:waypointTurnMode>\n",
+        );
+        out.push_str("          <wpml:waypointTurnDampingDist>0</wpml:waypointTurnDampingDist>\n");
+        out.push_str("        </wpml:waypointTurnParam>\n");
+        out.push_str("        <wpml:useStraightLine>1</wpml:useStraightLine>\n");
+        push_action_group(&mut out, mission, idx);
+        out.push_str("      </Placemark>\n");
+    }
+    out.push_str("    </Folder>\n");
+    push_document_footer(&mut out);
+    out
+}
+
+fn push_document_header(out: &mut String) {
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<kml xmlns=\"http://www.opengis.net/kml/2.2\" xmlns:wpml=\"{WPML_NAMESPACE}\">"
+    );
+    out.push_str("  <Document>\n");
+}
+
+fn push_document_footer(out: &mut String) {
+    out.push_str("  </Document>\n");
+    out.push_str("</kml>\n");
+}
+
+fn push_mission_config(out: &mut String, options: &WpmlMissionOptions) {
+    let speed = mission_speed_mps(options);
+    let security_height = options
+        .takeoff_security_height_m
+        .unwrap_or(DEFAULT_TAKEOFF_SECURITY_HEIGHT_M);
+    out.push_str("    <wpml:missionConfig>\n");
+    out.push_str("      <wpml:flyToWaylineMode>safely</wpml:flyToWaylineMode>\n");
+    let _ = writeln!(
+        out,
+        "      <wpml:finishAction>{}</wpml:finishAction>",
+        options.finish_action.as_str()
+    );
+    out.push_str("      <wpml:exitOnRCLost>executeLostAction</wpml:exitOnRCLost>\n");
+    let _ = writeln!(
+        out,
+        "      <wpml:executeRCLostAction>{}</wpml:executeRCLostAction>",
+        options.rc_lost_action.as_str()
+    );
+    let _ = writeln!(
+        out,
+        "      <wpml:takeOffSecurityHeight>{}</wpml:takeOffSecurityHeight>",
+        fmt_num(security_height)
+    );
+    let _ = writeln!(
+        out,
+        "      <wpml:globalTransitionalSpeed>{}</wpml:globalTransitionalSpeed>",
+        fmt_num(speed)
+    );
+    out.push_str("      <wpml:droneInfo>\n");
+    let _ = writeln!(
+        out,
+        "        <wpml:droneEnumValue>{}</wpml:droneEnumValue>",
+        options.drone_enum_value.unwrap_or(DEFAULT_DRONE_ENUM_VALUE)
+    );
+    let _ = writeln!(
+        out,
+        "        <wpml:droneSubEnumValue>{}</wpml:droneSubEnumValue>",
+        options.drone_sub_enum_value.unwrap_or(0)
+    );
+    out.push_str("      </wpml:droneInfo>\n");
+    if let Some(payload) = options.payload_enum_value {
+        out.push_str("      <wpml:payloadInfo>\n");
+        let _ = writeln!(
+            out,
+            "        <wpml:payloadEnumValue>{payload}</wpml:payloadEnumValue>"
+        );
+        out.push_str("        <wpml:payloadPositionIndex>0</wpml:payloadPositionIndex>\n");
+        out.push_str("      </wpml:payloadInfo>\n");
+    }
+    out.push_str("    </wpml:missionConfig>\n");
+}
+
+fn push_point(out: &mut String, wp: &Waypoint) {
+    out.push_str("        <Point>\n");
+    let _ = writeln!(
+        out,
+        "          <coordinates>{},{}</coordinates>",
+        fmt_coord(wp.lon),
+        fmt_coord(wp.lat)
+    );
+    out.push_str("        </Point>\n");
+}
+
+fn push_action_group(out: &mut String, mission: &WpmlMission<'_>, waypoint_index: usize) {
+    let actions: Vec<&WpmlAction> = mission
+        .actions
+        .iter()
+        .filter(|group| group.waypoint_index == waypoint_index)
+        .flat_map(|group| group.actions.iter())
+        .collect();
+    if actions.is_empty() {
+        return;
+    }
+
+    out.push_str("        <wpml:actionGroup>\n");
+    let _ = writeln!(
+        out,
+        "          <wpml:actionGroupId>{waypoint_index}</wpml:actionGroupId>"
+    );
+    let _ = writeln!(
+        out,
+        "          <wpml:actionGroupStartIndex>{waypoint_index}</wpml:actionGroupStartIndex>"
+    );
+    let _ = writeln!(
+        out,
+        "          <wpml:actionGroupEndIndex>{waypoint_index}</wpml:actionGroupEndIndex>"
+    );
+    out.push_str("          <wpml:actionGroupMode>sequence</wpml:actionGroupMode>\n");
+    out.push_str("          <wpml:actionTrigger>\n");
+    out.push_str("            <wpml:actionTriggerType>reachPoint</wpml:actionTriggerType>\n");
+    out.push_str("          </wpml:actionTrigger>\n");
+    for (action_id, action) in actions.into_iter().enumerate() {
+        out.push_str("          <wpml:action>\n");
+        let _ = writeln!(
+            out,
+            "            <wpml:actionId>{action_id}</wpml:actionId>"
+        );
+        let (func, params) = action_params(action);
+        let _ = writeln!(
+            out,
+            "            <wpml:actionActuatorFunc>{func}</wpml:actionActuatorFunc>"
+        );
+        out.push_str("            <wpml:actionActuatorFuncParam>\n");
+        for (key, value) in params {
+            let _ = writeln!(out, "              <wpml:{key}>{value}</wpml:{key}>");
+        }
+        out.push_str("            </wpml:actionActuatorFuncParam>\n");
+        out.push_str("          </wpml:action>\n");
+    }
+    out.push_str("        </wpml:actionGroup>\n");
+}
+
+fn action_params(action: &WpmlAction) -> (&'static str, Vec<(&'static str, String)>) {
+    match action {
+        WpmlAction::Hover { hover_time_s } => {
+            ("hover", vec![("hoverTime", fmt_num(*hover_time_s))])
+        }
+        WpmlAction::TakePhoto => (
+            "takePhoto",
+            vec![
+                ("payloadPositionIndex", "0".to_string()),
+                ("useGlobalPayloadLensIndex", "1".to_string()),
+            ],
+        ),
+        WpmlAction::StartRecord => (
+            "startRecord",
+            vec![
+                ("payloadPositionIndex", "0".to_string()),
+                ("useGlobalPayloadLensIndex", "1".to_string()),
+            ],
+        ),
+        WpmlAction::StopRecord => (
+            "stopRecord",
+            vec![("payloadPositionIndex", "0".to_string())],
+        ),
+        WpmlAction::RotateYaw { heading_deg } => (
+            "rotateYaw",
+            vec![
+                ("aircraftHeading", fmt_num(*heading_deg)),
+                ("aircraftPathMode", "counterClockwise".to_string()),
+            ],
+        ),
+        WpmlAction::GimbalPitch { pitch_deg } => (
+            "gimbalRotate",
+            vec![
+                ("gimbalHeadingYawBase", "aircraft".to_string()),
+                ("gimbalRotateMode", "absoluteAngle".to_string()),
+                ("gimbalPitchRotateEnable", "1".to_string()),
+                ("gimbalPitchRotateAngle", fmt_num(*pitch_deg)),
+                ("gimbalRollRotateEnable", "0".to_string()),
+                ("gimbalRollRotateAngle", "0".to_string()),
+                ("gimbalYawRotateEnable", "0".to_string()),
+                ("gimbalYawRotateAngle", "0".to_string()),
+                ("gimbalRotateTimeEnable", "0".to_string()),
+                ("gimbalRotateTime", "0".to_string()),
+                ("payloadPositionIndex", "0".to_string()),
+            ],
+        ),
+    }
+}
+
+fn mission_speed_mps(options: &WpmlMissionOptions) -> f64 {
+    clamp_speed_mps(
+        options
+            .auto_flight_speed_mps
+            .unwrap_or(DEFAULT_MISSION_SPEED_MPS),
+    )
+}
+
+fn waypoint_speed_mps(wp: &Waypoint, mission_speed: f64) -> f64 {
+    clamp_speed_mps(wp.speed_mps.unwrap_or(mission_speed))
+}
+
+fn clamp_speed_mps(speed: f64) -> f64 {
+    if speed.is_finite() && speed > 0.0 {
+        speed.clamp(MIN_WPML_SPEED_MPS, MAX_WPML_SPEED_MPS)
+    } else {
+        DEFAULT_MISSION_SPEED_MPS
+    }
+}
+
+fn route_distance_and_duration(waypoints: &[Waypoint], mission_speed: f64) -> (f64, f64) {
+    let mut distance_m = 0.0;
+    let mut duration_s = 0.0;
+    for pair in waypoints.windows(2) {
+        let horizontal = haversine_distance(pair[0].lat, pair[0].lon, pair[1].lat, pair[1].lon);
+        let vertical = pair[1].altitude_m - pair[0].altitude_m;
+        let segment = (horizontal * horizontal + vertical * vertical).sqrt();
+        distance_m += segment;
+        duration_s += segment / waypoint_speed_mps(&pair[0], mission_speed);
+    }
+    (distance_m, duration_s)
+}
+
+fn fmt_coord(value: f64) -> String {
+    format!("{value:.8}")
+}
+
+fn fmt_num(value: f64) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded.fract() == 0.0 {
+        format!("{rounded:.0}")
+    } else {
+        format!("{rounded}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn waypoint(lat: f64, lon: f64, altitude_m: f64, speed_mps: Option<f64>) -> Waypoint {
+        Waypoint {
+            lat,
+            lon,
+            altitude_m,
+            speed_mps,
+        }
+    }
+
+    #[test]
+    fn waylines_include_speeds_and_actions() {
+        let waypoints = vec![
+            waypoint(33.6846, -117.8265, 120.0, Some(8.0)),
+            waypoint(33.6900, -117.8200, 130.0, Some(40.0)),
+        ];
+        let actions = vec![WpmlWaypointActions {
+            waypoint_index: 1,
+            actions: vec![
+                WpmlAction::Hover { hover_time_s: 5.0 },
+                WpmlAction::TakePhoto,
+            ],
+        }];
+        let options = WpmlMissionOptions::default();
+        let mission = WpmlMission {
+            waypoints: &waypoints,
+            actions: &actions,
+            options: &options,
+            created_at_ms: 0,
+        };
+        assert!(validate_mission(&mission).is_empty());
+
+        let waylines = build_waylines_wpml(&mission);
+        assert!(waylines.contains("<coordinates>-117.82650000,33.68460000</coordinates>"));
+        assert!(waylines.contains("<wpml:waypointSpeed>8</wpml:waypointSpeed>"));
+        // Speeds above the DJI limit are clamped.
+        assert!(waylines.contains("<wpml:waypointSpeed>15</wpml:waypointSpeed>"));
+        assert!(waylines.contains("<wpml:actionActuatorFunc>hover</wpml:actionActuatorFunc>"));
+        assert!(waylines.contains("<wpml:hoverTime>5</wpml:hoverTime>"));
+        assert!(waylines.contains("<wpml:actionActuatorFunc>takePhoto</wpml:actionActuatorFunc>"));
+        assert_eq!(waylines.matches("<wpml:actionGroup>").count(), 1);
+    }
+
+    #[test]
+    fn kmz_contains_template_and_waylines() {
+        let waypoints = vec![
+            waypoint(33.6846, -117.8265, 120.0, None),
+            waypoint(33.6900, -117.8200, 120.0, None),
+        ];
+        let options = WpmlMissionOptions::default();
+        let mission = WpmlMission {
+            waypoints: &waypoints,
+            actions: &[],
+            options: &options,
+            created_at_ms: 0,
+        };
+        let bytes = build_kmz(&mission).expect("build kmz");
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).expect("open kmz");
+        let mut template = String::new();
+        archive
+            .by_name("wpmz/template.kml")
+            .expect("template entry")
+            .read_to_string(&mut template)
+            .expect("read template");
+        assert!(template.contains("<wpml:templateType>waypoint</wpml:templateType>"));
+        assert!(archive.by_name("wpmz/waylines.wpml").is_ok());
+    }
+
+    #[test]
+    fn rejects_actions_on_missing_waypoints() {
+        let waypoints = vec![
+            waypoint(33.6846, -117.8265, 120.0, None),
+            waypoint(33.6900, -117.8200, 120.0, None),
+        ];
+        let actions = vec![WpmlWaypointActions {
+            waypoint_index: 5,
+            actions: vec![WpmlAction::TakePhoto],
+        }];
+        let options = WpmlMissionOptions::default();
+        let mission = WpmlMission {
+            waypoints: &waypoints,
+            actions: &actions,
+            options: &options,
+            created_at_ms: 0,
+        };
+        assert_eq!(validate_mission(&mission).len(), 1);
+    }
+}
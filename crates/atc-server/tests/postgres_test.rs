@@ -0,0 +1,142 @@
+//! Postgres persistence backend tests.
+//!
+//! Run with: DATABASE_URL=postgres://user@host/db cargo test -p atc-server --features postgres --test postgres_test
+//! Each test creates a scratch database next to the one in `DATABASE_URL`; without it the tests
+//! pass without doing anything.
+#![cfg(feature = "postgres")]
+
+use std::time::Duration;
+
+use atc_core::models::{FlightPlan, FlightStatus, Waypoint};
+use atc_server::persistence::flight_plans::{
+    load_flight_plan, lock_scheduler, query_flight_plan_history, upsert_flight_plan,
+    FlightPlanHistoryFilter,
+};
+use atc_server::persistence::{open_database, schema_status};
+use chrono::{Duration as ChronoDuration, Utc};
+use sqlx::Connection;
+
+/// A fresh database on the `DATABASE_URL` server, or `None` when it is unset.
+async fn scratch_database() -> Option<String> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+    let mut admin = sqlx::PgConnection::connect(&url)
+        .await
+        .expect("connect to DATABASE_URL");
+    let name = format!("atc_test_{}", uuid::Uuid::new_v4().simple());
+    sqlx::query(&format!("CREATE DATABASE {}", name))
+        .execute(&mut admin)
+        .await
+        .expect("create scratch database");
+
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (base, format!("?{}", query)),
+        None => (url.as_str(), String::new()),
+    };
+    let server = base.rsplit_once('/').map_or(base, |(server, _)| server);
+    Some(format!("{}/{}{}", server, name, query))
+}
+
+#[tokio::test]
+async fn migrations_apply_once_and_are_tracked() {
+    let Some(url) = scratch_database().await else {
+        return;
+    };
+    let db = open_database(&url, 2, true).await.expect("migrate");
+    let status = schema_status(db.pool()).await.unwrap();
+    assert!(status.pending.is_empty() && status.unknown.is_empty());
+    assert_eq!(status.database_version, Some(status.supported_version));
+
+    // A replica that may not migrate starts against the migrated database.
+    let replica = open_database(&url, 1, false).await.expect("reopen");
+    assert_eq!(schema_status(replica.pool()).await.unwrap(), status);
+}
+
+#[tokio::test]
+async fn scheduler_lock_serializes_bookings() {
+    let Some(url) = scratch_database().await else {
+        return;
+    };
+    let db = open_database(&url, 2, true).await.expect("migrate");
+
+    let mut first = db.pool().begin().await.unwrap();
+    lock_scheduler(&mut first).await.unwrap();
+
+    let pool = db.pool().clone();
+    let second = tokio::spawn(async move {
+        let mut tx = pool.begin().await.unwrap();
+        lock_scheduler(&mut tx).await.unwrap();
+        tx.commit().await.unwrap();
+    });
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(
+        !second.is_finished(),
+        "second booking must wait for the first"
+    );
+
+    first.commit().await.unwrap();
+    tokio::time::timeout(Duration::from_secs(5), second)
+        .await
+        .expect("second booking proceeds once the first commits")
+        .unwrap();
+}
+
+#[tokio::test]
+async fn flight_plans_round_trip() {
+    let Some(url) = scratch_database().await else {
+        return;
+    };
+    let db = open_database(&url, 2, true).await.expect("migrate");
+
+    let departure = Utc::now();
+    let mut plan = FlightPlan {
+        flight_id: "FP-PG-1".to_string(),
+        drone_id: "DRONE-PG".to_string(),
+        owner_id: Some("operator-1".to_string()),
+        waypoints: vec![
+            Waypoint {
+                lat: 33.6846,
+                lon: -117.8265,
+                altitude_m: 60.0,
+                speed_mps: Some(12.0),
+            },
+            Waypoint {
+                lat: 33.6900,
+                lon: -117.8200,
+                altitude_m: 80.0,
+                speed_mps: None,
+            },
+        ],
+        trajectory_log: None,
+        metadata: None,
+        status: FlightStatus::Approved,
+        departure_time: departure,
+        arrival_time: Some(departure + ChronoDuration::minutes(10)),
+        created_at: departure,
+    };
+    upsert_flight_plan(db.pool(), &plan).await.unwrap();
+    plan.status = FlightStatus::Active;
+    upsert_flight_plan(db.pool(), &plan).await.unwrap();
+
+    let loaded = load_flight_plan(db.pool(), "FP-PG-1")
+        .await
+        .unwrap()
+        .expect("stored plan");
+    assert_eq!(loaded.status, FlightStatus::Active);
+    assert_eq!(loaded.owner_id.as_deref(), Some("operator-1"));
+    assert_eq!(loaded.waypoints.len(), 2);
+    assert_eq!(loaded.waypoints[1].altitude_m, 80.0);
+
+    let history = query_flight_plan_history(
+        db.pool(),
+        &FlightPlanHistoryFilter {
+            owner_id: Some("operator-1".to_string()),
+            status: Some(FlightStatus::Active),
+            limit: 10,
+            ..FlightPlanHistoryFilter::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].flight_id, "FP-PG-1");
+}